@@ -43,6 +43,7 @@ pub enum Expression<'ast> {
     Spawn(SpawnExpr<'ast>),
     Try(TryExpr<'ast>),
     Catch(CatchExpr<'ast>),
+    Finally(FinallyExpr<'ast>),
     Cast(CastExpr<'ast>),
     Range(RangeExpr<'ast>),
     Grouped(GroupedExpr<'ast>),
@@ -75,6 +76,7 @@ impl<'ast> Spanned for Expression<'ast> {
             Expression::Spawn(e) => e.span,
             Expression::Try(e) => e.span,
             Expression::Catch(e) => e.span,
+            Expression::Finally(e) => e.span,
             Expression::Cast(e) => e.span,
             Expression::Range(e) => e.span,
             Expression::Grouped(e) => e.span,
@@ -237,10 +239,21 @@ pub struct TryExpr<'ast> {
 pub struct CatchExpr<'ast> {
     pub expr: &'ast Expression<'ast>,
     pub error_binding: Ident,
+    /// Exception types handled by `catch (e: A | B)`; empty for a bare `catch e`
+    pub error_types: Vec<NamlType>,
     pub handler: &'ast BlockExpr<'ast>,
     pub span: Span,
 }
 
+/// `expr finally { ... }`, usually after a catch: the block runs however
+/// `expr` finishes, including a return or throw from its catch handler
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinallyExpr<'ast> {
+    pub expr: &'ast Expression<'ast>,
+    pub body: &'ast BlockExpr<'ast>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CastExpr<'ast> {
     pub expr: &'ast Expression<'ast>,
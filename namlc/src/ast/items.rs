@@ -18,7 +18,13 @@
 //! - Functions can be marked with #[platforms(native, edge, browser)]
 //! - Platform-specific implementations are handled at codegen time
 //!
+//! Attributes:
+//! - Functions and type definitions can carry `@name` or `@name(args)`
+//! - The type checker validates the built-in ones (@test, @inline,
+//!   @deprecated, @serialize); others are kept for tooling
+//!
 
+use super::literals::Literal;
 use super::statements::{BlockStmt, Statement};
 use super::types::{Ident, NamlType};
 use crate::source::{Span, Spanned};
@@ -27,7 +33,7 @@ use crate::source::{Span, Spanned};
 pub enum Item<'ast> {
     Function(FunctionItem<'ast>),
     Struct(StructItem),
-    Interface(InterfaceItem),
+    Interface(InterfaceItem<'ast>),
     Enum(EnumItem),
     Exception(ExceptionItem),
     Use(UseItem),
@@ -54,6 +60,33 @@ impl<'ast> Spanned for Item<'ast> {
     }
 }
 
+impl<'ast> Item<'ast> {
+    /// The attributes of items that can carry them
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Item::Function(i) => &i.attributes,
+            Item::Struct(i) => &i.attributes,
+            Item::Interface(i) => &i.attributes,
+            Item::Enum(i) => &i.attributes,
+            Item::Exception(i) => &i.attributes,
+            Item::TypeAlias(i) => &i.attributes,
+            _ => &[],
+        }
+    }
+
+    pub fn attributes_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            Item::Function(i) => Some(&mut i.attributes),
+            Item::Struct(i) => Some(&mut i.attributes),
+            Item::Interface(i) => Some(&mut i.attributes),
+            Item::Enum(i) => Some(&mut i.attributes),
+            Item::Exception(i) => Some(&mut i.attributes),
+            Item::TypeAlias(i) => Some(&mut i.attributes),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct TopLevelStmtItem<'ast> {
     pub stmt: Statement<'ast>,
@@ -92,6 +125,30 @@ pub struct Platforms {
     pub span: Span,
 }
 
+/// An `@name(args)` attribute on an item
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: Ident,
+    pub args: Vec<AttributeArg>,
+    pub span: Span,
+}
+
+/// An attribute argument: a literal or a bare identifier
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeArg {
+    Literal(Literal, Span),
+    Ident(Ident),
+}
+
+impl AttributeArg {
+    pub fn span(&self) -> Span {
+        match self {
+            AttributeArg::Literal(_, span) => *span,
+            AttributeArg::Ident(ident) => ident.span,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GenericParam {
     pub name: Ident,
@@ -122,8 +179,11 @@ pub struct FunctionItem<'ast> {
     pub return_ty: Option<NamlType>,
     pub throws: Vec<NamlType>,
     pub is_public: bool,
+    /// `const fn`: calls with constant arguments are evaluated at compile time
+    pub is_const: bool,
     pub body: Option<BlockStmt<'ast>>,
     pub platforms: Option<Platforms>,
+    pub attributes: Vec<Attribute>,
     pub span: Span,
 }
 
@@ -152,26 +212,32 @@ pub struct StructItem {
     pub implements: Vec<NamlType>,
     pub fields: Vec<StructField>,
     pub is_public: bool,
+    pub attributes: Vec<Attribute>,
     pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct InterfaceMethod {
+pub struct InterfaceMethod<'ast> {
     pub name: Ident,
     pub generics: Vec<GenericParam>,
     pub params: Vec<Parameter>,
     pub return_ty: Option<NamlType>,
     pub throws: Vec<NamlType>,
+    /// `static fn`: called as `Interface::name(...)`, without a receiver
+    pub is_static: bool,
+    /// Default implementation shared by implementors that don't define the method
+    pub body: Option<BlockStmt<'ast>>,
     pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct InterfaceItem {
+pub struct InterfaceItem<'ast> {
     pub name: Ident,
     pub generics: Vec<GenericParam>,
     pub extends: Vec<NamlType>,
-    pub methods: Vec<InterfaceMethod>,
+    pub methods: Vec<InterfaceMethod<'ast>>,
     pub is_public: bool,
+    pub attributes: Vec<Attribute>,
     pub span: Span,
 }
 
@@ -188,6 +254,7 @@ pub struct EnumItem {
     pub generics: Vec<GenericParam>,
     pub variants: Vec<EnumVariant>,
     pub is_public: bool,
+    pub attributes: Vec<Attribute>,
     pub span: Span,
 }
 
@@ -203,6 +270,7 @@ pub struct ExceptionItem {
     pub name: Ident,
     pub fields: Vec<ExceptionField>,
     pub is_public: bool,
+    pub attributes: Vec<Attribute>,
     pub span: Span,
 }
 
@@ -210,6 +278,8 @@ pub struct ExceptionItem {
 pub struct UseItem {
     pub path: Vec<Ident>,
     pub items: UseItems,
+    /// `pub use` re-exports the imported items from the importing module
+    pub is_public: bool,
     pub span: Span,
 }
 
@@ -223,7 +293,8 @@ pub struct ModuleItem<'ast> {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum UseItems {
-    All,
+    /// `*`, or `* except { a, b }` to leave some items out
+    All { except: Vec<Ident> },
     Specific(Vec<UseItemEntry>),
 }
 
@@ -250,6 +321,7 @@ pub struct TypeAliasItem {
     pub generics: Vec<GenericParam>,
     pub aliased_type: NamlType,
     pub is_public: bool,
+    pub attributes: Vec<Attribute>,
     pub span: Span,
 }
 
@@ -273,8 +345,10 @@ mod tests {
             return_ty: None,
             throws: vec![],
             is_public: false,
+            is_const: false,
             body: Some(BlockStmt::empty(Span::dummy())),
             platforms: None,
+            attributes: vec![],
             span: Span::dummy(),
         };
         assert!(!func.is_method());
@@ -288,6 +362,7 @@ mod tests {
             name: make_ident(&mut rodeo, "MyError"),
             fields: vec![],
             is_public: true,
+            attributes: vec![],
             span: Span::new(0, 50, 0),
         });
         assert_eq!(item.span(), Span::new(0, 50, 0));
@@ -13,6 +13,7 @@
 //! - statements: All statement node types
 //! - items: Top-level declarations (functions, structs, etc.)
 //! - visitor: Visitor pattern for AST traversal
+//! - visit: Stable, owned view of a file's declarations for external tools
 //!
 //! The root AST node is SourceFile, representing a complete naml source file.
 //!
@@ -25,6 +26,7 @@ pub mod operators;
 pub mod patterns;
 pub mod statements;
 pub mod types;
+pub mod visit;
 pub mod visitor;
 
 pub use arena::AstArena;
@@ -105,6 +105,8 @@ pub struct ReturnStmt<'ast> {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ThrowStmt<'ast> {
     pub value: Expression<'ast>,
+    /// Exception recorded as the thrown one's `cause` (`throw x caused_by e;`)
+    pub cause: Option<Expression<'ast>>,
     pub span: Span,
 }
 
@@ -0,0 +1,687 @@
+//!
+//! Stable AST View
+//!
+//! The parser's AST (`ast::Item`, `ast::Expression`, ...) is an internal
+//! representation: it borrows from an arena, stores names as interner
+//! symbols and changes shape whenever the compiler needs it to. External
+//! Rust tools - build scripts, scaffolding generators, `naml codegen`
+//! plugins - use this module instead.
+//!
+//! `parse_file` (or `Ast::parse`) produces an owned, read-only view of the
+//! declarations in a file: functions, methods, structs, enums, interfaces,
+//! exceptions, type aliases, imports, externs and modules, with names,
+//! types and attributes resolved to strings. Function bodies and top-level
+//! statements are not part of the view; `Ast::text` returns the source of
+//! any span for tools that need more.
+//!
+//! Versioning:
+//! - `API_VERSION` is bumped whenever a change could break a tool
+//! - View structs are `#[non_exhaustive]`: new fields and item kinds are
+//!   added without a version bump, so match with `_` arms and `..`
+//! - Types are rendered in naml syntax (`[int]`, `option<user>`,
+//!   `map<string, int>`), not as compiler type structures
+//!
+//! Traversal implements `AstVisitor`, whose default methods walk into
+//! modules, and calls `Ast::walk`. The view also serializes to JSON
+//! (`naml codegen --json`) for generators not written in Rust.
+//!
+
+use std::path::{Path, PathBuf};
+
+use lasso::Rodeo;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::ast::{self as internal, Literal, NamlType};
+use crate::lexer::tokenize;
+use crate::parser::{parse, ParseError};
+use crate::source::Span;
+
+/// Version of the view types; tools can refuse to run against a newer one
+pub const API_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum ParseFileError {
+    #[error("cannot read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{} syntax error(s), first: {}", errors.len(), errors[0].message)]
+    Syntax { errors: Vec<ParseError> },
+}
+
+/// Read and parse a naml source file into a stable view
+pub fn parse_file(path: &Path) -> Result<Ast, ParseFileError> {
+    let source = std::fs::read_to_string(path).map_err(|source| ParseFileError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ast::parse(&source)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Ast {
+    pub items: Vec<Item>,
+    #[serde(skip)]
+    pub source: String,
+}
+
+impl Ast {
+    pub fn parse(source: &str) -> Result<Ast, ParseFileError> {
+        let (tokens, interner) = tokenize(source);
+        let arena = internal::AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        if !parsed.errors.is_empty() {
+            return Err(ParseFileError::Syntax {
+                errors: parsed.errors,
+            });
+        }
+        let cx = Lower {
+            interner: &interner,
+        };
+        Ok(Ast {
+            items: cx.items(&parsed.ast.items),
+            source: source.to_string(),
+        })
+    }
+
+    /// Source text covered by `span`
+    pub fn text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    pub fn walk<V: AstVisitor>(&self, visitor: &mut V) {
+        for item in &self.items {
+            visitor.visit_item(item);
+        }
+    }
+
+    /// Free functions and methods at the top level of the file
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Function(f) => Some(f),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Item {
+    Function(Function),
+    Struct(Struct),
+    Enum(Enum),
+    Interface(Interface),
+    Exception(Exception),
+    TypeAlias(TypeAlias),
+    Use(Use),
+    Extern(Function),
+    Module(Module),
+}
+
+impl Item {
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Item::Function(i) | Item::Extern(i) => &i.attributes,
+            Item::Struct(i) => &i.attributes,
+            Item::Enum(i) => &i.attributes,
+            Item::Interface(i) => &i.attributes,
+            Item::Exception(i) => &i.attributes,
+            Item::TypeAlias(i) => &i.attributes,
+            Item::Use(_) | Item::Module(_) => &[],
+        }
+    }
+
+    /// The first attribute called `name`, e.g. `route` for `@route("/users")`
+    pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes().iter().find(|a| a.name == name)
+    }
+}
+
+/// An `@name(args)` annotation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<AttributeValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// A bare identifier argument
+    Ident(String),
+}
+
+impl AttributeValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::String(s) | AttributeValue::Ident(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Param {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// A function, a method (with `receiver`), an interface method or an extern
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Function {
+    pub name: String,
+    pub receiver: Option<Param>,
+    pub generics: Vec<String>,
+    pub params: Vec<Param>,
+    pub return_type: Option<String>,
+    pub throws: Vec<String>,
+    pub is_public: bool,
+    pub has_body: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Field {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub is_public: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Struct {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub implements: Vec<String>,
+    pub fields: Vec<Field>,
+    pub is_public: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Enum {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub variants: Vec<Variant>,
+    pub is_public: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Interface {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub extends: Vec<String>,
+    pub methods: Vec<Function>,
+    pub is_public: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Exception {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub is_public: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct TypeAlias {
+    pub name: String,
+    pub generics: Vec<String>,
+    pub aliased_type: String,
+    pub is_public: bool,
+    pub attributes: Vec<Attribute>,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+/// `use a::b::{c, d as e}`; `names` is empty for `use a::b::*`
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Use {
+    pub path: Vec<String>,
+    pub names: Vec<String>,
+    pub is_public: bool,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+/// `mod name { ... }`, or `mod name;` with no items
+#[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
+pub struct Module {
+    pub name: String,
+    pub items: Vec<Item>,
+    pub is_public: bool,
+    #[serde(serialize_with = "serialize_span")]
+    pub span: Span,
+}
+
+/// Spans serialize as `{"start": byte, "end": byte}`
+fn serialize_span<S: serde::Serializer>(span: &Span, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+    let mut state = serializer.serialize_struct("Span", 2)?;
+    state.serialize_field("start", &span.start)?;
+    state.serialize_field("end", &span.end)?;
+    state.end()
+}
+
+/// Read-only traversal of a view. Every method is optional; the default
+/// `visit_item` dispatches on the item kind and `visit_module` walks into
+/// the module's items.
+pub trait AstVisitor: Sized {
+    fn visit_item(&mut self, item: &Item) {
+        walk_item(self, item)
+    }
+
+    fn visit_function(&mut self, _function: &Function) {}
+
+    fn visit_struct(&mut self, _item: &Struct) {}
+
+    fn visit_enum(&mut self, _item: &Enum) {}
+
+    fn visit_interface(&mut self, _item: &Interface) {}
+
+    fn visit_exception(&mut self, _item: &Exception) {}
+
+    fn visit_type_alias(&mut self, _item: &TypeAlias) {}
+
+    fn visit_use(&mut self, _item: &Use) {}
+
+    fn visit_extern(&mut self, _item: &Function) {}
+
+    fn visit_module(&mut self, module: &Module) {
+        for item in &module.items {
+            self.visit_item(item);
+        }
+    }
+}
+
+pub fn walk_item<V: AstVisitor>(v: &mut V, item: &Item) {
+    match item {
+        Item::Function(f) => v.visit_function(f),
+        Item::Struct(s) => v.visit_struct(s),
+        Item::Enum(e) => v.visit_enum(e),
+        Item::Interface(i) => v.visit_interface(i),
+        Item::Exception(e) => v.visit_exception(e),
+        Item::TypeAlias(t) => v.visit_type_alias(t),
+        Item::Use(u) => v.visit_use(u),
+        Item::Extern(e) => v.visit_extern(e),
+        Item::Module(m) => v.visit_module(m),
+    }
+}
+
+/// Converts the internal AST into the view
+struct Lower<'a> {
+    interner: &'a Rodeo,
+}
+
+impl Lower<'_> {
+    fn name(&self, ident: &internal::Ident) -> String {
+        self.interner.resolve(&ident.symbol).to_string()
+    }
+
+    fn items(&self, items: &[internal::Item<'_>]) -> Vec<Item> {
+        items.iter().filter_map(|item| self.item(item)).collect()
+    }
+
+    fn item(&self, item: &internal::Item<'_>) -> Option<Item> {
+        Some(match item {
+            internal::Item::Function(f) => Item::Function(Function {
+                name: self.name(&f.name),
+                receiver: f.receiver.as_ref().map(|r| Param {
+                    name: self.name(&r.name),
+                    ty: self.ty(&r.ty),
+                }),
+                generics: self.generics(&f.generics),
+                params: self.params(&f.params),
+                return_type: f.return_ty.as_ref().map(|t| self.ty(t)),
+                throws: self.types(&f.throws),
+                is_public: f.is_public,
+                has_body: f.body.is_some(),
+                attributes: self.attributes(&f.attributes),
+                span: f.span,
+            }),
+            internal::Item::Struct(s) => Item::Struct(Struct {
+                name: self.name(&s.name),
+                generics: self.generics(&s.generics),
+                implements: self.types(&s.implements),
+                fields: s
+                    .fields
+                    .iter()
+                    .map(|f| Field {
+                        name: self.name(&f.name),
+                        ty: self.ty(&f.ty),
+                        is_public: f.is_public,
+                    })
+                    .collect(),
+                is_public: s.is_public,
+                attributes: self.attributes(&s.attributes),
+                span: s.span,
+            }),
+            internal::Item::Enum(e) => Item::Enum(Enum {
+                name: self.name(&e.name),
+                generics: self.generics(&e.generics),
+                variants: e
+                    .variants
+                    .iter()
+                    .map(|v| Variant {
+                        name: self.name(&v.name),
+                        fields: v.fields.as_deref().map(|f| self.types(f)).unwrap_or_default(),
+                    })
+                    .collect(),
+                is_public: e.is_public,
+                attributes: self.attributes(&e.attributes),
+                span: e.span,
+            }),
+            internal::Item::Interface(i) => Item::Interface(Interface {
+                name: self.name(&i.name),
+                generics: self.generics(&i.generics),
+                extends: self.types(&i.extends),
+                methods: i
+                    .methods
+                    .iter()
+                    .map(|m| Function {
+                        name: self.name(&m.name),
+                        receiver: None,
+                        generics: self.generics(&m.generics),
+                        params: self.params(&m.params),
+                        return_type: m.return_ty.as_ref().map(|t| self.ty(t)),
+                        throws: self.types(&m.throws),
+                        is_public: true,
+                        has_body: m.body.is_some(),
+                        attributes: Vec::new(),
+                        span: m.span,
+                    })
+                    .collect(),
+                is_public: i.is_public,
+                attributes: self.attributes(&i.attributes),
+                span: i.span,
+            }),
+            internal::Item::Exception(e) => Item::Exception(Exception {
+                name: self.name(&e.name),
+                fields: e
+                    .fields
+                    .iter()
+                    .map(|f| Field {
+                        name: self.name(&f.name),
+                        ty: self.ty(&f.ty),
+                        is_public: true,
+                    })
+                    .collect(),
+                is_public: e.is_public,
+                attributes: self.attributes(&e.attributes),
+                span: e.span,
+            }),
+            internal::Item::TypeAlias(t) => Item::TypeAlias(TypeAlias {
+                name: self.name(&t.name),
+                generics: self.generics(&t.generics),
+                aliased_type: self.ty(&t.aliased_type),
+                is_public: t.is_public,
+                attributes: self.attributes(&t.attributes),
+                span: t.span,
+            }),
+            internal::Item::Use(u) => Item::Use(Use {
+                path: u.path.iter().map(|p| self.name(p)).collect(),
+                names: match &u.items {
+                    internal::UseItems::All { .. } => Vec::new(),
+                    internal::UseItems::Specific(entries) => {
+                        entries.iter().map(|e| self.name(&e.name)).collect()
+                    }
+                },
+                is_public: u.is_public,
+                span: u.span,
+            }),
+            internal::Item::Extern(e) => Item::Extern(Function {
+                name: self.name(&e.name),
+                receiver: None,
+                generics: Vec::new(),
+                params: self.params(&e.params),
+                return_type: e.return_ty.as_ref().map(|t| self.ty(t)),
+                throws: self.types(&e.throws),
+                is_public: false,
+                has_body: false,
+                attributes: Vec::new(),
+                span: e.span,
+            }),
+            internal::Item::Mod(m) => Item::Module(Module {
+                name: self.name(&m.name),
+                items: m.body.as_deref().map(|items| self.items(items)).unwrap_or_default(),
+                is_public: m.is_public,
+                span: m.span,
+            }),
+            internal::Item::TopLevelStmt(_) => return None,
+        })
+    }
+
+    fn generics(&self, generics: &[internal::GenericParam]) -> Vec<String> {
+        generics.iter().map(|g| self.name(&g.name)).collect()
+    }
+
+    fn params(&self, params: &[internal::Parameter]) -> Vec<Param> {
+        params
+            .iter()
+            .map(|p| Param {
+                name: self.name(&p.name),
+                ty: self.ty(&p.ty),
+            })
+            .collect()
+    }
+
+    fn attributes(&self, attributes: &[internal::Attribute]) -> Vec<Attribute> {
+        attributes
+            .iter()
+            .map(|a| Attribute {
+                name: self.name(&a.name),
+                args: a
+                    .args
+                    .iter()
+                    .map(|arg| match arg {
+                        internal::AttributeArg::Ident(ident) => {
+                            AttributeValue::Ident(self.name(ident))
+                        }
+                        internal::AttributeArg::Literal(literal, _) => match literal {
+                            Literal::Int(n) => AttributeValue::Int(*n),
+                            Literal::UInt(n) => AttributeValue::Int(*n as i64),
+                            Literal::Float(f) => AttributeValue::Float(*f),
+                            Literal::Bool(b) => AttributeValue::Bool(*b),
+                            Literal::String(s) => {
+                                AttributeValue::String(self.interner.resolve(s).to_string())
+                            }
+                            Literal::Bytes(_) | Literal::None => {
+                                AttributeValue::Ident("none".to_string())
+                            }
+                        },
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn types(&self, types: &[NamlType]) -> Vec<String> {
+        types.iter().map(|t| self.ty(t)).collect()
+    }
+
+    /// Render a type the way it is written in naml source
+    fn ty(&self, ty: &NamlType) -> String {
+        match ty {
+            NamlType::Int => "int".to_string(),
+            NamlType::Uint => "uint".to_string(),
+            NamlType::Float => "float".to_string(),
+            NamlType::Bool => "bool".to_string(),
+            NamlType::String => "string".to_string(),
+            NamlType::Bytes => "bytes".to_string(),
+            NamlType::Unit => "()".to_string(),
+            NamlType::Decimal { precision, scale } => format!("decimal({}, {})", precision, scale),
+            NamlType::Array(inner) => format!("[{}]", self.ty(inner)),
+            NamlType::FixedArray(inner, size) => format!("[{}; {}]", self.ty(inner), size),
+            NamlType::Option(inner) => format!("option<{}>", self.ty(inner)),
+            NamlType::Map(key, value) => format!("map<{}, {}>", self.ty(key), self.ty(value)),
+            NamlType::Channel(inner) => format!("channel<{}>", self.ty(inner)),
+            NamlType::Mutex(inner) => format!("mutex<{}>", self.ty(inner)),
+            NamlType::Rwlock(inner) => format!("rwlock<{}>", self.ty(inner)),
+            NamlType::Atomic(inner) => format!("atomic<{}>", self.ty(inner)),
+            NamlType::Named(name) => self.name(name),
+            NamlType::Generic(name, args) => {
+                format!("{}<{}>", self.name(name), self.types(args).join(", "))
+            }
+            NamlType::Function { params, returns } => {
+                let params = self.types(params).join(", ");
+                match returns.as_ref() {
+                    NamlType::Unit => format!("fn({})", params),
+                    returns => format!("fn({}) -> {}", params, self.ty(returns)),
+                }
+            }
+            NamlType::Inferred => "_".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+use std::collections::arrays::{count, push};
+
+@route("GET", "/users")
+pub fn list_users(limit: int, filter: option<string>) -> [user] throws db_error {
+    return [];
+}
+
+pub struct user implements named {
+    pub id: int,
+    tags: map<string, [int]>
+}
+
+fn (self: user) name() -> string { return ""; }
+
+enum shape { circle(float), square(float), empty }
+
+mod handlers {
+    @route("POST", "/users")
+    fn create_user(u: user) {}
+}
+"#;
+
+    #[derive(Default)]
+    struct Routes(Vec<(String, String, String)>);
+
+    impl AstVisitor for Routes {
+        fn visit_function(&mut self, f: &Function) {
+            if let Some(route) = f.attributes.iter().find(|a| a.name == "route") {
+                self.0.push((
+                    route.args[0].as_str().unwrap().to_string(),
+                    route.args[1].as_str().unwrap().to_string(),
+                    f.name.clone(),
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_view_items() {
+        let ast = Ast::parse(SOURCE).unwrap();
+        let Item::Use(import) = &ast.items[0] else {
+            panic!("expected use, got {:?}", ast.items[0]);
+        };
+        assert_eq!(import.path, ["std", "collections", "arrays"]);
+        assert_eq!(import.names, ["count", "push"]);
+
+        let list = ast.functions().next().unwrap();
+        assert_eq!(list.name, "list_users");
+        assert_eq!(
+            list.params,
+            [
+                Param { name: "limit".into(), ty: "int".into() },
+                Param { name: "filter".into(), ty: "option<string>".into() },
+            ]
+        );
+        assert_eq!(list.return_type.as_deref(), Some("[user]"));
+        assert_eq!(list.throws, ["db_error"]);
+        assert!(ast.text(list.span).starts_with("fn list_users(limit: int"));
+
+        let Item::Struct(user) = &ast.items[2] else {
+            panic!("expected struct");
+        };
+        assert_eq!(user.implements, ["named"]);
+        assert_eq!(user.fields[1].ty, "map<string, [int]>");
+        assert!(!user.fields[1].is_public);
+
+        let method = ast.functions().nth(1).unwrap();
+        assert_eq!(method.receiver.as_ref().map(|r| r.ty.as_str()), Some("user"));
+
+        let Item::Enum(shape) = &ast.items[4] else {
+            panic!("expected enum");
+        };
+        assert_eq!(shape.variants[0].fields, ["float"]);
+        assert!(shape.variants[2].fields.is_empty());
+    }
+
+    #[test]
+    fn test_visitor_walks_modules() {
+        let ast = Ast::parse(SOURCE).unwrap();
+        let mut routes = Routes::default();
+        ast.walk(&mut routes);
+        assert_eq!(
+            routes.0,
+            [
+                ("GET".to_string(), "/users".to_string(), "list_users".to_string()),
+                ("POST".to_string(), "/users".to_string(), "create_user".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_view() {
+        let ast = Ast::parse("@tag(x, 2) struct p { x: int }").unwrap();
+        let json = serde_json::to_value(&ast).unwrap();
+        assert_eq!(json["items"][0]["kind"], "struct");
+        assert_eq!(json["items"][0]["attributes"][0]["args"], serde_json::json!(["x", 2]));
+        assert_eq!(json["items"][0]["fields"][0]["type"], "int");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Ast::parse("fn (").unwrap_err();
+        assert!(matches!(err, ParseFileError::Syntax { .. }), "{}", err);
+        let err = parse_file(Path::new("/nonexistent/file.nm")).unwrap_err();
+        assert!(err.to_string().starts_with("cannot read /nonexistent/file.nm"), "{}", err);
+    }
+}
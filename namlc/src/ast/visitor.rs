@@ -107,6 +107,11 @@ pub fn walk_item<'ast, V: Visitor<'ast>>(v: &mut V, item: &Item<'ast>) {
                 if let Some(ref ret) = method.return_ty {
                     v.visit_type(ret);
                 }
+                if let Some(ref body) = method.body {
+                    for stmt in &body.statements {
+                        v.visit_stmt(stmt);
+                    }
+                }
             }
         }
         Item::Enum(e) => {
@@ -209,6 +214,9 @@ pub fn walk_stmt<'ast, V: Visitor<'ast>>(v: &mut V, stmt: &Statement<'ast>) {
         }
         Statement::Throw(s) => {
             v.visit_expr(&s.value);
+            if let Some(ref cause) = s.cause {
+                v.visit_expr(cause);
+            }
         }
         Statement::If(s) => {
             v.visit_expr(&s.condition);
@@ -406,9 +414,18 @@ pub fn walk_expr<'ast, V: Visitor<'ast>>(v: &mut V, expr: &Expression<'ast>) {
         Expression::Try(e) => {
             v.visit_expr(e.expr);
         }
+        Expression::Finally(e) => {
+            v.visit_expr(e.expr);
+            for stmt in &e.body.statements {
+                v.visit_stmt(stmt);
+            }
+        }
         Expression::Catch(e) => {
             v.visit_expr(e.expr);
             v.visit_ident(&e.error_binding);
+            for ty in &e.error_types {
+                v.visit_type(ty);
+            }
             for stmt in &e.handler.statements {
                 v.visit_stmt(stmt);
             }
@@ -0,0 +1,282 @@
+//!
+//! Bench Module - Benchmark Timing and Baselines for `naml bench`
+//!
+//! A benchmark is a function with no parameters and no return value that is
+//! either named `bench_*` or marked `@bench`. Each one is timed in three
+//! phases, with the clock from `std::metrics` (`naml_metrics_perf_now`):
+//!
+//! 1. Warm-up: call it repeatedly for `BenchConfig::warmup_ns`, which also
+//!    estimates the time of one call
+//! 2. Sizing: pick how many calls make up one sample so that all samples
+//!    together take about `BenchConfig::measure_ns`
+//! 3. Measurement: take `BenchConfig::samples` samples and report the mean,
+//!    median and standard deviation of the time per call
+//!
+//! Results can be saved as a named baseline (`build/bench/<name>.json`) and
+//! later runs compared against it. A change is only reported when the
+//! median moved by more than `NOISE_THRESHOLD` and by more than twice the
+//! baseline's standard deviation.
+//!
+//! Usage:
+//!   let names = discover(&ast, &interner);
+//!   let stats = measure(&BenchConfig::default(), || bench_fn(0));
+//!   let change = compare(&stats, &baseline["bench_sort"]);
+//!
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use lasso::Rodeo;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{Item, SourceFile};
+use crate::runtime::naml_metrics_perf_now;
+use crate::typechecker::attributes::has_attribute;
+
+/// Relative change of the median below which results count as unchanged
+pub const NOISE_THRESHOLD: f64 = 0.02;
+
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    pub warmup_ns: i64,
+    pub measure_ns: i64,
+    pub samples: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup_ns: 300_000_000,
+            measure_ns: 1_000_000_000,
+            samples: 50,
+        }
+    }
+}
+
+/// Time per call, in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchStats {
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    pub stddev_ns: f64,
+    /// Calls made while measuring, warm-up excluded
+    pub iterations: u64,
+}
+
+impl BenchStats {
+    /// Statistics over per-call times, one per sample
+    pub fn from_samples(per_call_ns: &[f64], iterations: u64) -> Self {
+        let n = per_call_ns.len().max(1) as f64;
+        let mean = per_call_ns.iter().sum::<f64>() / n;
+        let variance = if per_call_ns.len() > 1 {
+            per_call_ns.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let mut sorted = per_call_ns.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let median = match sorted.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0,
+            len => sorted[len / 2],
+        };
+        Self {
+            mean_ns: mean,
+            median_ns: median,
+            stddev_ns: variance.sqrt(),
+            iterations,
+        }
+    }
+}
+
+/// How a result compares to its baseline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Unchanged(f64),
+    Improved(f64),
+    Regressed(f64),
+}
+
+/// Compare the medians of `current` and `baseline`; the payload is the
+/// relative change (0.05 is 5% slower)
+pub fn compare(current: &BenchStats, baseline: &BenchStats) -> Change {
+    if baseline.median_ns <= 0.0 {
+        return Change::Unchanged(0.0);
+    }
+    let delta = current.median_ns - baseline.median_ns;
+    let ratio = delta / baseline.median_ns;
+    if ratio.abs() <= NOISE_THRESHOLD || delta.abs() <= 2.0 * baseline.stddev_ns {
+        Change::Unchanged(ratio)
+    } else if ratio < 0.0 {
+        Change::Improved(ratio)
+    } else {
+        Change::Regressed(ratio)
+    }
+}
+
+/// Time `f` as described in the module docs
+pub fn measure(config: &BenchConfig, mut f: impl FnMut()) -> BenchStats {
+    let start = naml_metrics_perf_now();
+    let mut warmup_calls: u64 = 0;
+    loop {
+        f();
+        warmup_calls += 1;
+        if naml_metrics_perf_now() - start >= config.warmup_ns {
+            break;
+        }
+    }
+    let per_call = (naml_metrics_perf_now() - start) as f64 / warmup_calls as f64;
+
+    let samples = config.samples.max(1);
+    let budget = config.measure_ns as f64 / samples as f64;
+    let calls_per_sample = ((budget / per_call.max(1.0)) as u64).max(1);
+
+    let mut per_call_ns = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let start = naml_metrics_perf_now();
+        for _ in 0..calls_per_sample {
+            f();
+        }
+        let elapsed = naml_metrics_perf_now() - start;
+        per_call_ns.push(elapsed as f64 / calls_per_sample as f64);
+    }
+    BenchStats::from_samples(&per_call_ns, calls_per_sample * samples as u64)
+}
+
+/// Names of the benchmarks in `file`, in source order
+pub fn discover(file: &SourceFile, interner: &Rodeo) -> Vec<String> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(f)
+                if f.receiver.is_none()
+                    && f.generics.is_empty()
+                    && f.params.is_empty()
+                    && f.return_ty.is_none()
+                    && f.body.is_some() =>
+            {
+                let name = interner.resolve(&f.name.symbol);
+                let marked = has_attribute(&f.attributes, interner, "bench");
+                (marked || name.starts_with("bench_")).then(|| name.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Saved results keyed by `<file>::<function>`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub benches: BTreeMap<String, BenchStats>,
+}
+
+impl Baseline {
+    /// Where the baseline called `name` is stored under `root`
+    pub fn path(root: &Path, name: &str) -> PathBuf {
+        root.join("build").join("bench").join(format!("{}.json", name))
+    }
+
+    pub fn load(path: &Path) -> Result<Baseline, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read baseline {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("invalid baseline {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+}
+
+/// A duration in nanoseconds with a unit that keeps it readable
+pub fn format_ns(ns: f64) -> String {
+    if ns < 1_000.0 {
+        format!("{:.1} ns", ns)
+    } else if ns < 1_000_000.0 {
+        format!("{:.2} µs", ns / 1_000.0)
+    } else if ns < 1_000_000_000.0 {
+        format!("{:.2} ms", ns / 1_000_000.0)
+    } else {
+        format!("{:.2} s", ns / 1_000_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::AstArena;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+
+    fn stats(median_ns: f64, stddev_ns: f64) -> BenchStats {
+        BenchStats {
+            mean_ns: median_ns,
+            median_ns,
+            stddev_ns,
+            iterations: 100,
+        }
+    }
+
+    #[test]
+    fn test_from_samples() {
+        let s = BenchStats::from_samples(&[4.0, 1.0, 3.0, 2.0], 40);
+        assert_eq!(s.mean_ns, 2.5);
+        assert_eq!(s.median_ns, 2.5);
+        assert!((s.stddev_ns - 1.2909944).abs() < 1e-6);
+        assert_eq!(BenchStats::from_samples(&[7.0], 1).stddev_ns, 0.0);
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare(&stats(101.0, 0.0), &stats(100.0, 0.0)), Change::Unchanged(0.01));
+        assert_eq!(compare(&stats(120.0, 0.0), &stats(100.0, 0.0)), Change::Regressed(0.2));
+        assert_eq!(compare(&stats(80.0, 0.0), &stats(100.0, 0.0)), Change::Improved(-0.2));
+        // Within the baseline's noise
+        assert_eq!(compare(&stats(120.0, 0.0), &stats(100.0, 15.0)), Change::Unchanged(0.2));
+    }
+
+    #[test]
+    fn test_measure_adapts_iterations() {
+        let config = BenchConfig {
+            warmup_ns: 1_000_000,
+            measure_ns: 5_000_000,
+            samples: 5,
+        };
+        let mut calls = 0u64;
+        let s = measure(&config, || calls += 1);
+        assert!(s.iterations >= 5 && s.iterations % 5 == 0);
+        assert!(calls > s.iterations);
+        assert!(s.median_ns > 0.0);
+    }
+
+    #[test]
+    fn test_discover() {
+        let source = "fn bench_sort() {}\n@bench fn parse_all() {}\nfn bench_with(n: int) {}\nfn helper() {}";
+        let (tokens, interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        assert_eq!(discover(&parsed.ast, &interner), ["bench_sort", "parse_all"]);
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Baseline::path(dir.path(), "main");
+        let mut baseline = Baseline::default();
+        baseline.benches.insert("a.nm::bench_x".to_string(), stats(5.0, 1.0));
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        assert_eq!(loaded.benches["a.nm::bench_x"], stats(5.0, 1.0));
+        assert!(Baseline::load(&dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_format_ns() {
+        assert_eq!(format_ns(812.0), "812.0 ns");
+        assert_eq!(format_ns(1_234.0), "1.23 µs");
+        assert_eq!(format_ns(2_500_000.0), "2.50 ms");
+    }
+}
@@ -0,0 +1,111 @@
+//!
+//! Build Info - Values embedded by `std::runtime::build_info()`
+//!
+//! The program gets a map of strings fixed when it is compiled:
+//!
+//! - `version`: the naml compiler version
+//! - `git_hash`: `git rev-parse HEAD` in the directory naml runs from, or
+//!   `unknown` outside a git checkout
+//! - `target`: `native-<arch>-<os>`, `edge` or `browser`
+//! - `build_time`: UTC time as `YYYY-MM-DDTHH:MM:SSZ`, taken from
+//!   `SOURCE_DATE_EPOCH` when set so reproducible builds stay byte-identical
+//!
+
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ast::CompilationTarget;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: String,
+    pub git_hash: String,
+    pub target: String,
+    pub build_time: String,
+}
+
+impl BuildInfo {
+    pub fn collect(target: CompilationTarget) -> Self {
+        BuildInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: git_hash().unwrap_or_else(|| "unknown".to_string()),
+            target: target_name(target),
+            build_time: format_utc(build_timestamp()),
+        }
+    }
+
+    /// The map entries, in a fixed order
+    pub fn entries(&self) -> [(&'static str, &str); 4] {
+        [
+            ("version", &self.version),
+            ("git_hash", &self.git_hash),
+            ("target", &self.target),
+            ("build_time", &self.build_time),
+        ]
+    }
+}
+
+fn git_hash() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (!hash.is_empty()).then_some(hash)
+}
+
+fn target_name(target: CompilationTarget) -> String {
+    match target {
+        CompilationTarget::Native => {
+            format!("native-{}-{}", std::env::consts::ARCH, std::env::consts::OS)
+        }
+        CompilationTarget::Edge => "edge".to_string(),
+        CompilationTarget::Browser => "browser".to_string(),
+    }
+}
+
+/// Seconds since the Unix epoch: `SOURCE_DATE_EPOCH` if it holds a valid
+/// timestamp, otherwise the current time
+pub fn build_timestamp() -> i64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0)
+        })
+}
+
+fn format_utc(timestamp_s: i64) -> String {
+    let ms = timestamp_s * 1000;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        crate::runtime::naml_datetime_year(ms),
+        crate::runtime::naml_datetime_month(ms),
+        crate::runtime::naml_datetime_day(ms),
+        crate::runtime::naml_datetime_hour(ms),
+        crate::runtime::naml_datetime_minute(ms),
+        crate::runtime::naml_datetime_second(ms),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_collect() {
+        let info = BuildInfo::collect(CompilationTarget::Edge);
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.target, "edge");
+        assert_eq!(info.entries().map(|(k, _)| k), ["version", "git_hash", "target", "build_time"]);
+    }
+}
@@ -0,0 +1,134 @@
+//!
+//! `naml bench` - Run the benchmarks of every file under a path, print
+//! their timings against an optional saved baseline, and optionally save
+//! the results as a new baseline. Timing and baselines live in
+//! `namlc::bench`.
+//!
+
+use namlc::bench::{compare, format_ns, Baseline, BenchConfig, Change};
+use namlc::{check_with_types, compile_and_bench, parse, tokenize, AstArena, CompilationTarget, DiagnosticReporter, SourceFile};
+
+use crate::{create_package_manager, nm_files};
+
+pub fn bench_code(
+    path: Option<&std::path::Path>,
+    filter: Option<&str>,
+    save_baseline: Option<&str>,
+    baseline: Option<&str>,
+) {
+    let path = path.unwrap_or(std::path::Path::new("."));
+    let root = std::path::Path::new(".");
+    let compare_to = baseline.map(|name| {
+        Baseline::load(&Baseline::path(root, name)).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    });
+
+    let pkg_manager = create_package_manager(path.is_dir().then_some(path).or(path.parent()));
+    let config = BenchConfig::default();
+    let mut results = Baseline::default();
+    let mut regressed = 0;
+    let mut failed = 0;
+    for file in nm_files(path) {
+        let source_text = match std::fs::read_to_string(&file) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                failed += 1;
+                continue;
+            }
+        };
+        // Baseline keys must not depend on how the file was found
+        let file_name = file.strip_prefix(".").unwrap_or(&file).display().to_string();
+        let source_file = SourceFile::new(file_name.clone(), source_text.clone());
+        let (tokens, mut interner) = tokenize(&source_text);
+        let arena = AstArena::new();
+        let parse_result = parse(&tokens, &source_text, &arena);
+        let reporter = DiagnosticReporter::new(&source_file);
+        if !parse_result.errors.is_empty() {
+            reporter.report_parse_errors(&parse_result.errors);
+            failed += 1;
+            continue;
+        }
+
+        let benches: Vec<String> = namlc::bench::discover(&parse_result.ast, &interner)
+            .into_iter()
+            .filter(|name| filter.is_none_or(|f| name.contains(f)))
+            .collect();
+        if benches.is_empty() {
+            continue;
+        }
+
+        let type_result = check_with_types(
+            &parse_result.ast,
+            &mut interner,
+            file.parent().map(|p| p.to_path_buf()),
+            pkg_manager.as_ref(),
+        );
+        if !type_result.errors.is_empty() {
+            reporter.report_type_errors(&type_result.errors);
+            failed += 1;
+            continue;
+        }
+
+        let outcome = compile_and_bench(
+            &parse_result.ast,
+            &interner,
+            &type_result.annotations,
+            &type_result.imported_modules,
+            &source_file,
+            CompilationTarget::Native,
+            &benches,
+            &config,
+            |name, stats| {
+                let key = format!("{}::{}", file_name, name);
+                let change = match compare_to.as_ref().map(|b| b.benches.get(&key)) {
+                    None => String::new(),
+                    Some(None) => "  (not in baseline)".to_string(),
+                    Some(Some(base)) => match compare(stats, base) {
+                        Change::Unchanged(r) => format!("  {:+.1}% no change", r * 100.0),
+                        Change::Improved(r) => format!("  {:+.1}% improved", r * 100.0),
+                        Change::Regressed(r) => {
+                            regressed += 1;
+                            format!("  {:+.1}% regressed", r * 100.0)
+                        }
+                    },
+                };
+                println!(
+                    "{:<40} mean {:>10}  median {:>10}  stddev {:>10}  ({} iterations){}",
+                    key,
+                    format_ns(stats.mean_ns),
+                    format_ns(stats.median_ns),
+                    format_ns(stats.stddev_ns),
+                    stats.iterations,
+                    change
+                );
+            },
+        );
+        match outcome {
+            Ok(stats) => {
+                for (name, s) in stats {
+                    results.benches.insert(format!("{}::{}", file_name, name), s);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", file_name, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if let Some(name) = save_baseline {
+        let target = Baseline::path(root, name);
+        if let Err(e) = results.save(&target) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        println!("Saved baseline '{}' to {}", name, target.display());
+    }
+    println!("{} benchmarks, {} regressed, {} files with errors", results.benches.len(), regressed, failed);
+    if regressed > 0 || failed > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,139 @@
+//!
+//! `naml fuzz` - Find the file that defines a fuzz target, compile it and
+//! run the fuzzer, then report and save the first failure. The fuzzing
+//! itself lives in `namlc::fuzz`.
+//!
+
+use namlc::fuzz::{Corpus, FuzzConfig, FuzzTarget, Outcome};
+use namlc::{check_with_types, compile_and_fuzz, parse, tokenize, AstArena, CompilationTarget, DiagnosticReporter, SourceFile};
+
+use crate::{create_package_manager, nm_files};
+
+pub fn fuzz_code(function: &str, path: Option<&std::path::Path>, corpus_dir: Option<&std::path::Path>, config: &FuzzConfig) {
+    let path = path.unwrap_or(std::path::Path::new("."));
+    let mut found = Vec::new();
+    for file in nm_files(path) {
+        let Ok(source_text) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let (tokens, interner) = tokenize(&source_text);
+        let arena = AstArena::new();
+        let parse_result = parse(&tokens, &source_text, &arena);
+        match FuzzTarget::find(&parse_result.ast, &interner, function) {
+            Ok(Some(_)) => found.push(file),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("Error: {}: {}", file.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let file = match found.as_slice() {
+        [file] => file.clone(),
+        [] => {
+            eprintln!("Error: no function '{}' in {}", function, path.display());
+            std::process::exit(1);
+        }
+        _ => {
+            eprintln!("Error: '{}' is defined in several files; pass one of them:", function);
+            for file in &found {
+                eprintln!("  {}", file.display());
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let source_text = std::fs::read_to_string(&file).unwrap_or_else(|e| {
+        eprintln!("Error reading {}: {}", file.display(), e);
+        std::process::exit(1);
+    });
+    let source_file = SourceFile::new(file.display().to_string(), source_text.clone());
+    let (tokens, mut interner) = tokenize(&source_text);
+    let arena = AstArena::new();
+    let parse_result = parse(&tokens, &source_text, &arena);
+    let reporter = DiagnosticReporter::new(&source_file);
+    if !parse_result.errors.is_empty() {
+        reporter.report_parse_errors(&parse_result.errors);
+        std::process::exit(1);
+    }
+    let pkg_manager = create_package_manager(file.parent());
+    let type_result = check_with_types(
+        &parse_result.ast,
+        &mut interner,
+        file.parent().map(|p| p.to_path_buf()),
+        pkg_manager.as_ref(),
+    );
+    if !type_result.errors.is_empty() {
+        reporter.report_type_errors(&type_result.errors);
+        std::process::exit(1);
+    }
+    let Ok(Some(target)) = FuzzTarget::find(&parse_result.ast, &interner, function) else {
+        unreachable!("target found above");
+    };
+
+    let out_dir = std::path::Path::new("build").join("fuzz").join(function);
+    let corpus_dir = corpus_dir.map_or_else(|| out_dir.join("corpus"), |d| d.to_path_buf());
+    let mut corpus = Corpus::load(&corpus_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    println!(
+        "Fuzzing {} in {} ({} runs, seed {}, {} corpus inputs)",
+        function,
+        file.display(),
+        config.runs,
+        config.seed,
+        corpus.len()
+    );
+
+    let report = compile_and_fuzz(
+        &parse_result.ast,
+        &interner,
+        &type_result.annotations,
+        &type_result.imported_modules,
+        &source_file,
+        CompilationTarget::Native,
+        &target,
+        config,
+        &mut corpus,
+        namlc::fuzz::dictionary(&tokens, &interner),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    println!(
+        "{} runs: {} accepted, {} threw, {} new corpus inputs in {}",
+        report.runs,
+        report.accepted,
+        report.threw,
+        report.new_corpus,
+        corpus_dir.display()
+    );
+
+    let Some(failure) = report.failure else {
+        return;
+    };
+    let what = match &failure.outcome {
+        Outcome::Crashed(how) => format!("crash ({})", how),
+        Outcome::TimedOut => format!("timeout (over {} ms)", config.timeout_ms),
+        Outcome::Threw => "uncaught exception".to_string(),
+        Outcome::Accepted => "failure".to_string(),
+    };
+    println!("\nFound a {}", what);
+    for line in failure.stderr.lines().rev().take(5).collect::<Vec<_>>().into_iter().rev() {
+        println!("  | {}", line);
+    }
+    let shown: String = failure.input.escape_ascii().to_string().chars().take(200).collect();
+    println!(
+        "Input ({} bytes, minimized from {}): \"{}\"",
+        failure.input.len(),
+        failure.original_len,
+        shown
+    );
+    match failure.save(&out_dir.join("crashes")) {
+        Ok(saved) => println!("Saved to {}", saved.display()),
+        Err(e) => eprintln!("Error: {}", e),
+    }
+    std::process::exit(1);
+}
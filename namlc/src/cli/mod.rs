@@ -0,0 +1,6 @@
+//!
+//! Subcommands of the naml CLI that are large enough for a module of their own
+//!
+
+pub mod bench;
+pub mod fuzz;
@@ -0,0 +1,204 @@
+//!
+//! Scalar-Pair Return Convention for Options
+//!
+//! An `option<T>` value is a pointer to a 16-byte stack slot holding the tag
+//! (I32 at offset 0) and the payload (I64 at offset 8). Returning that pointer
+//! from a function hands the caller an address inside a frame that no longer
+//! exists, and forces every `some()` on a hot path through memory.
+//!
+//! Functions whose declared return type is an option instead return the tag
+//! and payload as two scalars in registers. The caller rebuilds the option in
+//! a slot of its own frame, so the value stays valid after the callee returns.
+//!
+//! The pair convention applies to user functions, methods and monomorphized
+//! generics of the file being compiled. Everything else keeps the boxed
+//! single-pointer convention:
+//! - functions referenced as values, since closures are called through a
+//!   `(data, args...) -> i64` signature by both codegen and the runtime
+//! - functions from imported modules, which are compiled before the value
+//!   references of the importing file are known
+//! - `main`, lambdas and spawn trampolines
+//!
+//! Small structs stay heap-allocated: they have reference semantics, so
+//! splitting them into registers would change program behavior.
+//!
+//! User enums are stack slots of varying size, too large for registers. A
+//! returned enum is copied into an arena block; the caller copies it back
+//! into a slot of its own frame and frees the block.
+//!
+
+use std::collections::HashSet;
+
+use cranelift::prelude::*;
+use cranelift_codegen::ir::{Inst, StackSlotData, StackSlotKind};
+use cranelift_frontend::FunctionBuilder;
+use lasso::Rodeo;
+
+use crate::ast::visitor::{walk_expr, Visitor};
+use crate::ast::{Expression, Item, NamlType};
+use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::{types as naml_types, CompileContext, JitCompiler};
+use crate::codegen::CodegenError;
+use crate::typechecker::Type as TcType;
+
+/// Cranelift types of the (tag, payload) pair returned for options
+pub const OPTION_PAIR_RETURNS: [Type; 2] = [types::I32, types::I64];
+
+/// Whether a function with this declared return type can use the pair convention
+pub fn is_option_pair_return(return_ty: Option<&NamlType>) -> bool {
+    matches!(return_ty, Some(NamlType::Option(_)))
+}
+
+/// Whether the function being compiled returns an option as a scalar pair
+pub fn returns_option_pair(builder: &FunctionBuilder<'_>) -> bool {
+    builder.func.signature.returns.len() == OPTION_PAIR_RETURNS.len()
+}
+
+impl JitCompiler<'_> {
+    /// Push the return values of a function declared as `name` with `return_ty`
+    pub(crate) fn push_return_abi(
+        &self,
+        sig: &mut Signature,
+        name: &str,
+        return_ty: Option<&NamlType>,
+    ) {
+        let pair = is_option_pair_return(return_ty)
+            && name != "main"
+            && self
+                .function_values
+                .as_ref()
+                .is_some_and(|values| !values.contains(name));
+        if pair {
+            sig.returns
+                .extend(OPTION_PAIR_RETURNS.iter().map(|&ty| AbiParam::new(ty)));
+        } else if let Some(ty) = return_ty {
+            sig.returns
+                .push(AbiParam::new(naml_types::naml_to_cranelift(ty)));
+        }
+    }
+}
+
+/// Collect the names of functions used as values rather than called directly
+pub fn collect_function_values(items: &[Item<'_>], interner: &Rodeo) -> HashSet<String> {
+    let mut scan = FunctionValueScan { interner, names: HashSet::new() };
+    for item in items {
+        scan.visit_item(item);
+    }
+    scan.names
+}
+
+/// Return an option pointer from a pair-returning function
+pub fn emit_option_pair_return(builder: &mut FunctionBuilder<'_>, option_ptr: Value) {
+    let tag = builder.ins().load(types::I32, MemFlags::new(), option_ptr, 0);
+    let payload = builder.ins().load(types::I64, MemFlags::new(), option_ptr, 8);
+    builder.ins().return_(&[tag, payload]);
+}
+
+/// Return `none` from a pair-returning function
+pub fn emit_none_pair_return(builder: &mut FunctionBuilder<'_>) {
+    let tag = builder.ins().iconst(types::I32, 0);
+    let payload = builder.ins().iconst(types::I64, 0);
+    builder.ins().return_(&[tag, payload]);
+}
+
+/// The value produced by a direct call, rebuilding a returned option pair
+/// in a slot of the caller's frame
+pub fn call_result_value(builder: &mut FunctionBuilder<'_>, call: Inst) -> Value {
+    let results = builder.inst_results(call).to_vec();
+    match results.as_slice() {
+        [] => builder.ins().iconst(types::I64, 0),
+        [tag, payload] => {
+            let slot = builder
+                .create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 16, 0));
+            let option_ptr = builder.ins().stack_addr(types::I64, slot, 0);
+            builder.ins().store(MemFlags::new(), *tag, option_ptr, 0);
+            builder.ins().store(MemFlags::new(), *payload, option_ptr, 8);
+            option_ptr
+        }
+        [value, ..] => *value,
+    }
+}
+
+/// Slot size of a user enum value of this type, `None` for anything else
+pub fn enum_value_size(ctx: &CompileContext<'_>, ty: Option<&TcType>) -> Option<u32> {
+    let name = match ty? {
+        TcType::Enum(e) => ctx.interner.resolve(&e.name),
+        TcType::Generic(name, _) => ctx.interner.resolve(name),
+        _ => return None,
+    };
+    ctx.enum_defs.get(name).map(|def| def.size as u32)
+}
+
+/// Copy an enum out of the current frame into an arena block before returning it
+pub fn emit_enum_escape(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    enum_ptr: Value,
+    size: u32,
+) -> Result<Value, CodegenError> {
+    let alloc_ref = rt_func_ref(ctx, builder, "naml_arena_alloc")?;
+    let size_val = builder.ins().iconst(types::I64, size as i64);
+    let call = builder.ins().call(alloc_ref, &[size_val]);
+    let block_ptr = builder.inst_results(call)[0];
+    copy_words(builder, enum_ptr, block_ptr, size);
+    Ok(block_ptr)
+}
+
+/// Move an enum returned in an arena block into a slot of the caller's frame
+pub fn emit_enum_land(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    block_ptr: Value,
+    size: u32,
+) -> Result<Value, CodegenError> {
+    let slot =
+        builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, size, 0));
+    let enum_ptr = builder.ins().stack_addr(types::I64, slot, 0);
+    copy_words(builder, block_ptr, enum_ptr, size);
+    let free_ref = rt_func_ref(ctx, builder, "naml_arena_free_sized")?;
+    let size_val = builder.ins().iconst(types::I64, size as i64);
+    builder.ins().call(free_ref, &[block_ptr, size_val]);
+    Ok(enum_ptr)
+}
+
+fn copy_words(builder: &mut FunctionBuilder<'_>, src: Value, dst: Value, size: u32) {
+    for offset in (0..size as i32).step_by(8) {
+        let word = builder.ins().load(types::I64, MemFlags::new(), src, offset);
+        builder.ins().store(MemFlags::new(), word, dst, offset);
+    }
+}
+
+/// Records identifiers that appear anywhere except the callee of a direct call
+struct FunctionValueScan<'r> {
+    interner: &'r Rodeo,
+    names: HashSet<String>,
+}
+
+impl<'ast> Visitor<'ast> for FunctionValueScan<'_> {
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        match expr {
+            Expression::Call(call) if matches!(call.callee, Expression::Identifier(_)) => {
+                for arg in &call.args {
+                    self.visit_expr(arg);
+                }
+            }
+            Expression::Identifier(ident) => {
+                self.names.insert(self.interner.resolve(&ident.ident.symbol).to_string());
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_options_use_pairs() {
+        let opt = NamlType::Option(Box::new(NamlType::Int));
+        assert!(is_option_pair_return(Some(&opt)));
+        assert!(!is_option_pair_return(Some(&NamlType::Int)));
+        assert!(!is_option_pair_return(None));
+    }
+}
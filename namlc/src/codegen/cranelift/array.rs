@@ -4,12 +4,37 @@ use crate::codegen::cranelift::misc::ensure_i64;
 use crate::codegen::cranelift::runtime::rt_func_ref;
 use crate::codegen::cranelift::strings::call_string_from_cstr;
 use crate::codegen::cranelift::expr::compile_expression;
+use crate::codegen::cranelift::heap::heap_type_from_type;
 use crate::codegen::cranelift::{
-    ARRAY_CAPACITY_OFFSET, ARRAY_DATA_OFFSET, ARRAY_LEN_OFFSET, CompileContext,
+    ARRAY_CAPACITY_OFFSET, ARRAY_DATA_OFFSET, ARRAY_LEN_OFFSET, CompileContext, HeapType,
 };
+use crate::runtime::{ARRAY_ELEM_FLOAT, ARRAY_ELEM_HEAP, ARRAY_ELEM_STRING, ARRAY_ELEM_VALUE};
+use crate::source::Spanned;
+use crate::typechecker::Type;
 use cranelift::prelude::*;
 use cranelift_module::Module;
 
+/// Runtime element kind (`ARRAY_ELEM_*`) of the array `arr` evaluates to
+pub fn array_elem_kind(ctx: &CompileContext<'_>, arr: &Expression<'_>) -> i64 {
+    match ctx.annotations.get_type(arr.span()).map(Type::resolve) {
+        Some(Type::Array(elem)) | Some(Type::FixedArray(elem, _)) => elem_kind(ctx, &elem),
+        _ => ARRAY_ELEM_VALUE,
+    }
+}
+
+/// Runtime element kind (`ARRAY_ELEM_*`) for values of type `elem`
+pub fn elem_kind(ctx: &CompileContext<'_>, elem: &Type) -> i64 {
+    let elem = elem.resolve();
+    if elem == Type::Float {
+        return ARRAY_ELEM_FLOAT;
+    }
+    match heap_type_from_type(&elem, ctx.interner) {
+        Some(HeapType::String) => ARRAY_ELEM_STRING,
+        Some(HeapType::OptionOf(_)) | None => ARRAY_ELEM_VALUE,
+        Some(_) => ARRAY_ELEM_HEAP,
+    }
+}
+
 pub fn compile_array_literal(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
@@ -51,21 +76,7 @@ pub fn call_array_index(
 ) -> Result<Value, CodegenError> {
     // In unsafe mode, skip bounds checking entirely for maximum performance
     if ctx.unsafe_mode {
-        let data_ptr = builder.ins().load(
-            cranelift::prelude::types::I64,
-            MemFlags::trusted(),
-            arr,
-            ARRAY_DATA_OFFSET,
-        );
-        let offset = builder.ins().ishl_imm(index, 3); // index * 8
-        let elem_addr = builder.ins().iadd(data_ptr, offset);
-        let value = builder.ins().load(
-            cranelift::prelude::types::I64,
-            MemFlags::trusted().with_notrap(),
-            elem_addr,
-            0,
-        );
-        return Ok(value);
+        return Ok(compile_unchecked_array_get(ctx, builder, arr, index, cranelift::prelude::types::I64));
     }
 
     // Safe mode: full bounds checking
@@ -127,6 +138,96 @@ pub fn call_array_index(
     Ok(result)
 }
 
+/// Load arr[index] with no bounds check
+/// Used in unsafe mode and where the index is proven in bounds (see bounds.rs)
+/// F64 element types load directly as floats, everything else as I64
+pub fn compile_unchecked_array_get(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    arr: Value,
+    index: Value,
+    element_type: cranelift::prelude::Type,
+) -> Value {
+    let ptr_type = ctx.module.target_config().pointer_type();
+    let data_ptr = builder.ins().load(
+        ptr_type,
+        MemFlags::trusted().with_notrap(),
+        arr,
+        ARRAY_DATA_OFFSET,
+    );
+    let offset = builder.ins().ishl_imm(index, 3); // index * 8
+    let elem_addr = builder.ins().iadd(data_ptr, offset);
+    let load_type = if element_type == cranelift::prelude::types::F64 {
+        cranelift::prelude::types::F64
+    } else {
+        cranelift::prelude::types::I64
+    };
+    builder.ins().load(
+        load_type,
+        MemFlags::trusted().with_notrap(),
+        elem_addr,
+        0,
+    )
+}
+
+/// Give an array whose buffer is shared with slice views (capacity 0) a
+/// buffer of its own before it is stored to
+fn emit_array_make_mut(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    arr: Value,
+) -> Result<(), CodegenError> {
+    let capacity = builder.ins().load(
+        cranelift::prelude::types::I64,
+        MemFlags::trusted(),
+        arr,
+        ARRAY_CAPACITY_OFFSET,
+    );
+    let shared_block = builder.create_block();
+    let done_block = builder.create_block();
+    builder
+        .ins()
+        .brif(capacity, done_block, &[], shared_block, &[]);
+
+    builder.switch_to_block(shared_block);
+    builder.seal_block(shared_block);
+    let func_ref = rt_func_ref(ctx, builder, "naml_array_make_mut")?;
+    builder.ins().call(func_ref, &[arr]);
+    builder.ins().jump(done_block, &[]);
+
+    builder.switch_to_block(done_block);
+    builder.seal_block(done_block);
+    Ok(())
+}
+
+/// Store to arr[index] with no bounds check
+/// Used in unsafe mode and where the index is proven in bounds (see bounds.rs)
+pub fn compile_unchecked_array_set(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    arr: Value,
+    index: Value,
+    value: Value,
+    element_type: Option<cranelift::prelude::Type>,
+) -> Result<(), CodegenError> {
+    let ptr_type = ctx.module.target_config().pointer_type();
+    let store_value = if element_type == Some(cranelift::prelude::types::F64) {
+        value
+    } else {
+        ensure_i64(builder, value)
+    };
+    emit_array_make_mut(ctx, builder, arr)?;
+    let data_ptr = builder
+        .ins()
+        .load(ptr_type, MemFlags::trusted(), arr, ARRAY_DATA_OFFSET);
+    let offset = builder.ins().ishl_imm(index, 3);
+    let elem_addr = builder.ins().iadd(data_ptr, offset);
+    builder
+        .ins()
+        .store(MemFlags::trusted(), store_value, elem_addr, 0);
+    Ok(())
+}
+
 pub fn call_array_len(
     _ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
@@ -152,6 +253,11 @@ pub fn call_array_set(
     value: Value,
     element_type: Option<cranelift::prelude::Type>,
 ) -> Result<(), CodegenError> {
+    // In unsafe mode, skip bounds checking entirely for maximum performance
+    if ctx.unsafe_mode {
+        return compile_unchecked_array_set(ctx, builder, arr, index, value, element_type);
+    }
+
     let ptr_type = ctx.module.target_config().pointer_type();
 
     // For typed float arrays, store directly without conversion
@@ -162,19 +268,6 @@ pub fn call_array_set(
         ensure_i64(builder, value)
     };
 
-    // In unsafe mode, skip bounds checking entirely for maximum performance
-    if ctx.unsafe_mode {
-        let data_ptr = builder
-            .ins()
-            .load(ptr_type, MemFlags::trusted(), arr, ARRAY_DATA_OFFSET as i32);
-        let offset = builder.ins().ishl_imm(index, 3);
-        let elem_addr = builder.ins().iadd(data_ptr, offset);
-        builder
-            .ins()
-            .store(MemFlags::trusted(), store_value, elem_addr, 0);
-        return Ok(());
-    }
-
     // Safe mode: full bounds checking
     // Load len field
     let len = builder
@@ -196,6 +289,7 @@ pub fn call_array_set(
     // In bounds: store value to data[index]
     builder.switch_to_block(in_bounds_block);
     builder.seal_block(in_bounds_block);
+    emit_array_make_mut(ctx, builder, arr)?;
 
     // Load data pointer
     let data_ptr = builder
@@ -352,27 +446,7 @@ pub fn compile_direct_array_get_or_panic(
 
     // In unsafe mode, skip bounds checking entirely for maximum performance
     if ctx.unsafe_mode {
-        let data_ptr = builder.ins().load(
-            ptr_type,
-            MemFlags::trusted().with_notrap(),
-            arr,
-            ARRAY_DATA_OFFSET,
-        );
-        let offset = builder.ins().ishl_imm(index, 3);
-        let elem_addr = builder.ins().iadd(data_ptr, offset);
-        // Load directly as the target type - eliminates bitcast overhead for floats
-        let load_type = if element_type == cranelift::prelude::types::F64 {
-            cranelift::prelude::types::F64
-        } else {
-            cranelift::prelude::types::I64
-        };
-        let val = builder.ins().load(
-            load_type,
-            MemFlags::trusted().with_notrap(),
-            elem_addr,
-            0,
-        );
-        return Ok(val);
+        return Ok(compile_unchecked_array_get(ctx, builder, arr, index, element_type));
     }
 
     // Safe mode: full bounds checking
@@ -3,6 +3,9 @@ use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
 use cranelift_codegen::ir::Value;
 use cranelift_frontend::FunctionBuilder;
 use crate::ast::{BinaryOp, UnaryOp};
+use crate::codegen::cranelift::exceptions::emit_inline_throw;
+use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::CompileContext;
 use crate::codegen::CodegenError;
 
 pub fn compile_binary_op(
@@ -122,6 +125,111 @@ pub fn compile_binary_op(
     Ok(result)
 }
 
+/// `+`, `-` or `*` on ints that panics instead of wrapping on overflow
+pub fn compile_checked_int_op(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    op: &BinaryOp,
+    lhs: Value,
+    rhs: Value,
+    signed: bool,
+) -> Result<Value, CodegenError> {
+    let (result, overflowed) = match (op, signed) {
+        (BinaryOp::Add, true) => builder.ins().sadd_overflow(lhs, rhs),
+        (BinaryOp::Add, false) => builder.ins().uadd_overflow(lhs, rhs),
+        (BinaryOp::Sub, true) => builder.ins().ssub_overflow(lhs, rhs),
+        (BinaryOp::Sub, false) => builder.ins().usub_overflow(lhs, rhs),
+        (BinaryOp::Mul, true) => builder.ins().smul_overflow(lhs, rhs),
+        (BinaryOp::Mul, false) => builder.ins().umul_overflow(lhs, rhs),
+        _ => return compile_binary_op(builder, op, lhs, rhs),
+    };
+    // Operation codes understood by naml_panic_overflow
+    let op_code = match op {
+        BinaryOp::Add => 0,
+        BinaryOp::Sub => 1,
+        _ => 2,
+    };
+
+    let overflow_block = builder.create_block();
+    let ok_block = builder.create_block();
+    builder.set_cold_block(overflow_block);
+    builder.ins().brif(overflowed, overflow_block, &[], ok_block, &[]);
+
+    builder.switch_to_block(overflow_block);
+    builder.seal_block(overflow_block);
+    let panic_func = rt_func_ref(ctx, builder, "naml_panic_overflow")?;
+    let code = builder.ins().iconst(types::I64, op_code);
+    builder.ins().call(panic_func, &[code]);
+    builder.ins().jump(ok_block, &[]);
+
+    builder.switch_to_block(ok_block);
+    builder.seal_block(ok_block);
+    Ok(result)
+}
+
+/// `/` or `%` on ints with an explicit check for a zero divisor (and for
+/// `int::MIN / -1`), which throws ArithmeticError where it is caught and
+/// panics elsewhere. A throw leaves for the handler at once, so nothing
+/// after the division runs. Both operators truncate toward zero, so the
+/// remainder takes the sign of the dividend.
+pub fn compile_checked_int_division(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    op: &BinaryOp,
+    lhs: Value,
+    rhs: Value,
+    signed: bool,
+) -> Result<Value, CodegenError> {
+    // Kinds understood by naml_throw_arithmetic_error and naml_panic_arithmetic
+    let zero_kind = if *op == BinaryOp::Div { 0 } else { 1 };
+    let is_zero = builder.ins().icmp_imm(IntCC::Equal, rhs, 0);
+    let invalid = if signed && *op == BinaryOp::Div {
+        let min_lhs = builder.ins().icmp_imm(IntCC::Equal, lhs, i64::MIN);
+        let minus_one = builder.ins().icmp_imm(IntCC::Equal, rhs, -1);
+        let overflow = builder.ins().band(min_lhs, minus_one);
+        builder.ins().bor(is_zero, overflow)
+    } else {
+        is_zero
+    };
+
+    let error_block = builder.create_block();
+    let ok_block = builder.create_block();
+    let merge_block = builder.create_block();
+    builder.append_block_param(merge_block, types::I64);
+    builder.set_cold_block(error_block);
+    builder.ins().brif(invalid, error_block, &[], ok_block, &[]);
+
+    builder.switch_to_block(error_block);
+    builder.seal_block(error_block);
+    let zero_kind = builder.ins().iconst(types::I64, zero_kind);
+    let overflow_kind = builder.ins().iconst(types::I64, 2);
+    let kind = builder.ins().select(is_zero, zero_kind, overflow_kind);
+    if ctx.catch_arithmetic {
+        let throw = rt_func_ref(ctx, builder, "naml_throw_arithmetic_error")?;
+        builder.ins().call(throw, &[kind]);
+        emit_inline_throw(ctx, builder)?;
+    } else {
+        let panic = rt_func_ref(ctx, builder, "naml_panic_arithmetic")?;
+        builder.ins().call(panic, &[kind]);
+        let zero = builder.ins().iconst(types::I64, 0);
+        builder.ins().jump(merge_block, &[zero]);
+    }
+
+    builder.switch_to_block(ok_block);
+    builder.seal_block(ok_block);
+    let result = match (op, signed) {
+        (BinaryOp::Div, true) => builder.ins().sdiv(lhs, rhs),
+        (BinaryOp::Div, false) => builder.ins().udiv(lhs, rhs),
+        (_, true) => builder.ins().srem(lhs, rhs),
+        (_, false) => builder.ins().urem(lhs, rhs),
+    };
+    builder.ins().jump(merge_block, &[result]);
+
+    builder.switch_to_block(merge_block);
+    builder.seal_block(merge_block);
+    Ok(builder.block_params(merge_block)[0])
+}
+
 pub fn compile_unary_op(
     builder: &mut FunctionBuilder<'_>,
     op: &UnaryOp,
@@ -0,0 +1,323 @@
+//!
+//! Bounds-Check Elimination for Array Loops
+//!
+//! Proves that an index variable stays inside an array for the whole body of
+//! a `for` loop, so accesses like `arr[i]!` and `arr[i] = v` can skip their
+//! bounds check. Applies in release mode only; unsafe mode drops every check
+//! anyway and debug builds keep them all.
+//!
+//! Recognized loops (`arr` must be a local array variable):
+//! - `for (i, x in arr)` - proves `i` for `arr` and the element load itself
+//! - `for (i in N..count(arr))` with a literal N >= 0
+//! - `for (i in N..=count(arr) - 1)` with a literal N >= 0
+//!
+//! The proof holds because the index starts non-negative, stays below the
+//! length read at loop entry, and array lengths only grow unless something
+//! shrinks them. The body is therefore rejected if it could shrink `arr` or
+//! rebind either name:
+//! - assigning to or re-declaring `i` or `arr` (including nested loop,
+//!   lambda, catch and pattern bindings)
+//! - calling anything other than a known non-shrinking builtin; user
+//!   functions, methods and closures could reach `arr` through an alias
+//! - spawn blocks, which run concurrently with the loop
+//!
+
+use lasso::Spur;
+
+use crate::ast::visitor::{walk_expr, walk_pattern, walk_stmt, Visitor};
+use crate::ast::{
+    BinaryOp, Expression, ForStmt, Ident, Literal, LiteralExpr, Pattern, Statement,
+    TemplateStringPart,
+};
+use crate::codegen::cranelift::CompileContext;
+use crate::codegen::cranelift::builtins::{BuiltinFunction, BuiltinStrategy, lookup_builtin};
+use crate::source::{Span, Spanned};
+use crate::typechecker::Type;
+
+/// An index variable proven to be within bounds of an array variable
+#[derive(Clone, Debug, PartialEq)]
+pub struct InBoundsFact {
+    pub index: String,
+    pub array: String,
+}
+
+/// Bounds facts established by a `for` loop, valid while its body compiles
+#[derive(Default)]
+pub struct LoopBounds {
+    pub facts: Vec<InBoundsFact>,
+    /// The implicit element load of `for (x in arr)` needs no check
+    pub element_load: bool,
+}
+
+/// Builtins that never shrink an array (`collections::arrays` members that
+/// read, grow, or rewrite in place without taking a closure)
+const NON_SHRINKING_ARRAY_BUILTINS: &[&str] = &[
+    "count", "push", "reserved", "get", "first", "last", "sum", "min", "max",
+    "contains", "index_of", "last_index_of", "fill", "swap", "capacity", "reserve",
+    "shrink_to_fit", "extend",
+];
+
+/// Analyze a `for` loop and return the bounds facts its body may rely on
+pub fn analyze_for_loop(ctx: &CompileContext<'_>, for_stmt: &ForStmt<'_>) -> LoopBounds {
+    if !ctx.release_mode || ctx.unsafe_mode {
+        return LoopBounds::default();
+    }
+
+    // for (i, x in arr)
+    if let Expression::Identifier(arr) = &for_stmt.iterable {
+        if !is_local_array(ctx, &for_stmt.iterable, arr.ident.symbol) {
+            return LoopBounds::default();
+        }
+        let mut bound = vec![arr.ident.symbol, for_stmt.value.symbol];
+        bound.extend(for_stmt.index.as_ref().map(|i| i.symbol));
+        if !body_preserves_bounds(ctx, for_stmt, &bound) {
+            return LoopBounds::default();
+        }
+        let facts = for_stmt
+            .index
+            .iter()
+            .map(|i| InBoundsFact {
+                index: ctx.interner.resolve(&i.symbol).to_string(),
+                array: ctx.interner.resolve(&arr.ident.symbol).to_string(),
+            })
+            .collect();
+        return LoopBounds { facts, element_load: true };
+    }
+
+    // for (i in N..count(arr)) and for (i in N..=count(arr) - 1)
+    let Some((start, end, inclusive)) = range_parts(&for_stmt.iterable) else {
+        return LoopBounds::default();
+    };
+    if !matches!(start, Expression::Literal(LiteralExpr { value: Literal::Int(n), .. }) if *n >= 0) {
+        return LoopBounds::default();
+    }
+    let count_arg = if inclusive {
+        match end {
+            Expression::Binary(bin)
+                if bin.op == BinaryOp::Sub
+                    && matches!(bin.right, Expression::Literal(LiteralExpr { value: Literal::Int(1), .. })) =>
+            {
+                array_count_arg(ctx, bin.left)
+            }
+            _ => None,
+        }
+    } else {
+        array_count_arg(ctx, end)
+    };
+    let Some(arr_expr) = count_arg else {
+        return LoopBounds::default();
+    };
+    let Expression::Identifier(arr) = arr_expr else {
+        return LoopBounds::default();
+    };
+    if !is_local_array(ctx, arr_expr, arr.ident.symbol) {
+        return LoopBounds::default();
+    }
+
+    let mut bound = vec![arr.ident.symbol, for_stmt.value.symbol];
+    bound.extend(for_stmt.index.as_ref().map(|i| i.symbol));
+    if !body_preserves_bounds(ctx, for_stmt, &bound) {
+        return LoopBounds::default();
+    }
+    LoopBounds {
+        facts: vec![InBoundsFact {
+            index: ctx.interner.resolve(&for_stmt.value.symbol).to_string(),
+            array: ctx.interner.resolve(&arr.ident.symbol).to_string(),
+        }],
+        element_load: false,
+    }
+}
+
+/// Whether `base[index]` is covered by a fact from an enclosing loop
+pub fn is_index_in_bounds(
+    ctx: &CompileContext<'_>,
+    base: &Expression<'_>,
+    index: &Expression<'_>,
+) -> bool {
+    if ctx.bounds_facts.is_empty() {
+        return false;
+    }
+    let (Expression::Identifier(arr), Expression::Identifier(idx)) = (base, index) else {
+        return false;
+    };
+    let arr_name = ctx.interner.resolve(&arr.ident.symbol);
+    let idx_name = ctx.interner.resolve(&idx.ident.symbol);
+    ctx.bounds_facts
+        .iter()
+        .any(|fact| fact.array == arr_name && fact.index == idx_name)
+}
+
+fn range_parts<'a, 'ast>(
+    iterable: &'a Expression<'ast>,
+) -> Option<(&'a Expression<'ast>, &'a Expression<'ast>, bool)> {
+    match iterable {
+        Expression::Binary(bin) if matches!(bin.op, BinaryOp::Range | BinaryOp::RangeIncl) => {
+            Some((bin.left, bin.right, bin.op == BinaryOp::RangeIncl))
+        }
+        Expression::Range(range) => Some((range.start?, range.end?, range.inclusive)),
+        _ => None,
+    }
+}
+
+fn is_local_array(ctx: &CompileContext<'_>, expr: &Expression<'_>, name: Spur) -> bool {
+    ctx.variables.contains_key(ctx.interner.resolve(&name))
+        && matches!(ctx.annotations.get_type(expr.span()), Some(Type::Array(_)))
+}
+
+/// The array argument of a `count(arr)` call to the collections builtin
+fn array_count_arg<'a, 'ast>(
+    ctx: &CompileContext<'_>,
+    expr: &'a Expression<'ast>,
+) -> Option<&'a Expression<'ast>> {
+    let Expression::Call(call) = expr else {
+        return None;
+    };
+    let builtin = resolve_builtin(ctx, call.callee, call.span)?;
+    if matches!(builtin.strategy, BuiltinStrategy::ArrayLength) && call.args.len() == 1 {
+        Some(&call.args[0])
+    } else {
+        None
+    }
+}
+
+/// Resolve a call to a builtin the same way expression codegen does
+pub(crate) fn resolve_builtin(
+    ctx: &CompileContext<'_>,
+    callee: &Expression<'_>,
+    span: Span,
+) -> Option<&'static BuiltinFunction> {
+    let Expression::Identifier(ident) = callee else {
+        return None;
+    };
+    let func_name = match ctx.annotations.get_aliased_call(span) {
+        Some(original) => original.as_str(),
+        None => ctx.interner.resolve(&ident.ident.symbol),
+    };
+    let actual_name = ctx
+        .annotations
+        .get_call_instantiation(span)
+        .map(|mangled| mangled.as_str())
+        .unwrap_or(func_name);
+    if ctx.functions.contains_key(actual_name) {
+        return None;
+    }
+    let qualified = ctx
+        .annotations
+        .get_resolved_module(span)
+        .map(|module| format!("{}::{}", module, func_name));
+    qualified
+        .and_then(|name| lookup_builtin(&name, ctx.target))
+        .or_else(|| lookup_builtin(func_name, ctx.target))
+}
+
+fn is_non_shrinking_builtin(name: &str) -> bool {
+    if let Some(func) = name.strip_prefix("collections::arrays::") {
+        return NON_SHRINKING_ARRAY_BUILTINS.contains(&func);
+    }
+    name.starts_with("strings::") || matches!(name, "print" | "println" | "fmt" | "warn" | "error")
+}
+
+fn body_preserves_bounds(ctx: &CompileContext<'_>, for_stmt: &ForStmt<'_>, bound: &[Spur]) -> bool {
+    let mut scan = BodyScan { ctx, bound, ok: true, in_pattern: false };
+    for stmt in &for_stmt.body.statements {
+        scan.visit_stmt(stmt);
+    }
+    scan.ok
+}
+
+/// Walks a loop body looking for anything that could invalidate a bounds fact
+struct BodyScan<'c, 'a> {
+    ctx: &'c CompileContext<'a>,
+    bound: &'c [Spur],
+    ok: bool,
+    in_pattern: bool,
+}
+
+impl BodyScan<'_, '_> {
+    fn check_binding(&mut self, name: Spur) {
+        if self.bound.contains(&name) {
+            self.ok = false;
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for BodyScan<'_, '_> {
+    fn visit_stmt(&mut self, stmt: &Statement<'ast>) {
+        if !self.ok {
+            return;
+        }
+        match stmt {
+            Statement::Var(var) => {
+                self.check_binding(var.name.symbol);
+                if let Some(ref else_block) = var.else_block {
+                    for stmt in &else_block.statements {
+                        self.visit_stmt(stmt);
+                    }
+                }
+            }
+            Statement::Const(c) => self.check_binding(c.name.symbol),
+            Statement::Assign(assign) => {
+                if let Expression::Identifier(target) = &assign.target {
+                    self.check_binding(target.ident.symbol);
+                }
+            }
+            Statement::For(f) => {
+                self.check_binding(f.value.symbol);
+                if let Some(ref idx) = f.index {
+                    self.check_binding(idx.symbol);
+                }
+            }
+            Statement::Locked(l) => self.check_binding(l.binding.symbol),
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        if !self.ok {
+            return;
+        }
+        match expr {
+            Expression::Call(call) => {
+                let allowed = resolve_builtin(self.ctx, call.callee, call.span)
+                    .is_some_and(|builtin| is_non_shrinking_builtin(builtin.name));
+                if !allowed {
+                    self.ok = false;
+                    return;
+                }
+            }
+            Expression::MethodCall(_) | Expression::Spawn(_) => {
+                self.ok = false;
+                return;
+            }
+            // Interpolations are parsed during codegen and may contain calls
+            Expression::TemplateString(template)
+                if template.parts.iter().any(|part| matches!(part, TemplateStringPart::Expression(_))) =>
+            {
+                self.ok = false;
+                return;
+            }
+            Expression::Lambda(lambda) => {
+                for param in &lambda.params {
+                    self.check_binding(param.name.symbol);
+                }
+            }
+            Expression::Catch(catch) => self.check_binding(catch.error_binding.symbol),
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern<'ast>) {
+        let was_in_pattern = self.in_pattern;
+        self.in_pattern = true;
+        walk_pattern(self, pattern);
+        self.in_pattern = was_in_pattern;
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if self.in_pattern {
+            self.check_binding(ident.symbol);
+        }
+    }
+}
@@ -25,20 +25,25 @@ use super::misc::{
     call_int_runtime, call_one_arg_int_runtime, call_one_arg_ptr_runtime,
     call_three_arg_int_runtime, call_three_arg_ptr_runtime, call_three_arg_void_runtime,
     call_two_arg_bool_runtime, call_two_arg_int_runtime, call_two_arg_ptr_runtime,
-    call_two_arg_runtime, call_void_runtime, ensure_i64,
+    call_two_arg_runtime, call_void_runtime, ensure_i64, from_generic_word,
 };
 use super::options::{
-    compile_option_from_array_access, compile_option_from_array_get, compile_option_from_index_of,
+    compile_option_from_array_access, compile_option_from_array_get, compile_option_from_found_index, compile_option_from_index_of,
     compile_option_from_last_index_of, compile_option_from_map_first,
     compile_option_from_map_remove, compile_option_from_minmax, compile_option_from_nullable_ptr,
     compile_option_from_remove_at,
 };
-use super::heap::heap_type_from_type;
-use super::runtime::emit_incref;
+use super::heap::{heap_type_from_type, HeapType};
+use super::ownership::is_owned_value;
+use super::runtime::{emit_decref, emit_incref};
 use super::strings::call_string_from_cstr;
+use super::map::key_kind;
 use super::{ARRAY_LEN_OFFSET, CompileContext};
 use crate::ast::{Expression, Literal, LiteralExpr};
 use crate::codegen::CodegenError;
+use crate::runtime::{ARRAY_ELEM_VALUE, MAP_KEY_INT};
+use crate::source::Span;
+use crate::typechecker::Type as TcType;
 use crate::ast::{CompilationTarget, Platform};
 
 const ALL: &[Platform] = &[Platform::Native, Platform::Edge, Platform::Browser];
@@ -58,18 +63,20 @@ pub enum BuiltinStrategy {
     OneArgOptionAccess(&'static str),
     /// One arg -> int return
     OneArgInt(&'static str),
+    /// One arg -> bool return
+    OneArgBool(&'static str),
     /// One arg -> ptr return
     OneArgPtr(&'static str),
     /// Two args -> ptr return
     TwoArgPtr(&'static str),
-    /// Three args -> ptr return
-    ThreeArgPtr(&'static str),
     /// Array get (arr, index) -> option
     ArrayGet,
     /// Array fill (arr, val) -> unit
     ArrayFill,
     /// Array clear (arr) -> unit
     ArrayClear,
+    /// One arg -> void (shrink_to_fit)
+    OneArgVoid(&'static str),
     /// Array min/max with is_min flag
     ArrayMinMax(&'static str, bool),
     /// Array index_of (arr, val) -> option<int>
@@ -84,13 +91,21 @@ pub enum BuiltinStrategy {
     TwoArgBool(&'static str),
     /// Two args -> option<int> using index_of pattern
     ArrayLastIndexOf,
+    /// (arr, ...) -> ptr with the element kind of arr appended (unique, zip)
+    ArrayKinded(&'static str),
+    /// (arr, val) -> int with the element kind appended (lower_bound, upper_bound)
+    ArraySortedSearch(&'static str),
+    /// Sorted array search (arr, val) -> option<int>
+    ArrayBinarySearch,
 
     // === IO Module ===
     /// No args -> int return (read_key, terminal_width, etc.)
     NoArgInt(&'static str),
+    /// No args -> bool return (is_cancelled)
+    NoArgBool(&'static str),
     /// No args -> void (clear_screen, hide_cursor, show_cursor)
     NoArgVoid(&'static str),
-    /// Two args -> void (set_cursor)
+    /// Two args -> void (set_cursor, reserve, extend)
     TwoArgVoid(&'static str),
 
     // === Random Module ===
@@ -105,16 +120,20 @@ pub enum BuiltinStrategy {
     /// (timestamp, fmt) -> string
     DatetimeFormat,
 
+    // === Runtime Module ===
+    /// () -> map<string, string> of values fixed at compile time
+    BuildInfo,
+
     // === Strings Module ===
-    /// One arg string -> int (len/char_len)
+    /// One arg string -> int (len/char_len, width)
     StringOneArgInt(&'static str),
-    /// One arg string -> ptr (upper, lower, ltrim, rtrim)
+    /// One arg string -> ptr (upper, lower, ltrim, rtrim, case styles)
     StringOneArgPtr(&'static str),
     /// (string, string) -> bool (has/contains, starts_with, ends_with)
     StringTwoArgBool(&'static str),
     /// (string, int) -> int (char_at)
     StringArgIntInt(&'static str),
-    /// (string, string) -> ptr (split returns array)
+    /// (string, string) -> ptr (split returns array, normalize)
     StringTwoArgPtr(&'static str),
     /// (string, string, string) -> ptr (replace, replace_all)
     StringThreeArgPtr(&'static str),
@@ -124,20 +143,54 @@ pub enum BuiltinStrategy {
     StringArgIntStrPtr(&'static str),
     /// (string, int) -> ptr (repeat)
     StringArgIntPtr(&'static str),
+    /// (string, string) -> int (compare, natural_compare)
+    StringTwoArgInt(&'static str),
+    /// (string, string, string) -> int (collate)
+    StringThreeArgInt(&'static str),
     /// (array<string>, string) -> string (concat/join)
     StringJoin,
+    /// () -> string_builder
+    StringBuilderNew(&'static str),
+    /// (string_builder, string | int) -> unit (sb_append, sb_append_int)
+    StringBuilderAppend(&'static str),
+
+    // === Sets / Priority Queues ===
+    /// () -> set<T> | priority_queue<T>, passing the element kind of the call's type
+    OrderedNew(&'static str),
+    /// (set<T> | priority_queue<T>, T) -> bool (set_add, set_contains, set_remove)
+    OrderedElementBool(&'static str),
+    /// (priority_queue<T>, T) -> unit (pq_push)
+    OrderedElementVoid(&'static str),
+
+    // === Deques ===
+    /// () -> deque<T>, passing the `ARRAY_ELEM_*` kind of the call's type
+    DequeNew,
+    /// (deque<T>, T) -> unit (push_front, push_back)
+    DequePush(&'static str),
 
     // === Threads/Channel Module ===
     /// No args -> void (join/wait_all)
     ThreadsJoin,
+    /// (task) -> T, waiting for the task to finish
+    TaskResult,
+    /// (condvar, mutex<T>, T) -> T
+    CondvarWait,
     /// (capacity) -> channel
     ChannelOpen,
     /// (channel, value) -> int
     ChannelSend,
     /// (channel) -> option<T>
     ChannelReceive,
+    /// (channel, value) -> bool, without blocking
+    ChannelTrySend,
+    /// (channel) -> option<T>, without blocking
+    ChannelTryReceive,
+    /// (channel, ms) -> option<T>
+    ChannelReceiveTimeout,
     /// (channel) -> void
     ChannelClose,
+    /// ([channel<T>], handler) -> option<R>
+    ChannelSelect,
     /// (value) -> mutex<T>
     MutexNew,
     /// (value) -> rwlock<T>
@@ -166,6 +219,16 @@ pub enum BuiltinStrategy {
     AtomicOr,
     /// (atomic<T>, T) -> T
     AtomicXor,
+    /// (group: int, task: fn()) -> unit
+    GroupSpawn,
+    /// (behavior: fn(string) -> string) -> int
+    ActorSpawn,
+    /// (actor: int, message: string) -> unit
+    ActorSend,
+    /// (actor: int, message: string, timeout_ms: int) -> string throws TimeoutError
+    ActorAsk,
+    /// (actor: int) -> unit
+    ActorStop,
 
     // ========================================
     // Lambda-based collection strategies
@@ -184,12 +247,14 @@ pub enum BuiltinStrategy {
     LambdaFindLast,
     /// (arr, closure) -> option<int> (find_last_index)
     LambdaFindLastIndex,
-    /// (arr, initial, closure) -> T (fold)
-    LambdaFold,
+    /// (arr, initial, closure) -> T (fold, par_fold)
+    LambdaFold(&'static str),
     /// (arr, initial, closure) -> array (scan)
     LambdaScan,
     /// (arr, closure) -> array (sort_by)
     LambdaSortBy,
+    /// (arr, closure) -> map keyed by the closure's return type (group_by)
+    LambdaGroupBy,
     /// (arr) -> option<T> (sample - random element)
     Sample,
 
@@ -240,6 +305,8 @@ pub enum BuiltinStrategy {
     FsAppend,
     /// (path, bytes) -> unit throws IOError
     FsWriteBytes,
+    /// (path, content) -> unit throws IOError (write_atomic)
+    FsWriteAtomic,
     /// (path, bytes) -> unit throws IOError
     FsAppendBytes,
     /// (path) -> bool
@@ -250,6 +317,8 @@ pub enum BuiltinStrategy {
     FsIsDir,
     /// (path) -> [string] throws IOError
     FsListDir,
+    /// (pattern) -> [string] throws IOError
+    FsGlob,
     /// (path) -> unit throws IOError
     FsMkdir,
     /// (path) -> unit throws IOError
@@ -275,6 +344,58 @@ pub enum BuiltinStrategy {
     /// (src, dst) -> unit throws IOError
     FsCopy,
     /// (src, dst) -> unit throws IOError
+    FsCopyVerified,
+    /// (path, expected_sha256) -> bool throws IOError
+    FsVerifyFile,
+    /// () -> int
+    FsCopyOptions,
+    /// (opts, pattern) -> unit throws IOError
+    FsCopyOptionsInclude,
+    /// (opts, pattern) -> unit throws IOError
+    FsCopyOptionsExclude,
+    /// (opts, policy) -> unit throws IOError
+    FsCopyOptionsOverwrite,
+    /// (opts, callback: fn(int, int)) -> unit
+    FsCopyOptionsOnProgress,
+    /// (opts) -> unit
+    FsCopyOptionsFree,
+    /// (src, dst, opts) -> unit throws IOError
+    FsCopyTree,
+    /// (src, dst, opts) -> unit throws IOError
+    FsMoveTree,
+    /// () -> int
+    FsWalkOptions,
+    /// (opts, depth) -> unit throws IOError
+    FsWalkOptionsMaxDepth,
+    /// (opts, follow: bool) -> unit throws IOError
+    FsWalkOptionsFollowSymlinks,
+    /// (opts, pattern) -> unit throws IOError
+    FsWalkOptionsInclude,
+    /// (opts, pattern) -> unit throws IOError
+    FsWalkOptionsExclude,
+    /// (opts) -> unit
+    FsWalkOptionsFree,
+    /// (path, opts) -> [string] throws IOError
+    FsWalkDir,
+    /// (path, opts) -> int throws IOError
+    FsWalkOpen,
+    /// (handle) -> option<string> throws IOError
+    FsWalkNext,
+    /// (handle) -> unit
+    FsWalkClose,
+    /// (path) -> channel<string>
+    FsReadAsync,
+    /// (path, content) -> channel<int>
+    FsWriteAsync,
+    /// (path) -> channel<[string]>
+    FsListDirAsync,
+    /// (channel<T>) -> T throws IOError
+    FsAwaitIo,
+    /// (path, fn(fs_event)) -> int throws IOError; runtime fn selects recursion
+    FsWatch(&'static str),
+    /// (handle) -> unit
+    FsUnwatch,
+    /// (src, dst) -> unit throws IOError
     FsRename,
     /// () -> string throws IOError
     FsGetwd,
@@ -284,6 +405,12 @@ pub enum BuiltinStrategy {
     FsCreateTemp,
     /// (prefix) -> string throws IOError
     FsMkdirTemp,
+    /// (prefix, body: fn(string)) -> unit throws IOError
+    FsWithTempFile,
+    /// (prefix, body: fn(string)) -> unit throws IOError
+    FsWithTempDir,
+    /// (path) -> unit
+    FsTempFileKeep,
     /// (path, mode) -> unit throws IOError
     FsChmod,
     /// (path, size) -> unit throws IOError
@@ -330,6 +457,18 @@ pub enum BuiltinStrategy {
     FsFileWriteLine,
     /// (handle) -> unit throws IOError
     FsFileFlush,
+    /// (handle) -> unit throws IOError
+    FsFileFsync,
+    /// (handle) -> unit throws IOError
+    FsFileFdatasync,
+    /// (handle) -> unit throws IOError
+    FsLockExclusive,
+    /// (handle) -> unit throws IOError
+    FsLockShared,
+    /// (handle) -> bool throws IOError
+    FsTryLock,
+    /// (handle) -> unit throws IOError
+    FsUnlock,
     /// (handle, offset, whence) -> int throws IOError
     FsFileSeek,
     /// (handle) -> int throws IOError
@@ -358,6 +497,14 @@ pub enum BuiltinStrategy {
     FsLchown,
     /// (path1, path2) -> bool throws IOError
     FsSameFile,
+    /// (path) -> int throws IOError
+    FsDiskFree,
+    /// (path) -> int throws IOError
+    FsDiskTotal,
+    /// (path) -> string throws IOError
+    FsFsType,
+    /// (path1, path2) -> bool throws IOError
+    FsIsSameFilesystem,
 
     // ========================================
     // Additional file handle strategies
@@ -440,6 +587,14 @@ pub enum BuiltinStrategy {
     OsGetegid,
     /// () -> [int] throws OSError (getgroups)
     OsGetgroups,
+    /// (service: string, user: string, password: secret) throws OSError
+    OsKeyringSetPassword,
+    /// (service: string, user: string) -> secret throws OSError
+    OsKeyringGetPassword,
+    /// (service: string, user: string) throws OSError
+    OsKeyringDeletePassword,
+    /// () -> [iface_info] throws OSError (net::interfaces)
+    OsNetInterfaces,
 
     // ========================================
     // Process module strategies
@@ -475,6 +630,32 @@ pub enum BuiltinStrategy {
     ProcessSigstop,
     ProcessSigcont,
 
+    // ========================================
+    // Worker pool strategies
+    // ========================================
+    /// (workers: int, entry: fn(bytes) -> bytes) -> int throws ProcessError
+    ProcessPoolOpen,
+    /// (pool: int, payload: bytes) -> int (job handle) throws ProcessError
+    ProcessPoolSubmit,
+    /// (job: int) -> bytes throws ProcessError
+    ProcessPoolAwait,
+    /// (pool: int) -> unit
+    ProcessPoolClose,
+
+    // ========================================
+    // IPC module strategies
+    // ========================================
+    /// (name: string, size: int) -> int throws ProcessError
+    IpcCreate,
+    /// (name: string) -> int throws ProcessError
+    IpcConnect,
+    /// (channel: int, data: bytes) throws ProcessError
+    IpcSend,
+    /// (channel: int) -> bytes throws ProcessError
+    IpcReceive,
+    /// (channel: int) -> unit
+    IpcClose,
+
     // ========================================
     // Testing module strategies
     // ========================================
@@ -532,6 +713,18 @@ pub enum BuiltinStrategy {
     CryptoPbkdf2(&'static str),
     /// (int) -> bytes (random bytes)
     CryptoRandomBytes(&'static str),
+    /// (string, int...) -> string throws DecodeError (OTP code from base32 secret)
+    CryptoOtpCode(&'static str),
+    /// (string, string, int) -> bool throws DecodeError (TOTP verify)
+    CryptoOtpVerify(&'static str),
+    /// (string | bytes) -> secret (wrap credential material)
+    CryptoSecretNew(&'static str),
+    /// (secret) -> string (explicit plaintext access)
+    CryptoSecretExpose(&'static str),
+    /// (secret) -> int (length in bytes)
+    CryptoSecretLen(&'static str),
+    /// (secret, secret) -> bool (constant-time comparison)
+    CryptoSecretEquals(&'static str),
 
     // ========================================
     // Encoding module strategies
@@ -542,10 +735,13 @@ pub enum BuiltinStrategy {
     EncodingStringToBytes(&'static str),
     /// (bytes) -> bool (validation)
     EncodingValidate(&'static str),
-    /// (bytes, out_tag, out_value) -> throwing decode to string
+    /// (bytes | string, out_tag, out_value) -> throwing decode to string
     EncodingDecodeToString(&'static str),
     /// (string, out_tag, out_value) -> throwing decode to bytes
     EncodingDecodeToBytes(&'static str),
+    /// (string | [[string]], delimiter | null, out_tag, out_value) -> throwing CSV call;
+    /// the flag says whether the caller passes a delimiter
+    EncodingCsv(&'static str, bool),
 
     // ========================================
     // JSON encoding strategies
@@ -585,6 +781,86 @@ pub enum BuiltinStrategy {
     /// (json) -> string throws EncodeError
     YamlEncode,
 
+    // ========================================
+    // MessagePack encoding strategies
+    // ========================================
+    /// (json) -> bytes
+    MsgpackEncode,
+    /// (bytes) -> json throws DecodeError
+    MsgpackDecode,
+
+    // ========================================
+    // CBOR encoding strategies
+    // ========================================
+    /// (json) -> bytes
+    CborEncode,
+    /// (bytes) -> json throws DecodeError
+    CborDecode,
+
+    // ========================================
+    // Compression strategies
+    // ========================================
+    /// (data: bytes, level: int) -> bytes
+    Compress(&'static str),
+    /// (data: bytes) -> bytes throws DecodeError
+    Decompress(&'static str),
+
+    // ========================================
+    // Validation strategies
+    // ========================================
+    /// (s: string) -> bool
+    ValidateFormat(&'static str),
+    /// (args...) -> runtime result, or unit when it returns nothing
+    ValidateCall(&'static str),
+
+    // ========================================
+    // Money strategies
+    // ========================================
+    /// (args...) -> money, string, int or float; MoneyError is raised by the runtime
+    MoneyCall(&'static str),
+
+    // ========================================
+    // Geo strategies
+    // ========================================
+    /// (args...) -> float, string or array
+    GeoCall(&'static str),
+    /// (args...) -> bool
+    GeoPredicate(&'static str),
+    /// (hash: string) -> array throws DecodeError
+    GeoDecode(&'static str),
+
+    // ========================================
+    // Regex strategies
+    // ========================================
+    /// (handle: int, s: string) -> bool
+    RegexMatch(&'static str),
+    /// (args...) -> int, string, array or unit; RegexError is raised by the runtime
+    RegexCall(&'static str),
+
+    // ========================================
+    // Image strategies
+    // ========================================
+    /// (args...) -> int, bytes or unit; ImageError is raised by the runtime
+    ImageCall(&'static str),
+
+    // ========================================
+    // PDF strategies
+    // ========================================
+    /// (args...) -> int, string, bytes or unit; PdfError is raised by the runtime
+    PdfCall(&'static str),
+
+    // ========================================
+    // Spreadsheet strategies
+    // ========================================
+    /// (args...) -> int, array, bytes or unit; XlsxError is raised by the runtime
+    XlsxCall(&'static str),
+
+    // ========================================
+    // Markdown strategies
+    // ========================================
+    /// (args...) -> int, string or unit; MarkdownError is raised by the runtime
+    MarkdownCall(&'static str),
+
     // ========================================
     // Binary encoding strategies
     // ========================================
@@ -629,6 +905,8 @@ pub enum BuiltinStrategy {
     // TCP Server
     /// (address: string) -> int throws NetworkError
     NetTcpListen,
+    /// (address: string, backlog: int) -> int throws NetworkError
+    NetTcpListenBacklog,
     /// (listener: int) -> int throws NetworkError
     NetTcpAccept,
     /// (listener: int) -> unit
@@ -651,6 +929,14 @@ pub enum BuiltinStrategy {
     NetTcpSetTimeout,
     /// (socket: int) -> string
     NetTcpPeerAddr,
+    /// (socket: int, enabled: bool) -> unit throws NetworkError
+    NetTcpSetNodelay,
+    /// (socket: int, idle_ms: int, interval_ms: int, count: int) -> unit throws NetworkError
+    NetTcpSetKeepalive,
+    /// (socket: int, size: int) -> unit throws NetworkError
+    NetTcpSetRecvBuffer,
+    /// (socket: int, ms: int) -> unit throws NetworkError
+    NetTcpSetLinger,
 
     // UDP
     /// (address: string) -> int throws NetworkError
@@ -677,6 +963,14 @@ pub enum BuiltinStrategy {
     NetHttpDelete,
     /// (ms: int) -> unit
     NetHttpSetTimeout,
+    /// (url: string) -> unit throws NetworkError
+    NetHttpSetProxy,
+    /// (hosts: string) -> unit
+    NetHttpSetNoProxy,
+    /// () -> unit
+    NetHttpResetProxy,
+    /// (count: int, backoff_ms: int) -> unit
+    NetHttpSetRetries,
     /// (response: int) -> int
     NetHttpStatus,
     /// (response: int) -> bytes
@@ -687,19 +981,19 @@ pub enum BuiltinStrategy {
     // ========================================
     /// () -> int (router handle)
     NetHttpServerOpenRouter,
-    /// (router: int, pattern: string, handler: fn_ptr) -> unit
+    /// (router: int, pattern: string, handler: fn(int) -> int) -> unit
     NetHttpServerGet,
-    /// (router: int, pattern: string, handler: fn_ptr) -> unit
+    /// (router: int, pattern: string, handler: fn(int) -> int) -> unit
     NetHttpServerPost,
-    /// (router: int, pattern: string, handler: fn_ptr) -> unit
+    /// (router: int, pattern: string, handler: fn(int) -> int) -> unit
     NetHttpServerPut,
-    /// (router: int, pattern: string, handler: fn_ptr) -> unit
+    /// (router: int, pattern: string, handler: fn(int) -> int) -> unit
     NetHttpServerPatch,
-    /// (router: int, pattern: string, handler: fn_ptr) -> unit
+    /// (router: int, pattern: string, handler: fn(int) -> int) -> unit
     NetHttpServerDelete,
     /// (router: int, middleware: int) -> unit
     NetHttpServerWith,
-    /// (router: int, prefix: string) -> int (group handle)
+    /// (router: int, prefix: string, setup: fn(int)) -> unit
     NetHttpServerGroup,
     /// (router: int, prefix: string, sub_router: int) -> unit
     NetHttpServerMount,
@@ -707,6 +1001,30 @@ pub enum BuiltinStrategy {
     NetHttpServerServe,
     /// (status: int, body: string) -> int (response handle)
     NetHttpServerTextResponse,
+    /// (checks: map<string, fn() -> bool>) -> int (router handle)
+    NetHttpServerHealthRouter,
+    /// (req: int, key: string, value: string) -> unit
+    NetHttpServerSetValue,
+    /// (req: int, key: string) -> option<string>
+    NetHttpServerGetValue,
+
+    // ========================================
+    // HTTP Middleware strategies
+    // ========================================
+    /// () -> int (middleware handle)
+    NetHttpMiddlewareLogger,
+    /// (ms: int) -> int (middleware handle)
+    NetHttpMiddlewareTimeout,
+    /// () -> int (middleware handle)
+    NetHttpMiddlewareRecover,
+    /// (origins: [string]) -> int (middleware handle)
+    NetHttpMiddlewareCors,
+    /// (requests_per_second: int) -> int (middleware handle)
+    NetHttpMiddlewareRateLimit,
+    /// () -> int (middleware handle)
+    NetHttpMiddlewareCompress,
+    /// () -> int (middleware handle)
+    NetHttpMiddlewareRequestId,
 
     // ========================================
     // TLS strategies
@@ -736,6 +1054,18 @@ pub enum BuiltinStrategy {
     /// (url: string, ca_path: string) -> bytes throws NetworkError
     NetHttpGetTls,
 
+    // ========================================
+    // S3 object storage strategies
+    // ========================================
+    /// (args...) -> runtime result, or unit when it returns nothing
+    NetS3Call(&'static str),
+
+    // ========================================
+    // Cloud metadata strategies
+    // ========================================
+    /// (args...) -> runtime result, or unit when it returns nothing
+    CloudMetadataCall(&'static str),
+
     // ========================================
     // SQLite database strategies
     // ========================================
@@ -811,6 +1141,66 @@ pub enum BuiltinStrategy {
     TimerCancelSchedule,
     /// (handle) -> int (epoch ms)
     TimerNextRun,
+
+    // ========================================
+    // Config module strategies
+    // ========================================
+    /// (path) -> int (file)
+    ConfigFile,
+    /// (prefix) -> int (env)
+    ConfigEnv,
+    /// ([string]) -> int (flags)
+    ConfigFlags,
+    /// (map<string, string>) -> int (values)
+    ConfigValues,
+    /// (source) -> void (free)
+    ConfigFree,
+    /// (schema, [int]) -> struct throws ConfigError (load)
+    ConfigLoad,
+
+    // ========================================
+    // Flags module strategies
+    // ========================================
+    /// (source) -> unit throws IOError (open_flags)
+    FlagsOpen,
+    /// (ms) -> unit (set_poll_interval)
+    FlagsSetPollInterval,
+    /// () -> unit (close_flags)
+    FlagsClose,
+    /// (name, default) -> bool (enabled)
+    FlagsEnabled,
+    /// (name, user_id, [string]) -> string (variant)
+    FlagsVariant,
+
+    // ========================================
+    // State machine module strategies
+    // ========================================
+    /// (initial) -> int (open_machine)
+    FsmOpen,
+    /// (machine, name) -> unit; the flag marks final states
+    FsmAddState(bool),
+    /// (machine, from, event, to) -> unit throws InvalidTransition
+    FsmAddTransition,
+    /// (machine, from, event, closure) -> unit throws InvalidTransition; names the runtime fn
+    FsmSetClosure(&'static str),
+    /// (machine, event) -> string throws InvalidTransition (fire)
+    FsmFire,
+    /// (machine, event) -> bool (can_fire)
+    FsmCanFire,
+    /// (machine) -> string (current_state)
+    FsmCurrent,
+    /// (machine) -> [string] (validate_machine)
+    FsmValidate,
+    /// (machine) -> string (to_dot)
+    FsmToDot,
+    /// (machine) -> unit (close_machine)
+    FsmClose,
+
+    // ========================================
+    // Job queue module strategies
+    // ========================================
+    /// (queue | path, args...) -> runtime result, or unit when it returns nothing
+    QueueCall(&'static str),
 }
 
 /// Registry entry for a built-in function
@@ -842,6 +1232,26 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::ArrayWithCapacity,
             platforms: ALL,
         },
+        BuiltinFunction {
+            name: "collections::arrays::capacity",
+            strategy: BuiltinStrategy::OneArgInt("naml_array_capacity"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::reserve",
+            strategy: BuiltinStrategy::TwoArgVoid("naml_array_reserve"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::shrink_to_fit",
+            strategy: BuiltinStrategy::OneArgVoid("naml_array_shrink_to_fit"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::extend",
+            strategy: BuiltinStrategy::TwoArgVoid("naml_array_extend"),
+            platforms: ALL,
+        },
         BuiltinFunction {
             name: "collections::arrays::pop",
             strategy: BuiltinStrategy::OneArgOptionAccess("naml_array_pop"),
@@ -892,6 +1302,21 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::ArrayMinMax("naml_array_max", false),
             platforms: ALL,
         },
+        BuiltinFunction {
+            name: "collections::arrays::sum_float",
+            strategy: BuiltinStrategy::OneArgInt("naml_array_sum_float"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::min_float",
+            strategy: BuiltinStrategy::ArrayMinMax("naml_array_min_float", true),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::max_float",
+            strategy: BuiltinStrategy::ArrayMinMax("naml_array_max_float", false),
+            platforms: ALL,
+        },
         BuiltinFunction {
             name: "collections::arrays::reversed",
             strategy: BuiltinStrategy::OneArgPtr("naml_array_reversed"),
@@ -902,6 +1327,11 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::OneArgPtr("naml_array_sort"),
             platforms: ALL,
         },
+        BuiltinFunction {
+            name: "collections::arrays::sort_float",
+            strategy: BuiltinStrategy::OneArgPtr("naml_array_sort_float"),
+            platforms: ALL,
+        },
         BuiltinFunction {
             name: "collections::arrays::flatten",
             strategy: BuiltinStrategy::OneArgPtr("naml_array_flatten"),
@@ -909,17 +1339,17 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         },
         BuiltinFunction {
             name: "collections::arrays::take",
-            strategy: BuiltinStrategy::TwoArgPtr("naml_array_take"),
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_take"),
             platforms: ALL,
         },
         BuiltinFunction {
             name: "collections::arrays::drop",
-            strategy: BuiltinStrategy::TwoArgPtr("naml_array_drop"),
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_drop"),
             platforms: ALL,
         },
         BuiltinFunction {
             name: "collections::arrays::slice",
-            strategy: BuiltinStrategy::ThreeArgPtr("naml_array_slice"),
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_slice"),
             platforms: ALL,
         },
         BuiltinFunction {
@@ -956,7 +1386,7 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         // Deduplication
         BuiltinFunction {
             name: "collections::arrays::unique",
-            strategy: BuiltinStrategy::OneArgPtr("naml_array_unique"),
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_unique"),
             platforms: ALL,
         },
         BuiltinFunction {
@@ -970,10 +1400,31 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::ArrayLastIndexOf,
             platforms: ALL,
         },
+        // Sorted search
+        BuiltinFunction {
+            name: "collections::arrays::binary_search",
+            strategy: BuiltinStrategy::ArrayBinarySearch,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::lower_bound",
+            strategy: BuiltinStrategy::ArraySortedSearch("naml_array_lower_bound"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::upper_bound",
+            strategy: BuiltinStrategy::ArraySortedSearch("naml_array_upper_bound"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::partition_point",
+            strategy: BuiltinStrategy::LambdaInt("naml_array_partition_point"),
+            platforms: ALL,
+        },
         // Array combination
         BuiltinFunction {
             name: "collections::arrays::zip",
-            strategy: BuiltinStrategy::TwoArgPtr("naml_array_zip"),
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_zip"),
             platforms: ALL,
         },
         BuiltinFunction {
@@ -984,7 +1435,12 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         // Splitting
         BuiltinFunction {
             name: "collections::arrays::chunk",
-            strategy: BuiltinStrategy::TwoArgPtr("naml_array_chunk"),
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_chunk"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::windows",
+            strategy: BuiltinStrategy::ArrayKinded("naml_array_windows"),
             platforms: ALL,
         },
         // Set operations
@@ -1092,7 +1548,7 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         },
         BuiltinFunction {
             name: "collections::arrays::fold",
-            strategy: BuiltinStrategy::LambdaFold,
+            strategy: BuiltinStrategy::LambdaFold("naml_array_fold"),
             platforms: ALL,
         },
         BuiltinFunction {
@@ -1100,11 +1556,32 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::LambdaScan,
             platforms: ALL,
         },
+        // Parallel (scheduler workers)
+        BuiltinFunction {
+            name: "collections::arrays::par_apply",
+            strategy: BuiltinStrategy::LambdaArray("naml_array_par_map"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::par_where",
+            strategy: BuiltinStrategy::LambdaArray("naml_array_par_filter"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "collections::arrays::par_fold",
+            strategy: BuiltinStrategy::LambdaFold("naml_array_par_fold"),
+            platforms: NATIVE_ONLY,
+        },
         BuiltinFunction {
             name: "collections::arrays::sort_by",
             strategy: BuiltinStrategy::LambdaSortBy,
             platforms: ALL,
         },
+        BuiltinFunction {
+            name: "collections::arrays::group_by",
+            strategy: BuiltinStrategy::LambdaGroupBy,
+            platforms: ALL,
+        },
         // ========================================
         // Collections module - map operations
         // ========================================
@@ -1373,6 +1850,14 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             platforms: ALL,
         },
         // ========================================
+        // Runtime module
+        // ========================================
+        BuiltinFunction {
+            name: "runtime::build_info",
+            strategy: BuiltinStrategy::BuildInfo,
+            platforms: ALL,
+        },
+        // ========================================
         // Metrics module
         // ========================================
         BuiltinFunction {
@@ -1493,161 +1978,575 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::StringOneArgPtr("naml_string_chars"),
             platforms: ALL,
         },
-        // ========================================
-        // Threads/Channel module
-        // ========================================
         BuiltinFunction {
-            name: "threads::sleep",
-            strategy: BuiltinStrategy::Sleep,
-            platforms: NATIVE_ONLY,
+            name: "strings::capitalize",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_capitalize"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::join",
-            strategy: BuiltinStrategy::ThreadsJoin,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_title",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_to_title"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::open_channel",
-            strategy: BuiltinStrategy::ChannelOpen,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_snake",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_to_snake"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::send",
-            strategy: BuiltinStrategy::ChannelSend,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_camel",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_to_camel"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::receive",
-            strategy: BuiltinStrategy::ChannelReceive,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_kebab",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_to_kebab"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::close",
-            strategy: BuiltinStrategy::ChannelClose,
-            platforms: NATIVE_ONLY,
+            name: "strings::parse_int_radix",
+            strategy: BuiltinStrategy::StringArgIntInt("naml_string_parse_int_radix"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::with_mutex",
-            strategy: BuiltinStrategy::MutexNew,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_radix",
+            strategy: BuiltinStrategy::TwoArgPtr("naml_string_to_radix"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::with_rwlock",
-            strategy: BuiltinStrategy::RwlockNew,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_hex",
+            strategy: BuiltinStrategy::OneArgPtr("naml_string_to_hex"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::with_atomic",
-            strategy: BuiltinStrategy::AtomicNew,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_octal",
+            strategy: BuiltinStrategy::OneArgPtr("naml_string_to_octal"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_load",
-            strategy: BuiltinStrategy::AtomicLoad,
-            platforms: NATIVE_ONLY,
+            name: "strings::to_binary",
+            strategy: BuiltinStrategy::OneArgPtr("naml_string_to_binary"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_store",
-            strategy: BuiltinStrategy::AtomicStore,
-            platforms: NATIVE_ONLY,
+            name: "strings::group_digits",
+            strategy: BuiltinStrategy::StringArgIntStrPtr("naml_string_group_digits"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_add",
-            strategy: BuiltinStrategy::AtomicAdd,
-            platforms: NATIVE_ONLY,
+            name: "strings::compare",
+            strategy: BuiltinStrategy::StringTwoArgInt("naml_string_compare"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_sub",
-            strategy: BuiltinStrategy::AtomicSub,
-            platforms: NATIVE_ONLY,
+            name: "strings::equals_ignore_case",
+            strategy: BuiltinStrategy::StringTwoArgBool("naml_string_equals_ignore_case"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_inc",
-            strategy: BuiltinStrategy::AtomicInc,
-            platforms: NATIVE_ONLY,
+            name: "strings::natural_compare",
+            strategy: BuiltinStrategy::StringTwoArgInt("naml_string_natural_compare"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_dec",
-            strategy: BuiltinStrategy::AtomicDec,
-            platforms: NATIVE_ONLY,
+            name: "strings::collate",
+            strategy: BuiltinStrategy::StringThreeArgInt("naml_string_collate"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_cas",
-            strategy: BuiltinStrategy::AtomicCas,
-            platforms: NATIVE_ONLY,
+            name: "strings::normalize",
+            strategy: BuiltinStrategy::StringTwoArgPtr("naml_string_normalize"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_swap",
-            strategy: BuiltinStrategy::AtomicSwap,
-            platforms: NATIVE_ONLY,
+            name: "strings::casefold",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_casefold"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_and",
-            strategy: BuiltinStrategy::AtomicAnd,
-            platforms: NATIVE_ONLY,
+            name: "strings::graphemes",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_graphemes"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_or",
-            strategy: BuiltinStrategy::AtomicOr,
-            platforms: NATIVE_ONLY,
+            name: "strings::width",
+            strategy: BuiltinStrategy::StringOneArgInt("naml_string_width"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "threads::atomic_xor",
-            strategy: BuiltinStrategy::AtomicXor,
-            platforms: NATIVE_ONLY,
+            name: "strings::builder::sb_new",
+            strategy: BuiltinStrategy::StringBuilderNew("naml_string_builder_new"),
+            platforms: ALL,
         },
-        // ========================================
-        // File system module
-        // ========================================
         BuiltinFunction {
-            name: "fs::read",
-            strategy: BuiltinStrategy::FsRead,
-            platforms: NATIVE_EDGE,
+            name: "strings::builder::sb_append",
+            strategy: BuiltinStrategy::StringBuilderAppend("naml_string_builder_append"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::read_bytes",
-            strategy: BuiltinStrategy::FsReadBytes,
-            platforms: NATIVE_EDGE,
+            name: "strings::builder::sb_append_int",
+            strategy: BuiltinStrategy::StringBuilderAppend("naml_string_builder_append_int"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::write",
-            strategy: BuiltinStrategy::FsWrite,
-            platforms: NATIVE_EDGE,
+            name: "strings::builder::sb_len",
+            strategy: BuiltinStrategy::StringOneArgInt("naml_string_builder_len"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::append",
-            strategy: BuiltinStrategy::FsAppend,
-            platforms: NATIVE_EDGE,
+            name: "strings::builder::sb_to_string",
+            strategy: BuiltinStrategy::StringOneArgPtr("naml_string_builder_to_string"),
+            platforms: ALL,
         },
+        // ========================================
+        // Sets and priority queues
+        // ========================================
         BuiltinFunction {
-            name: "fs::write_bytes",
-            strategy: BuiltinStrategy::FsWriteBytes,
-            platforms: NATIVE_EDGE,
+            name: "collections::sets::set_new",
+            strategy: BuiltinStrategy::OrderedNew("naml_set_new"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::append_bytes",
-            strategy: BuiltinStrategy::FsAppendBytes,
-            platforms: NATIVE_EDGE,
+            name: "collections::sets::set_add",
+            strategy: BuiltinStrategy::OrderedElementBool("naml_set_add"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::exists",
-            strategy: BuiltinStrategy::FsExists,
-            platforms: NATIVE_EDGE,
+            name: "collections::sets::set_contains",
+            strategy: BuiltinStrategy::OrderedElementBool("naml_set_contains"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::is_file",
-            strategy: BuiltinStrategy::FsIsFile,
-            platforms: NATIVE_EDGE,
+            name: "collections::sets::set_remove",
+            strategy: BuiltinStrategy::OrderedElementBool("naml_set_remove"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::is_dir",
-            strategy: BuiltinStrategy::FsIsDir,
-            platforms: NATIVE_EDGE,
+            name: "collections::sets::set_len",
+            strategy: BuiltinStrategy::OneArgInt("naml_set_len"),
+            platforms: ALL,
         },
         BuiltinFunction {
-            name: "fs::list_dir",
-            strategy: BuiltinStrategy::FsListDir,
-            platforms: NATIVE_EDGE,
+            name: "collections::sets::set_union",
+            strategy: BuiltinStrategy::TwoArgPtr("naml_set_union"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::sets::set_intersection",
+            strategy: BuiltinStrategy::TwoArgPtr("naml_set_intersection"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::sets::set_difference",
+            strategy: BuiltinStrategy::TwoArgPtr("naml_set_difference"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::sets::set_to_array",
+            strategy: BuiltinStrategy::OneArgPtr("naml_set_to_array"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::priority_queues::pq_new",
+            strategy: BuiltinStrategy::OrderedNew("naml_priority_queue_new"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::priority_queues::pq_push",
+            strategy: BuiltinStrategy::OrderedElementVoid("naml_priority_queue_push"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::priority_queues::pq_pop_min",
+            strategy: BuiltinStrategy::MapFirstOption("naml_priority_queue_pop_min"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::priority_queues::pq_peek_min",
+            strategy: BuiltinStrategy::MapFirstOption("naml_priority_queue_peek_min"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::priority_queues::pq_len",
+            strategy: BuiltinStrategy::OneArgInt("naml_priority_queue_len"),
+            platforms: ALL,
+        },
+        // ========================================
+        // Deques
+        // ========================================
+        BuiltinFunction {
+            name: "collections::deques::deque_new",
+            strategy: BuiltinStrategy::DequeNew,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::deques::push_front",
+            strategy: BuiltinStrategy::DequePush("naml_deque_push_front"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::deques::push_back",
+            strategy: BuiltinStrategy::DequePush("naml_deque_push_back"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::deques::pop_front",
+            strategy: BuiltinStrategy::MapFirstOption("naml_deque_pop_front"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::deques::pop_back",
+            strategy: BuiltinStrategy::MapFirstOption("naml_deque_pop_back"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::deques::deque_len",
+            strategy: BuiltinStrategy::OneArgInt("naml_deque_len"),
+            platforms: ALL,
+        },
+        // ========================================
+        // Iterators
+        // ========================================
+        BuiltinFunction {
+            name: "collections::iterators::iter_from_array",
+            strategy: BuiltinStrategy::OneArgPtr("naml_iter_from_array"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::iterators::iter_map",
+            strategy: BuiltinStrategy::LambdaArray("naml_iter_map"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::iterators::iter_filter",
+            strategy: BuiltinStrategy::LambdaArray("naml_iter_filter"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::iterators::iter_take",
+            strategy: BuiltinStrategy::TwoArgPtr("naml_iter_take"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::iterators::iter_next",
+            strategy: BuiltinStrategy::MapFirstOption("naml_iter_next"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "collections::iterators::iter_collect",
+            strategy: BuiltinStrategy::OneArgPtr("naml_iter_collect"),
+            platforms: ALL,
+        },
+        // ========================================
+        // Threads/Channel module
+        // ========================================
+        BuiltinFunction {
+            name: "threads::sleep",
+            strategy: BuiltinStrategy::Sleep,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::join",
+            strategy: BuiltinStrategy::ThreadsJoin,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::task_join",
+            strategy: BuiltinStrategy::OneArgVoid("naml_task_join"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::task_is_done",
+            strategy: BuiltinStrategy::OneArgBool("naml_task_is_done"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::task_result",
+            strategy: BuiltinStrategy::TaskResult,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::open_waitgroup",
+            strategy: BuiltinStrategy::NoArgInt("naml_waitgroup_new"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::waitgroup_add",
+            strategy: BuiltinStrategy::TwoArgVoid("naml_waitgroup_add"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::waitgroup_done",
+            strategy: BuiltinStrategy::OneArgVoid("naml_waitgroup_done"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::waitgroup_wait",
+            strategy: BuiltinStrategy::OneArgVoid("naml_waitgroup_wait"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::open_condvar",
+            strategy: BuiltinStrategy::NoArgInt("naml_condvar_new"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::condvar_wait",
+            strategy: BuiltinStrategy::CondvarWait,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::condvar_notify_one",
+            strategy: BuiltinStrategy::OneArgVoid("naml_condvar_notify_one"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::condvar_notify_all",
+            strategy: BuiltinStrategy::OneArgVoid("naml_condvar_notify_all"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::open_semaphore",
+            strategy: BuiltinStrategy::OneArgInt("naml_semaphore_new"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::semaphore_acquire",
+            strategy: BuiltinStrategy::OneArgVoid("naml_semaphore_acquire"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::semaphore_try_acquire",
+            strategy: BuiltinStrategy::OneArgBool("naml_semaphore_try_acquire"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::semaphore_release",
+            strategy: BuiltinStrategy::OneArgVoid("naml_semaphore_release"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::group_new",
+            strategy: BuiltinStrategy::NoArgInt("naml_group_new"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::group_spawn",
+            strategy: BuiltinStrategy::GroupSpawn,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::group_cancel",
+            strategy: BuiltinStrategy::OneArgVoid("naml_group_cancel"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::group_wait",
+            strategy: BuiltinStrategy::OneArgVoid("naml_group_wait"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::is_cancelled",
+            strategy: BuiltinStrategy::NoArgBool("naml_is_cancelled"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::open_channel",
+            strategy: BuiltinStrategy::ChannelOpen,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::send",
+            strategy: BuiltinStrategy::ChannelSend,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::receive",
+            strategy: BuiltinStrategy::ChannelReceive,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::try_send",
+            strategy: BuiltinStrategy::ChannelTrySend,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::try_receive",
+            strategy: BuiltinStrategy::ChannelTryReceive,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::receive_timeout",
+            strategy: BuiltinStrategy::ChannelReceiveTimeout,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::close",
+            strategy: BuiltinStrategy::ChannelClose,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::select",
+            strategy: BuiltinStrategy::ChannelSelect,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::with_mutex",
+            strategy: BuiltinStrategy::MutexNew,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::with_rwlock",
+            strategy: BuiltinStrategy::RwlockNew,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::with_atomic",
+            strategy: BuiltinStrategy::AtomicNew,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_new",
+            strategy: BuiltinStrategy::AtomicNew,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_load",
+            strategy: BuiltinStrategy::AtomicLoad,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_store",
+            strategy: BuiltinStrategy::AtomicStore,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_add",
+            strategy: BuiltinStrategy::AtomicAdd,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_sub",
+            strategy: BuiltinStrategy::AtomicSub,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_inc",
+            strategy: BuiltinStrategy::AtomicInc,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_dec",
+            strategy: BuiltinStrategy::AtomicDec,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_cas",
+            strategy: BuiltinStrategy::AtomicCas,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_swap",
+            strategy: BuiltinStrategy::AtomicSwap,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_and",
+            strategy: BuiltinStrategy::AtomicAnd,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_or",
+            strategy: BuiltinStrategy::AtomicOr,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::atomic_xor",
+            strategy: BuiltinStrategy::AtomicXor,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::actors::spawn_actor",
+            strategy: BuiltinStrategy::ActorSpawn,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::actors::send",
+            strategy: BuiltinStrategy::ActorSend,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::actors::ask",
+            strategy: BuiltinStrategy::ActorAsk,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "threads::actors::stop_actor",
+            strategy: BuiltinStrategy::ActorStop,
+            platforms: NATIVE_ONLY,
+        },
+        // ========================================
+        // File system module
+        // ========================================
+        BuiltinFunction {
+            name: "fs::read",
+            strategy: BuiltinStrategy::FsRead,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::read_bytes",
+            strategy: BuiltinStrategy::FsReadBytes,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::write",
+            strategy: BuiltinStrategy::FsWrite,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::append",
+            strategy: BuiltinStrategy::FsAppend,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::write_bytes",
+            strategy: BuiltinStrategy::FsWriteBytes,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::write_atomic",
+            strategy: BuiltinStrategy::FsWriteAtomic,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::append_bytes",
+            strategy: BuiltinStrategy::FsAppendBytes,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::exists",
+            strategy: BuiltinStrategy::FsExists,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::is_file",
+            strategy: BuiltinStrategy::FsIsFile,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::is_dir",
+            strategy: BuiltinStrategy::FsIsDir,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::list_dir",
+            strategy: BuiltinStrategy::FsListDir,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::glob",
+            strategy: BuiltinStrategy::FsGlob,
+            platforms: NATIVE_EDGE,
         },
         BuiltinFunction {
             name: "fs::mkdir",
@@ -1709,6 +2608,141 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::FsCopy,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "fs::copy_verified",
+            strategy: BuiltinStrategy::FsCopyVerified,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::verify_file",
+            strategy: BuiltinStrategy::FsVerifyFile,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_options",
+            strategy: BuiltinStrategy::FsCopyOptions,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_options_include",
+            strategy: BuiltinStrategy::FsCopyOptionsInclude,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_options_exclude",
+            strategy: BuiltinStrategy::FsCopyOptionsExclude,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_options_overwrite",
+            strategy: BuiltinStrategy::FsCopyOptionsOverwrite,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_options_on_progress",
+            strategy: BuiltinStrategy::FsCopyOptionsOnProgress,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_options_free",
+            strategy: BuiltinStrategy::FsCopyOptionsFree,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::copy_tree",
+            strategy: BuiltinStrategy::FsCopyTree,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::move_tree",
+            strategy: BuiltinStrategy::FsMoveTree,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_options",
+            strategy: BuiltinStrategy::FsWalkOptions,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_options_max_depth",
+            strategy: BuiltinStrategy::FsWalkOptionsMaxDepth,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_options_follow_symlinks",
+            strategy: BuiltinStrategy::FsWalkOptionsFollowSymlinks,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_options_include",
+            strategy: BuiltinStrategy::FsWalkOptionsInclude,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_options_exclude",
+            strategy: BuiltinStrategy::FsWalkOptionsExclude,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_options_free",
+            strategy: BuiltinStrategy::FsWalkOptionsFree,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_dir",
+            strategy: BuiltinStrategy::FsWalkDir,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_open",
+            strategy: BuiltinStrategy::FsWalkOpen,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_next",
+            strategy: BuiltinStrategy::FsWalkNext,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::walk_close",
+            strategy: BuiltinStrategy::FsWalkClose,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::read_async",
+            strategy: BuiltinStrategy::FsReadAsync,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "fs::write_async",
+            strategy: BuiltinStrategy::FsWriteAsync,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "fs::list_dir_async",
+            strategy: BuiltinStrategy::FsListDirAsync,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "fs::await_io",
+            strategy: BuiltinStrategy::FsAwaitIo,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "fs::watch",
+            strategy: BuiltinStrategy::FsWatch("naml_fs_watch"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "fs::watch_recursive",
+            strategy: BuiltinStrategy::FsWatch("naml_fs_watch_recursive"),
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "fs::unwatch",
+            strategy: BuiltinStrategy::FsUnwatch,
+            platforms: NATIVE_ONLY,
+        },
         BuiltinFunction {
             name: "fs::rename",
             strategy: BuiltinStrategy::FsRename,
@@ -1734,6 +2768,21 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::FsMkdirTemp,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "fs::with_temp_file",
+            strategy: BuiltinStrategy::FsWithTempFile,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::with_temp_dir",
+            strategy: BuiltinStrategy::FsWithTempDir,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::temp_file_keep",
+            strategy: BuiltinStrategy::FsTempFileKeep,
+            platforms: NATIVE_EDGE,
+        },
         BuiltinFunction {
             name: "fs::chmod",
             strategy: BuiltinStrategy::FsChmod,
@@ -1835,6 +2884,36 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::FsFileFlush,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "fs::file_fsync",
+            strategy: BuiltinStrategy::FsFileFsync,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::file_fdatasync",
+            strategy: BuiltinStrategy::FsFileFdatasync,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::lock_exclusive",
+            strategy: BuiltinStrategy::FsLockExclusive,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::lock_shared",
+            strategy: BuiltinStrategy::FsLockShared,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::try_lock",
+            strategy: BuiltinStrategy::FsTryLock,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::unlock",
+            strategy: BuiltinStrategy::FsUnlock,
+            platforms: NATIVE_EDGE,
+        },
         BuiltinFunction {
             name: "fs::file_seek",
             strategy: BuiltinStrategy::FsFileSeek,
@@ -1898,6 +2977,26 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::FsSameFile,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "fs::disk_free",
+            strategy: BuiltinStrategy::FsDiskFree,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::disk_total",
+            strategy: BuiltinStrategy::FsDiskTotal,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::fs_type",
+            strategy: BuiltinStrategy::FsFsType,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "fs::is_same_filesystem",
+            strategy: BuiltinStrategy::FsIsSameFilesystem,
+            platforms: NATIVE_EDGE,
+        },
         // ========================================
         // Additional file handle operations
         // ========================================
@@ -1991,18 +3090,38 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             platforms: ALL,
         },
         BuiltinFunction {
-            name: "path::has_root",
-            strategy: BuiltinStrategy::PathOneArgBool("naml_path_has_root"),
+            name: "path::has_root",
+            strategy: BuiltinStrategy::PathOneArgBool("naml_path_has_root"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "path::with_extension",
+            strategy: BuiltinStrategy::PathTwoArgStr("naml_path_with_extension"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "path::strip_prefix",
+            strategy: BuiltinStrategy::PathTwoArgStr("naml_path_strip_prefix"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "path::absolute_lexical",
+            strategy: BuiltinStrategy::PathOneArgStr("naml_path_absolute_lexical"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "path::relative_to",
+            strategy: BuiltinStrategy::PathTwoArgStr("naml_path_relative_to"),
             platforms: ALL,
         },
         BuiltinFunction {
-            name: "path::with_extension",
-            strategy: BuiltinStrategy::PathTwoArgStr("naml_path_with_extension"),
+            name: "path::safe_join",
+            strategy: BuiltinStrategy::PathTwoArgStr("naml_path_safe_join"),
             platforms: ALL,
         },
         BuiltinFunction {
-            name: "path::strip_prefix",
-            strategy: BuiltinStrategy::PathTwoArgStr("naml_path_strip_prefix"),
+            name: "path::is_within",
+            strategy: BuiltinStrategy::PathTwoArgBool("naml_path_is_within"),
             platforms: ALL,
         },
         BuiltinFunction {
@@ -2126,6 +3245,26 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::OsGetgroups,
             platforms: ALL,
         },
+        BuiltinFunction {
+            name: "os::keyring::set_password",
+            strategy: BuiltinStrategy::OsKeyringSetPassword,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "os::keyring::get_password",
+            strategy: BuiltinStrategy::OsKeyringGetPassword,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "os::keyring::delete_password",
+            strategy: BuiltinStrategy::OsKeyringDeletePassword,
+            platforms: NATIVE_ONLY,
+        },
+        BuiltinFunction {
+            name: "os::net::interfaces",
+            strategy: BuiltinStrategy::OsNetInterfaces,
+            platforms: NATIVE_ONLY,
+        },
         // ========================================
         // Process module
         // ========================================
@@ -2220,6 +3359,57 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             platforms: ALL,
         },
         // ========================================
+        // Worker pool module
+        // ========================================
+        BuiltinFunction {
+            name: "process::pool::open_worker_pool",
+            strategy: BuiltinStrategy::ProcessPoolOpen,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "process::pool::submit",
+            strategy: BuiltinStrategy::ProcessPoolSubmit,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "process::pool::await_result",
+            strategy: BuiltinStrategy::ProcessPoolAwait,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "process::pool::close_pool",
+            strategy: BuiltinStrategy::ProcessPoolClose,
+            platforms: ALL,
+        },
+        // ========================================
+        // IPC module
+        // ========================================
+        BuiltinFunction {
+            name: "ipc::ipc_create",
+            strategy: BuiltinStrategy::IpcCreate,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "ipc::ipc_connect",
+            strategy: BuiltinStrategy::IpcConnect,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "ipc::send",
+            strategy: BuiltinStrategy::IpcSend,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "ipc::receive",
+            strategy: BuiltinStrategy::IpcReceive,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "ipc::ipc_close",
+            strategy: BuiltinStrategy::IpcClose,
+            platforms: ALL,
+        },
+        // ========================================
         // Testing module
         // ========================================
         BuiltinFunction {
@@ -2353,6 +3543,59 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::EncodingDecodeToBytes("naml_encoding_base64_decode"),
             platforms: ALL,
         },
+        // Base32
+        BuiltinFunction {
+            name: "encoding::base32::encode",
+            strategy: BuiltinStrategy::EncodingBytesToString("naml_encoding_base32_encode"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::base32::decode",
+            strategy: BuiltinStrategy::EncodingDecodeToBytes("naml_encoding_base32_decode"),
+            platforms: ALL,
+        },
+        // Base58
+        BuiltinFunction {
+            name: "encoding::base58::encode",
+            strategy: BuiltinStrategy::EncodingBytesToString("naml_encoding_base58_encode"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::base58::decode",
+            strategy: BuiltinStrategy::EncodingDecodeToBytes("naml_encoding_base58_decode"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::base58::check_encode",
+            strategy: BuiltinStrategy::EncodingBytesToString("naml_encoding_base58_check_encode"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::base58::check_decode",
+            strategy: BuiltinStrategy::EncodingDecodeToBytes("naml_encoding_base58_check_decode"),
+            platforms: ALL,
+        },
+        // Punycode
+        BuiltinFunction {
+            name: "encoding::punycode::encode",
+            strategy: BuiltinStrategy::EncodingStringToBytes("naml_encoding_punycode_encode"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::punycode::decode",
+            strategy: BuiltinStrategy::EncodingDecodeToString("naml_encoding_punycode_decode"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::punycode::to_ascii",
+            strategy: BuiltinStrategy::EncodingDecodeToString("naml_encoding_punycode_to_ascii"),
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::punycode::to_unicode",
+            strategy: BuiltinStrategy::EncodingDecodeToString("naml_encoding_punycode_to_unicode"),
+            platforms: ALL,
+        },
         // URL
         BuiltinFunction {
             name: "encoding::url::encode",
@@ -2447,6 +3690,178 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             platforms: ALL,
         },
         // ========================================
+        // CSV encoding module
+        // ========================================
+        BuiltinFunction { name: "encoding::csv::parse", strategy: BuiltinStrategy::EncodingCsv("naml_encoding_csv_parse", false), platforms: ALL },
+        BuiltinFunction { name: "encoding::csv::parse_delimited", strategy: BuiltinStrategy::EncodingCsv("naml_encoding_csv_parse", true), platforms: ALL },
+        BuiltinFunction { name: "encoding::csv::parse_with_headers", strategy: BuiltinStrategy::EncodingCsv("naml_encoding_csv_parse_with_headers", false), platforms: ALL },
+        BuiltinFunction { name: "encoding::csv::parse_with_headers_delimited", strategy: BuiltinStrategy::EncodingCsv("naml_encoding_csv_parse_with_headers", true), platforms: ALL },
+        BuiltinFunction { name: "encoding::csv::to_csv", strategy: BuiltinStrategy::EncodingCsv("naml_encoding_csv_encode", false), platforms: ALL },
+        BuiltinFunction { name: "encoding::csv::to_csv_delimited", strategy: BuiltinStrategy::EncodingCsv("naml_encoding_csv_encode", true), platforms: ALL },
+        // ========================================
+        // MessagePack encoding module
+        // ========================================
+        BuiltinFunction {
+            name: "encoding::msgpack::encode",
+            strategy: BuiltinStrategy::MsgpackEncode,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::msgpack::decode",
+            strategy: BuiltinStrategy::MsgpackDecode,
+            platforms: ALL,
+        },
+        // ========================================
+        // CBOR encoding module
+        // ========================================
+        BuiltinFunction {
+            name: "encoding::cbor::encode",
+            strategy: BuiltinStrategy::CborEncode,
+            platforms: ALL,
+        },
+        BuiltinFunction {
+            name: "encoding::cbor::decode",
+            strategy: BuiltinStrategy::CborDecode,
+            platforms: ALL,
+        },
+        // ========================================
+        // Compression module
+        // ========================================
+        BuiltinFunction { name: "compress::gzip_compress", strategy: BuiltinStrategy::Compress("naml_compress_gzip_compress"), platforms: ALL },
+        BuiltinFunction { name: "compress::gzip_decompress", strategy: BuiltinStrategy::Decompress("naml_compress_gzip_decompress"), platforms: ALL },
+        BuiltinFunction { name: "compress::zlib_compress", strategy: BuiltinStrategy::Compress("naml_compress_zlib_compress"), platforms: ALL },
+        BuiltinFunction { name: "compress::zlib_decompress", strategy: BuiltinStrategy::Decompress("naml_compress_zlib_decompress"), platforms: ALL },
+        BuiltinFunction { name: "compress::zstd_compress", strategy: BuiltinStrategy::Compress("naml_compress_zstd_compress"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "compress::zstd_decompress", strategy: BuiltinStrategy::Decompress("naml_compress_zstd_decompress"), platforms: NATIVE_EDGE },
+        // ========================================
+        // Validation module
+        // ========================================
+        BuiltinFunction { name: "validate::is_email", strategy: BuiltinStrategy::ValidateFormat("naml_validate_is_email"), platforms: ALL },
+        BuiltinFunction { name: "validate::is_url", strategy: BuiltinStrategy::ValidateFormat("naml_validate_is_url"), platforms: ALL },
+        BuiltinFunction { name: "validate::is_uuid", strategy: BuiltinStrategy::ValidateFormat("naml_validate_is_uuid"), platforms: ALL },
+        BuiltinFunction { name: "validate::is_ipv4", strategy: BuiltinStrategy::ValidateFormat("naml_validate_is_ipv4"), platforms: ALL },
+        BuiltinFunction { name: "validate::is_ipv6", strategy: BuiltinStrategy::ValidateFormat("naml_validate_is_ipv6"), platforms: ALL },
+        BuiltinFunction { name: "validate::is_hostname", strategy: BuiltinStrategy::ValidateFormat("naml_validate_is_hostname"), platforms: ALL },
+        BuiltinFunction { name: "validate::matches_luhn", strategy: BuiltinStrategy::ValidateFormat("naml_validate_matches_luhn"), platforms: ALL },
+        BuiltinFunction { name: "validate::open_validator", strategy: BuiltinStrategy::ValidateCall("naml_validate_open"), platforms: ALL },
+        BuiltinFunction { name: "validate::add_rule", strategy: BuiltinStrategy::ValidateCall("naml_validate_add_rule"), platforms: ALL },
+        BuiltinFunction { name: "validate::validate", strategy: BuiltinStrategy::ValidateCall("naml_validate_validate"), platforms: ALL },
+        BuiltinFunction { name: "validate::check", strategy: BuiltinStrategy::ValidateCall("naml_validate_check"), platforms: ALL },
+        BuiltinFunction { name: "validate::close_validator", strategy: BuiltinStrategy::ValidateCall("naml_validate_close"), platforms: ALL },
+        // ========================================
+        // Money module
+        // ========================================
+        BuiltinFunction { name: "money::of", strategy: BuiltinStrategy::MoneyCall("naml_money_of"), platforms: ALL },
+        BuiltinFunction { name: "money::from_minor", strategy: BuiltinStrategy::MoneyCall("naml_money_from_minor"), platforms: ALL },
+        BuiltinFunction { name: "money::from_float", strategy: BuiltinStrategy::MoneyCall("naml_money_from_float"), platforms: ALL },
+        BuiltinFunction { name: "money::parse", strategy: BuiltinStrategy::MoneyCall("naml_money_parse"), platforms: ALL },
+        BuiltinFunction { name: "money::add", strategy: BuiltinStrategy::MoneyCall("naml_money_add"), platforms: ALL },
+        BuiltinFunction { name: "money::sub", strategy: BuiltinStrategy::MoneyCall("naml_money_sub"), platforms: ALL },
+        BuiltinFunction { name: "money::negate", strategy: BuiltinStrategy::MoneyCall("naml_money_negate"), platforms: ALL },
+        BuiltinFunction { name: "money::multiply", strategy: BuiltinStrategy::MoneyCall("naml_money_multiply"), platforms: ALL },
+        BuiltinFunction { name: "money::allocate", strategy: BuiltinStrategy::MoneyCall("naml_money_allocate"), platforms: ALL },
+        BuiltinFunction { name: "money::compare", strategy: BuiltinStrategy::MoneyCall("naml_money_compare"), platforms: ALL },
+        BuiltinFunction { name: "money::convert", strategy: BuiltinStrategy::MoneyCall("naml_money_convert"), platforms: ALL },
+        BuiltinFunction { name: "money::format", strategy: BuiltinStrategy::MoneyCall("naml_money_format"), platforms: ALL },
+        BuiltinFunction { name: "money::amount", strategy: BuiltinStrategy::MoneyCall("naml_money_amount"), platforms: ALL },
+        BuiltinFunction { name: "money::currency", strategy: BuiltinStrategy::MoneyCall("naml_money_currency"), platforms: ALL },
+        BuiltinFunction { name: "money::minor_units", strategy: BuiltinStrategy::MoneyCall("naml_money_minor_units"), platforms: ALL },
+        BuiltinFunction { name: "money::to_float", strategy: BuiltinStrategy::MoneyCall("naml_money_to_float"), platforms: ALL },
+        BuiltinFunction { name: "money::currency_digits", strategy: BuiltinStrategy::MoneyCall("naml_money_currency_digits"), platforms: ALL },
+        // ========================================
+        // Geo module
+        // ========================================
+        BuiltinFunction { name: "geo::haversine_km", strategy: BuiltinStrategy::GeoCall("naml_geo_haversine_km"), platforms: ALL },
+        BuiltinFunction { name: "geo::bearing", strategy: BuiltinStrategy::GeoCall("naml_geo_bearing"), platforms: ALL },
+        BuiltinFunction { name: "geo::destination", strategy: BuiltinStrategy::GeoCall("naml_geo_destination"), platforms: ALL },
+        BuiltinFunction { name: "geo::point_in_polygon", strategy: BuiltinStrategy::GeoPredicate("naml_geo_point_in_polygon"), platforms: ALL },
+        BuiltinFunction { name: "geo::geohash_encode", strategy: BuiltinStrategy::GeoCall("naml_geo_geohash_encode"), platforms: ALL },
+        BuiltinFunction { name: "geo::geohash_decode", strategy: BuiltinStrategy::GeoDecode("naml_geo_geohash_decode"), platforms: ALL },
+        BuiltinFunction { name: "geo::geohash_bounds", strategy: BuiltinStrategy::GeoDecode("naml_geo_geohash_bounds"), platforms: ALL },
+        BuiltinFunction { name: "geo::geohash_neighbors", strategy: BuiltinStrategy::GeoDecode("naml_geo_geohash_neighbors"), platforms: ALL },
+        BuiltinFunction { name: "geo::bbox_around", strategy: BuiltinStrategy::GeoCall("naml_geo_bbox_around"), platforms: ALL },
+        BuiltinFunction { name: "geo::bbox_contains", strategy: BuiltinStrategy::GeoPredicate("naml_geo_bbox_contains"), platforms: ALL },
+        BuiltinFunction { name: "geo::bbox_intersects", strategy: BuiltinStrategy::GeoPredicate("naml_geo_bbox_intersects"), platforms: ALL },
+        BuiltinFunction { name: "geo::bounding_box", strategy: BuiltinStrategy::GeoCall("naml_geo_bounding_box"), platforms: ALL },
+        // ========================================
+        // Regex module
+        // ========================================
+        BuiltinFunction { name: "regex::regex_compile", strategy: BuiltinStrategy::RegexCall("naml_regex_compile"), platforms: ALL },
+        BuiltinFunction { name: "regex::regex_match", strategy: BuiltinStrategy::RegexMatch("naml_regex_match"), platforms: ALL },
+        BuiltinFunction { name: "regex::regex_find_all", strategy: BuiltinStrategy::RegexCall("naml_regex_find_all"), platforms: ALL },
+        BuiltinFunction { name: "regex::regex_captures", strategy: BuiltinStrategy::RegexCall("naml_regex_captures"), platforms: ALL },
+        BuiltinFunction { name: "regex::regex_replace_all", strategy: BuiltinStrategy::RegexCall("naml_regex_replace_all"), platforms: ALL },
+        BuiltinFunction { name: "regex::regex_close", strategy: BuiltinStrategy::RegexCall("naml_regex_close"), platforms: ALL },
+        // ========================================
+        // Image module
+        // ========================================
+        BuiltinFunction { name: "image::load", strategy: BuiltinStrategy::ImageCall("naml_image_load"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "image::decode", strategy: BuiltinStrategy::ImageCall("naml_image_decode"), platforms: ALL },
+        BuiltinFunction { name: "image::create", strategy: BuiltinStrategy::ImageCall("naml_image_create"), platforms: ALL },
+        BuiltinFunction { name: "image::width", strategy: BuiltinStrategy::ImageCall("naml_image_width"), platforms: ALL },
+        BuiltinFunction { name: "image::height", strategy: BuiltinStrategy::ImageCall("naml_image_height"), platforms: ALL },
+        BuiltinFunction { name: "image::get_pixel", strategy: BuiltinStrategy::ImageCall("naml_image_get_pixel"), platforms: ALL },
+        BuiltinFunction { name: "image::set_pixel", strategy: BuiltinStrategy::ImageCall("naml_image_set_pixel"), platforms: ALL },
+        BuiltinFunction { name: "image::resize", strategy: BuiltinStrategy::ImageCall("naml_image_resize"), platforms: ALL },
+        BuiltinFunction { name: "image::thumbnail", strategy: BuiltinStrategy::ImageCall("naml_image_thumbnail"), platforms: ALL },
+        BuiltinFunction { name: "image::crop", strategy: BuiltinStrategy::ImageCall("naml_image_crop"), platforms: ALL },
+        BuiltinFunction { name: "image::rotate", strategy: BuiltinStrategy::ImageCall("naml_image_rotate"), platforms: ALL },
+        BuiltinFunction { name: "image::flip_horizontal", strategy: BuiltinStrategy::ImageCall("naml_image_flip_horizontal"), platforms: ALL },
+        BuiltinFunction { name: "image::flip_vertical", strategy: BuiltinStrategy::ImageCall("naml_image_flip_vertical"), platforms: ALL },
+        BuiltinFunction { name: "image::encode_png", strategy: BuiltinStrategy::ImageCall("naml_image_encode_png"), platforms: ALL },
+        BuiltinFunction { name: "image::encode_jpeg", strategy: BuiltinStrategy::ImageCall("naml_image_encode_jpeg"), platforms: ALL },
+        BuiltinFunction { name: "image::save", strategy: BuiltinStrategy::ImageCall("naml_image_save"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "image::close", strategy: BuiltinStrategy::ImageCall("naml_image_close"), platforms: ALL },
+        // ========================================
+        // PDF module
+        // ========================================
+        BuiltinFunction { name: "doc::pdf::extract_text", strategy: BuiltinStrategy::PdfCall("naml_pdf_extract_text"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "doc::pdf::page_count", strategy: BuiltinStrategy::PdfCall("naml_pdf_page_count"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "doc::pdf::create", strategy: BuiltinStrategy::PdfCall("naml_pdf_create"), platforms: ALL },
+        BuiltinFunction { name: "doc::pdf::add_page", strategy: BuiltinStrategy::PdfCall("naml_pdf_add_page"), platforms: ALL },
+        BuiltinFunction { name: "doc::pdf::draw_text", strategy: BuiltinStrategy::PdfCall("naml_pdf_draw_text"), platforms: ALL },
+        BuiltinFunction { name: "doc::pdf::to_bytes", strategy: BuiltinStrategy::PdfCall("naml_pdf_to_bytes"), platforms: ALL },
+        BuiltinFunction { name: "doc::pdf::save", strategy: BuiltinStrategy::PdfCall("naml_pdf_save"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "doc::pdf::close", strategy: BuiltinStrategy::PdfCall("naml_pdf_close"), platforms: ALL },
+        // ========================================
+        // Spreadsheet module
+        // ========================================
+        BuiltinFunction { name: "doc::xlsx::open", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_open"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "doc::xlsx::sheet_names", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_sheet_names"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::read_rows", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_read_rows"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::create", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_create"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::add_sheet", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_add_sheet"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::write_string", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_write_string"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::write_number", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_write_number"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::write_bool", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_write_bool"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::write_formula", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_write_formula"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::write_date", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_write_date"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::add_format", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_add_format"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_bold", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_bold"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_italic", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_italic"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_num_format", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_num_format"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_font_color", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_font_color"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_bg_color", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_bg_color"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_align", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_align"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::format_cell", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_format_cell"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::set_column_width", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_set_column_width"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::to_bytes", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_to_bytes"), platforms: ALL },
+        BuiltinFunction { name: "doc::xlsx::save", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_save"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "doc::xlsx::close", strategy: BuiltinStrategy::XlsxCall("naml_xlsx_close"), platforms: ALL },
+        // ========================================
+        // Markdown module
+        // ========================================
+        BuiltinFunction { name: "text::markdown::to_html", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_to_html"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::parse", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_parse"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_count", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_count"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_kind", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_kind"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_depth", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_depth"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_level", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_level"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_text", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_text"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_url", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_url"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::node_lang", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_node_lang"), platforms: ALL },
+        BuiltinFunction { name: "text::markdown::close", strategy: BuiltinStrategy::MarkdownCall("naml_markdown_close"), platforms: ALL },
+        // ========================================
         // Binary encoding module
         // ========================================
         BuiltinFunction { name: "encoding::binary::read_u8", strategy: BuiltinStrategy::BinaryTwoArgCall("naml_encoding_binary_read_u8"), platforms: ALL },
@@ -2490,6 +3905,7 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         BuiltinFunction { name: "encoding::binary::len", strategy: BuiltinStrategy::BinaryOneArgCall("naml_encoding_binary_len"), platforms: ALL },
         BuiltinFunction { name: "encoding::binary::capacity", strategy: BuiltinStrategy::BinaryOneArgCall("naml_encoding_binary_capacity"), platforms: ALL },
         BuiltinFunction { name: "encoding::binary::slice", strategy: BuiltinStrategy::BinaryThreeArgCall("naml_encoding_binary_slice"), platforms: ALL },
+        BuiltinFunction { name: "encoding::binary::view", strategy: BuiltinStrategy::BinaryThreeArgCall("naml_encoding_binary_view"), platforms: ALL },
         BuiltinFunction { name: "encoding::binary::concat", strategy: BuiltinStrategy::BinaryTwoArgCall("naml_encoding_binary_concat"), platforms: ALL },
         BuiltinFunction { name: "encoding::binary::append", strategy: BuiltinStrategy::BinaryTwoArgVoid("naml_encoding_binary_append"), platforms: ALL },
         BuiltinFunction { name: "encoding::binary::copy_within", strategy: BuiltinStrategy::BinaryFourArgVoid("naml_encoding_binary_copy_within"), platforms: ALL },
@@ -2520,6 +3936,14 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         BuiltinFunction { name: "crypto::hmac_verify_sha512", strategy: BuiltinStrategy::CryptoHmacVerify("naml_crypto_hmac_verify_sha512"), platforms: NATIVE_EDGE },
         BuiltinFunction { name: "crypto::pbkdf2_sha256", strategy: BuiltinStrategy::CryptoPbkdf2("naml_crypto_pbkdf2_sha256"), platforms: NATIVE_EDGE },
         BuiltinFunction { name: "crypto::random_bytes", strategy: BuiltinStrategy::CryptoRandomBytes("naml_crypto_random_bytes"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::otp::hotp", strategy: BuiltinStrategy::CryptoOtpCode("naml_crypto_otp_hotp"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::otp::totp_now", strategy: BuiltinStrategy::CryptoOtpCode("naml_crypto_otp_totp_now"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::otp::totp_verify", strategy: BuiltinStrategy::CryptoOtpVerify("naml_crypto_otp_totp_verify"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::secret_from_string", strategy: BuiltinStrategy::CryptoSecretNew("naml_crypto_secret_from_string"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::secret_from_bytes", strategy: BuiltinStrategy::CryptoSecretNew("naml_crypto_secret_from_bytes"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::secret_expose", strategy: BuiltinStrategy::CryptoSecretExpose("naml_crypto_secret_expose"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::secret_len", strategy: BuiltinStrategy::CryptoSecretLen("naml_crypto_secret_len"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "crypto::secret_equals", strategy: BuiltinStrategy::CryptoSecretEquals("naml_crypto_secret_equals"), platforms: NATIVE_EDGE },
         // ========================================
         // Networking module (strict hierarchy: net::tcp::server, net::tcp::client, etc.)
         // ========================================
@@ -2529,6 +3953,11 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::NetTcpListen,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "net::tcp::server::listen_backlog",
+            strategy: BuiltinStrategy::NetTcpListenBacklog,
+            platforms: NATIVE_EDGE,
+        },
         BuiltinFunction {
             name: "net::tcp::server::accept",
             strategy: BuiltinStrategy::NetTcpAccept,
@@ -2575,6 +4004,26 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::NetTcpPeerAddr,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "net::tcp::client::set_nodelay",
+            strategy: BuiltinStrategy::NetTcpSetNodelay,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "net::tcp::client::set_keepalive",
+            strategy: BuiltinStrategy::NetTcpSetKeepalive,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "net::tcp::client::set_recv_buffer",
+            strategy: BuiltinStrategy::NetTcpSetRecvBuffer,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "net::tcp::client::set_linger",
+            strategy: BuiltinStrategy::NetTcpSetLinger,
+            platforms: NATIVE_EDGE,
+        },
         BuiltinFunction {
             name: "net::tcp::client::close",
             strategy: BuiltinStrategy::NetTcpClientClose,
@@ -2637,6 +4086,26 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             strategy: BuiltinStrategy::NetHttpSetTimeout,
             platforms: NATIVE_EDGE,
         },
+        BuiltinFunction {
+            name: "net::http::client::set_proxy",
+            strategy: BuiltinStrategy::NetHttpSetProxy,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "net::http::client::set_no_proxy",
+            strategy: BuiltinStrategy::NetHttpSetNoProxy,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "net::http::client::reset_proxy",
+            strategy: BuiltinStrategy::NetHttpResetProxy,
+            platforms: NATIVE_EDGE,
+        },
+        BuiltinFunction {
+            name: "net::http::client::set_retries",
+            strategy: BuiltinStrategy::NetHttpSetRetries,
+            platforms: NATIVE_EDGE,
+        },
         BuiltinFunction {
             name: "net::http::client::status",
             strategy: BuiltinStrategy::NetHttpStatus,
@@ -2661,6 +4130,19 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         BuiltinFunction { name: "net::http::server::mount", strategy: BuiltinStrategy::NetHttpServerMount, platforms: NATIVE_EDGE },
         BuiltinFunction { name: "net::http::server::serve", strategy: BuiltinStrategy::NetHttpServerServe, platforms: NATIVE_EDGE },
         BuiltinFunction { name: "net::http::server::text_response", strategy: BuiltinStrategy::NetHttpServerTextResponse, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::server::health_router", strategy: BuiltinStrategy::NetHttpServerHealthRouter, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::server::set_value", strategy: BuiltinStrategy::NetHttpServerSetValue, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::server::get_value", strategy: BuiltinStrategy::NetHttpServerGetValue, platforms: NATIVE_EDGE },
+        // ========================================
+        // HTTP Middleware module
+        // ========================================
+        BuiltinFunction { name: "net::http::middleware::logger", strategy: BuiltinStrategy::NetHttpMiddlewareLogger, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::middleware::timeout", strategy: BuiltinStrategy::NetHttpMiddlewareTimeout, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::middleware::recover", strategy: BuiltinStrategy::NetHttpMiddlewareRecover, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::middleware::cors", strategy: BuiltinStrategy::NetHttpMiddlewareCors, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::middleware::rate_limit", strategy: BuiltinStrategy::NetHttpMiddlewareRateLimit, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::middleware::compress", strategy: BuiltinStrategy::NetHttpMiddlewareCompress, platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::http::middleware::request_id", strategy: BuiltinStrategy::NetHttpMiddlewareRequestId, platforms: NATIVE_EDGE },
         // ========================================
         // TLS module
         // ========================================
@@ -2725,6 +4207,26 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
             platforms: NATIVE_EDGE,
         },
         // ========================================
+        // S3 object storage module
+        // ========================================
+        BuiltinFunction { name: "net::s3::configure", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_configure"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::s3::reset_config", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_reset_config"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::s3::put_object", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_put_object"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::s3::put_file", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_put_file"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::s3::get_object", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_get_object"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::s3::list", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_list"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "net::s3::presign_url", strategy: BuiltinStrategy::NetS3Call("naml_net_s3_presign_url"), platforms: NATIVE_EDGE },
+        // ========================================
+        // Cloud metadata module
+        // ========================================
+        BuiltinFunction { name: "cloud::metadata::provider", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_provider"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "cloud::metadata::region", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_region"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "cloud::metadata::instance_id", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_instance_id"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "cloud::metadata::credentials", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_credentials"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "cloud::metadata::get", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_get"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "cloud::metadata::set_endpoint", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_set_endpoint"), platforms: NATIVE_EDGE },
+        BuiltinFunction { name: "cloud::metadata::reset", strategy: BuiltinStrategy::CloudMetadataCall("naml_cloud_metadata_reset"), platforms: NATIVE_EDGE },
+        // ========================================
         // SQLite database module
         // ========================================
         BuiltinFunction { name: "db::sqlite::open", strategy: BuiltinStrategy::SqliteOpen, platforms: NATIVE_EDGE },
@@ -2764,6 +4266,54 @@ pub fn get_builtin_registry() -> &'static [BuiltinFunction] {
         BuiltinFunction { name: "timers::schedule", strategy: BuiltinStrategy::TimerSchedule, platforms: NATIVE_ONLY },
         BuiltinFunction { name: "timers::cancel_schedule", strategy: BuiltinStrategy::TimerCancelSchedule, platforms: NATIVE_ONLY },
         BuiltinFunction { name: "timers::next_run", strategy: BuiltinStrategy::TimerNextRun, platforms: NATIVE_ONLY },
+        // ========================================
+        // Config module
+        // ========================================
+        BuiltinFunction { name: "config::file", strategy: BuiltinStrategy::ConfigFile, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "config::env", strategy: BuiltinStrategy::ConfigEnv, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "config::flags", strategy: BuiltinStrategy::ConfigFlags, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "config::values", strategy: BuiltinStrategy::ConfigValues, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "config::free", strategy: BuiltinStrategy::ConfigFree, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "config::load", strategy: BuiltinStrategy::ConfigLoad, platforms: NATIVE_ONLY },
+        // ========================================
+        // Flags module
+        // ========================================
+        BuiltinFunction { name: "flags::open_flags", strategy: BuiltinStrategy::FlagsOpen, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "flags::set_poll_interval", strategy: BuiltinStrategy::FlagsSetPollInterval, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "flags::close_flags", strategy: BuiltinStrategy::FlagsClose, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "flags::enabled", strategy: BuiltinStrategy::FlagsEnabled, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "flags::variant", strategy: BuiltinStrategy::FlagsVariant, platforms: NATIVE_ONLY },
+        // ========================================
+        // State machine module
+        // ========================================
+        BuiltinFunction { name: "fsm::open_machine", strategy: BuiltinStrategy::FsmOpen, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::add_state", strategy: BuiltinStrategy::FsmAddState(false), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::add_final_state", strategy: BuiltinStrategy::FsmAddState(true), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::add_transition", strategy: BuiltinStrategy::FsmAddTransition, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::set_guard", strategy: BuiltinStrategy::FsmSetClosure("naml_fsm_set_guard"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::set_action", strategy: BuiltinStrategy::FsmSetClosure("naml_fsm_set_action"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::fire", strategy: BuiltinStrategy::FsmFire, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::can_fire", strategy: BuiltinStrategy::FsmCanFire, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::current_state", strategy: BuiltinStrategy::FsmCurrent, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::validate_machine", strategy: BuiltinStrategy::FsmValidate, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::to_dot", strategy: BuiltinStrategy::FsmToDot, platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "fsm::close_machine", strategy: BuiltinStrategy::FsmClose, platforms: NATIVE_ONLY },
+        // ========================================
+        // Job queue module
+        // ========================================
+        BuiltinFunction { name: "queue::open_queue", strategy: BuiltinStrategy::QueueCall("naml_queue_open"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::close_queue", strategy: BuiltinStrategy::QueueCall("naml_queue_close"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::set_capacity", strategy: BuiltinStrategy::QueueCall("naml_queue_set_capacity"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::enqueue", strategy: BuiltinStrategy::QueueCall("naml_queue_enqueue"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::try_enqueue", strategy: BuiltinStrategy::QueueCall("naml_queue_try_enqueue"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::dequeue", strategy: BuiltinStrategy::QueueCall("naml_queue_dequeue"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::dequeue_wait", strategy: BuiltinStrategy::QueueCall("naml_queue_dequeue_wait"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::job_payload", strategy: BuiltinStrategy::QueueCall("naml_queue_job_payload"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::job_attempts", strategy: BuiltinStrategy::QueueCall("naml_queue_job_attempts"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::ack", strategy: BuiltinStrategy::QueueCall("naml_queue_ack"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::nack", strategy: BuiltinStrategy::QueueCall("naml_queue_nack"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::queue_size", strategy: BuiltinStrategy::QueueCall("naml_queue_size"), platforms: NATIVE_ONLY },
+        BuiltinFunction { name: "queue::dead_count", strategy: BuiltinStrategy::QueueCall("naml_queue_dead_count"), platforms: NATIVE_ONLY },
     ];
     REGISTRY
 }
@@ -2787,15 +4337,45 @@ pub fn lookup_builtin(name: &str, target: CompilationTarget) -> Option<&'static
     None
 }
 
+/// Compile a value being sent on `channel`, taking the reference the
+/// channel will own; also returns the element's heap type, if any
+fn compile_channel_value(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    channel: &Expression<'_>,
+    value: &Expression<'_>,
+) -> Result<(Value, Option<HeapType>), CodegenError> {
+    use super::expr::compile_expression;
+    use crate::source::Spanned;
+
+    let compiled = compile_expression(ctx, builder, value)?;
+    let heap_type = match ctx.annotations.get_type(channel.span()).map(|ty| ty.resolve()) {
+        Some(TcType::Channel(inner)) => heap_type_from_type(&inner, ctx.interner),
+        _ => None,
+    };
+
+    if matches!(value, Expression::Literal(LiteralExpr { value: Literal::String(_), .. })) {
+        return Ok((call_string_from_cstr(ctx, builder, compiled)?, heap_type));
+    }
+    if !is_owned_value(value)
+        && let Some(heap_type) = &heap_type
+    {
+        emit_incref(ctx, builder, compiled, heap_type)?;
+    }
+    Ok((compiled, heap_type))
+}
+
 /// Compile a built-in function call using the registry
 pub fn compile_builtin_call(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
     builtin: &BuiltinFunction,
     args: &[Expression<'_>],
+    call_span: Span,
 ) -> Result<Value, CodegenError> {
     use super::channels::{
-        call_channel_close, call_channel_new, call_channel_receive, call_channel_send,
+        call_channel_close, call_channel_new, call_channel_receive, call_channel_receive_timeout,
+        call_channel_select, call_channel_send, call_channel_try_receive, call_channel_try_send,
         call_mutex_new, call_rwlock_new,
     };
     use super::expr::compile_expression;
@@ -2804,7 +4384,7 @@ pub fn compile_builtin_call(
         compile_lambda_array_collection, compile_lambda_bool_collection, compile_lambda_find,
         compile_lambda_find_index, compile_lambda_find_last, compile_lambda_find_last_index,
         compile_lambda_fold, compile_lambda_int_collection, compile_lambda_scan,
-        compile_lambda_sort_by, compile_map_lambda_bool, compile_map_lambda_fold,
+        compile_lambda_group_by, compile_lambda_sort_by, compile_map_lambda_bool, compile_map_lambda_fold,
         compile_map_lambda_int, compile_map_lambda_map, compile_sample,
     };
     use super::misc::{call_datetime_format, call_random, call_random_float, call_sleep};
@@ -2846,6 +4426,12 @@ pub fn compile_builtin_call(
             call_one_arg_int_runtime(ctx, builder, runtime_fn, arr)
         }
 
+        BuiltinStrategy::OneArgBool(runtime_fn) => {
+            let arg0 = compile_expression(ctx, builder, &args[0])?;
+            let result = call_one_arg_int_runtime(ctx, builder, runtime_fn, arg0)?;
+            Ok(builder.ins().ireduce(types::I8, result))
+        }
+
         BuiltinStrategy::OneArgPtr(runtime_fn) => {
             let arr = compile_expression(ctx, builder, &args[0])?;
             call_one_arg_ptr_runtime(ctx, builder, runtime_fn, arr)
@@ -2857,13 +4443,6 @@ pub fn compile_builtin_call(
             call_two_arg_ptr_runtime(ctx, builder, runtime_fn, arg0, arg1)
         }
 
-        BuiltinStrategy::ThreeArgPtr(runtime_fn) => {
-            let arg0 = compile_expression(ctx, builder, &args[0])?;
-            let arg1 = compile_expression(ctx, builder, &args[1])?;
-            let arg2 = compile_expression(ctx, builder, &args[2])?;
-            call_three_arg_ptr_runtime(ctx, builder, runtime_fn, arg0, arg1, arg2)
-        }
-
         BuiltinStrategy::ArrayGet => {
             let arr = compile_expression(ctx, builder, &args[0])?;
             let index = compile_expression(ctx, builder, &args[1])?;
@@ -2881,6 +4460,13 @@ pub fn compile_builtin_call(
             call_array_clear_runtime(ctx, builder, arr)
         }
 
+        BuiltinStrategy::OneArgVoid(runtime_fn) => {
+            let arg0 = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            builder.ins().call(func_ref, &[arg0]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
         BuiltinStrategy::ArrayMinMax(runtime_fn, is_min) => {
             let arr = compile_expression(ctx, builder, &args[0])?;
             compile_option_from_minmax(ctx, builder, arr, runtime_fn, is_min)
@@ -2923,11 +4509,48 @@ pub fn compile_builtin_call(
             compile_option_from_last_index_of(ctx, builder, arr, val)
         }
 
+        BuiltinStrategy::ArrayKinded(runtime_fn) => {
+            let mut call_args = Vec::with_capacity(args.len() + 1);
+            for arg in args {
+                call_args.push(compile_expression(ctx, builder, arg)?);
+            }
+            let kind = super::array::array_elem_kind(ctx, &args[0]);
+            call_args.push(builder.ins().iconst(types::I64, kind));
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &call_args);
+            Ok(builder.inst_results(call)[0])
+        }
+
+        BuiltinStrategy::ArraySortedSearch(runtime_fn) => {
+            let arr = compile_expression(ctx, builder, &args[0])?;
+            let val = compile_expression(ctx, builder, &args[1])?;
+            let val = ensure_naml_string(ctx, builder, val, &args[1])?;
+            let val = ensure_i64(builder, val);
+            let kind = builder.ins().iconst(types::I64, super::array::array_elem_kind(ctx, &args[0]));
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &[arr, val, kind]);
+            Ok(builder.inst_results(call)[0])
+        }
+
+        BuiltinStrategy::ArrayBinarySearch => {
+            let arr = compile_expression(ctx, builder, &args[0])?;
+            let val = compile_expression(ctx, builder, &args[1])?;
+            let val = ensure_naml_string(ctx, builder, val, &args[1])?;
+            let val = ensure_i64(builder, val);
+            let kind = builder.ins().iconst(types::I64, super::array::array_elem_kind(ctx, &args[0]));
+            compile_option_from_found_index(ctx, builder, &[arr, val, kind], "naml_array_binary_search")
+        }
+
         // ========================================
         // IO strategies
         // ========================================
         BuiltinStrategy::NoArgInt(runtime_fn) => call_int_runtime(ctx, builder, runtime_fn),
 
+        BuiltinStrategy::NoArgBool(runtime_fn) => {
+            let result = call_int_runtime(ctx, builder, runtime_fn)?;
+            Ok(builder.ins().ireduce(types::I8, result))
+        }
+
         BuiltinStrategy::NoArgVoid(runtime_fn) => call_void_runtime(ctx, builder, runtime_fn),
 
         BuiltinStrategy::TwoArgVoid(runtime_fn) => {
@@ -2961,6 +4584,23 @@ pub fn compile_builtin_call(
             call_datetime_format(ctx, builder, timestamp, fmt)
         }
 
+        // ========================================
+        // Runtime strategies
+        // ========================================
+        BuiltinStrategy::BuildInfo => {
+            let info = crate::build_info::BuildInfo::collect(ctx.target);
+            let map = super::map::call_map_new(ctx, builder, crate::runtime::MAP_KEY_STRING)?;
+            let set_func_ref = rt_func_ref(ctx, builder, "naml_map_set")?;
+            for (key, value) in info.entries() {
+                let key = super::literal::compile_string_literal(ctx, builder, key)?;
+                let key = call_string_from_cstr(ctx, builder, key)?;
+                let value = super::literal::compile_string_literal(ctx, builder, value)?;
+                let value = call_string_from_cstr(ctx, builder, value)?;
+                builder.ins().call(set_func_ref, &[map, key, value]);
+            }
+            Ok(map)
+        }
+
         // ========================================
         // Strings strategies
         // ========================================
@@ -3033,6 +4673,24 @@ pub fn compile_builtin_call(
             call_two_arg_ptr_runtime(ctx, builder, runtime_fn, s, n)
         }
 
+        BuiltinStrategy::StringTwoArgInt(runtime_fn) => {
+            let a = compile_expression(ctx, builder, &args[0])?;
+            let a = ensure_naml_string(ctx, builder, a, &args[0])?;
+            let b = compile_expression(ctx, builder, &args[1])?;
+            let b = ensure_naml_string(ctx, builder, b, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, runtime_fn, a, b)
+        }
+
+        BuiltinStrategy::StringThreeArgInt(runtime_fn) => {
+            let a = compile_expression(ctx, builder, &args[0])?;
+            let a = ensure_naml_string(ctx, builder, a, &args[0])?;
+            let b = compile_expression(ctx, builder, &args[1])?;
+            let b = ensure_naml_string(ctx, builder, b, &args[1])?;
+            let locale = compile_expression(ctx, builder, &args[2])?;
+            let locale = ensure_naml_string(ctx, builder, locale, &args[2])?;
+            call_three_arg_int_runtime(ctx, builder, runtime_fn, a, b, locale)
+        }
+
         BuiltinStrategy::StringJoin => {
             let arr = compile_expression(ctx, builder, &args[0])?;
             let delim = compile_expression(ctx, builder, &args[1])?;
@@ -3040,6 +4698,63 @@ pub fn compile_builtin_call(
             call_two_arg_ptr_runtime(ctx, builder, "naml_string_join", arr, delim)
         }
 
+        BuiltinStrategy::StringBuilderNew(runtime_fn) => call_int_runtime(ctx, builder, runtime_fn),
+
+        BuiltinStrategy::StringBuilderAppend(runtime_fn) => {
+            let sb = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            call_two_arg_runtime(ctx, builder, runtime_fn, sb, value)
+        }
+
+        // ========================================
+        // Set / priority queue strategies
+        // ========================================
+        BuiltinStrategy::OrderedNew(runtime_fn) => {
+            let kind = match ctx.annotations.get_type(call_span).map(TcType::resolve) {
+                Some(TcType::Set(elem) | TcType::PriorityQueue(elem)) => key_kind(&elem),
+                _ => MAP_KEY_INT,
+            };
+            let kind = builder.ins().iconst(types::I64, kind as i64);
+            call_one_arg_ptr_runtime(ctx, builder, runtime_fn, kind)
+        }
+
+        BuiltinStrategy::OrderedElementBool(runtime_fn) => {
+            let collection = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            let value = ensure_i64(builder, value);
+            call_two_arg_bool_runtime(ctx, builder, runtime_fn, collection, value)
+        }
+
+        BuiltinStrategy::OrderedElementVoid(runtime_fn) => {
+            let collection = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            let value = ensure_i64(builder, value);
+            call_two_arg_runtime(ctx, builder, runtime_fn, collection, value)
+        }
+
+        // ========================================
+        // Deque strategies
+        // ========================================
+        BuiltinStrategy::DequeNew => {
+            let kind = match ctx.annotations.get_type(call_span).map(TcType::resolve) {
+                Some(TcType::Deque(elem)) => super::array::elem_kind(ctx, &elem),
+                _ => ARRAY_ELEM_VALUE,
+            };
+            let kind = builder.ins().iconst(types::I64, kind);
+            call_one_arg_ptr_runtime(ctx, builder, "naml_deque_new", kind)
+        }
+
+        BuiltinStrategy::DequePush(runtime_fn) => {
+            let deque = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            let value = ensure_i64(builder, value);
+            call_two_arg_runtime(ctx, builder, runtime_fn, deque, value)
+        }
+
         // ========================================
         // Threads/Channel strategies
         // ========================================
@@ -3049,6 +4764,30 @@ pub fn compile_builtin_call(
             Ok(builder.ins().iconst(types::I64, 0))
         }
 
+
+        BuiltinStrategy::TaskResult => {
+            let task = compile_expression(ctx, builder, &args[0])?;
+            let result = call_one_arg_int_runtime(ctx, builder, "naml_task_result", task)?;
+            // The task's state keeps its own reference to a heap result
+            let result_ty = ctx.annotations.get_type(call_span).map(TcType::resolve);
+            if let Some(heap_type) = result_ty.as_ref().and_then(|ty| heap_type_from_type(ty, ctx.interner)) {
+                emit_incref(ctx, builder, result, &heap_type)?;
+            }
+            Ok(from_generic_word(builder, result, result_ty.as_ref()))
+        }
+
+        BuiltinStrategy::CondvarWait => {
+            let condvar = compile_expression(ctx, builder, &args[0])?;
+            let mutex = compile_expression(ctx, builder, &args[1])?;
+            let value = compile_expression(ctx, builder, &args[2])?;
+            let value = ensure_i64(builder, value);
+            let func_ref = rt_func_ref(ctx, builder, "naml_condvar_wait")?;
+            let call = builder.ins().call(func_ref, &[condvar, mutex, value]);
+            let current = builder.inst_results(call)[0];
+            let value_ty = ctx.annotations.get_type(call_span).map(TcType::resolve);
+            Ok(from_generic_word(builder, current, value_ty.as_ref()))
+        }
+
         BuiltinStrategy::ChannelOpen => {
             let capacity = if args.is_empty() {
                 builder.ins().iconst(types::I64, 1)
@@ -3060,40 +4799,45 @@ pub fn compile_builtin_call(
 
         BuiltinStrategy::ChannelSend => {
             let channel = compile_expression(ctx, builder, &args[0])?;
-            let mut value = compile_expression(ctx, builder, &args[1])?;
-
-            let is_string_literal = matches!(
-                &args[1],
-                Expression::Literal(LiteralExpr { value: Literal::String(_), .. })
-            );
-            if is_string_literal {
-                value = call_string_from_cstr(ctx, builder, value)?;
-            }
+            let (value, _) = compile_channel_value(ctx, builder, &args[0], &args[1])?;
+            call_channel_send(ctx, builder, channel, value)
+        }
+
+        BuiltinStrategy::ChannelReceive => {
+            let channel = compile_expression(ctx, builder, &args[0])?;
+            call_channel_receive(ctx, builder, channel)
+        }
 
-            if !is_string_literal {
-                let is_fresh = matches!(
-                    &args[1],
-                    Expression::Call(_) | Expression::StructLiteral(_)
-                );
-                if !is_fresh {
-                    use crate::source::Spanned;
-                    if let Some(ch_ty) = ctx.annotations.get_type(args[0].span()) {
-                        let resolved = ch_ty.resolve();
-                        if let crate::typechecker::types::Type::Channel(inner) = &resolved {
-                            if let Some(heap_type) = heap_type_from_type(inner, ctx.interner) {
-                                emit_incref(ctx, builder, value, &heap_type)?;
-                            }
-                        }
-                    }
-                }
+        BuiltinStrategy::ChannelTrySend => {
+            let channel = compile_expression(ctx, builder, &args[0])?;
+            let (value, heap_type) = compile_channel_value(ctx, builder, &args[0], &args[1])?;
+            let sent = call_channel_try_send(ctx, builder, channel, value)?;
+
+            // A refused value was never handed over; drop the reference
+            // the channel would have owned
+            if let Some(heap_type) = heap_type {
+                let refused_block = builder.create_block();
+                let merge_block = builder.create_block();
+                builder.ins().brif(sent, merge_block, &[], refused_block, &[]);
+                builder.switch_to_block(refused_block);
+                builder.seal_block(refused_block);
+                emit_decref(ctx, builder, value, &heap_type)?;
+                builder.ins().jump(merge_block, &[]);
+                builder.switch_to_block(merge_block);
+                builder.seal_block(merge_block);
             }
+            Ok(builder.ins().ireduce(types::I8, sent))
+        }
 
-            call_channel_send(ctx, builder, channel, value)
+        BuiltinStrategy::ChannelTryReceive => {
+            let channel = compile_expression(ctx, builder, &args[0])?;
+            call_channel_try_receive(ctx, builder, channel)
         }
 
-        BuiltinStrategy::ChannelReceive => {
+        BuiltinStrategy::ChannelReceiveTimeout => {
             let channel = compile_expression(ctx, builder, &args[0])?;
-            call_channel_receive(ctx, builder, channel)
+            let timeout_ms = compile_expression(ctx, builder, &args[1])?;
+            call_channel_receive_timeout(ctx, builder, channel, timeout_ms)
         }
 
         BuiltinStrategy::ChannelClose => {
@@ -3102,6 +4846,12 @@ pub fn compile_builtin_call(
             Ok(builder.ins().iconst(types::I64, 0))
         }
 
+        BuiltinStrategy::ChannelSelect => {
+            let channels = compile_expression(ctx, builder, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            call_channel_select(ctx, builder, channels, closure)
+        }
+
         BuiltinStrategy::MutexNew => {
             let value = compile_expression(ctx, builder, &args[0])?;
             call_mutex_new(ctx, builder, value)
@@ -3250,6 +5000,47 @@ pub fn compile_builtin_call(
             Ok(builder.inst_results(call)[0])
         }
 
+        BuiltinStrategy::GroupSpawn => {
+            let group = compile_expression(ctx, builder, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            let func_ref = rt_func_ref(ctx, builder, "naml_group_spawn")?;
+            builder.ins().call(func_ref, &[group, func_ptr, data_ptr]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::ActorSpawn => {
+            let closure = compile_expression(ctx, builder, &args[0])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            call_two_arg_int_runtime(ctx, builder, "naml_actor_spawn", func_ptr, data_ptr)
+        }
+
+        BuiltinStrategy::ActorSend => {
+            let actor = compile_expression(ctx, builder, &args[0])?;
+            let message = compile_expression(ctx, builder, &args[1])?;
+            let message = ensure_naml_string(ctx, builder, message, &args[1])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_actor_send")?;
+            builder.ins().call(func_ref, &[actor, message]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::ActorAsk => {
+            let actor = compile_expression(ctx, builder, &args[0])?;
+            let message = compile_expression(ctx, builder, &args[1])?;
+            let message = ensure_naml_string(ctx, builder, message, &args[1])?;
+            let timeout = compile_expression(ctx, builder, &args[2])?;
+            call_three_arg_ptr_runtime(ctx, builder, "naml_actor_ask", actor, message, timeout)
+        }
+
+        BuiltinStrategy::ActorStop => {
+            let actor = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_actor_stop")?;
+            builder.ins().call(func_ref, &[actor]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
         // ========================================
         // Lambda-based collection strategies
         // ========================================
@@ -3295,11 +5086,11 @@ pub fn compile_builtin_call(
             compile_lambda_find_last_index(ctx, builder, arr, closure)
         }
 
-        BuiltinStrategy::LambdaFold => {
+        BuiltinStrategy::LambdaFold(runtime_fn) => {
             let arr = compile_expression(ctx, builder, &args[0])?;
             let initial = compile_expression(ctx, builder, &args[1])?;
             let closure = compile_expression(ctx, builder, &args[2])?;
-            compile_lambda_fold(ctx, builder, arr, initial, closure)
+            compile_lambda_fold(ctx, builder, arr, initial, closure, runtime_fn)
         }
 
         BuiltinStrategy::LambdaScan => {
@@ -3315,6 +5106,20 @@ pub fn compile_builtin_call(
             compile_lambda_sort_by(ctx, builder, arr, closure)
         }
 
+        BuiltinStrategy::LambdaGroupBy => {
+            use crate::source::Spanned;
+            let kind = super::array::array_elem_kind(ctx, &args[0]);
+            let key_kind = match ctx.annotations.get_type(args[1].span()).map(|ty| ty.resolve()) {
+                Some(crate::typechecker::types::Type::Function(func)) => {
+                    super::map::key_kind(&func.returns)
+                }
+                _ => crate::runtime::MAP_KEY_INT,
+            };
+            let arr = compile_expression(ctx, builder, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            compile_lambda_group_by(ctx, builder, arr, closure, kind, key_kind as i64)
+        }
+
         BuiltinStrategy::Sample => {
             let arr = compile_expression(ctx, builder, &args[0])?;
             compile_sample(ctx, builder, arr)
@@ -3463,6 +5268,14 @@ pub fn compile_builtin_call(
             call_two_arg_int_runtime(ctx, builder, "naml_fs_append", path, content)
         }
 
+        BuiltinStrategy::FsWriteAtomic => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let content = compile_expression(ctx, builder, &args[1])?;
+            let content = ensure_naml_string(ctx, builder, content, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_write_atomic", path, content)
+        }
+
         BuiltinStrategy::FsWriteBytes => {
             let path = compile_expression(ctx, builder, &args[0])?;
             let path = ensure_naml_string(ctx, builder, path, &args[0])?;
@@ -3501,6 +5314,12 @@ pub fn compile_builtin_call(
             call_one_arg_ptr_runtime(ctx, builder, "naml_fs_list_dir", path)
         }
 
+        BuiltinStrategy::FsGlob => {
+            let pattern = compile_expression(ctx, builder, &args[0])?;
+            let pattern = ensure_naml_string(ctx, builder, pattern, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fs_glob", pattern)
+        }
+
         BuiltinStrategy::FsMkdir => {
             let path = compile_expression(ctx, builder, &args[0])?;
             let path = ensure_naml_string(ctx, builder, path, &args[0])?;
@@ -3574,6 +5393,186 @@ pub fn compile_builtin_call(
             call_two_arg_int_runtime(ctx, builder, "naml_fs_copy", src, dst)
         }
 
+        BuiltinStrategy::FsCopyVerified => {
+            let src = compile_expression(ctx, builder, &args[0])?;
+            let src = ensure_naml_string(ctx, builder, src, &args[0])?;
+            let dst = compile_expression(ctx, builder, &args[1])?;
+            let dst = ensure_naml_string(ctx, builder, dst, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_copy_verified", src, dst)
+        }
+
+        BuiltinStrategy::FsVerifyFile => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let expected = compile_expression(ctx, builder, &args[1])?;
+            let expected = ensure_naml_string(ctx, builder, expected, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_verify_file", path, expected)
+        }
+
+        BuiltinStrategy::FsCopyOptions => call_int_runtime(ctx, builder, "naml_fs_copy_options"),
+
+        BuiltinStrategy::FsCopyOptionsInclude => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_copy_options_include", opts, value)
+        }
+
+        BuiltinStrategy::FsCopyOptionsExclude => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_copy_options_exclude", opts, value)
+        }
+
+        BuiltinStrategy::FsCopyOptionsOverwrite => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_copy_options_overwrite", opts, value)
+        }
+
+        BuiltinStrategy::FsCopyOptionsOnProgress => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            call_three_arg_void_runtime(ctx, builder, "naml_fs_copy_options_on_progress", opts, func_ptr, data_ptr)
+        }
+
+        BuiltinStrategy::FsCopyOptionsFree => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fs_copy_options_free")?;
+            builder.ins().call(func_ref, &[opts]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FsCopyTree => {
+            let src = compile_expression(ctx, builder, &args[0])?;
+            let src = ensure_naml_string(ctx, builder, src, &args[0])?;
+            let dst = compile_expression(ctx, builder, &args[1])?;
+            let dst = ensure_naml_string(ctx, builder, dst, &args[1])?;
+            let opts = compile_expression(ctx, builder, &args[2])?;
+            call_three_arg_int_runtime(ctx, builder, "naml_fs_copy_tree", src, dst, opts)
+        }
+
+        BuiltinStrategy::FsMoveTree => {
+            let src = compile_expression(ctx, builder, &args[0])?;
+            let src = ensure_naml_string(ctx, builder, src, &args[0])?;
+            let dst = compile_expression(ctx, builder, &args[1])?;
+            let dst = ensure_naml_string(ctx, builder, dst, &args[1])?;
+            let opts = compile_expression(ctx, builder, &args[2])?;
+            call_three_arg_int_runtime(ctx, builder, "naml_fs_move_tree", src, dst, opts)
+        }
+
+        BuiltinStrategy::FsWalkOptions => call_int_runtime(ctx, builder, "naml_fs_walk_options"),
+
+        BuiltinStrategy::FsWalkOptionsMaxDepth => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let depth = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_walk_options_max_depth", opts, depth)
+        }
+
+        BuiltinStrategy::FsWalkOptionsFollowSymlinks => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let follow = compile_expression(ctx, builder, &args[1])?;
+            // Convert bool (i8) to i64 for runtime call
+            let follow_i64 = builder
+                .ins()
+                .uextend(cranelift::prelude::types::I64, follow);
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_walk_options_follow_symlinks", opts, follow_i64)
+        }
+
+        BuiltinStrategy::FsWalkOptionsInclude => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_walk_options_include", opts, value)
+        }
+
+        BuiltinStrategy::FsWalkOptionsExclude => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let value = compile_expression(ctx, builder, &args[1])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_walk_options_exclude", opts, value)
+        }
+
+        BuiltinStrategy::FsWalkOptionsFree => {
+            let opts = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fs_walk_options_free")?;
+            builder.ins().call(func_ref, &[opts]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FsWalkDir => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let opts = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_ptr_runtime(ctx, builder, "naml_fs_walk_dir", path, opts)
+        }
+
+        BuiltinStrategy::FsWalkOpen => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let opts = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_walk_open", path, opts)
+        }
+
+        BuiltinStrategy::FsWalkNext => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            compile_option_from_nullable_ptr(ctx, builder, &[handle], "naml_fs_walk_next")
+        }
+
+        BuiltinStrategy::FsWalkClose => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fs_walk_close")?;
+            builder.ins().call(func_ref, &[handle]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FsReadAsync => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fs_read_async", path)
+        }
+
+        BuiltinStrategy::FsWriteAsync => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let content = compile_expression(ctx, builder, &args[1])?;
+            let content = ensure_naml_string(ctx, builder, content, &args[1])?;
+            call_two_arg_ptr_runtime(ctx, builder, "naml_fs_write_async", path, content)
+        }
+
+        BuiltinStrategy::FsListDirAsync => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fs_list_dir_async", path)
+        }
+
+        BuiltinStrategy::FsAwaitIo => {
+            let pending = compile_expression(ctx, builder, &args[0])?;
+            let result = call_one_arg_int_runtime(ctx, builder, "naml_fs_await_io", pending)?;
+            let result_ty = ctx.annotations.get_type(call_span).map(TcType::resolve);
+            Ok(from_generic_word(builder, result, result_ty.as_ref()))
+        }
+
+        BuiltinStrategy::FsWatch(runtime_fn) => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            call_three_arg_int_runtime(ctx, builder, runtime_fn, path, func_ptr, data_ptr)
+        }
+
+        BuiltinStrategy::FsUnwatch => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fs_unwatch")?;
+            builder.ins().call(func_ref, &[handle]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
         BuiltinStrategy::FsRename => {
             let src = compile_expression(ctx, builder, &args[0])?;
             let src = ensure_naml_string(ctx, builder, src, &args[0])?;
@@ -3605,6 +5604,32 @@ pub fn compile_builtin_call(
             call_one_arg_ptr_runtime(ctx, builder, "naml_fs_mkdir_temp", prefix)
         }
 
+        BuiltinStrategy::FsWithTempFile => {
+            let prefix = compile_expression(ctx, builder, &args[0])?;
+            let prefix = ensure_naml_string(ctx, builder, prefix, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            call_three_arg_void_runtime(ctx, builder, "naml_fs_with_temp_file", prefix, func_ptr, data_ptr)
+        }
+
+        BuiltinStrategy::FsWithTempDir => {
+            let prefix = compile_expression(ctx, builder, &args[0])?;
+            let prefix = ensure_naml_string(ctx, builder, prefix, &args[0])?;
+            let closure = compile_expression(ctx, builder, &args[1])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            call_three_arg_void_runtime(ctx, builder, "naml_fs_with_temp_dir", prefix, func_ptr, data_ptr)
+        }
+
+        BuiltinStrategy::FsTempFileKeep => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fs_temp_file_keep")?;
+            builder.ins().call(func_ref, &[path]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
         BuiltinStrategy::FsChmod => {
             let path = compile_expression(ctx, builder, &args[0])?;
             let path = ensure_naml_string(ctx, builder, path, &args[0])?;
@@ -3739,6 +5764,36 @@ pub fn compile_builtin_call(
             call_one_arg_int_runtime(ctx, builder, "naml_fs_file_flush", handle)
         }
 
+        BuiltinStrategy::FsFileFsync => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_file_fsync", handle)
+        }
+
+        BuiltinStrategy::FsFileFdatasync => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_file_fdatasync", handle)
+        }
+
+        BuiltinStrategy::FsLockExclusive => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_lock_exclusive", handle)
+        }
+
+        BuiltinStrategy::FsLockShared => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_lock_shared", handle)
+        }
+
+        BuiltinStrategy::FsTryLock => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_try_lock", handle)
+        }
+
+        BuiltinStrategy::FsUnlock => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_unlock", handle)
+        }
+
         BuiltinStrategy::FsFileSeek => {
             let handle = compile_expression(ctx, builder, &args[0])?;
             let offset = compile_expression(ctx, builder, &args[1])?;
@@ -3824,6 +5879,32 @@ pub fn compile_builtin_call(
             call_two_arg_int_runtime(ctx, builder, "naml_fs_same_file", path1, path2)
         }
 
+        BuiltinStrategy::FsDiskFree => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_disk_free", path)
+        }
+
+        BuiltinStrategy::FsDiskTotal => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fs_disk_total", path)
+        }
+
+        BuiltinStrategy::FsFsType => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fs_fs_type", path)
+        }
+
+        BuiltinStrategy::FsIsSameFilesystem => {
+            let path1 = compile_expression(ctx, builder, &args[0])?;
+            let path1 = ensure_naml_string(ctx, builder, path1, &args[0])?;
+            let path2 = compile_expression(ctx, builder, &args[1])?;
+            let path2 = ensure_naml_string(ctx, builder, path2, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_fs_is_same_filesystem", path1, path2)
+        }
+
         // ========================================
         // Additional file handle operations
         // ========================================
@@ -3933,7 +6014,7 @@ pub fn compile_builtin_call(
         BuiltinStrategy::EnvLookupEnv => {
             let key = compile_expression(ctx, builder, &args[0])?;
             let key = ensure_naml_string(ctx, builder, key, &args[0])?;
-            compile_option_from_nullable_ptr(ctx, builder, key, "naml_env_lookup_env")
+            compile_option_from_nullable_ptr(ctx, builder, &[key], "naml_env_lookup_env")
         }
 
         BuiltinStrategy::EnvSetenv => {
@@ -4071,6 +6152,39 @@ pub fn compile_builtin_call(
             Ok(results[0])
         }
 
+        BuiltinStrategy::OsKeyringSetPassword => {
+            let service = compile_expression(ctx, builder, &args[0])?;
+            let service = ensure_naml_string(ctx, builder, service, &args[0])?;
+            let user = compile_expression(ctx, builder, &args[1])?;
+            let user = ensure_naml_string(ctx, builder, user, &args[1])?;
+            let password = compile_expression(ctx, builder, &args[2])?;
+            call_three_arg_void_runtime(ctx, builder, "naml_os_keyring_set_password", service, user, password)
+        }
+
+        BuiltinStrategy::OsKeyringGetPassword => {
+            let service = compile_expression(ctx, builder, &args[0])?;
+            let service = ensure_naml_string(ctx, builder, service, &args[0])?;
+            let user = compile_expression(ctx, builder, &args[1])?;
+            let user = ensure_naml_string(ctx, builder, user, &args[1])?;
+            call_two_arg_ptr_runtime(ctx, builder, "naml_os_keyring_get_password", service, user)
+        }
+
+        BuiltinStrategy::OsKeyringDeletePassword => {
+            let service = compile_expression(ctx, builder, &args[0])?;
+            let service = ensure_naml_string(ctx, builder, service, &args[0])?;
+            let user = compile_expression(ctx, builder, &args[1])?;
+            let user = ensure_naml_string(ctx, builder, user, &args[1])?;
+            call_two_arg_runtime(ctx, builder, "naml_os_keyring_delete_password", service, user)
+        }
+
+        BuiltinStrategy::OsNetInterfaces => {
+            use super::runtime::rt_func_ref;
+            let func_ref = rt_func_ref(ctx, builder, "naml_os_net_interfaces")?;
+            let inst = builder.ins().call(func_ref, &[]);
+            let results = builder.inst_results(inst);
+            Ok(results[0])
+        }
+
         // ========================================
         // Process strategies
         // ========================================
@@ -4162,6 +6276,83 @@ pub fn compile_builtin_call(
             call_int_runtime(ctx, builder, "naml_process_sigcont")
         }
 
+        // ========================================
+        // Worker pool strategies
+        // ========================================
+        BuiltinStrategy::ProcessPoolOpen => {
+            let workers = compile_expression(ctx, builder, &args[0])?;
+            // The type checker only accepts a literal naming a top-level fn(bytes) -> bytes
+            let Expression::Literal(LiteralExpr { value: Literal::String(name), .. }) = &args[1] else {
+                return Err(CodegenError::JitCompile(
+                    "open_worker_pool expects the entry function name as a string literal".to_string(),
+                ));
+            };
+            let entry_name = ctx.interner.resolve(name).to_string();
+            let Some(&func_id) = ctx.functions.get(&entry_name) else {
+                return Err(CodegenError::JitCompile(format!("Undefined worker entry: {}", entry_name)));
+            };
+            let func_ref = ctx.module.declare_func_in_func(func_id, builder.func);
+            let func_ptr = builder.ins().func_addr(cranelift::prelude::types::I64, func_ref);
+            call_two_arg_int_runtime(ctx, builder, "naml_process_pool_open", workers, func_ptr)
+        }
+
+        BuiltinStrategy::ProcessPoolSubmit => {
+            let pool = compile_expression(ctx, builder, &args[0])?;
+            let payload = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_ptr_runtime(ctx, builder, "naml_process_pool_submit", pool, payload)
+        }
+
+        BuiltinStrategy::ProcessPoolAwait => {
+            let job = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_process_pool_await", job)
+        }
+
+        BuiltinStrategy::ProcessPoolClose => {
+            let pool = compile_expression(ctx, builder, &args[0])?;
+            use super::runtime::rt_func_ref;
+            let func_ref = rt_func_ref(ctx, builder, "naml_process_pool_close")?;
+            builder.ins().call(func_ref, &[pool]);
+            Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
+        }
+
+        // ========================================
+        // IPC strategies
+        // ========================================
+        BuiltinStrategy::IpcCreate => {
+            let name = compile_expression(ctx, builder, &args[0])?;
+            let name = ensure_naml_string(ctx, builder, name, &args[0])?;
+            let size = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_ipc_create", name, size)
+        }
+
+        BuiltinStrategy::IpcConnect => {
+            let name = compile_expression(ctx, builder, &args[0])?;
+            let name = ensure_naml_string(ctx, builder, name, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_ipc_connect", name)
+        }
+
+        BuiltinStrategy::IpcSend => {
+            let channel = compile_expression(ctx, builder, &args[0])?;
+            let data = compile_expression(ctx, builder, &args[1])?;
+            use super::runtime::rt_func_ref;
+            let func_ref = rt_func_ref(ctx, builder, "naml_ipc_send")?;
+            builder.ins().call(func_ref, &[channel, data]);
+            Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
+        }
+
+        BuiltinStrategy::IpcReceive => {
+            let channel = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_ipc_receive", channel)
+        }
+
+        BuiltinStrategy::IpcClose => {
+            let channel = compile_expression(ctx, builder, &args[0])?;
+            use super::runtime::rt_func_ref;
+            let func_ref = rt_func_ref(ctx, builder, "naml_ipc_close")?;
+            builder.ins().call(func_ref, &[channel]);
+            Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
+        }
+
         // ========================================
         // Testing strategies
         // ========================================
@@ -4378,6 +6569,47 @@ pub fn compile_builtin_call(
             call_one_arg_ptr_runtime(ctx, builder, runtime_fn, n)
         }
 
+        BuiltinStrategy::CryptoOtpCode(runtime_fn) => {
+            let secret = compile_expression(ctx, builder, &args[0])?;
+            let mut call_args = vec![ensure_naml_string(ctx, builder, secret, &args[0])?];
+            for arg in &args[1..] {
+                call_args.push(compile_expression(ctx, builder, arg)?);
+            }
+            call_decode_runtime(ctx, builder, runtime_fn, &call_args)
+        }
+
+        BuiltinStrategy::CryptoOtpVerify(runtime_fn) => {
+            let code = compile_expression(ctx, builder, &args[0])?;
+            let code = ensure_naml_string(ctx, builder, code, &args[0])?;
+            let secret = compile_expression(ctx, builder, &args[1])?;
+            let secret = ensure_naml_string(ctx, builder, secret, &args[1])?;
+            let skew = compile_expression(ctx, builder, &args[2])?;
+            // Throwing calls are always wrapped in catch, which narrows bools itself
+            call_decode_runtime(ctx, builder, runtime_fn, &[code, secret, skew])
+        }
+
+        BuiltinStrategy::CryptoSecretNew(runtime_fn) => {
+            let value = compile_expression(ctx, builder, &args[0])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, runtime_fn, value)
+        }
+
+        BuiltinStrategy::CryptoSecretExpose(runtime_fn) => {
+            let secret = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, runtime_fn, secret)
+        }
+
+        BuiltinStrategy::CryptoSecretLen(runtime_fn) => {
+            let secret = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, runtime_fn, secret)
+        }
+
+        BuiltinStrategy::CryptoSecretEquals(runtime_fn) => {
+            let a = compile_expression(ctx, builder, &args[0])?;
+            let b = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_bool_runtime(ctx, builder, runtime_fn, a, b)
+        }
+
         // ========================================
         // Encoding strategies
         // ========================================
@@ -4402,6 +6634,7 @@ pub fn compile_builtin_call(
             let ptr_type = ctx.module.target_config().pointer_type();
 
             let bytes = compile_expression(ctx, builder, &args[0])?;
+            let bytes = ensure_naml_string(ctx, builder, bytes, &args[0])?;
 
             let slot_tag = builder.create_sized_stack_slot(StackSlotData::new(
                 StackSlotKind::ExplicitSlot,
@@ -4512,6 +6745,20 @@ pub fn compile_builtin_call(
             Ok(result)
         }
 
+        BuiltinStrategy::EncodingCsv(runtime_fn, delimited) => {
+            let input = compile_expression(ctx, builder, &args[0])?;
+            let input = ensure_naml_string(ctx, builder, input, &args[0])?;
+            let delimiter = if delimited {
+                let delimiter = compile_expression(ctx, builder, &args[1])?;
+                ensure_naml_string(ctx, builder, delimiter, &args[1])?
+            } else {
+                let ptr_type = ctx.module.target_config().pointer_type();
+                builder.ins().iconst(ptr_type, 0)
+            };
+            // to_csv never reports an error, so its error path is never taken
+            call_decode_runtime(ctx, builder, runtime_fn, &[input, delimiter])
+        }
+
         // ========================================
         // Binary encoding strategies
         // ========================================
@@ -5010,6 +7257,73 @@ pub fn compile_builtin_call(
             Ok(result)
         }
 
+        // ========================================
+        // MessagePack encoding strategies
+        // ========================================
+        BuiltinStrategy::MsgpackEncode => {
+            let json = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_encoding_msgpack_encode", json)
+        }
+
+        BuiltinStrategy::MsgpackDecode => {
+            let data = compile_expression(ctx, builder, &args[0])?;
+            call_decode_runtime(ctx, builder, "naml_encoding_msgpack_decode", &[data])
+        }
+
+        // ========================================
+        // CBOR encoding strategies
+        // ========================================
+        BuiltinStrategy::CborEncode => {
+            let json = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_encoding_cbor_encode", json)
+        }
+
+        BuiltinStrategy::CborDecode => {
+            let data = compile_expression(ctx, builder, &args[0])?;
+            call_decode_runtime(ctx, builder, "naml_encoding_cbor_decode", &[data])
+        }
+
+        // ========================================
+        // Compression strategies
+        // ========================================
+        BuiltinStrategy::Compress(runtime_fn) => {
+            let data = compile_expression(ctx, builder, &args[0])?;
+            let level = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_ptr_runtime(ctx, builder, runtime_fn, data, level)
+        }
+
+        BuiltinStrategy::Decompress(runtime_fn) => {
+            let data = compile_expression(ctx, builder, &args[0])?;
+            call_decode_runtime(ctx, builder, runtime_fn, &[data])
+        }
+
+        // ========================================
+        // Validation strategies
+        // ========================================
+        BuiltinStrategy::ValidateFormat(runtime_fn) => {
+            let s = compile_expression(ctx, builder, &args[0])?;
+            let s = ensure_naml_string(ctx, builder, s, &args[0])?;
+            let func_ref = super::runtime::rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &[s]);
+            let result = builder.inst_results(call)[0];
+            Ok(builder.ins().ireduce(types::I8, result))
+        }
+
+        BuiltinStrategy::ValidateCall(runtime_fn) => {
+            use super::runtime::rt_func_ref;
+            let mut call_args = Vec::with_capacity(args.len());
+            for arg in args {
+                let value = compile_expression(ctx, builder, arg)?;
+                call_args.push(ensure_naml_string(ctx, builder, value, arg)?);
+            }
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &call_args);
+            match builder.inst_results(call).first() {
+                Some(&result) => Ok(result),
+                None => Ok(builder.ins().iconst(types::I64, 0)),
+            }
+        }
+
         // ========================================
         // Networking strategies
         // ========================================
@@ -5020,6 +7334,13 @@ pub fn compile_builtin_call(
             call_one_arg_int_runtime(ctx, builder, "naml_net_tcp_server_listen", addr)
         }
 
+        BuiltinStrategy::NetTcpListenBacklog => {
+            let addr = compile_expression(ctx, builder, &args[0])?;
+            let addr = ensure_naml_string(ctx, builder, addr, &args[0])?;
+            let backlog = compile_expression(ctx, builder, &args[1])?;
+            call_two_arg_int_runtime(ctx, builder, "naml_net_tcp_server_listen_backlog", addr, backlog)
+        }
+
         BuiltinStrategy::NetTcpAccept => {
             let listener = compile_expression(ctx, builder, &args[0])?;
             call_one_arg_int_runtime(ctx, builder, "naml_net_tcp_server_accept", listener)
@@ -5087,6 +7408,46 @@ pub fn compile_builtin_call(
             call_one_arg_ptr_runtime(ctx, builder, "naml_net_tcp_socket_peer_addr", socket)
         }
 
+        BuiltinStrategy::NetTcpSetNodelay => {
+            use super::runtime::rt_func_ref;
+            let socket = compile_expression(ctx, builder, &args[0])?;
+            let enabled = compile_expression(ctx, builder, &args[1])?;
+            // Convert bool (i8) to i64 for runtime call
+            let enabled = builder.ins().uextend(types::I64, enabled);
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_tcp_client_set_nodelay")?;
+            builder.ins().call(func_ref, &[socket, enabled]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::NetTcpSetKeepalive => {
+            use super::runtime::rt_func_ref;
+            let socket = compile_expression(ctx, builder, &args[0])?;
+            let idle_ms = compile_expression(ctx, builder, &args[1])?;
+            let interval_ms = compile_expression(ctx, builder, &args[2])?;
+            let count = compile_expression(ctx, builder, &args[3])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_tcp_client_set_keepalive")?;
+            builder.ins().call(func_ref, &[socket, idle_ms, interval_ms, count]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::NetTcpSetRecvBuffer => {
+            use super::runtime::rt_func_ref;
+            let socket = compile_expression(ctx, builder, &args[0])?;
+            let size = compile_expression(ctx, builder, &args[1])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_tcp_client_set_recv_buffer")?;
+            builder.ins().call(func_ref, &[socket, size]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::NetTcpSetLinger => {
+            use super::runtime::rt_func_ref;
+            let socket = compile_expression(ctx, builder, &args[0])?;
+            let ms = compile_expression(ctx, builder, &args[1])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_tcp_client_set_linger")?;
+            builder.ins().call(func_ref, &[socket, ms]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
         // UDP
         BuiltinStrategy::NetUdpBind => {
             let addr = compile_expression(ctx, builder, &args[0])?;
@@ -5185,6 +7546,40 @@ pub fn compile_builtin_call(
             Ok(builder.ins().iconst(types::I64, 0))
         }
 
+        BuiltinStrategy::NetHttpSetProxy => {
+            use super::runtime::rt_func_ref;
+            let url = compile_expression(ctx, builder, &args[0])?;
+            let url = ensure_naml_string(ctx, builder, url, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_http_client_set_proxy")?;
+            builder.ins().call(func_ref, &[url]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::NetHttpSetNoProxy => {
+            use super::runtime::rt_func_ref;
+            let hosts = compile_expression(ctx, builder, &args[0])?;
+            let hosts = ensure_naml_string(ctx, builder, hosts, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_http_client_set_no_proxy")?;
+            builder.ins().call(func_ref, &[hosts]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::NetHttpResetProxy => {
+            use super::runtime::rt_func_ref;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_http_client_reset_proxy")?;
+            builder.ins().call(func_ref, &[]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::NetHttpSetRetries => {
+            use super::runtime::rt_func_ref;
+            let count = compile_expression(ctx, builder, &args[0])?;
+            let backoff_ms = compile_expression(ctx, builder, &args[1])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_http_client_set_retries")?;
+            builder.ins().call(func_ref, &[count, backoff_ms]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
         BuiltinStrategy::NetHttpStatus => {
             let response = compile_expression(ctx, builder, &args[0])?;
             call_one_arg_int_runtime(ctx, builder, "naml_net_http_response_get_status", response)
@@ -5212,8 +7607,7 @@ pub fn compile_builtin_call(
             let pattern = compile_expression(ctx, builder, &args[1])?;
             let pattern = ensure_naml_string(ctx, builder, pattern, &args[1])?;
             let handler_closure = compile_expression(ctx, builder, &args[2])?;
-            let handler = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 0);
-            call_three_arg_void_runtime(ctx, builder, "naml_net_http_server_get", router, pattern, handler)
+            call_route_register(ctx, builder, "naml_net_http_server_get", router, pattern, handler_closure)
         }
 
         BuiltinStrategy::NetHttpServerPost => {
@@ -5221,8 +7615,7 @@ pub fn compile_builtin_call(
             let pattern = compile_expression(ctx, builder, &args[1])?;
             let pattern = ensure_naml_string(ctx, builder, pattern, &args[1])?;
             let handler_closure = compile_expression(ctx, builder, &args[2])?;
-            let handler = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 0);
-            call_three_arg_void_runtime(ctx, builder, "naml_net_http_server_post", router, pattern, handler)
+            call_route_register(ctx, builder, "naml_net_http_server_post", router, pattern, handler_closure)
         }
 
         BuiltinStrategy::NetHttpServerPut => {
@@ -5230,8 +7623,7 @@ pub fn compile_builtin_call(
             let pattern = compile_expression(ctx, builder, &args[1])?;
             let pattern = ensure_naml_string(ctx, builder, pattern, &args[1])?;
             let handler_closure = compile_expression(ctx, builder, &args[2])?;
-            let handler = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 0);
-            call_three_arg_void_runtime(ctx, builder, "naml_net_http_server_put", router, pattern, handler)
+            call_route_register(ctx, builder, "naml_net_http_server_put", router, pattern, handler_closure)
         }
 
         BuiltinStrategy::NetHttpServerPatch => {
@@ -5239,8 +7631,7 @@ pub fn compile_builtin_call(
             let pattern = compile_expression(ctx, builder, &args[1])?;
             let pattern = ensure_naml_string(ctx, builder, pattern, &args[1])?;
             let handler_closure = compile_expression(ctx, builder, &args[2])?;
-            let handler = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 0);
-            call_three_arg_void_runtime(ctx, builder, "naml_net_http_server_patch", router, pattern, handler)
+            call_route_register(ctx, builder, "naml_net_http_server_patch", router, pattern, handler_closure)
         }
 
         BuiltinStrategy::NetHttpServerDelete => {
@@ -5248,8 +7639,7 @@ pub fn compile_builtin_call(
             let pattern = compile_expression(ctx, builder, &args[1])?;
             let pattern = ensure_naml_string(ctx, builder, pattern, &args[1])?;
             let handler_closure = compile_expression(ctx, builder, &args[2])?;
-            let handler = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 0);
-            call_three_arg_void_runtime(ctx, builder, "naml_net_http_server_delete", router, pattern, handler)
+            call_route_register(ctx, builder, "naml_net_http_server_delete", router, pattern, handler_closure)
         }
 
         BuiltinStrategy::NetHttpServerWith => {
@@ -5262,7 +7652,12 @@ pub fn compile_builtin_call(
             let router = compile_expression(ctx, builder, &args[0])?;
             let prefix = compile_expression(ctx, builder, &args[1])?;
             let prefix = ensure_naml_string(ctx, builder, prefix, &args[1])?;
-            call_two_arg_int_runtime(ctx, builder, "naml_net_http_server_group", router, prefix)
+            let setup_closure = compile_expression(ctx, builder, &args[2])?;
+            let func_ptr = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), setup_closure, 0);
+            let data_ptr = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), setup_closure, 8);
+            let func_ref = rt_func_ref(ctx, builder, "naml_net_http_server_group")?;
+            builder.ins().call(func_ref, &[router, prefix, func_ptr, data_ptr]);
+            Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
         }
 
         BuiltinStrategy::NetHttpServerMount => {
@@ -5287,6 +7682,61 @@ pub fn compile_builtin_call(
             call_two_arg_int_runtime(ctx, builder, "naml_net_http_server_text_response", status, body)
         }
 
+        BuiltinStrategy::NetHttpServerHealthRouter => {
+            let checks = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_net_http_server_health_router", checks)
+        }
+
+        BuiltinStrategy::NetHttpServerSetValue => {
+            let req = compile_expression(ctx, builder, &args[0])?;
+            let key = compile_expression(ctx, builder, &args[1])?;
+            let key = ensure_naml_string(ctx, builder, key, &args[1])?;
+            let value = compile_expression(ctx, builder, &args[2])?;
+            let value = ensure_naml_string(ctx, builder, value, &args[2])?;
+            call_three_arg_void_runtime(ctx, builder, "naml_net_http_server_set_value", req, key, value)
+        }
+
+        BuiltinStrategy::NetHttpServerGetValue => {
+            let req = compile_expression(ctx, builder, &args[0])?;
+            let key = compile_expression(ctx, builder, &args[1])?;
+            let key = ensure_naml_string(ctx, builder, key, &args[1])?;
+            compile_option_from_nullable_ptr(ctx, builder, &[req, key], "naml_net_http_server_get_value")
+        }
+
+        // ========================================
+        // HTTP Middleware strategies
+        // ========================================
+        BuiltinStrategy::NetHttpMiddlewareLogger => {
+            call_int_runtime(ctx, builder, "naml_net_http_middleware_logger")
+        }
+
+        BuiltinStrategy::NetHttpMiddlewareTimeout => {
+            let ms = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_net_http_middleware_timeout", ms)
+        }
+
+        BuiltinStrategy::NetHttpMiddlewareRecover => {
+            call_int_runtime(ctx, builder, "naml_net_http_middleware_recover")
+        }
+
+        BuiltinStrategy::NetHttpMiddlewareCors => {
+            let origins = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_net_http_middleware_cors", origins)
+        }
+
+        BuiltinStrategy::NetHttpMiddlewareRateLimit => {
+            let rps = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_net_http_middleware_rate_limit", rps)
+        }
+
+        BuiltinStrategy::NetHttpMiddlewareCompress => {
+            call_int_runtime(ctx, builder, "naml_net_http_middleware_compress")
+        }
+
+        BuiltinStrategy::NetHttpMiddlewareRequestId => {
+            call_int_runtime(ctx, builder, "naml_net_http_middleware_request_id")
+        }
+
         // ========================================
         // TLS strategies
         // ========================================
@@ -5382,6 +7832,71 @@ pub fn compile_builtin_call(
             call_two_arg_ptr_runtime(ctx, builder, "naml_net_http_client_get_tls", url, ca_path)
         }
 
+        // ========================================
+        // S3 object storage strategies
+        // ========================================
+        BuiltinStrategy::NetS3Call(runtime_fn)
+        | BuiltinStrategy::CloudMetadataCall(runtime_fn)
+        | BuiltinStrategy::MoneyCall(runtime_fn)
+        | BuiltinStrategy::GeoCall(runtime_fn)
+        | BuiltinStrategy::RegexCall(runtime_fn)
+        | BuiltinStrategy::ImageCall(runtime_fn)
+        | BuiltinStrategy::PdfCall(runtime_fn)
+        | BuiltinStrategy::XlsxCall(runtime_fn)
+        | BuiltinStrategy::MarkdownCall(runtime_fn) => {
+            use super::runtime::rt_func_ref;
+            let mut call_args = Vec::with_capacity(args.len());
+            for arg in args {
+                let value = compile_expression(ctx, builder, arg)?;
+                let value = ensure_naml_string(ctx, builder, value, arg)?;
+                // Runtime functions take bools as i64
+                if builder.func.dfg.value_type(value) == types::I8 {
+                    call_args.push(builder.ins().uextend(types::I64, value));
+                } else {
+                    call_args.push(value);
+                }
+            }
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &call_args);
+            match builder.inst_results(call).first() {
+                Some(&result) => Ok(result),
+                None => Ok(builder.ins().iconst(types::I64, 0)),
+            }
+        }
+
+        // ========================================
+        // Geo strategies
+        // ========================================
+        BuiltinStrategy::GeoPredicate(runtime_fn) => {
+            let mut call_args = Vec::with_capacity(args.len());
+            for arg in args {
+                call_args.push(compile_expression(ctx, builder, arg)?);
+            }
+            let func_ref = super::runtime::rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &call_args);
+            let result = builder.inst_results(call)[0];
+            Ok(builder.ins().ireduce(types::I8, result))
+        }
+
+        BuiltinStrategy::GeoDecode(runtime_fn) => {
+            let hash = compile_expression(ctx, builder, &args[0])?;
+            let hash = ensure_naml_string(ctx, builder, hash, &args[0])?;
+            call_decode_runtime(ctx, builder, runtime_fn, &[hash])
+        }
+
+        // ========================================
+        // Regex strategies
+        // ========================================
+        BuiltinStrategy::RegexMatch(runtime_fn) => {
+            let handle = compile_expression(ctx, builder, &args[0])?;
+            let s = compile_expression(ctx, builder, &args[1])?;
+            let s = ensure_naml_string(ctx, builder, s, &args[1])?;
+            let func_ref = super::runtime::rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &[handle, s]);
+            let result = builder.inst_results(call)[0];
+            Ok(builder.ins().ireduce(types::I8, result))
+        }
+
         // ========================================
         // SQLite database strategies
         // ========================================
@@ -5651,9 +8166,271 @@ pub fn compile_builtin_call(
             let handle = compile_expression(ctx, builder, &args[0])?;
             call_one_arg_int_runtime(ctx, builder, "naml_timers_next_run", handle)
         }
+
+        // ========================================
+        // Config module strategies
+        // ========================================
+        BuiltinStrategy::ConfigFile => {
+            let path = compile_expression(ctx, builder, &args[0])?;
+            let path = ensure_naml_string(ctx, builder, path, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_config_file", path)
+        }
+
+        BuiltinStrategy::ConfigEnv => {
+            let prefix = compile_expression(ctx, builder, &args[0])?;
+            let prefix = ensure_naml_string(ctx, builder, prefix, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_config_env", prefix)
+        }
+
+        BuiltinStrategy::ConfigFlags => {
+            let args_arr = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_config_flags", args_arr)
+        }
+
+        BuiltinStrategy::ConfigValues => {
+            let map = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_config_values", map)
+        }
+
+        BuiltinStrategy::ConfigFree => {
+            let source = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_config_free")?;
+            builder.ins().call(func_ref, &[source]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::ConfigLoad => {
+            use crate::source::Spanned;
+            use crate::typechecker::types::Type;
+
+            // The typechecker annotates the schema literal with the struct it names
+            let Some(Type::Struct(schema)) = ctx.annotations.get_type(args[0].span()) else {
+                return Err(CodegenError::JitCompile(
+                    "config::load schema is not a struct".to_string(),
+                ));
+            };
+            let type_id = ctx
+                .struct_defs
+                .get(&schema.name)
+                .map(|def| def.type_id)
+                .ok_or_else(|| {
+                    CodegenError::JitCompile(format!(
+                        "Unknown config schema struct: {}",
+                        ctx.interner.resolve(&schema.name)
+                    ))
+                })?;
+            let spec = schema
+                .fields
+                .iter()
+                .map(|field| {
+                    let kind = match field.ty {
+                        Type::Int => "int",
+                        Type::Float => "float",
+                        Type::Bool => "bool",
+                        _ => "string",
+                    };
+                    format!("{}:{}", ctx.interner.resolve(&field.name), kind)
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+
+            let sources = compile_expression(ctx, builder, &args[1])?;
+            let type_id = builder.ins().iconst(types::I64, type_id as i64);
+            let spec = super::literal::compile_string_literal(ctx, builder, &spec)?;
+            let spec = call_string_from_cstr(ctx, builder, spec)?;
+            call_three_arg_ptr_runtime(ctx, builder, "naml_config_load", sources, type_id, spec)
+        }
+
+        // ========================================
+        // Flags module strategies
+        // ========================================
+        BuiltinStrategy::FlagsOpen => {
+            let source = compile_expression(ctx, builder, &args[0])?;
+            let source = ensure_naml_string(ctx, builder, source, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_flags_open")?;
+            builder.ins().call(func_ref, &[source]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FlagsSetPollInterval => {
+            let ms = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_flags_set_poll_interval")?;
+            builder.ins().call(func_ref, &[ms]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FlagsClose => call_void_runtime(ctx, builder, "naml_flags_close"),
+
+        BuiltinStrategy::FlagsEnabled => {
+            let name = compile_expression(ctx, builder, &args[0])?;
+            let name = ensure_naml_string(ctx, builder, name, &args[0])?;
+            let default = compile_expression(ctx, builder, &args[1])?;
+            let default = builder.ins().uextend(types::I64, default);
+            call_two_arg_bool_runtime(ctx, builder, "naml_flags_enabled", name, default)
+        }
+
+        BuiltinStrategy::FlagsVariant => {
+            let name = compile_expression(ctx, builder, &args[0])?;
+            let name = ensure_naml_string(ctx, builder, name, &args[0])?;
+            let user_id = compile_expression(ctx, builder, &args[1])?;
+            let user_id = ensure_naml_string(ctx, builder, user_id, &args[1])?;
+            let variants = compile_expression(ctx, builder, &args[2])?;
+            call_three_arg_ptr_runtime(ctx, builder, "naml_flags_variant", name, user_id, variants)
+        }
+
+        BuiltinStrategy::FsmOpen => {
+            let initial = compile_expression(ctx, builder, &args[0])?;
+            let initial = ensure_naml_string(ctx, builder, initial, &args[0])?;
+            call_one_arg_int_runtime(ctx, builder, "naml_fsm_open", initial)
+        }
+
+        BuiltinStrategy::FsmAddState(is_final) => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            let name = compile_expression(ctx, builder, &args[1])?;
+            let name = ensure_naml_string(ctx, builder, name, &args[1])?;
+            let is_final = builder.ins().iconst(types::I64, is_final as i64);
+            let func_ref = rt_func_ref(ctx, builder, "naml_fsm_add_state")?;
+            builder.ins().call(func_ref, &[machine, name, is_final]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FsmAddTransition => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            let mut names = Vec::with_capacity(3);
+            for arg in &args[1..4] {
+                let value = compile_expression(ctx, builder, arg)?;
+                names.push(ensure_naml_string(ctx, builder, value, arg)?);
+            }
+            let func_ref = rt_func_ref(ctx, builder, "naml_fsm_add_transition")?;
+            builder.ins().call(func_ref, &[machine, names[0], names[1], names[2]]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FsmSetClosure(runtime_fn) => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            let from = compile_expression(ctx, builder, &args[1])?;
+            let from = ensure_naml_string(ctx, builder, from, &args[1])?;
+            let event = compile_expression(ctx, builder, &args[2])?;
+            let event = ensure_naml_string(ctx, builder, event, &args[2])?;
+            let closure = compile_expression(ctx, builder, &args[3])?;
+            let func_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 0);
+            let data_ptr = builder.ins().load(types::I64, MemFlags::new(), closure, 8);
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            builder.ins().call(func_ref, &[machine, from, event, func_ptr, data_ptr]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::FsmFire => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            let event = compile_expression(ctx, builder, &args[1])?;
+            let event = ensure_naml_string(ctx, builder, event, &args[1])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fsm_fire")?;
+            let call = builder.ins().call(func_ref, &[machine, event]);
+            Ok(builder.inst_results(call)[0])
+        }
+
+        BuiltinStrategy::FsmCanFire => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            let event = compile_expression(ctx, builder, &args[1])?;
+            let event = ensure_naml_string(ctx, builder, event, &args[1])?;
+            call_two_arg_bool_runtime(ctx, builder, "naml_fsm_can_fire", machine, event)
+        }
+
+        BuiltinStrategy::FsmCurrent => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fsm_current", machine)
+        }
+
+        BuiltinStrategy::FsmValidate => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fsm_validate", machine)
+        }
+
+        BuiltinStrategy::FsmToDot => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            call_one_arg_ptr_runtime(ctx, builder, "naml_fsm_to_dot", machine)
+        }
+
+        BuiltinStrategy::FsmClose => {
+            let machine = compile_expression(ctx, builder, &args[0])?;
+            let func_ref = rt_func_ref(ctx, builder, "naml_fsm_close")?;
+            builder.ins().call(func_ref, &[machine]);
+            Ok(builder.ins().iconst(types::I64, 0))
+        }
+
+        BuiltinStrategy::QueueCall(runtime_fn) => {
+            let mut call_args = Vec::with_capacity(args.len());
+            for arg in args {
+                let value = compile_expression(ctx, builder, arg)?;
+                call_args.push(ensure_naml_string(ctx, builder, value, arg)?);
+            }
+            let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+            let call = builder.ins().call(func_ref, &call_args);
+            match builder.inst_results(call).first() {
+                Some(&result) => Ok(result),
+                None => Ok(builder.ins().iconst(types::I64, 0)),
+            }
+        }
     }
 }
 
+/// Call a runtime function that reports failure through trailing (out_tag, out_value)
+/// parameters, throwing DecodeError with the reported position when out_tag != 0
+fn call_decode_runtime(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    runtime_fn: &str,
+    args: &[Value],
+) -> Result<Value, CodegenError> {
+    use super::exceptions::throw_decode_error;
+    let ptr_type = ctx.module.target_config().pointer_type();
+
+    let slot_tag = builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 4, 4));
+    let slot_value = builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 8, 8));
+    let out_tag = builder.ins().stack_addr(ptr_type, slot_tag, 0);
+    let out_value = builder.ins().stack_addr(ptr_type, slot_value, 0);
+
+    let mut call_args = args.to_vec();
+    call_args.push(out_tag);
+    call_args.push(out_value);
+    let func_ref = super::runtime::rt_func_ref(ctx, builder, runtime_fn)?;
+    builder.ins().call(func_ref, &call_args);
+
+    let tag = builder.ins().load(types::I32, MemFlags::trusted(), out_tag, 0);
+    let value = builder.ins().load(types::I64, MemFlags::trusted(), out_value, 0);
+
+    let error_block = builder.create_block();
+    let merge_block = builder.create_block();
+    builder.append_block_param(merge_block, types::I64);
+
+    builder.ins().brif(tag, error_block, &[], merge_block, &[value]);
+
+    builder.switch_to_block(error_block);
+    builder.seal_block(error_block);
+    throw_decode_error(ctx, builder, value)?;
+    builder.ins().jump(merge_block, &[value]);
+
+    builder.switch_to_block(merge_block);
+    builder.seal_block(merge_block);
+    Ok(builder.block_params(merge_block)[0])
+}
+
+/// Register an HTTP route: passes the handler closure's function and data pointers
+fn call_route_register(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    runtime_fn: &str,
+    router: Value,
+    pattern: Value,
+    handler_closure: Value,
+) -> Result<Value, CodegenError> {
+    let func_ptr = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 0);
+    let data_ptr = builder.ins().load(cranelift::prelude::types::I64, MemFlags::new(), handler_closure, 8);
+    let func_ref = super::runtime::rt_func_ref(ctx, builder, runtime_fn)?;
+    builder.ins().call(func_ref, &[router, pattern, func_ptr, data_ptr]);
+    Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
+}
+
 fn get_atomic_type_suffix_from_arg(ctx: &CompileContext<'_>, arg: &Expression<'_>) -> &'static str {
     use crate::source::Spanned;
     if let Some(ty) = ctx.annotations.get_type(arg.span()) {
@@ -29,10 +29,50 @@ pub fn call_channel_send(
     Ok(builder.inst_results(call)[0])
 }
 
+pub fn call_channel_try_send(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    ch: Value,
+    value: Value,
+) -> Result<Value, CodegenError> {
+    let value = ensure_i64(builder, value);
+    let func_ref = rt_func_ref(ctx, builder, "naml_channel_try_send")?;
+    let call = builder.ins().call(func_ref, &[ch, value]);
+    Ok(builder.inst_results(call)[0])
+}
+
 pub fn call_channel_receive(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
     ch: Value,
+) -> Result<Value, CodegenError> {
+    receive_option(ctx, builder, "naml_channel_receive", &[ch])
+}
+
+pub fn call_channel_try_receive(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    ch: Value,
+) -> Result<Value, CodegenError> {
+    receive_option(ctx, builder, "naml_channel_try_receive", &[ch])
+}
+
+pub fn call_channel_receive_timeout(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    ch: Value,
+    timeout_ms: Value,
+) -> Result<Value, CodegenError> {
+    receive_option(ctx, builder, "naml_channel_receive_timeout", &[ch, timeout_ms])
+}
+
+/// Call a receive function taking `args` plus a pointer to the received
+/// value and returning the option tag, and build the option<T>
+fn receive_option(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    runtime_fn: &str,
+    args: &[Value],
 ) -> Result<Value, CodegenError> {
     // Allocate stack slot for option<T> (16 bytes: tag at 0, value at 8)
     let option_slot =
@@ -44,9 +84,11 @@ pub fn call_channel_receive(
         .ins()
         .stack_addr(cranelift::prelude::types::I64, option_slot, 8);
 
-    // Call runtime: naml_channel_receive(ch, &out_value) -> tag
-    let func_ref = rt_func_ref(ctx, builder, "naml_channel_receive")?;
-    let call = builder.ins().call(func_ref, &[ch, value_ptr]);
+    // Call runtime: runtime_fn(args..., &out_value) -> tag
+    let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+    let mut call_args = args.to_vec();
+    call_args.push(value_ptr);
+    let call = builder.ins().call(func_ref, &call_args);
     let tag = builder.inst_results(call)[0];
 
     // Store the tag (truncate i64 to i32 for option tag)
@@ -56,6 +98,41 @@ pub fn call_channel_receive(
     Ok(option_ptr)
 }
 
+pub fn call_channel_select(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    channels: Value,
+    closure: Value,
+) -> Result<Value, CodegenError> {
+    let func_ptr = builder
+        .ins()
+        .load(cranelift::prelude::types::I64, MemFlags::new(), closure, 0);
+    let data_ptr = builder
+        .ins()
+        .load(cranelift::prelude::types::I64, MemFlags::new(), closure, 8);
+
+    // option<R> of the handler's result, laid out like receive's
+    let option_slot =
+        builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 16, 0));
+    let option_ptr = builder
+        .ins()
+        .stack_addr(cranelift::prelude::types::I64, option_slot, 0);
+    let value_ptr = builder
+        .ins()
+        .stack_addr(cranelift::prelude::types::I64, option_slot, 8);
+
+    let func_ref = rt_func_ref(ctx, builder, "naml_channel_select")?;
+    let call = builder
+        .ins()
+        .call(func_ref, &[channels, func_ptr, data_ptr, value_ptr]);
+    let tag = builder.inst_results(call)[0];
+
+    let tag_i32 = builder.ins().ireduce(cranelift::prelude::types::I32, tag);
+    builder.ins().store(MemFlags::new(), tag_i32, option_ptr, 0);
+
+    Ok(option_ptr)
+}
+
 pub fn call_channel_close(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
@@ -1,13 +1,18 @@
 use std::collections::{HashMap, HashSet};
 use std::panic;
+use std::time::Instant;
 
 use cranelift::prelude::*;
+use indexmap::IndexMap;
 use cranelift_module::{FuncId, Linkage};
 
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::errors::convert_cranelift_error;
 use crate::codegen::cranelift::expr::compile_expression;
+use crate::codegen::cranelift::misc::ensure_i64;
+use crate::codegen::cranelift::symbols::symbol_name;
 use crate::codegen::cranelift::{CompileContext, JitCompiler, LambdaInfo};
+use crate::typechecker::Type;
 
 impl<'a> JitCompiler<'a> {
     pub(crate) fn declare_lambda_function(&mut self, info: &LambdaInfo) -> Result<FuncId, CodegenError> {
@@ -29,7 +34,7 @@ impl<'a> JitCompiler<'a> {
 
         let func_id = self
             .module
-            .declare_function(&info.func_name, Linkage::Local, &sig)
+            .declare_function(&symbol_name(&info.func_name), Linkage::Local, &sig)
             .map_err(|e| {
                 CodegenError::JitCompile(format!(
                     "Failed to declare lambda '{}': {}",
@@ -43,6 +48,7 @@ impl<'a> JitCompiler<'a> {
     }
 
     pub(crate) fn compile_lambda_function(&mut self, info: &LambdaInfo) -> Result<(), CodegenError> {
+        let started = Instant::now();
         let func_id = *self.functions.get(&info.func_name).ok_or_else(|| {
             CodegenError::JitCompile(format!("Lambda '{}' not declared", info.func_name))
         })?;
@@ -78,7 +84,7 @@ impl<'a> JitCompiler<'a> {
             extern_fns: &self.extern_fns,
             global_vars: &self.global_vars,
             variables: HashMap::new(),
-            var_heap_types: HashMap::new(),
+            var_heap_types: IndexMap::new(),
             var_counter: 0,
             block_terminated: false,
             loop_exit_block: None,
@@ -91,6 +97,8 @@ impl<'a> JitCompiler<'a> {
             type_substitutions: HashMap::new(),
             func_return_type: Some(cranelift::prelude::types::I64), // Lambdas always return i64
             release_mode: self.release_mode,
+            overflow_checks: self.overflow_checks,
+            catch_arithmetic: false,
             unsafe_mode: self.unsafe_mode,
             inline_functions: &self.inline_functions,
             inline_depth: 0,
@@ -98,11 +106,23 @@ impl<'a> JitCompiler<'a> {
             inline_result_var: None,
             borrowed_vars: HashSet::new(),
             reassigned_vars: HashSet::new(),
+            last_use_moves: HashSet::new(),
+            bounds_facts: Vec::new(),
+            hoisted_exprs: HashMap::new(),
+            interned_strings: &mut self.interned_strings,
+            string_objects: HashMap::new(),
+            finally_blocks: Vec::new(),
+            exception_landings: Vec::new(),
             target: self.target,
         };
 
         // Load captured variables from closure data
         for (i, var_name) in info.captured_vars.iter().enumerate() {
+            // Named functions referenced in the body are never stored in the
+            // closure data; leave them resolvable as functions
+            if ctx.functions.contains_key(var_name) {
+                continue;
+            }
             let var = Variable::new(ctx.var_counter);
             ctx.var_counter += 1;
             builder.declare_var(var, cranelift::prelude::types::I64);
@@ -116,16 +136,33 @@ impl<'a> JitCompiler<'a> {
                 .ins()
                 .load(cranelift::prelude::types::I64, MemFlags::new(), addr, 0);
             builder.def_var(var, val);
+            // Captures are borrowed from the closure environment
+            ctx.borrowed_vars.insert(var_name.clone());
             ctx.variables.insert(var_name.clone(), var);
         }
 
+        // Float parameters arrive as their i64 bit pattern
+        let param_types = match ctx.annotations.get_type(info.span) {
+            Some(Type::Function(func)) => func.params.clone(),
+            _ => Vec::new(),
+        };
+
         // Define lambda parameters (starting from param 1, since param 0 is closure data)
         for (i, param_name) in info.param_names.iter().enumerate() {
             let var = Variable::new(ctx.var_counter);
             ctx.var_counter += 1;
-            builder.declare_var(var, cranelift::prelude::types::I64);
             // Parameter i+1 because param 0 is the closure data
-            builder.def_var(var, block_params[i + 1]);
+            let mut val = block_params[i + 1];
+            if param_types.get(i) == Some(&Type::Float) {
+                builder.declare_var(var, cranelift::prelude::types::F64);
+                val = builder
+                    .ins()
+                    .bitcast(cranelift::prelude::types::F64, MemFlags::new(), val);
+            } else {
+                builder.declare_var(var, cranelift::prelude::types::I64);
+            }
+            builder.def_var(var, val);
+            ctx.borrowed_vars.insert(param_name.clone());
             ctx.variables.insert(param_name.clone(), var);
         }
 
@@ -134,20 +171,7 @@ impl<'a> JitCompiler<'a> {
         let result = compile_expression(&mut ctx, &mut builder, body)?;
 
         if !ctx.block_terminated {
-            let result_type = builder.func.dfg.value_type(result);
-            let result_i64 = if result_type == cranelift::prelude::types::I8 {
-                builder
-                    .ins()
-                    .uextend(cranelift::prelude::types::I64, result)
-            } else if result_type != cranelift::prelude::types::I64
-                && result_type != cranelift::prelude::types::F64
-            {
-                builder
-                    .ins()
-                    .uextend(cranelift::prelude::types::I64, result)
-            } else {
-                result
-            };
+            let result_i64 = ensure_i64(&mut builder, result);
             builder.ins().return_(&[result_i64]);
         }
 
@@ -159,7 +183,7 @@ impl<'a> JitCompiler<'a> {
         }));
 
         match result {
-            Ok(Ok(())) => {}
+            Ok(Ok(())) => self.record_symbol(func_id, &info.func_name, "lambda", info.span, started)?,
             Ok(Err(e)) => {
                 return Err(CodegenError::JitCompile(format!(
                     "Failed to define lambda '{}': {}",
@@ -5,7 +5,9 @@ use lasso::Rodeo;
 
 use crate::ast::{Expression, FunctionItem, Item, SourceFile, Statement};
 use crate::codegen::CodegenError;
+use crate::codegen::cranelift::abi::collect_function_values;
 use crate::codegen::cranelift::heap::{self, get_heap_type_resolved};
+use crate::codegen::cranelift::mono::GenericFunctions;
 use crate::codegen::cranelift::{
     types, EnumDef, EnumVariantDef, ExternFn, GlobalVarDef, JitCompiler, StructDef,
 };
@@ -131,57 +133,61 @@ impl<'a> JitCompiler<'a> {
             }
         }
 
-        // Collect global variable declarations from top-level statements
+        // Collect global variable and constant declarations from top-level statements
         for item in &ast.items {
             if let Item::TopLevelStmt(stmt_item) = item {
-                if let Statement::Var(var_stmt) = &stmt_item.stmt {
-                    let name = self.interner.resolve(&var_stmt.name.symbol).to_string();
-
-                    // Determine the Cranelift type from the type annotation or infer from init
-                    let cl_type = if let Some(ty) = &var_stmt.ty {
-                        types::naml_to_cranelift(ty)
-                    } else {
-                        // Default to I64 for most types
-                        cranelift::prelude::types::I64
-                    };
+                let (name, ty, init) = match &stmt_item.stmt {
+                    Statement::Var(var_stmt) => (&var_stmt.name, &var_stmt.ty, var_stmt.init.as_ref()),
+                    Statement::Const(const_stmt) => {
+                        (&const_stmt.name, &const_stmt.ty, Some(&const_stmt.init))
+                    }
+                    _ => continue,
+                };
+                let name = self.interner.resolve(&name.symbol).to_string();
+
+                // Determine the Cranelift type from the type annotation or infer from init
+                let cl_type = if let Some(ty) = ty {
+                    types::naml_to_cranelift(ty)
+                } else {
+                    // Default to I64 for most types
+                    cranelift::prelude::types::I64
+                };
 
-                    // Create a data section for this global variable (8 bytes)
-                    use cranelift_module::DataDescription;
-                    let data_id = self
-                        .module
-                        .declare_data(&format!("__global_{}", name), Linkage::Local, true, false)
-                        .map_err(|e| {
-                            CodegenError::JitCompile(format!(
-                                "Failed to declare global variable '{}': {}",
-                                name, e
-                            ))
-                        })?;
-
-                    let mut data_desc = DataDescription::new();
-                    data_desc.define_zeroinit(8); // 8 bytes for any value
-                    self.module.define_data(data_id, &data_desc).map_err(|e| {
+                // Create a data section for this global variable (8 bytes)
+                use cranelift_module::DataDescription;
+                let data_id = self
+                    .module
+                    .declare_data(&format!("__global_{}", name), Linkage::Local, true, false)
+                    .map_err(|e| {
                         CodegenError::JitCompile(format!(
-                            "Failed to define global variable '{}': {}",
+                            "Failed to declare global variable '{}': {}",
                             name, e
                         ))
                     })?;
 
-                    // Store the initializer expression pointer for later compilation
-                    let init_expr = var_stmt
-                        .init
-                        .as_ref()
-                        .map(|e| e as *const Expression as *const Expression<'static>)
-                        .unwrap_or(std::ptr::null());
-
-                    self.global_vars.insert(
-                        name,
-                        GlobalVarDef {
-                            data_id,
-                            init_expr,
-                            cl_type,
-                        },
-                    );
-                }
+                let mut data_desc = DataDescription::new();
+                data_desc.define_zeroinit(8); // 8 bytes for any value
+                self.module.define_data(data_id, &data_desc).map_err(|e| {
+                    CodegenError::JitCompile(format!(
+                        "Failed to define global variable '{}': {}",
+                        name, e
+                    ))
+                })?;
+
+                // Store the initializer expression pointer for later compilation
+                #[allow(clippy::unnecessary_cast)]
+                let init_expr = init
+                    .map(|e| e as *const Expression as *const Expression<'static>)
+                    .unwrap_or(std::ptr::null());
+
+                self.global_vars.insert(
+                    name,
+                    GlobalVarDef {
+                        data_id,
+                        init_expr,
+                        cl_type,
+                    },
+                );
             }
         }
 
@@ -196,6 +202,11 @@ impl<'a> JitCompiler<'a> {
             {
                 self.scan_for_spawn_blocks(body)?;
             }
+            if let Item::Interface(i) = item {
+                for body in i.methods.iter().filter_map(|m| m.body.as_ref()) {
+                    self.scan_for_spawn_blocks(body)?;
+                }
+            }
         }
 
         // Declare spawn trampolines
@@ -209,6 +220,9 @@ impl<'a> JitCompiler<'a> {
             let _ = id; // suppress unused warning
         }
 
+        // Functions used as values keep the boxed option return convention
+        self.function_values = Some(collect_function_values(&ast.items, self.interner));
+
         // Declare all functions first (standalone and methods)
         // Skip generic functions - they will be monomorphized
         for item in &ast.items {
@@ -229,6 +243,9 @@ impl<'a> JitCompiler<'a> {
             }
         }
 
+        // Interface static functions and inherited default methods
+        self.declare_interface_functions(&ast.items)?;
+
         // Identify inline candidates (small non-generic functions)
         for item in &ast.items {
             if let Item::Function(f) = item {
@@ -275,10 +292,16 @@ impl<'a> JitCompiler<'a> {
             }
         }
 
+        self.compile_interface_functions(&ast.items)?;
+
         Ok(())
     }
 
-    pub fn compile_module_source(&mut self, source: &str) -> Result<(), CodegenError> {
+    pub fn compile_module_source(
+        &mut self,
+        source: &str,
+        file_path: &Path,
+    ) -> Result<(), CodegenError> {
         let (tokens, mut module_interner) = crate::lexer::tokenize(source);
         let arena = crate::ast::AstArena::new();
         let parse_result = crate::parser::parse(&tokens, source, &arena);
@@ -328,44 +351,112 @@ impl<'a> JitCompiler<'a> {
             }
         }
 
+        // Generics are instantiated here for the types both this module and
+        // the importing file call them with; the importing file's requests
+        // are read before switching to the module's interner
+        let generics: GenericFunctions = parse_result
+            .ast
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(f)
+                    if f.receiver.is_none() && f.body.is_some() && !f.generics.is_empty()
+                        && self.should_compile_function(f) =>
+                {
+                    let name = module_interner.resolve(&f.name.symbol).to_string();
+                    Some((name, std::ptr::from_ref(f).cast::<FunctionItem<'static>>()))
+                }
+                _ => None,
+            })
+            .collect();
+        let public_generics: GenericFunctions = generics
+            .iter()
+            .filter(|&(_, &func)| unsafe { (*func).is_public })
+            .map(|(name, &func)| (name.clone(), func))
+            .collect();
+        let mut instantiations = self.requested_instantiations(&public_generics, &module_interner);
+
+        // Locations in stack traces and symbol maps refer to this file
+        let module_source = crate::source::SourceFile::new(file_path.display().to_string(), source.to_string());
         let saved_interner = self.interner;
         let saved_annotations = self.annotations;
+        let saved_source_info = self.source_info;
         self.interner = unsafe { std::mem::transmute::<&Rodeo, &Rodeo>(&module_interner) };
         self.annotations = unsafe {
             std::mem::transmute::<&TypeAnnotations, &TypeAnnotations>(&type_result.annotations)
         };
+        self.source_info = unsafe {
+            std::mem::transmute::<&crate::source::SourceFile, &crate::source::SourceFile>(&module_source)
+        };
 
+        // Private functions get a name of their own so they cannot clash with
+        // other files; while this module compiles, its calls reach them by
+        // their plain name
+        let stem = file_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut module_functions = Vec::new();
+        let mut shadowed = Vec::new();
         for item in &parse_result.ast.items {
             if let Item::Function(f) = item {
-                if f.is_public && f.receiver.is_none() && f.body.is_some() && f.generics.is_empty()
+                if f.receiver.is_none() && f.body.is_some() && f.generics.is_empty()
                     && self.should_compile_function(f)
                 {
-                    self.declare_function(f)?;
+                    let name = module_interner.resolve(&f.name.symbol).to_string();
+                    if f.is_public {
+                        self.declare_function(f)?;
+                        module_functions.push((f, name));
+                        continue;
+                    }
+                    let mut private_name = format!("{}::{}", stem, name);
+                    let mut n = 1;
+                    while self.functions.contains_key(&private_name) {
+                        n += 1;
+                        private_name = format!("{}{}::{}", stem, n, name);
+                    }
+                    let func_id = self.declare_function_named(f, &private_name)?;
+                    shadowed.push((name.clone(), self.functions.insert(name, func_id)));
+                    module_functions.push((f, private_name));
                 }
             }
         }
-        for item in &parse_result.ast.items {
-            if let Item::Function(f) = item {
-                if f.is_public && f.receiver.is_none() && f.body.is_some() && f.generics.is_empty()
-                    && self.should_compile_function(f)
-                {
-                    self.compile_function(f)?;
-                }
-            }
+        instantiations.extend(self.requested_instantiations(&generics, &module_interner));
+        shadowed.extend(self.instantiate(instantiations, Some(&stem))?);
+        for (f, name) in &module_functions {
+            self.compile_function_named(f, name)?;
+        }
+        for (name, previous) in shadowed.into_iter().rev() {
+            match previous {
+                Some(func_id) => self.functions.insert(name, func_id),
+                None => self.functions.remove(&name),
+            };
         }
 
         self.interner = saved_interner;
         self.annotations = saved_annotations;
+        self.source_info = saved_source_info;
         Ok(())
     }
 
-    pub fn run_main(&mut self) -> Result<(), CodegenError> {
+    /// Make JIT-compiled code callable and record its addresses in the
+    /// symbol map
+    pub fn finalize(&mut self) -> Result<(), CodegenError> {
         let jit = self.module.as_jit_mut().ok_or_else(|| {
-            CodegenError::JitCompile("run_main requires JIT backend".to_string())
+            CodegenError::JitCompile("finalize requires JIT backend".to_string())
         })?;
 
         jit.finalize_definitions()
             .map_err(|e| CodegenError::JitCompile(format!("Failed to finalize: {}", e)))?;
+        self.resolve_symbol_addresses();
+        Ok(())
+    }
+
+    pub fn run_main(&mut self) -> Result<(), CodegenError> {
+        self.finalize()?;
+        let jit = self.module.as_jit_mut().ok_or_else(|| {
+            CodegenError::JitCompile("run_main requires JIT backend".to_string())
+        })?;
 
         let main_id = self
             .functions
@@ -380,6 +471,34 @@ impl<'a> JitCompiler<'a> {
         Ok(())
     }
 
+    /// Entry point of a function taking no parameters and returning
+    /// nothing, such as a benchmark; call `finalize` first
+    pub fn void_function(&mut self, name: &str) -> Result<fn(i64), CodegenError> {
+        let func_id = *self
+            .functions
+            .get(name)
+            .ok_or_else(|| CodegenError::Execution(format!("No function named '{}'", name)))?;
+        let jit = self.module.as_jit_mut().ok_or_else(|| {
+            CodegenError::JitCompile("void_function requires JIT backend".to_string())
+        })?;
+        let ptr = jit.get_finalized_function(func_id);
+        Ok(unsafe { std::mem::transmute::<*const u8, fn(i64)>(ptr) })
+    }
+
+    /// Entry point of a function taking one parameter, such as a fuzz
+    /// target; a return value, if any, is dropped. Call `finalize` first
+    pub fn unary_function(&mut self, name: &str) -> Result<fn(i64, i64), CodegenError> {
+        let func_id = *self
+            .functions
+            .get(name)
+            .ok_or_else(|| CodegenError::Execution(format!("No function named '{}'", name)))?;
+        let jit = self.module.as_jit_mut().ok_or_else(|| {
+            CodegenError::JitCompile("unary_function requires JIT backend".to_string())
+        })?;
+        let ptr = jit.get_finalized_function(func_id);
+        Ok(unsafe { std::mem::transmute::<*const u8, fn(i64, i64)>(ptr) })
+    }
+
     pub fn emit_object(self, output: &Path) -> Result<(), CodegenError> {
         let obj_module = self.module.as_object().ok_or_else(|| {
             CodegenError::JitCompile("emit_object requires Object backend".to_string())
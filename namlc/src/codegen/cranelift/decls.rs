@@ -280,6 +280,436 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_capitalize",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_title",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_snake",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_camel",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_kebab",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_parse_int_radix",
+            &[ptr, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_radix",
+            &[i64t, i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_hex",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_octal",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_to_binary",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_group_digits",
+            &[ptr, i64t, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_compare",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_equals_ignore_case",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_natural_compare",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_collate",
+            &[ptr, ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_normalize",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_casefold",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_graphemes",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_width",
+            &[ptr],
+            &[i64t],
+        )?;
+
+        // String builder (strings::builder)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_new",
+            &[],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_append",
+            &[ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_append_int",
+            &[ptr, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_len",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_to_string",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_incref",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_string_builder_decref",
+            &[ptr],
+            &[],
+        )?;
+
+        // Sorted sets (collections::sets)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_new",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_add",
+            &[ptr, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_contains",
+            &[ptr, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_remove",
+            &[ptr, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_len",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_union",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_intersection",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_difference",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_to_array",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_incref",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_set_decref",
+            &[ptr],
+            &[],
+        )?;
+
+        // Priority queues (collections::priority_queues)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_new",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_push",
+            &[ptr, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_pop_min",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_peek_min",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_len",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_incref",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_priority_queue_decref",
+            &[ptr],
+            &[],
+        )?;
+
+        // Deques (collections::deques)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_new",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_push_front",
+            &[ptr, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_push_back",
+            &[ptr, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_pop_front",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_pop_back",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_len",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_incref",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_deque_decref",
+            &[ptr],
+            &[],
+        )?;
+
+        // Iterators (collections::iterators)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_from_array",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_map",
+            &[ptr, i64t, i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_filter",
+            &[ptr, i64t, i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_take",
+            &[ptr, i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_next",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_collect",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_incref",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_iter_decref",
+            &[ptr],
+            &[],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -324,6 +754,29 @@ impl<'a> JitCompiler<'a> {
             &[f64t],
             &[ptr],
         )?;
+
+        // Format specs ({:spec} placeholders)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_format_int",
+            &[i64t, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_format_float",
+            &[f64t, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_format_string",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -491,6 +944,41 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_make_mut",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_capacity",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_reserve",
+            &[ptr, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_shrink_to_fit",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_extend",
+            &[ptr, ptr],
+            &[],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -526,6 +1014,9 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[i64t],
         )?;
+        for name in ["naml_array_sum_float", "naml_array_min_float", "naml_array_max_float"] {
+            declare(&mut *self.module, &mut self.runtime_funcs, name, &[ptr], &[f64t])?;
+        }
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -544,21 +1035,21 @@ impl<'a> JitCompiler<'a> {
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_array_take",
-            &[ptr, i64t],
+            &[ptr, i64t, i64t],
             &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_array_drop",
-            &[ptr, i64t],
+            &[ptr, i64t, i64t],
             &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_array_slice",
-            &[ptr, i64t, i64t],
+            &[ptr, i64t, i64t, i64t],
             &[ptr],
         )?;
         declare(
@@ -568,6 +1059,34 @@ impl<'a> JitCompiler<'a> {
             &[ptr, i64t],
             &[i64t],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_lower_bound",
+            &[ptr, i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_upper_bound",
+            &[ptr, i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_binary_search",
+            &[ptr, i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_partition_point",
+            &[ptr, i64t, i64t],
+            &[i64t],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -631,6 +1150,30 @@ impl<'a> JitCompiler<'a> {
             &[ptr, i64t, i64t, i64t],
             &[i64t],
         )?;
+        // Parallel array functions run on the scheduler's workers
+        if is_native {
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_array_par_map",
+                &[ptr, i64t, i64t],
+                &[ptr],
+            )?;
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_array_par_filter",
+                &[ptr, i64t, i64t],
+                &[ptr],
+            )?;
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_array_par_fold",
+                &[ptr, i64t, i64t, i64t],
+                &[i64t],
+            )?;
+        }
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -645,6 +1188,13 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_sort_float",
+            &[ptr],
+            &[ptr],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -652,6 +1202,13 @@ impl<'a> JitCompiler<'a> {
             &[ptr, i64t, i64t],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_group_by",
+            &[ptr, i64t, i64t, i64t, i64t],
+            &[ptr],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -770,7 +1327,7 @@ impl<'a> JitCompiler<'a> {
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_array_unique",
-            &[ptr],
+            &[ptr, i64t],
             &[ptr],
         )?;
         declare(
@@ -814,7 +1371,7 @@ impl<'a> JitCompiler<'a> {
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_array_zip",
-            &[ptr, ptr],
+            &[ptr, ptr, i64t],
             &[ptr],
         )?;
         declare(
@@ -829,7 +1386,14 @@ impl<'a> JitCompiler<'a> {
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_array_chunk",
-            &[ptr, i64t],
+            &[ptr, i64t, i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_array_windows",
+            &[ptr, i64t, i64t],
             &[ptr],
         )?;
         declare(
@@ -924,8 +1488,15 @@ impl<'a> JitCompiler<'a> {
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_map_new",
-            &[i64t],
+            "naml_map_new",
+            &[i64t],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_map_new_keyed",
+            &[i64t, i64t],
             &[ptr],
         )?;
         declare(
@@ -1301,6 +1872,27 @@ impl<'a> JitCompiler<'a> {
                 &[ptr, ptr],
                 &[i64t],
             )?;
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_channel_try_send",
+                &[ptr, i64t],
+                &[i64t],
+            )?;
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_channel_try_receive",
+                &[ptr, ptr],
+                &[i64t],
+            )?;
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_channel_receive_timeout",
+                &[ptr, i64t, ptr],
+                &[i64t],
+            )?;
             declare(
                 &mut *self.module,
                 &mut self.runtime_funcs,
@@ -1308,6 +1900,13 @@ impl<'a> JitCompiler<'a> {
                 &[ptr],
                 &[],
             )?;
+            declare(
+                &mut *self.module,
+                &mut self.runtime_funcs,
+                "naml_channel_select",
+                &[ptr, i64t, i64t, ptr],
+                &[i64t],
+            )?;
             declare(
                 &mut *self.module,
                 &mut self.runtime_funcs,
@@ -1476,6 +2075,32 @@ impl<'a> JitCompiler<'a> {
                 &[ptr, ptr, i64t],
                 &[],
             )?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_spawn_task", &[ptr, ptr, i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_spawn_detached", &[ptr, ptr, i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_task_join", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_task_is_done", &[i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_task_result", &[i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_waitgroup_new", &[], &[ptr])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_waitgroup_add", &[ptr, i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_waitgroup_done", &[ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_waitgroup_wait", &[ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_condvar_new", &[], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_condvar_wait", &[i64t, ptr, i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_condvar_notify_one", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_condvar_notify_all", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_semaphore_new", &[i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_semaphore_acquire", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_semaphore_try_acquire", &[i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_semaphore_release", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_group_new", &[], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_group_spawn", &[i64t, i64t, i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_group_cancel", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_group_wait", &[i64t], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_is_cancelled", &[], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_actor_spawn", &[i64t, i64t], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_actor_send", &[i64t, ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_actor_ask", &[i64t, ptr, i64t], &[ptr])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_actor_stop", &[i64t], &[])?;
         }
         declare(
             &mut *self.module,
@@ -1585,6 +2210,17 @@ impl<'a> JitCompiler<'a> {
             declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_pbkdf2_sha256", &[ptr, ptr, i64t, i64t], &[ptr])?;
             // Crypto operations - random bytes: (i64) -> ptr
             declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_random_bytes", &[i64t], &[ptr])?;
+            // Crypto operations - OTP: (..., out_tag, out_value) -> ()
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_otp_hotp", &[ptr, i64t, ptr, ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_otp_totp_now", &[ptr, i64t, i64t, ptr, ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_otp_totp_verify", &[ptr, ptr, i64t, ptr, ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_secret_from_string", &[ptr], &[ptr])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_secret_from_bytes", &[ptr], &[ptr])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_secret_expose", &[ptr], &[ptr])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_secret_len", &[ptr], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_crypto_secret_equals", &[ptr, ptr], &[i64t])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_secret_incref", &[ptr], &[])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, "naml_secret_decref", &[ptr], &[])?;
         }
 
         declare(
@@ -1631,6 +2267,27 @@ impl<'a> JitCompiler<'a> {
             &[],
             &[],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_panic_overflow",
+            &[i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_panic_arithmetic",
+            &[i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_throw_arithmetic_error",
+            &[i64t],
+            &[],
+        )?;
 
         // Exception handling
         declare(
@@ -1689,6 +2346,27 @@ impl<'a> JitCompiler<'a> {
             &[],
             &[],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_exception_set_cause",
+            &[ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_exception_get_cause",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_exception_stack_string",
+            &[ptr],
+            &[ptr],
+        )?;
 
         // File system operations
         if is_native_or_edge {
@@ -1702,149 +2380,352 @@ impl<'a> JitCompiler<'a> {
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_read_bytes",
-            &[ptr],
-            &[ptr],
+            "naml_fs_read_bytes",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_write",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_append",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_write_atomic",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_write_bytes",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_append_bytes",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_exists",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_is_file",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_is_dir",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_list_dir",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_glob",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_mkdir",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_mkdir_all",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_remove",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_remove_all",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_join",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_dirname",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_basename",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_extension",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_absolute",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_size",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_modified",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy_verified",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_verify_file",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy_options",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy_options_include",
+            &[i64t, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy_options_exclude",
+            &[i64t, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy_options_overwrite",
+            &[i64t, ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_copy_options_on_progress",
+            &[i64t, i64t, i64t],
+            &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_write",
-            &[ptr, ptr],
+            "naml_fs_copy_options_free",
             &[i64t],
+            &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_append",
-            &[ptr, ptr],
+            "naml_fs_copy_tree",
+            &[ptr, ptr, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_write_bytes",
-            &[ptr, ptr],
+            "naml_fs_move_tree",
+            &[ptr, ptr, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_append_bytes",
-            &[ptr, ptr],
+            "naml_fs_walk_options",
+            &[],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_exists",
-            &[ptr],
+            "naml_fs_walk_options_max_depth",
+            &[i64t, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_is_file",
-            &[ptr],
+            "naml_fs_walk_options_follow_symlinks",
+            &[i64t, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_is_dir",
-            &[ptr],
+            "naml_fs_walk_options_include",
+            &[i64t, ptr],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_list_dir",
-            &[ptr],
-            &[ptr],
+            "naml_fs_walk_options_exclude",
+            &[i64t, ptr],
+            &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_mkdir",
-            &[ptr],
+            "naml_fs_walk_options_free",
             &[i64t],
+            &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_mkdir_all",
+            "naml_fs_walk_dir",
+            &[ptr, i64t],
             &[ptr],
-            &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_remove",
-            &[ptr],
+            "naml_fs_walk_open",
+            &[ptr, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_remove_all",
-            &[ptr],
+            "naml_fs_walk_next",
             &[i64t],
+            &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_join",
-            &[ptr],
-            &[ptr],
+            "naml_fs_walk_close",
+            &[i64t],
+            &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_dirname",
+            "naml_fs_read_async",
             &[ptr],
             &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_basename",
-            &[ptr],
+            "naml_fs_write_async",
+            &[ptr, ptr],
             &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_extension",
+            "naml_fs_list_dir_async",
             &[ptr],
             &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_absolute",
-            &[ptr],
+            "naml_fs_await_io",
             &[ptr],
+            &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_size",
-            &[ptr],
+            "naml_fs_watch",
+            &[ptr, i64t, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_modified",
-            &[ptr],
+            "naml_fs_watch_recursive",
+            &[ptr, i64t, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_fs_copy",
-            &[ptr, ptr],
+            "naml_fs_unwatch",
             &[i64t],
+            &[],
         )?;
         declare(
             &mut *self.module,
@@ -1881,6 +2762,27 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_with_temp_file",
+            &[ptr, i64t, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_with_temp_dir",
+            &[ptr, i64t, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_temp_file_keep",
+            &[ptr],
+            &[],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -2032,6 +2934,48 @@ impl<'a> JitCompiler<'a> {
             &[i64t],
             &[i64t],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_file_fsync",
+            &[i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_file_fdatasync",
+            &[i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_lock_exclusive",
+            &[i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_lock_shared",
+            &[i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_try_lock",
+            &[i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_unlock",
+            &[i64t],
+            &[i64t],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -2118,6 +3062,34 @@ impl<'a> JitCompiler<'a> {
             &[ptr, ptr],
             &[i64t],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_disk_free",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_disk_total",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_fs_type",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_fs_is_same_filesystem",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
 
         // Additional file handle operations
         declare(
@@ -2291,6 +3263,34 @@ impl<'a> JitCompiler<'a> {
             &[ptr, ptr],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_path_absolute_lexical",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_path_relative_to",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_path_safe_join",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_path_is_within",
+            &[ptr, ptr],
+            &[i64t],
+        )?;
 
         // Environment operations (from naml-std-env)
         declare(
@@ -2350,6 +3350,49 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
         )?;
 
+        // Config loading (from naml-std-config)
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_config_file", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_config_env", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_config_flags", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_config_values", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_config_free", &[i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_config_load", &[ptr, i64t, ptr], &[ptr])?;
+
+        // Feature flags (from naml-std-flags)
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_flags_open", &[ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_flags_set_poll_interval", &[i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_flags_close", &[], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_flags_enabled", &[ptr, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_flags_variant", &[ptr, ptr, ptr], &[ptr])?;
+
+        // State machines (from naml-std-fsm)
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_open", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_add_state", &[i64t, ptr, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_add_transition", &[i64t, ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_set_guard", &[i64t, ptr, ptr, i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_set_action", &[i64t, ptr, ptr, i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_fire", &[i64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_can_fire", &[i64t, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_current", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_validate", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_to_dot", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_fsm_close", &[i64t], &[])?;
+
+        // Job queues (from naml-std-queue)
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_open", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_close", &[i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_set_capacity", &[i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_enqueue", &[i64t, ptr, i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_try_enqueue", &[i64t, ptr, i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_dequeue", &[i64t, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_dequeue_wait", &[i64t, ptr, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_job_payload", &[i64t, i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_job_attempts", &[i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_ack", &[i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_nack", &[i64t, i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_size", &[i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_queue_dead_count", &[i64t], &[i64t])?;
+
         // OS operations (from naml-std-os)
         declare(
             &mut *self.module,
@@ -2442,6 +3485,34 @@ impl<'a> JitCompiler<'a> {
             &[ptr, i64t],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_os_keyring_set_password",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_os_keyring_get_password",
+            &[ptr, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_os_keyring_delete_password",
+            &[ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_os_net_interfaces",
+            &[],
+            &[ptr],
+        )?;
 
         // Process operations (from naml-std-process)
         declare(
@@ -2531,51 +3602,118 @@ impl<'a> JitCompiler<'a> {
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sighup",
-            &[],
-            &[i64t],
+            "naml_process_sighup",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_sigint",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_sigquit",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_sigkill",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_sigterm",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_sigstop",
+            &[],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_sigcont",
+            &[],
+            &[i64t],
+        )?;
+
+        // Worker pools (from naml-std-process)
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_pool_open",
+            &[i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_pool_submit",
+            &[i64t, ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_process_pool_await",
+            &[ptr],
+            &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sigint",
-            &[],
+            "naml_process_pool_close",
             &[i64t],
+            &[],
         )?;
+
+        // IPC channels (from naml-std-process)
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sigquit",
-            &[],
+            "naml_ipc_create",
+            &[ptr, i64t],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sigkill",
-            &[],
+            "naml_ipc_connect",
+            &[ptr],
             &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sigterm",
+            "naml_ipc_send",
+            &[i64t, ptr],
             &[],
-            &[i64t],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sigstop",
-            &[],
+            "naml_ipc_receive",
             &[i64t],
+            &[ptr],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
-            "naml_process_sigcont",
-            &[],
+            "naml_ipc_close",
             &[i64t],
+            &[],
         )?;
 
         // Testing operations (from naml-std-testing)
@@ -2810,6 +3948,79 @@ impl<'a> JitCompiler<'a> {
             &[ptr, ptr, ptr],
             &[],
         )?;
+        // Base32
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_base32_encode",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_base32_decode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+        // Base58
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_base58_encode",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_base58_decode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_base58_check_encode",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_base58_check_decode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+        // Punycode
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_punycode_encode",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_punycode_decode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_punycode_to_ascii",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_punycode_to_unicode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
         // URL
         declare(
             &mut *self.module,
@@ -3008,6 +4219,226 @@ impl<'a> JitCompiler<'a> {
             &[],
         )?;
 
+        // CSV encoding operations: (text | rows, delimiter, out_tag, out_value)
+        for name in [
+            "naml_encoding_csv_parse",
+            "naml_encoding_csv_parse_with_headers",
+            "naml_encoding_csv_encode",
+        ] {
+            declare(&mut *self.module, &mut self.runtime_funcs, name, &[ptr, ptr, ptr, ptr], &[])?;
+        }
+
+        // MessagePack encoding operations
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_msgpack_encode",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_msgpack_decode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+
+        // CBOR encoding operations
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_cbor_encode",
+            &[ptr],
+            &[ptr],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_encoding_cbor_decode",
+            &[ptr, ptr, ptr],
+            &[],
+        )?;
+
+        // Compression (from naml-std-compress)
+        for format in ["gzip", "zlib", "zstd"] {
+            let compress = format!("naml_compress_{}_compress", format);
+            let decompress = format!("naml_compress_{}_decompress", format);
+            declare(&mut *self.module, &mut self.runtime_funcs, &compress, &[ptr, i64t], &[ptr])?;
+            declare(&mut *self.module, &mut self.runtime_funcs, &decompress, &[ptr, ptr, ptr], &[])?;
+        }
+
+        // Validation (from naml-std-validate)
+        for name in [
+            "naml_validate_is_email",
+            "naml_validate_is_url",
+            "naml_validate_is_uuid",
+            "naml_validate_is_ipv4",
+            "naml_validate_is_ipv6",
+            "naml_validate_is_hostname",
+            "naml_validate_matches_luhn",
+        ] {
+            declare(&mut *self.module, &mut self.runtime_funcs, name, &[ptr], &[i64t])?;
+        }
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_validate_open", &[], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_validate_add_rule", &[i64t, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_validate_validate", &[i64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_validate_check", &[i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_validate_close", &[i64t], &[])?;
+
+        // Money (from naml-std-money); money values are string pointers
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_of", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_from_minor", &[i64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_from_float", &[f64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_parse", &[ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_add", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_sub", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_negate", &[ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_multiply", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_allocate", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_compare", &[ptr, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_convert", &[ptr, ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_format", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_amount", &[ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_currency", &[ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_minor_units", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_to_float", &[ptr], &[f64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_money_currency_digits", &[ptr], &[i64t])?;
+
+        // Geo (from naml-std-geo); [float] arrays hold f64 bit patterns
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_haversine_km", &[f64t, f64t, f64t, f64t], &[f64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_bearing", &[f64t, f64t, f64t, f64t], &[f64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_destination", &[f64t, f64t, f64t, f64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_point_in_polygon", &[f64t, f64t, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_geohash_encode", &[f64t, f64t, i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_geohash_decode", &[ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_geohash_bounds", &[ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_geohash_neighbors", &[ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_bbox_around", &[f64t, f64t, f64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_bbox_contains", &[ptr, f64t, f64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_bbox_intersects", &[ptr, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_geo_bounding_box", &[ptr], &[ptr])?;
+
+        // Regex (from naml-std-regex)
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_regex_compile", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_regex_match", &[i64t, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_regex_find_all", &[i64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_regex_captures", &[i64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_regex_replace_all", &[i64t, ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_regex_close", &[i64t], &[])?;
+
+        // Images (from naml-std-image); images are integer handles
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_load", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_decode", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_create", &[i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_width", &[i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_height", &[i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_get_pixel", &[i64t, i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_set_pixel", &[i64t, i64t, i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_resize", &[i64t, i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_thumbnail", &[i64t, i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_crop", &[i64t, i64t, i64t, i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_rotate", &[i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_flip_horizontal", &[i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_flip_vertical", &[i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_encode_png", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_encode_jpeg", &[i64t, i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_save", &[i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_image_close", &[i64t], &[])?;
+
+        // PDF documents (from naml-std-pdf); documents are integer handles
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_extract_text", &[ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_page_count", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_create", &[], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_add_page", &[i64t, f64t, f64t], &[i64t])?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_pdf_draw_text",
+            &[i64t, i64t, f64t, f64t, f64t, ptr],
+            &[],
+        )?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_to_bytes", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_save", &[i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_pdf_close", &[i64t], &[])?;
+
+        // Spreadsheets (from naml-std-xlsx); workbooks are integer handles
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_open", &[ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_sheet_names", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_read_rows", &[i64t, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_create", &[], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_add_sheet", &[i64t, ptr], &[i64t])?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_write_string",
+            &[i64t, i64t, i64t, i64t, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_write_number",
+            &[i64t, i64t, i64t, i64t, f64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_write_bool",
+            &[i64t, i64t, i64t, i64t, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_write_formula",
+            &[i64t, i64t, i64t, i64t, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_write_date",
+            &[i64t, i64t, i64t, i64t, ptr],
+            &[],
+        )?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_add_format", &[i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_set_bold", &[i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_set_italic", &[i64t, i64t], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_set_num_format", &[i64t, i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_set_font_color", &[i64t, i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_set_bg_color", &[i64t, i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_set_align", &[i64t, i64t, ptr], &[])?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_format_cell",
+            &[i64t, i64t, i64t, i64t, i64t],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_xlsx_set_column_width",
+            &[i64t, i64t, i64t, f64t],
+            &[],
+        )?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_to_bytes", &[i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_save", &[i64t, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_xlsx_close", &[i64t], &[])?;
+
+        // Markdown (from naml-std-markdown); parsed documents are integer handles
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_markdown_to_html", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_markdown_parse", &[ptr, ptr], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_markdown_node_count", &[i64t], &[i64t])?;
+        for name in ["naml_markdown_node_kind", "naml_markdown_node_text", "naml_markdown_node_url", "naml_markdown_node_lang"] {
+            declare(&mut *self.module, &mut self.runtime_funcs, name, &[i64t, i64t], &[ptr])?;
+        }
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_markdown_node_depth", &[i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_markdown_node_level", &[i64t, i64t], &[i64t])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_markdown_close", &[i64t], &[])?;
+
         // Binary encoding operations - integer reads: (ptr, i64) -> i64
         for name in [
             "naml_encoding_binary_read_u8", "naml_encoding_binary_read_i8",
@@ -3052,6 +4483,7 @@ impl<'a> JitCompiler<'a> {
         declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_len", &[ptr], &[i64t])?;
         declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_capacity", &[ptr], &[i64t])?;
         declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_slice", &[ptr, i64t, i64t], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_view", &[ptr, i64t, i64t], &[ptr])?;
         declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_concat", &[ptr, ptr], &[ptr])?;
         declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_append", &[ptr, ptr], &[])?;
         declare(&mut *self.module, &mut self.runtime_funcs, "naml_encoding_binary_copy_within", &[ptr, i64t, i64t, i64t], &[])?;
@@ -3203,6 +4635,13 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_stack_frames",
+            &[ptr],
+            &[ptr],
+        )?;
 
         // Networking operations (from naml-std-net)
         if is_native_or_edge {
@@ -3237,6 +4676,13 @@ impl<'a> JitCompiler<'a> {
             &[ptr],
             &[i64t],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_tcp_server_listen_backlog",
+            &[ptr, i64t],
+            &[i64t],
+        )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
@@ -3309,6 +4755,34 @@ impl<'a> JitCompiler<'a> {
             &[i64t],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_tcp_client_set_nodelay",
+            &[i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_tcp_client_set_keepalive",
+            &[i64t, i64t, i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_tcp_client_set_recv_buffer",
+            &[i64t, i64t],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_tcp_client_set_linger",
+            &[i64t, i64t],
+            &[i64t],
+        )?;
 
         // UDP
         declare(
@@ -3397,6 +4871,34 @@ impl<'a> JitCompiler<'a> {
             &[i64t],
             &[],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_client_set_proxy",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_client_set_no_proxy",
+            &[ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_client_reset_proxy",
+            &[],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_client_set_retries",
+            &[i64t, i64t],
+            &[],
+        )?;
         // HTTP Response accessors
         declare(
             &mut *self.module,
@@ -3425,35 +4927,35 @@ impl<'a> JitCompiler<'a> {
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_net_http_server_get",
-            &[i64t, ptr, ptr],
+            &[i64t, ptr, ptr, i64t],
             &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_net_http_server_post",
-            &[i64t, ptr, ptr],
+            &[i64t, ptr, ptr, i64t],
             &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_net_http_server_put",
-            &[i64t, ptr, ptr],
+            &[i64t, ptr, ptr, i64t],
             &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_net_http_server_patch",
-            &[i64t, ptr, ptr],
+            &[i64t, ptr, ptr, i64t],
             &[],
         )?;
         declare(
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_net_http_server_delete",
-            &[i64t, ptr, ptr],
+            &[i64t, ptr, ptr, i64t],
             &[],
         )?;
         declare(
@@ -3467,8 +4969,8 @@ impl<'a> JitCompiler<'a> {
             &mut *self.module,
             &mut self.runtime_funcs,
             "naml_net_http_server_group",
-            &[i64t, ptr],
-            &[i64t],
+            &[i64t, ptr, i64t, i64t],
+            &[],
         )?;
         declare(
             &mut *self.module,
@@ -3491,6 +4993,27 @@ impl<'a> JitCompiler<'a> {
             &[i64t, ptr],
             &[ptr],
         )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_server_health_router",
+            &[ptr],
+            &[i64t],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_server_set_value",
+            &[i64t, ptr, ptr],
+            &[],
+        )?;
+        declare(
+            &mut *self.module,
+            &mut self.runtime_funcs,
+            "naml_net_http_server_get_value",
+            &[i64t, ptr],
+            &[ptr],
+        )?;
 
         // HTTP Middleware
         declare(
@@ -3632,6 +5155,24 @@ impl<'a> JitCompiler<'a> {
             &[ptr, ptr],
             &[ptr],
         )?;
+
+        // S3 object storage
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_configure", &[ptr, ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_reset_config", &[], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_put_object", &[ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_put_file", &[ptr, ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_get_object", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_list", &[ptr, ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_net_s3_presign_url", &[ptr, ptr, ptr, i64t], &[ptr])?;
+
+        // Cloud instance metadata
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_provider", &[], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_region", &[], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_instance_id", &[], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_credentials", &[], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_get", &[ptr], &[ptr])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_set_endpoint", &[ptr, ptr], &[])?;
+        declare(&mut *self.module, &mut self.runtime_funcs, "naml_cloud_metadata_reset", &[], &[])?;
         }
 
         if is_native_or_edge {
@@ -185,6 +185,12 @@ impl<'a> JitCompiler<'a> {
                             }
                         }
                         HeapType::OptionOf(_) => "naml_struct_decref".to_string(),
+                        HeapType::Secret => "naml_secret_decref".to_string(),
+                        HeapType::StringBuilder => "naml_string_builder_decref".to_string(),
+                        HeapType::Set => "naml_set_decref".to_string(),
+                        HeapType::PriorityQueue => "naml_priority_queue_decref".to_string(),
+                        HeapType::Deque => "naml_deque_decref".to_string(),
+                        HeapType::Iterator => "naml_iter_decref".to_string(),
                     };
 
                     let inner_func_id = self
@@ -207,6 +213,12 @@ impl<'a> JitCompiler<'a> {
                 } else {
                     let decref_func_name: String = match ht {
                         HeapType::String => "naml_string_decref".to_string(),
+                        HeapType::Secret => "naml_secret_decref".to_string(),
+                        HeapType::StringBuilder => "naml_string_builder_decref".to_string(),
+                        HeapType::Set => "naml_set_decref".to_string(),
+                        HeapType::PriorityQueue => "naml_priority_queue_decref".to_string(),
+                        HeapType::Deque => "naml_deque_decref".to_string(),
+                        HeapType::Iterator => "naml_iter_decref".to_string(),
                         HeapType::Array(None) => "naml_array_decref".to_string(),
                         HeapType::Array(Some(elem_type)) => match elem_type.as_ref() {
                             HeapType::String => "naml_array_decref_strings".to_string(),
@@ -214,6 +226,12 @@ impl<'a> JitCompiler<'a> {
                             HeapType::Map(_) => "naml_array_decref_maps".to_string(),
                             HeapType::Struct(_) => "naml_array_decref_structs".to_string(),
                             HeapType::OptionOf(_) => "naml_array_decref".to_string(),
+                            HeapType::Secret => "naml_array_decref".to_string(),
+                            HeapType::StringBuilder => "naml_array_decref".to_string(),
+                            HeapType::Set => "naml_array_decref".to_string(),
+                            HeapType::PriorityQueue => "naml_array_decref".to_string(),
+                            HeapType::Deque => "naml_array_decref".to_string(),
+                            HeapType::Iterator => "naml_array_decref".to_string(),
                         },
                         HeapType::Map(None) => "naml_map_decref".to_string(),
                         HeapType::Map(Some(val_type)) => match val_type.as_ref() {
@@ -222,6 +240,12 @@ impl<'a> JitCompiler<'a> {
                             HeapType::Map(_) => "naml_map_decref_maps".to_string(),
                             HeapType::Struct(_) => "naml_map_decref_structs".to_string(),
                             HeapType::OptionOf(_) => "naml_map_decref".to_string(),
+                            HeapType::Secret => "naml_map_decref".to_string(),
+                            HeapType::StringBuilder => "naml_map_decref".to_string(),
+                            HeapType::Set => "naml_map_decref".to_string(),
+                            HeapType::PriorityQueue => "naml_map_decref".to_string(),
+                            HeapType::Deque => "naml_map_decref".to_string(),
+                            HeapType::Iterator => "naml_map_decref".to_string(),
                         },
                         HeapType::Struct(None) => "naml_struct_decref".to_string(),
                         HeapType::Struct(Some(field_struct_name)) => {
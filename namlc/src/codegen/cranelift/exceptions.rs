@@ -3,8 +3,57 @@ use cranelift_codegen::ir::Value;
 use cranelift_frontend::FunctionBuilder;
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::{CompileContext};
-use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::runtime::{emit_stack_pop, rt_func_ref};
+use crate::codegen::cranelift::stmt::compile_statement;
 use crate::codegen::cranelift::literal::compile_string_literal;
+use crate::ast::NamlType;
+use lasso::{Rodeo, Spur};
+
+/// Runtime type ID set when an exception of this type is thrown, matching
+/// the IDs in naml-std-core's exception module. None for the `error` base type.
+pub fn exception_type_id(ctx: &CompileContext<'_>, name: Spur) -> Option<i64> {
+    let builtin = match ctx.interner.resolve(&name) {
+        "IOError" => Some(1),
+        "PermissionError" => Some(2),
+        "DecodeError" => Some(3),
+        "PathError" => Some(4),
+        "NetworkError" => Some(5),
+        "TimeoutError" => Some(6),
+        "EnvError" => Some(7),
+        "OSError" => Some(8),
+        "ProcessError" => Some(9),
+        "DBError" => Some(10),
+        "EncodeError" => Some(11),
+        "ScheduleError" => Some(12),
+        "SecurityError" => Some(13),
+        "ArithmeticError" => Some(14),
+        "ConfigError" => Some(15),
+        "InvalidTransition" => Some(16),
+        "ValidationError" => Some(17),
+        "MoneyError" => Some(18),
+        "RegexError" => Some(19),
+        "ImageError" => Some(20),
+        "PdfError" => Some(21),
+        "XlsxError" => Some(22),
+        "MarkdownError" => Some(23),
+        "error" => return None,
+        _ => None,
+    };
+    builtin.or_else(|| {
+        ctx.exception_names
+            .contains(&name)
+            .then(|| ctx.struct_defs.get(&name))
+            .flatten()
+            .map(|def| crate::runtime::EXCEPTION_TYPE_USER_BASE + def.type_id as i64)
+    })
+}
+
+/// Whether a `throws` clause lists ArithmeticError
+pub fn throws_arithmetic(interner: &Rodeo, throws: &[NamlType]) -> bool {
+    throws
+        .iter()
+        .any(|t| matches!(t, NamlType::Named(name) if interner.resolve(&name.symbol) == "ArithmeticError"))
+}
 
 // Exception handling helper functions
 pub fn call_exception_set(
@@ -70,6 +119,105 @@ pub fn throw_encode_error(
     Ok(())
 }
 
+pub fn call_exception_set_typed(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    exception_ptr: Value,
+    type_id: i64,
+) -> Result<(), CodegenError> {
+    let type_id = builder.ins().iconst(types::I64, type_id);
+    let func_ref = rt_func_ref(ctx, builder, "naml_exception_set_typed")?;
+    builder.ins().call(func_ref, &[exception_ptr, type_id]);
+    Ok(())
+}
+
+/// Compile the enclosing `finally` blocks, innermost first, before control
+/// leaves the function. Each runs with only the blocks outside it pending.
+pub fn emit_pending_finally(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+) -> Result<(), CodegenError> {
+    let pending = std::mem::take(&mut ctx.finally_blocks);
+    let mut result = Ok(());
+    for depth in (0..pending.len()).rev() {
+        ctx.finally_blocks = pending[..depth].to_vec();
+        let body = unsafe { &*pending[depth] };
+        for stmt in &body.statements {
+            result = compile_statement(ctx, builder, stmt);
+            if result.is_err() || ctx.block_terminated {
+                break;
+            }
+        }
+        if result.is_err() || ctx.block_terminated {
+            break;
+        }
+    }
+    ctx.finally_blocks = pending;
+    result
+}
+
+/// Return from the current function with the pending exception still set,
+/// handing it to the caller's handler
+pub fn emit_exception_propagate(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+) -> Result<(), CodegenError> {
+    if !ctx.finally_blocks.is_empty() {
+        // Set the exception aside so calls in the finally blocks don't see it
+        let exception_ptr = call_exception_get(ctx, builder)?;
+        let get_type_id = rt_func_ref(ctx, builder, "naml_exception_get_type_id")?;
+        let call = builder.ins().call(get_type_id, &[]);
+        let type_id = builder.inst_results(call)[0];
+        call_exception_clear(ctx, builder)?;
+
+        emit_pending_finally(ctx, builder)?;
+        // A return from a finally block discards the exception
+        if ctx.block_terminated {
+            return Ok(());
+        }
+
+        let set_typed = rt_func_ref(ctx, builder, "naml_exception_set_typed")?;
+        builder.ins().call(set_typed, &[exception_ptr, type_id]);
+    }
+    emit_stack_pop(ctx, builder)?;
+    let returns: Vec<_> = builder
+        .func
+        .signature
+        .returns
+        .iter()
+        .map(|param| param.value_type)
+        .collect();
+    let zeros: Vec<Value> = returns
+        .into_iter()
+        .map(|ty| {
+            if ty == types::F64 {
+                builder.ins().f64const(0.0)
+            } else {
+                builder.ins().iconst(ty, 0)
+            }
+        })
+        .collect();
+    builder.ins().return_(&zeros);
+    ctx.block_terminated = true;
+    Ok(())
+}
+
+/// Leave the current block after inline code (a failed division, say) has
+/// set an exception: jump to the innermost enclosing handler, or propagate
+/// to the caller if there is none
+pub fn emit_inline_throw(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+) -> Result<(), CodegenError> {
+    if let Some(&landing) = ctx.exception_landings.last() {
+        builder.ins().jump(landing, &[]);
+        return Ok(());
+    }
+    emit_exception_propagate(ctx, builder)?;
+    ctx.block_terminated = false;
+    Ok(())
+}
+
 pub fn call_exception_get(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
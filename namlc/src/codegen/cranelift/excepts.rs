@@ -37,8 +37,8 @@ impl<'a> JitCompiler<'a> {
             s("DecodeError"),
             StructDef {
                 type_id: 0xFFFF_0003,
-                fields: vec![message, s("position")],
-                field_heap_types: vec![Some(HeapType::String), None],
+                fields: vec![s("position")],
+                field_heap_types: vec![None],
             },
         );
 
@@ -57,8 +57,8 @@ impl<'a> JitCompiler<'a> {
             s("ProcessError"),
             StructDef {
                 type_id: 0xFFFF_0009,
-                fields: vec![message, code],
-                field_heap_types: vec![Some(HeapType::String), None],
+                fields: vec![code],
+                field_heap_types: vec![None],
             },
         );
 
@@ -77,8 +77,8 @@ impl<'a> JitCompiler<'a> {
             s("DBError"),
             StructDef {
                 type_id: 0xFFFF_000A,
-                fields: vec![message, code],
-                field_heap_types: vec![Some(HeapType::String), None],
+                fields: vec![code],
+                field_heap_types: vec![None],
             },
         );
 
@@ -97,8 +97,8 @@ impl<'a> JitCompiler<'a> {
             s("OSError"),
             StructDef {
                 type_id: 0xFFFF_0008,
-                fields: vec![message, code],
-                field_heap_types: vec![Some(HeapType::String), None],
+                fields: vec![code],
+                field_heap_types: vec![None],
             },
         );
 
@@ -107,8 +107,8 @@ impl<'a> JitCompiler<'a> {
             s("NetworkError"),
             StructDef {
                 type_id: 0xFFFF_0005,
-                fields: vec![message, code],
-                field_heap_types: vec![Some(HeapType::String), None],
+                fields: vec![code],
+                field_heap_types: vec![None],
             },
         );
 
@@ -117,8 +117,8 @@ impl<'a> JitCompiler<'a> {
             s("TimeoutError"),
             StructDef {
                 type_id: 0xFFFF_0006,
-                fields: vec![message, s("timeout_ms")],
-                field_heap_types: vec![Some(HeapType::String), None],
+                fields: vec![s("timeout_ms")],
+                field_heap_types: vec![None],
             },
         );
 
@@ -142,6 +142,126 @@ impl<'a> JitCompiler<'a> {
             },
         );
 
+        self.exception_names.insert(s("SecurityError"));
+        self.struct_defs.insert(
+            s("SecurityError"),
+            StructDef {
+                type_id: 0xFFFF_000F,
+                fields: vec![path],
+                field_heap_types: vec![Some(HeapType::String)],
+            },
+        );
+
+        self.exception_names.insert(s("error"));
+        self.struct_defs.insert(
+            s("error"),
+            StructDef {
+                type_id: 0xFFFF_0011,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
+        self.exception_names.insert(s("ArithmeticError"));
+        self.struct_defs.insert(
+            s("ArithmeticError"),
+            StructDef {
+                type_id: 0xFFFF_0010,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
+        self.exception_names.insert(s("ConfigError"));
+        self.struct_defs.insert(
+            s("ConfigError"),
+            StructDef {
+                type_id: 0xFFFF_0014,
+                fields: vec![key],
+                field_heap_types: vec![Some(HeapType::String)],
+            },
+        );
+
+        self.exception_names.insert(s("InvalidTransition"));
+        self.struct_defs.insert(
+            s("InvalidTransition"),
+            StructDef {
+                type_id: 0xFFFF_0015,
+                fields: vec![s("state"), s("event")],
+                field_heap_types: vec![Some(HeapType::String), Some(HeapType::String)],
+            },
+        );
+
+        self.exception_names.insert(s("ValidationError"));
+        self.struct_defs.insert(
+            s("ValidationError"),
+            StructDef {
+                type_id: 0xFFFF_0016,
+                fields: vec![s("field"), s("rule")],
+                field_heap_types: vec![Some(HeapType::String), Some(HeapType::String)],
+            },
+        );
+
+        self.exception_names.insert(s("MoneyError"));
+        self.struct_defs.insert(
+            s("MoneyError"),
+            StructDef {
+                type_id: 0xFFFF_0017,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
+        self.exception_names.insert(s("RegexError"));
+        self.struct_defs.insert(
+            s("RegexError"),
+            StructDef {
+                type_id: 0xFFFF_0018,
+                fields: vec![s("pattern")],
+                field_heap_types: vec![Some(HeapType::String)],
+            },
+        );
+
+        self.exception_names.insert(s("ImageError"));
+        self.struct_defs.insert(
+            s("ImageError"),
+            StructDef {
+                type_id: 0xFFFF_0019,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
+        self.exception_names.insert(s("PdfError"));
+        self.struct_defs.insert(
+            s("PdfError"),
+            StructDef {
+                type_id: 0xFFFF_001A,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
+        self.exception_names.insert(s("XlsxError"));
+        self.struct_defs.insert(
+            s("XlsxError"),
+            StructDef {
+                type_id: 0xFFFF_001B,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
+        self.exception_names.insert(s("MarkdownError"));
+        self.struct_defs.insert(
+            s("MarkdownError"),
+            StructDef {
+                type_id: 0xFFFF_001C,
+                fields: vec![],
+                field_heap_types: vec![],
+            },
+        );
+
         self.exception_names.insert(s("TlsError"));
         self.struct_defs.insert(
             s("TlsError"),
@@ -1,24 +1,34 @@
 use crate::ast::{BinaryOp, Expression, Literal, LiteralExpr, NamlType, TemplateStringPart};
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::CompileContext;
-use crate::codegen::cranelift::array::{compile_array_literal, compile_direct_array_get_or_panic};
-use crate::codegen::cranelift::binop::{compile_binary_op, compile_unary_op};
+use crate::codegen::cranelift::abi::{call_result_value, emit_enum_land, enum_value_size};
+use crate::codegen::cranelift::array::{
+    compile_array_literal, compile_direct_array_get_or_panic, compile_unchecked_array_get,
+};
+use crate::codegen::cranelift::bounds::is_index_in_bounds;
+use crate::codegen::cranelift::binop::{
+    compile_binary_op, compile_checked_int_division, compile_checked_int_op, compile_unary_op,
+};
 use crate::codegen::cranelift::exceptions::{
     call_exception_check, call_exception_clear, call_exception_clear_ptr, call_exception_get,
+    emit_exception_propagate, exception_type_id,
 };
 use crate::codegen::cranelift::externs::compile_extern_call;
-use crate::codegen::cranelift::literal::compile_literal;
+use crate::codegen::cranelift::literal::{compile_const_value, compile_literal};
 use crate::codegen::cranelift::literal::compile_string_literal;
-use crate::codegen::cranelift::map::{compile_direct_map_get_or_panic, compile_map_literal};
+use crate::codegen::cranelift::map::{
+    compile_direct_map_get_or_panic, compile_map_key, compile_map_literal, is_map_index,
+};
 use crate::codegen::cranelift::method::compile_method_call;
+use crate::codegen::cranelift::misc::{coerce_call_args, from_generic_word};
 use crate::codegen::cranelift::options::{
-    compile_option_from_array_get, compile_option_from_map_get,
+    compile_option_from_array_get, compile_option_from_map_get, compile_option_from_nullable_ptr,
 };
-use crate::codegen::cranelift::runtime::{call_alloc_closure_data, emit_incref, rt_func_ref};
-use crate::codegen::cranelift::spawns::call_spawn_closure;
+use crate::codegen::cranelift::runtime::{call_alloc_closure_data, rt_func_ref};
+use crate::codegen::cranelift::spawns::compile_spawn;
 use crate::codegen::cranelift::stmt::compile_statement;
 use crate::codegen::cranelift::strings::{
-    call_bytes_to_string, call_float_to_string, call_int_to_string, call_string_concat,
+    call_bytes_to_string, call_float_to_string, call_format, call_int_to_string, call_string_concat,
     call_string_equals, call_string_from_cstr, call_string_to_bytes, call_string_to_float,
     call_string_to_int,
 };
@@ -70,8 +80,23 @@ pub fn compile_expression(
     builder: &mut FunctionBuilder<'_>,
     expr: &Expression<'_>,
 ) -> Result<Value, CodegenError> {
+    // Evaluated once before an enclosing loop
+    if !ctx.hoisted_exprs.is_empty()
+        && let Some(&var) = ctx.hoisted_exprs.get(&expr.span())
+    {
+        return Ok(builder.use_var(var));
+    }
+
     match expr {
-        Expression::Literal(lit_expr) => compile_literal(ctx, builder, &lit_expr.value),
+        Expression::Literal(lit_expr) => {
+            // Int literals in a float position were typed as floats
+            if let Literal::Int(n) = lit_expr.value
+                && matches!(ctx.annotations.get_type(lit_expr.span), Some(Type::Float))
+            {
+                return Ok(builder.ins().f64const(n as f64));
+            }
+            compile_literal(ctx, builder, &lit_expr.value)
+        }
 
         Expression::Identifier(ident) => {
             let name = ctx.interner.resolve(&ident.ident.symbol).to_string();
@@ -179,8 +204,14 @@ pub fn compile_expression(
                 let none_block = builder.create_block();
                 let merge_block = builder.create_block();
 
+                // Float payloads are loaded as floats, everything else as a word
+                let result_type = match ctx.annotations.get_type(bin.span) {
+                    Some(Type::Float) => cranelift::prelude::types::F64,
+                    _ => cranelift::prelude::types::I64,
+                };
+
                 // Add block parameter for the result
-                builder.append_block_param(merge_block, cranelift::prelude::types::I64);
+                builder.append_block_param(merge_block, result_type);
 
                 // Load the tag from offset 0 of the option struct
                 let tag =
@@ -196,10 +227,7 @@ pub fn compile_expression(
                 // Some block: extract the value from offset 8
                 builder.switch_to_block(some_block);
                 builder.seal_block(some_block);
-                let inner_value =
-                    builder
-                        .ins()
-                        .load(cranelift::prelude::types::I64, MemFlags::new(), lhs, 8);
+                let inner_value = builder.ins().load(result_type, MemFlags::new(), lhs, 8);
                 builder.ins().jump(merge_block, &[inner_value]);
 
                 // None block: evaluate and use rhs
@@ -225,9 +253,10 @@ pub fn compile_expression(
                 return Ok(result);
             }
 
-            // Check if this is a string comparison (Eq/NotEq)
+            // Check if this is a string comparison (Eq/NotEq); money values
+            // are canonical strings, so equal amounts compare equal
             let lhs_type = ctx.annotations.get_type(bin.left.span());
-            if matches!(lhs_type, Some(Type::String))
+            if matches!(lhs_type, Some(Type::String) | Some(Type::Money))
                 && matches!(bin.op, BinaryOp::Eq | BinaryOp::NotEq)
             {
                 let lhs = compile_expression(ctx, builder, bin.left)?;
@@ -296,25 +325,8 @@ pub fn compile_expression(
                 }
 
                 // Handle `is` operator for exception type checking
-                // Exception type IDs: IOError=1, PermissionError=2, DecodeError=3,
-                // PathError=4, NetworkError=5, TimeoutError=6
                 if let Expression::Identifier(ident) = bin.right {
-                    let type_name = ctx.interner.resolve(&ident.ident.symbol);
-                    let exception_type_id = match type_name {
-                        "IOError" => Some(1i64),
-                        "PermissionError" => Some(2i64),
-                        "DecodeError" => Some(3i64),
-                        "PathError" => Some(4i64),
-                        "NetworkError" => Some(5i64),
-                        "TimeoutError" => Some(6i64),
-                        "EnvError" => Some(7i64),
-                        "OSError" => Some(8i64),
-                        "ProcessError" => Some(9i64),
-                        "DBError" => Some(10i64),
-                        "EncodeError" => Some(11i64),
-                        "ScheduleError" => Some(12i64),
-                        _ => None,
-                    };
+                    let exception_type_id = exception_type_id(ctx, ident.ident.symbol);
 
                     if let Some(type_id) = exception_type_id {
                         // Call naml_exception_is_type to check if current exception matches
@@ -333,6 +345,34 @@ pub fn compile_expression(
 
             let lhs = compile_expression(ctx, builder, bin.left)?;
             let rhs = compile_expression(ctx, builder, bin.right)?;
+            if !ctx.unsafe_mode
+                && matches!(bin.op, BinaryOp::Div | BinaryOp::Mod)
+                && builder.func.dfg.value_type(lhs) == cranelift::prelude::types::I64
+            {
+                match ctx.annotations.get_type(bin.left.span()).map(Type::resolve) {
+                    Some(Type::Int) => {
+                        return compile_checked_int_division(ctx, builder, &bin.op, lhs, rhs, true);
+                    }
+                    Some(Type::Uint) => {
+                        return compile_checked_int_division(ctx, builder, &bin.op, lhs, rhs, false);
+                    }
+                    _ => {}
+                }
+            }
+            if ctx.overflow_checks
+                && matches!(bin.op, BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul)
+                && builder.func.dfg.value_type(lhs) == cranelift::prelude::types::I64
+            {
+                match ctx.annotations.get_type(bin.left.span()).map(Type::resolve) {
+                    Some(Type::Int) => {
+                        return compile_checked_int_op(ctx, builder, &bin.op, lhs, rhs, true);
+                    }
+                    Some(Type::Uint) => {
+                        return compile_checked_int_op(ctx, builder, &bin.op, lhs, rhs, false);
+                    }
+                    _ => {}
+                }
+            }
             compile_binary_op(builder, &bin.op, lhs, rhs)
         }
 
@@ -342,8 +382,16 @@ pub fn compile_expression(
         }
 
         Expression::Call(call) => {
+            // Calls to const fns with constant arguments were evaluated by the type checker
+            if let Some(value) = ctx.annotations.get_const_value(call.span) {
+                return compile_const_value(ctx, builder, value);
+            }
             if let Expression::Identifier(ident) = call.callee {
-                let func_name = ctx.interner.resolve(&ident.ident.symbol);
+                // Functions imported under an alias are compiled under their own name
+                let func_name = match ctx.annotations.get_aliased_call(call.span) {
+                    Some(original) => original.as_str(),
+                    None => ctx.interner.resolve(&ident.ident.symbol),
+                };
 
                 let actual_func_name =
                     if let Some(mangled_name) = ctx.annotations.get_call_instantiation(call.span) {
@@ -366,7 +414,7 @@ pub fn compile_expression(
                         .or_else(|| super::builtins::lookup_builtin(func_name, ctx.target))
                     {
                         return super::builtins::compile_builtin_call(
-                            ctx, builder, builtin, &call.args,
+                            ctx, builder, builtin, &call.args, call.span,
                         );
                     }
                 }
@@ -427,6 +475,7 @@ pub fn compile_expression(
                                 super::types::naml_to_cranelift(&inline_info.param_types[i]);
                             builder.declare_var(var, param_ty);
                             builder.def_var(var, arg_values[i]);
+                            ctx.borrowed_vars.insert(param_name.clone());
                             ctx.variables.insert(param_name.clone(), var);
                         }
 
@@ -503,15 +552,14 @@ pub fn compile_expression(
                         }
                         args.push(val);
                     }
+                    coerce_call_args(builder, func_ref, &mut args);
 
                     let call_inst = builder.ins().call(func_ref, &args);
-                    let results = builder.inst_results(call_inst);
-
-                    if results.is_empty() {
-                        Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
-                    } else {
-                        Ok(results[0])
+                    let result = call_result_value(builder, call_inst);
+                    if let Some(size) = enum_value_size(ctx, ctx.annotations.get_type(call.span)) {
+                        return emit_enum_land(ctx, builder, result, size);
                     }
+                    Ok(from_generic_word(builder, result, ctx.annotations.get_type(call.span)))
                 }
                 // Check for extern function
                 else if let Some(extern_fn) = ctx.extern_fns.get(func_name).cloned() {
@@ -554,7 +602,8 @@ pub fn compile_expression(
                     // Build arguments: first is data_ptr, then actual args
                     let mut args = vec![data_ptr];
                     for arg in &call.args {
-                        args.push(compile_expression(ctx, builder, arg)?);
+                        let val = compile_expression(ctx, builder, arg)?;
+                        args.push(super::misc::ensure_i64(builder, val));
                     }
 
                     // Indirect call through function pointer
@@ -563,6 +612,17 @@ pub fn compile_expression(
 
                     if results.is_empty() {
                         Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
+                    } else if matches!(ctx.annotations.get_type(call.span), Some(Type::Float)) {
+                        // Float results come back as their i64 bit pattern
+                        let result = results[0];
+                        Ok(builder
+                            .ins()
+                            .bitcast(cranelift::prelude::types::F64, MemFlags::new(), result))
+                    } else if let Some(size) =
+                        enum_value_size(ctx, ctx.annotations.get_type(call.span))
+                    {
+                        let result = results[0];
+                        emit_enum_land(ctx, builder, result, size)
                     } else {
                         Ok(results[0])
                     }
@@ -632,8 +692,15 @@ pub fn compile_expression(
                     segments.join("::")
                 };
 
+                // Static interface functions are declared as `{Interface}_{function}`
+                let static_name = match path_expr.segments.as_slice() {
+                    [owner, _] => Some(format!("{}_{}", ctx.interner.resolve(&owner.symbol), func_name)),
+                    _ => None,
+                };
+                let static_func = static_name.and_then(|name| ctx.functions.get(&name).copied());
+
                 // 1. Check if this function exists in ctx.functions (user-defined or imported)
-                if let Some(&func_id) = ctx.functions.get(&func_name) {
+                if let Some(func_id) = static_func.or_else(|| ctx.functions.get(&func_name).copied()) {
                     let func_ref = ctx.module.declare_func_in_func(func_id, builder.func);
 
                     // First arg is closure data (0 for regular functions)
@@ -656,13 +723,7 @@ pub fn compile_expression(
                     }
 
                     let call_inst = builder.ins().call(func_ref, &args);
-                    let results = builder.inst_results(call_inst);
-
-                    return if results.is_empty() {
-                        Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
-                    } else {
-                        Ok(results[0])
-                    };
+                    return Ok(call_result_value(builder, call_inst));
                 }
 
                 // 2. Check builtin registry - try qualified name first, then simple name
@@ -670,7 +731,7 @@ pub fn compile_expression(
                     .or_else(|| super::builtins::lookup_builtin(&func_name, ctx.target))
                 {
                     return super::builtins::compile_builtin_call(
-                        ctx, builder, builtin, &call.args,
+                        ctx, builder, builtin, &call.args, call.span,
                     );
                 }
 
@@ -772,7 +833,7 @@ pub fn compile_expression(
 
         Expression::Array(arr_expr) => compile_array_literal(ctx, builder, &arr_expr.elements),
 
-        Expression::Map(map_expr) => compile_map_literal(ctx, builder, &map_expr.entries),
+        Expression::Map(map_expr) => compile_map_literal(ctx, builder, map_expr),
 
         Expression::Index(index_expr) => {
             let base = compile_expression(ctx, builder, index_expr.base)?;
@@ -806,14 +867,9 @@ pub fn compile_expression(
             }
 
             // Indexing returns option<T> for safety (none if out of bounds / key not found)
-            if let Expression::Literal(LiteralExpr {
-                value: Literal::String(_),
-                ..
-            }) = index_expr.index
-            {
-                let cstr_ptr = compile_expression(ctx, builder, index_expr.index)?;
-                let naml_str = call_string_from_cstr(ctx, builder, cstr_ptr)?;
-                compile_option_from_map_get(ctx, builder, base, naml_str)
+            if is_map_index(ctx, index_expr) {
+                let key = compile_map_key(ctx, builder, index_expr.index)?;
+                compile_option_from_map_get(ctx, builder, base, key)
             } else {
                 let index = compile_expression(ctx, builder, index_expr.index)?;
                 compile_option_from_array_get(ctx, builder, base, index)
@@ -828,6 +884,7 @@ pub fn compile_expression(
                 method_call.receiver,
                 method_name,
                 &method_call.args,
+                method_call.span,
             )
         }
 
@@ -975,12 +1032,46 @@ pub fn compile_expression(
             let message_spur = ctx.interner.get("message").unwrap();
             let stack_spur = ctx.interner.get("stack").unwrap();
 
+            // iface_info and fs_event records are built by the runtime as plain
+            // structs with their fields in declaration order at 24+
+            let base_type = ctx.annotations.get_type(field_expr.base.span());
+            let runtime_record_fields: Option<&[&str]> = match base_type {
+                Some(Type::IfaceInfo) => Some(&["name", "ips", "mac", "flags", "mtu"]),
+                Some(Type::FsEvent) => Some(&["kind", "path", "timestamp"]),
+                _ => None,
+            };
+            if let Some(fields) = runtime_record_fields {
+                let field_str = ctx.interner.resolve(&field_spur);
+                let Some(idx) = fields.iter().position(|f| *f == field_str) else {
+                    return Err(CodegenError::JitCompile(format!(
+                        "Unknown {} field: {}",
+                        base_type.unwrap(),
+                        field_str
+                    )));
+                };
+                return Ok(builder.ins().load(
+                    cranelift::prelude::types::I64,
+                    MemFlags::new(),
+                    struct_ptr,
+                    24 + idx as i32 * 8,
+                ));
+            }
+
             // Use type annotation to determine correct field offset
             // Note: use ident.span (IdentExpr span), not ident.ident.span (Ident span)
             if let Expression::Identifier(ident) = field_expr.base
                 && let Some(type_ann) = ctx.annotations.get_type(ident.span)
             {
                 if let crate::typechecker::Type::Exception(exc_name) = type_ann {
+                    // The cause lives in the runtime's side table, not the layout
+                    if ctx.interner.resolve(&field_spur) == "cause" {
+                        return compile_option_from_nullable_ptr(
+                            ctx,
+                            builder,
+                            &[struct_ptr],
+                            "naml_exception_get_cause",
+                        );
+                    }
                     // Exception layout: message at 0, stack at 8, user fields at 16+
                     let offset = if field_spur == message_spur {
                         0
@@ -1090,6 +1181,10 @@ pub fn compile_expression(
                         struct_ptr,
                         offset,
                     );
+                    // function and file point at static C strings
+                    if offset < 16 {
+                        return call_string_from_cstr(ctx, builder, value);
+                    }
                     return Ok(value);
                 }
             }
@@ -1127,6 +1222,26 @@ pub fn compile_expression(
                 }
             }
 
+            // Instances of generic structs: the field's own annotation has the
+            // type arguments substituted in
+            if let Some(Type::Generic(name, _)) = ctx.annotations.get_type(field_expr.base.span())
+                && let Some(struct_def) = ctx.struct_defs.get(name)
+                && let Some(idx) = struct_def.fields.iter().position(|f| *f == field_spur)
+            {
+                let load_type = if let Some(Type::Float) = ctx.annotations.get_type(field_expr.span) {
+                    cranelift::prelude::types::F64
+                } else {
+                    cranelift::prelude::types::I64
+                };
+                let value = builder.ins().load(
+                    load_type,
+                    MemFlags::new(),
+                    struct_ptr,
+                    (24 + idx * 8) as i32,
+                );
+                return Ok(value);
+            }
+
             for (_, struct_def) in ctx.struct_defs.iter() {
                 if let Some(field_idx) = struct_def.fields.iter().position(|f| *f == field_spur) {
                     let offset = (24 + field_idx * 8) as i32;
@@ -1146,63 +1261,7 @@ pub fn compile_expression(
             )))
         }
 
-        Expression::Spawn(spawn_expr) => {
-            // Look up spawn ID from body pointer (globally unique across functions)
-            #[allow(clippy::unnecessary_cast)]
-            let body_key = spawn_expr.body as *const crate::ast::BlockExpr<'_> as usize;
-            let spawn_id = *ctx.spawn_body_to_id.get(&body_key).ok_or_else(|| {
-                CodegenError::JitCompile("Spawn block not found for body pointer".to_string())
-            })?;
-
-            let info = ctx
-                .spawn_blocks
-                .get(&spawn_id)
-                .ok_or_else(|| {
-                    CodegenError::JitCompile(format!("Spawn block {} not found", spawn_id))
-                })?
-                .clone();
-
-            let ptr_type = ctx.module.target_config().pointer_type();
-
-            // Calculate closure data size (8 bytes per captured variable)
-            let data_size = info.captured_vars.len() * 8;
-            let data_size_val = builder
-                .ins()
-                .iconst(cranelift::prelude::types::I64, data_size as i64);
-
-            // Allocate closure data
-            let data_ptr = if data_size > 0 {
-                call_alloc_closure_data(ctx, builder, data_size_val)?
-            } else {
-                builder.ins().iconst(ptr_type, 0)
-            };
-
-            // Store captured variables in closure data
-            for (i, var_name) in info.captured_vars.iter().enumerate() {
-                if let Some(&var) = ctx.variables.get(var_name) {
-                    let val = builder.use_var(var);
-                    let offset = builder.ins().iconst(ptr_type, (i * 8) as i64);
-                    let addr = builder.ins().iadd(data_ptr, offset);
-                    builder.ins().store(MemFlags::new(), val, addr, 0);
-                    if let Some(heap_type) = ctx.var_heap_types.get(var_name) {
-                        emit_incref(ctx, builder, val, &heap_type.clone())?;
-                    }
-                }
-            }
-
-            // Get trampoline function address
-            let trampoline_id = *ctx.functions.get(&info.func_name).ok_or_else(|| {
-                CodegenError::JitCompile(format!("Trampoline '{}' not found", info.func_name))
-            })?;
-            let trampoline_ref = ctx.module.declare_func_in_func(trampoline_id, builder.func);
-            let trampoline_addr = builder.ins().func_addr(ptr_type, trampoline_ref);
-
-            // Call spawn_closure to schedule the task
-            call_spawn_closure(ctx, builder, trampoline_addr, data_ptr, data_size_val)?;
-
-            // Return unit (0) as spawn expressions don't have a meaningful return value
-            Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
-        }
+        Expression::Spawn(spawn_expr) => compile_spawn(ctx, builder, spawn_expr, true),
 
         Expression::Some(some_expr) => {
             let mut inner_val = compile_expression(ctx, builder, some_expr.value)?;
@@ -1315,12 +1374,9 @@ pub fn compile_expression(
         Expression::Try(try_expr) => {
             // try converts a throwing expression to option<T>
             // Returns some(result) on success, none on exception
-            let result = compile_expression(ctx, builder, try_expr.expr)?;
 
-            // Check if an exception occurred
-            let has_exception = call_exception_check(ctx, builder)?;
-
-            // Allocate option struct on stack (16 bytes: tag i32 at 0, value i64 at 8)
+            // Allocate option struct on stack (16 bytes: tag i32 at 0, value i64 at 8),
+            // before the expression so a failed division can jump past it
             let option_slot = builder.create_sized_stack_slot(StackSlotData::new(
                 StackSlotKind::ExplicitSlot,
                 16,
@@ -1331,8 +1387,18 @@ pub fn compile_expression(
                     .ins()
                     .stack_addr(cranelift::prelude::types::I64, option_slot, 0);
 
-            // Create blocks for branching
             let exception_block = builder.create_block();
+            let outer_catch = std::mem::replace(&mut ctx.catch_arithmetic, true);
+            ctx.exception_landings.push(exception_block);
+            let result = compile_expression(ctx, builder, try_expr.expr);
+            ctx.exception_landings.pop();
+            ctx.catch_arithmetic = outer_catch;
+            let result = result?;
+
+            // Check if an exception occurred
+            let has_exception = call_exception_check(ctx, builder)?;
+
+            // Create blocks for branching
             let no_exception_block = builder.create_block();
             let merge_block = builder.create_block();
 
@@ -1369,19 +1435,66 @@ pub fn compile_expression(
             Ok(option_ptr)
         }
 
+        Expression::Finally(finally_expr) => {
+            #[allow(clippy::unnecessary_cast)]
+            let body = finally_expr.body as *const crate::ast::BlockExpr<'_>
+                as *const crate::ast::BlockExpr<'static>;
+
+            // Returns and throws inside the expression (e.g. in its catch
+            // handler) run the body before leaving the function
+            let propagate_block = builder.create_block();
+            ctx.finally_blocks.push(body);
+            ctx.exception_landings.push(propagate_block);
+            let result = compile_expression(ctx, builder, finally_expr.expr);
+            ctx.exception_landings.pop();
+            ctx.finally_blocks.pop();
+            let result = result?;
+
+            // An exception nothing caught runs the body, then propagates
+            let has_exception = call_exception_check(ctx, builder)?;
+            let normal_block = builder.create_block();
+            builder
+                .ins()
+                .brif(has_exception, propagate_block, &[], normal_block, &[]);
+
+            builder.switch_to_block(propagate_block);
+            builder.seal_block(propagate_block);
+            ctx.finally_blocks.push(body);
+            let propagated = emit_exception_propagate(ctx, builder);
+            ctx.finally_blocks.pop();
+            propagated?;
+            ctx.block_terminated = false;
+
+            // Normal completion, including a handled exception
+            builder.switch_to_block(normal_block);
+            builder.seal_block(normal_block);
+            for stmt in &finally_expr.body.statements {
+                compile_statement(ctx, builder, stmt)?;
+                if ctx.block_terminated {
+                    break;
+                }
+            }
+            Ok(result)
+        }
+
         Expression::Catch(catch_expr) => {
             // Get the expression type to handle Bool correctly
             let expr_type = ctx.annotations.get_type(catch_expr.expr.span());
             let is_bool_type = matches!(expr_type, Some(Type::Bool));
 
             // Compile the expression that might throw
-            let result = compile_expression(ctx, builder, catch_expr.expr)?;
+            let exception_block = builder.create_block();
+            let outer_catch = std::mem::replace(&mut ctx.catch_arithmetic, true);
+            ctx.exception_landings.push(exception_block);
+            let result = compile_expression(ctx, builder, catch_expr.expr);
+            ctx.exception_landings.pop();
+            ctx.catch_arithmetic = outer_catch;
+            let result = result?;
 
             // Check if an exception occurred
             let has_exception = call_exception_check(ctx, builder)?;
 
             // Create blocks for branching
-            let exception_block = builder.create_block();
             let no_exception_block = builder.create_block();
             let merge_block = builder.create_block();
 
@@ -1402,6 +1515,44 @@ pub fn compile_expression(
             builder.switch_to_block(exception_block);
             builder.seal_block(exception_block);
 
+            // `catch (e: A | B)` only handles the listed types; anything else
+            // propagates to the caller. Listing `error` handles every exception.
+            let catches_all = catch_expr.error_types.iter().any(|ty| {
+                matches!(ty, NamlType::Named(ident) if ctx.interner.resolve(&ident.symbol) == "error")
+            });
+            if !catch_expr.error_types.is_empty() && !catches_all {
+                let type_ids: Vec<i64> = catch_expr
+                    .error_types
+                    .iter()
+                    .filter_map(|ty| match ty {
+                        NamlType::Named(ident) => exception_type_id(ctx, ident.symbol),
+                        _ => None,
+                    })
+                    .collect();
+                let get_type_id = rt_func_ref(ctx, builder, "naml_exception_get_type_id")?;
+                let call = builder.ins().call(get_type_id, &[]);
+                let current = builder.inst_results(call)[0];
+                let mut matched = builder.ins().iconst(cranelift::prelude::types::I8, 0);
+                for type_id in type_ids {
+                    let is_type = builder.ins().icmp_imm(IntCC::Equal, current, type_id);
+                    matched = builder.ins().bor(matched, is_type);
+                }
+
+                let handler_block = builder.create_block();
+                let propagate_block = builder.create_block();
+                builder
+                    .ins()
+                    .brif(matched, handler_block, &[], propagate_block, &[]);
+
+                builder.switch_to_block(propagate_block);
+                builder.seal_block(propagate_block);
+                emit_exception_propagate(ctx, builder)?;
+                ctx.block_terminated = false;
+
+                builder.switch_to_block(handler_block);
+                builder.seal_block(handler_block);
+            }
+
             // Get the exception pointer and bind to the error variable
             let exception_ptr = call_exception_get(ctx, builder)?;
             let error_var_name = ctx
@@ -1812,15 +1963,10 @@ pub fn compile_expression(
             if let Expression::Index(index_expr) = unwrap_expr.expr {
                 let base = compile_expression(ctx, builder, index_expr.base)?;
 
-                // Check if this is a map access (string key) or array access (integer index)
-                if let Expression::Literal(LiteralExpr {
-                    value: Literal::String(_),
-                    ..
-                }) = index_expr.index
-                {
-                    let cstr_ptr = compile_expression(ctx, builder, index_expr.index)?;
-                    let naml_str = call_string_from_cstr(ctx, builder, cstr_ptr)?;
-                    return compile_direct_map_get_or_panic(ctx, builder, base, naml_str);
+                // Check if this is a map access or array access (integer index)
+                if is_map_index(ctx, index_expr) {
+                    let key = compile_map_key(ctx, builder, index_expr.index)?;
+                    return compile_direct_map_get_or_panic(ctx, builder, base, key);
                 } else {
                     // Array access: arr[index]!
                     let index = compile_expression(ctx, builder, index_expr.index)?;
@@ -1836,6 +1982,9 @@ pub fn compile_expression(
                         cranelift::prelude::types::I64
                     };
 
+                    if is_index_in_bounds(ctx, index_expr.base, index_expr.index) {
+                        return Ok(compile_unchecked_array_get(ctx, builder, base, index, element_cl_type));
+                    }
                     return compile_direct_array_get_or_panic(ctx, builder, base, index, element_cl_type);
                 }
             }
@@ -1889,7 +2038,13 @@ pub fn compile_expression(
             let some_block = builder.create_block();
             let none_block = builder.create_block();
             let merge_block = builder.create_block();
-            builder.append_block_param(merge_block, cranelift::prelude::types::I64);
+
+            // Float payloads are loaded as floats, everything else as a word
+            let result_type = match ctx.annotations.get_type(unwrap_expr.span) {
+                Some(Type::Float) => cranelift::prelude::types::F64,
+                _ => cranelift::prelude::types::I64,
+            };
+            builder.append_block_param(merge_block, result_type);
 
             // Check if tag == 0 (none)
             let is_none = builder.ins().icmp_imm(IntCC::Equal, tag, 0);
@@ -1903,18 +2058,17 @@ pub fn compile_expression(
             let panic_func = rt_func_ref(ctx, builder, "naml_panic_unwrap")?;
             builder.ins().call(panic_func, &[]);
             // Panic doesn't return, but we need to provide a value for the block
-            let zero = builder.ins().iconst(cranelift::prelude::types::I64, 0);
+            let zero = if result_type == cranelift::prelude::types::F64 {
+                builder.ins().f64const(0.0)
+            } else {
+                builder.ins().iconst(cranelift::prelude::types::I64, 0)
+            };
             builder.ins().jump(merge_block, &[zero]);
 
             // Some block: extract the value from offset 8
             builder.switch_to_block(some_block);
             builder.seal_block(some_block);
-            let inner_value = builder.ins().load(
-                cranelift::prelude::types::I64,
-                MemFlags::new(),
-                option_ptr,
-                8,
-            );
+            let inner_value = builder.ins().load(result_type, MemFlags::new(), option_ptr, 8);
             builder.ins().jump(merge_block, &[inner_value]);
 
             // Merge block
@@ -1949,7 +2103,13 @@ fn compile_template_string(
                 let ptr = compile_string_literal(ctx, builder, s)?;
                 call_string_from_cstr(ctx, builder, ptr)?
             }
-            TemplateStringPart::Expression(expr_str) => {
+            TemplateStringPart::Expression(raw) => {
+                // `{name:spec}` formats the value like a `fmt` placeholder
+                let (expr_str, spec) = match raw.split_once(':') {
+                    Some((expr_str, spec)) => (expr_str, Some(spec)),
+                    None => (raw.as_str(), None),
+                };
+
                 // Tokenize the expression to get the identifier
                 let (tokens, interner) = tokenize(expr_str);
 
@@ -1981,36 +2141,49 @@ fn compile_template_string(
 
                         // Check if this is a heap type (like string)
                         if let Some(heap_type) = ctx.var_heap_types.get(ident_name) {
-                            match heap_type {
-                                HeapType::String => val,                     // Already a string pointer
+                            match (heap_type, spec) {
+                                (HeapType::String, Some(spec)) => {
+                                    call_format(ctx, builder, "naml_format_string", val, spec)?
+                                }
+                                (HeapType::String, None) => val, // Already a string pointer
                                 _ => call_int_to_string(ctx, builder, val)?, // Other heap types
                             }
                         } else {
                             // Not a heap type - check Cranelift type
                             let var_type = builder.func.dfg.value_type(val);
                             if var_type == cranelift::prelude::types::F64 {
-                                call_float_to_string(ctx, builder, val)?
+                                match spec {
+                                    Some(spec) => call_format(ctx, builder, "naml_format_float", val, spec)?,
+                                    None => call_float_to_string(ctx, builder, val)?,
+                                }
                             } else if var_type == cranelift::prelude::types::I8 {
                                 // Bool type
                                 let true_str = compile_string_literal(ctx, builder, "true")?;
                                 let true_naml = call_string_from_cstr(ctx, builder, true_str)?;
                                 let false_str = compile_string_literal(ctx, builder, "false")?;
                                 let false_naml = call_string_from_cstr(ctx, builder, false_str)?;
-                                builder.ins().select(val, true_naml, false_naml)
+                                let text = builder.ins().select(val, true_naml, false_naml);
+                                match spec {
+                                    Some(spec) => call_format(ctx, builder, "naml_format_string", text, spec)?,
+                                    None => text,
+                                }
                             } else {
-                                call_int_to_string(ctx, builder, val)?
+                                match spec {
+                                    Some(spec) => call_format(ctx, builder, "naml_format_int", val, spec)?,
+                                    None => call_int_to_string(ctx, builder, val)?,
+                                }
                             }
                         }
                     } else {
                         // Variable not found, return as literal
                         let ptr =
-                            compile_string_literal(ctx, builder, &format!("{{{}}}", expr_str))?;
+                            compile_string_literal(ctx, builder, &format!("{{{}}}", raw))?;
                         call_string_from_cstr(ctx, builder, ptr)?
                     }
                 } else {
                     // Complex expression - not yet fully supported
                     // For now, output as literal with braces
-                    let ptr = compile_string_literal(ctx, builder, &format!("{{{}}}", expr_str))?;
+                    let ptr = compile_string_literal(ctx, builder, &format!("{{{}}}", raw))?;
                     call_string_from_cstr(ctx, builder, ptr)?
                 }
             }
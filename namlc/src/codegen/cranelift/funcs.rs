@@ -1,23 +1,37 @@
 use std::collections::{HashMap, HashSet};
 use std::panic;
+use std::time::Instant;
 
 use cranelift::prelude::*;
+use indexmap::IndexMap;
 use cranelift_module::{FuncId, Linkage, Module};
 
 use crate::ast::{Expression, FunctionItem};
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::errors::convert_cranelift_error;
+use crate::codegen::cranelift::exceptions::throws_arithmetic;
 use crate::codegen::cranelift::expr::compile_expression;
+use crate::codegen::cranelift::ownership::collect_last_use_moves;
 use crate::codegen::cranelift::runtime::{emit_cleanup_all_vars, emit_stack_pop, emit_stack_push};
 use crate::codegen::cranelift::stmt::compile_statement;
+use crate::codegen::cranelift::symbols::symbol_name;
+use crate::typechecker::attributes::has_attribute;
 use crate::codegen::cranelift::{
     collect_reassigned_vars, types, CompileContext, InlineFuncInfo, JitCompiler,
 };
 
 impl<'a> JitCompiler<'a> {
     pub(crate) fn declare_function(&mut self, func: &FunctionItem<'_>) -> Result<FuncId, CodegenError> {
-        let name = self.interner.resolve(&func.name.symbol);
+        let interner = self.interner;
+        self.declare_function_named(func, interner.resolve(&func.name.symbol))
+    }
 
+    /// Declare `func` under `name` rather than its own identifier
+    pub(crate) fn declare_function_named(
+        &mut self,
+        func: &FunctionItem<'_>,
+        name: &str,
+    ) -> Result<FuncId, CodegenError> {
         let mut sig = self.module.make_signature();
 
         sig.params
@@ -28,9 +42,8 @@ impl<'a> JitCompiler<'a> {
             sig.params.push(AbiParam::new(ty));
         }
 
-        if let Some(ref return_ty) = func.return_ty {
-            let ty = types::naml_to_cranelift(return_ty);
-            sig.returns.push(AbiParam::new(ty));
+        if func.return_ty.is_some() {
+            self.push_return_abi(&mut sig, name, func.return_ty.as_ref());
         } else if name == "main" && self.module.is_aot() {
             sig.returns
                 .push(AbiParam::new(cranelift::prelude::types::I32));
@@ -38,7 +51,7 @@ impl<'a> JitCompiler<'a> {
 
         let func_id = self
             .module
-            .declare_function(name, Linkage::Export, &sig)
+            .declare_function(&symbol_name(name), Linkage::Export, &sig)
             .map_err(|e| {
                 CodegenError::JitCompile(format!("Failed to declare function '{}': {}", name, e))
             })?;
@@ -49,7 +62,8 @@ impl<'a> JitCompiler<'a> {
     }
 
     /// Check if a function is a good candidate for inlining and store it if so.
-    /// Criteria: small body, no throws, no generics, not "main", not recursive.
+    /// Criteria: small body (or `@inline`), no throws, no generics, not "main",
+    /// not recursive.
     pub(crate) fn maybe_add_inline_candidate(&mut self, func: &FunctionItem<'_>) {
         let name = self.interner.resolve(&func.name.symbol);
 
@@ -74,9 +88,10 @@ impl<'a> JitCompiler<'a> {
             None => return,
         };
 
-        // Count statements - inline only small functions (max 5 statements)
+        // Count statements - inline only small functions (max 5 statements),
+        // unless the function asks for it with @inline
         let stmt_count = body.statements.len();
-        if stmt_count > 5 {
+        if stmt_count > 5 && !has_attribute(&func.attributes, self.interner, "inline") {
             return;
         }
 
@@ -108,7 +123,17 @@ impl<'a> JitCompiler<'a> {
     }
 
     pub(crate) fn compile_function(&mut self, func: &FunctionItem<'_>) -> Result<(), CodegenError> {
-        let name = self.interner.resolve(&func.name.symbol);
+        let interner = self.interner;
+        self.compile_function_named(func, interner.resolve(&func.name.symbol))
+    }
+
+    /// Compile `func` as the function declared under `name`
+    pub(crate) fn compile_function_named(
+        &mut self,
+        func: &FunctionItem<'_>,
+        name: &str,
+    ) -> Result<(), CodegenError> {
+        let started = Instant::now();
         let func_id = *self
             .functions
             .get(name)
@@ -149,7 +174,7 @@ impl<'a> JitCompiler<'a> {
             extern_fns: &self.extern_fns,
             global_vars: &self.global_vars,
             variables: HashMap::new(),
-            var_heap_types: HashMap::new(),
+            var_heap_types: IndexMap::new(),
             var_counter: 0,
             block_terminated: false,
             loop_exit_block: None,
@@ -162,6 +187,8 @@ impl<'a> JitCompiler<'a> {
             type_substitutions: HashMap::new(),
             func_return_type,
             release_mode: self.release_mode,
+            overflow_checks: self.overflow_checks,
+            catch_arithmetic: throws_arithmetic(self.interner, &func.throws),
             unsafe_mode: self.unsafe_mode,
             inline_functions: &self.inline_functions,
             inline_depth: 0,
@@ -169,12 +196,20 @@ impl<'a> JitCompiler<'a> {
             inline_result_var: None,
             borrowed_vars: HashSet::new(),
             reassigned_vars: HashSet::new(),
+            last_use_moves: HashSet::new(),
+            bounds_facts: Vec::new(),
+            hoisted_exprs: HashMap::new(),
+            interned_strings: &mut self.interned_strings,
+            string_objects: HashMap::new(),
+            finally_blocks: Vec::new(),
+            exception_landings: Vec::new(),
             target: self.target,
         };
 
-        // Scan function body for variable reassignments to enable borrow optimization
+        // Scan function body for variable reassignments and last-use moves to elide refcounting
         if let Some(ref body) = func.body {
             collect_reassigned_vars(&body.statements, self.interner, &mut ctx.reassigned_vars);
+            ctx.last_use_moves = collect_last_use_moves(&body.statements, self.interner);
         }
 
         for (i, param) in func.params.iter().enumerate() {
@@ -185,17 +220,18 @@ impl<'a> JitCompiler<'a> {
             let ty = types::naml_to_cranelift(&param.ty);
             builder.declare_var(var, ty);
             builder.def_var(var, val);
+            // Parameters are borrowed from the caller
+            ctx.borrowed_vars.insert(param_name.clone());
             ctx.variables.insert(param_name, var);
         }
 
         // Push function onto shadow stack for stack traces
-        let func_name_str = self.interner.resolve(&func.name.symbol);
         let (line, _) = self.source_info.line_col(func.span.start);
         let file_name = &*self.source_info.name;
         emit_stack_push(
             &mut ctx,
             &mut builder,
-            func_name_str,
+            name,
             file_name,
             line as u32,
         )?;
@@ -265,7 +301,7 @@ impl<'a> JitCompiler<'a> {
         }));
 
         match result {
-            Ok(Ok(())) => {}
+            Ok(Ok(())) => self.record_symbol(func_id, name, "function", func.span, started)?,
             Ok(Err(e)) => {
                 return Err(CodegenError::JitCompile(format!(
                     "Failed to define function '{}': {:?}",
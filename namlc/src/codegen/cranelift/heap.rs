@@ -5,6 +5,12 @@ pub(crate) enum HeapType {
     Map(Option<Box<HeapType>>),
     Struct(Option<lasso::Spur>),
     OptionOf(Box<HeapType>),
+    Secret,
+    StringBuilder,
+    Set,
+    PriorityQueue,
+    Deque,
+    Iterator,
 }
 
 pub fn get_heap_type_resolved(naml_ty: &crate::ast::NamlType, interner: &lasso::Rodeo) -> Option<HeapType> {
@@ -27,9 +33,32 @@ pub fn get_heap_type_resolved(naml_ty: &crate::ast::NamlType, interner: &lasso::
             get_heap_type_resolved(inner_ty, interner).map(|ht| HeapType::OptionOf(Box::new(ht)))
         }
         NamlType::Named(ident) => {
+            match interner.resolve(&ident.symbol) {
+                "secret" => return Some(HeapType::Secret),
+                "string_builder" => return Some(HeapType::StringBuilder),
+                // Money values are canonical strings such as "12.34 USD"
+                "money" => return Some(HeapType::String),
+                // Frame records are plain captured data without a heap header
+                "stack_frame" => return None,
+                // Interface records are built by the runtime and never freed
+                "iface_info" => return None,
+                // Watch events are owned by the watcher's dispatch
+                "fs_event" => return None,
+                // Wait groups are shared between tasks and never freed, like mutexes
+                "waitgroup" => return None,
+                // `atomic_int` is `atomic<int>`, which is not refcounted either
+                "atomic_int" => return None,
+                _ => {}
+            }
             Some(HeapType::Struct(Some(ident.symbol)))
         }
-        NamlType::Generic(_, _) => Some(HeapType::Struct(None)),
+        NamlType::Generic(ident, args) => match interner.resolve(&ident.symbol) {
+            "set" if args.len() == 1 => Some(HeapType::Set),
+            "priority_queue" if args.len() == 1 => Some(HeapType::PriorityQueue),
+            "deque" if args.len() == 1 => Some(HeapType::Deque),
+            "iterator" if args.len() == 1 => Some(HeapType::Iterator),
+            _ => Some(HeapType::Struct(None)),
+        },
         _ => None,
     }
 }
@@ -37,6 +66,12 @@ pub fn get_heap_type_resolved(naml_ty: &crate::ast::NamlType, interner: &lasso::
 pub fn remap_heap_type(ht: HeapType, from: &lasso::Rodeo, to: &lasso::Rodeo) -> HeapType {
     match ht {
         HeapType::String => HeapType::String,
+        HeapType::Secret => HeapType::Secret,
+        HeapType::StringBuilder => HeapType::StringBuilder,
+        HeapType::Set => HeapType::Set,
+        HeapType::PriorityQueue => HeapType::PriorityQueue,
+        HeapType::Deque => HeapType::Deque,
+        HeapType::Iterator => HeapType::Iterator,
         HeapType::Array(inner) => HeapType::Array(
             inner.map(|b| Box::new(remap_heap_type(*b, from, to))),
         ),
@@ -61,6 +96,13 @@ pub fn heap_type_from_type(
     use crate::typechecker::types::Type;
     match ty {
         Type::String => Some(HeapType::String),
+        Type::Secret => Some(HeapType::Secret),
+        Type::StringBuilder => Some(HeapType::StringBuilder),
+        Type::Set(_) => Some(HeapType::Set),
+        Type::PriorityQueue(_) => Some(HeapType::PriorityQueue),
+        Type::Deque(_) => Some(HeapType::Deque),
+        Type::Iterator(_) => Some(HeapType::Iterator),
+        Type::Money => Some(HeapType::String),
         Type::Array(elem) | Type::FixedArray(elem, _) => {
             let elem_heap = heap_type_from_type(elem, _interner).map(Box::new);
             Some(HeapType::Array(elem_heap))
@@ -10,6 +10,7 @@ use crate::ast::CompilationTarget;
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::{BackendModule, EnumDef, EnumVariantDef, JitCompiler};
 use crate::typechecker::TypeAnnotations;
+use crate::timings::FunctionTime;
 
 fn create_isa(pic: bool, release: bool) -> Result<cranelift_codegen::isa::OwnedTargetIsa, CodegenError> {
     let mut flag_builder = settings::builder();
@@ -45,7 +46,7 @@ impl<'a> JitCompiler<'a> {
     ) -> Result<Self, CodegenError> {
         let ctx = module.make_context();
 
-        let mut enum_defs = HashMap::new();
+        let mut enum_defs = IndexMap::new();
         enum_defs.insert(
             "option".to_string(),
             EnumDef {
@@ -76,21 +77,29 @@ impl<'a> JitCompiler<'a> {
             ctx,
             functions: HashMap::new(),
             runtime_funcs: HashMap::new(),
-            struct_defs: HashMap::new(),
+            struct_defs: IndexMap::new(),
             enum_defs,
             exception_names: HashSet::new(),
             extern_fns: HashMap::new(),
             global_vars: IndexMap::new(),
             next_type_id: 0,
             spawn_counter: 0,
-            spawn_blocks: HashMap::new(),
+            spawn_blocks: IndexMap::new(),
             spawn_body_to_id: HashMap::new(),
             lambda_counter: 0,
-            lambda_blocks: HashMap::new(),
+            lambda_blocks: IndexMap::new(),
             lambda_body_to_id: HashMap::new(),
             generic_functions: HashMap::new(),
+            instantiations: HashMap::new(),
             inline_functions: HashMap::new(),
+            function_values: None,
+            interned_strings: HashMap::new(),
+            symbols: Vec::new(),
+            function_times: None,
+            code_size: 0,
+            max_code_size: None,
             release_mode: release,
+            overflow_checks: !release && !unsafe_mode,
             unsafe_mode,
             target,
         };
@@ -169,6 +178,26 @@ impl<'a> JitCompiler<'a> {
             "naml_array_clear",
             crate::runtime::naml_array_clear as *const u8,
         );
+        builder.symbol(
+            "naml_array_make_mut",
+            crate::runtime::naml_array_make_mut as *const u8,
+        );
+        builder.symbol(
+            "naml_array_capacity",
+            crate::runtime::naml_array_capacity as *const u8,
+        );
+        builder.symbol(
+            "naml_array_reserve",
+            crate::runtime::naml_array_reserve as *const u8,
+        );
+        builder.symbol(
+            "naml_array_shrink_to_fit",
+            crate::runtime::naml_array_shrink_to_fit as *const u8,
+        );
+        builder.symbol(
+            "naml_array_extend",
+            crate::runtime::naml_array_extend as *const u8,
+        );
         builder.symbol(
             "naml_array_first",
             crate::runtime::naml_array_first as *const u8,
@@ -189,6 +218,9 @@ impl<'a> JitCompiler<'a> {
             "naml_array_max",
             crate::runtime::naml_array_max as *const u8,
         );
+        builder.symbol("naml_array_sum_float", crate::runtime::naml_array_sum_float as *const u8);
+        builder.symbol("naml_array_min_float", crate::runtime::naml_array_min_float as *const u8);
+        builder.symbol("naml_array_max_float", crate::runtime::naml_array_max_float as *const u8);
         builder.symbol(
             "naml_array_reverse",
             crate::runtime::naml_array_reverse as *const u8,
@@ -213,6 +245,22 @@ impl<'a> JitCompiler<'a> {
             "naml_array_index_of",
             crate::runtime::naml_array_index_of as *const u8,
         );
+        builder.symbol(
+            "naml_array_lower_bound",
+            crate::runtime::naml_array_lower_bound as *const u8,
+        );
+        builder.symbol(
+            "naml_array_upper_bound",
+            crate::runtime::naml_array_upper_bound as *const u8,
+        );
+        builder.symbol(
+            "naml_array_binary_search",
+            crate::runtime::naml_array_binary_search as *const u8,
+        );
+        builder.symbol(
+            "naml_array_partition_point",
+            crate::runtime::naml_array_partition_point as *const u8,
+        );
         builder.symbol(
             "naml_array_contains",
             crate::runtime::naml_array_contains as *const u8,
@@ -249,6 +297,21 @@ impl<'a> JitCompiler<'a> {
             "naml_array_fold",
             crate::runtime::naml_array_fold as *const u8,
         );
+        // Parallel array functions run on the scheduler's workers
+        if is_native {
+            builder.symbol(
+                "naml_array_par_map",
+                crate::runtime::naml_array_par_map as *const u8,
+            );
+            builder.symbol(
+                "naml_array_par_filter",
+                crate::runtime::naml_array_par_filter as *const u8,
+            );
+            builder.symbol(
+                "naml_array_par_fold",
+                crate::runtime::naml_array_par_fold as *const u8,
+            );
+        }
         builder.symbol(
             "naml_array_flatten",
             crate::runtime::naml_array_flatten as *const u8,
@@ -257,10 +320,15 @@ impl<'a> JitCompiler<'a> {
             "naml_array_sort",
             crate::runtime::naml_array_sort as *const u8,
         );
+        builder.symbol("naml_array_sort_float", crate::runtime::naml_array_sort_float as *const u8);
         builder.symbol(
             "naml_array_sort_by",
             crate::runtime::naml_array_sort_by as *const u8,
         );
+        builder.symbol(
+            "naml_array_group_by",
+            crate::runtime::naml_array_group_by as *const u8,
+        );
         builder.symbol(
             "naml_array_print",
             crate::runtime::naml_array_print as *const u8,
@@ -366,6 +434,10 @@ impl<'a> JitCompiler<'a> {
             "naml_array_chunk",
             crate::runtime::naml_array_chunk as *const u8,
         );
+        builder.symbol(
+            "naml_array_windows",
+            crate::runtime::naml_array_windows as *const u8,
+        );
         builder.symbol(
             "naml_array_partition",
             crate::runtime::naml_array_partition as *const u8,
@@ -483,8 +555,34 @@ impl<'a> JitCompiler<'a> {
                 "naml_spawn_closure",
                 crate::runtime::naml_spawn_closure as *const u8,
             );
+            builder.symbol("naml_spawn_task", crate::runtime::naml_spawn_task as *const u8);
+            builder.symbol("naml_spawn_detached", crate::runtime::naml_spawn_detached as *const u8);
+            builder.symbol("naml_task_join", crate::runtime::naml_task_join as *const u8);
+            builder.symbol("naml_task_is_done", crate::runtime::naml_task_is_done as *const u8);
+            builder.symbol("naml_task_result", crate::runtime::naml_task_result as *const u8);
+            builder.symbol("naml_waitgroup_new", crate::runtime::naml_waitgroup_new as *const u8);
+            builder.symbol("naml_waitgroup_add", crate::runtime::naml_waitgroup_add as *const u8);
+            builder.symbol("naml_waitgroup_done", crate::runtime::naml_waitgroup_done as *const u8);
+            builder.symbol("naml_waitgroup_wait", crate::runtime::naml_waitgroup_wait as *const u8);
+            builder.symbol("naml_condvar_new", crate::runtime::naml_condvar_new as *const u8);
+            builder.symbol("naml_condvar_wait", crate::runtime::naml_condvar_wait as *const u8);
+            builder.symbol("naml_condvar_notify_one", crate::runtime::naml_condvar_notify_one as *const u8);
+            builder.symbol("naml_condvar_notify_all", crate::runtime::naml_condvar_notify_all as *const u8);
+            builder.symbol("naml_semaphore_new", crate::runtime::naml_semaphore_new as *const u8);
+            builder.symbol("naml_semaphore_acquire", crate::runtime::naml_semaphore_acquire as *const u8);
+            builder.symbol("naml_semaphore_try_acquire", crate::runtime::naml_semaphore_try_acquire as *const u8);
+            builder.symbol("naml_semaphore_release", crate::runtime::naml_semaphore_release as *const u8);
+            builder.symbol("naml_group_new", crate::runtime::naml_group_new as *const u8);
+            builder.symbol("naml_group_spawn", crate::runtime::naml_group_spawn as *const u8);
+            builder.symbol("naml_group_cancel", crate::runtime::naml_group_cancel as *const u8);
+            builder.symbol("naml_group_wait", crate::runtime::naml_group_wait as *const u8);
+            builder.symbol("naml_is_cancelled", crate::runtime::naml_is_cancelled as *const u8);
             builder.symbol("naml_wait_all", crate::runtime::naml_wait_all as *const u8);
             builder.symbol("naml_sleep", crate::runtime::naml_sleep as *const u8);
+            builder.symbol("naml_actor_spawn", crate::runtime::naml_actor_spawn as *const u8);
+            builder.symbol("naml_actor_send", crate::runtime::naml_actor_send as *const u8);
+            builder.symbol("naml_actor_ask", crate::runtime::naml_actor_ask as *const u8);
+            builder.symbol("naml_actor_stop", crate::runtime::naml_actor_stop as *const u8);
         }
 
         // Random operations (all platforms)
@@ -544,6 +642,16 @@ impl<'a> JitCompiler<'a> {
             builder.symbol("naml_crypto_hmac_verify_sha512", crate::runtime::naml_crypto_hmac_verify_sha512 as *const u8);
             builder.symbol("naml_crypto_pbkdf2_sha256", crate::runtime::naml_crypto_pbkdf2_sha256 as *const u8);
             builder.symbol("naml_crypto_random_bytes", crate::runtime::naml_crypto_random_bytes as *const u8);
+            builder.symbol("naml_crypto_otp_hotp", crate::runtime::naml_crypto_otp_hotp as *const u8);
+            builder.symbol("naml_crypto_otp_totp_now", crate::runtime::naml_crypto_otp_totp_now as *const u8);
+            builder.symbol("naml_crypto_otp_totp_verify", crate::runtime::naml_crypto_otp_totp_verify as *const u8);
+            builder.symbol("naml_crypto_secret_from_string", crate::runtime::naml_crypto_secret_from_string as *const u8);
+            builder.symbol("naml_crypto_secret_from_bytes", crate::runtime::naml_crypto_secret_from_bytes as *const u8);
+            builder.symbol("naml_crypto_secret_expose", crate::runtime::naml_crypto_secret_expose as *const u8);
+            builder.symbol("naml_crypto_secret_len", crate::runtime::naml_crypto_secret_len as *const u8);
+            builder.symbol("naml_crypto_secret_equals", crate::runtime::naml_crypto_secret_equals as *const u8);
+            builder.symbol("naml_secret_incref", crate::runtime::naml_secret_incref as *const u8);
+            builder.symbol("naml_secret_decref", crate::runtime::naml_secret_decref as *const u8);
         }
 
         // Diagnostic builtins
@@ -554,6 +662,18 @@ impl<'a> JitCompiler<'a> {
             "naml_panic_unwrap",
             crate::runtime::naml_panic_unwrap as *const u8,
         );
+        builder.symbol(
+            "naml_panic_overflow",
+            crate::runtime::naml_panic_overflow as *const u8,
+        );
+        builder.symbol(
+            "naml_panic_arithmetic",
+            crate::runtime::naml_panic_arithmetic as *const u8,
+        );
+        builder.symbol(
+            "naml_throw_arithmetic_error",
+            crate::runtime::naml_throw_arithmetic_error as *const u8,
+        );
         builder.symbol(
             "naml_string_concat",
             crate::runtime::naml_string_concat as *const u8,
@@ -667,10 +787,26 @@ impl<'a> JitCompiler<'a> {
                 "naml_channel_receive",
                 crate::runtime::naml_channel_receive as *const u8,
             );
+            builder.symbol(
+                "naml_channel_try_send",
+                crate::runtime::naml_channel_try_send as *const u8,
+            );
+            builder.symbol(
+                "naml_channel_try_receive",
+                crate::runtime::naml_channel_try_receive as *const u8,
+            );
+            builder.symbol(
+                "naml_channel_receive_timeout",
+                crate::runtime::naml_channel_receive_timeout as *const u8,
+            );
             builder.symbol(
                 "naml_channel_close",
                 crate::runtime::naml_channel_close as *const u8,
             );
+            builder.symbol(
+                "naml_channel_select",
+                crate::runtime::naml_channel_select as *const u8,
+            );
             builder.symbol(
                 "naml_channel_len",
                 crate::runtime::naml_channel_len as *const u8,
@@ -780,6 +916,7 @@ impl<'a> JitCompiler<'a> {
 
         // Map operations
         builder.symbol("naml_map_new", crate::runtime::naml_map_new as *const u8);
+        builder.symbol("naml_map_new_keyed", crate::runtime::naml_map_new_keyed as *const u8);
         builder.symbol("naml_map_set", crate::runtime::naml_map_set as *const u8);
         builder.symbol(
             "naml_map_set_string",
@@ -919,6 +1056,10 @@ impl<'a> JitCompiler<'a> {
                 "naml_fs_append",
                 crate::runtime::naml_fs_append as *const u8,
             );
+            builder.symbol(
+                "naml_fs_write_atomic",
+                crate::runtime::naml_fs_write_atomic as *const u8,
+            );
             builder.symbol(
                 "naml_fs_write_bytes",
                 crate::runtime::naml_fs_write_bytes as *const u8,
@@ -943,6 +1084,7 @@ impl<'a> JitCompiler<'a> {
                 "naml_fs_list_dir",
                 crate::runtime::naml_fs_list_dir as *const u8,
             );
+            builder.symbol("naml_fs_glob", crate::runtime::naml_fs_glob as *const u8);
             builder.symbol("naml_fs_mkdir", crate::runtime::naml_fs_mkdir as *const u8);
             builder.symbol(
                 "naml_fs_mkdir_all",
@@ -979,6 +1121,108 @@ impl<'a> JitCompiler<'a> {
                 crate::runtime::naml_fs_modified as *const u8,
             );
             builder.symbol("naml_fs_copy", crate::runtime::naml_fs_copy as *const u8);
+            builder.symbol(
+                "naml_fs_copy_verified",
+                crate::runtime::naml_fs_copy_verified as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_verify_file",
+                crate::runtime::naml_fs_verify_file as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_options",
+                crate::runtime::naml_fs_copy_options as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_options_include",
+                crate::runtime::naml_fs_copy_options_include as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_options_exclude",
+                crate::runtime::naml_fs_copy_options_exclude as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_options_overwrite",
+                crate::runtime::naml_fs_copy_options_overwrite as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_options_on_progress",
+                crate::runtime::naml_fs_copy_options_on_progress as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_options_free",
+                crate::runtime::naml_fs_copy_options_free as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_copy_tree",
+                crate::runtime::naml_fs_copy_tree as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_move_tree",
+                crate::runtime::naml_fs_move_tree as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_options",
+                crate::runtime::naml_fs_walk_options as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_options_max_depth",
+                crate::runtime::naml_fs_walk_options_max_depth as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_options_follow_symlinks",
+                crate::runtime::naml_fs_walk_options_follow_symlinks as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_options_include",
+                crate::runtime::naml_fs_walk_options_include as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_options_exclude",
+                crate::runtime::naml_fs_walk_options_exclude as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_options_free",
+                crate::runtime::naml_fs_walk_options_free as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_dir",
+                crate::runtime::naml_fs_walk_dir as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_open",
+                crate::runtime::naml_fs_walk_open as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_next",
+                crate::runtime::naml_fs_walk_next as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_walk_close",
+                crate::runtime::naml_fs_walk_close as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_read_async",
+                crate::runtime::naml_fs_read_async as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_write_async",
+                crate::runtime::naml_fs_write_async as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_list_dir_async",
+                crate::runtime::naml_fs_list_dir_async as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_await_io",
+                crate::runtime::naml_fs_await_io as *const u8,
+            );
+            builder.symbol("naml_fs_watch", crate::runtime::naml_fs_watch as *const u8);
+            builder.symbol(
+                "naml_fs_watch_recursive",
+                crate::runtime::naml_fs_watch_recursive as *const u8,
+            );
+            builder.symbol("naml_fs_unwatch", crate::runtime::naml_fs_unwatch as *const u8);
             builder.symbol(
                 "naml_fs_rename",
                 crate::runtime::naml_fs_rename as *const u8,
@@ -993,6 +1237,18 @@ impl<'a> JitCompiler<'a> {
                 "naml_fs_mkdir_temp",
                 crate::runtime::naml_fs_mkdir_temp as *const u8,
             );
+            builder.symbol(
+                "naml_fs_with_temp_file",
+                crate::runtime::naml_fs_with_temp_file as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_with_temp_dir",
+                crate::runtime::naml_fs_with_temp_dir as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_temp_file_keep",
+                crate::runtime::naml_fs_temp_file_keep as *const u8,
+            );
             builder.symbol("naml_fs_chmod", crate::runtime::naml_fs_chmod as *const u8);
             builder.symbol(
                 "naml_fs_truncate",
@@ -1028,6 +1284,22 @@ impl<'a> JitCompiler<'a> {
                 "naml_fs_same_file",
                 crate::runtime::naml_fs_same_file as *const u8,
             );
+            builder.symbol(
+                "naml_fs_disk_free",
+                crate::runtime::naml_fs_disk_free as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_disk_total",
+                crate::runtime::naml_fs_disk_total as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_fs_type",
+                crate::runtime::naml_fs_fs_type as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_is_same_filesystem",
+                crate::runtime::naml_fs_is_same_filesystem as *const u8,
+            );
             builder.symbol(
                 "naml_fs_file_read_at",
                 crate::runtime::naml_fs_file_read_at as *const u8,
@@ -1132,6 +1404,30 @@ impl<'a> JitCompiler<'a> {
                 "naml_fs_file_flush",
                 crate::runtime::naml_fs_file_flush as *const u8,
             );
+            builder.symbol(
+                "naml_fs_file_fsync",
+                crate::runtime::naml_fs_file_fsync as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_file_fdatasync",
+                crate::runtime::naml_fs_file_fdatasync as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_lock_exclusive",
+                crate::runtime::naml_fs_lock_exclusive as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_lock_shared",
+                crate::runtime::naml_fs_lock_shared as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_try_lock",
+                crate::runtime::naml_fs_try_lock as *const u8,
+            );
+            builder.symbol(
+                "naml_fs_unlock",
+                crate::runtime::naml_fs_unlock as *const u8,
+            );
             builder.symbol(
                 "naml_fs_file_seek",
                 crate::runtime::naml_fs_file_seek as *const u8,
@@ -1219,6 +1515,22 @@ impl<'a> JitCompiler<'a> {
             "naml_path_strip_prefix",
             crate::runtime::naml_path_strip_prefix as *const u8,
         );
+        builder.symbol(
+            "naml_path_absolute_lexical",
+            crate::runtime::naml_path_absolute_lexical as *const u8,
+        );
+        builder.symbol(
+            "naml_path_relative_to",
+            crate::runtime::naml_path_relative_to as *const u8,
+        );
+        builder.symbol(
+            "naml_path_safe_join",
+            crate::runtime::naml_path_safe_join as *const u8,
+        );
+        builder.symbol(
+            "naml_path_is_within",
+            crate::runtime::naml_path_is_within as *const u8,
+        );
 
         // Environment operations (from naml-std-env)
         builder.symbol(
@@ -1254,6 +1566,49 @@ impl<'a> JitCompiler<'a> {
             crate::runtime::naml_env_error_new as *const u8,
         );
 
+        // Config loading (from naml-std-config)
+        builder.symbol("naml_config_file", crate::runtime::naml_config_file as *const u8);
+        builder.symbol("naml_config_env", crate::runtime::naml_config_env as *const u8);
+        builder.symbol("naml_config_flags", crate::runtime::naml_config_flags as *const u8);
+        builder.symbol("naml_config_values", crate::runtime::naml_config_values as *const u8);
+        builder.symbol("naml_config_free", crate::runtime::naml_config_free as *const u8);
+        builder.symbol("naml_config_load", crate::runtime::naml_config_load as *const u8);
+
+        // Feature flags (from naml-std-flags)
+        builder.symbol("naml_flags_open", crate::runtime::naml_flags_open as *const u8);
+        builder.symbol("naml_flags_set_poll_interval", crate::runtime::naml_flags_set_poll_interval as *const u8);
+        builder.symbol("naml_flags_close", crate::runtime::naml_flags_close as *const u8);
+        builder.symbol("naml_flags_enabled", crate::runtime::naml_flags_enabled as *const u8);
+        builder.symbol("naml_flags_variant", crate::runtime::naml_flags_variant as *const u8);
+
+        // State machines (from naml-std-fsm)
+        builder.symbol("naml_fsm_open", crate::runtime::naml_fsm_open as *const u8);
+        builder.symbol("naml_fsm_add_state", crate::runtime::naml_fsm_add_state as *const u8);
+        builder.symbol("naml_fsm_add_transition", crate::runtime::naml_fsm_add_transition as *const u8);
+        builder.symbol("naml_fsm_set_guard", crate::runtime::naml_fsm_set_guard as *const u8);
+        builder.symbol("naml_fsm_set_action", crate::runtime::naml_fsm_set_action as *const u8);
+        builder.symbol("naml_fsm_fire", crate::runtime::naml_fsm_fire as *const u8);
+        builder.symbol("naml_fsm_can_fire", crate::runtime::naml_fsm_can_fire as *const u8);
+        builder.symbol("naml_fsm_current", crate::runtime::naml_fsm_current as *const u8);
+        builder.symbol("naml_fsm_validate", crate::runtime::naml_fsm_validate as *const u8);
+        builder.symbol("naml_fsm_to_dot", crate::runtime::naml_fsm_to_dot as *const u8);
+        builder.symbol("naml_fsm_close", crate::runtime::naml_fsm_close as *const u8);
+
+        // Job queues (from naml-std-queue)
+        builder.symbol("naml_queue_open", crate::runtime::naml_queue_open as *const u8);
+        builder.symbol("naml_queue_close", crate::runtime::naml_queue_close as *const u8);
+        builder.symbol("naml_queue_set_capacity", crate::runtime::naml_queue_set_capacity as *const u8);
+        builder.symbol("naml_queue_enqueue", crate::runtime::naml_queue_enqueue as *const u8);
+        builder.symbol("naml_queue_try_enqueue", crate::runtime::naml_queue_try_enqueue as *const u8);
+        builder.symbol("naml_queue_dequeue", crate::runtime::naml_queue_dequeue as *const u8);
+        builder.symbol("naml_queue_dequeue_wait", crate::runtime::naml_queue_dequeue_wait as *const u8);
+        builder.symbol("naml_queue_job_payload", crate::runtime::naml_queue_job_payload as *const u8);
+        builder.symbol("naml_queue_job_attempts", crate::runtime::naml_queue_job_attempts as *const u8);
+        builder.symbol("naml_queue_ack", crate::runtime::naml_queue_ack as *const u8);
+        builder.symbol("naml_queue_nack", crate::runtime::naml_queue_nack as *const u8);
+        builder.symbol("naml_queue_size", crate::runtime::naml_queue_size as *const u8);
+        builder.symbol("naml_queue_dead_count", crate::runtime::naml_queue_dead_count as *const u8);
+
         // OS operations (from naml-std-os)
         builder.symbol(
             "naml_os_hostname",
@@ -1307,6 +1662,22 @@ impl<'a> JitCompiler<'a> {
             "naml_os_error_new",
             crate::runtime::naml_os_error_new as *const u8,
         );
+        builder.symbol(
+            "naml_os_keyring_set_password",
+            crate::runtime::naml_os_keyring_set_password as *const u8,
+        );
+        builder.symbol(
+            "naml_os_keyring_get_password",
+            crate::runtime::naml_os_keyring_get_password as *const u8,
+        );
+        builder.symbol(
+            "naml_os_keyring_delete_password",
+            crate::runtime::naml_os_keyring_delete_password as *const u8,
+        );
+        builder.symbol(
+            "naml_os_net_interfaces",
+            crate::runtime::naml_os_net_interfaces as *const u8,
+        );
 
         // Process operations (from naml-std-process)
         builder.symbol(
@@ -1386,6 +1757,19 @@ impl<'a> JitCompiler<'a> {
             crate::runtime::naml_process_sigcont as *const u8,
         );
 
+        // Worker pools (from naml-std-process)
+        builder.symbol("naml_process_pool_open", crate::runtime::naml_process_pool_open as *const u8);
+        builder.symbol("naml_process_pool_submit", crate::runtime::naml_process_pool_submit as *const u8);
+        builder.symbol("naml_process_pool_await", crate::runtime::naml_process_pool_await as *const u8);
+        builder.symbol("naml_process_pool_close", crate::runtime::naml_process_pool_close as *const u8);
+
+        // IPC channels (from naml-std-process)
+        builder.symbol("naml_ipc_create", crate::runtime::naml_ipc_create as *const u8);
+        builder.symbol("naml_ipc_connect", crate::runtime::naml_ipc_connect as *const u8);
+        builder.symbol("naml_ipc_send", crate::runtime::naml_ipc_send as *const u8);
+        builder.symbol("naml_ipc_receive", crate::runtime::naml_ipc_receive as *const u8);
+        builder.symbol("naml_ipc_close", crate::runtime::naml_ipc_close as *const u8);
+
         // Testing operations (from naml-std-testing)
         builder.symbol(
             "naml_testing_assert",
@@ -1493,6 +1877,18 @@ impl<'a> JitCompiler<'a> {
             "naml_exception_clear_ptr",
             crate::runtime::naml_exception_clear_ptr as *const u8,
         );
+        builder.symbol(
+            "naml_exception_set_cause",
+            crate::runtime::naml_exception_set_cause as *const u8,
+        );
+        builder.symbol(
+            "naml_exception_get_cause",
+            crate::runtime::naml_exception_get_cause as *const u8,
+        );
+        builder.symbol(
+            "naml_exception_stack_string",
+            crate::runtime::naml_exception_stack_string as *const u8,
+        );
 
         // Stack trace functions
         builder.symbol(
@@ -1519,6 +1915,10 @@ impl<'a> JitCompiler<'a> {
             "naml_stack_format",
             crate::runtime::naml_stack_format as *const u8,
         );
+        builder.symbol(
+            "naml_stack_frames",
+            crate::runtime::naml_stack_frames as *const u8,
+        );
 
         // String operations
         builder.symbol(
@@ -1625,6 +2025,256 @@ impl<'a> JitCompiler<'a> {
             "naml_string_chars",
             crate::runtime::naml_string_chars as *const u8,
         );
+        builder.symbol(
+            "naml_string_capitalize",
+            crate::runtime::naml_string_capitalize as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_title",
+            crate::runtime::naml_string_to_title as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_snake",
+            crate::runtime::naml_string_to_snake as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_camel",
+            crate::runtime::naml_string_to_camel as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_kebab",
+            crate::runtime::naml_string_to_kebab as *const u8,
+        );
+        builder.symbol(
+            "naml_string_parse_int_radix",
+            crate::runtime::naml_string_parse_int_radix as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_radix",
+            crate::runtime::naml_string_to_radix as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_hex",
+            crate::runtime::naml_string_to_hex as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_octal",
+            crate::runtime::naml_string_to_octal as *const u8,
+        );
+        builder.symbol(
+            "naml_string_to_binary",
+            crate::runtime::naml_string_to_binary as *const u8,
+        );
+        builder.symbol(
+            "naml_string_group_digits",
+            crate::runtime::naml_string_group_digits as *const u8,
+        );
+        builder.symbol(
+            "naml_string_compare",
+            crate::runtime::naml_string_compare as *const u8,
+        );
+        builder.symbol(
+            "naml_string_equals_ignore_case",
+            crate::runtime::naml_string_equals_ignore_case as *const u8,
+        );
+        builder.symbol(
+            "naml_string_natural_compare",
+            crate::runtime::naml_string_natural_compare as *const u8,
+        );
+        builder.symbol(
+            "naml_string_collate",
+            crate::runtime::naml_string_collate as *const u8,
+        );
+        builder.symbol(
+            "naml_string_normalize",
+            crate::runtime::naml_string_normalize as *const u8,
+        );
+        builder.symbol(
+            "naml_string_casefold",
+            crate::runtime::naml_string_casefold as *const u8,
+        );
+        builder.symbol(
+            "naml_string_graphemes",
+            crate::runtime::naml_string_graphemes as *const u8,
+        );
+        builder.symbol(
+            "naml_string_width",
+            crate::runtime::naml_string_width as *const u8,
+        );
+
+        // String builder (strings::builder)
+        builder.symbol(
+            "naml_string_builder_new",
+            crate::runtime::naml_string_builder_new as *const u8,
+        );
+        builder.symbol(
+            "naml_string_builder_append",
+            crate::runtime::naml_string_builder_append as *const u8,
+        );
+        builder.symbol(
+            "naml_string_builder_append_int",
+            crate::runtime::naml_string_builder_append_int as *const u8,
+        );
+        builder.symbol(
+            "naml_string_builder_len",
+            crate::runtime::naml_string_builder_len as *const u8,
+        );
+        builder.symbol(
+            "naml_string_builder_to_string",
+            crate::runtime::naml_string_builder_to_string as *const u8,
+        );
+        builder.symbol(
+            "naml_string_builder_incref",
+            crate::runtime::naml_string_builder_incref as *const u8,
+        );
+        builder.symbol(
+            "naml_string_builder_decref",
+            crate::runtime::naml_string_builder_decref as *const u8,
+        );
+
+        // Sorted sets (collections::sets)
+        builder.symbol(
+            "naml_set_new",
+            crate::runtime::naml_set_new as *const u8,
+        );
+        builder.symbol(
+            "naml_set_add",
+            crate::runtime::naml_set_add as *const u8,
+        );
+        builder.symbol(
+            "naml_set_contains",
+            crate::runtime::naml_set_contains as *const u8,
+        );
+        builder.symbol(
+            "naml_set_remove",
+            crate::runtime::naml_set_remove as *const u8,
+        );
+        builder.symbol(
+            "naml_set_len",
+            crate::runtime::naml_set_len as *const u8,
+        );
+        builder.symbol(
+            "naml_set_union",
+            crate::runtime::naml_set_union as *const u8,
+        );
+        builder.symbol(
+            "naml_set_intersection",
+            crate::runtime::naml_set_intersection as *const u8,
+        );
+        builder.symbol(
+            "naml_set_difference",
+            crate::runtime::naml_set_difference as *const u8,
+        );
+        builder.symbol(
+            "naml_set_to_array",
+            crate::runtime::naml_set_to_array as *const u8,
+        );
+        builder.symbol(
+            "naml_set_incref",
+            crate::runtime::naml_set_incref as *const u8,
+        );
+        builder.symbol(
+            "naml_set_decref",
+            crate::runtime::naml_set_decref as *const u8,
+        );
+
+        // Priority queues (collections::priority_queues)
+        builder.symbol(
+            "naml_priority_queue_new",
+            crate::runtime::naml_priority_queue_new as *const u8,
+        );
+        builder.symbol(
+            "naml_priority_queue_push",
+            crate::runtime::naml_priority_queue_push as *const u8,
+        );
+        builder.symbol(
+            "naml_priority_queue_pop_min",
+            crate::runtime::naml_priority_queue_pop_min as *const u8,
+        );
+        builder.symbol(
+            "naml_priority_queue_peek_min",
+            crate::runtime::naml_priority_queue_peek_min as *const u8,
+        );
+        builder.symbol(
+            "naml_priority_queue_len",
+            crate::runtime::naml_priority_queue_len as *const u8,
+        );
+        builder.symbol(
+            "naml_priority_queue_incref",
+            crate::runtime::naml_priority_queue_incref as *const u8,
+        );
+        builder.symbol(
+            "naml_priority_queue_decref",
+            crate::runtime::naml_priority_queue_decref as *const u8,
+        );
+
+        // Deques (collections::deques)
+        builder.symbol(
+            "naml_deque_new",
+            crate::runtime::naml_deque_new as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_push_front",
+            crate::runtime::naml_deque_push_front as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_push_back",
+            crate::runtime::naml_deque_push_back as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_pop_front",
+            crate::runtime::naml_deque_pop_front as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_pop_back",
+            crate::runtime::naml_deque_pop_back as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_len",
+            crate::runtime::naml_deque_len as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_incref",
+            crate::runtime::naml_deque_incref as *const u8,
+        );
+        builder.symbol(
+            "naml_deque_decref",
+            crate::runtime::naml_deque_decref as *const u8,
+        );
+
+        // Iterators (collections::iterators)
+        builder.symbol(
+            "naml_iter_from_array",
+            crate::runtime::naml_iter_from_array as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_map",
+            crate::runtime::naml_iter_map as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_filter",
+            crate::runtime::naml_iter_filter as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_take",
+            crate::runtime::naml_iter_take as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_next",
+            crate::runtime::naml_iter_next as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_collect",
+            crate::runtime::naml_iter_collect as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_incref",
+            crate::runtime::naml_iter_incref as *const u8,
+        );
+        builder.symbol(
+            "naml_iter_decref",
+            crate::runtime::naml_iter_decref as *const u8,
+        );
 
         // Type conversion operations
         builder.symbol(
@@ -1635,6 +2285,11 @@ impl<'a> JitCompiler<'a> {
             "naml_float_to_string",
             crate::runtime::naml_float_to_string as *const u8,
         );
+
+        // Format specs ({:spec} placeholders)
+        builder.symbol("naml_format_int", crate::runtime::naml_format_int as *const u8);
+        builder.symbol("naml_format_float", crate::runtime::naml_format_float as *const u8);
+        builder.symbol("naml_format_string", crate::runtime::naml_format_string as *const u8);
         builder.symbol(
             "naml_string_to_int",
             crate::runtime::naml_string_to_int as *const u8,
@@ -1719,6 +2374,46 @@ impl<'a> JitCompiler<'a> {
             "naml_encoding_base64_decode",
             crate::runtime::naml_encoding_base64_decode as *const u8,
         );
+        builder.symbol(
+            "naml_encoding_base32_encode",
+            crate::runtime::naml_encoding_base32_encode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_base32_decode",
+            crate::runtime::naml_encoding_base32_decode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_base58_encode",
+            crate::runtime::naml_encoding_base58_encode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_base58_decode",
+            crate::runtime::naml_encoding_base58_decode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_base58_check_encode",
+            crate::runtime::naml_encoding_base58_check_encode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_base58_check_decode",
+            crate::runtime::naml_encoding_base58_check_decode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_punycode_encode",
+            crate::runtime::naml_encoding_punycode_encode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_punycode_decode",
+            crate::runtime::naml_encoding_punycode_decode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_punycode_to_ascii",
+            crate::runtime::naml_encoding_punycode_to_ascii as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_punycode_to_unicode",
+            crate::runtime::naml_encoding_punycode_to_unicode as *const u8,
+        );
         builder.symbol(
             "naml_encoding_url_encode",
             crate::runtime::naml_encoding_url_encode as *const u8,
@@ -1774,6 +2469,7 @@ impl<'a> JitCompiler<'a> {
         builder.symbol("naml_encoding_binary_len", crate::runtime::naml_encoding_binary_len as *const u8);
         builder.symbol("naml_encoding_binary_capacity", crate::runtime::naml_encoding_binary_capacity as *const u8);
         builder.symbol("naml_encoding_binary_slice", crate::runtime::naml_encoding_binary_slice as *const u8);
+        builder.symbol("naml_encoding_binary_view", crate::runtime::naml_encoding_binary_view as *const u8);
         builder.symbol("naml_encoding_binary_concat", crate::runtime::naml_encoding_binary_concat as *const u8);
         builder.symbol("naml_encoding_binary_append", crate::runtime::naml_encoding_binary_append as *const u8);
         builder.symbol("naml_encoding_binary_copy_within", crate::runtime::naml_encoding_binary_copy_within as *const u8);
@@ -1888,6 +2584,168 @@ impl<'a> JitCompiler<'a> {
             crate::runtime::naml_encoding_yaml_encode as *const u8,
         );
 
+        // CSV encoding operations (from naml-std-encoding)
+        builder.symbol(
+            "naml_encoding_csv_parse",
+            crate::runtime::naml_encoding_csv_parse as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_csv_parse_with_headers",
+            crate::runtime::naml_encoding_csv_parse_with_headers as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_csv_encode",
+            crate::runtime::naml_encoding_csv_encode as *const u8,
+        );
+
+        // MessagePack encoding operations (from naml-std-encoding)
+        builder.symbol(
+            "naml_encoding_msgpack_encode",
+            crate::runtime::naml_encoding_msgpack_encode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_msgpack_decode",
+            crate::runtime::naml_encoding_msgpack_decode as *const u8,
+        );
+
+        // CBOR encoding operations (from naml-std-encoding)
+        builder.symbol(
+            "naml_encoding_cbor_encode",
+            crate::runtime::naml_encoding_cbor_encode as *const u8,
+        );
+        builder.symbol(
+            "naml_encoding_cbor_decode",
+            crate::runtime::naml_encoding_cbor_decode as *const u8,
+        );
+
+        // Compression operations (from naml-std-compress)
+        builder.symbol("naml_compress_gzip_compress", crate::runtime::naml_compress_gzip_compress as *const u8);
+        builder.symbol("naml_compress_gzip_decompress", crate::runtime::naml_compress_gzip_decompress as *const u8);
+        builder.symbol("naml_compress_zlib_compress", crate::runtime::naml_compress_zlib_compress as *const u8);
+        builder.symbol("naml_compress_zlib_decompress", crate::runtime::naml_compress_zlib_decompress as *const u8);
+        builder.symbol("naml_compress_zstd_compress", crate::runtime::naml_compress_zstd_compress as *const u8);
+        builder.symbol("naml_compress_zstd_decompress", crate::runtime::naml_compress_zstd_decompress as *const u8);
+
+        // Validation operations (from naml-std-validate)
+        builder.symbol("naml_validate_is_email", crate::runtime::naml_validate_is_email as *const u8);
+        builder.symbol("naml_validate_is_url", crate::runtime::naml_validate_is_url as *const u8);
+        builder.symbol("naml_validate_is_uuid", crate::runtime::naml_validate_is_uuid as *const u8);
+        builder.symbol("naml_validate_is_ipv4", crate::runtime::naml_validate_is_ipv4 as *const u8);
+        builder.symbol("naml_validate_is_ipv6", crate::runtime::naml_validate_is_ipv6 as *const u8);
+        builder.symbol("naml_validate_is_hostname", crate::runtime::naml_validate_is_hostname as *const u8);
+        builder.symbol("naml_validate_matches_luhn", crate::runtime::naml_validate_matches_luhn as *const u8);
+        builder.symbol("naml_validate_open", crate::runtime::naml_validate_open as *const u8);
+        builder.symbol("naml_validate_add_rule", crate::runtime::naml_validate_add_rule as *const u8);
+        builder.symbol("naml_validate_validate", crate::runtime::naml_validate_validate as *const u8);
+        builder.symbol("naml_validate_check", crate::runtime::naml_validate_check as *const u8);
+        builder.symbol("naml_validate_close", crate::runtime::naml_validate_close as *const u8);
+
+        // Money operations (from naml-std-money)
+        builder.symbol("naml_money_of", crate::runtime::naml_money_of as *const u8);
+        builder.symbol("naml_money_from_minor", crate::runtime::naml_money_from_minor as *const u8);
+        builder.symbol("naml_money_from_float", crate::runtime::naml_money_from_float as *const u8);
+        builder.symbol("naml_money_parse", crate::runtime::naml_money_parse as *const u8);
+        builder.symbol("naml_money_add", crate::runtime::naml_money_add as *const u8);
+        builder.symbol("naml_money_sub", crate::runtime::naml_money_sub as *const u8);
+        builder.symbol("naml_money_negate", crate::runtime::naml_money_negate as *const u8);
+        builder.symbol("naml_money_multiply", crate::runtime::naml_money_multiply as *const u8);
+        builder.symbol("naml_money_allocate", crate::runtime::naml_money_allocate as *const u8);
+        builder.symbol("naml_money_compare", crate::runtime::naml_money_compare as *const u8);
+        builder.symbol("naml_money_convert", crate::runtime::naml_money_convert as *const u8);
+        builder.symbol("naml_money_format", crate::runtime::naml_money_format as *const u8);
+        builder.symbol("naml_money_amount", crate::runtime::naml_money_amount as *const u8);
+        builder.symbol("naml_money_currency", crate::runtime::naml_money_currency as *const u8);
+        builder.symbol("naml_money_minor_units", crate::runtime::naml_money_minor_units as *const u8);
+        builder.symbol("naml_money_to_float", crate::runtime::naml_money_to_float as *const u8);
+        builder.symbol("naml_money_currency_digits", crate::runtime::naml_money_currency_digits as *const u8);
+
+        // Geo operations (from naml-std-geo)
+        builder.symbol("naml_geo_haversine_km", crate::runtime::naml_geo_haversine_km as *const u8);
+        builder.symbol("naml_geo_bearing", crate::runtime::naml_geo_bearing as *const u8);
+        builder.symbol("naml_geo_destination", crate::runtime::naml_geo_destination as *const u8);
+        builder.symbol("naml_geo_point_in_polygon", crate::runtime::naml_geo_point_in_polygon as *const u8);
+        builder.symbol("naml_geo_geohash_encode", crate::runtime::naml_geo_geohash_encode as *const u8);
+        builder.symbol("naml_geo_geohash_decode", crate::runtime::naml_geo_geohash_decode as *const u8);
+        builder.symbol("naml_geo_geohash_bounds", crate::runtime::naml_geo_geohash_bounds as *const u8);
+        builder.symbol("naml_geo_geohash_neighbors", crate::runtime::naml_geo_geohash_neighbors as *const u8);
+        builder.symbol("naml_geo_bbox_around", crate::runtime::naml_geo_bbox_around as *const u8);
+        builder.symbol("naml_geo_bbox_contains", crate::runtime::naml_geo_bbox_contains as *const u8);
+        builder.symbol("naml_geo_bbox_intersects", crate::runtime::naml_geo_bbox_intersects as *const u8);
+        builder.symbol("naml_geo_bounding_box", crate::runtime::naml_geo_bounding_box as *const u8);
+
+        // Regex operations (from naml-std-regex)
+        builder.symbol("naml_regex_compile", crate::runtime::naml_regex_compile as *const u8);
+        builder.symbol("naml_regex_match", crate::runtime::naml_regex_match as *const u8);
+        builder.symbol("naml_regex_find_all", crate::runtime::naml_regex_find_all as *const u8);
+        builder.symbol("naml_regex_captures", crate::runtime::naml_regex_captures as *const u8);
+        builder.symbol("naml_regex_replace_all", crate::runtime::naml_regex_replace_all as *const u8);
+        builder.symbol("naml_regex_close", crate::runtime::naml_regex_close as *const u8);
+
+        // Image operations (from naml-std-image)
+        builder.symbol("naml_image_load", crate::runtime::naml_image_load as *const u8);
+        builder.symbol("naml_image_decode", crate::runtime::naml_image_decode as *const u8);
+        builder.symbol("naml_image_create", crate::runtime::naml_image_create as *const u8);
+        builder.symbol("naml_image_width", crate::runtime::naml_image_width as *const u8);
+        builder.symbol("naml_image_height", crate::runtime::naml_image_height as *const u8);
+        builder.symbol("naml_image_get_pixel", crate::runtime::naml_image_get_pixel as *const u8);
+        builder.symbol("naml_image_set_pixel", crate::runtime::naml_image_set_pixel as *const u8);
+        builder.symbol("naml_image_resize", crate::runtime::naml_image_resize as *const u8);
+        builder.symbol("naml_image_thumbnail", crate::runtime::naml_image_thumbnail as *const u8);
+        builder.symbol("naml_image_crop", crate::runtime::naml_image_crop as *const u8);
+        builder.symbol("naml_image_rotate", crate::runtime::naml_image_rotate as *const u8);
+        builder.symbol("naml_image_flip_horizontal", crate::runtime::naml_image_flip_horizontal as *const u8);
+        builder.symbol("naml_image_flip_vertical", crate::runtime::naml_image_flip_vertical as *const u8);
+        builder.symbol("naml_image_encode_png", crate::runtime::naml_image_encode_png as *const u8);
+        builder.symbol("naml_image_encode_jpeg", crate::runtime::naml_image_encode_jpeg as *const u8);
+        builder.symbol("naml_image_save", crate::runtime::naml_image_save as *const u8);
+        builder.symbol("naml_image_close", crate::runtime::naml_image_close as *const u8);
+
+        // PDF operations (from naml-std-pdf)
+        builder.symbol("naml_pdf_extract_text", crate::runtime::naml_pdf_extract_text as *const u8);
+        builder.symbol("naml_pdf_page_count", crate::runtime::naml_pdf_page_count as *const u8);
+        builder.symbol("naml_pdf_create", crate::runtime::naml_pdf_create as *const u8);
+        builder.symbol("naml_pdf_add_page", crate::runtime::naml_pdf_add_page as *const u8);
+        builder.symbol("naml_pdf_draw_text", crate::runtime::naml_pdf_draw_text as *const u8);
+        builder.symbol("naml_pdf_to_bytes", crate::runtime::naml_pdf_to_bytes as *const u8);
+        builder.symbol("naml_pdf_save", crate::runtime::naml_pdf_save as *const u8);
+        builder.symbol("naml_pdf_close", crate::runtime::naml_pdf_close as *const u8);
+
+        // Spreadsheet operations (from naml-std-xlsx)
+        builder.symbol("naml_xlsx_open", crate::runtime::naml_xlsx_open as *const u8);
+        builder.symbol("naml_xlsx_sheet_names", crate::runtime::naml_xlsx_sheet_names as *const u8);
+        builder.symbol("naml_xlsx_read_rows", crate::runtime::naml_xlsx_read_rows as *const u8);
+        builder.symbol("naml_xlsx_create", crate::runtime::naml_xlsx_create as *const u8);
+        builder.symbol("naml_xlsx_add_sheet", crate::runtime::naml_xlsx_add_sheet as *const u8);
+        builder.symbol("naml_xlsx_write_string", crate::runtime::naml_xlsx_write_string as *const u8);
+        builder.symbol("naml_xlsx_write_number", crate::runtime::naml_xlsx_write_number as *const u8);
+        builder.symbol("naml_xlsx_write_bool", crate::runtime::naml_xlsx_write_bool as *const u8);
+        builder.symbol("naml_xlsx_write_formula", crate::runtime::naml_xlsx_write_formula as *const u8);
+        builder.symbol("naml_xlsx_write_date", crate::runtime::naml_xlsx_write_date as *const u8);
+        builder.symbol("naml_xlsx_add_format", crate::runtime::naml_xlsx_add_format as *const u8);
+        builder.symbol("naml_xlsx_set_bold", crate::runtime::naml_xlsx_set_bold as *const u8);
+        builder.symbol("naml_xlsx_set_italic", crate::runtime::naml_xlsx_set_italic as *const u8);
+        builder.symbol("naml_xlsx_set_num_format", crate::runtime::naml_xlsx_set_num_format as *const u8);
+        builder.symbol("naml_xlsx_set_font_color", crate::runtime::naml_xlsx_set_font_color as *const u8);
+        builder.symbol("naml_xlsx_set_bg_color", crate::runtime::naml_xlsx_set_bg_color as *const u8);
+        builder.symbol("naml_xlsx_set_align", crate::runtime::naml_xlsx_set_align as *const u8);
+        builder.symbol("naml_xlsx_format_cell", crate::runtime::naml_xlsx_format_cell as *const u8);
+        builder.symbol("naml_xlsx_set_column_width", crate::runtime::naml_xlsx_set_column_width as *const u8);
+        builder.symbol("naml_xlsx_to_bytes", crate::runtime::naml_xlsx_to_bytes as *const u8);
+        builder.symbol("naml_xlsx_save", crate::runtime::naml_xlsx_save as *const u8);
+        builder.symbol("naml_xlsx_close", crate::runtime::naml_xlsx_close as *const u8);
+
+        // Markdown (from naml-std-markdown)
+        builder.symbol("naml_markdown_to_html", crate::runtime::naml_markdown_to_html as *const u8);
+        builder.symbol("naml_markdown_parse", crate::runtime::naml_markdown_parse as *const u8);
+        builder.symbol("naml_markdown_node_count", crate::runtime::naml_markdown_node_count as *const u8);
+        builder.symbol("naml_markdown_node_kind", crate::runtime::naml_markdown_node_kind as *const u8);
+        builder.symbol("naml_markdown_node_depth", crate::runtime::naml_markdown_node_depth as *const u8);
+        builder.symbol("naml_markdown_node_level", crate::runtime::naml_markdown_node_level as *const u8);
+        builder.symbol("naml_markdown_node_text", crate::runtime::naml_markdown_node_text as *const u8);
+        builder.symbol("naml_markdown_node_url", crate::runtime::naml_markdown_node_url as *const u8);
+        builder.symbol("naml_markdown_node_lang", crate::runtime::naml_markdown_node_lang as *const u8);
+        builder.symbol("naml_markdown_close", crate::runtime::naml_markdown_close as *const u8);
+
         // Networking operations (from naml-std-net) - native and edge only
         if is_native_or_edge {
             // Exception constructors
@@ -1909,6 +2767,10 @@ impl<'a> JitCompiler<'a> {
                 "naml_net_tcp_server_listen",
                 crate::runtime::naml_net_tcp_server_listen as *const u8,
             );
+            builder.symbol(
+                "naml_net_tcp_server_listen_backlog",
+                crate::runtime::naml_net_tcp_server_listen_backlog as *const u8,
+            );
             builder.symbol(
                 "naml_net_tcp_server_accept",
                 crate::runtime::naml_net_tcp_server_accept as *const u8,
@@ -1951,6 +2813,22 @@ impl<'a> JitCompiler<'a> {
                 "naml_net_tcp_socket_peer_addr",
                 crate::runtime::naml_net_tcp_socket_peer_addr as *const u8,
             );
+            builder.symbol(
+                "naml_net_tcp_client_set_nodelay",
+                crate::runtime::naml_net_tcp_client_set_nodelay as *const u8,
+            );
+            builder.symbol(
+                "naml_net_tcp_client_set_keepalive",
+                crate::runtime::naml_net_tcp_client_set_keepalive as *const u8,
+            );
+            builder.symbol(
+                "naml_net_tcp_client_set_recv_buffer",
+                crate::runtime::naml_net_tcp_client_set_recv_buffer as *const u8,
+            );
+            builder.symbol(
+                "naml_net_tcp_client_set_linger",
+                crate::runtime::naml_net_tcp_client_set_linger as *const u8,
+            );
 
             // UDP
             builder.symbol(
@@ -2003,6 +2881,22 @@ impl<'a> JitCompiler<'a> {
                 "naml_net_http_client_set_timeout",
                 crate::runtime::naml_net_http_client_set_timeout as *const u8,
             );
+            builder.symbol(
+                "naml_net_http_client_set_proxy",
+                crate::runtime::naml_net_http_client_set_proxy as *const u8,
+            );
+            builder.symbol(
+                "naml_net_http_client_set_no_proxy",
+                crate::runtime::naml_net_http_client_set_no_proxy as *const u8,
+            );
+            builder.symbol(
+                "naml_net_http_client_reset_proxy",
+                crate::runtime::naml_net_http_client_reset_proxy as *const u8,
+            );
+            builder.symbol(
+                "naml_net_http_client_set_retries",
+                crate::runtime::naml_net_http_client_set_retries as *const u8,
+            );
             // HTTP Response accessors
             builder.symbol(
                 "naml_net_http_response_get_status",
@@ -2058,6 +2952,18 @@ impl<'a> JitCompiler<'a> {
                 "naml_net_http_server_text_response",
                 crate::runtime::naml_net_http_server_text_response as *const u8,
             );
+            builder.symbol(
+                "naml_net_http_server_health_router",
+                crate::runtime::naml_net_http_server_health_router as *const u8,
+            );
+            builder.symbol(
+                "naml_net_http_server_set_value",
+                crate::runtime::naml_net_http_server_set_value as *const u8,
+            );
+            builder.symbol(
+                "naml_net_http_server_get_value",
+                crate::runtime::naml_net_http_server_get_value as *const u8,
+            );
 
             // HTTP Middleware
             builder.symbol(
@@ -2142,6 +3048,24 @@ impl<'a> JitCompiler<'a> {
                 "naml_net_http_client_get_tls",
                 crate::runtime::naml_net_http_client_get_tls as *const u8,
             );
+
+            // S3 object storage
+            builder.symbol("naml_net_s3_configure", crate::runtime::naml_net_s3_configure as *const u8);
+            builder.symbol("naml_net_s3_reset_config", crate::runtime::naml_net_s3_reset_config as *const u8);
+            builder.symbol("naml_net_s3_put_object", crate::runtime::naml_net_s3_put_object as *const u8);
+            builder.symbol("naml_net_s3_put_file", crate::runtime::naml_net_s3_put_file as *const u8);
+            builder.symbol("naml_net_s3_get_object", crate::runtime::naml_net_s3_get_object as *const u8);
+            builder.symbol("naml_net_s3_list", crate::runtime::naml_net_s3_list as *const u8);
+            builder.symbol("naml_net_s3_presign_url", crate::runtime::naml_net_s3_presign_url as *const u8);
+
+            // Cloud instance metadata
+            builder.symbol("naml_cloud_metadata_provider", crate::runtime::naml_cloud_metadata_provider as *const u8);
+            builder.symbol("naml_cloud_metadata_region", crate::runtime::naml_cloud_metadata_region as *const u8);
+            builder.symbol("naml_cloud_metadata_instance_id", crate::runtime::naml_cloud_metadata_instance_id as *const u8);
+            builder.symbol("naml_cloud_metadata_credentials", crate::runtime::naml_cloud_metadata_credentials as *const u8);
+            builder.symbol("naml_cloud_metadata_get", crate::runtime::naml_cloud_metadata_get as *const u8);
+            builder.symbol("naml_cloud_metadata_set_endpoint", crate::runtime::naml_cloud_metadata_set_endpoint as *const u8);
+            builder.symbol("naml_cloud_metadata_reset", crate::runtime::naml_cloud_metadata_reset as *const u8);
         }
 
         // SQLite operations (from naml-std-db) - native and edge only
@@ -2198,4 +3122,24 @@ impl<'a> JitCompiler<'a> {
         let module = BackendModule::Object(ObjectModule::new(obj_builder));
         Self::build_compiler(interner, annotations, source_info, module, release, unsafe_mode, target)
     }
+
+    /// Override the default of checking int overflow only in debug builds
+    pub fn set_overflow_checks(&mut self, enabled: bool) {
+        self.overflow_checks = enabled;
+    }
+
+    /// Fail compilation once naml functions need more than `bytes` of
+    /// machine code in total
+    pub fn set_max_code_size(&mut self, bytes: usize) {
+        self.max_code_size = Some(bytes);
+    }
+
+    /// Start recording how long codegen takes for each function
+    pub fn enable_function_times(&mut self) {
+        self.function_times.get_or_insert_with(Vec::new);
+    }
+
+    pub fn take_function_times(&mut self) -> Vec<FunctionTime> {
+        self.function_times.take().unwrap_or_default()
+    }
 }
@@ -0,0 +1,137 @@
+//!
+//! Interface Default Methods and Static Functions
+//!
+//! Interfaces have no runtime representation: methods are dispatched
+//! statically by the receiver's type name. A default method body is
+//! therefore compiled once for every struct that inherits it, under the
+//! usual `{Struct}_{method}` name, with the interface name substituted by
+//! the struct so calls on `self` resolve to that struct's methods.
+//!
+//! Static interface functions are plain functions named
+//! `{Interface}_{function}` and are called as `Interface::function(...)`.
+//!
+
+use std::collections::HashMap;
+
+use crate::ast::{FunctionItem, Ident, InterfaceItem, InterfaceMethod, Item, NamlType, Receiver};
+use crate::codegen::CodegenError;
+use crate::codegen::cranelift::JitCompiler;
+
+impl<'a> JitCompiler<'a> {
+    /// Declare static interface functions and the inherited default methods
+    pub(crate) fn declare_interface_functions(
+        &mut self,
+        items: &[Item<'_>],
+    ) -> Result<(), CodegenError> {
+        let interner = self.interner;
+        for interface in interfaces(items) {
+            for method in interface.methods.iter().filter(|m| m.is_static && m.body.is_some()) {
+                let name = format!(
+                    "{}_{}",
+                    interner.resolve(&interface.name.symbol),
+                    interner.resolve(&method.name.symbol)
+                );
+                self.declare_function_named(&static_function(method), &name)?;
+            }
+        }
+        for (method, _) in self.inherited_defaults(items) {
+            self.declare_method(&method)?;
+        }
+        Ok(())
+    }
+
+    /// Compile the functions declared by `declare_interface_functions`
+    pub(crate) fn compile_interface_functions(
+        &mut self,
+        items: &[Item<'_>],
+    ) -> Result<(), CodegenError> {
+        let interner = self.interner;
+        for interface in interfaces(items) {
+            for method in interface.methods.iter().filter(|m| m.is_static && m.body.is_some()) {
+                let name = format!(
+                    "{}_{}",
+                    interner.resolve(&interface.name.symbol),
+                    interner.resolve(&method.name.symbol)
+                );
+                self.compile_function_named(&static_function(method), &name)?;
+            }
+        }
+        for (method, substitutions) in self.inherited_defaults(items) {
+            self.compile_method_with(&method, substitutions)?;
+        }
+        Ok(())
+    }
+
+    /// Each default method a struct inherits, as a method of that struct,
+    /// with the substitution mapping the interface to the struct
+    fn inherited_defaults<'ast>(
+        &self,
+        items: &[Item<'ast>],
+    ) -> Vec<(FunctionItem<'ast>, HashMap<String, String>)> {
+        // A body that never mentions `self` can take any name for the receiver
+        let self_symbol = self.interner.get("self");
+        let mut methods = Vec::new();
+        for info in self.annotations.get_default_methods() {
+            let Some(interface) = interfaces(items).find(|i| i.name.symbol == info.interface_name)
+            else {
+                continue;
+            };
+            let Some(method) = interface
+                .methods
+                .iter()
+                .find(|m| m.name.symbol == info.method_name && m.body.is_some())
+            else {
+                continue;
+            };
+
+            let struct_ident = Ident {
+                symbol: info.struct_name,
+                span: method.span,
+            };
+            let function = FunctionItem {
+                name: method.name.clone(),
+                receiver: Some(Receiver {
+                    name: Ident {
+                        symbol: self_symbol.unwrap_or(method.name.symbol),
+                        span: method.span,
+                    },
+                    ty: NamlType::Named(struct_ident),
+                    span: method.span,
+                }),
+                ..static_function(method)
+            };
+
+            let substitutions = HashMap::from([(
+                self.interner.resolve(&info.interface_name).to_string(),
+                self.interner.resolve(&info.struct_name).to_string(),
+            )]);
+            methods.push((function, substitutions));
+        }
+        methods
+    }
+}
+
+fn interfaces<'i, 'ast>(items: &'i [Item<'ast>]) -> impl Iterator<Item = &'i InterfaceItem<'ast>> {
+    items.iter().filter_map(|item| match item {
+        Item::Interface(i) => Some(i),
+        _ => None,
+    })
+}
+
+/// An interface function with a body as a free-standing function
+fn static_function<'ast>(method: &InterfaceMethod<'ast>) -> FunctionItem<'ast> {
+    FunctionItem {
+        name: method.name.clone(),
+        receiver: None,
+        generics: method.generics.clone(),
+        params: method.params.clone(),
+        return_ty: method.return_ty.clone(),
+        throws: method.throws.clone(),
+        is_public: true,
+        is_const: false,
+        body: method.body.clone(),
+        platforms: None,
+        attributes: Vec::new(),
+        span: method.span,
+    }
+}
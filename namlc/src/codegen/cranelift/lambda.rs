@@ -196,6 +196,7 @@ pub fn compile_lambda_fold(
     arr: Value,
     initial: Value,
     closure: Value,
+    runtime_fn: &str,
 ) -> Result<Value, CodegenError> {
     let func_ptr = builder
         .ins()
@@ -204,7 +205,7 @@ pub fn compile_lambda_fold(
         .ins()
         .load(cranelift::prelude::types::I64, MemFlags::new(), closure, 8);
 
-    let func_ref = rt_func_ref(ctx, builder, "naml_array_fold")?;
+    let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
     let call = builder
         .ins()
         .call(func_ref, &[arr, initial, func_ptr, data_ptr]);
@@ -229,6 +230,30 @@ pub fn compile_lambda_sort_by(
     Ok(builder.inst_results(call)[0])
 }
 
+pub fn compile_lambda_group_by(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    arr: Value,
+    closure: Value,
+    kind: i64,
+    key_kind: i64,
+) -> Result<Value, CodegenError> {
+    let func_ptr = builder
+        .ins()
+        .load(cranelift::prelude::types::I64, MemFlags::new(), closure, 0);
+    let data_ptr = builder
+        .ins()
+        .load(cranelift::prelude::types::I64, MemFlags::new(), closure, 8);
+    let kind = builder.ins().iconst(cranelift::prelude::types::I64, kind);
+    let key_kind = builder.ins().iconst(cranelift::prelude::types::I64, key_kind);
+
+    let func_ref = rt_func_ref(ctx, builder, "naml_array_group_by")?;
+    let call = builder
+        .ins()
+        .call(func_ref, &[arr, func_ptr, data_ptr, kind, key_kind]);
+    Ok(builder.inst_results(call)[0])
+}
+
 pub fn compile_lambda_find_last(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
@@ -0,0 +1,279 @@
+//!
+//! Loop-Invariant Code Motion
+//!
+//! Evaluates loads that cannot change while a loop runs once, before the
+//! loop, instead of on every iteration. Cranelift already hoists pure
+//! arithmetic out of loops and turns multiplications by powers of two into
+//! shifts, but it leaves loads and runtime calls where they are. Applies in
+//! release mode only.
+//!
+//! Hoisted expressions, whose operand must be a local variable the loop
+//! never rebinds:
+//! - `count(arr)` of an array
+//! - `len(s)` of a string
+//! - `s.field` of a struct
+//!
+//! The whole loop is left alone if it could change one of them: a call to
+//! anything other than a builtin that only reads, a method call, a spawn or
+//! a lambda. A field is not hoisted if the loop assigns a field of the same
+//! name on any value, since another variable may alias the struct.
+//!
+//! Operands are only read before the loop if they are non-null, because a
+//! variable declared without a value holds null until it is assigned.
+//!
+
+use std::collections::HashSet;
+
+use cranelift::prelude::*;
+use lasso::Spur;
+
+use crate::ast::visitor::{walk_expr, walk_pattern, walk_stmt, Visitor};
+use crate::ast::{Expression, Ident, Pattern, Statement, TemplateStringPart};
+use crate::codegen::cranelift::bounds::resolve_builtin;
+use crate::codegen::cranelift::builtins::BuiltinStrategy;
+use crate::codegen::cranelift::expr::compile_expression;
+use crate::codegen::cranelift::CompileContext;
+use crate::codegen::CodegenError;
+use crate::source::{Span, Spanned};
+use crate::typechecker::Type;
+
+/// Builtins that read arrays without changing their length
+const READ_ONLY_ARRAY_BUILTINS: &[&str] = &[
+    "count", "get", "first", "last", "sum", "min", "max", "contains", "index_of",
+    "last_index_of", "capacity",
+];
+
+/// Evaluate the invariant expressions of a loop before it starts, so that
+/// `compile_expression` reuses them. `bound` holds the names the loop binds
+/// on each iteration. Returns the spans to pass to `end_hoisting` once the
+/// loop has been compiled.
+pub fn hoist_loop_invariants(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    condition: Option<&Expression<'_>>,
+    body: &[Statement<'_>],
+    bound: &[Spur],
+) -> Result<Vec<Span>, CodegenError> {
+    if !ctx.release_mode {
+        return Ok(Vec::new());
+    }
+
+    let mut scan = LoopScan {
+        ctx,
+        rebound: bound.iter().copied().collect(),
+        assigned_fields: HashSet::new(),
+        candidates: Vec::new(),
+        ok: true,
+        in_pattern: false,
+    };
+    if let Some(condition) = condition {
+        scan.visit_expr(condition);
+    }
+    for stmt in body {
+        scan.visit_stmt(stmt);
+    }
+    if !scan.ok {
+        return Ok(Vec::new());
+    }
+    let LoopScan { rebound, assigned_fields, candidates, .. } = scan;
+
+    let mut hoisted = Vec::new();
+    for candidate in candidates {
+        if rebound.contains(&candidate.operand)
+            || candidate.field.is_some_and(|field| assigned_fields.contains(&field))
+        {
+            continue;
+        }
+        // SAFETY: the expression belongs to the loop being compiled
+        let expr: &Expression<'_> = unsafe { &*candidate.expr };
+        let span = expr.span();
+        if ctx.hoisted_exprs.contains_key(&span) {
+            continue;
+        }
+        let Some(&operand) = ctx.variables.get(ctx.interner.resolve(&candidate.operand)) else {
+            continue;
+        };
+
+        let eval_block = builder.create_block();
+        let null_block = builder.create_block();
+        let join_block = builder.create_block();
+        let base = builder.use_var(operand);
+        builder.ins().brif(base, eval_block, &[], null_block, &[]);
+
+        builder.switch_to_block(eval_block);
+        builder.seal_block(eval_block);
+        let value = compile_expression(ctx, builder, expr)?;
+        let ty = builder.func.dfg.value_type(value);
+        builder.append_block_param(join_block, ty);
+        builder.ins().jump(join_block, &[value]);
+
+        builder.switch_to_block(null_block);
+        builder.seal_block(null_block);
+        let zero = if ty.is_float() {
+            builder.ins().f64const(0.0)
+        } else {
+            builder.ins().iconst(ty, 0)
+        };
+        builder.ins().jump(join_block, &[zero]);
+
+        builder.switch_to_block(join_block);
+        builder.seal_block(join_block);
+        let var = Variable::new(ctx.var_counter);
+        ctx.var_counter += 1;
+        builder.declare_var(var, ty);
+        builder.def_var(var, builder.block_params(join_block)[0]);
+        ctx.hoisted_exprs.insert(span, var);
+        hoisted.push(span);
+    }
+    Ok(hoisted)
+}
+
+/// Forget the expressions hoisted for a loop that has been compiled
+pub fn end_hoisting(ctx: &mut CompileContext<'_>, hoisted: Vec<Span>) {
+    for span in hoisted {
+        ctx.hoisted_exprs.remove(&span);
+    }
+}
+
+/// An expression that is invariant unless `operand` is rebound or `field`
+/// is assigned somewhere in the loop
+struct Candidate {
+    expr: *const Expression<'static>,
+    operand: Spur,
+    field: Option<Spur>,
+}
+
+/// Walks a loop collecting candidates and whatever could invalidate them
+struct LoopScan<'c, 'a> {
+    ctx: &'c CompileContext<'a>,
+    rebound: HashSet<Spur>,
+    assigned_fields: HashSet<Spur>,
+    candidates: Vec<Candidate>,
+    ok: bool,
+    in_pattern: bool,
+}
+
+impl LoopScan<'_, '_> {
+    /// A local variable of the given type, named by `expr`
+    fn local_operand(&self, expr: &Expression<'_>, is_type: fn(&Type) -> bool) -> Option<Spur> {
+        let Expression::Identifier(ident) = expr else {
+            return None;
+        };
+        let is_local = self.ctx.variables.contains_key(self.ctx.interner.resolve(&ident.ident.symbol));
+        let typed = self.ctx.annotations.get_type(ident.span).is_some_and(is_type);
+        (is_local && typed).then_some(ident.ident.symbol)
+    }
+
+    fn push_candidate(&mut self, expr: &Expression<'_>, operand: Spur, field: Option<Spur>) {
+        self.candidates.push(Candidate {
+            expr: std::ptr::from_ref(expr).cast::<Expression<'static>>(),
+            operand,
+            field,
+        });
+    }
+}
+
+fn is_read_only_builtin(name: &str) -> bool {
+    if let Some(func) = name.strip_prefix("collections::arrays::") {
+        return READ_ONLY_ARRAY_BUILTINS.contains(&func);
+    }
+    name.starts_with("strings::") || matches!(name, "print" | "println" | "fmt" | "warn" | "error")
+}
+
+impl<'ast> Visitor<'ast> for LoopScan<'_, '_> {
+    fn visit_stmt(&mut self, stmt: &Statement<'ast>) {
+        if !self.ok {
+            return;
+        }
+        match stmt {
+            Statement::Var(var) => {
+                self.rebound.insert(var.name.symbol);
+            }
+            Statement::Const(c) => {
+                self.rebound.insert(c.name.symbol);
+            }
+            Statement::Assign(assign) => match &assign.target {
+                Expression::Identifier(target) => {
+                    self.rebound.insert(target.ident.symbol);
+                }
+                Expression::Field(field) => {
+                    self.assigned_fields.insert(field.field.symbol);
+                }
+                _ => {}
+            },
+            Statement::For(f) => {
+                self.rebound.insert(f.value.symbol);
+                if let Some(ref idx) = f.index {
+                    self.rebound.insert(idx.symbol);
+                }
+            }
+            Statement::Locked(l) => {
+                self.rebound.insert(l.binding.symbol);
+            }
+            _ => {}
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        if !self.ok {
+            return;
+        }
+        match expr {
+            Expression::Call(call) => {
+                let Some(builtin) = resolve_builtin(self.ctx, call.callee, call.span)
+                    .filter(|builtin| is_read_only_builtin(builtin.name))
+                else {
+                    self.ok = false;
+                    return;
+                };
+                if call.args.len() == 1 {
+                    let operand = if matches!(builtin.strategy, BuiltinStrategy::ArrayLength) {
+                        self.local_operand(&call.args[0], |ty| matches!(ty, Type::Array(_)))
+                    } else if builtin.name == "strings::len" {
+                        self.local_operand(&call.args[0], |ty| matches!(ty, Type::String))
+                    } else {
+                        None
+                    };
+                    if let Some(operand) = operand {
+                        self.push_candidate(expr, operand, None);
+                    }
+                }
+            }
+            Expression::Field(field) => {
+                if let Some(operand) = self.local_operand(field.base, |ty| matches!(ty, Type::Struct(_))) {
+                    self.push_candidate(expr, operand, Some(field.field.symbol));
+                }
+            }
+            Expression::MethodCall(_) | Expression::Spawn(_) | Expression::Lambda(_) => {
+                self.ok = false;
+                return;
+            }
+            // Interpolations are parsed during codegen and may contain calls
+            Expression::TemplateString(template)
+                if template.parts.iter().any(|part| matches!(part, TemplateStringPart::Expression(_))) =>
+            {
+                self.ok = false;
+                return;
+            }
+            Expression::Catch(catch) => {
+                self.rebound.insert(catch.error_binding.symbol);
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern<'ast>) {
+        let was_in_pattern = self.in_pattern;
+        self.in_pattern = true;
+        walk_pattern(self, pattern);
+        self.in_pattern = was_in_pattern;
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if self.in_pattern {
+            self.rebound.insert(ident.symbol);
+        }
+    }
+}
@@ -2,8 +2,13 @@ use cranelift::prelude::*;
 use cranelift_module::{DataDescription, Module};
 
 use crate::ast::Literal;
-use crate::codegen::cranelift::CompileContext;
+use crate::codegen::cranelift::array::{call_array_new, call_array_push};
+use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::strings::call_string_from_cstr;
+use crate::codegen::cranelift::{CompileContext, STRING_DATA_OFFSET, STRING_LEN_OFFSET};
 use crate::codegen::CodegenError;
+use crate::runtime::{HeapTag, NamlString, IMMORTAL_REFCOUNT};
+use crate::typechecker::ConstValue;
 
 pub fn compile_literal(
     ctx: &mut CompileContext<'_>,
@@ -46,30 +51,130 @@ pub fn compile_literal(
     }
 }
 
+/// Emit a string literal and return a pointer to its NUL-terminated bytes.
+///
+/// Each distinct literal is interned once per module as a static `NamlString`
+/// with an immortal refcount, so converting it to a naml string is free.
 pub fn compile_string_literal(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
     s: &str,
 ) -> Result<Value, CodegenError> {
-    let mut bytes = s.as_bytes().to_vec();
-    bytes.push(0);
+    let data_id = match ctx.interned_strings.get(s) {
+        Some(&data_id) => data_id,
+        None => {
+            let data_id = ctx
+                .module
+                .declare_anonymous_data(true, false)
+                .map_err(|e| CodegenError::JitCompile(format!("Failed to declare string data: {}", e)))?;
 
+            let mut data_description = DataDescription::new();
+            data_description.define(string_object_bytes(s).into_boxed_slice());
+            data_description.set_align(8);
+
+            ctx.module
+                .define_data(data_id, &data_description)
+                .map_err(|e| CodegenError::JitCompile(format!("Failed to define string data: {}", e)))?;
+
+            ctx.interned_strings.insert(s.to_string(), data_id);
+            data_id
+        }
+    };
+
+    let global_value = ctx.module.declare_data_in_func(data_id, builder.func);
+    let object = builder
+        .ins()
+        .global_value(ctx.module.target_config().pointer_type(), global_value);
+    let ptr = builder.ins().iadd_imm(object, STRING_DATA_OFFSET as i64);
+    ctx.string_objects.insert(ptr, object);
+
+    Ok(ptr)
+}
+
+/// Emit a value computed at compile time by a `const fn` call.
+///
+/// Strings become interned literals and arrays of scalars are copied from a
+/// read-only table in one runtime call; other arrays are built element-wise.
+pub fn compile_const_value(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    value: &ConstValue,
+) -> Result<Value, CodegenError> {
+    match value {
+        ConstValue::Int(n) => Ok(builder.ins().iconst(types::I64, *n)),
+        ConstValue::Float(f) => Ok(builder.ins().f64const(*f)),
+        ConstValue::Bool(b) => Ok(builder.ins().iconst(types::I8, *b as i64)),
+        ConstValue::String(s) => {
+            let ptr = compile_string_literal(ctx, builder, s)?;
+            call_string_from_cstr(ctx, builder, ptr)
+        }
+        ConstValue::Array(elements) => {
+            if let Some(words) = scalar_words(elements) {
+                return compile_word_table(ctx, builder, &words);
+            }
+            let capacity = builder.ins().iconst(types::I64, elements.len() as i64);
+            let arr = call_array_new(ctx, builder, capacity)?;
+            for element in elements {
+                let element = compile_const_value(ctx, builder, element)?;
+                call_array_push(ctx, builder, arr, element)?;
+            }
+            Ok(arr)
+        }
+    }
+}
+
+/// The i64 words of an array of scalars, as stored in a naml array
+fn scalar_words(elements: &[ConstValue]) -> Option<Vec<i64>> {
+    elements
+        .iter()
+        .map(|element| match element {
+            ConstValue::Int(n) => Some(*n),
+            ConstValue::Float(f) => Some(f.to_bits() as i64),
+            ConstValue::Bool(b) => Some(*b as i64),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Emit `words` as read-only data and copy them into a new array
+fn compile_word_table(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    words: &[i64],
+) -> Result<Value, CodegenError> {
     let data_id = ctx
         .module
         .declare_anonymous_data(false, false)
-        .map_err(|e| CodegenError::JitCompile(format!("Failed to declare string data: {}", e)))?;
+        .map_err(|e| CodegenError::JitCompile(format!("Failed to declare constant table: {}", e)))?;
 
+    let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
     let mut data_description = DataDescription::new();
     data_description.define(bytes.into_boxed_slice());
-
+    data_description.set_align(8);
     ctx.module
         .define_data(data_id, &data_description)
-        .map_err(|e| CodegenError::JitCompile(format!("Failed to define string data: {}", e)))?;
+        .map_err(|e| CodegenError::JitCompile(format!("Failed to define constant table: {}", e)))?;
 
     let global_value = ctx.module.declare_data_in_func(data_id, builder.func);
-    let ptr = builder
+    let table = builder
         .ins()
         .global_value(ctx.module.target_config().pointer_type(), global_value);
+    let len = builder.ins().iconst(types::I64, words.len() as i64);
+    let func_ref = rt_func_ref(ctx, builder, "naml_array_from")?;
+    let call = builder.ins().call(func_ref, &[table, len]);
+    Ok(builder.inst_results(call)[0])
+}
 
-    Ok(ptr)
-}
\ No newline at end of file
+/// Lay out a `NamlString` followed by a NUL terminator, padded to the full
+/// struct when the bytes fit inline
+fn string_object_bytes(s: &str) -> Vec<u8> {
+    let size = std::mem::size_of::<NamlString>().max(STRING_DATA_OFFSET + s.len() + 1);
+    let mut bytes = Vec::with_capacity(size);
+    bytes.extend_from_slice(&(IMMORTAL_REFCOUNT as u64).to_le_bytes());
+    bytes.push(HeapTag::String as u8);
+    bytes.resize(STRING_LEN_OFFSET, 0);
+    bytes.extend_from_slice(&(s.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(s.as_bytes());
+    bytes.resize(size, 0);
+    bytes
+}
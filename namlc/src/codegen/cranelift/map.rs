@@ -1,40 +1,143 @@
-use crate::ast::{Expression, Literal, LiteralExpr};
+use crate::ast::{Expression, IndexExpr, Literal, LiteralExpr, NamlType};
+use crate::codegen::cranelift::exceptions::emit_inline_throw;
 use crate::codegen::cranelift::expr::compile_expression;
 use crate::codegen::cranelift::{CompileContext, HeapType};
 use crate::codegen::CodegenError;
+use crate::source::Spanned;
+use crate::typechecker::Type;
 use cranelift::prelude::*;
 use crate::codegen::cranelift::misc::ensure_i64;
 use crate::codegen::cranelift::runtime::rt_func_ref;
 use crate::codegen::cranelift::strings::call_string_from_cstr;
+use crate::runtime::{ARITHMETIC_NAN_MAP_KEY, MAP_KEY_BOOL, MAP_KEY_FLOAT, MAP_KEY_INT, MAP_KEY_STRING};
 
-pub fn compile_map_literal(
+/// Runtime key kind (`MAP_KEY_*`) for a map whose keys have type `key`
+pub fn key_kind(key: &Type) -> u8 {
+    match key.resolve() {
+        Type::Int | Type::Uint => MAP_KEY_INT,
+        Type::Float => MAP_KEY_FLOAT,
+        Type::Bool => MAP_KEY_BOOL,
+        _ => MAP_KEY_STRING,
+    }
+}
+
+/// Runtime key kind for a map type written in the source
+pub fn ast_map_key_kind(ty: &NamlType) -> u8 {
+    match ty {
+        NamlType::Map(key, _) => match key.as_ref() {
+            NamlType::Int | NamlType::Uint => MAP_KEY_INT,
+            NamlType::Float => MAP_KEY_FLOAT,
+            NamlType::Bool => MAP_KEY_BOOL,
+            _ => MAP_KEY_STRING,
+        },
+        _ => MAP_KEY_STRING,
+    }
+}
+
+/// Whether `map` is a map with float keys
+pub fn has_float_keys(ctx: &CompileContext<'_>, map: &Expression<'_>) -> bool {
+    matches!(
+        ctx.annotations.get_type(map.span()).map(Type::resolve),
+        Some(Type::Map(key, _)) if key_kind(&key) == MAP_KEY_FLOAT
+    )
+}
+
+/// Run `store` to add an entry under `key`. Where the failure can be caught
+/// (see `catch_arithmetic`), a NaN float key throws ArithmeticError and
+/// leaves for the handler without storing; elsewhere the runtime panics on
+/// storing it.
+pub fn store_map_entry(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
-    entries: &[crate::ast::MapEntry<'_>],
-) -> Result<Value, CodegenError> {
-    let func_ref = rt_func_ref(ctx, builder, "naml_map_new")?;
+    float_key: bool,
+    key: Value,
+    store: impl FnOnce(&mut CompileContext<'_>, &mut FunctionBuilder<'_>) -> Result<(), CodegenError>,
+) -> Result<(), CodegenError> {
+    if !float_key || !ctx.catch_arithmetic {
+        return store(ctx, builder);
+    }
+    let key = builder.ins().bitcast(types::F64, MemFlags::new(), key);
+    let is_nan = builder.ins().fcmp(FloatCC::Unordered, key, key);
+
+    let nan_block = builder.create_block();
+    let store_block = builder.create_block();
+    builder.set_cold_block(nan_block);
+    builder.ins().brif(is_nan, nan_block, &[], store_block, &[]);
+
+    builder.switch_to_block(nan_block);
+    builder.seal_block(nan_block);
+    let throw = rt_func_ref(ctx, builder, "naml_throw_arithmetic_error")?;
+    let kind = builder.ins().iconst(types::I64, ARITHMETIC_NAN_MAP_KEY);
+    builder.ins().call(throw, &[kind]);
+    emit_inline_throw(ctx, builder)?;
+
+    builder.switch_to_block(store_block);
+    builder.seal_block(store_block);
+    store(ctx, builder)
+}
 
-    // Create map with capacity 16
+/// Whether `index_expr` indexes a map rather than an array
+pub fn is_map_index(ctx: &CompileContext<'_>, index_expr: &IndexExpr<'_>) -> bool {
+    match ctx.annotations.get_type(index_expr.base.span()).map(Type::resolve) {
+        Some(Type::Map(..)) => true,
+        Some(_) => false,
+        None => matches!(
+            index_expr.index,
+            Expression::Literal(LiteralExpr { value: Literal::String(_), .. })
+        ),
+    }
+}
+
+/// Create an empty map whose keys are of the given kind
+pub fn call_map_new(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    key_kind: u8,
+) -> Result<Value, CodegenError> {
+    let func_ref = rt_func_ref(ctx, builder, "naml_map_new_keyed")?;
     let capacity = builder.ins().iconst(cranelift::prelude::types::I64, 16);
-    let call = builder.ins().call(func_ref, &[capacity]);
-    let map_ptr = builder.inst_results(call)[0];
+    let kind = builder.ins().iconst(cranelift::prelude::types::I64, key_kind as i64);
+    let call = builder.ins().call(func_ref, &[capacity, kind]);
+    Ok(builder.inst_results(call)[0])
+}
+
+/// Compile a map key to the i64 word the runtime hashes
+pub fn compile_map_key(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    key: &Expression<'_>,
+) -> Result<Value, CodegenError> {
+    let value = compile_expression(ctx, builder, key)?;
+    // String literals compile to C strings; maps hold NamlString keys
+    let value = if let Expression::Literal(LiteralExpr {
+        value: Literal::String(_),
+        ..
+    }) = key
+    {
+        call_string_from_cstr(ctx, builder, value)?
+    } else {
+        value
+    };
+    Ok(ensure_i64(builder, value))
+}
+
+pub fn compile_map_literal(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    map_expr: &crate::ast::MapExpr<'_>,
+) -> Result<Value, CodegenError> {
+    let kind = match ctx.annotations.get_type(map_expr.span).map(Type::resolve) {
+        Some(Type::Map(key, _)) => key_kind(&key),
+        _ => MAP_KEY_STRING,
+    };
+    let map_ptr = call_map_new(ctx, builder, kind)?;
 
     // For each entry, call naml_map_set
-    if !entries.is_empty() {
+    if !map_expr.entries.is_empty() {
         let set_func_ref = rt_func_ref(ctx, builder, "naml_map_set")?;
 
-        for entry in entries {
-            // Convert string literals to NamlString pointers for map keys
-            let key = if let Expression::Literal(LiteralExpr {
-                value: Literal::String(_),
-                ..
-            }) = &entry.key
-            {
-                let cstr_ptr = compile_expression(ctx, builder, &entry.key)?;
-                call_string_from_cstr(ctx, builder, cstr_ptr)?
-            } else {
-                compile_expression(ctx, builder, &entry.key)?
-            };
+        for entry in map_expr.entries.iter() {
+            let key = compile_map_key(ctx, builder, &entry.key)?;
             let value = if let Expression::Literal(LiteralExpr {
                 value: Literal::String(_),
                 ..
@@ -45,9 +148,11 @@ pub fn compile_map_literal(
             } else {
                 compile_expression(ctx, builder, &entry.value)?
             };
-            let key = ensure_i64(builder, key);
             let value = ensure_i64(builder, value);
-            builder.ins().call(set_func_ref, &[map_ptr, key, value]);
+            store_map_entry(ctx, builder, kind == MAP_KEY_FLOAT, key, |_, builder| {
+                builder.ins().call(set_func_ref, &[map_ptr, key, value]);
+                Ok(())
+            })?;
         }
     }
 
@@ -80,6 +185,12 @@ pub fn call_map_set_typed(
         Some(HeapType::Map(_)) => "naml_map_set_map",
         Some(HeapType::Struct(_)) => "naml_map_set_struct",
         Some(HeapType::OptionOf(_)) => "naml_map_set",
+        Some(HeapType::Secret) => "naml_map_set",
+        Some(HeapType::StringBuilder) => "naml_map_set",
+        Some(HeapType::Set) => "naml_map_set",
+        Some(HeapType::PriorityQueue) => "naml_map_set",
+        Some(HeapType::Deque) => "naml_map_set",
+        Some(HeapType::Iterator) => "naml_map_set",
         None => "naml_map_set",
     };
 
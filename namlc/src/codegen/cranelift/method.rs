@@ -1,13 +1,16 @@
-use crate::ast::Expression;
+use crate::ast::{Expression, Literal, LiteralExpr};
+use crate::codegen::cranelift::abi::{call_result_value, emit_enum_land, enum_value_size};
 use crate::codegen::cranelift::expr::compile_expression;
-use crate::codegen::cranelift::map::{call_map_contains, call_map_set};
+use crate::codegen::cranelift::map::{call_map_contains, call_map_set, compile_map_key};
+use crate::codegen::cranelift::misc::{coerce_call_args, from_generic_word};
 use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::strings::call_string_from_cstr;
 use crate::codegen::cranelift::CompileContext;
 use crate::codegen::CodegenError;
-use crate::source::Spanned;
+use crate::source::{Span, Spanned};
 use crate::typechecker::Type;
 use cranelift::prelude::*;
-use cranelift_module::Module;
+use cranelift_module::FuncId;
 
 pub fn compile_method_call(
     ctx: &mut CompileContext<'_>,
@@ -15,6 +18,7 @@ pub fn compile_method_call(
     receiver: &Expression<'_>,
     method_name: &str,
     args: &[Expression<'_>],
+    span: Span,
 ) -> Result<Value, CodegenError> {
     let recv = compile_expression(ctx, builder, receiver)?;
 
@@ -24,22 +28,7 @@ pub fn compile_method_call(
         let type_name = ctx.interner.resolve(&s.name).to_string();
         let full_name = format!("{}_{}", type_name, method_name);
         if let Some(&func_id) = ctx.functions.get(&full_name) {
-            let ptr_type = ctx.module.target_config().pointer_type();
-            let func_ref = ctx.module.declare_func_in_func(func_id, builder.func);
-
-            // Compile arguments
-            let mut call_args = vec![recv];
-            for arg in args {
-                call_args.push(compile_expression(ctx, builder, arg)?);
-            }
-
-            let call = builder.ins().call(func_ref, &call_args);
-            let results = builder.inst_results(call);
-            if results.is_empty() {
-                return Ok(builder.ins().iconst(ptr_type, 0));
-            } else {
-                return Ok(results[0]);
-            }
+            return call_user_method(ctx, builder, func_id, recv, args, span);
         }
     }
 
@@ -51,7 +40,7 @@ pub fn compile_method_call(
                     "contains requires a key argument".to_string(),
                 ));
             }
-            let key = compile_expression(ctx, builder, &args[0])?;
+            let key = compile_map_key(ctx, builder, &args[0])?;
             call_map_contains(ctx, builder, recv, key)
         }
         "set" => {
@@ -60,7 +49,7 @@ pub fn compile_method_call(
                     "set requires key and value arguments".to_string(),
                 ));
             }
-            let key = compile_expression(ctx, builder, &args[0])?;
+            let key = compile_map_key(ctx, builder, &args[0])?;
             let value = compile_expression(ctx, builder, &args[1])?;
             call_map_set(ctx, builder, recv, key, value)?;
             Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
@@ -69,18 +58,42 @@ pub fn compile_method_call(
         "stack_string" => {
             let receiver_type = ctx.annotations.get_type(receiver.span());
             if matches!(receiver_type, Some(Type::Exception(_))) {
-                // Load stack array from offset 8
+                // Format the stack at offset 8, then each exception in the cause chain
+                let format_func = rt_func_ref(ctx, builder, "naml_exception_stack_string")?;
+                let call = builder.ins().call(format_func, &[recv]);
+                Ok(builder.inst_results(call)[0])
+            } else {
+                Err(CodegenError::JitCompile(
+                    "stack_string() is only available on exception types".to_string(),
+                ))
+            }
+        }
+        // Exception accessors: message() and stack(), same as the fields
+        "message" | "stack"
+            if matches!(
+                ctx.annotations.get_type(receiver.span()),
+                Some(Type::Exception(_))
+            ) =>
+        {
+            let offset = if method_name == "message" { 0 } else { 8 };
+            Ok(builder
+                .ins()
+                .load(cranelift::prelude::types::I64, MemFlags::new(), recv, offset))
+        }
+        // Exception method: stack_frames()
+        "stack_frames" => {
+            let receiver_type = ctx.annotations.get_type(receiver.span());
+            if matches!(receiver_type, Some(Type::Exception(_))) {
                 let stack_ptr =
                     builder
                         .ins()
                         .load(cranelift::prelude::types::I64, MemFlags::new(), recv, 8);
-                // Call naml_stack_format to get formatted string
-                let format_func = rt_func_ref(ctx, builder, "naml_stack_format")?;
-                let call = builder.ins().call(format_func, &[stack_ptr]);
+                let frames_func = rt_func_ref(ctx, builder, "naml_stack_frames")?;
+                let call = builder.ins().call(frames_func, &[stack_ptr]);
                 Ok(builder.inst_results(call)[0])
             } else {
                 Err(CodegenError::JitCompile(
-                    "stack_string() is only available on exception types".to_string(),
+                    "stack_frames() is only available on exception types".to_string(),
                 ))
             }
         }
@@ -89,6 +102,12 @@ pub fn compile_method_call(
             let receiver_type = ctx.annotations.get_type(receiver.span());
             let type_name = match receiver_type {
                 Some(Type::Struct(s)) => Some(ctx.interner.resolve(&s.name).to_string()),
+                Some(Type::Enum(e)) => Some(ctx.interner.resolve(&e.name).to_string()),
+                // `self` in an interface default method, compiled per struct
+                Some(Type::Interface(i)) => ctx
+                    .type_substitutions
+                    .get(ctx.interner.resolve(&i.name))
+                    .cloned(),
                 Some(Type::Generic(name, type_args)) => {
                     let name_str = ctx.interner.resolve(name).to_string();
                     // Check if this is a bare type parameter (no type args)
@@ -109,22 +128,7 @@ pub fn compile_method_call(
             if let Some(type_name) = type_name {
                 let full_name = format!("{}_{}", type_name, method_name);
                 if let Some(&func_id) = ctx.functions.get(&full_name) {
-                    let ptr_type = ctx.module.target_config().pointer_type();
-                    let func_ref = ctx.module.declare_func_in_func(func_id, builder.func);
-
-                    // Compile arguments
-                    let mut call_args = vec![recv];
-                    for arg in args {
-                        call_args.push(compile_expression(ctx, builder, arg)?);
-                    }
-
-                    let call = builder.ins().call(func_ref, &call_args);
-                    let results = builder.inst_results(call);
-                    if results.is_empty() {
-                        return Ok(builder.ins().iconst(ptr_type, 0));
-                    } else {
-                        return Ok(results[0]);
-                    }
+                    return call_user_method(ctx, builder, func_id, recv, args, span);
                 }
             }
 
@@ -134,4 +138,39 @@ pub fn compile_method_call(
             )))
         }
     }
-}
\ No newline at end of file
+}
+/// Call a user-defined method `func_id` on `recv`
+fn call_user_method(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    func_id: FuncId,
+    recv: Value,
+    args: &[Expression<'_>],
+    span: Span,
+) -> Result<Value, CodegenError> {
+    let func_ref = ctx.module.declare_func_in_func(func_id, builder.func);
+
+    // Compile arguments
+    let mut call_args = vec![recv];
+    for arg in args {
+        let mut val = compile_expression(ctx, builder, arg)?;
+        if matches!(
+            arg,
+            Expression::Literal(LiteralExpr {
+                value: Literal::String(_),
+                ..
+            })
+        ) {
+            val = call_string_from_cstr(ctx, builder, val)?;
+        }
+        call_args.push(val);
+    }
+    coerce_call_args(builder, func_ref, &mut call_args);
+
+    let call = builder.ins().call(func_ref, &call_args);
+    let result = call_result_value(builder, call);
+    if let Some(size) = enum_value_size(ctx, ctx.annotations.get_type(span)) {
+        return emit_enum_land(ctx, builder, result, size);
+    }
+    Ok(from_generic_word(builder, result, ctx.annotations.get_type(span)))
+}
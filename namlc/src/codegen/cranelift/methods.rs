@@ -1,14 +1,19 @@
 use std::collections::{HashMap, HashSet};
 use std::panic;
+use std::time::Instant;
 
 use cranelift::prelude::*;
+use indexmap::IndexMap;
 use cranelift_module::{FuncId, Linkage};
 
 use crate::ast::FunctionItem;
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::errors::convert_cranelift_error;
+use crate::codegen::cranelift::exceptions::throws_arithmetic;
 use crate::codegen::cranelift::runtime::{emit_cleanup_all_vars, emit_stack_pop, emit_stack_push};
+use crate::codegen::cranelift::ownership::collect_last_use_moves;
 use crate::codegen::cranelift::stmt::compile_statement;
+use crate::codegen::cranelift::symbols::symbol_name;
 use crate::codegen::cranelift::{collect_reassigned_vars, types, CompileContext, JitCompiler};
 
 impl<'a> JitCompiler<'a> {
@@ -45,14 +50,11 @@ impl<'a> JitCompiler<'a> {
             sig.params.push(AbiParam::new(ty));
         }
 
-        if let Some(ref return_ty) = func.return_ty {
-            let ty = types::naml_to_cranelift(return_ty);
-            sig.returns.push(AbiParam::new(ty));
-        }
+        self.push_return_abi(&mut sig, &full_name, func.return_ty.as_ref());
 
         let func_id = self
             .module
-            .declare_function(&full_name, Linkage::Local, &sig)
+            .declare_function(&symbol_name(&full_name), Linkage::Local, &sig)
             .map_err(|e| {
                 CodegenError::JitCompile(format!("Failed to declare method '{}': {}", full_name, e))
             })?;
@@ -63,6 +65,17 @@ impl<'a> JitCompiler<'a> {
     }
 
     pub(crate) fn compile_method(&mut self, func: &FunctionItem<'_>) -> Result<(), CodegenError> {
+        self.compile_method_with(func, HashMap::new())
+    }
+
+    /// Compile a method with type names substituted in its body, e.g. an
+    /// interface default compiled for one implementing struct
+    pub(crate) fn compile_method_with(
+        &mut self,
+        func: &FunctionItem<'_>,
+        type_substitutions: HashMap<String, String>,
+    ) -> Result<(), CodegenError> {
+        let started = Instant::now();
         let receiver = func
             .receiver
             .as_ref()
@@ -122,7 +135,7 @@ impl<'a> JitCompiler<'a> {
             extern_fns: &self.extern_fns,
             global_vars: &self.global_vars,
             variables: HashMap::new(),
-            var_heap_types: HashMap::new(),
+            var_heap_types: IndexMap::new(),
             var_counter: 0,
             block_terminated: false,
             loop_exit_block: None,
@@ -132,9 +145,11 @@ impl<'a> JitCompiler<'a> {
             lambda_blocks: &self.lambda_blocks,
             lambda_body_to_id: &self.lambda_body_to_id,
             annotations: self.annotations,
-            type_substitutions: HashMap::new(),
+            type_substitutions,
             func_return_type,
             release_mode: self.release_mode,
+            overflow_checks: self.overflow_checks,
+            catch_arithmetic: throws_arithmetic(self.interner, &func.throws),
             unsafe_mode: self.unsafe_mode,
             inline_functions: &self.inline_functions,
             inline_depth: 0,
@@ -142,6 +157,13 @@ impl<'a> JitCompiler<'a> {
             inline_result_var: None,
             borrowed_vars: HashSet::new(),
             reassigned_vars: HashSet::new(),
+            last_use_moves: HashSet::new(),
+            bounds_facts: Vec::new(),
+            hoisted_exprs: HashMap::new(),
+            interned_strings: &mut self.interned_strings,
+            string_objects: HashMap::new(),
+            finally_blocks: Vec::new(),
+            exception_landings: Vec::new(),
             target: self.target,
         };
 
@@ -152,6 +174,8 @@ impl<'a> JitCompiler<'a> {
         ctx.var_counter += 1;
         builder.declare_var(recv_var, ptr_type);
         builder.def_var(recv_var, recv_val);
+        // The receiver and parameters are borrowed from the caller
+        ctx.borrowed_vars.insert(receiver_name.clone());
         ctx.variables.insert(receiver_name, recv_var);
 
         // Set up regular parameters (offset by 1 due to receiver)
@@ -163,12 +187,14 @@ impl<'a> JitCompiler<'a> {
             let ty = types::naml_to_cranelift(&param.ty);
             builder.declare_var(var, ty);
             builder.def_var(var, val);
+            ctx.borrowed_vars.insert(param_name.clone());
             ctx.variables.insert(param_name, var);
         }
 
-        // Scan method body for variable reassignments to enable borrow optimization
+        // Scan method body for variable reassignments and last-use moves to elide refcounting
         if let Some(ref body) = func.body {
             collect_reassigned_vars(&body.statements, self.interner, &mut ctx.reassigned_vars);
+            ctx.last_use_moves = collect_last_use_moves(&body.statements, self.interner);
         }
 
         // Push method onto shadow stack for stack traces
@@ -200,7 +226,7 @@ impl<'a> JitCompiler<'a> {
         }));
 
         match result {
-            Ok(Ok(())) => {}
+            Ok(Ok(())) => self.record_symbol(func_id, &full_name, "method", func.span, started)?,
             Ok(Err(e)) => {
                 return Err(CodegenError::JitCompile(format!(
                     "Failed to define method '{}': {}",
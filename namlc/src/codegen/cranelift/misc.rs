@@ -1,7 +1,7 @@
 use crate::codegen::cranelift::{CompileContext};
 use crate::codegen::CodegenError;
 use cranelift::prelude::*;
-use cranelift_codegen::ir::Value;
+use cranelift_codegen::ir::{FuncRef, Value};
 use cranelift_frontend::FunctionBuilder;
 use crate::codegen::cranelift::runtime::rt_func_ref;
 use crate::codegen::cranelift::strings::call_string_from_cstr;
@@ -190,4 +190,42 @@ pub fn ensure_i64(builder: &mut FunctionBuilder<'_>, val: Value) -> Value {
     } else {
         val
     }
-}
\ No newline at end of file
+}
+/// Convert call arguments to the parameter types of the callee. Generic
+/// functions and methods take values of a type parameter as i64 words, so
+/// float and bool arguments are passed as their i64 bit pattern.
+pub fn coerce_call_args(builder: &mut FunctionBuilder<'_>, func_ref: FuncRef, args: &mut [Value]) {
+    let sig_ref = builder.func.dfg.ext_funcs[func_ref].signature;
+    let param_types: Vec<Type> = builder.func.dfg.signatures[sig_ref]
+        .params
+        .iter()
+        .map(|param| param.value_type)
+        .collect();
+    for (arg, param_type) in args.iter_mut().zip(param_types) {
+        if param_type == cranelift::prelude::types::I64 {
+            *arg = ensure_i64(builder, *arg);
+        }
+    }
+}
+
+/// Convert an i64 word returned by generic code back to the concrete type
+/// the caller expects
+pub fn from_generic_word(
+    builder: &mut FunctionBuilder<'_>,
+    val: Value,
+    expected: Option<&crate::typechecker::Type>,
+) -> Value {
+    use crate::typechecker::Type as TcType;
+    if builder.func.dfg.value_type(val) != cranelift::prelude::types::I64 {
+        return val;
+    }
+    match expected {
+        Some(TcType::Float) => {
+            builder
+                .ins()
+                .bitcast(cranelift::prelude::types::F64, MemFlags::new(), val)
+        }
+        Some(TcType::Bool) => builder.ins().ireduce(cranelift::prelude::types::I8, val),
+        _ => val,
+    }
+}
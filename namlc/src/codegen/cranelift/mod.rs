@@ -6,8 +6,10 @@
 //! over memory management and runtime semantics.
 //!
 
+mod abi;
 mod array;
 mod binop;
+mod bounds;
 mod builtins;
 mod channels;
 mod context;
@@ -17,13 +19,16 @@ mod expr;
 mod externs;
 mod heap;
 mod init;
+mod interfaces;
 mod io;
 mod lambda;
+mod licm;
 mod literal;
 mod map;
 mod method;
 mod misc;
 mod options;
+mod ownership;
 mod pattern;
 mod print;
 mod runtime;
@@ -41,15 +46,18 @@ mod funcs;
 mod methods;
 mod mono;
 mod scan;
+mod symbols;
 mod trampoline;
 
+pub use symbols::SymbolInfo;
+
 use std::collections::{HashMap, HashSet};
 use indexmap::IndexMap;
 
-use cranelift::prelude::{Block, Variable};
+use cranelift::prelude::{Block, Value, Variable};
 use cranelift_codegen as codegen;
 use cranelift_jit::JITModule;
-use cranelift_module::{FuncId, Module};
+use cranelift_module::{DataId, FuncId, Module};
 use cranelift_object::ObjectModule;
 use lasso::{Rodeo, Spur};
 
@@ -100,6 +108,7 @@ impl std::ops::DerefMut for BackendModule {
 use crate::ast::{CompilationTarget, Expression, FunctionItem, Statement};
 use crate::codegen::cranelift::heap::HeapType;
 use crate::typechecker::TypeAnnotations;
+use crate::timings::FunctionTime;
 
 #[derive(Clone)]
 pub struct StructDef {
@@ -148,6 +157,7 @@ pub struct LambdaInfo {
     pub captured_vars: Vec<String>,
     pub param_names: Vec<String>,
     pub body_ptr: *const crate::ast::Expression<'static>,
+    pub span: crate::source::Span,
 }
 
 /// Information for inlineable functions
@@ -166,25 +176,29 @@ pub struct CompileContext<'a> {
     module: &'a mut dyn Module,
     functions: &'a HashMap<String, FuncId>,
     runtime_funcs: &'a HashMap<String, FuncId>,
-    struct_defs: &'a HashMap<Spur, StructDef>,
-    enum_defs: &'a HashMap<String, EnumDef>,
+    struct_defs: &'a IndexMap<Spur, StructDef>,
+    enum_defs: &'a IndexMap<String, EnumDef>,
     exception_names: &'a HashSet<Spur>,
     extern_fns: &'a HashMap<String, ExternFn>,
     global_vars: &'a IndexMap<String, GlobalVarDef>,
     variables: HashMap<String, Variable>,
-    var_heap_types: HashMap<String, HeapType>,
+    var_heap_types: IndexMap<String, HeapType>,
     var_counter: usize,
     block_terminated: bool,
     loop_exit_block: Option<Block>,
     loop_header_block: Option<Block>,
-    spawn_blocks: &'a HashMap<u32, SpawnBlockInfo>,
+    spawn_blocks: &'a IndexMap<u32, SpawnBlockInfo>,
     spawn_body_to_id: &'a HashMap<usize, u32>,
-    lambda_blocks: &'a HashMap<u32, LambdaInfo>,
+    lambda_blocks: &'a IndexMap<u32, LambdaInfo>,
     lambda_body_to_id: &'a HashMap<usize, u32>,
     annotations: &'a TypeAnnotations,
     type_substitutions: HashMap<String, String>,
     func_return_type: Option<cranelift::prelude::Type>,
     release_mode: bool,
+    overflow_checks: bool,
+    /// Failed int division throws ArithmeticError instead of panicking:
+    /// inside `try`/`catch`, or in a function declared to throw it
+    catch_arithmetic: bool,
     unsafe_mode: bool,
     inline_functions: &'a HashMap<String, InlineFuncInfo>,
     inline_depth: u32,
@@ -192,6 +206,20 @@ pub struct CompileContext<'a> {
     inline_result_var: Option<Variable>,
     borrowed_vars: HashSet<String>,
     reassigned_vars: HashSet<String>,
+    /// Spans of `a` in top-level `var b = a;` / `b = a;` where `a` is dead afterwards
+    last_use_moves: HashSet<crate::source::Span>,
+    bounds_facts: Vec<bounds::InBoundsFact>,
+    /// Loop-invariant expressions evaluated before their loop, by span
+    hoisted_exprs: HashMap<crate::source::Span, Variable>,
+    interned_strings: &'a mut HashMap<String, DataId>,
+    /// Interned literal objects, keyed by the C string pointer handed out for them
+    string_objects: HashMap<Value, Value>,
+    /// Bodies of the enclosing `finally` blocks, outermost first, run before
+    /// a return, throw or propagated exception leaves the function
+    finally_blocks: Vec<*const crate::ast::BlockExpr<'static>>,
+    /// Handler blocks of the enclosing `try`, `catch` and `finally`
+    /// expressions, innermost last, for exceptions thrown by inline code
+    exception_landings: Vec<Block>,
     pub(crate) target: CompilationTarget,
 }
 
@@ -276,6 +304,11 @@ pub(crate) const ARRAY_LEN_OFFSET: i32 = 16;
 const ARRAY_CAPACITY_OFFSET: i32 = 24;
 const ARRAY_DATA_OFFSET: i32 = 32;
 
+// NamlString struct layout offsets (must match runtime/value.rs)
+// NamlString: header(16) + len(8) + data
+const STRING_LEN_OFFSET: usize = 16;
+const STRING_DATA_OFFSET: usize = 24;
+
 /// Global variable definition for codegen
 #[derive(Clone)]
 pub struct GlobalVarDef {
@@ -294,21 +327,37 @@ pub struct JitCompiler<'a> {
     ctx: codegen::Context,
     functions: HashMap<String, FuncId>,
     runtime_funcs: HashMap<String, FuncId>,
-    struct_defs: HashMap<Spur, StructDef>,
-    enum_defs: HashMap<String, EnumDef>,
+    struct_defs: IndexMap<Spur, StructDef>,
+    enum_defs: IndexMap<String, EnumDef>,
     exception_names: HashSet<Spur>,
     extern_fns: HashMap<String, ExternFn>,
     global_vars: IndexMap<String, GlobalVarDef>,
     next_type_id: u32,
     spawn_counter: u32,
-    spawn_blocks: HashMap<u32, SpawnBlockInfo>,
+    spawn_blocks: IndexMap<u32, SpawnBlockInfo>,
     spawn_body_to_id: HashMap<usize, u32>,
     lambda_counter: u32,
-    lambda_blocks: HashMap<u32, LambdaInfo>,
+    lambda_blocks: IndexMap<u32, LambdaInfo>,
     lambda_body_to_id: HashMap<usize, u32>,
     generic_functions: HashMap<String, *const FunctionItem<'a>>,
+    /// Generic instantiations already defined, by mangled name, shared by
+    /// the main file and every imported module
+    instantiations: HashMap<String, FuncId>,
     inline_functions: HashMap<String, InlineFuncInfo>,
+    /// Functions used as values; `None` until the main file has been scanned
+    function_values: Option<HashSet<String>>,
+    /// String literals already emitted as static `NamlString` objects
+    interned_strings: HashMap<String, DataId>,
+    /// Declared functions with their source locations, for symbol maps
+    symbols: Vec<SymbolInfo>,
+    /// Codegen time per function; only collected for `--timings`
+    function_times: Option<Vec<FunctionTime>>,
+    /// Bytes of machine code in `symbols`, and the limit set by `--max-code-size`
+    code_size: usize,
+    max_code_size: Option<usize>,
     release_mode: bool,
+    /// Panic on int overflow in `+`, `-` and `*`; on by default in debug builds
+    overflow_checks: bool,
     unsafe_mode: bool,
     target: CompilationTarget,
 }
@@ -1,52 +1,132 @@
 use std::collections::{HashMap, HashSet};
 use std::panic;
+use std::time::Instant;
 
 use cranelift::prelude::*;
+use indexmap::IndexMap;
+use lasso::Rodeo;
 use cranelift_module::{FuncId, Linkage};
 
 use crate::ast::FunctionItem;
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::{JitCompiler, CompileContext};
 use crate::codegen::cranelift::errors::convert_cranelift_error;
+use crate::codegen::cranelift::exceptions::throws_arithmetic;
 use crate::codegen::cranelift::runtime::emit_cleanup_all_vars;
 use crate::codegen::cranelift::stmt::compile_statement;
+use crate::codegen::cranelift::symbols::symbol_name;
 use crate::codegen::cranelift::types;
 use crate::typechecker::Type;
 
+/// A generic function to specialize, with the concrete type name bound to
+/// each of its type parameters
+pub(crate) struct Instantiation {
+    mangled_name: String,
+    func: *const FunctionItem<'static>,
+    type_substitutions: HashMap<String, String>,
+}
+
+/// Generic functions by name, for `requested_instantiations`
+pub(crate) type GenericFunctions = HashMap<String, *const FunctionItem<'static>>;
+
 impl<'a> JitCompiler<'a> {
     pub fn process_monomorphizations(&mut self) -> Result<(), CodegenError> {
-        let monomorphizations: Vec<_> = self
+        let generics: GenericFunctions = self
+            .generic_functions
+            .iter()
+            .map(|(name, &func)| (name.clone(), func.cast::<FunctionItem<'static>>()))
+            .collect();
+        let requests = self.requested_instantiations(&generics, self.interner);
+        self.instantiate(requests, None)?;
+        Ok(())
+    }
+
+    /// The instantiations of `generics` that the file being compiled calls,
+    /// sorted so that builds are reproducible. `generics_interner` is the one
+    /// the generic functions were parsed with, which differs from the current
+    /// interner when a file calls generics of a module it imports.
+    pub(crate) fn requested_instantiations(
+        &self,
+        generics: &GenericFunctions,
+        generics_interner: &Rodeo,
+    ) -> Vec<Instantiation> {
+        let mut monomorphizations: Vec<_> = self
             .annotations
             .get_monomorphizations()
             .values()
-            .cloned()
             .collect();
+        monomorphizations.sort_by(|a, b| a.mangled_name.cmp(&b.mangled_name));
 
+        let mut requests = Vec::new();
         for mono_info in monomorphizations {
-            let func_name = self.interner.resolve(&mono_info.function_name).to_string();
-
-            // Get the generic function AST
-            let func_ptr = match self.generic_functions.get(&func_name) {
-                Some(ptr) => *ptr,
-                None => continue,
+            let func_name = self.interner.resolve(&mono_info.function_name);
+            let Some(&func_ptr) = generics.get(func_name) else {
+                continue;
             };
 
             let func = unsafe { &*func_ptr };
             let mut type_substitutions = HashMap::new();
             for (param, arg_ty) in func.generics.iter().zip(mono_info.type_args.iter()) {
-                let param_name = self.interner.resolve(&param.name.symbol).to_string();
+                let param_name = generics_interner.resolve(&param.name.symbol).to_string();
                 let concrete_name = self.mangle_type_name(arg_ty);
                 type_substitutions.insert(param_name, concrete_name);
             }
+            requests.push(Instantiation {
+                mangled_name: mono_info.mangled_name.clone(),
+                func: func_ptr,
+                type_substitutions,
+            });
+        }
+        requests
+    }
 
-            // Declare the monomorphized function
-            self.declare_monomorphized_function(func, &mono_info.mangled_name)?;
-
-            // Compile the monomorphized function with type substitutions
-            self.compile_monomorphized_function(func, &mono_info.mangled_name, type_substitutions)?;
+    /// Declare every requested instantiation, then compile them, so that
+    /// generic functions can call one another.
+    ///
+    /// Instantiations of public generics are shared: one already defined for
+    /// another file is reused rather than compiled again. When compiling the
+    /// module `module_stem`, its private generics get names of their own like
+    /// its private functions, and shadow their mangled names; the shadowed
+    /// entries are returned for the caller to restore.
+    pub(crate) fn instantiate(
+        &mut self,
+        requests: Vec<Instantiation>,
+        module_stem: Option<&str>,
+    ) -> Result<Vec<(String, Option<FuncId>)>, CodegenError> {
+        let mut shadowed = Vec::new();
+        let mut to_compile = Vec::new();
+        for request in requests {
+            let func = unsafe { &*request.func };
+            match module_stem {
+                Some(stem) if !func.is_public => {
+                    let mut private_name = format!("{}::{}", stem, request.mangled_name);
+                    let mut n = 1;
+                    while self.functions.contains_key(&private_name) {
+                        n += 1;
+                        private_name = format!("{}{}::{}", stem, n, request.mangled_name);
+                    }
+                    let func_id = self.declare_monomorphized_function(func, &private_name)?;
+                    let previous = self.functions.insert(request.mangled_name.clone(), func_id);
+                    shadowed.push((request.mangled_name.clone(), previous));
+                    to_compile.push((private_name, request));
+                }
+                _ => {
+                    if let Some(&func_id) = self.instantiations.get(&request.mangled_name) {
+                        self.functions.insert(request.mangled_name, func_id);
+                        continue;
+                    }
+                    let func_id = self.declare_monomorphized_function(func, &request.mangled_name)?;
+                    self.instantiations.insert(request.mangled_name.clone(), func_id);
+                    to_compile.push((request.mangled_name.clone(), request));
+                }
+            }
         }
 
-        Ok(())
+        for (name, request) in to_compile {
+            let func = unsafe { &*request.func };
+            self.compile_monomorphized_function(func, &name, request.type_substitutions)?;
+        }
+        Ok(shadowed)
     }
 
     fn mangle_type_name(&self, ty: &Type) -> String {
@@ -80,14 +160,11 @@ impl<'a> JitCompiler<'a> {
             sig.params.push(AbiParam::new(ty));
         }
 
-        if let Some(ref return_ty) = func.return_ty {
-            let ty = types::naml_to_cranelift(return_ty);
-            sig.returns.push(AbiParam::new(ty));
-        }
+        self.push_return_abi(&mut sig, mangled_name, func.return_ty.as_ref());
 
         let func_id = self
             .module
-            .declare_function(mangled_name, Linkage::Export, &sig)
+            .declare_function(&symbol_name(mangled_name), Linkage::Export, &sig)
             .map_err(|e| {
                 CodegenError::JitCompile(format!(
                     "Failed to declare monomorphized function '{}': {}",
@@ -106,6 +183,7 @@ impl<'a> JitCompiler<'a> {
         mangled_name: &str,
         type_substitutions: HashMap<String, String>,
     ) -> Result<(), CodegenError> {
+        let started = Instant::now();
         let func_id = *self.functions.get(mangled_name).ok_or_else(|| {
             CodegenError::JitCompile(format!(
                 "Monomorphized function '{}' not declared",
@@ -145,7 +223,7 @@ impl<'a> JitCompiler<'a> {
             extern_fns: &self.extern_fns,
             global_vars: &self.global_vars,
             variables: HashMap::new(),
-            var_heap_types: HashMap::new(),
+            var_heap_types: IndexMap::new(),
             var_counter: 0,
             block_terminated: false,
             loop_exit_block: None,
@@ -158,6 +236,8 @@ impl<'a> JitCompiler<'a> {
             type_substitutions,
             func_return_type,
             release_mode: self.release_mode,
+            overflow_checks: self.overflow_checks,
+            catch_arithmetic: throws_arithmetic(self.interner, &func.throws),
             unsafe_mode: self.unsafe_mode,
             inline_functions: &self.inline_functions,
             inline_depth: 0,
@@ -165,6 +245,13 @@ impl<'a> JitCompiler<'a> {
             inline_result_var: None,
             borrowed_vars: HashSet::new(),
             reassigned_vars: HashSet::new(),
+            last_use_moves: HashSet::new(),
+            bounds_facts: Vec::new(),
+            hoisted_exprs: HashMap::new(),
+            interned_strings: &mut self.interned_strings,
+            string_objects: HashMap::new(),
+            finally_blocks: Vec::new(),
+            exception_landings: Vec::new(),
             target: self.target,
         };
 
@@ -176,6 +263,8 @@ impl<'a> JitCompiler<'a> {
             let ty = types::naml_to_cranelift(&param.ty);
             builder.declare_var(var, ty);
             builder.def_var(var, val);
+            // Parameters are borrowed from the caller
+            ctx.borrowed_vars.insert(param_name.clone());
             ctx.variables.insert(param_name, var);
         }
 
@@ -201,7 +290,7 @@ impl<'a> JitCompiler<'a> {
         }));
 
         match result {
-            Ok(Ok(())) => {}
+            Ok(Ok(())) => self.record_symbol(func_id, mangled_name, "generic", func.span, started)?,
             Ok(Err(e)) => {
                 return Err(CodegenError::JitCompile(format!(
                     "Failed to define monomorphized function '{}': {}",
@@ -10,7 +10,7 @@ use crate::codegen::cranelift::misc::ensure_i64;
 pub fn compile_option_from_nullable_ptr(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
-    arg: Value,
+    args: &[Value],
     runtime_fn: &str,
 ) -> Result<Value, CodegenError> {
     let option_slot =
@@ -20,7 +20,7 @@ pub fn compile_option_from_nullable_ptr(
         .stack_addr(cranelift::prelude::types::I64, option_slot, 0);
 
     let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
-    let call = builder.ins().call(func_ref, &[arg]);
+    let call = builder.ins().call(func_ref, args);
     let result_ptr = builder.inst_results(call)[0];
 
     let some_block = builder.create_block();
@@ -349,6 +349,35 @@ pub fn compile_option_from_last_index_of(
     Ok(option_ptr)
 }
 
+/// Call `runtime_fn` and wrap the index it returns in an option, with -1
+/// meaning not found
+pub fn compile_option_from_found_index(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    args: &[Value],
+    runtime_fn: &str,
+) -> Result<Value, CodegenError> {
+    let option_slot =
+        builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, 16, 0));
+    let option_ptr = builder
+        .ins()
+        .stack_addr(cranelift::prelude::types::I64, option_slot, 0);
+
+    let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+    let call = builder.ins().call(func_ref, args);
+    let index = builder.inst_results(call)[0];
+
+    let neg_one = builder.ins().iconst(cranelift::prelude::types::I64, -1);
+    let found = builder.ins().icmp(IntCC::NotEqual, index, neg_one);
+    let tag = builder.ins().uextend(cranelift::prelude::types::I32, found);
+    builder.ins().store(MemFlags::new(), tag, option_ptr, 0);
+    let zero = builder.ins().iconst(cranelift::prelude::types::I64, 0);
+    let value = builder.ins().select(found, index, zero);
+    builder.ins().store(MemFlags::new(), value, option_ptr, 8);
+
+    Ok(option_ptr)
+}
+
 pub fn compile_option_from_remove_at(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
@@ -0,0 +1,177 @@
+//!
+//! Ownership Analysis for Refcount Elision
+//!
+//! Heap values carry one reference per owner. Codegen classifies each value
+//! it stores or returns so that reference-count traffic is only emitted when
+//! ownership actually changes hands:
+//!
+//! - Owned temporaries (calls, method calls, struct literals, `some(..)`)
+//!   already hold a fresh reference, so binding them to a variable, assigning
+//!   them, or sending them on a channel moves that reference without an incref.
+//! - Returning a local that owns its value moves it to the caller. Returning
+//!   anything the function does not own (a parameter, a global, a borrowed
+//!   local, or a field/index place) takes a new reference, which is what makes
+//!   every call result an owned temporary. Parameters and closure captures
+//!   are recorded as borrowed variables when a function body starts.
+//! - `var b = a;` and `b = a;` at the top level of a function body move `a`
+//!   into `b` when `a` is never mentioned again: `b` takes over the reference
+//!   and `a` is dropped from scope-exit cleanup, eliding the incref/decref pair.
+//!
+
+use std::collections::HashSet;
+
+use cranelift::prelude::Value;
+use cranelift_frontend::FunctionBuilder;
+use lasso::Rodeo;
+
+use crate::ast::visitor::{walk_expr, walk_stmt, Visitor};
+use crate::ast::{AssignOp, Expression, Ident, Statement, TemplateStringPart};
+use crate::codegen::cranelift::heap::heap_type_from_type;
+use crate::codegen::cranelift::runtime::emit_incref;
+use crate::codegen::cranelift::CompileContext;
+use crate::codegen::CodegenError;
+use crate::source::{Span, Spanned};
+
+/// Whether an expression produces a reference owned by whoever receives it
+pub fn is_owned_value(expr: &Expression<'_>) -> bool {
+    matches!(
+        expr,
+        Expression::StructLiteral(_)
+            | Expression::Call(_)
+            | Expression::MethodCall(_)
+            | Expression::Some(_)
+    )
+}
+
+/// Whether an expression reads a value stored in a field or collection slot,
+/// which stays owned by its container
+pub fn is_borrowed_place(expr: &Expression<'_>) -> bool {
+    match expr {
+        Expression::Field(_) | Expression::Index(_) => true,
+        Expression::ForceUnwrap(uw) => is_borrowed_place(uw.expr),
+        _ => false,
+    }
+}
+
+/// Whether returning `expr` hands out a reference the function does not own:
+/// a borrowed place, a parameter or borrowed local, or a global
+pub fn returns_unowned(ctx: &CompileContext<'_>, expr: &Expression<'_>) -> bool {
+    match expr {
+        Expression::Identifier(ident) => {
+            let name = ctx.interner.resolve(&ident.ident.symbol);
+            ctx.borrowed_vars.contains(name) || !ctx.variables.contains_key(name)
+        }
+        _ => is_borrowed_place(expr),
+    }
+}
+
+/// Take a new reference to a returned value the function does not own, so
+/// the caller always receives an owned value
+pub fn emit_return_incref(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    expr: &Expression<'_>,
+    val: Value,
+) -> Result<(), CodegenError> {
+    if !returns_unowned(ctx, expr) {
+        return Ok(());
+    }
+    let heap_type = ctx
+        .annotations
+        .get_type(expr.span())
+        .and_then(|ty| heap_type_from_type(ty, ctx.interner));
+    if let Some(heap_type) = heap_type {
+        emit_incref(ctx, builder, val, &heap_type)?;
+    }
+    Ok(())
+}
+
+/// Move `source` into the heap variable `target` if the analysis proved it is
+/// dead afterwards. `target` takes over the reference and `source` is dropped
+/// from scope-exit cleanup; returns whether the move happened.
+pub fn try_move_last_use(ctx: &mut CompileContext<'_>, target: &str, source: &Expression<'_>) -> bool {
+    let Expression::Identifier(ident) = source else {
+        return false;
+    };
+    if ctx.inline_depth > 0 || !ctx.last_use_moves.contains(&source.span()) {
+        return false;
+    }
+    let name = ctx.interner.resolve(&ident.ident.symbol);
+    // Both sides must own their values: parameters and borrowed locals do not
+    let owns = |var: &str| ctx.var_heap_types.contains_key(var) && !ctx.borrowed_vars.contains(var);
+    if !owns(name) || !owns(target) {
+        return false;
+    }
+    ctx.borrowed_vars.insert(name.to_string());
+    true
+}
+
+/// Collect the spans of `var b = a;` initializers and `b = a;` values at the
+/// top level of a function body where `a` is never mentioned afterwards
+pub fn collect_last_use_moves(stmts: &[Statement<'_>], interner: &Rodeo) -> HashSet<Span> {
+    let mut moves = HashSet::new();
+    for (i, stmt) in stmts.iter().enumerate() {
+        let (target, source, source_ident) = match stmt {
+            Statement::Var(var) if var.else_block.is_none() => match &var.init {
+                Some(init @ Expression::Identifier(ident)) => (var.name.symbol, init, ident),
+                _ => continue,
+            },
+            Statement::Assign(assign) if matches!(assign.op, AssignOp::Assign) => {
+                match (&assign.target, &assign.value) {
+                    (Expression::Identifier(target), value @ Expression::Identifier(ident)) => {
+                        (target.ident.symbol, value, ident)
+                    }
+                    _ => continue,
+                }
+            }
+            _ => continue,
+        };
+        if source_ident.ident.symbol == target {
+            continue;
+        }
+        let name = interner.resolve(&source_ident.ident.symbol);
+        let mut scan = MentionScan { interner, name, found: false };
+        for later in &stmts[i + 1..] {
+            scan.visit_stmt(later);
+        }
+        if !scan.found {
+            moves.insert(source.span());
+        }
+    }
+    moves
+}
+
+/// Looks for any mention of a name, including inside template interpolations
+struct MentionScan<'r> {
+    interner: &'r Rodeo,
+    name: &'r str,
+    found: bool,
+}
+
+impl<'ast> Visitor<'ast> for MentionScan<'_> {
+    fn visit_stmt(&mut self, stmt: &Statement<'ast>) {
+        if !self.found {
+            walk_stmt(self, stmt);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        if self.found {
+            return;
+        }
+        if let Expression::TemplateString(template) = expr {
+            // Interpolations are parsed during codegen, so match their raw text
+            self.found = template.parts.iter().any(|part| {
+                matches!(part, TemplateStringPart::Expression(raw) if raw.contains(self.name))
+            });
+            return;
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if self.interner.resolve(&ident.symbol) == self.name {
+            self.found = true;
+        }
+    }
+}
@@ -38,61 +38,115 @@ pub fn compile_pattern_match(
         }
 
         Pattern::Variant(variant) => {
-            if variant.path.is_empty() {
-                return Err(CodegenError::JitCompile("Empty variant path".to_string()));
-            }
-            let (enum_name, variant_name) = if variant.path.len() == 1 {
-                let var_name = ctx.interner.resolve(&variant.path[0].symbol).to_string();
-                let mut found = None;
-                for (e_name, enum_def) in ctx.enum_defs.iter() {
-                    if enum_def.variants.iter().any(|v| v.name == var_name) {
-                        found = Some((e_name.clone(), var_name.clone()));
-                        break;
-                    }
-                }
+            let tag_value = variant_tag(ctx, variant)?;
+            let tag = builder.ins().load(
+                cranelift::prelude::types::I64,
+                MemFlags::new(),
+                scrutinee,
+                0,
+            );
+            let expected_tag = builder
+                .ins()
+                .iconst(cranelift::prelude::types::I64, tag_value);
+            Ok(builder.ins().icmp(IntCC::Equal, tag, expected_tag))
+        }
 
-                match found {
-                    Some(pair) => pair,
-                    None => {
-                        return Err(CodegenError::JitCompile(format!(
-                            "Unknown variant: {}",
-                            var_name
-                        )));
-                    }
-                }
-            } else {
-                // Qualified path
-                let enum_name = ctx.interner.resolve(&variant.path[0].symbol).to_string();
-                let variant_name = ctx
-                    .interner
-                    .resolve(&variant.path.last().unwrap().symbol)
-                    .to_string();
-                (enum_name, variant_name)
-            };
+        Pattern::Wildcard(_) => Ok(builder.ins().iconst(cranelift::prelude::types::I8, 1)),
 
-            if let Some(enum_def) = ctx.enum_defs.get(&enum_name)
-                && let Some(var_def) = enum_def.variants.iter().find(|v| v.name == variant_name)
-            {
-                let tag = builder.ins().load(
-                    cranelift::prelude::types::I64,
-                    MemFlags::new(),
-                    scrutinee,
-                    0,
-                );
-                let expected_tag = builder
-                    .ins()
-                    .iconst(cranelift::prelude::types::I64, var_def.tag as i64);
-                return Ok(builder.ins().icmp(IntCC::Equal, tag, expected_tag));
-            }
+        Pattern::_Phantom(_) => Ok(builder.ins().iconst(cranelift::prelude::types::I8, 0)),
+    }
+}
+
+/// The constant a switch case compares against, for cases that can be
+/// dispatched with a jump table or binary search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaseKey {
+    /// An integer literal, compared with the scrutinee itself
+    Int(i64),
+    /// An enum variant, compared with the tag stored at the start of the
+    /// scrutinee
+    Tag(i64),
+}
 
-            Err(CodegenError::JitCompile(format!(
-                "Unknown enum variant: {}::{}",
-                enum_name, variant_name
-            )))
+impl CaseKey {
+    pub fn value(self) -> i64 {
+        match self {
+            CaseKey::Int(v) | CaseKey::Tag(v) => v,
         }
+    }
+}
 
-        Pattern::Wildcard(_) => Ok(builder.ins().iconst(cranelift::prelude::types::I8, 1)),
+/// The key of a case whose pattern is a constant, or `None` for patterns
+/// that bind or match anything
+pub fn case_key(ctx: &CompileContext<'_>, pattern: &crate::ast::Pattern<'_>) -> Option<CaseKey> {
+    use crate::ast::{Literal, Pattern};
 
-        Pattern::_Phantom(_) => Ok(builder.ins().iconst(cranelift::prelude::types::I8, 0)),
+    match pattern {
+        Pattern::Literal(lit) => match lit.value {
+            Literal::Int(n) => Some(CaseKey::Int(n)),
+            Literal::UInt(n) => Some(CaseKey::Int(n as i64)),
+            _ => None,
+        },
+        Pattern::Identifier(ident) => {
+            let name = ctx.interner.resolve(&ident.ident.symbol);
+            ctx.enum_defs.values().find_map(|enum_def| {
+                enum_def
+                    .variants
+                    .iter()
+                    .find(|v| v.name == name)
+                    .map(|v| CaseKey::Tag(v.tag as i64))
+            })
+        }
+        Pattern::Variant(variant) => variant_tag(ctx, variant).ok().map(CaseKey::Tag),
+        Pattern::Wildcard(_) | Pattern::_Phantom(_) => None,
+    }
+}
+
+/// Tag of the enum variant named by `variant`, qualified or not
+fn variant_tag(
+    ctx: &CompileContext<'_>,
+    variant: &crate::ast::VariantPattern,
+) -> Result<i64, CodegenError> {
+    if variant.path.is_empty() {
+        return Err(CodegenError::JitCompile("Empty variant path".to_string()));
+    }
+    let (enum_name, variant_name) = if variant.path.len() == 1 {
+        let var_name = ctx.interner.resolve(&variant.path[0].symbol).to_string();
+        let mut found = None;
+        for (e_name, enum_def) in ctx.enum_defs.iter() {
+            if enum_def.variants.iter().any(|v| v.name == var_name) {
+                found = Some((e_name.clone(), var_name.clone()));
+                break;
+            }
+        }
+
+        match found {
+            Some(pair) => pair,
+            None => {
+                return Err(CodegenError::JitCompile(format!(
+                    "Unknown variant: {}",
+                    var_name
+                )));
+            }
+        }
+    } else {
+        // Qualified path
+        let enum_name = ctx.interner.resolve(&variant.path[0].symbol).to_string();
+        let variant_name = ctx
+            .interner
+            .resolve(&variant.path.last().unwrap().symbol)
+            .to_string();
+        (enum_name, variant_name)
+    };
+
+    if let Some(enum_def) = ctx.enum_defs.get(&enum_name)
+        && let Some(var_def) = enum_def.variants.iter().find(|v| v.name == variant_name)
+    {
+        return Ok(var_def.tag as i64);
     }
+
+    Err(CodegenError::JitCompile(format!(
+        "Unknown enum variant: {}::{}",
+        enum_name, variant_name
+    )))
 }
@@ -11,6 +11,8 @@ use cranelift_codegen::ir::Value;
 use cranelift_frontend::FunctionBuilder;
 use crate::codegen::cranelift::misc::ensure_i64;
 use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::strings::arg_to_formatted_string;
+use crate::runtime::{format_pieces, FormatPiece};
 
 pub fn compile_print_call(
     ctx: &mut CompileContext<'_>,
@@ -25,41 +27,35 @@ pub fn compile_print_call(
         return Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0));
     }
 
-    // Check if first arg is a format string with {}
+    // Check if first arg is a format string with {} or {:spec}
     if let Expression::Literal(LiteralExpr {
                                    value: Literal::String(spur),
                                    ..
                                }) = &args[0]
     {
         let format_str = ctx.interner.resolve(spur);
-        if format_str.contains("{}") {
+        let pieces = format_pieces(format_str);
+        if pieces.iter().any(|p| matches!(p, FormatPiece::Placeholder(_))) {
             // Format string mode
             let mut arg_idx = 1;
-            let mut last_end = 0;
 
-            for (start, _) in format_str.match_indices("{}") {
-                // Print literal part before placeholder
-                if start > last_end {
-                    let literal_part = &format_str[last_end..start];
-                    let ptr = compile_string_literal(ctx, builder, literal_part)?;
-                    call_print_str(ctx, builder, ptr)?;
+            for piece in pieces {
+                match piece {
+                    FormatPiece::Literal(literal_part) => {
+                        let ptr = compile_string_literal(ctx, builder, literal_part)?;
+                        call_print_str(ctx, builder, ptr)?;
+                    }
+                    FormatPiece::Placeholder(_) if arg_idx >= args.len() => {}
+                    FormatPiece::Placeholder(None) => {
+                        print_arg(ctx, builder, &args[arg_idx])?;
+                        arg_idx += 1;
+                    }
+                    FormatPiece::Placeholder(Some(spec)) => {
+                        let formatted = arg_to_formatted_string(ctx, builder, &args[arg_idx], spec)?;
+                        call_print_naml_string(ctx, builder, formatted)?;
+                        arg_idx += 1;
+                    }
                 }
-
-                // Print the argument
-                if arg_idx < args.len() {
-                    let arg = &args[arg_idx];
-                    print_arg(ctx, builder, arg)?;
-                    arg_idx += 1;
-                }
-
-                last_end = start + 2;
-            }
-
-            // Print remaining literal after last placeholder
-            if last_end < format_str.len() {
-                let remaining = &format_str[last_end..];
-                let ptr = compile_string_literal(ctx, builder, remaining)?;
-                call_print_str(ctx, builder, ptr)?;
             }
 
             if newline {
@@ -140,7 +136,7 @@ fn emit_print_typed(
     ty: Option<&Type>,
 ) -> Result<(), CodegenError> {
     match ty {
-        Some(Type::String) => {
+        Some(Type::String) | Some(Type::Money) => {
             call_print_naml_string(ctx, builder, val)?;
         }
         Some(Type::Float) => {
@@ -181,6 +177,10 @@ fn emit_print_typed(
         Some(Type::Struct(st)) => {
             emit_print_struct(ctx, builder, val, st)?;
         }
+        Some(Type::Secret) => {
+            let redacted = compile_string_literal(ctx, builder, "[REDACTED]")?;
+            call_print_str(ctx, builder, redacted)?;
+        }
         _ => {
             let val_type = builder.func.dfg.value_type(val);
             if val_type == cranelift::prelude::types::F64 {
@@ -97,6 +97,12 @@ pub fn emit_incref(
 
     let func_name = match heap_type {
         HeapType::String => "naml_string_incref",
+        HeapType::Secret => "naml_secret_incref",
+        HeapType::StringBuilder => "naml_string_builder_incref",
+        HeapType::Set => "naml_set_incref",
+        HeapType::PriorityQueue => "naml_priority_queue_incref",
+        HeapType::Deque => "naml_deque_incref",
+        HeapType::Iterator => "naml_iter_incref",
         HeapType::Array(_) => "naml_array_incref",
         HeapType::Map(_) => "naml_map_incref",
         HeapType::Struct(_) => "naml_struct_incref",
@@ -171,6 +177,12 @@ pub fn emit_decref(
 
     let func_name: String = match heap_type {
         HeapType::String => "naml_string_decref".to_string(),
+        HeapType::Secret => "naml_secret_decref".to_string(),
+        HeapType::StringBuilder => "naml_string_builder_decref".to_string(),
+        HeapType::Set => "naml_set_decref".to_string(),
+        HeapType::PriorityQueue => "naml_priority_queue_decref".to_string(),
+        HeapType::Deque => "naml_deque_decref".to_string(),
+        HeapType::Iterator => "naml_iter_decref".to_string(),
         HeapType::Array(None) => "naml_array_decref".to_string(),
         HeapType::Array(Some(elem_type)) => match elem_type.as_ref() {
             HeapType::String => "naml_array_decref_strings".to_string(),
@@ -178,6 +190,12 @@ pub fn emit_decref(
             HeapType::Map(_) => "naml_array_decref_maps".to_string(),
             HeapType::Struct(_) => "naml_array_decref_structs".to_string(),
             HeapType::OptionOf(_) => "naml_array_decref".to_string(),
+            HeapType::Secret => "naml_array_decref".to_string(),
+            HeapType::StringBuilder => "naml_array_decref".to_string(),
+            HeapType::Set => "naml_array_decref".to_string(),
+            HeapType::PriorityQueue => "naml_array_decref".to_string(),
+            HeapType::Deque => "naml_array_decref".to_string(),
+            HeapType::Iterator => "naml_array_decref".to_string(),
         },
         HeapType::Map(None) => "naml_map_decref".to_string(),
         HeapType::Map(Some(val_type)) => match val_type.as_ref() {
@@ -186,6 +204,12 @@ pub fn emit_decref(
             HeapType::Map(_) => "naml_map_decref_maps".to_string(),
             HeapType::Struct(_) => "naml_map_decref_structs".to_string(),
             HeapType::OptionOf(_) => "naml_map_decref".to_string(),
+            HeapType::Secret => "naml_map_decref".to_string(),
+            HeapType::StringBuilder => "naml_map_decref".to_string(),
+            HeapType::Set => "naml_map_decref".to_string(),
+            HeapType::PriorityQueue => "naml_map_decref".to_string(),
+            HeapType::Deque => "naml_map_decref".to_string(),
+            HeapType::Iterator => "naml_map_decref".to_string(),
         },
         HeapType::Struct(None) => {
             if ctx.unsafe_mode {
@@ -141,6 +141,7 @@ impl<'a> JitCompiler<'a> {
                         captured_vars: captured,
                         param_names,
                         body_ptr,
+                        span: lambda_expr.span,
                     },
                 );
                 self.lambda_body_to_id.insert(body_ptr as usize, id);
@@ -176,6 +177,12 @@ impl<'a> JitCompiler<'a> {
                     self.scan_expression_for_spawns(elem)?;
                 }
             }
+            Expression::Map(map) => {
+                for entry in &map.entries {
+                    self.scan_expression_for_spawns(&entry.key)?;
+                    self.scan_expression_for_spawns(&entry.value)?;
+                }
+            }
             Expression::If(if_expr) => {
                 self.scan_expression_for_spawns(if_expr.condition)?;
                 self.scan_for_spawn_blocks_expr(if_expr.then_branch)?;
@@ -209,6 +216,10 @@ impl<'a> JitCompiler<'a> {
             Expression::Try(try_expr) => {
                 self.scan_expression_for_spawns(try_expr.expr)?;
             }
+            Expression::Finally(finally_expr) => {
+                self.scan_expression_for_spawns(finally_expr.expr)?;
+                self.scan_for_spawn_blocks_expr(finally_expr.body)?;
+            }
             _ => {}
         }
         Ok(())
@@ -395,6 +406,12 @@ impl<'a> JitCompiler<'a> {
                     self.collect_vars_in_expression(elem, captured, defined);
                 }
             }
+            Expression::Map(map) => {
+                for entry in &map.entries {
+                    self.collect_vars_in_expression(&entry.key, captured, defined);
+                    self.collect_vars_in_expression(&entry.value, captured, defined);
+                }
+            }
             Expression::Grouped(grouped) => {
                 self.collect_vars_in_expression(grouped.inner, captured, defined);
             }
@@ -1,18 +1,78 @@
 use cranelift::prelude::*;
 use cranelift_codegen::ir::Value;
 use cranelift_frontend::FunctionBuilder;
+use cranelift_module::Module;
+
+use crate::ast::SpawnExpr;
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::{CompileContext};
-use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::codegen::cranelift::runtime::{call_alloc_closure_data, emit_incref, rt_func_ref};
 
-pub fn call_spawn_closure(
+/// Schedule a spawn block and return its task handle. Untracked spawns,
+/// whose value is discarded, run detached and evaluate to 0.
+pub fn compile_spawn(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
-    func_addr: Value,
-    data: Value,
-    data_size: Value,
-) -> Result<(), CodegenError> {
-    let func_ref = rt_func_ref(ctx, builder, "naml_spawn_closure")?;
-    builder.ins().call(func_ref, &[func_addr, data, data_size]);
-    Ok(())
-}
\ No newline at end of file
+    spawn_expr: &SpawnExpr<'_>,
+    tracked: bool,
+) -> Result<Value, CodegenError> {
+    // Look up spawn ID from body pointer (globally unique across functions)
+    #[allow(clippy::unnecessary_cast)]
+    let body_key = spawn_expr.body as *const crate::ast::BlockExpr<'_> as usize;
+    let spawn_id = *ctx.spawn_body_to_id.get(&body_key).ok_or_else(|| {
+        CodegenError::JitCompile("Spawn block not found for body pointer".to_string())
+    })?;
+
+    let info = ctx
+        .spawn_blocks
+        .get(&spawn_id)
+        .ok_or_else(|| {
+            CodegenError::JitCompile(format!("Spawn block {} not found", spawn_id))
+        })?
+        .clone();
+
+    let ptr_type = ctx.module.target_config().pointer_type();
+
+    // Calculate closure data size (8 bytes per captured variable)
+    let data_size = info.captured_vars.len() * 8;
+    let data_size_val = builder
+        .ins()
+        .iconst(cranelift::prelude::types::I64, data_size as i64);
+
+    // Allocate closure data
+    let data_ptr = if data_size > 0 {
+        call_alloc_closure_data(ctx, builder, data_size_val)?
+    } else {
+        builder.ins().iconst(ptr_type, 0)
+    };
+
+    // Store captured variables in closure data
+    for (i, var_name) in info.captured_vars.iter().enumerate() {
+        if let Some(&var) = ctx.variables.get(var_name) {
+            let val = builder.use_var(var);
+            let offset = builder.ins().iconst(ptr_type, (i * 8) as i64);
+            let addr = builder.ins().iadd(data_ptr, offset);
+            builder.ins().store(MemFlags::new(), val, addr, 0);
+            if let Some(heap_type) = ctx.var_heap_types.get(var_name) {
+                emit_incref(ctx, builder, val, &heap_type.clone())?;
+            }
+        }
+    }
+
+    // Get trampoline function address
+    let trampoline_id = *ctx.functions.get(&info.func_name).ok_or_else(|| {
+        CodegenError::JitCompile(format!("Trampoline '{}' not found", info.func_name))
+    })?;
+    let trampoline_ref = ctx.module.declare_func_in_func(trampoline_id, builder.func);
+    let trampoline_addr = builder.ins().func_addr(ptr_type, trampoline_ref);
+
+    if tracked {
+        let func_ref = rt_func_ref(ctx, builder, "naml_spawn_task")?;
+        let call = builder.ins().call(func_ref, &[trampoline_addr, data_ptr, data_size_val]);
+        Ok(builder.inst_results(call)[0])
+    } else {
+        let func_ref = rt_func_ref(ctx, builder, "naml_spawn_detached")?;
+        builder.ins().call(func_ref, &[trampoline_addr, data_ptr, data_size_val]);
+        Ok(builder.ins().iconst(cranelift::prelude::types::I64, 0))
+    }
+}
@@ -1,11 +1,21 @@
 use crate::ast::{BinaryOp, Expression, Literal, LiteralExpr, Statement};
+use crate::codegen::cranelift::abi::{
+    emit_enum_escape, emit_none_pair_return, emit_option_pair_return, enum_value_size,
+    returns_option_pair,
+};
 use crate::codegen::cranelift::array::{
     call_array_index, call_array_len, call_array_new, call_array_set,
-    compile_direct_array_get_or_panic,
+    compile_direct_array_get_or_panic, compile_unchecked_array_get, compile_unchecked_array_set,
 };
-use crate::codegen::cranelift::pattern::compile_pattern_match;
+use crate::codegen::cranelift::bounds::{analyze_for_loop, is_index_in_bounds};
+use crate::codegen::cranelift::licm::{end_hoisting, hoist_loop_invariants};
+use crate::codegen::cranelift::pattern::{case_key, compile_pattern_match, CaseKey};
 use crate::codegen::cranelift::expr::compile_expression;
-use crate::codegen::cranelift::map::call_map_set;
+use crate::codegen::cranelift::map::{
+    ast_map_key_kind, call_map_new, call_map_set, compile_map_key, has_float_keys, is_map_index,
+    store_map_entry,
+};
+use crate::codegen::cranelift::ownership::{emit_return_incref, is_owned_value, try_move_last_use};
 use crate::codegen::cranelift::{
     get_field_access_base_var, types, CompileContext, HeapType,
 };
@@ -14,8 +24,12 @@ use crate::codegen::CodegenError;
 use crate::source::Spanned;
 use crate::typechecker::Type;
 use cranelift::prelude::*;
-use crate::codegen::cranelift::exceptions::call_exception_set;
+use cranelift_frontend::Switch;
+use crate::codegen::cranelift::exceptions::{
+    call_exception_set_typed, emit_exception_propagate, emit_pending_finally, exception_type_id,
+};
 use crate::codegen::cranelift::runtime::{emit_cleanup_all_vars, emit_decref, emit_incref, emit_stack_pop, get_returned_var_name, rt_func_ref};
+use crate::codegen::cranelift::spawns::compile_spawn;
 use crate::codegen::cranelift::strings::{call_string_char_at, call_string_char_len, call_string_from_cstr};
 
 fn try_compile_option_field_direct(
@@ -80,10 +94,16 @@ pub fn compile_statement(
 
             // Track heap type for cleanup (skip enum types - they are stack-allocated,
             // and exception types - they use raw allocation, not NamlStruct)
-            let skip_heap_tracking = matches!(var_stmt.ty.as_ref(), Some(crate::ast::NamlType::Named(ident)) if {
-                let type_name = ctx.interner.resolve(&ident.symbol).to_string();
-                ctx.enum_defs.contains_key(&type_name) || ctx.exception_names.contains(&ident.symbol)
-            });
+            let skip_heap_tracking = match var_stmt.ty.as_ref() {
+                Some(crate::ast::NamlType::Named(ident)) => {
+                    let type_name = ctx.interner.resolve(&ident.symbol);
+                    ctx.enum_defs.contains_key(type_name) || ctx.exception_names.contains(&ident.symbol)
+                }
+                Some(crate::ast::NamlType::Generic(ident, _)) => {
+                    ctx.enum_defs.contains_key(ctx.interner.resolve(&ident.symbol))
+                }
+                _ => false,
+            };
             if !skip_heap_tracking {
                 if let Some(ref naml_ty) = var_stmt.ty
                     && let Some(heap_type) = get_heap_type_resolved(naml_ty, ctx.interner)
@@ -248,35 +268,31 @@ pub fn compile_statement(
 
                 builder.def_var(var, val);
                 // Skip incref in inlined functions (borrow semantics).
-                // At top level, skip incref for fresh values (struct literal,
-                // function call) which already have refcount=1.
+                // At top level, skip incref for owned values (struct literal,
+                // call results) which already have refcount=1, and for the last
+                // use of another owning variable, whose reference moves here.
                 // Also skip incref when borrowing from a non-reassigned variable's field.
-                if ctx.inline_depth == 0 {
-                    let is_fresh_value = matches!(init,
-                        Expression::StructLiteral(_) | Expression::Call(_) | Expression::Some(_)
-                    );
-                    if !is_fresh_value {
-                        let can_borrow = get_field_access_base_var(init, ctx.interner)
-                            .is_some_and(|base| !ctx.reassigned_vars.contains(&base));
-                        if can_borrow {
-                            ctx.borrowed_vars.insert(var_name.clone());
-                        } else {
-                            let heap_type_clone = ctx.var_heap_types.get(&var_name).cloned();
-                            if let Some(ref heap_type) = heap_type_clone {
-                                emit_incref(ctx, builder, val, heap_type)?;
-                            }
+                if ctx.inline_depth == 0
+                    && !is_owned_value(init)
+                    && !try_move_last_use(ctx, &var_name, init)
+                {
+                    let can_borrow = get_field_access_base_var(init, ctx.interner)
+                        .is_some_and(|base| !ctx.reassigned_vars.contains(&base));
+                    if can_borrow {
+                        ctx.borrowed_vars.insert(var_name.clone());
+                    } else {
+                        let heap_type_clone = ctx.var_heap_types.get(&var_name).cloned();
+                        if let Some(ref heap_type) = heap_type_clone {
+                            emit_incref(ctx, builder, val, heap_type)?;
                         }
                     }
                 }
             } else {
                 // No initializer - create default values for collection types
                 let val = match var_stmt.ty.as_ref() {
-                    Some(crate::ast::NamlType::Map(_, _)) => {
+                    Some(map_ty @ crate::ast::NamlType::Map(_, _)) => {
                         // Create empty map with default capacity
-                        let capacity = builder.ins().iconst(cranelift::prelude::types::I64, 16);
-                        let func_ref = rt_func_ref(ctx, builder, "naml_map_new")?;
-                        let call = builder.ins().call(func_ref, &[capacity]);
-                        builder.inst_results(call)[0]
+                        call_map_new(ctx, builder, ast_map_key_kind(map_ty))?
                     }
                     Some(crate::ast::NamlType::Array(_)) => {
                         // Create empty array with default capacity
@@ -326,8 +342,12 @@ pub fn compile_statement(
 
                         builder.def_var(var, val);
 
-                        // Incref the new value since we're storing a new reference
-                        if let Some(ref heap_type) = heap_type_clone {
+                        // Incref the new value since we're storing a new reference,
+                        // unless it is an owned value or a moved last use
+                        if let Some(ref heap_type) = heap_type_clone
+                            && !is_owned_value(&assign.value)
+                            && !try_move_last_use(ctx, &var_name, &assign.value)
+                        {
                             emit_incref(ctx, builder, val, heap_type)?;
                         }
                     } else {
@@ -341,14 +361,8 @@ pub fn compile_statement(
                     let base = compile_expression(ctx, builder, index_expr.base)?;
                     let value = compile_expression(ctx, builder, &assign.value)?;
 
-                    // Check if index is a string literal - if so, use map_set with NamlString conversion
-                    if let Expression::Literal(LiteralExpr {
-                        value: Literal::String(_),
-                        ..
-                    }) = index_expr.index
-                    {
-                        let cstr_ptr = compile_expression(ctx, builder, index_expr.index)?;
-                        let naml_str = call_string_from_cstr(ctx, builder, cstr_ptr)?;
+                    if is_map_index(ctx, index_expr) {
+                        let key = compile_map_key(ctx, builder, index_expr.index)?;
 
                         // Convert value to NamlString if it's a string literal
                         let final_value = if let Expression::Literal(LiteralExpr {
@@ -361,7 +375,10 @@ pub fn compile_statement(
                             value
                         };
 
-                        call_map_set(ctx, builder, base, naml_str, final_value)?;
+                        let float_key = has_float_keys(ctx, index_expr.base);
+                        store_map_entry(ctx, builder, float_key, key, |ctx, builder| {
+                            call_map_set(ctx, builder, base, key, final_value)
+                        })?;
                     } else {
                         // Default to array set for integer indices
                         let index = compile_expression(ctx, builder, index_expr.index)?;
@@ -375,7 +392,11 @@ pub fn compile_statement(
                         } else {
                             None
                         };
-                        call_array_set(ctx, builder, base, index, value, element_type)?;
+                        if is_index_in_bounds(ctx, index_expr.base, index_expr.index) {
+                            compile_unchecked_array_set(ctx, builder, base, index, value, element_type)?;
+                        } else {
+                            call_array_set(ctx, builder, base, index, value, element_type)?;
+                        }
                     }
                 }
                 Expression::Field(field_expr) => {
@@ -456,10 +477,17 @@ pub fn compile_statement(
                                             // Get struct pointer from array element
                                             let arr_ptr = compile_expression(ctx, builder, index_expr.base)?;
                                             let index = compile_expression(ctx, builder, index_expr.index)?;
-                                            let struct_ptr = compile_direct_array_get_or_panic(
-                                                ctx, builder, arr_ptr, index,
-                                                cranelift::prelude::types::I64
-                                            )?;
+                                            let struct_ptr = if is_index_in_bounds(ctx, index_expr.base, index_expr.index) {
+                                                compile_unchecked_array_get(
+                                                    ctx, builder, arr_ptr, index,
+                                                    cranelift::prelude::types::I64
+                                                )
+                                            } else {
+                                                compile_direct_array_get_or_panic(
+                                                    ctx, builder, arr_ptr, index,
+                                                    cranelift::prelude::types::I64
+                                                )?
+                                            };
 
                                             // Determine field type for typed store (F64 for floats)
                                             let field_type = struct_type.fields.iter()
@@ -519,6 +547,7 @@ pub fn compile_statement(
                     {
                         val = call_string_from_cstr(ctx, builder, val)?;
                     }
+                    emit_return_incref(ctx, builder, expr, val)?;
 
                     // Handle type coercion for i8 to i64
                     let val_type = builder.func.dfg.value_type(val);
@@ -538,9 +567,6 @@ pub fn compile_statement(
                 ctx.block_terminated = true;
             } else {
                 // Normal return - emit actual return instruction
-                // Pop from shadow stack before returning
-                emit_stack_pop(ctx, builder)?;
-
                 if let Some(ref expr) = ret.value {
                     let mut val = compile_expression(ctx, builder, expr)?;
 
@@ -557,6 +583,15 @@ pub fn compile_statement(
                     {
                         val = call_string_from_cstr(ctx, builder, val)?;
                     }
+                    emit_return_incref(ctx, builder, expr, val)?;
+
+                    // Run enclosing finally blocks once the value is computed
+                    emit_pending_finally(ctx, builder)?;
+                    if ctx.block_terminated {
+                        return Ok(());
+                    }
+                    // Pop from shadow stack before returning
+                    emit_stack_pop(ctx, builder)?;
 
                     // Determine if we're returning a local heap variable (ownership transfer)
                     let returned_var =
@@ -572,21 +607,34 @@ pub fn compile_statement(
                     // Cleanup all local heap variables except the returned one
                     emit_cleanup_all_vars(ctx, builder, exclude_var)?;
 
-                    // Only extend i8 to i64 if the function signature expects i64 (lambdas)
-                    // Regular bool-returning functions should return i8 directly
-                    let val_type = builder.func.dfg.value_type(val);
-                    let val = if val_type == cranelift::prelude::types::I8
-                        && ctx.func_return_type == Some(cranelift::prelude::types::I64)
-                    {
-                        builder.ins().uextend(cranelift::prelude::types::I64, val)
+                    if let Some(size) = enum_value_size(ctx, return_type) {
+                        val = emit_enum_escape(ctx, builder, val, size)?;
+                    }
+
+                    if returns_option_pair(builder) {
+                        emit_option_pair_return(builder, val);
                     } else {
-                        val
-                    };
-                    builder.ins().return_(&[val]);
+                        // Only widen to i64 if the function signature expects i64 (lambdas)
+                        // Regular bool-returning functions should return i8 directly
+                        let val = if ctx.func_return_type == Some(cranelift::prelude::types::I64) {
+                            super::misc::ensure_i64(builder, val)
+                        } else {
+                            val
+                        };
+                        builder.ins().return_(&[val]);
+                    }
                 } else {
+                    emit_pending_finally(ctx, builder)?;
+                    if ctx.block_terminated {
+                        return Ok(());
+                    }
+                    emit_stack_pop(ctx, builder)?;
+
                     // Void return - cleanup all heap variables
                     emit_cleanup_all_vars(ctx, builder, None)?;
-                    if let Some(ret_ty) = ctx.func_return_type {
+                    if returns_option_pair(builder) {
+                        emit_none_pair_return(builder);
+                    } else if let Some(ret_ty) = ctx.func_return_type {
                         let zero = builder.ins().iconst(ret_ty, 0);
                         builder.ins().return_(&[zero]);
                     } else {
@@ -598,7 +646,12 @@ pub fn compile_statement(
         }
 
         Statement::Expression(expr_stmt) => {
-            compile_expression(ctx, builder, &expr_stmt.expr)?;
+            // A spawn whose handle is discarded runs detached
+            if let Expression::Spawn(spawn_expr) = &expr_stmt.expr {
+                compile_spawn(ctx, builder, spawn_expr, false)?;
+            } else {
+                compile_expression(ctx, builder, &expr_stmt.expr)?;
+            }
         }
 
         Statement::If(if_stmt) => {
@@ -669,6 +722,13 @@ pub fn compile_statement(
             ctx.loop_exit_block = Some(exit_block);
             ctx.loop_header_block = Some(header_block);
 
+            let hoisted = hoist_loop_invariants(
+                ctx,
+                builder,
+                Some(&while_stmt.condition),
+                &while_stmt.body.statements,
+                &[],
+            )?;
             builder.ins().jump(header_block, &[]);
 
             builder.switch_to_block(header_block);
@@ -720,6 +780,7 @@ pub fn compile_statement(
 
             // Remove loop-local heap types so outer loops don't see them
             ctx.var_heap_types.retain(|name, _| heap_vars_before.contains(name.as_str()));
+            end_hoisting(ctx, hoisted);
 
             // Restore previous loop context
             ctx.loop_exit_block = prev_loop_exit;
@@ -727,6 +788,14 @@ pub fn compile_statement(
         }
 
         Statement::For(for_stmt) => {
+            // Index variables proven in bounds for the whole body (release mode)
+            let loop_bounds = analyze_for_loop(ctx, for_stmt);
+            let outer_facts = ctx.bounds_facts.len();
+
+            let mut bound = vec![for_stmt.value.symbol];
+            bound.extend(for_stmt.index.as_ref().map(|i| i.symbol));
+            let hoisted = hoist_loop_invariants(ctx, builder, None, &for_stmt.body.statements, &bound)?;
+
             // Check if iterable is a range expression (binary op with Range or RangeIncl)
             let range_info = match &for_stmt.iterable {
                 Expression::Binary(bin)
@@ -768,6 +837,11 @@ pub fn compile_statement(
                     Some(Type::String)
                 );
 
+            let is_iterator = matches!(
+                ctx.annotations.get_type(for_stmt.iterable.span()).map(Type::resolve),
+                Some(Type::Iterator(_))
+            );
+
             if let Some((start_expr, end_expr, inclusive)) = range_info {
                 // Handle range iteration directly without array allocation
                 // Get start and end values
@@ -828,12 +902,14 @@ pub fn compile_statement(
                 builder.seal_block(body_block);
                 ctx.block_terminated = false;
 
+                ctx.bounds_facts.extend(loop_bounds.facts);
                 for stmt in &for_stmt.body.statements {
                     compile_statement(ctx, builder, stmt)?;
                     if ctx.block_terminated {
                         break;
                     }
                 }
+                ctx.bounds_facts.truncate(outer_facts);
 
                 // Increment index
                 if !ctx.block_terminated {
@@ -943,6 +1019,89 @@ pub fn compile_statement(
                 builder.seal_block(exit_block);
                 ctx.block_terminated = false;
 
+                ctx.loop_exit_block = prev_loop_exit;
+                ctx.loop_header_block = prev_loop_header;
+            } else if is_iterator {
+                // Pull elements from a lazy iterator until it is exhausted. The
+                // header advances the iterator, so `continue` needs no extra step.
+                let iter_ptr = compile_expression(ctx, builder, &for_stmt.iterable)?;
+
+                let found_slot = builder.create_sized_stack_slot(StackSlotData::new(
+                    StackSlotKind::ExplicitSlot,
+                    8,
+                    0,
+                ));
+
+                // Index starts at -1 and is bumped before each element
+                let idx_var = Variable::new(ctx.var_counter);
+                ctx.var_counter += 1;
+                builder.declare_var(idx_var, cranelift::prelude::types::I64);
+                let minus_one = builder.ins().iconst(cranelift::prelude::types::I64, -1);
+                builder.def_var(idx_var, minus_one);
+
+                let val_var = Variable::new(ctx.var_counter);
+                ctx.var_counter += 1;
+                builder.declare_var(val_var, cranelift::prelude::types::I64);
+                let zero = builder.ins().iconst(cranelift::prelude::types::I64, 0);
+                builder.def_var(val_var, zero);
+                let val_name = ctx.interner.resolve(&for_stmt.value.symbol).to_string();
+                ctx.variables.insert(val_name, val_var);
+
+                if let Some(ref idx_ident) = for_stmt.index {
+                    let idx_name = ctx.interner.resolve(&idx_ident.symbol).to_string();
+                    ctx.variables.insert(idx_name, idx_var);
+                }
+
+                let header_block = builder.create_block();
+                let body_block = builder.create_block();
+                let exit_block = builder.create_block();
+
+                let prev_loop_exit = ctx.loop_exit_block.take();
+                let prev_loop_header = ctx.loop_header_block.take();
+                ctx.loop_exit_block = Some(exit_block);
+                ctx.loop_header_block = Some(header_block);
+
+                builder.ins().jump(header_block, &[]);
+
+                builder.switch_to_block(header_block);
+                let found_ptr = builder
+                    .ins()
+                    .stack_addr(cranelift::prelude::types::I64, found_slot, 0);
+                let next_ref = rt_func_ref(ctx, builder, "naml_iter_next")?;
+                let call = builder.ins().call(next_ref, &[iter_ptr, found_ptr]);
+                let elem = builder.inst_results(call)[0];
+                builder.def_var(val_var, elem);
+                let idx_val = builder.use_var(idx_var);
+                let next_idx = builder.ins().iadd_imm(idx_val, 1);
+                builder.def_var(idx_var, next_idx);
+                let found = builder.ins().load(
+                    cranelift::prelude::types::I64,
+                    MemFlags::new(),
+                    found_ptr,
+                    0,
+                );
+                builder.ins().brif(found, body_block, &[], exit_block, &[]);
+
+                builder.switch_to_block(body_block);
+                builder.seal_block(body_block);
+                ctx.block_terminated = false;
+
+                for stmt in &for_stmt.body.statements {
+                    compile_statement(ctx, builder, stmt)?;
+                    if ctx.block_terminated {
+                        break;
+                    }
+                }
+
+                if !ctx.block_terminated {
+                    builder.ins().jump(header_block, &[]);
+                }
+
+                builder.seal_block(header_block);
+                builder.switch_to_block(exit_block);
+                builder.seal_block(exit_block);
+                ctx.block_terminated = false;
+
                 ctx.loop_exit_block = prev_loop_exit;
                 ctx.loop_header_block = prev_loop_header;
             } else {
@@ -989,15 +1148,21 @@ pub fn compile_statement(
 
                 let idx_val = builder.use_var(idx_var);
                 // Use call_array_index for direct element access (returns raw value)
-                let elem = call_array_index(ctx, builder, arr_ptr, idx_val)?;
+                let elem = if loop_bounds.element_load {
+                    compile_unchecked_array_get(ctx, builder, arr_ptr, idx_val, cranelift::prelude::types::I64)
+                } else {
+                    call_array_index(ctx, builder, arr_ptr, idx_val)?
+                };
                 builder.def_var(val_var, elem);
 
+                ctx.bounds_facts.extend(loop_bounds.facts);
                 for stmt in &for_stmt.body.statements {
                     compile_statement(ctx, builder, stmt)?;
                     if ctx.block_terminated {
                         break;
                     }
                 }
+                ctx.bounds_facts.truncate(outer_facts);
 
                 if !ctx.block_terminated {
                     let idx_val = builder.use_var(idx_var);
@@ -1015,6 +1180,7 @@ pub fn compile_statement(
                 ctx.loop_exit_block = prev_loop_exit;
                 ctx.loop_header_block = prev_loop_header;
             }
+            end_hoisting(ctx, hoisted);
         }
 
         Statement::Loop(loop_stmt) => {
@@ -1026,10 +1192,10 @@ pub fn compile_statement(
             ctx.loop_exit_block = Some(exit_block);
             ctx.loop_header_block = Some(body_block);
 
+            let hoisted = hoist_loop_invariants(ctx, builder, None, &loop_stmt.body.statements, &[])?;
             builder.ins().jump(body_block, &[]);
 
             builder.switch_to_block(body_block);
-            builder.seal_block(body_block);
             ctx.block_terminated = false;
 
             for stmt in &loop_stmt.body.statements {
@@ -1043,9 +1209,12 @@ pub fn compile_statement(
                 builder.ins().jump(body_block, &[]);
             }
 
+            // Sealed once the back edge is known
+            builder.seal_block(body_block);
             builder.switch_to_block(exit_block);
             builder.seal_block(exit_block);
             ctx.block_terminated = false;
+            end_hoisting(ctx, hoisted);
 
             ctx.loop_exit_block = prev_loop_exit;
             ctx.loop_header_block = prev_loop_header;
@@ -1078,39 +1247,46 @@ pub fn compile_statement(
             let merge_block = builder.create_block();
             let default_block = builder.create_block();
 
-            // Create case blocks and check blocks
-            let mut case_blocks = Vec::new();
-            let mut check_blocks = Vec::new();
-
-            for _ in &switch_stmt.cases {
-                case_blocks.push(builder.create_block());
-                check_blocks.push(builder.create_block());
-            }
-
-            // Jump to first check (or default if no cases)
-            if !check_blocks.is_empty() {
-                builder.ins().jump(check_blocks[0], &[]);
-            } else {
-                builder.ins().jump(default_block, &[]);
-            }
-
-            // Build the chain of checks using pattern matching
-            for (i, case) in switch_stmt.cases.iter().enumerate() {
-                builder.switch_to_block(check_blocks[i]);
-                builder.seal_block(check_blocks[i]);
-
-                // Use compile_pattern_match instead of compile_expression
-                let cond = compile_pattern_match(ctx, builder, &case.pattern, scrutinee)?;
+            let case_blocks: Vec<Block> =
+                switch_stmt.cases.iter().map(|_| builder.create_block()).collect();
+
+            // Switches whose cases are all integer literals or all enum
+            // variants dispatch through jump tables for dense keys and a
+            // binary search otherwise; the rest test each case in turn
+            let keys: Option<Vec<CaseKey>> = switch_stmt
+                .cases
+                .iter()
+                .map(|case| case_key(ctx, &case.pattern))
+                .collect();
+            let keys = keys.filter(|keys| {
+                keys.len() >= MIN_DISPATCH_CASES
+                    && builder.func.dfg.value_type(scrutinee) == cranelift::prelude::types::I64
+                    && keys.iter().all(|k| {
+                        std::mem::discriminant(k) == std::mem::discriminant(&keys[0])
+                    })
+            });
 
-                let next_check = if i + 1 < switch_stmt.cases.len() {
-                    check_blocks[i + 1]
-                } else {
-                    default_block
+            if let Some(keys) = keys {
+                let discriminant = match keys[0] {
+                    CaseKey::Int(_) => scrutinee,
+                    CaseKey::Tag(_) => builder.ins().load(
+                        cranelift::prelude::types::I64,
+                        MemFlags::new(),
+                        scrutinee,
+                        0,
+                    ),
                 };
-
-                builder
-                    .ins()
-                    .brif(cond, case_blocks[i], &[], next_check, &[]);
+                let mut dispatch = Switch::new();
+                for (key, &case_block) in keys.iter().zip(&case_blocks) {
+                    // Of cases with the same key, the first one matches
+                    let entry = key.value() as u64 as u128;
+                    if !dispatch.entries().contains_key(&entry) {
+                        dispatch.set_entry(entry, case_block);
+                    }
+                }
+                dispatch.emit(builder, discriminant, default_block);
+            } else {
+                compile_switch_checks(ctx, builder, switch_stmt, scrutinee, &case_blocks, default_block)?;
             }
 
             // Compile each case body with pattern variable bindings
@@ -1167,13 +1343,22 @@ pub fn compile_statement(
             let stack_ptr = builder.inst_results(stack_call)[0];
             builder.ins().store(MemFlags::new(), stack_ptr, exception_ptr, 8);
 
-            // Set the current exception in thread-local storage
-            call_exception_set(ctx, builder, exception_ptr)?;
+            // Record the original exception for `throw ... caused_by err;`
+            if let Some(cause) = &throw_stmt.cause {
+                let cause_ptr = compile_expression(ctx, builder, cause)?;
+                let set_cause_func = rt_func_ref(ctx, builder, "naml_exception_set_cause")?;
+                builder.ins().call(set_cause_func, &[exception_ptr, cause_ptr]);
+            }
 
-            // Return 0 (indicates exception) from the function
-            let zero = builder.ins().iconst(cranelift::prelude::types::I64, 0);
-            builder.ins().return_(&[zero]);
-            ctx.block_terminated = true;
+            // Set the current exception and its type ID in thread-local storage
+            let type_id = match ctx.annotations.get_type(throw_stmt.value.span()) {
+                Some(Type::Exception(name)) => exception_type_id(ctx, *name),
+                _ => None,
+            };
+            call_exception_set_typed(ctx, builder, exception_ptr, type_id.unwrap_or(0))?;
+
+            // Pop our frame and return zero (indicates exception) from the function
+            emit_exception_propagate(ctx, builder)?;
         }
 
         Statement::Const(const_stmt) => {
@@ -1262,6 +1447,49 @@ pub fn compile_statement(
     Ok(())
 }
 
+/// Fewest constant cases for a switch to dispatch with `Switch` rather
+/// than testing each case in turn
+const MIN_DISPATCH_CASES: usize = 3;
+
+/// Test each case's pattern in order, branching to the first that matches
+/// or to `default_block`
+fn compile_switch_checks(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    switch_stmt: &crate::ast::SwitchStmt<'_>,
+    scrutinee: Value,
+    case_blocks: &[Block],
+    default_block: Block,
+) -> Result<(), CodegenError> {
+    let check_blocks: Vec<Block> = switch_stmt.cases.iter().map(|_| builder.create_block()).collect();
+
+    // Jump to first check (or default if no cases)
+    if !check_blocks.is_empty() {
+        builder.ins().jump(check_blocks[0], &[]);
+    } else {
+        builder.ins().jump(default_block, &[]);
+    }
+
+    for (i, case) in switch_stmt.cases.iter().enumerate() {
+        builder.switch_to_block(check_blocks[i]);
+        builder.seal_block(check_blocks[i]);
+
+        // Use compile_pattern_match instead of compile_expression
+        let cond = compile_pattern_match(ctx, builder, &case.pattern, scrutinee)?;
+
+        let next_check = if i + 1 < switch_stmt.cases.len() {
+            check_blocks[i + 1]
+        } else {
+            default_block
+        };
+
+        builder
+            .ins()
+            .brif(cond, case_blocks[i], &[], next_check, &[]);
+    }
+    Ok(())
+}
+
 fn bind_pattern_vars(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
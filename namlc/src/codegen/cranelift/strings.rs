@@ -8,6 +8,7 @@ use crate::codegen::cranelift::expr::compile_expression;
 use crate::codegen::cranelift::literal::compile_string_literal;
 use crate::codegen::cranelift::misc::ensure_i64;
 use crate::codegen::cranelift::runtime::rt_func_ref;
+use crate::runtime::{format_pieces, FormatPiece};
 use crate::source::Spanned;
 
 pub fn call_string_equals(
@@ -104,11 +105,15 @@ pub fn call_bytes_to_string(
 }
 
 
+/// Convert a C string pointer to a NamlString; interned literals are used as-is
 pub fn call_string_from_cstr(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
     cstr_ptr: Value,
 ) -> Result<Value, CodegenError> {
+    if let Some(&object) = ctx.string_objects.get(&cstr_ptr) {
+        return Ok(object);
+    }
     let func_ref = rt_func_ref(ctx, builder, "naml_string_from_cstr")?;
     let call = builder.ins().call(func_ref, &[cstr_ptr]);
     Ok(builder.inst_results(call)[0])
@@ -166,8 +171,12 @@ pub fn arg_to_naml_string(
             let val = compile_expression(ctx, builder, arg)?;
             let expr_type = ctx.annotations.get_type(arg.span());
             match expr_type {
-                Some(crate::typechecker::Type::String) => Ok(val),
+                Some(crate::typechecker::Type::String) | Some(crate::typechecker::Type::Money) => Ok(val),
                 Some(crate::typechecker::Type::Float) => call_float_to_string(ctx, builder, val),
+                Some(crate::typechecker::Type::Secret) => {
+                    let ptr = compile_string_literal(ctx, builder, "[REDACTED]")?;
+                    call_string_from_cstr(ctx, builder, ptr)
+                }
                 _ => {
                     let val_type = builder.func.dfg.value_type(val);
                     if val_type == cranelift::prelude::types::F64 {
@@ -181,6 +190,64 @@ pub fn arg_to_naml_string(
     }
 }
 
+/// Convert a `{:spec}` argument to a NamlString formatted by the runtime
+pub fn arg_to_formatted_string(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    arg: &Expression<'_>,
+    spec: &str,
+) -> Result<Value, CodegenError> {
+    let (runtime_fn, val) = match arg {
+        Expression::Literal(LiteralExpr {
+                                value: Literal::Int(n),
+                                ..
+                            }) => ("naml_format_int", builder.ins().iconst(cranelift::prelude::types::I64, *n)),
+        Expression::Literal(LiteralExpr {
+                                value: Literal::Float(f),
+                                ..
+                            }) => ("naml_format_float", builder.ins().f64const(*f)),
+        Expression::Literal(LiteralExpr {
+                                value: Literal::String(_),
+                                ..
+                            }) => ("naml_format_string", arg_to_naml_string(ctx, builder, arg)?),
+        _ => {
+            let val = compile_expression(ctx, builder, arg)?;
+            match ctx.annotations.get_type(arg.span()) {
+                Some(crate::typechecker::Type::String) | Some(crate::typechecker::Type::Money) => {
+                    ("naml_format_string", val)
+                }
+                Some(crate::typechecker::Type::Secret) => {
+                    let ptr = compile_string_literal(ctx, builder, "[REDACTED]")?;
+                    ("naml_format_string", call_string_from_cstr(ctx, builder, ptr)?)
+                }
+                _ => {
+                    if builder.func.dfg.value_type(val) == cranelift::prelude::types::F64 {
+                        ("naml_format_float", val)
+                    } else {
+                        ("naml_format_int", ensure_i64(builder, val))
+                    }
+                }
+            }
+        }
+    };
+    call_format(ctx, builder, runtime_fn, val, spec)
+}
+
+/// Call `naml_format_int`, `naml_format_float` or `naml_format_string` with a spec
+pub fn call_format(
+    ctx: &mut CompileContext<'_>,
+    builder: &mut FunctionBuilder<'_>,
+    runtime_fn: &str,
+    value: Value,
+    spec: &str,
+) -> Result<Value, CodegenError> {
+    let spec_ptr = compile_string_literal(ctx, builder, spec)?;
+    let spec_val = call_string_from_cstr(ctx, builder, spec_ptr)?;
+    let func_ref = rt_func_ref(ctx, builder, runtime_fn)?;
+    let call = builder.ins().call(func_ref, &[value, spec_val]);
+    Ok(builder.inst_results(call)[0])
+}
+
 pub fn call_string_concat(
     ctx: &mut CompileContext<'_>,
     builder: &mut FunctionBuilder<'_>,
@@ -208,38 +275,27 @@ pub fn build_message_string(
                                }) = &args[0]
     {
         let format_str = ctx.interner.resolve(spur).to_string();
-        if format_str.contains("{}") {
+        let pieces = format_pieces(&format_str);
+        if pieces.iter().any(|p| matches!(p, FormatPiece::Placeholder(_))) {
             let mut result: Option<Value> = None;
             let mut arg_idx = 1;
-            let mut last_end = 0;
-
-            for (start, _) in format_str.match_indices("{}") {
-                if start > last_end {
-                    let literal_part = &format_str[last_end..start];
-                    let ptr = compile_string_literal(ctx, builder, literal_part)?;
-                    let part = call_string_from_cstr(ctx, builder, ptr)?;
-                    result = Some(match result {
-                        Some(acc) => call_string_concat(ctx, builder, acc, part)?,
-                        None => part,
-                    });
-                }
-
-                if arg_idx < args.len() {
-                    let part = arg_to_naml_string(ctx, builder, &args[arg_idx])?;
-                    arg_idx += 1;
-                    result = Some(match result {
-                        Some(acc) => call_string_concat(ctx, builder, acc, part)?,
-                        None => part,
-                    });
-                }
 
-                last_end = start + 2;
-            }
-
-            if last_end < format_str.len() {
-                let remaining = &format_str[last_end..];
-                let ptr = compile_string_literal(ctx, builder, remaining)?;
-                let part = call_string_from_cstr(ctx, builder, ptr)?;
+            for piece in pieces {
+                let part = match piece {
+                    FormatPiece::Literal(literal_part) => {
+                        let ptr = compile_string_literal(ctx, builder, literal_part)?;
+                        call_string_from_cstr(ctx, builder, ptr)?
+                    }
+                    FormatPiece::Placeholder(_) if arg_idx >= args.len() => continue,
+                    FormatPiece::Placeholder(None) => {
+                        arg_idx += 1;
+                        arg_to_naml_string(ctx, builder, &args[arg_idx - 1])?
+                    }
+                    FormatPiece::Placeholder(Some(spec)) => {
+                        arg_idx += 1;
+                        arg_to_formatted_string(ctx, builder, &args[arg_idx - 1], spec)?
+                    }
+                };
                 result = Some(match result {
                     Some(acc) => call_string_concat(ctx, builder, acc, part)?,
                     None => part,
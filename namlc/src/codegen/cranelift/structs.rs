@@ -52,7 +52,7 @@ pub fn call_struct_new(
     Ok(ptr)
 }
 
-pub fn struct_has_heap_fields(struct_defs: &HashMap<lasso::Spur, StructDef>, struct_name: &lasso::Spur) -> bool {
+pub fn struct_has_heap_fields(struct_defs: &indexmap::IndexMap<lasso::Spur, StructDef>, struct_name: &lasso::Spur) -> bool {
     if let Some(def) = struct_defs.get(struct_name) {
         def.field_heap_types.iter().any(|ht| ht.is_some())
     } else {
@@ -0,0 +1,152 @@
+//!
+//! Symbol Names and Symbol Maps
+//!
+//! Both backends name generated code the same way: every naml function,
+//! method, generic instance, lambda and spawn trampoline gets the symbol
+//! `naml.<name>`, where `<name>` is the codegen name (`Point_area` for a
+//! method, `module::helper` for a private module function). Only `main`
+//! keeps its plain name because the AOT entry point must be `main`. The
+//! prefix keeps naml code from clashing with C symbols in linked binaries.
+//!
+//! Each declaration is also recorded with its source location, so a
+//! `.naml-symbols.json` side file can map profiler symbols back to naml
+//! source. JIT maps additionally carry the finalized code address.
+//!
+//! The machine code size of every function is recorded as well. With a
+//! limit set (`--max-code-size`), compilation stops once the total goes
+//! over it and the error lists the largest functions, which is usually
+//! where a generic instantiated for many types shows up.
+//!
+
+use std::path::Path;
+use std::time::Instant;
+
+use cranelift_module::FuncId;
+use serde::Serialize;
+
+use crate::codegen::CodegenError;
+use crate::source::Span;
+use crate::timings::FunctionTime;
+
+use super::JitCompiler;
+
+/// Symbol used for the naml function called `name` in codegen
+pub(crate) fn symbol_name(name: &str) -> String {
+    if name == "main" {
+        name.to_string()
+    } else {
+        format!("naml.{}", name)
+    }
+}
+
+/// One generated function in a symbol map
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub name: String,
+    pub kind: &'static str,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub start: u32,
+    pub end: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<u64>,
+    /// Bytes of machine code
+    pub code_size: u32,
+    #[serde(skip)]
+    pub(crate) func_id: FuncId,
+}
+
+/// Number of functions listed when the code size limit is exceeded
+const LARGEST_SHOWN: usize = 10;
+
+impl<'a> JitCompiler<'a> {
+    /// Record a defined function for the symbol map, along with its codegen
+    /// time since `started` when function times are enabled. Fails when the
+    /// function takes the generated code over the `--max-code-size` limit.
+    pub(crate) fn record_symbol(
+        &mut self,
+        func_id: FuncId,
+        name: &str,
+        kind: &'static str,
+        span: Span,
+        started: Instant,
+    ) -> Result<(), CodegenError> {
+        if let Some(times) = &mut self.function_times {
+            times.push(FunctionTime::new(name, kind, started.elapsed()));
+        }
+        // Still holds the function that was just defined
+        let code_size = self.ctx.compiled_code().map_or(0, |code| code.code_info().total_size);
+        let (line, column) = self.source_info.line_col(span.start);
+        self.symbols.push(SymbolInfo {
+            symbol: symbol_name(name),
+            name: name.to_string(),
+            kind,
+            file: self.source_info.name.to_string(),
+            line,
+            column,
+            start: span.start,
+            end: span.end,
+            address: None,
+            code_size,
+            func_id,
+        });
+
+        self.code_size += code_size as usize;
+        match self.max_code_size {
+            Some(max) if self.code_size > max => Err(CodegenError::JitCompile(format!(
+                "generated code is {} bytes, over the --max-code-size limit of {} bytes\n{}",
+                self.code_size,
+                max,
+                self.code_size_report(LARGEST_SHOWN).trim_end()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Total bytes of machine code generated for naml functions so far
+    pub fn code_size(&self) -> usize {
+        self.code_size
+    }
+
+    /// The `count` functions with the most machine code, largest first
+    pub fn largest_functions(&self, count: usize) -> Vec<&SymbolInfo> {
+        let mut symbols: Vec<&SymbolInfo> = self.symbols.iter().collect();
+        symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.code_size));
+        symbols.truncate(count);
+        symbols
+    }
+
+    /// Text listing of the `count` largest functions
+    pub fn code_size_report(&self, count: usize) -> String {
+        let mut out = format!("Largest functions ({} bytes in {} functions):\n", self.code_size, self.symbols.len());
+        for info in self.largest_functions(count) {
+            out.push_str(&format!("  {:>8} bytes  {} ({}, {}:{})\n", info.code_size, info.name, info.kind, info.file, info.line));
+        }
+        out
+    }
+
+    pub fn symbols(&self) -> &[SymbolInfo] {
+        &self.symbols
+    }
+
+    /// Fill in code addresses once the JIT module has been finalized
+    pub(crate) fn resolve_symbol_addresses(&mut self) {
+        let Some(jit) = self.module.as_jit_mut() else {
+            return;
+        };
+        for info in &mut self.symbols {
+            info.address = Some(jit.get_finalized_function(info.func_id) as u64);
+        }
+    }
+
+    pub fn write_symbol_map(&self, path: &Path) -> Result<(), CodegenError> {
+        let json = serde_json::to_string_pretty(&self.symbols).map_err(|e| {
+            CodegenError::JitCompile(format!("Failed to serialize symbol map: {}", e))
+        })?;
+        std::fs::write(path, json).map_err(|e| {
+            CodegenError::JitCompile(format!("Failed to write symbol map: {}", e))
+        })
+    }
+}
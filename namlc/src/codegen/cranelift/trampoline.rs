@@ -1,13 +1,16 @@
 use std::collections::{HashMap, HashSet};
 use std::panic;
+use std::time::Instant;
 
 use cranelift::prelude::*;
+use indexmap::IndexMap;
 use cranelift_module::{FuncId, Linkage};
 
 use crate::codegen::CodegenError;
 use crate::codegen::cranelift::errors::convert_cranelift_error;
 use crate::codegen::cranelift::runtime::emit_cleanup_all_vars;
 use crate::codegen::cranelift::stmt::compile_statement;
+use crate::codegen::cranelift::symbols::symbol_name;
 use crate::codegen::cranelift::{CompileContext, JitCompiler, SpawnBlockInfo};
 
 impl<'a> JitCompiler<'a> {
@@ -19,10 +22,12 @@ impl<'a> JitCompiler<'a> {
         let mut sig = self.module.make_signature();
         sig.params
             .push(AbiParam::new(cranelift::prelude::types::I64)); // *mut u8 as i64
+        sig.returns
+            .push(AbiParam::new(cranelift::prelude::types::I64)); // task result
 
         let func_id = self
             .module
-            .declare_function(&info.func_name, Linkage::Local, &sig)
+            .declare_function(&symbol_name(&info.func_name), Linkage::Local, &sig)
             .map_err(|e| {
                 CodegenError::JitCompile(format!(
                     "Failed to declare spawn trampoline '{}': {}",
@@ -36,6 +41,7 @@ impl<'a> JitCompiler<'a> {
     }
 
     pub(crate) fn compile_spawn_trampoline(&mut self, info: &SpawnBlockInfo) -> Result<(), CodegenError> {
+        let started = Instant::now();
         let func_id = *self.functions.get(&info.func_name).ok_or_else(|| {
             CodegenError::JitCompile(format!("Trampoline '{}' not declared", info.func_name))
         })?;
@@ -70,7 +76,7 @@ impl<'a> JitCompiler<'a> {
             extern_fns: &self.extern_fns,
             global_vars: &self.global_vars,
             variables: HashMap::new(),
-            var_heap_types: HashMap::new(),
+            var_heap_types: IndexMap::new(),
             var_counter: 0,
             block_terminated: false,
             loop_exit_block: None,
@@ -81,8 +87,10 @@ impl<'a> JitCompiler<'a> {
             lambda_body_to_id: &self.lambda_body_to_id,
             annotations: self.annotations,
             type_substitutions: HashMap::new(),
-            func_return_type: None,
+            func_return_type: Some(cranelift::prelude::types::I64),
             release_mode: self.release_mode,
+            overflow_checks: self.overflow_checks,
+            catch_arithmetic: false,
             unsafe_mode: self.unsafe_mode,
             inline_functions: &self.inline_functions,
             inline_depth: 0,
@@ -90,6 +98,13 @@ impl<'a> JitCompiler<'a> {
             inline_result_var: None,
             borrowed_vars: HashSet::new(),
             reassigned_vars: HashSet::new(),
+            last_use_moves: HashSet::new(),
+            bounds_facts: Vec::new(),
+            hoisted_exprs: HashMap::new(),
+            interned_strings: &mut self.interned_strings,
+            string_objects: HashMap::new(),
+            finally_blocks: Vec::new(),
+            exception_landings: Vec::new(),
             target: self.target,
         };
 
@@ -121,9 +136,11 @@ impl<'a> JitCompiler<'a> {
             }
         }
 
+        // Falling off the end of the block leaves the task's result at 0
         if !ctx.block_terminated {
             emit_cleanup_all_vars(&mut ctx, &mut builder, None)?;
-            builder.ins().return_(&[]);
+            let zero = builder.ins().iconst(cranelift::prelude::types::I64, 0);
+            builder.ins().return_(&[zero]);
         }
 
         builder.finalize();
@@ -134,7 +151,7 @@ impl<'a> JitCompiler<'a> {
         }));
 
         match result {
-            Ok(Ok(())) => {}
+            Ok(Ok(())) => self.record_symbol(func_id, &info.func_name, "spawn", unsafe { &*info.body_ptr }.span, started)?,
             Ok(Err(e)) => {
                 return Err(CodegenError::JitCompile(format!(
                     "Failed to define trampoline '{}': {}",
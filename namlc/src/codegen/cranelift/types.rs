@@ -60,12 +60,19 @@ pub fn tc_type_to_cranelift(ty: &TcType) -> Type {
         TcType::Mutex(_) => types::I64,
         TcType::Rwlock(_) => types::I64,
         TcType::Atomic(_) => types::I64,
+        TcType::Set(_) | TcType::PriorityQueue(_) | TcType::Deque(_) | TcType::Iterator(_) => types::I64,
         TcType::Struct(_) => types::I64,
         TcType::Enum(_) => types::I64,
         TcType::Interface(_) => types::I64,
         TcType::Exception(_) => types::I64,
         TcType::StackFrame => types::I64,
+        TcType::IfaceInfo => types::I64,
+        TcType::FsEvent => types::I64,
         TcType::Json => types::I64,
+        TcType::Secret => types::I64,
+        TcType::Money => types::I64,
+        TcType::StringBuilder => types::I64,
+        TcType::Waitgroup => types::I64,
         TcType::Function(_) => types::I64,
         TcType::TypeVar(_) => types::I64,
         TcType::Generic(_, _) => types::I64,
@@ -9,6 +9,13 @@
 //! 2. JIT compile to native machine code
 //! 3. Execute directly
 //!
+//! Both entry points can also write a symbol map (see `cranelift::SymbolInfo`)
+//! relating generated symbols to naml source, for profilers, and record
+//! codegen times per function into a `Timings` report.
+//! `compile_and_bench` compiles the same way in release mode and times
+//! individual functions instead of running `main`; `compile_and_fuzz`
+//! keeps the debug checks and feeds generated inputs to one function.
+//!
 
 pub mod cranelift;
 
@@ -16,7 +23,10 @@ use lasso::Rodeo;
 use thiserror::Error;
 
 use crate::ast::{CompilationTarget, SourceFile};
+use crate::bench::{self, BenchConfig, BenchStats};
+use crate::fuzz::{self, Corpus, FuzzConfig, FuzzReport, FuzzTarget, InputKind};
 use crate::source::SourceFile as SourceInfo;
+use crate::timings::Timings;
 use crate::typechecker::{ImportedModule, TypeAnnotations};
 
 #[derive(Debug, Error)]
@@ -43,15 +53,110 @@ pub fn compile_and_run(
     release: bool,
     unsafe_mode: bool,
     target: CompilationTarget,
+    symbol_map: Option<&std::path::Path>,
+    overflow_checks: Option<bool>,
+    max_code_size: Option<usize>,
+    timings: Option<&mut Timings>,
 ) -> Result<(), CodegenError> {
     let mut jit = cranelift::JitCompiler::new(interner, annotations, source_info, release, unsafe_mode, target)?;
+    if let Some(enabled) = overflow_checks {
+        jit.set_overflow_checks(enabled);
+    }
+    if let Some(bytes) = max_code_size {
+        jit.set_max_code_size(bytes);
+    }
+    if timings.is_some() {
+        jit.enable_function_times();
+    }
+    let started = std::time::Instant::now();
     for module in imported_modules {
-        jit.compile_module_source(&module.source_text)?;
+        jit.compile_module_source(&module.source_text, &module.file_path)?;
     }
     jit.compile(ast)?;
+    // The report is written before `main` runs, which may never return
+    if let Some(timings) = timings {
+        timings.record_phase("codegen", started.elapsed());
+        timings.functions.extend(jit.take_function_times());
+        timings.phase("finalize", || jit.finalize())?;
+        timings.write_report();
+    }
+    if let Some(path) = symbol_map {
+        jit.finalize()?;
+        jit.write_symbol_map(path)?;
+    }
     jit.run_main()
 }
 
+/// JIT compile with release settings and time each function in `benches`,
+/// calling `report` as each one finishes
+#[allow(clippy::too_many_arguments)]
+pub fn compile_and_bench(
+    ast: &SourceFile<'_>,
+    interner: &Rodeo,
+    annotations: &TypeAnnotations,
+    imported_modules: &[ImportedModule],
+    source_info: &SourceInfo,
+    target: CompilationTarget,
+    benches: &[String],
+    config: &BenchConfig,
+    mut report: impl FnMut(&str, &BenchStats),
+) -> Result<Vec<(String, BenchStats)>, CodegenError> {
+    let mut jit = cranelift::JitCompiler::new(interner, annotations, source_info, true, false, target)?;
+    for module in imported_modules {
+        jit.compile_module_source(&module.source_text, &module.file_path)?;
+    }
+    jit.compile(ast)?;
+    jit.finalize()?;
+
+    let mut results = Vec::with_capacity(benches.len());
+    for name in benches {
+        let func = jit.void_function(name)?;
+        let stats = bench::measure(config, || func(0));
+        report(name, &stats);
+        results.push((name.clone(), stats));
+    }
+    Ok(results)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn compile_and_fuzz(
+    ast: &SourceFile<'_>,
+    interner: &Rodeo,
+    annotations: &TypeAnnotations,
+    imported_modules: &[ImportedModule],
+    source_info: &SourceInfo,
+    target: CompilationTarget,
+    fuzz_target: &FuzzTarget,
+    config: &FuzzConfig,
+    corpus: &mut Corpus,
+    dictionary: Vec<Vec<u8>>,
+) -> Result<FuzzReport, CodegenError> {
+    let mut jit = cranelift::JitCompiler::new(interner, annotations, source_info, false, false, target)?;
+    for module in imported_modules {
+        jit.compile_module_source(&module.source_text, &module.file_path)?;
+    }
+    jit.compile(ast)?;
+    jit.finalize()?;
+
+    let func = jit.unary_function(&fuzz_target.name)?;
+    let input_kind = fuzz_target.input;
+    // Inputs are leaked: each batch runs in a child process that exits after it
+    let execute = |input: &[u8]| unsafe {
+        let arg = match input_kind {
+            InputKind::Bytes => crate::runtime::naml_bytes_from(input.as_ptr(), input.len()) as i64,
+            InputKind::String => {
+                let text = String::from_utf8_lossy(input);
+                crate::runtime::naml_string_new(text.as_ptr(), text.len()) as i64
+            }
+        };
+        func(0, arg);
+        let threw = crate::runtime::naml_exception_check() != 0;
+        crate::runtime::naml_exception_clear();
+        threw
+    };
+    fuzz::fuzz(config, corpus, dictionary, fuzz_target.throws, execute).map_err(CodegenError::Execution)
+}
+
 pub fn compile_to_object(
     ast: &SourceFile<'_>,
     interner: &Rodeo,
@@ -62,15 +167,61 @@ pub fn compile_to_object(
     release: bool,
     unsafe_mode: bool,
     target: CompilationTarget,
+    symbol_map: Option<&std::path::Path>,
+    overflow_checks: Option<bool>,
+    max_code_size: Option<usize>,
+    mut timings: Option<&mut Timings>,
 ) -> Result<(), CodegenError> {
+    // Absolute paths would make the object depend on where the project is
+    // checked out, so file names in stack traces are made relative
+    let root = std::path::absolute(&*source_info.name)
+        .ok()
+        .and_then(|p| p.parent().map(std::path::Path::to_path_buf));
+    let source_info = &SourceInfo::new(
+        reproducible_path(std::path::Path::new(&*source_info.name), root.as_deref()),
+        source_info.source.clone(),
+    );
     let mut compiler = cranelift::JitCompiler::new_aot(
         interner, annotations, source_info, release, unsafe_mode, target,
     )?;
+    if let Some(enabled) = overflow_checks {
+        compiler.set_overflow_checks(enabled);
+    }
+    if let Some(bytes) = max_code_size {
+        compiler.set_max_code_size(bytes);
+    }
+    if timings.is_some() {
+        compiler.enable_function_times();
+    }
+    let started = std::time::Instant::now();
     for module in imported_modules {
-        compiler.compile_module_source(&module.source_text)?;
+        let path = reproducible_path(&module.file_path, root.as_deref());
+        compiler.compile_module_source(&module.source_text, std::path::Path::new(&path))?;
     }
     compiler.compile(ast)?;
-    compiler.emit_object(output)
+    if let Some(timings) = timings.as_deref_mut() {
+        timings.record_phase("codegen", started.elapsed());
+        timings.functions.extend(compiler.take_function_times());
+    }
+    if let Some(path) = symbol_map {
+        compiler.write_symbol_map(path)?;
+    }
+    match timings {
+        Some(timings) => timings.phase("emit", || compiler.emit_object(output)),
+        None => compiler.emit_object(output),
+    }
+}
+
+/// `path` as embedded in an object file: relative paths are kept, absolute
+/// ones are made relative to `root` or else cut down to the file name
+fn reproducible_path(path: &std::path::Path, root: Option<&std::path::Path>) -> String {
+    if path.is_relative() {
+        return path.display().to_string();
+    }
+    match root.and_then(|root| path.strip_prefix(root).ok()) {
+        Some(relative) => relative.display().to_string(),
+        None => path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+    }
 }
 
 #[cfg(test)]
@@ -109,6 +260,10 @@ mod tests {
             false,
             false,
             CompilationTarget::Native,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("AOT compilation failed");
 
@@ -116,4 +271,250 @@ mod tests {
         assert!(metadata.len() > 100, "object file too small: {} bytes", metadata.len());
         std::fs::remove_file(&output).ok();
     }
+
+    #[test]
+    fn test_aot_symbol_map() {
+        let source = "struct P { x: int }\nfn (self: P) get() -> int { return self.x; }\nfn add(a: int, b: int) -> int { return a + b; }\nfn main() {\n    var p: P = P { x: 1 };\n    println(add(p.get(), 2));\n}\n";
+        let source_info = crate::source::SourceFile::new("symbols.nm".to_string(), source.to_string());
+        let (tokens, mut interner) = crate::lexer::tokenize(source);
+        let arena = crate::ast::AstArena::new();
+        let parse_result = crate::parser::parse(&tokens, source, &arena);
+        assert!(parse_result.errors.is_empty(), "parse errors");
+        let type_result = crate::typechecker::check_with_types(&parse_result.ast, &mut interner, None, None);
+        assert!(type_result.errors.is_empty(), "type errors");
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("symbols.o");
+        let map_path = dir.path().join("symbols.naml-symbols.json");
+        compile_to_object(
+            &parse_result.ast,
+            &interner,
+            &type_result.annotations,
+            &type_result.imported_modules,
+            &source_info,
+            &output,
+            false,
+            false,
+            CompilationTarget::Native,
+            Some(&map_path),
+            None,
+            None,
+            None,
+        )
+        .expect("AOT compilation failed");
+
+        let map: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&map_path).unwrap()).unwrap();
+        let entry = |name: &str| {
+            map.as_array()
+                .unwrap()
+                .iter()
+                .find(|s| s["name"] == name)
+                .unwrap_or_else(|| panic!("no symbol for {}", name))
+                .clone()
+        };
+        assert_eq!(entry("main")["symbol"], "main");
+        assert_eq!(entry("add")["symbol"], "naml.add");
+        assert_eq!(entry("add")["line"], 3);
+        assert_eq!(entry("P_get")["kind"], "method");
+        assert_eq!(entry("P_get")["file"], "symbols.nm");
+        assert!(entry("add")["code_size"].as_u64().unwrap() > 0);
+
+        let object = std::fs::read(&output).unwrap();
+        assert!(object.windows(8).any(|w| w == b"naml.add"));
+    }
+
+    #[test]
+    fn test_max_code_size() {
+        let source = "fn id<T>(x: T) -> T { return x; }\nfn main() {\n    println(id(1));\n    println(id(\"a\"));\n}\n";
+        let source_info = crate::source::SourceFile::new("size.nm".to_string(), source.to_string());
+        let (tokens, mut interner) = crate::lexer::tokenize(source);
+        let arena = crate::ast::AstArena::new();
+        let parse_result = crate::parser::parse(&tokens, source, &arena);
+        assert!(parse_result.errors.is_empty(), "parse errors");
+        let type_result = crate::typechecker::check_with_types(&parse_result.ast, &mut interner, None, None);
+        assert!(type_result.errors.is_empty(), "type errors");
+
+        let dir = tempfile::tempdir().unwrap();
+        let build = |max_code_size: Option<usize>| {
+            compile_to_object(
+                &parse_result.ast,
+                &interner,
+                &type_result.annotations,
+                &type_result.imported_modules,
+                &source_info,
+                &dir.path().join("size.o"),
+                false,
+                false,
+                CompilationTarget::Native,
+                None,
+                None,
+                max_code_size,
+                None,
+            )
+        };
+        build(Some(1 << 20)).expect("a small program fits in 1 MiB");
+        let message = build(Some(64)).expect_err("limit of 64 bytes").to_string();
+        assert!(message.contains("over the --max-code-size limit of 64 bytes"), "{}", message);
+        assert!(message.contains("Largest functions"), "{}", message);
+    }
+
+    #[test]
+    fn test_aot_timings() {
+        let source = "struct P { x: int }\nfn (self: P) get() -> int { return self.x; }\nfn main() {\n    var p: P = P { x: 1 };\n    println(p.get());\n}\n";
+        let source_info = crate::source::SourceFile::new("timings.nm".to_string(), source.to_string());
+        let (tokens, mut interner) = crate::lexer::tokenize(source);
+        let arena = crate::ast::AstArena::new();
+        let parse_result = crate::parser::parse(&tokens, source, &arena);
+        assert!(parse_result.errors.is_empty(), "parse errors");
+        let type_result = crate::typechecker::check_with_types(&parse_result.ast, &mut interner, None, None);
+        assert!(type_result.errors.is_empty(), "type errors");
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("timings.o");
+        let mut timings = Timings::new(dir.path().join("timings.naml-timings.json"));
+        compile_to_object(
+            &parse_result.ast,
+            &interner,
+            &type_result.annotations,
+            &type_result.imported_modules,
+            &source_info,
+            &output,
+            false,
+            false,
+            CompilationTarget::Native,
+            None,
+            None,
+            None,
+            Some(&mut timings),
+        )
+        .expect("AOT compilation failed");
+
+        let phases: Vec<&str> = timings.phases.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(phases, ["codegen", "emit"]);
+        let kind = |name: &str| timings.functions.iter().find(|f| f.name == name).map(|f| f.kind);
+        assert_eq!(kind("main"), Some("function"));
+        assert_eq!(kind("P_get"), Some("method"));
+    }
+
+    #[test]
+    fn test_compile_and_bench() {
+        let source = "fn bench_add() {\n    var x: int = 1 + 2;\n}\nfn main() {\n    panic(\"main must not run\");\n}\n";
+        let source_info = crate::source::SourceFile::new("bench.nm".to_string(), source.to_string());
+        let (tokens, mut interner) = crate::lexer::tokenize(source);
+        let arena = crate::ast::AstArena::new();
+        let parse_result = crate::parser::parse(&tokens, source, &arena);
+        assert!(parse_result.errors.is_empty(), "parse errors");
+        let type_result = crate::typechecker::check_with_types(&parse_result.ast, &mut interner, None, None);
+        assert!(type_result.errors.is_empty(), "type errors");
+
+        let config = BenchConfig {
+            warmup_ns: 1_000_000,
+            measure_ns: 2_000_000,
+            samples: 4,
+        };
+        let mut reported = Vec::new();
+        let results = compile_and_bench(
+            &parse_result.ast,
+            &interner,
+            &type_result.annotations,
+            &type_result.imported_modules,
+            &source_info,
+            CompilationTarget::Native,
+            &["bench_add".to_string()],
+            &config,
+            |name, _| reported.push(name.to_string()),
+        )
+        .expect("bench failed");
+        assert_eq!(reported, ["bench_add"]);
+        assert_eq!(results[0].0, "bench_add");
+        assert!(results[0].1.iterations >= 4);
+    }
+
+    #[test]
+    fn test_compile_and_fuzz() {
+        let source = "use std::strings::{has, starts_with};\nexception BadInput {\n    position: int,\n}\nfn decode(text: string) throws BadInput {\n    if (!starts_with(text, \"v\")) {\n        throw BadInput(\"missing version\");\n    }\n    if (has(text, \"x\")) {\n        panic(\"unsupported\");\n    }\n}\nfn main() {}\n";
+        let source_info = crate::source::SourceFile::new("fuzz.nm".to_string(), source.to_string());
+        let (tokens, mut interner) = crate::lexer::tokenize(source);
+        let arena = crate::ast::AstArena::new();
+        let parse_result = crate::parser::parse(&tokens, source, &arena);
+        assert!(parse_result.errors.is_empty(), "parse errors");
+        let type_result = crate::typechecker::check_with_types(&parse_result.ast, &mut interner, None, None);
+        assert!(type_result.errors.is_empty(), "type errors: {:?}", type_result.errors);
+
+        let target = FuzzTarget::find(&parse_result.ast, &interner, "decode").unwrap().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mut corpus = Corpus::load(dir.path()).unwrap();
+        let config = FuzzConfig {
+            runs: 20_000,
+            seed: 1,
+            ..FuzzConfig::default()
+        };
+        let report = compile_and_fuzz(
+            &parse_result.ast,
+            &interner,
+            &type_result.annotations,
+            &type_result.imported_modules,
+            &source_info,
+            CompilationTarget::Native,
+            &target,
+            &config,
+            &mut corpus,
+            fuzz::dictionary(&tokens, &interner),
+        )
+        .expect("fuzz failed");
+        let failure = report.failure.expect("no crash found");
+        assert_eq!(failure.input, b"vx");
+        assert_eq!(failure.outcome, fuzz::Outcome::Crashed("killed by SIGABRT".to_string()));
+        assert!(report.threw > 0 && report.new_corpus > 0);
+    }
+    #[test]
+    fn test_aot_object_is_reproducible() {
+        let source = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/aot/generics.nm")).unwrap();
+        let source = source + "\nstruct Job { name: string, tags: [string] }\nexception Failed { job: string }\nfn run(j: Job) -> int throws Failed {\n    var n: int = count(j.tags);\n    var f: fn(int) -> int = fn (x: int) -> int { return x + n; };\n    return f(1);\n}\n";
+        let build = || {
+            // Absolute path: it must not end up in the object
+            let name = std::env::temp_dir().join("nested").join("repro.nm").display().to_string();
+            let source_info = crate::source::SourceFile::new(name, source.clone());
+            let (tokens, mut interner) = crate::lexer::tokenize(&source);
+            let arena = crate::ast::AstArena::new();
+            let parse_result = crate::parser::parse(&tokens, &source, &arena);
+            assert!(parse_result.errors.is_empty(), "parse errors");
+            let type_result = crate::typechecker::check_with_types(&parse_result.ast, &mut interner, None, None);
+            assert!(type_result.errors.is_empty(), "type errors: {:?}", type_result.errors);
+            let dir = tempfile::tempdir().unwrap();
+            let output = dir.path().join("repro.o");
+            compile_to_object(
+                &parse_result.ast,
+                &interner,
+                &type_result.annotations,
+                &type_result.imported_modules,
+                &source_info,
+                &output,
+                false,
+                false,
+                CompilationTarget::Native,
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("AOT compilation failed");
+            std::fs::read(&output).unwrap()
+        };
+        let first = build();
+        for _ in 0..3 {
+            assert!(build() == first, "object differs between builds");
+        }
+        let temp = std::env::temp_dir().display().to_string();
+        assert!(!first.windows(temp.len()).any(|w| w == temp.as_bytes()), "absolute path embedded");
+    }
+
+    #[test]
+    fn test_reproducible_path() {
+        let root = std::path::Path::new("/work/app");
+        assert_eq!(reproducible_path(std::path::Path::new("src/main.nm"), Some(root)), "src/main.nm");
+        assert_eq!(reproducible_path(std::path::Path::new("/work/app/lib/util.nm"), Some(root)), "lib/util.nm");
+        assert_eq!(reproducible_path(std::path::Path::new("/home/me/.naml/pkg/json.nm"), Some(root)), "json.nm");
+    }
 }
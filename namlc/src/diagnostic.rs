@@ -3,28 +3,53 @@
 //!
 //! This module provides error reporting with source context using miette.
 //! Errors display line numbers, column positions, and source code snippets.
+//! Type errors also show their error code, a "did you mean" hint when the
+//! checker found a close match, and secondary labels at related definitions.
+//! Type warnings are rendered the same way with warning severity, and lints
+//! from custom lint passes with the severity the pass chose.
+//!
+//! A `NamlDiagnostic` holds the data of one diagnostic; the reporter decides
+//! how to render it. `MessageFormat::Human` prints miette reports to stderr,
+//! `MessageFormat::Json` prints one JSON object per line to stdout with the
+//! code, severity, message, spans (byte offsets plus 1-based line/column),
+//! help text and suggested replacements, for CI annotations and editors.
+//! Replacements that `naml fix` can apply also carry the span they replace.
 //!
 //! Usage:
 //!   let reporter = DiagnosticReporter::new(&source_file);
 //!   reporter.report_parse_errors(&errors);
 //!   reporter.report_type_errors(&errors);
+//!   reporter.report_type_warnings(&warnings);
+//!   reporter.report_lints(&lints);
+//!
+//!   let reporter = DiagnosticReporter::with_format(&source_file, MessageFormat::Json);
 //!
 
-use miette::{Diagnostic, LabeledSpan, NamedSource, Report, SourceSpan};
+use miette::{Diagnostic, LabeledSpan, NamedSource, Report, Severity, SourceSpan};
+use serde_json::{json, Value};
 use thiserror::Error;
 
+use crate::lint::{Lint, LintLevel};
 use crate::parser::ParseError;
 use crate::source::SourceFile;
-use crate::typechecker::TypeError;
+use crate::typechecker::{TypeError, TypeWarning};
 
 #[derive(Debug, Error)]
 #[error("{message}")]
 pub struct NamlDiagnostic {
     message: String,
+    /// The message without its location, for structured output
+    summary: String,
     src: NamedSource<String>,
     span: SourceSpan,
     label: String,
     help_text: Option<String>,
+    code: Option<&'static str>,
+    notes: Vec<(SourceSpan, String)>,
+    severity: Severity,
+    suggestion: Option<String>,
+    /// Source the suggestion replaces, when it is not the primary span
+    suggestion_span: Option<SourceSpan>,
 }
 
 impl Diagnostic for NamlDiagnostic {
@@ -32,11 +57,22 @@ impl Diagnostic for NamlDiagnostic {
         Some(&self.src)
     }
 
+    fn severity(&self) -> Option<Severity> {
+        Some(self.severity)
+    }
+
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.code
+            .map(|code| Box::new(code) as Box<dyn std::fmt::Display>)
+    }
+
     fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
-        Some(Box::new(std::iter::once(LabeledSpan::new_primary_with_span(
-            Some(self.label.clone()),
-            self.span,
-        ))))
+        let primary = LabeledSpan::new_primary_with_span(Some(self.label.clone()), self.span);
+        let notes = self
+            .notes
+            .iter()
+            .map(|(span, label)| LabeledSpan::new_with_span(Some(label.clone()), *span));
+        Some(Box::new(std::iter::once(primary).chain(notes)))
     }
 
     fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
@@ -53,10 +89,16 @@ impl NamlDiagnostic {
 
         Self {
             message: format!("parse error at {}:{}", line, col),
+            summary: err.message.clone(),
             src: NamedSource::new(&source.name, source.source.to_string()),
             span: (span.start as usize, (span.end - span.start) as usize).into(),
             label: err.message.clone(),
             help_text: None,
+            code: None,
+            notes: Vec::new(),
+            severity: Severity::Error,
+            suggestion: None,
+            suggestion_span: None,
         }
     }
 
@@ -64,15 +106,150 @@ impl NamlDiagnostic {
         let span = err.span();
         let (line, col) = source.line_col(span.start);
         let (message, label, help) = type_error_details(err);
+        let suggestion = err.suggestion().map(str::to_string);
+        let help = match err.suggestion() {
+            Some(name) => Some(format!("did you mean '{}'?", name)),
+            None => help,
+        };
+        let notes = err
+            .notes()
+            .into_iter()
+            .map(|(note, label)| {
+                let note_span = (note.start as usize, (note.end - note.start) as usize).into();
+                (note_span, label)
+            })
+            .collect();
 
         Self {
             message: format!("{} at {}:{}", message, line, col),
+            summary: message,
+            src: NamedSource::new(&source.name, source.source.to_string()),
+            span: (span.start as usize, (span.end - span.start) as usize).into(),
+            label,
+            help_text: help,
+            code: Some(err.code()),
+            notes,
+            severity: Severity::Error,
+            suggestion,
+            suggestion_span: None,
+        }
+    }
+
+    pub fn from_type_warning(warning: &TypeWarning, source: &SourceFile) -> Self {
+        let span = warning.span();
+        let (line, col) = source.line_col(span.start);
+        let (label, help) = match warning {
+            TypeWarning::Deprecated { note, replacement, .. } => {
+                let help = match (note, replacement) {
+                    (Some(note), Some(new)) => Some(format!("{}; use `{}` instead", note, new)),
+                    (None, Some(new)) => Some(format!("use `{}` instead", new)),
+                    (note, None) => note.clone(),
+                };
+                ("deprecated".to_string(), help)
+            }
+            TypeWarning::FloatMapKey { .. } => (
+                "float-keyed map".to_string(),
+                Some("-0.0 and 0.0 are the same key, and storing a NaN key throws ArithmeticError".to_string()),
+            ),
+            TypeWarning::UnusedImport { .. } => (
+                "never used".to_string(),
+                Some("remove the import, or run `naml fix`".to_string()),
+            ),
+        };
+        let fix = warning.fix();
+
+        Self {
+            message: format!("{} at {}:{}", warning, line, col),
+            summary: warning.to_string(),
             src: NamedSource::new(&source.name, source.source.to_string()),
             span: (span.start as usize, (span.end - span.start) as usize).into(),
             label,
             help_text: help,
+            code: None,
+            notes: Vec::new(),
+            severity: Severity::Warning,
+            suggestion: fix.as_ref().map(|f| f.replacement.clone()),
+            suggestion_span: fix
+                .map(|f| (f.span.start as usize, (f.span.end - f.span.start) as usize).into()),
+        }
+    }
+
+    pub fn from_lint(lint: &Lint, source: &SourceFile) -> Self {
+        let (line, col) = source.line_col(lint.span.start);
+        let severity = match lint.level {
+            LintLevel::Warning => Severity::Warning,
+            LintLevel::Error => Severity::Error,
+        };
+
+        Self {
+            message: format!("{} at {}:{}", lint.message, line, col),
+            summary: lint.message.clone(),
+            src: NamedSource::new(&source.name, source.source.to_string()),
+            span: (lint.span.start as usize, (lint.span.end - lint.span.start) as usize).into(),
+            label: format!("reported by lint '{}'", lint.pass),
+            help_text: None,
+            code: None,
+            notes: Vec::new(),
+            severity,
+            suggestion: None,
+            suggestion_span: None,
         }
     }
+
+    /// The diagnostic as a JSON object, resolving spans against `source`
+    pub fn to_json(&self, source: &SourceFile) -> Value {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Advice => "help",
+        };
+        let primary = json_span(source, self.span, &self.label, true);
+        let spans: Vec<Value> = std::iter::once(primary)
+            .chain(
+                self.notes
+                    .iter()
+                    .map(|(span, label)| json_span(source, *span, label, false)),
+            )
+            .collect();
+        let suggestions: Vec<Value> = self
+            .suggestion
+            .iter()
+            .map(|replacement| match self.suggestion_span {
+                Some(span) => json!({
+                    "replacement": replacement,
+                    "span": json_span(source, span, "", false),
+                }),
+                None => json!({ "replacement": replacement }),
+            })
+            .collect();
+
+        json!({
+            "file": source.name.as_ref(),
+            "severity": severity,
+            "code": self.code,
+            "message": self.summary,
+            "spans": spans,
+            "help": self.help_text,
+            "suggestions": suggestions,
+        })
+    }
+}
+
+fn json_span(source: &SourceFile, span: SourceSpan, label: &str, primary: bool) -> Value {
+    let start = span.offset();
+    let end = start + span.len();
+    let (line, column) = source.line_col(start as u32);
+    let (end_line, end_column) = source.line_col(end as u32);
+    json!({
+        "start": start,
+        "end": end,
+        "line": line,
+        "column": column,
+        "end_line": end_line,
+        "end_column": end_column,
+        "label": label,
+        "primary": primary,
+    })
 }
 
 fn type_error_details(err: &TypeError) -> (String, String, Option<String>) {
@@ -112,10 +289,15 @@ fn type_error_details(err: &TypeError) -> (String, String, Option<String>) {
             "already defined".to_string(),
             Some("rename or remove one of the definitions".to_string()),
         ),
-        TypeError::DuplicateImport { name, .. } => (
-            format!("duplicate import of '{}'", name),
-            "already imported".to_string(),
-            Some("use qualified names like 'hex::encode' and 'base64::encode'".to_string()),
+        TypeError::DuplicateImport { name, first, second, .. } => (
+            format!("duplicate import of '{}': imported from both '{}' and '{}'", name, first, second),
+            format!("already imported from '{}'", first),
+            Some(format!(
+                "import one under another name, e.g. '{} as {}_{}', or use qualified names",
+                name,
+                last_segment(second),
+                name
+            )),
         ),
         TypeError::InvalidOperation { op, ty, .. } => (
             format!("invalid operation '{}' on type '{}'", op, ty),
@@ -246,38 +428,98 @@ fn type_error_details(err: &TypeError) -> (String, String, Option<String>) {
             "remove `try`".to_string(),
             Some("use either `try expr ?? default` or `expr catch e { handler }`".to_string()),
         ),
-        TypeError::AmbiguousFunction { name, .. } => (
-            format!("ambiguous function '{}': imported from multiple modules", name),
+        TypeError::AmbiguousFunction { name, modules, .. } => (
+            format!(
+                "ambiguous function '{}': imported from {}",
+                name,
+                modules.iter().map(|m| format!("'{}'", m)).collect::<Vec<_>>().join(" and ")
+            ),
             "ambiguous".to_string(),
-            Some(format!("use a qualified name like 'arrays::{}' or 'maps::{}'", name, name)),
+            Some(format!(
+                "use a qualified name like {}, or import it explicitly",
+                modules
+                    .iter()
+                    .map(|m| format!("'{}::{}'", last_segment(m), name))
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            )),
         ),
         TypeError::PackageError { package, reason, .. } => (
             format!("package error '{}': {}", package, reason),
             "package error".to_string(),
             Some("run `naml pkg get` to download dependencies".to_string()),
         ),
+        TypeError::ConflictingDefaultMethod { struct_name, method_name, first, second, .. } => (
+            format!(
+                "struct '{}' inherits conflicting default methods '{}' from '{}' and '{}'",
+                struct_name, method_name, first, second
+            ),
+            format!("ambiguous method '{}'", method_name),
+            Some(format!("define '{}' on struct '{}' to choose one", method_name, struct_name)),
+        ),
+        TypeError::ImportCycle { cycle, .. } => (
+            format!("import cycle: {}", cycle),
+            "import leads to a cycle".to_string(),
+            Some("move the shared items into a module both can import".to_string()),
+        ),
+        TypeError::ConstEval { function, reason, .. } => (
+            format!("cannot evaluate '{}' at compile time: {}", function, reason),
+            "evaluated here".to_string(),
+            Some("const fn calls with constant arguments run while compiling".to_string()),
+        ),
+        TypeError::NotConst { what, .. } => (
+            format!("{} is not allowed in a const fn", what),
+            "not evaluable at compile time".to_string(),
+            Some("remove `const` from the function, or compute this another way".to_string()),
+        ),
+        TypeError::InvalidAttribute { name, reason, .. } => (
+            format!("invalid attribute '@{}': {}", name, reason),
+            "attribute used here".to_string(),
+            None,
+        ),
     }
 }
 
+/// The last segment of a module path, as used in qualified names
+fn last_segment(module: &str) -> &str {
+    module.rsplit("::").next().unwrap_or(module)
+}
+
+/// How the reporter renders diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageFormat {
+    #[default]
+    Human,
+    Json,
+}
+
 pub struct DiagnosticReporter<'a> {
     source: &'a SourceFile,
+    format: MessageFormat,
 }
 
 impl<'a> DiagnosticReporter<'a> {
     pub fn new(source: &'a SourceFile) -> Self {
-        Self { source }
+        Self::with_format(source, MessageFormat::Human)
+    }
+
+    pub fn with_format(source: &'a SourceFile, format: MessageFormat) -> Self {
+        Self { source, format }
+    }
+
+    pub fn emit(&self, diag: NamlDiagnostic) {
+        match self.format {
+            MessageFormat::Human => eprintln!("{:?}", Report::new(diag)),
+            MessageFormat::Json => println!("{}", diag.to_json(self.source)),
+        }
     }
 
     pub fn report_parse_error(&self, err: &ParseError) {
-        let diag = NamlDiagnostic::from_parse_error(err, self.source);
-        let report = Report::new(diag);
-        eprintln!("{:?}", report);
+        self.emit(NamlDiagnostic::from_parse_error(err, self.source));
     }
 
     pub fn report_type_error(&self, err: &TypeError) {
-        let diag = NamlDiagnostic::from_type_error(err, self.source);
-        let report = Report::new(diag);
-        eprintln!("{:?}", report);
+        self.emit(NamlDiagnostic::from_type_error(err, self.source));
     }
 
     pub fn report_parse_errors(&self, errors: &[ParseError]) {
@@ -292,6 +534,18 @@ impl<'a> DiagnosticReporter<'a> {
         }
     }
 
+    pub fn report_type_warnings(&self, warnings: &[TypeWarning]) {
+        for warning in warnings {
+            self.emit(NamlDiagnostic::from_type_warning(warning, self.source));
+        }
+    }
+
+    pub fn report_lints(&self, lints: &[Lint]) {
+        for lint in lints {
+            self.emit(NamlDiagnostic::from_lint(lint, self.source));
+        }
+    }
+
     pub fn has_errors(parse_errors: &[ParseError], type_errors: &[TypeError]) -> bool {
         !parse_errors.is_empty() || !type_errors.is_empty()
     }
@@ -326,5 +580,62 @@ mod tests {
         let diag = NamlDiagnostic::from_type_error(&err, &source);
         assert!(diag.message.contains("type mismatch"));
         assert!(diag.help_text.is_some());
+        assert_eq!(diag.code, Some("E0001"));
+    }
+
+    #[test]
+    fn test_diagnostic_suggestion_and_note() {
+        let source = SourceFile::new("test.nm", "struct P { x: int } fn f(p: P) { p.y; }");
+        let err = TypeError::UndefinedField {
+            ty: "P".to_string(),
+            field: "y".to_string(),
+            span: Span::new(33, 36, 0),
+            suggestion: Some("x".to_string()),
+            defined_at: Some(Span::new(0, 19, 0)),
+        };
+
+        let diag = NamlDiagnostic::from_type_error(&err, &source);
+        assert_eq!(diag.help_text.as_deref(), Some("did you mean 'x'?"));
+        assert_eq!(diag.notes.len(), 1);
+        assert_eq!(diag.labels().unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_diagnostic_to_json() {
+        let source = SourceFile::new("test.nm", "struct P { x: int }\nfn f(p: P) { p.y; }");
+        let err = TypeError::UndefinedField {
+            ty: "P".to_string(),
+            field: "y".to_string(),
+            span: Span::new(33, 36, 0),
+            suggestion: Some("x".to_string()),
+            defined_at: Some(Span::new(0, 19, 0)),
+        };
+
+        let json = NamlDiagnostic::from_type_error(&err, &source).to_json(&source);
+        assert_eq!(json["file"], "test.nm");
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["code"], err.code());
+        assert!(!json["message"].as_str().unwrap().contains(" at "));
+        assert_eq!(json["spans"][0]["primary"], true);
+        assert_eq!(json["spans"][0]["line"], 2);
+        assert_eq!(json["spans"][0]["column"], 14);
+        assert_eq!(json["spans"][1]["line"], 1);
+        assert_eq!(json["suggestions"][0]["replacement"], "x");
+    }
+
+    #[test]
+    fn test_warning_fix_to_json() {
+        let source = SourceFile::new("test.nm", "use std::io::{read_line};\nfn main() {}");
+        let warning = TypeWarning::UnusedImport {
+            name: "read_line".to_string(),
+            span: Span::new(14, 23, 0),
+            removal: Span::new(0, 25, 0),
+        };
+
+        let json = NamlDiagnostic::from_type_warning(&warning, &source).to_json(&source);
+        assert_eq!(json["severity"], "warning");
+        assert_eq!(json["suggestions"][0]["replacement"], "");
+        assert_eq!(json["suggestions"][0]["span"]["start"], 0);
+        assert_eq!(json["suggestions"][0]["span"]["end"], 25);
     }
 }
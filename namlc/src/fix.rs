@@ -0,0 +1,298 @@
+//!
+//! Fix Module - Applying Machine-Applicable Suggestions
+//!
+//! `naml fix` rewrites a file so that the diagnostics with an unambiguous
+//! fix go away, without touching anything else in the source:
+//!
+//! - Missing semicolons: when the parser expected a `;` and the next token
+//!   starts a new line, one is inserted at the end of the previous line.
+//!   The insertion is kept only if parsing then gets further than before.
+//! - Unused imports: the entry (or the whole `use` item) is deleted
+//! - Deprecated calls: `@deprecated("note", new_name)` functions and
+//!   methods are renamed to `new_name` at each call site
+//!
+//! Warning fixes come from `TypeWarning::fix` and are applied through the
+//! `Rewriter`, in rounds, until the checker has nothing left to fix. A file
+//! that does not type check is left alone, and so is a file the fixes would
+//! break: every round is checked again before its result is accepted.
+//!
+//! Usage:
+//!   let outcome = fix_source(&source, Some(dir), None)?;
+//!   std::fs::write(path, &outcome.source)?;
+//!   print!("{}", unified_diff("main.nm", &source, &outcome.source));
+//!
+
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::ast::{AstArena, Item};
+use crate::lexer::{tokenize, tokenize_with_trivia};
+use crate::parser::{parse, ParseError};
+use crate::rewrite::{RewriteError, Rewriter, SyntaxFile};
+use crate::source::{SourceFile, Span, Spanned};
+use crate::typechecker::{check_with_types, Fix, TypeError};
+
+/// Upper bound on semicolons inserted into one file
+const MAX_SEMICOLONS: usize = 100;
+
+/// Upper bound on rounds of warning fixes (a replacement may itself be
+/// deprecated)
+const MAX_ROUNDS: usize = 10;
+
+/// A fix that was applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedFix {
+    /// 1-based line of the fixed code
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FixOutcome {
+    /// The fixed source; equal to the input when nothing applied
+    pub source: String,
+    pub applied: Vec<AppliedFix>,
+}
+
+impl FixOutcome {
+    pub fn is_unchanged(&self) -> bool {
+        self.applied.is_empty()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FixError {
+    /// Parse errors remain after inserting the semicolons that were
+    /// unambiguous; `text` is the source the errors point into
+    #[error("the file does not parse")]
+    Parse {
+        text: String,
+        errors: Vec<ParseError>,
+    },
+
+    #[error("the file has type errors")]
+    Type {
+        text: String,
+        errors: Vec<TypeError>,
+    },
+
+    #[error("applying the fixes would introduce errors: {0}")]
+    Regressed(String),
+
+    #[error(transparent)]
+    Rewrite(#[from] RewriteError),
+}
+
+/// Apply every machine-applicable fix to `source`. `source_dir` and
+/// `package_manager` resolve local and package imports, as for `check`.
+pub fn fix_source(
+    source: &str,
+    source_dir: Option<&Path>,
+    package_manager: Option<&naml_pkg::PackageManager>,
+) -> Result<FixOutcome, FixError> {
+    let mut applied = Vec::new();
+    let mut text = insert_semicolons(source, &mut applied)?;
+
+    for round in 0..MAX_ROUNDS {
+        let (tokens, mut interner) = tokenize(&text);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, &text, &arena);
+        if !parsed.errors.is_empty() {
+            return Err(FixError::Regressed(parsed.errors[0].message.clone()));
+        }
+        let result = check_with_types(
+            &parsed.ast,
+            &mut interner,
+            source_dir.map(Path::to_path_buf),
+            package_manager,
+        );
+        if !result.errors.is_empty() {
+            if round > 0 {
+                return Err(FixError::Regressed(result.errors[0].to_string()));
+            }
+            return Err(FixError::Type {
+                text,
+                errors: result.errors,
+            });
+        }
+
+        let mut fixes: Vec<(Fix, String)> = result
+            .warnings
+            .iter()
+            .filter_map(|w| w.fix().map(|fix| (fix, w.to_string())))
+            .collect();
+        fixes.sort_by_key(|(fix, _)| (fix.span.start, fix.span.end));
+        // One `use` item removal is reported once per unused entry
+        fixes.dedup_by(|a, b| a.0 == b.0);
+        if fixes.is_empty() {
+            break;
+        }
+
+        let use_spans: Vec<Span> = parsed
+            .ast
+            .items
+            .iter()
+            .filter(|item| matches!(item, Item::Use(_)))
+            .map(|item| item.span())
+            .collect();
+        let file = SyntaxFile::parse(&text).map_err(|errors| FixError::Parse {
+            text: text.clone(),
+            errors,
+        })?;
+        let lines = SourceFile::new("", text.as_str());
+        let mut rewriter = Rewriter::new(&file);
+        let mut end = 0;
+        for (fix, message) in fixes {
+            // Overlapping fixes wait for the next round
+            if fix.span.start < end {
+                continue;
+            }
+            end = fix.span.end;
+            let whole_item = use_spans.contains(&fix.span) && fix.replacement.is_empty();
+            match file.items().iter().position(|item| item.span == fix.span) {
+                Some(index) if whole_item => rewriter.remove_item(index)?,
+                _ => rewriter.replace_span(fix.span, fix.replacement),
+            }
+            applied.push(AppliedFix {
+                line: lines.line_col(fix.span.start).0,
+                message,
+            });
+        }
+        text = rewriter.apply()?;
+    }
+
+    Ok(FixOutcome {
+        source: text,
+        applied,
+    })
+}
+
+/// Insert the semicolons the parser asks for at line ends, one at a time
+fn insert_semicolons(source: &str, applied: &mut Vec<AppliedFix>) -> Result<String, FixError> {
+    let mut text = source.to_string();
+    let mut errors = parse_errors(&text);
+    for _ in 0..MAX_SEMICOLONS {
+        let Some(first) = errors.first() else {
+            break;
+        };
+        let Some(offset) = missing_semicolon(&text, first) else {
+            break;
+        };
+        let mut candidate = text.clone();
+        candidate.insert(offset as usize, ';');
+        let next = parse_errors(&candidate);
+        // Accept the semicolon only if the parser now gets past the point
+        // where it stopped (shifted by the inserted byte)
+        if next.first().is_some_and(|e| e.span.start <= first.span.start + 1) {
+            break;
+        }
+        let (line, _) = SourceFile::new("", text.as_str()).line_col(offset);
+        applied.push(AppliedFix {
+            line,
+            message: "missing semicolon".to_string(),
+        });
+        text = candidate;
+        errors = next;
+    }
+
+    if errors.is_empty() {
+        Ok(text)
+    } else {
+        Err(FixError::Parse { text, errors })
+    }
+}
+
+fn parse_errors(source: &str) -> Vec<ParseError> {
+    let (tokens, _) = tokenize(source);
+    let arena = AstArena::new();
+    parse(&tokens, source, &arena).errors
+}
+
+/// Where to insert the semicolon `error` asks for: right after the last
+/// token before it, when a line break separates the two
+fn missing_semicolon(source: &str, error: &ParseError) -> Option<u32> {
+    if error.message != "expected Semicolon" {
+        return None;
+    }
+    let tokens = tokenize_with_trivia(source);
+    let previous = tokens
+        .iter()
+        .rev()
+        .find(|t| !t.is_trivia() && t.span.end <= error.span.start)?;
+    let gap = source.get(previous.span.end as usize..error.span.start as usize)?;
+    gap.contains('\n').then_some(previous.span.end)
+}
+
+/// A unified diff from `old` to `new`, labelled with `path`
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(source: &str) -> FixOutcome {
+        fix_source(source, None, None).unwrap()
+    }
+
+    #[test]
+    fn test_missing_semicolons() {
+        let outcome = fix("fn main() {\n    var x: int = 1\n    var y: int = x + 1 // two\n    println(y);\n}\n");
+        assert_eq!(
+            outcome.source,
+            "fn main() {\n    var x: int = 1;\n    var y: int = x + 1; // two\n    println(y);\n}\n"
+        );
+        assert_eq!(outcome.applied.len(), 2);
+        assert_eq!(outcome.applied[0].line, 2);
+    }
+
+    #[test]
+    fn test_unused_imports() {
+        let source = "use std::strings::{upper, lower, ltrim};\nuse std::io::read_line;\n\nfn main() {\n    println(lower(\"A\"));\n}\n";
+        let outcome = fix(source);
+        assert_eq!(
+            outcome.source,
+            "use std::strings::{lower};\n\nfn main() {\n    println(lower(\"A\"));\n}\n"
+        );
+        let messages: Vec<_> = outcome.applied.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["unused import 'upper'", "unused import 'ltrim'", "unused import 'read_line'"]
+        );
+    }
+
+    #[test]
+    fn test_deprecated_rename() {
+        let source = "@deprecated(\"renamed\", total)\nfn sum(a: int) -> int { return a; }\nfn total(a: int) -> int { return a; }\nfn main() {\n    var n: int = sum(1) + sum(2);\n}\n";
+        let outcome = fix(source);
+        assert!(outcome.source.contains("var n: int = total(1) + total(2);"), "{}", outcome.source);
+        assert_eq!(outcome.applied.len(), 2);
+        assert!(fix(&outcome.source).is_unchanged());
+    }
+
+    #[test]
+    fn test_refuses_broken_files() {
+        assert!(matches!(
+            fix_source("fn main() { var x: int = true; }", None, None),
+            Err(FixError::Type { .. })
+        ));
+        // Not at a line end, so the semicolon is ambiguous
+        assert!(matches!(
+            fix_source("fn main() { var x: int = 1 var y: int = 2; }", None, None),
+            Err(FixError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let diff = unified_diff("main.nm", "a\nb\n", "a\nc\n");
+        assert!(diff.starts_with("--- a/main.nm\n+++ b/main.nm\n"), "{}", diff);
+        assert!(diff.contains("-b\n+c\n"), "{}", diff);
+    }
+}
@@ -0,0 +1,659 @@
+//!
+//! Fuzz Module - Robustness Testing for `naml fuzz`
+//!
+//! A fuzz target is a function with a single `bytes` or `string` parameter,
+//! such as a config loader or a protocol decoder. `naml fuzz` calls it with
+//! generated inputs until one makes it fail:
+//!
+//! - Inputs are mutations of the corpus (the files in the corpus directory
+//!   plus new inputs the target accepted) or random bytes. Mutations flip,
+//!   insert and delete bytes, splice corpus entries and insert the string
+//!   literals of the target's file, so a parser sees its own keywords and
+//!   delimiters rather than only noise.
+//! - Inputs run in batches in a forked child, because a naml panic aborts
+//!   the process. The child records its progress in shared memory, so when
+//!   it dies the parent knows which input did it.
+//! - A failure is a crash (a panic, a signal, an exit), an input running
+//!   longer than the timeout, or, with `fail_on_throw`, an exception
+//!   escaping the target. Otherwise exceptions are how a target rejects
+//!   input, and a target that declares `throws` grows the corpus with the
+//!   inputs it accepted.
+//! - A failing input is minimized by deleting chunks of it for as long as
+//!   it still fails the same way (timeouts are kept as they are, since
+//!   each attempt would take the whole timeout).
+//!
+//! Fuzzing stops at the first failure. Forking needs a Unix platform.
+//!
+//! Usage:
+//!   let target = FuzzTarget::find(&ast, &interner, "parse_config")?.unwrap();
+//!   let mut corpus = Corpus::load(&dir)?;
+//!   let report = fuzz(&config, &mut corpus, dictionary, target.throws, |input| call(input))?;
+//!
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use lasso::Rodeo;
+
+use crate::ast::{Item, NamlType, SourceFile};
+use crate::lexer::{Token, TokenKind};
+
+/// Inputs run per forked child
+const BATCH_SIZE: usize = 1000;
+
+/// Upper bound on runs spent minimizing one input
+const MAX_MINIMIZE_RUNS: usize = 2000;
+
+/// Upper bound on corpus entries added during one run
+const MAX_NEW_CORPUS: usize = 1000;
+
+/// Bytes that tend to matter to parsers: boundaries, digits, delimiters
+const INTERESTING: &[u8] = &[
+    0, 1, 0x7f, 0x80, 0xff, b'0', b'9', b'-', b' ', b'\n', b'"', b'\\', b'{', b'}', b'[', b']', b':', b',', b'=',
+];
+
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    /// Inputs to try, seeds included
+    pub runs: u64,
+    pub max_len: usize,
+    pub timeout_ms: u64,
+    pub seed: u64,
+    pub fail_on_throw: bool,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            runs: 10_000,
+            max_len: 4096,
+            timeout_ms: 1000,
+            seed: 0,
+            fail_on_throw: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    Bytes,
+    /// Inputs are converted with `String::from_utf8_lossy`
+    String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzTarget {
+    pub name: String,
+    pub input: InputKind,
+    /// Whether the function declares `throws`
+    pub throws: bool,
+}
+
+impl FuzzTarget {
+    /// The function `name` in `file`; `None` if the file does not define it,
+    /// an error if it does but cannot be fuzzed
+    pub fn find(file: &SourceFile, interner: &Rodeo, name: &str) -> Result<Option<FuzzTarget>, String> {
+        let Some(f) = file.items.iter().find_map(|item| match item {
+            Item::Function(f) if f.receiver.is_none() && interner.resolve(&f.name.symbol) == name => Some(f),
+            _ => None,
+        }) else {
+            return Ok(None);
+        };
+        let input = match f.params.as_slice() {
+            [param] if param.ty == NamlType::Bytes => InputKind::Bytes,
+            [param] if param.ty == NamlType::String => InputKind::String,
+            _ => return Err(format!("'{}' must take a single bytes or string parameter", name)),
+        };
+        if !f.generics.is_empty() || f.body.is_none() {
+            return Err(format!("'{}' must be a non-generic function with a body", name));
+        }
+        Ok(Some(FuzzTarget {
+            name: name.to_string(),
+            input,
+            throws: !f.throws.is_empty(),
+        }))
+    }
+}
+
+/// The string literals of a file, for inserting into inputs
+pub fn dictionary(tokens: &[Token], interner: &Rodeo) -> Vec<Vec<u8>> {
+    let mut seen = HashSet::new();
+    tokens
+        .iter()
+        .filter(|t| t.kind == TokenKind::StringLit)
+        .filter_map(|t| t.symbol.map(|s| interner.resolve(&s).as_bytes().to_vec()))
+        .filter(|word| !word.is_empty() && word.len() <= 64 && seen.insert(word.clone()))
+        .collect()
+}
+
+/// What one input did to the target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Accepted,
+    Threw,
+    Crashed(String),
+    TimedOut,
+}
+
+impl Outcome {
+    pub fn is_failure(&self, fail_on_throw: bool) -> bool {
+        match self {
+            Outcome::Accepted => false,
+            Outcome::Threw => fail_on_throw,
+            Outcome::Crashed(_) | Outcome::TimedOut => true,
+        }
+    }
+
+    fn same_kind(&self, other: &Outcome) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// xorshift64*, seeded so that runs can be repeated
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `0..n`; `n` must not be 0
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+pub struct Mutator {
+    rng: Rng,
+    dictionary: Vec<Vec<u8>>,
+    max_len: usize,
+}
+
+impl Mutator {
+    pub fn new(seed: u64, dictionary: Vec<Vec<u8>>, max_len: usize) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            dictionary,
+            max_len,
+        }
+    }
+
+    /// The next input: usually a mutated corpus entry, sometimes fresh bytes
+    pub fn next_input(&mut self, corpus: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = if corpus.is_empty() || self.rng.below(8) == 0 {
+            self.fresh()
+        } else {
+            corpus[self.rng.below(corpus.len())].clone()
+        };
+        for _ in 0..1 + self.rng.below(4) {
+            self.mutate(&mut data, corpus);
+        }
+        data.truncate(self.max_len);
+        data
+    }
+
+    fn fresh(&mut self) -> Vec<u8> {
+        let len = self.rng.below(self.max_len.min(64) + 1);
+        let printable = self.rng.below(2) == 0;
+        (0..len)
+            .map(|_| {
+                if printable {
+                    b' ' + self.rng.below(95) as u8
+                } else {
+                    self.rng.next_u64() as u8
+                }
+            })
+            .collect()
+    }
+
+    fn mutate(&mut self, data: &mut Vec<u8>, corpus: &[Vec<u8>]) {
+        let pos = self.rng.below(data.len() + 1);
+        let inside = pos < data.len();
+        match self.rng.below(8) {
+            0 if inside => data[pos] ^= 1 << self.rng.below(8),
+            1 if inside => data[pos] = self.rng.next_u64() as u8,
+            2 => data.insert(pos, INTERESTING[self.rng.below(INTERESTING.len())]),
+            3 if inside => {
+                let end = (pos + 1 + self.rng.below(8)).min(data.len());
+                data.drain(pos..end);
+            }
+            4 if inside => {
+                let end = (pos + 1 + self.rng.below(16)).min(data.len());
+                let chunk = data[pos..end].to_vec();
+                let at = self.rng.below(data.len() + 1);
+                data.splice(at..at, chunk);
+            }
+            5 if !self.dictionary.is_empty() => {
+                let word = self.dictionary[self.rng.below(self.dictionary.len())].clone();
+                data.splice(pos..pos, word);
+            }
+            6 if !corpus.is_empty() => {
+                let other = &corpus[self.rng.below(corpus.len())];
+                let cut = self.rng.below(other.len() + 1);
+                data.truncate(pos);
+                data.extend_from_slice(&other[cut..]);
+            }
+            _ => data.insert(pos, self.rng.next_u64() as u8),
+        }
+    }
+}
+
+/// Shrink `input` while `fails` holds: delete chunks of halving size, down
+/// to single bytes
+pub fn minimize(input: &[u8], mut fails: impl FnMut(&[u8]) -> bool) -> Vec<u8> {
+    let mut best = input.to_vec();
+    let mut runs = 0;
+    let mut chunk = best.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < best.len() && runs < MAX_MINIMIZE_RUNS {
+            let end = (start + chunk).min(best.len());
+            let mut candidate = best[..start].to_vec();
+            candidate.extend_from_slice(&best[end..]);
+            runs += 1;
+            if fails(&candidate) {
+                best = candidate;
+            } else {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+    best
+}
+
+/// FNV-1a, so that file names stay the same across builds
+fn input_hash(input: &[u8]) -> u64 {
+    input.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100_0000_01b3))
+}
+
+/// Inputs worth mutating, one file each, named by content hash
+pub struct Corpus {
+    dir: PathBuf,
+    pub inputs: Vec<Vec<u8>>,
+    seen: HashSet<u64>,
+}
+
+impl Corpus {
+    /// Read every file in `dir`, creating it if needed
+    pub fn load(dir: &Path) -> Result<Corpus, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("cannot read {}: {}", dir.display(), e))?;
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_file()).collect();
+        paths.sort();
+        let mut corpus = Corpus {
+            dir: dir.to_path_buf(),
+            inputs: Vec::new(),
+            seen: HashSet::new(),
+        };
+        for path in paths {
+            let input = std::fs::read(&path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            if corpus.seen.insert(input_hash(&input)) {
+                corpus.inputs.push(input);
+            }
+        }
+        Ok(corpus)
+    }
+
+    /// Add `input` unless it is already known, saving it to the directory
+    pub fn add(&mut self, input: &[u8]) -> Result<bool, String> {
+        let hash = input_hash(input);
+        if !self.seen.insert(hash) {
+            return Ok(false);
+        }
+        let path = self.dir.join(format!("{:016x}", hash));
+        std::fs::write(&path, input).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+        self.inputs.push(input.to_vec());
+        Ok(true)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub outcome: Outcome,
+    /// The minimized input
+    pub input: Vec<u8>,
+    pub original_len: usize,
+    /// What the target printed to stderr on the minimized input
+    pub stderr: String,
+}
+
+impl Failure {
+    /// Save the input as `crash-<hash>`, `timeout-<hash>` or `throw-<hash>`
+    /// in `dir`
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        let prefix = match self.outcome {
+            Outcome::Crashed(_) | Outcome::Accepted => "crash",
+            Outcome::TimedOut => "timeout",
+            Outcome::Threw => "throw",
+        };
+        std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        let path = dir.join(format!("{}-{:016x}", prefix, input_hash(&self.input)));
+        std::fs::write(&path, &self.input).map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FuzzReport {
+    pub runs: u64,
+    pub accepted: u64,
+    pub threw: u64,
+    pub new_corpus: usize,
+    pub failure: Option<Failure>,
+}
+
+/// Fuzz the target behind `execute`, which runs one input and returns
+/// whether an exception escaped. The corpus entries run first, after the
+/// empty input; a target that `throws` adds the inputs it accepted.
+#[cfg(unix)]
+pub fn fuzz(
+    config: &FuzzConfig,
+    corpus: &mut Corpus,
+    dictionary: Vec<Vec<u8>>,
+    throws: bool,
+    mut execute: impl FnMut(&[u8]) -> bool,
+) -> Result<FuzzReport, String> {
+    let timeout = std::time::Duration::from_millis(config.timeout_ms.max(1));
+    let log = std::env::temp_dir().join(format!("naml-fuzz-{}.log", std::process::id()));
+    let mut mutator = Mutator::new(config.seed, dictionary, config.max_len);
+    let mut report = FuzzReport::default();
+    let mut pending: Vec<Vec<u8>> = std::iter::once(Vec::new()).chain(corpus.inputs.iter().cloned()).collect();
+
+    while report.failure.is_none() && (report.runs < config.runs || !pending.is_empty()) {
+        if pending.is_empty() {
+            let count = (config.runs - report.runs).min(BATCH_SIZE as u64);
+            pending = (0..count).map(|_| mutator.next_input(&corpus.inputs)).collect();
+        }
+        let outcomes = isolated::run_batch(&mut execute, &pending, timeout, &log)?;
+        for (input, outcome) in pending.iter().zip(&outcomes) {
+            report.runs += 1;
+            match outcome {
+                Outcome::Accepted => {
+                    report.accepted += 1;
+                    if throws && report.new_corpus < MAX_NEW_CORPUS && corpus.add(input)? {
+                        report.new_corpus += 1;
+                    }
+                }
+                Outcome::Threw => report.threw += 1,
+                _ => {}
+            }
+            if !outcome.is_failure(config.fail_on_throw) {
+                continue;
+            }
+            let minimized = if *outcome == Outcome::TimedOut {
+                input.clone()
+            } else {
+                minimize(input, |candidate| {
+                    isolated::run_batch(&mut execute, &[candidate.to_vec()], timeout, &log)
+                        .is_ok_and(|o| o.first().is_some_and(|o| o.same_kind(outcome)))
+                })
+            };
+            // Run the minimized input once more for its outcome and output
+            let rerun = isolated::run_batch(&mut execute, std::slice::from_ref(&minimized), timeout, &log)?;
+            report.failure = Some(Failure {
+                outcome: rerun.into_iter().next().filter(|o| o.same_kind(outcome)).unwrap_or_else(|| outcome.clone()),
+                input: minimized,
+                original_len: input.len(),
+                stderr: std::fs::read_to_string(&log).unwrap_or_default(),
+            });
+            break;
+        }
+        pending.drain(..outcomes.len());
+    }
+    let _ = std::fs::remove_file(&log);
+    Ok(report)
+}
+
+#[cfg(not(unix))]
+pub fn fuzz(
+    _config: &FuzzConfig,
+    _corpus: &mut Corpus,
+    _dictionary: Vec<Vec<u8>>,
+    _throws: bool,
+    _execute: impl FnMut(&[u8]) -> bool,
+) -> Result<FuzzReport, String> {
+    Err("naml fuzz runs inputs in forked processes and needs a Unix platform".to_string())
+}
+
+#[cfg(unix)]
+mod isolated {
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+    use std::time::{Duration, Instant};
+
+    use super::Outcome;
+
+    const NOT_RUN: u8 = 0;
+    const ACCEPTED: u8 = 1;
+    const THREW: u8 = 2;
+
+    /// Run `inputs` in order in a forked child, with its stdout and stderr
+    /// sent to `log`. Returns an outcome per input up to and including the
+    /// first crash or timeout; the rest did not run.
+    pub fn run_batch(
+        execute: &mut dyn FnMut(&[u8]) -> bool,
+        inputs: &[Vec<u8>],
+        timeout: Duration,
+        log: &Path,
+    ) -> Result<Vec<Outcome>, String> {
+        let log_file = std::fs::File::create(log).map_err(|e| format!("cannot create {}: {}", log.display(), e))?;
+        // One counter of started inputs, then one status byte per input
+        let size = 8 + inputs.len();
+        let shared = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                size,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if shared == libc::MAP_FAILED {
+            return Err(format!("mmap failed: {}", std::io::Error::last_os_error()));
+        }
+        let started = unsafe { &*(shared as *const AtomicU64) };
+        let status = unsafe { std::slice::from_raw_parts((shared as *const AtomicU8).add(8), inputs.len()) };
+
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+        let pid = unsafe { libc::fork() };
+        if pid < 0 {
+            unsafe { libc::munmap(shared, size) };
+            return Err(format!("fork failed: {}", std::io::Error::last_os_error()));
+        }
+        if pid == 0 {
+            unsafe {
+                libc::dup2(log_file.as_raw_fd(), 1);
+                libc::dup2(log_file.as_raw_fd(), 2);
+            }
+            for (i, input) in inputs.iter().enumerate() {
+                started.store(i as u64 + 1, Ordering::SeqCst);
+                let threw = execute(input);
+                status[i].store(if threw { THREW } else { ACCEPTED }, Ordering::SeqCst);
+            }
+            unsafe { libc::_exit(0) };
+        }
+
+        let mut wait_status = 0;
+        let mut timed_out = false;
+        let mut last_started = 0;
+        let mut since = Instant::now();
+        loop {
+            if unsafe { libc::waitpid(pid, &mut wait_status, libc::WNOHANG) } == pid {
+                break;
+            }
+            let now_started = started.load(Ordering::SeqCst);
+            if now_started != last_started {
+                last_started = now_started;
+                since = Instant::now();
+            } else if since.elapsed() > timeout {
+                unsafe {
+                    libc::kill(pid, libc::SIGKILL);
+                    libc::waitpid(pid, &mut wait_status, 0);
+                }
+                timed_out = true;
+                break;
+            }
+            std::thread::sleep(Duration::from_micros(200));
+        }
+
+        let mut outcomes: Vec<Outcome> = status
+            .iter()
+            .map(|s| s.load(Ordering::SeqCst))
+            .take_while(|&s| s != NOT_RUN)
+            .map(|s| if s == THREW { Outcome::Threw } else { Outcome::Accepted })
+            .collect();
+        if outcomes.len() < inputs.len() {
+            outcomes.push(if timed_out {
+                Outcome::TimedOut
+            } else if libc::WIFSIGNALED(wait_status) {
+                Outcome::Crashed(signal_name(libc::WTERMSIG(wait_status)))
+            } else {
+                Outcome::Crashed(format!("exited with status {}", libc::WEXITSTATUS(wait_status)))
+            });
+        }
+        unsafe { libc::munmap(shared, size) };
+        Ok(outcomes)
+    }
+
+    fn signal_name(signal: i32) -> String {
+        let name = match signal {
+            libc::SIGABRT => "SIGABRT",
+            libc::SIGSEGV => "SIGSEGV",
+            libc::SIGBUS => "SIGBUS",
+            libc::SIGFPE => "SIGFPE",
+            libc::SIGILL => "SIGILL",
+            libc::SIGTRAP => "SIGTRAP",
+            _ => return format!("killed by signal {}", signal),
+        };
+        format!("killed by {}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::AstArena;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+
+    #[test]
+    fn test_find_target() {
+        let source = "fn decode(data: bytes) -> int throws DecodeError { return 0; }\nfn load(text: string) {}\nfn sum(a: int, b: int) -> int { return a + b; }\nexception DecodeError { reason: string }";
+        let (tokens, interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        let decode = FuzzTarget::find(&parsed.ast, &interner, "decode").unwrap().unwrap();
+        assert_eq!((decode.input, decode.throws), (InputKind::Bytes, true));
+        let load = FuzzTarget::find(&parsed.ast, &interner, "load").unwrap().unwrap();
+        assert_eq!((load.input, load.throws), (InputKind::String, false));
+        assert!(FuzzTarget::find(&parsed.ast, &interner, "sum").is_err());
+        assert!(FuzzTarget::find(&parsed.ast, &interner, "missing").unwrap().is_none());
+        assert_eq!(dictionary(&tokens, &interner), Vec::<Vec<u8>>::new());
+    }
+
+    #[test]
+    fn test_dictionary() {
+        let source = "fn f(s: string) -> bool { return s == \"key\" || s == \"[\" || s == \"key\" || s == \"\"; }";
+        let (tokens, interner) = tokenize(source);
+        assert_eq!(dictionary(&tokens, &interner), [b"key".to_vec(), b"[".to_vec()]);
+    }
+
+    #[test]
+    fn test_mutator_is_deterministic() {
+        let corpus = vec![b"name = value".to_vec()];
+        let mut a = Mutator::new(7, vec![b"true".to_vec()], 32);
+        let mut b = Mutator::new(7, vec![b"true".to_vec()], 32);
+        for _ in 0..200 {
+            let input = a.next_input(&corpus);
+            assert!(input.len() <= 32);
+            assert_eq!(input, b.next_input(&corpus));
+        }
+    }
+
+    #[test]
+    fn test_minimize() {
+        let input = b"header: {\"a\": [1, 2, 3]} trailer";
+        let minimized = minimize(input, |c| c.contains(&b'[') && c.contains(&b'3'));
+        assert_eq!(minimized, b"[3");
+    }
+
+    #[test]
+    fn test_corpus_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut corpus = Corpus::load(dir.path()).unwrap();
+        assert!(corpus.add(b"abc").unwrap());
+        assert!(!corpus.add(b"abc").unwrap());
+        assert!(corpus.add(b"").unwrap());
+        let loaded = Corpus::load(dir.path()).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.inputs.contains(&b"abc".to_vec()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fuzz_finds_and_minimizes_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut corpus = Corpus::load(dir.path()).unwrap();
+        corpus.add(b"ok: 1").unwrap();
+        let config = FuzzConfig {
+            runs: 50_000,
+            seed: 3,
+            ..FuzzConfig::default()
+        };
+        let report = fuzz(&config, &mut corpus, vec![b"!!".to_vec()], true, |input| {
+            if input.windows(2).any(|w| w == b"!!") {
+                std::process::abort();
+            }
+            !input.starts_with(b"ok")
+        })
+        .unwrap();
+        let failure = report.failure.expect("no crash found");
+        assert_eq!(failure.outcome, Outcome::Crashed("killed by SIGABRT".to_string()));
+        assert_eq!(failure.input, b"!!");
+        assert!(report.threw > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fuzz_timeout_and_clean_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut corpus = Corpus::load(dir.path()).unwrap();
+        let config = FuzzConfig {
+            runs: 20,
+            timeout_ms: 50,
+            ..FuzzConfig::default()
+        };
+        let report = fuzz(&config, &mut corpus, vec![], false, |input| {
+            if input.is_empty() {
+                std::thread::sleep(std::time::Duration::from_secs(5));
+            }
+            false
+        })
+        .unwrap();
+        assert_eq!(report.failure.unwrap().outcome, Outcome::TimedOut);
+
+        let report = fuzz(&config, &mut corpus, vec![], false, |_| false).unwrap();
+        assert!(report.failure.is_none());
+        assert_eq!((report.runs, report.accepted), (20, 20));
+    }
+}
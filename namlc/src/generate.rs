@@ -0,0 +1,239 @@
+//!
+//! Code Generators
+//!
+//! Scaffolding generators, such as "HTTP route stubs from `@route`
+//! attributes", are written as generators and run by `naml codegen`. A
+//! generator implements `Generator`: it receives the stable view of a file
+//! (`ast::visit::Ast`) and emits files through `GeneratedFiles`. It never
+//! sees the compiler's internal AST, so it keeps working across releases
+//! as long as `ast::visit::API_VERSION` is unchanged.
+//!
+//! Generators are registered on a `GeneratorRegistry`, either directly by
+//! a Rust build script embedding namlc, or from a plugin library loaded
+//! with `naml codegen --plugin <lib>`. A plugin is a `cdylib` that exports
+//!
+//!   #[unsafe(no_mangle)]
+//!   pub fn naml_register_generators(registry: &mut namlc::generate::GeneratorRegistry)
+//!
+//! Emitted paths are relative to the output directory; absolute paths and
+//! `..` components are rejected so a generator cannot write elsewhere.
+//!
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::ast::visit::{Ast, API_VERSION};
+use crate::lint::open_plugin;
+
+/// Name of the function a plugin library exports to register its generators
+pub const REGISTER_SYMBOL: &str = "naml_register_generators";
+
+/// Signature of `REGISTER_SYMBOL`
+pub type RegisterFn = fn(&mut GeneratorRegistry);
+
+/// Produces files from the declarations of a naml source file
+pub trait Generator {
+    /// Shown in errors and in `naml codegen` output
+    fn name(&self) -> &str;
+
+    /// The view version the generator was built against. The default is
+    /// compiled into the generator, so leave it alone.
+    fn api_version(&self) -> u32 {
+        API_VERSION
+    }
+
+    fn generate(&mut self, ast: &Ast, out: &mut GeneratedFiles) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedFile {
+    pub generator: String,
+    /// Relative to the output directory
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Collects the files one generator emits
+pub struct GeneratedFiles {
+    generator: String,
+    files: Vec<GeneratedFile>,
+}
+
+impl GeneratedFiles {
+    pub fn emit(&mut self, path: impl Into<PathBuf>, contents: impl Into<String>) {
+        self.files.push(GeneratedFile {
+            generator: self.generator.clone(),
+            path: path.into(),
+            contents: contents.into(),
+        });
+    }
+}
+
+/// The generators to run, and the plugin libraries they came from
+#[derive(Default)]
+pub struct GeneratorRegistry {
+    generators: Vec<Box<dyn Generator>>,
+    /// Plugin handles are never closed: the generators' code lives in them
+    libraries: Vec<*mut libc::c_void>,
+}
+
+impl GeneratorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, generator: Box<dyn Generator>) {
+        self.generators.push(generator);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    /// Load a plugin library and let it register its generators
+    pub fn load_plugin(&mut self, path: &Path) -> Result<(), String> {
+        let (handle, register) = open_plugin(path, REGISTER_SYMBOL)?;
+        self.libraries.push(handle);
+        // SAFETY: plugins export `REGISTER_SYMBOL` with the `RegisterFn` signature
+        let register: RegisterFn = unsafe { std::mem::transmute(register) };
+        register(self);
+        Ok(())
+    }
+
+    /// Run every generator over `ast`, returning the emitted files in order.
+    /// Stops at the first generator that fails or emits an invalid path.
+    pub fn run(&mut self, ast: &Ast) -> Result<Vec<GeneratedFile>, String> {
+        let mut files = Vec::new();
+        for generator in &mut self.generators {
+            let name = generator.name().to_string();
+            if generator.api_version() != API_VERSION {
+                return Err(format!(
+                    "generator '{}' was built for AST view version {}, this compiler provides {}",
+                    name,
+                    generator.api_version(),
+                    API_VERSION
+                ));
+            }
+            let mut out = GeneratedFiles {
+                generator: name.clone(),
+                files: Vec::new(),
+            };
+            generator
+                .generate(ast, &mut out)
+                .map_err(|e| format!("generator '{}' failed: {}", name, e))?;
+            for file in &out.files {
+                let escapes = file
+                    .path
+                    .components()
+                    .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+                if escapes || file.path.as_os_str().is_empty() {
+                    return Err(format!(
+                        "generator '{}' emitted invalid path '{}': paths must be relative and stay inside the output directory",
+                        name,
+                        file.path.display()
+                    ));
+                }
+            }
+            files.extend(out.files);
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::visit::{AstVisitor, Function};
+
+    /// Emits one handler stub per `@route(method, path)` function
+    struct RouteStubs;
+
+    impl Generator for RouteStubs {
+        fn name(&self) -> &str {
+            "route-stubs"
+        }
+
+        fn generate(&mut self, ast: &Ast, out: &mut GeneratedFiles) -> Result<(), String> {
+            struct Collect(String);
+            impl AstVisitor for Collect {
+                fn visit_function(&mut self, f: &Function) {
+                    if let Some(route) = f.attributes.iter().find(|a| a.name == "route") {
+                        let path = route.args.get(1).and_then(|a| a.as_str()).unwrap_or("/");
+                        self.0.push_str(&format!("// {}\nfn {}_stub() {{}}\n", path, f.name));
+                    }
+                }
+            }
+            let mut collect = Collect(String::new());
+            ast.walk(&mut collect);
+            out.emit("routes.nm", collect.0);
+            Ok(())
+        }
+    }
+
+    struct Escaping;
+
+    impl Generator for Escaping {
+        fn name(&self) -> &str {
+            "escaping"
+        }
+
+        fn generate(&mut self, _ast: &Ast, out: &mut GeneratedFiles) -> Result<(), String> {
+            out.emit("../outside.nm", "");
+            Ok(())
+        }
+    }
+
+    struct Outdated;
+
+    impl Generator for Outdated {
+        fn name(&self) -> &str {
+            "outdated"
+        }
+
+        fn api_version(&self) -> u32 {
+            0
+        }
+
+        fn generate(&mut self, _ast: &Ast, _out: &mut GeneratedFiles) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_generators() {
+        let ast = Ast::parse("@route(\"GET\", \"/users\")\nfn list_users() {}\nfn helper() {}").unwrap();
+        let mut registry = GeneratorRegistry::new();
+        registry.register(Box::new(RouteStubs));
+        let files = registry.run(&ast).unwrap();
+        assert_eq!(
+            files,
+            [GeneratedFile {
+                generator: "route-stubs".to_string(),
+                path: PathBuf::from("routes.nm"),
+                contents: "// /users\nfn list_users_stub() {}\n".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_generators() {
+        let ast = Ast::parse("fn main() {}").unwrap();
+
+        let mut registry = GeneratorRegistry::new();
+        registry.register(Box::new(Escaping));
+        let err = registry.run(&ast).unwrap_err();
+        assert!(err.contains("invalid path '../outside.nm'"), "{}", err);
+
+        let mut registry = GeneratorRegistry::new();
+        registry.register(Box::new(Outdated));
+        let err = registry.run(&ast).unwrap_err();
+        assert!(err.contains("built for AST view version 0"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_missing_plugin() {
+        let mut registry = GeneratorRegistry::new();
+        let err = registry.load_plugin(Path::new("/nonexistent/libgen.so")).unwrap_err();
+        assert!(err.contains("cannot load plugin"), "{}", err);
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,353 @@
+//!
+//! Highlight Module - Syntax Highlighting
+//!
+//! This module classifies naml source into highlight tokens using the
+//! compiler's own lexer, so the docs generator, the REPL and third-party
+//! tools colour code exactly the way the compiler reads it.
+//!
+//! `highlight` returns tokens that cover the source without gaps, trivia
+//! included, in source order. `HighlightKind` is the stable part of the
+//! API: lexer token kinds may change, the highlight kinds and their names
+//! (`HighlightKind::as_str`) do not.
+//!
+//! Classification is lexical with one token of context on each side:
+//! - Identifiers followed by `(` or after `fn` are functions
+//! - Identifiers after `struct`, `enum`, `interface`, `exception`, `type`
+//!   or `implements` are types, as are the built-in type keywords
+//! - `@name` and `#[...]` markers are attributes
+//!
+//! Renderers:
+//! - `to_html`: `<span class="naml-{kind}">` per token, text escaped,
+//!   whitespace and plain identifiers left unwrapped
+//! - `to_ansi`: SGR colour codes for terminals, reset after every token
+//!
+//! Usage:
+//!   for token in highlight(source) {
+//!       let text = &source[token.span.start as usize..token.span.end as usize];
+//!   }
+//!   let html = to_html(source);
+//!
+
+use crate::lexer::{Keyword, Token, TokenKind, tokenize_with_trivia};
+use crate::source::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    Keyword,
+    Type,
+    Constant,
+    Ident,
+    Function,
+    Number,
+    String,
+    Comment,
+    Operator,
+    Punctuation,
+    Attribute,
+    Whitespace,
+    Error,
+}
+
+impl HighlightKind {
+    /// Stable lowercase name, used as the HTML class suffix
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HighlightKind::Keyword => "keyword",
+            HighlightKind::Type => "type",
+            HighlightKind::Constant => "constant",
+            HighlightKind::Ident => "ident",
+            HighlightKind::Function => "function",
+            HighlightKind::Number => "number",
+            HighlightKind::String => "string",
+            HighlightKind::Comment => "comment",
+            HighlightKind::Operator => "operator",
+            HighlightKind::Punctuation => "punctuation",
+            HighlightKind::Attribute => "attribute",
+            HighlightKind::Whitespace => "whitespace",
+            HighlightKind::Error => "error",
+        }
+    }
+
+    fn ansi_code(self) -> Option<&'static str> {
+        match self {
+            HighlightKind::Keyword => Some("35"),
+            HighlightKind::Type => Some("36"),
+            HighlightKind::Constant | HighlightKind::Number => Some("33"),
+            HighlightKind::Function => Some("34"),
+            HighlightKind::String => Some("32"),
+            HighlightKind::Comment => Some("90"),
+            HighlightKind::Attribute => Some("93"),
+            HighlightKind::Error => Some("4;31"),
+            HighlightKind::Ident
+            | HighlightKind::Operator
+            | HighlightKind::Punctuation
+            | HighlightKind::Whitespace => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightToken {
+    pub kind: HighlightKind,
+    pub span: Span,
+}
+
+pub fn highlight(source: &str) -> Vec<HighlightToken> {
+    let tokens = tokenize_with_trivia(source);
+    let significant: Vec<usize> = (0..tokens.len())
+        .filter(|&i| !tokens[i].is_trivia())
+        .collect();
+
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut next_significant: usize = 0;
+    for token in &tokens {
+        let kind = if token.is_trivia() {
+            classify_trivia(token.kind)
+        } else {
+            let prev = next_significant
+                .checked_sub(1)
+                .map(|j| &tokens[significant[j]]);
+            let next = significant.get(next_significant + 1).map(|&j| &tokens[j]);
+            let prev2 = next_significant
+                .checked_sub(2)
+                .map(|j| &tokens[significant[j]]);
+            next_significant += 1;
+            classify(token, prev, prev2, next)
+        };
+        result.push(HighlightToken {
+            kind,
+            span: token.span,
+        });
+    }
+    result
+}
+
+fn classify_trivia(kind: TokenKind) -> HighlightKind {
+    match kind {
+        TokenKind::Comment => HighlightKind::Comment,
+        _ => HighlightKind::Whitespace,
+    }
+}
+
+fn classify(
+    token: &Token,
+    prev: Option<&Token>,
+    prev2: Option<&Token>,
+    next: Option<&Token>,
+) -> HighlightKind {
+    let prev_kind = prev.map(|t| t.kind);
+    match token.kind {
+        TokenKind::Ident => {
+            if prev_kind == Some(TokenKind::At) {
+                HighlightKind::Attribute
+            } else if matches!(
+                prev_kind,
+                Some(TokenKind::Keyword(
+                    Keyword::Struct
+                        | Keyword::Enum
+                        | Keyword::Interface
+                        | Keyword::Exception
+                        | Keyword::Type
+                        | Keyword::Implements
+                ))
+            ) {
+                HighlightKind::Type
+            } else if prev_kind == Some(TokenKind::Keyword(Keyword::Fn))
+                || next.map(|t| t.kind) == Some(TokenKind::LParen)
+            {
+                HighlightKind::Function
+            } else {
+                HighlightKind::Ident
+            }
+        }
+        TokenKind::IntLit | TokenKind::FloatLit => HighlightKind::Number,
+        TokenKind::StringLit | TokenKind::TemplateLit | TokenKind::BytesLit => {
+            HighlightKind::String
+        }
+        TokenKind::Keyword(keyword) => classify_keyword(keyword, prev_kind, prev2.map(|t| t.kind)),
+        TokenKind::At | TokenKind::Hash => HighlightKind::Attribute,
+        TokenKind::LParen
+        | TokenKind::RParen
+        | TokenKind::LBrace
+        | TokenKind::RBrace
+        | TokenKind::LBracket
+        | TokenKind::RBracket
+        | TokenKind::Comma
+        | TokenKind::Colon
+        | TokenKind::ColonColon
+        | TokenKind::Semicolon
+        | TokenKind::Dot => HighlightKind::Punctuation,
+        TokenKind::Error => HighlightKind::Error,
+        TokenKind::Whitespace | TokenKind::Newline | TokenKind::Comment | TokenKind::Eof => {
+            classify_trivia(token.kind)
+        }
+        _ => HighlightKind::Operator,
+    }
+}
+
+fn classify_keyword(
+    keyword: Keyword,
+    prev: Option<TokenKind>,
+    prev2: Option<TokenKind>,
+) -> HighlightKind {
+    match keyword {
+        Keyword::True | Keyword::False | Keyword::None => HighlightKind::Constant,
+        Keyword::Int
+        | Keyword::Uint
+        | Keyword::Float
+        | Keyword::Decimal
+        | Keyword::Bool
+        | Keyword::String
+        | Keyword::Bytes
+        | Keyword::Option
+        | Keyword::Map
+        | Keyword::Channel
+        | Keyword::Mutex
+        | Keyword::Rwlock
+        | Keyword::Atomic => HighlightKind::Type,
+        Keyword::Platforms
+            if prev == Some(TokenKind::LBracket) && prev2 == Some(TokenKind::Hash) =>
+        {
+            HighlightKind::Attribute
+        }
+        _ => HighlightKind::Keyword,
+    }
+}
+
+/// Render source as HTML spans with `naml-{kind}` classes; the caller
+/// supplies the surrounding `<pre>`/`<code>`
+pub fn to_html(source: &str) -> String {
+    let mut out = String::with_capacity(source.len() * 2);
+    for token in highlight(source) {
+        let text = &source[token.span.start as usize..token.span.end as usize];
+        match token.kind {
+            HighlightKind::Whitespace | HighlightKind::Ident => escape_html(text, &mut out),
+            kind => {
+                out.push_str("<span class=\"naml-");
+                out.push_str(kind.as_str());
+                out.push_str("\">");
+                escape_html(text, &mut out);
+                out.push_str("</span>");
+            }
+        }
+    }
+    out
+}
+
+/// Render source with ANSI colour codes for terminal output
+pub fn to_ansi(source: &str) -> String {
+    let mut out = String::with_capacity(source.len() * 2);
+    for token in highlight(source) {
+        let text = &source[token.span.start as usize..token.span.end as usize];
+        match token.kind.ansi_code() {
+            Some(code) => {
+                out.push_str("\x1b[");
+                out.push_str(code);
+                out.push('m');
+                out.push_str(text);
+                out.push_str("\x1b[0m");
+            }
+            None => out.push_str(text),
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<(&str, HighlightKind)> {
+        highlight(source)
+            .into_iter()
+            .filter(|t| t.kind != HighlightKind::Whitespace)
+            .map(|t| (&source[t.span.start as usize..t.span.end as usize], t.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_highlight_covers_source() {
+        let source = "fn main() {\n    // hi\n    var x: int = 1;\n}\n";
+        let tokens = highlight(source);
+        let mut pos = 0;
+        for token in &tokens {
+            assert_eq!(token.span.start, pos);
+            pos = token.span.end;
+        }
+        assert_eq!(pos as usize, source.len());
+    }
+
+    #[test]
+    fn test_highlight_kinds() {
+        let source =
+            "@inline\nfn add(a: int) -> option<float> { return print(\"x\", 1.5, true); } // done";
+        assert_eq!(
+            kinds(source),
+            vec![
+                ("@", HighlightKind::Attribute),
+                ("inline", HighlightKind::Attribute),
+                ("fn", HighlightKind::Keyword),
+                ("add", HighlightKind::Function),
+                ("(", HighlightKind::Punctuation),
+                ("a", HighlightKind::Ident),
+                (":", HighlightKind::Punctuation),
+                ("int", HighlightKind::Type),
+                (")", HighlightKind::Punctuation),
+                ("->", HighlightKind::Operator),
+                ("option", HighlightKind::Type),
+                ("<", HighlightKind::Operator),
+                ("float", HighlightKind::Type),
+                (">", HighlightKind::Operator),
+                ("{", HighlightKind::Punctuation),
+                ("return", HighlightKind::Keyword),
+                ("print", HighlightKind::Function),
+                ("(", HighlightKind::Punctuation),
+                ("\"x\"", HighlightKind::String),
+                (",", HighlightKind::Punctuation),
+                ("1.5", HighlightKind::Number),
+                (",", HighlightKind::Punctuation),
+                ("true", HighlightKind::Constant),
+                (")", HighlightKind::Punctuation),
+                (";", HighlightKind::Punctuation),
+                ("}", HighlightKind::Punctuation),
+                ("// done", HighlightKind::Comment),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlight_type_declarations() {
+        let source = "struct point implements shape {}";
+        let kinds = kinds(source);
+        assert_eq!(kinds[1], ("point", HighlightKind::Type));
+        assert_eq!(kinds[3], ("shape", HighlightKind::Type));
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html = to_html("var s = \"<a>\";");
+        assert_eq!(
+            html,
+            "<span class=\"naml-keyword\">var</span> s <span class=\"naml-operator\">=</span> \
+             <span class=\"naml-string\">&quot;&lt;a&gt;&quot;</span><span class=\"naml-punctuation\">;</span>"
+        );
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        assert_eq!(to_ansi("if x"), "\x1b[35mif\x1b[0m x");
+    }
+}
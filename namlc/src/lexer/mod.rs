@@ -7,7 +7,8 @@
 //! Key design decisions:
 //! - Zero-copy: Tokens reference the source string, no allocations per token
 //! - String interning: Identifiers and strings stored via lasso::Spur
-//! - Whitespace/comments filtered out for fast parsing (no trivia in output)
+//! - Whitespace/comments filtered out for fast parsing (no trivia in output);
+//!   `tokenize_with_trivia` keeps them for tools such as highlighting
 //!
 //! Token categories:
 //! - Keywords: fn, var, const, if, while, for, etc.
@@ -387,11 +388,15 @@ pub enum Keyword {
     Spawn,
     Throw,
     Throws,
+    CausedBy,
+    Finally,
     Try,
     Catch,
     As,
     Is,
     Implements,
+    Static,
+    Except,
     Not,
     And,
     Or,
@@ -433,6 +438,18 @@ pub fn tokenize_with_interner(source: &str, interner: &mut Rodeo) -> Vec<Token>
     lexer.tokenize_all()
 }
 
+/// Tokenize keeping whitespace, newlines and comments, so the tokens cover
+/// the whole source without gaps (used by syntax highlighting)
+pub fn tokenize_with_trivia(source: &str) -> Vec<Token> {
+    let mut interner = Rodeo::default();
+    let mut lexer = Lexer::new(source, &mut interner);
+    let mut tokens = Vec::new();
+    while !lexer.is_eof() {
+        tokens.push(lexer.next_token());
+    }
+    tokens
+}
+
 struct Lexer<'a, 'r> {
     source: &'a str,
     bytes: &'a [u8],
@@ -991,6 +1008,8 @@ impl<'a, 'r> Lexer<'a, 'r> {
             (0x6B636F6C, 0x6465) => TokenKind::Keyword(Keyword::Locked), // "locked"
             (0x6F6C7772, 0x6B63) => TokenKind::Keyword(Keyword::Rwlock), // "rwlock"
             (0x6D6F7461, 0x6369) => TokenKind::Keyword(Keyword::Atomic), // "atomic"
+            (0x74617473, 0x6369) => TokenKind::Keyword(Keyword::Static), // "static"
+            (0x65637865, 0x7470) => TokenKind::Keyword(Keyword::Except), // "except"
             _ => TokenKind::Ident,
         }
     }
@@ -1007,6 +1026,7 @@ impl<'a, 'r> Lexer<'a, 'r> {
             (0x69636564, 0x616D, b'l') => TokenKind::Keyword(Keyword::Decimal), // "decimal"
             (0x636F6C72, 0x656B, b'd') => TokenKind::Keyword(Keyword::Rlocked), // "rlocked"
             (0x636F6C77, 0x656B, b'd') => TokenKind::Keyword(Keyword::Wlocked), // "wlocked"
+            (0x616E6966, 0x6C6C, b'y') => TokenKind::Keyword(Keyword::Finally), // "finally"
             _ => TokenKind::Ident,
         }
     }
@@ -1030,6 +1050,7 @@ impl<'a, 'r> Lexer<'a, 'r> {
             (0x65746E69, 0x63616672, b'e') => TokenKind::Keyword(Keyword::Interface), // "interface"
             (0x65637865, 0x6F697470, b'n') => TokenKind::Keyword(Keyword::Exception), // "exception"
             (0x74616C70, 0x6D726F66, b's') => TokenKind::Keyword(Keyword::Platforms), // "platforms"
+            (0x73756163, 0x625F6465, b'y') => TokenKind::Keyword(Keyword::CausedBy), // "caused_by"
             _ => TokenKind::Ident,
         }
     }
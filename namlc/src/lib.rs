@@ -6,35 +6,64 @@
 //!
 //! - source: Source file handling, spans, and diagnostics
 //! - lexer: Tokenization of naml source code
-//! - ast: Abstract syntax tree definitions
+//! - highlight: Syntax highlighting on top of the lexer (HTML/ANSI output)
+//! - ast: Abstract syntax tree definitions (`ast::visit` is the stable view
+//!   for external tools)
 //! - parser: Parsing tokens into AST
+//! - rewrite: Lossless source view and edits for automated refactors
+//! - fix: Applies machine-applicable suggestions for `naml fix`
 //! - typechecker: Type system and inference
+//! - lint: Custom lint passes and plugin loading for `naml check`
+//! - generate: Code generators and plugin loading for `naml codegen`
 //! - codegen: Cranelift JIT code generation
+//! - build_info: Values embedded by `std::runtime::build_info()`
+//! - bench: Benchmark discovery, timing and baselines for `naml bench`
+//! - fuzz: Input generation, crash isolation and minimization for `naml fuzz`
+//! - timings: Per-phase and per-function compile times for `--timings`
 //! - runtime: Runtime support (arrays, strings, memory management)
 //!
 //! Entry points:
 //! - `tokenize`: Convert source text into tokens
+//! - `highlight`: Classify source text for syntax highlighting
 //! - `parse`: Parse tokens into AST
+//! - `parse_file`: Read a file into the stable AST view
+//! - `SyntaxFile`/`Rewriter`: Edit source text without losing formatting
+//! - `fix_source`: Apply the fixes `naml fix` would make
 //! - `check`: Type check an AST
 //! - `compile_and_run`: JIT compile and execute
 //!
 
 pub mod ast;
+pub mod bench;
+pub mod build_info;
 pub mod codegen;
 pub mod diagnostic;
+pub mod fix;
+pub mod fuzz;
+pub mod generate;
+pub mod highlight;
 pub mod lexer;
 pub mod linker;
+pub mod lint;
 pub mod parser;
+pub mod rewrite;
 pub mod runtime;
 pub mod source;
+pub mod timings;
 pub mod typechecker;
 
 pub use ast::{AstArena, CompilationTarget};
+pub use ast::visit::parse_file;
+pub use codegen::compile_and_bench;
+pub use codegen::compile_and_fuzz;
 pub use codegen::compile_and_run;
 pub use codegen::compile_to_object;
-pub use diagnostic::DiagnosticReporter;
+pub use diagnostic::{DiagnosticReporter, MessageFormat};
+pub use fix::fix_source;
+pub use highlight::{highlight, HighlightKind, HighlightToken};
 pub use lexer::tokenize;
 pub use parser::parse;
+pub use rewrite::{Rewriter, SyntaxFile};
 pub use source::SourceFile;
 pub use typechecker::{check, check_with_types, check_with_types_for_target, TypeCheckResult, ImportedModule, StdModuleFn, get_std_module_functions};
 pub use typechecker::symbols::{SymbolTable, FunctionSig, MethodSig, TypeDef, StructDef, EnumDef, ModuleNamespace};
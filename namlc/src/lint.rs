@@ -0,0 +1,342 @@
+//!
+//! Lint Passes
+//!
+//! Project-specific rules, such as "no println in request handlers", are
+//! written as lint passes and run by `naml check` after type checking. A
+//! pass implements `LintPass` and is handed every item, statement and
+//! expression of the file in source order, together with a `LintContext`
+//! that resolves names, looks up inferred types and reports diagnostics.
+//!
+//! Passes are registered on a `LintRegistry`, either directly by a Rust
+//! program embedding namlc, or from a plugin library loaded with
+//! `naml check --plugin <lib>`. A plugin is a `cdylib` built against the
+//! same namlc version that exports
+//!
+//!   #[unsafe(no_mangle)]
+//!   pub fn naml_register_lints(registry: &mut namlc::lint::LintRegistry)
+//!
+//! Lints reported as errors make `naml check` fail; warnings do not.
+//!
+
+use std::path::Path;
+
+use lasso::{Rodeo, Spur};
+
+use crate::ast::visitor::{self, Visitor};
+use crate::ast::{Expression, Item, SourceFile, Statement};
+use crate::source::Span;
+use crate::typechecker::{SymbolTable, Type, TypeAnnotations, TypeCheckResult};
+
+/// Name of the function a plugin library exports to register its passes
+pub const REGISTER_SYMBOL: &str = "naml_register_lints";
+
+/// Signature of `REGISTER_SYMBOL`
+pub type RegisterFn = fn(&mut LintRegistry);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Warning,
+    Error,
+}
+
+/// A problem reported by a lint pass
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub pass: String,
+    pub level: LintLevel,
+    pub message: String,
+    pub span: Span,
+}
+
+/// A custom check over type-checked code. Every hook is optional.
+pub trait LintPass {
+    /// Shown with every diagnostic the pass reports
+    fn name(&self) -> &str;
+
+    fn check_item(&mut self, _cx: &mut LintContext<'_>, _item: &Item<'_>) {}
+
+    /// Called after everything inside `item` has been visited
+    fn check_item_post(&mut self, _cx: &mut LintContext<'_>, _item: &Item<'_>) {}
+
+    fn check_stmt(&mut self, _cx: &mut LintContext<'_>, _stmt: &Statement<'_>) {}
+
+    fn check_expr(&mut self, _cx: &mut LintContext<'_>, _expr: &Expression<'_>) {}
+}
+
+/// What a lint pass can see and do while it runs
+pub struct LintContext<'a> {
+    interner: &'a Rodeo,
+    annotations: &'a TypeAnnotations,
+    symbols: &'a SymbolTable,
+    function: Option<Spur>,
+    pass: String,
+    lints: Vec<Lint>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn resolve(&self, symbol: &Spur) -> &'a str {
+        self.interner.resolve(symbol)
+    }
+
+    /// The inferred type of the expression at `span`
+    pub fn type_of(&self, span: Span) -> Option<&'a Type> {
+        self.annotations.get_type(span)
+    }
+
+    pub fn symbols(&self) -> &'a SymbolTable {
+        self.symbols
+    }
+
+    /// Name of the function or method being visited
+    pub fn current_function(&self) -> Option<&'a str> {
+        self.function.map(|f| self.interner.resolve(&f))
+    }
+
+    pub fn warn(&mut self, span: Span, message: impl Into<String>) {
+        self.report(LintLevel::Warning, span, message.into());
+    }
+
+    pub fn error(&mut self, span: Span, message: impl Into<String>) {
+        self.report(LintLevel::Error, span, message.into());
+    }
+
+    fn report(&mut self, level: LintLevel, span: Span, message: String) {
+        self.lints.push(Lint {
+            pass: self.pass.clone(),
+            level,
+            message,
+            span,
+        });
+    }
+}
+
+/// The lint passes to run, and the plugin libraries they came from
+#[derive(Default)]
+pub struct LintRegistry {
+    passes: Vec<Box<dyn LintPass>>,
+    /// Plugin handles are never closed: the passes' code lives in them
+    libraries: Vec<*mut libc::c_void>,
+}
+
+impl LintRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, pass: Box<dyn LintPass>) {
+        self.passes.push(pass);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Load a plugin library and let it register its passes
+    pub fn load_plugin(&mut self, path: &Path) -> Result<(), String> {
+        let (handle, register) = open_plugin(path, REGISTER_SYMBOL)?;
+        self.libraries.push(handle);
+        // SAFETY: plugins export `REGISTER_SYMBOL` with the `RegisterFn` signature
+        let register: RegisterFn = unsafe { std::mem::transmute(register) };
+        register(self);
+        Ok(())
+    }
+
+    /// Run every pass over a type-checked file, returning the lints in the
+    /// order they were reported
+    pub fn run(
+        &mut self,
+        file: &SourceFile<'_>,
+        interner: &Rodeo,
+        result: &TypeCheckResult,
+    ) -> Vec<Lint> {
+        let mut walker = LintWalker {
+            passes: &mut self.passes,
+            cx: LintContext {
+                interner,
+                annotations: &result.annotations,
+                symbols: &result.symbols,
+                function: None,
+                pass: String::new(),
+                lints: Vec::new(),
+            },
+        };
+        for item in &file.items {
+            walker.visit_item(item);
+        }
+        walker.cx.lints
+    }
+}
+
+/// Open a plugin library and look up `symbol` in it, returning the library
+/// handle (to keep open for as long as the plugin's code is used) and the
+/// symbol's address
+#[cfg(unix)]
+pub(crate) fn open_plugin(
+    path: &Path,
+    symbol: &str,
+) -> Result<(*mut libc::c_void, *mut libc::c_void), String> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| format!("invalid plugin path '{}'", path.display()))?;
+    let dl_error = || unsafe {
+        let err = libc::dlerror();
+        if err.is_null() {
+            "unknown error".to_string()
+        } else {
+            CStr::from_ptr(err).to_string_lossy().into_owned()
+        }
+    };
+
+    let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        return Err(format!("cannot load plugin '{}': {}", path.display(), dl_error()));
+    }
+    let c_symbol = CString::new(symbol).expect("symbol name has no NUL");
+    let address = unsafe { libc::dlsym(handle, c_symbol.as_ptr()) };
+    if address.is_null() {
+        unsafe { libc::dlclose(handle) };
+        return Err(format!(
+            "plugin '{}' does not export `{}`",
+            path.display(),
+            symbol
+        ));
+    }
+    Ok((handle, address))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn open_plugin(
+    path: &Path,
+    _symbol: &str,
+) -> Result<(*mut libc::c_void, *mut libc::c_void), String> {
+    Err(format!(
+        "cannot load plugin '{}': plugins are only supported on unix",
+        path.display()
+    ))
+}
+
+/// Walks the AST once, calling every pass at each node
+struct LintWalker<'r, 'a> {
+    passes: &'r mut [Box<dyn LintPass>],
+    cx: LintContext<'a>,
+}
+
+impl LintWalker<'_, '_> {
+    fn each_pass(&mut self, mut hook: impl FnMut(&mut dyn LintPass, &mut LintContext<'_>)) {
+        for pass in self.passes.iter_mut() {
+            self.cx.pass.clear();
+            self.cx.pass.push_str(pass.name());
+            hook(pass.as_mut(), &mut self.cx);
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for LintWalker<'_, '_> {
+    fn visit_item(&mut self, item: &Item<'ast>) {
+        let outer = self.cx.function;
+        if let Item::Function(f) = item {
+            self.cx.function = Some(f.name.symbol);
+        }
+        self.each_pass(|pass, cx| pass.check_item(cx, item));
+        visitor::walk_item(self, item);
+        self.each_pass(|pass, cx| pass.check_item_post(cx, item));
+        self.cx.function = outer;
+    }
+
+    fn visit_stmt(&mut self, stmt: &Statement<'ast>) {
+        self.each_pass(|pass, cx| pass.check_stmt(cx, stmt));
+        visitor::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        self.each_pass(|pass, cx| pass.check_expr(cx, expr));
+        visitor::walk_expr(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::AstArena;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+    use crate::source::Spanned;
+    use crate::typechecker::check_with_types;
+
+    /// Rejects `println` inside functions whose name starts with `handle_`
+    struct NoPrintInHandlers;
+
+    impl LintPass for NoPrintInHandlers {
+        fn name(&self) -> &str {
+            "no-print-in-handlers"
+        }
+
+        fn check_expr(&mut self, cx: &mut LintContext<'_>, expr: &Expression<'_>) {
+            let Expression::Call(call) = expr else {
+                return;
+            };
+            let Expression::Identifier(callee) = call.callee else {
+                return;
+            };
+            let in_handler = cx.current_function().is_some_and(|f| f.starts_with("handle_"));
+            if in_handler && cx.resolve(&callee.ident.symbol) == "println" {
+                cx.error(call.span, "handlers must log instead of printing");
+            }
+        }
+    }
+
+    /// Warns about string variables, to exercise type lookups
+    struct StringVars;
+
+    impl LintPass for StringVars {
+        fn name(&self) -> &str {
+            "string-vars"
+        }
+
+        fn check_stmt(&mut self, cx: &mut LintContext<'_>, stmt: &Statement<'_>) {
+            if let Statement::Var(var) = stmt
+                && let Some(init) = &var.init
+                && cx.type_of(init.span()) == Some(&Type::String)
+            {
+                cx.warn(var.span, format!("'{}' is a string", cx.resolve(&var.name.symbol)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lint_passes() {
+        let source = "fn handle_get() { println(\"hit\"); }\nfn main() { var s: string = \"x\"; println(s); }";
+        let (tokens, mut interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        let result = check_with_types(&parsed.ast, &mut interner, None, None);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let mut registry = LintRegistry::new();
+        registry.register(Box::new(NoPrintInHandlers));
+        registry.register(Box::new(StringVars));
+        let lints = registry.run(&parsed.ast, &interner, &result);
+
+        let reported: Vec<_> = lints
+            .iter()
+            .map(|l| (l.pass.as_str(), l.level, l.message.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                ("no-print-in-handlers", LintLevel::Error, "handlers must log instead of printing"),
+                ("string-vars", LintLevel::Warning, "'s' is a string"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_missing_plugin() {
+        let mut registry = LintRegistry::new();
+        let err = registry.load_plugin(Path::new("/nonexistent/liblint.so")).unwrap_err();
+        assert!(err.contains("cannot load plugin"), "{}", err);
+        assert!(registry.is_empty());
+    }
+}
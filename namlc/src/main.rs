@@ -5,21 +5,37 @@
 //! - naml run <file>: JIT compile and execute
 //! - naml build: Compile to native binary or WASM
 //! - naml check: Type check without building
+//! - naml fix: Apply the checker's machine-applicable fixes (`--dry-run` diffs)
+//! - naml bench: Time `bench_*` / `@bench` functions, with saved baselines (`cli::bench`)
+//! - naml fuzz <function>: Feed generated inputs to a function (`cli::fuzz`)
+//! - naml codegen <file>: Run code generator plugins over a file's declarations
 //! - naml pkg init: Create a new project
 //! - naml pkg get: Download all dependencies
+//! - naml --explain E0001: Describe a type checker error code
 //!
 
+mod cli;
+
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
-use namlc::{check_with_types, check_with_types_for_target, compile_and_run, compile_to_object, parse, tokenize, AstArena, CompilationTarget, DiagnosticReporter, SourceFile};
+use namlc::ast::visit::ParseFileError;
+use namlc::fix::FixError;
+use namlc::fuzz::FuzzConfig;
+use namlc::generate::GeneratorRegistry;
+use namlc::lint::{LintLevel, LintRegistry};
+use namlc::timings::Timings;
+
+use namlc::{check_with_types, check_with_types_for_target, compile_and_run, compile_to_object, parse, tokenize, AstArena, CompilationTarget, DiagnosticReporter, MessageFormat, SourceFile};
 
 #[derive(Parser)]
 #[command(name = "naml")]
 #[command(author, version, about = "The naml programming language", long_about = None)]
 struct Cli {
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+    #[arg(long, value_name = "CODE", help = "Explain a type checker error code, e.g. E0002")]
+    explain: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -32,6 +48,16 @@ enum Commands {
         release: bool,
         #[arg(long, help = "Unsafe mode: disable array bounds checking for maximum performance")]
         r#unsafe: bool,
+        #[arg(long, help = "Write a .naml-symbols.json map of generated symbols next to the file")]
+        emit_symbols: bool,
+        #[arg(long, help = "Report compile time per phase and per function, also as .naml-timings.json next to the file")]
+        timings: bool,
+        #[arg(long, value_name = "on|off", help = "Panic on int overflow in + - * (default: on unless --release or --unsafe)")]
+        overflow_checks: Option<String>,
+        #[arg(long, value_name = "BYTES", help = "Fail when the generated machine code exceeds BYTES, listing the largest functions")]
+        max_code_size: Option<usize>,
+        #[arg(long, value_name = "FORMAT", default_value = "human", help = "Diagnostic format: human or json")]
+        message_format: String,
     },
     Build {
         file: PathBuf,
@@ -43,12 +69,71 @@ enum Commands {
         release: bool,
         #[arg(long, help = "Unsafe mode: disable array bounds checking")]
         r#unsafe: bool,
+        #[arg(long, help = "Write a .naml-symbols.json map of generated symbols next to the binary")]
+        emit_symbols: bool,
+        #[arg(long, help = "Report compile time per phase and per function, also as .naml-timings.json next to the binary")]
+        timings: bool,
+        #[arg(long, value_name = "on|off", help = "Panic on int overflow in + - * (default: on unless --release or --unsafe)")]
+        overflow_checks: Option<String>,
+        #[arg(long, value_name = "BYTES", help = "Fail when the generated machine code exceeds BYTES, listing the largest functions")]
+        max_code_size: Option<usize>,
+        #[arg(long, value_name = "FORMAT", default_value = "human", help = "Diagnostic format: human or json")]
+        message_format: String,
     },
     Check {
         path: Option<PathBuf>,
+        #[arg(long = "plugin", help = "Load lint passes from a plugin library")]
+        plugins: Vec<PathBuf>,
+        #[arg(long, value_name = "FORMAT", default_value = "human", help = "Diagnostic format: human or json")]
+        message_format: String,
+    },
+    #[command(about = "Apply machine-applicable fixes: missing semicolons, unused imports, deprecated calls")]
+    Fix {
+        path: Option<PathBuf>,
+        #[arg(long, help = "Print the changes as a diff instead of writing them")]
+        dry_run: bool,
+    },
+    #[command(about = "Time bench_* and @bench functions, optionally against a saved baseline")]
+    Bench {
+        path: Option<PathBuf>,
+        #[arg(long, help = "Only run benchmarks whose name contains this")]
+        filter: Option<String>,
+        #[arg(long, value_name = "NAME", help = "Save the results as baseline NAME (build/bench/NAME.json)")]
+        save_baseline: Option<String>,
+        #[arg(long, value_name = "NAME", help = "Compare with baseline NAME; exit 1 if anything regressed")]
+        baseline: Option<String>,
+    },
+    #[command(about = "Feed generated inputs to a function taking bytes or a string and save any that crash it")]
+    Fuzz {
+        function: String,
+        path: Option<PathBuf>,
+        #[arg(long, default_value_t = 10_000, help = "Number of inputs to try")]
+        runs: u64,
+        #[arg(long, help = "Random seed (default: from the clock)")]
+        seed: Option<u64>,
+        #[arg(long, default_value_t = 4096, help = "Maximum input length in bytes")]
+        max_len: usize,
+        #[arg(long, default_value_t = 1000, help = "Time limit per input in milliseconds")]
+        timeout_ms: u64,
+        #[arg(long, value_name = "DIR", help = "Corpus directory (default: build/fuzz/FUNCTION/corpus)")]
+        corpus: Option<PathBuf>,
+        #[arg(long, help = "Count an exception escaping the function as a failure")]
+        fail_on_throw: bool,
+    },
+    #[command(about = "Generate files from a file's declarations with generator plugins")]
+    Codegen {
+        file: PathBuf,
+        #[arg(long = "plugin", help = "Load generators from a plugin library")]
+        plugins: Vec<PathBuf>,
+        #[arg(short, long, help = "Output directory (default: the file's directory)")]
+        out: Option<PathBuf>,
+        #[arg(long, help = "Print the stable AST view as JSON instead of running generators")]
+        json: bool,
     },
     Test {
         filter: Option<String>,
+        #[arg(long, value_name = "FORMAT", default_value = "human", help = "Diagnostic format: human or json")]
+        message_format: String,
     },
     #[command(about = "Package manager commands")]
     Pkg {
@@ -71,18 +156,85 @@ enum PkgCommands {
 fn main() {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Run { file, cached, release, r#unsafe } => {
-            run_file(&file, cached, release, r#unsafe);
+    if let Some(code) = cli.explain {
+        explain_error_code(&code);
+        return;
+    }
+    let Some(command) = cli.command else {
+        use clap::CommandFactory;
+        Cli::command().print_help().ok();
+        std::process::exit(2);
+    };
+
+    match command {
+        Commands::Run {
+            file,
+            cached,
+            release,
+            r#unsafe,
+            emit_symbols,
+            timings,
+            overflow_checks,
+            max_code_size,
+            message_format,
+        } => {
+            let format = parse_message_format(&message_format);
+            let overflow_checks = overflow_checks.as_deref().map(parse_overflow_checks);
+            run_file(&file, cached, release, r#unsafe, emit_symbols, timings, overflow_checks, max_code_size, format);
+        }
+        Commands::Build {
+            file,
+            output,
+            target,
+            release,
+            r#unsafe,
+            emit_symbols,
+            timings,
+            overflow_checks,
+            max_code_size,
+            message_format,
+        } => {
+            let format = parse_message_format(&message_format);
+            let overflow_checks = overflow_checks.as_deref().map(parse_overflow_checks);
+            build_project(
+                &file,
+                output.as_deref(),
+                &target,
+                release,
+                r#unsafe,
+                emit_symbols,
+                timings,
+                overflow_checks,
+                max_code_size,
+                format,
+            );
         }
-        Commands::Build { file, output, target, release, r#unsafe } => {
-            build_project(&file, output.as_deref(), &target, release, r#unsafe);
+        Commands::Check { path, plugins, message_format } => {
+            let format = parse_message_format(&message_format);
+            check_code(path.as_deref(), &plugins, format);
+        }
+        Commands::Fix { path, dry_run } => {
+            fix_code(path.as_deref(), dry_run);
+        }
+        Commands::Bench { path, filter, save_baseline, baseline } => {
+            cli::bench::bench_code(path.as_deref(), filter.as_deref(), save_baseline.as_deref(), baseline.as_deref());
+        }
+        Commands::Fuzz { function, path, runs, seed, max_len, timeout_ms, corpus, fail_on_throw } => {
+            let seed = seed.unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or(0)
+            });
+            let config = FuzzConfig { runs, max_len, timeout_ms, seed, fail_on_throw };
+            cli::fuzz::fuzz_code(&function, path.as_deref(), corpus.as_deref(), &config);
         }
-        Commands::Check { path } => {
-            check_code(path.as_deref());
+        Commands::Codegen { file, plugins, out, json } => {
+            codegen_file(&file, &plugins, out.as_deref(), json);
         }
-        Commands::Test { filter } => {
-            run_tests(filter.as_deref());
+        Commands::Test { filter, message_format } => {
+            let format = parse_message_format(&message_format);
+            run_tests(filter.as_deref(), format);
         }
         Commands::Pkg { command } => match command {
             PkgCommands::Init { name } => pkg_init(&name),
@@ -91,11 +243,33 @@ fn main() {
     }
 }
 
-fn run_file(file: &PathBuf, cached: bool, release: bool, unsafe_mode: bool) {
+fn explain_error_code(code: &str) {
+    match namlc::typechecker::explain::explain(code) {
+        Some(text) => print!("{}", text),
+        None => {
+            eprintln!("Error: no explanation for error code '{}'", code);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_file(
+    file: &PathBuf,
+    cached: bool,
+    release: bool,
+    unsafe_mode: bool,
+    emit_symbols: bool,
+    timings: bool,
+    overflow_checks: Option<bool>,
+    max_code_size: Option<usize>,
+    format: MessageFormat,
+) {
     if file.extension().map(|e| e != "nm").unwrap_or(true) {
         eprintln!("Error: expected a .nm file, got '{}'", file.display());
         std::process::exit(1);
     }
+    let mut timings = timings.then(|| Timings::new(file.with_extension("naml-timings.json")));
     let source_text = match std::fs::read_to_string(file) {
         Ok(s) => s,
         Err(e) => {
@@ -106,13 +280,13 @@ fn run_file(file: &PathBuf, cached: bool, release: bool, unsafe_mode: bool) {
 
     let file_name = file.display().to_string();
     let source_file = SourceFile::new(file_name.clone(), source_text.clone());
-    let (tokens, mut interner) = tokenize(&source_text);
+    let (tokens, mut interner) = timed(&mut timings, "lex", || tokenize(&source_text));
 
     let arena = AstArena::new();
-    let parse_result = parse(&tokens, &source_text, &arena);
+    let parse_result = timed(&mut timings, "parse", || parse(&tokens, &source_text, &arena));
 
     if !parse_result.errors.is_empty() {
-        let reporter = DiagnosticReporter::new(&source_file);
+        let reporter = DiagnosticReporter::with_format(&source_file, format);
         reporter.report_parse_errors(&parse_result.errors);
         std::process::exit(1);
     }
@@ -121,15 +295,13 @@ fn run_file(file: &PathBuf, cached: bool, release: bool, unsafe_mode: bool) {
 
     let pkg_manager = create_package_manager(source_dir.as_deref());
 
-    let type_result = check_with_types(
-        &parse_result.ast,
-        &mut interner,
-        source_dir,
-        pkg_manager.as_ref(),
-    );
+    let type_result = timed(&mut timings, "typecheck", || {
+        check_with_types(&parse_result.ast, &mut interner, source_dir, pkg_manager.as_ref())
+    });
 
+    let reporter = DiagnosticReporter::with_format(&source_file, format);
+    reporter.report_type_warnings(&type_result.warnings);
     if !type_result.errors.is_empty() {
-        let reporter = DiagnosticReporter::new(&source_file);
         reporter.report_type_errors(&type_result.errors);
         std::process::exit(1);
     }
@@ -138,6 +310,7 @@ fn run_file(file: &PathBuf, cached: bool, release: bool, unsafe_mode: bool) {
         eprintln!("(cached mode not yet implemented)");
     }
 
+    let symbol_map = emit_symbols.then(|| file.with_extension("naml-symbols.json"));
     match compile_and_run(
         &parse_result.ast,
         &interner,
@@ -147,6 +320,10 @@ fn run_file(file: &PathBuf, cached: bool, release: bool, unsafe_mode: bool) {
         release,
         unsafe_mode,
         CompilationTarget::Native,
+        symbol_map.as_deref(),
+        overflow_checks,
+        max_code_size,
+        timings.as_mut(),
     ) {
         Ok(()) => {}
         Err(e) => {
@@ -156,6 +333,36 @@ fn run_file(file: &PathBuf, cached: bool, release: bool, unsafe_mode: bool) {
     }
 }
 
+/// Run `f`, recording it as `phase` when `--timings` is on
+fn timed<T>(timings: &mut Option<Timings>, phase: &str, f: impl FnOnce() -> T) -> T {
+    match timings {
+        Some(timings) => timings.phase(phase, f),
+        None => f(),
+    }
+}
+
+fn parse_message_format(format: &str) -> MessageFormat {
+    match format {
+        "human" => MessageFormat::Human,
+        "json" => MessageFormat::Json,
+        _ => {
+            eprintln!("Error: unknown message format '{}'. Valid formats: human, json", format);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_overflow_checks(value: &str) -> bool {
+    match value {
+        "on" => true,
+        "off" => false,
+        _ => {
+            eprintln!("Error: unknown overflow check setting '{}'. Valid settings: on, off", value);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn parse_target(target: &str) -> CompilationTarget {
     match target {
         "native" => CompilationTarget::Native,
@@ -168,12 +375,18 @@ fn parse_target(target: &str) -> CompilationTarget {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_project(
     file: &PathBuf,
     output: Option<&std::path::Path>,
     target: &str,
     release: bool,
     unsafe_mode: bool,
+    emit_symbols: bool,
+    timings: bool,
+    overflow_checks: Option<bool>,
+    max_code_size: Option<usize>,
+    format: MessageFormat,
 ) {
     let compilation_target = parse_target(target);
 
@@ -186,6 +399,8 @@ fn build_project(
         eprintln!("Error: expected a .nm file, got '{}'", file.display());
         std::process::exit(1);
     }
+    // The report goes next to the binary, whose path is known further down
+    let mut timings = timings.then(|| Timings::new(PathBuf::new()));
 
     let source_text = match std::fs::read_to_string(file) {
         Ok(s) => s,
@@ -197,13 +412,13 @@ fn build_project(
 
     let file_name = file.display().to_string();
     let source_file = SourceFile::new(file_name.clone(), source_text.clone());
-    let (tokens, mut interner) = tokenize(&source_text);
+    let (tokens, mut interner) = timed(&mut timings, "lex", || tokenize(&source_text));
 
     let arena = AstArena::new();
-    let parse_result = parse(&tokens, &source_text, &arena);
+    let parse_result = timed(&mut timings, "parse", || parse(&tokens, &source_text, &arena));
 
     if !parse_result.errors.is_empty() {
-        let reporter = DiagnosticReporter::new(&source_file);
+        let reporter = DiagnosticReporter::with_format(&source_file, format);
         reporter.report_parse_errors(&parse_result.errors);
         std::process::exit(1);
     }
@@ -211,20 +426,45 @@ fn build_project(
     let source_dir = std::path::Path::new(&file_name).parent().map(|p| p.to_path_buf());
     let pkg_manager = create_package_manager(source_dir.as_deref());
 
-    let type_result = check_with_types_for_target(
-        &parse_result.ast,
-        &mut interner,
-        source_dir,
-        pkg_manager.as_ref(),
-        compilation_target,
-    );
+    let type_result = timed(&mut timings, "typecheck", || {
+        check_with_types_for_target(
+            &parse_result.ast,
+            &mut interner,
+            source_dir,
+            pkg_manager.as_ref(),
+            compilation_target,
+        )
+    });
 
+    let reporter = DiagnosticReporter::with_format(&source_file, format);
+    reporter.report_type_warnings(&type_result.warnings);
     if !type_result.errors.is_empty() {
-        let reporter = DiagnosticReporter::new(&source_file);
         reporter.report_type_errors(&type_result.errors);
         std::process::exit(1);
     }
 
+    let output_path = output.map(|p| p.to_path_buf()).unwrap_or_else(|| {
+        let build_dir = PathBuf::from("build");
+        if !build_dir.exists() {
+            std::fs::create_dir_all(&build_dir).unwrap_or_else(|e| {
+                eprintln!("Error creating build directory: {}", e);
+                std::process::exit(1);
+            });
+        }
+        let stem = file.file_stem().unwrap_or_default();
+        build_dir.join(stem)
+    });
+    let symbol_map = emit_symbols.then(|| {
+        let mut name = output_path.clone().into_os_string();
+        name.push(".naml-symbols.json");
+        PathBuf::from(name)
+    });
+    if let Some(timings) = &mut timings {
+        let mut name = output_path.clone().into_os_string();
+        name.push(".naml-timings.json");
+        timings.report_path = PathBuf::from(name);
+    }
+
     let obj_file = std::env::temp_dir().join("naml_build.o");
 
     match compile_to_object(
@@ -237,6 +477,10 @@ fn build_project(
         release,
         unsafe_mode,
         compilation_target,
+        symbol_map.as_deref(),
+        overflow_checks,
+        max_code_size,
+        timings.as_mut(),
     ) {
         Ok(()) => {}
         Err(e) => {
@@ -245,18 +489,6 @@ fn build_project(
         }
     }
 
-    let output_path = output.map(|p| p.to_path_buf()).unwrap_or_else(|| {
-        let build_dir = PathBuf::from("build");
-        if !build_dir.exists() {
-            std::fs::create_dir_all(&build_dir).unwrap_or_else(|e| {
-                eprintln!("Error creating build directory: {}", e);
-                std::process::exit(1);
-            });
-        }
-        let stem = file.file_stem().unwrap_or_default();
-        build_dir.join(stem)
-    });
-
     let runtime_lib = match namlc::linker::find_runtime_lib() {
         Ok(path) => path,
         Err(e) => {
@@ -266,7 +498,7 @@ fn build_project(
         }
     };
 
-    match namlc::linker::link(&obj_file, &output_path, &runtime_lib) {
+    match timed(&mut timings, "link", || namlc::linker::link(&obj_file, &output_path, &runtime_lib)) {
         Ok(()) => {
             println!("Built {}", output_path.display());
         }
@@ -278,21 +510,90 @@ fn build_project(
     }
 
     let _ = std::fs::remove_file(&obj_file);
+    if let Some(timings) = &timings {
+        timings.write_report();
+    }
 }
 
-fn check_code(path: Option<&std::path::Path>) {
+fn check_code(path: Option<&std::path::Path>, plugins: &[PathBuf], format: MessageFormat) {
     let path = path.unwrap_or(std::path::Path::new("."));
 
+    let mut lints = LintRegistry::new();
+    for plugin in plugins {
+        if let Err(e) = lints.load_plugin(plugin) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
     if path.is_file() {
-        check_file(path);
+        check_file(path, &mut lints, format);
     } else if path.is_dir() {
-        check_directory(path);
+        check_directory(path, &mut lints, format);
     } else {
         eprintln!("Error: {} does not exist", path.display());
         std::process::exit(1);
     }
 }
 
+fn codegen_file(path: &std::path::Path, plugins: &[PathBuf], out: Option<&std::path::Path>, json: bool) {
+    let ast = match namlc::parse_file(path) {
+        Ok(ast) => ast,
+        Err(ParseFileError::Syntax { errors }) => {
+            let source_text = std::fs::read_to_string(path).unwrap_or_default();
+            let source_file = SourceFile::new(path.display().to_string(), source_text);
+            DiagnosticReporter::new(&source_file).report_parse_errors(&errors);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&ast).expect("AST view serializes"));
+        return;
+    }
+
+    let mut generators = GeneratorRegistry::new();
+    for plugin in plugins {
+        if let Err(e) = generators.load_plugin(plugin) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    if generators.is_empty() {
+        eprintln!("Error: no generators; pass --plugin <lib>, or --json to print the AST view");
+        std::process::exit(1);
+    }
+
+    let files = match generators.run(&ast) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let out_dir = out
+        .map(std::path::Path::to_path_buf)
+        .unwrap_or_else(|| path.parent().map(std::path::Path::to_path_buf).unwrap_or_default());
+    for file in files {
+        let target = out_dir.join(&file.path);
+        if let Some(parent) = target.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            eprintln!("Error: cannot create {}: {}", parent.display(), e);
+            std::process::exit(1);
+        }
+        if let Err(e) = std::fs::write(&target, &file.contents) {
+            eprintln!("Error: cannot write {}: {}", target.display(), e);
+            std::process::exit(1);
+        }
+        println!("Generated {} ({})", target.display(), file.generator);
+    }
+}
+
 fn create_package_manager(source_dir: Option<&std::path::Path>) -> Option<naml_pkg::PackageManager> {
     let root = naml_pkg::find_project_root(source_dir?)?;
     let manifest_path = root.join("naml.toml");
@@ -312,7 +613,7 @@ fn create_package_manager(source_dir: Option<&std::path::Path>) -> Option<naml_p
     }
 }
 
-fn check_file(path: &std::path::Path) {
+fn check_file(path: &std::path::Path, lints: &mut LintRegistry, format: MessageFormat) {
     if path.extension().map(|e| e != "nm").unwrap_or(true) {
         eprintln!("Error: expected a .nm file, got '{}'", path.display());
         std::process::exit(1);
@@ -334,7 +635,7 @@ fn check_file(path: &std::path::Path) {
     let mut has_errors = false;
 
     if !parse_result.errors.is_empty() {
-        let reporter = DiagnosticReporter::new(&source_file);
+        let reporter = DiagnosticReporter::with_format(&source_file, format);
         reporter.report_parse_errors(&parse_result.errors);
         has_errors = true;
     }
@@ -342,28 +643,33 @@ fn check_file(path: &std::path::Path) {
     if !has_errors {
         let source_dir = path.parent().map(|p| p.to_path_buf());
         let pkg_manager = create_package_manager(source_dir.as_deref());
-        let type_errors = check_with_types(
+        let type_result = check_with_types(
             &parse_result.ast,
             &mut interner,
             source_dir,
             pkg_manager.as_ref(),
-        ).errors;
+        );
 
-        if !type_errors.is_empty() {
-            let reporter = DiagnosticReporter::new(&source_file);
-            reporter.report_type_errors(&type_errors);
+        let reporter = DiagnosticReporter::with_format(&source_file, format);
+        reporter.report_type_warnings(&type_result.warnings);
+        if !type_result.errors.is_empty() {
+            reporter.report_type_errors(&type_result.errors);
             has_errors = true;
+        } else if !lints.is_empty() {
+            let found = lints.run(&parse_result.ast, &interner, &type_result);
+            reporter.report_lints(&found);
+            has_errors = found.iter().any(|l| l.level == LintLevel::Error);
         }
     }
 
     if has_errors {
         std::process::exit(1);
-    } else {
+    } else if format == MessageFormat::Human {
         println!("No errors in {}", file_name);
     }
 }
 
-fn check_directory(path: &std::path::Path) {
+fn check_directory(path: &std::path::Path, lints: &mut LintRegistry, format: MessageFormat) {
     let pkg_manager = create_package_manager(Some(path));
     let mut checked = 0;
     let mut errors = 0;
@@ -392,23 +698,28 @@ fn check_directory(path: &std::path::Path) {
             let mut file_has_errors = false;
 
             if !parse_result.errors.is_empty() {
-                let reporter = DiagnosticReporter::new(&source_file);
+                let reporter = DiagnosticReporter::with_format(&source_file, format);
                 reporter.report_parse_errors(&parse_result.errors);
                 file_has_errors = true;
             }
 
             if !file_has_errors {
                 let source_dir = file_path.parent().map(|p| p.to_path_buf());
-                let type_errors = check_with_types(
+                let type_result = check_with_types(
                     &parse_result.ast,
                     &mut interner,
                     source_dir,
                     pkg_manager.as_ref(),
-                ).errors;
-                if !type_errors.is_empty() {
-                    let reporter = DiagnosticReporter::new(&source_file);
-                    reporter.report_type_errors(&type_errors);
+                );
+                let reporter = DiagnosticReporter::with_format(&source_file, format);
+                reporter.report_type_warnings(&type_result.warnings);
+                if !type_result.errors.is_empty() {
+                    reporter.report_type_errors(&type_result.errors);
                     file_has_errors = true;
+                } else if !lints.is_empty() {
+                    let found = lints.run(&parse_result.ast, &interner, &type_result);
+                    reporter.report_lints(&found);
+                    file_has_errors = found.iter().any(|l| l.level == LintLevel::Error);
                 }
             }
 
@@ -419,13 +730,99 @@ fn check_directory(path: &std::path::Path) {
         }
     }
 
-    println!("Checked {} files, {} with errors", checked, errors);
+    if format == MessageFormat::Human {
+        println!("Checked {} files, {} with errors", checked, errors);
+    }
 
     if errors > 0 {
         std::process::exit(1);
     }
 }
 
+/// `path` itself if it is a file, otherwise the .nm files below it
+fn nm_files(path: &std::path::Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        vec![path.to_path_buf()]
+    } else if path.is_dir() {
+        walkdir::WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().map(|e| e == "nm").unwrap_or(false))
+            .collect()
+    } else {
+        eprintln!("Error: {} does not exist", path.display());
+        std::process::exit(1);
+    }
+}
+
+fn fix_code(path: Option<&std::path::Path>, dry_run: bool) {
+    let path = path.unwrap_or(std::path::Path::new("."));
+    let files = nm_files(path);
+
+    let pkg_manager = create_package_manager(path.is_dir().then_some(path).or(path.parent()));
+    let mut fixed = 0;
+    let mut failed = 0;
+    for file in &files {
+        match fix_file(file, pkg_manager.as_ref(), dry_run) {
+            Ok(true) => fixed += 1,
+            Ok(false) => {}
+            Err(()) => failed += 1,
+        }
+    }
+
+    if !dry_run {
+        println!("Fixed {} of {} files, {} with errors", fixed, files.len(), failed);
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Fix one file; `Ok(true)` if anything changed (or would, in a dry run)
+fn fix_file(
+    path: &std::path::Path,
+    pkg_manager: Option<&naml_pkg::PackageManager>,
+    dry_run: bool,
+) -> Result<bool, ()> {
+    let source_text = std::fs::read_to_string(path).map_err(|e| {
+        eprintln!("Error reading {}: {}", path.display(), e);
+    })?;
+    let file_name = path.display().to_string();
+
+    let outcome = match namlc::fix_source(&source_text, path.parent(), pkg_manager) {
+        Ok(outcome) => outcome,
+        Err(FixError::Parse { text, errors }) => {
+            let source_file = SourceFile::new(file_name, text);
+            DiagnosticReporter::new(&source_file).report_parse_errors(&errors);
+            return Err(());
+        }
+        Err(FixError::Type { text, errors }) => {
+            let source_file = SourceFile::new(file_name, text);
+            DiagnosticReporter::new(&source_file).report_type_errors(&errors);
+            return Err(());
+        }
+        Err(e) => {
+            eprintln!("Error: {}: {}", file_name, e);
+            return Err(());
+        }
+    };
+    if outcome.is_unchanged() {
+        return Ok(false);
+    }
+
+    for fix in &outcome.applied {
+        eprintln!("{}:{}: fixed {}", file_name, fix.line, fix.message);
+    }
+    if dry_run {
+        print!("{}", namlc::fix::unified_diff(&file_name, &source_text, &outcome.source));
+    } else if let Err(e) = std::fs::write(path, &outcome.source) {
+        eprintln!("Error writing {}: {}", file_name, e);
+        return Err(());
+    }
+    Ok(true)
+}
+
 fn pkg_init(name: &str) {
     let dir = PathBuf::from(name);
     match naml_pkg::init_project(name, &dir) {
@@ -476,7 +873,10 @@ fn pkg_get() {
     }
 }
 
-fn run_tests(filter: Option<&str>) {
+fn run_tests(filter: Option<&str>, format: MessageFormat) {
+    if format == MessageFormat::Json {
+        return;
+    }
     if let Some(f) = filter {
         println!("Running tests matching: {}", f);
     } else {
@@ -34,6 +34,7 @@ pub enum PErrorKind {
     MutNotAllowedOnVar,
     MutNotAllowedOnReceiver,
     NamedParamInFnType,
+    AttributeNotAllowed,
     Nom(ErrorKind),
 }
 
@@ -169,6 +169,11 @@ fn parse_postfix<'a, 'ast>(
                 input = new_input;
                 expr = new_expr;
             }
+            Some(TokenKind::Keyword(Keyword::Finally)) => {
+                let (new_input, new_expr) = parse_finally(arena, input, expr)?;
+                input = new_input;
+                expr = new_expr;
+            }
             Some(TokenKind::Bang) => {
                 // Force unwrap: expr!
                 let (new_input, new_expr) = parse_force_unwrap(arena, input, expr)?;
@@ -934,7 +939,25 @@ fn parse_catch<'a, 'ast>(
 ) -> PResult<'a, Expression<'ast>> {
     let start_span = expr.span();
     let (input, _) = keyword(Keyword::Catch)(input)?;
-    let (input, error_binding) = ident(input)?;
+    let (input, error_binding, error_types) = if check(TokenKind::LParen)(input) {
+        // catch (e: IOError | NetworkError) { ... }
+        let (input, _) = token(TokenKind::LParen)(input)?;
+        let (input, error_binding) = ident(input)?;
+        let (input, _) = token(TokenKind::Colon)(input)?;
+        let (mut input, first) = parse_type(input)?;
+        let mut error_types = vec![first];
+        while check(TokenKind::Pipe)(input) {
+            let (next, _) = token(TokenKind::Pipe)(input)?;
+            let (next, ty) = parse_type(next)?;
+            error_types.push(ty);
+            input = next;
+        }
+        let (input, _) = token(TokenKind::RParen)(input)?;
+        (input, error_binding, error_types)
+    } else {
+        let (input, error_binding) = ident(input)?;
+        (input, error_binding, Vec::new())
+    };
     let (input, handler_block) = parse_block(arena, input)?;
     let handler = BlockExpr {
         statements: handler_block.statements,
@@ -948,12 +971,38 @@ fn parse_catch<'a, 'ast>(
         Expression::Catch(CatchExpr {
             expr: arena.alloc(expr),
             error_binding,
+            error_types,
             handler: arena.alloc(handler),
             span,
         }),
     ))
 }
 
+fn parse_finally<'a, 'ast>(
+    arena: &'ast AstArena,
+    input: TokenStream<'a>,
+    expr: Expression<'ast>,
+) -> PResult<'a, Expression<'ast>> {
+    let start_span = expr.span();
+    let (input, _) = keyword(Keyword::Finally)(input)?;
+    let (input, body_block) = parse_block(arena, input)?;
+    let body = BlockExpr {
+        statements: body_block.statements,
+        tail: None,
+        span: body_block.span,
+    };
+    let span = start_span.merge(body.span);
+
+    Ok((
+        input,
+        Expression::Finally(FinallyExpr {
+            expr: arena.alloc(expr),
+            body: arena.alloc(body),
+            span,
+        }),
+    ))
+}
+
 fn parse_force_unwrap<'a, 'ast>(
     arena: &'ast AstArena,
     input: TokenStream<'a>,
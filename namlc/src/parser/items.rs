@@ -21,11 +21,23 @@ pub fn parse_item<'a, 'ast>(
     arena: &'ast AstArena,
     input: TokenStream<'a>,
 ) -> PResult<'a, Item<'ast>> {
-    let (input, platforms) = if check(TokenKind::Hash)(input) {
-        parse_platforms_attr(input)?
-    } else {
-        (input, None)
-    };
+    let mut platforms = None;
+    let mut attributes = Vec::new();
+    let mut input = input;
+    loop {
+        if check(TokenKind::Hash)(input) {
+            let (rest, parsed) = parse_platforms_attr(input)?;
+            platforms = parsed;
+            input = rest;
+        } else if check(TokenKind::At)(input) {
+            let (rest, attribute) = parse_attribute(input)?;
+            attributes.push(attribute);
+            input = rest;
+        } else {
+            break;
+        }
+    }
+    let attributes_start = input;
 
     let (input, is_public) = if check_keyword(Keyword::Pub)(input) {
         let (input, _) = keyword(Keyword::Pub)(input)?;
@@ -34,22 +46,107 @@ pub fn parse_item<'a, 'ast>(
         (input, false)
     };
 
-    match input.first().map(|t| t.kind) {
+    let (input, mut item) = match input.first().map(|t| t.kind) {
         Some(TokenKind::Keyword(Keyword::Fn)) => {
             parse_function_item(arena, input, is_public, platforms)
         }
+        Some(TokenKind::Keyword(Keyword::Const))
+            if input.tokens.get(1).map(|t| t.kind) == Some(TokenKind::Keyword(Keyword::Fn)) =>
+        {
+            parse_function_item(arena, input, is_public, platforms)
+        }
         Some(TokenKind::Keyword(Keyword::Struct)) => parse_struct_item(input, is_public),
         Some(TokenKind::Keyword(Keyword::Enum)) => parse_enum_item(input, is_public),
-        Some(TokenKind::Keyword(Keyword::Interface)) => parse_interface_item(input, is_public),
+        Some(TokenKind::Keyword(Keyword::Interface)) => {
+            parse_interface_item(arena, input, is_public)
+        }
         Some(TokenKind::Keyword(Keyword::Exception)) => parse_exception_item(input, is_public),
-        Some(TokenKind::Keyword(Keyword::Use)) => parse_use_item(input),
+        Some(TokenKind::Keyword(Keyword::Use)) => parse_use_item(input, is_public),
         Some(TokenKind::Keyword(Keyword::Extern)) => parse_extern_item(input),
         Some(TokenKind::Keyword(Keyword::Mod)) => parse_mod_item(arena, input, is_public),
         Some(TokenKind::Keyword(Keyword::Type)) => parse_type_alias_item(input, is_public),
         _ => parse_top_level_stmt(arena, input),
+    }?;
+
+    if !attributes.is_empty() {
+        match item.attributes_mut() {
+            Some(slot) => *slot = attributes,
+            None => {
+                return Err(nom::Err::Error(PError {
+                    input: attributes_start,
+                    kind: PErrorKind::AttributeNotAllowed,
+                }));
+            }
+        }
     }
+    Ok((input, item))
 }
 
+/// Parse `@name` or `@name(arg, ...)` where each argument is a literal or
+/// an identifier
+fn parse_attribute<'a>(input: TokenStream<'a>) -> PResult<'a, Attribute> {
+    let (input, start) = token(TokenKind::At)(input)?;
+    let (input, name) = ident(input)?;
+    if !check(TokenKind::LParen)(input) {
+        let span = start.span.merge(name.span);
+        return Ok((input, Attribute { name, args: Vec::new(), span }));
+    }
+
+    let (mut input, _) = token(TokenKind::LParen)(input)?;
+    let mut args = Vec::new();
+    while !check(TokenKind::RParen)(input) {
+        let (rest, arg) = parse_attribute_arg(input)?;
+        args.push(arg);
+        input = rest;
+        if !check(TokenKind::Comma)(input) {
+            break;
+        }
+        let (rest, _) = token(TokenKind::Comma)(input)?;
+        input = rest;
+    }
+    let (input, end) = token(TokenKind::RParen)(input)?;
+
+    Ok((
+        input,
+        Attribute {
+            name,
+            args,
+            span: start.span.merge(end.span),
+        },
+    ))
+}
+
+fn parse_attribute_arg<'a>(input: TokenStream<'a>) -> PResult<'a, AttributeArg> {
+    match input.first().map(|t| t.kind) {
+        Some(TokenKind::StringLit) => {
+            let (input, (value, span)) = string_lit(input)?;
+            Ok((input, AttributeArg::Literal(Literal::String(value), span)))
+        }
+        Some(TokenKind::IntLit) => {
+            let (input, (value, span)) = int_lit(input)?;
+            Ok((input, AttributeArg::Literal(Literal::Int(value), span)))
+        }
+        Some(TokenKind::FloatLit) => {
+            let (input, (value, span)) = float_lit(input)?;
+            Ok((input, AttributeArg::Literal(Literal::Float(value), span)))
+        }
+        Some(TokenKind::Keyword(Keyword::True)) => {
+            let (input, tok) = keyword(Keyword::True)(input)?;
+            Ok((input, AttributeArg::Literal(Literal::Bool(true), tok.span)))
+        }
+        Some(TokenKind::Keyword(Keyword::False)) => {
+            let (input, tok) = keyword(Keyword::False)(input)?;
+            Ok((input, AttributeArg::Literal(Literal::Bool(false), tok.span)))
+        }
+        _ => {
+            let (input, name) = ident(input)?;
+            Ok((input, AttributeArg::Ident(name)))
+        }
+    }
+}
+
+
+
 fn parse_platforms_attr<'a>(input: TokenStream<'a>) -> PResult<'a, Option<Platforms>> {
     let (input, start) = token(TokenKind::Hash)(input)?;
     let (input, _) = token(TokenKind::LBracket)(input)?;
@@ -106,7 +203,15 @@ fn parse_function_item<'a, 'ast>(
     is_public: bool,
     platforms: Option<Platforms>,
 ) -> PResult<'a, Item<'ast>> {
-    let (input, start) = keyword(Keyword::Fn)(input)?;
+    let (input, const_kw) = if check_keyword(Keyword::Const)(input) {
+        let (input, tok) = keyword(Keyword::Const)(input)?;
+        (input, Some(tok))
+    } else {
+        (input, None)
+    };
+    let is_const = const_kw.is_some();
+    let (input, fn_kw) = keyword(Keyword::Fn)(input)?;
+    let start = const_kw.unwrap_or(fn_kw);
 
     let (input, receiver) = if check(TokenKind::LParen)(input) {
         let (input, recv) = parse_receiver(input)?;
@@ -184,8 +289,10 @@ fn parse_function_item<'a, 'ast>(
             return_ty,
             throws,
             is_public,
+            is_const,
             body,
             platforms,
+            attributes: Vec::new(),
             span: start.span.merge(end_span),
         }),
     ))
@@ -291,6 +398,7 @@ fn parse_struct_item<'a, 'ast>(input: TokenStream<'a>, is_public: bool) -> PResu
             implements,
             fields,
             is_public,
+            attributes: Vec::new(),
             span: start.span.merge(end.span),
         }),
     ))
@@ -355,6 +463,7 @@ fn parse_enum_item<'a, 'ast>(input: TokenStream<'a>, is_public: bool) -> PResult
             generics,
             variants,
             is_public,
+            attributes: Vec::new(),
             span: start.span.merge(end.span),
         }),
     ))
@@ -397,6 +506,7 @@ fn parse_enum_variants<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<EnumVarian
 }
 
 fn parse_interface_item<'a, 'ast>(
+    arena: &'ast AstArena,
     input: TokenStream<'a>,
     is_public: bool,
 ) -> PResult<'a, Item<'ast>> {
@@ -418,7 +528,7 @@ fn parse_interface_item<'a, 'ast>(
     };
 
     let (input, _) = token(TokenKind::LBrace)(input)?;
-    let (input, methods) = parse_interface_methods(input)?;
+    let (input, methods) = parse_interface_methods(arena, input)?;
     let (input, end) = token(TokenKind::RBrace)(input)?;
 
     Ok((
@@ -429,12 +539,16 @@ fn parse_interface_item<'a, 'ast>(
             extends,
             methods,
             is_public,
+            attributes: Vec::new(),
             span: start.span.merge(end.span),
         }),
     ))
 }
 
-fn parse_interface_methods<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<InterfaceMethod>> {
+fn parse_interface_methods<'a, 'ast>(
+    arena: &'ast AstArena,
+    input: TokenStream<'a>,
+) -> PResult<'a, Vec<InterfaceMethod<'ast>>> {
     let mut methods = Vec::with_capacity(8);
     let mut input = input;
 
@@ -443,7 +557,14 @@ fn parse_interface_methods<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<Interf
             break;
         }
 
-        let (new_input, start) = keyword(Keyword::Fn)(input)?;
+        let (new_input, static_kw) = if check_keyword(Keyword::Static)(input) {
+            let (i, kw) = keyword(Keyword::Static)(input)?;
+            (i, Some(kw))
+        } else {
+            (input, None)
+        };
+        let (new_input, fn_kw) = keyword(Keyword::Fn)(new_input)?;
+        let start = static_kw.unwrap_or(fn_kw);
         let (new_input, name) = ident(new_input)?;
 
         let (new_input, generics) = if check(TokenKind::Lt)(new_input) {
@@ -467,7 +588,7 @@ fn parse_interface_methods<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<Interf
 
         let (new_input, throws) = if check_keyword(Keyword::Throws)(new_input) {
             let (mut i, _) = keyword(Keyword::Throws)(new_input)?;
-            if check(TokenKind::Semicolon)(i) {
+            if check(TokenKind::Semicolon)(i) || check(TokenKind::LBrace)(i) {
                 (i, vec![])
             } else {
                 let mut throws_types = Vec::new();
@@ -477,7 +598,7 @@ fn parse_interface_methods<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<Interf
 
                 while check(TokenKind::Comma)(i) {
                     let (new_i, _) = token(TokenKind::Comma)(i)?;
-                    if check(TokenKind::Semicolon)(new_i) {
+                    if check(TokenKind::Semicolon)(new_i) || check(TokenKind::LBrace)(new_i) {
                         break;
                     }
                     let (new_i, ty) = parse_type(new_i)?;
@@ -491,7 +612,14 @@ fn parse_interface_methods<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<Interf
             (new_input, vec![])
         };
 
-        let (new_input, end) = token(TokenKind::Semicolon)(new_input)?;
+        let (new_input, body, end_span) = if check(TokenKind::LBrace)(new_input) {
+            let (i, block) = parse_block(arena, new_input)?;
+            let end_span = block.span;
+            (i, Some(block), end_span)
+        } else {
+            let (i, end) = token(TokenKind::Semicolon)(new_input)?;
+            (i, None, end.span)
+        };
 
         methods.push(InterfaceMethod {
             name,
@@ -499,7 +627,9 @@ fn parse_interface_methods<'a>(input: TokenStream<'a>) -> PResult<'a, Vec<Interf
             params,
             return_ty,
             throws,
-            span: start.span.merge(end.span),
+            is_static: static_kw.is_some(),
+            body,
+            span: start.span.merge(end_span),
         });
 
         input = new_input;
@@ -550,12 +680,13 @@ fn parse_exception_item<'a, 'ast>(
             name,
             fields,
             is_public,
+            attributes: Vec::new(),
             span: start.span.merge(end.span),
         }),
     ))
 }
 
-fn parse_use_item<'a, 'ast>(input: TokenStream<'a>) -> PResult<'a, Item<'ast>> {
+fn parse_use_item<'a, 'ast>(input: TokenStream<'a>, is_public: bool) -> PResult<'a, Item<'ast>> {
     let (input, start) = keyword(Keyword::Use)(input)?;
 
     let (input, first) = ident(input)?;
@@ -577,7 +708,16 @@ fn parse_use_item<'a, 'ast>(input: TokenStream<'a>) -> PResult<'a, Item<'ast>> {
 
     let (input, items) = if check(TokenKind::Star)(input) {
         let (input, _) = token(TokenKind::Star)(input)?;
-        (input, UseItems::All)
+        let (input, except) = if check_keyword(Keyword::Except)(input) {
+            let (input, _) = keyword(Keyword::Except)(input)?;
+            let (input, _) = token(TokenKind::LBrace)(input)?;
+            let (input, entries) = parse_use_entries(input)?;
+            let (input, _) = token(TokenKind::RBrace)(input)?;
+            (input, entries.into_iter().map(|e| e.name).collect())
+        } else {
+            (input, Vec::new())
+        };
+        (input, UseItems::All { except })
     } else if check(TokenKind::LBrace)(input) {
         let (input, _) = token(TokenKind::LBrace)(input)?;
         let (input, entries) = parse_use_entries(input)?;
@@ -600,6 +740,7 @@ fn parse_use_item<'a, 'ast>(input: TokenStream<'a>) -> PResult<'a, Item<'ast>> {
         Item::Use(UseItem {
             path,
             items,
+            is_public,
             span: start.span.merge(end.span),
         }),
     ))
@@ -729,6 +870,7 @@ fn parse_type_alias_item<'a, 'ast>(
             generics,
             aliased_type,
             is_public,
+            attributes: Vec::new(),
             span: start.span.merge(end.span),
         }),
     ))
@@ -73,6 +73,9 @@ fn error_message(kind: &PErrorKind) -> String {
         PErrorKind::NamedParamInFnType => {
             "function types don't support named parameters; use `fn(int)` not `fn(x: int)`".to_string()
         }
+        PErrorKind::AttributeNotAllowed => {
+            "attributes are only allowed on functions and type definitions".to_string()
+        }
         PErrorKind::Nom(ek) => format!("parse error: {:?}", ek),
     }
 }
@@ -127,6 +130,7 @@ pub fn parse<'ast>(tokens: &[Token], source: &str, arena: &'ast AstArena) -> Par
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::{Item, UseItems};
     use crate::lexer::tokenize;
 
     fn assert_parses(source: &str) {
@@ -164,6 +168,33 @@ mod tests {
         assert_parses_items("fn add(a: int, b: int) -> int { return a + b; }", 1);
     }
 
+    #[test]
+    fn test_parse_const_fn() {
+        assert_parses_items("const fn sq(n: int) -> int { return n * n; } const N: int = sq(4);", 2);
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let source = "@inline @deprecated(\"use add\", 2) #[platforms(native)] pub fn plus(a: int) -> int { return a; }\n@serialize struct P { x: int }";
+        let (tokens, interner) = tokenize(source);
+        let arena = AstArena::new();
+        let result = parse(&tokens, source, &arena);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let Item::Function(func) = &result.ast.items[0] else {
+            panic!("expected a function");
+        };
+        assert!(func.platforms.is_some());
+        let names: Vec<_> = func.attributes.iter().map(|a| interner.resolve(&a.name.symbol)).collect();
+        assert_eq!(names, ["inline", "deprecated"]);
+        assert_eq!(func.attributes[1].args.len(), 2);
+        assert_eq!(result.ast.items[1].attributes().len(), 1);
+
+        let source = "@test use std::strings::*;";
+        let (tokens, _interner) = tokenize(source);
+        let result = parse(&tokens, source, &arena);
+        assert!(result.errors[0].message.contains("attributes are only allowed"));
+    }
+
     #[test]
     fn test_parse_struct() {
         assert_parses_items("struct Point { x: int, y: int }", 1);
@@ -199,6 +230,20 @@ mod tests {
         assert_parses_items("use std::random::*;", 1);
     }
 
+    #[test]
+    fn test_parse_use_except_and_reexport() {
+        let source = "use std::strings::* except { split, join };\npub use crate::db::{connect as open};";
+        let (tokens, _interner) = tokenize(source);
+        let arena = AstArena::new();
+        let result = parse(&tokens, source, &arena);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let Item::Use(glob) = &result.ast.items[0] else { panic!("expected use") };
+        assert!(matches!(&glob.items, UseItems::All { except } if except.len() == 2));
+        assert!(!glob.is_public);
+        let Item::Use(reexport) = &result.ast.items[1] else { panic!("expected use") };
+        assert!(reexport.is_public);
+    }
+
     #[test]
     fn test_parse_method() {
         assert_parses("fn (self: Point) distance() -> float { return 0.0; }");
@@ -170,12 +170,20 @@ fn parse_throw_stmt<'a, 'ast>(
 ) -> PResult<'a, Statement<'ast>> {
     let (input, start) = keyword(Keyword::Throw)(input)?;
     let (input, value) = parse_expression(arena, input)?;
+    let (input, cause) = if check_keyword(Keyword::CausedBy)(input) {
+        let (input, _) = keyword(Keyword::CausedBy)(input)?;
+        let (input, cause) = parse_expression(arena, input)?;
+        (input, Some(cause))
+    } else {
+        (input, None)
+    };
     let (input, _) = token(TokenKind::Semicolon)(input)?;
 
     Ok((
         input,
         Statement::Throw(ThrowStmt {
             value,
+            cause,
             span: start.span,
         }),
     ))
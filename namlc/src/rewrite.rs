@@ -0,0 +1,377 @@
+//!
+//! Rewrite Module - Lossless Source Edits
+//!
+//! The parser's AST drops whitespace and comments, so printing it back
+//! loses the user's formatting. Automated refactors (`naml fix`, plugin
+//! code actions) edit the original text instead:
+//!
+//! - `SyntaxFile::parse` keeps every token, trivia included, so
+//!   `SyntaxFile::text` reproduces the source byte for byte, and records
+//!   the span of each top-level item
+//! - `Rewriter` collects edits against that text and applies them in one
+//!   pass; edits never move each other, because every span refers to the
+//!   original source
+//!
+//! An item's `full_span` covers what belongs to it in the source: its
+//! attributes, the `pub` keyword and the comment lines directly above it
+//! (no blank line in between). `span` is the parser's span of the item.
+//!
+//! Edits must not overlap. Insertions (empty spans) at the same offset are
+//! applied in the order they were added.
+//!
+//! Usage:
+//!   let file = SyntaxFile::parse(source)?;
+//!   let mut rewriter = Rewriter::new(&file);
+//!   rewriter.replace_span(span, "new_name");
+//!   rewriter.insert_before_item(0, "use std::io::*;");
+//!   let fixed = rewriter.apply()?;
+//!
+
+use thiserror::Error;
+
+use crate::ast::{AstArena, Item};
+use crate::lexer::{tokenize, tokenize_with_trivia, Token, TokenKind};
+use crate::parser::{parse, ParseError};
+use crate::source::{Span, Spanned};
+
+/// A top-level item of a `SyntaxFile`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemNode {
+    /// Declared name; `None` for imports and top-level statements
+    pub name: Option<String>,
+    pub span: Span,
+    pub full_span: Span,
+}
+
+/// Source text with all of its tokens, trivia included
+#[derive(Debug, Clone)]
+pub struct SyntaxFile {
+    source: String,
+    tokens: Vec<Token>,
+    items: Vec<ItemNode>,
+}
+
+impl SyntaxFile {
+    pub fn parse(source: &str) -> Result<SyntaxFile, Vec<ParseError>> {
+        let (parser_tokens, interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&parser_tokens, source, &arena);
+        if !parsed.errors.is_empty() {
+            return Err(parsed.errors);
+        }
+
+        let tokens = tokenize_with_trivia(source);
+        let mut items = Vec::with_capacity(parsed.ast.items.len());
+        let mut prev_end = 0;
+        for item in &parsed.ast.items {
+            let span = item.span();
+            let name = match item {
+                Item::Function(f) => Some(f.name.symbol),
+                Item::Struct(s) => Some(s.name.symbol),
+                Item::Interface(i) => Some(i.name.symbol),
+                Item::Enum(e) => Some(e.name.symbol),
+                Item::Exception(e) => Some(e.name.symbol),
+                Item::TypeAlias(t) => Some(t.name.symbol),
+                Item::Mod(m) => Some(m.name.symbol),
+                Item::Extern(e) => Some(e.name.symbol),
+                Item::Use(_) | Item::TopLevelStmt(_) => None,
+            };
+            let full_start = leading_start(&tokens, prev_end, span.start);
+            items.push(ItemNode {
+                name: name.map(|s| interner.resolve(&s).to_string()),
+                span,
+                full_span: Span::new(full_start, span.end, span.file_id),
+            });
+            prev_end = span.end;
+        }
+
+        Ok(SyntaxFile {
+            source: source.to_string(),
+            tokens,
+            items,
+        })
+    }
+
+    /// The source, unchanged
+    pub fn text(&self) -> &str {
+        &self.source
+    }
+
+    pub fn slice(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    /// Every token in source order, trivia included; their spans tile the source
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    pub fn items(&self) -> &[ItemNode] {
+        &self.items
+    }
+
+    /// The first item declared with `name`
+    pub fn item(&self, name: &str) -> Option<&ItemNode> {
+        self.items.iter().find(|item| item.name.as_deref() == Some(name))
+    }
+
+    /// Offset of the start of the line containing `offset`
+    fn line_start(&self, offset: u32) -> u32 {
+        self.source[..offset as usize]
+            .rfind('\n')
+            .map_or(0, |i| i as u32 + 1)
+    }
+}
+
+/// Where the source belonging to an item starting at `start` begins: the
+/// first token after the previous item (attributes, `pub`), extended over
+/// comment lines directly above it
+fn leading_start(tokens: &[Token], prev_end: u32, start: u32) -> u32 {
+    let gap: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| t.span.start >= prev_end && t.span.end <= start)
+        .collect();
+    let first_significant = gap.iter().position(|t| !t.is_trivia());
+    let mut begin = first_significant.unwrap_or(gap.len());
+
+    // Walk back over comments, stopping at a blank line
+    let mut newlines = 0;
+    let mut i = begin;
+    while i > 0 {
+        let token = gap[i - 1];
+        match token.kind {
+            TokenKind::Comment => {
+                begin = i - 1;
+                newlines = 0;
+            }
+            TokenKind::Newline => {
+                newlines += 1;
+                if newlines > 1 {
+                    break;
+                }
+            }
+            TokenKind::Whitespace => {}
+            _ => break,
+        }
+        i -= 1;
+    }
+    gap.get(begin).map_or(start, |t| t.span.start)
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RewriteError {
+    #[error("edits at {}..{} and {}..{} overlap", first.start, first.end, second.start, second.end)]
+    Overlap { first: Span, second: Span },
+    #[error("edit at {}..{} is outside the source ({} bytes)", span.start, span.end, len)]
+    OutOfBounds { span: Span, len: usize },
+    #[error("edit at {}..{} splits a character", span.start, span.end)]
+    NotCharBoundary { span: Span },
+    #[error("no item {index}, the file has {count}")]
+    NoSuchItem { index: usize, count: usize },
+}
+
+/// Replace the text at `span` with `text`; an empty span inserts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub span: Span,
+    pub text: String,
+}
+
+/// Collects edits against a `SyntaxFile` and applies them together
+pub struct Rewriter<'a> {
+    file: &'a SyntaxFile,
+    edits: Vec<Edit>,
+}
+
+impl<'a> Rewriter<'a> {
+    pub fn new(file: &'a SyntaxFile) -> Self {
+        Self {
+            file,
+            edits: Vec::new(),
+        }
+    }
+
+    pub fn replace_span(&mut self, span: Span, text: impl Into<String>) {
+        self.edits.push(Edit {
+            span,
+            text: text.into(),
+        });
+    }
+
+    pub fn delete_span(&mut self, span: Span) {
+        self.replace_span(span, "");
+    }
+
+    pub fn insert_at(&mut self, offset: u32, text: impl Into<String>) {
+        self.replace_span(Span::new(offset, offset, 0), text);
+    }
+
+    /// Insert `text` as whole lines above item `index` (above its
+    /// attributes and comments), indented like the item
+    pub fn insert_before_item(&mut self, index: usize, text: &str) -> Result<(), RewriteError> {
+        let item = self.item(index)?;
+        let line_start = self.file.line_start(item.full_span.start);
+        let indent = &self.file.source[line_start as usize..item.full_span.start as usize];
+        let indent = if indent.trim().is_empty() { indent } else { "" };
+        let mut lines = String::new();
+        for line in text.lines() {
+            if !line.is_empty() {
+                lines.push_str(indent);
+            }
+            lines.push_str(line);
+            lines.push('\n');
+        }
+        self.insert_at(line_start, lines);
+        Ok(())
+    }
+
+    /// Delete item `index` with its attributes, comments and the rest of
+    /// its last line
+    pub fn remove_item(&mut self, index: usize) -> Result<(), RewriteError> {
+        let item = self.item(index)?;
+        let source = &self.file.source;
+        let line_start = self.file.line_start(item.full_span.start);
+        let start = if source[line_start as usize..item.full_span.start as usize].trim().is_empty() {
+            line_start
+        } else {
+            item.full_span.start
+        };
+        let rest = &source[item.full_span.end as usize..];
+        let end = match rest.find('\n') {
+            Some(i) if rest[..i].trim().is_empty() => item.full_span.end + i as u32 + 1,
+            _ => item.full_span.end,
+        };
+        self.delete_span(Span::new(start, end, item.span.file_id));
+        Ok(())
+    }
+
+    fn item(&self, index: usize) -> Result<ItemNode, RewriteError> {
+        self.file
+            .items
+            .get(index)
+            .cloned()
+            .ok_or(RewriteError::NoSuchItem {
+                index,
+                count: self.file.items.len(),
+            })
+    }
+
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// The source with every edit applied
+    pub fn apply(&self) -> Result<String, RewriteError> {
+        let source = &self.file.source;
+        let mut order: Vec<&Edit> = self.edits.iter().collect();
+        // Stable: insertions at one offset keep the order they were added in
+        order.sort_by_key(|e| (e.span.start, e.span.end));
+
+        for edit in &order {
+            let (start, end) = (edit.span.start as usize, edit.span.end as usize);
+            if start > end || end > source.len() {
+                return Err(RewriteError::OutOfBounds {
+                    span: edit.span,
+                    len: source.len(),
+                });
+            }
+            if !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+                return Err(RewriteError::NotCharBoundary { span: edit.span });
+            }
+        }
+        for pair in order.windows(2) {
+            let (a, b) = (pair[0].span, pair[1].span);
+            if b.start < a.end {
+                return Err(RewriteError::Overlap {
+                    first: a,
+                    second: b,
+                });
+            }
+        }
+
+        let mut out = String::with_capacity(source.len());
+        let mut pos = 0;
+        for edit in order {
+            out.push_str(&source[pos..edit.span.start as usize]);
+            out.push_str(&edit.text);
+            pos = edit.span.end as usize;
+        }
+        out.push_str(&source[pos..]);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "use std::io::*;\n\n// Adds two numbers\n@inline\npub fn add(a: int, b: int) -> int {\n    return a + b; // sum\n}\n\nfn main() {\n    print(add(1, 2));\n}\n";
+
+    #[test]
+    fn test_lossless_tokens() {
+        let file = SyntaxFile::parse(SOURCE).unwrap();
+        let text: String = file.tokens().iter().map(|t| file.slice(t.span)).collect();
+        assert_eq!(text, SOURCE);
+        assert_eq!(file.text(), SOURCE);
+    }
+
+    #[test]
+    fn test_item_spans() {
+        let file = SyntaxFile::parse(SOURCE).unwrap();
+        let names: Vec<Option<&str>> = file.items().iter().map(|i| i.name.as_deref()).collect();
+        assert_eq!(names, [None, Some("add"), Some("main")]);
+        let add = file.item("add").unwrap();
+        assert!(file.slice(add.full_span).starts_with("// Adds two numbers\n@inline\npub fn add"));
+        assert!(file.slice(add.span).starts_with("fn add"));
+        let main = file.item("main").unwrap();
+        assert_eq!(main.full_span, main.span);
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let file = SyntaxFile::parse(SOURCE).unwrap();
+        let add = file.item("add").unwrap().span;
+        let name = Span::new(add.start + 3, add.start + 6, 0);
+        assert_eq!(file.slice(name), "add");
+
+        let mut rewriter = Rewriter::new(&file);
+        rewriter.replace_span(name, "sum");
+        rewriter.insert_before_item(1, "use std::math::*;").unwrap();
+        rewriter.remove_item(0).unwrap();
+        let fixed = rewriter.apply().unwrap();
+        assert!(fixed.starts_with("\nuse std::math::*;\n// Adds two numbers\n@inline\npub fn sum(a: int"), "{}", fixed);
+        assert!(fixed.contains("return a + b; // sum"));
+    }
+
+    #[test]
+    fn test_insert_keeps_indentation() {
+        let source = "mod util {\n    fn helper() {}\n}\n";
+        let file = SyntaxFile::parse(source).unwrap();
+        let mut rewriter = Rewriter::new(&file);
+        rewriter.insert_before_item(0, "// utilities").unwrap();
+        assert_eq!(rewriter.apply().unwrap(), format!("// utilities\n{}", source));
+    }
+
+    #[test]
+    fn test_rejects_bad_edits() {
+        let file = SyntaxFile::parse(SOURCE).unwrap();
+        let mut rewriter = Rewriter::new(&file);
+        rewriter.replace_span(Span::new(0, 10, 0), "x");
+        rewriter.replace_span(Span::new(5, 12, 0), "y");
+        assert!(matches!(rewriter.apply(), Err(RewriteError::Overlap { .. })));
+
+        let mut rewriter = Rewriter::new(&file);
+        rewriter.delete_span(Span::new(0, 10_000, 0));
+        assert!(matches!(rewriter.apply(), Err(RewriteError::OutOfBounds { .. })));
+
+        let mut rewriter = Rewriter::new(&file);
+        assert_eq!(
+            rewriter.remove_item(9),
+            Err(RewriteError::NoSuchItem { index: 9, count: 3 })
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//!
+//! Timings Module - Compile-Time Profiling for `--timings`
+//!
+//! `naml run --timings` and `naml build --timings` record how long each
+//! compiler phase took (lex, parse, typecheck, codegen, then finalize for
+//! the JIT or emit and link for a binary) and how long codegen spent on
+//! each function, from building its IR through Cranelift's compilation.
+//!
+//! The report goes to stderr as text, listing the phases and the slowest
+//! functions, and to a `.naml-timings.json` file next to the source (run)
+//! or the binary (build) with every function.
+//!
+//! Usage:
+//!   let mut timings = Timings::new(file.with_extension("naml-timings.json"));
+//!   let ast = timings.phase("parse", || parse(&tokens, &source, &arena));
+//!   timings.write_report();
+//!
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::bench::format_ns;
+
+/// Number of functions listed in the text report
+pub const SLOWEST_SHOWN: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTime {
+    pub name: String,
+    pub ms: f64,
+}
+
+/// Time codegen spent on one generated function
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionTime {
+    pub name: String,
+    pub kind: &'static str,
+    pub ms: f64,
+}
+
+/// Layout of the JSON report, with functions from slowest to fastest
+#[derive(Serialize)]
+struct Report<'a> {
+    total_ms: f64,
+    phases: &'a [PhaseTime],
+    functions: Vec<&'a FunctionTime>,
+}
+
+#[derive(Debug)]
+pub struct Timings {
+    /// Where `write_report` puts the JSON report
+    pub report_path: PathBuf,
+    pub phases: Vec<PhaseTime>,
+    /// Every function in compilation order; see `slowest`
+    pub functions: Vec<FunctionTime>,
+}
+
+fn millis(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+impl FunctionTime {
+    pub fn new(name: &str, kind: &'static str, elapsed: Duration) -> Self {
+        FunctionTime { name: name.to_string(), kind, ms: millis(elapsed) }
+    }
+}
+
+impl Timings {
+    pub fn new(report_path: PathBuf) -> Self {
+        Timings { report_path, phases: Vec::new(), functions: Vec::new() }
+    }
+
+    /// Run `f` and record its duration as phase `name`
+    pub fn phase<T>(&mut self, name: &str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.record_phase(name, started.elapsed());
+        result
+    }
+
+    pub fn record_phase(&mut self, name: &str, elapsed: Duration) {
+        self.phases.push(PhaseTime { name: name.to_string(), ms: millis(elapsed) });
+    }
+
+    pub fn record_function(&mut self, name: &str, kind: &'static str, elapsed: Duration) {
+        self.functions.push(FunctionTime::new(name, kind, elapsed));
+    }
+
+    pub fn total_ms(&self) -> f64 {
+        self.phases.iter().map(|p| p.ms).sum()
+    }
+
+    /// Functions ordered from slowest to fastest
+    pub fn slowest(&self) -> Vec<&FunctionTime> {
+        let mut functions: Vec<&FunctionTime> = self.functions.iter().collect();
+        functions.sort_by(|a, b| b.ms.total_cmp(&a.ms));
+        functions
+    }
+
+    pub fn to_text(&self) -> String {
+        let total = self.total_ms();
+        let mut out = String::from("Compile timings:\n");
+        for phase in &self.phases {
+            let share = if total > 0.0 { phase.ms / total * 100.0 } else { 0.0 };
+            out.push_str(&format!("  {:<10} {:>10}  {:>5.1}%\n", phase.name, format_ns(phase.ms * 1e6), share));
+        }
+        out.push_str(&format!("  {:<10} {:>10}\n", "total", format_ns(total * 1e6)));
+
+        if !self.functions.is_empty() {
+            out.push_str(&format!("Slowest functions ({} compiled):\n", self.functions.len()));
+            for function in self.slowest().into_iter().take(SLOWEST_SHOWN) {
+                out.push_str(&format!("  {:>10}  {} ({})\n", format_ns(function.ms * 1e6), function.name, function.kind));
+            }
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        let report = Report { total_ms: self.total_ms(), phases: &self.phases, functions: self.slowest() };
+        serde_json::to_string_pretty(&report).unwrap_or_default()
+    }
+
+    /// Print the text report to stderr and write the JSON report to
+    /// `report_path`; failing to write it only prints a warning
+    pub fn write_report(&self) {
+        eprint!("{}", self.to_text());
+        match std::fs::write(&self.report_path, self.to_json()) {
+            Ok(()) => eprintln!("Timings written to {}", self.report_path.display()),
+            Err(e) => eprintln!("Warning: cannot write {}: {}", self.report_path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Timings {
+        let mut timings = Timings::new(PathBuf::from("timings.json"));
+        timings.record_phase("parse", Duration::from_millis(3));
+        timings.record_phase("codegen", Duration::from_millis(7));
+        timings.record_function("fast", "function", Duration::from_millis(1));
+        timings.record_function("slow", "method", Duration::from_millis(5));
+        timings
+    }
+
+    #[test]
+    fn test_phase_records_duration() {
+        let mut timings = Timings::new(PathBuf::from("timings.json"));
+        assert_eq!(timings.phase("lex", || 42), 42);
+        assert_eq!(timings.phases.len(), 1);
+        assert_eq!(timings.phases[0].name, "lex");
+    }
+
+    #[test]
+    fn test_text_report() {
+        let text = sample().to_text();
+        assert!(text.contains("parse"), "{}", text);
+        assert!(text.contains("30.0%"), "{}", text);
+        assert!(text.contains("Slowest functions (2 compiled)"), "{}", text);
+        assert!(text.find("slow (method)").unwrap() < text.find("fast (function)").unwrap(), "{}", text);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json: serde_json::Value = serde_json::from_str(&sample().to_json()).unwrap();
+        assert_eq!(json["total_ms"].as_f64().unwrap().round(), 10.0);
+        assert_eq!(json["phases"][1]["name"], "codegen");
+        assert_eq!(json["functions"][0]["name"], "slow");
+        assert_eq!(json["functions"][0]["kind"], "method");
+    }
+
+    #[test]
+    fn test_write_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut timings = sample();
+        timings.report_path = dir.path().join("app.naml-timings.json");
+        timings.write_report();
+        let text = std::fs::read_to_string(&timings.report_path).unwrap();
+        assert!(text.contains("\"slow\""), "{}", text);
+    }
+}
@@ -0,0 +1,127 @@
+//!
+//! Item Attributes
+//!
+//! Items can carry `@name` or `@name(args)` attributes. The parser keeps
+//! them on the AST, the symbol table keeps them on function and method
+//! signatures, and this module validates the ones the compiler understands:
+//!
+//! - `@test`: a test function, with no receiver, parameters or return type
+//! - `@bench`: a benchmark for `naml bench`, shaped like a test function
+//! - `@inline`: lets the inliner take the function regardless of its size
+//! - `@deprecated`, `@deprecated("note")` or `@deprecated("note", new_name)`:
+//!   warns at every call site; with `new_name`, `naml fix` rewrites the
+//!   calls to use it
+//! - `@serialize`: marks a struct or enum for serialization
+//!
+//! Other attribute names are accepted unchecked so tools and codegen plugins
+//! can define their own (E0039 covers misuse of the built-in ones).
+//!
+
+use lasso::Rodeo;
+
+use crate::ast::{Attribute, AttributeArg, Item, Literal};
+
+use super::error::TypeError;
+
+/// Report misuse of the built-in attributes on `item`
+pub fn check_attributes(item: &Item<'_>, interner: &Rodeo, errors: &mut Vec<TypeError>) {
+    for attribute in item.attributes() {
+        let name = interner.resolve(&attribute.name.symbol);
+        let reason = match name {
+            "test" => check_runnable(item, false).or_else(|| no_args(attribute)),
+            "bench" => check_runnable(item, true).or_else(|| no_args(attribute)),
+            "inline" => match item {
+                Item::Function(_) => no_args(attribute),
+                _ => Some("only functions can be inlined"),
+            },
+            "deprecated" => match item {
+                Item::Function(_) => check_deprecated(attribute),
+                _ => Some("only functions and methods can be deprecated"),
+            },
+            "serialize" => match item {
+                Item::Struct(_) | Item::Enum(_) => no_args(attribute),
+                _ => Some("only structs and enums can be serialized"),
+            },
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            errors.push(TypeError::InvalidAttribute {
+                name: name.to_string(),
+                reason: reason.to_string(),
+                span: attribute.span,
+            });
+        }
+    }
+}
+
+/// Tests and benchmarks are called by their runner with no arguments
+fn check_runnable(item: &Item<'_>, bench: bool) -> Option<&'static str> {
+    let Item::Function(func) = item else {
+        return Some(if bench { "only functions can be benchmarks" } else { "only functions can be tests" });
+    };
+    if func.receiver.is_some() || !func.generics.is_empty() {
+        Some(if bench {
+            "a benchmark must be a plain function, not a method or generic"
+        } else {
+            "a test must be a plain function, not a method or generic"
+        })
+    } else if !func.params.is_empty() || func.return_ty.is_some() {
+        Some(if bench {
+            "a benchmark takes no parameters and returns nothing"
+        } else {
+            "a test takes no parameters and returns nothing"
+        })
+    } else {
+        None
+    }
+}
+
+fn check_deprecated(attribute: &Attribute) -> Option<&'static str> {
+    match attribute.args.as_slice() {
+        []
+        | [AttributeArg::Literal(Literal::String(_), _)]
+        | [AttributeArg::Literal(Literal::String(_), _), AttributeArg::Ident(_)] => None,
+        _ => Some("expected no arguments, a note string, or a note and a replacement name"),
+    }
+}
+
+fn no_args(attribute: &Attribute) -> Option<&'static str> {
+    if attribute.args.is_empty() {
+        None
+    } else {
+        Some("takes no arguments")
+    }
+}
+
+/// Whether `attributes` include one called `name`
+pub fn has_attribute(attributes: &[Attribute], interner: &Rodeo, name: &str) -> bool {
+    attributes
+        .iter()
+        .any(|a| interner.resolve(&a.name.symbol) == name)
+}
+
+/// What `@deprecated` says about an item
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deprecation {
+    pub note: Option<String>,
+    /// Name to call instead, from `@deprecated("note", new_name)`
+    pub replacement: Option<String>,
+}
+
+/// `Some` if `attributes` mark the item as deprecated
+pub fn deprecation(attributes: &[Attribute], interner: &Rodeo) -> Option<Deprecation> {
+    let attribute = attributes
+        .iter()
+        .find(|a| interner.resolve(&a.name.symbol) == "deprecated")?;
+    let note = match attribute.args.first() {
+        Some(AttributeArg::Literal(Literal::String(note), _)) => {
+            Some(interner.resolve(note).to_string())
+        }
+        _ => None,
+    };
+    let replacement = match attribute.args.get(1) {
+        Some(AttributeArg::Ident(name)) => Some(interner.resolve(&name.symbol).to_string()),
+        _ => None,
+    };
+    Some(Deprecation { note, replacement })
+}
@@ -0,0 +1,393 @@
+//!
+//! Const Fn Interpreter
+//!
+//! Runs the statements and expressions of a const fn body. Values and the
+//! operators on them live in `value`.
+//!
+
+use std::collections::HashMap;
+
+use lasso::{Rodeo, Spur};
+
+use crate::ast::{self, BinaryOp, Expression, FunctionItem, NamlType, Statement};
+
+use crate::typechecker::typed_ast::TypeAnnotations;
+
+use super::builtin_name;
+use super::value::{binary, call_builtin, fail, literal_value, unary, EvalError, Value};
+
+/// Statements and expressions one top-level evaluation may execute
+const STEP_LIMIT: usize = 10_000_000;
+
+/// Nesting of const fn calls one evaluation may reach
+const DEPTH_LIMIT: usize = 256;
+
+/// How a statement finished
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+pub(super) struct Evaluator<'a, 'f, 'ast> {
+    pub(super) interner: &'a Rodeo,
+    pub(super) annotations: &'a TypeAnnotations,
+    pub(super) functions: &'a HashMap<Spur, &'f FunctionItem<'ast>>,
+    pub(super) globals: HashMap<Spur, Value>,
+    pub(super) steps: usize,
+    pub(super) depth: usize,
+}
+
+/// The local variables of one const fn call
+type Frame = Vec<HashMap<Spur, Value>>;
+
+impl Evaluator<'_, '_, '_> {
+    fn step(&mut self) -> Result<(), EvalError> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return fail(format!("exceeded the limit of {} evaluation steps", STEP_LIMIT));
+        }
+        Ok(())
+    }
+
+    pub(super) fn call(&mut self, name: Spur, args: Vec<Value>) -> Result<Value, EvalError> {
+        let Some(func) = self.functions.get(&name).copied() else {
+            return fail(format!("'{}' is not a const fn", self.interner.resolve(&name)));
+        };
+        let Some(body) = &func.body else {
+            return Ok(Value::Unit);
+        };
+        if self.depth >= DEPTH_LIMIT {
+            return fail(format!("calls nested more than {} deep", DEPTH_LIMIT));
+        }
+
+        let mut frame: Frame = vec![func
+            .params
+            .iter()
+            .map(|p| p.name.symbol)
+            .zip(args)
+            .collect()];
+        self.depth += 1;
+        let flow = self.exec_block(&body.statements, &mut frame);
+        self.depth -= 1;
+        match flow? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(Value::Unit),
+        }
+    }
+
+    fn exec_block(&mut self, stmts: &[Statement<'_>], frame: &mut Frame) -> Result<Flow, EvalError> {
+        frame.push(HashMap::new());
+        let mut flow = Ok(Flow::Normal);
+        for stmt in stmts {
+            flow = self.exec(stmt, frame);
+            if !matches!(flow, Ok(Flow::Normal)) {
+                break;
+            }
+        }
+        frame.pop();
+        flow
+    }
+
+    fn exec(&mut self, stmt: &Statement<'_>, frame: &mut Frame) -> Result<Flow, EvalError> {
+        self.step()?;
+        match stmt {
+            Statement::Var(v) => {
+                let value = match &v.init {
+                    Some(init) => self.eval(init, frame)?,
+                    None => Value::Unit,
+                };
+                define(frame, v.name.symbol, value);
+            }
+            Statement::Const(c) => {
+                let value = self.eval(&c.init, frame)?;
+                define(frame, c.name.symbol, value);
+            }
+            Statement::Assign(a) => {
+                let mut value = self.eval(&a.value, frame)?;
+                if let Some(op) = a.op.to_binary_op() {
+                    let current = self.eval(&a.target, frame)?;
+                    value = binary(op, current, value)?;
+                }
+                self.assign(&a.target, value, frame)?;
+            }
+            Statement::Expression(e) => {
+                self.eval(&e.expr, frame)?;
+            }
+            Statement::Return(r) => {
+                let value = match &r.value {
+                    Some(value) => self.eval(value, frame)?,
+                    None => Value::Unit,
+                };
+                return Ok(Flow::Return(value));
+            }
+            Statement::If(if_stmt) => return self.exec_if(if_stmt, frame),
+            Statement::While(w) => loop {
+                if !self.condition(&w.condition, frame)? {
+                    break;
+                }
+                match self.exec_block(&w.body.statements, frame)? {
+                    Flow::Break => break,
+                    Flow::Return(value) => return Ok(Flow::Return(value)),
+                    Flow::Normal | Flow::Continue => {}
+                }
+            },
+            Statement::Loop(l) => loop {
+                self.step()?;
+                match self.exec_block(&l.body.statements, frame)? {
+                    Flow::Break => break,
+                    Flow::Return(value) => return Ok(Flow::Return(value)),
+                    Flow::Normal | Flow::Continue => {}
+                }
+            },
+            Statement::For(f) => return self.exec_for(f, frame),
+            Statement::Break(_) => return Ok(Flow::Break),
+            Statement::Continue(_) => return Ok(Flow::Continue),
+            Statement::Block(block) => return self.exec_block(&block.statements, frame),
+            _ => return fail("this statement cannot run at compile time"),
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn exec_if(&mut self, if_stmt: &ast::IfStmt<'_>, frame: &mut Frame) -> Result<Flow, EvalError> {
+        if self.condition(&if_stmt.condition, frame)? {
+            return self.exec_block(&if_stmt.then_branch.statements, frame);
+        }
+        match &if_stmt.else_branch {
+            Some(ast::ElseBranch::ElseIf(else_if)) => self.exec_if(else_if, frame),
+            Some(ast::ElseBranch::Else(block)) => self.exec_block(&block.statements, frame),
+            None => Ok(Flow::Normal),
+        }
+    }
+
+    fn exec_for(&mut self, f: &ast::ForStmt<'_>, frame: &mut Frame) -> Result<Flow, EvalError> {
+        let items: Vec<Value> = match range_bounds(&f.iterable) {
+            Some((start, end, inclusive)) => {
+                let start = match start {
+                    Some(start) => self.int(start, frame)?,
+                    None => 0,
+                };
+                let Some(end) = end else {
+                    return fail("an unbounded range cannot run at compile time");
+                };
+                let end = self.int(end, frame)?;
+                let end = if inclusive { end.saturating_add(1) } else { end };
+                if end.saturating_sub(start) as usize > STEP_LIMIT {
+                    return fail(format!("exceeded the limit of {} evaluation steps", STEP_LIMIT));
+                }
+                (start..end).map(Value::Int).collect()
+            }
+            None => match self.eval(&f.iterable, frame)? {
+                Value::Array(elements) => elements.borrow().clone(),
+                Value::Str(s) => s.chars().map(|c| Value::Str(c.to_string().into())).collect(),
+                _ => return fail("this value cannot be iterated at compile time"),
+            },
+        };
+
+        for (i, item) in items.into_iter().enumerate() {
+            self.step()?;
+            frame.push(HashMap::new());
+            if let Some(index) = &f.index {
+                define(frame, index.symbol, Value::Int(i as i64));
+            }
+            define(frame, f.value.symbol, item);
+            let flow = self.exec_block(&f.body.statements, frame);
+            frame.pop();
+            match flow? {
+                Flow::Break => break,
+                Flow::Return(value) => return Ok(Flow::Return(value)),
+                Flow::Normal | Flow::Continue => {}
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn condition(&mut self, expr: &Expression<'_>, frame: &mut Frame) -> Result<bool, EvalError> {
+        match self.eval(expr, frame)? {
+            Value::Bool(b) => Ok(b),
+            _ => fail("condition is not a bool"),
+        }
+    }
+
+    fn int(&mut self, expr: &Expression<'_>, frame: &mut Frame) -> Result<i64, EvalError> {
+        match self.eval(expr, frame)? {
+            Value::Int(n) => Ok(n),
+            _ => fail("expected an int"),
+        }
+    }
+
+    fn assign(&mut self, target: &Expression<'_>, value: Value, frame: &mut Frame) -> Result<(), EvalError> {
+        match target {
+            Expression::Identifier(ident) => {
+                let name = ident.ident.symbol;
+                match frame.iter_mut().rev().find_map(|scope| scope.get_mut(&name)) {
+                    Some(slot) => *slot = value,
+                    None => {
+                        return fail(format!("cannot assign to '{}'", self.interner.resolve(&name)));
+                    }
+                }
+                Ok(())
+            }
+            Expression::Index(idx) => {
+                let base = self.eval(idx.base, frame)?;
+                let index = self.int(idx.index, frame)?;
+                let Value::Array(elements) = base else {
+                    return fail("only array elements can be assigned");
+                };
+                let mut elements = elements.borrow_mut();
+                let len = elements.len();
+                match usize::try_from(index).ok().and_then(|i| elements.get_mut(i)) {
+                    Some(slot) => *slot = value,
+                    None => {
+                        return fail(format!("index {} is out of bounds for an array of length {}", index, len));
+                    }
+                }
+                Ok(())
+            }
+            _ => fail("this assignment cannot run at compile time"),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expression<'_>, frame: &mut Frame) -> Result<Value, EvalError> {
+        self.step()?;
+        match expr {
+            Expression::Literal(lit) => match literal_value(self.interner, &lit.value) {
+                Some(value) => Ok(value),
+                None => fail("this literal cannot be used at compile time"),
+            },
+            Expression::Identifier(ident) => {
+                let name = ident.ident.symbol;
+                if let Some(value) = frame.iter().rev().find_map(|scope| scope.get(&name)) {
+                    return Ok(value.clone());
+                }
+                match self.globals.get(&name) {
+                    Some(value) => Ok(value.deep_copy()),
+                    None => fail(format!("'{}' is not known at compile time", self.interner.resolve(&name))),
+                }
+            }
+            Expression::Grouped(g) => self.eval(g.inner, frame),
+            Expression::Unary(u) => {
+                let operand = self.eval(u.operand, frame)?;
+                unary(u.op, operand)
+            }
+            Expression::Binary(b) => match b.op {
+                BinaryOp::And => {
+                    Ok(Value::Bool(self.condition(b.left, frame)? && self.condition(b.right, frame)?))
+                }
+                BinaryOp::Or => {
+                    Ok(Value::Bool(self.condition(b.left, frame)? || self.condition(b.right, frame)?))
+                }
+                BinaryOp::NullCoalesce => match self.eval(b.left, frame)? {
+                    Value::Option(Some(value)) => Ok(*value),
+                    Value::Option(None) => self.eval(b.right, frame),
+                    value => Ok(value),
+                },
+                op => {
+                    let left = self.eval(b.left, frame)?;
+                    let right = self.eval(b.right, frame)?;
+                    binary(op, left, right)
+                }
+            },
+            Expression::Ternary(t) => {
+                if self.condition(t.condition, frame)? {
+                    self.eval(t.true_expr, frame)
+                } else {
+                    self.eval(t.false_expr, frame)
+                }
+            }
+            Expression::Elvis(e) => match self.eval(e.left, frame)? {
+                Value::Option(Some(value)) => Ok(*value),
+                Value::Option(None) => self.eval(e.right, frame),
+                value => Ok(value),
+            },
+            Expression::Array(arr) => {
+                let elements = arr
+                    .elements
+                    .iter()
+                    .map(|e| self.eval(e, frame))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::array(elements))
+            }
+            Expression::Index(idx) => {
+                let base = self.eval(idx.base, frame)?;
+                let index = self.int(idx.index, frame)?;
+                match base {
+                    Value::Array(elements) => Ok(Value::Option(
+                        usize::try_from(index)
+                            .ok()
+                            .and_then(|i| elements.borrow().get(i).cloned())
+                            .map(Box::new),
+                    )),
+                    Value::Str(s) => Ok(Value::Str(
+                        usize::try_from(index)
+                            .ok()
+                            .and_then(|i| s.chars().nth(i))
+                            .map(|c| c.to_string())
+                            .unwrap_or_default()
+                            .into(),
+                    )),
+                    _ => fail("this value cannot be indexed at compile time"),
+                }
+            }
+            Expression::Some(s) => Ok(Value::Option(Some(Box::new(self.eval(s.value, frame)?)))),
+            Expression::ForceUnwrap(u) => match self.eval(u.expr, frame)? {
+                Value::Option(Some(value)) => Ok(*value),
+                Value::Option(None) => match u.expr {
+                    Expression::Index(_) => fail("index out of bounds"),
+                    _ => fail("unwrapped a none value"),
+                },
+                value => Ok(value),
+            },
+            Expression::Cast(cast) => {
+                let value = self.eval(cast.expr, frame)?;
+                match (&cast.target_ty, value) {
+                    (NamlType::Int, Value::Int(n)) => Ok(Value::Int(n)),
+                    (NamlType::Int, Value::Float(f)) => Ok(Value::Int(f as i64)),
+                    (NamlType::Float, Value::Int(n)) => Ok(Value::Float(n as f64)),
+                    (NamlType::Float, Value::Float(f)) => Ok(Value::Float(f)),
+                    _ => fail("this cast cannot run at compile time"),
+                }
+            }
+            Expression::Call(call) => {
+                let Expression::Identifier(callee) = call.callee else {
+                    return fail("only named functions can be called at compile time");
+                };
+                let args = call
+                    .args
+                    .iter()
+                    .map(|arg| self.eval(arg, frame))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let name = callee.ident.symbol;
+                if self.functions.contains_key(&name) {
+                    return self.call(name, args);
+                }
+                match builtin_name(self.annotations, self.interner, name, call.span) {
+                    Some(builtin) => call_builtin(builtin, args),
+                    None => fail(format!("'{}' is not a const fn", self.interner.resolve(&name))),
+                }
+            }
+            _ => fail("this expression cannot run at compile time"),
+        }
+    }
+}
+
+/// The start, end and inclusiveness of a range loop's iterable
+fn range_bounds<'e, 'ast>(
+    iterable: &'e Expression<'ast>,
+) -> Option<(Option<&'e Expression<'ast>>, Option<&'e Expression<'ast>>, bool)> {
+    match iterable {
+        Expression::Range(range) => Some((range.start, range.end, range.inclusive)),
+        Expression::Binary(bin) if matches!(bin.op, BinaryOp::Range | BinaryOp::RangeIncl) => {
+            Some((Some(bin.left), Some(bin.right), bin.op == BinaryOp::RangeIncl))
+        }
+        _ => None,
+    }
+}
+
+fn define(frame: &mut Frame, name: Spur, value: Value) {
+    if let Some(scope) = frame.last_mut() {
+        scope.insert(name, value);
+    }
+}
+
@@ -0,0 +1,487 @@
+//!
+//! Compile-Time Evaluation of Const Functions
+//!
+//! Functions declared with `const fn` can be evaluated while compiling.
+//! Once the program has type checked, every call to a const fn whose
+//! arguments are constants is run by a small interpreter over the AST and
+//! the result is recorded in the type annotations, so codegen emits the
+//! value instead of the call. Lookup tables, sizes and precomputed strings
+//! built by a const fn therefore cost nothing when the program starts.
+//!
+//! Constant arguments are literals, arrays of constants, operators applied
+//! to constants, top-level `const` declarations with constant initializers
+//! and calls to const fns with constant arguments.
+//!
+//! A const fn body may use locals, arithmetic, strings, arrays, control flow,
+//! calls to other const fns and the pure std functions in `CONST_BUILTINS`.
+//! Anything else is reported when the program is checked, whether or not the
+//! function is ever evaluated (E0038). Failures during evaluation, such as a
+//! division by zero or running past `STEP_LIMIT`, are reported at the call
+//! that triggered them (E0037).
+//!
+
+mod eval;
+mod value;
+
+use std::collections::{HashMap, HashSet};
+
+use lasso::{Rodeo, Spur};
+
+use crate::ast::visitor::{self, Visitor};
+use crate::ast::{
+    BinaryOp, Expression, FunctionItem, Item, Literal, NamlType, Pattern, SourceFile, Statement,
+};
+use crate::source::{Span, Spanned};
+
+use super::error::TypeError;
+use super::typed_ast::TypeAnnotations;
+
+use eval::Evaluator;
+use value::{binary, literal_value, unary, Value};
+
+/// Std functions a const fn may call, by module-qualified name
+const CONST_BUILTINS: &[&str] = &[
+    "collections::arrays::count",
+    "collections::arrays::push",
+    "strings::len",
+    "strings::upper",
+    "strings::lower",
+];
+
+/// A value computed at compile time, as recorded for codegen
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<ConstValue>),
+}
+
+/// Check the bodies of the file's const fns, then evaluate the calls to them
+/// that have constant arguments
+pub fn evaluate_constants(
+    file: &SourceFile<'_>,
+    interner: &Rodeo,
+    annotations: &mut TypeAnnotations,
+    errors: &mut Vec<TypeError>,
+) {
+    let functions: HashMap<Spur, &FunctionItem<'_>> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(f) if f.is_const && f.receiver.is_none() => Some((f.name.symbol, f)),
+            _ => None,
+        })
+        .collect();
+    if functions.is_empty() {
+        return;
+    }
+    let const_globals: HashSet<Spur> = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::TopLevelStmt(s) => match &s.stmt {
+                Statement::Const(c) => Some(c.name.symbol),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    for func in functions.values() {
+        let mut check = BodyCheck {
+            interner,
+            annotations,
+            functions: &functions,
+            const_globals: &const_globals,
+            locals: declared_names(func),
+            errors,
+        };
+        if let Some(body) = &func.body {
+            for stmt in &body.statements {
+                check.visit_stmt(stmt);
+            }
+        }
+    }
+    // Only evaluate programs that are known to be well-typed
+    if !errors.is_empty() {
+        return;
+    }
+
+    let mut folder = CallFolder {
+        eval: Evaluator {
+            interner,
+            annotations,
+            functions: &functions,
+            globals: HashMap::new(),
+            steps: 0,
+            depth: 0,
+        },
+        locals: HashSet::new(),
+        values: HashMap::new(),
+        errors: Vec::new(),
+    };
+
+    // Top-level constants first, in order, so later constants and all call
+    // sites can use them as constant arguments
+    for item in &file.items {
+        if let Item::TopLevelStmt(s) = item
+            && let Statement::Const(c) = &s.stmt
+        {
+            folder.visit_expr(&c.init);
+            if let Some(value) = folder.constant(&c.init) {
+                folder.eval.globals.insert(c.name.symbol, value);
+            }
+        }
+    }
+    for item in &file.items {
+        match item {
+            Item::Function(f) => {
+                folder.locals = declared_names(f);
+                if let Some(body) = &f.body {
+                    for stmt in &body.statements {
+                        folder.visit_stmt(stmt);
+                    }
+                }
+            }
+            Item::TopLevelStmt(s) if !matches!(s.stmt, Statement::Const(_)) => {
+                folder.locals.clear();
+                folder.visit_stmt(&s.stmt);
+            }
+            _ => {}
+        }
+    }
+
+    errors.extend(folder.errors);
+    for (span, value) in folder.values {
+        annotations.record_const_value(span, value);
+    }
+}
+
+/// The names a function declares: parameters, locals, loop variables,
+/// lambda parameters and pattern bindings. A use of one of them is never a
+/// top-level constant.
+fn declared_names(func: &FunctionItem<'_>) -> HashSet<Spur> {
+    struct Declared(HashSet<Spur>);
+
+    impl<'ast> Visitor<'ast> for Declared {
+        fn visit_stmt(&mut self, stmt: &Statement<'ast>) {
+            match stmt {
+                Statement::Var(v) => {
+                    self.0.insert(v.name.symbol);
+                }
+                Statement::Const(c) => {
+                    self.0.insert(c.name.symbol);
+                }
+                Statement::For(f) => {
+                    self.0.insert(f.value.symbol);
+                    if let Some(index) = &f.index {
+                        self.0.insert(index.symbol);
+                    }
+                }
+                _ => {}
+            }
+            visitor::walk_stmt(self, stmt)
+        }
+
+        fn visit_expr(&mut self, expr: &Expression<'ast>) {
+            if let Expression::Lambda(lambda) = expr {
+                self.0.extend(lambda.params.iter().map(|p| p.name.symbol));
+            }
+            visitor::walk_expr(self, expr)
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern<'ast>) {
+            if let Pattern::Identifier(ident) = pattern {
+                self.0.insert(ident.ident.symbol);
+            }
+            visitor::walk_pattern(self, pattern)
+        }
+    }
+
+    let mut declared = Declared(func.params.iter().map(|p| p.name.symbol).collect());
+    if let Some(recv) = &func.receiver {
+        declared.0.insert(recv.name.symbol);
+    }
+    if let Some(body) = &func.body {
+        for stmt in &body.statements {
+            declared.visit_stmt(stmt);
+        }
+    }
+    declared.0
+}
+
+/// Reports what a const fn body uses that the evaluator cannot run
+struct BodyCheck<'a, 'f, 'ast> {
+    interner: &'a Rodeo,
+    annotations: &'a TypeAnnotations,
+    functions: &'a HashMap<Spur, &'f FunctionItem<'ast>>,
+    const_globals: &'a HashSet<Spur>,
+    locals: HashSet<Spur>,
+    errors: &'a mut Vec<TypeError>,
+}
+
+impl BodyCheck<'_, '_, '_> {
+    fn reject(&mut self, what: String, span: Span) {
+        self.errors.push(TypeError::NotConst { what, span });
+    }
+}
+
+impl<'ast> Visitor<'ast> for BodyCheck<'_, '_, 'ast> {
+    fn visit_stmt(&mut self, stmt: &Statement<'ast>) {
+        let rejected = match stmt {
+            Statement::Var(v) if v.else_block.is_some() => Some("`var ... else`"),
+            Statement::Throw(_) => Some("`throw`"),
+            Statement::Switch(_) => Some("`switch`"),
+            Statement::Locked(_) => Some("a locked block"),
+            Statement::Assign(a)
+                if !matches!(a.target, Expression::Identifier(_) | Expression::Index(_)) =>
+            {
+                Some("assignment to a field")
+            }
+            _ => None,
+        };
+        match rejected {
+            Some(what) => self.reject(what.to_string(), stmt.span()),
+            None => visitor::walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        let rejected = match expr {
+            Expression::Identifier(ident) => {
+                let name = ident.ident.symbol;
+                if !self.locals.contains(&name) && !self.const_globals.contains(&name) {
+                    let what = if self.functions.contains_key(&name) {
+                        "a function value"
+                    } else {
+                        "a global variable"
+                    };
+                    Some(format!("reading {} '{}'", what, self.interner.resolve(&name)))
+                } else {
+                    None
+                }
+            }
+            Expression::Call(call) => {
+                let Expression::Identifier(callee) = call.callee else {
+                    self.reject("calling a function value".to_string(), call.span);
+                    return;
+                };
+                let name = callee.ident.symbol;
+                if !self.functions.contains_key(&name)
+                    && builtin_name(self.annotations, self.interner, name, call.span).is_none()
+                {
+                    Some(format!("call to non-const function '{}'", self.interner.resolve(&name)))
+                } else {
+                    for arg in &call.args {
+                        self.visit_expr(arg);
+                    }
+                    return;
+                }
+            }
+            Expression::Literal(lit) if matches!(lit.value, Literal::Bytes(_)) => {
+                Some("a bytes literal".to_string())
+            }
+            Expression::Binary(bin) if bin.op == BinaryOp::Is => Some("`is`".to_string()),
+            Expression::Cast(cast) if !matches!(cast.target_ty, NamlType::Int | NamlType::Float) => {
+                Some("a cast to a type other than int or float".to_string())
+            }
+            Expression::Path(_) => Some("a path".to_string()),
+            Expression::MethodCall(_) => Some("a method call".to_string()),
+            Expression::Field(_) => Some("a field access".to_string()),
+            Expression::Map(_) => Some("a map".to_string()),
+            Expression::StructLiteral(_) => Some("a struct literal".to_string()),
+            Expression::If(_) | Expression::Block(_) => Some("a block expression".to_string()),
+            Expression::Lambda(_) => Some("a lambda".to_string()),
+            Expression::Spawn(_) => Some("`spawn`".to_string()),
+            Expression::Try(_) | Expression::Catch(_) | Expression::Finally(_) => Some("exception handling".to_string()),
+            Expression::FallibleCast(_) => Some("a fallible cast".to_string()),
+            Expression::TemplateString(_) => Some("a template string".to_string()),
+            _ => None,
+        };
+        match rejected {
+            Some(what) => self.reject(what, expr.span()),
+            None => visitor::walk_expr(self, expr),
+        }
+    }
+}
+
+/// The module-qualified name of a const-evaluable std function called as `name`
+fn builtin_name(
+    annotations: &TypeAnnotations,
+    interner: &Rodeo,
+    name: Spur,
+    call_span: Span,
+) -> Option<&'static str> {
+    let module = annotations.get_resolved_module(call_span)?;
+    let name = match annotations.get_aliased_call(call_span) {
+        Some(original) => original.as_str(),
+        None => interner.resolve(&name),
+    };
+    let qualified = format!("{}::{}", module, name);
+    CONST_BUILTINS.iter().copied().find(|b| *b == qualified)
+}
+
+/// Finds calls to const fns with constant arguments and evaluates them
+struct CallFolder<'a, 'f, 'ast> {
+    eval: Evaluator<'a, 'f, 'ast>,
+    /// Names declared by the function being visited
+    locals: HashSet<Spur>,
+    values: HashMap<Span, ConstValue>,
+    errors: Vec<TypeError>,
+}
+
+impl CallFolder<'_, '_, '_> {
+    /// The value of a constant expression, or `None` if it is not constant.
+    /// Calls inside it have already been folded by `visit_expr`.
+    fn constant(&mut self, expr: &Expression<'_>) -> Option<Value> {
+        match expr {
+            Expression::Literal(lit) => literal_value(self.eval.interner, &lit.value),
+            Expression::Identifier(ident) if !self.locals.contains(&ident.ident.symbol) => {
+                self.eval.globals.get(&ident.ident.symbol).map(Value::deep_copy)
+            }
+            Expression::Grouped(g) => self.constant(g.inner),
+            Expression::Unary(u) => {
+                let operand = self.constant(u.operand)?;
+                unary(u.op, operand).ok()
+            }
+            Expression::Binary(b) if b.op != BinaryOp::Is => {
+                let left = self.constant(b.left)?;
+                let right = self.constant(b.right)?;
+                binary(b.op, left, right).ok()
+            }
+            Expression::Array(arr) => {
+                let elements = arr
+                    .elements
+                    .iter()
+                    .map(|e| self.constant(e))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Value::array(elements))
+            }
+            Expression::Call(call) => self.values.get(&call.span).map(Value::from_const),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for CallFolder<'_, '_, 'ast> {
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        // Fold inner calls first so they count as constant arguments
+        visitor::walk_expr(self, expr);
+
+        let Expression::Call(call) = expr else {
+            return;
+        };
+        let Expression::Identifier(callee) = call.callee else {
+            return;
+        };
+        let name = callee.ident.symbol;
+        if self.locals.contains(&name) || !self.eval.functions.contains_key(&name) {
+            return;
+        }
+        let Some(args) = call
+            .args
+            .iter()
+            .map(|arg| self.constant(arg))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+
+        self.eval.steps = 0;
+        match self.eval.call(name, args) {
+            Ok(value) => {
+                if let Some(value) = value.to_const() {
+                    self.values.insert(call.span, value);
+                }
+            }
+            Err(err) => self.errors.push(TypeError::ConstEval {
+                function: self.eval.interner.resolve(&name).to_string(),
+                reason: err.reason,
+                span: call.span,
+            }),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast;
+    use crate::lexer::tokenize;
+    use crate::parser::parse;
+    use crate::typechecker::check_with_types;
+
+    /// The values folded into the calls of `source`, in source order
+    fn folded(source: &str) -> (Vec<ConstValue>, Vec<TypeError>) {
+        let (tokens, mut interner) = tokenize(source);
+        let arena = ast::AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+        let result = check_with_types(&parsed.ast, &mut interner, None, None);
+        let mut values: Vec<_> = result.annotations.const_values().collect();
+        values.sort_by_key(|(span, _)| span.start);
+        (values.into_iter().map(|(_, v)| v.clone()).collect(), result.errors)
+    }
+
+    #[test]
+    fn test_const_fn_calls_are_folded() {
+        let (values, errors) = folded(
+            "use std::collections::arrays::*;
+            use std::strings::*;
+            const fn squares(n: int) -> [int] {
+                var out: [int] = [];
+                for (i: int in 0..n) { push(out, i * i); }
+                return out;
+            }
+            const fn greet(name: string) -> string { return \"hi \" + upper(name); }
+            const fn fact(n: int) -> int { if (n <= 1) { return 1; } return n * fact(n - 1); }
+            const SIZE: int = 4;
+            fn main() {
+                var t: [int] = squares(SIZE);
+                var s: string = greet(\"x\");
+                var f: int = fact(SIZE + 1);
+                var n: int = 3;
+                var r: int = fact(n);
+            }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            values,
+            [
+                ConstValue::Array(vec![
+                    ConstValue::Int(0),
+                    ConstValue::Int(1),
+                    ConstValue::Int(4),
+                    ConstValue::Int(9)
+                ]),
+                ConstValue::String("hi X".to_string()),
+                ConstValue::Int(120),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_const_fn_errors() {
+        let (_, errors) = folded(
+            "const fn bad(n: int) -> int { println(n); return n; }
+            fn main() { var x: int = bad(1); }",
+        );
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0038");
+
+        let (values, errors) = folded(
+            "const fn div(a: int, b: int) -> int { return a / b; }
+            const fn spin() -> int { while (true) { } return 0; }
+            fn main() { var x: int = div(1, 0); var y: int = spin(); }",
+        );
+        assert!(values.is_empty());
+        let messages: Vec<_> = errors.iter().map(|e| (e.code(), e.to_string())).collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages[0].1.contains("division by zero"), "{:?}", messages);
+        assert!(messages[1].1.contains("evaluation steps"), "{:?}", messages);
+        assert!(messages.iter().all(|(code, _)| *code == "E0037"));
+    }
+}
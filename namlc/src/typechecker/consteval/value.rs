@@ -0,0 +1,185 @@
+//!
+//! Const Evaluation Values
+//!
+//! The values a const fn computes with, their conversion to and from
+//! `ConstValue`, and the operators and std functions applied to them.
+//!
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lasso::Rodeo;
+
+use crate::ast::{BinaryOp, Literal, UnaryOp};
+
+use super::ConstValue;
+
+/// A value while evaluating. Arrays are shared like at runtime, so pushing
+/// to one array variable is seen through every other reference to it.
+#[derive(Debug, Clone)]
+pub(super) enum Value {
+    Unit,
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(Rc<str>),
+    Array(Rc<RefCell<Vec<Value>>>),
+    Option(Option<Box<Value>>),
+}
+
+impl Value {
+    pub(super) fn array(elements: Vec<Value>) -> Self {
+        Value::Array(Rc::new(RefCell::new(elements)))
+    }
+
+    pub(super) fn from_const(value: &ConstValue) -> Self {
+        match value {
+            ConstValue::Int(n) => Value::Int(*n),
+            ConstValue::Float(f) => Value::Float(*f),
+            ConstValue::Bool(b) => Value::Bool(*b),
+            ConstValue::String(s) => Value::Str(s.as_str().into()),
+            ConstValue::Array(elements) => {
+                Value::array(elements.iter().map(Value::from_const).collect())
+            }
+        }
+    }
+
+    pub(super) fn to_const(&self) -> Option<ConstValue> {
+        Some(match self {
+            Value::Int(n) => ConstValue::Int(*n),
+            Value::Float(f) => ConstValue::Float(*f),
+            Value::Bool(b) => ConstValue::Bool(*b),
+            Value::Str(s) => ConstValue::String(s.to_string()),
+            Value::Array(elements) => ConstValue::Array(
+                elements
+                    .borrow()
+                    .iter()
+                    .map(Value::to_const)
+                    .collect::<Option<_>>()?,
+            ),
+            Value::Unit | Value::Option(_) => return None,
+        })
+    }
+
+    /// A copy that shares no arrays with `self`, for each use of a constant
+    pub(super) fn deep_copy(&self) -> Self {
+        match self {
+            Value::Array(elements) => {
+                Value::array(elements.borrow().iter().map(Value::deep_copy).collect())
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+pub(super) fn literal_value(interner: &Rodeo, lit: &Literal) -> Option<Value> {
+    Some(match lit {
+        Literal::Int(n) => Value::Int(*n),
+        Literal::UInt(n) => Value::Int(*n as i64),
+        Literal::Float(f) => Value::Float(*f),
+        Literal::Bool(b) => Value::Bool(*b),
+        Literal::String(s) => Value::Str(interner.resolve(s).into()),
+        Literal::None => Value::Option(None),
+        Literal::Bytes(_) => return None,
+    })
+}
+
+pub(super) struct EvalError {
+    pub(super) reason: String,
+}
+
+pub(super) fn fail<T>(reason: impl Into<String>) -> Result<T, EvalError> {
+    Err(EvalError {
+        reason: reason.into(),
+    })
+}
+
+pub(super) fn unary(op: UnaryOp, operand: Value) -> Result<Value, EvalError> {
+    match (op, operand) {
+        (UnaryOp::Neg, Value::Int(n)) => Ok(Value::Int(n.wrapping_neg())),
+        (UnaryOp::Neg, Value::Float(f)) => Ok(Value::Float(-f)),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::BitNot, Value::Int(n)) => Ok(Value::Int(!n)),
+        _ => fail("invalid operand"),
+    }
+}
+
+/// Binary operators with the semantics of the generated code: integer
+/// arithmetic wraps and shifts use the low six bits of the amount
+pub(super) fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, EvalError> {
+    use Value::{Bool, Float, Int, Str};
+
+    Ok(match (op, left, right) {
+        (BinaryOp::Add, Int(a), Int(b)) => Int(a.wrapping_add(b)),
+        (BinaryOp::Sub, Int(a), Int(b)) => Int(a.wrapping_sub(b)),
+        (BinaryOp::Mul, Int(a), Int(b)) => Int(a.wrapping_mul(b)),
+        (BinaryOp::Div | BinaryOp::Mod, Int(_), Int(0)) => return fail("division by zero"),
+        (BinaryOp::Div, Int(a), Int(b)) => Int(a.wrapping_div(b)),
+        (BinaryOp::Mod, Int(a), Int(b)) => Int(a.wrapping_rem(b)),
+        (BinaryOp::BitAnd, Int(a), Int(b)) => Int(a & b),
+        (BinaryOp::BitOr, Int(a), Int(b)) => Int(a | b),
+        (BinaryOp::BitXor, Int(a), Int(b)) => Int(a ^ b),
+        (BinaryOp::Shl, Int(a), Int(b)) => Int(a.wrapping_shl(b as u32)),
+        (BinaryOp::Shr, Int(a), Int(b)) => Int(a.wrapping_shr(b as u32)),
+
+        (BinaryOp::Add, Float(a), Float(b)) => Float(a + b),
+        (BinaryOp::Sub, Float(a), Float(b)) => Float(a - b),
+        (BinaryOp::Mul, Float(a), Float(b)) => Float(a * b),
+        (BinaryOp::Div, Float(a), Float(b)) => Float(a / b),
+        (BinaryOp::Mod, Float(a), Float(b)) => Float(a - (a / b).trunc() * b),
+
+        (BinaryOp::Add, Str(a), Str(b)) => Str(format!("{}{}", a, b).into()),
+
+        (BinaryOp::Eq, a, b) => Bool(equal(&a, &b)),
+        (BinaryOp::NotEq, a, b) => Bool(!equal(&a, &b)),
+        (BinaryOp::Lt, Int(a), Int(b)) => Bool(a < b),
+        (BinaryOp::LtEq, Int(a), Int(b)) => Bool(a <= b),
+        (BinaryOp::Gt, Int(a), Int(b)) => Bool(a > b),
+        (BinaryOp::GtEq, Int(a), Int(b)) => Bool(a >= b),
+        (BinaryOp::Lt, Float(a), Float(b)) => Bool(a < b),
+        (BinaryOp::LtEq, Float(a), Float(b)) => Bool(a <= b),
+        (BinaryOp::Gt, Float(a), Float(b)) => Bool(a > b),
+        (BinaryOp::GtEq, Float(a), Float(b)) => Bool(a >= b),
+        (BinaryOp::And, Bool(a), Bool(b)) => Bool(a && b),
+        (BinaryOp::Or, Bool(a), Bool(b)) => Bool(a || b),
+        _ => return fail("this operation cannot run at compile time"),
+    })
+}
+
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Unit, Value::Unit) => true,
+        (Value::Int(a), Value::Int(b)) => a == b,
+        (Value::Float(a), Value::Float(b)) => a == b,
+        (Value::Bool(a), Value::Bool(b)) => a == b,
+        (Value::Str(a), Value::Str(b)) => a == b,
+        (Value::Array(a), Value::Array(b)) => Rc::ptr_eq(a, b),
+        (Value::Option(a), Value::Option(b)) => match (a, b) {
+            (Some(a), Some(b)) => equal(a, b),
+            (None, None) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+pub(super) fn call_builtin(name: &str, args: Vec<Value>) -> Result<Value, EvalError> {
+    let mut args = args.into_iter();
+    let first = args.next();
+    match (name, first) {
+        ("collections::arrays::count", Some(Value::Array(elements))) => {
+            Ok(Value::Int(elements.borrow().len() as i64))
+        }
+        ("collections::arrays::push", Some(Value::Array(elements))) => {
+            let Some(value) = args.next() else {
+                return fail("push needs a value");
+            };
+            elements.borrow_mut().push(value);
+            Ok(Value::Unit)
+        }
+        ("strings::len", Some(Value::Str(s))) => Ok(Value::Int(s.chars().count() as i64)),
+        ("strings::upper", Some(Value::Str(s))) => Ok(Value::Str(s.to_uppercase().into())),
+        ("strings::lower", Some(Value::Str(s))) => Ok(Value::Str(s.to_lowercase().into())),
+        _ => fail(format!("'{}' cannot run at compile time", name)),
+    }
+}
@@ -72,6 +72,10 @@ impl Scope {
     pub fn get_mut(&mut self, name: Spur) -> Option<&mut Binding> {
         self.bindings.get_mut(&name)
     }
+
+    pub fn names(&self) -> impl Iterator<Item = Spur> + '_ {
+        self.bindings.keys().copied()
+    }
 }
 
 impl Default for Scope {
@@ -134,6 +138,11 @@ impl TypeEnv {
         None
     }
 
+    /// Names of all variables visible from the innermost scope
+    pub fn visible_names(&self) -> impl Iterator<Item = Spur> + '_ {
+        self.scopes.iter().flat_map(Scope::names)
+    }
+
     pub fn lookup_mut(&mut self, name: Spur) -> Option<&mut Binding> {
         for scope in self.scopes.iter_mut().rev() {
             if let Some(binding) = scope.get_mut(name) {
@@ -189,6 +198,20 @@ impl TypeEnv {
         });
     }
 
+    /// Enter a `spawn` block, which keeps the enclosing function's throws
+    /// and type parameters but returns its task's result to `task_result`
+    pub fn enter_spawn(&mut self, return_ty: Type) {
+        let context = match self.function_stack.last() {
+            Some(enclosing) => FunctionContext { return_ty, ..enclosing.clone() },
+            None => FunctionContext {
+                return_ty,
+                throws: Vec::new(),
+                type_params: HashMap::new(),
+            },
+        };
+        self.function_stack.push(context);
+    }
+
     pub fn exit_function(&mut self) {
         self.function_stack.pop();
     }
@@ -15,6 +15,10 @@
 //! - InvalidOperation: Operation not valid for types
 //! - InferenceFailed: Could not infer type
 //!
+//! Every variant has a stable error code (`E0001`...) that `naml --explain`
+//! documents. Lookup failures can carry a "did you mean" suggestion, and
+//! errors about a known item can point at its definition as a second span.
+//!
 
 use crate::source::Span;
 use thiserror::Error;
@@ -29,19 +33,33 @@ pub enum TypeError {
     },
 
     #[error("undefined variable '{name}'")]
-    UndefinedVariable { name: String, span: Span },
+    UndefinedVariable {
+        name: String,
+        span: Span,
+        suggestion: Option<String>,
+    },
 
     #[error("undefined type '{name}'")]
-    UndefinedType { name: String, span: Span },
+    UndefinedType {
+        name: String,
+        span: Span,
+        suggestion: Option<String>,
+    },
 
     #[error("undefined function '{name}'")]
-    UndefinedFunction { name: String, span: Span },
+    UndefinedFunction {
+        name: String,
+        span: Span,
+        suggestion: Option<String>,
+    },
 
     #[error("undefined field '{field}' on type '{ty}'")]
     UndefinedField {
         ty: String,
         field: String,
         span: Span,
+        suggestion: Option<String>,
+        defined_at: Option<Span>,
     },
 
     #[error("undefined method '{method}' on type '{ty}'")]
@@ -49,13 +67,20 @@ pub enum TypeError {
         ty: String,
         method: String,
         span: Span,
+        suggestion: Option<String>,
+        defined_at: Option<Span>,
     },
 
     #[error("duplicate definition '{name}'")]
     DuplicateDefinition { name: String, span: Span },
 
-    #[error("duplicate import of '{name}': already imported from another module")]
-    DuplicateImport { name: String, span: Span },
+    #[error("duplicate import of '{name}': imported from both '{first}' and '{second}'")]
+    DuplicateImport {
+        name: String,
+        first: String,
+        second: String,
+        span: Span,
+    },
 
     #[error("invalid operation: cannot apply {op} to {ty}")]
     InvalidOperation {
@@ -80,6 +105,7 @@ pub enum TypeError {
         expected: usize,
         found: usize,
         span: Span,
+        defined_at: Option<Span>,
     },
 
     #[error("wrong number of type arguments: expected {expected}, found {found}")]
@@ -154,6 +180,7 @@ pub enum TypeError {
         module: String,
         symbol: String,
         span: Span,
+        suggestion: Option<String>,
     },
 
     #[error("symbol '{symbol}' in module '{module}' is not public")]
@@ -179,8 +206,12 @@ pub enum TypeError {
     #[error("`try` and `catch` cannot be used together")]
     TryWithCatch { span: Span },
 
-    #[error("ambiguous function '{name}': exists in multiple imported modules, use a qualified path")]
-    AmbiguousFunction { name: String, span: Span },
+    #[error("ambiguous function '{name}': imported from {}, use a qualified path", modules.join(", "))]
+    AmbiguousFunction {
+        name: String,
+        modules: Vec<String>,
+        span: Span,
+    },
 
     #[error("package error '{package}': {reason}")]
     PackageError {
@@ -188,6 +219,35 @@ pub enum TypeError {
         reason: String,
         span: Span,
     },
+
+    #[error("struct '{struct_name}' inherits conflicting default methods '{method_name}' from '{first}' and '{second}'")]
+    ConflictingDefaultMethod {
+        struct_name: String,
+        method_name: String,
+        first: String,
+        second: String,
+        span: Span,
+    },
+
+    #[error("import cycle: {cycle}")]
+    ImportCycle { cycle: String, span: Span },
+
+    #[error("cannot evaluate '{function}' at compile time: {reason}")]
+    ConstEval {
+        function: String,
+        reason: String,
+        span: Span,
+    },
+
+    #[error("{what} is not allowed in a const fn")]
+    NotConst { what: String, span: Span },
+
+    #[error("invalid attribute '@{name}': {reason}")]
+    InvalidAttribute {
+        name: String,
+        reason: String,
+        span: Span,
+    },
 }
 
 impl TypeError {
@@ -227,6 +287,112 @@ impl TypeError {
             TypeError::TryWithCatch { span } => *span,
             TypeError::AmbiguousFunction { span, .. } => *span,
             TypeError::PackageError { span, .. } => *span,
+            TypeError::ConflictingDefaultMethod { span, .. } => *span,
+            TypeError::ImportCycle { span, .. } => *span,
+            TypeError::ConstEval { span, .. } => *span,
+            TypeError::NotConst { span, .. } => *span,
+            TypeError::InvalidAttribute { span, .. } => *span,
+        }
+    }
+
+    /// Stable code identifying the kind of error, explained by `naml --explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeError::TypeMismatch { .. } => "E0001",
+            TypeError::UndefinedVariable { .. } => "E0002",
+            TypeError::UndefinedType { .. } => "E0003",
+            TypeError::UndefinedFunction { .. } => "E0004",
+            TypeError::UndefinedField { .. } => "E0005",
+            TypeError::UndefinedMethod { .. } => "E0006",
+            TypeError::DuplicateDefinition { .. } => "E0007",
+            TypeError::DuplicateImport { .. } => "E0008",
+            TypeError::InvalidOperation { .. } => "E0009",
+            TypeError::InvalidBinaryOp { .. } => "E0010",
+            TypeError::InferenceFailed { .. } => "E0011",
+            TypeError::WrongArgCount { .. } => "E0012",
+            TypeError::WrongTypeArgCount { .. } => "E0013",
+            TypeError::NotCallable { .. } => "E0014",
+            TypeError::NotIndexable { .. } => "E0015",
+            TypeError::NotIterable { .. } => "E0016",
+            TypeError::ImmutableAssignment { .. } => "E0017",
+            TypeError::PlatformMismatch { .. } => "E0018",
+            TypeError::MissingReturn { .. } => "E0019",
+            TypeError::UnreachableCode { .. } => "E0020",
+            TypeError::BreakOutsideLoop { .. } => "E0021",
+            TypeError::ContinueOutsideLoop { .. } => "E0022",
+            TypeError::BoundNotSatisfied { .. } => "E0023",
+            TypeError::NoBoundForMethod { .. } => "E0024",
+            TypeError::Custom { .. } => "E0025",
+            TypeError::MissingInterfaceMethod { .. } => "E0026",
+            TypeError::UnknownModule { .. } => "E0027",
+            TypeError::UnknownModuleSymbol { .. } => "E0028",
+            TypeError::PrivateSymbol { .. } => "E0029",
+            TypeError::ModuleFileError { .. } => "E0030",
+            TypeError::UncaughtException { .. } => "E0031",
+            TypeError::TryWithCatch { .. } => "E0032",
+            TypeError::AmbiguousFunction { .. } => "E0033",
+            TypeError::PackageError { .. } => "E0034",
+            TypeError::ConflictingDefaultMethod { .. } => "E0035",
+            TypeError::ImportCycle { .. } => "E0036",
+            TypeError::ConstEval { .. } => "E0037",
+            TypeError::NotConst { .. } => "E0038",
+            TypeError::InvalidAttribute { .. } => "E0039",
+        }
+    }
+
+    /// The closest known name to a misspelled one, if any was found
+    pub fn suggestion(&self) -> Option<&str> {
+        match self {
+            TypeError::UndefinedVariable { suggestion, .. }
+            | TypeError::UndefinedType { suggestion, .. }
+            | TypeError::UndefinedFunction { suggestion, .. }
+            | TypeError::UndefinedField { suggestion, .. }
+            | TypeError::UndefinedMethod { suggestion, .. }
+            | TypeError::UnknownModuleSymbol { suggestion, .. } => suggestion.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Secondary spans worth showing next to the error, with their labels
+    pub fn notes(&self) -> Vec<(Span, String)> {
+        match self {
+            TypeError::UndefinedField { ty, defined_at: Some(def), .. }
+            | TypeError::UndefinedMethod { ty, defined_at: Some(def), .. } => {
+                vec![(*def, format!("'{}' defined here", ty))]
+            }
+            TypeError::WrongArgCount { defined_at: Some(def), .. } => {
+                vec![(*def, "defined here".to_string())]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Attach a suggestion to errors that can carry one
+    pub fn with_suggestion(mut self, name: Option<String>) -> Self {
+        match &mut self {
+            TypeError::UndefinedVariable { suggestion, .. }
+            | TypeError::UndefinedType { suggestion, .. }
+            | TypeError::UndefinedFunction { suggestion, .. }
+            | TypeError::UndefinedField { suggestion, .. }
+            | TypeError::UndefinedMethod { suggestion, .. }
+            | TypeError::UnknownModuleSymbol { suggestion, .. } => *suggestion = name,
+            _ => {}
+        }
+        self
+    }
+
+    /// Drop definition notes whose span fails `keep`, e.g. because the
+    /// definition lives in another file than the one being reported
+    pub fn retain_notes(&mut self, keep: impl Fn(Span) -> bool) {
+        match self {
+            TypeError::UndefinedField { defined_at, .. }
+            | TypeError::UndefinedMethod { defined_at, .. }
+            | TypeError::WrongArgCount { defined_at, .. }
+                if defined_at.is_some_and(|span| !keep(span)) =>
+            {
+                *defined_at = None;
+            }
+            _ => {}
         }
     }
 
@@ -242,6 +408,7 @@ impl TypeError {
         TypeError::UndefinedVariable {
             name: name.into(),
             span,
+            suggestion: None,
         }
     }
 
@@ -249,8 +416,74 @@ impl TypeError {
         TypeError::UndefinedType {
             name: name.into(),
             span,
+            suggestion: None,
         }
     }
 }
 
 pub type TypeResult<T> = Result<T, TypeError>;
+
+/// Problems worth pointing out that do not stop compilation
+#[derive(Debug, Clone, Error)]
+pub enum TypeWarning {
+    #[error("use of deprecated {kind} '{name}'")]
+    Deprecated {
+        kind: &'static str,
+        name: String,
+        note: Option<String>,
+        /// Name of the function or method to call instead
+        replacement: Option<String>,
+        span: Span,
+        /// The function or method name at the use site
+        name_span: Span,
+    },
+
+    #[error("map with float keys")]
+    FloatMapKey { span: Span },
+
+    #[error("unused import '{name}'")]
+    UnusedImport {
+        name: String,
+        span: Span,
+        /// Source to delete to drop the import: the entry and its comma, or
+        /// the whole `use` item when none of its entries are used
+        removal: Span,
+    },
+}
+
+/// A machine-applicable edit: replace the source at `span` with `replacement`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl TypeWarning {
+    pub fn span(&self) -> Span {
+        match self {
+            TypeWarning::Deprecated { span, .. } => *span,
+            TypeWarning::FloatMapKey { span } => *span,
+            TypeWarning::UnusedImport { span, .. } => *span,
+        }
+    }
+
+    /// The edit that resolves the warning, when one can be applied without
+    /// a human deciding anything
+    pub fn fix(&self) -> Option<Fix> {
+        match self {
+            TypeWarning::Deprecated {
+                replacement: Some(replacement),
+                name_span,
+                ..
+            } => Some(Fix {
+                span: *name_span,
+                replacement: replacement.clone(),
+            }),
+            TypeWarning::UnusedImport { removal, .. } => Some(Fix {
+                span: *removal,
+                replacement: String::new(),
+            }),
+            _ => None,
+        }
+    }
+}
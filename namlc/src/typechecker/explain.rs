@@ -0,0 +1,571 @@
+//!
+//! Error Code Explanations
+//!
+//! Long-form descriptions of the type checker's error codes, printed by
+//! `naml --explain EXXXX`. Each entry says what triggers the error, shows a
+//! minimal program that produces it, and how to fix it. Codes are stable:
+//! they are never reused once assigned, see `TypeError::code`.
+//!
+
+/// Error codes paired with their explanations, in code order
+pub const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "E0001",
+        r#"A value's type does not match the type expected at that position.
+
+Erroneous code example:
+
+    var count: int = "three";
+
+The declared type of `count` is `int`, but the initializer is a `string`.
+Either change the value to match the annotation or change the annotation:
+
+    var count: int = 3;
+"#,
+    ),
+    (
+        "E0002",
+        r#"A name was used that is not declared in any enclosing scope.
+
+Erroneous code example:
+
+    fn main() {
+        var total: int = 10;
+        println(totl);
+    }
+
+Check the spelling (the compiler suggests close matches), or declare the
+variable before using it. Variables are only visible inside the block that
+declares them.
+"#,
+    ),
+    (
+        "E0003",
+        r#"A type name was used that is neither built in nor declared.
+
+Erroneous code example:
+
+    var p: Piont = Piont { x: 1, y: 2 };
+
+Check the spelling, declare the type with `struct`, `enum`, `interface` or
+`type`, or import it from the module that defines it with `use`.
+"#,
+    ),
+    (
+        "E0004",
+        r#"A function was called that is not defined or imported.
+
+Erroneous code example:
+
+    fn main() {
+        pritnln("hello");
+    }
+
+Check the spelling, define the function, or import it with `use`, e.g.
+`use std::strings::*;` for string functions.
+"#,
+    ),
+    (
+        "E0005",
+        r#"A field was accessed that the value's type does not have.
+
+Erroneous code example:
+
+    struct Point { x: int, y: int }
+
+    fn main() {
+        var p: Point = Point { x: 1, y: 2 };
+        println(p.z);
+    }
+
+The note points at the definition of the type so its fields can be
+checked. Arrays have no fields besides `length`; use the functions in
+`std::collections` instead.
+"#,
+    ),
+    (
+        "E0006",
+        r#"A method was called that the receiver's type does not define.
+
+Erroneous code example:
+
+    struct Point { x: int, y: int }
+
+    fn main() {
+        var p: Point = Point { x: 1, y: 2 };
+        p.move_by(1);
+    }
+
+Methods are declared with a receiver, e.g. `fn (self: Point) move_by(...)`.
+Strings, arrays, options and channels have no methods: call the functions
+from `std::strings`, `std::collections` or `std::threads` instead.
+"#,
+    ),
+    (
+        "E0007",
+        r#"The same name was defined twice in one scope.
+
+Rename or remove one of the definitions.
+"#,
+    ),
+    (
+        "E0008",
+        r#"The same name was imported from two different modules.
+
+Erroneous code example:
+
+    use std::encoding::hex::{encode};
+    use std::encoding::base64::{encode};
+
+Import one of them under another name, e.g.
+`use std::encoding::base64::{encode as encode_base64};`, or import the
+modules and call the functions through qualified names like `hex::encode`
+and `base64::encode`.
+"#,
+    ),
+    (
+        "E0009",
+        r#"A unary operator or statement was applied to a type that does not
+support it.
+
+Erroneous code example:
+
+    var flag: bool = true;
+    var x: bool = ~flag;
+
+Bitwise operators need integers and `!` needs a `bool`.
+"#,
+    ),
+    (
+        "E0010",
+        r#"A binary operator was applied to operands it does not support.
+
+Erroneous code example:
+
+    var n: int = 1 + true;
+
+Arithmetic needs numbers of the same type and comparisons need comparable
+operands. Convert one side explicitly, e.g. with `as float`.
+"#,
+    ),
+    (
+        "E0011",
+        r#"The compiler could not work out a type from the surrounding code.
+
+This usually happens for empty collections or unused generic results:
+
+    var items = [];
+
+Add a type annotation: `var items: [int] = [];`.
+"#,
+    ),
+    (
+        "E0012",
+        r#"A function or method was called with the wrong number of arguments.
+
+Erroneous code example:
+
+    fn add(a: int, b: int) -> int {
+        return a + b;
+    }
+
+    fn main() {
+        println(add(1));
+    }
+
+The note points at the definition so the expected parameters can be
+checked. Pass exactly one argument per parameter.
+"#,
+    ),
+    (
+        "E0013",
+        r#"A generic function was given the wrong number of explicit type
+arguments.
+
+Erroneous code example:
+
+    fn identity<T>(x: T) -> T {
+        return x;
+    }
+
+    var n: int = identity<int, string>(1);
+
+Pass one type argument per type parameter, or leave them out and let them be
+inferred from the arguments.
+"#,
+    ),
+    (
+        "E0014",
+        r#"A value that is not a function was called.
+
+Erroneous code example:
+
+    var count: int = 3;
+    count();
+
+Only functions, methods and lambdas can be called.
+"#,
+    ),
+    (
+        "E0015",
+        r#"Index syntax `value[i]` was used on a type that cannot be indexed.
+
+Only arrays, maps and strings support indexing.
+"#,
+    ),
+    (
+        "E0016",
+        r#"A `for` loop iterates over a type that is not iterable.
+
+Erroneous code example:
+
+    for (x: int in 42) {
+        println(x);
+    }
+
+Loop over an array, a map, a string or a range such as `0..10`.
+"#,
+    ),
+    (
+        "E0017",
+        r#"A value declared with `const` was assigned to.
+
+Erroneous code example:
+
+    const limit: int = 10;
+    limit = 20;
+
+Declare it with `var` if it needs to change.
+"#,
+    ),
+    (
+        "E0018",
+        r#"A function that is restricted to some platforms is used while
+compiling for a platform it does not support.
+
+Functions annotated with `#[platforms(native)]`, for example, cannot be
+called from code built for the browser. Guard the call behind a function
+with a matching `#[platforms(...)]` attribute, or pick a different target.
+"#,
+    ),
+    (
+        "E0019",
+        r#"A function with a return type can reach its end without returning.
+
+Erroneous code example:
+
+    fn sign(n: int) -> int {
+        if (n > 0) {
+            return 1;
+        }
+    }
+
+Make sure every path ends in a `return` of the declared type.
+"#,
+    ),
+    (
+        "E0020",
+        r#"Code follows a statement that always exits, so it never runs.
+
+Remove the unreachable statements or move them before the `return`,
+`break`, `continue` or `throw`.
+"#,
+    ),
+    (
+        "E0021",
+        r#"`break` was used outside of a `while`, `for` or `loop` body.
+
+Only loops can be exited with `break`; use `return` to leave a function.
+"#,
+    ),
+    (
+        "E0022",
+        r#"`continue` was used outside of a `while`, `for` or `loop` body.
+
+Only loops can be continued; use `return` to leave a function early.
+"#,
+    ),
+    (
+        "E0023",
+        r#"A type argument does not satisfy a bound of the generic it is
+passed to.
+
+Erroneous code example:
+
+    fn show<T: Describable>(item: T) -> string {
+        return item.describe();
+    }
+
+    show(42);
+
+Pass a type that implements the interface, or implement it for the type
+with `struct ... implements Describable` and the required methods.
+"#,
+    ),
+    (
+        "E0024",
+        r#"A method was called on a value of a type parameter whose bounds do
+not provide that method.
+
+Erroneous code example:
+
+    fn show<T>(item: T) -> string {
+        return item.describe();
+    }
+
+Add a bound that declares the method: `fn show<T: Describable>(item: T)`.
+"#,
+    ),
+    (
+        "E0025",
+        r#"A type error without a more specific code. The message describes
+the problem; common causes are unknown enum variants, struct literals of
+non-struct types and misuse of `throw`.
+"#,
+    ),
+    (
+        "E0026",
+        r#"A struct declares that it implements an interface but is missing one
+of the interface's methods.
+
+Erroneous code example:
+
+    interface Describable {
+        fn describe() -> string;
+    }
+
+    struct Point implements Describable {
+        x: int,
+        y: int,
+    }
+
+Add the method with a receiver of the struct type:
+
+    pub fn (self: Point) describe() -> string {
+        return "point";
+    }
+"#,
+    ),
+    (
+        "E0027",
+        r#"A `use` declaration names a module that does not exist.
+
+Erroneous code example:
+
+    use std::colections::*;
+
+Check the path against the standard library modules or the files of your
+project. Local modules are resolved relative to the importing file, and
+paths starting with `crate::` relative to the directory of the file being
+compiled.
+"#,
+    ),
+    (
+        "E0028",
+        r#"A `use` declaration imports a name the module does not define.
+
+Erroneous code example:
+
+    use std::strings::{uper};
+
+Check the spelling against the module's exports; the compiler suggests
+close matches.
+"#,
+    ),
+    (
+        "E0029",
+        r#"An item from another module was imported or used but is not marked
+`pub` in that module.
+
+Add `pub` in front of the item's definition to export it.
+"#,
+    ),
+    (
+        "E0030",
+        r#"A module file referenced by a `use` declaration could not be read
+or parsed.
+
+Check that the file exists next to the importing file, is readable, and
+has no syntax errors of its own.
+"#,
+    ),
+    (
+        "E0031",
+        r#"A call can throw an exception that is neither caught nor declared.
+
+Erroneous code example:
+
+    fn divide(a: int, b: int) -> int throws DivisionByZero {
+        ...
+    }
+
+    fn main() {
+        println(divide(1, 0));
+    }
+
+Handle it with `divide(1, 0) catch e { ... }`, supply a fallback with
+`try divide(1, 0) ?? 0`, or add `throws DivisionByZero` to the calling
+function's signature.
+"#,
+    ),
+    (
+        "E0032",
+        r#"`try` and `catch` were combined on the same expression.
+
+Use either `try expr ?? default` to fall back to a default value, or
+`expr catch e { handler }` to handle the exception, but not both.
+"#,
+    ),
+    (
+        "E0033",
+        r#"A function name was used unqualified but is imported from more than
+one module.
+
+Erroneous code example:
+
+    use std::collections::arrays::*;
+    use std::collections::maps::*;
+
+    println(count(items));
+
+Call it through its module instead, e.g. `arrays::count(items)`, import the
+one you mean explicitly with `use std::collections::arrays::{count};`, or
+leave it out of a wildcard with `use std::collections::maps::* except { count };`.
+"#,
+    ),
+    (
+        "E0034",
+        r#"A package dependency could not be resolved or loaded.
+
+Run `naml pkg get` to download the dependencies listed in naml.toml, and
+check that the package name in the `use` declaration matches the manifest.
+"#,
+    ),
+    (
+        "E0035",
+        r#"A struct implements two interfaces that both provide a default body
+for the same method, and does not define the method itself.
+
+Erroneous code example:
+
+    interface Named {
+        fn label() -> string { return "named"; }
+    }
+
+    interface Tagged {
+        fn label() -> string { return "tagged"; }
+    }
+
+    struct Item implements Named, Tagged {
+        id: int,
+    }
+
+Define the method on the struct to pick one behaviour. A struct's own
+methods always take precedence over interface defaults.
+"#,
+    ),
+    (
+        "E0036",
+        r#"Modules import each other in a cycle.
+
+Erroneous code example:
+
+    // src/db.nm
+    use crate::api::*;
+
+    // src/api.nm
+    use crate::db::*;
+
+A module can only be imported once everything it imports is known, so the
+chain of `use` declarations must not lead back to a module that is still
+being loaded. Move the items both modules need into a third module and
+import it from each.
+"#,
+    ),
+    (
+        "E0037",
+        r#"A call to a `const fn` with constant arguments failed while it was
+being evaluated at compile time.
+
+Erroneous code example:
+
+    const fn ratio(a: int, b: int) -> int {
+        return a / b;
+    }
+
+    const HALF: int = ratio(1, 0);
+
+Such calls run while compiling, so errors they would raise at runtime, like
+division by zero or an index out of bounds, are reported here instead.
+Evaluation also stops after a fixed number of steps, which catches loops
+that never end. Fix the arguments, or call the function with a value that
+is only known at runtime if it really should run then.
+"#,
+    ),
+    (
+        "E0038",
+        r#"A `const fn` uses something that cannot run at compile time.
+
+Erroneous code example:
+
+    const fn greet(name: string) -> string {
+        println(name);
+        return "hi " + name;
+    }
+
+The body of a `const fn` may only use arithmetic, strings, arrays, local
+variables, control flow, other `const fn`s and a few pure standard library
+functions such as `count`, `push`, `len`, `upper` and `lower`. Remove
+`const` from the function, or move the side effect to its caller.
+"#,
+    ),
+    (
+        "E0039",
+        r#"A built-in attribute was used on the wrong kind of item or with the
+wrong arguments.
+
+Erroneous code example:
+
+    @inline
+    struct Point { x: int, y: int }
+
+`@test` and `@bench` go on a function without parameters or return type,
+`@inline` on a function, `@deprecated`, `@deprecated("note")` or
+`@deprecated("note", new_name)` on a function or method,
+and `@serialize` on a struct or enum. Attributes with other names are not
+checked, so tools can define their own.
+"#,
+    ),
+];
+
+/// The explanation for an error code, accepting `E0001`, `e0001` or `0001`
+pub fn explain(code: &str) -> Option<&'static str> {
+    let code = code.trim();
+    let digits = code
+        .strip_prefix('E')
+        .or_else(|| code.strip_prefix('e'))
+        .unwrap_or(code);
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| known[1..] == *digits)
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::Span;
+    use crate::typechecker::TypeError;
+
+    #[test]
+    fn test_codes_are_explained_in_order() {
+        for (i, (code, text)) in EXPLANATIONS.iter().enumerate() {
+            assert_eq!(*code, format!("E{:04}", i + 1));
+            assert!(!text.trim().is_empty());
+        }
+        let err = TypeError::undefined_var("x", Span::dummy());
+        assert_eq!(explain(err.code()), explain("e0002"));
+        assert!(explain("0039").is_some());
+        assert!(explain("E9999").is_none());
+    }
+}
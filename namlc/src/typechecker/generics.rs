@@ -49,9 +49,7 @@ pub fn find_method_from_bounds(
 fn find_method_in_bound(bound: &Type, method_name: Spur, symbols: &SymbolTable) -> Option<MethodType> {
     match bound {
         Type::Generic(interface_name, _type_args) => {
-            if let Some(TypeDef::Interface(interface)) = symbols.get_type(*interface_name) {
-                return find_method_in_interface(interface, method_name);
-            }
+            return find_interface_method(*interface_name, method_name, symbols);
         }
         Type::Interface(interface_type) => {
             for method in &interface_type.methods {
@@ -65,9 +63,28 @@ fn find_method_in_bound(bound: &Type, method_name: Spur, symbols: &SymbolTable)
     None
 }
 
+/// An instance method of an interface or of the interfaces it extends,
+/// searched in declaration order
+pub fn find_interface_method(
+    interface_name: Spur,
+    method_name: Spur,
+    symbols: &SymbolTable,
+) -> Option<MethodType> {
+    let Some(TypeDef::Interface(interface)) = symbols.get_type(interface_name) else {
+        return None;
+    };
+    find_method_in_interface(interface, method_name).or_else(|| {
+        interface.extends.iter().find_map(|parent| match parent {
+            Type::Generic(name, _) => find_interface_method(*name, method_name, symbols),
+            Type::Interface(i) => find_interface_method(i.name, method_name, symbols),
+            _ => None,
+        })
+    })
+}
+
 fn find_method_in_interface(interface: &InterfaceDef, method_name: Spur) -> Option<MethodType> {
     for method_def in &interface.methods {
-        if method_def.name == method_name {
+        if method_def.name == method_name && !method_def.is_static {
             return Some(MethodType {
                 name: method_def.name,
                 params: method_def.params.iter().map(|(_, ty)| ty.clone()).collect(),
@@ -229,6 +246,9 @@ mod tests {
                     params: vec![],
                     return_ty: Type::Int,
                     throws: vec![],
+                    is_static: false,
+                    has_default: false,
+                    span: Span::dummy(),
                 }],
                 is_public: true,
                 span: Span::dummy(),
@@ -18,14 +18,16 @@
 use lasso::Rodeo;
 
 use crate::ast::{self, CompilationTarget, Expression, Literal, Pattern};
+use crate::runtime::{format_pieces, FormatPiece, FormatSpec};
 use crate::source::Spanned;
 
 use super::env::TypeEnv;
-use super::error::TypeError;
+use super::error::{TypeError, TypeWarning};
+use super::suggest::did_you_mean;
 use super::symbols::{SymbolTable, TypeDef};
 use super::typed_ast::{ExprTypeInfo, TypeAnnotations};
-use super::types::{FunctionType, Type, TypeParam};
-use super::unify::{fresh_type_var, unify};
+use super::types::{EnumType, FunctionType, Type, TypeParam};
+use super::unify::{fresh_type_var, unify, unify_arg};
 
 /// Replace default generic Spur with actual type parameter Spur
 fn fix_generic_spur(ty: &mut Type, type_param_spur: lasso::Spur) {
@@ -41,6 +43,10 @@ fn fix_generic_spur(ty: &mut Type, type_param_spur: lasso::Spur) {
         Type::Mutex(inner) => fix_generic_spur(inner, type_param_spur),
         Type::Rwlock(inner) => fix_generic_spur(inner, type_param_spur),
         Type::Atomic(inner) => fix_generic_spur(inner, type_param_spur),
+        Type::Set(inner) => fix_generic_spur(inner, type_param_spur),
+        Type::PriorityQueue(inner) => fix_generic_spur(inner, type_param_spur),
+        Type::Deque(inner) => fix_generic_spur(inner, type_param_spur),
+        Type::Iterator(inner) => fix_generic_spur(inner, type_param_spur),
         Type::Map(k, v) => {
             fix_generic_spur(k, type_param_spur);
             fix_generic_spur(v, type_param_spur);
@@ -49,12 +55,23 @@ fn fix_generic_spur(ty: &mut Type, type_param_spur: lasso::Spur) {
     }
 }
 
+/// Whether an expression is an int literal, possibly negated or grouped
+fn is_int_literal(expr: &Expression) -> bool {
+    match expr {
+        Expression::Literal(lit) => matches!(lit.value, Literal::Int(_)),
+        Expression::Unary(un) => un.op == ast::UnaryOp::Neg && is_int_literal(un.operand),
+        Expression::Grouped(grouped) => is_int_literal(grouped.inner),
+        _ => false,
+    }
+}
+
 pub struct TypeInferrer<'a> {
     pub env: &'a mut TypeEnv,
     pub symbols: &'a SymbolTable,
     pub interner: &'a Rodeo,
     pub next_var_id: &'a mut u32,
     pub errors: &'a mut Vec<TypeError>,
+    pub warnings: &'a mut Vec<TypeWarning>,
     pub annotations: &'a mut TypeAnnotations,
     pub switch_scrutinee: Option<Type>,
     pub in_catch_context: bool,
@@ -86,30 +103,76 @@ impl<'a> TypeInferrer<'a> {
         }
     }
 
+    /// Warn about a use of a function or method marked `@deprecated`
+    fn check_deprecated(
+        &mut self,
+        kind: &'static str,
+        name: lasso::Spur,
+        attributes: &[ast::Attribute],
+        span: crate::source::Span,
+        name_span: crate::source::Span,
+    ) {
+        let Some(deprecation) = super::attributes::deprecation(attributes, self.interner) else {
+            return;
+        };
+        // A call through a path is seen both as a call and as a path
+        if self.warnings.iter().any(|w| w.span() == span) {
+            return;
+        }
+        self.warnings.push(TypeWarning::Deprecated {
+            kind,
+            name: self.interner.resolve(&name).to_string(),
+            note: deprecation.note,
+            replacement: deprecation.replacement,
+            span,
+            name_span,
+        });
+    }
+
+    /// Warn when a variable holds a map keyed by floats
+    fn check_float_map_key(&mut self, ty: &Type, span: crate::source::Span) {
+        if let Type::Map(key, _) = ty.resolve()
+            && key.resolve() == Type::Float
+        {
+            self.warnings.push(TypeWarning::FloatMapKey { span });
+        }
+    }
+
     pub fn infer_expr(&mut self, expr: &Expression) -> Type {
+        self.infer_expr_with(expr, None)
+    }
+
+    /// Infer the type of an expression given the type its context expects.
+    /// The expected type types unannotated lambda parameters, empty array and
+    /// map literals, and int literals in a float position. It is only a hint:
+    /// callers still unify the result with the type they need.
+    pub fn infer_expr_with(&mut self, expr: &Expression, expected: Option<&Type>) -> Type {
+        let expected = expected.map(Type::resolve);
+        let expected = expected.as_ref();
         let ty = match expr {
-            Expression::Literal(lit) => self.infer_literal(lit),
+            Expression::Literal(lit) => self.infer_literal(lit, expected),
             Expression::Identifier(ident) => self.infer_identifier(ident),
             Expression::Path(path) => self.infer_path(path),
             Expression::Binary(bin) => self.infer_binary(bin),
-            Expression::Unary(un) => self.infer_unary(un),
+            Expression::Unary(un) => self.infer_unary(un, expected),
             Expression::Call(call) => self.infer_call(call),
             Expression::MethodCall(call) => self.infer_method_call(call),
             Expression::Index(idx) => self.infer_index(idx),
             Expression::Field(field) => self.infer_field(field),
-            Expression::Array(arr) => self.infer_array(arr),
-            Expression::Map(map) => self.infer_map(map),
+            Expression::Array(arr) => self.infer_array(arr, expected),
+            Expression::Map(map) => self.infer_map(map, expected),
             Expression::StructLiteral(lit) => self.infer_struct_literal(lit),
             Expression::If(if_expr) => self.infer_if(if_expr),
             Expression::Block(block) => self.infer_block(block),
-            Expression::Lambda(lambda) => self.infer_lambda(lambda),
+            Expression::Lambda(lambda) => self.infer_lambda(lambda, expected),
             Expression::Spawn(spawn) => self.infer_spawn(spawn),
             Expression::Try(try_expr) => self.infer_try(try_expr),
             Expression::Catch(catch) => self.infer_catch(catch),
+            Expression::Finally(finally) => self.infer_finally(finally),
             Expression::Cast(cast) => self.infer_cast(cast),
             Expression::FallibleCast(cast) => self.infer_fallible_cast(cast),
             Expression::Range(range) => self.infer_range(range),
-            Expression::Grouped(grouped) => self.infer_expr(grouped.inner),
+            Expression::Grouped(grouped) => self.infer_expr_with(grouped.inner, expected),
             Expression::Some(some) => self.infer_some(some),
             Expression::Ternary(ternary) => self.infer_ternary(ternary),
             Expression::Elvis(elvis) => self.infer_elvis(elvis),
@@ -194,12 +257,22 @@ impl<'a> TypeInferrer<'a> {
             Type::Mutex(inner) => format!("Mutex_{}", self.mangle_type(inner)),
             Type::Rwlock(inner) => format!("Rwlock_{}", self.mangle_type(inner)),
             Type::Atomic(inner) => format!("Atomic_{}", self.mangle_type(inner)),
+            Type::Set(inner) => format!("Set_{}", self.mangle_type(inner)),
+            Type::PriorityQueue(inner) => format!("PriorityQueue_{}", self.mangle_type(inner)),
+            Type::Deque(inner) => format!("Deque_{}", self.mangle_type(inner)),
+            Type::Iterator(inner) => format!("Iterator_{}", self.mangle_type(inner)),
             Type::Struct(s) => self.interner.resolve(&s.name).to_string(),
             Type::Enum(e) => self.interner.resolve(&e.name).to_string(),
             Type::Interface(i) => self.interner.resolve(&i.name).to_string(),
             Type::Exception(name) => self.interner.resolve(name).to_string(),
             Type::StackFrame => "stack_frame".to_string(),
+            Type::IfaceInfo => "iface_info".to_string(),
+            Type::FsEvent => "fs_event".to_string(),
             Type::Json => "json".to_string(),
+            Type::Secret => "secret".to_string(),
+            Type::Money => "money".to_string(),
+            Type::StringBuilder => "string_builder".to_string(),
+            Type::Waitgroup => "waitgroup".to_string(),
             Type::Function(_) => "fn".to_string(),
             Type::TypeVar(tv) => format!("T{}", tv.id),
             Type::Generic(name, args) => {
@@ -215,6 +288,59 @@ impl<'a> TypeInferrer<'a> {
         }
     }
 
+    /// Closest visible variable or function name to an undefined one
+    fn suggest_name(&self, name: lasso::Spur) -> Option<String> {
+        let candidates = self
+            .env
+            .visible_names()
+            .chain(self.symbols.function_names().copied())
+            .map(|spur| self.interner.resolve(&spur));
+        did_you_mean(self.interner.resolve(&name), candidates)
+    }
+
+    /// Closest of `fields` to an undefined field name
+    fn suggest_field(
+        &self,
+        name: lasso::Spur,
+        fields: impl IntoIterator<Item = lasso::Spur>,
+    ) -> Option<String> {
+        let candidates = fields.into_iter().map(|spur| self.interner.resolve(&spur));
+        did_you_mean(self.interner.resolve(&name), candidates)
+    }
+
+    /// Closest method defined on `type_name` to an undefined method name
+    fn suggest_method(&self, type_name: lasso::Spur, name: lasso::Spur) -> Option<String> {
+        let methods = self.symbols.get_methods(type_name)?;
+        self.suggest_field(name, methods.iter().map(|m| m.name))
+    }
+
+    /// Closest known type name to an undefined one
+    fn suggest_type(&self, name: lasso::Spur) -> Option<String> {
+        self.suggest_field(name, self.symbols.all_types().map(|(spur, _)| *spur))
+    }
+
+    /// Where a struct, enum or exception is declared, for definition notes
+    fn type_definition(&self, name: lasso::Spur) -> Option<crate::source::Span> {
+        match self.symbols.get_type(name)? {
+            TypeDef::Struct(def) => Some(def.span),
+            TypeDef::Enum(def) => Some(def.span),
+            TypeDef::Exception(def) => Some(def.span),
+            _ => None,
+        }
+    }
+
+    /// Where the function called by `callee` is declared, for definition notes
+    fn callee_definition(&self, callee: &Expression) -> Option<crate::source::Span> {
+        match callee {
+            Expression::Identifier(ident) if self.env.lookup(ident.ident.symbol).is_none() => self
+                .symbols
+                .get_function(ident.ident.symbol)
+                .filter(|sig| sig.module.is_none())
+                .map(|sig| sig.span),
+            _ => None,
+        }
+    }
+
     fn display_type(&self, ty: &Type) -> String {
         match ty {
             Type::Int => "int".to_string(),
@@ -232,12 +358,22 @@ impl<'a> TypeInferrer<'a> {
             Type::Mutex(inner) => format!("mutex<{}>", self.display_type(inner)),
             Type::Rwlock(inner) => format!("rwlock<{}>", self.display_type(inner)),
             Type::Atomic(inner) => format!("atomic<{}>", self.display_type(inner)),
+            Type::Set(inner) => format!("set<{}>", self.display_type(inner)),
+            Type::PriorityQueue(inner) => format!("priority_queue<{}>", self.display_type(inner)),
+            Type::Deque(inner) => format!("deque<{}>", self.display_type(inner)),
+            Type::Iterator(inner) => format!("iterator<{}>", self.display_type(inner)),
             Type::Struct(s) => self.interner.resolve(&s.name).to_string(),
             Type::Enum(e) => self.interner.resolve(&e.name).to_string(),
             Type::Interface(i) => self.interner.resolve(&i.name).to_string(),
             Type::Exception(name) => self.interner.resolve(name).to_string(),
             Type::StackFrame => "stack_frame".to_string(),
+            Type::IfaceInfo => "iface_info".to_string(),
+            Type::FsEvent => "fs_event".to_string(),
             Type::Json => "json".to_string(),
+            Type::Secret => "secret".to_string(),
+            Type::Money => "money".to_string(),
+            Type::StringBuilder => "string_builder".to_string(),
+            Type::Waitgroup => "waitgroup".to_string(),
             Type::Function(f) => {
                 let params = f
                     .params
@@ -328,14 +464,55 @@ impl<'a> TypeInferrer<'a> {
         }
     }
 
-    fn infer_template_string(&mut self, _template: &ast::TemplateStringExpr) -> Type {
+    fn infer_template_string(&mut self, template: &ast::TemplateStringExpr) -> Type {
         // Template strings contain raw expression strings that are parsed during codegen
-        // They always evaluate to String type
+        // They always evaluate to String type; only `{name:spec}` specs are checked here
+        for part in &template.parts {
+            if let ast::TemplateStringPart::Expression(raw) = part
+                && let Some((_, spec)) = raw.split_once(':')
+                && let Err(message) = check_format_spec(spec, None)
+            {
+                self.errors.push(TypeError::Custom { message, span: template.span });
+            }
+        }
         Type::String
     }
 
-    fn infer_literal(&mut self, lit: &ast::LiteralExpr) -> Type {
+    /// Check the `{:spec}` placeholders of a `fmt`/`print`-style call against
+    /// the spec grammar and the types of the arguments they format
+    fn check_format_specs(&mut self, call: &ast::CallExpr, arg_types: &[Type]) {
+        let Expression::Identifier(ident) = call.callee else {
+            return;
+        };
+        if !matches!(
+            self.interner.resolve(&ident.ident.symbol),
+            "print" | "println" | "fmt" | "warn" | "error" | "panic"
+        ) {
+            return;
+        }
+        let Some(Expression::Literal(ast::LiteralExpr { value: Literal::String(spur), span })) = call.args.first()
+        else {
+            return;
+        };
+        let format_str = self.interner.resolve(spur).to_string();
+        let mut arg_idx = 1;
+        for piece in format_pieces(&format_str) {
+            let FormatPiece::Placeholder(spec) = piece else {
+                continue;
+            };
+            let arg_ty = arg_types.get(arg_idx).map(|ty| ty.resolve());
+            arg_idx += 1;
+            if let Some(spec) = spec
+                && let Err(message) = check_format_spec(spec, arg_ty.as_ref())
+            {
+                self.errors.push(TypeError::Custom { message, span: *span });
+            }
+        }
+    }
+
+    fn infer_literal(&mut self, lit: &ast::LiteralExpr, expected: Option<&Type>) -> Type {
         match &lit.value {
+            Literal::Int(_) if expected == Some(&Type::Float) => Type::Float,
             Literal::Int(_) => Type::Int,
             Literal::UInt(_) => Type::Uint,
             Literal::Float(_) => Type::Float,
@@ -356,6 +533,7 @@ impl<'a> TypeInferrer<'a> {
             let name = self.interner.resolve(&ident.ident.symbol).to_string();
             self.errors.push(TypeError::AmbiguousFunction {
                 name,
+                modules: self.symbols.ambiguous_modules(ident.ident.symbol),
                 span: ident.span,
             });
             Type::Error
@@ -372,19 +550,25 @@ impl<'a> TypeInferrer<'a> {
                     Type::Error
                 }
             }
-        } else if let Some(Type::Enum(ref e)) = self.switch_scrutinee {
+        } else if let Some(scrutinee) = self.switch_scrutinee.clone()
+            && let Some(e) = self.enum_instance(&scrutinee)
+        {
             // In a switch context, try to resolve bare identifier as enum variant
             for variant in &e.variants {
                 if variant.name == ident.ident.symbol {
-                    return Type::Enum(e.clone());
+                    return scrutinee;
                 }
             }
             let name = self.interner.resolve(&ident.ident.symbol).to_string();
-            self.errors.push(TypeError::undefined_var(name, ident.span));
+            let suggestion = self.suggest_name(ident.ident.symbol);
+            self.errors
+                .push(TypeError::undefined_var(name, ident.span).with_suggestion(suggestion));
             Type::Error
         } else {
             let name = self.interner.resolve(&ident.ident.symbol).to_string();
-            self.errors.push(TypeError::undefined_var(name, ident.span));
+            let suggestion = self.suggest_name(ident.ident.symbol);
+            self.errors
+                .push(TypeError::undefined_var(name, ident.span).with_suggestion(suggestion));
             Type::Error
         }
     }
@@ -397,6 +581,8 @@ impl<'a> TypeInferrer<'a> {
             match resolved {
                 super::symbols::ResolvedItem::Function(sig) => {
                     self.check_platform_for_function(sig, path.span);
+                    let name_span = path.segments.last().map_or(path.span, |s| s.span);
+                    self.check_deprecated("function", sig.name, &sig.attributes, path.span, name_span);
                     return Type::Function(self.symbols.to_function_type(sig));
                 }
                 super::symbols::ResolvedItem::Type(def) => {
@@ -425,23 +611,52 @@ impl<'a> TypeInferrer<'a> {
             let first = &path.segments[0];
             let variant_name = path.segments[1].symbol;
             if let Some(super::symbols::TypeDef::Enum(e)) = self.symbols.get_type(first.symbol) {
-                let enum_ty = self.symbols.to_enum_type(e);
-                for (name, fields) in &e.variants {
-                    if *name == variant_name {
-                        if let Some(field_types) = fields {
+                // Each use of a generic enum's variant gets fresh type arguments
+                let (enum_ty, value_ty) = if e.type_params.is_empty() {
+                    let enum_ty = self.symbols.to_enum_type(e);
+                    (enum_ty.clone(), Type::Enum(enum_ty))
+                } else {
+                    let type_args: Vec<Type> = e
+                        .type_params
+                        .iter()
+                        .map(|_| fresh_type_var(self.next_var_id))
+                        .collect();
+                    let enum_ty = self.symbols.instantiate_enum_type(e, &type_args);
+                    (enum_ty, Type::Generic(e.name, type_args))
+                };
+                for variant in enum_ty.variants {
+                    if variant.name == variant_name {
+                        if let Some(field_types) = variant.fields {
                             return Type::Function(FunctionType {
-                                params: field_types.clone(),
-                                returns: Box::new(Type::Enum(enum_ty)),
+                                params: field_types,
+                                returns: Box::new(value_ty),
                                 throws: vec![],
                                 is_variadic: false,
                             });
                         }
-                        return Type::Enum(enum_ty);
+                        return value_ty;
                     }
                 }
             }
         }
 
+        // Static interface functions: Interface::function
+        if path.segments.len() == 2
+            && let Some(super::symbols::TypeDef::Interface(interface)) =
+                self.symbols.get_type(path.segments[0].symbol)
+            && let Some(method) = interface
+                .methods
+                .iter()
+                .find(|m| m.is_static && m.name == path.segments[1].symbol)
+        {
+            return Type::Function(FunctionType {
+                params: method.params.iter().map(|(_, ty)| ty.clone()).collect(),
+                returns: Box::new(method.return_ty.clone()),
+                throws: method.throws.clone(),
+                is_variadic: false,
+            });
+        }
+
         // 3. Fallback: single segment in local scope (functions/types)
         if path.segments.len() == 1 {
             let ident = &path.segments[0];
@@ -449,12 +664,14 @@ impl<'a> TypeInferrer<'a> {
                 let name = self.interner.resolve(&ident.symbol).to_string();
                 self.errors.push(TypeError::AmbiguousFunction {
                     name,
+                    modules: self.symbols.ambiguous_modules(ident.symbol),
                     span: path.span,
                 });
                 return Type::Error;
             }
             if let Some(sig) = self.symbols.get_function(ident.symbol) {
                 self.check_platform_for_function(sig, path.span);
+                self.check_deprecated("function", sig.name, &sig.attributes, path.span, ident.span);
                 return Type::Function(self.symbols.to_function_type(sig));
             }
             if let Some(def) = self.symbols.get_type(ident.symbol) {
@@ -462,6 +679,21 @@ impl<'a> TypeInferrer<'a> {
             }
         }
 
+        // Items of an imported module file that it does not export
+        if path.segments.len() == 2
+            && self
+                .symbols
+                .get_module(path.segments[0].symbol)
+                .is_some_and(|m| m.is_private(path.segments[1].symbol))
+        {
+            self.errors.push(TypeError::PrivateSymbol {
+                module: self.interner.resolve(&path.segments[0].symbol).to_string(),
+                symbol: self.interner.resolve(&path.segments[1].symbol).to_string(),
+                span: path.span,
+            });
+            return Type::Error;
+        }
+
         let path_str = path_spurs
             .iter()
             .map(|s| self.interner.resolve(s))
@@ -474,6 +706,19 @@ impl<'a> TypeInferrer<'a> {
         Type::Error
     }
 
+    /// The enum a value of type `ty` belongs to. For an instance of a generic
+    /// enum the type arguments are substituted into the variant fields.
+    fn enum_instance(&self, ty: &Type) -> Option<EnumType> {
+        match ty {
+            Type::Enum(e) => Some(e.clone()),
+            Type::Generic(name, args) if !args.is_empty() => match self.symbols.get_type(*name) {
+                Some(TypeDef::Enum(def)) => Some(self.symbols.instantiate_enum_type(def, args)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     fn type_from_def(&self, def: &super::symbols::TypeDef) -> Type {
         use super::symbols::TypeDef;
         match def {
@@ -520,8 +765,18 @@ impl<'a> TypeInferrer<'a> {
             return Type::Bool;
         }
 
-        let left_ty = self.infer_expr(bin.left);
-        let right_ty = self.infer_expr(bin.right);
+        let mut left_ty = self.infer_expr(bin.left);
+        let mut right_ty = self.infer_expr(bin.right);
+
+        // An int literal next to a float operand is a float, so `x * 2`
+        // works for a float `x`
+        if !matches!(bin.op, Shl | Shr | BitAnd | BitOr | BitXor | And | Or) {
+            if left_ty.resolve() == Type::Float && is_int_literal(bin.right) {
+                right_ty = self.infer_expr_with(bin.right, Some(&Type::Float));
+            } else if right_ty.resolve() == Type::Float && is_int_literal(bin.left) {
+                left_ty = self.infer_expr_with(bin.left, Some(&Type::Float));
+            }
+        }
 
         match bin.op {
             Add => {
@@ -679,8 +934,12 @@ impl<'a> TypeInferrer<'a> {
         }
     }
 
-    fn infer_unary(&mut self, un: &ast::UnaryExpr) -> Type {
-        let operand_ty = self.infer_expr(un.operand);
+    fn infer_unary(&mut self, un: &ast::UnaryExpr, expected: Option<&Type>) -> Type {
+        let operand_ty = if un.op == ast::UnaryOp::Neg {
+            self.infer_expr_with(un.operand, expected)
+        } else {
+            self.infer_expr(un.operand)
+        };
 
         use ast::UnaryOp::*;
         match un.op {
@@ -720,13 +979,17 @@ impl<'a> TypeInferrer<'a> {
     fn infer_call(&mut self, call: &ast::CallExpr) -> Type {
         // Check if callee is an identifier referring to a generic function or exception
         if let ast::Expression::Identifier(ident) = call.callee {
-            // Check for exception constructor: ExceptionType("message")
-            if let Some(TypeDef::Exception(exc_def)) = self.symbols.get_type(ident.ident.symbol) {
+            // Check for exception constructor: ExceptionType("message").
+            // The `error` base type has no constructor; `error(...)` is the builtin.
+            if let Some(TypeDef::Exception(exc_def)) = self.symbols.get_type(ident.ident.symbol)
+                && self.interner.resolve(&exc_def.name) != "error"
+            {
                 if call.args.len() != 1 {
                     self.errors.push(TypeError::WrongArgCount {
                         expected: 1,
                         found: call.args.len(),
                         span: call.span,
+                        defined_at: Some(exc_def.span),
                     });
                     return Type::Error;
                 }
@@ -741,6 +1004,7 @@ impl<'a> TypeInferrer<'a> {
                 let name = self.interner.resolve(&ident.ident.symbol).to_string();
                 self.errors.push(TypeError::AmbiguousFunction {
                     name,
+                    modules: self.symbols.ambiguous_modules(ident.ident.symbol),
                     span: call.span,
                 });
                 return Type::Error;
@@ -748,10 +1012,21 @@ impl<'a> TypeInferrer<'a> {
 
             if let Some(func_sig) = self.symbols.get_function(ident.ident.symbol) {
                 self.check_platform_for_function(func_sig, call.span);
+                self.check_deprecated(
+                    "function",
+                    func_sig.name,
+                    &func_sig.attributes,
+                    call.span,
+                    ident.ident.span,
+                );
                 if let Some(ref module) = func_sig.module {
                     self.annotations
                         .record_resolved_module(call.span, module.clone());
                 }
+                if let Some(original) = self.symbols.original_name(ident.ident.symbol) {
+                    self.annotations
+                        .record_aliased_call(call.span, self.interner.resolve(&original).to_string());
+                }
                 if !func_sig.type_params.is_empty() {
                     return self.infer_generic_call(call, func_sig);
                 }
@@ -795,34 +1070,57 @@ impl<'a> TypeInferrer<'a> {
             }
         }
 
+        // A call to a name that is defined nowhere is an undefined function
+        // rather than an undefined variable
+        if let ast::Expression::Identifier(ident) = call.callee
+            && self.env.lookup(ident.ident.symbol).is_none()
+            && self.symbols.get_function(ident.ident.symbol).is_none()
+            && self.symbols.get_type(ident.ident.symbol).is_none()
+        {
+            let name = self.interner.resolve(&ident.ident.symbol).to_string();
+            self.errors.push(TypeError::UndefinedFunction {
+                name,
+                span: ident.span,
+                suggestion: self.suggest_name(ident.ident.symbol),
+            });
+            for arg in &call.args {
+                self.infer_expr(arg);
+            }
+            return Type::Error;
+        }
+
         let callee_ty = self.infer_expr(call.callee);
         let resolved = callee_ty.resolve();
 
         match resolved {
             Type::Function(func) => {
                 if func.is_variadic {
+                    let mut arg_types = Vec::with_capacity(call.args.len());
                     for (arg, param_ty) in call.args.iter().zip(func.params.iter()) {
-                        let arg_ty = self.infer_expr(arg);
-                        if let Err(e) = unify(&arg_ty, param_ty, arg.span()) {
+                        let arg_ty = self.infer_expr_with(arg, Some(param_ty));
+                        if let Err(e) = unify_arg(&arg_ty, param_ty, arg.span()) {
                             self.errors.push(e);
                         }
+                        arg_types.push(arg_ty);
                     }
                     for arg in call.args.iter().skip(func.params.len()) {
-                        self.infer_expr(arg);
+                        arg_types.push(self.infer_expr(arg));
                     }
+                    self.check_format_specs(call, &arg_types);
                 } else {
                     if call.args.len() != func.params.len() {
                         self.errors.push(TypeError::WrongArgCount {
                             expected: func.params.len(),
                             found: call.args.len(),
                             span: call.span,
+                            defined_at: self.callee_definition(call.callee),
                         });
                         return Type::Error;
                     }
 
                     for (arg, param_ty) in call.args.iter().zip(func.params.iter()) {
-                        let arg_ty = self.infer_expr(arg);
-                        if let Err(e) = unify(&arg_ty, param_ty, arg.span()) {
+                        let arg_ty = self.infer_expr_with(arg, Some(param_ty));
+                        if let Err(e) = unify_arg(&arg_ty, param_ty, arg.span()) {
                             self.errors.push(e);
                         }
                     }
@@ -833,6 +1131,13 @@ impl<'a> TypeInferrer<'a> {
                     self.check_uncaught_exceptions(&func.throws, call.span);
                 }
 
+                if self.is_std_call(call.callee, "config", "load") {
+                    return self.infer_config_load(call);
+                }
+                if self.is_std_call(call.callee, "process::pool", "open_worker_pool") {
+                    self.check_worker_entry(call);
+                }
+
                 (*func.returns).clone()
             }
             Type::Error => Type::Error,
@@ -846,6 +1151,129 @@ impl<'a> TypeInferrer<'a> {
         }
     }
 
+    /// Whether a callee names the std function `name` of `module`
+    fn is_std_call(&self, callee: &Expression, module: &str, name: &str) -> bool {
+        let module_name = module.rsplit("::").next().unwrap_or(module);
+        match callee {
+            Expression::Identifier(ident) => {
+                let original = self
+                    .symbols
+                    .original_name(ident.ident.symbol)
+                    .unwrap_or(ident.ident.symbol);
+                self.interner.resolve(&original) == name
+                    && self
+                        .symbols
+                        .get_function(ident.ident.symbol)
+                        .is_some_and(|sig| sig.module.as_deref() == Some(module))
+            }
+            Expression::Path(path) => {
+                let names: Vec<&str> = path
+                    .segments
+                    .iter()
+                    .map(|seg| self.interner.resolve(&seg.symbol))
+                    .collect();
+                names.ends_with(&[module_name, name])
+                    && self
+                        .symbols
+                        .get_module_function(
+                            path.segments[path.segments.len() - 2].symbol,
+                            path.segments[path.segments.len() - 1].symbol,
+                        )
+                        .is_none_or(|sig| sig.module.as_deref() == Some(module))
+            }
+            _ => false,
+        }
+    }
+
+    /// `config::load("Name", sources)` returns the struct its first argument
+    /// names. The name must be a string literal and the struct's fields must
+    /// be int, float, bool or string. The schema literal is annotated with
+    /// the struct type so codegen can build the field spec.
+    fn infer_config_load(&mut self, call: &ast::CallExpr) -> Type {
+        let Some(ast::Expression::Literal(ast::LiteralExpr {
+            value: ast::Literal::String(name),
+            span,
+        })) = call.args.first()
+        else {
+            self.errors.push(TypeError::Custom {
+                message: "config::load expects the schema struct name as a string literal"
+                    .to_string(),
+                span: call.span,
+            });
+            return Type::Error;
+        };
+
+        let struct_type = match self.symbols.get_type(*name) {
+            Some(TypeDef::Struct(def)) if def.type_params.is_empty() => {
+                self.symbols.to_struct_type(def)
+            }
+            _ => {
+                self.errors.push(TypeError::Custom {
+                    message: format!(
+                        "config::load schema '{}' must name a non-generic struct",
+                        self.interner.resolve(name)
+                    ),
+                    span: *span,
+                });
+                return Type::Error;
+            }
+        };
+
+        for field in &struct_type.fields {
+            if !matches!(field.ty, Type::Int | Type::Float | Type::Bool | Type::String) {
+                self.errors.push(TypeError::Custom {
+                    message: format!(
+                        "config field '{}' has type {}; expected int, float, bool or string",
+                        self.interner.resolve(&field.name),
+                        self.display_type(&field.ty)
+                    ),
+                    span: *span,
+                });
+                return Type::Error;
+            }
+        }
+
+        let ty = Type::Struct(struct_type);
+        self.annotations.annotate_type(*span, ty.clone());
+        ty
+    }
+
+    /// `open_worker_pool(n, "entry")` forks workers that run the function
+    /// its second argument names, so the name must be a string literal
+    /// naming a top-level, non-generic `fn(bytes) -> bytes`.
+    fn check_worker_entry(&mut self, call: &ast::CallExpr) {
+        let Some(ast::Expression::Literal(ast::LiteralExpr {
+            value: ast::Literal::String(name),
+            span,
+        })) = call.args.get(1)
+        else {
+            if let Some(arg) = call.args.get(1) {
+                self.errors.push(TypeError::Custom {
+                    message: "open_worker_pool expects the entry function name as a string literal"
+                        .to_string(),
+                    span: arg.span(),
+                });
+            }
+            return;
+        };
+
+        let is_entry = self.symbols.get_function(*name).is_some_and(|sig| {
+            sig.module.is_none()
+                && sig.type_params.is_empty()
+                && matches!(sig.params.as_slice(), [(_, Type::Bytes)])
+                && sig.return_ty == Type::Bytes
+        });
+        if !is_entry {
+            self.errors.push(TypeError::Custom {
+                message: format!(
+                    "worker entry '{}' must name a top-level fn(bytes) -> bytes",
+                    self.interner.resolve(name)
+                ),
+                span: *span,
+            });
+        }
+    }
+
     fn infer_generic_call(
         &mut self,
         call: &ast::CallExpr,
@@ -882,14 +1310,22 @@ impl<'a> TypeInferrer<'a> {
                 expected: func_sig.params.len(),
                 found: call.args.len(),
                 span: call.span,
+                defined_at: func_sig.module.is_none().then_some(func_sig.span),
             });
             return Type::Error;
         }
 
-        // Check argument types with substitution applied
-        for (arg, (_, param_ty)) in call.args.iter().zip(func_sig.params.iter()) {
-            let arg_ty = self.infer_expr(arg);
+        // Check argument types with substitution applied. Lambdas go last so
+        // the other arguments can first fix the type arguments their
+        // parameter types are inferred from.
+        let (lambdas, others): (Vec<_>, Vec<_>) = call
+            .args
+            .iter()
+            .zip(func_sig.params.iter())
+            .partition(|(arg, _)| matches!(arg, Expression::Lambda(_)));
+        for (arg, (_, param_ty)) in others.into_iter().chain(lambdas) {
             let substituted_param_ty = param_ty.substitute(&substitution);
+            let arg_ty = self.infer_expr_with(arg, Some(&substituted_param_ty));
             if let Err(e) = unify(&arg_ty, &substituted_param_ty, arg.span()) {
                 self.errors.push(e);
             }
@@ -908,6 +1344,17 @@ impl<'a> TypeInferrer<'a> {
             })
             .collect();
 
+        if func_sig.module.as_deref() == Some("collections::arrays") {
+            let name = self.symbols.original_name(func_sig.name).unwrap_or(func_sig.name);
+            self.dispatch_numeric_aggregate(call, name, &resolved_type_args);
+        }
+        if matches!(
+            func_sig.module.as_deref(),
+            Some("collections::sets" | "collections::priority_queues")
+        ) {
+            self.check_ordered_element(call, &resolved_type_args);
+        }
+
         // Generate mangled name: func_TypeArg1_TypeArg2
         let func_name = self.interner.resolve(&func_sig.name);
         let mangled_name = self.mangle_generic_function(func_name, &resolved_type_args);
@@ -932,6 +1379,55 @@ impl<'a> TypeInferrer<'a> {
         func_sig.return_ty.substitute(&substitution)
     }
 
+    /// `sum`, `min`, `max` and `sort` have one runtime function for int
+    /// arrays and one for float arrays. Float calls are recorded as calls to
+    /// the `*_float` variant; other element types are rejected.
+    fn dispatch_numeric_aggregate(&mut self, call: &ast::CallExpr, name: lasso::Spur, type_args: &[Type]) {
+        let name = self.interner.resolve(&name);
+        if !matches!(name, "sum" | "min" | "max" | "sort") {
+            return;
+        }
+        match type_args.first() {
+            Some(Type::Float) => {
+                let float_name = format!("{}_float", name);
+                self.annotations.record_aliased_call(call.span, float_name);
+            }
+            Some(Type::Int | Type::TypeVar(_) | Type::Error) | None => {}
+            Some(other) => {
+                let found = format!("[{}]", self.display_type(other));
+                self.errors.push(TypeError::TypeMismatch {
+                    expected: "[int] or [float]".to_string(),
+                    found,
+                    span: call.args.first().map_or(call.span, |arg| arg.span()),
+                });
+            }
+        }
+    }
+
+    /// Sets and priority queues order their elements by value, which the
+    /// runtime can do for scalars and strings only
+    fn check_ordered_element(&mut self, call: &ast::CallExpr, type_args: &[Type]) {
+        match type_args.first() {
+            Some(
+                Type::Int
+                | Type::Uint
+                | Type::Float
+                | Type::Bool
+                | Type::String
+                | Type::TypeVar(_)
+                | Type::Error,
+            )
+            | None => {}
+            Some(other) => {
+                self.errors.push(TypeError::TypeMismatch {
+                    expected: "int, uint, float, bool or string elements".to_string(),
+                    found: self.display_type(other),
+                    span: call.span,
+                });
+            }
+        }
+    }
+
     fn infer_method_call(&mut self, call: &ast::MethodCallExpr) -> Type {
         let receiver_ty = self.infer_expr(call.receiver);
         let resolved = receiver_ty.resolve();
@@ -943,6 +1439,8 @@ impl<'a> TypeInferrer<'a> {
                 ty: self.display_type(&resolved),
                 method: method_name.to_string(),
                 span: call.span,
+                suggestion: None,
+                defined_at: None,
             });
             return Type::Error;
         }
@@ -954,6 +1452,8 @@ impl<'a> TypeInferrer<'a> {
                 ty: self.display_type(&resolved),
                 method: method_name.to_string(),
                 span: call.span,
+                suggestion: None,
+                defined_at: None,
             });
             return Type::Error;
         }
@@ -965,42 +1465,52 @@ impl<'a> TypeInferrer<'a> {
                 ty: self.display_type(&resolved),
                 method: method_name.to_string(),
                 span: call.span,
+                suggestion: None,
+                defined_at: None,
             });
             return Type::Error;
         }
 
-        // Check if receiver is a bare type parameter (T with no type args)
-        // If so, look up methods from its bounds
-        if let Type::Generic(param_name, type_args) = &resolved
-            && type_args.is_empty()
-        {
-            // This might be a type parameter - check if it has bounds with this method
-            if let Some(method_type) = super::generics::find_method_from_bounds(
-                *param_name,
+        // A bare type parameter (T with no type args) gets its methods from its
+        // bounds, and an interface-typed receiver (`self` in a default method)
+        // from the interface
+        let interface_method = match &resolved {
+            Type::Generic(param_name, type_args) if type_args.is_empty() => {
+                super::generics::find_method_from_bounds(
+                    *param_name,
+                    call.method.symbol,
+                    self.env,
+                    self.symbols,
+                )
+            }
+            Type::Interface(interface) => super::generics::find_interface_method(
+                interface.name,
                 call.method.symbol,
-                self.env,
                 self.symbols,
-            ) {
-                // Check argument count
-                if call.args.len() != method_type.params.len() {
-                    self.errors.push(TypeError::WrongArgCount {
-                        expected: method_type.params.len(),
-                        found: call.args.len(),
-                        span: call.span,
-                    });
-                    return Type::Error;
-                }
+            ),
+            _ => None,
+        };
+        if let Some(method_type) = interface_method {
+            // Check argument count
+            if call.args.len() != method_type.params.len() {
+                self.errors.push(TypeError::WrongArgCount {
+                    expected: method_type.params.len(),
+                    found: call.args.len(),
+                    span: call.span,
+                    defined_at: None,
+                });
+                return Type::Error;
+            }
 
-                // Check argument types
-                for (arg, param_ty) in call.args.iter().zip(method_type.params.iter()) {
-                    let arg_ty = self.infer_expr(arg);
-                    if let Err(e) = unify(&arg_ty, param_ty, arg.span()) {
-                        self.errors.push(e);
-                    }
+            // Check argument types
+            for (arg, param_ty) in call.args.iter().zip(method_type.params.iter()) {
+                let arg_ty = self.infer_expr_with(arg, Some(param_ty));
+                if let Err(e) = unify(&arg_ty, param_ty, arg.span()) {
+                    self.errors.push(e);
                 }
-
-                return method_type.returns;
             }
+
+            return method_type.returns;
         }
 
         // Exception built-in methods
@@ -1013,15 +1523,45 @@ impl<'a> TypeInferrer<'a> {
                             expected: 0,
                             found: call.args.len(),
                             span: call.span,
+                            defined_at: None,
                         });
                     }
                     Type::String
                 }
+                "stack_frames" => {
+                    if !call.args.is_empty() {
+                        self.errors.push(TypeError::WrongArgCount {
+                            expected: 0,
+                            found: call.args.len(),
+                            span: call.span,
+                            defined_at: None,
+                        });
+                    }
+                    Type::Array(Box::new(Type::StackFrame))
+                }
+                // Accessors matching the `message` and `stack` fields
+                "message" | "stack" => {
+                    if !call.args.is_empty() {
+                        self.errors.push(TypeError::WrongArgCount {
+                            expected: 0,
+                            found: call.args.len(),
+                            span: call.span,
+                            defined_at: None,
+                        });
+                    }
+                    if method_name == "message" {
+                        Type::String
+                    } else {
+                        Type::Array(Box::new(Type::StackFrame))
+                    }
+                }
                 _ => {
                     self.errors.push(TypeError::UndefinedMethod {
                         ty: self.display_type(&resolved),
                         method: method_name.to_string(),
                         span: call.span,
+                        suggestion: None,
+                        defined_at: None,
                     });
                     Type::Error
                 }
@@ -1035,6 +1575,8 @@ impl<'a> TypeInferrer<'a> {
                 ty: self.display_type(&resolved),
                 method: method_name.to_string(),
                 span: call.span,
+                suggestion: None,
+                defined_at: None,
             });
             return Type::Error;
         }
@@ -1050,17 +1592,27 @@ impl<'a> TypeInferrer<'a> {
                     ty: self.display_type(&resolved),
                     method,
                     span: call.span,
+                    suggestion: None,
+                    defined_at: None,
                 });
                 return Type::Error;
             }
         };
 
         if let Some(method) = self.symbols.get_method(type_name, call.method.symbol) {
+            self.check_deprecated(
+                "method",
+                method.name,
+                &method.attributes,
+                call.span,
+                call.method.span,
+            );
             if call.args.len() != method.params.len() {
                 self.errors.push(TypeError::WrongArgCount {
                     expected: method.params.len(),
                     found: call.args.len(),
                     span: call.span,
+                    defined_at: Some(method.span),
                 });
                 return Type::Error;
             }
@@ -1069,25 +1621,25 @@ impl<'a> TypeInferrer<'a> {
             use std::collections::HashMap;
             let substitutions: HashMap<lasso::Spur, Type> =
                 if let Type::Generic(_, type_args) = &resolved {
-                    // Look up the struct definition to get type parameter names
-                    if let Some(TypeDef::Struct(struct_def)) = self.symbols.get_type(type_name) {
-                        struct_def
-                            .type_params
-                            .iter()
-                            .zip(type_args.iter())
-                            .map(|(param, arg)| (param.name, arg.clone()))
-                            .collect()
-                    } else {
-                        HashMap::new()
-                    }
+                    // Look up the type definition to get type parameter names
+                    let type_params = match self.symbols.get_type(type_name) {
+                        Some(TypeDef::Struct(struct_def)) => struct_def.type_params.as_slice(),
+                        Some(TypeDef::Enum(enum_def)) => enum_def.type_params.as_slice(),
+                        _ => &[],
+                    };
+                    type_params
+                        .iter()
+                        .zip(type_args.iter())
+                        .map(|(param, arg)| (param.name, arg.clone()))
+                        .collect()
                 } else {
                     HashMap::new()
                 };
 
             for (arg, (_, param_ty)) in call.args.iter().zip(method.params.iter()) {
-                let arg_ty = self.infer_expr(arg);
                 // Substitute type parameters in the parameter type
                 let substituted_param_ty = param_ty.substitute(&substitutions);
+                let arg_ty = self.infer_expr_with(arg, Some(&substituted_param_ty));
                 if let Err(e) = unify(&arg_ty, &substituted_param_ty, arg.span()) {
                     self.errors.push(e);
                 }
@@ -1111,6 +1663,8 @@ impl<'a> TypeInferrer<'a> {
                 ty: self.display_type(&resolved),
                 method,
                 span: call.span,
+                suggestion: self.suggest_method(type_name, call.method.symbol),
+                defined_at: self.type_definition(type_name),
             });
             Type::Error
         }
@@ -1182,6 +1736,8 @@ impl<'a> TypeInferrer<'a> {
                     ty: self.display_type(&resolved),
                     field: field_name.to_string(),
                     span: field.span,
+                    suggestion: None,
+                    defined_at: None,
                 });
                 Type::Error
             }
@@ -1193,9 +1749,11 @@ impl<'a> TypeInferrer<'a> {
                 }
                 let field_name = self.interner.resolve(&field.field.symbol).to_string();
                 self.errors.push(TypeError::UndefinedField {
-                    ty: format!("{:?}", s.name),
+                    ty: self.interner.resolve(&s.name).to_string(),
                     field: field_name,
                     span: field.span,
+                    suggestion: self.suggest_field(field.field.symbol, s.fields.iter().map(|f| f.name)),
+                    defined_at: self.type_definition(s.name),
                 });
                 Type::Error
             }
@@ -1235,9 +1793,14 @@ impl<'a> TypeInferrer<'a> {
                     }
                     let field_name = self.interner.resolve(&field.field.symbol).to_string();
                     self.errors.push(TypeError::UndefinedField {
-                        ty: format!("{:?}", name),
+                        ty: self.interner.resolve(&name).to_string(),
                         field: field_name,
                         span: field.span,
+                        suggestion: self.suggest_field(
+                            field.field.symbol,
+                            struct_ty.fields.iter().map(|f| f.name),
+                        ),
+                        defined_at: Some(def.span),
                     });
                     Type::Error
                 } else {
@@ -1246,6 +1809,8 @@ impl<'a> TypeInferrer<'a> {
                         ty: self.display_type(&resolved),
                         field: field_name,
                         span: field.span,
+                        suggestion: None,
+                        defined_at: None,
                     });
                     Type::Error
                 }
@@ -1260,6 +1825,13 @@ impl<'a> TypeInferrer<'a> {
                     if field_name_str == "stack" {
                         return Type::Array(Box::new(Type::StackFrame));
                     }
+                    if field_name_str == "cause" {
+                        return self
+                            .interner
+                            .get("error")
+                            .map(|name| Type::Option(Box::new(Type::Exception(name))))
+                            .unwrap_or(Type::Error);
+                    }
                     // User-defined exception fields
                     for (f_name, f_ty) in &def.fields {
                         if *f_name == field.field.symbol {
@@ -1270,6 +1842,11 @@ impl<'a> TypeInferrer<'a> {
                         ty: self.display_type(&resolved),
                         field: field_name_str.to_string(),
                         span: field.span,
+                        suggestion: self.suggest_field(
+                            field.field.symbol,
+                            def.fields.iter().map(|(name, _)| *name),
+                        ),
+                        defined_at: Some(def.span),
                     });
                     Type::Error
                 } else {
@@ -1287,6 +1864,46 @@ impl<'a> TypeInferrer<'a> {
                             ty: "stack_frame".to_string(),
                             field: field_name.to_string(),
                             span: field.span,
+                            suggestion: did_you_mean(field_name, ["function", "file", "line"]),
+                            defined_at: None,
+                        });
+                        Type::Error
+                    }
+                }
+            }
+            Type::IfaceInfo => {
+                let field_name = self.interner.resolve(&field.field.symbol);
+                match field_name {
+                    "name" | "mac" => Type::String,
+                    "ips" | "flags" => Type::Array(Box::new(Type::String)),
+                    "mtu" => Type::Int,
+                    _ => {
+                        self.errors.push(TypeError::UndefinedField {
+                            ty: "iface_info".to_string(),
+                            field: field_name.to_string(),
+                            span: field.span,
+                            suggestion: did_you_mean(
+                                field_name,
+                                ["name", "ips", "mac", "flags", "mtu"],
+                            ),
+                            defined_at: None,
+                        });
+                        Type::Error
+                    }
+                }
+            }
+            Type::FsEvent => {
+                let field_name = self.interner.resolve(&field.field.symbol);
+                match field_name {
+                    "kind" | "path" => Type::String,
+                    "timestamp" => Type::Int,
+                    _ => {
+                        self.errors.push(TypeError::UndefinedField {
+                            ty: "fs_event".to_string(),
+                            field: field_name.to_string(),
+                            span: field.span,
+                            suggestion: did_you_mean(field_name, ["kind", "path", "timestamp"]),
+                            defined_at: None,
                         });
                         Type::Error
                     }
@@ -1299,20 +1916,29 @@ impl<'a> TypeInferrer<'a> {
                     ty: self.display_type(&resolved),
                     field: field_name,
                     span: field.span,
+                    suggestion: None,
+                    defined_at: None,
                 });
                 Type::Error
             }
         }
     }
 
-    fn infer_array(&mut self, arr: &ast::ArrayExpr) -> Type {
+    fn infer_array(&mut self, arr: &ast::ArrayExpr, expected: Option<&Type>) -> Type {
+        let expected_elem = match expected {
+            Some(Type::Array(elem)) | Some(Type::FixedArray(elem, _)) => Some(&**elem),
+            _ => None,
+        };
         if arr.elements.is_empty() {
-            return Type::Array(Box::new(fresh_type_var(self.next_var_id)));
+            let elem = expected_elem
+                .cloned()
+                .unwrap_or_else(|| fresh_type_var(self.next_var_id));
+            return Type::Array(Box::new(elem));
         }
 
-        let first_ty = self.infer_expr(&arr.elements[0]);
+        let first_ty = self.infer_expr_with(&arr.elements[0], expected_elem);
         for elem in arr.elements.iter().skip(1) {
-            let elem_ty = self.infer_expr(elem);
+            let elem_ty = self.infer_expr_with(elem, expected_elem.or(Some(&first_ty)));
             if let Err(e) = unify(&elem_ty, &first_ty, elem.span()) {
                 self.errors.push(e);
             }
@@ -1321,20 +1947,27 @@ impl<'a> TypeInferrer<'a> {
         Type::Array(Box::new(first_ty.resolve()))
     }
 
-    fn infer_map(&mut self, map: &ast::MapExpr) -> Type {
+    fn infer_map(&mut self, map: &ast::MapExpr, expected: Option<&Type>) -> Type {
+        let (expected_key, expected_val) = match expected {
+            Some(Type::Map(key, val)) => (Some(&**key), Some(&**val)),
+            _ => (None, None),
+        };
         if map.entries.is_empty() {
-            return Type::Map(
-                Box::new(fresh_type_var(self.next_var_id)),
-                Box::new(fresh_type_var(self.next_var_id)),
-            );
+            let key = expected_key
+                .cloned()
+                .unwrap_or_else(|| fresh_type_var(self.next_var_id));
+            let val = expected_val
+                .cloned()
+                .unwrap_or_else(|| fresh_type_var(self.next_var_id));
+            return Type::Map(Box::new(key), Box::new(val));
         }
 
-        let first_key_ty = self.infer_expr(&map.entries[0].key);
-        let first_val_ty = self.infer_expr(&map.entries[0].value);
+        let first_key_ty = self.infer_expr_with(&map.entries[0].key, expected_key);
+        let first_val_ty = self.infer_expr_with(&map.entries[0].value, expected_val);
 
         for entry in map.entries.iter().skip(1) {
-            let key_ty = self.infer_expr(&entry.key);
-            let val_ty = self.infer_expr(&entry.value);
+            let key_ty = self.infer_expr_with(&entry.key, expected_key.or(Some(&first_key_ty)));
+            let val_ty = self.infer_expr_with(&entry.value, expected_val.or(Some(&first_val_ty)));
             if let Err(e) = unify(&key_ty, &first_key_ty, entry.key.span()) {
                 self.errors.push(e);
             }
@@ -1370,9 +2003,10 @@ impl<'a> TypeInferrer<'a> {
                             .iter()
                             .find(|f| f.name == field_lit.name.symbol);
                         if let Some(field_def) = field_def {
-                            let value_ty = self.infer_expr(&field_lit.value);
                             // Apply substitution to field type to replace type params with type vars
                             let substituted_field_ty = field_def.ty.substitute(&substitution);
+                            let value_ty =
+                                self.infer_expr_with(&field_lit.value, Some(&substituted_field_ty));
                             if let Err(e) = unify(&value_ty, &substituted_field_ty, field_lit.span)
                             {
                                 self.errors.push(e);
@@ -1381,9 +2015,14 @@ impl<'a> TypeInferrer<'a> {
                             let field_name =
                                 self.interner.resolve(&field_lit.name.symbol).to_string();
                             self.errors.push(TypeError::UndefinedField {
-                                ty: format!("{:?}", lit.name.symbol),
+                                ty: self.interner.resolve(&lit.name.symbol).to_string(),
                                 field: field_name,
                                 span: field_lit.span,
+                                suggestion: self.suggest_field(
+                                    field_lit.name.symbol,
+                                    struct_ty.fields.iter().map(|f| f.name),
+                                ),
+                                defined_at: self.type_definition(lit.name.symbol),
                             });
                         }
                     }
@@ -1414,7 +2053,7 @@ impl<'a> TypeInferrer<'a> {
                             .iter()
                             .find(|(name, _)| *name == field_lit.name.symbol);
                         if let Some((_, field_ty)) = field_def {
-                            let value_ty = self.infer_expr(&field_lit.value);
+                            let value_ty = self.infer_expr_with(&field_lit.value, Some(field_ty));
                             if let Err(e) = unify(&value_ty, field_ty, field_lit.span) {
                                 self.errors.push(e);
                             }
@@ -1422,9 +2061,14 @@ impl<'a> TypeInferrer<'a> {
                             let field_name =
                                 self.interner.resolve(&field_lit.name.symbol).to_string();
                             self.errors.push(TypeError::UndefinedField {
-                                ty: format!("{:?}", lit.name.symbol),
+                                ty: self.interner.resolve(&lit.name.symbol).to_string(),
                                 field: field_name,
                                 span: field_lit.span,
+                                suggestion: self.suggest_field(
+                                    field_lit.name.symbol,
+                                    exc.fields.iter().map(|(name, _)| *name),
+                                ),
+                                defined_at: Some(exc.span),
                             });
                         }
                     }
@@ -1440,11 +2084,19 @@ impl<'a> TypeInferrer<'a> {
                     Type::Error
                 }
             }
+        } else if let Some(module) = self.symbols.hidden_type_module(lit.name.symbol) {
+            self.errors.push(TypeError::PrivateSymbol {
+                module: module.to_string(),
+                symbol: self.interner.resolve(&lit.name.symbol).to_string(),
+                span: lit.span,
+            });
+            Type::Error
         } else {
             let name = self.interner.resolve(&lit.name.symbol).to_string();
             self.errors.push(TypeError::UndefinedType {
                 name,
                 span: lit.span,
+                suggestion: self.suggest_type(lit.name.symbol),
             });
             Type::Error
         }
@@ -1489,13 +2141,22 @@ impl<'a> TypeInferrer<'a> {
         result
     }
 
-    fn infer_lambda(&mut self, lambda: &ast::LambdaExpr) -> Type {
+    fn infer_lambda(&mut self, lambda: &ast::LambdaExpr, expected: Option<&Type>) -> Type {
         self.env.push_scope();
 
+        // A lambda passed where a function type is expected takes its
+        // unannotated parameter types from that function type
+        let expected = match expected {
+            Some(Type::Function(func)) if func.params.len() == lambda.params.len() => Some(func),
+            _ => None,
+        };
+
         let mut param_types = Vec::new();
-        for param in &lambda.params {
+        for (i, param) in lambda.params.iter().enumerate() {
             let ty = if let Some(ty_annot) = &param.ty {
                 self.convert_ast_type(ty_annot)
+            } else if let Some(func) = expected {
+                func.params[i].clone()
             } else {
                 fresh_type_var(self.next_var_id)
             };
@@ -1523,6 +2184,12 @@ impl<'a> TypeInferrer<'a> {
                 self.errors.push(e);
             }
             expected_return_ty
+        } else if body_ty == Type::Unit
+            && !matches!(expected_return_ty.resolve(), Type::TypeVar(_) | Type::Unit)
+        {
+            // A block body without a tail value returns through `return`
+            // statements, which already fixed the return type
+            expected_return_ty.resolve()
         } else if unify(&body_ty, &expected_return_ty, lambda.span).is_err() {
             body_ty
         } else {
@@ -1549,10 +2216,13 @@ impl<'a> TypeInferrer<'a> {
                 span: spawn.span,
             });
         }
-        // Infer the block body for type checking purposes
+        // `return` inside the block sets the task's result
+        let result_ty = fresh_type_var(self.next_var_id);
+        self.env.enter_spawn(result_ty);
         let _body_ty = self.infer_block(spawn.body);
-        // Spawn runs concurrently and doesn't return a value
-        Type::Unit
+        self.env.exit_function();
+        // Spawn runs concurrently and evaluates to the task's handle
+        Type::Int
     }
 
     fn infer_try(&mut self, try_expr: &ast::TryExpr) -> Type {
@@ -1581,7 +2251,14 @@ impl<'a> TypeInferrer<'a> {
         self.in_catch_context = prev_catch_context;
 
         // Determine the exception type from the expression being caught
-        let exception_ty = self.get_throws_type(catch.expr);
+        let exception_ty = if catch.error_types.is_empty() {
+            self.get_throws_types(catch.expr)
+                .into_iter()
+                .next()
+                .unwrap_or(Type::Error)
+        } else {
+            self.check_catch_types(catch)
+        };
 
         self.env.push_scope();
 
@@ -1600,25 +2277,102 @@ impl<'a> TypeInferrer<'a> {
         expr_ty
     }
 
-    /// Get the exception type that an expression can throw
-    fn get_throws_type(&self, expr: &Expression) -> Type {
+    fn infer_finally(&mut self, finally: &ast::FinallyExpr) -> Type {
+        let expr_ty = self.infer_expr(finally.expr);
+
+        self.env.push_scope();
+        for stmt in &finally.body.statements {
+            self.check_stmt(stmt);
+        }
+        self.env.pop_scope();
+
+        expr_ty
+    }
+
+    /// Check the types listed in `catch (e: A | B)` and return the binding's type:
+    /// the single listed type, or the `error` base type for several or a catch-all.
+    /// Thrown types that are not listed must still be propagated.
+    fn check_catch_types(&mut self, catch: &ast::CatchExpr) -> Type {
+        let base = self.interner.get("error");
+        let mut listed = Vec::new();
+        for ast_ty in &catch.error_types {
+            let ty = self.convert_ast_type(ast_ty);
+            match ty {
+                Type::Exception(name) => listed.push(name),
+                Type::Error => {}
+                _ => self.errors.push(TypeError::Custom {
+                    message: format!(
+                        "catch expects exception types, found {}",
+                        self.display_type(&ty)
+                    ),
+                    span: catch.span,
+                }),
+            }
+        }
+
+        if !listed.iter().any(|name| Some(*name) == base) {
+            let unhandled: Vec<Type> = self
+                .get_throws_types(catch.expr)
+                .into_iter()
+                .filter(|t| !matches!(t, Type::Exception(name) if listed.contains(name)))
+                .collect();
+            self.check_uncaught_exceptions(&unhandled, catch.expr.span());
+        }
+
+        match listed.as_slice() {
+            [single] => Type::Exception(*single),
+            [] => Type::Error,
+            _ => base.map(Type::Exception).unwrap_or(Type::Error),
+        }
+    }
+
+    fn arithmetic_error(&self) -> Vec<Type> {
+        self.interner.get("ArithmeticError").map(Type::Exception).into_iter().collect()
+    }
+
+    /// Whether the expression at `span` is a map with float keys
+    fn has_float_keys(&self, span: crate::source::Span) -> bool {
+        matches!(
+            self.annotations.get_type(span).map(Type::resolve),
+            Some(Type::Map(key, _)) if matches!(key.resolve(), Type::Float)
+        )
+    }
+
+    /// Get the exception types that an expression can throw
+    fn get_throws_types(&self, expr: &Expression) -> Vec<Type> {
         match expr {
             Expression::Call(call) => {
                 // Check if callee is a function with throws
                 if let Expression::Identifier(ident) = call.callee
                     && let Some(func_sig) = self.symbols.get_function(ident.ident.symbol)
-                    && let Some(first_throw) = func_sig.throws.first()
+                    && !func_sig.throws.is_empty()
                 {
-                    return first_throw.clone();
+                    return func_sig.throws.clone();
                 }
-                Type::Error
+                // Qualified callees (e.g. `hex::decode`) carry throws on their annotated type
+                if let Some(Type::Function(func)) = self.annotations.get_type(call.callee.span())
+                    && !func.throws.is_empty()
+                {
+                    return func.throws.clone();
+                }
+                Vec::new()
             }
             Expression::MethodCall(_method_call) => {
                 // For method calls, we'd need to look up the method's throws
-                // For now, return Error as fallback
-                Type::Error
+                // For now, return no types as fallback
+                Vec::new()
             }
-            _ => Type::Error,
+            // Storing a NaN key in a float-keyed map throws ArithmeticError
+            Expression::Map(map_expr) if self.has_float_keys(map_expr.span) => self.arithmetic_error(),
+            // Integer division by zero throws ArithmeticError
+            Expression::Binary(bin) if matches!(bin.op, ast::BinaryOp::Div | ast::BinaryOp::Mod) => {
+                match self.annotations.get_type(bin.left.span()).map(Type::resolve) {
+                    Some(Type::Int | Type::Uint) => self.arithmetic_error(),
+                    _ => Vec::new(),
+                }
+            }
+            Expression::Grouped(grouped) => self.get_throws_types(grouped.inner),
+            _ => Vec::new(),
         }
     }
 
@@ -1655,8 +2409,36 @@ impl<'a> TypeInferrer<'a> {
     }
 
     fn infer_cast(&mut self, cast: &ast::CastExpr) -> Type {
-        self.infer_expr(cast.expr);
-        self.convert_ast_type(&cast.target_ty)
+        let source_ty = self.infer_expr(cast.expr);
+        let target_ty = self.convert_ast_type(&cast.target_ty);
+        // Secrets only leave their wrapper through an explicit expose call
+        if matches!(source_ty.resolve(), Type::Secret) && !matches!(target_ty, Type::Secret) {
+            self.errors.push(TypeError::InvalidOperation {
+                op: "cast".into(),
+                ty: self.display_type(&source_ty),
+                span: cast.span,
+            });
+        }
+        // Money is only built and read through std::money, which checks
+        // the amount against its currency
+        let money_cast = matches!(source_ty.resolve(), Type::Money) || matches!(target_ty, Type::Money);
+        if money_cast && source_ty.resolve() != target_ty {
+            self.errors.push(TypeError::InvalidOperation {
+                op: "cast".into(),
+                ty: self.display_type(&source_ty),
+                span: cast.span,
+            });
+        }
+        // A builder's text is read with sb_to_string, which copies it
+        let builder_cast = matches!(source_ty.resolve(), Type::StringBuilder) || matches!(target_ty, Type::StringBuilder);
+        if builder_cast && source_ty.resolve() != target_ty {
+            self.errors.push(TypeError::InvalidOperation {
+                op: "cast".into(),
+                ty: self.display_type(&source_ty),
+                span: cast.span,
+            });
+        }
+        target_ty
     }
 
     fn infer_fallible_cast(&mut self, cast: &ast::FallibleCastExpr) -> Type {
@@ -1710,7 +2492,7 @@ impl<'a> TypeInferrer<'a> {
 
             Pattern::Identifier(ident) => {
                 // Identifier pattern: first check if it's an enum variant name
-                if let Type::Enum(enum_ty) = scrutinee_ty {
+                if let Some(enum_ty) = self.enum_instance(scrutinee_ty) {
                     for variant in &enum_ty.variants {
                         if variant.name == ident.ident.symbol {
                             // It's a variant name, return the enum type
@@ -1736,7 +2518,7 @@ impl<'a> TypeInferrer<'a> {
                 // Check if it's a qualified path (EnumType::Variant) or bare variant
                 if variant.path.len() == 1 {
                     // Bare variant name - use scrutinee type to resolve
-                    if let Type::Enum(enum_ty) = scrutinee_ty {
+                    if let Some(enum_ty) = self.enum_instance(scrutinee_ty) {
                         for var in &enum_ty.variants {
                             if var.name == first.symbol {
                                 // Bind variant fields if present
@@ -1768,7 +2550,16 @@ impl<'a> TypeInferrer<'a> {
                 if let Some(def) = self.symbols.get_type(first.symbol) {
                     use super::symbols::TypeDef;
                     if let TypeDef::Enum(e) = def {
-                        let enum_ty = self.symbols.to_enum_type(e);
+                        // Take the field types of a generic enum from the scrutinee
+                        let (enum_ty, pattern_ty) = match self.enum_instance(scrutinee_ty) {
+                            Some(instance) if instance.name == e.name => {
+                                (instance, scrutinee_ty.clone())
+                            }
+                            _ => {
+                                let enum_ty = self.symbols.to_enum_type(e);
+                                (enum_ty.clone(), Type::Enum(enum_ty))
+                            }
+                        };
                         let variant_name = variant.path.last().unwrap().symbol;
 
                         for var in &enum_ty.variants {
@@ -1785,7 +2576,7 @@ impl<'a> TypeInferrer<'a> {
                                         }
                                     }
                                 }
-                                return Type::Enum(enum_ty);
+                                return pattern_ty;
                             }
                         }
                     }
@@ -1858,40 +2649,40 @@ impl<'a> TypeInferrer<'a> {
                     self.env.define(var.name.symbol, ty, var.mutable);
                 } else {
                     // Original logic for normal var statements
-                    let ty = if let Some(annot) = &var.ty {
-                        self.convert_ast_type(annot)
-                    } else if let Some(init) = &var.init {
-                        self.infer_expr(init)
-                    } else {
-                        fresh_type_var(self.next_var_id)
-                    };
-
-                    if let Some(init) = &var.init {
-                        let init_ty = self.infer_expr(init);
-                        // Allow int literals to be assigned to uint (non-negative int → uint coercion)
-                        let should_unify = !self.is_int_to_uint_coercion(&init_ty, &ty, init);
-                        if should_unify && let Err(e) = unify(&init_ty, &ty, init.span()) {
-                            self.errors.push(e);
+                    let annotated = var.ty.as_ref().map(|annot| self.convert_ast_type(annot));
+                    let ty = match (&var.init, annotated) {
+                        (Some(init), Some(ty)) => {
+                            let init_ty = self.infer_expr_with(init, Some(&ty));
+                            // Allow int literals to be assigned to uint (non-negative int → uint coercion)
+                            let should_unify = !self.is_int_to_uint_coercion(&init_ty, &ty, init);
+                            if should_unify && let Err(e) = unify(&init_ty, &ty, init.span()) {
+                                self.errors.push(e);
+                            }
+                            ty
                         }
-                    }
+                        (Some(init), None) => self.infer_expr(init),
+                        (None, Some(ty)) => ty,
+                        (None, None) => fresh_type_var(self.next_var_id),
+                    };
 
+                    self.check_float_map_key(&ty, var.name.span);
                     self.env.define(var.name.symbol, ty, var.mutable);
                 }
             }
             Const(c) => {
                 let ty = if let Some(annot) = &c.ty {
-                    self.convert_ast_type(annot)
+                    let ty = self.convert_ast_type(annot);
+                    let init_ty = self.infer_expr_with(&c.init, Some(&ty));
+                    // Allow int literals to be assigned to uint (non-negative int → uint coercion)
+                    let should_unify = !self.is_int_to_uint_coercion(&init_ty, &ty, &c.init);
+                    if should_unify && let Err(e) = unify(&init_ty, &ty, c.init.span()) {
+                        self.errors.push(e);
+                    }
+                    ty
                 } else {
                     self.infer_expr(&c.init)
                 };
 
-                let init_ty = self.infer_expr(&c.init);
-                // Allow int literals to be assigned to uint (non-negative int → uint coercion)
-                let should_unify = !self.is_int_to_uint_coercion(&init_ty, &ty, &c.init);
-                if should_unify && let Err(e) = unify(&init_ty, &ty, c.init.span()) {
-                    self.errors.push(e);
-                }
-
                 self.env.define(c.name.symbol, ty, false);
             }
             Assign(assign) => {
@@ -1906,7 +2697,7 @@ impl<'a> TypeInferrer<'a> {
                         if let Err(e) = unify(&index_ty, &key, idx.index.span()) {
                             self.errors.push(e);
                         }
-                        let value_ty = self.infer_expr(&assign.value);
+                        let value_ty = self.infer_expr_with(&assign.value, Some(&val));
                         if let Err(e) = unify(&value_ty, &val, assign.value.span()) {
                             self.errors.push(e);
                         }
@@ -1917,7 +2708,7 @@ impl<'a> TypeInferrer<'a> {
                         if let Err(e) = unify(&index_ty, &Type::Int, idx.index.span()) {
                             self.errors.push(e);
                         }
-                        let value_ty = self.infer_expr(&assign.value);
+                        let value_ty = self.infer_expr_with(&assign.value, Some(&elem));
                         if let Err(e) = unify(&value_ty, &elem, assign.value.span()) {
                             self.errors.push(e);
                         }
@@ -1926,7 +2717,7 @@ impl<'a> TypeInferrer<'a> {
                 }
 
                 let target_ty = self.infer_expr(&assign.target);
-                let value_ty = self.infer_expr(&assign.value);
+                let value_ty = self.infer_expr_with(&assign.value, Some(&target_ty));
                 if let Err(e) = unify(&value_ty, &target_ty, assign.span) {
                     self.errors.push(e);
                 }
@@ -1936,8 +2727,9 @@ impl<'a> TypeInferrer<'a> {
             }
             Return(ret) => {
                 if let Some(value) = &ret.value {
-                    let ret_ty = self.infer_expr(value);
-                    if let Some(expected) = self.env.expected_return_type()
+                    let expected = self.env.expected_return_type().cloned();
+                    let ret_ty = self.infer_expr_with(value, expected.as_ref());
+                    if let Some(expected) = &expected
                         && let Err(e) = unify(&ret_ty, expected, value.span())
                     {
                         self.errors.push(e);
@@ -1946,6 +2738,18 @@ impl<'a> TypeInferrer<'a> {
             }
             Throw(throw) => {
                 self.infer_expr(&throw.value);
+                if let Some(cause) = &throw.cause {
+                    let cause_ty = self.infer_expr(cause).resolve();
+                    if !matches!(cause_ty, Type::Exception(_) | Type::Error) {
+                        self.errors.push(TypeError::Custom {
+                            message: format!(
+                                "caused_by expects an exception, found {}",
+                                self.display_type(&cause_ty)
+                            ),
+                            span: cause.span(),
+                        });
+                    }
+                }
             }
             If(if_stmt) => {
                 let cond_ty = self.infer_expr(&if_stmt.condition);
@@ -1995,6 +2799,7 @@ impl<'a> TypeInferrer<'a> {
                 let elem_ty = match &resolved {
                     Type::Array(elem) | Type::FixedArray(elem, _) => (**elem).clone(),
                     Type::Map(_, val) => (**val).clone(),
+                    Type::Iterator(elem) => (**elem).clone(),
                     Type::String => Type::String,
                     Type::Error => Type::Error,
                     _ => {
@@ -2149,9 +2954,30 @@ impl<'a> TypeInferrer<'a> {
                 if name == "stack_frame" {
                     return Type::StackFrame;
                 }
+                if name == "iface_info" {
+                    return Type::IfaceInfo;
+                }
+                if name == "fs_event" {
+                    return Type::FsEvent;
+                }
                 if name == "json" {
                     return Type::Json;
                 }
+                if name == "secret" {
+                    return Type::Secret;
+                }
+                if name == "money" {
+                    return Type::Money;
+                }
+                if name == "string_builder" {
+                    return Type::StringBuilder;
+                }
+                if name == "waitgroup" {
+                    return Type::Waitgroup;
+                }
+                if name == "atomic_int" {
+                    return Type::Atomic(Box::new(Type::Int));
+                }
 
                 // Look up the name to see if it's a known type (struct, enum, etc.)
                 if let Some(def) = self.symbols.get_type(ident.symbol) {
@@ -2172,6 +2998,16 @@ impl<'a> TypeInferrer<'a> {
                 let converted_args: Vec<Type> =
                     args.iter().map(|a| self.convert_ast_type(a)).collect();
 
+                if let [elem] = converted_args.as_slice() {
+                    match self.interner.resolve(&ident.symbol) {
+                        "set" => return Type::Set(Box::new(elem.clone())),
+                        "priority_queue" => return Type::PriorityQueue(Box::new(elem.clone())),
+                        "deque" => return Type::Deque(Box::new(elem.clone())),
+                        "iterator" => return Type::Iterator(Box::new(elem.clone())),
+                        _ => {}
+                    }
+                }
+
                 // Check if this is a type alias with type params
                 if let Some(def) = self.symbols.get_type(ident.symbol) {
                     use super::symbols::TypeDef;
@@ -2276,6 +3112,26 @@ impl<'a> TypeInferrer<'a> {
                 type_params,
                 type_args,
             ))),
+            Type::Set(inner) => Type::Set(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
+            Type::PriorityQueue(inner) => Type::PriorityQueue(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
+            Type::Deque(inner) => Type::Deque(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
+            Type::Iterator(inner) => Type::Iterator(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
             Type::Function(ft) => Type::Function(FunctionType {
                 params: ft
                     .params
@@ -2351,6 +3207,23 @@ impl<'a> TypeInferrer<'a> {
             span: crate::source::Span::dummy(),
             module: None,
             platforms,
+            attributes: vec![],
         })
     }
 }
+
+/// Parse a format spec and check that it can format a value of `ty`
+/// (unknown when `None`)
+fn check_format_spec(spec: &str, ty: Option<&Type>) -> Result<(), String> {
+    let parsed = FormatSpec::parse(spec).map_err(|e| format!("invalid format spec '{{:{}}}': {}", spec, e))?;
+    let fits = match ty {
+        None | Some(Type::Error | Type::Int | Type::Uint | Type::TypeVar(_) | Type::Generic(..)) => true,
+        Some(Type::Float) => !parsed.is_radix(),
+        Some(_) => !parsed.is_numeric(),
+    };
+    if fits {
+        return Ok(());
+    }
+    let needed = if parsed.is_radix() { "an int" } else { "a number" };
+    Err(format!("format spec '{{:{}}}' needs {}, found {}", spec, needed, ty.unwrap()))
+}
@@ -15,29 +15,37 @@
 //! Entry point: `check()` function takes an AST and returns errors
 //!
 
+pub mod attributes;
+pub mod consteval;
 pub mod env;
 pub mod error;
+pub mod explain;
 pub mod generics;
 pub mod infer;
+pub mod suggest;
 pub mod symbols;
 pub mod typed_ast;
 pub mod types;
 pub mod unify;
+pub mod unused;
 
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-use lasso::{Rodeo, Spur};
+use lasso::{Key, Rodeo, Spur};
 
 use crate::ast::{self, CompilationTarget, Item, Platform, SourceFile, UseItems};
-use crate::source::Span;
+use crate::source::{Span, Spanned};
 
-pub use error::{TypeError, TypeResult};
+pub use consteval::ConstValue;
+pub use error::{Fix, TypeError, TypeResult, TypeWarning};
 pub use symbols::SymbolTable;
-pub use typed_ast::TypeAnnotations;
+pub use typed_ast::{DefaultMethodInfo, TypeAnnotations};
 pub use types::Type;
 
 pub struct TypeCheckResult {
     pub errors: Vec<TypeError>,
+    pub warnings: Vec<TypeWarning>,
     pub annotations: TypeAnnotations,
     pub symbols: SymbolTable,
     pub imported_modules: Vec<ImportedModule>,
@@ -56,14 +64,33 @@ pub struct ImportedModule {
     pub file_path: PathBuf,
 }
 
+/// What a local module file makes available to the files importing it
+#[derive(Debug, Clone, Default)]
+struct ModuleExports {
+    functions: Vec<FunctionSig>,
+    types: Vec<(Spur, TypeDef)>,
+    /// Functions and types defined without `pub`
+    private: Vec<Spur>,
+    /// Re-exported functions whose `pub use` renamed them, with the
+    /// name they are defined under
+    aliases: Vec<(Spur, Spur)>,
+}
+
 pub struct TypeChecker<'a> {
     symbols: SymbolTable,
     env: TypeEnv,
     interner: &'a mut Rodeo,
     errors: Vec<TypeError>,
+    warnings: Vec<TypeWarning>,
     annotations: TypeAnnotations,
     next_var_id: u32,
     source_dir: Option<PathBuf>,
+    /// Directory of the file being compiled, where `crate::` paths start
+    crate_root: Option<PathBuf>,
+    /// Module files being loaded, innermost last, for cycle detection
+    module_stack: Vec<PathBuf>,
+    import_span: Span,
+    loaded_modules: HashMap<PathBuf, ModuleExports>,
     imported_modules: Vec<ImportedModule>,
     package_manager: Option<&'a naml_pkg::PackageManager>,
     target: CompilationTarget,
@@ -150,9 +177,14 @@ impl<'a> TypeChecker<'a> {
             env: TypeEnv::new(),
             interner,
             errors: Vec::new(),
+            warnings: Vec::new(),
             annotations: TypeAnnotations::new(),
             next_var_id: 0,
+            crate_root: source_dir.clone(),
             source_dir,
+            module_stack: Vec::new(),
+            import_span: Span::dummy(),
+            loaded_modules: HashMap::new(),
             imported_modules: Vec::new(),
             package_manager,
             target,
@@ -187,6 +219,7 @@ impl<'a> TypeChecker<'a> {
                     span: Span::dummy(),
                     module: None,
                     platforms: None,
+                    attributes: vec![],
                 });
             }
         }
@@ -199,7 +232,7 @@ impl<'a> TypeChecker<'a> {
         let code_name = self.interner.get_or_intern("code");
         self.interner.get_or_intern("stack");
         self.interner.get_or_intern("key");
-        self.interner.get_or_intern("position");
+        let position_name = self.interner.get_or_intern("position");
         self.interner.get_or_intern("timeout_ms");
         self.interner.get_or_intern("function");
         self.interner.get_or_intern("file");
@@ -225,7 +258,7 @@ impl<'a> TypeChecker<'a> {
             decode_error_name,
             TypeDef::Exception(ExceptionDef {
                 name: decode_error_name,
-                fields: vec![(msg_name, Type::String)],
+                fields: vec![(msg_name, Type::String), (position_name, Type::Int)],
                 is_public: true,
                 span: Span::dummy(),
             }),
@@ -247,7 +280,7 @@ impl<'a> TypeChecker<'a> {
             network_error_name,
             TypeDef::Exception(ExceptionDef {
                 name: network_error_name,
-                fields: vec![(msg_name, Type::String)],
+                fields: vec![(msg_name, Type::String), (code_name, Type::Int)],
                 is_public: true,
                 span: Span::dummy(),
             }),
@@ -360,7 +393,160 @@ impl<'a> TypeChecker<'a> {
             }),
         );
 
-        let tls_error_name = self.interner.get_or_intern("TlsError");
+        let security_error_name = self.interner.get_or_intern("SecurityError");
+        self.symbols.define_type(
+            security_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: security_error_name,
+                fields: vec![
+                    (msg_name, Type::String),
+                    (path_name, Type::String),
+                ],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        // Base type of every exception, seen through its message and stack:
+        // the type of `e.cause` and of a `catch (e: error)` binding
+        let exception_name = self.interner.get_or_intern("error");
+        self.symbols.define_type(
+            exception_name,
+            TypeDef::Exception(ExceptionDef {
+                name: exception_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let arithmetic_error_name = self.interner.get_or_intern("ArithmeticError");
+        self.symbols.define_type(
+            arithmetic_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: arithmetic_error_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let config_error_name = self.interner.get_or_intern("ConfigError");
+        self.symbols.define_type(
+            config_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: config_error_name,
+                fields: vec![
+                    (msg_name, Type::String),
+                    (key_name, Type::String),
+                ],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let invalid_transition_name = self.interner.get_or_intern("InvalidTransition");
+        let state_name = self.interner.get_or_intern("state");
+        let event_name = self.interner.get_or_intern("event");
+        self.symbols.define_type(
+            invalid_transition_name,
+            TypeDef::Exception(ExceptionDef {
+                name: invalid_transition_name,
+                fields: vec![
+                    (msg_name, Type::String),
+                    (state_name, Type::String),
+                    (event_name, Type::String),
+                ],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let validation_error_name = self.interner.get_or_intern("ValidationError");
+        let field_name = self.interner.get_or_intern("field");
+        let rule_name = self.interner.get_or_intern("rule");
+        self.symbols.define_type(
+            validation_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: validation_error_name,
+                fields: vec![
+                    (msg_name, Type::String),
+                    (field_name, Type::String),
+                    (rule_name, Type::String),
+                ],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let money_error_name = self.interner.get_or_intern("MoneyError");
+        self.symbols.define_type(
+            money_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: money_error_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let regex_error_name = self.interner.get_or_intern("RegexError");
+        let pattern_name = self.interner.get_or_intern("pattern");
+        self.symbols.define_type(
+            regex_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: regex_error_name,
+                fields: vec![(msg_name, Type::String), (pattern_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let image_error_name = self.interner.get_or_intern("ImageError");
+        self.symbols.define_type(
+            image_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: image_error_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let pdf_error_name = self.interner.get_or_intern("PdfError");
+        self.symbols.define_type(
+            pdf_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: pdf_error_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let xlsx_error_name = self.interner.get_or_intern("XlsxError");
+        self.symbols.define_type(
+            xlsx_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: xlsx_error_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+        let markdown_error_name = self.interner.get_or_intern("MarkdownError");
+        self.symbols.define_type(
+            markdown_error_name,
+            TypeDef::Exception(ExceptionDef {
+                name: markdown_error_name,
+                fields: vec![(msg_name, Type::String)],
+                is_public: true,
+                span: Span::dummy(),
+            }),
+        );
+
+                let tls_error_name = self.interner.get_or_intern("TlsError");
         self.symbols.define_type(
             tls_error_name,
             TypeDef::Exception(ExceptionDef {
@@ -383,27 +569,44 @@ impl<'a> TypeChecker<'a> {
             "random",
             "io",
             "threads",
+            "threads::actors",
             "datetime",
             "metrics",
+            "runtime",
             "strings",
+            "strings::builder",
             "collections",
             "collections::arrays",
             "collections::maps",
+            "collections::sets",
+            "collections::priority_queues",
+            "collections::deques",
+            "collections::iterators",
             "fs",
             "path",
             "encoding",
             "encoding::utf8",
             "encoding::hex",
             "encoding::base64",
+            "encoding::base32",
+            "encoding::base58",
+            "encoding::punycode",
             "encoding::url",
             "encoding::json",
             "encoding::toml",
             "encoding::yaml",
+            "encoding::csv",
+            "encoding::msgpack",
+            "encoding::cbor",
             "encoding::binary",
             "testing",
             "env",
             "os",
+            "os::keyring",
+            "os::net",
             "process",
+            "process::pool",
+            "ipc",
             "net",
             "net::tcp",
             "net::tcp::server",
@@ -414,10 +617,29 @@ impl<'a> TypeChecker<'a> {
             "net::http::server",
             "net::http::middleware",
             "net::tls",
+            "net::s3",
+            "cloud",
+            "cloud::metadata",
             "timers",
+            "config",
+            "flags",
+            "fsm",
+            "queue",
+            "compress",
+            "validate",
+            "money",
+            "geo",
+            "regex",
+            "image",
             "db",
             "db::sqlite",
+            "doc",
+            "doc::pdf",
+            "doc::xlsx",
+            "text",
+            "text::markdown",
             "crypto",
+            "crypto::otp",
         ];
 
         for module in modules {
@@ -448,9 +670,31 @@ impl<'a> TypeChecker<'a> {
         self.collect_definitions(file);
         self.validate_interface_implementations();
         self.check_items(file);
+        self.evaluate_constants(file);
+        self.retain_local_notes(file);
         std::mem::take(&mut self.errors)
     }
 
+    /// Drop definition notes pointing into imported module files. Their
+    /// spans index a different source, so they would label the wrong text.
+    fn retain_local_notes(&mut self, file: &SourceFile) {
+        fn collect(items: &[Item], spans: &mut HashSet<Span>) {
+            for item in items {
+                spans.insert(item.span());
+                if let Item::Mod(module) = item
+                    && let Some(body) = &module.body
+                {
+                    collect(body, spans);
+                }
+            }
+        }
+        let mut local = HashSet::new();
+        collect(&file.items, &mut local);
+        for err in &mut self.errors {
+            err.retain_notes(|span| local.contains(&span));
+        }
+    }
+
     fn validate_interface_implementations(&mut self) {
         let structs: Vec<_> = self
             .symbols
@@ -465,39 +709,95 @@ impl<'a> TypeChecker<'a> {
             .collect();
 
         for struct_def in structs {
+            let struct_name = self.interner.resolve(&struct_def.name).to_string();
+            let mut methods = Vec::new();
             for impl_ty in &struct_def.implements {
-                let interface_name = match impl_ty {
-                    Type::Generic(name, _) => *name,
-                    Type::Interface(i) => i.name,
-                    _ => continue,
-                };
-
-                let interface_def = match self.symbols.get_type(interface_name) {
-                    Some(TypeDef::Interface(i)) => i.clone(),
-                    _ => continue,
-                };
+                match impl_ty {
+                    Type::Generic(name, _) => methods.extend(self.interface_methods(*name)),
+                    Type::Interface(i) => methods.extend(self.interface_methods(i.name)),
+                    _ => {}
+                }
+            }
 
-                for required_method in &interface_def.methods {
-                    let method_name_str = self.interner.resolve(&required_method.name);
-                    let has_method = self
-                        .symbols
-                        .get_method(struct_def.name, required_method.name);
-
-                    if has_method.is_none() {
-                        let struct_name = self.interner.resolve(&struct_def.name).to_string();
-                        let interface_name_str = self.interner.resolve(&interface_name).to_string();
-                        self.errors.push(TypeError::MissingInterfaceMethod {
-                            struct_name,
-                            interface_name: interface_name_str,
-                            method_name: method_name_str.to_string(),
+            // Resolution order: the struct's own method wins, otherwise it
+            // inherits the interface default; two different defaults conflict
+            let mut inherited: HashMap<Spur, Spur> = HashMap::new();
+            for (owner, method) in methods.iter().filter(|(_, m)| m.has_default && !m.is_static) {
+                if let Some(&first) = inherited.get(&method.name) {
+                    if first != *owner {
+                        self.errors.push(TypeError::ConflictingDefaultMethod {
+                            struct_name: struct_name.clone(),
+                            method_name: self.interner.resolve(&method.name).to_string(),
+                            first: self.interner.resolve(&first).to_string(),
+                            second: self.interner.resolve(owner).to_string(),
                             span: struct_def.span,
                         });
                     }
+                    continue;
+                }
+                if self.symbols.get_method(struct_def.name, method.name).is_some() {
+                    continue;
+                }
+                inherited.insert(method.name, *owner);
+                self.symbols.define_method(
+                    struct_def.name,
+                    MethodSig {
+                        name: method.name,
+                        receiver_ty: Type::Struct(self.symbols.to_struct_type(&struct_def)),
+                        type_params: method.type_params.clone(),
+                        params: method.params.clone(),
+                        return_ty: method.return_ty.clone(),
+                        throws: method.throws.clone(),
+                        is_public: true,
+                        span: method.span,
+                        attributes: vec![],
+                    },
+                );
+                self.annotations.record_default_method(DefaultMethodInfo {
+                    struct_name: struct_def.name,
+                    interface_name: *owner,
+                    method_name: method.name,
+                });
+            }
+
+            for (owner, method) in methods.iter().filter(|(_, m)| !m.has_default && !m.is_static) {
+                if self.symbols.get_method(struct_def.name, method.name).is_none() {
+                    self.errors.push(TypeError::MissingInterfaceMethod {
+                        struct_name: struct_name.clone(),
+                        interface_name: self.interner.resolve(owner).to_string(),
+                        method_name: self.interner.resolve(&method.name).to_string(),
+                        span: struct_def.span,
+                    });
                 }
             }
         }
     }
 
+    /// Methods of an interface followed by those of the interfaces it
+    /// extends, each paired with the interface declaring it
+    fn interface_methods(&self, interface_name: Spur) -> Vec<(Spur, InterfaceMethodDef)> {
+        let mut methods = Vec::new();
+        let mut pending = vec![interface_name];
+        let mut seen = HashSet::new();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name) {
+                continue;
+            }
+            let Some(TypeDef::Interface(interface)) = self.symbols.get_type(name) else {
+                continue;
+            };
+            methods.extend(interface.methods.iter().map(|m| (name, m.clone())));
+            for parent in interface.extends.iter().rev() {
+                match parent {
+                    Type::Generic(parent, _) => pending.push(*parent),
+                    Type::Interface(i) => pending.push(i.name),
+                    _ => {}
+                }
+            }
+        }
+        methods
+    }
+
     fn collect_definitions(&mut self, file: &SourceFile) {
         for item in &file.items {
             self.collect_item_definition(item);
@@ -619,88 +919,19 @@ impl<'a> TypeChecker<'a> {
             return;
         }
 
-        let mut functions_to_import = Vec::new();
-        let mut types_to_import = Vec::new();
-        let mut submodules_to_import = Vec::new();
-        let mut import_errors = Vec::new();
-        let mut resolved_module_found = false;
-
-        {
-            // Immutable borrow scope
-            let mut curr_module = &self.symbols.root;
-            let mut resolved = true;
-
-            for &seg in &path_spurs {
-                if let Some(sub) = curr_module.get_submodule(seg) {
-                    curr_module = sub;
-                } else {
-                    resolved = false;
-                    break;
-                }
-            }
-
-            if resolved {
-                resolved_module_found = true;
-                match &use_item.items {
-                    UseItems::All => {
-                        for sig in curr_module.all_functions() {
-                            functions_to_import.push(sig.clone());
-                        }
-                        for (name, def) in curr_module.all_types() {
-                            types_to_import.push((*name, def.clone()));
-                        }
-                        for (name, sub) in curr_module.all_submodules() {
-                            submodules_to_import.push((*name, sub.clone()));
-                        }
-                    }
-                    UseItems::Specific(entries) => {
-                        for entry in entries {
-                            let name = entry.name.symbol;
-                            let import_name =
-                                entry.alias.as_ref().map(|a| a.symbol).unwrap_or(name);
-                            let mut found = false;
-
-                            if let Some(sig) = curr_module.get_function(name) {
-                                let mut sig = sig.clone();
-                                sig.name = import_name;
-                                functions_to_import.push(sig);
-                                found = true;
-                            }
-                            if let Some(def) = curr_module.get_type(name) {
-                                types_to_import.push((import_name, def.clone()));
-                                found = true;
-                            }
-
-                            if !found {
-                                let name_str = self.interner.resolve(&name).to_string();
-                                let module_name =
-                                    self.interner.resolve(&curr_module.name).to_string();
-                                import_errors.push(TypeError::UnknownModuleSymbol {
-                                    module: module_name,
-                                    symbol: name_str,
-                                    span: entry.span,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
+        // Modules that are already known: std and those imported before
+        let mut known_module = Some(&self.symbols.root);
+        for &seg in &path_spurs {
+            known_module = known_module.and_then(|m| m.get_submodule(seg));
         }
 
-        if resolved_module_found {
-            // Perform the actual imports
-            for sig in functions_to_import {
-                self.symbols.import_function(sig);
-            }
-            for (name, def) in types_to_import {
-                self.symbols.define_type(name, def);
-            }
-            for (name, sub) in submodules_to_import {
-                self.symbols.define_module(name, sub);
-            }
-            for err in import_errors {
-                self.errors.push(err);
-            }
+        if let Some(module) = known_module.cloned() {
+            let module_name = path_spurs
+                .iter()
+                .map(|&s| self.interner.resolve(&s))
+                .collect::<Vec<_>>()
+                .join("::");
+            self.import_module_items(&module, &module_name, &use_item.items);
         } else if path_spurs[0] == self.interner.get_or_intern("std") {
             // Already tried pre-populated std, if not found then it's an error
             let path_str = path_spurs
@@ -734,6 +965,123 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Bring the items a `use` selects from a module into scope
+    fn import_module_items(
+        &mut self,
+        module: &symbols::ModuleNamespace,
+        module_name: &str,
+        items: &UseItems,
+    ) {
+        match items {
+            UseItems::All { except } => {
+                for name in except {
+                    if module.get_function(name.symbol).is_none()
+                        && module.get_type(name.symbol).is_none()
+                    {
+                        self.unknown_module_symbol(module, module_name, name.symbol, name.span);
+                    }
+                }
+                let excluded = |name: Spur| except.iter().any(|e| e.symbol == name);
+                for sig in module.all_functions() {
+                    if !excluded(sig.name) {
+                        self.symbols.import_glob_function(sig.clone());
+                        if let Some(&original) = module.aliases.get(&sig.name) {
+                            self.symbols.define_alias(sig.name, original);
+                        }
+                    }
+                }
+                for (name, def) in module.all_types() {
+                    if !excluded(*name) {
+                        self.symbols.define_type(*name, def.clone());
+                    }
+                }
+                for (name, sub) in module.all_submodules() {
+                    if !excluded(*name) {
+                        self.symbols.define_module(*name, sub.clone());
+                    }
+                }
+            }
+            UseItems::Specific(entries) => {
+                for entry in entries {
+                    let name = entry.name.symbol;
+                    let import_name = entry.alias.as_ref().map(|a| a.symbol).unwrap_or(name);
+                    let span = self.import_error_span(entry.span);
+                    let mut found = false;
+
+                    if let Some(sig) = module.get_function(name) {
+                        let second = sig.module.clone().unwrap_or_else(|| module_name.to_string());
+                        let sig = FunctionSig {
+                            name: import_name,
+                            ..sig.clone()
+                        };
+                        let original = module.aliases.get(&name).copied().unwrap_or(name);
+                        match self.symbols.import_function(sig) {
+                            Ok(()) if import_name != original => {
+                                self.symbols.define_alias(import_name, original)
+                            }
+                            Ok(()) => {}
+                            Err(first) => self.errors.push(TypeError::DuplicateImport {
+                                name: self.interner.resolve(&import_name).to_string(),
+                                first,
+                                second,
+                                span,
+                            }),
+                        }
+                        found = true;
+                    }
+                    if let Some(def) = module.get_type(name) {
+                        self.symbols.define_type(import_name, def.clone());
+                        found = true;
+                    }
+
+                    if !found && module.is_private(name) {
+                        self.errors.push(TypeError::PrivateSymbol {
+                            module: module_name.to_string(),
+                            symbol: self.interner.resolve(&name).to_string(),
+                            span,
+                        });
+                    } else if !found {
+                        self.unknown_module_symbol(module, module_name, name, entry.span);
+                    }
+                }
+            }
+        }
+    }
+
+    fn unknown_module_symbol(
+        &mut self,
+        module: &symbols::ModuleNamespace,
+        module_name: &str,
+        name: Spur,
+        span: Span,
+    ) {
+        let name_str = self.interner.resolve(&name).to_string();
+        let exports = module
+            .all_functions()
+            .map(|sig| sig.name)
+            .chain(module.all_types().map(|(spur, _)| *spur));
+        let suggestion = suggest::did_you_mean(
+            &name_str,
+            exports.map(|spur| self.interner.resolve(&spur)),
+        );
+        self.errors.push(TypeError::UnknownModuleSymbol {
+            module: module_name.to_string(),
+            symbol: name_str,
+            span: self.import_error_span(span),
+            suggestion,
+        });
+    }
+
+    /// Errors in modules imported by other modules are reported at the
+    /// import in the file being compiled, whose source is the one shown
+    fn import_error_span(&self, span: Span) -> Span {
+        if self.module_stack.is_empty() {
+            span
+        } else {
+            self.import_span
+        }
+    }
+
     fn create_std_fn_sig(
         &mut self,
         module_fn: &StdModuleFn,
@@ -799,17 +1147,18 @@ impl<'a> TypeChecker<'a> {
             span: Span::dummy(),
             module,
             platforms,
+            attributes: vec![],
         })
     }
 
-    /// Recursively fix Type::Generic with default spur to use the first type parameter
+    /// Recursively replace placeholder spurs in Type::Generic with the type
+    /// parameter at that position: the default spur is the first parameter,
+    /// `Spur::try_from_usize(1)` the second
     fn fix_default_generic_spur(ty: &mut Type, type_params: &[TypeParam]) {
         match ty {
             Type::Generic(g_spur, _) => {
-                if *g_spur == lasso::Spur::default() {
-                    if let Some(tp) = type_params.first() {
-                        *g_spur = tp.name;
-                    }
+                if let Some(tp) = type_params.get(g_spur.into_usize()) {
+                    *g_spur = tp.name;
                 }
             }
             Type::Channel(inner) => Self::fix_default_generic_spur(inner, type_params),
@@ -818,19 +1167,51 @@ impl<'a> TypeChecker<'a> {
             Type::Mutex(inner) => Self::fix_default_generic_spur(inner, type_params),
             Type::Rwlock(inner) => Self::fix_default_generic_spur(inner, type_params),
             Type::Atomic(inner) => Self::fix_default_generic_spur(inner, type_params),
+            Type::Set(inner) => Self::fix_default_generic_spur(inner, type_params),
+            Type::PriorityQueue(inner) => Self::fix_default_generic_spur(inner, type_params),
+            Type::Deque(inner) => Self::fix_default_generic_spur(inner, type_params),
+            Type::Iterator(inner) => Self::fix_default_generic_spur(inner, type_params),
             Type::Map(k, v) => {
                 Self::fix_default_generic_spur(k, type_params);
                 Self::fix_default_generic_spur(v, type_params);
             }
+            Type::Function(func) => {
+                for param in &mut func.params {
+                    Self::fix_default_generic_spur(param, type_params);
+                }
+                Self::fix_default_generic_spur(&mut func.returns, type_params);
+            }
             _ => {}
         }
     }
 
+    fn get_strings_builder_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new("sb_new", vec![], Type::StringBuilder, platforms),
+            StdModuleFn::new(
+                "sb_append",
+                vec![("sb", Type::StringBuilder), ("s", Type::String)],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::new(
+                "sb_append_int",
+                vec![("sb", Type::StringBuilder), ("n", Type::Int)],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::new("sb_len", vec![("sb", Type::StringBuilder)], Type::Int, platforms),
+            StdModuleFn::new("sb_to_string", vec![("sb", Type::StringBuilder)], Type::String, platforms),
+        ]
+    }
+
     fn get_collections_array_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         // Use a default Spur for generic type T
         let generic_t = || Type::Generic(lasso::Spur::default(), vec![]);
         let array_of_t = || Type::Array(Box::new(generic_t()));
         let option_of_t = || Type::Option(Box::new(generic_t()));
+        let generic_k = || Type::Generic(Spur::try_from_usize(1).unwrap(), vec![]);
+        const NATIVE_ONLY: &[Platform] = &[Platform::Native];
 
         vec![
             // Basic functions (Go-style) - generic over element type T
@@ -848,6 +1229,35 @@ impl<'a> TypeChecker<'a> {
                 array_of_t(),
                 platforms,
             ),
+            // Capacity management
+            StdModuleFn::generic(
+                "capacity",
+                vec!["T"],
+                vec![("arr", array_of_t())],
+                Type::Int,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "reserve",
+                vec!["T"],
+                vec![("arr", array_of_t()), ("additional", Type::Int)],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "shrink_to_fit",
+                vec!["T"],
+                vec![("arr", array_of_t())],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "extend",
+                vec!["T"],
+                vec![("arr", array_of_t()), ("other", array_of_t())],
+                Type::Unit,
+                platforms,
+            ),
             StdModuleFn::generic(
                 "push",
                 vec!["T"],
@@ -905,23 +1315,22 @@ impl<'a> TypeChecker<'a> {
                 option_of_t(),
                 platforms,
             ),
-            // Aggregation - these only make sense for numeric types, keep as int for now
-            StdModuleFn::new(
-                "sum",
-                vec![("arr", Type::Array(Box::new(Type::Int)))],
-                Type::Int,
-                platforms,
-            ),
+            // Aggregation - T must be int or float; the checker dispatches
+            // float arrays to the *_float variants
+            StdModuleFn::generic("sum", vec!["T"], vec![("arr", array_of_t())], generic_t(), platforms),
+            StdModuleFn::generic("min", vec!["T"], vec![("arr", array_of_t())], option_of_t(), platforms),
+            StdModuleFn::generic("max", vec!["T"], vec![("arr", array_of_t())], option_of_t(), platforms),
+            StdModuleFn::new("sum_float", vec![("arr", Type::array(Type::Float))], Type::Float, platforms),
             StdModuleFn::new(
-                "min",
-                vec![("arr", Type::Array(Box::new(Type::Int)))],
-                Type::Option(Box::new(Type::Int)),
+                "min_float",
+                vec![("arr", Type::array(Type::Float))],
+                Type::Option(Box::new(Type::Float)),
                 platforms,
             ),
             StdModuleFn::new(
-                "max",
-                vec![("arr", Type::Array(Box::new(Type::Int)))],
-                Type::Option(Box::new(Type::Int)),
+                "max_float",
+                vec![("arr", Type::array(Type::Float))],
+                Type::Option(Box::new(Type::Float)),
                 platforms,
             ),
             // Transformation - generic
@@ -1116,27 +1525,47 @@ impl<'a> TypeChecker<'a> {
                 Type::Array(Box::new(Type::Int)),
                 platforms,
             ),
+            StdModuleFn::generic("sort", vec!["T"], vec![("arr", array_of_t())], array_of_t(), platforms),
             StdModuleFn::new(
-                "sort",
-                vec![("arr", Type::Array(Box::new(Type::Int)))],
-                Type::Array(Box::new(Type::Int)),
+                "sort_float",
+                vec![("arr", Type::array(Type::Float))],
+                Type::array(Type::Float),
                 platforms,
             ),
-            StdModuleFn::new(
+            StdModuleFn::generic(
                 "sort_by",
+                vec!["T"],
                 vec![
-                    ("arr", Type::Array(Box::new(Type::Int))),
+                    ("arr", array_of_t()),
                     (
                         "comparator",
                         Type::Function(types::FunctionType {
-                            params: vec![Type::Int, Type::Int],
+                            params: vec![generic_t(), generic_t()],
                             returns: Box::new(Type::Int),
                             throws: vec![],
                             is_variadic: false,
                         }),
                     ),
                 ],
-                Type::Array(Box::new(Type::Int)),
+                array_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "group_by",
+                vec!["T", "K"],
+                vec![
+                    ("arr", array_of_t()),
+                    (
+                        "key_fn",
+                        Type::Function(types::FunctionType {
+                            params: vec![generic_t()],
+                            returns: Box::new(generic_k()),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Map(Box::new(generic_k()), Box::new(array_of_t())),
                 platforms,
             ),
             // Mutation operations
@@ -1179,10 +1608,11 @@ impl<'a> TypeChecker<'a> {
                 platforms,
             ),
             // Deduplication
-            StdModuleFn::new(
+            StdModuleFn::generic(
                 "unique",
-                vec![("arr", Type::Array(Box::new(Type::Int)))],
-                Type::Array(Box::new(Type::Int)),
+                vec!["T"],
+                vec![("arr", array_of_t())],
+                array_of_t(),
                 platforms,
             ),
             StdModuleFn::new(
@@ -1191,6 +1621,46 @@ impl<'a> TypeChecker<'a> {
                 Type::Array(Box::new(Type::Int)),
                 platforms,
             ),
+            // Sorted search
+            StdModuleFn::generic(
+                "binary_search",
+                vec!["T"],
+                vec![("arr", array_of_t()), ("val", generic_t())],
+                Type::Option(Box::new(Type::Int)),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "lower_bound",
+                vec!["T"],
+                vec![("arr", array_of_t()), ("val", generic_t())],
+                Type::Int,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "upper_bound",
+                vec!["T"],
+                vec![("arr", array_of_t()), ("val", generic_t())],
+                Type::Int,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "partition_point",
+                vec!["T"],
+                vec![
+                    ("arr", array_of_t()),
+                    (
+                        "predicate",
+                        Type::Function(types::FunctionType {
+                            params: vec![generic_t()],
+                            returns: Box::new(Type::Bool),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Int,
+                platforms,
+            ),
             // Backward search
             StdModuleFn::new(
                 "last_index_of",
@@ -1245,13 +1715,11 @@ impl<'a> TypeChecker<'a> {
                 Type::Array(Box::new(Type::Int)),
                 platforms,
             ),
-            StdModuleFn::new(
+            StdModuleFn::generic(
                 "zip",
-                vec![
-                    ("arr1", Type::Array(Box::new(Type::Int))),
-                    ("arr2", Type::Array(Box::new(Type::Int))),
-                ],
-                Type::Array(Box::new(Type::Array(Box::new(Type::Int)))),
+                vec!["T"],
+                vec![("arr1", array_of_t()), ("arr2", array_of_t())],
+                Type::Array(Box::new(array_of_t())),
                 platforms,
             ),
             StdModuleFn::new(
@@ -1264,13 +1732,18 @@ impl<'a> TypeChecker<'a> {
                 platforms,
             ),
             // Splitting
-            StdModuleFn::new(
+            StdModuleFn::generic(
                 "chunk",
-                vec![
-                    ("arr", Type::Array(Box::new(Type::Int))),
-                    ("size", Type::Int),
-                ],
-                Type::Array(Box::new(Type::Array(Box::new(Type::Int)))),
+                vec!["T"],
+                vec![("arr", array_of_t()), ("size", Type::Int)],
+                Type::Array(Box::new(array_of_t())),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "windows",
+                vec!["T"],
+                vec![("arr", array_of_t()), ("size", Type::Int)],
+                Type::Array(Box::new(array_of_t())),
                 platforms,
             ),
             StdModuleFn::new(
@@ -1405,6 +1878,59 @@ impl<'a> TypeChecker<'a> {
                 Type::Array(Box::new(Type::Int)),
                 platforms,
             ),
+            // Parallel over the scheduler's workers
+            StdModuleFn::new(
+                "par_apply",
+                vec![
+                    ("arr", Type::Array(Box::new(Type::Int))),
+                    (
+                        "mapper",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::Int],
+                            returns: Box::new(Type::Int),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Array(Box::new(Type::Int)),
+                NATIVE_ONLY,
+            ),
+            StdModuleFn::new(
+                "par_where",
+                vec![
+                    ("arr", Type::Array(Box::new(Type::Int))),
+                    (
+                        "predicate",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::Int],
+                            returns: Box::new(Type::Bool),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Array(Box::new(Type::Int)),
+                NATIVE_ONLY,
+            ),
+            StdModuleFn::new(
+                "par_fold",
+                vec![
+                    ("arr", Type::Array(Box::new(Type::Int))),
+                    ("initial", Type::Int),
+                    (
+                        "reducer",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::Int, Type::Int],
+                            returns: Box::new(Type::Int),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Int,
+                NATIVE_ONLY,
+            ),
             // Random
             StdModuleFn::new(
                 "shuffle",
@@ -1683,6 +2209,193 @@ impl<'a> TypeChecker<'a> {
         ]
     }
 
+    fn get_collections_set_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let generic_t = || Type::Generic(lasso::Spur::default(), vec![]);
+        let set_of_t = || Type::Set(Box::new(generic_t()));
+
+        vec![
+            StdModuleFn::generic("set_new", vec!["T"], vec![], set_of_t(), platforms),
+            StdModuleFn::generic(
+                "set_add",
+                vec!["T"],
+                vec![("s", set_of_t()), ("value", generic_t())],
+                Type::Bool,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "set_contains",
+                vec!["T"],
+                vec![("s", set_of_t()), ("value", generic_t())],
+                Type::Bool,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "set_remove",
+                vec!["T"],
+                vec![("s", set_of_t()), ("value", generic_t())],
+                Type::Bool,
+                platforms,
+            ),
+            StdModuleFn::generic("set_len", vec!["T"], vec![("s", set_of_t())], Type::Int, platforms),
+            StdModuleFn::generic(
+                "set_union",
+                vec!["T"],
+                vec![("a", set_of_t()), ("b", set_of_t())],
+                set_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "set_intersection",
+                vec!["T"],
+                vec![("a", set_of_t()), ("b", set_of_t())],
+                set_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "set_difference",
+                vec!["T"],
+                vec![("a", set_of_t()), ("b", set_of_t())],
+                set_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "set_to_array",
+                vec!["T"],
+                vec![("s", set_of_t())],
+                Type::Array(Box::new(generic_t())),
+                platforms,
+            ),
+        ]
+    }
+
+    fn get_collections_priority_queue_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let generic_t = || Type::Generic(lasso::Spur::default(), vec![]);
+        let pq_of_t = || Type::PriorityQueue(Box::new(generic_t()));
+
+        vec![
+            StdModuleFn::generic("pq_new", vec!["T"], vec![], pq_of_t(), platforms),
+            StdModuleFn::generic(
+                "pq_push",
+                vec!["T"],
+                vec![("pq", pq_of_t()), ("value", generic_t())],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "pq_pop_min",
+                vec!["T"],
+                vec![("pq", pq_of_t())],
+                Type::Option(Box::new(generic_t())),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "pq_peek_min",
+                vec!["T"],
+                vec![("pq", pq_of_t())],
+                Type::Option(Box::new(generic_t())),
+                platforms,
+            ),
+            StdModuleFn::generic("pq_len", vec!["T"], vec![("pq", pq_of_t())], Type::Int, platforms),
+        ]
+    }
+
+    fn get_collections_deque_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let generic_t = || Type::Generic(lasso::Spur::default(), vec![]);
+        let deque_of_t = || Type::Deque(Box::new(generic_t()));
+
+        vec![
+            StdModuleFn::generic("deque_new", vec!["T"], vec![], deque_of_t(), platforms),
+            StdModuleFn::generic(
+                "push_front",
+                vec!["T"],
+                vec![("dq", deque_of_t()), ("value", generic_t())],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "push_back",
+                vec!["T"],
+                vec![("dq", deque_of_t()), ("value", generic_t())],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "pop_front",
+                vec!["T"],
+                vec![("dq", deque_of_t())],
+                Type::Option(Box::new(generic_t())),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "pop_back",
+                vec!["T"],
+                vec![("dq", deque_of_t())],
+                Type::Option(Box::new(generic_t())),
+                platforms,
+            ),
+            StdModuleFn::generic("deque_len", vec!["T"], vec![("dq", deque_of_t())], Type::Int, platforms),
+        ]
+    }
+
+    fn get_collections_iterator_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let generic_t = || Type::Generic(lasso::Spur::default(), vec![]);
+        let generic_u = || Type::Generic(Spur::try_from_usize(1).unwrap(), vec![]);
+        let iter_of_t = || Type::Iterator(Box::new(generic_t()));
+        let stage_fn = |returns: Type| {
+            Type::Function(types::FunctionType {
+                params: vec![generic_t()],
+                returns: Box::new(returns),
+                throws: vec![],
+                is_variadic: false,
+            })
+        };
+
+        vec![
+            StdModuleFn::generic(
+                "iter_from_array",
+                vec!["T"],
+                vec![("arr", Type::Array(Box::new(generic_t())))],
+                iter_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "iter_map",
+                vec!["T", "U"],
+                vec![("it", iter_of_t()), ("mapper", stage_fn(generic_u()))],
+                Type::Iterator(Box::new(generic_u())),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "iter_filter",
+                vec!["T"],
+                vec![("it", iter_of_t()), ("predicate", stage_fn(Type::Bool))],
+                iter_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "iter_take",
+                vec!["T"],
+                vec![("it", iter_of_t()), ("n", Type::Int)],
+                iter_of_t(),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "iter_next",
+                vec!["T"],
+                vec![("it", iter_of_t())],
+                Type::Option(Box::new(generic_t())),
+                platforms,
+            ),
+            StdModuleFn::generic(
+                "iter_collect",
+                vec!["T"],
+                vec![("it", iter_of_t())],
+                Type::Array(Box::new(generic_t())),
+                platforms,
+            ),
+        ]
+    }
+
     fn get_fs_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
             // File reading
@@ -1715,6 +2428,13 @@ impl<'a> TypeChecker<'a> {
                 vec!["IOError", "PermissionError"],
                 platforms,
             ),
+            StdModuleFn::throwing(
+                "write_atomic",
+                vec![("path", Type::String), ("content", Type::String)],
+                Type::Unit,
+                vec!["IOError", "PermissionError"],
+                platforms,
+            ),
             StdModuleFn::throwing(
                 "append",
                 vec![("path", Type::String), ("content", Type::String)],
@@ -1741,6 +2461,13 @@ impl<'a> TypeChecker<'a> {
                 vec!["IOError", "PermissionError"],
                 platforms,
             ),
+            StdModuleFn::throwing(
+                "glob",
+                vec![("pattern", Type::String)],
+                Type::Array(Box::new(Type::String)),
+                vec!["IOError"],
+                platforms,
+            ),
             StdModuleFn::throwing(
                 "mkdir",
                 vec![("path", Type::String)],
@@ -1811,24 +2538,146 @@ impl<'a> TypeChecker<'a> {
                 platforms,
             ),
             StdModuleFn::throwing(
-                "rename",
+                "copy_verified",
                 vec![("src", Type::String), ("dst", Type::String)],
                 Type::Unit,
                 vec!["IOError"],
                 platforms,
             ),
-            // Memory-mapped file operations
             StdModuleFn::throwing(
-                "mmap_open",
-                vec![("path", Type::String), ("writable", Type::Bool)],
-                Type::Int,
+                "verify_file",
+                vec![("path", Type::String), ("expected_sha256", Type::String)],
+                Type::Bool,
                 vec!["IOError"],
                 platforms,
             ),
+            // Recursive copy/move; options are an int handle (0 = defaults)
+            StdModuleFn::new("copy_options", vec![], Type::Int, platforms),
             StdModuleFn::throwing(
-                "mmap_len",
-                vec![("handle", Type::Int)],
-                Type::Int,
+                "copy_options_include",
+                vec![("opts", Type::Int), ("pattern", Type::String)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "copy_options_exclude",
+                vec![("opts", Type::Int), ("pattern", Type::String)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "copy_options_overwrite",
+                vec![("opts", Type::Int), ("policy", Type::String)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::new(
+                "copy_options_on_progress",
+                vec![
+                    ("opts", Type::Int),
+                    (
+                        "callback",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::Int, Type::Int],
+                            returns: Box::new(Type::Unit),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::new("copy_options_free", vec![("opts", Type::Int)], Type::Unit, platforms),
+            StdModuleFn::throwing(
+                "copy_tree",
+                vec![("src", Type::String), ("dst", Type::String), ("opts", Type::Int)],
+                Type::Unit,
+                vec!["IOError", "PermissionError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "move_tree",
+                vec![("src", Type::String), ("dst", Type::String), ("opts", Type::Int)],
+                Type::Unit,
+                vec!["IOError", "PermissionError"],
+                platforms,
+            ),
+            // Recursive directory walking; options are an int handle (0 = defaults)
+            StdModuleFn::new("walk_options", vec![], Type::Int, platforms),
+            StdModuleFn::throwing(
+                "walk_options_max_depth",
+                vec![("opts", Type::Int), ("depth", Type::Int)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "walk_options_follow_symlinks",
+                vec![("opts", Type::Int), ("follow", Type::Bool)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "walk_options_include",
+                vec![("opts", Type::Int), ("pattern", Type::String)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "walk_options_exclude",
+                vec![("opts", Type::Int), ("pattern", Type::String)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::new("walk_options_free", vec![("opts", Type::Int)], Type::Unit, platforms),
+            StdModuleFn::throwing(
+                "walk_dir",
+                vec![("path", Type::String), ("opts", Type::Int)],
+                Type::Array(Box::new(Type::String)),
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "walk_open",
+                vec![("path", Type::String), ("opts", Type::Int)],
+                Type::Int,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "walk_next",
+                vec![("handle", Type::Int)],
+                Type::Option(Box::new(Type::String)),
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::new("walk_close", vec![("handle", Type::Int)], Type::Unit, platforms),
+            StdModuleFn::throwing(
+                "rename",
+                vec![("src", Type::String), ("dst", Type::String)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            // Memory-mapped file operations
+            StdModuleFn::throwing(
+                "mmap_open",
+                vec![("path", Type::String), ("writable", Type::Bool)],
+                Type::Int,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "mmap_len",
+                vec![("handle", Type::Int)],
+                Type::Int,
                 vec!["IOError"],
                 platforms,
             ),
@@ -1943,6 +2792,48 @@ impl<'a> TypeChecker<'a> {
                 vec!["IOError"],
                 platforms,
             ),
+            StdModuleFn::throwing(
+                "file_fsync",
+                vec![("handle", Type::Int)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "file_fdatasync",
+                vec![("handle", Type::Int)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "lock_exclusive",
+                vec![("handle", Type::Int)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "lock_shared",
+                vec![("handle", Type::Int)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "try_lock",
+                vec![("handle", Type::Int)],
+                Type::Bool,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "unlock",
+                vec![("handle", Type::Int)],
+                Type::Unit,
+                vec!["IOError"],
+                platforms,
+            ),
             StdModuleFn::throwing(
                 "file_seek",
                 vec![
@@ -1999,6 +2890,44 @@ impl<'a> TypeChecker<'a> {
                 vec!["IOError", "PermissionError"],
                 platforms,
             ),
+            // Scoped temp entries, deleted once the closure returns
+            StdModuleFn::throwing(
+                "with_temp_file",
+                vec![
+                    ("prefix", Type::String),
+                    (
+                        "body",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::String],
+                            returns: Box::new(Type::Unit),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Unit,
+                vec!["IOError", "PermissionError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "with_temp_dir",
+                vec![
+                    ("prefix", Type::String),
+                    (
+                        "body",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::String],
+                            returns: Box::new(Type::Unit),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Unit,
+                vec!["IOError", "PermissionError"],
+                platforms,
+            ),
+            StdModuleFn::new("temp_file_keep", vec![("path", Type::String)], Type::Unit, platforms),
             // Permission and size operations
             StdModuleFn::throwing(
                 "chmod",
@@ -2095,6 +3024,35 @@ impl<'a> TypeChecker<'a> {
                 vec!["IOError"],
                 platforms,
             ),
+            // Disk space and filesystem info
+            StdModuleFn::throwing(
+                "disk_free",
+                vec![("path", Type::String)],
+                Type::Int,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "disk_total",
+                vec![("path", Type::String)],
+                Type::Int,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "fs_type",
+                vec![("path", Type::String)],
+                Type::String,
+                vec!["IOError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "is_same_filesystem",
+                vec![("path1", Type::String), ("path2", Type::String)],
+                Type::Bool,
+                vec!["IOError"],
+                platforms,
+            ),
             // Additional file handle operations
             StdModuleFn::throwing(
                 "file_read_at",
@@ -2157,6 +3115,76 @@ impl<'a> TypeChecker<'a> {
                 vec!["IOError", "PermissionError"],
                 platforms,
             ),
+            // Async I/O on blocking threads; await_io throws what the
+            // operation failed with, receive yields none instead
+            StdModuleFn::new(
+                "read_async",
+                vec![("path", Type::String)],
+                Type::Channel(Box::new(Type::String)),
+                &[Platform::Native],
+            ),
+            StdModuleFn::new(
+                "write_async",
+                vec![("path", Type::String), ("content", Type::String)],
+                Type::Channel(Box::new(Type::Int)),
+                &[Platform::Native],
+            ),
+            StdModuleFn::new(
+                "list_dir_async",
+                vec![("path", Type::String)],
+                Type::Channel(Box::new(Type::Array(Box::new(Type::String)))),
+                &[Platform::Native],
+            ),
+            StdModuleFn {
+                throws: vec!["IOError", "PermissionError"],
+                ..StdModuleFn::generic(
+                    "await_io",
+                    vec!["T"],
+                    vec![(
+                        "pending",
+                        Type::Channel(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                    )],
+                    Type::Generic(lasso::Spur::default(), vec![]),
+                    &[Platform::Native],
+                )
+            },
+            StdModuleFn::throwing(
+                "watch",
+                vec![
+                    ("path", Type::String),
+                    (
+                        "callback",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::FsEvent],
+                            returns: Box::new(Type::Unit),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Int,
+                vec!["IOError"],
+                &[Platform::Native],
+            ),
+            StdModuleFn::throwing(
+                "watch_recursive",
+                vec![
+                    ("path", Type::String),
+                    (
+                        "callback",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::FsEvent],
+                            returns: Box::new(Type::Unit),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    ),
+                ],
+                Type::Int,
+                vec!["IOError"],
+                &[Platform::Native],
+            ),
+            StdModuleFn::new("unwatch", vec![("handle", Type::Int)], Type::Unit, &[Platform::Native]),
         ]
     }
 
@@ -2200,57 +3228,118 @@ impl<'a> TypeChecker<'a> {
         ]
     }
 
-    fn get_encoding_url_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+    fn get_encoding_base32_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
-            StdModuleFn::new("encode", vec![("s", Type::String)], Type::String, platforms),
+            StdModuleFn::new("encode", vec![("data", Type::Bytes)], Type::String, platforms),
             StdModuleFn::throwing(
                 "decode",
                 vec![("s", Type::String)],
-                Type::String,
+                Type::Bytes,
                 vec!["DecodeError"],
                 platforms,
             ),
         ]
     }
 
-    fn get_encoding_json_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+    fn get_encoding_base58_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
+            StdModuleFn::new("encode", vec![("data", Type::Bytes)], Type::String, platforms),
             StdModuleFn::throwing(
                 "decode",
                 vec![("s", Type::String)],
-                Type::Json,
+                Type::Bytes,
                 vec!["DecodeError"],
                 platforms,
             ),
-            StdModuleFn::new("encode", vec![("value", Type::Json)], Type::String, platforms),
-            StdModuleFn::new("encode_pretty", vec![("value", Type::Json)], Type::String, platforms),
-            StdModuleFn::new(
-                "exists",
-                vec![("data", Type::Json), ("key", Type::String)],
-                Type::Bool,
-                platforms,
-            ),
+            StdModuleFn::new("check_encode", vec![("data", Type::Bytes)], Type::String, platforms),
             StdModuleFn::throwing(
-                "path",
-                vec![("data", Type::Json), ("jq_path", Type::String)],
-                Type::Json,
-                vec!["PathError"],
-                platforms,
-            ),
-            StdModuleFn::new(
-                "keys",
-                vec![("data", Type::Json)],
-                Type::Array(Box::new(Type::String)),
+                "check_decode",
+                vec![("s", Type::String)],
+                Type::Bytes,
+                vec!["DecodeError"],
                 platforms,
             ),
-            StdModuleFn::new("count", vec![("data", Type::Json)], Type::Int, platforms),
-            StdModuleFn::new("get_type", vec![("data", Type::Json)], Type::Int, platforms),
-            StdModuleFn::new("type_name", vec![("data", Type::Json)], Type::String, platforms),
-            StdModuleFn::new("is_null", vec![("data", Type::Json)], Type::Bool, platforms),
         ]
     }
 
-    fn get_encoding_toml_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+    fn get_encoding_punycode_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new("encode", vec![("s", Type::String)], Type::String, platforms),
+            StdModuleFn::throwing(
+                "decode",
+                vec![("s", Type::String)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "to_ascii",
+                vec![("domain", Type::String)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "to_unicode",
+                vec![("domain", Type::String)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+        ]
+    }
+
+    fn get_encoding_url_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new("encode", vec![("s", Type::String)], Type::String, platforms),
+            StdModuleFn::throwing(
+                "decode",
+                vec![("s", Type::String)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+        ]
+    }
+
+    fn get_encoding_json_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::throwing(
+                "decode",
+                vec![("s", Type::String)],
+                Type::Json,
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::new("encode", vec![("value", Type::Json)], Type::String, platforms),
+            StdModuleFn::new("encode_pretty", vec![("value", Type::Json)], Type::String, platforms),
+            StdModuleFn::new(
+                "exists",
+                vec![("data", Type::Json), ("key", Type::String)],
+                Type::Bool,
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "path",
+                vec![("data", Type::Json), ("jq_path", Type::String)],
+                Type::Json,
+                vec!["PathError"],
+                platforms,
+            ),
+            StdModuleFn::new(
+                "keys",
+                vec![("data", Type::Json)],
+                Type::Array(Box::new(Type::String)),
+                platforms,
+            ),
+            StdModuleFn::new("count", vec![("data", Type::Json)], Type::Int, platforms),
+            StdModuleFn::new("get_type", vec![("data", Type::Json)], Type::Int, platforms),
+            StdModuleFn::new("type_name", vec![("data", Type::Json)], Type::String, platforms),
+            StdModuleFn::new("is_null", vec![("data", Type::Json)], Type::Bool, platforms),
+        ]
+    }
+
+    fn get_encoding_toml_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
             StdModuleFn::throwing(
                 "decode",
@@ -2295,6 +3384,390 @@ impl<'a> TypeChecker<'a> {
         ]
     }
 
+    fn get_encoding_csv_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let rows = || Type::Array(Box::new(Type::Array(Box::new(Type::String))));
+        let records = || {
+            Type::Array(Box::new(Type::Map(Box::new(Type::String), Box::new(Type::String))))
+        };
+        vec![
+            StdModuleFn::throwing(
+                "parse",
+                vec![("text", Type::String)],
+                rows(),
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "parse_delimited",
+                vec![("text", Type::String), ("delimiter", Type::String)],
+                rows(),
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "parse_with_headers",
+                vec![("text", Type::String)],
+                records(),
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "parse_with_headers_delimited",
+                vec![("text", Type::String), ("delimiter", Type::String)],
+                records(),
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::new("to_csv", vec![("rows", rows())], Type::String, platforms),
+            StdModuleFn::throwing(
+                "to_csv_delimited",
+                vec![("rows", rows()), ("delimiter", Type::String)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+        ]
+    }
+
+    fn get_encoding_msgpack_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new("encode", vec![("value", Type::Json)], Type::Bytes, platforms),
+            StdModuleFn::throwing(
+                "decode",
+                vec![("data", Type::Bytes)],
+                Type::Json,
+                vec!["DecodeError"],
+                platforms,
+            ),
+        ]
+    }
+
+    fn get_encoding_cbor_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new("encode", vec![("value", Type::Json)], Type::Bytes, platforms),
+            StdModuleFn::throwing(
+                "decode",
+                vec![("data", Type::Bytes)],
+                Type::Json,
+                vec!["DecodeError"],
+                platforms,
+            ),
+        ]
+    }
+
+    /// gzip and zlib run everywhere; zstd needs the native zstd library
+    fn get_compress_functions(
+        platforms: &'static [Platform],
+        zstd_platforms: &'static [Platform],
+    ) -> Vec<StdModuleFn> {
+        let compress = |name, platforms| {
+            StdModuleFn::new(name, vec![("data", Type::Bytes), ("level", Type::Int)], Type::Bytes, platforms)
+        };
+        let decompress = |name, platforms| {
+            StdModuleFn::throwing(name, vec![("data", Type::Bytes)], Type::Bytes, vec!["DecodeError"], platforms)
+        };
+        vec![
+            compress("gzip_compress", platforms),
+            decompress("gzip_decompress", platforms),
+            compress("zlib_compress", platforms),
+            decompress("zlib_decompress", platforms),
+            compress("zstd_compress", zstd_platforms),
+            decompress("zstd_decompress", zstd_platforms),
+        ]
+    }
+
+    fn get_image_functions(
+        platforms: &'static [Platform],
+        fs_platforms: &'static [Platform],
+    ) -> Vec<StdModuleFn> {
+        let throwing = |name, params, ret, platforms| {
+            StdModuleFn::throwing(name, params, ret, vec!["ImageError"], platforms)
+        };
+        let img = || ("img", Type::Int);
+        vec![
+            throwing("load", vec![("path", Type::String)], Type::Int, fs_platforms),
+            throwing("decode", vec![("data", Type::Bytes)], Type::Int, platforms),
+            throwing("create", vec![("width", Type::Int), ("height", Type::Int)], Type::Int, platforms),
+            StdModuleFn::new("width", vec![img()], Type::Int, platforms),
+            StdModuleFn::new("height", vec![img()], Type::Int, platforms),
+            throwing("get_pixel", vec![img(), ("x", Type::Int), ("y", Type::Int)], Type::Int, platforms),
+            throwing(
+                "set_pixel",
+                vec![img(), ("x", Type::Int), ("y", Type::Int), ("rgba", Type::Int)],
+                Type::Unit,
+                platforms,
+            ),
+            throwing("resize", vec![img(), ("width", Type::Int), ("height", Type::Int)], Type::Int, platforms),
+            throwing(
+                "thumbnail",
+                vec![img(), ("max_width", Type::Int), ("max_height", Type::Int)],
+                Type::Int,
+                platforms,
+            ),
+            throwing(
+                "crop",
+                vec![img(), ("x", Type::Int), ("y", Type::Int), ("width", Type::Int), ("height", Type::Int)],
+                Type::Int,
+                platforms,
+            ),
+            throwing("rotate", vec![img(), ("degrees", Type::Int)], Type::Int, platforms),
+            throwing("flip_horizontal", vec![img()], Type::Int, platforms),
+            throwing("flip_vertical", vec![img()], Type::Int, platforms),
+            throwing("encode_png", vec![img()], Type::Bytes, platforms),
+            throwing("encode_jpeg", vec![img(), ("quality", Type::Int)], Type::Bytes, platforms),
+            throwing("save", vec![img(), ("path", Type::String)], Type::Unit, fs_platforms),
+            StdModuleFn::new("close", vec![img()], Type::Unit, platforms),
+        ]
+    }
+
+    fn get_doc_pdf_functions(
+        platforms: &'static [Platform],
+        fs_platforms: &'static [Platform],
+    ) -> Vec<StdModuleFn> {
+        let throwing = |name, params, ret, platforms| {
+            StdModuleFn::throwing(name, params, ret, vec!["PdfError"], platforms)
+        };
+        let doc = || ("doc", Type::Int);
+        vec![
+            throwing("extract_text", vec![("path", Type::String)], Type::String, fs_platforms),
+            throwing("page_count", vec![("path", Type::String)], Type::Int, fs_platforms),
+            StdModuleFn::new("create", vec![], Type::Int, platforms),
+            throwing("add_page", vec![doc(), ("width", Type::Float), ("height", Type::Float)], Type::Int, platforms),
+            throwing(
+                "draw_text",
+                vec![
+                    doc(),
+                    ("page", Type::Int),
+                    ("x", Type::Float),
+                    ("y", Type::Float),
+                    ("size", Type::Float),
+                    ("text", Type::String),
+                ],
+                Type::Unit,
+                platforms,
+            ),
+            throwing("to_bytes", vec![doc()], Type::Bytes, platforms),
+            throwing("save", vec![doc(), ("path", Type::String)], Type::Unit, fs_platforms),
+            StdModuleFn::new("close", vec![doc()], Type::Unit, platforms),
+        ]
+    }
+
+    fn get_doc_xlsx_functions(
+        platforms: &'static [Platform],
+        fs_platforms: &'static [Platform],
+    ) -> Vec<StdModuleFn> {
+        let throwing = |name, params, ret, platforms| {
+            StdModuleFn::throwing(name, params, ret, vec!["XlsxError"], platforms)
+        };
+        let book = || ("book", Type::Int);
+        let cell = |value: (&'static str, Type)| {
+            vec![book(), ("sheet", Type::Int), ("row", Type::Int), ("col", Type::Int), value]
+        };
+        let format = || ("format", Type::Int);
+        vec![
+            throwing("open", vec![("path", Type::String)], Type::Int, fs_platforms),
+            throwing("sheet_names", vec![book()], Type::array(Type::String), platforms),
+            throwing(
+                "read_rows",
+                vec![book(), ("sheet", Type::String)],
+                Type::array(Type::array(Type::String)),
+                platforms,
+            ),
+            StdModuleFn::new("create", vec![], Type::Int, platforms),
+            throwing("add_sheet", vec![book(), ("name", Type::String)], Type::Int, platforms),
+            throwing("write_string", cell(("value", Type::String)), Type::Unit, platforms),
+            throwing("write_number", cell(("value", Type::Float)), Type::Unit, platforms),
+            throwing("write_bool", cell(("value", Type::Bool)), Type::Unit, platforms),
+            throwing("write_formula", cell(("formula", Type::String)), Type::Unit, platforms),
+            throwing("write_date", cell(("value", Type::String)), Type::Unit, platforms),
+            throwing("add_format", vec![book()], Type::Int, platforms),
+            throwing("set_bold", vec![book(), format()], Type::Unit, platforms),
+            throwing("set_italic", vec![book(), format()], Type::Unit, platforms),
+            throwing("set_num_format", vec![book(), format(), ("pattern", Type::String)], Type::Unit, platforms),
+            throwing("set_font_color", vec![book(), format(), ("color", Type::String)], Type::Unit, platforms),
+            throwing("set_bg_color", vec![book(), format(), ("color", Type::String)], Type::Unit, platforms),
+            throwing("set_align", vec![book(), format(), ("align", Type::String)], Type::Unit, platforms),
+            throwing("format_cell", cell(format()), Type::Unit, platforms),
+            throwing(
+                "set_column_width",
+                vec![book(), ("sheet", Type::Int), ("col", Type::Int), ("width", Type::Float)],
+                Type::Unit,
+                platforms,
+            ),
+            throwing("to_bytes", vec![book()], Type::Bytes, platforms),
+            throwing("save", vec![book(), ("path", Type::String)], Type::Unit, fs_platforms),
+            StdModuleFn::new("close", vec![book()], Type::Unit, platforms),
+        ]
+    }
+
+    fn get_text_markdown_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let throwing = |name, params, ret| {
+            StdModuleFn::throwing(name, params, ret, vec!["MarkdownError"], platforms)
+        };
+        let source = || vec![("md", Type::String), ("options", Type::String)];
+        let node = || vec![("doc", Type::Int), ("index", Type::Int)];
+        vec![
+            throwing("to_html", source(), Type::String),
+            throwing("parse", source(), Type::Int),
+            throwing("node_count", vec![("doc", Type::Int)], Type::Int),
+            throwing("node_kind", node(), Type::String),
+            throwing("node_depth", node(), Type::Int),
+            throwing("node_level", node(), Type::Int),
+            throwing("node_text", node(), Type::String),
+            throwing("node_url", node(), Type::String),
+            throwing("node_lang", node(), Type::String),
+            StdModuleFn::new("close", vec![("doc", Type::Int)], Type::Unit, platforms),
+        ]
+    }
+
+    fn get_regex_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let handle_and_string = || vec![("handle", Type::Int), ("s", Type::String)];
+        vec![
+            StdModuleFn::throwing(
+                "regex_compile",
+                vec![("pattern", Type::String)],
+                Type::Int,
+                vec!["RegexError"],
+                platforms,
+            ),
+            StdModuleFn::new("regex_match", handle_and_string(), Type::Bool, platforms),
+            StdModuleFn::new("regex_find_all", handle_and_string(), Type::array(Type::String), platforms),
+            StdModuleFn::new("regex_captures", handle_and_string(), Type::array(Type::String), platforms),
+            StdModuleFn::new(
+                "regex_replace_all",
+                vec![("handle", Type::Int), ("s", Type::String), ("replacement", Type::String)],
+                Type::String,
+                platforms,
+            ),
+            StdModuleFn::new("regex_close", vec![("handle", Type::Int)], Type::Unit, platforms),
+        ]
+    }
+
+    fn get_geo_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let floats = || Type::array(Type::Float);
+        let two_points = || {
+            vec![("lat1", Type::Float), ("lon1", Type::Float), ("lat2", Type::Float), ("lon2", Type::Float)]
+        };
+        let decode = |name, ret| {
+            StdModuleFn::throwing(name, vec![("hash", Type::String)], ret, vec!["DecodeError"], platforms)
+        };
+        vec![
+            StdModuleFn::new("haversine_km", two_points(), Type::Float, platforms),
+            StdModuleFn::new("bearing", two_points(), Type::Float, platforms),
+            StdModuleFn::new(
+                "destination",
+                vec![
+                    ("lat", Type::Float),
+                    ("lon", Type::Float),
+                    ("bearing", Type::Float),
+                    ("distance_km", Type::Float),
+                ],
+                floats(),
+                platforms,
+            ),
+            StdModuleFn::new(
+                "point_in_polygon",
+                vec![("lat", Type::Float), ("lon", Type::Float), ("polygon", floats())],
+                Type::Bool,
+                platforms,
+            ),
+            StdModuleFn::new(
+                "geohash_encode",
+                vec![("lat", Type::Float), ("lon", Type::Float), ("precision", Type::Int)],
+                Type::String,
+                platforms,
+            ),
+            decode("geohash_decode", floats()),
+            decode("geohash_bounds", floats()),
+            decode("geohash_neighbors", Type::array(Type::String)),
+            StdModuleFn::new(
+                "bbox_around",
+                vec![("lat", Type::Float), ("lon", Type::Float), ("radius_km", Type::Float)],
+                floats(),
+                platforms,
+            ),
+            StdModuleFn::new(
+                "bbox_contains",
+                vec![("bbox", floats()), ("lat", Type::Float), ("lon", Type::Float)],
+                Type::Bool,
+                platforms,
+            ),
+            StdModuleFn::new("bbox_intersects", vec![("a", floats()), ("b", floats())], Type::Bool, platforms),
+            StdModuleFn::new("bounding_box", vec![("points", floats())], floats(), platforms),
+        ]
+    }
+
+    fn get_money_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let throwing = |name, params, ret| StdModuleFn::throwing(name, params, ret, vec!["MoneyError"], platforms);
+        vec![
+            throwing("of", vec![("amount", Type::String), ("currency", Type::String)], Type::Money),
+            throwing("from_minor", vec![("minor", Type::Int), ("currency", Type::String)], Type::Money),
+            throwing("from_float", vec![("value", Type::Float), ("currency", Type::String)], Type::Money),
+            throwing("parse", vec![("s", Type::String)], Type::Money),
+            throwing("add", vec![("a", Type::Money), ("b", Type::Money)], Type::Money),
+            throwing("sub", vec![("a", Type::Money), ("b", Type::Money)], Type::Money),
+            throwing("negate", vec![("m", Type::Money)], Type::Money),
+            throwing("multiply", vec![("m", Type::Money), ("factor", Type::String)], Type::Money),
+            throwing(
+                "allocate",
+                vec![("m", Type::Money), ("ratios", Type::array(Type::Int))],
+                Type::array(Type::Money),
+            ),
+            throwing("compare", vec![("a", Type::Money), ("b", Type::Money)], Type::Int),
+            throwing(
+                "convert",
+                vec![
+                    ("m", Type::Money),
+                    ("currency", Type::String),
+                    ("rates", Type::map(Type::String, Type::String)),
+                ],
+                Type::Money,
+            ),
+            throwing("format", vec![("m", Type::Money), ("locale", Type::String)], Type::String),
+            StdModuleFn::new("amount", vec![("m", Type::Money)], Type::String, platforms),
+            StdModuleFn::new("currency", vec![("m", Type::Money)], Type::String, platforms),
+            StdModuleFn::new("minor_units", vec![("m", Type::Money)], Type::Int, platforms),
+            StdModuleFn::new("to_float", vec![("m", Type::Money)], Type::Float, platforms),
+            throwing("currency_digits", vec![("currency", Type::String)], Type::Int),
+        ]
+    }
+
+    fn get_validate_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        let predicate = |name| StdModuleFn::new(name, vec![("s", Type::String)], Type::Bool, platforms);
+        let values = Type::map(Type::String, Type::String);
+        vec![
+            predicate("is_email"),
+            predicate("is_url"),
+            predicate("is_uuid"),
+            predicate("is_ipv4"),
+            predicate("is_ipv6"),
+            predicate("is_hostname"),
+            StdModuleFn::new("matches_luhn", vec![("card", Type::String)], Type::Bool, platforms),
+            StdModuleFn::new("open_validator", vec![], Type::Int, platforms),
+            StdModuleFn::throwing(
+                "add_rule",
+                vec![("validator", Type::Int), ("field", Type::String), ("rules", Type::String)],
+                Type::Unit,
+                vec!["ValidationError"],
+                platforms,
+            ),
+            StdModuleFn::new(
+                "validate",
+                vec![("validator", Type::Int), ("values", values.clone())],
+                Type::array(Type::map(Type::String, Type::String)),
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "check",
+                vec![("validator", Type::Int), ("values", values)],
+                Type::Unit,
+                vec!["ValidationError"],
+                platforms,
+            ),
+            StdModuleFn::new("close_validator", vec![("validator", Type::Int)], Type::Unit, platforms),
+        ]
+    }
+
     fn get_encoding_binary_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
             StdModuleFn::new("read_u8", vec![("buf", Type::Bytes), ("offset", Type::Int)], Type::Int, platforms),
@@ -2338,6 +3811,7 @@ impl<'a> TypeChecker<'a> {
             StdModuleFn::new("len", vec![("buf", Type::Bytes)], Type::Int, platforms),
             StdModuleFn::new("capacity", vec![("buf", Type::Bytes)], Type::Int, platforms),
             StdModuleFn::new("slice", vec![("buf", Type::Bytes), ("start", Type::Int), ("end", Type::Int)], Type::Bytes, platforms),
+            StdModuleFn::new("view", vec![("buf", Type::Bytes), ("start", Type::Int), ("end", Type::Int)], Type::Bytes, platforms),
             StdModuleFn::new("concat", vec![("a", Type::Bytes), ("b", Type::Bytes)], Type::Bytes, platforms),
             StdModuleFn::new("append", vec![("dst", Type::Bytes), ("src", Type::Bytes)], Type::Unit, platforms),
             StdModuleFn::new("copy_within", vec![("buf", Type::Bytes), ("src_start", Type::Int), ("src_end", Type::Int), ("dst_start", Type::Int)], Type::Unit, platforms),
@@ -2368,6 +3842,13 @@ impl<'a> TypeChecker<'a> {
                 vec!["NetworkError"],
                 platforms,
             ),
+            StdModuleFn::throwing(
+                "listen_backlog",
+                vec![("address", Type::String), ("backlog", Type::Int)],
+                Type::Int,
+                vec!["NetworkError"],
+                platforms,
+            ),
             StdModuleFn::new("close", vec![("listener", Type::Int)], Type::Unit, platforms),
             StdModuleFn::new("local_addr", vec![("listener", Type::Int)], Type::String, platforms),
         ]
@@ -2411,6 +3892,39 @@ impl<'a> TypeChecker<'a> {
                 platforms,
             ),
             StdModuleFn::new("peer_addr", vec![("socket", Type::Int)], Type::String, platforms),
+            StdModuleFn::throwing(
+                "set_nodelay",
+                vec![("socket", Type::Int), ("enabled", Type::Bool)],
+                Type::Unit,
+                vec!["NetworkError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "set_keepalive",
+                vec![
+                    ("socket", Type::Int),
+                    ("idle_ms", Type::Int),
+                    ("interval_ms", Type::Int),
+                    ("count", Type::Int),
+                ],
+                Type::Unit,
+                vec!["NetworkError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "set_recv_buffer",
+                vec![("socket", Type::Int), ("size", Type::Int)],
+                Type::Unit,
+                vec!["NetworkError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "set_linger",
+                vec![("socket", Type::Int), ("ms", Type::Int)],
+                Type::Unit,
+                vec!["NetworkError"],
+                platforms,
+            ),
         ]
     }
 
@@ -2501,6 +4015,21 @@ impl<'a> TypeChecker<'a> {
                 platforms,
             ),
             StdModuleFn::new("set_timeout", vec![("ms", Type::Int)], Type::Unit, platforms),
+            StdModuleFn::throwing(
+                "set_proxy",
+                vec![("url", Type::String)],
+                Type::Unit,
+                vec!["NetworkError"],
+                platforms,
+            ),
+            StdModuleFn::new("set_no_proxy", vec![("hosts", Type::String)], Type::Unit, platforms),
+            StdModuleFn::new("reset_proxy", vec![], Type::Unit, platforms),
+            StdModuleFn::new(
+                "set_retries",
+                vec![("count", Type::Int), ("backoff_ms", Type::Int)],
+                Type::Unit,
+                platforms,
+            ),
             StdModuleFn::throwing(
                 "get_tls",
                 vec![("url", Type::String), ("ca_path", Type::String)],
@@ -2600,8 +4129,17 @@ impl<'a> TypeChecker<'a> {
             ),
             StdModuleFn::new(
                 "group",
-                vec![("router", Type::Int), ("prefix", Type::String)],
-                Type::Int,
+                vec![
+                    ("router", Type::Int),
+                    ("prefix", Type::String),
+                    ("setup", Type::Function(types::FunctionType {
+                        params: vec![Type::Int],
+                        returns: Box::new(Type::Unit),
+                        throws: vec![],
+                        is_variadic: false,
+                    })),
+                ],
+                Type::Unit,
                 platforms,
             ),
             StdModuleFn::new(
@@ -2627,6 +4165,39 @@ impl<'a> TypeChecker<'a> {
                 Type::Int,
                 platforms,
             ),
+            StdModuleFn::new(
+                "health_router",
+                vec![(
+                    "checks",
+                    Type::Map(
+                        Box::new(Type::String),
+                        Box::new(Type::Function(types::FunctionType {
+                            params: vec![],
+                            returns: Box::new(Type::Bool),
+                            throws: vec![],
+                            is_variadic: false,
+                        })),
+                    ),
+                )],
+                Type::Int,
+                platforms,
+            ),
+            StdModuleFn::new(
+                "set_value",
+                vec![
+                    ("req", Type::Int),
+                    ("key", Type::String),
+                    ("value", Type::String),
+                ],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::new(
+                "get_value",
+                vec![("req", Type::Int), ("key", Type::String)],
+                Type::Option(Box::new(Type::String)),
+                platforms,
+            ),
             StdModuleFn::throwing(
                 "serve_tls",
                 vec![
@@ -2757,6 +4328,63 @@ impl<'a> TypeChecker<'a> {
             "threads" => Some(vec![
                 StdModuleFn::new("sleep", vec![("ms", Type::Int)], Type::Unit, NATIVE_ONLY),
                 StdModuleFn::new("join", vec![], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("task_join", vec![("task", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("task_is_done", vec![("task", Type::Int)], Type::Bool, NATIVE_ONLY),
+                StdModuleFn::generic(
+                    "task_result",
+                    vec!["T"],
+                    vec![("task", Type::Int)],
+                    Type::Generic(lasso::Spur::default(), vec![]),
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("open_waitgroup", vec![], Type::Waitgroup, NATIVE_ONLY),
+                StdModuleFn::new(
+                    "waitgroup_add",
+                    vec![("wg", Type::Waitgroup), ("n", Type::Int)],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("waitgroup_done", vec![("wg", Type::Waitgroup)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("waitgroup_wait", vec![("wg", Type::Waitgroup)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("open_condvar", vec![], Type::Int, NATIVE_ONLY),
+                StdModuleFn::generic(
+                    "condvar_wait",
+                    vec!["T"],
+                    vec![
+                        ("cv", Type::Int),
+                        ("m", Type::Mutex(Box::new(Type::Generic(lasso::Spur::default(), vec![])))),
+                        ("value", Type::Generic(lasso::Spur::default(), vec![])),
+                    ],
+                    Type::Generic(lasso::Spur::default(), vec![]),
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("condvar_notify_one", vec![("cv", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("condvar_notify_all", vec![("cv", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("open_semaphore", vec![("permits", Type::Int)], Type::Int, NATIVE_ONLY),
+                StdModuleFn::new("semaphore_acquire", vec![("sem", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("semaphore_try_acquire", vec![("sem", Type::Int)], Type::Bool, NATIVE_ONLY),
+                StdModuleFn::new("semaphore_release", vec![("sem", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("group_new", vec![], Type::Int, NATIVE_ONLY),
+                StdModuleFn::new(
+                    "group_spawn",
+                    vec![
+                        ("group", Type::Int),
+                        (
+                            "task",
+                            Type::Function(types::FunctionType {
+                                params: vec![],
+                                returns: Box::new(Type::Unit),
+                                throws: vec![],
+                                is_variadic: false,
+                            }),
+                        ),
+                    ],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("group_cancel", vec![("group", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("group_wait", vec![("group", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("is_cancelled", vec![], Type::Bool, NATIVE_ONLY),
                 StdModuleFn::generic(
                     "open_channel",
                     vec!["T"],
@@ -2787,6 +4415,42 @@ impl<'a> TypeChecker<'a> {
                     Type::Option(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
                     NATIVE_ONLY,
                 ),
+                StdModuleFn::generic(
+                    "try_send",
+                    vec!["T"],
+                    vec![
+                        (
+                            "ch",
+                            Type::Channel(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                        ),
+                        ("value", Type::Generic(lasso::Spur::default(), vec![])),
+                    ],
+                    Type::Bool,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::generic(
+                    "try_receive",
+                    vec!["T"],
+                    vec![(
+                        "ch",
+                        Type::Channel(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                    )],
+                    Type::Option(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::generic(
+                    "receive_timeout",
+                    vec!["T"],
+                    vec![
+                        (
+                            "ch",
+                            Type::Channel(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                        ),
+                        ("ms", Type::Int),
+                    ],
+                    Type::Option(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                    NATIVE_ONLY,
+                ),
                 StdModuleFn::generic(
                     "close",
                     vec!["T"],
@@ -2797,6 +4461,27 @@ impl<'a> TypeChecker<'a> {
                     Type::Unit,
                     NATIVE_ONLY,
                 ),
+                StdModuleFn::generic(
+                    "select",
+                    vec!["T", "R"],
+                    vec![
+                        (
+                            "channels",
+                            Type::Array(Box::new(Type::Channel(Box::new(Type::Generic(lasso::Spur::default(), vec![]))))),
+                        ),
+                        (
+                            "handler",
+                            Type::Function(types::FunctionType {
+                                params: vec![Type::Int, Type::Generic(lasso::Spur::default(), vec![])],
+                                returns: Box::new(Type::Generic(Spur::try_from_usize(1).unwrap(), vec![])),
+                                throws: vec![],
+                                is_variadic: false,
+                            }),
+                        ),
+                    ],
+                    Type::Option(Box::new(Type::Generic(Spur::try_from_usize(1).unwrap(), vec![]))),
+                    NATIVE_ONLY,
+                ),
                 StdModuleFn::generic(
                     "with_mutex",
                     vec!["T"],
@@ -2818,6 +4503,13 @@ impl<'a> TypeChecker<'a> {
                     Type::Atomic(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
                     NATIVE_ONLY,
                 ),
+                StdModuleFn::generic(
+                    "atomic_new",
+                    vec!["T"],
+                    vec![("value", Type::Generic(lasso::Spur::default(), vec![]))],
+                    Type::Atomic(Box::new(Type::Generic(lasso::Spur::default(), vec![]))),
+                    NATIVE_ONLY,
+                ),
                 StdModuleFn::generic(
                     "atomic_load",
                     vec!["T"],
@@ -2921,6 +4613,36 @@ impl<'a> TypeChecker<'a> {
                     NATIVE_ONLY,
                 ),
             ]),
+            "threads::actors" => Some(vec![
+                StdModuleFn::new(
+                    "spawn_actor",
+                    vec![(
+                        "behavior",
+                        Type::Function(types::FunctionType {
+                            params: vec![Type::String],
+                            returns: Box::new(Type::String),
+                            throws: vec![],
+                            is_variadic: false,
+                        }),
+                    )],
+                    Type::Int,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "send",
+                    vec![("actor", Type::Int), ("message", Type::String)],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "ask",
+                    vec![("actor", Type::Int), ("message", Type::String), ("timeout_ms", Type::Int)],
+                    Type::String,
+                    vec!["TimeoutError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("stop_actor", vec![("actor", Type::Int)], Type::Unit, NATIVE_ONLY),
+            ]),
             "datetime" => Some(vec![
                 StdModuleFn::new("now_ms", vec![], Type::Int, ALL_PLATFORMS),
                 StdModuleFn::new("now_s", vec![], Type::Int, ALL_PLATFORMS),
@@ -2938,6 +4660,12 @@ impl<'a> TypeChecker<'a> {
                     ALL_PLATFORMS,
                 ),
             ]),
+            "runtime" => Some(vec![StdModuleFn::new(
+                "build_info",
+                vec![],
+                Type::Map(Box::new(Type::String), Box::new(Type::String)),
+                ALL_PLATFORMS,
+            )]),
             "metrics" => Some(vec![
                 StdModuleFn::new("perf_now", vec![], Type::Int, ALL_PLATFORMS),
                 StdModuleFn::new("elapsed_ms", vec![("start_ns", Type::Int)], Type::Int, ALL_PLATFORMS),
@@ -3078,47 +4806,177 @@ impl<'a> TypeChecker<'a> {
                     ALL_PLATFORMS,
                 ),
                 StdModuleFn::new(
-                    "lpad",
+                    "lpad",
+                    vec![
+                        ("s", Type::String),
+                        ("len", Type::Int),
+                        ("char", Type::String),
+                    ],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "rpad",
+                    vec![
+                        ("s", Type::String),
+                        ("len", Type::Int),
+                        ("char", Type::String),
+                    ],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "repeat",
+                    vec![("s", Type::String), ("n", Type::Int)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "lines",
+                    vec![("s", Type::String)],
+                    Type::Array(Box::new(Type::String)),
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "chars",
+                    vec![("s", Type::String)],
+                    Type::Array(Box::new(Type::String)),
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "capitalize",
+                    vec![("s", Type::String)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_title",
+                    vec![("s", Type::String)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_snake",
+                    vec![("s", Type::String)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_camel",
+                    vec![("s", Type::String)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_kebab",
+                    vec![("s", Type::String)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::throwing(
+                    "parse_int_radix",
+                    vec![("s", Type::String), ("base", Type::Int)],
+                    Type::Int,
+                    vec!["DecodeError"],
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_radix",
+                    vec![("n", Type::Int), ("base", Type::Int)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_hex",
+                    vec![("n", Type::Int)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_octal",
+                    vec![("n", Type::Int)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "to_binary",
+                    vec![("n", Type::Int)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "group_digits",
                     vec![
                         ("s", Type::String),
-                        ("len", Type::Int),
-                        ("char", Type::String),
+                        ("size", Type::Int),
+                        ("sep", Type::String),
                     ],
                     Type::String,
                     ALL_PLATFORMS,
                 ),
                 StdModuleFn::new(
-                    "rpad",
+                    "compare",
+                    vec![("a", Type::String), ("b", Type::String)],
+                    Type::Int,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "equals_ignore_case",
+                    vec![("a", Type::String), ("b", Type::String)],
+                    Type::Bool,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "natural_compare",
+                    vec![("a", Type::String), ("b", Type::String)],
+                    Type::Int,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "collate",
                     vec![
-                        ("s", Type::String),
-                        ("len", Type::Int),
-                        ("char", Type::String),
+                        ("a", Type::String),
+                        ("b", Type::String),
+                        ("locale", Type::String),
                     ],
+                    Type::Int,
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "normalize",
+                    vec![("s", Type::String), ("form", Type::String)],
                     Type::String,
                     ALL_PLATFORMS,
                 ),
                 StdModuleFn::new(
-                    "repeat",
-                    vec![("s", Type::String), ("n", Type::Int)],
+                    "casefold",
+                    vec![("s", Type::String)],
                     Type::String,
                     ALL_PLATFORMS,
                 ),
                 StdModuleFn::new(
-                    "lines",
+                    "graphemes",
                     vec![("s", Type::String)],
                     Type::Array(Box::new(Type::String)),
                     ALL_PLATFORMS,
                 ),
                 StdModuleFn::new(
-                    "chars",
+                    "width",
                     vec![("s", Type::String)],
-                    Type::Array(Box::new(Type::String)),
+                    Type::Int,
                     ALL_PLATFORMS,
                 ),
             ]),
+            "strings::builder" => Some(Self::get_strings_builder_functions(ALL_PLATFORMS)),
             "collections" => Some(vec![]),
             "collections::arrays" => Some(Self::get_collections_array_functions(ALL_PLATFORMS)),
             "collections::maps" => Some(Self::get_collections_map_functions(ALL_PLATFORMS)),
+            "collections::sets" => Some(Self::get_collections_set_functions(ALL_PLATFORMS)),
+            "collections::priority_queues" => {
+                Some(Self::get_collections_priority_queue_functions(ALL_PLATFORMS))
+            }
+            "collections::deques" => Some(Self::get_collections_deque_functions(ALL_PLATFORMS)),
+            "collections::iterators" => Some(Self::get_collections_iterator_functions(ALL_PLATFORMS)),
             "env" => Some(vec![
                 StdModuleFn::new("getenv", vec![("key", Type::String)], Type::String, NATIVE_EDGE),
                 StdModuleFn::new(
@@ -3151,6 +5009,234 @@ impl<'a> TypeChecker<'a> {
                 StdModuleFn::new("environ", vec![], Type::Array(Box::new(Type::String)), NATIVE_EDGE),
                 StdModuleFn::new("expand_env", vec![("s", Type::String)], Type::String, NATIVE_EDGE),
             ]),
+            "config" => Some(vec![
+                StdModuleFn::new("file", vec![("path", Type::String)], Type::Int, NATIVE_ONLY),
+                StdModuleFn::new("env", vec![("prefix", Type::String)], Type::Int, NATIVE_ONLY),
+                StdModuleFn::new(
+                    "flags",
+                    vec![("args", Type::Array(Box::new(Type::String)))],
+                    Type::Int,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "values",
+                    vec![("entries", Type::Map(Box::new(Type::String), Box::new(Type::String)))],
+                    Type::Int,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("free", vec![("source", Type::Int)], Type::Unit, NATIVE_ONLY),
+                // Returns the struct named by `schema`; see infer_config_load
+                StdModuleFn::throwing(
+                    "load",
+                    vec![("schema", Type::String), ("sources", Type::Array(Box::new(Type::Int)))],
+                    Type::Unit,
+                    vec!["ConfigError"],
+                    NATIVE_ONLY,
+                ),
+            ]),
+            "flags" => Some(vec![
+                StdModuleFn::throwing(
+                    "open_flags",
+                    vec![("source", Type::String)],
+                    Type::Unit,
+                    vec!["IOError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("set_poll_interval", vec![("ms", Type::Int)], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new("close_flags", vec![], Type::Unit, NATIVE_ONLY),
+                StdModuleFn::new(
+                    "enabled",
+                    vec![("name", Type::String), ("default", Type::Bool)],
+                    Type::Bool,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "variant",
+                    vec![
+                        ("name", Type::String),
+                        ("user_id", Type::String),
+                        ("variants", Type::Array(Box::new(Type::String))),
+                    ],
+                    Type::String,
+                    NATIVE_ONLY,
+                ),
+            ]),
+            "fsm" => Some(vec![
+                StdModuleFn::new("open_machine", vec![("initial", Type::String)], Type::Int, NATIVE_ONLY),
+                StdModuleFn::new(
+                    "add_state",
+                    vec![("machine", Type::Int), ("name", Type::String)],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "add_final_state",
+                    vec![("machine", Type::Int), ("name", Type::String)],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "add_transition",
+                    vec![
+                        ("machine", Type::Int),
+                        ("from", Type::String),
+                        ("event", Type::String),
+                        ("to", Type::String),
+                    ],
+                    Type::Unit,
+                    vec!["InvalidTransition"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "set_guard",
+                    vec![
+                        ("machine", Type::Int),
+                        ("from", Type::String),
+                        ("event", Type::String),
+                        (
+                            "guard",
+                            Type::Function(types::FunctionType {
+                                params: vec![],
+                                returns: Box::new(Type::Bool),
+                                throws: vec![],
+                                is_variadic: false,
+                            }),
+                        ),
+                    ],
+                    Type::Unit,
+                    vec!["InvalidTransition"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "set_action",
+                    vec![
+                        ("machine", Type::Int),
+                        ("from", Type::String),
+                        ("event", Type::String),
+                        (
+                            "action",
+                            Type::Function(types::FunctionType {
+                                params: vec![Type::String, Type::String],
+                                returns: Box::new(Type::Unit),
+                                throws: vec![],
+                                is_variadic: false,
+                            }),
+                        ),
+                    ],
+                    Type::Unit,
+                    vec!["InvalidTransition"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "fire",
+                    vec![("machine", Type::Int), ("event", Type::String)],
+                    Type::String,
+                    vec!["InvalidTransition"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "can_fire",
+                    vec![("machine", Type::Int), ("event", Type::String)],
+                    Type::Bool,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("current_state", vec![("machine", Type::Int)], Type::String, NATIVE_ONLY),
+                StdModuleFn::new(
+                    "validate_machine",
+                    vec![("machine", Type::Int)],
+                    Type::Array(Box::new(Type::String)),
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("to_dot", vec![("machine", Type::Int)], Type::String, NATIVE_ONLY),
+                StdModuleFn::new("close_machine", vec![("machine", Type::Int)], Type::Unit, NATIVE_ONLY),
+            ]),
+            "queue" => Some(vec![
+                StdModuleFn::throwing(
+                    "open_queue",
+                    vec![("path", Type::String)],
+                    Type::Int,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "close_queue",
+                    vec![("queue", Type::Int)],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "set_capacity",
+                    vec![("queue", Type::Int), ("max_jobs", Type::Int)],
+                    Type::Unit,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "enqueue",
+                    vec![
+                        ("queue", Type::Int),
+                        ("payload", Type::String),
+                        ("run_at", Type::Int),
+                        ("max_retries", Type::Int),
+                    ],
+                    Type::Int,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "try_enqueue",
+                    vec![
+                        ("queue", Type::Int),
+                        ("payload", Type::String),
+                        ("run_at", Type::Int),
+                        ("max_retries", Type::Int),
+                    ],
+                    Type::Int,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "dequeue",
+                    vec![("queue", Type::Int), ("worker_id", Type::String)],
+                    Type::Int,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "dequeue_wait",
+                    vec![("queue", Type::Int), ("worker_id", Type::String), ("timeout_ms", Type::Int)],
+                    Type::Int,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "job_payload",
+                    vec![("queue", Type::Int), ("job", Type::Int)],
+                    Type::String,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new(
+                    "job_attempts",
+                    vec![("queue", Type::Int), ("job", Type::Int)],
+                    Type::Int,
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "ack",
+                    vec![("queue", Type::Int), ("job", Type::Int)],
+                    Type::Unit,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "nack",
+                    vec![("queue", Type::Int), ("job", Type::Int), ("retry_delay_ms", Type::Int)],
+                    Type::Unit,
+                    vec!["DBError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("queue_size", vec![("queue", Type::Int)], Type::Int, NATIVE_ONLY),
+                StdModuleFn::new("dead_count", vec![("queue", Type::Int)], Type::Int, NATIVE_ONLY),
+            ]),
             "os" => Some(vec![
                 StdModuleFn::throwing(
                     "hostname",
@@ -3201,6 +5287,36 @@ impl<'a> TypeChecker<'a> {
                     NATIVE_ONLY,
                 ),
             ]),
+            "os::net" => Some(vec![StdModuleFn::throwing(
+                "interfaces",
+                vec![],
+                Type::Array(Box::new(Type::IfaceInfo)),
+                vec!["OSError"],
+                NATIVE_ONLY,
+            )]),
+            "os::keyring" => Some(vec![
+                StdModuleFn::throwing(
+                    "set_password",
+                    vec![("service", Type::String), ("user", Type::String), ("password", Type::Secret)],
+                    Type::Unit,
+                    vec!["OSError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "get_password",
+                    vec![("service", Type::String), ("user", Type::String)],
+                    Type::Secret,
+                    vec!["OSError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "delete_password",
+                    vec![("service", Type::String), ("user", Type::String)],
+                    Type::Unit,
+                    vec!["OSError"],
+                    NATIVE_ONLY,
+                ),
+            ]),
             "process" => Some(vec![
                 StdModuleFn::new("getpid", vec![], Type::Int, NATIVE_ONLY),
                 StdModuleFn::new("getppid", vec![], Type::Int, NATIVE_ONLY),
@@ -3257,6 +5373,63 @@ impl<'a> TypeChecker<'a> {
                 StdModuleFn::new("SIGSTOP", vec![], Type::Int, NATIVE_ONLY),
                 StdModuleFn::new("SIGCONT", vec![], Type::Int, NATIVE_ONLY),
             ]),
+            "process::pool" => Some(vec![
+                // entry_fn_name must be a string literal naming a top-level
+                // fn(bytes) -> bytes; see check_worker_entry
+                StdModuleFn::throwing(
+                    "open_worker_pool",
+                    vec![("workers", Type::Int), ("entry_fn_name", Type::String)],
+                    Type::Int,
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "submit",
+                    vec![("pool", Type::Int), ("payload", Type::Bytes)],
+                    Type::Channel(Box::new(Type::Bytes)),
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "await_result",
+                    vec![("job", Type::Channel(Box::new(Type::Bytes)))],
+                    Type::Bytes,
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("close_pool", vec![("pool", Type::Int)], Type::Unit, NATIVE_ONLY),
+            ]),
+            "ipc" => Some(vec![
+                StdModuleFn::throwing(
+                    "ipc_create",
+                    vec![("name", Type::String), ("size", Type::Int)],
+                    Type::Int,
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "ipc_connect",
+                    vec![("name", Type::String)],
+                    Type::Int,
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "send",
+                    vec![("channel", Type::Int), ("data", Type::Bytes)],
+                    Type::Unit,
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::throwing(
+                    "receive",
+                    vec![("channel", Type::Int)],
+                    Type::Bytes,
+                    vec!["ProcessError"],
+                    NATIVE_ONLY,
+                ),
+                StdModuleFn::new("ipc_close", vec![("channel", Type::Int)], Type::Unit, NATIVE_ONLY),
+            ]),
             "testing" => Some(vec![
                 StdModuleFn::new(
                     "assert",
@@ -3443,17 +5616,51 @@ impl<'a> TypeChecker<'a> {
                     Type::String,
                     ALL_PLATFORMS,
                 ),
+                // Lexical resolution (no filesystem access)
+                StdModuleFn::new("absolute_lexical", vec![("path", Type::String)], Type::String, ALL_PLATFORMS),
+                StdModuleFn::new(
+                    "relative_to",
+                    vec![("path", Type::String), ("base", Type::String)],
+                    Type::String,
+                    ALL_PLATFORMS,
+                ),
+                // Traversal-safe joining of untrusted input
+                StdModuleFn::throwing(
+                    "safe_join",
+                    vec![("base", Type::String), ("untrusted", Type::String)],
+                    Type::String,
+                    vec!["SecurityError"],
+                    ALL_PLATFORMS,
+                ),
+                StdModuleFn::new(
+                    "is_within",
+                    vec![("base", Type::String), ("path", Type::String)],
+                    Type::Bool,
+                    ALL_PLATFORMS,
+                ),
             ]),
             // Encoding module and submodules
             "encoding" => Some(vec![]),
             "encoding::utf8" => Some(Self::get_encoding_utf8_functions(ALL_PLATFORMS)),
             "encoding::hex" => Some(Self::get_encoding_hex_functions(ALL_PLATFORMS)),
             "encoding::base64" => Some(Self::get_encoding_base64_functions(ALL_PLATFORMS)),
+            "encoding::base32" => Some(Self::get_encoding_base32_functions(ALL_PLATFORMS)),
+            "encoding::base58" => Some(Self::get_encoding_base58_functions(ALL_PLATFORMS)),
+            "encoding::punycode" => Some(Self::get_encoding_punycode_functions(ALL_PLATFORMS)),
             "encoding::url" => Some(Self::get_encoding_url_functions(ALL_PLATFORMS)),
             "encoding::json" => Some(Self::get_encoding_json_functions(ALL_PLATFORMS)),
             "encoding::toml" => Some(Self::get_encoding_toml_functions(ALL_PLATFORMS)),
             "encoding::yaml" => Some(Self::get_encoding_yaml_functions(ALL_PLATFORMS)),
+            "encoding::csv" => Some(Self::get_encoding_csv_functions(ALL_PLATFORMS)),
+            "encoding::msgpack" => Some(Self::get_encoding_msgpack_functions(ALL_PLATFORMS)),
+            "encoding::cbor" => Some(Self::get_encoding_cbor_functions(ALL_PLATFORMS)),
             "encoding::binary" => Some(Self::get_encoding_binary_functions(ALL_PLATFORMS)),
+            "compress" => Some(Self::get_compress_functions(ALL_PLATFORMS, NATIVE_EDGE)),
+            "validate" => Some(Self::get_validate_functions(ALL_PLATFORMS)),
+            "money" => Some(Self::get_money_functions(ALL_PLATFORMS)),
+            "geo" => Some(Self::get_geo_functions(ALL_PLATFORMS)),
+            "regex" => Some(Self::get_regex_functions(ALL_PLATFORMS)),
+            "image" => Some(Self::get_image_functions(ALL_PLATFORMS, NATIVE_EDGE)),
             // Net module hierarchy - strict: parent modules expose only submodules, not functions
             // Parent modules - no functions, only submodules
             "net" => Some(vec![]),
@@ -3467,14 +5674,104 @@ impl<'a> TypeChecker<'a> {
             "net::http::server" => Some(Self::get_net_http_server_functions(NATIVE_EDGE)),
             "net::http::middleware" => Some(Self::get_net_http_middleware_functions(NATIVE_EDGE)),
             "net::tls" => Some(Self::get_net_tls_functions(NATIVE_EDGE)),
+            "net::s3" => Some(Self::get_net_s3_functions(NATIVE_EDGE)),
+            "cloud" => Some(vec![]),
+            "cloud::metadata" => Some(Self::get_cloud_metadata_functions(NATIVE_EDGE)),
             "db" => Some(vec![]),
             "db::sqlite" => Some(Self::get_db_sqlite_functions(NATIVE_EDGE)),
+            "doc" => Some(vec![]),
+            "doc::pdf" => Some(Self::get_doc_pdf_functions(ALL_PLATFORMS, NATIVE_EDGE)),
+            "doc::xlsx" => Some(Self::get_doc_xlsx_functions(ALL_PLATFORMS, NATIVE_EDGE)),
+            "text" => Some(vec![]),
+            "text::markdown" => Some(Self::get_text_markdown_functions(ALL_PLATFORMS)),
             // Crypto module
             "crypto" => Some(Self::get_crypto_functions(NATIVE_EDGE)),
+            "crypto::otp" => Some(Self::get_crypto_otp_functions(NATIVE_EDGE)),
             _ => None,
         }
     }
 
+    fn get_net_s3_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new(
+                "configure",
+                vec![
+                    ("endpoint", Type::String),
+                    ("region", Type::String),
+                    ("access_key", Type::String),
+                    ("secret_key", Type::String),
+                ],
+                Type::Unit,
+                platforms,
+            ),
+            StdModuleFn::new("reset_config", vec![], Type::Unit, platforms),
+            StdModuleFn::throwing(
+                "put_object",
+                vec![("bucket", Type::String), ("key", Type::String), ("data", Type::Bytes)],
+                Type::Unit,
+                vec!["NetworkError", "TimeoutError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "put_file",
+                vec![("bucket", Type::String), ("key", Type::String), ("path", Type::String)],
+                Type::Unit,
+                vec!["IOError", "NetworkError", "TimeoutError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "get_object",
+                vec![("bucket", Type::String), ("key", Type::String)],
+                Type::Bytes,
+                vec!["NetworkError", "TimeoutError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "list",
+                vec![("bucket", Type::String), ("prefix", Type::String)],
+                Type::array(Type::String),
+                vec!["NetworkError", "TimeoutError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "presign_url",
+                vec![
+                    ("method", Type::String),
+                    ("bucket", Type::String),
+                    ("key", Type::String),
+                    ("expires_secs", Type::Int),
+                ],
+                Type::String,
+                vec!["NetworkError"],
+                platforms,
+            ),
+        ]
+    }
+
+    fn get_cloud_metadata_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::new("provider", vec![], Type::String, platforms),
+            StdModuleFn::throwing("region", vec![], Type::String, vec!["NetworkError"], platforms),
+            StdModuleFn::throwing("instance_id", vec![], Type::String, vec!["NetworkError"], platforms),
+            StdModuleFn::throwing(
+                "credentials",
+                vec![],
+                Type::map(Type::String, Type::String),
+                vec!["NetworkError"],
+                platforms,
+            ),
+            StdModuleFn::throwing("get", vec![("path", Type::String)], Type::String, vec!["NetworkError"], platforms),
+            StdModuleFn::throwing(
+                "set_endpoint",
+                vec![("provider", Type::String), ("endpoint", Type::String)],
+                Type::Unit,
+                vec!["NetworkError"],
+                platforms,
+            ),
+            StdModuleFn::new("reset", vec![], Type::Unit, platforms),
+        ]
+    }
+
     fn get_db_sqlite_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
             StdModuleFn::throwing(
@@ -3631,6 +5928,32 @@ impl<'a> TypeChecker<'a> {
         ]
     }
 
+    fn get_crypto_otp_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
+        vec![
+            StdModuleFn::throwing(
+                "hotp",
+                vec![("secret", Type::String), ("counter", Type::Int)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "totp_now",
+                vec![("secret", Type::String), ("period", Type::Int), ("digits", Type::Int)],
+                Type::String,
+                vec!["DecodeError"],
+                platforms,
+            ),
+            StdModuleFn::throwing(
+                "totp_verify",
+                vec![("code", Type::String), ("secret", Type::String), ("skew", Type::Int)],
+                Type::Bool,
+                vec!["DecodeError"],
+                platforms,
+            ),
+        ]
+    }
+
     fn get_crypto_functions(platforms: &'static [Platform]) -> Vec<StdModuleFn> {
         vec![
             StdModuleFn::new("md5", vec![("data", Type::Bytes)], Type::Bytes, platforms),
@@ -3643,44 +5966,44 @@ impl<'a> TypeChecker<'a> {
             StdModuleFn::new("sha512_hex", vec![("data", Type::Bytes)], Type::String, platforms),
             StdModuleFn::new(
                 "hmac_sha256",
-                vec![("key", Type::Bytes), ("data", Type::Bytes)],
+                vec![("key", Type::Secret), ("data", Type::Bytes)],
                 Type::Bytes,
                 platforms,
             ),
             StdModuleFn::new(
                 "hmac_sha256_hex",
-                vec![("key", Type::Bytes), ("data", Type::Bytes)],
+                vec![("key", Type::Secret), ("data", Type::Bytes)],
                 Type::String,
                 platforms,
             ),
             StdModuleFn::new(
                 "hmac_sha512",
-                vec![("key", Type::Bytes), ("data", Type::Bytes)],
+                vec![("key", Type::Secret), ("data", Type::Bytes)],
                 Type::Bytes,
                 platforms,
             ),
             StdModuleFn::new(
                 "hmac_sha512_hex",
-                vec![("key", Type::Bytes), ("data", Type::Bytes)],
+                vec![("key", Type::Secret), ("data", Type::Bytes)],
                 Type::String,
                 platforms,
             ),
             StdModuleFn::new(
                 "hmac_verify_sha256",
-                vec![("key", Type::Bytes), ("data", Type::Bytes), ("mac", Type::Bytes)],
+                vec![("key", Type::Secret), ("data", Type::Bytes), ("mac", Type::Bytes)],
                 Type::Bool,
                 platforms,
             ),
             StdModuleFn::new(
                 "hmac_verify_sha512",
-                vec![("key", Type::Bytes), ("data", Type::Bytes), ("mac", Type::Bytes)],
+                vec![("key", Type::Secret), ("data", Type::Bytes), ("mac", Type::Bytes)],
                 Type::Bool,
                 platforms,
             ),
             StdModuleFn::new(
                 "pbkdf2_sha256",
                 vec![
-                    ("password", Type::Bytes),
+                    ("password", Type::Secret),
                     ("salt", Type::Bytes),
                     ("iterations", Type::Int),
                     ("key_len", Type::Int),
@@ -3689,6 +6012,26 @@ impl<'a> TypeChecker<'a> {
                 platforms,
             ),
             StdModuleFn::new("random_bytes", vec![("n", Type::Int)], Type::Bytes, platforms),
+            StdModuleFn::new(
+                "secret_from_string",
+                vec![("s", Type::String)],
+                Type::Secret,
+                platforms,
+            ),
+            StdModuleFn::new(
+                "secret_from_bytes",
+                vec![("data", Type::Bytes)],
+                Type::Secret,
+                platforms,
+            ),
+            StdModuleFn::new("secret_expose", vec![("s", Type::Secret)], Type::String, platforms),
+            StdModuleFn::new("secret_len", vec![("s", Type::Secret)], Type::Int, platforms),
+            StdModuleFn::new(
+                "secret_equals",
+                vec![("a", Type::Secret), ("b", Type::Secret)],
+                Type::Bool,
+                platforms,
+            ),
         ]
     }
 
@@ -3734,178 +6077,21 @@ impl<'a> TypeChecker<'a> {
             }
         }
 
-        let source_text = match std::fs::read_to_string(&file_path) {
-            Ok(s) => s,
-            Err(e) => {
-                self.errors.push(TypeError::PackageError {
-                    package: package_name.to_string(),
-                    reason: format!("cannot read {}: {}", file_path.display(), e),
-                    span,
-                });
-                return;
-            }
-        };
-
-        let old_dir = self.source_dir.take();
-        self.source_dir = file_path.parent().map(|p| p.to_path_buf());
-
-        let tokens = crate::lexer::tokenize_with_interner(&source_text, self.interner);
-        let arena = crate::ast::AstArena::new();
-        let parse_result = crate::parser::parse(&tokens, &source_text, &arena);
-
-        if !parse_result.errors.is_empty() {
+        if !file_path.exists() {
             self.errors.push(TypeError::PackageError {
                 package: package_name.to_string(),
-                reason: format!("parse errors in {}", file_path.display()),
+                reason: format!("cannot read {}: no such file", file_path.display()),
                 span,
             });
-            self.source_dir = old_dir;
             return;
         }
 
-        let mut pub_functions: Vec<(String, Vec<(String, Type)>, Type, bool)> = Vec::new();
-        let mut pub_type_spurs: Vec<lasso::Spur> = Vec::new();
-
-        for item in &parse_result.ast.items {
-            match item {
-                Item::Function(func) if func.is_public && func.receiver.is_none() => {
-                    let name = self.interner.resolve(&func.name.symbol).to_string();
-                    let params: Vec<_> = func
-                        .params
-                        .iter()
-                        .map(|p| {
-                            let pname = self.interner.resolve(&p.name.symbol).to_string();
-                            let pty = self.convert_type(&p.ty);
-                            (pname, pty)
-                        })
-                        .collect();
-                    let return_ty = func
-                        .return_ty
-                        .as_ref()
-                        .map(|t| self.convert_type(t))
-                        .unwrap_or(Type::Unit);
-                    pub_functions.push((name, params, return_ty, false));
-                }
-                Item::Use(sub_use) => {
-                    self.resolve_use_item(sub_use);
-                }
-                _ => {
-                    self.collect_item_definition(item);
-                    let type_spur = match item {
-                        Item::Struct(s) if s.is_public => Some(s.name.symbol),
-                        Item::Enum(e) if e.is_public => Some(e.name.symbol),
-                        Item::Interface(i) if i.is_public => Some(i.name.symbol),
-                        Item::Exception(e) if e.is_public => Some(e.name.symbol),
-                        Item::TypeAlias(a) if a.is_public => Some(a.name.symbol),
-                        _ => None,
-                    };
-                    if let Some(spur) = type_spur {
-                        pub_type_spurs.push(spur);
-                    }
-                }
-            }
+        if self.module_stack.is_empty() {
+            self.import_span = span;
         }
-
-        self.source_dir = old_dir;
-
-        let module_name = path.last().unwrap();
-        let module_spur = self.interner.get_or_intern(module_name.as_str());
-
-        match items {
-            UseItems::All => {
-                for (name, params, return_ty, is_variadic) in &pub_functions {
-                    let spur = self.interner.get_or_intern(name.as_str());
-                    let params: Vec<_> = params
-                        .iter()
-                        .map(|(pname, pty)| {
-                            let pspur = self.interner.get_or_intern(pname.as_str());
-                            (pspur, pty.clone())
-                        })
-                        .collect();
-                    let sig = FunctionSig {
-                        name: spur,
-                        type_params: vec![],
-                        params,
-                        return_ty: return_ty.clone(),
-                        throws: vec![],
-                        is_public: true,
-                        is_variadic: *is_variadic,
-                        span: crate::source::Span::dummy(),
-                        module: Some(path.join("::")),
-                        platforms: None,
-                    };
-                    self.symbols
-                        .register_module(module_spur)
-                        .add_function(sig.clone());
-                    self.symbols.import_function(sig);
-                }
-                for type_spur in &pub_type_spurs {
-                    if let Some(type_def) = self.symbols.get_type(*type_spur) {
-                        let type_def = type_def.clone();
-                        self.symbols
-                            .register_module(module_spur)
-                            .define_type(*type_spur, type_def);
-                    }
-                }
-            }
-            UseItems::Specific(entries) => {
-                for entry in entries {
-                    let entry_name = self.interner.resolve(&entry.name.symbol).to_string();
-                    let found = pub_functions
-                        .iter()
-                        .find(|(name, _, _, _)| *name == entry_name);
-                    match found {
-                        Some((_, params, return_ty, is_variadic)) => {
-                            let spur = entry.name.symbol;
-                            let params: Vec<_> = params
-                                .iter()
-                                .map(|(pname, pty)| {
-                                    let pspur = self.interner.get_or_intern(pname.as_str());
-                                    (pspur, pty.clone())
-                                })
-                                .collect();
-                            let sig = FunctionSig {
-                                name: spur,
-                                type_params: vec![],
-                                params,
-                                return_ty: return_ty.clone(),
-                                throws: vec![],
-                                is_public: true,
-                                is_variadic: *is_variadic,
-                                span: crate::source::Span::dummy(),
-                                module: Some(path.join("::")),
-                                platforms: None,
-                            };
-                            self.symbols
-                                .register_module(module_spur)
-                                .add_function(sig.clone());
-                            self.symbols.import_function(sig);
-                        }
-                        None => {
-                            if pub_type_spurs.contains(&entry.name.symbol) {
-                                if let Some(type_def) = self.symbols.get_type(entry.name.symbol) {
-                                    let type_def = type_def.clone();
-                                    self.symbols
-                                        .register_module(module_spur)
-                                        .define_type(entry.name.symbol, type_def);
-                                }
-                            } else {
-                                self.errors.push(TypeError::PrivateSymbol {
-                                    module: path.join("::"),
-                                    symbol: entry_name,
-                                    span: entry.span,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
+        if let Some(exports) = self.load_module(&file_path, &path.join("::"), span) {
+            self.bind_module_exports(path, &exports, Some(items));
         }
-
-        self.imported_modules.push(ImportedModule {
-            source_text,
-            file_path,
-        });
     }
 
     fn resolve_local_module(
@@ -3914,8 +6100,20 @@ impl<'a> TypeChecker<'a> {
         items: &UseItems,
         span: crate::source::Span,
     ) {
-        let source_dir = match &self.source_dir {
-            Some(d) => d.clone(),
+        // Errors in modules imported by other modules are reported at the
+        // import in the file being compiled, whose source is the one shown
+        if self.module_stack.is_empty() {
+            self.import_span = span;
+        }
+        let span = self.import_span;
+
+        // `crate::` paths start at the crate root, others next to the importing file
+        let (base_dir, segments) = match path.split_first() {
+            Some((first, rest)) if first == "crate" => (self.crate_root.clone(), rest),
+            _ => (self.source_dir.clone(), path),
+        };
+        let base_dir = match base_dir {
+            Some(d) => d,
             None => {
                 self.errors.push(TypeError::ModuleFileError {
                     path: path.join("::"),
@@ -3926,22 +6124,75 @@ impl<'a> TypeChecker<'a> {
             }
         };
 
-        let mut file_path = source_dir;
-        for segment in path {
+        let mut module_path = path.to_vec();
+        let mut file_path = Self::module_file(&base_dir, segments);
+        let mut import_items = Some(items);
+
+        // `use crate::db;` names the module itself, used as `db::connect()`
+        if let UseItems::Specific(entries) = items
+            && let [entry] = entries.as_slice()
+            && entry.alias.is_none()
+            && (segments.is_empty() || !file_path.exists())
+        {
+            let name = self.interner.resolve(&entry.name.symbol).to_string();
+            let mut nested = segments.to_vec();
+            nested.push(name.clone());
+            let nested_file = Self::module_file(&base_dir, &nested);
+            if nested_file.exists() {
+                file_path = nested_file;
+                module_path.push(name);
+                import_items = None;
+            }
+        }
+
+        let module_name = module_path.join("::");
+        if let Some(exports) = self.load_module(&file_path, &module_name, span) {
+            self.bind_module_exports(&module_path, &exports, import_items);
+        }
+    }
+
+    /// The file of module `segments` under `base_dir`: `a/b.nm`, or
+    /// `a/b/main.nm` when `b` is a directory
+    fn module_file(base_dir: &Path, segments: &[String]) -> PathBuf {
+        let mut file_path = base_dir.to_path_buf();
+        for segment in segments {
             file_path.push(segment);
         }
+        let main_file = file_path.join("main.nm");
         file_path.set_extension("nm");
+        if !file_path.exists() && main_file.exists() {
+            return main_file;
+        }
+        file_path
+    }
 
-        if !file_path.exists() {
-            let mut dir_path = file_path.clone();
-            dir_path.set_extension("");
-            let main_file = dir_path.join("main.nm");
-            if main_file.exists() {
-                file_path = main_file;
-            }
+    /// Collect the definitions of a module file and work out its exports.
+    /// Each file is loaded once; importing it again reuses its exports.
+    fn load_module(
+        &mut self,
+        file_path: &Path,
+        module_name: &str,
+        span: crate::source::Span,
+    ) -> Option<ModuleExports> {
+        let key = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+
+        if let Some(start) = self.module_stack.iter().position(|p| *p == key) {
+            let cycle = self.module_stack[start..]
+                .iter()
+                .chain(std::iter::once(&key))
+                .map(|p| self.module_display_path(p))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            self.errors.push(TypeError::ImportCycle { cycle, span });
+            return None;
+        }
+        if let Some(exports) = self.loaded_modules.get(&key) {
+            return Some(exports.clone());
         }
 
-        let source_text = match std::fs::read_to_string(&file_path) {
+        let source_text = match std::fs::read_to_string(file_path) {
             Ok(s) => s,
             Err(e) => {
                 self.errors.push(TypeError::ModuleFileError {
@@ -3949,7 +6200,7 @@ impl<'a> TypeChecker<'a> {
                     reason: e.to_string(),
                     span,
                 });
-                return;
+                return None;
             }
         };
 
@@ -3963,165 +6214,182 @@ impl<'a> TypeChecker<'a> {
                 reason: "parse errors in module file".to_string(),
                 span,
             });
-            return;
+            return None;
         }
 
-        let mut pub_functions: Vec<(String, Vec<(String, Type)>, Type, bool)> = Vec::new();
-        let mut pub_type_spurs: Vec<lasso::Spur> = Vec::new();
+        // Imports inside the module resolve relative to the module's own file,
+        // and the functions they bring into scope are only the module's
+        self.module_stack.push(key.clone());
+        let function_scope = self.symbols.function_scope();
+        let old_dir = std::mem::replace(
+            &mut self.source_dir,
+            file_path.parent().map(Path::to_path_buf),
+        );
+
+        let mut exports = ModuleExports::default();
+        let mut private_types = Vec::new();
 
+        // Types first, so function signatures can refer to them in any order
         for item in &parse_result.ast.items {
-            match item {
-                Item::Function(func) if func.is_public && func.receiver.is_none() => {
-                    let name = self.interner.resolve(&func.name.symbol).to_string();
-                    let params: Vec<_> = func
-                        .params
-                        .iter()
-                        .map(|p| {
-                            let pname = self.interner.resolve(&p.name.symbol).to_string();
-                            let pty = self.convert_type(&p.ty);
-                            (pname, pty)
-                        })
-                        .collect();
-                    let return_ty = func
-                        .return_ty
-                        .as_ref()
-                        .map(|t| self.convert_type(t))
-                        .unwrap_or(Type::Unit);
-                    pub_functions.push((name, params, return_ty, false));
-                }
-                _ => {
-                    self.collect_item_definition(item);
-                    let type_spur = match item {
-                        Item::Struct(s) if s.is_public => Some(s.name.symbol),
-                        Item::Enum(e) if e.is_public => Some(e.name.symbol),
-                        Item::Interface(i) if i.is_public => Some(i.name.symbol),
-                        Item::Exception(e) if e.is_public => Some(e.name.symbol),
-                        Item::TypeAlias(a) if a.is_public => Some(a.name.symbol),
-                        _ => None,
-                    };
-                    if let Some(spur) = type_spur {
-                        pub_type_spurs.push(spur);
-                    }
+            if matches!(item, Item::Function(func) if func.receiver.is_none()) {
+                continue;
+            }
+            if let Item::Use(use_item) = item
+                && use_item.is_public
+            {
+                self.collect_reexports(use_item, &mut exports);
+                continue;
+            }
+            self.collect_item_definition(item);
+            let (name, is_public) = match item {
+                Item::Struct(s) => (s.name.symbol, s.is_public),
+                Item::Enum(e) => (e.name.symbol, e.is_public),
+                Item::Interface(i) => (i.name.symbol, i.is_public),
+                Item::Exception(e) => (e.name.symbol, e.is_public),
+                Item::TypeAlias(a) => (a.name.symbol, a.is_public),
+                _ => continue,
+            };
+            if is_public {
+                if let Some(def) = self.symbols.get_type(name) {
+                    exports.types.push((name, def.clone()));
                 }
+            } else {
+                private_types.push(name);
             }
         }
 
-        let module_name = path.last().unwrap();
-        let module_spur = match self.interner.get(module_name.as_str()) {
-            Some(s) => s,
-            None => {
-                self.imported_modules.push(ImportedModule {
-                    source_text,
-                    file_path,
-                });
-                return;
-            }
-        };
-
-        match items {
-            UseItems::All => {
-                for (name, params, return_ty, is_variadic) in &pub_functions {
-                    if let Some(spur) = self.interner.get(name.as_str()) {
-                        let params: Vec<_> = params
-                            .iter()
-                            .map(|(pname, pty)| {
-                                let pspur = self.interner.get(pname.as_str()).unwrap_or(spur);
-                                (pspur, pty.clone())
-                            })
-                            .collect();
-                        let sig = FunctionSig {
-                            name: spur,
-                            type_params: vec![],
-                            params,
-                            return_ty: return_ty.clone(),
-                            throws: vec![],
-                            is_public: true,
-                            is_variadic: *is_variadic,
-                            span: crate::source::Span::dummy(),
-                            module: Some(path.join("::")),
-                            platforms: None,
-                        };
-                        self.symbols
-                            .register_module(module_spur)
-                            .add_function(sig.clone());
-                        self.symbols.import_function(sig.clone());
-                    }
-                }
-                for type_spur in &pub_type_spurs {
-                    if let Some(type_def) = self.symbols.get_type(*type_spur) {
-                        let type_def = type_def.clone();
-                        self.symbols
-                            .register_module(module_spur)
-                            .define_type(*type_spur, type_def);
-                    }
-                }
+        for item in &parse_result.ast.items {
+            let Item::Function(func) = item else {
+                continue;
+            };
+            if func.receiver.is_some() {
+                continue;
             }
-            UseItems::Specific(entries) => {
-                for entry in entries {
-                    let entry_name = self.interner.resolve(&entry.name.symbol).to_string();
-                    let found = pub_functions
-                        .iter()
-                        .find(|(name, _, _, _)| *name == entry_name);
-                    match found {
-                        Some((_, params, return_ty, is_variadic)) => {
-                            let spur = entry.name.symbol;
-                            let params: Vec<_> = params
-                                .iter()
-                                .map(|(pname, pty)| {
-                                    let pspur = self.interner.get(pname.as_str()).unwrap_or(spur);
-                                    (pspur, pty.clone())
-                                })
-                                .collect();
-                            let sig = FunctionSig {
-                                name: spur,
-                                type_params: vec![],
-                                params,
-                                return_ty: return_ty.clone(),
-                                throws: vec![],
-                                is_public: true,
-                                is_variadic: *is_variadic,
-                                span: crate::source::Span::dummy(),
-                                module: Some(path.join("::")),
-                                platforms: None,
-                            };
-                            self.symbols
-                                .register_module(module_spur)
-                                .add_function(sig.clone());
-                            if self.symbols.has_function(sig.name) {
-                                self.symbols.mark_ambiguous(sig.name);
-                                self.errors.push(TypeError::DuplicateImport {
-                                    name: entry_name.clone(),
-                                    span: entry.span,
-                                });
-                            } else {
-                                self.symbols.import_function(sig.clone());
-                            }
-                        }
-                        None => {
-                            if pub_type_spurs.contains(&entry.name.symbol) {
-                                if let Some(type_def) = self.symbols.get_type(entry.name.symbol) {
-                                    let type_def = type_def.clone();
-                                    self.symbols
-                                        .register_module(module_spur)
-                                        .define_type(entry.name.symbol, type_def);
-                                }
-                            } else {
-                                self.errors.push(TypeError::PrivateSymbol {
-                                    module: path.join("::"),
-                                    symbol: entry_name,
-                                    span: entry.span,
-                                });
-                            }
-                        }
-                    }
-                }
+            if !func.is_public {
+                exports.private.push(func.name.symbol);
+                continue;
             }
+            let type_params = func
+                .generics
+                .iter()
+                .map(|g| TypeParam {
+                    name: g.name.symbol,
+                    bounds: g.bounds.iter().map(|b| self.convert_type(b)).collect(),
+                })
+                .collect();
+            let params = func
+                .params
+                .iter()
+                .map(|p| (p.name.symbol, self.convert_type(&p.ty)))
+                .collect();
+            let return_ty = func
+                .return_ty
+                .as_ref()
+                .map(|t| self.convert_type(t))
+                .unwrap_or(Type::Unit);
+            let throws = func.throws.iter().map(|t| self.convert_type(t)).collect();
+            exports.functions.push(FunctionSig {
+                name: func.name.symbol,
+                type_params,
+                params,
+                return_ty,
+                throws,
+                is_public: true,
+                is_variadic: false,
+                span: crate::source::Span::dummy(),
+                module: None,
+                platforms: None,
+                attributes: func.attributes.clone(),
+            });
+        }
+
+        // Private types stay usable by the module's own signatures above,
+        // but are out of scope for the files importing it
+        for name in private_types {
+            self.symbols.hide_type(name, module_name.to_string());
+            exports.private.push(name);
         }
 
+        self.source_dir = old_dir;
+        self.module_stack.pop();
+        self.symbols.restore_function_scope(function_scope);
+
+        self.loaded_modules.insert(key, exports.clone());
         self.imported_modules.push(ImportedModule {
             source_text,
-            file_path,
+            file_path: file_path.to_path_buf(),
         });
+        Some(exports)
+    }
+
+    /// Resolve a module's `pub use` and add what it imports to the module's
+    /// exports, so importers see those items as the module's own
+    fn collect_reexports(&mut self, use_item: &ast::UseItem, exports: &mut ModuleExports) {
+        let before = self.symbols.function_scope();
+        let types_before: HashSet<Spur> = self.symbols.type_names().copied().collect();
+        self.resolve_use_item(use_item);
+
+        for sig in self.symbols.functions_since(&before) {
+            if let Some(original) = self.symbols.original_name(sig.name) {
+                exports.aliases.push((sig.name, original));
+            }
+            exports.functions.push(sig);
+        }
+        let new_types: Vec<Spur> = self
+            .symbols
+            .type_names()
+            .filter(|name| !types_before.contains(name))
+            .copied()
+            .collect();
+        for name in new_types {
+            if let Some(def) = self.symbols.get_type(name) {
+                exports.types.push((name, def.clone()));
+            }
+        }
+    }
+
+    /// Register a module's exports under its name for qualified access and
+    /// import the requested items; `None` imports only the module name
+    fn bind_module_exports(
+        &mut self,
+        module_path: &[String],
+        exports: &ModuleExports,
+        items: Option<&UseItems>,
+    ) {
+        let module_name = module_path.join("::");
+        let module_spur = self
+            .interner
+            .get_or_intern(module_path.last().map(String::as_str).unwrap_or_default());
+
+        let namespace = self.symbols.register_module(module_spur);
+        for sig in &exports.functions {
+            // Re-exported functions keep the module that defines them
+            namespace.add_function(FunctionSig {
+                module: sig.module.clone().or_else(|| Some(module_name.clone())),
+                ..sig.clone()
+            });
+        }
+        for (name, def) in &exports.types {
+            namespace.define_type(*name, def.clone());
+        }
+        namespace.private.extend(exports.private.iter().copied());
+        namespace.aliases.extend(exports.aliases.iter().copied());
+        let namespace = namespace.clone();
+
+        if let Some(items) = items {
+            self.import_module_items(&namespace, &module_name, items);
+        }
+    }
+
+    /// A module file's path relative to the crate root, for diagnostics
+    fn module_display_path(&self, file_path: &Path) -> String {
+        self.crate_root
+            .as_ref()
+            .and_then(|root| root.canonicalize().ok())
+            .and_then(|root| file_path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| file_path.to_path_buf())
+            .display()
+            .to_string()
     }
 
     fn collect_function(&mut self, func: &ast::FunctionItem) {
@@ -4171,6 +6439,7 @@ impl<'a> TypeChecker<'a> {
             span: func.span,
             module: None,
             platforms,
+            attributes: func.attributes.clone(),
         });
     }
 
@@ -4200,6 +6469,7 @@ impl<'a> TypeChecker<'a> {
             span: ext.span,
             module: None,
             platforms: None,
+            attributes: Vec::new(),
         });
     }
 
@@ -4210,6 +6480,7 @@ impl<'a> TypeChecker<'a> {
         let type_name = match &receiver_ty {
             Type::Generic(name, _) => *name,
             Type::Struct(s) => s.name,
+            Type::Enum(e) => e.name,
             _ => return,
         };
 
@@ -4247,6 +6518,7 @@ impl<'a> TypeChecker<'a> {
                 throws,
                 is_public: func.is_public,
                 span: func.span,
+                attributes: func.attributes.clone(),
             },
         );
     }
@@ -4358,6 +6630,9 @@ impl<'a> TypeChecker<'a> {
                     params,
                     return_ty,
                     throws,
+                    is_static: m.is_static,
+                    has_default: m.body.is_some(),
+                    span: m.span,
                 }
             })
             .collect();
@@ -4417,6 +6692,10 @@ impl<'a> TypeChecker<'a> {
         );
     }
 
+    fn evaluate_constants(&mut self, file: &SourceFile) {
+        consteval::evaluate_constants(file, self.interner, &mut self.annotations, &mut self.errors);
+    }
+
     fn check_items(&mut self, file: &SourceFile) {
         // Pass 1: Process all top-level statements (global variables)
         // so they're visible to all functions regardless of source order
@@ -4428,14 +6707,87 @@ impl<'a> TypeChecker<'a> {
 
         // Pass 2: Process functions and modules
         for item in &file.items {
+            attributes::check_attributes(item, self.interner, &mut self.errors);
             match item {
                 Item::Function(func) => self.check_function(func),
+                Item::Interface(i) => self.check_interface(i),
                 Item::Mod(m) => self.check_mod(m),
                 _ => {}
             }
         }
     }
 
+    /// Check the default method bodies and static functions of an interface.
+    /// Inside a default body `self` has the interface type, so only the
+    /// interface's own methods are available on it.
+    fn check_interface(&mut self, i: &ast::InterfaceItem) {
+        let self_ty = match self.symbols.get_type(i.name.symbol) {
+            Some(TypeDef::Interface(def)) => Type::Interface(self.symbols.to_interface_type(def)),
+            _ => return,
+        };
+        let self_symbol = self.interner.get_or_intern("self");
+
+        for method in &i.methods {
+            let Some(body) = &method.body else {
+                if method.is_static {
+                    self.errors.push(TypeError::Custom {
+                        message: format!(
+                            "static interface function '{}' must have a body",
+                            self.interner.resolve(&method.name.symbol)
+                        ),
+                        span: method.span,
+                    });
+                }
+                continue;
+            };
+
+            let return_ty = method
+                .return_ty
+                .as_ref()
+                .map(|t| self.convert_type(t))
+                .unwrap_or(Type::Unit);
+            let throws = method.throws.iter().map(|t| self.convert_type(t)).collect();
+            let type_params: Vec<TypeParam> = method
+                .generics
+                .iter()
+                .map(|g| TypeParam {
+                    name: g.name.symbol,
+                    bounds: g.bounds.iter().map(|b| self.convert_type(b)).collect(),
+                })
+                .collect();
+
+            self.env.enter_function(return_ty, throws, &type_params);
+            self.env.push_scope();
+
+            if !method.is_static {
+                self.env.define(self_symbol, self_ty.clone(), true);
+            }
+            for param in &method.params {
+                let ty = self.convert_type(&param.ty);
+                self.env.define(param.name.symbol, ty, false);
+            }
+
+            let mut inferrer = TypeInferrer {
+                env: &mut self.env,
+                symbols: &self.symbols,
+                interner: self.interner,
+                next_var_id: &mut self.next_var_id,
+                errors: &mut self.errors,
+                warnings: &mut self.warnings,
+                annotations: &mut self.annotations,
+                switch_scrutinee: None,
+                in_catch_context: false,
+                target: self.target,
+            };
+            for stmt in &body.statements {
+                inferrer.check_stmt(stmt);
+            }
+
+            self.env.pop_scope();
+            self.env.exit_function();
+        }
+    }
+
     fn check_mod<'ast>(&mut self, m: &'ast ast::ModuleItem<'ast>) {
         let name_spur = m.name.symbol;
         self.symbols.enter_module(name_spur);
@@ -4446,6 +6798,7 @@ impl<'a> TypeChecker<'a> {
                 }
             }
             for item in items {
+                attributes::check_attributes(item, self.interner, &mut self.errors);
                 match item {
                     Item::Function(func) => self.check_function(func),
                     Item::Mod(inner_m) => self.check_mod(inner_m),
@@ -4465,6 +6818,7 @@ impl<'a> TypeChecker<'a> {
             interner: self.interner,
             next_var_id: &mut self.next_var_id,
             errors: &mut self.errors,
+            warnings: &mut self.warnings,
             annotations: &mut self.annotations,
             switch_scrutinee: None,
             in_catch_context: false,
@@ -4491,6 +6845,7 @@ impl<'a> TypeChecker<'a> {
             let type_name = match &recv_ty {
                 Type::Generic(name, _) => Some(*name),
                 Type::Struct(s) => Some(s.name),
+                Type::Enum(e) => Some(e.name),
                 _ => None,
             };
             type_name
@@ -4525,6 +6880,7 @@ impl<'a> TypeChecker<'a> {
                 interner: self.interner,
                 next_var_id: &mut self.next_var_id,
                 errors: &mut self.errors,
+                warnings: &mut self.warnings,
                 annotations: &mut self.annotations,
                 switch_scrutinee: None,
                 in_catch_context: false,
@@ -4569,9 +6925,30 @@ impl<'a> TypeChecker<'a> {
                 if name == "stack_frame" {
                     return Type::StackFrame;
                 }
+                if name == "iface_info" {
+                    return Type::IfaceInfo;
+                }
+                if name == "fs_event" {
+                    return Type::FsEvent;
+                }
                 if name == "json" {
                     return Type::Json;
                 }
+                if name == "secret" {
+                    return Type::Secret;
+                }
+                if name == "money" {
+                    return Type::Money;
+                }
+                if name == "string_builder" {
+                    return Type::StringBuilder;
+                }
+                if name == "waitgroup" {
+                    return Type::Waitgroup;
+                }
+                if name == "atomic_int" {
+                    return Type::Atomic(Box::new(Type::Int));
+                }
 
                 if let Some(def) = self.symbols.get_type(ident.symbol) {
                     match def {
@@ -4588,6 +6965,16 @@ impl<'a> TypeChecker<'a> {
             ast::NamlType::Generic(ident, args) => {
                 let converted_args: Vec<Type> = args.iter().map(|a| self.convert_type(a)).collect();
 
+                if let [elem] = converted_args.as_slice() {
+                    match self.interner.resolve(&ident.symbol) {
+                        "set" => return Type::Set(Box::new(elem.clone())),
+                        "priority_queue" => return Type::PriorityQueue(Box::new(elem.clone())),
+                        "deque" => return Type::Deque(Box::new(elem.clone())),
+                        "iterator" => return Type::Iterator(Box::new(elem.clone())),
+                        _ => {}
+                    }
+                }
+
                 // Check if this is a type alias with type params
                 if let Some(TypeDef::TypeAlias(alias)) = self.symbols.get_type(ident.symbol) {
                     if alias.type_params.len() == converted_args.len() {
@@ -4659,6 +7046,26 @@ impl<'a> TypeChecker<'a> {
                 type_params,
                 type_args,
             ))),
+            Type::Set(inner) => Type::Set(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
+            Type::PriorityQueue(inner) => Type::PriorityQueue(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
+            Type::Deque(inner) => Type::Deque(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
+            Type::Iterator(inner) => Type::Iterator(Box::new(self.substitute_type_args(
+                inner,
+                type_params,
+                type_args,
+            ))),
             Type::Function(ft) => Type::Function(types::FunctionType {
                 params: ft
                     .params
@@ -4703,9 +7110,14 @@ pub fn check_with_types_for_target(
     checker.collect_definitions(file);
     checker.validate_interface_implementations();
     checker.check_items(file);
+    checker.evaluate_constants(file);
+    checker.retain_local_notes(file);
+    let unused = unused::unused_imports(file, checker.interner);
+    checker.warnings.extend(unused);
 
     TypeCheckResult {
         errors: std::mem::take(&mut checker.errors),
+        warnings: std::mem::take(&mut checker.warnings),
         annotations: std::mem::take(&mut checker.annotations),
         symbols: checker.symbols,
         imported_modules: std::mem::take(&mut checker.imported_modules),
@@ -4762,6 +7174,33 @@ mod tests {
         assert!(matches!(errors[0], TypeError::UndefinedVariable { .. }));
     }
 
+    #[test]
+    fn test_undefined_names_suggest_close_matches() {
+        let errors = check_source(
+            "struct Point { x: int, y: int }
+             fn greet() {}
+             fn main() {
+                 var total: int = 1;
+                 var p: Point = Point { x: 1, y: 2 };
+                 var a: int = totl;
+                 var b: int = p.yy;
+                 gret();
+             }",
+        );
+        let suggestions: Vec<_> = errors.iter().map(|e| (e.code(), e.suggestion())).collect();
+        assert_eq!(
+            suggestions,
+            vec![("E0002", Some("total")), ("E0005", Some("y")), ("E0004", Some("greet"))]
+        );
+        assert_eq!(errors[1].notes().len(), 1);
+    }
+
+    #[test]
+    fn test_wrong_arg_count_notes_definition() {
+        let errors = check_source("fn add(a: int, b: int) -> int { return a + b; } fn main() { add(1); }");
+        assert!(matches!(errors[0], TypeError::WrongArgCount { defined_at: Some(_), .. }));
+    }
+
     #[test]
     fn test_valid_if_statement() {
         let errors = check_source("fn main() { if (true) { var x: int = 1; } }");
@@ -4826,6 +7265,372 @@ mod tests {
         assert!(errors.is_empty());
     }
 
+    #[test]
+    fn test_lambda_params_inferred_from_expected_type() {
+        let errors = check_source(
+            "fn apply(f: fn(int) -> int, x: int) -> int { return f(x); }
+             fn map_all<T, U>(xs: [T], f: fn(T) -> U) -> [U] { return []; }
+             fn main() {
+                 var a: int = apply(fn(x) { return x * 2; }, 5);
+                 var f: fn(string) -> int = fn(s) { return 1; };
+                 var b: [bool] = map_all([1, 2], fn(n) { return n > 1; });
+             }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source(
+            "fn apply(f: fn(int) -> int, x: int) -> int { return f(x); }
+             fn main() { var a: int = apply(fn(x) { return x == \"a\"; }, 5); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_empty_literals_take_expected_type() {
+        let errors = check_source(
+            "fn total(xs: [int]) -> int { return 0; }
+             fn keys(m: map<string, int>) -> int { return 0; }
+             fn main() {
+                 var a: int = total([]);
+                 var b: int = keys({});
+                 var m: map<string, [float]> = {};
+                 m[\"x\"] = [];
+             }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_int_literals_default_to_float_in_float_position() {
+        let errors = check_source(
+            "fn scale(v: float) -> float { return v * 2; }
+             fn main() {
+                 var a: float = 1;
+                 var b: float = -3;
+                 var c: [float] = [1, 2.5];
+                 var d: float = scale(4) + 1;
+             }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source("fn main() { var a: int = 1.5; var b: float = 1 << 2; }");
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_generic_enum_variants_and_methods() {
+        let errors = check_source(
+            "enum Result<T, E> { Ok(T), Err(E) }
+             fn (self: Result<T, E>) unwrap_or(fallback: T) -> T {
+                 switch (self) {
+                     case Ok(v): { return v; }
+                     default: { return fallback; }
+                 }
+                 return fallback;
+             }
+             fn parse(s: string) -> Result<int, string> {
+                 if (s == \"1\") { return Result::Ok(1); }
+                 return Result::Err(s);
+             }
+             fn main() {
+                 var r: Result<int, string> = parse(\"1\");
+                 var n: int = r.unwrap_or(0);
+                 var names: Result<string, int> = Result::Ok(\"x\");
+                 var s: string = names.unwrap_or(\"y\");
+                 switch (r) {
+                     case Err(msg): { var m: string = msg; }
+                     default: { }
+                 }
+             }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source(
+            "enum Result<T, E> { Ok(T), Err(E) }
+             fn main() { var r: Result<int, string> = Result::Ok(\"x\"); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_methods_on_enums() {
+        let errors = check_source(
+            "enum Shape { Circle(int), Square(int) }
+             fn (self: Shape) area() -> int {
+                 switch (self) {
+                     case Circle(r): { return 3 * r * r; }
+                     case Square(s): { return s * s; }
+                 }
+                 return 0;
+             }
+             fn main() { var a: int = Shape::Square(2).area(); }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_interface_default_and_static_methods() {
+        let errors = check_source(
+            "interface Named {
+                 fn name() -> string;
+                 fn greet() -> string { return self.name(); }
+                 static fn prefix() -> string { return \"hi\"; }
+             }
+             struct User implements Named { id: int }
+             fn (self: User) name() -> string { return \"user\"; }
+             fn main() {
+                 var u: User = User { id: 1 };
+                 var g: string = u.greet();
+                 var p: string = Named::prefix();
+             }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        // Required methods are still required, and static functions are not
+        // callable on instances
+        let errors = check_source(
+            "interface Named {
+                 fn name() -> string;
+                 static fn prefix() -> string { return \"hi\"; }
+             }
+             struct User implements Named { id: int }
+             fn main() { var u: User = User { id: 1 }; var p: string = u.prefix(); }",
+        );
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(matches!(errors[0], TypeError::MissingInterfaceMethod { .. }));
+    }
+
+    #[test]
+    fn test_conflicting_interface_defaults() {
+        let source = "interface A { fn label() -> string { return \"a\"; } }
+             interface B { fn label() -> string { return \"b\"; } }
+             struct Both implements A, B { id: int }";
+        let errors = check_source(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0035");
+
+        // Defining the method on the struct resolves the conflict
+        let errors = check_source(&format!(
+            "{}\nfn (self: Both) label() -> string {{ return \"both\"; }}",
+            source
+        ));
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    /// Type check `main` as the entry file of a project made of `files`
+    fn check_project(files: &[(&str, &str)], main: &str) -> Vec<TypeError> {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, source) in files {
+            let file = dir.path().join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, source).unwrap();
+        }
+        let (tokens, mut interner) = tokenize(main);
+        let arena = AstArena::new();
+        let result = parse(&tokens, main, &arena);
+        assert!(result.errors.is_empty(), "Parse errors: {:?}", result.errors);
+        check_with_types(&result.ast, &mut interner, Some(dir.path().to_path_buf()), None).errors
+    }
+
+    #[test]
+    fn test_crate_modules_and_visibility() {
+        let files = [
+            (
+                "db.nm",
+                "struct Row { id: int }
+                 pub struct Conn { id: int }
+                 fn next_id() -> int { return 1; }
+                 pub fn connect() -> Conn { return Conn { id: next_id() }; }",
+            ),
+            (
+                "api/users.nm",
+                "use crate::db::{connect};
+                 pub fn user_id() -> int { return connect().id; }",
+            ),
+        ];
+
+        let errors = check_project(
+            &files,
+            "use crate::db;
+             use crate::api::users::{user_id};
+             fn main() { var c: Conn = db::connect(); var n: int = user_id() + c.id; }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_project(&files, "use crate::db::{next_id, conect};");
+        let codes: Vec<&str> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, ["E0029", "E0028"], "{:?}", errors);
+        assert_eq!(errors[1].suggestion(), Some("connect"));
+
+        let errors = check_project(
+            &files,
+            "use crate::db;
+             fn main() { var n: int = db::next_id(); println(Row { id: 1 }); }",
+        );
+        let codes: Vec<&str> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, ["E0029", "E0029"], "{:?}", errors);
+    }
+
+    #[test]
+    fn test_module_import_cycle() {
+        let files = [
+            ("a.nm", "use crate::b::*;\npub fn fa() -> int { return 1; }"),
+            ("b.nm", "use crate::a::*;\npub fn fb() -> int { return 2; }"),
+        ];
+        let errors = check_project(&files, "use crate::a::*;");
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0036");
+        assert!(errors[0].to_string().contains("a.nm -> b.nm -> a.nm"), "{}", errors[0]);
+
+        // A module imported from two places is loaded once
+        let files = [
+            ("base.nm", "pub fn one() -> int { return 1; }"),
+            ("left.nm", "use crate::base::*;\npub fn two() -> int { return one() + 1; }"),
+        ];
+        let errors = check_project(
+            &files,
+            "use crate::left::*;\nuse crate::base::{one};\nfn main() { var n: int = two() + one(); }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_import_alias_and_exclusions() {
+        let errors = check_source(
+            "use std::collections::arrays::{sort as sort_ints};\nuse std::strings::* except { upper };\nfn main() { var xs: [int] = sort_ints([2, 1]); var s: string = lower(\"A\"); }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source(
+            "use std::collections::arrays::{sort as sort_ints};\nfn main() { var xs: [int] = sort([2, 1]); }",
+        );
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0004");
+
+        let errors = check_source(
+            "use std::strings::* except { upper, uppr };\nfn main() { var s: string = upper(\"a\"); }",
+        );
+        let codes: Vec<_> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, ["E0028", "E0004"], "{:?}", errors);
+    }
+
+    #[test]
+    fn test_import_name_clashes() {
+        let errors = check_source(
+            "use std::encoding::hex::{encode};\nuse std::encoding::base64::{encode};",
+        );
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0008");
+        assert!(errors[0].to_string().contains("'encoding::hex' and 'encoding::base64'"), "{}", errors[0]);
+
+        let errors = check_source(
+            "use std::collections::arrays::*;\nuse std::collections::maps::*;\nfn main() { var n: int = count([1]); }",
+        );
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0033");
+        assert!(errors[0].to_string().contains("collections::arrays, collections::maps"), "{}", errors[0]);
+
+        // An explicit import settles which glob import a name comes from
+        let errors = check_source(
+            "use std::collections::arrays::*;\nuse std::collections::maps::*;\nuse std::collections::arrays::{count};\nfn main() { var n: int = count([1]); }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_pub_use_reexports() {
+        let files = [
+            ("inner.nm", "pub fn base() -> int { return 1; }\npub fn other() -> int { return 2; }"),
+            (
+                "facade.nm",
+                "pub use crate::inner::{base};\npub use std::collections::arrays::{sort as sort_ints};\nuse crate::inner::{other};",
+            ),
+        ];
+        let errors = check_project(
+            &files,
+            "use crate::facade::*;\nfn main() { var n: int = base(); var xs: [int] = sort_ints([2, 1]); }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        // Plain `use` stays private to the module
+        let errors = check_project(&files, "use crate::facade::{other};");
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].code(), "E0028");
+    }
+
+    #[test]
+    fn test_attributes() {
+        let errors = check_source(
+            "@test fn checks() {}\n@bench fn timed() {}\n@inline @plugin(fast, 2) fn twice(n: int) -> int { return n * 2; }\n@serialize struct P { x: int }",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let errors = check_source(
+            "@test fn checks(n: int) {}\n@inline struct P { x: int }\n@deprecated(1) fn old() {}\n@bench fn timed() -> int { return 1; }",
+        );
+        let codes: Vec<_> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, ["E0039", "E0039", "E0039", "E0039"], "{:?}", errors);
+
+        let source = "struct P { x: int }\n@deprecated pub fn (self: P) get() -> int { return self.x; }\n@deprecated(\"use sum\") fn add(a: int) -> int { return a; }\nfn main() { var p: P = P { x: 1 }; var n: int = add(p.get()); }";
+        let (tokens, mut interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        let result = check_with_types(&parsed.ast, &mut interner, None, None);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let warnings: Vec<_> = result.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            ["use of deprecated function 'add'", "use of deprecated method 'get'"]
+        );
+        let TypeWarning::Deprecated { note, .. } = &result.warnings[0] else {
+            panic!("expected a deprecation warning");
+        };
+        assert_eq!(note.as_deref(), Some("use sum"));
+
+        let source = "@deprecated(\"renamed\", total) fn sum(a: int) -> int { return a; }\nfn total(a: int) -> int { return a; }\nfn main() { var n: int = sum(1); }";
+        let (tokens, mut interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        let result = check_with_types(&parsed.ast, &mut interner, None, None);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let fix = result.warnings[0].fix().expect("expected a fix");
+        assert_eq!(&source[fix.span.start as usize..fix.span.end as usize], "sum");
+        assert_eq!(fix.replacement, "total");
+    }
+
+    #[test]
+    fn test_unused_imports() {
+        let source = "use std::strings::{upper, lower as low, ltrim};\nuse std::io::*;\npub use std::strings::{rtrim};\nfn main() { println(`{upper(\"a\")}`); }";
+        let (tokens, mut interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        let result = check_with_types(&parsed.ast, &mut interner, None, None);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let removals: Vec<_> = result
+            .warnings
+            .iter()
+            .map(|w| {
+                let TypeWarning::UnusedImport { name, removal, .. } = w else {
+                    panic!("expected an unused import warning, got {:?}", w);
+                };
+                (name.as_str(), &source[removal.start as usize..removal.end as usize])
+            })
+            .collect();
+        assert_eq!(removals, [("low", ", lower as low"), ("ltrim", ", ltrim")]);
+    }
+
+    #[test]
+    fn test_float_map_key_warning() {
+        let source = "fn main() { var f: map<float, int> = {}; var g: map<int, float> = {}; var h: map<float, int> = f; }";
+        let (tokens, mut interner) = tokenize(source);
+        let arena = AstArena::new();
+        let parsed = parse(&tokens, source, &arena);
+        let result = check_with_types(&parsed.ast, &mut interner, None, None);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        let warnings: Vec<_> = result.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(warnings, ["map with float keys", "map with float keys"]);
+    }
+
     #[test]
     fn test_global_var_in_function() {
         let errors = check_source(
@@ -4841,4 +7646,248 @@ mod tests {
         );
         assert!(errors.is_empty(), "Global variables defined after functions should still be visible: {:?}", errors);
     }
+
+    #[test]
+    fn test_money_only_through_std_money() {
+        let prelude = "use std::money::*;\nfn main() {\n    var a: money = of(\"1.50\", \"USD\") catch e { return; };\n";
+        let ok = check_source(&format!(
+            "{}    var b: money = add(a, a) catch e {{ return; }};\n    var same: bool = a == b;\n}}",
+            prelude
+        ));
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in ["var b: money = a + a;", "var f: float = a * 2.0;", "var lt: bool = a < a;", "var s: string = a as string;", "var m: money = \"1 USD\" as money;"] {
+            let errors = check_source(&format!("{}    {}\n}}", prelude, body));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_format_specs() {
+        let ok = check_source(
+            "fn main() { var s: string = \"x\"; var n: int = 5; var t: string = fmt(\"{:>8} {:08x} {:,.2} {:.1}\", s, n, 1.5, n); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for call in ["fmt(\"{:x}\", s)", "fmt(\"{:,}\", s)", "fmt(\"{:b}\", 1.5)", "fmt(\"{:10q}\", 1)", "`{s:%}`"] {
+            let errors = check_source(&format!("fn main() {{ var s: string = \"x\"; var t: string = {}; }}", call));
+            assert!(!errors.is_empty(), "expected an error for `{}`", call);
+        }
+    }
+
+    #[test]
+    fn test_string_builder() {
+        let ok = check_source(
+            "use std::strings::builder::*;\nfn main() { var sb: string_builder = sb_new(); sb_append(sb, \"n=\"); sb_append_int(sb, 3); var s: string = sb_to_string(sb); var n: int = sb_len(sb); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in ["sb_append(sb, 3);", "var s: string = sb;", "var s: string = sb as string;", "var b: string_builder = \"x\" as string_builder;"] {
+            let errors = check_source(&format!(
+                "use std::strings::builder::*;\nfn main() {{ var sb: string_builder = sb_new(); {} }}",
+                body
+            ));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_generic_array_grouping() {
+        let ok = check_source(
+            "use std::collections::arrays::*;\nfn main() { var xs: [float] = [1.5, 2.5]; var u: [float] = unique(xs); var w: [[float]] = windows(xs, 2); var c: [[float]] = chunk(xs, 1); var z: [[float]] = zip(xs, u); var g: map<bool, [float]> = group_by(xs, fn(x: float) -> bool { return x > 2.0; }); var names: map<int, [string]> = group_by([\"a\"], fn(s: string) -> int { return 1; }); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in [
+            "var u: [int] = unique([\"a\"]);",
+            "var z: [[int]] = zip([1], [\"a\"]);",
+            "var g: map<string, [int]> = group_by([1], fn(x: int) -> int { return x; });",
+        ] {
+            let errors = check_source(&format!("use std::collections::arrays::*;\nfn main() {{ {} }}", body));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+    #[test]
+    fn test_numeric_aggregates() {
+        let ok = check_source(
+            "use std::collections::arrays::*;\nfn main() { var xs: [float] = [1.5]; var s: float = sum(xs); var m: option<float> = min(xs); var n: option<float> = max(xs); var o: [float] = sort(xs); var i: int = sum([1, 2]); var f: float = sum_float(xs); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in ["var s: string = sum([\"a\"]);", "var s: [bool] = sort([true]);", "var s: int = sum([1.5]);"] {
+            let errors = check_source(&format!("use std::collections::arrays::*;\nfn main() {{ {} }}", body));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_sets_and_priority_queues() {
+        let ok = check_source(
+            "use std::collections::sets::*;\nuse std::collections::priority_queues::*;\nfn main() { var s: set<string> = set_new(); var added: bool = set_add(s, \"a\"); var u: set<string> = set_union(s, set_new()); var xs: [string] = set_to_array(u); var q: priority_queue<float> = pq_new(); pq_push(q, 1.5); var top: option<float> = pq_pop_min(q); var n: int = pq_len(q) + set_len(s); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in [
+            "var s: set<int> = set_new(); set_add(s, \"a\");",
+            "var s: set<[int]> = set_new(); set_add(s, [1]);",
+            "var q: priority_queue<int> = pq_new(); var top: option<string> = pq_pop_min(q);",
+            "var s: set<int> = pq_new();",
+        ] {
+            let errors = check_source(&format!(
+                "use std::collections::sets::*;\nuse std::collections::priority_queues::*;\nfn main() {{ {} }}",
+                body
+            ));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_deques() {
+        let ok = check_source(
+            "use std::collections::deques::*;\nstruct P { x: int }\nfn main() { var dq: deque<P> = deque_new(); push_back(dq, P { x: 1 }); push_front(dq, P { x: 0 }); var p: option<P> = pop_front(dq); var q: option<P> = pop_back(dq); var n: int = deque_len(dq); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in [
+            "var dq: deque<int> = deque_new(); push_back(dq, \"a\");",
+            "var dq: deque<int> = deque_new(); var s: option<string> = pop_front(dq);",
+            "var dq: deque<int> = [1];",
+        ] {
+            let errors = check_source(&format!("use std::collections::deques::*;\nfn main() {{ {} }}", body));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_iterators() {
+        let ok = check_source(
+            "use std::collections::iterators::*;\nfn main() { var it: iterator<int> = iter_from_array([1, 2, 3]); var names: iterator<string> = iter_map(iter_filter(it, fn (x: int) -> bool { return x > 1; }), fn (x: int) -> string { return fmt(\"{}\", x); }); var first: option<string> = iter_next(names); var rest: [string] = iter_collect(iter_take(names, 1)); for (i, s in names) { var t: string = s; var j: int = i; } }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in [
+            "var it: iterator<int> = iter_from_array([\"a\"]);",
+            "var it: iterator<string> = iter_map(iter_from_array([1]), fn (x: int) -> int { return x; });",
+            "var xs: [int] = iter_collect(iter_filter(iter_from_array([1]), fn (x: int) -> int { return x; }));",
+            "for (x in iter_from_array([1])) { var s: string = x; }",
+        ] {
+            let errors = check_source(&format!("use std::collections::iterators::*;\nfn main() {{ {} }}", body));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_sorted_search() {
+        let ok = check_source(
+            "use std::collections::arrays::*;\nfn main() { var xs: [float] = [0.5, 1.5]; var i: option<int> = binary_search(xs, 1.5); var lo: int = lower_bound([\"a\", \"b\"], \"b\"); var hi: int = upper_bound([1, 2], 2); var p: int = partition_point([\"a\"], fn (s: string) -> bool { return s == \"a\"; }); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for body in [
+            "var i: option<int> = binary_search([1, 2], \"a\");",
+            "var i: int = binary_search([1, 2], 1);",
+            "var p: int = partition_point([1], fn (s: string) -> bool { return true; });",
+            "var p: int = partition_point([1], fn (x: int) -> int { return x; });",
+        ] {
+            let errors = check_source(&format!("use std::collections::arrays::*;\nfn main() {{ {} }}", body));
+            assert!(!errors.is_empty(), "expected an error for `{}`", body);
+        }
+    }
+
+    #[test]
+    fn test_parallel_array_functions() {
+        let ok = check_source(
+            "use std::collections::arrays::*;\nfn main() { var xs: [int] = par_apply([1, 2], fn (x: int) -> int { return x * 2; }); var ys: [int] = par_where(xs, fn (x: int) -> bool { return x > 2; }); var total: int = par_fold(ys, 0, fn (a: int, b: int) -> int { return a + b; }); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        let errors = check_source(
+            "use std::collections::arrays::*;\nfn main() { var total: int = par_fold([1], 0, fn (a: int) -> int { return a; }); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_channel_select() {
+        let ok = check_source(
+            "use std::threads::*;\nfn main() { var a: channel<int> = open_channel(1); var b: channel<int> = open_channel(1); var r: option<string> = select([a, b], fn (i: int, v: int) -> string { return \"x\"; }); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        let errors = check_source(
+            "use std::threads::*;\nfn main() { var a: channel<int> = open_channel(1); var r: option<int> = select([a], fn (v: int) -> int { return v; }); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_channel_polling() {
+        let ok = check_source(
+            "use std::threads::*;\nfn main() { var ch: channel<string> = open_channel(1); var sent: bool = try_send(ch, \"a\"); var a: option<string> = try_receive(ch); var b: option<string> = receive_timeout(ch, 10); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        let errors = check_source(
+            "use std::threads::*;\nfn main() { var ch: channel<int> = open_channel(1); var sent: bool = try_send(ch, \"a\"); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_task_handles() {
+        let ok = check_source(
+            "use std::threads::*;\nfn main() { var t: int = spawn { return \"x\"; }; task_join(t); var d: bool = task_is_done(t); var r: string = task_result(t); var wg: waitgroup = open_waitgroup(); waitgroup_add(wg, 1); waitgroup_done(wg); waitgroup_wait(wg); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        // `return` inside a spawn sets the task's result, not the function's
+        let ok = check_source(
+            "use std::threads::*;\nfn f() -> string { spawn { return 1; }; return \"s\"; }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        let errors = check_source(
+            "use std::threads::*;\nfn main() { var wg: waitgroup = open_waitgroup(); waitgroup_add(wg, \"1\"); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_condvar_semaphore() {
+        let ok = check_source(
+            "use std::threads::*;\nfn main() { var m: mutex<string> = with_mutex(\"\"); var cv: int = open_condvar(); locked (s: string in m) { s = condvar_wait(cv, m, s); } condvar_notify_one(cv); condvar_notify_all(cv); var sem: int = open_semaphore(1); semaphore_acquire(sem); var got: bool = semaphore_try_acquire(sem); semaphore_release(sem); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        // The value handed to condvar_wait must match the mutex's type
+        let errors = check_source(
+            "use std::threads::*;\nfn main() { var m: mutex<int> = with_mutex(0); var cv: int = open_condvar(); var v: int = condvar_wait(cv, m, \"x\"); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_task_groups() {
+        let ok = check_source(
+            "use std::threads::*;\nfn main() { var g: int = group_new(); group_spawn(g, fn() { var stop: bool = is_cancelled(); }); group_cancel(g); group_wait(g); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        // Group tasks take no arguments
+        let errors = check_source(
+            "use std::threads::*;\nfn main() { var g: int = group_new(); group_spawn(g, fn(n: int) { }); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_atomic_int() {
+        let ok = check_source(
+            "use std::threads::*;\nfn bump(a: atomic_int) -> int { return atomic_add(a, 1); }\nfn main() { var a: atomic_int = atomic_new(0); var b: atomic<int> = a; bump(b); var swapped: bool = atomic_cas(a, 1, 2); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        let errors = check_source(
+            "use std::threads::*;\nfn main() { var a: atomic_int = atomic_new(\"x\"); }",
+        );
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_worker_pool_entry_name() {
+        let ok = check_source(
+            "use std::process::pool::*;\nfn shout(b: bytes) -> bytes { return b; }\nfn main() { var p: int = open_worker_pool(2, \"shout\") catch e { return; }; var job: channel<bytes> = submit(p, \"x\" as bytes) catch e { return; }; var r: bytes = await_result(job) catch e { return; }; close_pool(p); }",
+        );
+        assert!(ok.is_empty(), "{:?}", ok);
+        for entry in ["\"half\"", "\"missing\"", "name"] {
+            let errors = check_source(&format!(
+                "use std::process::pool::*;\nfn half(x: int) -> int {{ return x; }}\nfn main() {{ var name: string = \"half\"; var p: int = open_worker_pool(1, {}) catch e {{ return; }}; }}",
+                entry
+            ));
+            assert!(!errors.is_empty(), "expected an error for {}", entry);
+        }
+    }
 }
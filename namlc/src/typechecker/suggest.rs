@@ -0,0 +1,91 @@
+//!
+//! "Did You Mean" Suggestions
+//!
+//! Finds the known name closest to a misspelled identifier, field, method,
+//! type or import. Names are compared by edit distance, where swapping two
+//! adjacent characters counts as a single edit (as in Damerau-Levenshtein).
+//! A candidate is only suggested when it is close enough relative to the
+//! length of the name that it plausibly is what was meant:
+//! - up to one edit for names of five characters or fewer
+//! - up to a third of the name's length beyond that
+//!
+//! A candidate that differs only in letter case always wins.
+//!
+
+/// The candidate closest to `name`, if any is within the edit budget
+pub fn did_you_mean<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut best: Option<(usize, &str)> = None;
+    for candidate in candidates {
+        if candidate == name || candidate.is_empty() {
+            continue;
+        }
+        let distance = if candidate.eq_ignore_ascii_case(name) {
+            0
+        } else {
+            edit_distance(name, candidate)
+        };
+        if distance > max_distance {
+            continue;
+        }
+        // Ties go to the alphabetically first name so output is stable
+        let better = match best {
+            None => true,
+            Some((best_distance, best_name)) => {
+                distance < best_distance || (distance == best_distance && candidate < best_name)
+            }
+        };
+        if better {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, name)| name.to_string())
+}
+
+/// Edit distance between two strings counting insertions, deletions,
+/// substitutions and adjacent transpositions of chars
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for i in 0..a.len() {
+        curr[0] = i + 1;
+        for j in 0..b.len() {
+            let cost = usize::from(a[i] != b[j]);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                curr[j + 1] = curr[j + 1].min(before[j - 1] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("count", "count"), 0);
+        assert_eq!(edit_distance("cuont", "count"), 1);
+        assert_eq!(edit_distance("lenght", "length"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let names = ["count", "counter", "println", "total"];
+        assert_eq!(did_you_mean("coutn", names), Some("count".to_string()));
+        assert_eq!(did_you_mean("cont", names), Some("count".to_string()));
+        assert_eq!(did_you_mean("printn", names), Some("println".to_string()));
+        assert_eq!(did_you_mean("Total", names), Some("total".to_string()));
+        assert_eq!(did_you_mean("xyz", names), None);
+        assert_eq!(did_you_mean("count", names), None);
+    }
+}
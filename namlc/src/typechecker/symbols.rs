@@ -11,7 +11,7 @@
 //! definitions, then used during type checking to resolve references.
 //!
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use lasso::Spur;
 
@@ -19,7 +19,7 @@ use super::types::{
     EnumType, FieldType, FunctionType, InterfaceType, MethodType, StructType, Type, TypeParam,
     VariantType,
 };
-use crate::ast::Platform;
+use crate::ast::{Attribute, Platform};
 use crate::source::Span;
 
 #[derive(Debug, Clone)]
@@ -34,6 +34,7 @@ pub struct FunctionSig {
     pub span: Span,
     pub module: Option<String>,
     pub platforms: Option<Vec<Platform>>,
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +47,7 @@ pub struct MethodSig {
     pub throws: Vec<Type>,
     pub is_public: bool,
     pub span: Span,
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone)]
@@ -93,6 +95,9 @@ pub struct InterfaceMethodDef {
     pub params: Vec<(Spur, Type)>,
     pub return_ty: Type,
     pub throws: Vec<Type>,
+    pub is_static: bool,
+    pub has_default: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
@@ -118,6 +123,11 @@ pub struct ModuleNamespace {
     pub functions: HashMap<Spur, FunctionSig>,
     pub types: HashMap<Spur, TypeDef>,
     pub submodules: HashMap<Spur, ModuleNamespace>,
+    /// Items the module's file defines without `pub`
+    pub private: HashSet<Spur>,
+    /// Functions the module re-exports under another name, mapped to the
+    /// name they are defined under
+    pub aliases: HashMap<Spur, Spur>,
 }
 
 impl ModuleNamespace {
@@ -127,6 +137,8 @@ impl ModuleNamespace {
             functions: HashMap::new(),
             types: HashMap::new(),
             submodules: HashMap::new(),
+            private: HashSet::new(),
+            aliases: HashMap::new(),
         }
     }
 
@@ -171,6 +183,10 @@ impl ModuleNamespace {
     pub fn all_submodules(&self) -> impl Iterator<Item = (&Spur, &ModuleNamespace)> {
         self.submodules.iter()
     }
+
+    pub fn is_private(&self, name: Spur) -> bool {
+        self.private.contains(&name)
+    }
 }
 
 impl Default for ModuleNamespace {
@@ -179,7 +195,19 @@ impl Default for ModuleNamespace {
     }
 }
 
-use std::collections::HashSet;
+/// Snapshot of the unqualified functions in scope, see `SymbolTable::function_scope`
+pub struct FunctionScope {
+    functions: HashMap<Spur, FunctionSig>,
+    ambiguous: HashMap<Spur, Vec<String>>,
+    glob_imports: HashSet<Spur>,
+    aliases: HashMap<Spur, Spur>,
+    module_functions: HashMap<Spur, FunctionSig>,
+}
+
+/// Where a function in scope comes from, for diagnostics
+fn origin(sig: &FunctionSig) -> String {
+    sig.module.clone().unwrap_or_else(|| "this file".to_string())
+}
 
 #[derive(Debug, Clone)]
 pub enum ResolvedItem<'a> {
@@ -195,8 +223,15 @@ pub struct SymbolTable {
     // (though in a proper module system, these should be scoped)
     types: HashMap<Spur, TypeDef>,
     functions: HashMap<Spur, FunctionSig>,
-    ambiguous_functions: HashSet<Spur>,
+    /// Names two glob imports provide, with the modules providing them
+    ambiguous_functions: HashMap<Spur, Vec<String>>,
+    /// Names in scope through a glob import, which explicit imports replace
+    glob_imports: HashSet<Spur>,
+    /// Functions imported under an alias, mapped to their original name
+    aliases: HashMap<Spur, Spur>,
     methods: HashMap<Spur, Vec<MethodSig>>,
+    /// Private types of imported module files, with the module defining them
+    hidden_types: HashMap<Spur, String>,
     pub current_path: Vec<Spur>,
 }
 
@@ -206,8 +241,11 @@ impl SymbolTable {
             root: ModuleNamespace::new(Spur::default()),
             types: HashMap::new(),
             functions: HashMap::new(),
-            ambiguous_functions: HashSet::new(),
+            ambiguous_functions: HashMap::new(),
+            glob_imports: HashSet::new(),
+            aliases: HashMap::new(),
             methods: HashMap::new(),
+            hidden_types: HashMap::new(),
             current_path: Vec::new(),
         }
     }
@@ -245,10 +283,24 @@ impl SymbolTable {
     }
 
     pub fn define_type(&mut self, name: Spur, def: TypeDef) {
+        self.hidden_types.remove(&name);
         self.types.insert(name, def.clone());
         self.get_current_module_mut().define_type(name, def);
     }
 
+    /// Remove a module file's private type from scope once the module's
+    /// exports are known, remembering where it came from for diagnostics
+    pub fn hide_type(&mut self, name: Spur, module: String) {
+        self.types.remove(&name);
+        self.get_current_module_mut().types.remove(&name);
+        self.hidden_types.insert(name, module);
+    }
+
+    /// The module whose private type `name` is, if it is one
+    pub fn hidden_type_module(&self, name: Spur) -> Option<&str> {
+        self.hidden_types.get(&name).map(String::as_str)
+    }
+
     pub fn define_module(&mut self, name: Spur, module: ModuleNamespace) {
         self.get_current_module_mut().define_submodule(name, module);
     }
@@ -324,20 +376,97 @@ impl SymbolTable {
 
     pub fn define_function(&mut self, sig: FunctionSig) {
         self.ambiguous_functions.remove(&sig.name);
+        self.glob_imports.remove(&sig.name);
+        self.aliases.remove(&sig.name);
         self.functions.insert(sig.name, sig.clone());
         self.get_current_module_mut().add_function(sig);
     }
 
-    pub fn import_function(&mut self, sig: FunctionSig) {
-        if let Some(existing) = self.functions.get(&sig.name) {
-            if existing.module != sig.module {
-                self.ambiguous_functions.insert(sig.name);
+    /// Import a function by name. An explicit import replaces one that came
+    /// in through a glob; two explicit imports of a name from different
+    /// modules clash, returning where the first came from
+    pub fn import_function(&mut self, sig: FunctionSig) -> Result<(), String> {
+        if let Some(existing) = self.functions.get(&sig.name)
+            && existing.module != sig.module
+            && !self.glob_imports.contains(&sig.name)
+        {
+            return Err(origin(existing));
+        }
+        self.ambiguous_functions.remove(&sig.name);
+        self.glob_imports.remove(&sig.name);
+        self.aliases.remove(&sig.name);
+        self.functions.insert(sig.name, sig.clone());
+        self.get_current_module_mut().add_function(sig);
+        Ok(())
+    }
+
+    /// Import a function through a glob. Local definitions and explicit
+    /// imports take precedence; two globs providing the name make it
+    /// ambiguous until one of them is chosen explicitly
+    pub fn import_glob_function(&mut self, sig: FunctionSig) {
+        if let Some(existing) = self.functions.get(&sig.name)
+            && existing.module != sig.module
+        {
+            if !self.glob_imports.contains(&sig.name) {
+                return;
             }
+            let first = origin(existing);
+            let modules = self
+                .ambiguous_functions
+                .entry(sig.name)
+                .or_insert_with(|| vec![first]);
+            modules.push(origin(&sig));
         }
+        self.glob_imports.insert(sig.name);
+        self.aliases.remove(&sig.name);
         self.functions.insert(sig.name, sig.clone());
         self.get_current_module_mut().add_function(sig);
     }
 
+    /// Record that `alias` was imported in place of the function `original`
+    pub fn define_alias(&mut self, alias: Spur, original: Spur) {
+        self.aliases.insert(alias, original);
+    }
+
+    /// The name a function imported under an alias was defined with
+    pub fn original_name(&self, name: Spur) -> Option<Spur> {
+        self.aliases.get(&name).copied()
+    }
+
+    /// The functions currently in scope by their plain name, so a module
+    /// file's own imports can be undone once the module is loaded
+    pub fn function_scope(&self) -> FunctionScope {
+        FunctionScope {
+            functions: self.functions.clone(),
+            ambiguous: self.ambiguous_functions.clone(),
+            glob_imports: self.glob_imports.clone(),
+            aliases: self.aliases.clone(),
+            module_functions: self.get_current_module().functions.clone(),
+        }
+    }
+
+    pub fn restore_function_scope(&mut self, scope: FunctionScope) {
+        self.functions = scope.functions;
+        self.ambiguous_functions = scope.ambiguous;
+        self.glob_imports = scope.glob_imports;
+        self.aliases = scope.aliases;
+        self.get_current_module_mut().functions = scope.module_functions;
+    }
+
+    /// Functions brought into scope since `scope` was taken
+    pub fn functions_since(&self, scope: &FunctionScope) -> Vec<FunctionSig> {
+        self.functions
+            .values()
+            .filter(|sig| {
+                scope
+                    .functions
+                    .get(&sig.name)
+                    .is_none_or(|before| before.module != sig.module)
+            })
+            .cloned()
+            .collect()
+    }
+
     pub fn define_module_function(&mut self, sig: FunctionSig) {
         self.get_current_module_mut().add_function(sig);
     }
@@ -346,18 +475,27 @@ impl SymbolTable {
         self.functions.contains_key(&name)
     }
 
-    pub fn mark_ambiguous(&mut self, name: Spur) {
-        self.ambiguous_functions.insert(name);
+    pub fn is_ambiguous(&self, name: Spur) -> bool {
+        self.ambiguous_functions.contains_key(&name)
     }
 
-    pub fn is_ambiguous(&self, name: Spur) -> bool {
-        self.ambiguous_functions.contains(&name)
+    /// The modules whose glob imports all provide `name`
+    pub fn ambiguous_modules(&self, name: Spur) -> Vec<String> {
+        self.ambiguous_functions.get(&name).cloned().unwrap_or_default()
     }
 
     pub fn get_function(&self, name: Spur) -> Option<&FunctionSig> {
         self.functions.get(&name)
     }
 
+    pub fn type_names(&self) -> impl Iterator<Item = &Spur> {
+        self.types.keys()
+    }
+
+    pub fn function_names(&self) -> impl Iterator<Item = &Spur> {
+        self.functions.keys()
+    }
+
     pub fn define_method(&mut self, type_name: Spur, method: MethodSig) {
         self.methods.entry(type_name).or_default().push(method);
     }
@@ -410,6 +548,27 @@ impl SymbolTable {
         }
     }
 
+    /// The enum type of a generic enum applied to `type_args`, with the
+    /// arguments substituted into its variant fields
+    pub fn instantiate_enum_type(&self, def: &EnumDef, type_args: &[Type]) -> EnumType {
+        let substitution: HashMap<Spur, Type> = def
+            .type_params
+            .iter()
+            .map(|tp| tp.name)
+            .zip(type_args.iter().cloned())
+            .collect();
+        let mut enum_ty = self.to_enum_type(def);
+        for variant in &mut enum_ty.variants {
+            if let Some(fields) = &mut variant.fields {
+                for field in fields.iter_mut() {
+                    *field = field.substitute(&substitution);
+                }
+            }
+        }
+        enum_ty.type_args = type_args.to_vec();
+        enum_ty
+    }
+
     pub fn to_interface_type(&self, def: &InterfaceDef) -> InterfaceType {
         InterfaceType {
             name: def.name,
@@ -470,6 +629,7 @@ mod tests {
             span: Span::dummy(),
             module: None,
             platforms: None,
+            attributes: vec![],
         });
 
         assert!(table.get_function(main).is_some());
@@ -521,6 +681,7 @@ mod tests {
                 throws: vec![],
                 is_public: true,
                 span: Span::dummy(),
+                attributes: vec![],
             },
         );
 
@@ -22,6 +22,7 @@ use std::collections::HashMap;
 use lasso::Spur;
 
 use crate::source::Span;
+use super::consteval::ConstValue;
 use super::types::Type;
 
 #[derive(Debug, Clone)]
@@ -31,6 +32,14 @@ pub struct MonomorphizationInfo {
     pub mangled_name: String,
 }
 
+/// An interface default method that a struct inherits instead of defining
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaultMethodInfo {
+    pub struct_name: Spur,
+    pub interface_name: Spur,
+    pub method_name: Spur,
+}
+
 #[derive(Debug, Clone)]
 pub struct ExprTypeInfo {
     pub ty: Type,
@@ -64,6 +73,9 @@ pub struct TypeAnnotations {
     monomorphizations: HashMap<String, MonomorphizationInfo>,
     call_site_instantiations: HashMap<Span, String>,
     resolved_modules: HashMap<Span, String>,
+    aliased_calls: HashMap<Span, String>,
+    const_values: HashMap<Span, ConstValue>,
+    default_methods: Vec<DefaultMethodInfo>,
 }
 
 impl TypeAnnotations {
@@ -73,6 +85,9 @@ impl TypeAnnotations {
             monomorphizations: HashMap::new(),
             call_site_instantiations: HashMap::new(),
             resolved_modules: HashMap::new(),
+            aliased_calls: HashMap::new(),
+            const_values: HashMap::new(),
+            default_methods: Vec::new(),
         }
     }
 
@@ -147,6 +162,38 @@ impl TypeAnnotations {
     pub fn get_resolved_module(&self, span: Span) -> Option<&String> {
         self.resolved_modules.get(&span)
     }
+
+    /// Record the name a call compiles to when it differs from the callee:
+    /// the defining name of a function imported under an alias, or the
+    /// element-type variant picked for a std function
+    pub fn record_aliased_call(&mut self, call_span: Span, original: String) {
+        self.aliased_calls.insert(call_span, original);
+    }
+
+    pub fn get_aliased_call(&self, span: Span) -> Option<&String> {
+        self.aliased_calls.get(&span)
+    }
+
+    /// Record the compile-time value of a const fn call
+    pub fn record_const_value(&mut self, call_span: Span, value: ConstValue) {
+        self.const_values.insert(call_span, value);
+    }
+
+    pub fn get_const_value(&self, span: Span) -> Option<&ConstValue> {
+        self.const_values.get(&span)
+    }
+
+    pub fn const_values(&self) -> impl Iterator<Item = (&Span, &ConstValue)> {
+        self.const_values.iter()
+    }
+
+    pub fn record_default_method(&mut self, info: DefaultMethodInfo) {
+        self.default_methods.push(info);
+    }
+
+    pub fn get_default_methods(&self) -> &[DefaultMethodInfo] {
+        &self.default_methods
+    }
 }
 
 #[cfg(test)]
@@ -45,6 +45,13 @@ pub enum Type {
     Mutex(Box<Type>),
     Rwlock(Box<Type>),
     Atomic(Box<Type>),
+    // Sorted set, min-heap and double-ended queue for collections::sets,
+    // collections::priority_queues and collections::deques
+    Set(Box<Type>),
+    PriorityQueue(Box<Type>),
+    Deque(Box<Type>),
+    // Lazy pipeline over an array for collections::iterators
+    Iterator(Box<Type>),
 
     Struct(StructType),
     Enum(EnumType),
@@ -54,9 +61,27 @@ pub enum Type {
     // Built-in stack frame type for exception stack traces
     StackFrame,
 
+    // Built-in network interface record for os::net::interfaces
+    IfaceInfo,
+
+    // Built-in change event passed to fs::watch callbacks
+    FsEvent,
+
     // Dynamic JSON type for encoding::json module
     Json,
 
+    // Redacted credential type for crypto secrets
+    Secret,
+
+    // Currency-tagged amount for the money module
+    Money,
+
+    // Growable buffer for strings::builder
+    StringBuilder,
+
+    // Counter of outstanding work for threads wait groups
+    Waitgroup,
+
     Function(FunctionType),
 
     TypeVar(TypeVarRef),
@@ -242,6 +267,10 @@ impl Type {
             Type::Mutex(inner) => Type::Mutex(Box::new(inner.resolve())),
             Type::Rwlock(inner) => Type::Rwlock(Box::new(inner.resolve())),
             Type::Atomic(inner) => Type::Atomic(Box::new(inner.resolve())),
+            Type::Set(inner) => Type::Set(Box::new(inner.resolve())),
+            Type::PriorityQueue(inner) => Type::PriorityQueue(Box::new(inner.resolve())),
+            Type::Deque(inner) => Type::Deque(Box::new(inner.resolve())),
+            Type::Iterator(inner) => Type::Iterator(Box::new(inner.resolve())),
             Type::Function(f) => Type::Function(FunctionType {
                 params: f.params.iter().map(|p| p.resolve()).collect(),
                 returns: Box::new(f.returns.resolve()),
@@ -264,7 +293,7 @@ impl Type {
                 false
             }
             Type::Array(elem) | Type::FixedArray(elem, _) => elem.contains_var(var_id),
-            Type::Option(inner) | Type::Channel(inner) | Type::Mutex(inner) | Type::Rwlock(inner) | Type::Atomic(inner) => inner.contains_var(var_id),
+            Type::Option(inner) | Type::Channel(inner) | Type::Mutex(inner) | Type::Rwlock(inner) | Type::Atomic(inner) | Type::Set(inner) | Type::PriorityQueue(inner) | Type::Deque(inner) | Type::Iterator(inner) => inner.contains_var(var_id),
             Type::Map(k, v) => k.contains_var(var_id) || v.contains_var(var_id),
             Type::Function(f) => {
                 f.params.iter().any(|p| p.contains_var(var_id))
@@ -302,6 +331,12 @@ impl Type {
             Type::Mutex(inner) => Type::Mutex(Box::new(inner.substitute(substitutions))),
             Type::Rwlock(inner) => Type::Rwlock(Box::new(inner.substitute(substitutions))),
             Type::Atomic(inner) => Type::Atomic(Box::new(inner.substitute(substitutions))),
+            Type::Set(inner) => Type::Set(Box::new(inner.substitute(substitutions))),
+            Type::PriorityQueue(inner) => {
+                Type::PriorityQueue(Box::new(inner.substitute(substitutions)))
+            }
+            Type::Deque(inner) => Type::Deque(Box::new(inner.substitute(substitutions))),
+            Type::Iterator(inner) => Type::Iterator(Box::new(inner.substitute(substitutions))),
             Type::Function(f) => Type::Function(FunctionType {
                 params: f.params.iter().map(|p| p.substitute(substitutions)).collect(),
                 returns: Box::new(f.returns.substitute(substitutions)),
@@ -331,12 +366,22 @@ impl fmt::Display for Type {
             Type::Mutex(inner) => write!(f, "mutex<{}>", inner),
             Type::Rwlock(inner) => write!(f, "rwlock<{}>", inner),
             Type::Atomic(inner) => write!(f, "atomic<{}>", inner),
+            Type::Set(inner) => write!(f, "set<{}>", inner),
+            Type::PriorityQueue(inner) => write!(f, "priority_queue<{}>", inner),
+            Type::Deque(inner) => write!(f, "deque<{}>", inner),
+            Type::Iterator(inner) => write!(f, "iterator<{}>", inner),
             Type::Struct(s) => write!(f, "struct:{:?}", s.name),
             Type::Enum(e) => write!(f, "enum:{:?}", e.name),
             Type::Interface(i) => write!(f, "interface:{:?}", i.name),
             Type::Exception(name) => write!(f, "exception:{:?}", name),
             Type::StackFrame => write!(f, "stack_frame"),
+            Type::IfaceInfo => write!(f, "iface_info"),
+            Type::FsEvent => write!(f, "fs_event"),
             Type::Json => write!(f, "json"),
+            Type::Secret => write!(f, "secret"),
+            Type::Money => write!(f, "money"),
+            Type::StringBuilder => write!(f, "string_builder"),
+            Type::Waitgroup => write!(f, "waitgroup"),
             Type::Function(func) => {
                 write!(f, "fn(")?;
                 for (i, p) in func.params.iter().enumerate() {
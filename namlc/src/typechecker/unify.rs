@@ -35,7 +35,14 @@ pub fn unify(a: &Type, b: &Type, span: Span) -> TypeResult<()> {
         | (Type::String, Type::String)
         | (Type::Bytes, Type::Bytes)
         | (Type::Unit, Type::Unit)
-        | (Type::Json, Type::Json) => Ok(()),
+        | (Type::Json, Type::Json)
+        | (Type::StackFrame, Type::StackFrame)
+        | (Type::IfaceInfo, Type::IfaceInfo)
+        | (Type::FsEvent, Type::FsEvent)
+        | (Type::Secret, Type::Secret)
+        | (Type::Money, Type::Money)
+        | (Type::StringBuilder, Type::StringBuilder)
+        | (Type::Waitgroup, Type::Waitgroup) => Ok(()),
 
         (Type::TypeVar(var), other) | (other, Type::TypeVar(var)) => {
             if let Type::TypeVar(other_var) = other
@@ -94,6 +101,22 @@ pub fn unify(a: &Type, b: &Type, span: Span) -> TypeResult<()> {
             unify(a_inner, b_inner, span)
         }
 
+        (Type::Set(a_inner), Type::Set(b_inner)) => {
+            unify(a_inner, b_inner, span)
+        }
+
+        (Type::PriorityQueue(a_inner), Type::PriorityQueue(b_inner)) => {
+            unify(a_inner, b_inner, span)
+        }
+
+        (Type::Deque(a_inner), Type::Deque(b_inner)) => {
+            unify(a_inner, b_inner, span)
+        }
+
+        (Type::Iterator(a_inner), Type::Iterator(b_inner)) => {
+            unify(a_inner, b_inner, span)
+        }
+
         (Type::Function(a_fn), Type::Function(b_fn)) => {
             if a_fn.params.len() != b_fn.params.len() {
                 return Err(TypeError::type_mismatch(
@@ -206,6 +229,14 @@ pub fn unify(a: &Type, b: &Type, span: Span) -> TypeResult<()> {
             Ok(())
         }
 
+        // A generic enum named without type arguments unifies with any of its
+        // instances
+        (Type::Enum(e), Type::Generic(name, _)) | (Type::Generic(name, _), Type::Enum(e))
+            if e.name == *name && e.type_args.is_empty() =>
+        {
+            Ok(())
+        }
+
         // Allow Type::Struct to unify with Type::Generic of the same name
         (Type::Struct(s), Type::Generic(name, args)) | (Type::Generic(name, args), Type::Struct(s)) => {
             if s.name != *name {
@@ -240,6 +271,15 @@ pub fn unify_with_expected(found: &Type, expected: &Type, span: Span) -> TypeRes
     unify(found, expected, span)
 }
 
+/// Unify a call argument with its parameter type. Raw `bytes` may be passed
+/// where a `secret` is expected, but a secret never flows into a `bytes` slot.
+pub fn unify_arg(arg: &Type, param: &Type, span: Span) -> TypeResult<()> {
+    if matches!((arg.resolve(), param), (Type::Bytes, Type::Secret)) {
+        return Ok(());
+    }
+    unify(arg, param, span)
+}
+
 pub fn fresh_type_var(id: &mut u32) -> Type {
     let var = TypeVarRef::new(*id);
     *id += 1;
@@ -251,6 +291,13 @@ mod tests {
     use super::*;
     use crate::source::Span;
 
+    #[test]
+    fn test_unify_arg_bytes_to_secret() {
+        assert!(unify_arg(&Type::Bytes, &Type::Secret, Span::dummy()).is_ok());
+        assert!(unify_arg(&Type::Secret, &Type::Bytes, Span::dummy()).is_err());
+        assert!(unify_arg(&Type::String, &Type::Secret, Span::dummy()).is_err());
+    }
+
     #[test]
     fn test_unify_same_primitives() {
         assert!(unify(&Type::Int, &Type::Int, Span::dummy()).is_ok());
@@ -0,0 +1,129 @@
+//!
+//! Unused Import Detection
+//!
+//! Reports names brought in by `use path::{a, b as c}` that nothing in the
+//! file refers to. A name counts as used when any identifier outside the
+//! `use` items resolves to the same symbol, so a local binding that shadows
+//! an import keeps it alive; that errs on the side of not warning.
+//!
+//! Template string interpolations are only parsed during codegen, so the
+//! words inside `{...}` are counted as uses too.
+//!
+//! `pub use` re-exports and `use path::*` are never reported. Each warning
+//! carries the span to delete: the entry with its separating comma, or the
+//! whole `use` item when none of its entries are used.
+//!
+
+use std::collections::HashSet;
+
+use lasso::{Rodeo, Spur};
+
+use crate::ast::visitor::{self, Visitor};
+use crate::ast::{AttributeArg, Expression, Ident, Item, SourceFile, TemplateStringPart, UseItems};
+use crate::source::Span;
+
+use super::error::TypeWarning;
+
+struct UsedNames<'a> {
+    interner: &'a Rodeo,
+    symbols: HashSet<Spur>,
+    words: HashSet<String>,
+}
+
+impl<'ast> Visitor<'ast> for UsedNames<'_> {
+    fn visit_item(&mut self, item: &Item<'ast>) {
+        match item {
+            Item::Use(_) => {}
+            Item::Function(f) => {
+                for attribute in &f.attributes {
+                    for arg in &attribute.args {
+                        if let AttributeArg::Ident(ident) = arg {
+                            self.symbols.insert(ident.symbol);
+                        }
+                    }
+                }
+                visitor::walk_item(self, item);
+            }
+            _ => visitor::walk_item(self, item),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expression<'ast>) {
+        if let Expression::TemplateString(template) = expr {
+            for part in &template.parts {
+                if let TemplateStringPart::Expression(source) = part {
+                    self.words.extend(
+                        source
+                            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                            .filter(|w| !w.is_empty())
+                            .map(str::to_string),
+                    );
+                }
+            }
+        }
+        visitor::walk_expr(self, expr);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.symbols.insert(ident.symbol);
+    }
+}
+
+impl UsedNames<'_> {
+    fn contains(&self, symbol: Spur) -> bool {
+        self.symbols.contains(&symbol) || self.words.contains(self.interner.resolve(&symbol))
+    }
+}
+
+/// Warnings for the unused entries of the file's top-level `use` items
+pub fn unused_imports(file: &SourceFile, interner: &Rodeo) -> Vec<TypeWarning> {
+    let mut used = UsedNames {
+        interner,
+        symbols: HashSet::new(),
+        words: HashSet::new(),
+    };
+    for item in &file.items {
+        used.visit_item(item);
+    }
+
+    let mut warnings = Vec::new();
+    for item in &file.items {
+        let Item::Use(use_item) = item else {
+            continue;
+        };
+        let UseItems::Specific(entries) = &use_item.items else {
+            continue;
+        };
+        if use_item.is_public || entries.is_empty() {
+            continue;
+        }
+        let unused: Vec<bool> = entries
+            .iter()
+            .map(|e| !used.contains(e.alias.as_ref().unwrap_or(&e.name).symbol))
+            .collect();
+        // An entry's span stops at its name; take in `as alias` too
+        let spans: Vec<Span> = entries
+            .iter()
+            .map(|e| e.alias.as_ref().map_or(e.span, |alias| e.span.merge(alias.span)))
+            .collect();
+        let last_used = unused.iter().rposition(|u| !u);
+        for (i, entry) in entries.iter().enumerate() {
+            if !unused[i] {
+                continue;
+            }
+            let removal = match last_used {
+                None => use_item.span,
+                // Entries after the last used one go along with the comma
+                // before them, so the list does not end in a dangling comma
+                Some(last) if i > last => Span::new(spans[i - 1].end, spans[i].end, entry.span.file_id),
+                Some(_) => Span::new(spans[i].start, spans[i + 1].start, entry.span.file_id),
+            };
+            warnings.push(TypeWarning::UnusedImport {
+                name: interner.resolve(&entry.alias.as_ref().unwrap_or(&entry.name).symbol).to_string(),
+                span: spans[i],
+                removal,
+            });
+        }
+    }
+    warnings
+}
@@ -11,6 +11,8 @@
 /// 3. Executes the resulting binary, captures stdout
 /// 4. Returns stdout as String
 ///
+/// `aot_run_release` does the same with `naml build --release`.
+///
 /// Run all:  `cargo test --test aot`
 /// Run one:  `cargo test --test aot hello`
 ///
@@ -35,6 +37,14 @@ fn fixture_path(name: &str) -> PathBuf {
 }
 
 fn aot_run(fixture_name: &str) -> String {
+    aot_run_with_args(fixture_name, &[])
+}
+
+fn aot_run_release(fixture_name: &str) -> String {
+    aot_run_with_args(fixture_name, &["--release"])
+}
+
+fn aot_run_with_args(fixture_name: &str, build_args: &[&str]) -> String {
     let naml = env!("CARGO_BIN_EXE_naml");
     let src = fixture_path(fixture_name);
     assert!(src.exists(), "Fixture not found: {}", src.display());
@@ -46,6 +56,7 @@ fn aot_run(fixture_name: &str) -> String {
         let _lock = BUILD_LOCK.lock().unwrap();
         let build = Command::new(naml)
             .args(["build", &src.to_string_lossy(), "-o", &out_bin.to_string_lossy()])
+            .args(build_args)
             .output()
             .expect("failed to run naml build");
 
@@ -153,6 +164,130 @@ fn maps() {
     assert!(out.contains("OK"), "got: {}", out);
 }
 
+#[test]
+fn map_keys() {
+    let out = aot_run("map_keys");
+    assert!(out.contains("OK"), "got: {}", out);
+    // Only the store of 2.5 gets past `m[k] = 1`; the NaN stores throw
+    assert_eq!(out.matches("after store").count(), 1, "got: {}", out);
+}
+
+#[test]
+fn array_grouping() {
+    let out = aot_run("array_grouping");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn array_float_aggregates() {
+    let out = aot_run("array_float_aggregates");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn overflow_checks() {
+    let out = aot_run("overflow");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn division() {
+    let out = aot_run("division");
+    assert!(out.contains("OK"), "got: {}", out);
+    assert!(!out.contains("after div"), "ran past a failed division: {}", out);
+}
+
+#[test]
+fn string_compare() {
+    let out = aot_run("string_compare");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn string_unicode() {
+    let out = aot_run("string_unicode");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn string_builder() {
+    let out = aot_run("string_builder");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn collections_sets_queues() {
+    let out = aot_run("collections_sets_queues");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn collections_deque() {
+    let out = aot_run("collections_deque");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn collections_iterators() {
+    let out = aot_run("collections_iterators");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn collections_binary_search() {
+    let out = aot_run("collections_binary_search");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn collections_parallel() {
+    let out = aot_run("collections_parallel");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn fmt_spec() {
+    let out = aot_run("fmt_spec");
+    assert!(out.contains("ab   |    7"), "got: {}", out);
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn radix() {
+    let out = aot_run("radix");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn stack_frames() {
+    let out = aot_run("stack_frames");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn exception_cause() {
+    let out = aot_run("exception_cause");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn typed_catch() {
+    let out = aot_run("typed_catch");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn finally() {
+    let out = aot_run("finally");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn overflow_checks_off() {
+    let out = aot_run_with_args("overflow_wrap", &["--overflow-checks", "off"]);
+    assert!(out.contains("wrapped"), "got: {}", out);
+}
+
 // ── Tier 2: Type System ─────────────────────────────────────────────
 
 #[test]
@@ -185,6 +320,12 @@ fn methods() {
     assert!(out.contains("OK"), "got: {}", out);
 }
 
+#[test]
+fn option_returns() {
+    let out = aot_run("option_returns");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
 #[test]
 fn type_casting() {
     let out = aot_run("type_casting");
@@ -211,6 +352,24 @@ fn interfaces() {
     assert!(out.contains("OK"), "got: {}", out);
 }
 
+#[test]
+fn modules() {
+    let out = aot_run("modules");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn module_generics() {
+    let out = aot_run("module_generics");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn const_fn() {
+    let out = aot_run("const_fn");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
 #[test]
 fn nested_structs() {
     let out = aot_run("nested_structs");
@@ -231,6 +390,48 @@ fn channels() {
     assert!(out.contains("OK"), "got: {}", out);
 }
 
+#[test]
+fn channel_select() {
+    let out = aot_run("channel_select");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn channel_polling() {
+    let out = aot_run("channel_polling");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn task_handles() {
+    let out = aot_run("task_handles");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn condvar_semaphore() {
+    let out = aot_run("condvar_semaphore");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn task_groups() {
+    let out = aot_run("task_groups");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn switch_dispatch() {
+    let out = aot_run("switch_dispatch");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn runtime_build_info() {
+    let out = aot_run("runtime_build_info");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
 #[test]
 fn mutex() {
     let out = aot_run("mutex");
@@ -243,6 +444,12 @@ fn atomics() {
     assert!(out.contains("OK"), "got: {}", out);
 }
 
+#[test]
+fn actors() {
+    let out = aot_run("actors");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
 // ── Tier 5: Std Library ─────────────────────────────────────────────
 
 #[test]
@@ -263,6 +470,180 @@ fn std_metrics() {
     assert!(out.contains("true"), "got: {}", out);
 }
 
+#[test]
+fn std_os_net() {
+    let out = aot_run("std_os_net");
+    assert!(out.contains("true"), "got: {}", out);
+}
+
+#[test]
+fn std_fs_async() {
+    let out = aot_run("std_fs_async");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_process_pool() {
+    let out = aot_run("std_process_pool");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_fs_watch() {
+    let out = aot_run("std_fs_watch");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_fs_glob() {
+    let out = aot_run("std_fs_glob");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_fs_atomic() {
+    let out = aot_run("std_fs_atomic");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_fs_lock() {
+    let out = aot_run("std_fs_lock");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_fs_walk() {
+    let out = aot_run("std_fs_walk");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_net_tcp_options() {
+    let out = aot_run("std_net_tcp_options");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_net_s3() {
+    let out = aot_run("std_net_s3");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_cloud_metadata() {
+    let out = aot_run("std_cloud_metadata");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_net_http_proxy() {
+    let out = aot_run("std_net_http_proxy");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_net_http_retry() {
+    let out = aot_run("std_net_http_retry");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_config() {
+    let out = aot_run("std_config");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_flags() {
+    let out = aot_run("std_flags");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_fsm() {
+    let out = aot_run("std_fsm");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_encoding_csv() {
+    let out = aot_run("std_encoding_csv");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_encoding_msgpack() {
+    let out = aot_run("std_encoding_msgpack");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_encoding_cbor() {
+    let out = aot_run("std_encoding_cbor");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_compress() {
+    let out = aot_run("std_compress");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_validate() {
+    let out = aot_run("std_validate");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_money() {
+    let out = aot_run("std_money");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_geo() {
+    let out = aot_run("std_geo");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_regex() {
+    let out = aot_run("std_regex");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_image() {
+    let out = aot_run("std_image");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_pdf() {
+    let out = aot_run("std_pdf");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_xlsx() {
+    let out = aot_run("std_xlsx");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_markdown() {
+    let out = aot_run("std_markdown");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn std_queue() {
+    let out = aot_run("std_queue");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
 // ── Tier 6: Refcount / Memory ───────────────────────────────────────
 
 #[test]
@@ -289,6 +670,30 @@ fn mem_array_strings() {
     assert!(out.contains("OK"), "got: {}", out);
 }
 
+#[test]
+fn mem_interned_strings() {
+    let out = aot_run("mem_interned_strings");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn mem_array_views() {
+    let out = aot_run("mem_array_views");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn mem_string_views() {
+    let out = aot_run("mem_string_views");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn mem_ownership() {
+    let out = aot_run("mem_ownership");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
 #[test]
 fn mem_spawn_captured() {
     let out = aot_run("mem_spawn_captured");
@@ -300,3 +705,17 @@ fn mem_binary_tree() {
     let out = aot_run("mem_binary_tree");
     assert!(out.contains("127"), "expected 127 nodes, got: {}", out);
 }
+
+// ── Tier 7: Release-Mode Optimizations ──────────────────────────────
+
+#[test]
+fn release_array_loops() {
+    let out = aot_run_release("release_array_loops");
+    assert!(out.contains("OK"), "got: {}", out);
+}
+
+#[test]
+fn release_loop_invariants() {
+    let out = aot_run_release("release_loop_invariants");
+    assert!(out.contains("OK"), "got: {}", out);
+}
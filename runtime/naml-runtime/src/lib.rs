@@ -26,8 +26,24 @@ pub use naml_std_testing::*;
 pub use naml_std_sqlite3::*;
 pub use naml_std_timers::*;
 pub use naml_std_crypto::*;
+pub use naml_std_config::*;
+pub use naml_std_flags::*;
+pub use naml_std_fsm::*;
+pub use naml_std_queue::*;
+pub use naml_std_compress::*;
+pub use naml_std_validate::*;
+pub use naml_std_money::*;
+pub use naml_std_geo::*;
+pub use naml_std_regex::*;
+pub use naml_std_image::*;
+pub use naml_std_pdf::*;
+pub use naml_std_xlsx::*;
+pub use naml_std_markdown::*;
 
 pub use naml_std_collections::arrays::*;
+pub use naml_std_collections::deque::*;
+pub use naml_std_collections::iter::*;
+pub use naml_std_collections::parallel::*;
 pub use naml_std_collections::maps::{
     naml_map_count, naml_map_contains_key, naml_map_remove, naml_map_clear,
     naml_map_keys, naml_map_values, naml_map_entries, naml_map_first_key, naml_map_first_value,
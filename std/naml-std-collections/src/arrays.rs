@@ -12,6 +12,8 @@
 //! - `sum(arr: [int]) -> int` - Sum all elements
 //! - `min(arr: [int]) -> option<int>` - Find minimum
 //! - `max(arr: [int]) -> option<int>` - Find maximum
+//! - `sum_float / min_float / max_float / sort_float` - The same for `[float]`;
+//!   the type checker picks them for `sum`, `min`, `max` and `sort` on floats
 //!
 //! ## Transformation
 //! - `reversed(arr: [int]) -> [int]` - Create reversed copy
@@ -30,6 +32,10 @@
 //! - `shift(arr: [int]) -> option<int>` - Remove and return first element
 //! - `fill(arr: [int], val: int) -> unit` - Fill array with value
 //! - `clear(arr: [int]) -> unit` - Remove all elements
+//! - `capacity(arr: [int]) -> int` - Elements storable without reallocating
+//! - `reserve(arr: [int], additional: int) -> unit` - Grow capacity ahead of pushes
+//! - `shrink_to_fit(arr: [int]) -> unit` - Release unused capacity
+//! - `extend(arr: [int], other: [int]) -> unit` - Append all elements of other
 //! - `get(arr: [int], index: int) -> option<int>` - Safe index access
 //!
 //! ## Lambda-based Functions
@@ -45,7 +51,8 @@
 //! - `fold(arr: [int], init: int, fn: fn(int, int) -> int) -> int` - Reduce
 //! - `flatten(arr: [[int]]) -> [int]` - Flatten nested arrays
 //! - `sort(arr: [int]) -> [int]` - Sort ascending
-//! - `sort_by(arr: [int], fn: fn(int, int) -> int) -> [int]` - Sort with comparator
+//! - `sort_by(arr: [T], fn: fn(T, T) -> int) -> [T]` - Sort with comparator (any element type)
+//! - `group_by(arr: [T], fn: fn(T) -> K) -> map<K, [T]>` - Group elements by key (any element type)
 //!
 //! ## Mutation Operations
 //! - `insert(arr: [int], index: int, value: int) -> unit` - Insert at index
@@ -54,9 +61,15 @@
 //! - `swap(arr: [int], i: int, j: int) -> unit` - Swap two elements
 //!
 //! ## Deduplication
-//! - `unique(arr: [int]) -> [int]` - Remove duplicates preserving order
+//! - `unique(arr: [T]) -> [T]` - Remove duplicates preserving order (any element type)
 //! - `compact(arr: [int]) -> [int]` - Remove consecutive duplicates
 //!
+//! ## Sorted Search
+//! - `binary_search(arr: [T], val: T) -> option<int>` - Index of `val` in a sorted array
+//! - `lower_bound(arr: [T], val: T) -> int` - First index whose element is not less than `val`
+//! - `upper_bound(arr: [T], val: T) -> int` - First index whose element is greater than `val`
+//! - `partition_point(arr: [T], fn: fn(T) -> bool) -> int` - First index where the predicate is false
+//!
 //! ## Backward Search
 //! - `last_index_of(arr: [int], val: int) -> option<int>` - Find last index
 //! - `find_last(arr: [int], fn: fn(int) -> bool) -> option<int>` - Find last match
@@ -64,11 +77,12 @@
 //!
 //! ## Array Combination
 //! - `concat(arr1: [int], arr2: [int]) -> [int]` - Concatenate arrays
-//! - `zip(arr1: [int], arr2: [int]) -> [[int]]` - Zip two arrays
+//! - `zip(arr1: [T], arr2: [T]) -> [[T]]` - Zip two arrays (any element type)
 //! - `unzip(arr: [[int]]) -> [[int]]` - Unzip array of pairs
 //!
 //! ## Splitting
-//! - `chunk(arr: [int], size: int) -> [[int]]` - Split into chunks
+//! - `chunk(arr: [T], size: int) -> [[T]]` - Split into chunks (any element type)
+//! - `windows(arr: [T], size: int) -> [[T]]` - Overlapping windows (any element type)
 //! - `partition(arr: [int], fn: fn(int) -> bool) -> [[int]]` - Partition by predicate
 //!
 //! ## Set Operations
@@ -88,8 +102,29 @@
 //! - `sample(arr: [int]) -> option<int>` - Random element
 //! - `sample_n(arr: [int], n: int) -> [int]` - Random n elements
 //!
+//! Functions that compare or copy elements of any type take the element
+//! kind (`ARRAY_ELEM_*`) from codegen, so strings compare by content and
+//! copied heap values are retained. `chunk` and `windows` return views
+//! that share the input's buffer instead of copying.
+//!
+
+use std::collections::HashSet;
+
+use naml_std_core::{
+    NamlArray, NamlMap, NamlString, naml_array_make_mut, naml_array_new, naml_array_push,
+    naml_array_view, naml_map_get, naml_map_new_keyed, naml_map_set_array, naml_string_decref,
+    normalize_float_key, retain_array_elem, simd, MAP_KEY_STRING,
+};
+
+pub use naml_std_core::{ARRAY_ELEM_FLOAT, ARRAY_ELEM_HEAP, ARRAY_ELEM_STRING, ARRAY_ELEM_VALUE};
 
-use naml_std_core::{NamlArray, naml_array_new, naml_array_push};
+unsafe fn string_bytes<'a>(elem: i64) -> &'a [u8] {
+    let s = elem as *const NamlString;
+    if s.is_null() {
+        return &[];
+    }
+    std::slice::from_raw_parts((*s).as_ptr(), (*s).len)
+}
 
 /// Get first element of array (returns 0 if empty, use with option wrapper)
 #[unsafe(no_mangle)]
@@ -164,6 +199,60 @@ pub unsafe extern "C" fn naml_array_max(arr: *const NamlArray) -> i64 {
     }
 }
 
+/// Sum all elements of a float array
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_sum_float(arr: *const NamlArray) -> f64 {
+    unsafe {
+        if arr.is_null() {
+            return 0.0;
+        }
+        let mut sum = 0.0;
+        for i in 0..(*arr).len {
+            sum += f64::from_bits(*(*arr).data.add(i) as u64);
+        }
+        sum
+    }
+}
+
+/// Find the minimum of a float array, skipping NaN unless every element is NaN
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_min_float(arr: *const NamlArray) -> f64 {
+    unsafe {
+        if arr.is_null() || (*arr).len == 0 {
+            return f64::NAN;
+        }
+        let mut min = f64::NAN;
+        for i in 0..(*arr).len {
+            min = min.min(f64::from_bits(*(*arr).data.add(i) as u64));
+        }
+        min
+    }
+}
+
+/// Find the maximum of a float array, skipping NaN unless every element is NaN
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_max_float(arr: *const NamlArray) -> f64 {
+    unsafe {
+        if arr.is_null() || (*arr).len == 0 {
+            return f64::NAN;
+        }
+        let mut max = f64::NAN;
+        for i in 0..(*arr).len {
+            max = max.max(f64::from_bits(*(*arr).data.add(i) as u64));
+        }
+        max
+    }
+}
+
 /// Create a new reversed copy of array
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_array_reversed(arr: *const NamlArray) -> *mut NamlArray {
@@ -181,42 +270,44 @@ pub unsafe extern "C" fn naml_array_reversed(arr: *const NamlArray) -> *mut Naml
     }
 }
 
-/// Take first n elements
+/// Take first n elements, as a view sharing `arr`'s buffer
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_array_take(arr: *const NamlArray, n: i64) -> *mut NamlArray {
+pub unsafe extern "C" fn naml_array_take(arr: *mut NamlArray, n: i64, elem_kind: i64) -> *mut NamlArray {
     unsafe {
         if arr.is_null() || n <= 0 {
             return naml_array_new(0);
         }
         let take_count = std::cmp::min(n as usize, (*arr).len);
-        let new_arr = naml_array_new(take_count);
-        for i in 0..take_count {
-            naml_array_push(new_arr, *(*arr).data.add(i));
+        if take_count == 0 {
+            return naml_array_new(0);
         }
-        new_arr
+        naml_array_view(arr, 0, take_count, elem_kind)
     }
 }
 
-/// Drop first n elements
+/// Drop first n elements, as a view sharing `arr`'s buffer
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_array_drop(arr: *const NamlArray, n: i64) -> *mut NamlArray {
+pub unsafe extern "C" fn naml_array_drop(arr: *mut NamlArray, n: i64, elem_kind: i64) -> *mut NamlArray {
     unsafe {
         if arr.is_null() {
             return naml_array_new(0);
         }
         let skip = std::cmp::min(n as usize, (*arr).len);
-        let remaining = (*arr).len - skip;
-        let new_arr = naml_array_new(remaining);
-        for i in skip..(*arr).len {
-            naml_array_push(new_arr, *(*arr).data.add(i));
+        if skip == (*arr).len {
+            return naml_array_new(0);
         }
-        new_arr
+        naml_array_view(arr, skip, (*arr).len, elem_kind)
     }
 }
 
-/// Slice array from start to end (exclusive)
+/// Slice array from start to end (exclusive), as a view sharing `arr`'s buffer
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_array_slice(arr: *const NamlArray, start: i64, end: i64) -> *mut NamlArray {
+pub unsafe extern "C" fn naml_array_slice(
+    arr: *mut NamlArray,
+    start: i64,
+    end: i64,
+    elem_kind: i64,
+) -> *mut NamlArray {
     unsafe {
         if arr.is_null() {
             return naml_array_new(0);
@@ -227,12 +318,7 @@ pub unsafe extern "C" fn naml_array_slice(arr: *const NamlArray, start: i64, end
         if start_idx >= end_idx {
             return naml_array_new(0);
         }
-        let slice_len = end_idx - start_idx;
-        let new_arr = naml_array_new(slice_len);
-        for i in start_idx..end_idx {
-            naml_array_push(new_arr, *(*arr).data.add(i));
-        }
-        new_arr
+        naml_array_view(arr, start_idx, end_idx, elem_kind)
     }
 }
 
@@ -243,12 +329,58 @@ pub unsafe extern "C" fn naml_array_index_of(arr: *const NamlArray, value: i64)
         if arr.is_null() {
             return -1;
         }
-        for i in 0..(*arr).len {
-            if *(*arr).data.add(i) == value {
-                return i as i64;
-            }
-        }
-        -1
+        let values = std::slice::from_raw_parts((*arr).data, (*arr).len);
+        simd::position_i64(values, value).map_or(-1, |i| i as i64)
+    }
+}
+
+/// Order of two elements of the given kind: ints (and other values) as
+/// signed integers, floats as by `sort_float`, strings by content
+unsafe fn compare_elems(kind: i64, a: i64, b: i64) -> std::cmp::Ordering {
+    match kind {
+        ARRAY_ELEM_FLOAT => f64::from_bits(a as u64).total_cmp(&f64::from_bits(b as u64)),
+        ARRAY_ELEM_STRING => string_bytes(a).cmp(string_bytes(b)),
+        _ => a.cmp(&b),
+    }
+}
+
+unsafe fn sorted_elems<'a>(arr: *const NamlArray) -> &'a [i64] {
+    if arr.is_null() {
+        return &[];
+    }
+    std::slice::from_raw_parts((*arr).data, (*arr).len)
+}
+
+/// First index whose element is not less than `value` (the array's length
+/// if there is none); the array must be sorted ascending
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_lower_bound(arr: *const NamlArray, value: i64, kind: i64) -> i64 {
+    sorted_elems(arr).partition_point(|&e| compare_elems(kind, e, value).is_lt()) as i64
+}
+
+/// First index whose element is greater than `value`
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_upper_bound(arr: *const NamlArray, value: i64, kind: i64) -> i64 {
+    sorted_elems(arr).partition_point(|&e| compare_elems(kind, e, value).is_le()) as i64
+}
+
+/// Index of the first element equal to `value` in a sorted array, or -1
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_binary_search(arr: *const NamlArray, value: i64, kind: i64) -> i64 {
+    let values = sorted_elems(arr);
+    let index = naml_array_lower_bound(arr, value, kind) as usize;
+    match values.get(index) {
+        Some(&e) if compare_elems(kind, e, value).is_eq() => index as i64,
+        _ => -1,
     }
 }
 
@@ -318,6 +450,24 @@ pub unsafe extern "C" fn naml_array_count_if(
     count
 }
 
+/// First index where the predicate is false, for an array partitioned so
+/// that every element satisfying it comes first
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_partition_point(
+    arr: *const NamlArray,
+    func_ptr: i64,
+    data_ptr: i64,
+) -> i64 {
+    if func_ptr == 0 {
+        return 0;
+    }
+    let predicate: PredicateFn = std::mem::transmute(func_ptr as usize);
+    sorted_elems(arr).partition_point(|&e| predicate(data_ptr, e) != 0) as i64
+}
+
 /// Map each element through a function, returning a new array (apply)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_array_map(
@@ -454,11 +604,27 @@ pub unsafe extern "C" fn naml_array_sort(arr: *mut NamlArray) -> *mut NamlArray
     if arr.is_null() || (*arr).len <= 1 {
         return arr;
     }
+    naml_array_make_mut(arr);
     let slice = std::slice::from_raw_parts_mut((*arr).data, (*arr).len);
     slice.sort();
     arr
 }
 
+/// Sort a float array in place (ascending, -0.0 before 0.0, NaN last)
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_sort_float(arr: *mut NamlArray) -> *mut NamlArray {
+    if arr.is_null() || (*arr).len <= 1 {
+        return arr;
+    }
+    naml_array_make_mut(arr);
+    let slice = std::slice::from_raw_parts_mut((*arr).data, (*arr).len);
+    slice.sort_by(|a, b| f64::from_bits(*a as u64).total_cmp(&f64::from_bits(*b as u64)));
+    arr
+}
+
 /// Sort array in place using a comparator function
 /// Comparator should return < 0 if a < b, 0 if a == b, > 0 if a > b
 #[unsafe(no_mangle)]
@@ -471,6 +637,7 @@ pub unsafe extern "C" fn naml_array_sort_by(
         return arr;
     }
     let comparator: CompareFn = std::mem::transmute(func_ptr as usize);
+    naml_array_make_mut(arr);
     let slice = std::slice::from_raw_parts_mut((*arr).data, (*arr).len);
     slice.sort_by(|a, b| {
         let cmp = comparator(data_ptr, *a, *b);
@@ -479,6 +646,43 @@ pub unsafe extern "C" fn naml_array_sort_by(
     arr
 }
 
+/// Group elements by the key `key_fn` returns for them, keeping the order
+/// of elements within each group. `key_kind` is the `MAP_KEY_*` kind of the
+/// keys and `kind` the element kind.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_group_by(
+    arr: *const NamlArray,
+    func_ptr: i64,
+    data_ptr: i64,
+    kind: i64,
+    key_kind: i64,
+) -> *mut NamlMap {
+    let groups = naml_map_new_keyed(0, key_kind);
+    if arr.is_null() || func_ptr == 0 {
+        return groups;
+    }
+    let key_fn: MapperFn = std::mem::transmute(func_ptr as usize);
+    for i in 0..(*arr).len {
+        let elem = *(*arr).data.add(i);
+        let key = key_fn(data_ptr, elem);
+        let mut group = naml_map_get(groups, key) as *mut NamlArray;
+        if group.is_null() {
+            group = naml_array_new(0);
+            naml_map_set_array(groups, key, group as i64);
+        }
+        retain_array_elem(kind, elem);
+        naml_array_push(group, elem);
+        // The map holds its own reference to string keys
+        if key_kind == MAP_KEY_STRING as i64 && key != 0 {
+            naml_string_decref(key as *mut NamlString);
+        }
+    }
+    groups
+}
+
 /// Insert element at index, shifting subsequent elements right
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_array_insert(arr: *mut NamlArray, index: i64, value: i64) {
@@ -487,16 +691,7 @@ pub unsafe extern "C" fn naml_array_insert(arr: *mut NamlArray, index: i64, valu
     }
     let len = (*arr).len;
     let idx = if index < 0 { 0 } else { std::cmp::min(index as usize, len) };
-    if (*arr).len >= (*arr).capacity {
-        let new_cap = if (*arr).capacity == 0 { 4 } else { (*arr).capacity * 2 };
-        let new_data = std::alloc::realloc(
-            (*arr).data as *mut u8,
-            std::alloc::Layout::array::<i64>((*arr).capacity).unwrap(),
-            new_cap * std::mem::size_of::<i64>(),
-        ) as *mut i64;
-        (*arr).data = new_data;
-        (*arr).capacity = new_cap;
-    }
+    naml_std_core::naml_array_reserve(arr, 1);
     if idx < len {
         std::ptr::copy((*arr).data.add(idx), (*arr).data.add(idx + 1), len - idx);
     }
@@ -511,6 +706,7 @@ pub unsafe extern "C" fn naml_array_remove_at(arr: *mut NamlArray, index: i64) -
         return 0;
     }
     let idx = index as usize;
+    naml_array_make_mut(arr);
     let value = *(*arr).data.add(idx);
     let len = (*arr).len;
     if idx < len - 1 {
@@ -528,6 +724,7 @@ pub unsafe extern "C" fn naml_array_remove(arr: *mut NamlArray, value: i64) -> i
     }
     for i in 0..(*arr).len {
         if *(*arr).data.add(i) == value {
+            naml_array_make_mut(arr);
             let len = (*arr).len;
             if i < len - 1 {
                 std::ptr::copy((*arr).data.add(i + 1), (*arr).data.add(i), len - i - 1);
@@ -551,28 +748,35 @@ pub unsafe extern "C" fn naml_array_swap(arr: *mut NamlArray, i: i64, j: i64) {
     }
     let idx_i = i as usize;
     let idx_j = j as usize;
+    naml_array_make_mut(arr);
     let temp = *(*arr).data.add(idx_i);
     *(*arr).data.add(idx_i) = *(*arr).data.add(idx_j);
     *(*arr).data.add(idx_j) = temp;
 }
 
-/// Create new array with duplicates removed (preserving first occurrence order)
+/// Create new array with duplicates removed (preserving first occurrence order).
+/// Strings compare by content, floats by value (with -0.0 equal to 0.0 and
+/// all NaNs equal) and other heap values by identity.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_array_unique(arr: *const NamlArray) -> *mut NamlArray {
+pub unsafe extern "C" fn naml_array_unique(arr: *const NamlArray, kind: i64) -> *mut NamlArray {
     if arr.is_null() {
         return naml_array_new(0);
     }
-    let new_arr = naml_array_new((*arr).len);
-    for i in 0..(*arr).len {
-        let val = *(*arr).data.add(i);
-        let mut found = false;
-        for j in 0..(*new_arr).len {
-            if *(*new_arr).data.add(j) == val {
-                found = true;
-                break;
-            }
-        }
-        if !found {
+    let values = std::slice::from_raw_parts((*arr).data, (*arr).len);
+    let new_arr = naml_array_new(values.len());
+    let mut seen_values = HashSet::new();
+    let mut seen_strings = HashSet::new();
+    for &val in values {
+        let is_new = match kind {
+            ARRAY_ELEM_STRING => seen_strings.insert(string_bytes(val)),
+            ARRAY_ELEM_FLOAT => seen_values.insert(normalize_float_key(val).unwrap_or(f64::NAN.to_bits() as i64)),
+            _ => seen_values.insert(val),
+        };
+        if is_new {
+            retain_array_elem(kind, val);
             naml_array_push(new_arr, val);
         }
     }
@@ -601,12 +805,8 @@ pub unsafe extern "C" fn naml_array_last_index_of(arr: *const NamlArray, value:
     if arr.is_null() || (*arr).len == 0 {
         return -1;
     }
-    for i in (0..(*arr).len).rev() {
-        if *(*arr).data.add(i) == value {
-            return i as i64;
-        }
-    }
-    -1
+    let values = std::slice::from_raw_parts((*arr).data, (*arr).len);
+    simd::rposition_i64(values, value).map_or(-1, |i| i as i64)
 }
 
 /// Find last element satisfying predicate (returns element, sets found_flag)
@@ -682,6 +882,7 @@ pub unsafe extern "C" fn naml_array_concat(
 pub unsafe extern "C" fn naml_array_zip(
     arr1: *const NamlArray,
     arr2: *const NamlArray,
+    kind: i64,
 ) -> *mut NamlArray {
     let len1 = if arr1.is_null() { 0 } else { (*arr1).len };
     let len2 = if arr2.is_null() { 0 } else { (*arr2).len };
@@ -689,8 +890,10 @@ pub unsafe extern "C" fn naml_array_zip(
     let result = naml_array_new(min_len);
     for i in 0..min_len {
         let pair = naml_array_new(2);
-        naml_array_push(pair, *(*arr1).data.add(i));
-        naml_array_push(pair, *(*arr2).data.add(i));
+        for elem in [*(*arr1).data.add(i), *(*arr2).data.add(i)] {
+            retain_array_elem(kind, elem);
+            naml_array_push(pair, elem);
+        }
         naml_array_push(result, pair as i64);
     }
     result
@@ -721,29 +924,43 @@ pub unsafe extern "C" fn naml_array_unzip(arr: *const NamlArray) -> *mut NamlArr
     result
 }
 
-/// Split array into chunks of given size
+/// Split array into chunks of given size; the last chunk may be shorter
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_array_chunk(arr: *const NamlArray, size: i64) -> *mut NamlArray {
+pub unsafe extern "C" fn naml_array_chunk(arr: *mut NamlArray, size: i64, elem_kind: i64) -> *mut NamlArray {
     if arr.is_null() || size <= 0 {
         return naml_array_new(0);
     }
     let chunk_size = size as usize;
     let len = (*arr).len;
-    let num_chunks = (len + chunk_size - 1) / chunk_size;
-    let result = naml_array_new(num_chunks);
+    let result = naml_array_new(len.div_ceil(chunk_size));
     let mut i = 0;
     while i < len {
         let end = std::cmp::min(i + chunk_size, len);
-        let chunk = naml_array_new(end - i);
-        for j in i..end {
-            naml_array_push(chunk, *(*arr).data.add(j));
-        }
-        naml_array_push(result, chunk as i64);
+        naml_array_push(result, naml_array_view(arr, i, end, elem_kind) as i64);
         i = end;
     }
     result
 }
 
+/// Every run of `size` consecutive elements, in order. Empty when `size`
+/// is not positive or larger than the array.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_windows(arr: *mut NamlArray, size: i64, elem_kind: i64) -> *mut NamlArray {
+    if arr.is_null() || size <= 0 || size as usize > (*arr).len {
+        return naml_array_new(0);
+    }
+    let window_size = size as usize;
+    let count = (*arr).len - window_size + 1;
+    let result = naml_array_new(count);
+    for i in 0..count {
+        naml_array_push(result, naml_array_view(arr, i, i + window_size, elem_kind) as i64);
+    }
+    result
+}
+
 /// Partition array by predicate into [matching, non-matching]
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_array_partition(
@@ -1073,6 +1290,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_float_aggregates() {
+        unsafe {
+            let arr = naml_array_new(5);
+            for x in [2.5, -1.25, f64::NAN, 0.0, -0.0] {
+                naml_array_push(arr, x.to_bits() as i64);
+            }
+            assert!(naml_array_sum_float(arr).is_nan());
+            assert_eq!(naml_array_min_float(arr), -1.25);
+            assert_eq!(naml_array_max_float(arr), 2.5);
+            naml_array_sort_float(arr);
+            let sorted: Vec<f64> = (0..5).map(|i| f64::from_bits(*(*arr).data.add(i) as u64)).collect();
+            assert_eq!(&sorted[..4], &[-1.25, -0.0, 0.0, 2.5]);
+            assert!(sorted[1].is_sign_negative() && sorted[4].is_nan());
+            let finite = naml_array_new(2);
+            naml_array_push(finite, 2.5f64.to_bits() as i64);
+            naml_array_push(finite, (-1.25f64).to_bits() as i64);
+            assert_eq!(naml_array_sum_float(finite), 1.25);
+        }
+    }
+
+    #[test]
+    fn test_sorted_search() {
+        unsafe extern "C" fn below_four(_data: i64, x: i64) -> i64 {
+            (x < 4) as i64
+        }
+        unsafe {
+            let arr = naml_array_new(6);
+            for x in [1, 3, 3, 3, 5, 8] {
+                naml_array_push(arr, x);
+            }
+            let kind = ARRAY_ELEM_VALUE;
+            assert_eq!(naml_array_lower_bound(arr, 3, kind), 1);
+            assert_eq!(naml_array_upper_bound(arr, 3, kind), 4);
+            assert_eq!(naml_array_binary_search(arr, 3, kind), 1);
+            assert_eq!(naml_array_binary_search(arr, 4, kind), -1);
+            assert_eq!(naml_array_binary_search(arr, 9, kind), -1);
+            assert_eq!(naml_array_lower_bound(arr, 9, kind), 6);
+            assert_eq!(naml_array_partition_point(arr, below_four as *const () as i64, 0), 4);
+
+            let floats = naml_array_new(3);
+            for x in [-2.5f64, 0.5, 1.5] {
+                naml_array_push(floats, x.to_bits() as i64);
+            }
+            assert_eq!(naml_array_binary_search(floats, 0.5f64.to_bits() as i64, ARRAY_ELEM_FLOAT), 1);
+            assert_eq!(naml_array_lower_bound(floats, 0.0f64.to_bits() as i64, ARRAY_ELEM_FLOAT), 1);
+            assert_eq!(naml_array_lower_bound(std::ptr::null(), 1, kind), 0);
+        }
+    }
+
     #[test]
     fn test_reversed() {
         unsafe {
@@ -1104,4 +1371,46 @@ mod tests {
             assert_eq!(*(*arr).data.add(4), 5);
         }
     }
+
+    #[test]
+    fn test_unique_by_kind() {
+        unsafe {
+            let words = naml_array_new(3);
+            let a1 = naml_std_core::naml_string_new("a".as_ptr(), 1);
+            let a2 = naml_std_core::naml_string_new("a".as_ptr(), 1);
+            naml_array_push(words, a1 as i64);
+            naml_array_push(words, a2 as i64);
+            let distinct = naml_array_unique(words, ARRAY_ELEM_STRING);
+            assert_eq!((*distinct).len, 1);
+            assert_eq!((*a1).header.refcount(), 2);
+
+            let floats = naml_array_new(3);
+            for f in [0.0f64, -0.0, f64::NAN, f64::NAN] {
+                naml_array_push(floats, f.to_bits() as i64);
+            }
+            assert_eq!((*naml_array_unique(floats, ARRAY_ELEM_FLOAT)).len, 2);
+            assert_eq!((*naml_array_unique(floats, ARRAY_ELEM_VALUE)).len, 3);
+        }
+    }
+
+    #[test]
+    fn test_chunk_and_windows() {
+        unsafe {
+            let arr = naml_array_new(5);
+            for i in 1..=5 {
+                naml_array_push(arr, i);
+            }
+            let chunks = naml_array_chunk(arr, 2, ARRAY_ELEM_VALUE);
+            assert_eq!((*chunks).len, 3);
+            let last = *(*chunks).data.add(2) as *const NamlArray;
+            assert_eq!(((*last).len, *(*last).data), (1, 5));
+
+            let windows = naml_array_windows(arr, 3, ARRAY_ELEM_VALUE);
+            assert_eq!((*windows).len, 3);
+            let second = *(*windows).data.add(1) as *const NamlArray;
+            assert_eq!(std::slice::from_raw_parts((*second).data, (*second).len), &[2, 3, 4]);
+            assert_eq!((*naml_array_windows(arr, 6, ARRAY_ELEM_VALUE)).len, 0);
+            assert_eq!((*naml_array_windows(arr, 0, ARRAY_ELEM_VALUE)).len, 0);
+        }
+    }
 }
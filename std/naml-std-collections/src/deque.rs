@@ -0,0 +1,210 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+//!
+//! Deque - Double-Ended Queue
+//!
+//! A `deque<T>` is a growable ring buffer (Rust `VecDeque`), so pushing
+//! and popping at either end is amortized O(1). Arrays only grow at the
+//! back; `shift` and inserting at index 0 move every element.
+//!
+//! Each deque records the element kind (`ARRAY_ELEM_*`) codegen passes to
+//! `naml_deque_new`. The deque holds a reference to string and other heap
+//! elements: pops hand that reference to the caller, and freeing the deque
+//! releases whatever is left.
+//!
+
+use std::collections::VecDeque;
+
+use naml_std_core::{
+    HeapHeader, HeapTag, NamlArray, NamlMap, NamlPriorityQueue, NamlSet, NamlString,
+    NamlStringBuilder, NamlStruct, naml_array_decref, naml_map_decref,
+    naml_priority_queue_decref, naml_set_decref, naml_string_builder_decref, naml_string_decref,
+    naml_struct_decref,
+};
+
+use crate::arrays::{ARRAY_ELEM_HEAP, ARRAY_ELEM_STRING};
+
+/// A heap-allocated double-ended queue
+#[repr(C)]
+pub struct NamlDeque {
+    pub header: HeapHeader,
+    pub kind: i64,
+    pub items: VecDeque<i64>,
+}
+
+impl Drop for NamlDeque {
+    fn drop(&mut self) {
+        for elem in std::mem::take(&mut self.items) {
+            unsafe { release_elem(self.kind, elem) };
+        }
+    }
+}
+
+/// Drop the deque's reference to an element it still holds
+unsafe fn release_elem(kind: i64, elem: i64) {
+    if elem == 0 || (kind != ARRAY_ELEM_STRING && kind != ARRAY_ELEM_HEAP) {
+        return;
+    }
+    let header = elem as *mut HeapHeader;
+    match (*header).tag {
+        HeapTag::String => naml_string_decref(elem as *mut NamlString),
+        HeapTag::Array => naml_array_decref(elem as *mut NamlArray),
+        HeapTag::Map => naml_map_decref(elem as *mut NamlMap),
+        HeapTag::Struct => naml_struct_decref(elem as *mut NamlStruct),
+        HeapTag::StringBuilder => naml_string_builder_decref(elem as *mut NamlStringBuilder),
+        HeapTag::Set => naml_set_decref(elem as *mut NamlSet),
+        HeapTag::PriorityQueue => naml_priority_queue_decref(elem as *mut NamlPriorityQueue),
+        HeapTag::Deque => naml_deque_decref(elem as *mut NamlDeque),
+        HeapTag::Iterator => crate::iter::naml_iter_decref(elem as *mut crate::iter::NamlIter),
+        _ => {
+            (*header).decref();
+        }
+    }
+}
+
+unsafe fn push(dq: *mut NamlDeque, value: i64, front: bool) {
+    if dq.is_null() {
+        return;
+    }
+    let kind = (*dq).kind;
+    if (kind == ARRAY_ELEM_STRING || kind == ARRAY_ELEM_HEAP) && value != 0 {
+        (*(value as *mut HeapHeader)).incref();
+    }
+    if front {
+        (*dq).items.push_front(value);
+    } else {
+        (*dq).items.push_back(value);
+    }
+}
+
+unsafe fn pop(dq: *mut NamlDeque, found_flag: *mut i64, front: bool) -> i64 {
+    let popped = if dq.is_null() {
+        None
+    } else if front {
+        (*dq).items.pop_front()
+    } else {
+        (*dq).items.pop_back()
+    };
+    if !found_flag.is_null() {
+        *found_flag = popped.is_some() as i64;
+    }
+    popped.unwrap_or(0)
+}
+
+/// Create an empty deque whose elements are of the given `ARRAY_ELEM_*` kind
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_deque_new(kind: i64) -> *mut NamlDeque {
+    Box::into_raw(Box::new(NamlDeque {
+        header: HeapHeader::new(HeapTag::Deque),
+        kind,
+        items: VecDeque::new(),
+    }))
+}
+
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_push_front(dq: *mut NamlDeque, value: i64) {
+    push(dq, value, true);
+}
+
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_push_back(dq: *mut NamlDeque, value: i64) {
+    push(dq, value, false);
+}
+
+/// Remove and return the first element; `found_flag` is 0 if the deque is empty
+///
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque and
+/// `found_flag` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_pop_front(dq: *mut NamlDeque, found_flag: *mut i64) -> i64 {
+    pop(dq, found_flag, true)
+}
+
+/// Remove and return the last element; `found_flag` is 0 if the deque is empty
+///
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque and
+/// `found_flag` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_pop_back(dq: *mut NamlDeque, found_flag: *mut i64) -> i64 {
+    pop(dq, found_flag, false)
+}
+
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_len(dq: *const NamlDeque) -> i64 {
+    if dq.is_null() {
+        return 0;
+    }
+    (*dq).items.len() as i64
+}
+
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_incref(dq: *mut NamlDeque) {
+    if !dq.is_null() {
+        (*dq).header.incref();
+    }
+}
+
+/// Decrement reference count and free the deque, releasing its heap elements
+///
+/// # Safety
+/// The caller must ensure `dq` is a valid pointer to a NamlDeque or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_deque_decref(dq: *mut NamlDeque) {
+    if !dq.is_null() && (*dq).header.decref() {
+        drop(Box::from_raw(dq));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrays::ARRAY_ELEM_VALUE;
+    use naml_std_core::naml_string_new;
+
+    #[test]
+    fn test_both_ends() {
+        unsafe {
+            let dq = naml_deque_new(ARRAY_ELEM_VALUE);
+            naml_deque_push_back(dq, 2);
+            naml_deque_push_back(dq, 3);
+            naml_deque_push_front(dq, 1);
+            assert_eq!(naml_deque_len(dq), 3);
+            let mut found = 0;
+            assert_eq!(naml_deque_pop_front(dq, &mut found), 1);
+            assert_eq!(naml_deque_pop_back(dq, &mut found), 3);
+            assert_eq!(naml_deque_pop_back(dq, &mut found), 2);
+            assert_eq!(found, 1);
+            naml_deque_pop_front(dq, &mut found);
+            assert_eq!(found, 0);
+            naml_deque_decref(dq);
+        }
+    }
+
+    #[test]
+    fn test_string_elements() {
+        unsafe {
+            let dq = naml_deque_new(ARRAY_ELEM_STRING);
+            let a = naml_string_new("a".as_ptr(), 1);
+            let b = naml_string_new("b".as_ptr(), 1);
+            naml_deque_push_back(dq, a as i64);
+            naml_deque_push_front(dq, b as i64);
+            assert_eq!((*a).header.refcount(), 2);
+            let mut found = 0;
+            let first = naml_deque_pop_front(dq, &mut found) as *mut NamlString;
+            assert_eq!((*first).as_str(), "b");
+            naml_string_decref(first);
+            naml_deque_decref(dq);
+            assert_eq!((*a).header.refcount(), 1);
+            assert_eq!((*b).header.refcount(), 1);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+//!
+//! Iterator - Lazy Collection Pipelines
+//!
+//! `apply` and `where` each build a full array, so
+//! `where(apply(arr, f), g)` allocates an intermediate array that is
+//! thrown away. An `iterator<T>` instead records the pipeline (a source
+//! array plus map / filter / take stages) and pulls one element at a time
+//! through every stage, so nothing is materialized until `iter_collect`
+//! or a `for` loop consumes it.
+//!
+//! Adding a stage returns a new iterator that shares the source array and
+//! copies the stages, so the iterator it was built from is unaffected.
+//! Stages call closures with the same `(data_ptr, element)` convention as
+//! the array lambda helpers.
+//!
+//! Elements are lent, as with `apply` and `where`: the iterator holds a
+//! reference to its source array, not to the elements.
+//!
+
+use naml_std_core::{naml_array_decref, naml_array_incref, naml_array_new, naml_array_push, HeapHeader, HeapTag, NamlArray};
+
+type StageFn = unsafe extern "C" fn(data_ptr: i64, element: i64) -> i64;
+
+#[derive(Clone, Copy)]
+enum Stage {
+    Map { func: StageFn, data: i64 },
+    Filter { func: StageFn, data: i64 },
+    Take { remaining: i64 },
+}
+
+/// A heap-allocated lazy iterator over an array
+#[repr(C)]
+pub struct NamlIter {
+    pub header: HeapHeader,
+    source: *mut NamlArray,
+    pos: usize,
+    stages: Vec<Stage>,
+    done: bool,
+}
+
+impl Drop for NamlIter {
+    fn drop(&mut self) {
+        unsafe { naml_array_decref(self.source) };
+    }
+}
+
+impl NamlIter {
+    /// Pull the next element through every stage
+    unsafe fn next(&mut self) -> Option<i64> {
+        'pull: while !self.done {
+            if self.source.is_null() || self.pos >= (*self.source).len {
+                self.done = true;
+                break;
+            }
+            let mut value = *(*self.source).data.add(self.pos);
+            self.pos += 1;
+            for stage in &mut self.stages {
+                match stage {
+                    Stage::Map { func, data } => value = func(*data, value),
+                    Stage::Filter { func, data } => {
+                        if func(*data, value) == 0 {
+                            continue 'pull;
+                        }
+                    }
+                    Stage::Take { remaining } => {
+                        if *remaining <= 0 {
+                            self.done = true;
+                            break 'pull;
+                        }
+                        *remaining -= 1;
+                    }
+                }
+            }
+            return Some(value);
+        }
+        None
+    }
+}
+
+fn alloc_iter(source: *mut NamlArray, pos: usize, stages: Vec<Stage>, done: bool) -> *mut NamlIter {
+    unsafe { naml_array_incref(source) };
+    Box::into_raw(Box::new(NamlIter {
+        header: HeapHeader::new(HeapTag::Iterator),
+        source,
+        pos,
+        stages,
+        done,
+    }))
+}
+
+/// A copy of `it` with one more stage
+unsafe fn with_stage(it: *const NamlIter, stage: Stage) -> *mut NamlIter {
+    if it.is_null() {
+        return alloc_iter(std::ptr::null_mut(), 0, vec![stage], true);
+    }
+    let mut stages = (*it).stages.clone();
+    stages.push(stage);
+    alloc_iter((*it).source, (*it).pos, stages, (*it).done)
+}
+
+/// Iterate over the elements of `arr`; the iterator keeps the array alive
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_from_array(arr: *mut NamlArray) -> *mut NamlIter {
+    alloc_iter(arr, 0, Vec::new(), arr.is_null())
+}
+
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_map(it: *const NamlIter, func_ptr: i64, data_ptr: i64) -> *mut NamlIter {
+    let func: StageFn = std::mem::transmute(func_ptr as usize);
+    with_stage(it, Stage::Map { func, data: data_ptr })
+}
+
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_filter(it: *const NamlIter, func_ptr: i64, data_ptr: i64) -> *mut NamlIter {
+    let func: StageFn = std::mem::transmute(func_ptr as usize);
+    with_stage(it, Stage::Filter { func, data: data_ptr })
+}
+
+/// Stop after `n` elements; a negative `n` is treated as 0
+///
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_take(it: *const NamlIter, n: i64) -> *mut NamlIter {
+    with_stage(it, Stage::Take { remaining: n.max(0) })
+}
+
+/// Advance the iterator; `found_flag` is 0 once it is exhausted
+///
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter or null and
+/// `found_flag` is a valid pointer to an i64 or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_next(it: *mut NamlIter, found_flag: *mut i64) -> i64 {
+    let next = if it.is_null() { None } else { (*it).next() };
+    if !found_flag.is_null() {
+        *found_flag = next.is_some() as i64;
+    }
+    next.unwrap_or(0)
+}
+
+/// Drain the remaining elements into a new array
+///
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_collect(it: *mut NamlIter) -> *mut NamlArray {
+    let arr = naml_array_new(0);
+    if it.is_null() {
+        return arr;
+    }
+    while let Some(value) = (*it).next() {
+        naml_array_push(arr, value);
+    }
+    arr
+}
+
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_incref(it: *mut NamlIter) {
+    if !it.is_null() {
+        (*it).header.incref();
+    }
+}
+
+/// Decrement reference count and free the iterator, releasing its source array
+///
+/// # Safety
+/// The caller must ensure `it` is a valid pointer to a NamlIter or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_iter_decref(it: *mut NamlIter) {
+    if !it.is_null() && (*it).header.decref() {
+        drop(Box::from_raw(it));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use naml_std_core::naml_array_get;
+
+    unsafe extern "C" fn double(_data: i64, x: i64) -> i64 {
+        x * 2
+    }
+
+    unsafe extern "C" fn above_limit(data: i64, x: i64) -> i64 {
+        (x > data) as i64
+    }
+
+    unsafe fn array_of(values: &[i64]) -> *mut NamlArray {
+        let arr = naml_array_new(values.len());
+        for &v in values {
+            naml_array_push(arr, v);
+        }
+        arr
+    }
+
+    unsafe fn to_vec(arr: *mut NamlArray) -> Vec<i64> {
+        (0..(*arr).len as i64).map(|i| naml_array_get(arr, i)).collect()
+    }
+
+    #[test]
+    fn test_fused_pipeline() {
+        unsafe {
+            let arr = array_of(&[1, 2, 3, 4, 5, 6]);
+            let it = naml_iter_from_array(arr);
+            let mapped = naml_iter_map(it, double as *const () as i64, 0);
+            let filtered = naml_iter_filter(mapped, above_limit as *const () as i64, 4);
+            let taken = naml_iter_take(filtered, 2);
+            assert_eq!(to_vec(naml_iter_collect(taken)), [6, 8]);
+            assert_eq!((*arr).header.refcount(), 5);
+
+            // Earlier iterators are unaffected by the stages added after them
+            assert_eq!(to_vec(naml_iter_collect(mapped)), [2, 4, 6, 8, 10, 12]);
+            for i in [it, mapped, filtered, taken] {
+                naml_iter_decref(i);
+            }
+            assert_eq!((*arr).header.refcount(), 1);
+            naml_array_decref(arr);
+        }
+    }
+
+    #[test]
+    fn test_next_and_take_zero() {
+        unsafe {
+            let arr = array_of(&[7, 8]);
+            let it = naml_iter_from_array(arr);
+            let mut found = 0;
+            assert_eq!(naml_iter_next(it, &mut found), 7);
+            assert_eq!(found, 1);
+            let rest = naml_iter_map(it, double as *const () as i64, 0);
+            assert_eq!(naml_iter_next(rest, &mut found), 16);
+            naml_iter_next(rest, &mut found);
+            assert_eq!(found, 0);
+
+            let none = naml_iter_take(it, 0);
+            assert_eq!(to_vec(naml_iter_collect(none)), [] as [i64; 0]);
+            for i in [it, rest, none] {
+                naml_iter_decref(i);
+            }
+            naml_array_decref(arr);
+        }
+    }
+}
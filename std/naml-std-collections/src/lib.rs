@@ -1,5 +1,11 @@
 pub mod arrays;
+pub mod deque;
+pub mod iter;
 pub mod maps;
+pub mod parallel;
 
 pub use arrays::*;
+pub use deque::*;
+pub use iter::*;
 pub use maps::*;
+pub use parallel::*;
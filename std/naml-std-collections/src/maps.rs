@@ -34,10 +34,10 @@
 /// - `all(m, fn) -> bool` - All entries match
 ///
 
-use naml_std_core::{NamlArray, NamlString, NamlMap,
+use naml_std_core::{NamlArray, NamlMap,
                     naml_array_new, naml_array_push,
-                    naml_map_new, naml_map_set, naml_map_contains,
-                    hash_string, string_eq};
+                    naml_map_new, naml_map_new_like, naml_map_set, naml_map_contains,
+                    map_key_eq, map_key_hash, map_lookup_key};
 
 /// Get number of entries in map
 #[unsafe(no_mangle)]
@@ -54,7 +54,10 @@ pub unsafe extern "C" fn naml_map_contains_key(map: *const NamlMap, key: i64) ->
     if map.is_null() {
         return 0;
     }
-    let hash = hash_string(key as *const NamlString);
+    let Some(key) = map_lookup_key(map, key) else {
+        return 0;
+    };
+    let hash = map_key_hash(map, key);
     let mut idx = (hash as usize) % (*map).capacity;
     let start_idx = idx;
     loop {
@@ -62,7 +65,7 @@ pub unsafe extern "C" fn naml_map_contains_key(map: *const NamlMap, key: i64) ->
         if !(*entry).occupied {
             return 0;
         }
-        if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
+        if map_key_eq(map, (*entry).key, key) {
             return 1;
         }
         idx = (idx + 1) % (*map).capacity;
@@ -82,7 +85,10 @@ pub unsafe extern "C" fn naml_map_remove(map: *mut NamlMap, key: i64, found_flag
         }
         return 0;
     }
-    let hash = hash_string(key as *const NamlString);
+    let Some(key) = map_lookup_key(map, key) else {
+        return 0;
+    };
+    let hash = map_key_hash(map, key);
     let mut idx = (hash as usize) % (*map).capacity;
     let start_idx = idx;
     loop {
@@ -93,7 +99,7 @@ pub unsafe extern "C" fn naml_map_remove(map: *mut NamlMap, key: i64, found_flag
             }
             return 0;
         }
-        if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
+        if map_key_eq(map, (*entry).key, key) {
             let value = (*entry).value;
             (*entry).occupied = false;
             (*entry).key = 0;
@@ -339,7 +345,7 @@ pub unsafe extern "C" fn naml_map_transform(
         return naml_map_new(16);
     }
     let transformer: MapTransformFn = std::mem::transmute(func_ptr as usize);
-    let result = naml_map_new((*map).capacity);
+    let result = naml_map_new_like(map, (*map).capacity);
     for i in 0..(*map).capacity {
         let entry = (*map).entries.add(i);
         if (*entry).occupied {
@@ -362,7 +368,7 @@ pub unsafe extern "C" fn naml_map_where(
         return naml_map_new(16);
     }
     let predicate: MapPredicateFn = std::mem::transmute(func_ptr as usize);
-    let result = naml_map_new((*map).capacity);
+    let result = naml_map_new_like(map, (*map).capacity);
     for i in 0..(*map).capacity {
         let entry = (*map).entries.add(i);
         if (*entry).occupied {
@@ -386,7 +392,7 @@ pub unsafe extern "C" fn naml_map_reject(
         return naml_map_new(16);
     }
     let predicate: MapPredicateFn = std::mem::transmute(func_ptr as usize);
-    let result = naml_map_new((*map).capacity);
+    let result = naml_map_new_like(map, (*map).capacity);
     for i in 0..(*map).capacity {
         let entry = (*map).entries.add(i);
         if (*entry).occupied {
@@ -407,7 +413,7 @@ pub unsafe extern "C" fn naml_map_merge(
 
     let cap_a = if a.is_null() { 0 } else { (*a).capacity };
     let cap_b = if b.is_null() { 0 } else { (*b).capacity };
-    let result = naml_map_new(std::cmp::max(cap_a, cap_b).max(16));
+    let result = naml_map_new_like(if a.is_null() { b } else { a }, std::cmp::max(cap_a, cap_b).max(16));
 
     if !a.is_null() {
         for i in 0..(*a).capacity {
@@ -439,7 +445,7 @@ pub unsafe extern "C" fn naml_map_defaults(
 
     let cap_m = if map.is_null() { 0 } else { (*map).capacity };
     let cap_d = if defs.is_null() { 0 } else { (*defs).capacity };
-    let result = naml_map_new(std::cmp::max(cap_m, cap_d).max(16));
+    let result = naml_map_new_like(if map.is_null() { defs } else { map }, std::cmp::max(cap_m, cap_d).max(16));
 
     if !defs.is_null() {
         for i in 0..(*defs).capacity {
@@ -473,7 +479,7 @@ pub unsafe extern "C" fn naml_map_intersect(
         return naml_map_new(16);
     }
 
-    let result = naml_map_new(std::cmp::min((*a).capacity, (*b).capacity).max(16));
+    let result = naml_map_new_like(a, std::cmp::min((*a).capacity, (*b).capacity).max(16));
 
     for i in 0..(*a).capacity {
         let entry = (*a).entries.add(i);
@@ -498,7 +504,7 @@ pub unsafe extern "C" fn naml_map_diff(
         return naml_map_new(16);
     }
 
-    let result = naml_map_new((*a).capacity.max(16));
+    let result = naml_map_new_like(a, (*a).capacity.max(16));
 
     for i in 0..(*a).capacity {
         let entry = (*a).entries.add(i);
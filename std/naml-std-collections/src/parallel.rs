@@ -0,0 +1,175 @@
+#![allow(unsafe_op_in_unsafe_fn)]
+//!
+//! Parallel - Data-Parallel Array Functions
+//!
+//! `par_apply`, `par_where` and `par_fold` split the array into chunks
+//! and run them on the M:N scheduler's workers (see
+//! `naml_std_threads::run_chunks`), returning once every chunk is done.
+//! Results keep the array's order.
+//!
+//! Arrays shorter than `MIN_CHUNK_LEN` run on the calling thread, where
+//! dispatching would cost more than it saves. The closure is called from
+//! several threads at once, so it should not mutate captured state.
+//!
+//! `par_fold` folds each chunk on its own, starting from the chunk's first
+//! element, then combines the chunk results in order starting from
+//! `initial`. This gives the same answer as `fold` when the reducer is
+//! associative, as with `+`, `*`, `min` or `max`.
+//!
+
+use std::sync::Mutex;
+
+use naml_std_core::{naml_array_new, naml_array_push, NamlArray};
+use naml_std_threads::{run_chunks, worker_count};
+
+type MapperFn = unsafe extern "C" fn(data_ptr: i64, element: i64) -> i64;
+type FoldFn = unsafe extern "C" fn(data_ptr: i64, accumulator: i64, element: i64) -> i64;
+
+/// Smallest chunk worth sending to another worker
+const MIN_CHUNK_LEN: usize = 1024;
+
+/// Chunks per worker, so that uneven chunks still balance out
+const CHUNKS_PER_WORKER: usize = 4;
+
+unsafe fn elements<'a>(arr: *const NamlArray) -> &'a [i64] {
+    if arr.is_null() {
+        return &[];
+    }
+    std::slice::from_raw_parts((*arr).data, (*arr).len)
+}
+
+/// Elements per chunk for an array of `len` elements
+fn chunk_len(len: usize) -> usize {
+    let chunks = (len / MIN_CHUNK_LEN).clamp(1, worker_count() * CHUNKS_PER_WORKER);
+    len.div_ceil(chunks).max(1)
+}
+
+/// Run `work` over each chunk of `values` and collect the per-chunk results
+/// in order. A single chunk runs on the calling thread.
+fn map_chunks<T: Send>(values: &[i64], work: impl Fn(&[i64]) -> T + Sync) -> Vec<T> {
+    let chunks: Vec<&[i64]> = values.chunks(chunk_len(values.len())).collect();
+    if chunks.len() <= 1 {
+        return chunks.into_iter().map(work).collect();
+    }
+    let slots: Vec<Mutex<Option<T>>> = chunks.iter().map(|_| Mutex::new(None)).collect();
+    run_chunks(chunks.len(), &|i| {
+        *slots[i].lock().unwrap() = Some(work(chunks[i]));
+    });
+    slots.into_iter().map(|slot| slot.into_inner().unwrap().unwrap()).collect()
+}
+
+/// Map each element through a function on several workers (par_apply)
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_par_map(arr: *const NamlArray, func_ptr: i64, data_ptr: i64) -> *mut NamlArray {
+    let values = elements(arr);
+    if values.is_empty() || func_ptr == 0 {
+        return naml_array_new(0);
+    }
+    let mapper: MapperFn = std::mem::transmute(func_ptr as usize);
+    let parts = map_chunks(values, |chunk| chunk.iter().map(|&e| unsafe { mapper(data_ptr, e) }).collect::<Vec<_>>());
+    let result = naml_array_new(values.len());
+    for value in parts.into_iter().flatten() {
+        naml_array_push(result, value);
+    }
+    result
+}
+
+/// Keep the elements satisfying a predicate, tested on several workers
+/// (par_where)
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_par_filter(arr: *const NamlArray, func_ptr: i64, data_ptr: i64) -> *mut NamlArray {
+    let values = elements(arr);
+    if values.is_empty() || func_ptr == 0 {
+        return naml_array_new(0);
+    }
+    let predicate: MapperFn = std::mem::transmute(func_ptr as usize);
+    let parts = map_chunks(values, |chunk| {
+        chunk.iter().copied().filter(|&e| unsafe { predicate(data_ptr, e) } != 0).collect::<Vec<_>>()
+    });
+    let result = naml_array_new(0);
+    for value in parts.into_iter().flatten() {
+        naml_array_push(result, value);
+    }
+    result
+}
+
+/// Fold chunks on several workers and combine them in order (par_fold);
+/// see the module docs for when this matches `fold`
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_par_fold(
+    arr: *const NamlArray,
+    initial: i64,
+    func_ptr: i64,
+    data_ptr: i64,
+) -> i64 {
+    let values = elements(arr);
+    if values.is_empty() || func_ptr == 0 {
+        return initial;
+    }
+    let folder: FoldFn = std::mem::transmute(func_ptr as usize);
+    let partials = map_chunks(values, |chunk| {
+        chunk.iter().copied().reduce(|acc, e| unsafe { folder(data_ptr, acc, e) })
+    });
+    partials.into_iter().flatten().fold(initial, |acc, partial| folder(data_ptr, acc, partial))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use naml_std_core::naml_array_get;
+
+    unsafe extern "C" fn square(_data: i64, x: i64) -> i64 {
+        x * x
+    }
+
+    unsafe extern "C" fn is_multiple(data: i64, x: i64) -> i64 {
+        (x % data == 0) as i64
+    }
+
+    unsafe extern "C" fn add(_data: i64, acc: i64, x: i64) -> i64 {
+        acc + x
+    }
+
+    unsafe fn range(n: i64) -> *mut NamlArray {
+        let arr = naml_array_new(n as usize);
+        for i in 0..n {
+            naml_array_push(arr, i);
+        }
+        arr
+    }
+
+    unsafe fn to_vec(arr: *mut NamlArray) -> Vec<i64> {
+        (0..(*arr).len as i64).map(|i| naml_array_get(arr, i)).collect()
+    }
+
+    #[test]
+    fn test_par_matches_sequential() {
+        unsafe {
+            for n in [0, 1, 10, 100_003] {
+                let arr = range(n);
+                let expected: Vec<i64> = (0..n).map(|x| x * x).collect();
+                assert_eq!(to_vec(naml_array_par_map(arr, square as *const () as i64, 0)), expected);
+                let expected: Vec<i64> = (0..n).filter(|x| x % 7 == 0).collect();
+                assert_eq!(to_vec(naml_array_par_filter(arr, is_multiple as *const () as i64, 7)), expected);
+                assert_eq!(naml_array_par_fold(arr, 5, add as *const () as i64, 0), 5 + n * (n - 1) / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunking() {
+        assert_eq!(chunk_len(0), 1);
+        assert_eq!(chunk_len(MIN_CHUNK_LEN - 1), MIN_CHUNK_LEN - 1);
+        let len = 100 * MIN_CHUNK_LEN * worker_count();
+        assert_eq!(len.div_ceil(chunk_len(len)), worker_count() * CHUNKS_PER_WORKER);
+    }
+}
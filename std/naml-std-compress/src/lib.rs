@@ -0,0 +1,259 @@
+///
+/// naml-std-compress - Compression
+///
+/// Compresses and decompresses bytes in the gzip, zlib and Zstandard
+/// formats, e.g. for HTTP payloads and log archives.
+///
+/// ## Functions
+///
+/// - `gzip_compress(data: bytes, level: int) -> bytes` - gzip (RFC 1952)
+/// - `gzip_decompress(data: bytes) -> bytes throws DecodeError`
+/// - `zlib_compress(data: bytes, level: int) -> bytes` - zlib (RFC 1950)
+/// - `zlib_decompress(data: bytes) -> bytes throws DecodeError`
+/// - `zstd_compress(data: bytes, level: int) -> bytes` - Zstandard (RFC 8878)
+/// - `zstd_decompress(data: bytes) -> bytes throws DecodeError`
+///
+/// ## Levels
+///
+/// gzip and zlib take levels 0 (store) to 9 (smallest), zstd 1 to 22.
+/// Out-of-range levels are clamped and a negative level selects the
+/// format's default (6 for gzip/zlib, 3 for zstd).
+///
+/// ## Decompression
+///
+/// Concatenated gzip members and zstd frames are decompressed as one
+/// stream. Corrupt, truncated or trailing input throws DecodeError, whose
+/// position is the number of input bytes consumed when decoding stopped.
+///
+
+use std::io::{self, Read};
+
+use flate2::Compression;
+use flate2::bufread::{MultiGzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use naml_std_core::bytes::{naml_bytes_from, NamlBytes};
+
+const DEFLATE_DEFAULT_LEVEL: u32 = 6;
+
+fn deflate_level(level: i64) -> Compression {
+    if level < 0 {
+        Compression::new(DEFLATE_DEFAULT_LEVEL)
+    } else {
+        Compression::new(level.min(9) as u32)
+    }
+}
+
+fn zstd_level(level: i64) -> i32 {
+    let range = zstd::compression_level_range();
+    if level < 0 {
+        zstd::DEFAULT_COMPRESSION_LEVEL
+    } else {
+        (level.clamp(1, *range.end() as i64)) as i32
+    }
+}
+
+fn gzip_compress(data: &[u8], level: i64) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), deflate_level(level));
+    // Writing into a Vec cannot fail
+    let _ = io::Write::write_all(&mut encoder, data);
+    encoder.finish().unwrap_or_default()
+}
+
+fn zlib_compress(data: &[u8], level: i64) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), deflate_level(level));
+    let _ = io::Write::write_all(&mut encoder, data);
+    encoder.finish().unwrap_or_default()
+}
+
+fn zstd_compress(data: &[u8], level: i64) -> Vec<u8> {
+    zstd::bulk::compress(data, zstd_level(level)).unwrap_or_default()
+}
+
+/// Read `decoder` to the end; on error, the number of bytes of `data`
+/// it had consumed, as left in `remaining`
+fn read_all<R: Read>(mut decoder: R, remaining: impl Fn(&R) -> usize, len: usize) -> Result<Vec<u8>, usize> {
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out).map_err(|_| len - remaining(&decoder))?;
+    Ok(out)
+}
+
+fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, usize> {
+    if data.is_empty() {
+        return Err(0);
+    }
+    read_all(MultiGzDecoder::new(data), |d| d.get_ref().len(), data.len())
+}
+
+fn zlib_decompress(data: &[u8]) -> Result<Vec<u8>, usize> {
+    let mut decoder = ZlibDecoder::new(data);
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out).map_err(|_| data.len() - decoder.get_ref().len())?;
+    // A zlib stream ends at its checksum; anything after it is an error
+    let consumed = decoder.total_in() as usize;
+    if consumed == 0 || consumed != data.len() {
+        return Err(consumed);
+    }
+    Ok(out)
+}
+
+fn zstd_decompress(data: &[u8]) -> Result<Vec<u8>, usize> {
+    if data.is_empty() {
+        return Err(0);
+    }
+    let decoder = zstd::stream::read::Decoder::with_buffer(data).map_err(|_| 0usize)?;
+    read_all(decoder, |d| d.get_ref().len(), data.len())
+}
+
+unsafe fn bytes_slice<'a>(b: *const NamlBytes) -> &'a [u8] {
+    if b.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts((*b).as_ptr(), (*b).len) }
+    }
+}
+
+unsafe fn bytes_result(data: Vec<u8>) -> *mut NamlBytes {
+    unsafe { naml_bytes_from(data.as_ptr(), data.len()) }
+}
+
+/// Store a decompression result in the out parameters:
+/// tag = 0: success, value = NamlBytes pointer
+/// tag = 1: error, value = input bytes consumed before the error
+unsafe fn set_decoded(result: Result<Vec<u8>, usize>, out_tag: *mut i32, out_value: *mut i64) {
+    unsafe {
+        match result {
+            Ok(data) => {
+                *out_tag = 0;
+                *out_value = bytes_result(data) as i64;
+            }
+            Err(position) => {
+                *out_tag = 1;
+                *out_value = position as i64;
+            }
+        }
+    }
+}
+
+/// Compress bytes as gzip at `level` (0-9, negative for the default)
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_compress_gzip_compress(data: *const NamlBytes, level: i64) -> *mut NamlBytes {
+    unsafe { bytes_result(gzip_compress(bytes_slice(data), level)) }
+}
+
+/// Decompress gzip bytes
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes, `out_tag`
+/// is a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_compress_gzip_decompress(
+    data: *const NamlBytes,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe { set_decoded(gzip_decompress(bytes_slice(data)), out_tag, out_value) }
+}
+
+/// Compress bytes as zlib at `level` (0-9, negative for the default)
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_compress_zlib_compress(data: *const NamlBytes, level: i64) -> *mut NamlBytes {
+    unsafe { bytes_result(zlib_compress(bytes_slice(data), level)) }
+}
+
+/// Decompress zlib bytes
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes, `out_tag`
+/// is a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_compress_zlib_decompress(
+    data: *const NamlBytes,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe { set_decoded(zlib_decompress(bytes_slice(data)), out_tag, out_value) }
+}
+
+/// Compress bytes as a Zstandard frame at `level` (1-22, negative for the default)
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_compress_zstd_compress(data: *const NamlBytes, level: i64) -> *mut NamlBytes {
+    unsafe { bytes_result(zstd_compress(bytes_slice(data), level)) }
+}
+
+/// Decompress Zstandard bytes
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes, `out_tag`
+/// is a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_compress_zstd_decompress(
+    data: *const NamlBytes,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe { set_decoded(zstd_decompress(bytes_slice(data)), out_tag, out_value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<u8> {
+        b"naml compresses repetitive log lines well\n".repeat(64)
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let data = sample();
+        for level in [-1, 0, 1, 9, 42] {
+            let gz = gzip_compress(&data, level);
+            assert_eq!(&gz[..2], &[0x1f, 0x8b]);
+            assert_eq!(gzip_decompress(&gz), Ok(data.clone()));
+            assert_eq!(zlib_decompress(&zlib_compress(&data, level)), Ok(data.clone()));
+            assert_eq!(zstd_decompress(&zstd_compress(&data, level)), Ok(data.clone()));
+        }
+        assert!(gzip_compress(&data, 9).len() < data.len() / 10);
+        assert_eq!(gzip_decompress(&gzip_compress(b"", 6)), Ok(Vec::new()));
+        assert_eq!(zstd_decompress(&zstd_compress(b"", 3)), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_concatenated_streams() {
+        let mut gz = gzip_compress(b"first ", 6);
+        gz.extend(gzip_compress(b"second", 6));
+        assert_eq!(gzip_decompress(&gz), Ok(b"first second".to_vec()));
+
+        let mut zst = zstd_compress(b"first ", 3);
+        zst.extend(zstd_compress(b"second", 3));
+        assert_eq!(zstd_decompress(&zst), Ok(b"first second".to_vec()));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let data = sample();
+        assert_eq!(gzip_decompress(b""), Err(0));
+        assert_eq!(zlib_decompress(b""), Err(0));
+        assert_eq!(zstd_decompress(b""), Err(0));
+        assert!(gzip_decompress(b"not gzip at all").is_err());
+        assert!(zstd_decompress(b"not zstd at all").is_err());
+
+        let zlib = zlib_compress(&data, 6);
+        let mut trailing = zlib.clone();
+        trailing.push(0);
+        assert_eq!(zlib_decompress(&trailing), Err(zlib.len()));
+
+        let gz = gzip_compress(&data, 6);
+        assert!(gzip_decompress(&gz[..gz.len() - 4]).is_err());
+        let zst = zstd_compress(&data, 3);
+        assert!(zstd_decompress(&zst[..zst.len() - 2]).is_err());
+    }
+}
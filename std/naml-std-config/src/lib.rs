@@ -0,0 +1,205 @@
+///
+/// naml-std-config - Structured Configuration Loading
+///
+/// Fills a naml struct from layered configuration sources, so services
+/// don't each write their own file/env/flag glue.
+///
+/// ## Functions
+///
+/// - `file(path: string) -> int` - naml.toml-style file source
+/// - `env(prefix: string) -> int` - Environment variables named `PREFIX_KEY`
+/// - `flags(args: [string]) -> int` - Command line flags
+/// - `values(entries: map<string, string>) -> int` - Fixed values, usually defaults
+/// - `free(source: int)` - Release a source handle
+/// - `load(schema: string, sources: [int]) -> <schema> throws ConfigError`
+///
+/// ## Loading
+///
+/// `load` names a struct whose fields are int, float, bool or string. The
+/// compiler passes the struct's type id and a `name:kind` field spec; each
+/// field is looked up by name in the merged sources and coerced to its
+/// kind. Later sources override earlier ones. A missing field, a value that
+/// does not coerce, an unreadable file or an unknown handle throws
+/// ConfigError with the offending key (empty if the error has none).
+///
+
+mod sources;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use naml_std_core::{
+    naml_array_get, naml_array_len, naml_exception_set_typed, naml_stack_capture,
+    naml_string_new, naml_struct_new, naml_struct_set_field, NamlArray, NamlMap, NamlString,
+    NamlStruct, EXCEPTION_TYPE_CONFIG_ERROR,
+};
+
+use sources::{merge, FieldValue, Schema, Source};
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static SOURCES: LazyLock<Mutex<HashMap<i64, Source>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+/// Throw ConfigError with layout: message@0, stack@8, key@16
+fn throw_config_error(message: &str, key: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate ConfigError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_from_string(message);
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut *mut NamlString) = naml_from_string(key);
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_CONFIG_ERROR);
+    }
+}
+
+fn register(source: Source) -> i64 {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
+    SOURCES.lock().unwrap().insert(handle, source);
+    handle
+}
+
+/// Create a source reading a naml.toml-style file when loaded
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_config_file(path: *const NamlString) -> i64 {
+    let path = unsafe { string_from_naml(path) };
+    register(Source::File(PathBuf::from(path)))
+}
+
+/// Create a source reading environment variables named `PREFIX_KEY`
+///
+/// # Safety
+/// The caller must ensure `prefix` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_config_env(prefix: *const NamlString) -> i64 {
+    let prefix = unsafe { string_from_naml(prefix) };
+    register(Source::Env(prefix))
+}
+
+/// Create a source parsing command line flags from an array of strings
+///
+/// # Safety
+/// The caller must ensure `args` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_config_flags(args: *const NamlArray) -> i64 {
+    let mut items = Vec::new();
+    if !args.is_null() {
+        unsafe {
+            for i in 0..naml_array_len(args) {
+                items.push(string_from_naml(naml_array_get(args, i) as *const NamlString));
+            }
+        }
+    }
+    register(Source::Flags(items))
+}
+
+/// Create a source holding the entries of a `map<string, string>`
+///
+/// # Safety
+/// The caller must ensure `map` is a valid pointer to a NamlMap or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_config_values(map: *const NamlMap) -> i64 {
+    let mut pairs = Vec::new();
+    if !map.is_null() {
+        unsafe {
+            for i in 0..(*map).capacity {
+                let entry = (*map).entries.add(i);
+                if (*entry).occupied {
+                    pairs.push((
+                        string_from_naml((*entry).key as *const NamlString),
+                        string_from_naml((*entry).value as *const NamlString),
+                    ));
+                }
+            }
+        }
+    }
+    register(Source::Values(pairs))
+}
+
+/// Release a source handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_config_free(source: i64) {
+    SOURCES.lock().unwrap().remove(&source);
+}
+
+/// Merge `sources` into a new struct of `type_id` described by `spec`
+///
+/// # Arguments
+/// * `sources` - Array of source handles, lowest precedence first
+/// * `type_id` - Type id of the schema struct
+/// * `spec` - Field list as `name:kind,...` in field order
+///
+/// Returns null after throwing ConfigError.
+///
+/// # Safety
+/// The caller must ensure `sources` is a valid pointer to a NamlArray or null
+/// and `spec` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_config_load(
+    sources: *const NamlArray,
+    type_id: i64,
+    spec: *const NamlString,
+) -> *mut NamlStruct {
+    let spec = unsafe { string_from_naml(spec) };
+    let Some(schema) = Schema::parse(&spec) else {
+        throw_config_error(&format!("Invalid config schema '{}'", spec), "");
+        return std::ptr::null_mut();
+    };
+
+    let mut resolved = Vec::new();
+    {
+        let registry = SOURCES.lock().unwrap();
+        let count = if sources.is_null() { 0 } else { unsafe { naml_array_len(sources) } };
+        for i in 0..count {
+            let handle = unsafe { naml_array_get(sources, i) };
+            match registry.get(&handle) {
+                Some(source) => resolved.push(source.clone()),
+                None => {
+                    drop(registry);
+                    throw_config_error(&format!("Unknown config source handle {}", handle), "");
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    }
+
+    let fields = match merge(&resolved, &schema) {
+        Ok(fields) => fields,
+        Err((message, key)) => {
+            throw_config_error(&message, &key);
+            return std::ptr::null_mut();
+        }
+    };
+
+    unsafe {
+        let result = naml_struct_new(type_id as u32, fields.len() as u32);
+        for (index, value) in fields.into_iter().enumerate() {
+            let word = match value {
+                FieldValue::Word(w) => w,
+                FieldValue::Float(f) => f.to_bits() as i64,
+                FieldValue::String(s) => naml_from_string(&s) as i64,
+            };
+            naml_struct_set_field(result, index as u32, word);
+        }
+        result
+    }
+}
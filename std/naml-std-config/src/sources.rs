@@ -0,0 +1,294 @@
+///
+/// Configuration Sources
+///
+/// Each source resolves to a flat map from normalized key to raw string
+/// value. Keys are lowercase with `_` between words, so `[db] host` in a
+/// file, `APP_DB_HOST` in the environment and `--db-host` on the command
+/// line all name the struct field `db_host`.
+///
+/// Sources are read when a config is loaded, not when they are created, so
+/// one source handle can be loaded again to pick up changes.
+///
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Field kinds a schema struct may use
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum FieldKind {
+    Int,
+    Float,
+    Bool,
+    String,
+}
+
+/// Parsed field list of a schema struct, in field order
+#[derive(Debug, PartialEq)]
+pub(crate) struct Schema {
+    pub fields: Vec<(String, FieldKind)>,
+}
+
+impl Schema {
+    /// Parse the `name:kind,name:kind` spec generated by the compiler
+    pub(crate) fn parse(spec: &str) -> Option<Self> {
+        let mut fields = Vec::new();
+        for field in spec.split(',').filter(|f| !f.is_empty()) {
+            let (name, kind) = field.split_once(':')?;
+            let kind = match kind {
+                "int" => FieldKind::Int,
+                "float" => FieldKind::Float,
+                "bool" => FieldKind::Bool,
+                "string" => FieldKind::String,
+                _ => return None,
+            };
+            fields.push((name.to_string(), kind));
+        }
+        Some(Self { fields })
+    }
+
+    fn is_bool(&self, key: &str) -> bool {
+        self.fields
+            .iter()
+            .any(|(name, kind)| name == key && *kind == FieldKind::Bool)
+    }
+}
+
+/// A configuration error: message and the key it concerns (empty if none)
+pub(crate) type ConfigError = (String, String);
+
+#[derive(Clone, Debug)]
+pub(crate) enum Source {
+    /// naml.toml-style file; tables flatten into `table_key`
+    File(PathBuf),
+    /// Environment variables named `PREFIX_KEY`
+    Env(String),
+    /// Command line arguments
+    Flags(Vec<String>),
+    /// Fixed key/value pairs
+    Values(Vec<(String, String)>),
+}
+
+/// Lowercase a key and use `_` as the word separator
+pub(crate) fn normalize_key(key: &str) -> String {
+    key.trim()
+        .chars()
+        .map(|c| match c {
+            '-' | '.' | ' ' => '_',
+            c => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+impl Source {
+    pub(crate) fn resolve(&self, schema: &Schema) -> Result<HashMap<String, String>, ConfigError> {
+        match self {
+            Source::File(path) => {
+                let text = std::fs::read_to_string(path).map_err(|e| {
+                    (format!("Failed to read config file '{}': {}", path.display(), e), String::new())
+                })?;
+                parse_toml(&text).map_err(|e| {
+                    (format!("Invalid config file '{}': {}", path.display(), e), String::new())
+                })
+            }
+            Source::Env(prefix) => Ok(env_values(prefix, std::env::vars())),
+            Source::Flags(args) => Ok(flag_values(args, schema)),
+            Source::Values(pairs) => Ok(pairs
+                .iter()
+                .map(|(k, v)| (normalize_key(k), v.clone()))
+                .collect()),
+        }
+    }
+}
+
+fn parse_toml(text: &str) -> Result<HashMap<String, String>, String> {
+    let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+    let mut values = HashMap::new();
+    flatten_table("", &table, &mut values);
+    Ok(values)
+}
+
+fn flatten_table(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = format!("{}{}", prefix, normalize_key(key));
+        match value {
+            toml::Value::Table(inner) => flatten_table(&format!("{}_", key), inner, out),
+            toml::Value::String(s) => {
+                out.insert(key, s.clone());
+            }
+            other => {
+                out.insert(key, other.to_string());
+            }
+        }
+    }
+}
+
+/// Variables named `PREFIX_FOO_BAR` map to `foo_bar`; an empty prefix takes every variable
+fn env_values(prefix: &str, vars: impl Iterator<Item = (String, String)>) -> HashMap<String, String> {
+    let prefix = prefix.trim_end_matches('_');
+    let prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{}_", prefix.to_ascii_uppercase())
+    };
+    vars.filter_map(|(name, value)| {
+        let rest = name.strip_prefix(&prefix)?;
+        (!rest.is_empty()).then(|| (normalize_key(rest), value))
+    })
+    .collect()
+}
+
+/// Parse `--name=value`, `--name value` and bare `--name` (true). A bare flag
+/// only takes the next argument as its value when the field is not a bool.
+/// Arguments not starting with `--` are skipped; `--` ends flag parsing.
+fn flag_values(args: &[String], schema: &Schema) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        i += 1;
+        if arg == "--" {
+            break;
+        }
+        let Some(flag) = arg.strip_prefix("--") else {
+            continue;
+        };
+        if let Some((name, value)) = flag.split_once('=') {
+            values.insert(normalize_key(name), value.to_string());
+            continue;
+        }
+        let key = normalize_key(flag);
+        let takes_value = !schema.is_bool(&key)
+            && args.get(i).is_some_and(|next| !next.starts_with("--"));
+        if takes_value {
+            values.insert(key, args[i].clone());
+            i += 1;
+        } else {
+            values.insert(key, "true".to_string());
+        }
+    }
+    values
+}
+
+/// A field value coerced to its kind, as stored in the struct's field slot
+pub(crate) enum FieldValue {
+    Word(i64),
+    Float(f64),
+    String(String),
+}
+
+pub(crate) fn coerce(key: &str, raw: &str, kind: FieldKind) -> Result<FieldValue, ConfigError> {
+    let invalid = |what: &str| {
+        (format!("Invalid {} for config value '{}': '{}'", what, key, raw), key.to_string())
+    };
+    let trimmed = raw.trim();
+    match kind {
+        FieldKind::Int => trimmed
+            .replace('_', "")
+            .parse()
+            .map(FieldValue::Word)
+            .map_err(|_| invalid("int")),
+        FieldKind::Float => trimmed
+            .replace('_', "")
+            .parse()
+            .map(FieldValue::Float)
+            .map_err(|_| invalid("float")),
+        FieldKind::Bool => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(FieldValue::Word(1)),
+            "false" | "no" | "off" | "0" => Ok(FieldValue::Word(0)),
+            _ => Err(invalid("bool")),
+        },
+        FieldKind::String => Ok(FieldValue::String(raw.to_string())),
+    }
+}
+
+/// Merge resolved sources, later sources overriding earlier ones, and
+/// coerce every schema field. Missing fields are an error.
+pub(crate) fn merge(sources: &[Source], schema: &Schema) -> Result<Vec<FieldValue>, ConfigError> {
+    let mut merged = HashMap::new();
+    for source in sources {
+        merged.extend(source.resolve(schema)?);
+    }
+    schema
+        .fields
+        .iter()
+        .map(|(name, kind)| match merged.get(name) {
+            Some(raw) => coerce(name, raw, *kind),
+            None => Err((format!("Missing config value '{}'", name), name.clone())),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::parse("port:int,db_host:string,ratio:float,verbose:bool").unwrap()
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_schema_parse() {
+        assert_eq!(schema().fields[1], ("db_host".to_string(), FieldKind::String));
+        assert_eq!(Schema::parse("").unwrap().fields, vec![]);
+        assert!(Schema::parse("port:uint").is_none());
+    }
+
+    #[test]
+    fn test_toml_flattens_tables() {
+        let values = parse_toml("port = 8080\nratio = 0.5\n[db]\nhost = \"localhost\"\n").unwrap();
+        assert_eq!(values["port"], "8080");
+        assert_eq!(values["ratio"], "0.5");
+        assert_eq!(values["db_host"], "localhost");
+        assert!(parse_toml("port = ").is_err());
+    }
+
+    #[test]
+    fn test_env_prefix_mapping() {
+        let vars = vec![
+            ("APP_DB_HOST".to_string(), "db".to_string()),
+            ("APP_PORT".to_string(), "80".to_string()),
+            ("HOME".to_string(), "/root".to_string()),
+        ];
+        let values = env_values("app", vars.into_iter());
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["db_host"], "db");
+        assert_eq!(values["port"], "80");
+    }
+
+    #[test]
+    fn test_flag_forms() {
+        let args = strings(&["prog", "--port=90", "--db-host", "remote", "--verbose", "input.txt", "--", "--ratio=2"]);
+        let values = flag_values(&args, &schema());
+        assert_eq!(values["port"], "90");
+        assert_eq!(values["db_host"], "remote");
+        assert_eq!(values["verbose"], "true");
+        assert!(!values.contains_key("ratio"));
+    }
+
+    #[test]
+    fn test_merge_precedence_and_coercion() {
+        let defaults = Source::Values(vec![
+            ("port".to_string(), "80".to_string()),
+            ("db_host".to_string(), "localhost".to_string()),
+            ("ratio".to_string(), "0.25".to_string()),
+            ("verbose".to_string(), "off".to_string()),
+        ]);
+        let flags = Source::Flags(strings(&["--port", "9000", "--verbose"]));
+        let fields = merge(&[defaults.clone(), flags], &schema()).unwrap();
+        assert!(matches!(fields[0], FieldValue::Word(9000)));
+        assert!(matches!(&fields[1], FieldValue::String(s) if s == "localhost"));
+        assert!(matches!(fields[2], FieldValue::Float(r) if r == 0.25));
+        assert!(matches!(fields[3], FieldValue::Word(1)));
+
+        let bad = Source::Flags(strings(&["--port=eighty"]));
+        let err = merge(&[defaults, bad], &schema()).err().unwrap();
+        assert_eq!(err.1, "port");
+
+        let missing = merge(&[], &schema()).err().unwrap();
+        assert_eq!(missing, ("Missing config value 'port'".to_string(), "port".to_string()));
+    }
+}
@@ -243,8 +243,11 @@ pub fn struct_alloc_size(field_count: u32) -> usize {
     std::mem::size_of::<crate::value::NamlStruct>() + (field_count as usize) * std::mem::size_of::<i64>()
 }
 
+/// Bytes needed for a string of `len` bytes plus its NUL terminator, at
+/// least a whole `NamlString`
 pub fn string_alloc_size(len: usize) -> usize {
-    std::mem::size_of::<crate::value::NamlString>() + len
+    std::mem::size_of::<crate::value::NamlString>()
+        .max(std::mem::offset_of!(crate::value::NamlString, data) + len + 1)
 }
 
 pub const MAX_SMALL_STRING: usize = 224;
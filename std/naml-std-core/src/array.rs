@@ -5,9 +5,69 @@
 //! Arrays are generic over element type at the naml level, but at runtime
 //! we store elements as 64-bit values (either primitives or pointers).
 //!
+//! Slicing does not copy: a view points into the buffer of the array it was
+//! sliced from. The buffer then moves into a refcounted `ArrayStorage` held
+//! by the parent and every view, and all holders report a capacity of 0.
+//! Writes go through `naml_array_make_mut`, which gives the writer a private
+//! buffer (copy-on-write), so views and parents never observe each other's
+//! mutations. Elements belong to the storage and are released with it, by
+//! the element kind recorded when the first view was taken. A writer that
+//! copies out of a still-shared storage takes its own reference to each
+//! element it copies; the last holder takes the buffer back and releases the
+//! elements outside its range.
+//!
 
 use std::alloc::{alloc, dealloc, realloc, Layout};
-use crate::value::{HeapHeader, HeapTag, NamlString, naml_string_decref};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::value::{HeapHeader, HeapTag, NamlString, NamlStruct, naml_string_decref, naml_struct_decref};
+use crate::map::{NamlMap, naml_map_decref};
+use crate::priority_queue::{NamlPriorityQueue, naml_priority_queue_decref};
+use crate::set::{NamlSet, naml_set_decref};
+use crate::string_builder::{NamlStringBuilder, naml_string_builder_decref};
+
+/// Element kinds passed by codegen to functions generic over the element type
+pub const ARRAY_ELEM_VALUE: i64 = 0;
+pub const ARRAY_ELEM_FLOAT: i64 = 1;
+pub const ARRAY_ELEM_STRING: i64 = 2;
+/// Any other refcounted value (arrays, maps, structs, ...)
+pub const ARRAY_ELEM_HEAP: i64 = 3;
+
+/// Take a reference to an element of the given kind
+///
+/// # Safety
+/// For string and heap kinds `elem` must be null or point to a live heap object.
+pub unsafe fn retain_array_elem(kind: i64, elem: i64) {
+    if (kind == ARRAY_ELEM_STRING || kind == ARRAY_ELEM_HEAP) && elem != 0 {
+        unsafe { (*(elem as *mut HeapHeader)).incref() };
+    }
+}
+
+/// Drop a reference to an element of the given kind. Heap elements are
+/// released by their tag; types defined outside this crate only lose the
+/// reference.
+///
+/// # Safety
+/// For string and heap kinds `elem` must be null or point to a live heap object.
+pub unsafe fn release_array_elem(kind: i64, elem: i64) {
+    if elem == 0 || (kind != ARRAY_ELEM_STRING && kind != ARRAY_ELEM_HEAP) {
+        return;
+    }
+    unsafe {
+        let header = elem as *mut HeapHeader;
+        match (*header).tag {
+            HeapTag::String => naml_string_decref(elem as *mut NamlString),
+            HeapTag::Array => naml_array_decref(elem as *mut NamlArray),
+            HeapTag::Map => naml_map_decref(elem as *mut NamlMap),
+            HeapTag::Struct => naml_struct_decref(elem as *mut NamlStruct),
+            HeapTag::StringBuilder => naml_string_builder_decref(elem as *mut NamlStringBuilder),
+            HeapTag::Set => naml_set_decref(elem as *mut NamlSet),
+            HeapTag::PriorityQueue => naml_priority_queue_decref(elem as *mut NamlPriorityQueue),
+            _ => {
+                (*header).decref();
+            }
+        }
+    }
+}
 
 /// A heap-allocated array of i64 values
 /// (All naml values are represented as i64 at runtime)
@@ -15,10 +75,28 @@ use crate::value::{HeapHeader, HeapTag, NamlString, naml_string_decref};
 pub struct NamlArray {
     pub header: HeapHeader,
     pub len: usize,
+    /// 0 while the buffer is shared through `storage`
     pub capacity: usize,
     pub data: *mut i64,
+    /// Storage owning the elements while the buffer is shared, or null when
+    /// the array owns its buffer and elements
+    pub storage: *mut ArrayStorage,
 }
 
+/// A buffer shared by an array and the views sliced from it
+#[repr(C)]
+pub struct ArrayStorage {
+    refcount: AtomicUsize,
+    data: *mut i64,
+    len: usize,
+    capacity: usize,
+    /// `ARRAY_ELEM_*` kind of the elements, used to release them
+    elem_kind: i64,
+}
+
+/// Capacity of arrays created without an explicit size
+pub const DEFAULT_ARRAY_CAPACITY: usize = 4;
+
 /// Create a new empty array with given initial capacity
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_array_new(capacity: usize) -> *mut NamlArray {
@@ -29,7 +107,7 @@ pub unsafe extern "C" fn naml_array_new(capacity: usize) -> *mut NamlArray {
             panic!("Failed to allocate array");
         }
 
-        let cap = if capacity == 0 { 4 } else { capacity };
+        let cap = if capacity == 0 { DEFAULT_ARRAY_CAPACITY } else { capacity };
         let data_layout = Layout::array::<i64>(cap).unwrap();
         let data = alloc(data_layout) as *mut i64;
         if data.is_null() {
@@ -41,6 +119,7 @@ pub unsafe extern "C" fn naml_array_new(capacity: usize) -> *mut NamlArray {
         (*ptr).len = 0;
         (*ptr).capacity = cap;
         (*ptr).data = data;
+        (*ptr).storage = std::ptr::null_mut();
 
         ptr
     }
@@ -73,11 +152,7 @@ pub unsafe extern "C" fn naml_array_decref(arr: *mut NamlArray) {
     if !arr.is_null() {
         unsafe {
             if (*arr).header.decref() {
-                let data_layout = Layout::array::<i64>((*arr).capacity).unwrap();
-                dealloc((*arr).data as *mut u8, data_layout);
-
-                let layout = Layout::new::<NamlArray>();
-                dealloc(arr as *mut u8, layout);
+                free_array(arr, |_| {});
             }
         }
     }
@@ -89,18 +164,7 @@ pub unsafe extern "C" fn naml_array_decref_strings(arr: *mut NamlArray) {
     if !arr.is_null() {
         unsafe {
             if (*arr).header.decref() {
-                for i in 0..(*arr).len {
-                    let elem = *(*arr).data.add(i);
-                    if elem != 0 {
-                        naml_string_decref(elem as *mut NamlString);
-                    }
-                }
-
-                let data_layout = Layout::array::<i64>((*arr).capacity).unwrap();
-                dealloc((*arr).data as *mut u8, data_layout);
-
-                let layout = Layout::new::<NamlArray>();
-                dealloc(arr as *mut u8, layout);
+                free_array(arr, |elem| naml_string_decref(elem as *mut NamlString));
             }
         }
     }
@@ -112,20 +176,144 @@ pub unsafe extern "C" fn naml_array_decref_arrays(arr: *mut NamlArray) {
     if !arr.is_null() {
         unsafe {
             if (*arr).header.decref() {
-                for i in 0..(*arr).len {
-                    let elem = *(*arr).data.add(i);
-                    if elem != 0 {
-                        naml_array_decref(elem as *mut NamlArray);
-                    }
-                }
-
-                let data_layout = Layout::array::<i64>((*arr).capacity).unwrap();
-                dealloc((*arr).data as *mut u8, data_layout);
-
-                let layout = Layout::new::<NamlArray>();
-                dealloc(arr as *mut u8, layout);
+                free_array(arr, |elem| naml_array_decref(elem as *mut NamlArray));
+            }
+        }
+    }
+}
+
+/// Free an array whose refcount reached zero, releasing its elements with
+/// `decref_elem`. Shared buffers are only released with their last holder.
+unsafe fn free_array(arr: *mut NamlArray, decref_elem: impl Fn(i64)) {
+    unsafe {
+        let storage = (*arr).storage;
+        if storage.is_null() {
+            release_buffer((*arr).data, (*arr).len, (*arr).capacity, &decref_elem);
+        } else {
+            release_storage(storage);
+        }
+
+        let layout = Layout::new::<NamlArray>();
+        dealloc(arr as *mut u8, layout);
+    }
+}
+
+/// Drop one reference to `storage`, releasing it and its elements once
+/// unreferenced
+unsafe fn release_storage(storage: *mut ArrayStorage) {
+    unsafe {
+        if (*storage).refcount.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let owned = Box::from_raw(storage);
+            let kind = owned.elem_kind;
+            release_buffer(owned.data, owned.len, owned.capacity, &|elem| release_array_elem(kind, elem));
+        }
+    }
+}
+
+unsafe fn release_buffer(data: *mut i64, len: usize, capacity: usize, decref_elem: &impl Fn(i64)) {
+    unsafe {
+        for i in 0..len {
+            let elem = *data.add(i);
+            if elem != 0 {
+                decref_elem(elem);
             }
         }
+        let data_layout = Layout::array::<i64>(capacity).unwrap();
+        dealloc(data as *mut u8, data_layout);
+    }
+}
+
+/// Create a view of `arr[start..end]` that shares its buffer. `elem_kind`
+/// (`ARRAY_ELEM_*`) says how the shared elements are retained and released.
+/// Indices must already be clamped so that `start <= end <= len`.
+///
+/// Taking a view writes to `arr` although its contents stay the same: an
+/// array that owns its buffer hands it to a new shared storage and its
+/// capacity becomes 0.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray that no
+/// other thread reads or writes during the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_view(
+    arr: *mut NamlArray,
+    start: usize,
+    end: usize,
+    elem_kind: i64,
+) -> *mut NamlArray {
+    unsafe {
+        if (*arr).capacity != 0 {
+            let storage = Box::new(ArrayStorage {
+                refcount: AtomicUsize::new(1),
+                data: (*arr).data,
+                len: (*arr).len,
+                capacity: (*arr).capacity,
+                elem_kind,
+            });
+            (*arr).storage = Box::into_raw(storage);
+            (*arr).capacity = 0;
+        }
+        let storage = (*arr).storage;
+        (*storage).refcount.fetch_add(1, Ordering::Relaxed);
+
+        let layout = Layout::new::<NamlArray>();
+        let view = alloc(layout) as *mut NamlArray;
+        if view.is_null() {
+            panic!("Failed to allocate array view");
+        }
+        (*view).header = HeapHeader::new(HeapTag::Array);
+        (*view).len = end - start;
+        (*view).capacity = 0;
+        (*view).data = (*arr).data.add(start);
+        (*view).storage = storage;
+        view
+    }
+}
+
+/// Give `arr` a buffer of its own before it is written to. Called by
+/// codegen before stores into arrays whose capacity is 0.
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_make_mut(arr: *mut NamlArray) {
+    unsafe {
+        if arr.is_null() || (*arr).capacity != 0 {
+            return;
+        }
+        let storage = (*arr).storage;
+        let len = (*arr).len;
+        let kind = (*storage).elem_kind;
+        if (*storage).refcount.load(Ordering::Acquire) == 1 {
+            // Last holder: take the buffer back, releasing the elements
+            // outside this array's range
+            let owned = Box::from_raw(storage);
+            let start = (*arr).data.offset_from(owned.data) as usize;
+            for i in (0..start).chain(start + len..owned.len) {
+                release_array_elem(kind, *owned.data.add(i));
+            }
+            if start != 0 {
+                std::ptr::copy((*arr).data, owned.data, len);
+            }
+            (*arr).data = owned.data;
+            (*arr).capacity = owned.capacity;
+        } else {
+            // Still shared: copy out and take a reference to each element
+            // copied, so the array owns its elements like any other
+            let capacity = len.max(DEFAULT_ARRAY_CAPACITY);
+            let data = alloc(Layout::array::<i64>(capacity).unwrap()) as *mut i64;
+            if data.is_null() {
+                panic!("Failed to allocate array data");
+            }
+            std::ptr::copy_nonoverlapping((*arr).data, data, len);
+            for i in 0..len {
+                retain_array_elem(kind, *data.add(i));
+            }
+            (*arr).data = data;
+            (*arr).capacity = capacity;
+            release_storage(storage);
+        }
+        (*arr).storage = std::ptr::null_mut();
     }
 }
 
@@ -165,6 +353,7 @@ pub unsafe extern "C" fn naml_array_set(arr: *mut NamlArray, index: i64, value:
     unsafe {
         let idx = index as usize;
         if idx < (*arr).len {
+            naml_array_make_mut(arr);
             *(*arr).data.add(idx) = value;
         }
     }
@@ -179,17 +368,7 @@ pub unsafe extern "C" fn naml_array_push(arr: *mut NamlArray, value: i64) {
 
     unsafe {
         if (*arr).len >= (*arr).capacity {
-            let new_capacity = (*arr).capacity * 2;
-            let old_layout = Layout::array::<i64>((*arr).capacity).unwrap();
-            let new_layout = Layout::array::<i64>(new_capacity).unwrap();
-
-            let new_data = realloc((*arr).data as *mut u8, old_layout, new_layout.size()) as *mut i64;
-            if new_data.is_null() {
-                panic!("Failed to grow array");
-            }
-
-            (*arr).data = new_data;
-            (*arr).capacity = new_capacity;
+            grow_to(arr, (*arr).len + 1);
         }
 
         *(*arr).data.add((*arr).len) = value;
@@ -197,6 +376,130 @@ pub unsafe extern "C" fn naml_array_push(arr: *mut NamlArray, value: i64) {
     }
 }
 
+/// Grow the backing storage to hold at least `min_capacity` elements.
+/// Capacity at least doubles so repeated growth stays amortized O(1).
+unsafe fn grow_to(arr: *mut NamlArray, min_capacity: usize) {
+    unsafe {
+        naml_array_make_mut(arr);
+        if min_capacity <= (*arr).capacity {
+            return;
+        }
+        let new_capacity = min_capacity.max((*arr).capacity * 2).max(DEFAULT_ARRAY_CAPACITY);
+        resize_storage(arr, new_capacity);
+    }
+}
+
+unsafe fn resize_storage(arr: *mut NamlArray, new_capacity: usize) {
+    unsafe {
+        let old_layout = Layout::array::<i64>((*arr).capacity).unwrap();
+        let new_layout = Layout::array::<i64>(new_capacity).unwrap();
+
+        let new_data = realloc((*arr).data as *mut u8, old_layout, new_layout.size()) as *mut i64;
+        if new_data.is_null() {
+            panic!("Failed to resize array");
+        }
+
+        (*arr).data = new_data;
+        (*arr).capacity = new_capacity;
+    }
+}
+
+/// Get the number of elements the array can hold without reallocating
+/// (0 while the buffer is shared with views)
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_capacity(arr: *const NamlArray) -> i64 {
+    if arr.is_null() {
+        0
+    } else {
+        unsafe { (*arr).capacity as i64 }
+    }
+}
+
+/// Ensure room for at least `additional` more elements
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_reserve(arr: *mut NamlArray, additional: i64) {
+    if arr.is_null() || additional <= 0 {
+        return;
+    }
+    unsafe {
+        grow_to(arr, (*arr).len + additional as usize);
+    }
+}
+
+/// Release unused capacity
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_shrink_to_fit(arr: *mut NamlArray) {
+    if arr.is_null() {
+        return;
+    }
+    unsafe {
+        naml_array_make_mut(arr);
+        // Keep at least one slot so the storage layout is never zero-sized
+        let target = (*arr).len.max(1);
+        if target < (*arr).capacity {
+            resize_storage(arr, target);
+        }
+    }
+}
+
+/// Append all elements of `other` to `arr`
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null and
+/// `other` is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_extend(arr: *mut NamlArray, other: *const NamlArray) {
+    if arr.is_null() || other.is_null() {
+        return;
+    }
+    unsafe {
+        let count = (*other).len;
+        grow_to(arr, (*arr).len + count);
+        // Read `other` after growing: it may be `arr` itself
+        std::ptr::copy_nonoverlapping((*other).data, (*arr).data.add((*arr).len), count);
+        (*arr).len += count;
+    }
+}
+
+/// Append raw bytes to `arr`, one element per byte
+///
+/// # Safety
+/// The caller must ensure `arr` is a valid pointer to a NamlArray or null and
+/// `data` is a valid pointer to an u8 or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_array_extend_from_bytes(arr: *mut NamlArray, data: *const u8, len: usize) {
+    if arr.is_null() || data.is_null() || len == 0 {
+        return;
+    }
+    unsafe {
+        grow_to(arr, (*arr).len + len);
+        let bytes = std::slice::from_raw_parts(data, len);
+        let dst = std::slice::from_raw_parts_mut((*arr).data.add((*arr).len), len);
+        for (slot, &byte) in dst.iter_mut().zip(bytes) {
+            *slot = byte as i64;
+        }
+        (*arr).len += len;
+    }
+}
+
+/// Create an array holding one element per byte
+pub fn naml_array_from_bytes(bytes: &[u8]) -> *mut NamlArray {
+    unsafe {
+        let arr = naml_array_new(bytes.len());
+        naml_array_extend_from_bytes(arr, bytes.as_ptr(), bytes.len());
+        arr
+    }
+}
+
 /// Pop element from end of array (returns 0 if empty)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_array_pop(arr: *mut NamlArray) -> i64 {
@@ -209,6 +512,7 @@ pub unsafe extern "C" fn naml_array_pop(arr: *mut NamlArray) -> i64 {
             return 0;
         }
 
+        naml_array_make_mut(arr);
         (*arr).len -= 1;
         *(*arr).data.add((*arr).len)
     }
@@ -222,12 +526,8 @@ pub unsafe extern "C" fn naml_array_contains(arr: *const NamlArray, value: i64)
     }
 
     unsafe {
-        for i in 0..(*arr).len {
-            if *(*arr).data.add(i) == value {
-                return 1;
-            }
-        }
-        0
+        let values = std::slice::from_raw_parts((*arr).data, (*arr).len);
+        if crate::simd::position_i64(values, value).is_some() { 1 } else { 0 }
     }
 }
 
@@ -266,6 +566,7 @@ pub unsafe extern "C" fn naml_array_shift(arr: *mut NamlArray) -> i64 {
             return 0;
         }
 
+        naml_array_make_mut(arr);
         let first = *(*arr).data;
 
         if (*arr).len > 1 {
@@ -289,6 +590,7 @@ pub unsafe extern "C" fn naml_array_fill(arr: *mut NamlArray, value: i64) {
     }
 
     unsafe {
+        naml_array_make_mut(arr);
         for i in 0..(*arr).len {
             *(*arr).data.add(i) = value;
         }
@@ -302,6 +604,7 @@ pub unsafe extern "C" fn naml_array_clear(arr: *mut NamlArray) {
         return;
     }
     unsafe {
+        naml_array_make_mut(arr);
         (*arr).len = 0;
     }
 }
@@ -357,11 +660,7 @@ pub unsafe extern "C" fn naml_array_decref_maps(arr: *mut NamlArray) {
     if !arr.is_null() {
         unsafe {
             if (*arr).header.decref() {
-                let data_layout = Layout::array::<i64>((*arr).capacity).unwrap();
-                dealloc((*arr).data as *mut u8, data_layout);
-
-                let layout = Layout::new::<NamlArray>();
-                dealloc(arr as *mut u8, layout);
+                free_array(arr, |_| {});
             }
         }
     }
@@ -373,18 +672,9 @@ pub unsafe extern "C" fn naml_array_decref_structs(arr: *mut NamlArray) {
     if !arr.is_null() {
         unsafe {
             if (*arr).header.decref() {
-                for i in 0..(*arr).len {
-                    let elem = *(*arr).data.add(i);
-                    if elem != 0 {
-                        crate::value::naml_struct_decref(elem as *mut crate::value::NamlStruct);
-                    }
-                }
-
-                let data_layout = Layout::array::<i64>((*arr).capacity).unwrap();
-                dealloc((*arr).data as *mut u8, data_layout);
-
-                let layout = Layout::new::<NamlArray>();
-                dealloc(arr as *mut u8, layout);
+                free_array(arr, |elem| {
+                    crate::value::naml_struct_decref(elem as *mut crate::value::NamlStruct)
+                });
             }
         }
     }
@@ -397,6 +687,7 @@ pub unsafe extern "C" fn naml_array_reverse(arr: *mut NamlArray) -> *mut NamlArr
         if arr.is_null() || (*arr).len <= 1 {
             return arr;
         }
+        naml_array_make_mut(arr);
         let len = (*arr).len;
         for i in 0..len / 2 {
             let j = len - 1 - i;
@@ -439,4 +730,161 @@ mod tests {
             naml_array_decref(arr);
         }
     }
+
+    #[test]
+    fn test_array_reserve_and_shrink() {
+        unsafe {
+            let arr = naml_array_new(0);
+            naml_array_push(arr, 1);
+            naml_array_reserve(arr, 100);
+            assert!(naml_array_capacity(arr) >= 101);
+            assert_eq!(naml_array_get(arr, 0), 1);
+
+            naml_array_shrink_to_fit(arr);
+            assert_eq!(naml_array_capacity(arr), 1);
+            naml_array_push(arr, 2);
+            assert_eq!(naml_array_capacity(arr), DEFAULT_ARRAY_CAPACITY as i64);
+
+            naml_array_decref(arr);
+        }
+    }
+
+    #[test]
+    fn test_array_extend() {
+        unsafe {
+            let arr = naml_array_from_bytes(&[1, 2, 255]);
+            assert_eq!(naml_array_len(arr), 3);
+            assert_eq!(naml_array_get(arr, 2), 255);
+
+            naml_array_extend(arr, arr);
+            assert_eq!(naml_array_len(arr), 6);
+            assert_eq!(naml_array_get(arr, 3), 1);
+            assert_eq!(naml_array_get(arr, 5), 255);
+
+            naml_array_decref(arr);
+        }
+    }
+
+    #[test]
+    fn test_array_view_shares_buffer() {
+        unsafe {
+            let arr = naml_array_new(0);
+            for i in 0..6 {
+                naml_array_push(arr, i);
+            }
+            let view = naml_array_view(arr, 2, 5, ARRAY_ELEM_VALUE);
+            assert_eq!(naml_array_len(view), 3);
+            assert_eq!((*view).data, (*arr).data.add(2));
+            assert_eq!(naml_array_capacity(arr), 0);
+            assert_eq!(naml_array_get(view, 0), 2);
+
+            // Writes on either side copy before storing
+            naml_array_set(view, 0, 99);
+            naml_array_push(arr, 6);
+            assert_eq!(naml_array_get(arr, 2), 2);
+            assert_eq!(naml_array_get(view, 0), 99);
+            assert_eq!(naml_array_len(arr), 7);
+
+            naml_array_decref(arr);
+            naml_array_decref(view);
+        }
+    }
+
+    #[test]
+    fn test_array_view_reclaims_when_last_holder() {
+        unsafe {
+            let arr = naml_array_from_bytes(&[1, 2, 3, 4]);
+            let view = naml_array_view(arr, 1, 3, ARRAY_ELEM_VALUE);
+            naml_array_decref(arr);
+
+            naml_array_make_mut(view);
+            assert!((*view).storage.is_null());
+            assert!(naml_array_capacity(view) >= 2);
+            assert_eq!(naml_array_get(view, 0), 2);
+            assert_eq!(naml_array_get(view, 1), 3);
+            naml_array_decref(view);
+        }
+    }
+
+    unsafe fn string_array(texts: &[&str]) -> (*mut NamlArray, Vec<*mut NamlString>) {
+        unsafe {
+            let arr = naml_array_new(0);
+            let mut strings = Vec::new();
+            for text in texts {
+                let s = crate::value::naml_string_new(text.as_ptr(), text.len());
+                // Keep each string alive past the array to observe its refcount
+                (*s).header.incref();
+                naml_array_push(arr, s as i64);
+                strings.push(s);
+            }
+            (arr, strings)
+        }
+    }
+
+    #[test]
+    fn test_array_view_of_strings_releases_once() {
+        unsafe {
+            let (arr, strings) = string_array(&["a", "b", "c"]);
+            let view = naml_array_view(arr, 0, 2, ARRAY_ELEM_STRING);
+            let nested = naml_array_view(view, 1, 2, ARRAY_ELEM_STRING);
+            naml_array_decref_strings(view);
+            naml_array_decref_strings(arr);
+            naml_array_decref_strings(nested);
+
+            for s in strings {
+                assert_eq!((*s).header.refcount(), 1);
+                naml_string_decref(s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_array_view_copy_out_retains_elements() {
+        unsafe {
+            let (arr, strings) = string_array(&["a", "b", "c"]);
+            let view = naml_array_view(arr, 0, 2, ARRAY_ELEM_STRING);
+
+            // Still shared with `arr`, so the view copies and takes references
+            naml_array_make_mut(view);
+            assert!((*view).storage.is_null());
+            assert_eq!((*strings[0]).header.refcount(), 3);
+            assert_eq!((*strings[2]).header.refcount(), 2);
+
+            // The view owns its copies: replacing one drops only its reference
+            naml_string_decref(naml_array_get(view, 1) as *mut NamlString);
+            naml_array_set(view, 1, 0);
+            naml_array_decref_strings(arr);
+            assert_eq!((*strings[1]).header.refcount(), 1);
+            assert_eq!((*strings[0]).header.refcount(), 2);
+            naml_array_decref_strings(view);
+
+            for s in strings {
+                assert_eq!((*s).header.refcount(), 1);
+                naml_string_decref(s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_array_view_take_back_releases_outside_range() {
+        unsafe {
+            let (arr, strings) = string_array(&["a", "b", "c", "d"]);
+            let view = naml_array_view(arr, 1, 3, ARRAY_ELEM_STRING);
+            naml_array_decref_strings(arr);
+
+            // Last holder: the buffer comes back and "a" and "d" are released
+            naml_array_make_mut(view);
+            assert_eq!(naml_array_get(view, 0), strings[1] as i64);
+            assert_eq!(naml_array_get(view, 1), strings[2] as i64);
+            assert_eq!((*strings[0]).header.refcount(), 1);
+            assert_eq!((*strings[3]).header.refcount(), 1);
+            assert_eq!((*strings[1]).header.refcount(), 2);
+
+            naml_array_decref_strings(view);
+            for s in strings {
+                assert_eq!((*s).header.refcount(), 1);
+                naml_string_decref(s);
+            }
+        }
+    }
 }
@@ -4,21 +4,69 @@
 /// Provides heap-allocated byte arrays with reference counting.
 /// Similar to strings but for raw binary data.
 ///
-/// Operations: new, from, len, get, set, incref, decref, to_string, string_to_bytes.
+/// Operations: new, from, view, len, get, set, incref, decref, to_string, string_to_bytes.
+///
+/// A view shares a range of another buffer's bytes, so writes through
+/// either are visible in both. Growing a view past its range moves it onto
+/// a buffer of its own.
 ///
 
 use std::alloc::{alloc, dealloc, Layout};
-use crate::{HeapHeader, HeapTag, NamlString, naml_string_new};
+use crate::{HEAP_FLAG_VIEW, HeapHeader, HeapTag, NamlString, naml_string_new};
 
 /// A heap-allocated byte array
 #[repr(C)]
 pub struct NamlBytes {
     pub header: HeapHeader,
     pub len: usize,
+    /// Bytes available at `as_ptr` without growing
     pub capacity: usize,
+    /// The bytes. A view holds a `BytesView` here instead, so read and
+    /// write them through `as_ptr`/`as_mut_ptr`.
     pub data: [u8; 0],
 }
 
+/// What a bytes view stores in `NamlBytes::data`: the buffer that owns the
+/// bytes, which it holds a reference to, and where its range starts
+#[repr(C)]
+struct BytesView {
+    parent: *mut NamlBytes,
+    ptr: *mut u8,
+}
+
+impl NamlBytes {
+    fn view(&self) -> *mut BytesView {
+        self.data.as_ptr() as *mut BytesView
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        if self.header.is_view() {
+            unsafe { (*self.view()).ptr }
+        } else {
+            self.data.as_ptr()
+        }
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        if self.header.is_view() {
+            unsafe { (*self.view()).ptr }
+        } else {
+            self.data.as_mut_ptr()
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+fn view_layout() -> Layout {
+    Layout::from_size_align(
+        std::mem::size_of::<NamlBytes>() + std::mem::size_of::<BytesView>(),
+        std::mem::align_of::<NamlBytes>(),
+    ).unwrap()
+}
+
 /// Allocate new bytes with given capacity
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_bytes_new(capacity: usize) -> *mut NamlBytes {
@@ -89,7 +137,7 @@ pub unsafe extern "C" fn naml_bytes_get(b: *const NamlBytes, index: i64) -> i64
         if index < 0 || index as usize >= (*b).len {
             return 0;
         }
-        *(*b).data.as_ptr().add(index as usize) as i64
+        *(*b).as_ptr().add(index as usize) as i64
     }
 }
 
@@ -101,8 +149,60 @@ pub unsafe extern "C" fn naml_bytes_set(b: *mut NamlBytes, index: i64, value: i6
     }
     unsafe {
         if index >= 0 && (index as usize) < (*b).len {
-            *(*b).data.as_mut_ptr().add(index as usize) = value as u8;
+            *(*b).as_mut_ptr().add(index as usize) = value as u8;
+        }
+    }
+}
+
+/// Create a view of `b[start..end]` that shares its bytes. Indices must
+/// already be clamped so that `start <= end <= len`.
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_bytes_view(b: *const NamlBytes, start: usize, end: usize) -> *mut NamlBytes {
+    unsafe {
+        let parent = if (*b).header.is_view() { (*(*b).view()).parent } else { b as *mut NamlBytes };
+        (*parent).header.incref();
+
+        let ptr = alloc(view_layout()) as *mut NamlBytes;
+        if ptr.is_null() {
+            panic!("Failed to allocate bytes view");
+        }
+        (*ptr).header = HeapHeader::new((*b).header.tag);
+        (*ptr).header.flags = HEAP_FLAG_VIEW;
+        (*ptr).len = end - start;
+        (*ptr).capacity = end - start;
+        (*ptr).view().write(BytesView {
+            parent,
+            ptr: ((*b).as_ptr() as *mut u8).add(start),
+        });
+        ptr
+    }
+}
+
+/// Make sure a view has room for `capacity` bytes. A view that must grow
+/// past its range is moved onto a new buffer holding a copy of its bytes,
+/// and stops sharing them. Owned bytes are left for the caller to grow.
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes.
+pub unsafe fn reserve_bytes_view(b: *mut NamlBytes, capacity: usize) {
+    unsafe {
+        if !(*b).header.is_view() || capacity <= (*b).capacity {
+            return;
         }
+        let new_cap = capacity.next_power_of_two().max(capacity);
+        let owned = naml_bytes_new(new_cap);
+        (*owned).header.tag = (*b).header.tag;
+        std::ptr::copy_nonoverlapping((*b).as_ptr(), (*owned).data.as_mut_ptr(), (*b).len);
+        (*owned).len = (*b).len;
+
+        let view = (*b).view();
+        naml_bytes_decref((*view).parent);
+        (*view).parent = owned;
+        (*view).ptr = (*owned).data.as_mut_ptr();
+        (*b).capacity = new_cap;
     }
 }
 
@@ -120,6 +220,12 @@ pub unsafe extern "C" fn naml_bytes_decref(b: *mut NamlBytes) {
     if !b.is_null() {
         unsafe {
             if (*b).header.decref() {
+                if (*b).header.is_view() {
+                    let parent = (*(*b).view()).parent;
+                    dealloc(b as *mut u8, view_layout());
+                    naml_bytes_decref(parent);
+                    return;
+                }
                 let cap = (*b).capacity;
                 let layout = Layout::from_size_align(
                     std::mem::size_of::<NamlBytes>() + cap,
@@ -138,8 +244,7 @@ pub unsafe extern "C" fn naml_bytes_to_string(b: *const NamlBytes) -> *mut NamlS
         return unsafe { naml_string_new(std::ptr::null(), 0) };
     }
     unsafe {
-        let slice = std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len);
-        naml_string_new(slice.as_ptr(), slice.len())
+        naml_string_new((*b).as_ptr(), (*b).len)
     }
 }
 
@@ -151,7 +256,7 @@ pub unsafe extern "C" fn naml_string_to_bytes(s: *const NamlString) -> *mut Naml
     }
     unsafe {
         let len = (*s).len;
-        naml_bytes_from((*s).data.as_ptr(), len)
+        naml_bytes_from((*s).as_ptr(), len)
     }
 }
 
@@ -181,4 +286,31 @@ mod tests {
             naml_bytes_decref(b);
         }
     }
+
+    #[test]
+    fn test_bytes_view_shares_until_grown() {
+        unsafe {
+            let data = b"hello world";
+            let b = naml_bytes_from(data.as_ptr(), data.len());
+            let view = naml_bytes_view(b, 6, 11);
+            let nested = naml_bytes_view(view, 1, 3);
+            assert_eq!((*view).as_bytes(), b"world");
+            assert_eq!((*nested).as_bytes(), b"or");
+            assert_eq!((*b).header.refcount(), 3);
+
+            naml_bytes_set(view, 0, 'W' as i64);
+            assert_eq!(naml_bytes_get(b, 6), 'W' as i64);
+
+            reserve_bytes_view(view, 8);
+            assert_eq!((*b).header.refcount(), 2);
+            naml_bytes_set(view, 1, 'O' as i64);
+            assert_eq!((*view).as_bytes(), b"WOrld");
+            assert_eq!((*b).as_bytes(), b"hello World");
+
+            naml_bytes_decref(nested);
+            naml_bytes_decref(view);
+            assert_eq!((*b).header.refcount(), 1);
+            naml_bytes_decref(b);
+        }
+    }
 }
@@ -12,15 +12,37 @@
 //! - 4: PathError
 //! - 5: NetworkError
 //! - 6: TimeoutError
+//! - 14: ArithmeticError
+//! - 15: ConfigError
+//! - 16: InvalidTransition
+//! - 17: ValidationError
+//! - 18: MoneyError
+//! - 19: RegexError
+//! - 20: ImageError
+//! - 21: PdfError
+//! - 22: XlsxError
+//! - 23: MarkdownError
+//! - 4096+: User-defined exceptions (base + compiler type ID)
+//!
+//! Causes recorded by `throw ... caused_by err;` live in a side table keyed by
+//! the exception pointer, so exception layouts are unaffected.
 //!
 
 use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use crate::stack::{naml_stack_capture, naml_stack_format};
+use crate::value::{NamlString, naml_string_decref, naml_string_new};
 
 thread_local! {
     static CURRENT_EXCEPTION: Cell<*mut u8> = const { Cell::new(std::ptr::null_mut()) };
     static CURRENT_EXCEPTION_TYPE_ID: Cell<i64> = const { Cell::new(0) };
 }
 
+static EXCEPTION_CAUSES: LazyLock<Mutex<HashMap<usize, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Exception type IDs for built-in exceptions
 pub const EXCEPTION_TYPE_UNKNOWN: i64 = 0;
 pub const EXCEPTION_TYPE_IO_ERROR: i64 = 1;
@@ -35,6 +57,33 @@ pub const EXCEPTION_TYPE_PROCESS_ERROR: i64 = 9;
 pub const EXCEPTION_TYPE_DB_ERROR: i64 = 10;
 pub const EXCEPTION_TYPE_ENCODE_ERROR: i64 = 11;
 pub const EXCEPTION_TYPE_SCHEDULE_ERROR: i64 = 12;
+pub const EXCEPTION_TYPE_SECURITY_ERROR: i64 = 13;
+pub const EXCEPTION_TYPE_ARITHMETIC_ERROR: i64 = 14;
+pub const EXCEPTION_TYPE_CONFIG_ERROR: i64 = 15;
+pub const EXCEPTION_TYPE_INVALID_TRANSITION: i64 = 16;
+pub const EXCEPTION_TYPE_VALIDATION_ERROR: i64 = 17;
+pub const EXCEPTION_TYPE_MONEY_ERROR: i64 = 18;
+pub const EXCEPTION_TYPE_REGEX_ERROR: i64 = 19;
+pub const EXCEPTION_TYPE_IMAGE_ERROR: i64 = 20;
+pub const EXCEPTION_TYPE_PDF_ERROR: i64 = 21;
+pub const EXCEPTION_TYPE_XLSX_ERROR: i64 = 22;
+pub const EXCEPTION_TYPE_MARKDOWN_ERROR: i64 = 23;
+pub const EXCEPTION_TYPE_USER_BASE: i64 = 4096;
+
+/// Kinds of ArithmeticError passed by generated code
+pub const ARITHMETIC_DIVIDE_BY_ZERO: i64 = 0;
+pub const ARITHMETIC_REMAINDER_BY_ZERO: i64 = 1;
+pub const ARITHMETIC_DIVIDE_OVERFLOW: i64 = 2;
+pub const ARITHMETIC_NAN_MAP_KEY: i64 = 3;
+
+pub fn arithmetic_error_message(kind: i64) -> &'static str {
+    match kind {
+        ARITHMETIC_DIVIDE_BY_ZERO => "attempt to divide by zero",
+        ARITHMETIC_REMAINDER_BY_ZERO => "attempt to calculate the remainder with a divisor of zero",
+        ARITHMETIC_NAN_MAP_KEY => "NaN cannot be used as a map key",
+        _ => "attempt to divide with overflow",
+    }
+}
 
 /// Set the current exception (called by throw)
 #[unsafe(no_mangle)]
@@ -86,3 +135,70 @@ pub extern "C" fn naml_exception_clear_ptr() {
 pub extern "C" fn naml_exception_check() -> i64 {
     CURRENT_EXCEPTION.with(|ex| if ex.get().is_null() { 0 } else { 1 })
 }
+
+/// Record `cause` as the cause of `exception_ptr` (called by `throw ... caused_by`)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_exception_set_cause(exception_ptr: *mut u8, cause_ptr: *mut u8) {
+    if exception_ptr.is_null() || cause_ptr.is_null() || exception_ptr == cause_ptr {
+        return;
+    }
+    let mut causes = EXCEPTION_CAUSES.lock().unwrap();
+    causes.insert(exception_ptr as usize, cause_ptr as usize);
+}
+
+/// Get the cause of an exception (null if it has none)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_exception_get_cause(exception_ptr: *mut u8) -> *mut u8 {
+    let causes = EXCEPTION_CAUSES.lock().unwrap();
+    causes
+        .get(&(exception_ptr as usize))
+        .map_or(std::ptr::null_mut(), |&cause| cause as *mut u8)
+}
+
+/// Format an exception's stack trace followed by each cause in its chain
+/// Takes exception pointer (message@0, stack@8), returns NamlString pointer
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_exception_stack_string(exception_ptr: *mut u8) -> *mut NamlString {
+    unsafe {
+        let mut result = String::new();
+        let mut current = exception_ptr;
+        let mut seen = Vec::new();
+        while !current.is_null() && !seen.contains(&current) {
+            if !seen.is_empty() {
+                let message = *(current as *const *const NamlString);
+                let message = if message.is_null() { "" } else { (*message).as_str() };
+                result.push_str("Caused by: ");
+                result.push_str(message);
+                result.push('\n');
+            }
+            let formatted = naml_stack_format(*(current.add(8) as *const *mut u8));
+            let trace = (*formatted).as_str();
+            // Causes list their frames directly under the "Caused by:" line
+            if seen.is_empty() {
+                result.push_str(trace);
+            } else {
+                result.push_str(trace.split_once('\n').map_or("", |(_, frames)| frames));
+            }
+            naml_string_decref(formatted);
+            seen.push(current);
+            current = naml_exception_get_cause(current);
+        }
+        naml_string_new(result.as_ptr(), result.len())
+    }
+}
+
+/// Throw ArithmeticError with layout: message@0, stack@8
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_throw_arithmetic_error(kind: i64) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(16, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate ArithmeticError");
+        }
+        let message = arithmetic_error_message(kind);
+        *(ptr as *mut *mut NamlString) = naml_string_new(message.as_ptr(), message.len());
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_ARITHMETIC_ERROR);
+    }
+}
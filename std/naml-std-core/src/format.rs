@@ -0,0 +1,424 @@
+///
+/// Format Spec Runtime Functions
+///
+/// Implements the `{:spec}` placeholders of `fmt`, `print`, `println`,
+/// `warn`, `error`, `panic` and template strings. A spec has the form
+/// `[[fill]align][+][#][0][width][,][.precision][type]`:
+///
+/// - `align`: `<` left, `>` right, `^` center (numbers default to right,
+///   strings to left); `fill` is any character and defaults to a space
+/// - `+`: always print a sign for numbers
+/// - `#`: prefix `0x`, `0o` or `0b` for the radix types
+/// - `0`: pad numbers with zeros after the sign and prefix
+/// - `,`: group integer digits in thousands
+/// - `.precision`: digits after the point for numbers, or the maximum
+///   number of characters for strings
+/// - `type`: `x`/`X` hex, `o` octal, `b` binary (ints only), `e` exponent
+///
+/// The compiler parses every spec with `FormatSpec::parse` and reports
+/// invalid ones, so the runtime functions only ever see valid specs.
+///
+
+use crate::value::{naml_string_new, NamlString};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatAlign {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatKind {
+    Default,
+    Hex,
+    UpperHex,
+    Octal,
+    Binary,
+    Exponent,
+}
+
+/// Widths and precisions above this are rejected
+pub const MAX_FORMAT_WIDTH: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub fill: char,
+    pub align: Option<FormatAlign>,
+    pub sign_plus: bool,
+    pub alternate: bool,
+    pub zero: bool,
+    pub width: usize,
+    pub grouping: bool,
+    pub precision: Option<usize>,
+    pub kind: FormatKind,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        Self {
+            fill: ' ',
+            align: None,
+            sign_plus: false,
+            alternate: false,
+            zero: false,
+            width: 0,
+            grouping: false,
+            precision: None,
+            kind: FormatKind::Default,
+        }
+    }
+}
+
+fn align_of(c: char) -> Option<FormatAlign> {
+    match c {
+        '<' => Some(FormatAlign::Left),
+        '>' => Some(FormatAlign::Right),
+        '^' => Some(FormatAlign::Center),
+        _ => None,
+    }
+}
+
+impl FormatSpec {
+    /// Parse the text after the `:` of a placeholder
+    pub fn parse(spec: &str) -> Result<FormatSpec, String> {
+        let mut out = FormatSpec::default();
+        let chars: Vec<char> = spec.chars().collect();
+        let mut i = 0;
+
+        if let Some(align) = chars.get(1).and_then(|&c| align_of(c)) {
+            out.fill = chars[0];
+            out.align = Some(align);
+            i = 2;
+        } else if let Some(align) = chars.first().and_then(|&c| align_of(c)) {
+            out.align = Some(align);
+            i = 1;
+        }
+        if chars.get(i) == Some(&'+') {
+            out.sign_plus = true;
+            i += 1;
+        }
+        if chars.get(i) == Some(&'#') {
+            out.alternate = true;
+            i += 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            out.zero = true;
+            i += 1;
+        }
+        let (width, next) = parse_number(&chars, i, "width")?;
+        out.width = width.unwrap_or(0);
+        i = next;
+        if chars.get(i) == Some(&',') {
+            out.grouping = true;
+            i += 1;
+        }
+        if chars.get(i) == Some(&'.') {
+            let (precision, next) = parse_number(&chars, i + 1, "precision")?;
+            out.precision = Some(precision.ok_or("missing precision after '.'")?);
+            i = next;
+        }
+        if let Some(&c) = chars.get(i) {
+            out.kind = match c {
+                'x' => FormatKind::Hex,
+                'X' => FormatKind::UpperHex,
+                'o' => FormatKind::Octal,
+                'b' => FormatKind::Binary,
+                'e' => FormatKind::Exponent,
+                _ => return Err(format!("unknown format type '{}'", c)),
+            };
+            i += 1;
+        }
+        if i < chars.len() {
+            return Err(format!("unexpected '{}'", chars[i..].iter().collect::<String>()));
+        }
+
+        if out.is_radix() {
+            if out.grouping {
+                return Err("',' cannot be combined with a radix type".to_string());
+            }
+            if out.precision.is_some() {
+                return Err("precision cannot be combined with a radix type".to_string());
+            }
+        } else if out.alternate {
+            return Err("'#' needs a radix type (x, X, o or b)".to_string());
+        }
+        Ok(out)
+    }
+
+    /// Hex, octal or binary, which only apply to integers
+    pub fn is_radix(&self) -> bool {
+        matches!(self.kind, FormatKind::Hex | FormatKind::UpperHex | FormatKind::Octal | FormatKind::Binary)
+    }
+
+    /// Whether the spec only makes sense for numbers
+    pub fn is_numeric(&self) -> bool {
+        self.is_radix() || self.kind == FormatKind::Exponent || self.sign_plus || self.grouping || self.zero
+    }
+
+    /// Format an integer. A precision or `e` formats it as a float.
+    pub fn format_int(&self, n: i64) -> String {
+        if self.precision.is_some() || self.kind == FormatKind::Exponent {
+            return self.format_float(n as f64);
+        }
+        let magnitude = n.unsigned_abs();
+        let (prefix, digits) = match self.kind {
+            FormatKind::Hex => ("0x", format!("{:x}", magnitude)),
+            FormatKind::UpperHex => ("0x", format!("{:X}", magnitude)),
+            FormatKind::Octal => ("0o", format!("{:o}", magnitude)),
+            FormatKind::Binary => ("0b", format!("{:b}", magnitude)),
+            _ => ("", self.group(magnitude.to_string())),
+        };
+        let mut sign = self.sign(n < 0).to_string();
+        if self.alternate {
+            sign.push_str(prefix);
+        }
+        self.pad_number(&sign, &digits)
+    }
+
+    /// Format a float. Radix types do not apply to floats and are ignored.
+    pub fn format_float(&self, f: f64) -> String {
+        if !f.is_finite() {
+            let text = if f.is_nan() { "NaN".to_string() } else { format!("{}inf", self.sign(f < 0.0)) };
+            return self.pad(&text, FormatAlign::Right);
+        }
+        let magnitude = f.abs();
+        let body = match (self.kind, self.precision) {
+            (FormatKind::Exponent, Some(p)) => format!("{:.*e}", p, magnitude),
+            (FormatKind::Exponent, None) => format!("{:e}", magnitude),
+            (_, Some(p)) => format!("{:.*}", p, magnitude),
+            (_, None) => magnitude.to_string(),
+        };
+        let digits = match body.find(['.', 'e']) {
+            Some(end) => format!("{}{}", self.group(body[..end].to_string()), &body[end..]),
+            None => self.group(body),
+        };
+        self.pad_number(self.sign(f.is_sign_negative()), &digits)
+    }
+
+    /// Format a string; a precision truncates it to that many characters
+    pub fn format_str(&self, s: &str) -> String {
+        match self.precision {
+            Some(p) => {
+                let end = s.char_indices().nth(p).map_or(s.len(), |(i, _)| i);
+                self.pad(&s[..end], FormatAlign::Left)
+            }
+            None => self.pad(s, FormatAlign::Left),
+        }
+    }
+
+    fn sign(&self, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if self.sign_plus {
+            "+"
+        } else {
+            ""
+        }
+    }
+
+    fn group(&self, digits: String) -> String {
+        if !self.grouping || digits.len() <= 3 {
+            return digits;
+        }
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(',');
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    /// Zero padding goes between the sign and the digits; otherwise pad
+    /// the whole number
+    fn pad_number(&self, sign: &str, digits: &str) -> String {
+        if self.zero && self.align.is_none() {
+            let len = sign.chars().count() + digits.chars().count();
+            let zeros = "0".repeat(self.width.saturating_sub(len));
+            return format!("{}{}{}", sign, zeros, digits);
+        }
+        self.pad(&format!("{}{}", sign, digits), FormatAlign::Right)
+    }
+
+    fn pad(&self, text: &str, default_align: FormatAlign) -> String {
+        let len = text.chars().count();
+        if len >= self.width {
+            return text.to_string();
+        }
+        let total = self.width - len;
+        let (left, right) = match self.align.unwrap_or(default_align) {
+            FormatAlign::Left => (0, total),
+            FormatAlign::Right => (total, 0),
+            FormatAlign::Center => (total / 2, total - total / 2),
+        };
+        let fill = |n: usize| std::iter::repeat_n(self.fill, n).collect::<String>();
+        format!("{}{}{}", fill(left), text, fill(right))
+    }
+}
+
+/// A piece of a format string: literal text, `{}` or `{:spec}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatPiece<'a> {
+    Literal(&'a str),
+    Placeholder(Option<&'a str>),
+}
+
+/// Split a format string into literal text and placeholders. Braces that
+/// do not form `{}` or `{:spec}` are kept as literal text.
+pub fn format_pieces(format_str: &str) -> Vec<FormatPiece<'_>> {
+    let mut pieces = Vec::new();
+    let mut literal_start = 0;
+    let mut pos = 0;
+    while let Some(offset) = format_str[pos..].find('{') {
+        let start = pos + offset;
+        let rest = &format_str[start + 1..];
+        let placeholder = if rest.starts_with('}') {
+            Some((None, start + 2))
+        } else if let Some(spec) = rest.strip_prefix(':') {
+            spec.find('}').map(|end| (Some(&spec[..end]), start + 2 + end + 1))
+        } else {
+            None
+        };
+        match placeholder {
+            Some((spec, end)) => {
+                if start > literal_start {
+                    pieces.push(FormatPiece::Literal(&format_str[literal_start..start]));
+                }
+                pieces.push(FormatPiece::Placeholder(spec));
+                literal_start = end;
+                pos = end;
+            }
+            None => pos = start + 1,
+        }
+    }
+    if literal_start < format_str.len() {
+        pieces.push(FormatPiece::Literal(&format_str[literal_start..]));
+    }
+    pieces
+}
+
+fn parse_number(chars: &[char], start: usize, what: &str) -> Result<(Option<usize>, usize), String> {
+    let mut end = start;
+    while end < chars.len() && chars[end].is_ascii_digit() {
+        end += 1;
+    }
+    if end == start {
+        return Ok((None, start));
+    }
+    let text: String = chars[start..end].iter().collect();
+    match text.parse::<usize>() {
+        Ok(n) if n <= MAX_FORMAT_WIDTH => Ok((Some(n), end)),
+        _ => Err(format!("{} must be at most {}", what, MAX_FORMAT_WIDTH)),
+    }
+}
+
+unsafe fn spec_of(spec: *const NamlString) -> FormatSpec {
+    if spec.is_null() {
+        return FormatSpec::default();
+    }
+    FormatSpec::parse(unsafe { (*spec).as_str() }).unwrap_or_default()
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+/// Format an integer with a spec
+///
+/// # Safety
+/// The caller must ensure `spec` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_format_int(n: i64, spec: *const NamlString) -> *mut NamlString {
+    unsafe { naml_from_string(&spec_of(spec).format_int(n)) }
+}
+
+/// Format a float with a spec
+///
+/// # Safety
+/// The caller must ensure `spec` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_format_float(f: f64, spec: *const NamlString) -> *mut NamlString {
+    unsafe { naml_from_string(&spec_of(spec).format_float(f)) }
+}
+
+/// Format a string with a spec
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString or null and
+/// `spec` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_format_string(s: *const NamlString, spec: *const NamlString) -> *mut NamlString {
+    unsafe {
+        let text = if s.is_null() { "" } else { (*s).as_str() };
+        naml_from_string(&spec_of(spec).format_str(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(s: &str) -> FormatSpec {
+        FormatSpec::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let s = spec("*^+010,.3");
+        assert_eq!((s.fill, s.align, s.sign_plus, s.zero), ('*', Some(FormatAlign::Center), true, true));
+        assert_eq!((s.width, s.grouping, s.precision), (10, true, Some(3)));
+        assert_eq!(spec(">").align, Some(FormatAlign::Right));
+        assert_eq!(spec("<<").fill, '<');
+        assert!(FormatSpec::parse("10q").is_err());
+        assert!(FormatSpec::parse(".").is_err());
+        assert!(FormatSpec::parse("#10").is_err());
+        assert!(FormatSpec::parse(".3x").is_err());
+        assert!(FormatSpec::parse("99999").is_err());
+    }
+
+    #[test]
+    fn test_format_pieces() {
+        use FormatPiece::*;
+        assert_eq!(
+            format_pieces("a {} b {:>5} {x} {:"),
+            [Literal("a "), Placeholder(None), Literal(" b "), Placeholder(Some(">5")), Literal(" {x} {:")]
+        );
+        assert_eq!(format_pieces("{}{}"), [Placeholder(None), Placeholder(None)]);
+        assert_eq!(format_pieces("plain"), [Literal("plain")]);
+    }
+
+    #[test]
+    fn test_format_int() {
+        assert_eq!(spec(">6").format_int(42), "    42");
+        assert_eq!(spec("<6").format_int(42), "42    ");
+        assert_eq!(spec("^6").format_int(42), "  42  ");
+        assert_eq!(spec("08x").format_int(255), "000000ff");
+        assert_eq!(spec("#06X").format_int(255), "0x00FF");
+        assert_eq!(spec("b").format_int(-5), "-101");
+        assert_eq!(spec(",").format_int(-1234567), "-1,234,567");
+        assert_eq!(spec("+05").format_int(42), "+0042");
+        assert_eq!(spec(".2").format_int(3), "3.00");
+    }
+
+    #[test]
+    fn test_format_float() {
+        assert_eq!(spec(".3").format_float(1.23456), "1.235");
+        assert_eq!(spec("8.2").format_float(-2.5), "   -2.50");
+        assert_eq!(spec("08.2").format_float(-2.5), "-0002.50");
+        assert_eq!(spec(",.2").format_float(1234567.891), "1,234,567.89");
+        assert_eq!(spec(".2e").format_float(1234.5), "1.23e3");
+        assert_eq!(spec("+").format_float(1.5), "+1.5");
+        assert_eq!(spec("06").format_float(f64::INFINITY), "   inf");
+    }
+
+    #[test]
+    fn test_format_str() {
+        assert_eq!(spec("6").format_str("ab"), "ab    ");
+        assert_eq!(spec(">6").format_str("ab"), "    ab");
+        assert_eq!(spec("-^7").format_str("mid"), "--mid--");
+        assert_eq!(spec(".3").format_str("naïve"), "naï");
+        assert_eq!(spec("3").format_str("toolong"), "toolong");
+    }
+}
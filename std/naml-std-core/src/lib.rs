@@ -8,7 +8,11 @@
 //! - `NamlArray` for heap-allocated dynamic arrays
 //! - `NamlBytes` for heap-allocated byte arrays
 //! - `NamlStruct` for heap-allocated struct instances
+//! - `NamlStringBuilder` for building strings without quadratic copying
+//! - `NamlSet` and `NamlPriorityQueue` for sorted sets and min-heaps
 //! - Exception handling primitives for try/catch support
+//! - `FormatSpec` for the `{:spec}` placeholders of `fmt` and `print`
+//! - SIMD kernels for string, array and byte scanning
 //!
 //! All heap objects use atomic reference counting for thread safety.
 //! Values are passed as 64-bit tagged pointers or inline primitives.
@@ -17,17 +21,26 @@
 pub mod value;
 pub mod array;
 pub mod bytes;
+pub mod string_builder;
 pub mod map;
+pub mod set;
+pub mod priority_queue;
 pub mod print;
+pub mod format;
 pub mod exception;
 pub mod stack;
 pub mod arena;
+pub mod simd;
 
 pub use value::*;
 pub use array::*;
 pub use bytes::*;
+pub use string_builder::*;
 pub use map::*;
+pub use set::*;
+pub use priority_queue::*;
 pub use print::*;
+pub use format::*;
 pub use exception::*;
 pub use stack::*;
 pub use arena::*;
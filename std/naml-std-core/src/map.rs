@@ -1,8 +1,17 @@
 ///
 /// Map Runtime
 ///
-/// Hash map implementation for naml map<K, V> type.
-/// Uses string keys with FNV-1a hashing and linear probing.
+/// Hash map implementation for naml map<K, V> type, with linear probing.
+/// Each map records the kind of its keys, which decides hashing and equality:
+///
+/// - string keys hash their bytes with FNV-1a and compare by content; the map
+///   holds a reference to each key
+/// - int, uint and bool keys hash their value and compare by value
+/// - float keys hash their bits after normalizing -0.0 to 0.0, so the two
+///   zeros are one key; NaN is never equal to itself, so looking one up
+///   finds nothing and storing one throws ArithmeticError where it can be
+///   caught, like a failed integer division, and panics otherwise. Generated
+///   code checks for a catchable NaN key before calling in.
 ///
 /// Core operations: new, set, get, contains, len, incref, decref.
 /// Typed set variants handle refcount management for heap values.
@@ -11,7 +20,8 @@
 
 use std::alloc::{alloc, alloc_zeroed, dealloc, Layout};
 use crate::{HeapHeader, HeapTag, NamlString, NamlArray, NamlStruct,
-            naml_string_decref, naml_array_decref, naml_struct_decref};
+            naml_string_decref, naml_array_decref, naml_struct_decref,
+            arithmetic_error_message, ARITHMETIC_NAN_MAP_KEY};
 
 const INITIAL_CAPACITY: usize = 16;
 const LOAD_FACTOR: f64 = 0.75;
@@ -22,6 +32,7 @@ pub struct NamlMap {
     pub capacity: usize,
     pub length: usize,
     pub entries: *mut MapEntry,
+    pub key_kind: u8,
 }
 
 #[repr(C)]
@@ -38,7 +49,7 @@ pub fn hash_string(s: *const NamlString) -> u64 {
     if s.is_null() { return 0; }
     unsafe {
         let len = (*s).len;
-        let data = (*s).data.as_ptr();
+        let data = (*s).as_ptr();
         // FNV-1a hash
         let mut hash: u64 = 0xcbf29ce484222325;
         for i in 0..len {
@@ -54,14 +65,112 @@ pub fn string_eq(a: *const NamlString, b: *const NamlString) -> bool {
     if a.is_null() || b.is_null() { return false; }
     unsafe {
         if (*a).len != (*b).len { return false; }
-        let a_slice = std::slice::from_raw_parts((*a).data.as_ptr(), (*a).len);
-        let b_slice = std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len);
+        let a_slice = std::slice::from_raw_parts((*a).as_ptr(), (*a).len);
+        let b_slice = std::slice::from_raw_parts((*b).as_ptr(), (*b).len);
         a_slice == b_slice
     }
 }
 
+pub const MAP_KEY_STRING: u8 = 0;
+pub const MAP_KEY_INT: u8 = 1;
+pub const MAP_KEY_FLOAT: u8 = 2;
+pub const MAP_KEY_BOOL: u8 = 3;
+
+/// The float key `key` stands for, with -0.0 folded into 0.0; `None` for NaN
+pub fn normalize_float_key(key: i64) -> Option<i64> {
+    let f = f64::from_bits(key as u64);
+    if f.is_nan() {
+        None
+    } else if f == 0.0 {
+        Some(0)
+    } else {
+        Some(key)
+    }
+}
+
+fn mix64(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58476d1ce4e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Hash of a (normalized) key of `map`
+///
+/// # Safety
+/// The caller must ensure `map` is a valid pointer to a NamlMap.
+pub unsafe fn map_key_hash(map: *const NamlMap, key: i64) -> u64 {
+    unsafe {
+        match (*map).key_kind {
+            MAP_KEY_STRING => hash_string(key as *const NamlString),
+            _ => mix64(key as u64),
+        }
+    }
+}
+
+/// Whether two (normalized) keys of `map` are the same key
+///
+/// # Safety
+/// The caller must ensure `map` is a valid pointer to a NamlMap.
+pub unsafe fn map_key_eq(map: *const NamlMap, a: i64, b: i64) -> bool {
+    unsafe {
+        match (*map).key_kind {
+            MAP_KEY_STRING => string_eq(a as *const NamlString, b as *const NamlString),
+            _ => a == b,
+        }
+    }
+}
+
+/// The stored form of `key`, or `None` if no entry can have it
+///
+/// # Safety
+/// The caller must ensure `map` is a valid pointer to a NamlMap.
+pub unsafe fn map_lookup_key(map: *const NamlMap, key: i64) -> Option<i64> {
+    unsafe {
+        if (*map).key_kind == MAP_KEY_FLOAT { normalize_float_key(key) } else { Some(key) }
+    }
+}
+
+unsafe fn retain_key(map: *const NamlMap, key: i64) {
+    unsafe {
+        if (*map).key_kind == MAP_KEY_STRING && key != 0 {
+            (*(key as *mut NamlString)).header.incref();
+        }
+    }
+}
+
+unsafe fn release_key(map: *const NamlMap, key: i64) {
+    unsafe {
+        if (*map).key_kind == MAP_KEY_STRING && key != 0 {
+            naml_string_decref(key as *mut NamlString);
+        }
+    }
+}
+
+unsafe fn format_key(map: *const NamlMap, key: i64) -> String {
+    unsafe {
+        match (*map).key_kind {
+            MAP_KEY_STRING => {
+                let key_ptr = key as *const NamlString;
+                let key_str = if key_ptr.is_null() { "null" } else { (*key_ptr).as_str() };
+                format!("\"{}\"", key_str)
+            }
+            MAP_KEY_FLOAT => f64::from_bits(key as u64).to_string(),
+            MAP_KEY_BOOL => (key != 0).to_string(),
+            _ => key.to_string(),
+        }
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_map_new(capacity: usize) -> *mut NamlMap {
+    naml_map_new_keyed(capacity, MAP_KEY_STRING as i64)
+}
+
+/// Create a map whose keys are of the given `MAP_KEY_*` kind
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_map_new_keyed(capacity: usize, key_kind: i64) -> *mut NamlMap {
     let cap = if capacity < INITIAL_CAPACITY { INITIAL_CAPACITY } else { capacity };
     unsafe {
         let map_layout = Layout::new::<NamlMap>();
@@ -76,48 +185,33 @@ pub unsafe extern "C" fn naml_map_new(capacity: usize) -> *mut NamlMap {
         (*map_ptr).capacity = cap;
         (*map_ptr).length = 0;
         (*map_ptr).entries = entries_ptr;
+        (*map_ptr).key_kind = key_kind as u8;
         map_ptr
     }
 }
 
-/// Set a primitive value in the map (no refcount management for values)
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_map_set(map: *mut NamlMap, key: i64, value: i64) {
-    if map.is_null() { return; }
+/// Create an empty map with the same key kind as `map`
+///
+/// # Safety
+/// The caller must ensure `map` is a valid pointer to a NamlMap or null.
+pub unsafe fn naml_map_new_like(map: *const NamlMap, capacity: usize) -> *mut NamlMap {
     unsafe {
-        if ((*map).length + 1) as f64 / (*map).capacity as f64 > LOAD_FACTOR {
-            resize_map(map);
-        }
-        let hash = hash_string(key as *const NamlString);
-        let mut idx = (hash as usize) % (*map).capacity;
-        loop {
-            let entry = (*map).entries.add(idx);
-            if !(*entry).occupied {
-                (*entry).key = key;
-                (*entry).value = value;
-                (*entry).occupied = true;
-                (*map).length += 1;
-                if key != 0 { (*(key as *mut NamlString)).header.incref(); }
-                return;
-            }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
-                (*entry).value = value;
-                return;
-            }
-            idx = (idx + 1) % (*map).capacity;
-        }
+        let kind = if map.is_null() { MAP_KEY_STRING } else { (*map).key_kind };
+        naml_map_new_keyed(capacity, kind as i64)
     }
 }
 
-/// Set a string value in the map (decrefs old string value when updating)
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_map_set_string(map: *mut NamlMap, key: i64, value: i64) {
-    if map.is_null() { return; }
+/// Insert or update an entry, handing a replaced value to `release`
+unsafe fn map_insert(map: *mut NamlMap, key: i64, value: i64, release: unsafe fn(i64)) {
     unsafe {
+        let Some(key) = map_lookup_key(map, key) else {
+            eprintln!("panic: {}", arithmetic_error_message(ARITHMETIC_NAN_MAP_KEY));
+            std::process::abort();
+        };
         if ((*map).length + 1) as f64 / (*map).capacity as f64 > LOAD_FACTOR {
             resize_map(map);
         }
-        let hash = hash_string(key as *const NamlString);
+        let hash = map_key_hash(map, key);
         let mut idx = (hash as usize) % (*map).capacity;
         loop {
             let entry = (*map).entries.add(idx);
@@ -126,12 +220,12 @@ pub unsafe extern "C" fn naml_map_set_string(map: *mut NamlMap, key: i64, value:
                 (*entry).value = value;
                 (*entry).occupied = true;
                 (*map).length += 1;
-                if key != 0 { (*(key as *mut NamlString)).header.incref(); }
+                retain_key(map, key);
                 return;
             }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
+            if map_key_eq(map, (*entry).key, key) {
                 if (*entry).value != 0 {
-                    naml_string_decref((*entry).value as *mut NamlString);
+                    release((*entry).value);
                 }
                 (*entry).value = value;
                 return;
@@ -141,140 +235,75 @@ pub unsafe extern "C" fn naml_map_set_string(map: *mut NamlMap, key: i64, value:
     }
 }
 
-/// Set an array value in the map (decrefs old array value when updating)
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_map_set_array(map: *mut NamlMap, key: i64, value: i64) {
-    if map.is_null() { return; }
+/// Index of the entry for `key`, if present
+unsafe fn map_find(map: *const NamlMap, key: i64) -> Option<usize> {
     unsafe {
-        if ((*map).length + 1) as f64 / (*map).capacity as f64 > LOAD_FACTOR {
-            resize_map(map);
-        }
-        let hash = hash_string(key as *const NamlString);
+        let key = map_lookup_key(map, key)?;
+        let hash = map_key_hash(map, key);
         let mut idx = (hash as usize) % (*map).capacity;
+        let start_idx = idx;
         loop {
             let entry = (*map).entries.add(idx);
-            if !(*entry).occupied {
-                (*entry).key = key;
-                (*entry).value = value;
-                (*entry).occupied = true;
-                (*map).length += 1;
-                if key != 0 { (*(key as *mut NamlString)).header.incref(); }
-                return;
-            }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
-                if (*entry).value != 0 {
-                    naml_array_decref((*entry).value as *mut NamlArray);
-                }
-                (*entry).value = value;
-                return;
+            if !(*entry).occupied { return None; }
+            if map_key_eq(map, (*entry).key, key) {
+                return Some(idx);
             }
             idx = (idx + 1) % (*map).capacity;
+            if idx == start_idx { return None; }
         }
     }
 }
 
+/// Set a primitive value in the map (no refcount management for values)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_map_set(map: *mut NamlMap, key: i64, value: i64) {
+    if map.is_null() { return; }
+    unsafe { map_insert(map, key, value, |_| {}) }
+}
+
+/// Set a string value in the map (decrefs old string value when updating)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_map_set_string(map: *mut NamlMap, key: i64, value: i64) {
+    if map.is_null() { return; }
+    unsafe { map_insert(map, key, value, |old| naml_string_decref(old as *mut NamlString)) }
+}
+
+/// Set an array value in the map (decrefs old array value when updating)
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_map_set_array(map: *mut NamlMap, key: i64, value: i64) {
+    if map.is_null() { return; }
+    unsafe { map_insert(map, key, value, |old| naml_array_decref(old as *mut NamlArray)) }
+}
+
 /// Set a map value in the map (decrefs old map value when updating)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_map_set_map(map: *mut NamlMap, key: i64, value: i64) {
     if map.is_null() { return; }
-    unsafe {
-        if ((*map).length + 1) as f64 / (*map).capacity as f64 > LOAD_FACTOR {
-            resize_map(map);
-        }
-        let hash = hash_string(key as *const NamlString);
-        let mut idx = (hash as usize) % (*map).capacity;
-        loop {
-            let entry = (*map).entries.add(idx);
-            if !(*entry).occupied {
-                (*entry).key = key;
-                (*entry).value = value;
-                (*entry).occupied = true;
-                (*map).length += 1;
-                if key != 0 { (*(key as *mut NamlString)).header.incref(); }
-                return;
-            }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
-                if (*entry).value != 0 {
-                    naml_map_decref((*entry).value as *mut NamlMap);
-                }
-                (*entry).value = value;
-                return;
-            }
-            idx = (idx + 1) % (*map).capacity;
-        }
-    }
+    unsafe { map_insert(map, key, value, |old| naml_map_decref(old as *mut NamlMap)) }
 }
 
 /// Set a struct value in the map (decrefs old struct value when updating)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_map_set_struct(map: *mut NamlMap, key: i64, value: i64) {
     if map.is_null() { return; }
-    unsafe {
-        if ((*map).length + 1) as f64 / (*map).capacity as f64 > LOAD_FACTOR {
-            resize_map(map);
-        }
-        let hash = hash_string(key as *const NamlString);
-        let mut idx = (hash as usize) % (*map).capacity;
-        loop {
-            let entry = (*map).entries.add(idx);
-            if !(*entry).occupied {
-                (*entry).key = key;
-                (*entry).value = value;
-                (*entry).occupied = true;
-                (*map).length += 1;
-                if key != 0 { (*(key as *mut NamlString)).header.incref(); }
-                return;
-            }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
-                if (*entry).value != 0 {
-                    naml_struct_decref((*entry).value as *mut NamlStruct);
-                }
-                (*entry).value = value;
-                return;
-            }
-            idx = (idx + 1) % (*map).capacity;
-        }
-    }
+    unsafe { map_insert(map, key, value, |old| naml_struct_decref(old as *mut NamlStruct)) }
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_map_get(map: *const NamlMap, key: i64) -> i64 {
     if map.is_null() { return 0; }
     unsafe {
-        let hash = hash_string(key as *const NamlString);
-        let mut idx = (hash as usize) % (*map).capacity;
-        let start_idx = idx;
-        loop {
-            let entry = (*map).entries.add(idx);
-            if !(*entry).occupied { return 0; }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
-                return (*entry).value;
-            }
-            idx = (idx + 1) % (*map).capacity;
-            if idx == start_idx { break; }
+        match map_find(map, key) {
+            Some(idx) => (*(*map).entries.add(idx)).value,
+            None => 0,
         }
-        0
     }
 }
 
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_map_contains(map: *const NamlMap, key: i64) -> i64 {
     if map.is_null() { return 0; }
-    unsafe {
-        let hash = hash_string(key as *const NamlString);
-        let mut idx = (hash as usize) % (*map).capacity;
-        let start_idx = idx;
-        loop {
-            let entry = (*map).entries.add(idx);
-            if !(*entry).occupied { return 0; }
-            if string_eq((*entry).key as *const NamlString, key as *const NamlString) {
-                return 1;
-            }
-            idx = (idx + 1) % (*map).capacity;
-            if idx == start_idx { break; }
-        }
-        0
-    }
+    unsafe { map_find(map, key).is_some() as i64 }
 }
 
 #[unsafe(no_mangle)]
@@ -295,7 +324,7 @@ pub unsafe extern "C" fn naml_map_decref(map: *mut NamlMap) {
             for i in 0..(*map).capacity {
                 let entry = (*map).entries.add(i);
                 if (*entry).occupied && (*entry).key != 0 {
-                    naml_string_decref((*entry).key as *mut NamlString);
+                    release_key(map, (*entry).key);
                 }
             }
             let entries_layout = Layout::array::<MapEntry>((*map).capacity).unwrap();
@@ -316,7 +345,7 @@ pub unsafe extern "C" fn naml_map_decref_strings(map: *mut NamlMap) {
                 let entry = (*map).entries.add(i);
                 if (*entry).occupied {
                     if (*entry).key != 0 {
-                        naml_string_decref((*entry).key as *mut NamlString);
+                        release_key(map, (*entry).key);
                     }
                     if (*entry).value != 0 {
                         naml_string_decref((*entry).value as *mut NamlString);
@@ -341,7 +370,7 @@ pub unsafe extern "C" fn naml_map_decref_arrays(map: *mut NamlMap) {
                 let entry = (*map).entries.add(i);
                 if (*entry).occupied {
                     if (*entry).key != 0 {
-                        naml_string_decref((*entry).key as *mut NamlString);
+                        release_key(map, (*entry).key);
                     }
                     if (*entry).value != 0 {
                         naml_array_decref((*entry).value as *mut NamlArray);
@@ -366,7 +395,7 @@ pub unsafe extern "C" fn naml_map_decref_maps(map: *mut NamlMap) {
                 let entry = (*map).entries.add(i);
                 if (*entry).occupied {
                     if (*entry).key != 0 {
-                        naml_string_decref((*entry).key as *mut NamlString);
+                        release_key(map, (*entry).key);
                     }
                     if (*entry).value != 0 {
                         naml_map_decref((*entry).value as *mut NamlMap);
@@ -391,7 +420,7 @@ pub unsafe extern "C" fn naml_map_decref_structs(map: *mut NamlMap) {
                 let entry = (*map).entries.add(i);
                 if (*entry).occupied {
                     if (*entry).key != 0 {
-                        naml_string_decref((*entry).key as *mut NamlString);
+                        release_key(map, (*entry).key);
                     }
                     if (*entry).value != 0 {
                         naml_struct_decref((*entry).value as *mut NamlStruct);
@@ -420,10 +449,7 @@ pub unsafe extern "C" fn naml_map_print(map: *const NamlMap) {
             if (*entry).occupied {
                 if !first { print!(", "); }
                 first = false;
-                let key_ptr = (*entry).key as *const NamlString;
-                if !key_ptr.is_null() {
-                    print!("\"{}\": {}", (*key_ptr).as_str(), (*entry).value);
-                }
+                print!("{}: {}", format_key(map, (*entry).key), (*entry).value);
             }
         }
         print!("}}");
@@ -444,13 +470,12 @@ pub unsafe extern "C" fn naml_map_print_string_values(map: *const NamlMap) {
             if (*entry).occupied {
                 if !first { print!(", "); }
                 first = false;
-                let key_ptr = (*entry).key as *const NamlString;
+                let key_str = format_key(map, (*entry).key);
                 let val_ptr = (*entry).value as *const NamlString;
-                let key_str = if !key_ptr.is_null() { (*key_ptr).as_str() } else { "null" };
                 if !val_ptr.is_null() {
-                    print!("\"{}\": \"{}\"", key_str, (*val_ptr).as_str());
+                    print!("{}: \"{}\"", key_str, (*val_ptr).as_str());
                 } else {
-                    print!("\"{}\": null", key_str);
+                    print!("{}: null", key_str);
                 }
             }
         }
@@ -472,11 +497,8 @@ pub unsafe extern "C" fn naml_map_print_float_values(map: *const NamlMap) {
             if (*entry).occupied {
                 if !first { print!(", "); }
                 first = false;
-                let key_ptr = (*entry).key as *const NamlString;
                 let float_val = f64::from_bits((*entry).value as u64);
-                if !key_ptr.is_null() {
-                    print!("\"{}\": {}", (*key_ptr).as_str(), float_val);
-                }
+                print!("{}: {}", format_key(map, (*entry).key), float_val);
             }
         }
         print!("}}");
@@ -497,11 +519,8 @@ pub unsafe extern "C" fn naml_map_print_bool_values(map: *const NamlMap) {
             if (*entry).occupied {
                 if !first { print!(", "); }
                 first = false;
-                let key_ptr = (*entry).key as *const NamlString;
                 let bool_str = if (*entry).value != 0 { "true" } else { "false" };
-                if !key_ptr.is_null() {
-                    print!("\"{}\": {}", (*key_ptr).as_str(), bool_str);
-                }
+                print!("{}: {}", format_key(map, (*entry).key), bool_str);
             }
         }
         print!("}}");
@@ -536,7 +555,7 @@ unsafe fn resize_map(map: *mut NamlMap) {
 
 unsafe fn rehash_entry(map: *mut NamlMap, key: i64, value: i64) {
     unsafe {
-        let hash = hash_string(key as *const NamlString);
+        let hash = map_key_hash(map, key);
         let mut idx = (hash as usize) % (*map).capacity;
         loop {
             let entry = (*map).entries.add(idx);
@@ -0,0 +1,189 @@
+///
+/// NamlPriorityQueue - Min-Heap
+///
+/// A `priority_queue<T>` hands out its smallest element first. It is a
+/// binary heap (Rust `BinaryHeap` over reversed elements), so push and
+/// pop_min are O(log n) and peek_min is O(1).
+///
+/// Elements are ordered like set elements (see `set.rs`): strings by
+/// content, ints and bools by value, floats numerically. Floats use a
+/// total order, so NaN is accepted and comes out after every number.
+/// Equal elements come out in no particular order.
+///
+/// The queue holds a reference to each string element; pop_min hands that
+/// reference to the caller, peek_min lends it.
+///
+/// Operations: new, push, pop_min, peek_min, len, incref, decref.
+///
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Element, HeapHeader, HeapTag};
+
+/// A heap-allocated min-priority queue
+#[repr(C)]
+pub struct NamlPriorityQueue {
+    pub header: HeapHeader,
+    pub kind: u8,
+    pub heap: BinaryHeap<Reverse<Element>>,
+}
+
+impl Drop for NamlPriorityQueue {
+    fn drop(&mut self) {
+        for Reverse(item) in std::mem::take(&mut self.heap) {
+            unsafe { item.release() };
+        }
+    }
+}
+
+/// Create an empty queue whose elements are of the given `MAP_KEY_*` kind
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_priority_queue_new(kind: i64) -> *mut NamlPriorityQueue {
+    Box::into_raw(Box::new(NamlPriorityQueue {
+        header: HeapHeader::new(HeapTag::PriorityQueue),
+        kind: kind as u8,
+        heap: BinaryHeap::new(),
+    }))
+}
+
+/// # Safety
+/// The caller must ensure `pq` is a valid pointer to a NamlPriorityQueue or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_priority_queue_push(pq: *mut NamlPriorityQueue, value: i64) {
+    if pq.is_null() {
+        return;
+    }
+    unsafe {
+        let element = Element::new((*pq).kind, value);
+        element.retain();
+        (*pq).heap.push(Reverse(element));
+    }
+}
+
+/// Remove and return the smallest element; `found_flag` is 0 if the queue is empty
+///
+/// # Safety
+/// The caller must ensure `pq` is a valid pointer to a NamlPriorityQueue or
+/// null and `found_flag` is a valid pointer to an i64 or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_priority_queue_pop_min(pq: *mut NamlPriorityQueue, found_flag: *mut i64) -> i64 {
+    unsafe {
+        let popped = if pq.is_null() { None } else { (*pq).heap.pop() };
+        if !found_flag.is_null() {
+            *found_flag = popped.is_some() as i64;
+        }
+        popped.map_or(0, |Reverse(e)| e.bits)
+    }
+}
+
+/// The smallest element without removing it; `found_flag` is 0 if the queue is empty
+///
+/// # Safety
+/// The caller must ensure `pq` is a valid pointer to a NamlPriorityQueue or
+/// null and `found_flag` is a valid pointer to an i64 or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_priority_queue_peek_min(pq: *const NamlPriorityQueue, found_flag: *mut i64) -> i64 {
+    unsafe {
+        let top = if pq.is_null() { None } else { (*pq).heap.peek() };
+        if !found_flag.is_null() {
+            *found_flag = top.is_some() as i64;
+        }
+        top.map_or(0, |Reverse(e)| e.bits)
+    }
+}
+
+/// # Safety
+/// The caller must ensure `pq` is a valid pointer to a NamlPriorityQueue or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_priority_queue_len(pq: *const NamlPriorityQueue) -> i64 {
+    if pq.is_null() {
+        return 0;
+    }
+    unsafe { (*pq).heap.len() as i64 }
+}
+
+/// # Safety
+/// The caller must ensure `pq` is a valid pointer to a NamlPriorityQueue or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_priority_queue_incref(pq: *mut NamlPriorityQueue) {
+    if !pq.is_null() {
+        unsafe { (*pq).header.incref(); }
+    }
+}
+
+/// Decrement reference count and free the queue, releasing its string elements
+///
+/// # Safety
+/// The caller must ensure `pq` is a valid pointer to a NamlPriorityQueue or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_priority_queue_decref(pq: *mut NamlPriorityQueue) {
+    if !pq.is_null() {
+        unsafe {
+            if (*pq).header.decref() {
+                drop(Box::from_raw(pq));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{naml_string_decref, naml_string_new, NamlString, MAP_KEY_FLOAT, MAP_KEY_INT, MAP_KEY_STRING};
+
+    #[test]
+    fn test_pops_in_ascending_order() {
+        unsafe {
+            let pq = naml_priority_queue_new(MAP_KEY_INT as i64);
+            for v in [7, -2, 9, 3, -2] {
+                naml_priority_queue_push(pq, v);
+            }
+            assert_eq!(naml_priority_queue_len(pq), 5);
+            let mut found = 0;
+            assert_eq!(naml_priority_queue_peek_min(pq, &mut found), -2);
+            let mut out = Vec::new();
+            loop {
+                let v = naml_priority_queue_pop_min(pq, &mut found);
+                if found == 0 {
+                    break;
+                }
+                out.push(v);
+            }
+            assert_eq!(out, [-2, -2, 3, 7, 9]);
+            naml_priority_queue_decref(pq);
+        }
+    }
+
+    #[test]
+    fn test_float_and_string_queues() {
+        unsafe {
+            let pq = naml_priority_queue_new(MAP_KEY_FLOAT as i64);
+            for v in [f64::NAN, 1.5, -0.5] {
+                naml_priority_queue_push(pq, v.to_bits() as i64);
+            }
+            let mut found = 0;
+            assert_eq!(f64::from_bits(naml_priority_queue_pop_min(pq, &mut found) as u64), -0.5);
+            assert_eq!(f64::from_bits(naml_priority_queue_pop_min(pq, &mut found) as u64), 1.5);
+            assert!(f64::from_bits(naml_priority_queue_pop_min(pq, &mut found) as u64).is_nan());
+            naml_priority_queue_decref(pq);
+
+            let pq = naml_priority_queue_new(MAP_KEY_STRING as i64);
+            let b = naml_string_new("b".as_ptr(), 1);
+            let a = naml_string_new("a".as_ptr(), 1);
+            naml_priority_queue_push(pq, b as i64);
+            naml_priority_queue_push(pq, a as i64);
+            assert_eq!((*a).header.refcount(), 2);
+            let first = naml_priority_queue_pop_min(pq, &mut found) as *mut NamlString;
+            assert_eq!((*first).as_str(), "a");
+            naml_string_decref(first);
+            naml_priority_queue_decref(pq);
+            assert_eq!((*a).header.refcount(), 1);
+            assert_eq!((*b).header.refcount(), 1);
+        }
+    }
+}
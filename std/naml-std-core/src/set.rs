@@ -0,0 +1,362 @@
+///
+/// NamlSet - Sorted Set
+///
+/// A `set<T>` holds distinct values in ascending order, backed by a Rust
+/// `BTreeSet`, so membership tests and inserts are O(log n) instead of the
+/// hashing plus key/value bookkeeping of a `map<T, bool>`.
+///
+/// Each set records the kind of its elements with the map key kinds
+/// (`MAP_KEY_*`), which decides ordering and equality:
+///
+/// - string elements compare by content (bytewise); the set holds a
+///   reference to each element
+/// - int and bool elements compare by value
+/// - float elements compare numerically with -0.0 folded into 0.0; NaN has
+///   no place in an ordered set, so adding one panics and looking one up
+///   finds nothing
+///
+/// Operations: new, add, contains, remove, len, union, intersection,
+/// difference, to_array, incref, decref.
+///
+
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use crate::{
+    naml_array_new, naml_array_push, naml_string_decref, normalize_float_key, HeapHeader, HeapTag,
+    NamlArray, NamlString, MAP_KEY_FLOAT, MAP_KEY_STRING,
+};
+
+/// An element of a set or priority queue: the value's i64 word plus the
+/// kind that says how to compare it
+#[derive(Clone, Copy, Debug)]
+pub struct Element {
+    pub kind: u8,
+    pub bits: i64,
+}
+
+impl Element {
+    pub fn new(kind: u8, bits: i64) -> Self {
+        Self { kind, bits }
+    }
+
+    /// Take a reference to a string element; other kinds are plain values
+    ///
+    /// # Safety
+    /// A string element's bits must point to a live NamlString.
+    pub unsafe fn retain(self) {
+        if self.kind == MAP_KEY_STRING && self.bits != 0 {
+            unsafe { (*(self.bits as *mut NamlString)).header.incref() };
+        }
+    }
+
+    /// Drop the reference taken by `retain`
+    ///
+    /// # Safety
+    /// A string element's bits must point to a live NamlString.
+    pub unsafe fn release(self) {
+        if self.kind == MAP_KEY_STRING && self.bits != 0 {
+            unsafe { naml_string_decref(self.bits as *mut NamlString) };
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        if self.bits == 0 {
+            return &[];
+        }
+        let s = self.bits as *const NamlString;
+        unsafe { std::slice::from_raw_parts((*s).as_ptr(), (*s).len) }
+    }
+}
+
+impl Ord for Element {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.kind {
+            MAP_KEY_STRING => self.as_bytes().cmp(other.as_bytes()),
+            MAP_KEY_FLOAT => f64::from_bits(self.bits as u64).total_cmp(&f64::from_bits(other.bits as u64)),
+            _ => self.bits.cmp(&other.bits),
+        }
+    }
+}
+
+impl PartialOrd for Element {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Element {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Element {}
+
+/// A heap-allocated sorted set
+#[repr(C)]
+pub struct NamlSet {
+    pub header: HeapHeader,
+    pub kind: u8,
+    pub items: BTreeSet<Element>,
+}
+
+impl Drop for NamlSet {
+    fn drop(&mut self) {
+        for item in std::mem::take(&mut self.items) {
+            unsafe { item.release() };
+        }
+    }
+}
+
+impl NamlSet {
+    /// The stored form of `value`, or `None` if no element can have it
+    fn lookup(&self, value: i64) -> Option<Element> {
+        if self.kind == MAP_KEY_FLOAT {
+            normalize_float_key(value).map(|bits| Element::new(self.kind, bits))
+        } else {
+            Some(Element::new(self.kind, value))
+        }
+    }
+}
+
+fn alloc_set(kind: u8, items: BTreeSet<Element>) -> *mut NamlSet {
+    for item in &items {
+        unsafe { item.retain() };
+    }
+    Box::into_raw(Box::new(NamlSet {
+        header: HeapHeader::new(HeapTag::Set),
+        kind,
+        items,
+    }))
+}
+
+/// Create an empty set whose elements are of the given `MAP_KEY_*` kind
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_set_new(kind: i64) -> *mut NamlSet {
+    alloc_set(kind as u8, BTreeSet::new())
+}
+
+/// Add `value`; returns 1 if it was not in the set yet
+///
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_add(set: *mut NamlSet, value: i64) -> i64 {
+    if set.is_null() {
+        return 0;
+    }
+    unsafe {
+        let Some(element) = (*set).lookup(value) else {
+            eprintln!("panic: NaN cannot be added to a set");
+            std::process::abort();
+        };
+        if (*set).items.contains(&element) {
+            return 0;
+        }
+        element.retain();
+        (*set).items.insert(element);
+        1
+    }
+}
+
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_contains(set: *const NamlSet, value: i64) -> i64 {
+    if set.is_null() {
+        return 0;
+    }
+    unsafe { (*set).lookup(value).is_some_and(|e| (*set).items.contains(&e)) as i64 }
+}
+
+/// Remove `value`; returns 1 if it was in the set
+///
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_remove(set: *mut NamlSet, value: i64) -> i64 {
+    if set.is_null() {
+        return 0;
+    }
+    unsafe {
+        let Some(element) = (*set).lookup(value) else {
+            return 0;
+        };
+        match (*set).items.take(&element) {
+            Some(stored) => {
+                stored.release();
+                1
+            }
+            None => 0,
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_len(set: *const NamlSet) -> i64 {
+    if set.is_null() {
+        return 0;
+    }
+    unsafe { (*set).items.len() as i64 }
+}
+
+/// Build a new set from two sets of the same kind; a null set counts as empty
+unsafe fn combine(
+    a: *const NamlSet,
+    b: *const NamlSet,
+    op: fn(&BTreeSet<Element>, &BTreeSet<Element>) -> BTreeSet<Element>,
+) -> *mut NamlSet {
+    let empty = BTreeSet::new();
+    unsafe {
+        let kind = if !a.is_null() {
+            (*a).kind
+        } else if !b.is_null() {
+            (*b).kind
+        } else {
+            MAP_KEY_STRING
+        };
+        let a_items = if a.is_null() { &empty } else { &(*a).items };
+        let b_items = if b.is_null() { &empty } else { &(*b).items };
+        alloc_set(kind, op(a_items, b_items))
+    }
+}
+
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlSet and `b` is a
+/// valid pointer to a NamlSet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_union(a: *const NamlSet, b: *const NamlSet) -> *mut NamlSet {
+    unsafe { combine(a, b, |a, b| a.union(b).copied().collect()) }
+}
+
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlSet and `b` is a
+/// valid pointer to a NamlSet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_intersection(a: *const NamlSet, b: *const NamlSet) -> *mut NamlSet {
+    unsafe { combine(a, b, |a, b| a.intersection(b).copied().collect()) }
+}
+
+/// Elements of `a` that are not in `b`
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlSet and `b` is a
+/// valid pointer to a NamlSet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_difference(a: *const NamlSet, b: *const NamlSet) -> *mut NamlSet {
+    unsafe { combine(a, b, |a, b| a.difference(b).copied().collect()) }
+}
+
+/// The elements in ascending order; string elements are shared with the array
+///
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_to_array(set: *const NamlSet) -> *mut NamlArray {
+    unsafe {
+        if set.is_null() {
+            return naml_array_new(0);
+        }
+        let arr = naml_array_new((*set).items.len());
+        for item in &(*set).items {
+            item.retain();
+            naml_array_push(arr, item.bits);
+        }
+        arr
+    }
+}
+
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_incref(set: *mut NamlSet) {
+    if !set.is_null() {
+        unsafe { (*set).header.incref(); }
+    }
+}
+
+/// Decrement reference count and free the set, releasing its string elements
+///
+/// # Safety
+/// The caller must ensure `set` is a valid pointer to a NamlSet or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_set_decref(set: *mut NamlSet) {
+    if !set.is_null() {
+        unsafe {
+            if (*set).header.decref() {
+                drop(Box::from_raw(set));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{naml_array_get, naml_string_new, MAP_KEY_INT};
+
+    fn ints(set: *const NamlSet) -> Vec<i64> {
+        unsafe { (*set).items.iter().map(|e| e.bits).collect() }
+    }
+
+    #[test]
+    fn test_int_set_operations() {
+        unsafe {
+            let a = naml_set_new(MAP_KEY_INT as i64);
+            for v in [5, 1, 3, 1, 5] {
+                naml_set_add(a, v);
+            }
+            assert_eq!(ints(a), [1, 3, 5]);
+            assert_eq!(naml_set_contains(a, 3), 1);
+            assert_eq!(naml_set_remove(a, 3), 1);
+            assert_eq!(naml_set_remove(a, 3), 0);
+
+            let b = naml_set_new(MAP_KEY_INT as i64);
+            naml_set_add(b, 5);
+            naml_set_add(b, 9);
+            let union = naml_set_union(a, b);
+            let both = naml_set_intersection(a, b);
+            let only_a = naml_set_difference(a, b);
+            assert_eq!(ints(union), [1, 5, 9]);
+            assert_eq!(ints(both), [5]);
+            assert_eq!(ints(only_a), [1]);
+            assert_eq!((*union).header.tag, HeapTag::Set);
+            for s in [a, b, union, both, only_a] {
+                naml_set_decref(s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_float_and_string_sets() {
+        unsafe {
+            let floats = naml_set_new(MAP_KEY_FLOAT as i64);
+            naml_set_add(floats, 0.0f64.to_bits() as i64);
+            assert_eq!(naml_set_add(floats, (-0.0f64).to_bits() as i64), 0);
+            assert_eq!(naml_set_contains(floats, f64::NAN.to_bits() as i64), 0);
+            naml_set_add(floats, (-2.5f64).to_bits() as i64);
+            let arr = naml_set_to_array(floats);
+            assert_eq!(f64::from_bits(naml_array_get(arr, 0) as u64), -2.5);
+            naml_set_decref(floats);
+
+            let strings = naml_set_new(MAP_KEY_STRING as i64);
+            let pear = naml_string_new("pear".as_ptr(), 4);
+            let apple = naml_string_new("apple".as_ptr(), 5);
+            let apple2 = naml_string_new("apple".as_ptr(), 5);
+            naml_set_add(strings, pear as i64);
+            naml_set_add(strings, apple as i64);
+            assert_eq!(naml_set_add(strings, apple2 as i64), 0);
+            assert_eq!((*apple).header.refcount(), 2);
+            assert_eq!((*apple2).header.refcount(), 1);
+            let arr = naml_set_to_array(strings);
+            assert_eq!((*(naml_array_get(arr, 0) as *const NamlString)).as_str(), "apple");
+            assert_eq!(naml_set_remove(strings, apple2 as i64), 1);
+            assert_eq!((*apple).header.refcount(), 2);
+            naml_set_decref(strings);
+            assert_eq!((*pear).header.refcount(), 2);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//!
+//! SIMD Kernels for Hot Runtime Primitives
+//!
+//! Byte and word scanning routines shared by the string, array, bytes and
+//! encoding runtime functions. Each kernel picks the widest implementation
+//! available on the target:
+//! - x86_64: SSE2 compares (baseline), SSSE3 shuffles detected at runtime
+//! - aarch64: NEON (baseline)
+//! - anything else: a scalar fallback with identical results
+//!
+//! Substring search delegates to `memchr::memmem`, which already dispatches
+//! to AVX2/SSE2/NEON internally. Both the JIT and the static `naml-runtime`
+//! library link these crates, so compiled programs get the same kernels.
+//!
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Compare two byte slices for equality
+#[inline]
+pub fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    if a.as_ptr() == b.as_ptr() {
+        return true;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        return unsafe { bytes_eq_sse2(a, b) };
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        return unsafe { bytes_eq_neon(a, b) };
+    }
+
+    #[allow(unreachable_code)]
+    {
+        a == b
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn bytes_eq_sse2(a: &[u8], b: &[u8]) -> bool {
+    let len = a.len();
+    let mut i = 0;
+    unsafe {
+        while i + 16 <= len {
+            let va = _mm_loadu_si128(a.as_ptr().add(i) as *const __m128i);
+            let vb = _mm_loadu_si128(b.as_ptr().add(i) as *const __m128i);
+            if _mm_movemask_epi8(_mm_cmpeq_epi8(va, vb)) != 0xFFFF {
+                return false;
+            }
+            i += 16;
+        }
+    }
+    a[i..] == b[i..]
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn bytes_eq_neon(a: &[u8], b: &[u8]) -> bool {
+    let len = a.len();
+    let mut i = 0;
+    unsafe {
+        while i + 16 <= len {
+            let va = vld1q_u8(a.as_ptr().add(i));
+            let vb = vld1q_u8(b.as_ptr().add(i));
+            if vminvq_u8(vceqq_u8(va, vb)) != 0xFF {
+                return false;
+            }
+            i += 16;
+        }
+    }
+    a[i..] == b[i..]
+}
+
+/// Find the first occurrence of `needle` in `haystack`
+#[inline]
+pub fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle.len() {
+        0 => Some(0),
+        1 => memchr::memchr(needle[0], haystack),
+        _ => memchr::memmem::find(haystack, needle),
+    }
+}
+
+/// Find the first index of `value` in a slice of words
+#[inline]
+pub fn position_i64(values: &[i64], value: i64) -> Option<usize> {
+    // Wide chunks reduce to a single branch and auto-vectorize into packed
+    // compares; the exact lane is only located once a chunk matches
+    let mut chunks = values.chunks_exact(8);
+    let mut base = 0;
+    for chunk in &mut chunks {
+        if chunk.iter().fold(false, |hit, &x| hit | (x == value)) {
+            return chunk.iter().position(|&x| x == value).map(|i| base + i);
+        }
+        base += 8;
+    }
+    chunks
+        .remainder()
+        .iter()
+        .position(|&x| x == value)
+        .map(|i| base + i)
+}
+
+/// Find the last index of `value` in a slice of words
+#[inline]
+pub fn rposition_i64(values: &[i64], value: i64) -> Option<usize> {
+    let mut chunks = values.rchunks_exact(8);
+    let mut end = values.len();
+    for chunk in &mut chunks {
+        end -= 8;
+        if chunk.iter().fold(false, |hit, &x| hit | (x == value)) {
+            return chunk.iter().rposition(|&x| x == value).map(|i| end + i);
+        }
+    }
+    chunks.remainder().iter().rposition(|&x| x == value)
+}
+
+/// Encode `data` as lowercase hex into `out`, which must hold `2 * data.len()` bytes
+pub fn hex_encode(data: &[u8], out: &mut [u8]) {
+    assert!(out.len() >= data.len() * 2, "hex output buffer too small");
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("ssse3") {
+            return unsafe { hex_encode_ssse3(data, out) };
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        return unsafe { hex_encode_neon(data, out) };
+    }
+
+    #[allow(unreachable_code)]
+    hex_encode_scalar(data, out)
+}
+
+#[inline]
+fn hex_encode_scalar(data: &[u8], out: &mut [u8]) {
+    for (pair, &byte) in out.chunks_exact_mut(2).zip(data) {
+        pair[0] = HEX_DIGITS[(byte >> 4) as usize];
+        pair[1] = HEX_DIGITS[(byte & 0x0f) as usize];
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "ssse3")]
+unsafe fn hex_encode_ssse3(data: &[u8], out: &mut [u8]) {
+    let len = data.len();
+    let mut i = 0;
+    unsafe {
+        let digits = _mm_loadu_si128(HEX_DIGITS.as_ptr() as *const __m128i);
+        let low_nibble = _mm_set1_epi8(0x0f);
+        while i + 16 <= len {
+            let chunk = _mm_loadu_si128(data.as_ptr().add(i) as *const __m128i);
+            let hi = _mm_and_si128(_mm_srli_epi16(chunk, 4), low_nibble);
+            let lo = _mm_and_si128(chunk, low_nibble);
+            let hi_digits = _mm_shuffle_epi8(digits, hi);
+            let lo_digits = _mm_shuffle_epi8(digits, lo);
+            let dst = out.as_mut_ptr().add(i * 2);
+            _mm_storeu_si128(dst as *mut __m128i, _mm_unpacklo_epi8(hi_digits, lo_digits));
+            _mm_storeu_si128(dst.add(16) as *mut __m128i, _mm_unpackhi_epi8(hi_digits, lo_digits));
+            i += 16;
+        }
+    }
+    hex_encode_scalar(&data[i..], &mut out[i * 2..]);
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn hex_encode_neon(data: &[u8], out: &mut [u8]) {
+    let len = data.len();
+    let mut i = 0;
+    unsafe {
+        let digits = vld1q_u8(HEX_DIGITS.as_ptr());
+        let low_nibble = vdupq_n_u8(0x0f);
+        while i + 16 <= len {
+            let chunk = vld1q_u8(data.as_ptr().add(i));
+            let hi_digits = vqtbl1q_u8(digits, vshrq_n_u8(chunk, 4));
+            let lo_digits = vqtbl1q_u8(digits, vandq_u8(chunk, low_nibble));
+            vst2q_u8(out.as_mut_ptr().add(i * 2), uint8x16x2_t(hi_digits, lo_digits));
+            i += 16;
+        }
+    }
+    hex_encode_scalar(&data[i..], &mut out[i * 2..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 37 + 11) as u8).collect()
+    }
+
+    #[test]
+    fn test_bytes_eq_matches_scalar() {
+        for len in [0, 1, 15, 16, 17, 31, 32, 100] {
+            let a = sample(len);
+            let mut b = a.clone();
+            assert!(bytes_eq(&a, &b));
+            for pos in 0..len {
+                b[pos] ^= 1;
+                assert!(!bytes_eq(&a, &b), "len {} pos {}", len, pos);
+                b[pos] ^= 1;
+            }
+        }
+        assert!(!bytes_eq(b"abc", b"abcd"));
+    }
+
+    #[test]
+    fn test_find_bytes() {
+        assert_eq!(find_bytes(b"hello world", b""), Some(0));
+        assert_eq!(find_bytes(b"hello world", b"o"), Some(4));
+        assert_eq!(find_bytes(b"hello world", b"world"), Some(6));
+        assert_eq!(find_bytes(b"hello", b"hello world"), None);
+        assert_eq!(find_bytes(b"aaaaab", b"ab"), Some(4));
+    }
+
+    #[test]
+    fn test_position_i64() {
+        let values: Vec<i64> = (0..37).collect();
+        for v in 0..37 {
+            assert_eq!(position_i64(&values, v), Some(v as usize));
+            assert_eq!(rposition_i64(&values, v), Some(v as usize));
+        }
+        assert_eq!(position_i64(&values, 99), None);
+        assert_eq!(rposition_i64(&values, -1), None);
+        assert_eq!(position_i64(&[], 0), None);
+
+        let repeated = [5i64; 20];
+        assert_eq!(position_i64(&repeated, 5), Some(0));
+        assert_eq!(rposition_i64(&repeated, 5), Some(19));
+    }
+
+    #[test]
+    fn test_hex_encode_matches_scalar() {
+        for len in [0, 1, 15, 16, 17, 33, 256] {
+            let data = sample(len);
+            let mut fast = vec![0u8; len * 2];
+            let mut slow = vec![0u8; len * 2];
+            hex_encode(&data, &mut fast);
+            hex_encode_scalar(&data, &mut slow);
+            assert_eq!(fast, slow, "len {}", len);
+        }
+        let mut out = [0u8; 6];
+        hex_encode(&[0x00, 0xab, 0xff], &mut out);
+        assert_eq!(&out, b"00abff");
+    }
+}
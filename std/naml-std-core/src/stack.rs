@@ -1,4 +1,4 @@
-use crate::array::{naml_array_new, naml_array_push};
+use crate::array::{NamlArray, naml_array_incref, naml_array_new, naml_array_push};
 ///
 /// naml-std-core/stack.rs - Stack Frame Type and Shadow Stack Runtime
 ///
@@ -88,6 +88,19 @@ pub extern "C" fn naml_stack_capture() -> *mut u8 {
     }
 }
 
+/// Return a captured [stack_frame] array as a value the caller owns
+/// (backs `exception.stack_frames()`; null yields an empty array)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_stack_frames(stack_ptr: *mut u8) -> *mut NamlArray {
+    unsafe {
+        if stack_ptr.is_null() {
+            return naml_array_new(0);
+        }
+        naml_array_incref(stack_ptr as *mut NamlArray);
+        stack_ptr as *mut NamlArray
+    }
+}
+
 /// Clear the stack (called on thread init or after unhandled exception)
 #[unsafe(no_mangle)]
 pub extern "C" fn naml_stack_clear() {
@@ -100,8 +113,6 @@ pub extern "C" fn naml_stack_clear() {
 /// Takes pointer to [stack_frame] array, returns NamlString pointer
 #[unsafe(no_mangle)]
 pub extern "C" fn naml_stack_format(stack_ptr: *mut u8) -> *mut NamlString {
-    use crate::array::NamlArray;
-
     if stack_ptr.is_null() {
         let empty = b"Stack trace: (empty)\n";
         return unsafe { crate::value::naml_string_new(empty.as_ptr(), empty.len()) };
@@ -0,0 +1,166 @@
+///
+/// NamlStringBuilder - Growable String Buffer
+///
+/// Repeated `+` on strings copies both operands every time, so building a
+/// string piece by piece is quadratic. A string builder keeps one growable
+/// buffer and only copies it once, in `to_string`.
+///
+/// The builder is a reference-counted heap object tagged
+/// `HeapTag::StringBuilder`; the buffer itself is a Rust `Vec` owned by
+/// the object and dropped with it.
+///
+/// Operations: new, append, append_int, len, to_string, incref, decref.
+///
+
+use crate::{naml_string_new, HeapHeader, HeapTag, NamlString};
+
+/// A heap-allocated string builder
+#[repr(C)]
+pub struct NamlStringBuilder {
+    pub header: HeapHeader,
+    pub buf: Vec<u8>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_string_builder_new() -> *mut NamlStringBuilder {
+    Box::into_raw(Box::new(NamlStringBuilder {
+        header: HeapHeader::new(HeapTag::StringBuilder),
+        buf: Vec::new(),
+    }))
+}
+
+/// Append the bytes of `s`; a null string appends nothing
+///
+/// # Safety
+/// The caller must ensure `sb` is a valid pointer to a NamlStringBuilder or
+/// null and `s` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_builder_append(sb: *mut NamlStringBuilder, s: *const NamlString) {
+    if sb.is_null() || s.is_null() {
+        return;
+    }
+    unsafe {
+        let bytes = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+        (*sb).buf.extend_from_slice(bytes);
+    }
+}
+
+/// Append the decimal form of `n` without allocating an intermediate string
+///
+/// # Safety
+/// The caller must ensure `sb` is a valid pointer to a NamlStringBuilder or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_builder_append_int(sb: *mut NamlStringBuilder, n: i64) {
+    if sb.is_null() {
+        return;
+    }
+    let mut digits = [0u8; 20];
+    let mut pos = digits.len();
+    let mut value = n.unsigned_abs();
+    loop {
+        pos -= 1;
+        digits[pos] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    unsafe {
+        if n < 0 {
+            (*sb).buf.push(b'-');
+        }
+        (*sb).buf.extend_from_slice(&digits[pos..]);
+    }
+}
+
+/// Length of the text built so far, in bytes
+///
+/// # Safety
+/// The caller must ensure `sb` is a valid pointer to a NamlStringBuilder or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_builder_len(sb: *const NamlStringBuilder) -> i64 {
+    if sb.is_null() {
+        return 0;
+    }
+    unsafe { (*sb).buf.len() as i64 }
+}
+
+/// Copy the text built so far into a new string; the builder stays usable
+///
+/// # Safety
+/// The caller must ensure `sb` is a valid pointer to a NamlStringBuilder or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_builder_to_string(sb: *const NamlStringBuilder) -> *mut NamlString {
+    unsafe {
+        if sb.is_null() {
+            return naml_string_new(std::ptr::null(), 0);
+        }
+        naml_string_new((*sb).buf.as_ptr(), (*sb).buf.len())
+    }
+}
+
+/// # Safety
+/// The caller must ensure `sb` is a valid pointer to a NamlStringBuilder or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_builder_incref(sb: *mut NamlStringBuilder) {
+    if !sb.is_null() {
+        unsafe { (*sb).header.incref(); }
+    }
+}
+
+/// Decrement reference count and free the builder and its buffer if zero
+///
+/// # Safety
+/// The caller must ensure `sb` is a valid pointer to a NamlStringBuilder or
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_builder_decref(sb: *mut NamlStringBuilder) {
+    if !sb.is_null() {
+        unsafe {
+            if (*sb).header.decref() {
+                drop(Box::from_raw(sb));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_to_string() {
+        unsafe {
+            let sb = naml_string_builder_new();
+            assert_eq!((*sb).header.tag, HeapTag::StringBuilder);
+            let s = naml_string_new("total: ".as_ptr(), 7);
+            naml_string_builder_append(sb, s);
+            naml_string_builder_append_int(sb, -1205);
+            naml_string_builder_append_int(sb, 0);
+            naml_string_builder_append_int(sb, i64::MIN);
+            assert_eq!(naml_string_builder_len(sb), 7 + 5 + 1 + 20);
+
+            let out = naml_string_builder_to_string(sb);
+            assert_eq!((*out).as_str(), "total: -12050-9223372036854775808");
+            naml_string_builder_append(sb, s);
+            assert_eq!((*out).as_str(), "total: -12050-9223372036854775808");
+            naml_string_builder_decref(sb);
+        }
+    }
+
+    #[test]
+    fn test_refcount() {
+        unsafe {
+            let sb = naml_string_builder_new();
+            naml_string_builder_incref(sb);
+            assert_eq!((*sb).header.refcount(), 2);
+            naml_string_builder_decref(sb);
+            assert_eq!((*sb).header.refcount(), 1);
+            naml_string_builder_decref(sb);
+        }
+    }
+}
@@ -34,30 +34,59 @@ pub enum HeapTag {
     AtomicInt = 10,
     AtomicUint = 11,
     AtomicBool = 12,
+    Secret = 13,
+    StringBuilder = 14,
+    Set = 15,
+    PriorityQueue = 16,
+    Deque = 17,
+    Iterator = 18,
+    Waitgroup = 19,
 }
 
+/// Refcount marking an object that is never freed, such as a string literal
+/// emitted into a module data section. Reference counting skips these.
+pub const IMMORTAL_REFCOUNT: usize = 1 << 62;
+
+/// Strings up to this many bytes are stored inline: the bytes and their NUL
+/// terminator fit in `NamlString::data` itself, so a short key or field name
+/// is one fixed-size object recycled through the thread-local arena
+pub const INLINE_STRING_CAPACITY: usize = 23;
+
 /// Header for all heap-allocated objects
 #[repr(C)]
 pub struct HeapHeader {
     pub refcount: AtomicUsize,
     pub tag: HeapTag,
-    pub _pad: [u8; 7],
+    /// `HEAP_FLAG_*` bits describing how the object is stored
+    pub flags: u8,
+    pub _pad: [u8; 6],
 }
 
+/// Set on a string or bytes object that borrows its bytes from a parent
+/// object instead of owning them
+pub const HEAP_FLAG_VIEW: u8 = 1;
+
 impl HeapHeader {
     pub fn new(tag: HeapTag) -> Self {
         Self {
             refcount: AtomicUsize::new(1),
             tag,
-            _pad: [0; 7],
+            flags: 0,
+            _pad: [0; 6],
         }
     }
 
     pub fn incref(&self) {
+        if self.is_immortal() {
+            return;
+        }
         self.refcount.fetch_add(1, Ordering::Relaxed);
     }
 
     pub fn decref(&self) -> bool {
+        if self.is_immortal() {
+            return false;
+        }
         if self.refcount.fetch_sub(1, Ordering::Release) == 1 {
             std::sync::atomic::fence(Ordering::Acquire);
             true
@@ -69,6 +98,14 @@ impl HeapHeader {
     pub fn refcount(&self) -> usize {
         self.refcount.load(Ordering::Relaxed)
     }
+
+    pub fn is_immortal(&self) -> bool {
+        self.refcount() >= IMMORTAL_REFCOUNT
+    }
+
+    pub fn is_view(&self) -> bool {
+        self.flags & HEAP_FLAG_VIEW != 0
+    }
 }
 
 /// A heap-allocated string
@@ -76,16 +113,49 @@ impl HeapHeader {
 pub struct NamlString {
     pub header: HeapHeader,
     pub len: usize,
-    pub data: [u8; 0],
+    /// The bytes followed by a NUL. Strings longer than
+    /// `INLINE_STRING_CAPACITY` are allocated with room past the end of the
+    /// struct and continue there. A view holds a `StringView` here instead,
+    /// so read the bytes through `as_ptr`/`as_bytes`.
+    pub data: [u8; INLINE_STRING_CAPACITY + 1],
+}
+
+/// What a string view stores in `NamlString::data`: the owning string,
+/// which it holds a reference to, and where its bytes start in it
+#[repr(C)]
+struct StringView {
+    parent: *mut NamlString,
+    ptr: *const u8,
 }
 
+// Compiled code reads `len` and `data` at fixed offsets
+const _: () = {
+    assert!(std::mem::offset_of!(NamlString, len) == 16);
+    assert!(std::mem::offset_of!(NamlString, data) == 24);
+    assert!(std::mem::size_of::<NamlString>() == 48);
+};
+
 impl NamlString {
-    pub fn as_str(&self) -> &str {
-        unsafe {
-            let slice = std::slice::from_raw_parts(self.data.as_ptr(), self.len);
-            std::str::from_utf8_unchecked(slice)
+    fn view(&self) -> &StringView {
+        unsafe { &*(self.data.as_ptr() as *const StringView) }
+    }
+
+    /// Start of the bytes. Only owned strings are NUL-terminated.
+    pub fn as_ptr(&self) -> *const u8 {
+        if self.header.is_view() {
+            self.view().ptr
+        } else {
+            self.data.as_ptr()
         }
     }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+
+    pub fn as_str(&self) -> &str {
+        unsafe { std::str::from_utf8_unchecked(self.as_bytes()) }
+    }
 }
 
 /// A heap-allocated struct instance
@@ -97,16 +167,24 @@ pub struct NamlStruct {
     pub fields: [i64; 0],
 }
 
+/// Layout of a string too long to be stored inline
+#[inline(always)]
+fn outline_string_layout(len: usize) -> Layout {
+    Layout::from_size_align(
+        crate::arena::string_alloc_size(len),
+        std::mem::align_of::<NamlString>(),
+    ).unwrap()
+}
+
 /// Allocate a new string on the heap
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_string_new(data: *const u8, len: usize) -> *mut NamlString {
     unsafe {
-        let layout = Layout::from_size_align(
-            std::mem::size_of::<NamlString>() + len,
-            std::mem::align_of::<NamlString>(),
-        ).unwrap();
-
-        let ptr = alloc(layout) as *mut NamlString;
+        let ptr = if len <= INLINE_STRING_CAPACITY {
+            crate::arena::arena_alloc(std::mem::size_of::<NamlString>()) as *mut NamlString
+        } else {
+            alloc(outline_string_layout(len)) as *mut NamlString
+        };
         if ptr.is_null() {
             panic!("Failed to allocate string");
         }
@@ -114,9 +192,11 @@ pub unsafe extern "C" fn naml_string_new(data: *const u8, len: usize) -> *mut Na
         (*ptr).header = HeapHeader::new(HeapTag::String);
         (*ptr).len = len;
 
+        let bytes = std::ptr::addr_of_mut!((*ptr).data) as *mut u8;
         if !data.is_null() && len > 0 {
-            std::ptr::copy_nonoverlapping(data, (*ptr).data.as_mut_ptr(), len);
+            std::ptr::copy_nonoverlapping(data, bytes, len);
         }
+        *bytes.add(len) = 0;
 
         ptr
     }
@@ -137,16 +217,55 @@ pub unsafe extern "C" fn naml_string_decref(s: *mut NamlString) {
         unsafe {
             if (*s).header.decref() {
                 let len = (*s).len;
-                let layout = Layout::from_size_align(
-                    std::mem::size_of::<NamlString>() + len,
-                    std::mem::align_of::<NamlString>(),
-                ).unwrap();
-                dealloc(s as *mut u8, layout);
+                if (*s).header.is_view() {
+                    let parent = (*s).view().parent;
+                    crate::arena::arena_free(s as *mut u8, std::mem::size_of::<NamlString>());
+                    naml_string_decref(parent);
+                } else if len <= INLINE_STRING_CAPACITY {
+                    crate::arena::arena_free(s as *mut u8, std::mem::size_of::<NamlString>());
+                } else {
+                    dealloc(s as *mut u8, outline_string_layout(len));
+                }
             }
         }
     }
 }
 
+/// Get the bytes `start..end` of a string. Results that fit inline are
+/// copied; longer ones are views that reference the string that owns the
+/// bytes, so slicing a view never chains views together. Offsets must
+/// already be clamped so that `start <= end <= len` and both fall on char
+/// boundaries.
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_view(s: *const NamlString, start: usize, end: usize) -> *mut NamlString {
+    unsafe {
+        let len = end - start;
+        let bytes = (*s).as_ptr().add(start);
+        if len <= INLINE_STRING_CAPACITY {
+            return naml_string_new(bytes, len);
+        }
+        if start == 0 && len == (*s).len {
+            naml_string_incref(s as *mut NamlString);
+            return s as *mut NamlString;
+        }
+        let parent = if (*s).header.is_view() { (*s).view().parent } else { s as *mut NamlString };
+        naml_string_incref(parent);
+
+        let view = crate::arena::arena_alloc(std::mem::size_of::<NamlString>()) as *mut NamlString;
+        if view.is_null() {
+            panic!("Failed to allocate string view");
+        }
+        (*view).header = HeapHeader::new(HeapTag::String);
+        (*view).header.flags = HEAP_FLAG_VIEW;
+        (*view).len = len;
+        (std::ptr::addr_of_mut!((*view).data) as *mut StringView).write(StringView { parent, ptr: bytes });
+        view
+    }
+}
+
 /// Get string length
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_string_len(s: *const NamlString) -> i64 {
@@ -157,13 +276,13 @@ pub unsafe extern "C" fn naml_string_len(s: *const NamlString) -> i64 {
     }
 }
 
-/// Get pointer to string data (for printing)
+/// Get pointer to string data (for printing). Views are not NUL-terminated.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_string_data(s: *const NamlString) -> *const u8 {
     if s.is_null() {
         std::ptr::null()
     } else {
-        unsafe { (*s).data.as_ptr() }
+        unsafe { (*s).as_ptr() }
     }
 }
 
@@ -178,10 +297,10 @@ pub unsafe extern "C" fn naml_string_concat(a: *const NamlString, b: *const Naml
         let result = naml_string_new(std::ptr::null(), total_len);
 
         if a_len > 0 {
-            std::ptr::copy_nonoverlapping((*a).data.as_ptr(), (*result).data.as_mut_ptr(), a_len);
+            std::ptr::copy_nonoverlapping((*a).as_ptr(), (*result).data.as_mut_ptr(), a_len);
         }
         if b_len > 0 {
-            std::ptr::copy_nonoverlapping((*b).data.as_ptr(), (*result).data.as_mut_ptr().add(a_len), b_len);
+            std::ptr::copy_nonoverlapping((*b).as_ptr(), (*result).data.as_mut_ptr().add(a_len), b_len);
         }
 
         result
@@ -198,14 +317,14 @@ pub unsafe extern "C" fn naml_string_eq(a: *const NamlString, b: *const NamlStri
         if a.is_null() || b.is_null() {
             return 0;
         }
+        if a == b {
+            return 1;
+        }
         if (*a).len != (*b).len {
             return 0;
         }
 
-        let a_slice = std::slice::from_raw_parts((*a).data.as_ptr(), (*a).len);
-        let b_slice = std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len);
-
-        if a_slice == b_slice { 1 } else { 0 }
+        if crate::simd::bytes_eq((*a).as_bytes(), (*b).as_bytes()) { 1 } else { 0 }
     }
 }
 
@@ -214,8 +333,7 @@ pub unsafe extern "C" fn naml_string_eq(a: *const NamlString, b: *const NamlStri
 pub unsafe extern "C" fn naml_string_print(s: *const NamlString) {
     if !s.is_null() {
         unsafe {
-            let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
-            if let Ok(str_val) = std::str::from_utf8(slice) {
+            if let Ok(str_val) = std::str::from_utf8((*s).as_bytes()) {
                 print!("{}", str_val);
             }
         }
@@ -358,7 +476,8 @@ pub unsafe extern "C" fn naml_string_trim(s: *const NamlString) -> *mut NamlStri
         }
         let str_val = (*s).as_str();
         let trimmed = str_val.trim();
-        naml_string_new(trimmed.as_ptr(), trimmed.len())
+        let start = trimmed.as_ptr().offset_from(str_val.as_ptr()) as usize;
+        naml_string_view(s, start, start + trimmed.len())
     }
 }
 
@@ -562,4 +681,69 @@ mod tests {
             naml_string_decref(c);
         }
     }
+
+    #[test]
+    fn test_short_strings_are_inline() {
+        unsafe {
+            let a = naml_string_new(b"key".as_ptr(), 3);
+            assert_eq!((&(*a).data)[..4], *b"key\0");
+            naml_string_decref(a);
+
+            let full = "an_inline_field_name_23";
+            let b = naml_string_new(full.as_ptr(), full.len());
+            assert_eq!(a, b);
+            assert_eq!((*b).as_str(), full);
+            assert_eq!((*b).data[INLINE_STRING_CAPACITY], 0);
+            naml_string_decref(b);
+        }
+    }
+
+    #[test]
+    fn test_long_strings_extend_past_struct() {
+        unsafe {
+            let text = "a string longer than the inline capacity";
+            let s = naml_string_new(text.as_ptr(), text.len());
+            assert_eq!((*s).as_str(), text);
+            assert_eq!(*naml_string_data(s).add(text.len()), 0);
+            naml_string_decref(s);
+        }
+    }
+
+    #[test]
+    fn test_string_view_references_owner() {
+        unsafe {
+            let text = "  a string longer than the inline capacity  ";
+            let s = naml_string_new(text.as_ptr(), text.len());
+            let trimmed = naml_string_trim(s);
+            assert!((*trimmed).header.is_view());
+            assert_eq!((*trimmed).as_str(), text.trim());
+            assert_eq!((*s).header.refcount(), 2);
+
+            let nested = naml_string_view(trimmed, 2, (*trimmed).len);
+            assert_eq!((*nested).as_str(), "string longer than the inline capacity");
+            assert_eq!((*s).header.refcount(), 3);
+            let same = naml_string_view(s, 4, text.len() - 2);
+            assert_eq!(naml_string_eq(nested, same), 1);
+            naml_string_decref(same);
+
+            let short = naml_string_view(trimmed, 0, 8);
+            assert!(!(*short).header.is_view());
+            assert_eq!((*short).as_str(), "a string");
+
+            naml_string_decref(short);
+            naml_string_decref(trimmed);
+            naml_string_decref(nested);
+            assert_eq!((*s).header.refcount(), 1);
+            naml_string_decref(s);
+        }
+    }
+
+    #[test]
+    fn test_immortal_refcount_is_untouched() {
+        let header = HeapHeader::new(HeapTag::String);
+        header.refcount.store(IMMORTAL_REFCOUNT, Ordering::Relaxed);
+        header.incref();
+        assert!(!header.decref());
+        assert_eq!(header.refcount(), IMMORTAL_REFCOUNT);
+    }
 }
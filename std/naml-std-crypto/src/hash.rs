@@ -8,10 +8,14 @@
 /// - `naml_crypto_<algo>(data) -> bytes` — raw digest bytes
 /// - `naml_crypto_<algo>_hex(data) -> string` — lowercase hex-encoded digest string
 ///
+/// `sha256_reader` and `sha256_copy` stream SHA-256 over `Read` sources for other
+/// std crates (e.g. fs checksum verification) without loading whole files.
+///
 
 use naml_std_core::bytes::NamlBytes;
 use naml_std_core::value::NamlString;
 use std::alloc::Layout;
+use std::io::{Read, Write};
 
 use md5::Md5;
 use sha1::Sha1;
@@ -50,7 +54,7 @@ fn bytes_as_slice(b: *const NamlBytes) -> &'static [u8] {
         if b.is_null() {
             return &[];
         }
-        std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len)
+        std::slice::from_raw_parts((*b).as_ptr(), (*b).len)
     }
 }
 
@@ -114,6 +118,37 @@ pub unsafe extern "C" fn naml_crypto_sha512_hex(data: *const NamlBytes) -> *mut
     create_string_from(&hex_str)
 }
 
+/// SHA-256 of an in-memory buffer, for Rust callers such as request signing
+pub fn sha256_digest(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Chunk size used when streaming data through a hasher
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// SHA-256 of everything read from `reader`, processed in fixed-size chunks
+pub fn sha256_reader<R: Read>(reader: R) -> std::io::Result<[u8; 32]> {
+    sha256_copy(reader, std::io::sink())
+}
+
+/// Copy `reader` into `writer`, hashing the data with SHA-256 on the way through
+pub fn sha256_copy<R: Read, W: Write>(mut reader: R, mut writer: W) -> std::io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buf[..n]);
+        writer.write_all(&buf[..n])?;
+    }
+    writer.flush()?;
+    Ok(hasher.finalize().into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,7 +159,7 @@ mod tests {
 
     fn read_hex_string(s: *const NamlString) -> String {
         unsafe {
-            let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+            let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
             std::str::from_utf8(slice).unwrap().to_string()
         }
     }
@@ -178,6 +213,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sha256_streaming_matches_oneshot() {
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let expected: [u8; 32] = Sha256::digest(&data).into();
+        assert_eq!(sha256_reader(&data[..]).unwrap(), expected);
+
+        let mut copied = Vec::new();
+        assert_eq!(sha256_copy(&data[..], &mut copied).unwrap(), expected);
+        assert_eq!(copied, data);
+    }
+
     #[test]
     fn test_sha256_raw_length() {
         unsafe {
@@ -2,7 +2,8 @@
 /// std::crypto - HMAC Functions
 ///
 /// Provides HMAC-SHA256 and HMAC-SHA512 message authentication codes
-/// with constant-time verification using the `hmac` crate.
+/// with constant-time verification using the `hmac` crate. Keys may be
+/// `bytes` or a `secret`; both share the `NamlBytes` layout.
 ///
 /// Functions:
 /// - `naml_crypto_hmac_sha256(key, data) -> bytes` — HMAC-SHA256 tag
@@ -56,10 +57,17 @@ fn bytes_as_slice(b: *const NamlBytes) -> &'static [u8] {
         if b.is_null() {
             return &[];
         }
-        std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len)
+        std::slice::from_raw_parts((*b).as_ptr(), (*b).len)
     }
 }
 
+/// HMAC-SHA256 tag of `data` under `key`, for Rust callers such as request signing
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_crypto_hmac_sha256(
     key: *const NamlBytes,
@@ -152,7 +160,7 @@ mod tests {
 
     fn read_hex_string(s: *const NamlString) -> String {
         unsafe {
-            let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+            let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
             std::str::from_utf8(slice).unwrap().to_string()
         }
     }
@@ -42,7 +42,7 @@ fn bytes_as_slice(b: *const NamlBytes) -> &'static [u8] {
         if b.is_null() {
             return &[];
         }
-        std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len)
+        std::slice::from_raw_parts((*b).as_ptr(), (*b).len)
     }
 }
 
@@ -78,8 +78,8 @@ mod tests {
             let salt = make_bytes(b"salt");
             let result1 = naml_crypto_pbkdf2_sha256(password, salt, 1000, 32);
             let result2 = naml_crypto_pbkdf2_sha256(password, salt, 1000, 32);
-            let s1 = std::slice::from_raw_parts((*result1).data.as_ptr(), (*result1).len);
-            let s2 = std::slice::from_raw_parts((*result2).data.as_ptr(), (*result2).len);
+            let s1 = std::slice::from_raw_parts((*result1).as_ptr(), (*result1).len);
+            let s2 = std::slice::from_raw_parts((*result2).as_ptr(), (*result2).len);
             assert_eq!(s1, s2);
         }
     }
@@ -102,8 +102,8 @@ mod tests {
             let salt2 = make_bytes(b"salt2");
             let r1 = naml_crypto_pbkdf2_sha256(password, salt1, 100, 32);
             let r2 = naml_crypto_pbkdf2_sha256(password, salt2, 100, 32);
-            let s1 = std::slice::from_raw_parts((*r1).data.as_ptr(), (*r1).len);
-            let s2 = std::slice::from_raw_parts((*r2).data.as_ptr(), (*r2).len);
+            let s1 = std::slice::from_raw_parts((*r1).as_ptr(), (*r1).len);
+            let s2 = std::slice::from_raw_parts((*r2).as_ptr(), (*r2).len);
             assert_ne!(s1, s2);
         }
     }
@@ -115,8 +115,8 @@ mod tests {
             let salt = make_bytes(b"salt");
             let r1 = naml_crypto_pbkdf2_sha256(password, salt, 1, 32);
             let r2 = naml_crypto_pbkdf2_sha256(password, salt, 2, 32);
-            let s1 = std::slice::from_raw_parts((*r1).data.as_ptr(), (*r1).len);
-            let s2 = std::slice::from_raw_parts((*r2).data.as_ptr(), (*r2).len);
+            let s1 = std::slice::from_raw_parts((*r1).as_ptr(), (*r1).len);
+            let s2 = std::slice::from_raw_parts((*r2).as_ptr(), (*r2).len);
             assert_ne!(s1, s2);
         }
     }
@@ -7,6 +7,8 @@
 /// - **HMAC**: SHA-256 and SHA-512 message authentication with constant-time verify
 /// - **KDF**: PBKDF2-SHA-256 key derivation
 /// - **Random**: Cryptographically secure random byte generation
+/// - **OTP**: HOTP/TOTP one-time passwords for authenticator-app 2FA
+/// - **Secret**: Credential values that never print and are zeroized when released
 ///
 /// All functions operate on `NamlBytes` (raw binary) and `NamlString` (UTF-8 text).
 /// Heap objects are reference-counted and follow naml's ownership model.
@@ -15,9 +17,13 @@
 pub mod hash;
 pub mod hmac_mod;
 pub mod kdf;
+pub mod otp;
 pub mod random;
+pub mod secret;
 
 pub use hash::*;
 pub use hmac_mod::*;
 pub use kdf::*;
+pub use otp::*;
 pub use random::*;
+pub use secret::*;
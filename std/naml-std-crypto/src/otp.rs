@@ -0,0 +1,287 @@
+///
+/// std::crypto::otp - One-Time Passwords
+///
+/// Provides HOTP (RFC 4226) and TOTP (RFC 6238) codes compatible with common
+/// authenticator apps. Codes use HMAC-SHA1 as those apps expect.
+///
+/// Secrets are base32 strings as shown in provisioning URIs and QR codes.
+/// Spaces, lowercase letters, and missing `=` padding are accepted. An invalid
+/// secret is reported via the out parameters so codegen can throw DecodeError.
+///
+/// Functions:
+/// - `naml_crypto_otp_hotp(secret, counter) -> string` — 6-digit counter-based code
+/// - `naml_crypto_otp_totp_now(secret, period, digits) -> string` — current time-based code
+/// - `naml_crypto_otp_totp_verify(code, secret, skew) -> bool` — check a 30-second TOTP code,
+///   allowing `skew` periods of clock drift on either side
+///
+
+use naml_std_core::value::NamlString;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+type HmacSha1 = Hmac<Sha1>;
+
+const DEFAULT_PERIOD: u64 = 30;
+const DEFAULT_DIGITS: u32 = 6;
+const MAX_DIGITS: u32 = 10;
+
+fn string_as_str(s: *const NamlString) -> &'static str {
+    unsafe {
+        if s.is_null() {
+            return "";
+        }
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+        std::str::from_utf8_unchecked(slice)
+    }
+}
+
+fn create_string_from(s: &str) -> *mut NamlString {
+    unsafe {
+        naml_std_core::value::naml_string_new(s.as_ptr(), s.len())
+    }
+}
+
+/// Decode a base32 secret, returning the input position of the first bad character
+fn decode_secret(secret: &str) -> Result<Vec<u8>, usize> {
+    let mut normalized = Vec::with_capacity(secret.len());
+    let mut positions = Vec::with_capacity(secret.len());
+    for (i, c) in secret.bytes().enumerate() {
+        if c == b' ' || c == b'-' || c == b'=' {
+            continue;
+        }
+        normalized.push(c.to_ascii_uppercase());
+        positions.push(i);
+    }
+
+    BASE32_NOPAD.decode(&normalized).map_err(|e| {
+        positions.get(e.position).copied().unwrap_or(secret.len())
+    })
+}
+
+/// Compute an HOTP code for `counter` (RFC 4226 dynamic truncation)
+fn hotp_code(key: &[u8], counter: u64, digits: u32) -> String {
+    let mut mac = HmacSha1::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    let code = binary as u64 % 10u64.pow(digits);
+    format!("{:0width$}", code, width = digits as usize)
+}
+
+/// Compute a TOTP code for the given unix time
+fn totp_code(key: &[u8], unix_secs: u64, period: u64, digits: u32) -> String {
+    hotp_code(key, unix_secs / period, digits)
+}
+
+/// Check `code` against the TOTP window around `unix_secs`
+fn totp_matches(key: &[u8], code: &str, unix_secs: u64, skew: u64) -> bool {
+    let digits = code.len() as u32;
+    if digits == 0 || digits > MAX_DIGITS || !code.bytes().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let step = unix_secs / DEFAULT_PERIOD;
+    let mut matched = false;
+    for counter in step.saturating_sub(skew)..=step.saturating_add(skew) {
+        let expected = hotp_code(key, counter, digits);
+        // Compare every byte so timing does not reveal how much of the code matched
+        let diff = expected
+            .bytes()
+            .zip(code.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        matched |= diff == 0;
+    }
+    matched
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+unsafe fn write_result(out_tag: *mut i32, out_value: *mut i64, result: Result<i64, usize>) {
+    unsafe {
+        match result {
+            Ok(value) => {
+                *out_tag = 0;
+                *out_value = value;
+            }
+            Err(pos) => {
+                *out_tag = 1;
+                *out_value = pos as i64;
+            }
+        }
+    }
+}
+
+/// Generate a 6-digit HOTP code
+/// Returns via out parameters:
+/// tag = 0: success, value = string pointer
+/// tag = 1: error, value = position of invalid secret character
+///
+/// # Safety
+/// The caller must ensure `secret` is a valid pointer to a NamlString,
+/// `out_tag` is a valid pointer to an i32 and `out_value` is a valid pointer to
+/// an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_otp_hotp(
+    secret: *const NamlString,
+    counter: i64,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    let result = decode_secret(string_as_str(secret)).map(|key| {
+        create_string_from(&hotp_code(&key, counter as u64, DEFAULT_DIGITS)) as i64
+    });
+    unsafe { write_result(out_tag, out_value, result) }
+}
+
+/// Generate the TOTP code for the current time.
+/// A non-positive period defaults to 30 seconds; digits are clamped to 1..=10.
+/// Returns via out parameters:
+/// tag = 0: success, value = string pointer
+/// tag = 1: error, value = position of invalid secret character
+///
+/// # Safety
+/// The caller must ensure `secret` is a valid pointer to a NamlString,
+/// `out_tag` is a valid pointer to an i32 and `out_value` is a valid pointer to
+/// an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_otp_totp_now(
+    secret: *const NamlString,
+    period: i64,
+    digits: i64,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    let period = if period > 0 { period as u64 } else { DEFAULT_PERIOD };
+    let digits = digits.clamp(1, MAX_DIGITS as i64) as u32;
+    let result = decode_secret(string_as_str(secret)).map(|key| {
+        create_string_from(&totp_code(&key, unix_now(), period, digits)) as i64
+    });
+    unsafe { write_result(out_tag, out_value, result) }
+}
+
+/// Verify a 30-second TOTP code, allowing `skew` periods of drift either way.
+/// The number of digits is taken from the code itself.
+/// Returns via out parameters:
+/// tag = 0: success, value = 1 if the code matches, 0 otherwise
+/// tag = 1: error, value = position of invalid secret character
+///
+/// # Safety
+/// The caller must ensure `code` is a valid pointer to a NamlString, `secret`
+/// is a valid pointer to a NamlString, `out_tag` is a valid pointer to an i32
+/// and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_otp_totp_verify(
+    code: *const NamlString,
+    secret: *const NamlString,
+    skew: i64,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    let code = string_as_str(code);
+    let skew = skew.max(0) as u64;
+    let result = decode_secret(string_as_str(secret))
+        .map(|key| totp_matches(&key, code, unix_now(), skew) as i64);
+    unsafe { write_result(out_tag, out_value, result) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4226 / RFC 6238 test secret "12345678901234567890"
+    const RFC_KEY: &[u8] = b"12345678901234567890";
+    const RFC_KEY_B32: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn read_string(s: *const NamlString) -> String {
+        string_as_str(s).to_string()
+    }
+
+    #[test]
+    fn test_hotp_rfc4226_vectors() {
+        let expected = ["755224", "287082", "359152", "969429", "338314"];
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(hotp_code(RFC_KEY, counter as u64, 6), *code);
+        }
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        assert_eq!(totp_code(RFC_KEY, 59, 30, 8), "94287082");
+        assert_eq!(totp_code(RFC_KEY, 1111111109, 30, 8), "07081804");
+        assert_eq!(totp_code(RFC_KEY, 2000000000, 30, 8), "69279037");
+    }
+
+    #[test]
+    fn test_decode_secret_lenient() {
+        assert_eq!(decode_secret(RFC_KEY_B32).unwrap(), RFC_KEY);
+        assert_eq!(decode_secret("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap(), RFC_KEY);
+        assert_eq!(decode_secret("GEZD 1NBV"), Err(5));
+    }
+
+    #[test]
+    fn test_totp_matches_with_skew() {
+        let now = 1111111109;
+        let previous = totp_code(RFC_KEY, now - 30, 30, 6);
+        assert!(totp_matches(RFC_KEY, &previous, now, 1));
+        assert!(!totp_matches(RFC_KEY, &previous, now, 0));
+        assert!(!totp_matches(RFC_KEY, "12ab56", now, 1));
+        assert!(!totp_matches(RFC_KEY, "", now, 1));
+    }
+
+    #[test]
+    fn test_hotp_ffi() {
+        unsafe {
+            let secret = create_string_from(RFC_KEY_B32);
+            let mut tag: i32 = -1;
+            let mut value: i64 = 0;
+            naml_crypto_otp_hotp(secret, 1, &mut tag, &mut value);
+            assert_eq!(tag, 0);
+            assert_eq!(read_string(value as *const NamlString), "287082");
+        }
+    }
+
+    #[test]
+    fn test_totp_now_and_verify_ffi() {
+        unsafe {
+            let secret = create_string_from(RFC_KEY_B32);
+            let mut tag: i32 = -1;
+            let mut value: i64 = 0;
+            naml_crypto_otp_totp_now(secret, 30, 6, &mut tag, &mut value);
+            assert_eq!(tag, 0);
+            let code = value as *const NamlString;
+            assert_eq!((*code).len, 6);
+
+            naml_crypto_otp_totp_verify(code, secret, 1, &mut tag, &mut value);
+            assert_eq!(tag, 0);
+            assert_eq!(value, 1);
+        }
+    }
+
+    #[test]
+    fn test_invalid_secret_ffi() {
+        unsafe {
+            let secret = create_string_from("not!base32");
+            let mut tag: i32 = -1;
+            let mut value: i64 = 0;
+            naml_crypto_otp_hotp(secret, 0, &mut tag, &mut value);
+            assert_eq!(tag, 1);
+            assert_eq!(value, 3);
+        }
+    }
+}
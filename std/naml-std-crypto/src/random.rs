@@ -68,8 +68,8 @@ mod tests {
         unsafe {
             let r1 = naml_crypto_random_bytes(32);
             let r2 = naml_crypto_random_bytes(32);
-            let s1 = std::slice::from_raw_parts((*r1).data.as_ptr(), (*r1).len);
-            let s2 = std::slice::from_raw_parts((*r2).data.as_ptr(), (*r2).len);
+            let s1 = std::slice::from_raw_parts((*r1).as_ptr(), (*r1).len);
+            let s2 = std::slice::from_raw_parts((*r2).as_ptr(), (*r2).len);
             assert_ne!(s1, s2);
         }
     }
@@ -0,0 +1,204 @@
+///
+/// std::crypto - Secret Values
+///
+/// A `secret` holds credential material (API keys, passwords, HMAC keys) that
+/// must not leak into logs or crash dumps. At runtime it has the same layout as
+/// `NamlBytes` but is tagged `HeapTag::Secret`, so functions that read key bytes
+/// accept either type without copying.
+///
+/// - Printing or formatting a secret yields `[REDACTED]` (handled in codegen)
+/// - When the last reference is released the buffer is zeroized before it is freed
+/// - The plaintext is only available through an explicit `secret_expose` call
+///
+/// Functions:
+/// - `naml_crypto_secret_from_string(s) -> secret` — Wrap UTF-8 text
+/// - `naml_crypto_secret_from_bytes(b) -> secret` — Wrap raw bytes
+/// - `naml_crypto_secret_expose(s) -> string` — Copy the plaintext out as a string
+/// - `naml_crypto_secret_len(s) -> int` — Length in bytes
+/// - `naml_crypto_secret_equals(a, b) -> bool` — Constant-time comparison
+/// - `naml_secret_incref(s)` / `naml_secret_decref(s)` — Reference counting
+///
+
+use naml_std_core::bytes::NamlBytes;
+use naml_std_core::value::NamlString;
+use std::alloc::Layout;
+
+use zeroize::Zeroize;
+
+fn secret_layout(cap: usize) -> Layout {
+    Layout::from_size_align(
+        std::mem::size_of::<NamlBytes>() + cap,
+        std::mem::align_of::<NamlBytes>(),
+    ).unwrap()
+}
+
+/// Allocate a secret holding a copy of `data`; used by other std crates that hand out credentials
+pub fn create_secret_from(data: &[u8]) -> *mut NamlBytes {
+    unsafe {
+        let len = data.len();
+        let cap = if len == 0 { 8 } else { len };
+        let ptr = std::alloc::alloc(secret_layout(cap)) as *mut NamlBytes;
+        if ptr.is_null() {
+            panic!("Failed to allocate secret");
+        }
+        (*ptr).header = naml_std_core::HeapHeader::new(naml_std_core::HeapTag::Secret);
+        (*ptr).len = len;
+        (*ptr).capacity = cap;
+        if len > 0 {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), (*ptr).data.as_mut_ptr(), len);
+        }
+        ptr
+    }
+}
+
+fn secret_as_slice(s: *const NamlBytes) -> &'static [u8] {
+    unsafe {
+        if s.is_null() {
+            return &[];
+        }
+        std::slice::from_raw_parts((*s).as_ptr(), (*s).len)
+    }
+}
+
+fn string_as_slice(s: *const NamlString) -> &'static [u8] {
+    unsafe {
+        if s.is_null() {
+            return &[];
+        }
+        std::slice::from_raw_parts((*s).as_ptr(), (*s).len)
+    }
+}
+
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_secret_from_string(s: *const NamlString) -> *mut NamlBytes {
+    create_secret_from(string_as_slice(s))
+}
+
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_secret_from_bytes(b: *const NamlBytes) -> *mut NamlBytes {
+    create_secret_from(secret_as_slice(b))
+}
+
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_secret_expose(s: *const NamlBytes) -> *mut NamlString {
+    let data = secret_as_slice(s);
+    unsafe { naml_std_core::value::naml_string_new(data.as_ptr(), data.len()) }
+}
+
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_secret_len(s: *const NamlBytes) -> i64 {
+    secret_as_slice(s).len() as i64
+}
+
+/// Compare two secrets without short-circuiting on the first differing byte
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlBytes and `b` is a
+/// valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_crypto_secret_equals(a: *const NamlBytes, b: *const NamlBytes) -> i64 {
+    let a = secret_as_slice(a);
+    let b = secret_as_slice(b);
+    if a.len() != b.len() {
+        return 0;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    if diff == 0 { 1 } else { 0 }
+}
+
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlBytes or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_secret_incref(s: *mut NamlBytes) {
+    if !s.is_null() {
+        unsafe { (*s).header.incref(); }
+    }
+}
+
+/// Decrement reference count; on the last release zeroize the whole buffer, then free
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlBytes or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_secret_decref(s: *mut NamlBytes) {
+    if !s.is_null() {
+        unsafe {
+            if (*s).header.decref() {
+                let cap = (*s).capacity;
+                std::slice::from_raw_parts_mut((*s).data.as_mut_ptr(), cap).zeroize();
+                (*s).len.zeroize();
+                std::alloc::dealloc(s as *mut u8, secret_layout(cap));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_string(s: &str) -> *mut NamlString {
+        unsafe { naml_std_core::value::naml_string_new(s.as_ptr(), s.len()) }
+    }
+
+    #[test]
+    fn test_secret_roundtrip() {
+        unsafe {
+            let secret = naml_crypto_secret_from_string(make_string("hunter2"));
+            assert_eq!((*secret).header.tag, naml_std_core::HeapTag::Secret);
+            assert_eq!(naml_crypto_secret_len(secret), 7);
+
+            let exposed = naml_crypto_secret_expose(secret);
+            assert_eq!(string_as_slice(exposed), b"hunter2");
+            naml_secret_decref(secret);
+        }
+    }
+
+    #[test]
+    fn test_secret_equals() {
+        unsafe {
+            let a = naml_crypto_secret_from_string(make_string("token-a"));
+            let b = naml_crypto_secret_from_string(make_string("token-a"));
+            let c = naml_crypto_secret_from_string(make_string("token-b"));
+            let short = naml_crypto_secret_from_string(make_string("token"));
+            assert_eq!(naml_crypto_secret_equals(a, b), 1);
+            assert_eq!(naml_crypto_secret_equals(a, c), 0);
+            assert_eq!(naml_crypto_secret_equals(a, short), 0);
+        }
+    }
+
+    #[test]
+    fn test_secret_usable_as_hmac_key() {
+        unsafe {
+            let key = naml_crypto_secret_from_string(make_string("key"));
+            let data = naml_std_core::bytes::naml_bytes_from(
+                b"The quick brown fox jumps over the lazy dog".as_ptr(),
+                43,
+            );
+            let hex = crate::naml_crypto_hmac_sha256_hex(key, data);
+            assert_eq!(
+                string_as_slice(hex),
+                b"f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+            );
+        }
+    }
+
+    #[test]
+    fn test_secret_refcount() {
+        unsafe {
+            let secret = naml_crypto_secret_from_bytes(std::ptr::null());
+            naml_secret_incref(secret);
+            naml_secret_decref(secret);
+            assert_eq!(naml_crypto_secret_len(secret), 0);
+            naml_secret_decref(secret);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+///
+/// std::encoding::base32 - Base32 Encoding/Decoding
+///
+/// Provides bytes <-> base32 string conversion (RFC 4648) using the `data-encoding` crate.
+/// - encode(data: bytes) -> string: Convert bytes to padded uppercase base32
+/// - decode(s: string) -> bytes throws DecodeError: Convert base32 string to bytes
+///
+/// Decoding is case-insensitive and accepts input with or without trailing `=`
+/// padding, which covers the unpadded lowercase secrets used by authenticator apps.
+///
+
+use data_encoding::{BASE32, BASE32_NOPAD};
+use naml_std_core::bytes::NamlBytes;
+use naml_std_core::value::NamlString;
+use std::alloc::Layout;
+
+/// Encode bytes to base32 string
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_base32_encode(b: *const NamlBytes) -> *mut NamlString {
+    if b.is_null() {
+        return unsafe { naml_std_core::value::naml_string_new(std::ptr::null(), 0) };
+    }
+
+    unsafe {
+        let len = (*b).len;
+        let data = std::slice::from_raw_parts((*b).as_ptr(), len);
+        let b32_string = BASE32.encode(data);
+        naml_std_core::value::naml_string_new(b32_string.as_ptr(), b32_string.len())
+    }
+}
+
+/// Decode base32 string to bytes
+/// Returns via out parameters:
+/// tag = 0: success, value = bytes pointer
+/// tag = 1: error, value = position of invalid character
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString or null,
+/// `out_tag` is a valid pointer to an i32 and `out_value` is a valid pointer to
+/// an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_base32_decode(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    if s.is_null() {
+        unsafe {
+            *out_tag = 0;
+            *out_value = create_empty_bytes() as i64;
+        }
+        return;
+    }
+
+    unsafe {
+        let len = (*s).len;
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
+
+        // Padding is stripped from the end only, so error positions still index the input
+        let unpadded_len = data.iter().rposition(|&c| c != b'=').map_or(0, |i| i + 1);
+        let normalized = data[..unpadded_len].to_ascii_uppercase();
+
+        match BASE32_NOPAD.decode(&normalized) {
+            Ok(bytes) => {
+                let result = create_bytes_from(bytes.as_ptr(), bytes.len());
+                *out_tag = 0;
+                *out_value = result as i64;
+            }
+            Err(e) => {
+                *out_tag = 1;
+                *out_value = e.position as i64;
+            }
+        }
+    }
+}
+
+fn create_empty_bytes() -> *mut NamlBytes {
+    unsafe {
+        let layout = Layout::from_size_align(
+            std::mem::size_of::<NamlBytes>() + 8,
+            std::mem::align_of::<NamlBytes>(),
+        ).unwrap();
+        let ptr = std::alloc::alloc_zeroed(layout) as *mut NamlBytes;
+        (*ptr).header = naml_std_core::HeapHeader::new(naml_std_core::HeapTag::Bytes);
+        (*ptr).len = 0;
+        (*ptr).capacity = 8;
+        ptr
+    }
+}
+
+fn create_bytes_from(data: *const u8, len: usize) -> *mut NamlBytes {
+    unsafe {
+        let cap = if len == 0 { 8 } else { len };
+        let layout = Layout::from_size_align(
+            std::mem::size_of::<NamlBytes>() + cap,
+            std::mem::align_of::<NamlBytes>(),
+        ).unwrap();
+        let ptr = std::alloc::alloc(layout) as *mut NamlBytes;
+        (*ptr).header = naml_std_core::HeapHeader::new(naml_std_core::HeapTag::Bytes);
+        (*ptr).len = len;
+        (*ptr).capacity = cap;
+        if len > 0 && !data.is_null() {
+            std::ptr::copy_nonoverlapping(data, (*ptr).data.as_mut_ptr(), len);
+        }
+        ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn decode(input: &str) -> (i32, i64) {
+        unsafe {
+            let s = naml_std_core::value::naml_string_new(input.as_ptr(), input.len());
+            let mut tag: i32 = -1;
+            let mut value: i64 = 0;
+            naml_encoding_base32_decode(s, &mut tag, &mut value);
+            (tag, value)
+        }
+    }
+
+    #[test]
+    fn test_base32_encode() {
+        unsafe {
+            let data = b"foobar";
+            let bytes = create_bytes_from(data.as_ptr(), 6);
+            let result = naml_encoding_base32_encode(bytes);
+            let s = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
+            assert_eq!(s, b"MZXW6YTBOI======");
+        }
+    }
+
+    #[test]
+    fn test_base32_decode_valid() {
+        unsafe {
+            for input in ["MZXW6YTBOI======", "MZXW6YTBOI", "mzxw6ytboi"] {
+                let (tag, value) = decode(input);
+                assert_eq!(tag, 0, "{}", input);
+                let bytes = value as *const NamlBytes;
+                let decoded = std::slice::from_raw_parts((*bytes).as_ptr(), (*bytes).len);
+                assert_eq!(decoded, b"foobar");
+            }
+        }
+    }
+
+    #[test]
+    fn test_base32_decode_invalid() {
+        unsafe {
+            let (tag, value) = decode("MZX1");
+            assert_eq!(tag, 1);
+            assert_eq!(value, 3);
+        }
+    }
+}
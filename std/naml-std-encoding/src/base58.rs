@@ -0,0 +1,227 @@
+///
+/// std::encoding::base58 - Base58 and Base58Check Encoding/Decoding
+///
+/// Provides bytes <-> base58 string conversion using the Bitcoin alphabet via the `bs58` crate.
+/// - encode(data: bytes) -> string: Convert bytes to base58 string
+/// - decode(s: string) -> bytes throws DecodeError: Convert base58 string to bytes
+/// - check_encode(data: bytes) -> string: Append a 4-byte double-SHA256 checksum and encode
+/// - check_decode(s: string) -> bytes throws DecodeError: Decode and verify the checksum,
+///   returning the payload without it
+///
+/// Checksum failures are reported at the position just past the end of the input.
+///
+
+use naml_std_core::bytes::NamlBytes;
+use naml_std_core::value::NamlString;
+use std::alloc::Layout;
+
+/// Encode bytes to base58 string
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_base58_encode(b: *const NamlBytes) -> *mut NamlString {
+    unsafe { encode_with(b, |data| bs58::encode(data).into_string()) }
+}
+
+/// Decode base58 string to bytes
+/// Returns via out parameters:
+/// tag = 0: success, value = bytes pointer
+/// tag = 1: error, value = position of invalid character
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString, `out_tag` is
+/// a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_base58_decode(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe { decode_with(s, out_tag, out_value, |data| bs58::decode(data).into_vec()) }
+}
+
+/// Encode bytes to base58check string (payload followed by a 4-byte checksum)
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_base58_check_encode(b: *const NamlBytes) -> *mut NamlString {
+    unsafe { encode_with(b, |data| bs58::encode(data).with_check().into_string()) }
+}
+
+/// Decode base58check string to bytes, verifying and stripping the checksum
+/// Returns via out parameters:
+/// tag = 0: success, value = bytes pointer
+/// tag = 1: error, value = position of invalid character or input length on checksum failure
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString, `out_tag` is
+/// a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_base58_check_decode(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        decode_with(s, out_tag, out_value, |data| {
+            bs58::decode(data).with_check(None).into_vec()
+        })
+    }
+}
+
+unsafe fn encode_with(b: *const NamlBytes, encode: impl Fn(&[u8]) -> String) -> *mut NamlString {
+    unsafe {
+        let data = if b.is_null() {
+            &[][..]
+        } else {
+            std::slice::from_raw_parts((*b).as_ptr(), (*b).len)
+        };
+        let encoded = encode(data);
+        naml_std_core::value::naml_string_new(encoded.as_ptr(), encoded.len())
+    }
+}
+
+unsafe fn decode_with(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+    decode: impl Fn(&[u8]) -> bs58::decode::Result<Vec<u8>>,
+) {
+    if s.is_null() {
+        unsafe {
+            *out_tag = 0;
+            *out_value = create_empty_bytes() as i64;
+        }
+        return;
+    }
+
+    unsafe {
+        let len = (*s).len;
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
+
+        match decode(data) {
+            Ok(bytes) => {
+                let result = create_bytes_from(bytes.as_ptr(), bytes.len());
+                *out_tag = 0;
+                *out_value = result as i64;
+            }
+            Err(e) => {
+                *out_tag = 1;
+                *out_value = match e {
+                    bs58::decode::Error::InvalidCharacter { index, .. } => index as i64,
+                    bs58::decode::Error::NonAsciiCharacter { index } => index as i64,
+                    _ => len as i64,
+                };
+            }
+        }
+    }
+}
+
+fn create_empty_bytes() -> *mut NamlBytes {
+    unsafe {
+        let layout = Layout::from_size_align(
+            std::mem::size_of::<NamlBytes>() + 8,
+            std::mem::align_of::<NamlBytes>(),
+        ).unwrap();
+        let ptr = std::alloc::alloc_zeroed(layout) as *mut NamlBytes;
+        (*ptr).header = naml_std_core::HeapHeader::new(naml_std_core::HeapTag::Bytes);
+        (*ptr).len = 0;
+        (*ptr).capacity = 8;
+        ptr
+    }
+}
+
+fn create_bytes_from(data: *const u8, len: usize) -> *mut NamlBytes {
+    unsafe {
+        let cap = if len == 0 { 8 } else { len };
+        let layout = Layout::from_size_align(
+            std::mem::size_of::<NamlBytes>() + cap,
+            std::mem::align_of::<NamlBytes>(),
+        ).unwrap();
+        let ptr = std::alloc::alloc(layout) as *mut NamlBytes;
+        (*ptr).header = naml_std_core::HeapHeader::new(naml_std_core::HeapTag::Bytes);
+        (*ptr).len = len;
+        (*ptr).capacity = cap;
+        if len > 0 && !data.is_null() {
+            std::ptr::copy_nonoverlapping(data, (*ptr).data.as_mut_ptr(), len);
+        }
+        ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn string_of(s: *const NamlString) -> String {
+        unsafe {
+            let data = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+            String::from_utf8(data.to_vec()).unwrap()
+        }
+    }
+
+    unsafe fn run_decode(
+        decode: unsafe extern "C" fn(*const NamlString, *mut i32, *mut i64),
+        input: &str,
+    ) -> (i32, i64) {
+        unsafe {
+            let s = naml_std_core::value::naml_string_new(input.as_ptr(), input.len());
+            let mut tag: i32 = -1;
+            let mut value: i64 = 0;
+            decode(s, &mut tag, &mut value);
+            (tag, value)
+        }
+    }
+
+    #[test]
+    fn test_base58_roundtrip() {
+        unsafe {
+            let data = b"\x00\x00hello";
+            let bytes = create_bytes_from(data.as_ptr(), data.len());
+            let encoded = string_of(naml_encoding_base58_encode(bytes));
+            assert_eq!(encoded, "11Cn8eVZg");
+
+            let (tag, value) = run_decode(naml_encoding_base58_decode, &encoded);
+            assert_eq!(tag, 0);
+            let out = value as *const NamlBytes;
+            let decoded = std::slice::from_raw_parts((*out).as_ptr(), (*out).len);
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn test_base58_decode_invalid_character() {
+        unsafe {
+            let (tag, value) = run_decode(naml_encoding_base58_decode, "abc0def");
+            assert_eq!(tag, 1);
+            assert_eq!(value, 3);
+        }
+    }
+
+    #[test]
+    fn test_base58_check_roundtrip() {
+        unsafe {
+            let payload = [0u8; 21];
+            let bytes = create_bytes_from(payload.as_ptr(), payload.len());
+            let encoded = string_of(naml_encoding_base58_check_encode(bytes));
+            assert_eq!(encoded, "1111111111111111111114oLvT2");
+
+            let (tag, value) = run_decode(naml_encoding_base58_check_decode, &encoded);
+            assert_eq!(tag, 0);
+            let out = value as *const NamlBytes;
+            assert_eq!((*out).len, 21);
+        }
+    }
+
+    #[test]
+    fn test_base58_check_bad_checksum() {
+        unsafe {
+            let (tag, value) =
+                run_decode(naml_encoding_base58_check_decode, "1111111111111111111114oLvT3");
+            assert_eq!(tag, 1);
+            assert_eq!(value, 27);
+        }
+    }
+}
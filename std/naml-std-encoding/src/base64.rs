@@ -20,7 +20,7 @@ pub unsafe extern "C" fn naml_encoding_base64_encode(b: *const NamlBytes) -> *mu
 
     unsafe {
         let len = (*b).len;
-        let data = std::slice::from_raw_parts((*b).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*b).as_ptr(), len);
         let b64_string = STANDARD.encode(data);
         naml_std_core::value::naml_string_new(b64_string.as_ptr(), b64_string.len())
     }
@@ -46,7 +46,7 @@ pub unsafe extern "C" fn naml_encoding_base64_decode(
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
 
         match STANDARD.decode(data) {
             Ok(bytes) => {
@@ -109,7 +109,7 @@ mod tests {
             let data = b"Hello";
             let bytes = create_bytes_from(data.as_ptr(), 5);
             let result = naml_encoding_base64_encode(bytes);
-            let s = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let s = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             assert_eq!(s, b"SGVsbG8=");
         }
     }
@@ -123,7 +123,7 @@ mod tests {
             naml_encoding_base64_decode(s, &mut tag, &mut value);
             assert_eq!(tag, 0);
             let bytes = value as *const NamlBytes;
-            let decoded = std::slice::from_raw_parts((*bytes).data.as_ptr(), (*bytes).len);
+            let decoded = std::slice::from_raw_parts((*bytes).as_ptr(), (*bytes).len);
             assert_eq!(decoded, b"Hello");
         }
     }
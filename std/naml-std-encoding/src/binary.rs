@@ -13,27 +13,31 @@
 ///   write_u32_be/le, write_i32_be/le, write_u64_be/le, write_i64_be/le,
 ///   write_f32_be/le, write_f64_be/le
 ///
-/// **Buffer operations:** alloc, from_string, len, capacity, slice, concat,
+/// **Buffer operations:** alloc, from_string, len, capacity, slice, view, concat,
 ///   append, copy_within, clear, resize, fill
 ///
+/// `view` shares the buffer's bytes, so writes through either side are
+/// visible in both until the view is grown past its range.
+///
 /// **Search operations:** index_of, contains, starts_with, ends_with, equals
 ///
 
-use naml_std_core::bytes::NamlBytes;
+use naml_std_core::bytes::{NamlBytes, naml_bytes_view, reserve_bytes_view};
+use naml_std_core::simd;
 use naml_std_core::value::NamlString;
 use std::alloc::Layout;
 
 fn buf_data(buf: *const NamlBytes) -> &'static [u8] {
     unsafe {
         let len = (*buf).len;
-        std::slice::from_raw_parts((*buf).data.as_ptr(), len)
+        std::slice::from_raw_parts((*buf).as_ptr(), len)
     }
 }
 
 fn buf_data_mut(buf: *mut NamlBytes) -> &'static mut [u8] {
     unsafe {
         let len = (*buf).len;
-        std::slice::from_raw_parts_mut((*buf).data.as_mut_ptr(), len)
+        std::slice::from_raw_parts_mut((*buf).as_mut_ptr(), len)
     }
 }
 
@@ -410,7 +414,7 @@ pub unsafe extern "C" fn naml_encoding_binary_from_string(s: *const NamlString)
     }
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         create_bytes_from_slice(data)
     }
 }
@@ -441,6 +445,20 @@ pub unsafe extern "C" fn naml_encoding_binary_slice(
     create_bytes_from_slice(&data[s..e])
 }
 
+/// Like `slice`, but the result shares `buf`'s bytes instead of copying them
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_binary_view(
+    buf: *const NamlBytes,
+    start: i64,
+    end: i64,
+) -> *mut NamlBytes {
+    if buf.is_null() { return create_bytes_with_capacity(8); }
+    let len = unsafe { (*buf).len };
+    let s = (start.max(0) as usize).min(len);
+    let e = (end.max(0) as usize).min(len).max(s);
+    unsafe { naml_bytes_view(buf, s, e) }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_encoding_binary_concat(
     a: *const NamlBytes,
@@ -477,13 +495,14 @@ pub unsafe extern "C" fn naml_encoding_binary_append(
         if src_len == 0 { return; }
         let dst_len = (*dst).len;
         let needed = dst_len + src_len;
+        reserve_bytes_view(dst, needed);
         if needed > (*dst).capacity {
             let new_cap = needed.next_power_of_two().max(needed);
             let _ = realloc_bytes(dst, new_cap);
         }
         std::ptr::copy_nonoverlapping(
-            (*src).data.as_ptr(),
-            (*dst).data.as_mut_ptr().add(dst_len),
+            (*src).as_ptr(),
+            (*dst).as_mut_ptr().add(dst_len),
             src_len,
         );
         (*dst).len = needed;
@@ -506,7 +525,7 @@ pub unsafe extern "C" fn naml_encoding_binary_copy_within(
         if ss >= se { return; }
         let copy_len = se - ss;
         if ds + copy_len > len { return; }
-        let ptr = (*buf).data.as_mut_ptr();
+        let ptr = (*buf).as_mut_ptr();
         std::ptr::copy(ptr.add(ss), ptr.add(ds), copy_len);
     }
 }
@@ -516,7 +535,7 @@ pub unsafe extern "C" fn naml_encoding_binary_clear(buf: *mut NamlBytes) {
     if buf.is_null() { return; }
     unsafe {
         let len = (*buf).len;
-        std::ptr::write_bytes((*buf).data.as_mut_ptr(), 0, len);
+        std::ptr::write_bytes((*buf).as_mut_ptr(), 0, len);
     }
 }
 
@@ -526,12 +545,13 @@ pub unsafe extern "C" fn naml_encoding_binary_resize(buf: *mut NamlBytes, new_le
     let new_len = if new_len < 0 { 0usize } else { new_len as usize };
     unsafe {
         let old_len = (*buf).len;
+        reserve_bytes_view(buf, new_len);
         if new_len > (*buf).capacity {
             let new_cap = new_len.next_power_of_two().max(new_len);
             let _ = realloc_bytes(buf, new_cap);
         }
         if new_len > old_len {
-            std::ptr::write_bytes((*buf).data.as_mut_ptr().add(old_len), 0, new_len - old_len);
+            std::ptr::write_bytes((*buf).as_mut_ptr().add(old_len), 0, new_len - old_len);
         }
         (*buf).len = new_len;
     }
@@ -542,7 +562,7 @@ pub unsafe extern "C" fn naml_encoding_binary_fill(buf: *mut NamlBytes, value: i
     if buf.is_null() { return; }
     unsafe {
         let len = (*buf).len;
-        std::ptr::write_bytes((*buf).data.as_mut_ptr(), value as u8, len);
+        std::ptr::write_bytes((*buf).as_mut_ptr(), value as u8, len);
     }
 }
 
@@ -554,14 +574,7 @@ pub unsafe extern "C" fn naml_encoding_binary_index_of(
     if haystack.is_null() || needle.is_null() { return -1; }
     let h = buf_data(haystack);
     let n = buf_data(needle);
-    if n.is_empty() { return 0; }
-    if n.len() > h.len() { return -1; }
-    for i in 0..=(h.len() - n.len()) {
-        if h[i..i + n.len()] == *n {
-            return i as i64;
-        }
-    }
-    -1
+    simd::find_bytes(h, n).map_or(-1, |i| i as i64)
 }
 
 #[unsafe(no_mangle)]
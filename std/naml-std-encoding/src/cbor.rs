@@ -0,0 +1,241 @@
+///
+/// std::encoding::cbor - CBOR Encoding/Decoding (RFC 8949)
+///
+/// Converts between json values and CBOR, using the same value model as
+/// the json and msgpack modules:
+/// - encode(value: json) -> bytes: Serialize to CBOR
+/// - decode(data: bytes) -> json throws DecodeError: Parse one CBOR data item
+///
+/// Integers keep their exact value and floats are written in the shortest
+/// form that preserves them. When decoding:
+/// - byte strings become an array of byte values
+/// - map keys that are not text are converted to their JSON text, so an
+///   int key 1 becomes "1"
+/// - tagged values become an object {"tag": <tag number>, "value": <item>},
+///   except bignums (tags 2 and 3) that fit in 128 bits, which become
+///   numbers; integers outside the int/uint 64-bit range become floats
+/// - NaN or infinite floats and undefined become null
+///
+/// DecodeError's position is the byte offset where decoding stopped.
+///
+
+use ciborium::value::Value as CborValue;
+use naml_std_core::bytes::{naml_bytes_from, NamlBytes};
+use serde_json::{Map, Number, Value};
+
+use crate::json::{create_json, NamlJson};
+
+/// Unsigned and negative bignum tags (RFC 8949 section 3.4.3)
+const TAG_BIGNUM_POS: u64 = 2;
+const TAG_BIGNUM_NEG: u64 = 3;
+
+fn json_to_cbor(value: &Value) -> CborValue {
+    match value {
+        Value::Null => CborValue::Null,
+        Value::Bool(b) => CborValue::Bool(*b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                CborValue::Integer(i.into())
+            } else if let Some(u) = n.as_u64() {
+                CborValue::Integer(u.into())
+            } else {
+                CborValue::Float(n.as_f64().unwrap_or(0.0))
+            }
+        }
+        Value::String(s) => CborValue::Text(s.clone()),
+        Value::Array(items) => CborValue::Array(items.iter().map(json_to_cbor).collect()),
+        Value::Object(map) => CborValue::Map(
+            map.iter()
+                .map(|(k, v)| (CborValue::Text(k.clone()), json_to_cbor(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn float_to_json(f: f64) -> Value {
+    Number::from_f64(f).map_or(Value::Null, Value::Number)
+}
+
+/// Integers that fit neither i64 nor u64 become floats
+fn integer_to_json(n: i128) -> Value {
+    if let Ok(n) = i64::try_from(n) {
+        Value::from(n)
+    } else if let Ok(n) = u64::try_from(n) {
+        Value::from(n)
+    } else {
+        float_to_json(n as f64)
+    }
+}
+
+/// Value of a bignum with up to 16 big-endian magnitude bytes
+fn bignum_to_json(negative: bool, digits: &[u8]) -> Value {
+    let n = digits.iter().fold(0u128, |acc, &b| (acc << 8) | b as u128);
+    match (i128::try_from(n), negative) {
+        (Ok(n), false) => integer_to_json(n),
+        (Ok(n), true) => integer_to_json(-1 - n),
+        (Err(_), false) => float_to_json(n as f64),
+        (Err(_), true) => float_to_json(-1.0 - n as f64),
+    }
+}
+
+fn cbor_to_json(value: CborValue) -> Value {
+    match value {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(b) => Value::Bool(b),
+        CborValue::Integer(i) => integer_to_json(i128::from(i)),
+        CborValue::Float(f) => float_to_json(f),
+        CborValue::Text(s) => Value::String(s),
+        CborValue::Bytes(data) => Value::Array(data.into_iter().map(Value::from).collect()),
+        CborValue::Array(items) => Value::Array(items.into_iter().map(cbor_to_json).collect()),
+        CborValue::Map(entries) => {
+            let mut map = Map::new();
+            for (k, v) in entries {
+                let key = match cbor_to_json(k) {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                map.insert(key, cbor_to_json(v));
+            }
+            Value::Object(map)
+        }
+        CborValue::Tag(tag, item) => match (tag, *item) {
+            (TAG_BIGNUM_POS | TAG_BIGNUM_NEG, CborValue::Bytes(digits)) if digits.len() <= 16 => {
+                bignum_to_json(tag == TAG_BIGNUM_NEG, &digits)
+            }
+            (tag, item) => {
+                let mut map = Map::new();
+                map.insert("tag".to_string(), Value::from(tag));
+                map.insert("value".to_string(), cbor_to_json(item));
+                Value::Object(map)
+            }
+        },
+        // Value is non-exhaustive; nothing else is produced today
+        _ => Value::Null,
+    }
+}
+
+/// Decode exactly one data item from `data`, or the byte position of the error
+fn decode_value(data: &[u8]) -> Result<Value, usize> {
+    let mut rest = data;
+    let value: CborValue = ciborium::de::from_reader(&mut rest).map_err(|e| match e {
+        ciborium::de::Error::Syntax(offset) | ciborium::de::Error::Semantic(Some(offset), _) => offset,
+        _ => data.len() - rest.len(),
+    })?;
+    if !rest.is_empty() {
+        return Err(data.len() - rest.len());
+    }
+    Ok(cbor_to_json(value))
+}
+
+/// Encode a json value as CBOR bytes
+///
+/// # Safety
+/// The caller must ensure `json` is a valid pointer to a NamlJson or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_cbor_encode(json: *const NamlJson) -> *mut NamlBytes {
+    let value = if json.is_null() {
+        CborValue::Null
+    } else {
+        unsafe { json_to_cbor((*json).get_value()) }
+    };
+    let mut out = Vec::new();
+    // Writing into a Vec cannot fail
+    let _ = ciborium::ser::into_writer(&value, &mut out);
+    unsafe { naml_bytes_from(out.as_ptr(), out.len()) }
+}
+
+/// Decode CBOR bytes into a json value
+/// Returns via out parameters:
+/// tag = 0: success, value = NamlJson pointer
+/// tag = 1: error, value = byte position of the error
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes or null,
+/// `out_tag` is a valid pointer to an i32 and `out_value` is a valid pointer to
+/// an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_cbor_decode(
+    b: *const NamlBytes,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    let data = if b.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts((*b).as_ptr(), (*b).len) }
+    };
+    unsafe {
+        match decode_value(data) {
+            Ok(value) => {
+                *out_tag = 0;
+                *out_value = create_json(value) as i64;
+            }
+            Err(position) => {
+                *out_tag = 1;
+                *out_value = position as i64;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut out = Vec::new();
+        ciborium::ser::into_writer(&json_to_cbor(value), &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = json!({
+            "name": "naml",
+            "tags": ["a", "b"],
+            "count": -3,
+            "big": u64::MAX,
+            "ratio": 0.5,
+            "ok": true,
+            "none": null
+        });
+        assert_eq!(decode_value(&encode(&value)), Ok(value));
+        assert_eq!(encode(&json!(5)), vec![0x05]);
+        assert_eq!(encode(&json!("hi")), vec![0x62, b'h', b'i']);
+        assert_eq!(encode(&json!(1.5)), vec![0xf9, 0x3e, 0x00]);
+    }
+
+    #[test]
+    fn test_decode_mapping() {
+        // {1: h'0708', true: NaN}
+        let data = [0xa2, 0x01, 0x42, 0x07, 0x08, 0xf5, 0xf9, 0x7e, 0x00];
+        assert_eq!(decode_value(&data), Ok(json!({"1": [7, 8], "true": null})));
+
+        // 1(1363896240): epoch date-time tag
+        let data = [0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0];
+        assert_eq!(decode_value(&data), Ok(json!({"tag": 1, "value": 1363896240})));
+
+        // 2(h'010000000000000000') = 2^64, beyond u64
+        let data = [0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(decode_value(&data), Ok(json!(18446744073709551616.0)));
+
+        // 3(h'00ff') = -256, 3(h'0100000000000000000000000000000000') stays tagged
+        assert_eq!(decode_value(&[0xc3, 0x42, 0x00, 0xff]), Ok(json!(-256)));
+        let mut long = vec![0xc3, 0x51, 0x01];
+        long.extend([0; 16]);
+        assert_eq!(decode_value(&long).unwrap()["tag"], json!(3));
+
+        // undefined
+        assert_eq!(decode_value(&[0xf7]), Ok(Value::Null));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode_value(&[0x82, 0x01]), Err(2));
+        assert_eq!(decode_value(&[0x01, 0x02]), Err(1));
+        assert_eq!(decode_value(&[]), Err(0));
+        // Reserved additional information value 28
+        assert_eq!(decode_value(&[0x81, 0x1c]), Err(1));
+    }
+}
@@ -0,0 +1,322 @@
+///
+/// std::encoding::csv - CSV Parsing and Serialization
+///
+/// Reads and writes RFC 4180 style CSV:
+/// - parse(text: string) -> [[string]] throws DecodeError: Rows of fields
+/// - parse_with_headers(text: string) -> [map<string, string>] throws DecodeError:
+///   One map per record, keyed by the first row
+/// - to_csv(rows: [[string]]) -> string: Serialize rows, quoting fields as needed
+/// - parse_delimited, parse_with_headers_delimited, to_csv_delimited: The same
+///   with a single-character delimiter in place of `,`
+///
+/// A field may be wrapped in `"` to hold the delimiter, line breaks or `""`
+/// for a literal quote. Rows end with `\n` or `\r\n`; blank lines are
+/// skipped and `to_csv` ends every row with `\n`. DecodeError carries the
+/// byte position of the problem: an unterminated quote, text after a
+/// closing quote, a quote inside an unquoted field, or a record whose field
+/// count differs from the header row. An invalid delimiter (empty, longer
+/// than one character, a quote or a line break) is reported at position 0.
+///
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use naml_std_core::array::{naml_array_new, naml_array_push, NamlArray};
+use naml_std_core::map::{naml_map_new, naml_map_set_string};
+use naml_std_core::value::{naml_string_decref, naml_string_new, NamlString};
+
+/// Rows with the byte offset each one starts at, or the position of the
+/// first error
+type Rows = Vec<(usize, Vec<String>)>;
+
+fn is_field_end(c: char, delimiter: char) -> bool {
+    c == delimiter || c == '\n' || c == '\r'
+}
+
+/// Parse one field, leaving the delimiter or line break that ends it
+fn parse_field(chars: &mut Peekable<CharIndices>, delimiter: char) -> Result<String, usize> {
+    let mut field = String::new();
+    if let Some(&(start, '"')) = chars.peek() {
+        chars.next();
+        loop {
+            match chars.next() {
+                None => return Err(start),
+                Some((_, '"')) => {
+                    if let Some(&(_, '"')) = chars.peek() {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        break;
+                    }
+                }
+                Some((_, c)) => field.push(c),
+            }
+        }
+        if let Some(&(pos, c)) = chars.peek()
+            && !is_field_end(c, delimiter)
+        {
+            return Err(pos);
+        }
+        return Ok(field);
+    }
+
+    while let Some(&(pos, c)) = chars.peek() {
+        if is_field_end(c, delimiter) {
+            break;
+        }
+        if c == '"' {
+            return Err(pos);
+        }
+        field.push(c);
+        chars.next();
+    }
+    Ok(field)
+}
+
+fn parse_rows(text: &str, delimiter: char) -> Result<Rows, usize> {
+    let mut rows = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    loop {
+        while let Some(&(_, '\n' | '\r')) = chars.peek() {
+            chars.next();
+        }
+        let Some(&(row_start, _)) = chars.peek() else {
+            break;
+        };
+        let mut row = Vec::new();
+        loop {
+            row.push(parse_field(&mut chars, delimiter)?);
+            match chars.next() {
+                Some((_, c)) if c == delimiter => {}
+                _ => break,
+            }
+        }
+        rows.push((row_start, row));
+    }
+    Ok(rows)
+}
+
+/// Quote a field if it holds the delimiter, a quote or a line break
+fn write_field(out: &mut String, field: &str, delimiter: char) {
+    if field.chars().any(|c| c == '"' || is_field_end(c, delimiter)) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+fn write_rows(rows: &[Vec<String>], delimiter: char) -> String {
+    let mut out = String::new();
+    for row in rows {
+        // A lone empty field would otherwise be a blank line, which parse skips
+        if let [only] = row.as_slice()
+            && only.is_empty()
+        {
+            out.push_str("\"\"");
+        }
+        for (i, field) in row.iter().enumerate() {
+            if i > 0 {
+                out.push(delimiter);
+            }
+            write_field(&mut out, field, delimiter);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `,` for a null delimiter, otherwise its single character
+unsafe fn delimiter_from_naml(s: *const NamlString) -> Option<char> {
+    if s.is_null() {
+        return Some(',');
+    }
+    let mut chars = unsafe { (*s).as_str() }.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c != '"' && c != '\n' && c != '\r' => Some(c),
+        _ => None,
+    }
+}
+
+unsafe fn str_from_naml<'a>(s: *const NamlString) -> &'a str {
+    if s.is_null() {
+        return "";
+    }
+    unsafe { (*s).as_str() }
+}
+
+unsafe fn naml_from_str(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+unsafe fn set_error(out_tag: *mut i32, out_value: *mut i64, position: usize) {
+    unsafe {
+        *out_tag = 1;
+        *out_value = position as i64;
+    }
+}
+
+/// Parse CSV into an array of rows, each an array of strings
+/// Returns via out parameters:
+/// tag = 0: success, value = array pointer
+/// tag = 1: error, value = byte position of the error
+///
+/// # Safety
+/// The caller must ensure `text` is a valid pointer to a NamlString,
+/// `delimiter` is a valid pointer to a NamlString, `out_tag` is a valid pointer
+/// to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_csv_parse(
+    text: *const NamlString,
+    delimiter: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        let Some(delimiter) = delimiter_from_naml(delimiter) else {
+            return set_error(out_tag, out_value, 0);
+        };
+        let rows = match parse_rows(str_from_naml(text), delimiter) {
+            Ok(rows) => rows,
+            Err(position) => return set_error(out_tag, out_value, position),
+        };
+        let result = naml_array_new(rows.len());
+        for (_, row) in &rows {
+            let fields = naml_array_new(row.len());
+            for field in row {
+                naml_array_push(fields, naml_from_str(field) as i64);
+            }
+            naml_array_push(result, fields as i64);
+        }
+        *out_tag = 0;
+        *out_value = result as i64;
+    }
+}
+
+/// Parse CSV whose first row names the columns into an array of maps
+/// Returns via out parameters like `naml_encoding_csv_parse`
+///
+/// # Safety
+/// The caller must ensure `text` is a valid pointer to a NamlString,
+/// `delimiter` is a valid pointer to a NamlString, `out_tag` is a valid pointer
+/// to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_csv_parse_with_headers(
+    text: *const NamlString,
+    delimiter: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        let Some(delimiter) = delimiter_from_naml(delimiter) else {
+            return set_error(out_tag, out_value, 0);
+        };
+        let rows = match parse_rows(str_from_naml(text), delimiter) {
+            Ok(rows) => rows,
+            Err(position) => return set_error(out_tag, out_value, position),
+        };
+        let mut rows = rows.into_iter();
+        let headers = rows.next().map(|(_, row)| row).unwrap_or_default();
+        let records: Rows = rows.collect();
+        if let Some((start, _)) = records.iter().find(|(_, row)| row.len() != headers.len()) {
+            return set_error(out_tag, out_value, *start);
+        }
+
+        let result = naml_array_new(records.len());
+        for (_, row) in &records {
+            let record = naml_map_new(headers.len());
+            for (header, field) in headers.iter().zip(row) {
+                let key = naml_from_str(header);
+                naml_map_set_string(record, key as i64, naml_from_str(field) as i64);
+                naml_string_decref(key);
+            }
+            naml_array_push(result, record as i64);
+        }
+        *out_tag = 0;
+        *out_value = result as i64;
+    }
+}
+
+/// Serialize an array of string arrays as CSV
+/// Returns via out parameters:
+/// tag = 0: success, value = string pointer
+/// tag = 1: error (invalid delimiter), value = 0
+///
+/// # Safety
+/// The caller must ensure `rows` is a valid pointer to a NamlArray or null,
+/// `delimiter` is a valid pointer to a NamlString, `out_tag` is a valid pointer
+/// to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_csv_encode(
+    rows: *const NamlArray,
+    delimiter: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        let Some(delimiter) = delimiter_from_naml(delimiter) else {
+            return set_error(out_tag, out_value, 0);
+        };
+        let mut collected = Vec::new();
+        if !rows.is_null() {
+            for i in 0..(*rows).len {
+                let row = *(*rows).data.add(i) as *const NamlArray;
+                let mut fields = Vec::new();
+                if !row.is_null() {
+                    for j in 0..(*row).len {
+                        let field = *(*row).data.add(j) as *const NamlString;
+                        fields.push(str_from_naml(field).to_string());
+                    }
+                }
+                collected.push(fields);
+            }
+        }
+        let csv = write_rows(&collected, delimiter);
+        *out_tag = 0;
+        *out_value = naml_from_str(&csv) as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(rows: Rows) -> Vec<Vec<String>> {
+        rows.into_iter().map(|(_, row)| row).collect()
+    }
+
+    #[test]
+    fn test_parse_quoting_and_line_endings() {
+        let text = "name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\n\nx,\n";
+        let rows = fields(parse_rows(text, ',').unwrap());
+        assert_eq!(
+            rows,
+            vec![
+                vec!["name".to_string(), "note".to_string()],
+                vec!["Smith, J".to_string(), "said \"hi\"\nthen left".to_string()],
+                vec!["x".to_string(), String::new()],
+            ]
+        );
+        assert_eq!(fields(parse_rows("a;b", ';').unwrap()), vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn test_parse_errors_report_position() {
+        assert_eq!(parse_rows("a,\"open", ','), Err(2));
+        assert_eq!(parse_rows("\"a\"b", ','), Err(3));
+        assert_eq!(parse_rows("a\"b", ','), Err(1));
+    }
+
+    #[test]
+    fn test_write_round_trips() {
+        let rows = vec![
+            vec!["a;b".to_string(), "line\nbreak".to_string(), "q\"".to_string()],
+            vec![String::new()],
+            vec!["plain".to_string(), String::new(), "x".to_string()],
+        ];
+        let csv = write_rows(&rows, ';');
+        assert_eq!(csv, "\"a;b\";\"line\nbreak\";\"q\"\"\"\n\"\"\nplain;;x\n");
+        assert_eq!(fields(parse_rows(&csv, ';').unwrap()), rows);
+    }
+}
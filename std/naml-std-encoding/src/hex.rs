@@ -1,7 +1,8 @@
 ///
 /// std::encoding::hex - Hexadecimal Encoding/Decoding
 ///
-/// Provides bytes <-> hex string conversion using the `hex` crate.
+/// Provides bytes <-> hex string conversion. Encoding uses the SIMD kernel
+/// from naml-std-core; decoding uses the `hex` crate.
 /// - encode(data: bytes) -> string: Convert bytes to lowercase hex string
 /// - decode(s: string) -> bytes throws DecodeError: Convert hex string to bytes
 ///
@@ -19,8 +20,9 @@ pub unsafe extern "C" fn naml_encoding_hex_encode(b: *const NamlBytes) -> *mut N
 
     unsafe {
         let len = (*b).len;
-        let data = std::slice::from_raw_parts((*b).data.as_ptr(), len);
-        let hex_string = hex::encode(data);
+        let data = std::slice::from_raw_parts((*b).as_ptr(), len);
+        let mut hex_string = vec![0u8; len * 2];
+        naml_std_core::simd::hex_encode(data, &mut hex_string);
         naml_std_core::value::naml_string_new(hex_string.as_ptr(), hex_string.len())
     }
 }
@@ -45,7 +47,7 @@ pub unsafe extern "C" fn naml_encoding_hex_decode(
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let hex_str = std::str::from_utf8_unchecked(data);
 
         match hex::decode(hex_str) {
@@ -108,7 +110,7 @@ mod tests {
             let data = [0x48, 0x65, 0x6c, 0x6c, 0x6f];
             let bytes = create_bytes_from(data.as_ptr(), 5);
             let result = naml_encoding_hex_encode(bytes);
-            let s = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let s = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             assert_eq!(s, b"48656c6c6f");
         }
     }
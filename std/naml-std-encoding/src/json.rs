@@ -93,7 +93,7 @@ pub unsafe extern "C" fn naml_json_decode(
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let json_str = std::str::from_utf8_unchecked(data);
 
         match serde_json::from_str(json_str) {
@@ -149,7 +149,7 @@ pub unsafe extern "C" fn naml_json_exists(json: *const NamlJson, key: *const Nam
     unsafe {
         let value = &(*json).value;
         let key_len = (*key).len;
-        let key_data = std::slice::from_raw_parts((*key).data.as_ptr(), key_len);
+        let key_data = std::slice::from_raw_parts((*key).as_ptr(), key_len);
         let key_str = std::str::from_utf8_unchecked(key_data);
 
         match value {
@@ -195,7 +195,7 @@ pub unsafe extern "C" fn naml_json_path(
         }
 
         let path_len = (*path_str).len;
-        let path_data = std::slice::from_raw_parts((*path_str).data.as_ptr(), path_len);
+        let path_data = std::slice::from_raw_parts((*path_str).as_ptr(), path_len);
         let path = std::str::from_utf8_unchecked(path_data);
 
         match navigate_path(value, path) {
@@ -373,7 +373,7 @@ pub unsafe extern "C" fn naml_json_index_string(
     unsafe {
         let value = &(*json).value;
         let key_len = (*key).len;
-        let key_data = std::slice::from_raw_parts((*key).data.as_ptr(), key_len);
+        let key_data = std::slice::from_raw_parts((*key).as_ptr(), key_len);
         let key_str = std::str::from_utf8_unchecked(key_data);
 
         let result = value.get(key_str).cloned().unwrap_or(Value::Null);
@@ -557,7 +557,7 @@ pub unsafe extern "C" fn naml_json_from_string(s: *const NamlString) -> *mut Nam
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let string = std::str::from_utf8_unchecked(data).to_string();
         create_json(Value::String(string))
     }
@@ -608,7 +608,7 @@ pub unsafe extern "C" fn naml_json_object_set(
     unsafe {
         if let Value::Object(ref mut map) = (*obj).value {
             let key_len = (*key).len;
-            let key_data = std::slice::from_raw_parts((*key).data.as_ptr(), key_len);
+            let key_data = std::slice::from_raw_parts((*key).as_ptr(), key_len);
             let key_str = std::str::from_utf8_unchecked(key_data).to_string();
 
             let val = if value.is_null() {
@@ -658,7 +658,7 @@ mod tests {
         unsafe {
             let json = create_json(serde_json::json!({"name": "test"}));
             let result = naml_json_encode(json);
-            let s = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let s = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             assert_eq!(s, br#"{"name":"test"}"#);
         }
     }
@@ -683,7 +683,7 @@ mod tests {
             let result = value as *const NamlJson;
             let result_str = naml_json_as_string(result);
             assert!(!result_str.is_null());
-            let s = std::slice::from_raw_parts((*result_str).data.as_ptr(), (*result_str).len);
+            let s = std::slice::from_raw_parts((*result_str).as_ptr(), (*result_str).len);
             assert_eq!(s, b"Alice");
         }
     }
@@ -5,8 +5,14 @@
 /// - utf8: String <-> bytes UTF-8 conversion
 /// - hex: Bytes <-> hex string conversion
 /// - base64: Bytes <-> base64 string conversion
+/// - base32: Bytes <-> base32 string conversion
+/// - base58: Bytes <-> base58 / base58check string conversion
+/// - punycode: Unicode <-> punycode and IDNA domain conversion
 /// - url: URL percent-encoding/decoding
 /// - json: JSON parsing and serialization
+/// - csv: CSV parsing and serialization
+/// - msgpack: MessagePack encoding and decoding
+/// - cbor: CBOR encoding and decoding
 ///
 /// All decode functions can throw DecodeError on invalid input.
 ///
@@ -14,34 +20,51 @@
 pub mod utf8;
 pub mod hex;
 pub mod base64;
+pub mod base32;
+pub mod base58;
+pub mod punycode;
 pub mod url;
 pub mod json;
 pub mod toml;
 pub mod yaml;
+pub mod csv;
+pub mod msgpack;
+pub mod cbor;
 pub mod binary;
 
 pub use utf8::*;
 pub use hex::*;
 pub use base64::*;
+pub use base32::*;
+pub use base58::*;
+pub use punycode::*;
 pub use url::*;
 pub use json::*;
 pub use toml::*;
 pub use yaml::*;
+pub use csv::*;
+pub use msgpack::*;
+pub use cbor::*;
 pub use binary::*;
 
 use naml_std_core::value::NamlString;
 
-/// Create a DecodeError exception struct
-/// Returns a pointer to a struct with: message (string), position (int)
+/// Create a DecodeError exception
+/// Layout: message@0, stack@8, position@16 (matches other exception types)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_decode_error_new(
     message: *const NamlString,
     position: i64,
-) -> *mut naml_std_core::NamlStruct {
+) -> *mut u8 {
     unsafe {
-        let exc = naml_std_core::naml_struct_new(0xFFFF_0003, 2);
-        naml_std_core::naml_struct_set_field(exc, 0, message as i64);
-        naml_std_core::naml_struct_set_field(exc, 1, position);
-        exc
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate DecodeError");
+        }
+        *(ptr as *mut i64) = message as i64;
+        *(ptr.add(8) as *mut i64) = 0;
+        *(ptr.add(16) as *mut i64) = position;
+        ptr
     }
 }
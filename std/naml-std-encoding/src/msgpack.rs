@@ -0,0 +1,181 @@
+///
+/// std::encoding::msgpack - MessagePack Encoding/Decoding
+///
+/// Converts between json values and MessagePack, using the same value
+/// model as the json module:
+/// - encode(value: json) -> bytes: Serialize to MessagePack
+/// - decode(data: bytes) -> json throws DecodeError: Parse one MessagePack value
+///
+/// Integers keep their exact value and floats are written as float 64.
+/// When decoding, binary data becomes an array of byte values, map keys
+/// that are not strings are converted to their JSON text (`1`, `true`),
+/// and NaN or infinite floats become null. Extension types are rejected.
+/// DecodeError's position is the byte offset where decoding stopped; for
+/// an extension type it is 0.
+///
+
+use naml_std_core::bytes::{naml_bytes_from, NamlBytes};
+use rmpv::Value as MsgValue;
+use serde_json::{Map, Number, Value};
+
+use crate::json::{create_json, NamlJson};
+
+fn json_to_msgpack(value: &Value) -> MsgValue {
+    match value {
+        Value::Null => MsgValue::Nil,
+        Value::Bool(b) => MsgValue::Boolean(*b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                MsgValue::from(i)
+            } else if let Some(u) = n.as_u64() {
+                MsgValue::from(u)
+            } else {
+                MsgValue::F64(n.as_f64().unwrap_or(0.0))
+            }
+        }
+        Value::String(s) => MsgValue::from(s.as_str()),
+        Value::Array(items) => MsgValue::Array(items.iter().map(json_to_msgpack).collect()),
+        Value::Object(map) => MsgValue::Map(
+            map.iter()
+                .map(|(k, v)| (MsgValue::from(k.as_str()), json_to_msgpack(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Convert a decoded value, or None if it contains an extension type
+fn msgpack_to_json(value: MsgValue) -> Option<Value> {
+    Some(match value {
+        MsgValue::Nil => Value::Null,
+        MsgValue::Boolean(b) => Value::Bool(b),
+        MsgValue::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(n), _) => Value::from(n),
+            (None, Some(n)) => Value::from(n),
+            (None, None) => Value::Null,
+        },
+        MsgValue::F32(f) => Number::from_f64(f as f64).map_or(Value::Null, Value::Number),
+        MsgValue::F64(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        MsgValue::String(s) => Value::String(String::from_utf8_lossy(s.as_bytes()).into_owned()),
+        MsgValue::Binary(data) => Value::Array(data.into_iter().map(Value::from).collect()),
+        MsgValue::Array(items) => {
+            Value::Array(items.into_iter().map(msgpack_to_json).collect::<Option<_>>()?)
+        }
+        MsgValue::Map(entries) => {
+            let mut map = Map::new();
+            for (k, v) in entries {
+                let key = match msgpack_to_json(k)? {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                map.insert(key, msgpack_to_json(v)?);
+            }
+            Value::Object(map)
+        }
+        MsgValue::Ext(..) => return None,
+    })
+}
+
+/// Decode exactly one value from `data`, or the byte position of the error
+fn decode_value(data: &[u8]) -> Result<Value, usize> {
+    let mut rest = data;
+    let value = rmpv::decode::read_value(&mut rest).map_err(|_| data.len() - rest.len())?;
+    if !rest.is_empty() {
+        return Err(data.len() - rest.len());
+    }
+    msgpack_to_json(value).ok_or(0)
+}
+
+/// Encode a json value as MessagePack bytes
+///
+/// # Safety
+/// The caller must ensure `json` is a valid pointer to a NamlJson or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_msgpack_encode(json: *const NamlJson) -> *mut NamlBytes {
+    let value = if json.is_null() {
+        MsgValue::Nil
+    } else {
+        unsafe { json_to_msgpack((*json).get_value()) }
+    };
+    let mut out = Vec::new();
+    // Writing into a Vec cannot fail
+    let _ = rmpv::encode::write_value(&mut out, &value);
+    unsafe { naml_bytes_from(out.as_ptr(), out.len()) }
+}
+
+/// Decode MessagePack bytes into a json value
+/// Returns via out parameters:
+/// tag = 0: success, value = NamlJson pointer
+/// tag = 1: error, value = byte position of the error
+///
+/// # Safety
+/// The caller must ensure `b` is a valid pointer to a NamlBytes or null,
+/// `out_tag` is a valid pointer to an i32 and `out_value` is a valid pointer to
+/// an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_msgpack_decode(
+    b: *const NamlBytes,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    let data = if b.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts((*b).as_ptr(), (*b).len) }
+    };
+    unsafe {
+        match decode_value(data) {
+            Ok(value) => {
+                *out_tag = 0;
+                *out_value = create_json(value) as i64;
+            }
+            Err(position) => {
+                *out_tag = 1;
+                *out_value = position as i64;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut out = Vec::new();
+        rmpv::encode::write_value(&mut out, &json_to_msgpack(value)).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = json!({
+            "name": "naml",
+            "tags": ["a", "b"],
+            "count": -3,
+            "big": u64::MAX,
+            "ratio": 0.5,
+            "ok": true,
+            "none": null
+        });
+        assert_eq!(decode_value(&encode(&value)), Ok(value));
+        assert_eq!(encode(&json!(5)), vec![0x05]);
+        assert_eq!(encode(&json!("hi")), vec![0xa2, b'h', b'i']);
+    }
+
+    #[test]
+    fn test_decode_mapping_and_errors() {
+        let mut data = Vec::new();
+        let map = MsgValue::Map(vec![
+            (MsgValue::from(1), MsgValue::Binary(vec![7, 8])),
+            (MsgValue::from(true), MsgValue::F64(f64::NAN)),
+        ]);
+        rmpv::encode::write_value(&mut data, &map).unwrap();
+        assert_eq!(decode_value(&data), Ok(json!({"1": [7, 8], "true": null})));
+
+        assert_eq!(decode_value(&[0x92, 0x01]), Err(2));
+        assert_eq!(decode_value(&[0x01, 0x02]), Err(1));
+        assert_eq!(decode_value(&[0xd4, 0x01, 0x00]), Err(0));
+        assert_eq!(decode_value(&[]), Err(0));
+    }
+}
@@ -0,0 +1,204 @@
+///
+/// std::encoding::punycode - Punycode and IDNA Domain Conversion
+///
+/// Provides Unicode <-> ASCII conversion (RFC 3492 / UTS #46) using the `idna` crate.
+/// - encode(s: string) -> string: Punycode-encode a single label (no `xn--` prefix)
+/// - decode(s: string) -> string throws DecodeError: Decode a single punycode label
+/// - to_ascii(domain: string) -> string throws DecodeError: Convert an internationalized
+///   domain name to its `xn--` ASCII form
+/// - to_unicode(domain: string) -> string throws DecodeError: Convert an ASCII domain
+///   name with `xn--` labels back to Unicode
+///
+/// Punycode input must be ASCII; the first non-ASCII byte is reported as the error
+/// position. Other failures report the position just past the end of the input.
+///
+
+use naml_std_core::value::NamlString;
+
+/// Punycode-encode a string
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_punycode_encode(s: *const NamlString) -> *mut NamlString {
+    if s.is_null() {
+        return unsafe { naml_std_core::value::naml_string_new(std::ptr::null(), 0) };
+    }
+
+    unsafe {
+        let len = (*s).len;
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
+        let str_val = std::str::from_utf8_unchecked(data);
+        // Only fails on overflow for inputs far beyond any DNS label length
+        let encoded = idna::punycode::encode_str(str_val).unwrap_or_default();
+        naml_std_core::value::naml_string_new(encoded.as_ptr(), encoded.len())
+    }
+}
+
+/// Decode a punycode string
+/// Returns via out parameters:
+/// tag = 0: success, value = string pointer
+/// tag = 1: error, value = position of invalid input
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString, `out_tag` is
+/// a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_punycode_decode(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        convert_with(s, out_tag, out_value, |input| {
+            if let Some(pos) = input.bytes().position(|b| !b.is_ascii()) {
+                return Err(pos);
+            }
+            idna::punycode::decode_to_string(input).ok_or(input.len())
+        })
+    }
+}
+
+/// Convert a domain name to its ASCII (`xn--`) form using strict STD3 DNS rules
+/// Returns via out parameters:
+/// tag = 0: success, value = string pointer
+/// tag = 1: error, value = input length
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString, `out_tag` is
+/// a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_punycode_to_ascii(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        convert_with(s, out_tag, out_value, |input| {
+            idna::domain_to_ascii_strict(input).map_err(|_| input.len())
+        })
+    }
+}
+
+/// Convert an ASCII domain name with `xn--` labels to Unicode
+/// Returns via out parameters:
+/// tag = 0: success, value = string pointer
+/// tag = 1: error, value = input length
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString, `out_tag` is
+/// a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_encoding_punycode_to_unicode(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        convert_with(s, out_tag, out_value, |input| {
+            let (unicode, result) = idna::domain_to_unicode(input);
+            result.map(|_| unicode).map_err(|_| input.len())
+        })
+    }
+}
+
+unsafe fn convert_with(
+    s: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+    convert: impl Fn(&str) -> Result<String, usize>,
+) {
+    if s.is_null() {
+        unsafe {
+            *out_tag = 0;
+            *out_value = naml_std_core::value::naml_string_new(std::ptr::null(), 0) as i64;
+        }
+        return;
+    }
+
+    unsafe {
+        let len = (*s).len;
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
+        let str_val = std::str::from_utf8_unchecked(data);
+
+        match convert(str_val) {
+            Ok(converted) => {
+                let result = naml_std_core::value::naml_string_new(converted.as_ptr(), converted.len());
+                *out_tag = 0;
+                *out_value = result as i64;
+            }
+            Err(pos) => {
+                *out_tag = 1;
+                *out_value = pos as i64;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn string_of(s: *const NamlString) -> String {
+        unsafe {
+            let data = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+            String::from_utf8(data.to_vec()).unwrap()
+        }
+    }
+
+    unsafe fn run(
+        convert: unsafe extern "C" fn(*const NamlString, *mut i32, *mut i64),
+        input: &str,
+    ) -> (i32, i64) {
+        unsafe {
+            let s = naml_std_core::value::naml_string_new(input.as_ptr(), input.len());
+            let mut tag: i32 = -1;
+            let mut value: i64 = 0;
+            convert(s, &mut tag, &mut value);
+            (tag, value)
+        }
+    }
+
+    #[test]
+    fn test_punycode_encode() {
+        unsafe {
+            let input = "bücher";
+            let s = naml_std_core::value::naml_string_new(input.as_ptr(), input.len());
+            assert_eq!(string_of(naml_encoding_punycode_encode(s)), "bcher-kva");
+        }
+    }
+
+    #[test]
+    fn test_punycode_decode() {
+        unsafe {
+            let (tag, value) = run(naml_encoding_punycode_decode, "bcher-kva");
+            assert_eq!(tag, 0);
+            assert_eq!(string_of(value as *const NamlString), "bücher");
+
+            let (tag, value) = run(naml_encoding_punycode_decode, "bü-kva");
+            assert_eq!(tag, 1);
+            assert_eq!(value, 1);
+        }
+    }
+
+    #[test]
+    fn test_domain_roundtrip() {
+        unsafe {
+            let (tag, value) = run(naml_encoding_punycode_to_ascii, "münchen.de");
+            assert_eq!(tag, 0);
+            assert_eq!(string_of(value as *const NamlString), "xn--mnchen-3ya.de");
+
+            let (tag, value) = run(naml_encoding_punycode_to_unicode, "xn--mnchen-3ya.de");
+            assert_eq!(tag, 0);
+            assert_eq!(string_of(value as *const NamlString), "münchen.de");
+        }
+    }
+
+    #[test]
+    fn test_to_ascii_invalid() {
+        unsafe {
+            let (tag, _) = run(naml_encoding_punycode_to_ascii, "exa mple.com");
+            assert_eq!(tag, 1);
+        }
+    }
+}
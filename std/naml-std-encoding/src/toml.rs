@@ -36,7 +36,7 @@ pub unsafe extern "C" fn naml_encoding_toml_decode(
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let toml_str = std::str::from_utf8_unchecked(data);
 
         match toml_str.parse::<toml::Value>() {
@@ -17,7 +17,7 @@ pub unsafe extern "C" fn naml_encoding_url_encode(s: *const NamlString) -> *mut
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let str_val = std::str::from_utf8_unchecked(data);
         let encoded = urlencoding::encode(str_val);
         naml_std_core::value::naml_string_new(encoded.as_ptr(), encoded.len())
@@ -44,7 +44,7 @@ pub unsafe extern "C" fn naml_encoding_url_decode(
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let str_val = std::str::from_utf8_unchecked(data);
 
         // Validate percent sequences first (urlencoding crate passes through invalid ones)
@@ -100,7 +100,7 @@ mod tests {
         unsafe {
             let s = naml_std_core::value::naml_string_new(b"hello world".as_ptr(), 11);
             let result = naml_encoding_url_encode(s);
-            let encoded = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let encoded = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             assert_eq!(encoded, b"hello%20world");
         }
     }
@@ -110,7 +110,7 @@ mod tests {
         unsafe {
             let s = naml_std_core::value::naml_string_new(b"a&b=c".as_ptr(), 5);
             let result = naml_encoding_url_encode(s);
-            let encoded = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let encoded = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             assert_eq!(encoded, b"a%26b%3Dc");
         }
     }
@@ -124,7 +124,7 @@ mod tests {
             naml_encoding_url_decode(s, &mut tag, &mut value);
             assert_eq!(tag, 0);
             let result = value as *const NamlString;
-            let decoded = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let decoded = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             assert_eq!(decoded, b"hello world");
         }
     }
@@ -138,7 +138,7 @@ mod tests {
             naml_encoding_url_decode(s, &mut tag, &mut value);
             assert_eq!(tag, 0);
             let result = value as *const NamlString;
-            let decoded = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let decoded = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             // urlencoding crate keeps + as +, doesn't convert to space
             // This is RFC 3986 compliant (+ is only space in application/x-www-form-urlencoded)
             assert_eq!(decoded, b"hello+world");
@@ -20,7 +20,7 @@ pub unsafe extern "C" fn naml_encoding_utf8_encode(s: *const NamlString) -> *mut
 
     unsafe {
         let len = (*s).len;
-        let data = (*s).data.as_ptr();
+        let data = (*s).as_ptr();
         create_bytes_from(data, len)
     }
 }
@@ -45,7 +45,7 @@ pub unsafe extern "C" fn naml_encoding_utf8_decode(
 
     unsafe {
         let len = (*b).len;
-        let data = std::slice::from_raw_parts((*b).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*b).as_ptr(), len);
 
         match std::str::from_utf8(data) {
             Ok(_valid_str) => {
@@ -70,7 +70,7 @@ pub unsafe extern "C" fn naml_encoding_utf8_is_valid(b: *const NamlBytes) -> i64
 
     unsafe {
         let len = (*b).len;
-        let data = std::slice::from_raw_parts((*b).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*b).as_ptr(), len);
         if std::str::from_utf8(data).is_ok() { 1 } else { 0 }
     }
 }
@@ -35,7 +35,7 @@ pub unsafe extern "C" fn naml_encoding_yaml_decode(
 
     unsafe {
         let len = (*s).len;
-        let data = std::slice::from_raw_parts((*s).data.as_ptr(), len);
+        let data = std::slice::from_raw_parts((*s).as_ptr(), len);
         let yaml_str = std::str::from_utf8_unchecked(data);
 
         match serde_yaml::from_str::<serde_yaml::Value>(yaml_str) {
@@ -32,7 +32,7 @@ unsafe fn string_from_naml(s: *const NamlString) -> String {
         return String::new();
     }
     unsafe {
-        let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
         String::from_utf8_lossy(slice).into_owned()
     }
 }
@@ -0,0 +1,140 @@
+///
+/// Flag Definitions and Bucketing
+///
+/// A flag document is a JSON object mapping flag names to either a bool or
+/// an object with an optional `enabled` bool (default true) and an optional
+/// `weights` array of non-negative integers, one per variant:
+///
+/// ```json
+/// { "dark_mode": true, "checkout": { "enabled": true, "weights": [90, 10] } }
+/// ```
+///
+/// Users are bucketed by the 64-bit FNV-1a hash of `name:user_id`, so a
+/// user keeps the same variant across processes and restarts, and different
+/// flags bucket users independently.
+///
+
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Flag {
+    pub enabled: bool,
+    pub weights: Vec<u64>,
+}
+
+/// Parse a flag document
+pub(crate) fn parse_flags(text: &str) -> Result<HashMap<String, Flag>, String> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| "flag document must be a JSON object".to_string())?;
+
+    let mut flags = HashMap::new();
+    for (name, value) in object {
+        let flag = match value {
+            serde_json::Value::Bool(enabled) => Flag { enabled: *enabled, weights: Vec::new() },
+            serde_json::Value::Object(fields) => {
+                let enabled = match fields.get("enabled") {
+                    None => true,
+                    Some(v) => v
+                        .as_bool()
+                        .ok_or_else(|| format!("flag '{}': enabled must be a bool", name))?,
+                };
+                let weights = match fields.get("weights") {
+                    None => Vec::new(),
+                    Some(v) => v
+                        .as_array()
+                        .and_then(|items| items.iter().map(|w| w.as_u64()).collect())
+                        .ok_or_else(|| {
+                            format!("flag '{}': weights must be non-negative integers", name)
+                        })?,
+                };
+                Flag { enabled, weights }
+            }
+            _ => return Err(format!("flag '{}' must be a bool or an object", name)),
+        };
+        flags.insert(name.clone(), flag);
+    }
+    Ok(flags)
+}
+
+/// 64-bit FNV-1a hash of `name:user_id`
+pub(crate) fn bucket_hash(name: &str, user_id: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in name.bytes().chain(std::iter::once(b':')).chain(user_id.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Index of the variant a user falls into, out of `count` variants.
+/// Disabled and unknown flags always give variant 0. Weights are used when
+/// there is one per variant and they are not all zero; otherwise variants
+/// are equally likely.
+pub(crate) fn variant_index(flag: Option<&Flag>, name: &str, user_id: &str, count: usize) -> usize {
+    let Some(flag) = flag.filter(|f| f.enabled) else {
+        return 0;
+    };
+    let hash = bucket_hash(name, user_id);
+    let total: u64 = flag.weights.iter().sum();
+    if flag.weights.len() != count || total == 0 {
+        return (hash % count as u64) as usize;
+    }
+    let mut point = hash % total;
+    for (index, weight) in flag.weights.iter().enumerate() {
+        if point < *weight {
+            return index;
+        }
+        point -= weight;
+    }
+    count - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_flags() {
+        let flags = parse_flags(
+            r#"{"dark_mode": true, "beta": false, "checkout": {"weights": [90, 10]}}"#,
+        )
+        .unwrap();
+        assert!(flags["dark_mode"].enabled);
+        assert!(!flags["beta"].enabled);
+        assert_eq!(flags["checkout"], Flag { enabled: true, weights: vec![90, 10] });
+
+        assert!(parse_flags("[]").is_err());
+        assert!(parse_flags(r#"{"x": 1}"#).is_err());
+        assert!(parse_flags(r#"{"x": {"weights": [-1]}}"#).is_err());
+    }
+
+    #[test]
+    fn test_bucketing_is_stable() {
+        // Fixed value so bucketing never changes between releases
+        assert_eq!(bucket_hash("checkout", "user-1"), 0x76a6_ee17_cb59_7ee0);
+        let flag = Flag { enabled: true, weights: Vec::new() };
+        let first = variant_index(Some(&flag), "checkout", "user-1", 3);
+        for _ in 0..10 {
+            assert_eq!(variant_index(Some(&flag), "checkout", "user-1", 3), first);
+        }
+    }
+
+    #[test]
+    fn test_variant_distribution() {
+        let weighted = Flag { enabled: true, weights: vec![90, 10] };
+        let mut counts = [0usize; 2];
+        for user in 0..10_000 {
+            counts[variant_index(Some(&weighted), "checkout", &user.to_string(), 2)] += 1;
+        }
+        assert!((8_500..9_500).contains(&counts[0]), "counts: {:?}", counts);
+
+        let disabled = Flag { enabled: false, weights: vec![0, 100] };
+        assert_eq!(variant_index(Some(&disabled), "checkout", "user-1", 2), 0);
+        assert_eq!(variant_index(None, "checkout", "user-1", 2), 0);
+
+        let all_second = Flag { enabled: true, weights: vec![0, 100] };
+        assert_eq!(variant_index(Some(&all_second), "checkout", "user-1", 2), 1);
+    }
+}
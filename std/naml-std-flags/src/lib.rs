@@ -0,0 +1,226 @@
+///
+/// naml-std-flags - Feature Flags and A/B Bucketing
+///
+/// A lightweight flag client: flags are loaded from a JSON document and kept
+/// fresh by a background thread that re-reads the source.
+///
+/// ## Functions
+///
+/// - `open_flags(source: string) throws IOError` - Load flags from a JSON file or HTTP(S) URL
+/// - `set_poll_interval(ms: int)` - How often the source is re-read (0 disables polling)
+/// - `close_flags()` - Stop polling and forget all flags
+/// - `enabled(name: string, default: bool) -> bool` - Whether a flag is on
+/// - `variant(name: string, user_id: string, variants: [string]) -> string` - Stable per-user variant
+///
+/// ## Sources
+///
+/// A source starting with `http://` or `https://` is fetched with a GET
+/// request using the HTTP client's timeout, proxy and retry settings; any
+/// other source is a file path. The first load must succeed. A failed
+/// refresh keeps the previous flags.
+///
+/// Opening a new source replaces the previous one and its flags. The flag
+/// set is process-wide and can be read from any thread.
+///
+
+mod flag;
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+use naml_std_core::{naml_array_get, naml_array_len, naml_string_new, NamlArray, NamlString};
+
+use flag::{parse_flags, variant_index, Flag};
+
+/// Default time between refreshes of the flag source
+const DEFAULT_POLL_INTERVAL_MS: u64 = 30_000;
+
+/// Longest a poller sleeps before noticing a new interval or source
+const MAX_TICK: Duration = Duration::from_secs(1);
+
+static POLL_INTERVAL_MS: AtomicU64 = AtomicU64::new(DEFAULT_POLL_INTERVAL_MS);
+
+/// Bumped whenever the source changes, stopping the previous poller
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+static FLAGS: LazyLock<RwLock<HashMap<String, Flag>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+/// Read and parse the flag document at `source`
+fn load_source(source: &str) -> io::Result<HashMap<String, Flag>> {
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        let (status, body) = naml_std_net::http::client::fetch("GET", source, None, Vec::new())?;
+        if !(200..300).contains(&status) {
+            return Err(io::Error::other(format!("HTTP {} fetching flags", status)));
+        }
+        String::from_utf8(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+    } else {
+        std::fs::read_to_string(source)?
+    };
+    parse_flags(&text).map_err(|e| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("Invalid flag document: {}", e))
+    })
+}
+
+/// Re-read `source` until `generation` is superseded
+fn poll(source: String, generation: u64) {
+    let mut last_refresh = Instant::now();
+    loop {
+        let interval = Duration::from_millis(POLL_INTERVAL_MS.load(Ordering::SeqCst));
+        std::thread::sleep(if interval.is_zero() { MAX_TICK } else { interval.min(MAX_TICK) });
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let interval = Duration::from_millis(POLL_INTERVAL_MS.load(Ordering::SeqCst));
+        if interval.is_zero() || last_refresh.elapsed() < interval {
+            continue;
+        }
+        last_refresh = Instant::now();
+        if let Ok(flags) = load_source(&source)
+            && GENERATION.load(Ordering::SeqCst) == generation
+        {
+            *FLAGS.write().unwrap() = flags;
+        }
+    }
+}
+
+/// Load flags from `source` and keep them fresh in the background
+///
+/// # Safety
+/// The caller must ensure `source` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_flags_open(source: *const NamlString) {
+    let source = unsafe { string_from_naml(source) };
+    let flags = match load_source(&source) {
+        Ok(flags) => flags,
+        Err(e) => {
+            naml_std_fs::throw_io_error(e, &source);
+            return;
+        }
+    };
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *FLAGS.write().unwrap() = flags;
+    std::thread::spawn(move || poll(source, generation));
+}
+
+/// Set the time between refreshes; 0 disables polling
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_flags_set_poll_interval(ms: i64) {
+    POLL_INTERVAL_MS.store(ms.max(0) as u64, Ordering::SeqCst);
+}
+
+/// Stop polling and forget all flags
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_flags_close() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    FLAGS.write().unwrap().clear();
+}
+
+/// Whether flag `name` is on, or `default` when no flag has that name
+///
+/// # Safety
+/// The caller must ensure `name` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_flags_enabled(name: *const NamlString, default: i64) -> i64 {
+    let name = unsafe { string_from_naml(name) };
+    match FLAGS.read().unwrap().get(&name) {
+        Some(flag) => flag.enabled as i64,
+        None => default,
+    }
+}
+
+/// The variant `user_id` is bucketed into for flag `name`
+///
+/// Returns the first variant when the flag is off or unknown, and an empty
+/// string when `variants` is empty.
+///
+/// # Safety
+/// The caller must ensure `name` is a valid pointer to a NamlString, `user_id`
+/// is a valid pointer to a NamlString and `variants` is a valid pointer to a
+/// NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_flags_variant(
+    name: *const NamlString,
+    user_id: *const NamlString,
+    variants: *const NamlArray,
+) -> *mut NamlString {
+    let count = if variants.is_null() { 0 } else { unsafe { naml_array_len(variants) } };
+    if count == 0 {
+        return unsafe { naml_from_string("") };
+    }
+    let name = unsafe { string_from_naml(name) };
+    let user_id = unsafe { string_from_naml(user_id) };
+    let index = variant_index(FLAGS.read().unwrap().get(&name), &name, &user_id, count as usize);
+    unsafe {
+        let chosen = naml_array_get(variants, index as i64) as *const NamlString;
+        naml_from_string(&string_from_naml(chosen))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_source_file() {
+        let path = std::env::temp_dir().join(format!("naml_flags_{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"dark_mode": true}"#).unwrap();
+        let flags = load_source(path.to_str().unwrap()).unwrap();
+        assert!(flags["dark_mode"].enabled);
+
+        std::fs::write(&path, "not json").unwrap();
+        let err = load_source(path.to_str().unwrap()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(load_source(path.to_str().unwrap()).is_err());
+    }
+
+    /// Answer each connection with `status` and `body`
+    fn serve(status: &'static str, body: &'static str, connections: usize) -> String {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(connections) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}/flags.json", addr)
+    }
+
+    #[test]
+    fn test_load_source_http() {
+        let url = serve("200 OK", r#"{"beta": {"weights": [1, 1]}}"#, 1);
+        let flags = load_source(&url).unwrap();
+        assert_eq!(flags["beta"].weights, vec![1, 1]);
+
+        let url = serve("404 Not Found", "missing", 1);
+        let err = load_source(&url).unwrap_err();
+        assert!(err.to_string().contains("404"), "got: {}", err);
+    }
+}
@@ -0,0 +1,160 @@
+///
+/// Async File I/O
+///
+/// Runs file operations on a pool of blocking threads so a long read or
+/// directory scan does not block the calling task or a scheduler worker.
+/// Each function returns a `channel<T>` that acts as a future. `await_io`
+/// blocks until the operation finishes and yields its result, throwing the
+/// IOError (or PermissionError) the synchronous call would have thrown.
+/// `receive(ch)` also works and yields `none` on failure.
+///
+/// Paths and contents are copied before dispatch, so the caller may release
+/// its strings immediately.
+///
+/// Functions:
+/// - `read_async(path) -> channel<string>` - Read a file as UTF-8
+/// - `write_async(path, content) -> channel<int>` - Write a file, yields the bytes written
+/// - `list_dir_async(path) -> channel<[string]>` - List directory contents
+/// - `await_io(pending) -> T` - Wait for an async operation, throws IOError
+///
+
+use naml_std_core::{naml_array_new, naml_array_push, naml_string_new, NamlString};
+use naml_std_threads::{
+    naml_channel_receive, spawn_completion, take_channel_failure, Failure, NamlChannel,
+};
+
+use crate::{list_dir_entries, path_from_naml_string, throw_io_error};
+
+/// Why an async operation failed, recorded on its channel
+struct IoFailure {
+    error: std::io::Error,
+    path: String,
+}
+
+fn io_failure(error: std::io::Error, path: &str) -> Failure {
+    Box::new(IoFailure {
+        error,
+        path: path.to_string(),
+    })
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_read_async(path: *const NamlString) -> *mut NamlChannel {
+    let path_str = unsafe { path_from_naml_string(path) };
+    spawn_completion(move || {
+        let content = std::fs::read_to_string(&path_str).map_err(|e| io_failure(e, &path_str))?;
+        Ok(unsafe { naml_string_new(content.as_ptr(), content.len()) } as i64)
+    })
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString and
+/// `content` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_write_async(
+    path: *const NamlString,
+    content: *const NamlString,
+) -> *mut NamlChannel {
+    let path_str = unsafe { path_from_naml_string(path) };
+    let data = if content.is_null() {
+        Vec::new()
+    } else {
+        unsafe { (*content).as_bytes().to_vec() }
+    };
+    spawn_completion(move || {
+        std::fs::write(&path_str, &data).map_err(|e| io_failure(e, &path_str))?;
+        Ok(data.len() as i64)
+    })
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_list_dir_async(path: *const NamlString) -> *mut NamlChannel {
+    let path_str = unsafe { path_from_naml_string(path) };
+    spawn_completion(move || {
+        let entries = list_dir_entries(&path_str).map_err(|e| io_failure(e, &path_str))?;
+        unsafe {
+            let arr = naml_array_new(entries.len());
+            for entry in &entries {
+                naml_array_push(arr, naml_string_new(entry.as_ptr(), entry.len()) as i64);
+            }
+            Ok(arr as i64)
+        }
+    })
+}
+
+/// Wait for an async operation and return its result. If it failed, throws
+/// the IOError or PermissionError it failed with and returns 0. Awaiting a
+/// channel whose result was already received throws IOError.
+///
+/// # Safety
+/// The caller must ensure `pending` is a valid pointer to a NamlChannel or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_await_io(pending: *mut NamlChannel) -> i64 {
+    let mut value = 0;
+    if unsafe { naml_channel_receive(pending, &mut value) } == 1 {
+        return value;
+    }
+    let failure = unsafe { take_channel_failure(pending) }
+        .and_then(|failure| failure.downcast::<IoFailure>().ok());
+    match failure {
+        Some(failure) => throw_io_error(failure.error, &failure.path),
+        None => throw_io_error(std::io::Error::other("async operation has no result left to receive"), ""),
+    };
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use naml_std_core::{naml_exception_clear, naml_exception_get};
+    use naml_std_threads::naml_channel_decref;
+
+    unsafe fn receive(ch: *mut NamlChannel) -> Option<i64> {
+        let mut value = 0;
+        let got = unsafe { naml_channel_receive(ch, &mut value) };
+        unsafe { naml_channel_decref(ch) };
+        (got == 1).then_some(value)
+    }
+
+    #[test]
+    fn test_write_then_read_async() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("async.txt").to_string_lossy().into_owned();
+        unsafe {
+            let path_ptr = naml_string_new(path.as_ptr(), path.len());
+            let content = naml_string_new("héllo".as_ptr(), "héllo".len());
+            assert_eq!(receive(naml_fs_write_async(path_ptr, content)), Some(6));
+
+            let read = receive(naml_fs_read_async(path_ptr)).unwrap() as *const NamlString;
+            assert_eq!((*read).as_str(), "héllo");
+
+            let dir_str = dir.path().to_string_lossy().into_owned();
+            let dir_ptr = naml_string_new(dir_str.as_ptr(), dir_str.len());
+            let listed = receive(naml_fs_list_dir_async(dir_ptr)).unwrap();
+            assert_eq!((*(listed as *const naml_std_core::NamlArray)).len, 1);
+        }
+    }
+
+    #[test]
+    fn test_await_io_throws_the_error() {
+        let missing = "/definitely/not/a/real/path.txt";
+        unsafe {
+            let path_ptr = naml_string_new(missing.as_ptr(), missing.len());
+            assert_eq!(receive(naml_fs_read_async(path_ptr)), None);
+
+            let pending = naml_fs_read_async(path_ptr);
+            assert_eq!(naml_fs_await_io(pending), 0);
+            let error = naml_exception_get();
+            assert!(!error.is_null());
+            let path = *(error.add(16) as *const *const NamlString);
+            assert_eq!((*path).as_str(), missing);
+            assert_eq!(*(error.add(24) as *const i64), 2);
+            naml_exception_clear();
+            naml_channel_decref(pending);
+        }
+    }
+}
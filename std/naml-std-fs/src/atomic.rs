@@ -0,0 +1,129 @@
+///
+/// Atomic File Writes
+///
+/// `write_atomic` never leaves a partially written target: the content goes
+/// to a temp file in the target's directory, which is synced to disk and
+/// then renamed over the target. Readers see either the old file or the new
+/// one, even if the process or machine crashes midway.
+///
+/// The temp file lives in the same directory so the rename stays on one
+/// filesystem. An existing target's permissions carry over to the new file;
+/// new files get the same mode `write` would give them. On Unix the
+/// directory is synced after the rename so the new entry survives a crash too.
+///
+/// Functions:
+/// - `write_atomic(path, content)` - Replace a file's content atomically
+///
+
+use std::io::Write;
+use std::path::Path;
+
+use naml_std_core::NamlString;
+
+use crate::{path_from_naml_string, throw_io_error};
+
+/// Write `content` to a synced temp file next to `path` and rename it over `path`
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+
+    let prefix = format!(".{}.", name);
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(&prefix).suffix(".tmp");
+    // New files get the umask-filtered default mode, like `write`, not tempfile's 0600
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(std::fs::Permissions::from_mode(0o666));
+    }
+    let mut temp = builder.tempfile_in(dir)?;
+    temp.write_all(content)?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        temp.as_file().set_permissions(metadata.permissions())?;
+    }
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|e| e.error)?;
+
+    #[cfg(unix)]
+    std::fs::File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+/// Replace the content of a file atomically
+/// Returns 0 on success, sets exception on error
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString and
+/// `content` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_write_atomic(
+    path: *const NamlString,
+    content: *const NamlString,
+) -> i64 {
+    let path_str = unsafe { path_from_naml_string(path) };
+    let bytes = if content.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts((*content).as_ptr(), (*content).len) }
+    };
+
+    match write_atomic(Path::new(&path_str), bytes) {
+        Ok(()) => 0,
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+
+        write_atomic(&path, b"port = 1\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "port = 1\n");
+
+        write_atomic(&path, b"port = 2\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "port = 2\n");
+
+        // No temp files are left behind
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_keeps_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.txt");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let fresh = dir.path().join("fresh.txt");
+        write_atomic(&fresh, b"new").unwrap();
+        std::fs::write(dir.path().join("plain.txt"), "new").unwrap();
+        let plain = std::fs::metadata(dir.path().join("plain.txt")).unwrap().permissions().mode();
+        assert_eq!(std::fs::metadata(&fresh).unwrap().permissions().mode(), plain);
+    }
+
+    #[test]
+    fn test_write_atomic_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("file.txt");
+        assert!(write_atomic(&path, b"x").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+///
+/// Checksummed Copy and Verification
+///
+/// Streams file contents through SHA-256 (shared with naml-std-crypto) so a
+/// copy can be verified without reading the files through separate APIs.
+///
+/// Functions:
+/// - `copy_verified(src, dst)` - Copy a file, then re-read the destination and
+///   compare SHA-256 digests; a mismatch removes `dst` and throws IOError
+/// - `verify_file(path, expected_sha256) -> bool` - Compare a file against a hex digest
+///
+
+use std::fs::File;
+use std::io::{Error, ErrorKind};
+
+use naml_std_core::NamlString;
+use naml_std_crypto::{sha256_copy, sha256_reader};
+
+use crate::{path_from_naml_string, throw_io_error};
+
+fn to_hex(digest: &[u8; 32]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Copy `src` to `dst` and confirm the written data hashes to the same digest.
+/// Errors carry the path they relate to.
+fn copy_with_verify(src: &str, dst: &str) -> Result<(), (Error, String)> {
+    let src_file = File::open(src).map_err(|e| (e, src.to_string()))?;
+    let src_meta = src_file.metadata().map_err(|e| (e, src.to_string()))?;
+
+    // Truncating the destination would destroy the source if both are the same file
+    if let (Ok(a), Ok(b)) = (std::fs::canonicalize(src), std::fs::canonicalize(dst))
+        && a == b
+    {
+        let e = Error::new(ErrorKind::InvalidInput, "source and destination are the same file");
+        return Err((e, dst.to_string()));
+    }
+
+    let mut dst_file = File::create(dst).map_err(|e| (e, dst.to_string()))?;
+    let src_digest = sha256_copy(src_file, &mut dst_file).map_err(|e| (e, dst.to_string()))?;
+    dst_file.sync_all().map_err(|e| (e, dst.to_string()))?;
+    drop(dst_file);
+
+    let dst_digest = File::open(dst)
+        .and_then(sha256_reader)
+        .map_err(|e| (e, dst.to_string()))?;
+    if src_digest != dst_digest {
+        let _ = std::fs::remove_file(dst);
+        let message = format!(
+            "checksum mismatch after copy: expected {}, got {}",
+            to_hex(&src_digest),
+            to_hex(&dst_digest)
+        );
+        return Err((Error::new(ErrorKind::InvalidData, message), dst.to_string()));
+    }
+
+    std::fs::set_permissions(dst, src_meta.permissions()).map_err(|e| (e, dst.to_string()))
+}
+
+/// Check whether the SHA-256 of the file at `path` equals `expected` (hex, any case)
+fn file_matches(path: &str, expected: &str) -> std::io::Result<bool> {
+    let digest = File::open(path).and_then(sha256_reader)?;
+    Ok(to_hex(&digest) == expected.trim().to_ascii_lowercase())
+}
+
+/// Copy file from src to dst, verifying the copy with SHA-256
+/// Returns 0 on success, sets exception on error or checksum mismatch
+///
+/// # Safety
+/// The caller must ensure `src` is a valid pointer to a NamlString and `dst` is
+/// a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_copy_verified(
+    src: *const NamlString,
+    dst: *const NamlString,
+) -> i64 {
+    let src_str = unsafe { path_from_naml_string(src) };
+    let dst_str = unsafe { path_from_naml_string(dst) };
+
+    match copy_with_verify(&src_str, &dst_str) {
+        Ok(()) => 0,
+        Err((e, path)) => {
+            throw_io_error(e, &path);
+            0
+        }
+    }
+}
+
+/// Check a file against an expected hex-encoded SHA-256 digest
+/// Returns 1 if it matches, 0 otherwise, sets exception if the file cannot be read
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString and
+/// `expected_sha256` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_verify_file(
+    path: *const NamlString,
+    expected_sha256: *const NamlString,
+) -> i64 {
+    let path_str = unsafe { path_from_naml_string(path) };
+    let expected = unsafe { path_from_naml_string(expected_sha256) };
+
+    match file_matches(&path_str, &expected) {
+        Ok(matches) => matches as i64,
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+    #[test]
+    fn test_copy_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dst = dir.path().join("dst.txt");
+        std::fs::write(&src, b"hello world").unwrap();
+
+        let (src, dst) = (src.to_string_lossy(), dst.to_string_lossy());
+        copy_with_verify(&src, &dst).unwrap();
+        assert_eq!(std::fs::read(&*dst).unwrap(), b"hello world");
+
+        let (e, path) = copy_with_verify(&src, &src).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidInput);
+        assert_eq!(path, src);
+        assert_eq!(std::fs::read(&*src).unwrap(), b"hello world");
+
+        let missing = dir.path().join("missing").to_string_lossy().into_owned();
+        let (_, path) = copy_with_verify(&missing, &dst).unwrap_err();
+        assert_eq!(path, missing);
+    }
+
+    #[test]
+    fn test_verify_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.txt");
+        std::fs::write(&file, b"hello world").unwrap();
+        let file = file.to_string_lossy();
+
+        assert!(file_matches(&file, HELLO_SHA256).unwrap());
+        assert!(file_matches(&file, &format!(" {} ", HELLO_SHA256.to_uppercase())).unwrap());
+        assert!(!file_matches(&file, "deadbeef").unwrap());
+        assert!(file_matches("/definitely/not/here", HELLO_SHA256).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+///
+/// Disk Space and Filesystem Queries
+///
+/// Reports capacity and type of the filesystem that holds a path, so tools can
+/// check for space before writing large files.
+///
+/// - Unix: statvfs/statfs, with device IDs for filesystem identity
+/// - Windows: GetDiskFreeSpaceExW and GetVolumeInformationW
+///
+/// Functions:
+/// - `disk_free(path) -> int` - Bytes available to the current user
+/// - `disk_total(path) -> int` - Total size of the filesystem in bytes
+/// - `fs_type(path) -> string` - Filesystem type name (e.g. "ext4", "apfs", "NTFS")
+/// - `is_same_filesystem(a, b) -> bool` - Whether two paths live on the same filesystem
+///
+
+use naml_std_core::{naml_string_new, NamlString};
+
+use crate::{path_from_naml_string, throw_io_error};
+
+/// Free and total byte counts for the filesystem containing a path
+struct DiskSpace {
+    available: u64,
+    total: u64,
+}
+
+#[cfg(unix)]
+fn c_path(path: &str) -> std::io::Result<std::ffi::CString> {
+    std::ffi::CString::new(path.as_bytes()).map_err(|_| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path contains null byte")
+    })
+}
+
+// statvfs field widths vary by platform; block counts are 32-bit on macOS
+#[cfg(unix)]
+#[allow(clippy::useless_conversion)]
+fn disk_space(path: &str) -> std::io::Result<DiskSpace> {
+    let c_path = c_path(path)?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let fragment = u64::from(stat.f_frsize);
+    Ok(DiskSpace {
+        available: u64::from(stat.f_bavail) * fragment,
+        total: u64::from(stat.f_blocks) * fragment,
+    })
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn filesystem_type(path: &str) -> std::io::Result<String> {
+    let c_path = c_path(path)?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // f_type's width differs per architecture; the magic numbers fit in 32 bits
+    let magic = stat.f_type as u32;
+    let name = match magic {
+        0xEF53 => "ext4",
+        0x5846_5342 => "xfs",
+        0x9123_683E => "btrfs",
+        0x2FC1_2FC1 => "zfs",
+        0xF2F5_2010 => "f2fs",
+        0xCA45_1A4E => "bcachefs",
+        0x0102_1994 => "tmpfs",
+        0x8584_58F6 => "ramfs",
+        0x794C_7630 => "overlay",
+        0x7371_7368 => "squashfs",
+        0x0000_9660 => "iso9660",
+        0x0000_6969 => "nfs",
+        0xFF53_4D42 => "cifs",
+        0xFE53_4D42 => "smb2",
+        0x0102_1997 => "9p",
+        0x6573_5546 => "fuse",
+        0x0000_4D44 => "vfat",
+        0x2011_BAB0 => "exfat",
+        0x5346_544E => "ntfs",
+        0x0000_9FA0 => "proc",
+        0x6265_6572 => "sysfs",
+        0x6367_7270 => "cgroup2",
+        0x0000_1CD1 => "devpts",
+        _ => return Ok(format!("0x{:x}", magic)),
+    };
+    Ok(name.to_string())
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+fn filesystem_type(path: &str) -> std::io::Result<String> {
+    let c_path = c_path(path)?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    Ok(name.to_string_lossy().into_owned())
+}
+
+#[cfg(unix)]
+fn same_filesystem(a: &str, b: &str) -> Result<bool, (std::io::Error, String)> {
+    use std::os::unix::fs::MetadataExt;
+
+    let meta_a = std::fs::metadata(a).map_err(|e| (e, a.to_string()))?;
+    let meta_b = std::fs::metadata(b).map_err(|e| (e, b.to_string()))?;
+    Ok(meta_a.dev() == meta_b.dev())
+}
+
+#[cfg(windows)]
+fn wide(path: &str) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    std::ffi::OsStr::new(path).encode_wide().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(windows)]
+fn disk_space(path: &str) -> std::io::Result<DiskSpace> {
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path = wide(path);
+    let mut available = 0u64;
+    let mut total = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(path.as_ptr(), &mut available, &mut total, std::ptr::null_mut())
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(DiskSpace { available, total })
+}
+
+/// Volume information for the volume holding a path: (serial number, filesystem name)
+#[cfg(windows)]
+fn volume_info(path: &str) -> std::io::Result<(u32, String)> {
+    use windows_sys::Win32::Storage::FileSystem::{GetVolumeInformationW, GetVolumePathNameW};
+
+    let path = wide(path);
+    let mut root = [0u16; 261];
+    if unsafe { GetVolumePathNameW(path.as_ptr(), root.as_mut_ptr(), root.len() as u32) } == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let mut serial = 0u32;
+    let mut fs_name = [0u16; 261];
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            &mut serial,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            fs_name.as_mut_ptr(),
+            fs_name.len() as u32,
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let len = fs_name.iter().position(|&c| c == 0).unwrap_or(fs_name.len());
+    Ok((serial, String::from_utf16_lossy(&fs_name[..len])))
+}
+
+#[cfg(windows)]
+fn filesystem_type(path: &str) -> std::io::Result<String> {
+    volume_info(path).map(|(_, name)| name)
+}
+
+#[cfg(windows)]
+fn same_filesystem(a: &str, b: &str) -> Result<bool, (std::io::Error, String)> {
+    let (serial_a, _) = volume_info(a).map_err(|e| (e, a.to_string()))?;
+    let (serial_b, _) = volume_info(b).map_err(|e| (e, b.to_string()))?;
+    Ok(serial_a == serial_b)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn unsupported() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, "disk queries are not supported on this platform")
+}
+
+#[cfg(not(any(unix, windows)))]
+fn disk_space(_path: &str) -> std::io::Result<DiskSpace> {
+    Err(unsupported())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn filesystem_type(_path: &str) -> std::io::Result<String> {
+    Err(unsupported())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn same_filesystem(a: &str, _b: &str) -> Result<bool, (std::io::Error, String)> {
+    Err((unsupported(), a.to_string()))
+}
+
+/// Get bytes available to the current user on the filesystem containing path
+/// Returns byte count, sets exception on error
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_disk_free(path: *const NamlString) -> i64 {
+    let path_str = unsafe { path_from_naml_string(path) };
+    match disk_space(&path_str) {
+        Ok(space) => space.available.min(i64::MAX as u64) as i64,
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            0
+        }
+    }
+}
+
+/// Get the total size in bytes of the filesystem containing path
+/// Returns byte count, sets exception on error
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_disk_total(path: *const NamlString) -> i64 {
+    let path_str = unsafe { path_from_naml_string(path) };
+    match disk_space(&path_str) {
+        Ok(space) => space.total.min(i64::MAX as u64) as i64,
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            0
+        }
+    }
+}
+
+/// Get the type name of the filesystem containing path
+/// Returns e.g. "ext4", "tmpfs", "apfs", "NTFS"; sets exception on error
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_fs_type(path: *const NamlString) -> *mut NamlString {
+    let path_str = unsafe { path_from_naml_string(path) };
+    match filesystem_type(&path_str) {
+        Ok(name) => unsafe { naml_string_new(name.as_ptr(), name.len()) },
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Check if two paths are on the same filesystem (rename between them is atomic)
+/// Returns 1 if same, 0 if different, sets exception on error
+///
+/// # Safety
+/// The caller must ensure `path1` is a valid pointer to a NamlString and
+/// `path2` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_is_same_filesystem(
+    path1: *const NamlString,
+    path2: *const NamlString,
+) -> i64 {
+    let path1_str = unsafe { path_from_naml_string(path1) };
+    let path2_str = unsafe { path_from_naml_string(path2) };
+    match same_filesystem(&path1_str, &path2_str) {
+        Ok(same) => same as i64,
+        Err((e, path)) => {
+            throw_io_error(e, &path);
+            0
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_space() {
+        let space = disk_space("/").unwrap();
+        assert!(space.total > 0);
+        assert!(space.available <= space.total);
+        assert!(disk_space("/definitely/not/here").is_err());
+    }
+
+    #[test]
+    fn test_filesystem_type() {
+        let name = filesystem_type("/").unwrap();
+        assert!(!name.is_empty());
+        assert!(filesystem_type("/definitely/not/here").is_err());
+    }
+
+    #[test]
+    fn test_same_filesystem() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, b"x").unwrap();
+        let dir_str = dir.path().to_string_lossy();
+        assert_eq!(same_filesystem(&dir_str, &file.to_string_lossy()).ok(), Some(true));
+
+        let (_, path) = same_filesystem(&dir_str, "/definitely/not/here").unwrap_err();
+        assert_eq!(path, "/definitely/not/here");
+    }
+}
@@ -29,7 +29,7 @@ pub const SEEK_END: i64 = 2;
 
 /// File open mode
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum FileMode {
+pub(crate) enum FileMode {
     Read,
     Write,
     Append,
@@ -71,17 +71,17 @@ impl FileMode {
 }
 
 /// Buffered file handle supporting read/write operations
-struct FileHandle {
-    file: File,
+pub(crate) struct FileHandle {
+    pub(crate) file: File,
     reader: Option<BufReader<File>>,
-    writer: Option<BufWriter<File>>,
-    path: String,
+    pub(crate) writer: Option<BufWriter<File>>,
+    pub(crate) path: String,
     mode: FileMode,
     eof: bool,
 }
 
 impl FileHandle {
-    fn new(file: File, path: String, mode: FileMode) -> std::io::Result<Self> {
+    pub(crate) fn new(file: File, path: String, mode: FileMode) -> std::io::Result<Self> {
         let reader = if mode.can_read() {
             Some(BufReader::new(file.try_clone()?))
         } else {
@@ -103,7 +103,7 @@ impl FileHandle {
     }
 }
 
-struct FileRegistry {
+pub(crate) struct FileRegistry {
     handles: HashMap<i64, FileHandle>,
     next_id: i64,
 }
@@ -116,31 +116,31 @@ impl FileRegistry {
         }
     }
 
-    fn insert(&mut self, handle: FileHandle) -> i64 {
+    pub(crate) fn insert(&mut self, handle: FileHandle) -> i64 {
         let id = self.next_id;
         self.next_id += 1;
         self.handles.insert(id, handle);
         id
     }
 
-    fn get(&self, id: i64) -> Option<&FileHandle> {
+    pub(crate) fn get(&self, id: i64) -> Option<&FileHandle> {
         self.handles.get(&id)
     }
 
-    fn get_mut(&mut self, id: i64) -> Option<&mut FileHandle> {
+    pub(crate) fn get_mut(&mut self, id: i64) -> Option<&mut FileHandle> {
         self.handles.get_mut(&id)
     }
 
-    fn remove(&mut self, id: i64) -> Option<FileHandle> {
+    pub(crate) fn remove(&mut self, id: i64) -> Option<FileHandle> {
         self.handles.remove(&id)
     }
 }
 
-static FILE_REGISTRY: std::sync::LazyLock<Mutex<FileRegistry>> =
+pub(crate) static FILE_REGISTRY: std::sync::LazyLock<Mutex<FileRegistry>> =
     std::sync::LazyLock::new(|| Mutex::new(FileRegistry::new()));
 
 /// Helper to throw a file handle error
-fn throw_file_error(message: &str, handle: i64) {
+pub(crate) fn throw_file_error(message: &str, handle: i64) {
     let path = format!("file handle {}", handle);
     unsafe {
         let message_ptr = naml_string_new(message.as_ptr(), message.len());
@@ -21,6 +21,7 @@
 //! - `read_bytes(path: string) -> bytes throws IOError`
 //! - `write(path: string, content: string) throws IOError`
 //! - `write_bytes(path: string, content: bytes) throws IOError`
+//! - `write_atomic(path: string, content: string) throws IOError`
 //! - `append(path: string, content: string) throws IOError`
 //! - `append_bytes(path: string, content: bytes) throws IOError`
 //!
@@ -33,6 +34,12 @@
 //! - `file_write(handle: int, content: string) -> int throws IOError`
 //! - `file_write_line(handle: int, content: string) -> int throws IOError`
 //! - `file_flush(handle: int) throws IOError`
+//! - `file_fsync(handle: int) throws IOError`
+//! - `file_fdatasync(handle: int) throws IOError`
+//! - `lock_exclusive(handle: int) throws IOError`
+//! - `lock_shared(handle: int) throws IOError`
+//! - `try_lock(handle: int) -> bool throws IOError`
+//! - `unlock(handle: int) throws IOError`
 //! - `file_seek(handle: int, offset: int, whence: int) -> int throws IOError`
 //! - `file_tell(handle: int) -> int throws IOError`
 //! - `file_eof(handle: int) -> bool throws IOError`
@@ -43,6 +50,7 @@
 //! - `is_file(path: string) -> bool`
 //! - `is_dir(path: string) -> bool`
 //! - `list_dir(path: string) -> [string] throws IOError`
+//! - `glob(pattern: string) -> [string] throws IOError`
 //! - `mkdir(path: string) throws IOError`
 //! - `mkdir_all(path: string) throws IOError`
 //! - `remove(path: string) throws IOError`
@@ -56,6 +64,54 @@
 //! - `modified(path: string) -> int throws IOError`
 //! - `copy(src: string, dst: string) throws IOError`
 //! - `rename(src: string, dst: string) throws IOError`
+//! - `copy_verified(src: string, dst: string) throws IOError`
+//! - `verify_file(path: string, expected_sha256: string) -> bool throws IOError`
+//!
+//! ### Disk Space
+//! - `disk_free(path: string) -> int throws IOError`
+//! - `disk_total(path: string) -> int throws IOError`
+//! - `fs_type(path: string) -> string throws IOError`
+//! - `is_same_filesystem(a: string, b: string) -> bool throws IOError`
+//!
+//! ### Recursive Copy
+//! - `copy_options() -> int`
+//! - `copy_options_include(opts: int, pattern: string) throws IOError`
+//! - `copy_options_exclude(opts: int, pattern: string) throws IOError`
+//! - `copy_options_overwrite(opts: int, policy: string) throws IOError`
+//! - `copy_options_on_progress(opts: int, callback: fn(int, int))`
+//! - `copy_options_free(opts: int)`
+//! - `copy_tree(src: string, dst: string, opts: int) throws IOError`
+//! - `move_tree(src: string, dst: string, opts: int) throws IOError`
+//!
+//! ### Directory Walking
+//! - `walk_options() -> int`
+//! - `walk_options_max_depth(opts: int, depth: int) throws IOError`
+//! - `walk_options_follow_symlinks(opts: int, follow: bool) throws IOError`
+//! - `walk_options_include(opts: int, pattern: string) throws IOError`
+//! - `walk_options_exclude(opts: int, pattern: string) throws IOError`
+//! - `walk_options_free(opts: int)`
+//! - `walk_dir(path: string, opts: int) -> [string] throws IOError`
+//! - `walk_open(path: string, opts: int) -> int throws IOError`
+//! - `walk_next(handle: int) -> option<string> throws IOError`
+//! - `walk_close(handle: int)`
+//!
+//! ### Temporary Files
+//! - `create_temp(prefix: string) -> string throws IOError`
+//! - `mkdir_temp(prefix: string) -> string throws IOError`
+//! - `with_temp_file(prefix: string, body: fn(string)) throws IOError`
+//! - `with_temp_dir(prefix: string, body: fn(string)) throws IOError`
+//! - `temp_file_keep(path: string)`
+//!
+//! ### Async I/O
+//! - `read_async(path: string) -> channel<string>`
+//! - `write_async(path: string, content: string) -> channel<int>`
+//! - `list_dir_async(path: string) -> channel<[string]>`
+//! - `await_io<T>(pending: channel<T>) -> T throws IOError`
+//!
+//! ### Watching
+//! - `watch(path: string, callback: fn(fs_event)) -> int throws IOError`
+//! - `watch_recursive(path: string, callback: fn(fs_event)) -> int throws IOError`
+//! - `unwatch(handle: int)`
 //!
 //! ## Platform Support
 //!
@@ -63,15 +119,37 @@
 //! Browser WASM uses OPFS (not yet implemented). TODO
 //!
 
+mod async_io;
+mod atomic;
+mod checksum;
+mod disk;
 mod file_handle;
 mod links;
+mod lock;
 mod mmap;
 mod ownership;
-
+mod pattern;
+mod sync;
+mod temp;
+mod tree;
+mod walk;
+mod watch;
+
+pub use async_io::*;
+pub use atomic::*;
+pub use checksum::*;
+pub use disk::*;
 pub use file_handle::*;
 pub use links::*;
+pub use lock::*;
 pub use mmap::*;
 pub use ownership::*;
+pub use pattern::*;
+pub use sync::*;
+pub use temp::*;
+pub use tree::*;
+pub use walk::*;
+pub use watch::*;
 
 use naml_std_core::{
     naml_exception_set_typed, naml_stack_capture, naml_string_new,
@@ -186,7 +264,7 @@ pub(crate) fn throw_permission_error(error: std::io::Error, path: &str) -> *mut
 /// This is a helper for fs functions to convert Rust errors to naml exceptions.
 /// If the error is a permission error (EACCES/EPERM), throws PermissionError instead.
 /// Returns null to indicate an exception was thrown.
-pub(crate) fn throw_io_error(error: std::io::Error, path: &str) -> *mut u8 {
+pub fn throw_io_error(error: std::io::Error, path: &str) -> *mut u8 {
     // Check if this is a permission error
     if is_permission_error(&error) {
         return throw_permission_error(error, path);
@@ -219,7 +297,7 @@ pub(crate) unsafe fn path_from_naml_string(s: *const NamlString) -> String {
         return String::new();
     }
     unsafe {
-        let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
         String::from_utf8_lossy(slice).into_owned()
     }
 }
@@ -246,13 +324,7 @@ pub unsafe extern "C" fn naml_fs_read_bytes(path: *const NamlString) -> *mut nam
     let path_str = unsafe { path_from_naml_string(path) };
 
     match std::fs::read(&path_str) {
-        Ok(bytes) => {
-            let arr = unsafe { naml_std_core::naml_array_new(bytes.len()) };
-            for &byte in bytes.iter() {
-                unsafe { naml_std_core::naml_array_push(arr, byte as i64) };
-            }
-            arr
-        }
+        Ok(bytes) => naml_std_core::naml_array_from_bytes(&bytes),
         Err(e) => {
             throw_io_error(e, &path_str);
             std::ptr::null_mut()
@@ -321,7 +393,7 @@ pub unsafe extern "C" fn naml_fs_write_bytes(
 
     // Extract bytes from NamlBytes
     let len = unsafe { (*content).len };
-    let data = unsafe { std::slice::from_raw_parts((*content).data.as_ptr(), len) };
+    let data = unsafe { std::slice::from_raw_parts((*content).as_ptr(), len) };
 
     match std::fs::write(&path_str, data) {
         Ok(()) => 0,
@@ -349,7 +421,7 @@ pub unsafe extern "C" fn naml_fs_append_bytes(
 
     // Extract bytes from NamlBytes
     let len = unsafe { (*content).len };
-    let data = unsafe { std::slice::from_raw_parts((*content).data.as_ptr(), len) };
+    let data = unsafe { std::slice::from_raw_parts((*content).as_ptr(), len) };
 
     let result = std::fs::OpenOptions::new()
         .create(true)
@@ -581,13 +653,8 @@ pub unsafe extern "C" fn naml_fs_rename(src: *const NamlString, dst: *const Naml
 pub unsafe extern "C" fn naml_fs_list_dir(path: *const NamlString) -> *mut naml_std_core::NamlArray {
     let path_str = unsafe { path_from_naml_string(path) };
 
-    match std::fs::read_dir(&path_str) {
+    match list_dir_entries(&path_str) {
         Ok(entries) => {
-            let entries: Vec<_> = entries
-                .filter_map(|e| e.ok())
-                .map(|e| e.path().to_string_lossy().into_owned())
-                .collect();
-
             let arr = unsafe { naml_std_core::naml_array_new(entries.len()) };
             for entry in entries.iter() {
                 let s = unsafe { naml_string_new(entry.as_ptr(), entry.len()) };
@@ -602,6 +669,14 @@ pub unsafe extern "C" fn naml_fs_list_dir(path: *const NamlString) -> *mut naml_
     }
 }
 
+/// Full paths of the entries in a directory, skipping unreadable entries
+pub(crate) fn list_dir_entries(path: &str) -> std::io::Result<Vec<String>> {
+    Ok(std::fs::read_dir(path)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().to_string_lossy().into_owned())
+        .collect())
+}
+
 /// Join path components with platform separator
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_fs_join(parts: *const naml_std_core::NamlArray) -> *mut NamlString {
@@ -202,11 +202,7 @@ pub unsafe extern "C" fn naml_fs_mmap_read(
         return std::ptr::null_mut();
     }
 
-    let arr = unsafe { naml_std_core::naml_array_new(read_len) };
-    for i in start..end {
-        unsafe { naml_std_core::naml_array_push(arr, mmap_ref[i] as i64) };
-    }
-    arr
+    naml_std_core::naml_array_from_bytes(&mmap_ref[start..end])
 }
 
 /// Write bytes to a memory-mapped region
@@ -222,7 +218,7 @@ pub unsafe extern "C" fn naml_fs_mmap_write(
     }
 
     let data_len = unsafe { (*data).len };
-    let data_slice = unsafe { std::slice::from_raw_parts((*data).data.as_ptr(), data_len) };
+    let data_slice = unsafe { std::slice::from_raw_parts((*data).as_ptr(), data_len) };
 
     let mut registry = MMAP_REGISTRY.lock().unwrap();
     let mmap_ref = match registry.get_mut(handle) {
@@ -0,0 +1,374 @@
+///
+/// Glob Patterns
+///
+/// Expands a glob pattern into the paths that match it, like a shell does.
+/// The pattern is compiled into one matcher per path segment, and only the
+/// directories the pattern can reach are read.
+///
+/// Syntax (within one path segment):
+/// - `*`      - any run of characters
+/// - `?`      - any single character
+/// - `[abc]`  - one of the listed characters; ranges like `[a-z]` work too
+/// - `[!abc]` - any character not listed (`[^abc]` is the same)
+/// - `**`     - as a whole segment, zero or more directories
+///
+/// `/` separates segments on every platform; on Windows `\` does too, and
+/// patterns may start with a drive (`C:\`). Use a class such as `[*]` to
+/// match a metacharacter literally.
+///
+/// Wildcards never match a leading `.`, so hidden files and directories are
+/// only found when the pattern spells out the dot (`.config/*`). `**` does
+/// not descend into symlinked directories. Unreadable directories are skipped.
+///
+/// Matches are returned sorted. Relative patterns give paths relative to the
+/// working directory.
+///
+/// Functions:
+/// - `glob(pattern) -> [string]` - Paths matching the pattern
+///
+
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use naml_std_core::{naml_array_new, naml_array_push, naml_string_new, NamlArray, NamlString};
+
+use crate::{path_from_naml_string, throw_io_error};
+
+/// One matcher step within a segment
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Char(char),
+    AnyChar,
+    AnyRun,
+    Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    /// No wildcards: checked directly instead of listing the directory
+    Literal(String),
+    Wild(Vec<Token>),
+    /// `**`
+    AnyDirs,
+}
+
+#[derive(Debug, PartialEq)]
+struct GlobPattern {
+    /// Root prefix of absolute patterns ("/" or "C:\")
+    root: Option<String>,
+    segments: Vec<Segment>,
+}
+
+fn is_separator(c: char, windows: bool) -> bool {
+    c == '/' || (windows && c == '\\')
+}
+
+/// Split off the root of an absolute pattern
+fn split_root(pattern: &str, windows: bool) -> (Option<String>, &str) {
+    let bytes = pattern.as_bytes();
+    if windows && bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && is_separator(bytes[2] as char, true) {
+        return (Some(format!("{}\\", &pattern[..2])), &pattern[3..]);
+    }
+    match pattern.chars().next() {
+        Some(c) if is_separator(c, windows) => {
+            let root = if windows { "\\" } else { "/" };
+            (Some(root.to_string()), &pattern[1..])
+        }
+        _ => (None, pattern),
+    }
+}
+
+/// Compile a pattern, treating `\` as a separator when `windows` is set
+fn compile(pattern: &str, windows: bool) -> Result<GlobPattern, String> {
+    if pattern.is_empty() {
+        return Err("empty glob pattern".to_string());
+    }
+    let (root, rest) = split_root(pattern, windows);
+
+    let mut segments: Vec<Segment> = Vec::new();
+    for part in rest.split(|c| is_separator(c, windows)).filter(|p| !p.is_empty()) {
+        let segment = if part == "**" {
+            // Consecutive `**` segments mean the same as one
+            if segments.last() == Some(&Segment::AnyDirs) {
+                continue;
+            }
+            Segment::AnyDirs
+        } else {
+            let tokens = compile_segment(part)?;
+            if tokens.iter().all(|t| matches!(t, Token::Char(_))) {
+                Segment::Literal(part.to_string())
+            } else {
+                Segment::Wild(tokens)
+            }
+        };
+        segments.push(segment);
+    }
+    Ok(GlobPattern { root, segments })
+}
+
+fn compile_segment(part: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = part.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => {
+                if tokens.last() != Some(&Token::AnyRun) {
+                    tokens.push(Token::AnyRun);
+                }
+            }
+            '?' => tokens.push(Token::AnyChar),
+            '[' => {
+                let negated = matches!(chars.peek(), Some('!') | Some('^'));
+                if negated {
+                    chars.next();
+                }
+                let mut ranges = Vec::new();
+                let mut first = true;
+                loop {
+                    let Some(start) = chars.next() else {
+                        return Err(format!("unclosed character class in '{}'", part));
+                    };
+                    // A `]` right after the opening bracket is a literal
+                    if start == ']' && !first {
+                        break;
+                    }
+                    first = false;
+                    let mut end = start;
+                    if chars.peek() == Some(&'-') {
+                        let mut lookahead = chars.clone();
+                        lookahead.next();
+                        if let Some(&next) = lookahead.peek()
+                            && next != ']'
+                        {
+                            chars.next();
+                            end = chars.next().unwrap_or(start);
+                        }
+                    }
+                    if end < start {
+                        return Err(format!("invalid range '{}-{}' in '{}'", start, end, part));
+                    }
+                    ranges.push((start, end));
+                }
+                tokens.push(Token::Class { negated, ranges });
+            }
+            c => tokens.push(Token::Char(c)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Whether one file name matches a wildcard segment
+fn matches_name(tokens: &[Token], name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    if name.first() == Some(&'.') && tokens.first() != Some(&Token::Char('.')) {
+        return false;
+    }
+    matches_from(tokens, &name)
+}
+
+fn matches_from(tokens: &[Token], name: &[char]) -> bool {
+    let Some((token, rest)) = tokens.split_first() else {
+        return name.is_empty();
+    };
+    match token {
+        Token::AnyRun => (0..=name.len()).any(|skip| matches_from(rest, &name[skip..])),
+        _ => match name.split_first() {
+            Some((&c, remaining)) => token_matches(token, c) && matches_from(rest, remaining),
+            None => false,
+        },
+    }
+}
+
+fn token_matches(token: &Token, c: char) -> bool {
+    match token {
+        Token::Char(expected) => *expected == c,
+        Token::AnyChar | Token::AnyRun => true,
+        Token::Class { negated, ranges } => ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated,
+    }
+}
+
+/// Entries of a directory as (name, path, is a real directory), sorted by name.
+/// An empty `dir` means the working directory.
+fn read_entries(dir: &Path) -> Vec<(String, PathBuf, bool)> {
+    let listing = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let Ok(read_dir) = std::fs::read_dir(listing) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<_> = read_dir
+        .filter_map(|e| e.ok())
+        .map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let is_dir = e.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            (name.clone(), dir.join(&name), is_dir)
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+fn expand(dir: &Path, segments: &[Segment], out: &mut Vec<PathBuf>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        out.push(dir.to_path_buf());
+        return;
+    };
+    match segment {
+        Segment::Literal(name) => {
+            let path = dir.join(name);
+            if rest.is_empty() {
+                if std::fs::symlink_metadata(&path).is_ok() {
+                    out.push(path);
+                }
+            } else if path.is_dir() {
+                expand(&path, rest, out);
+            }
+        }
+        Segment::Wild(tokens) => {
+            for (name, path, _) in read_entries(dir) {
+                if !matches_name(tokens, &name) {
+                    continue;
+                }
+                if rest.is_empty() {
+                    out.push(path);
+                } else if path.is_dir() {
+                    expand(&path, rest, out);
+                }
+            }
+        }
+        Segment::AnyDirs if rest.is_empty() => {
+            // A trailing `**` matches everything below the directory
+            for (name, path, is_dir) in read_entries(dir) {
+                if name.starts_with('.') {
+                    continue;
+                }
+                out.push(path.clone());
+                if is_dir {
+                    expand(&path, segments, out);
+                }
+            }
+        }
+        Segment::AnyDirs => {
+            expand(dir, rest, out);
+            for (name, path, is_dir) in read_entries(dir) {
+                if is_dir && !name.starts_with('.') {
+                    expand(&path, segments, out);
+                }
+            }
+        }
+    }
+}
+
+/// All paths matching a compiled pattern, sorted and without duplicates
+fn glob_paths(pattern: &GlobPattern) -> Vec<String> {
+    let base = pattern.root.as_deref().map(PathBuf::from).unwrap_or_default();
+    let mut paths = Vec::new();
+    expand(&base, &pattern.segments, &mut paths);
+    let mut paths: Vec<String> = paths.into_iter().map(|p| p.to_string_lossy().into_owned()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Expand a glob pattern into the matching paths
+/// Returns null and sets exception on an invalid pattern
+///
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_glob(pattern: *const NamlString) -> *mut NamlArray {
+    let pattern_str = unsafe { path_from_naml_string(pattern) };
+    let compiled = match compile(&pattern_str, cfg!(windows)) {
+        Ok(compiled) => compiled,
+        Err(msg) => {
+            throw_io_error(Error::new(ErrorKind::InvalidInput, msg), &pattern_str);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let paths = glob_paths(&compiled);
+    unsafe {
+        let arr = naml_array_new(paths.len());
+        for p in &paths {
+            naml_array_push(arr, naml_string_new(p.as_ptr(), p.len()) as i64);
+        }
+        arr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wild(part: &str) -> Vec<Token> {
+        compile_segment(part).unwrap()
+    }
+
+    #[test]
+    fn test_match_tokens() {
+        assert!(matches_name(&wild("*.nm"), "main.nm"));
+        assert!(!matches_name(&wild("*.nm"), "main.rs"));
+        assert!(matches_name(&wild("?.txt"), "a.txt"));
+        assert!(!matches_name(&wild("?.txt"), "ab.txt"));
+        assert!(matches_name(&wild("file[0-9]"), "file7"));
+        assert!(!matches_name(&wild("file[!0-9]"), "file7"));
+        assert!(matches_name(&wild("[]x]"), "]"));
+        assert!(matches_name(&wild("a-[*]"), "a-*"));
+        assert!(!matches_name(&wild("*"), ".hidden"));
+        assert!(matches_name(&wild(".*"), ".hidden"));
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        assert!(compile("src/[abc", false).is_err());
+        assert!(compile("[z-a]", false).is_err());
+        assert!(compile("", false).is_err());
+    }
+
+    #[test]
+    fn test_windows_separators() {
+        let unix = compile("src/**/*.nm", false).unwrap();
+        let windows = compile("src\\**\\*.nm", true).unwrap();
+        assert_eq!(unix.segments, windows.segments);
+        assert_eq!(windows.root, None);
+
+        let drive = compile("C:\\data\\*.txt", true).unwrap();
+        assert_eq!(drive.root.as_deref(), Some("C:\\"));
+        assert_eq!(drive.segments[0], Segment::Literal("data".to_string()));
+
+        let mixed = compile("\\logs/**/*.log", true).unwrap();
+        assert_eq!(mixed.root.as_deref(), Some("\\"));
+        assert_eq!(mixed.segments.len(), 3);
+
+        // Outside Windows a backslash is an ordinary file name character
+        let literal = compile("src\\*.nm", false).unwrap();
+        assert_eq!(literal.segments.len(), 1);
+    }
+
+    #[test]
+    fn test_glob_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/nested/deep")).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join("main.nm"), "").unwrap();
+        std::fs::write(root.join("src/lib.nm"), "").unwrap();
+        std::fs::write(root.join("src/notes.txt"), "").unwrap();
+        std::fs::write(root.join("src/nested/deep/util.nm"), "").unwrap();
+        std::fs::write(root.join(".git/config.nm"), "").unwrap();
+
+        let glob = |pattern: &str| -> Vec<String> {
+            let full = format!("{}/{}", root.display(), pattern);
+            glob_paths(&compile(&full, cfg!(windows)).unwrap())
+                .into_iter()
+                .map(|p| crate::tree::relative_slash_path(root, Path::new(&p)))
+                .collect()
+        };
+
+        assert_eq!(glob("*.nm"), vec!["main.nm"]);
+        assert_eq!(glob("src/**/*.nm"), vec!["src/lib.nm", "src/nested/deep/util.nm"]);
+        assert_eq!(glob("**/*.nm"), vec!["main.nm", "src/lib.nm", "src/nested/deep/util.nm"]);
+        assert_eq!(glob("src/*.???"), vec!["src/notes.txt"]);
+        assert_eq!(glob(".git/*"), vec![".git/config.nm"]);
+        assert_eq!(glob("src/nested/**"), vec!["src/nested/deep", "src/nested/deep/util.nm"]);
+        assert!(glob("missing/*.nm").is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+///
+/// Scoped Temporary Files and Directories
+///
+/// `create_temp` and `mkdir_temp` hand back a bare path, so the caller must
+/// remember to remove it. The scoped variants here create the entry, pass its
+/// path to a closure, and delete it once the closure returns, including when
+/// the closure returns with an exception set.
+///
+/// Functions:
+/// - `with_temp_file(prefix, fn(path))` - Run a closure with a temp file, then delete it
+/// - `with_temp_dir(prefix, fn(path))` - Run a closure with a temp directory, then delete it recursively
+/// - `temp_file_keep(path)` - Keep a scoped temp file or directory after its closure returns
+///
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use naml_std_core::{naml_string_new, NamlString};
+
+use crate::{path_from_naml_string, throw_io_error};
+
+/// Compiled closure: fn(path: string), called with its closure data
+type TempCallback = unsafe extern "C" fn(data_ptr: i64, path: *mut NamlString) -> i64;
+
+/// Paths of scoped temp entries whose closure is still running, mapped to
+/// whether `temp_file_keep` was called for them
+static ACTIVE_TEMP_PATHS: LazyLock<Mutex<HashMap<String, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Call the closure with `path` and report whether the entry should be kept
+fn run_scoped(path: &str, func_ptr: i64, data_ptr: i64) -> bool {
+    ACTIVE_TEMP_PATHS.lock().unwrap().insert(path.to_string(), false);
+
+    unsafe {
+        let callback = std::mem::transmute::<usize, TempCallback>(func_ptr as usize);
+        callback(data_ptr, naml_string_new(path.as_ptr(), path.len()));
+    }
+
+    ACTIVE_TEMP_PATHS.lock().unwrap().remove(path).unwrap_or(false)
+}
+
+fn prefix_or_default(prefix: &str) -> &str {
+    if prefix.is_empty() { "naml" } else { prefix }
+}
+
+/// Create a temp file, run the closure with its path, then delete the file
+/// Sets exception if the file cannot be created; the closure is not called
+///
+/// # Safety
+/// The caller must ensure `prefix` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_with_temp_file(
+    prefix: *const NamlString,
+    func_ptr: i64,
+    data_ptr: i64,
+) {
+    let prefix_str = unsafe { path_from_naml_string(prefix) };
+    let prefix_str = prefix_or_default(&prefix_str);
+
+    match tempfile::Builder::new().prefix(prefix_str).tempfile() {
+        Ok(file) => {
+            // Close our handle so the closure can reopen, rename, or remove the file
+            let temp_path = file.into_temp_path();
+            let path_str = temp_path.to_string_lossy().into_owned();
+            if run_scoped(&path_str, func_ptr, data_ptr) {
+                let _ = temp_path.keep();
+            }
+            // Dropping the TempPath removes the file; a missing file is ignored
+        }
+        Err(e) => {
+            throw_io_error(e, prefix_str);
+        }
+    }
+}
+
+/// Create a temp directory, run the closure with its path, then remove it recursively
+/// Sets exception if the directory cannot be created; the closure is not called
+///
+/// # Safety
+/// The caller must ensure `prefix` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_with_temp_dir(
+    prefix: *const NamlString,
+    func_ptr: i64,
+    data_ptr: i64,
+) {
+    let prefix_str = unsafe { path_from_naml_string(prefix) };
+    let prefix_str = prefix_or_default(&prefix_str);
+
+    match tempfile::Builder::new().prefix(prefix_str).tempdir() {
+        Ok(dir) => {
+            let path_str = dir.path().to_string_lossy().into_owned();
+            if run_scoped(&path_str, func_ptr, data_ptr) {
+                let _ = dir.keep();
+            }
+        }
+        Err(e) => {
+            throw_io_error(e, prefix_str);
+        }
+    }
+}
+
+/// Keep a scoped temp entry after its closure returns
+/// Has no effect on paths that were not created by with_temp_file/with_temp_dir
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_temp_file_keep(path: *const NamlString) {
+    let path_str = unsafe { path_from_naml_string(path) };
+    if let Some(keep) = ACTIVE_TEMP_PATHS.lock().unwrap().get_mut(&path_str) {
+        *keep = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    thread_local! {
+        static SEEN: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+    }
+
+    fn make_string(s: &str) -> *mut NamlString {
+        unsafe { naml_string_new(s.as_ptr(), s.len()) }
+    }
+
+    unsafe extern "C" fn record_path(keep: i64, path: *mut NamlString) -> i64 {
+        let path_str = unsafe { path_from_naml_string(path) };
+        assert!(std::path::Path::new(&path_str).exists());
+        if keep != 0 {
+            unsafe { naml_fs_temp_file_keep(path) };
+        }
+        SEEN.with(|seen| *seen.borrow_mut() = Some(PathBuf::from(path_str)));
+        0
+    }
+
+    fn seen_path() -> PathBuf {
+        SEEN.with(|seen| seen.borrow_mut().take()).expect("closure was not called")
+    }
+
+    #[test]
+    fn test_with_temp_file_removes_file() {
+        unsafe {
+            naml_fs_with_temp_file(make_string("naml_test_"), record_path as *const () as usize as i64, 0);
+        }
+        let path = seen_path();
+        assert!(path.file_name().unwrap().to_string_lossy().starts_with("naml_test_"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_with_temp_dir_removes_contents() {
+        unsafe extern "C" fn fill_dir(_data: i64, path: *mut NamlString) -> i64 {
+            let dir = PathBuf::from(unsafe { path_from_naml_string(path) });
+            std::fs::create_dir(dir.join("nested")).unwrap();
+            std::fs::write(dir.join("nested/file.txt"), b"data").unwrap();
+            SEEN.with(|seen| *seen.borrow_mut() = Some(dir));
+            0
+        }
+
+        unsafe {
+            naml_fs_with_temp_dir(make_string(""), fill_dir as *const () as usize as i64, 0);
+        }
+        assert!(!seen_path().exists());
+    }
+
+    #[test]
+    fn test_temp_file_keep() {
+        unsafe {
+            naml_fs_with_temp_file(make_string("naml_keep_"), record_path as *const () as usize as i64, 1);
+        }
+        let path = seen_path();
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,695 @@
+///
+/// Recursive Copy and Move
+///
+/// Copies or moves whole directory trees in one call, with glob filters,
+/// an overwrite policy, and an optional progress callback. Options live in a
+/// handle registry (like file handles); handle 0 means "all defaults".
+///
+/// Per-file failures do not stop the walk. They are collected and reported
+/// together as a single IOError once every other entry has been processed.
+///
+/// Overwrite policies (for files that already exist at the destination):
+/// - "overwrite" - replace the existing file (default)
+/// - "skip"      - keep the existing file
+/// - "newer"     - replace only if the source was modified more recently
+/// - "error"     - leave the file and report it as a failure
+///
+/// Glob patterns match paths relative to the source root using `/` separators.
+/// A pattern without `/` matches against the entry's file name at any depth.
+/// Excluded directories are not descended into; include patterns apply to files.
+///
+/// Functions:
+/// - `copy_options() -> int` - Create an options handle
+/// - `copy_options_include(opts, pattern)` - Only copy files matching pattern
+/// - `copy_options_exclude(opts, pattern)` - Skip files and directories matching pattern
+/// - `copy_options_overwrite(opts, policy)` - Set the overwrite policy
+/// - `copy_options_on_progress(opts, fn(bytes_done, total))` - Report progress
+/// - `copy_options_free(opts)` - Release an options handle
+/// - `copy_tree(src, dst, opts)` - Copy a directory tree
+/// - `move_tree(src, dst, opts)` - Move a directory tree (rename when possible)
+///
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use glob::{MatchOptions, Pattern};
+use naml_std_core::{naml_exception_get, NamlString};
+
+use crate::{path_from_naml_string, throw_io_error};
+
+/// Compiled progress closure: fn(bytes_done: int, total: int)
+type ProgressFn = unsafe extern "C" fn(data_ptr: i64, done: i64, total: i64) -> i64;
+
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OverwritePolicy {
+    Overwrite,
+    Skip,
+    Newer,
+    Error,
+}
+
+impl OverwritePolicy {
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "overwrite" => Some(OverwritePolicy::Overwrite),
+            "skip" => Some(OverwritePolicy::Skip),
+            "newer" => Some(OverwritePolicy::Newer),
+            "error" => Some(OverwritePolicy::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Progress {
+    func: ProgressFn,
+    data: i64,
+}
+
+#[derive(Clone)]
+struct CopyOptions {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    overwrite: OverwritePolicy,
+    progress: Option<Progress>,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            overwrite: OverwritePolicy::Overwrite,
+            progress: None,
+        }
+    }
+}
+
+pub(crate) fn pattern_matches(pattern: &Pattern, rel: &str) -> bool {
+    if pattern.as_str().contains('/') {
+        return pattern.matches_with(rel, GLOB_OPTIONS);
+    }
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    pattern.matches_with(name, GLOB_OPTIONS)
+}
+
+impl CopyOptions {
+    fn is_excluded(&self, rel: &str) -> bool {
+        self.exclude.iter().any(|p| pattern_matches(p, rel))
+    }
+
+    fn is_included(&self, rel: &str) -> bool {
+        self.include.is_empty() || self.include.iter().any(|p| pattern_matches(p, rel))
+    }
+}
+
+struct OptionsRegistry {
+    options: HashMap<i64, CopyOptions>,
+    next_id: i64,
+}
+
+impl OptionsRegistry {
+    fn new() -> Self {
+        Self {
+            options: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    fn insert(&mut self, options: CopyOptions) -> i64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.options.insert(id, options);
+        id
+    }
+}
+
+static OPTIONS_REGISTRY: LazyLock<Mutex<OptionsRegistry>> =
+    LazyLock::new(|| Mutex::new(OptionsRegistry::new()));
+
+fn invalid_handle(handle: i64) -> Error {
+    Error::new(ErrorKind::InvalidInput, format!("invalid copy options handle {}", handle))
+}
+
+/// Snapshot the options for a handle so no lock is held while copying
+fn load_options(handle: i64) -> Result<CopyOptions, Error> {
+    if handle == 0 {
+        return Ok(CopyOptions::default());
+    }
+    OPTIONS_REGISTRY
+        .lock()
+        .unwrap()
+        .options
+        .get(&handle)
+        .cloned()
+        .ok_or_else(|| invalid_handle(handle))
+}
+
+fn update_options(handle: i64, update: impl FnOnce(&mut CopyOptions)) -> Result<(), Error> {
+    let mut registry = OPTIONS_REGISTRY.lock().unwrap();
+    let options = registry.options.get_mut(&handle).ok_or_else(|| invalid_handle(handle))?;
+    update(options);
+    Ok(())
+}
+
+/// A file (or symlink) selected for transfer, relative to the source root
+struct Entry {
+    rel: String,
+    size: u64,
+    is_symlink: bool,
+}
+
+/// Walk `dir` collecting selected files and the directories to create
+fn collect(
+    root: &Path,
+    dir: &Path,
+    options: &CopyOptions,
+    dirs: &mut Vec<String>,
+    files: &mut Vec<Entry>,
+    errors: &mut Vec<(String, Error)>,
+) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push((dir.to_string_lossy().into_owned(), e));
+            return;
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push((dir.to_string_lossy().into_owned(), e));
+                continue;
+            }
+        };
+        let path = entry.path();
+        let rel = relative_slash_path(root, &path);
+        if options.is_excluded(&rel) {
+            continue;
+        }
+
+        let meta = match std::fs::symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(e) => {
+                errors.push((path.to_string_lossy().into_owned(), e));
+                continue;
+            }
+        };
+
+        if meta.is_dir() {
+            dirs.push(rel);
+            collect(root, &path, options, dirs, files, errors);
+        } else if options.is_included(&rel) {
+            files.push(Entry {
+                rel,
+                size: if meta.file_type().is_symlink() { 0 } else { meta.len() },
+                is_symlink: meta.file_type().is_symlink(),
+            });
+        }
+    }
+}
+
+pub(crate) fn relative_slash_path(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    let parts: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    parts.join("/")
+}
+
+/// Tracks bytes copied and forwards them to the naml progress closure
+struct ProgressTracker {
+    progress: Option<Progress>,
+    done: u64,
+    total: u64,
+    reported: Option<u64>,
+}
+
+impl ProgressTracker {
+    /// Record `bytes` more and report; returns false if the callback threw
+    fn advance(&mut self, bytes: u64) -> bool {
+        self.done += bytes;
+        let Some(progress) = self.progress else {
+            return true;
+        };
+        unsafe {
+            (progress.func)(progress.data, self.done as i64, self.total as i64);
+        }
+        self.reported = Some(self.done);
+        naml_exception_get().is_null()
+    }
+
+    /// Send a final report unless the last one already covered every byte
+    fn finish(&mut self) -> bool {
+        if self.reported == Some(self.done) {
+            return true;
+        }
+        self.advance(0)
+    }
+}
+
+/// Outcome of transferring a single file
+enum FileResult {
+    Copied,
+    Skipped,
+    Aborted,
+}
+
+fn should_write(dst: &Path, src_meta: &std::fs::Metadata, policy: OverwritePolicy) -> Result<bool, Error> {
+    let dst_meta = match std::fs::symlink_metadata(dst) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e),
+    };
+    match policy {
+        OverwritePolicy::Overwrite => Ok(true),
+        OverwritePolicy::Skip => Ok(false),
+        OverwritePolicy::Newer => Ok(src_meta.modified()? > dst_meta.modified()?),
+        OverwritePolicy::Error => Err(Error::new(ErrorKind::AlreadyExists, "destination already exists")),
+    }
+}
+
+fn copy_symlink(src: &Path, dst: &Path) -> Result<(), Error> {
+    let target = std::fs::read_link(src)?;
+    if std::fs::symlink_metadata(dst).is_ok() {
+        std::fs::remove_file(dst)?;
+    }
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, dst)
+    }
+    #[cfg(not(unix))]
+    {
+        // Without portable symlink creation, copy what the link points to
+        let _ = target;
+        std::fs::copy(src, dst).map(|_| ())
+    }
+}
+
+fn copy_file(
+    src: &Path,
+    dst: &Path,
+    entry: &Entry,
+    policy: OverwritePolicy,
+    tracker: &mut ProgressTracker,
+) -> Result<FileResult, Error> {
+    let src_meta = std::fs::symlink_metadata(src)?;
+    if !should_write(dst, &src_meta, policy)? {
+        tracker.done += entry.size;
+        return Ok(FileResult::Skipped);
+    }
+
+    if entry.is_symlink {
+        copy_symlink(src, dst)?;
+        return Ok(FileResult::Copied);
+    }
+
+    let mut reader = File::open(src)?;
+    let mut writer = File::create(dst)?;
+    let mut buf = vec![0u8; COPY_CHUNK_SIZE];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..n])?;
+        if !tracker.advance(n as u64) {
+            return Ok(FileResult::Aborted);
+        }
+    }
+    writer.flush()?;
+    std::fs::set_permissions(dst, src_meta.permissions())?;
+    Ok(FileResult::Copied)
+}
+
+/// Result of a tree transfer: failures and whether a progress callback threw
+struct TransferReport {
+    errors: Vec<(String, Error)>,
+    attempted: usize,
+    aborted: bool,
+}
+
+/// Copy the selected contents of `src` into `dst`. When `remove_source` is set,
+/// each successfully copied file is deleted from `src` (used by move_tree).
+fn transfer_tree(src: &Path, dst: &Path, options: &CopyOptions, remove_source: bool) -> TransferReport {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+
+    match std::fs::metadata(src) {
+        Ok(meta) if meta.is_dir() => collect(src, src, options, &mut dirs, &mut files, &mut errors),
+        Ok(_) => {
+            let e = Error::new(ErrorKind::InvalidInput, "source is not a directory");
+            errors.push((src.to_string_lossy().into_owned(), e));
+        }
+        Err(e) => errors.push((src.to_string_lossy().into_owned(), e)),
+    }
+    if !errors.is_empty() && dirs.is_empty() && files.is_empty() {
+        return TransferReport { errors, attempted: 0, aborted: false };
+    }
+
+    if let Err(e) = std::fs::create_dir_all(dst) {
+        errors.push((dst.to_string_lossy().into_owned(), e));
+        return TransferReport { errors, attempted: 0, aborted: false };
+    }
+    for rel in &dirs {
+        let path = dst.join(rel);
+        if let Err(e) = std::fs::create_dir_all(&path) {
+            errors.push((path.to_string_lossy().into_owned(), e));
+        }
+    }
+
+    let mut tracker = ProgressTracker {
+        progress: options.progress,
+        done: 0,
+        total: files.iter().map(|f| f.size).sum(),
+        reported: None,
+    };
+
+    for entry in &files {
+        let src_path = src.join(&entry.rel);
+        let dst_path = dst.join(&entry.rel);
+        match copy_file(&src_path, &dst_path, entry, options.overwrite, &mut tracker) {
+            Ok(FileResult::Copied) => {
+                if remove_source && let Err(e) = std::fs::remove_file(&src_path) {
+                    errors.push((src_path.to_string_lossy().into_owned(), e));
+                }
+            }
+            Ok(FileResult::Skipped) => {}
+            Ok(FileResult::Aborted) => {
+                return TransferReport { errors, attempted: files.len(), aborted: true };
+            }
+            Err(e) => errors.push((dst_path.to_string_lossy().into_owned(), e)),
+        }
+    }
+
+    if remove_source {
+        // Deepest first, so emptied parents can go too; non-empty ones stay
+        for rel in dirs.iter().rev() {
+            let _ = std::fs::remove_dir(src.join(rel));
+        }
+        let _ = std::fs::remove_dir(src);
+    }
+
+    // Callers always see a final 100% report, even for empty or skipped trees
+    if !tracker.finish() {
+        return TransferReport { errors, attempted: files.len(), aborted: true };
+    }
+
+    TransferReport { errors, attempted: files.len(), aborted: false }
+}
+
+/// Throw one IOError summarizing every failure; path is the first failing path
+fn throw_transfer_errors(action: &str, report: TransferReport) {
+    let Some((path, first)) = report.errors.first() else {
+        return;
+    };
+    let message = if report.errors.len() == 1 {
+        format!("{} failed: {}", action, first)
+    } else {
+        let details: Vec<String> = report
+            .errors
+            .iter()
+            .map(|(path, e)| format!("{}: {}", path, e))
+            .collect();
+        format!(
+            "{} failed for {} entries ({} files selected): {}",
+            action,
+            report.errors.len(),
+            report.attempted,
+            details.join("; ")
+        )
+    };
+    throw_io_error(Error::new(first.kind(), message), path);
+}
+
+/// Create a copy options handle with default settings
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_copy_options() -> i64 {
+    OPTIONS_REGISTRY.lock().unwrap().insert(CopyOptions::default())
+}
+
+unsafe fn add_pattern(handle: i64, pattern: *const NamlString, exclude: bool) -> i64 {
+    let pattern_str = unsafe { path_from_naml_string(pattern) };
+    let compiled = match Pattern::new(&pattern_str) {
+        Ok(p) => p,
+        Err(e) => {
+            let e = Error::new(ErrorKind::InvalidInput, format!("invalid glob pattern: {}", e.msg));
+            throw_io_error(e, &pattern_str);
+            return 0;
+        }
+    };
+    let result = update_options(handle, |options| {
+        if exclude {
+            options.exclude.push(compiled);
+        } else {
+            options.include.push(compiled);
+        }
+    });
+    if let Err(e) = result {
+        throw_io_error(e, &pattern_str);
+    }
+    0
+}
+
+/// Only copy files whose relative path matches the glob
+/// Returns 0, sets exception on invalid pattern or handle
+///
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_copy_options_include(handle: i64, pattern: *const NamlString) -> i64 {
+    unsafe { add_pattern(handle, pattern, false) }
+}
+
+/// Skip files and directories whose relative path matches the glob
+/// Returns 0, sets exception on invalid pattern or handle
+///
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_copy_options_exclude(handle: i64, pattern: *const NamlString) -> i64 {
+    unsafe { add_pattern(handle, pattern, true) }
+}
+
+/// Set the overwrite policy: "overwrite", "skip", "newer", or "error"
+/// Returns 0, sets exception on unknown policy or invalid handle
+///
+/// # Safety
+/// The caller must ensure `policy` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_copy_options_overwrite(handle: i64, policy: *const NamlString) -> i64 {
+    let policy_str = unsafe { path_from_naml_string(policy) };
+    let Some(policy) = OverwritePolicy::from_str(&policy_str) else {
+        let e = Error::new(
+            ErrorKind::InvalidInput,
+            "unknown overwrite policy (expected overwrite, skip, newer, or error)",
+        );
+        throw_io_error(e, &policy_str);
+        return 0;
+    };
+    if let Err(e) = update_options(handle, |options| options.overwrite = policy) {
+        throw_io_error(e, &policy_str);
+    }
+    0
+}
+
+/// Register a progress closure called with (bytes_done, total) as data is copied
+/// Unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_copy_options_on_progress(handle: i64, func_ptr: i64, data_ptr: i64) {
+    let _ = update_options(handle, |options| {
+        options.progress = Some(Progress {
+            func: unsafe { std::mem::transmute::<usize, ProgressFn>(func_ptr as usize) },
+            data: data_ptr,
+        });
+    });
+}
+
+/// Release a copy options handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_copy_options_free(handle: i64) {
+    OPTIONS_REGISTRY.lock().unwrap().options.remove(&handle);
+}
+
+/// Recursively copy src into dst
+/// Returns 0, sets a single aggregated exception if any entry failed
+///
+/// # Safety
+/// The caller must ensure `src` is a valid pointer to a NamlString and `dst` is
+/// a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_copy_tree(
+    src: *const NamlString,
+    dst: *const NamlString,
+    handle: i64,
+) -> i64 {
+    let src_str = unsafe { path_from_naml_string(src) };
+    let dst_str = unsafe { path_from_naml_string(dst) };
+    let options = match load_options(handle) {
+        Ok(options) => options,
+        Err(e) => {
+            throw_io_error(e, &src_str);
+            return 0;
+        }
+    };
+
+    let report = transfer_tree(Path::new(&src_str), Path::new(&dst_str), &options, false);
+    if !report.aborted {
+        throw_transfer_errors("copy_tree", report);
+    }
+    0
+}
+
+/// Move src to dst. Uses a single rename when dst does not exist and no
+/// filters are set; otherwise copies and removes each transferred file.
+/// Returns 0, sets a single aggregated exception if any entry failed
+///
+/// # Safety
+/// The caller must ensure `src` is a valid pointer to a NamlString and `dst` is
+/// a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_move_tree(
+    src: *const NamlString,
+    dst: *const NamlString,
+    handle: i64,
+) -> i64 {
+    let src_str = unsafe { path_from_naml_string(src) };
+    let dst_str = unsafe { path_from_naml_string(dst) };
+    let options = match load_options(handle) {
+        Ok(options) => options,
+        Err(e) => {
+            throw_io_error(e, &src_str);
+            return 0;
+        }
+    };
+
+    let (src_path, dst_path) = (PathBuf::from(&src_str), PathBuf::from(&dst_str));
+    let unfiltered = options.include.is_empty() && options.exclude.is_empty();
+    // A rename moves no data, so the progress closure is not called
+    if unfiltered && !dst_path.exists() && std::fs::rename(&src_path, &dst_path).is_ok() {
+        return 0;
+    }
+
+    let report = transfer_tree(&src_path, &dst_path, &options, true);
+    if !report.aborted {
+        throw_transfer_errors("move_tree", report);
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tree(root: &Path) {
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("README.md"), b"readme").unwrap();
+        std::fs::write(root.join("src/main.nm"), b"fn main() {}").unwrap();
+        std::fs::write(root.join("src/nested/util.nm"), b"fn util() {}").unwrap();
+        std::fs::write(root.join("src/debug.log"), b"log").unwrap();
+        std::fs::write(root.join("target/out.bin"), b"binary").unwrap();
+    }
+
+    fn options(include: &[&str], exclude: &[&str], overwrite: OverwritePolicy) -> CopyOptions {
+        CopyOptions {
+            include: include.iter().map(|p| Pattern::new(p).unwrap()).collect(),
+            exclude: exclude.iter().map(|p| Pattern::new(p).unwrap()).collect(),
+            overwrite,
+            progress: None,
+        }
+    }
+
+    #[test]
+    fn test_copy_tree_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        make_tree(&src);
+
+        let opts = options(&[], &["target", "*.log"], OverwritePolicy::Overwrite);
+        let report = transfer_tree(&src, &dst, &opts, false);
+        assert!(report.errors.is_empty());
+        assert!(dst.join("README.md").exists());
+        assert!(dst.join("src/nested/util.nm").exists());
+        assert!(!dst.join("src/debug.log").exists());
+        assert!(!dst.join("target").exists());
+
+        let dst2 = dir.path().join("c");
+        let opts = options(&["src/**/*.nm"], &[], OverwritePolicy::Overwrite);
+        transfer_tree(&src, &dst2, &opts, false);
+        assert!(dst2.join("src/main.nm").exists());
+        assert!(dst2.join("src/nested/util.nm").exists());
+        assert!(!dst2.join("README.md").exists());
+    }
+
+    #[test]
+    fn test_overwrite_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        make_tree(&src);
+        std::fs::create_dir_all(&dst).unwrap();
+        std::fs::write(dst.join("README.md"), b"existing").unwrap();
+
+        transfer_tree(&src, &dst, &options(&[], &[], OverwritePolicy::Skip), false);
+        assert_eq!(std::fs::read(dst.join("README.md")).unwrap(), b"existing");
+
+        let report = transfer_tree(&src, &dst, &options(&[], &[], OverwritePolicy::Error), false);
+        assert!(report.errors.iter().any(|(_, e)| e.kind() == ErrorKind::AlreadyExists));
+        assert!(report.errors.len() >= 2);
+
+        transfer_tree(&src, &dst, &options(&[], &[], OverwritePolicy::Overwrite), false);
+        assert_eq!(std::fs::read(dst.join("README.md")).unwrap(), b"readme");
+    }
+
+    #[test]
+    fn test_move_tree_keeps_excluded() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        make_tree(&src);
+
+        let report = transfer_tree(&src, &dst, &options(&[], &["*.log"], OverwritePolicy::Overwrite), true);
+        assert!(report.errors.is_empty());
+        assert!(dst.join("src/main.nm").exists());
+        assert!(!src.join("src/main.nm").exists());
+        assert!(!src.join("target").exists());
+        assert!(src.join("src/debug.log").exists());
+    }
+
+    #[test]
+    fn test_progress_reports_total() {
+        thread_local! {
+            static LAST: std::cell::Cell<(i64, i64)> = const { std::cell::Cell::new((0, 0)) };
+        }
+        unsafe extern "C" fn record(_data: i64, done: i64, total: i64) -> i64 {
+            LAST.with(|last| last.set((done, total)));
+            0
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let (src, dst) = (dir.path().join("a"), dir.path().join("b"));
+        make_tree(&src);
+
+        let mut opts = options(&[], &[], OverwritePolicy::Overwrite);
+        opts.progress = Some(Progress { func: record, data: 0 });
+        transfer_tree(&src, &dst, &opts, false);
+        let expected = (6 + 12 + 12 + 3 + 6) as i64;
+        assert_eq!(LAST.with(|last| last.get()), (expected, expected));
+    }
+}
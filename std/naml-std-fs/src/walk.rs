@@ -0,0 +1,378 @@
+///
+/// Directory Walking
+///
+/// Recursively lists the entries below a directory, depth first with the
+/// entries of each directory sorted by name. Options live in a handle
+/// registry like copy options; handle 0 means "all defaults" (no depth
+/// limit, symlinks not followed, no filters).
+///
+/// Glob patterns match paths relative to the walk root using `/` separators.
+/// A pattern without `/` matches against the entry's name at any depth.
+/// Excluded entries are not returned and excluded directories are not
+/// descended into. Include patterns select which entries are returned;
+/// directories are still descended into when they do not match.
+///
+/// Returned paths are full paths (the root joined with the relative path),
+/// like `list_dir`. The root itself is never returned.
+///
+/// Functions:
+/// - `walk_options() -> int` - Create an options handle
+/// - `walk_options_max_depth(opts, depth)` - Limit depth (1 = direct entries, 0 = unlimited)
+/// - `walk_options_follow_symlinks(opts, follow)` - Descend into symlinked directories
+/// - `walk_options_include(opts, pattern)` - Only return entries matching pattern
+/// - `walk_options_exclude(opts, pattern)` - Skip entries and directories matching pattern
+/// - `walk_options_free(opts)` - Release an options handle
+/// - `walk_dir(path, opts) -> [string]` - Collect every selected path
+/// - `walk_open(path, opts) -> int` - Start a streaming walk
+/// - `walk_next(handle) -> option<string>` - Next selected path, none when done
+/// - `walk_close(handle)` - Release a walk handle
+///
+
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use glob::Pattern;
+use naml_std_core::{naml_array_new, naml_array_push, naml_string_new, NamlArray, NamlString};
+use walkdir::WalkDir;
+
+use crate::tree::{pattern_matches, relative_slash_path};
+use crate::{path_from_naml_string, throw_io_error};
+
+#[derive(Clone, Default)]
+struct WalkOptions {
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+/// A walk in progress: the underlying iterator plus the filters applied to it
+struct Walker {
+    root: PathBuf,
+    entries: walkdir::IntoIter,
+    options: WalkOptions,
+}
+
+impl Walker {
+    fn open(root: &Path, options: WalkOptions) -> Result<Self, Error> {
+        if !std::fs::metadata(root)?.is_dir() {
+            return Err(Error::new(ErrorKind::InvalidInput, "not a directory"));
+        }
+        let mut walk = WalkDir::new(root)
+            .min_depth(1)
+            .follow_links(options.follow_symlinks)
+            .sort_by_file_name();
+        if let Some(depth) = options.max_depth {
+            walk = walk.max_depth(depth);
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            entries: walk.into_iter(),
+            options,
+        })
+    }
+
+    /// Advance to the next selected entry. After an error the walk can continue.
+    fn next_path(&mut self) -> Option<Result<String, walkdir::Error>> {
+        loop {
+            let entry = match self.entries.next()? {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+            let rel = relative_slash_path(&self.root, entry.path());
+            if self.options.exclude.iter().any(|p| pattern_matches(p, &rel)) {
+                if entry.file_type().is_dir() {
+                    self.entries.skip_current_dir();
+                }
+                continue;
+            }
+            if self.options.include.is_empty() || self.options.include.iter().any(|p| pattern_matches(p, &rel)) {
+                return Some(Ok(entry.path().to_string_lossy().into_owned()));
+            }
+        }
+    }
+}
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static OPTIONS: LazyLock<Mutex<HashMap<i64, WalkOptions>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Open walks, by handle. Each walker has its own lock so a slow directory
+/// read does not block other walks.
+static WALKERS: LazyLock<Mutex<HashMap<i64, Arc<Mutex<Walker>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn load_options(handle: i64) -> Result<WalkOptions, Error> {
+    if handle == 0 {
+        return Ok(WalkOptions::default());
+    }
+    OPTIONS
+        .lock()
+        .unwrap()
+        .get(&handle)
+        .cloned()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("invalid walk options handle {}", handle)))
+}
+
+/// Apply `update` to the options behind `handle`, throwing on unknown handles
+fn update_options(handle: i64, path: &str, update: impl FnOnce(&mut WalkOptions)) -> i64 {
+    match OPTIONS.lock().unwrap().get_mut(&handle) {
+        Some(options) => update(options),
+        None => {
+            let e = Error::new(ErrorKind::InvalidInput, format!("invalid walk options handle {}", handle));
+            throw_io_error(e, path);
+        }
+    }
+    0
+}
+
+/// Throw a walk error, reporting the entry that failed when known
+fn throw_walk_error(e: walkdir::Error, root: &str) {
+    let path = e
+        .path()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.to_string());
+    throw_io_error(e.into(), &path);
+}
+
+/// Create a walk options handle with default settings
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_walk_options() -> i64 {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    OPTIONS.lock().unwrap().insert(handle, WalkOptions::default());
+    handle
+}
+
+/// Limit how deep the walk goes; 1 returns only the root's direct entries
+/// and 0 or less removes the limit
+/// Returns 0, sets exception on invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_walk_options_max_depth(handle: i64, depth: i64) -> i64 {
+    let depth = if depth > 0 { Some(depth as usize) } else { None };
+    update_options(handle, "", |options| options.max_depth = depth)
+}
+
+/// Descend into symlinked directories (symlink loops are reported as errors)
+/// Returns 0, sets exception on invalid handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_walk_options_follow_symlinks(handle: i64, follow: i64) -> i64 {
+    update_options(handle, "", |options| options.follow_symlinks = follow != 0)
+}
+
+unsafe fn add_pattern(handle: i64, pattern: *const NamlString, exclude: bool) -> i64 {
+    let pattern_str = unsafe { path_from_naml_string(pattern) };
+    let compiled = match Pattern::new(&pattern_str) {
+        Ok(p) => p,
+        Err(e) => {
+            let e = Error::new(ErrorKind::InvalidInput, format!("invalid glob pattern: {}", e.msg));
+            throw_io_error(e, &pattern_str);
+            return 0;
+        }
+    };
+    update_options(handle, &pattern_str, |options| {
+        if exclude {
+            options.exclude.push(compiled);
+        } else {
+            options.include.push(compiled);
+        }
+    })
+}
+
+/// Only return entries whose relative path matches the glob
+/// Returns 0, sets exception on invalid pattern or handle
+///
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_walk_options_include(handle: i64, pattern: *const NamlString) -> i64 {
+    unsafe { add_pattern(handle, pattern, false) }
+}
+
+/// Skip entries whose relative path matches the glob, without descending
+/// into matching directories
+/// Returns 0, sets exception on invalid pattern or handle
+///
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_walk_options_exclude(handle: i64, pattern: *const NamlString) -> i64 {
+    unsafe { add_pattern(handle, pattern, true) }
+}
+
+/// Release a walk options handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_walk_options_free(handle: i64) {
+    OPTIONS.lock().unwrap().remove(&handle);
+}
+
+/// Recursively collect the selected paths below a directory
+/// Returns null and sets exception on the first error
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_walk_dir(path: *const NamlString, options: i64) -> *mut NamlArray {
+    let path_str = unsafe { path_from_naml_string(path) };
+    let mut walker = match load_options(options).and_then(|o| Walker::open(Path::new(&path_str), o)) {
+        Ok(walker) => walker,
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            return std::ptr::null_mut();
+        }
+    };
+
+    let mut paths = Vec::new();
+    while let Some(next) = walker.next_path() {
+        match next {
+            Ok(p) => paths.push(p),
+            Err(e) => {
+                throw_walk_error(e, &path_str);
+                return std::ptr::null_mut();
+            }
+        }
+    }
+
+    unsafe {
+        let arr = naml_array_new(paths.len());
+        for p in &paths {
+            naml_array_push(arr, naml_string_new(p.as_ptr(), p.len()) as i64);
+        }
+        arr
+    }
+}
+
+/// Start a streaming walk; entries are read lazily by `naml_fs_walk_next`
+/// Returns the walk handle, or -1 and sets exception
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_walk_open(path: *const NamlString, options: i64) -> i64 {
+    let path_str = unsafe { path_from_naml_string(path) };
+    match load_options(options).and_then(|o| Walker::open(Path::new(&path_str), o)) {
+        Ok(walker) => {
+            let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+            WALKERS.lock().unwrap().insert(handle, Arc::new(Mutex::new(walker)));
+            handle
+        }
+        Err(e) => {
+            throw_io_error(e, &path_str);
+            -1
+        }
+    }
+}
+
+/// Next selected path of a walk, or null when the walk is finished
+/// Returns null and sets exception on an entry error or invalid handle;
+/// later calls continue with the following entries
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_walk_next(handle: i64) -> *mut NamlString {
+    let Some(walker) = WALKERS.lock().unwrap().get(&handle).cloned() else {
+        let e = Error::new(ErrorKind::InvalidInput, format!("invalid walk handle {}", handle));
+        throw_io_error(e, "");
+        return std::ptr::null_mut();
+    };
+    let mut walker = walker.lock().unwrap();
+    match walker.next_path() {
+        Some(Ok(p)) => unsafe { naml_string_new(p.as_ptr(), p.len()) },
+        Some(Err(e)) => {
+            let root = walker.root.to_string_lossy().into_owned();
+            throw_walk_error(e, &root);
+            std::ptr::null_mut()
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Release a walk handle; unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_walk_close(handle: i64) {
+    WALKERS.lock().unwrap().remove(&handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        std::fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "m").unwrap();
+        std::fs::write(dir.path().join("src/nested/lib.rs"), "l").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/out.rs"), "o").unwrap();
+        dir
+    }
+
+    fn walk(root: &Path, options: WalkOptions) -> Vec<String> {
+        let mut walker = Walker::open(root, options).unwrap();
+        let mut paths = Vec::new();
+        while let Some(next) = walker.next_path() {
+            paths.push(relative_slash_path(root, Path::new(&next.unwrap())));
+        }
+        paths
+    }
+
+    #[test]
+    fn test_walk_order_and_depth() {
+        let dir = sample_tree();
+        assert_eq!(
+            walk(dir.path(), WalkOptions::default()),
+            vec!["a.txt", "src", "src/main.rs", "src/nested", "src/nested/lib.rs", "target", "target/out.rs"]
+        );
+
+        let shallow = WalkOptions { max_depth: Some(1), ..Default::default() };
+        assert_eq!(walk(dir.path(), shallow), vec!["a.txt", "src", "target"]);
+    }
+
+    #[test]
+    fn test_walk_filters() {
+        let dir = sample_tree();
+        let options = WalkOptions {
+            include: vec![Pattern::new("*.rs").unwrap()],
+            exclude: vec![Pattern::new("target").unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(walk(dir.path(), options), vec!["src/main.rs", "src/nested/lib.rs"]);
+    }
+
+    #[test]
+    fn test_walk_handles() {
+        let dir = sample_tree();
+        let opts = naml_fs_walk_options();
+        naml_fs_walk_options_max_depth(opts, 1);
+        let options = load_options(opts).unwrap();
+        naml_fs_walk_options_free(opts);
+        assert!(load_options(opts).is_err());
+
+        let walker = Walker::open(dir.path(), options).unwrap();
+        let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+        WALKERS.lock().unwrap().insert(handle, Arc::new(Mutex::new(walker)));
+        let mut count = 0;
+        while !naml_fs_walk_next(handle).is_null() {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        naml_fs_walk_close(handle);
+        assert!(!WALKERS.lock().unwrap().contains_key(&handle));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_follow_symlinks() {
+        let dir = sample_tree();
+        std::os::unix::fs::symlink(dir.path().join("src/nested"), dir.path().join("link")).unwrap();
+
+        let plain = walk(dir.path(), WalkOptions::default());
+        assert!(plain.contains(&"link".to_string()));
+        assert!(!plain.contains(&"link/lib.rs".to_string()));
+
+        let follow = WalkOptions { follow_symlinks: true, ..Default::default() };
+        assert!(walk(dir.path(), follow).contains(&"link/lib.rs".to_string()));
+    }
+}
@@ -0,0 +1,288 @@
+///
+/// File Watching
+///
+/// Watches a file or directory and calls a naml closure for every change.
+/// Each watch is a `notify::RecommendedWatcher` (inotify on Linux, FSEvents
+/// on macOS, ReadDirectoryChangesW on Windows), so changes are reported as
+/// the OS delivers them instead of by rescanning the tree. The watcher's
+/// event thread turns each notification into `fs_event` records and hands
+/// them to the M:N scheduler, so callbacks run on worker threads just like
+/// timer callbacks.
+///
+/// A watch has at most one dispatch task in flight. Events that arrive
+/// while a task is still running are queued and picked up by that task, so
+/// callbacks for one watch never overlap and always see events in the order
+/// they arrived.
+///
+/// Every write is reported, so a burst of writes to one file yields one
+/// "modify" per write. Access-time changes and changes to a directory's own
+/// size or mtime are not reported. A rename is reported as a "remove" of
+/// the old path and a "create" of the new one.
+///
+/// Events are `fs_event` records with three fields:
+/// - `kind: string` - "create", "modify" or "remove"
+/// - `path: string` - Full path of the entry that changed
+/// - `timestamp: int` - Detection time in milliseconds since the Unix epoch
+///
+/// Functions:
+/// - `watch(path, fn(fs_event)) -> int` - Watch a file or a directory's direct entries
+/// - `watch_recursive(path, fn(fs_event)) -> int` - Watch a directory tree
+/// - `unwatch(handle)` - Stop a watch; unknown handles are ignored
+///
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use naml_std_core::{naml_string_new, naml_struct_new, naml_struct_set_field, NamlString, NamlStruct};
+use naml_std_threads::{naml_alloc_closure_data, naml_spawn_closure};
+use notify::event::{MetadataKind, ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{path_from_naml_string, throw_io_error};
+
+/// Compiled event closure: fn(event: fs_event)
+type EventFn = unsafe extern "C" fn(data_ptr: i64, event: *mut NamlStruct) -> i64;
+
+const FS_EVENT_STRUCT_TYPE_ID: u32 = 0xFFFF_0013;
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+/// Running watches, by handle. Dropping a watcher stops it.
+static WATCHES: LazyLock<Mutex<HashMap<i64, RecommendedWatcher>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The callback of one watch and the events waiting for it
+struct WatchState {
+    func: EventFn,
+    data: i64,
+    queue: Mutex<EventQueue>,
+}
+
+struct EventQueue {
+    events: Vec<*mut NamlStruct>,
+    /// Whether a dispatch task is running; it drains the queue before exiting
+    in_flight: bool,
+}
+
+// The closure data and queued events belong to the watch and are only
+// touched under the queue lock or by the single dispatch task.
+unsafe impl Send for WatchState {}
+unsafe impl Sync for WatchState {}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_watch(
+    path: *const NamlString,
+    func_ptr: i64,
+    data_ptr: i64,
+) -> i64 {
+    unsafe { start_watch(path, func_ptr, data_ptr, RecursiveMode::NonRecursive) }
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fs_watch_recursive(
+    path: *const NamlString,
+    func_ptr: i64,
+    data_ptr: i64,
+) -> i64 {
+    unsafe { start_watch(path, func_ptr, data_ptr, RecursiveMode::Recursive) }
+}
+
+/// Stop a watch. Callbacks already dispatched still run.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fs_unwatch(handle: i64) {
+    let watcher = WATCHES.lock().unwrap().remove(&handle);
+    drop(watcher);
+}
+
+unsafe fn start_watch(path: *const NamlString, func_ptr: i64, data_ptr: i64, mode: RecursiveMode) -> i64 {
+    let path_str = unsafe { path_from_naml_string(path) };
+    let root = PathBuf::from(&path_str);
+
+    // Report a missing root as the usual "not found" IOError
+    if let Err(e) = std::fs::metadata(&root) {
+        throw_io_error(e, &path_str);
+        return -1;
+    }
+
+    let state = Arc::new(WatchState {
+        func: unsafe { std::mem::transmute::<usize, EventFn>(func_ptr as usize) },
+        data: data_ptr,
+        queue: Mutex::new(EventQueue {
+            events: Vec::new(),
+            in_flight: false,
+        }),
+    });
+    let handler = move |result: notify::Result<Event>| {
+        if let Ok(event) = result {
+            queue_events(&state, &event);
+        }
+    };
+    let watcher = RecommendedWatcher::new(handler, notify::Config::default())
+        .and_then(|mut watcher| watcher.watch(&root, mode).map(|()| watcher));
+    match watcher {
+        Ok(watcher) => {
+            let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+            WATCHES.lock().unwrap().insert(handle, watcher);
+            handle
+        }
+        Err(e) => {
+            throw_io_error(io_error(e), &path_str);
+            -1
+        }
+    }
+}
+
+fn io_error(error: notify::Error) -> std::io::Error {
+    match error.kind {
+        notify::ErrorKind::Io(e) => e,
+        notify::ErrorKind::PathNotFound => std::io::ErrorKind::NotFound.into(),
+        notify::ErrorKind::MaxFilesWatch => {
+            std::io::Error::other("the limit on watched files has been reached")
+        }
+        notify::ErrorKind::Generic(message) => std::io::Error::other(message),
+        other => std::io::Error::other(format!("{:?}", other)),
+    }
+}
+
+/// The fs_event kinds and paths a notification stands for
+fn changes(event: &Event) -> Vec<(&'static str, PathBuf)> {
+    let all = |kind: &'static str| event.paths.iter().map(|p| (kind, p.clone())).collect();
+    match &event.kind {
+        EventKind::Create(_) => all("create"),
+        EventKind::Remove(_) => all("remove"),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => all("remove"),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => all("create"),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => match event.paths.as_slice() {
+            [from, to] => vec![("remove", from.clone()), ("create", to.clone())],
+            _ => Vec::new(),
+        },
+        // The backend could not tell which side of a rename this path is
+        EventKind::Modify(ModifyKind::Name(_)) => event
+            .paths
+            .iter()
+            .map(|p| (if p.exists() { "create" } else { "remove" }, p.clone()))
+            .collect(),
+        EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime)) => Vec::new(),
+        // Directory sizes and mtimes change with their contents; only their
+        // appearance and disappearance are reported
+        EventKind::Modify(_) => event
+            .paths
+            .iter()
+            .filter(|p| !p.is_dir())
+            .map(|p| ("modify", p.clone()))
+            .collect(),
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => Vec::new(),
+    }
+}
+
+/// Queue the events of a notification and start a dispatch task unless one
+/// is already running
+fn queue_events(state: &Arc<WatchState>, event: &Event) {
+    let changes = changes(event);
+    if changes.is_empty() {
+        return;
+    }
+    let mut queue = state.queue.lock().unwrap();
+    queue
+        .events
+        .extend(changes.iter().map(|(kind, path)| new_event(kind, path)));
+    if !queue.in_flight {
+        queue.in_flight = true;
+        let events = std::mem::take(&mut queue.events);
+        drop(queue);
+        dispatch(Arc::clone(state), events);
+    }
+}
+
+/// Build an fs_event struct: kind, path, timestamp at fields 0..3
+fn new_event(kind: &str, path: &Path) -> *mut NamlStruct {
+    let path_str = path.to_string_lossy();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    unsafe {
+        let event = naml_struct_new(FS_EVENT_STRUCT_TYPE_ID, 3);
+        naml_struct_set_field(event, 0, naml_string_new(kind.as_ptr(), kind.len()) as i64);
+        naml_struct_set_field(event, 1, naml_string_new(path_str.as_ptr(), path_str.len()) as i64);
+        naml_struct_set_field(event, 2, timestamp);
+        event
+    }
+}
+
+/// Watch state and a batch of events handed to a scheduler task
+struct Dispatch {
+    state: Arc<WatchState>,
+    events: Vec<*mut NamlStruct>,
+}
+
+/// Run the callback for each event of a batch on the scheduler
+fn dispatch(state: Arc<WatchState>, events: Vec<*mut NamlStruct>) {
+    let batch = Box::new(Dispatch { state, events });
+    let size = std::mem::size_of::<*mut Dispatch>();
+    let data = naml_alloc_closure_data(size);
+    unsafe { *(data as *mut *mut Dispatch) = Box::into_raw(batch) };
+    naml_spawn_closure(run_dispatch, data, size);
+}
+
+/// Scheduler entry point: run the batch, then whatever was queued while it
+/// ran. The scheduler frees the closure data afterwards.
+extern "C" fn run_dispatch(data: *mut u8) {
+    let Dispatch { state, mut events } = *unsafe { Box::from_raw(*(data as *mut *mut Dispatch)) };
+    loop {
+        for event in &events {
+            unsafe { (state.func)(state.data, *event) };
+        }
+        let mut queue = state.queue.lock().unwrap();
+        if queue.events.is_empty() {
+            queue.in_flight = false;
+            return;
+        }
+        events = std::mem::take(&mut queue.events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    #[test]
+    fn test_changes_map_notify_events() {
+        let create = Event::new(EventKind::Create(CreateKind::File)).add_path("/w/new".into());
+        assert_eq!(changes(&create), vec![("create", PathBuf::from("/w/new"))]);
+
+        let write = Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+            .add_path("/w/changed".into());
+        assert_eq!(changes(&write), vec![("modify", PathBuf::from("/w/changed"))]);
+
+        let remove = Event::new(EventKind::Remove(RemoveKind::File)).add_path("/w/gone".into());
+        assert_eq!(changes(&remove), vec![("remove", PathBuf::from("/w/gone"))]);
+
+        let rename = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path("/w/old".into())
+            .add_path("/w/new".into());
+        assert_eq!(
+            changes(&rename),
+            vec![("remove", PathBuf::from("/w/old")), ("create", PathBuf::from("/w/new"))]
+        );
+
+        let atime = Event::new(EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime)))
+            .add_path("/w/read".into());
+        assert!(changes(&atime).is_empty());
+    }
+
+    #[test]
+    fn test_directory_modify_is_not_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let touched = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(dir.path().to_path_buf());
+        assert!(changes(&touched).is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+///
+/// naml-std-fsm - State Machines
+///
+/// Declarative finite state machines with validation, so workflows are
+/// described as data and checked instead of hand-built on switch statements.
+///
+/// ## Functions
+///
+/// - `open_machine(initial: string) -> int` - Create a machine in its initial state
+/// - `add_state(machine: int, name: string)` - Declare a state
+/// - `add_final_state(machine: int, name: string)` - Declare a state where the workflow may end
+/// - `add_transition(machine: int, from: string, event: string, to: string) throws InvalidTransition`
+/// - `set_guard(machine: int, from: string, event: string, guard: fn() -> bool) throws InvalidTransition`
+/// - `set_action(machine: int, from: string, event: string, action: fn(string, string)) throws InvalidTransition`
+/// - `fire(machine: int, event: string) -> string throws InvalidTransition` - Take a transition
+/// - `can_fire(machine: int, event: string) -> bool` - Whether `fire` would succeed
+/// - `current_state(machine: int) -> string`
+/// - `validate_machine(machine: int) -> [string]` - Problems with the definition
+/// - `to_dot(machine: int) -> string` - Graphviz DOT source
+/// - `close_machine(machine: int)` - Release a machine
+///
+/// ## Firing
+///
+/// `fire` looks up the transition for the current state and event, runs its
+/// guard if it has one, moves to the target state and then runs the action
+/// with the old and new state. The machine is not locked while guards and
+/// actions run, so they may inspect or fire the same machine.
+///
+
+mod machine;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use naml_std_core::{
+    naml_array_new, naml_array_push, naml_exception_set_typed, naml_stack_capture,
+    naml_string_decref, naml_string_new, NamlArray, NamlString, EXCEPTION_TYPE_INVALID_TRANSITION,
+};
+
+use machine::{Closure, Machine};
+
+/// Guard signature: closure data; returns non-zero to allow the transition
+type GuardFn = unsafe extern "C" fn(data_ptr: i64) -> i64;
+
+/// Action signature: closure data, old state, new state
+type ActionFn =
+    unsafe extern "C" fn(data_ptr: i64, from: *mut NamlString, to: *mut NamlString) -> i64;
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static MACHINES: LazyLock<Mutex<HashMap<i64, Machine>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+/// Throw InvalidTransition with layout: message@0, stack@8, state@16, event@24
+fn throw_invalid_transition(message: &str, state: &str, event: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(32, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate InvalidTransition");
+        }
+        *(ptr as *mut *mut NamlString) = naml_from_string(message);
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut *mut NamlString) = naml_from_string(state);
+        *(ptr.add(24) as *mut *mut NamlString) = naml_from_string(event);
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_INVALID_TRANSITION);
+    }
+}
+
+fn unknown_machine(handle: i64, event: &str) {
+    throw_invalid_transition(&format!("unknown machine handle {}", handle), "", event);
+}
+
+/// Create a machine starting in `initial`
+///
+/// # Safety
+/// The caller must ensure `initial` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_open(initial: *const NamlString) -> i64 {
+    let initial = unsafe { string_from_naml(initial) };
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
+    MACHINES.lock().unwrap().insert(handle, Machine::new(&initial));
+    handle
+}
+
+/// Declare a state, final if `is_final` is non-zero
+///
+/// # Safety
+/// The caller must ensure `name` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_add_state(handle: i64, name: *const NamlString, is_final: i64) {
+    let name = unsafe { string_from_naml(name) };
+    if let Some(machine) = MACHINES.lock().unwrap().get_mut(&handle) {
+        machine.add_state(&name, is_final != 0);
+    }
+}
+
+/// Add a transition; throws if `(from, event)` already has one
+///
+/// # Safety
+/// The caller must ensure `from` is a valid pointer to a NamlString, `event` is
+/// a valid pointer to a NamlString and `to` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_add_transition(
+    handle: i64,
+    from: *const NamlString,
+    event: *const NamlString,
+    to: *const NamlString,
+) {
+    let (from, event, to) = unsafe { (string_from_naml(from), string_from_naml(event), string_from_naml(to)) };
+    let mut machines = MACHINES.lock().unwrap();
+    let Some(machine) = machines.get_mut(&handle) else {
+        drop(machines);
+        unknown_machine(handle, &event);
+        return;
+    };
+    if let Err(message) = machine.add_transition(&from, &event, &to) {
+        drop(machines);
+        throw_invalid_transition(&message, &from, &event);
+    }
+}
+
+/// Attach a guard or action closure to an existing transition
+fn set_closure(handle: i64, from: &str, event: &str, closure: Closure, is_guard: bool) {
+    let mut machines = MACHINES.lock().unwrap();
+    let Some(machine) = machines.get_mut(&handle) else {
+        drop(machines);
+        unknown_machine(handle, event);
+        return;
+    };
+    match machine.find_mut(from, event) {
+        Some(t) if is_guard => t.guard = Some(closure),
+        Some(t) => t.action = Some(closure),
+        None => {
+            drop(machines);
+            let message = format!("no transition from '{}' on '{}'", from, event);
+            throw_invalid_transition(&message, from, event);
+        }
+    }
+}
+
+/// Only allow the transition when the guard closure returns true
+///
+/// # Safety
+/// The caller must ensure `from` is a valid pointer to a NamlString and `event`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_set_guard(
+    handle: i64,
+    from: *const NamlString,
+    event: *const NamlString,
+    func_ptr: i64,
+    data_ptr: i64,
+) {
+    let (from, event) = unsafe { (string_from_naml(from), string_from_naml(event)) };
+    set_closure(handle, &from, &event, Closure { func: func_ptr, data: data_ptr }, true);
+}
+
+/// Run the action closure with (from, to) after the transition is taken
+///
+/// # Safety
+/// The caller must ensure `from` is a valid pointer to a NamlString and `event`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_set_action(
+    handle: i64,
+    from: *const NamlString,
+    event: *const NamlString,
+    func_ptr: i64,
+    data_ptr: i64,
+) {
+    let (from, event) = unsafe { (string_from_naml(from), string_from_naml(event)) };
+    set_closure(handle, &from, &event, Closure { func: func_ptr, data: data_ptr }, false);
+}
+
+fn run_guard(guard: Option<Closure>) -> bool {
+    match guard {
+        Some(g) => unsafe {
+            let func = std::mem::transmute::<usize, GuardFn>(g.func as usize);
+            func(g.data) != 0
+        },
+        None => true,
+    }
+}
+
+/// The transition `event` would take from the current state: (from, to,
+/// guard, action), or the error message when there is none
+fn lookup(handle: i64, event: &str) -> Result<(String, String, Option<Closure>, Option<Closure>), String> {
+    let machines = MACHINES.lock().unwrap();
+    let machine = machines
+        .get(&handle)
+        .ok_or_else(|| format!("unknown machine handle {}", handle))?;
+    let from = machine.current.clone();
+    match machine.find(&from, event) {
+        Some(t) => Ok((from, t.to.clone(), t.guard, t.action)),
+        None => Err(format!("no transition from '{}' on '{}'", from, event)),
+    }
+}
+
+fn current(handle: i64) -> String {
+    MACHINES.lock().unwrap().get(&handle).map(|m| m.current.clone()).unwrap_or_default()
+}
+
+/// Take the transition for `event` from the current state and return the
+/// new state. Throws if there is no such transition or its guard refuses.
+///
+/// # Safety
+/// The caller must ensure `event` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_fire(handle: i64, event: *const NamlString) -> *mut NamlString {
+    let event = unsafe { string_from_naml(event) };
+    let (from, to, guard, action) = match lookup(handle, &event) {
+        Ok(found) => found,
+        Err(message) => {
+            throw_invalid_transition(&message, &current(handle), &event);
+            return std::ptr::null_mut();
+        }
+    };
+
+    if !run_guard(guard) {
+        let message = format!("guard refused transition from '{}' on '{}'", from, event);
+        throw_invalid_transition(&message, &from, &event);
+        return std::ptr::null_mut();
+    }
+
+    {
+        let mut machines = MACHINES.lock().unwrap();
+        let Some(machine) = machines.get_mut(&handle) else {
+            drop(machines);
+            unknown_machine(handle, &event);
+            return std::ptr::null_mut();
+        };
+        machine.current = to.clone();
+    }
+
+    if let Some(a) = action {
+        unsafe {
+            let func = std::mem::transmute::<usize, ActionFn>(a.func as usize);
+            let from_ptr = naml_from_string(&from);
+            let to_ptr = naml_from_string(&to);
+            func(a.data, from_ptr, to_ptr);
+            naml_string_decref(from_ptr);
+            naml_string_decref(to_ptr);
+        }
+    }
+    unsafe { naml_from_string(&to) }
+}
+
+/// 1 if `fire(event)` would succeed now (the guard is run), 0 otherwise
+///
+/// # Safety
+/// The caller must ensure `event` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_fsm_can_fire(handle: i64, event: *const NamlString) -> i64 {
+    let event = unsafe { string_from_naml(event) };
+    match lookup(handle, &event) {
+        Ok((_, _, guard, _)) => run_guard(guard) as i64,
+        Err(_) => 0,
+    }
+}
+
+/// Name of the current state, or an empty string for an unknown handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fsm_current(handle: i64) -> *mut NamlString {
+    unsafe { naml_from_string(&current(handle)) }
+}
+
+/// Problems with the machine's definition, empty when it is well formed
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fsm_validate(handle: i64) -> *mut NamlArray {
+    let problems = match MACHINES.lock().unwrap().get(&handle) {
+        Some(machine) => machine.validate(),
+        None => vec![format!("unknown machine handle {}", handle)],
+    };
+    unsafe {
+        let arr = naml_array_new(problems.len());
+        for problem in &problems {
+            naml_array_push(arr, naml_from_string(problem) as i64);
+        }
+        arr
+    }
+}
+
+/// Graphviz DOT source for the machine
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fsm_to_dot(handle: i64) -> *mut NamlString {
+    let dot = MACHINES.lock().unwrap().get(&handle).map(|m| m.to_dot()).unwrap_or_default();
+    unsafe { naml_from_string(&dot) }
+}
+
+/// Release a machine
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_fsm_close(handle: i64) {
+    MACHINES.lock().unwrap().remove(&handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use naml_std_core::{naml_exception_clear, naml_exception_get_type_id};
+
+    unsafe extern "C" fn refuse(_data: i64) -> i64 {
+        0
+    }
+
+    fn s(text: &str) -> *mut NamlString {
+        unsafe { naml_from_string(text) }
+    }
+
+    #[test]
+    fn test_fire_follows_transitions_and_guards() {
+        unsafe {
+            let m = naml_fsm_open(s("cart"));
+            naml_fsm_add_transition(m, s("cart"), s("pay"), s("paid"));
+            naml_fsm_add_transition(m, s("paid"), s("ship"), s("shipped"));
+            naml_fsm_set_guard(m, s("paid"), s("ship"), refuse as *const () as usize as i64, 0);
+
+            assert_eq!(naml_fsm_can_fire(m, s("pay")), 1);
+            assert_eq!((*naml_fsm_fire(m, s("pay"))).as_str(), "paid");
+
+            assert_eq!(naml_fsm_can_fire(m, s("ship")), 0);
+            assert!(naml_fsm_fire(m, s("ship")).is_null());
+            assert_eq!(naml_exception_get_type_id(), EXCEPTION_TYPE_INVALID_TRANSITION);
+            naml_exception_clear();
+            assert_eq!((*naml_fsm_current(m)).as_str(), "paid");
+
+            assert!(naml_fsm_fire(m, s("pay")).is_null());
+            naml_exception_clear();
+            naml_fsm_close(m);
+        }
+    }
+}
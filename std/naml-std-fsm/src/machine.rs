@@ -0,0 +1,271 @@
+///
+/// Machine Definitions, Validation and DOT Export
+///
+/// A machine is a set of named states, a subset of which are final, and
+/// transitions keyed by `(from, event)`. At most one transition exists for
+/// each key, so firing an event is deterministic. Guards and actions are
+/// opaque here; `lib.rs` stores the closure pointers and calls them.
+///
+/// States may be declared before or after the transitions that use them.
+/// `validate` reports everything that looks like a modelling mistake
+/// instead of rejecting registrations one at a time.
+///
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt::Write;
+
+/// A naml closure: function pointer and captured data
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Closure {
+    pub func: i64,
+    pub data: i64,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Transition {
+    pub from: String,
+    pub event: String,
+    pub to: String,
+    pub guard: Option<Closure>,
+    pub action: Option<Closure>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Machine {
+    pub initial: String,
+    pub current: String,
+    /// Declared states in declaration order, with whether each is final
+    pub states: Vec<(String, bool)>,
+    pub transitions: Vec<Transition>,
+}
+
+impl Machine {
+    pub fn new(initial: &str) -> Self {
+        Self {
+            initial: initial.to_string(),
+            current: initial.to_string(),
+            states: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Declare a state; declaring it again only updates whether it is final
+    pub fn add_state(&mut self, name: &str, is_final: bool) {
+        match self.states.iter_mut().find(|(s, _)| s == name) {
+            Some(state) => state.1 = is_final,
+            None => self.states.push((name.to_string(), is_final)),
+        }
+    }
+
+    /// Add a transition; fails if `(from, event)` already has one
+    pub fn add_transition(&mut self, from: &str, event: &str, to: &str) -> Result<(), String> {
+        if self.find(from, event).is_some() {
+            return Err(format!(
+                "transition from '{}' on '{}' is already defined",
+                from, event
+            ));
+        }
+        self.transitions.push(Transition {
+            from: from.to_string(),
+            event: event.to_string(),
+            to: to.to_string(),
+            guard: None,
+            action: None,
+        });
+        Ok(())
+    }
+
+    pub fn find(&self, from: &str, event: &str) -> Option<&Transition> {
+        self.transitions.iter().find(|t| t.from == from && t.event == event)
+    }
+
+    pub fn find_mut(&mut self, from: &str, event: &str) -> Option<&mut Transition> {
+        self.transitions.iter_mut().find(|t| t.from == from && t.event == event)
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.states.iter().any(|(s, _)| s == name)
+    }
+
+    /// States reachable from `start` by following transitions forwards
+    /// (`forward`) or backwards
+    fn reachable(&self, start: &[&str], forward: bool) -> BTreeSet<String> {
+        let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+        for t in &self.transitions {
+            let (a, b) = if forward { (&t.from, &t.to) } else { (&t.to, &t.from) };
+            edges.entry(a.as_str()).or_default().push(b.as_str());
+        }
+        let mut seen: BTreeSet<String> = start.iter().map(|s| s.to_string()).collect();
+        let mut queue: VecDeque<&str> = start.iter().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            for next in edges.get(state).into_iter().flatten() {
+                if seen.insert(next.to_string()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Every problem found in the definition, one message each. Empty when
+    /// the machine is well formed.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !self.is_declared(&self.initial) {
+            problems.push(format!("initial state '{}' is not declared", self.initial));
+        }
+        let mut undeclared = BTreeSet::new();
+        for t in &self.transitions {
+            for state in [&t.from, &t.to] {
+                if !self.is_declared(state) && undeclared.insert(state.clone()) {
+                    problems.push(format!(
+                        "state '{}' is used by a transition but not declared",
+                        state
+                    ));
+                }
+            }
+        }
+
+        let reachable = self.reachable(&[&self.initial], true);
+        let finals: Vec<&str> = self
+            .states
+            .iter()
+            .filter(|(_, f)| *f)
+            .map(|(s, _)| s.as_str())
+            .collect();
+        let can_finish = self.reachable(&finals, false);
+
+        for (state, is_final) in &self.states {
+            if !reachable.contains(state) {
+                problems.push(format!("state '{}' is unreachable from '{}'", state, self.initial));
+                continue;
+            }
+            let has_exit = self.transitions.iter().any(|t| &t.from == state);
+            if !is_final && !has_exit {
+                problems.push(format!("state '{}' has no transitions and is not final", state));
+            } else if !finals.is_empty() && !can_finish.contains(state) {
+                problems.push(format!("no final state can be reached from '{}'", state));
+            }
+        }
+        problems
+    }
+
+    /// Graphviz DOT source for the machine. Final states are drawn as double
+    /// circles, the current state is filled, and guarded transitions are
+    /// labelled `event [guarded]`.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph fsm {\n    rankdir=LR;\n");
+        let _ = writeln!(out, "    __start [shape=point];");
+        let _ = writeln!(out, "    __start -> {};", quote(&self.initial));
+
+        let mut seen = BTreeSet::new();
+        let mut names: Vec<(&str, bool)> = Vec::new();
+        for (state, is_final) in &self.states {
+            seen.insert(state.as_str());
+            names.push((state, *is_final));
+        }
+        for t in &self.transitions {
+            for state in [&t.from, &t.to] {
+                if seen.insert(state.as_str()) {
+                    names.push((state, false));
+                }
+            }
+        }
+        for (state, is_final) in names {
+            let shape = if is_final { "doublecircle" } else { "circle" };
+            let fill = if state == self.current { ", style=filled" } else { "" };
+            let _ = writeln!(out, "    {} [shape={}{}];", quote(state), shape, fill);
+        }
+        for t in &self.transitions {
+            let label = if t.guard.is_some() {
+                format!("{} [guarded]", t.event)
+            } else {
+                t.event.clone()
+            };
+            let _ = writeln!(
+                out,
+                "    {} -> {} [label={}];",
+                quote(&t.from),
+                quote(&t.to),
+                quote(&label)
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Quote a DOT identifier
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_machine() -> Machine {
+        let mut m = Machine::new("cart");
+        m.add_state("cart", false);
+        m.add_state("paid", false);
+        m.add_state("shipped", true);
+        m.add_transition("cart", "pay", "paid").unwrap();
+        m.add_transition("paid", "ship", "shipped").unwrap();
+        m
+    }
+
+    #[test]
+    fn test_valid_machine_has_no_problems() {
+        assert!(order_machine().validate().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_transition_rejected() {
+        let mut m = order_machine();
+        assert!(m.add_transition("cart", "pay", "shipped").is_err());
+        assert!(m.add_transition("cart", "cancel", "shipped").is_ok());
+    }
+
+    #[test]
+    fn test_validate_reports_problems() {
+        let mut m = order_machine();
+        m.add_state("refunded", false);
+        m.add_state("limbo", false);
+        m.add_transition("paid", "hold", "limbo").unwrap();
+        m.add_transition("paid", "lose", "lost").unwrap();
+
+        let problems = m.validate();
+        assert!(problems.contains(&"state 'lost' is used by a transition but not declared".to_string()));
+        assert!(problems.contains(&"state 'refunded' is unreachable from 'cart'".to_string()));
+        assert!(problems.contains(&"state 'limbo' has no transitions and is not final".to_string()));
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+
+        let mut looping = Machine::new("a");
+        looping.add_state("a", false);
+        looping.add_state("b", false);
+        looping.add_state("done", true);
+        looping.add_transition("a", "go", "b").unwrap();
+        looping.add_transition("b", "back", "a").unwrap();
+        assert_eq!(
+            looping.validate(),
+            vec![
+                "no final state can be reached from 'a'".to_string(),
+                "no final state can be reached from 'b'".to_string(),
+                "state 'done' is unreachable from 'a'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut m = order_machine();
+        m.find_mut("paid", "ship").unwrap().guard = Some(Closure { func: 1, data: 0 });
+        let dot = m.to_dot();
+        assert!(dot.starts_with("digraph fsm {\n"));
+        assert!(dot.contains("    __start -> \"cart\";\n"));
+        assert!(dot.contains("    \"cart\" [shape=circle, style=filled];\n"));
+        assert!(dot.contains("    \"shipped\" [shape=doublecircle];\n"));
+        assert!(dot.contains("    \"paid\" -> \"shipped\" [label=\"ship [guarded]\"];\n"));
+    }
+}
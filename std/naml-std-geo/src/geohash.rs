@@ -0,0 +1,136 @@
+///
+/// Geohash
+///
+/// Geohashes name cells of a latitude/longitude grid with base-32
+/// strings; each extra character narrows the cell, and nearby points
+/// usually share a prefix, which makes them useful as index keys for
+/// proximity searches.
+///
+
+use crate::sphere::BBox;
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Longest supported geohash (cells of a few centimetres)
+pub const MAX_PRECISION: usize = 12;
+
+/// Encode a point as a geohash of `precision` characters (clamped to
+/// 1-12); out-of-range coordinates are clamped to the valid ranges
+pub fn encode(lat: f64, lon: f64, precision: usize) -> String {
+    let precision = precision.clamp(1, MAX_PRECISION);
+    let lat = if lat.is_nan() { 0.0 } else { lat.clamp(-90.0, 90.0) };
+    let lon = if lon.is_nan() { 0.0 } else { lon.clamp(-180.0, 180.0) };
+    let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+    let mut even = true;
+    for _ in 0..precision {
+        let mut index = 0;
+        for _ in 0..5 {
+            let (range, value): (&mut (f64, f64), f64) = if even { (&mut lon_range, lon) } else { (&mut lat_range, lat) };
+            let mid = (range.0 + range.1) / 2.0;
+            index <<= 1;
+            if value >= mid {
+                index |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+        hash.push(BASE32[index] as char);
+    }
+    hash
+}
+
+/// The cell named by a geohash (case-insensitive); on error, the byte
+/// offset of the first invalid character
+pub fn bounds(hash: &str) -> Result<BBox, usize> {
+    if hash.is_empty() || hash.len() > MAX_PRECISION {
+        return Err(hash.len().min(MAX_PRECISION));
+    }
+    let (mut lat_range, mut lon_range) = ((-90.0f64, 90.0f64), (-180.0f64, 180.0f64));
+    let mut even = true;
+    for (position, b) in hash.bytes().enumerate() {
+        let index = BASE32
+            .iter()
+            .position(|&c| c == b.to_ascii_lowercase())
+            .ok_or(position)?;
+        for bit in (0..5).rev() {
+            let range = if even { &mut lon_range } else { &mut lat_range };
+            let mid = (range.0 + range.1) / 2.0;
+            if index & (1 << bit) != 0 {
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+    }
+    Ok(BBox { min_lat: lat_range.0, min_lon: lon_range.0, max_lat: lat_range.1, max_lon: lon_range.1 })
+}
+
+/// The center of a geohash cell as `(lat, lon)`
+pub fn decode(hash: &str) -> Result<(f64, f64), usize> {
+    let b = bounds(hash)?;
+    Ok(((b.min_lat + b.max_lat) / 2.0, (b.min_lon + b.max_lon) / 2.0))
+}
+
+/// The adjacent cells of the same size, clockwise from north (N, NE, E,
+/// SE, S, SW, W, NW). Longitudes wrap at the antimeridian; cells past a
+/// pole do not exist and are left out.
+pub fn neighbors(hash: &str) -> Result<Vec<String>, usize> {
+    let b = bounds(hash)?;
+    let (lat, lon) = ((b.min_lat + b.max_lat) / 2.0, (b.min_lon + b.max_lon) / 2.0);
+    let (dlat, dlon) = (b.max_lat - b.min_lat, b.max_lon - b.min_lon);
+    let offsets = [(1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (-1.0, 1.0), (-1.0, 0.0), (-1.0, -1.0), (0.0, -1.0), (1.0, -1.0)];
+    Ok(offsets
+        .iter()
+        .filter_map(|(dy, dx)| {
+            let lat = lat + dy * dlat;
+            if !(-90.0..=90.0).contains(&lat) {
+                return None;
+            }
+            let lon = (lon + dx * dlon + 180.0).rem_euclid(360.0) - 180.0;
+            Some(encode(lat, lon, hash.len()))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(encode(57.64911, 10.40744, 11), "u4pruydqqvj");
+        assert_eq!(encode(-25.382708, -49.265506, 8), "6gkzwgjz");
+        assert_eq!(encode(0.0, 0.0, 0), "s");
+        assert_eq!(encode(91.0, 200.0, 20).len(), MAX_PRECISION);
+        let (lat, lon) = decode("u4pruydqqvj").unwrap();
+        assert!((lat - 57.64911).abs() < 1e-5 && (lon - 10.40744).abs() < 1e-5);
+        assert_eq!(decode("U4PRUYDQQVJ"), decode("u4pruydqqvj"));
+        let cell = bounds("ezs42").unwrap();
+        assert!(cell.contains(42.6, -5.6));
+        assert!(cell.max_lat - cell.min_lat < 0.05);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(decode(""), Err(0));
+        assert_eq!(decode("u4pa"), Err(3));
+        assert_eq!(decode("0123456789bcd"), Err(MAX_PRECISION));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(
+            neighbors("ezs42").unwrap(),
+            ["ezs48", "ezs49", "ezs43", "ezs41", "ezs40", "ezefp", "ezefr", "ezefx"]
+        );
+        // Wraps around the antimeridian
+        let n = neighbors("2").unwrap();
+        assert!(n.contains(&"r".to_string()), "{:?}", n);
+        // No cells north of the north pole
+        assert_eq!(neighbors("zzzz").unwrap().len(), 5);
+    }
+}
@@ -0,0 +1,215 @@
+///
+/// naml-std-geo - Geospatial Primitives
+///
+/// Great-circle distances, polygon containment, geohashes and bounding
+/// boxes for (latitude, longitude) coordinates in degrees, e.g. for
+/// "stores near me" queries and geofencing.
+///
+/// ## Functions
+///
+/// - `haversine_km(lat1: float, lon1: float, lat2: float, lon2: float) -> float` - Great-circle distance
+/// - `bearing(lat1: float, lon1: float, lat2: float, lon2: float) -> float` - Initial bearing, 0-360
+/// - `destination(lat: float, lon: float, bearing: float, distance_km: float) -> [float]` - `[lat, lon]`
+/// - `point_in_polygon(lat: float, lon: float, polygon: [float]) -> bool`
+/// - `geohash_encode(lat: float, lon: float, precision: int) -> string`
+/// - `geohash_decode(hash: string) -> [float] throws DecodeError` - Cell center `[lat, lon]`
+/// - `geohash_bounds(hash: string) -> [float] throws DecodeError` - Cell bounding box
+/// - `geohash_neighbors(hash: string) -> [string] throws DecodeError` - Adjacent cells
+/// - `bbox_around(lat: float, lon: float, radius_km: float) -> [float]`
+/// - `bbox_contains(bbox: [float], lat: float, lon: float) -> bool`
+/// - `bbox_intersects(a: [float], b: [float]) -> bool`
+/// - `bounding_box(points: [float]) -> [float]`
+///
+/// ## Representation
+///
+/// Points lists and polygons are flat `[lat, lon, lat, lon, ...]` arrays.
+/// Bounding boxes are `[min_lat, min_lon, max_lat, max_lon]`; a box whose
+/// min_lon is greater than its max_lon crosses the antimeridian. Distances
+/// use a spherical Earth of mean radius 6371.0088 km, which is within
+/// about 0.5% of the ellipsoid.
+///
+
+mod geohash;
+mod sphere;
+
+use naml_std_core::{naml_array_get, naml_array_new, naml_array_push, naml_string_new, NamlArray, NamlString};
+use sphere::BBox;
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+/// Read a `[float]` array; floats are stored as their bit patterns
+unsafe fn floats_from_naml(arr: *const NamlArray) -> Vec<f64> {
+    if arr.is_null() {
+        return Vec::new();
+    }
+    unsafe { (0..(*arr).len as i64).map(|i| f64::from_bits(naml_array_get(arr, i) as u64)).collect() }
+}
+
+/// Pair up a flat `[lat, lon, ...]` array, ignoring a trailing odd value
+unsafe fn points_from_naml(arr: *const NamlArray) -> Vec<(f64, f64)> {
+    unsafe { floats_from_naml(arr) }.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+}
+
+unsafe fn bbox_from_naml(arr: *const NamlArray) -> Option<BBox> {
+    match unsafe { floats_from_naml(arr) }[..] {
+        [min_lat, min_lon, max_lat, max_lon] => Some(BBox { min_lat, min_lon, max_lat, max_lon }),
+        _ => None,
+    }
+}
+
+unsafe fn floats_result(values: &[f64]) -> *mut NamlArray {
+    unsafe {
+        let arr = naml_array_new(values.len());
+        for v in values {
+            naml_array_push(arr, v.to_bits() as i64);
+        }
+        arr
+    }
+}
+
+unsafe fn bbox_result(b: BBox) -> *mut NamlArray {
+    unsafe { floats_result(&[b.min_lat, b.min_lon, b.max_lat, b.max_lon]) }
+}
+
+unsafe fn set_decoded(result: Result<*mut NamlArray, usize>, out_tag: *mut i32, out_value: *mut i64) {
+    unsafe {
+        match result {
+            Ok(arr) => {
+                *out_tag = 0;
+                *out_value = arr as i64;
+            }
+            Err(position) => {
+                *out_tag = 1;
+                *out_value = position as i64;
+            }
+        }
+    }
+}
+
+/// Great-circle distance in kilometres
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_geo_haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    sphere::haversine_km(lat1, lon1, lat2, lon2)
+}
+
+/// Initial bearing in degrees clockwise from north
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_geo_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    sphere::bearing(lat1, lon1, lat2, lon2)
+}
+
+/// The `[lat, lon]` reached by travelling `distance_km` along `bearing`
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_geo_destination(lat: f64, lon: f64, bearing: f64, distance_km: f64) -> *mut NamlArray {
+    let (lat, lon) = sphere::destination(lat, lon, bearing, distance_km);
+    unsafe { floats_result(&[lat, lon]) }
+}
+
+/// # Safety
+/// The caller must ensure `polygon` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_point_in_polygon(lat: f64, lon: f64, polygon: *const NamlArray) -> i64 {
+    sphere::point_in_polygon(lat, lon, &unsafe { points_from_naml(polygon) }) as i64
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_geo_geohash_encode(lat: f64, lon: f64, precision: i64) -> *mut NamlString {
+    let hash = geohash::encode(lat, lon, precision.max(0) as usize);
+    unsafe { naml_string_new(hash.as_ptr(), hash.len()) }
+}
+
+/// Center of a geohash cell as `[lat, lon]`
+///
+/// # Safety
+/// The caller must ensure `hash` is a valid pointer to a NamlString, `out_tag`
+/// is a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_geohash_decode(hash: *const NamlString, out_tag: *mut i32, out_value: *mut i64) {
+    unsafe {
+        let result = geohash::decode(&string_from_naml(hash)).map(|(lat, lon)| floats_result(&[lat, lon]));
+        set_decoded(result, out_tag, out_value)
+    }
+}
+
+/// Bounding box of a geohash cell
+///
+/// # Safety
+/// The caller must ensure `hash` is a valid pointer to a NamlString, `out_tag`
+/// is a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_geohash_bounds(hash: *const NamlString, out_tag: *mut i32, out_value: *mut i64) {
+    unsafe {
+        let result = geohash::bounds(&string_from_naml(hash)).map(|b| bbox_result(b));
+        set_decoded(result, out_tag, out_value)
+    }
+}
+
+/// Adjacent cells of the same precision, clockwise from north
+///
+/// # Safety
+/// The caller must ensure `hash` is a valid pointer to a NamlString, `out_tag`
+/// is a valid pointer to an i32 and `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_geohash_neighbors(
+    hash: *const NamlString,
+    out_tag: *mut i32,
+    out_value: *mut i64,
+) {
+    unsafe {
+        let result = geohash::neighbors(&string_from_naml(hash)).map(|cells| {
+            let arr = naml_array_new(cells.len());
+            for cell in cells {
+                naml_array_push(arr, naml_string_new(cell.as_ptr(), cell.len()) as i64);
+            }
+            arr
+        });
+        set_decoded(result, out_tag, out_value)
+    }
+}
+
+/// Smallest box holding the circle of `radius_km` around a point
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_geo_bbox_around(lat: f64, lon: f64, radius_km: f64) -> *mut NamlArray {
+    unsafe { bbox_result(sphere::bbox_around(lat, lon, radius_km)) }
+}
+
+/// False unless `bbox` has exactly four elements and holds the point
+///
+/// # Safety
+/// The caller must ensure `bbox` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_bbox_contains(bbox: *const NamlArray, lat: f64, lon: f64) -> i64 {
+    unsafe { bbox_from_naml(bbox) }.is_some_and(|b| b.contains(lat, lon)) as i64
+}
+
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlArray and `b` is a
+/// valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_bbox_intersects(a: *const NamlArray, b: *const NamlArray) -> i64 {
+    unsafe {
+        match (bbox_from_naml(a), bbox_from_naml(b)) {
+            (Some(a), Some(b)) => a.intersects(&b) as i64,
+            _ => 0,
+        }
+    }
+}
+
+/// Box around a flat `[lat, lon, ...]` point list; empty for no points
+///
+/// # Safety
+/// The caller must ensure `points` is a valid pointer to a NamlArray.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_geo_bounding_box(points: *const NamlArray) -> *mut NamlArray {
+    unsafe {
+        match BBox::around(&points_from_naml(points)) {
+            Some(b) => bbox_result(b),
+            None => naml_array_new(0),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+///
+/// Spherical Geometry
+///
+/// Great-circle distance, bearing and destination on a sphere with the
+/// Earth's mean radius, plus bounding boxes in degrees. Longitudes wrap at
+/// the antimeridian: a box whose west edge is greater than its east edge
+/// spans it.
+///
+
+/// IUGG mean Earth radius
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// A bounding box in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BBox {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+/// Wrap a longitude into [-180, 180]
+fn wrap_lon(lon: f64) -> f64 {
+    if (-180.0..=180.0).contains(&lon) {
+        lon
+    } else {
+        (lon + 180.0).rem_euclid(360.0) - 180.0
+    }
+}
+
+pub fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = (lat2 - lat1).to_radians();
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+/// Initial bearing from the first point to the second, in degrees
+/// clockwise from north (0 to 360)
+pub fn bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dlambda = (lon2 - lon1).to_radians();
+    let y = dlambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * dlambda.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// The point `distance_km` from the start along `bearing_deg`
+pub fn destination(lat: f64, lon: f64, bearing_deg: f64, distance_km: f64) -> (f64, f64) {
+    let delta = distance_km / EARTH_RADIUS_KM;
+    let theta = bearing_deg.to_radians();
+    let phi1 = lat.to_radians();
+    let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+    let lambda2 = lon.to_radians()
+        + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+    (phi2.to_degrees(), wrap_lon(lambda2.to_degrees()))
+}
+
+/// Ray casting over `(lat, lon)` vertices, treating edges as straight
+/// lines in degree space. The polygon closes itself; points exactly on an
+/// edge may fall on either side.
+pub fn point_in_polygon(lat: f64, lon: f64, polygon: &[(f64, f64)]) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (lat_i, lon_i) = polygon[i];
+        let (lat_j, lon_j) = polygon[j];
+        if (lat_i > lat) != (lat_j > lat) && lon < (lon_j - lon_i) * (lat - lat_i) / (lat_j - lat_i) + lon_i {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// The smallest box holding every point within `radius_km` of the center.
+/// Near a pole the box spans all longitudes.
+pub fn bbox_around(lat: f64, lon: f64, radius_km: f64) -> BBox {
+    let radius = radius_km.max(0.0) / EARTH_RADIUS_KM;
+    let min_lat = lat - radius.to_degrees();
+    let max_lat = lat + radius.to_degrees();
+    if min_lat <= -90.0 || max_lat >= 90.0 || radius >= std::f64::consts::PI {
+        return BBox { min_lat: min_lat.max(-90.0), min_lon: -180.0, max_lat: max_lat.min(90.0), max_lon: 180.0 };
+    }
+    let dlon = (radius.sin() / lat.to_radians().cos()).min(1.0).asin().to_degrees();
+    if dlon >= 180.0 {
+        return BBox { min_lat, min_lon: -180.0, max_lat, max_lon: 180.0 };
+    }
+    BBox { min_lat, min_lon: wrap_lon(lon - dlon), max_lat, max_lon: wrap_lon(lon + dlon) }
+}
+
+impl BBox {
+    /// Longitude ranges covered, split in two when crossing the antimeridian
+    fn lon_ranges(&self) -> Vec<(f64, f64)> {
+        if self.min_lon <= self.max_lon {
+            vec![(self.min_lon, self.max_lon)]
+        } else {
+            vec![(self.min_lon, 180.0), (-180.0, self.max_lon)]
+        }
+    }
+
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        (self.min_lat..=self.max_lat).contains(&lat)
+            && self.lon_ranges().iter().any(|(lo, hi)| (*lo..=*hi).contains(&lon))
+    }
+
+    pub fn intersects(&self, other: &BBox) -> bool {
+        let lat_overlap = self.min_lat <= other.max_lat && other.min_lat <= self.max_lat;
+        lat_overlap
+            && self.lon_ranges().iter().any(|(a_lo, a_hi)| {
+                other.lon_ranges().iter().any(|(b_lo, b_hi)| a_lo <= b_hi && b_lo <= a_hi)
+            })
+    }
+
+    /// The box around `points`, or `None` when there are none
+    pub fn around(points: &[(f64, f64)]) -> Option<BBox> {
+        let (&(lat, lon), rest) = points.split_first()?;
+        let mut bbox = BBox { min_lat: lat, min_lon: lon, max_lat: lat, max_lon: lon };
+        for &(lat, lon) in rest {
+            bbox.min_lat = bbox.min_lat.min(lat);
+            bbox.min_lon = bbox.min_lon.min(lon);
+            bbox.max_lat = bbox.max_lat.max(lat);
+            bbox.max_lon = bbox.max_lon.max(lon);
+        }
+        Some(bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, eps: f64) -> bool {
+        (a - b).abs() < eps
+    }
+
+    #[test]
+    fn test_distance_and_bearing() {
+        // Paris to London
+        assert!(close(haversine_km(48.8566, 2.3522, 51.5074, -0.1278), 343.56, 0.5));
+        assert_eq!(haversine_km(10.0, 20.0, 10.0, 20.0), 0.0);
+        // Antipodes
+        assert!(close(haversine_km(0.0, 0.0, 0.0, 180.0), std::f64::consts::PI * EARTH_RADIUS_KM, 1e-6));
+        assert!(close(bearing(0.0, 0.0, 0.0, 1.0), 90.0, 1e-9));
+        assert!(close(bearing(0.0, 0.0, -1.0, 0.0), 180.0, 1e-9));
+        assert!(close(bearing(0.0, 1.0, 0.0, 0.0), 270.0, 1e-9));
+    }
+
+    #[test]
+    fn test_destination_round_trip() {
+        let (lat, lon) = destination(48.8566, 2.3522, 310.0, 343.0);
+        assert!(close(haversine_km(48.8566, 2.3522, lat, lon), 343.0, 1e-6));
+        let (_, lon) = destination(0.0, 179.5, 90.0, 111.2);
+        assert!(lon < -179.0, "{}", lon);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let square = [(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)];
+        assert!(point_in_polygon(5.0, 5.0, &square));
+        assert!(!point_in_polygon(15.0, 5.0, &square));
+        assert!(!point_in_polygon(5.0, -0.1, &square));
+        // L shape: the notch is outside
+        let l = [(0.0, 0.0), (0.0, 4.0), (2.0, 4.0), (2.0, 2.0), (4.0, 2.0), (4.0, 0.0)];
+        assert!(point_in_polygon(1.0, 3.0, &l));
+        assert!(!point_in_polygon(3.0, 3.0, &l));
+        assert!(!point_in_polygon(1.0, 1.0, &square[..2]));
+    }
+
+    #[test]
+    fn test_bbox() {
+        let b = bbox_around(52.52, 13.405, 10.0);
+        assert!(b.contains(52.52, 13.405));
+        assert!(b.contains(52.6, 13.405));
+        assert!(!b.contains(52.7, 13.405));
+        assert!(close(b.max_lat - 52.52, 10.0 / EARTH_RADIUS_KM * 180.0 / std::f64::consts::PI, 1e-9));
+
+        // Crossing the antimeridian
+        let fiji = bbox_around(-17.7, 179.9, 50.0);
+        assert!(fiji.min_lon > fiji.max_lon);
+        assert!(fiji.contains(-17.7, -179.9));
+        assert!(!fiji.contains(-17.7, 0.0));
+        let east = BBox { min_lat: -20.0, min_lon: -179.95, max_lat: -15.0, max_lon: -170.0 };
+        assert!(fiji.intersects(&east));
+        assert!(!b.intersects(&east));
+
+        // Reaching a pole covers every longitude
+        let pole = bbox_around(89.9, 0.0, 50.0);
+        assert_eq!((pole.min_lon, pole.max_lon, pole.max_lat), (-180.0, 180.0, 90.0));
+
+        let pts = [(1.0, 5.0), (-2.0, 3.0), (4.0, -1.0)];
+        assert_eq!(BBox::around(&pts), Some(BBox { min_lat: -2.0, min_lon: -1.0, max_lat: 4.0, max_lon: 5.0 }));
+        assert_eq!(BBox::around(&[]), None);
+    }
+}
@@ -0,0 +1,241 @@
+///
+/// naml-std-image - Images
+///
+/// Decodes PNG, JPEG, GIF, WebP and BMP images into integer handles,
+/// transforms them and encodes them back to bytes, e.g. for thumbnail
+/// services.
+///
+/// ## Functions
+///
+/// - `load(path: string) -> int throws ImageError` - Read and decode a file
+/// - `decode(data: bytes) -> int throws ImageError` - Decode in-memory bytes
+/// - `create(width: int, height: int) -> int throws ImageError` - Blank transparent image
+/// - `width(img: int) -> int` / `height(img: int) -> int`
+/// - `get_pixel(img: int, x: int, y: int) -> int throws ImageError` - `0xRRGGBBAA`
+/// - `set_pixel(img: int, x: int, y: int, rgba: int) throws ImageError`
+/// - `resize(img: int, width: int, height: int) -> int throws ImageError` - Exact size
+/// - `thumbnail(img: int, max_width: int, max_height: int) -> int throws ImageError` - Fit, keep aspect
+/// - `crop(img: int, x: int, y: int, width: int, height: int) -> int throws ImageError`
+/// - `rotate(img: int, degrees: int) -> int throws ImageError` - Multiples of 90, clockwise
+/// - `flip_horizontal(img: int) -> int throws ImageError` / `flip_vertical(...)`
+/// - `encode_png(img: int) -> bytes throws ImageError`
+/// - `encode_jpeg(img: int, quality: int) -> bytes throws ImageError` - Quality 1-100
+/// - `save(img: int, path: string) throws ImageError` - Format from the extension
+/// - `close(img: int)` - Release an image
+///
+/// ## Handles
+///
+/// Images live in a process-wide registry (the same scheme as sqlite
+/// connections). Transforms return a new handle and leave the original
+/// untouched; only `set_pixel` changes an image in place. Every handle,
+/// including transform results, stays allocated until `close`.
+///
+
+mod transform;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use image::DynamicImage;
+use naml_std_core::bytes::{naml_bytes_from, NamlBytes};
+use naml_std_core::{naml_exception_set_typed, naml_stack_capture, naml_string_new, NamlString, EXCEPTION_TYPE_IMAGE_ERROR};
+
+use transform::ImageResult;
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static IMAGES: LazyLock<Mutex<HashMap<i64, Arc<DynamicImage>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn bytes_slice<'a>(b: *const NamlBytes) -> &'a [u8] {
+    if b.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts((*b).as_ptr(), (*b).len) }
+    }
+}
+
+/// Throw ImageError with layout: message@0, stack@8
+fn throw_image_error(message: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(16, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate ImageError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_string_new(message.as_ptr(), message.len());
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_IMAGE_ERROR);
+    }
+}
+
+/// The image for a handle; cloned out so decoding and transforms run
+/// without holding the registry lock
+fn image(handle: i64) -> ImageResult<Arc<DynamicImage>> {
+    IMAGES
+        .lock()
+        .unwrap()
+        .get(&handle)
+        .cloned()
+        .ok_or_else(|| format!("unknown image handle {}", handle))
+}
+
+/// Register a new image and return its handle, throwing on failure
+fn handle_result(result: ImageResult<DynamicImage>) -> i64 {
+    match result {
+        Ok(img) => {
+            let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+            IMAGES.lock().unwrap().insert(handle, Arc::new(img));
+            handle
+        }
+        Err(message) => {
+            throw_image_error(&message);
+            -1
+        }
+    }
+}
+
+/// Apply a transform to a handle's image, registering the result
+fn transform_handle(handle: i64, f: impl FnOnce(&DynamicImage) -> ImageResult<DynamicImage>) -> i64 {
+    handle_result(image(handle).and_then(|img| f(&img)))
+}
+
+unsafe fn bytes_result(result: ImageResult<Vec<u8>>) -> *mut NamlBytes {
+    match result {
+        Ok(data) => unsafe { naml_bytes_from(data.as_ptr(), data.len()) },
+        Err(message) => {
+            throw_image_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+fn decode_image(data: &[u8]) -> ImageResult<DynamicImage> {
+    image::load_from_memory(data).map_err(|e| e.to_string())
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_image_load(path: *const NamlString) -> i64 {
+    let path = unsafe { string_from_naml(path) };
+    handle_result(
+        std::fs::read(&path)
+            .map_err(|e| format!("{}: {}", path, e))
+            .and_then(|data| decode_image(&data)),
+    )
+}
+
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_image_decode(data: *const NamlBytes) -> i64 {
+    handle_result(decode_image(unsafe { bytes_slice(data) }))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_create(width: i64, height: i64) -> i64 {
+    handle_result(transform::create(width, height))
+}
+
+/// Width in pixels, or 0 for an unknown handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_width(handle: i64) -> i64 {
+    image(handle).map_or(0, |img| img.width() as i64)
+}
+
+/// Height in pixels, or 0 for an unknown handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_height(handle: i64) -> i64 {
+    image(handle).map_or(0, |img| img.height() as i64)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_get_pixel(handle: i64, x: i64, y: i64) -> i64 {
+    image(handle).and_then(|img| transform::get_pixel(&img, x, y)).unwrap_or_else(|message| {
+        throw_image_error(&message);
+        0
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_set_pixel(handle: i64, x: i64, y: i64, rgba: i64) {
+    let mut images = IMAGES.lock().unwrap();
+    let result = match images.get_mut(&handle) {
+        // Copies the pixels only if a transform still holds this image
+        Some(img) => transform::set_pixel(Arc::make_mut(img), x, y, rgba),
+        None => Err(format!("unknown image handle {}", handle)),
+    };
+    drop(images);
+    if let Err(message) = result {
+        throw_image_error(&message);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_resize(handle: i64, width: i64, height: i64) -> i64 {
+    transform_handle(handle, |img| transform::resize(img, width, height))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_thumbnail(handle: i64, max_width: i64, max_height: i64) -> i64 {
+    transform_handle(handle, |img| transform::thumbnail(img, max_width, max_height))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_crop(handle: i64, x: i64, y: i64, width: i64, height: i64) -> i64 {
+    transform_handle(handle, |img| transform::crop(img, x, y, width, height))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_rotate(handle: i64, degrees: i64) -> i64 {
+    transform_handle(handle, |img| transform::rotate(img, degrees))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_flip_horizontal(handle: i64) -> i64 {
+    transform_handle(handle, |img| Ok(img.fliph()))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_flip_vertical(handle: i64) -> i64 {
+    transform_handle(handle, |img| Ok(img.flipv()))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_encode_png(handle: i64) -> *mut NamlBytes {
+    unsafe { bytes_result(image(handle).and_then(|img| transform::encode_png(&img))) }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_encode_jpeg(handle: i64, quality: i64) -> *mut NamlBytes {
+    unsafe { bytes_result(image(handle).and_then(|img| transform::encode_jpeg(&img, quality))) }
+}
+
+/// Encode for the file's extension and write it
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_image_save(handle: i64, path: *const NamlString) {
+    let path = unsafe { string_from_naml(path) };
+    let result = image(handle)
+        .and_then(|img| transform::encode_for_path(&img, &path))
+        .and_then(|data| std::fs::write(&path, data).map_err(|e| format!("{}: {}", path, e)));
+    if let Err(message) = result {
+        throw_image_error(&message);
+    }
+}
+
+/// Release an image; unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_image_close(handle: i64) {
+    IMAGES.lock().unwrap().remove(&handle);
+}
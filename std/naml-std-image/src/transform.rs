@@ -0,0 +1,175 @@
+///
+/// Image Transforms
+///
+/// Geometry and pixel operations on decoded images. Every transform
+/// returns a new image and leaves its input untouched; failures are
+/// messages for ImageError.
+///
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImage, GenericImageView, ImageFormat, Rgba};
+
+pub type ImageResult<T> = Result<T, String>;
+
+/// Largest width or height accepted when creating or resizing
+pub const MAX_DIMENSION: i64 = 65_535;
+
+/// JPEG quality used by `save`
+pub const SAVE_JPEG_QUALITY: i64 = 90;
+
+fn dimension(name: &str, value: i64) -> ImageResult<u32> {
+    if (1..=MAX_DIMENSION).contains(&value) {
+        Ok(value as u32)
+    } else {
+        Err(format!("{} must be between 1 and {}, got {}", name, MAX_DIMENSION, value))
+    }
+}
+
+/// A blank, fully transparent RGBA image
+pub fn create(width: i64, height: i64) -> ImageResult<DynamicImage> {
+    Ok(DynamicImage::new_rgba8(dimension("width", width)?, dimension("height", height)?))
+}
+
+/// Scale to exactly `width` x `height` with a Lanczos filter
+pub fn resize(img: &DynamicImage, width: i64, height: i64) -> ImageResult<DynamicImage> {
+    Ok(img.resize_exact(dimension("width", width)?, dimension("height", height)?, FilterType::Lanczos3))
+}
+
+/// Scale down to fit within `max_width` x `max_height`, keeping the aspect
+/// ratio. Images that already fit are returned at their own size.
+pub fn thumbnail(img: &DynamicImage, max_width: i64, max_height: i64) -> ImageResult<DynamicImage> {
+    let (max_width, max_height) = (dimension("max_width", max_width)?, dimension("max_height", max_height)?);
+    if img.width() <= max_width && img.height() <= max_height {
+        return Ok(img.clone());
+    }
+    Ok(img.resize(max_width, max_height, FilterType::Lanczos3))
+}
+
+/// The `width` x `height` region whose top-left corner is at (x, y)
+pub fn crop(img: &DynamicImage, x: i64, y: i64, width: i64, height: i64) -> ImageResult<DynamicImage> {
+    let (w, h) = (dimension("width", width)?, dimension("height", height)?);
+    if x < 0 || y < 0 || x + width > img.width() as i64 || y + height > img.height() as i64 {
+        return Err(format!(
+            "crop {}x{} at ({}, {}) is outside the {}x{} image",
+            width, height, x, y, img.width(), img.height()
+        ));
+    }
+    Ok(img.crop_imm(x as u32, y as u32, w, h))
+}
+
+/// Rotate clockwise by a multiple of 90 degrees; negative angles turn
+/// counterclockwise
+pub fn rotate(img: &DynamicImage, degrees: i64) -> ImageResult<DynamicImage> {
+    match degrees.rem_euclid(360) {
+        0 => Ok(img.clone()),
+        90 => Ok(img.rotate90()),
+        180 => Ok(img.rotate180()),
+        270 => Ok(img.rotate270()),
+        _ => Err(format!("rotation must be a multiple of 90 degrees, got {}", degrees)),
+    }
+}
+
+fn check_bounds(img: &DynamicImage, x: i64, y: i64) -> ImageResult<(u32, u32)> {
+    if x < 0 || y < 0 || x >= img.width() as i64 || y >= img.height() as i64 {
+        return Err(format!("pixel ({}, {}) is outside the {}x{} image", x, y, img.width(), img.height()));
+    }
+    Ok((x as u32, y as u32))
+}
+
+/// The pixel at (x, y) packed as 0xRRGGBBAA
+pub fn get_pixel(img: &DynamicImage, x: i64, y: i64) -> ImageResult<i64> {
+    let (x, y) = check_bounds(img, x, y)?;
+    Ok(u32::from_be_bytes(img.get_pixel(x, y).0) as i64)
+}
+
+/// Set the pixel at (x, y) from 0xRRGGBBAA, converted to the image's
+/// own color type
+pub fn set_pixel(img: &mut DynamicImage, x: i64, y: i64, rgba: i64) -> ImageResult<()> {
+    let (x, y) = check_bounds(img, x, y)?;
+    img.put_pixel(x, y, Rgba((rgba as u32).to_be_bytes()));
+    Ok(())
+}
+
+fn encode(img: &DynamicImage, format: ImageFormat) -> ImageResult<Vec<u8>> {
+    let mut out = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut out, format).map_err(|e| e.to_string())?;
+    Ok(out.into_inner())
+}
+
+/// Encode as PNG
+pub fn encode_png(img: &DynamicImage) -> ImageResult<Vec<u8>> {
+    encode(img, ImageFormat::Png)
+}
+
+/// Encode as baseline JPEG at `quality` (clamped to 1-100). JPEG has no
+/// alpha channel, so transparency is dropped.
+pub fn encode_jpeg(img: &DynamicImage, quality: i64) -> ImageResult<Vec<u8>> {
+    let mut out = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100) as u8);
+    DynamicImage::ImageRgb8(img.to_rgb8()).write_with_encoder(encoder).map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// Encode for the format named by a file extension, e.g. `photo.webp`
+pub fn encode_for_path(img: &DynamicImage, path: &str) -> ImageResult<Vec<u8>> {
+    match ImageFormat::from_path(path) {
+        Ok(ImageFormat::Jpeg) => encode_jpeg(img, SAVE_JPEG_QUALITY),
+        Ok(format) => encode(img, format),
+        Err(_) => Err(format!("unknown image format for {}", path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DynamicImage {
+        let mut img = create(4, 2).unwrap();
+        set_pixel(&mut img, 0, 0, 0xFF0000FF).unwrap();
+        set_pixel(&mut img, 3, 1, 0x00FF00FF).unwrap();
+        img
+    }
+
+    #[test]
+    fn test_pixels() {
+        let img = sample();
+        assert_eq!(get_pixel(&img, 0, 0), Ok(0xFF0000FF));
+        assert_eq!(get_pixel(&img, 1, 0), Ok(0));
+        assert!(get_pixel(&img, 4, 0).is_err());
+        assert!(get_pixel(&img, 0, -1).is_err());
+        assert!(create(0, 10).is_err());
+    }
+
+    #[test]
+    fn test_geometry() {
+        let img = sample();
+        let turned = rotate(&img, 90).unwrap();
+        assert_eq!((turned.width(), turned.height()), (2, 4));
+        // The top-left corner moves to the top-right
+        assert_eq!(get_pixel(&turned, 1, 0), Ok(0xFF0000FF));
+        assert_eq!(rotate(&img, -90).unwrap(), rotate(&img, 270).unwrap());
+        assert_eq!(rotate(&img, 360).unwrap(), img);
+        assert!(rotate(&img, 45).is_err());
+
+        let part = crop(&img, 2, 1, 2, 1).unwrap();
+        assert_eq!(get_pixel(&part, 1, 0), Ok(0x00FF00FF));
+        assert!(crop(&img, 3, 0, 2, 1).is_err());
+
+        let big = resize(&img, 8, 4).unwrap();
+        assert_eq!((big.width(), big.height()), (8, 4));
+        let thumb = thumbnail(&create(400, 100).unwrap(), 100, 100).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (100, 25));
+        assert_eq!(thumbnail(&img, 100, 100).unwrap(), img);
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let img = sample();
+        let png = encode_png(&img).unwrap();
+        assert_eq!(&png[..4], b"\x89PNG");
+        assert_eq!(image::load_from_memory(&png).unwrap().to_rgba8(), img.to_rgba8());
+        let jpeg = encode_jpeg(&img, 90).unwrap();
+        assert_eq!(&jpeg[..2], b"\xFF\xD8");
+        assert_eq!(image::load_from_memory(&jpeg).unwrap().width(), 4);
+    }
+}
@@ -146,7 +146,7 @@ pub extern "C" fn naml_terminal_height() -> i64 {
 pub unsafe extern "C" fn naml_warn(s: *const naml_std_core::NamlString) {
     if !s.is_null() {
         unsafe {
-            let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+            let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
             if let Ok(msg) = std::str::from_utf8(slice) {
                 eprintln!("warning: {}", msg);
             }
@@ -159,7 +159,7 @@ pub unsafe extern "C" fn naml_warn(s: *const naml_std_core::NamlString) {
 pub unsafe extern "C" fn naml_error(s: *const naml_std_core::NamlString) {
     if !s.is_null() {
         unsafe {
-            let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+            let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
             if let Ok(msg) = std::str::from_utf8(slice) {
                 eprintln!("error: {}", msg);
             }
@@ -172,7 +172,7 @@ pub unsafe extern "C" fn naml_error(s: *const naml_std_core::NamlString) {
 pub unsafe extern "C" fn naml_panic(s: *const naml_std_core::NamlString) {
     if !s.is_null() {
         unsafe {
-            let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+            let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
             if let Ok(msg) = std::str::from_utf8(slice) {
                 eprintln!("panic: {}", msg);
             }
@@ -187,3 +187,23 @@ pub extern "C" fn naml_panic_unwrap() {
     eprintln!("panic: attempted to unwrap a none value");
     std::process::abort();
 }
+
+/// Panic when an overflow-checked int operation overflows
+/// (0 = add, 1 = subtract, 2 = multiply)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_panic_overflow(op: i64) {
+    let verb = match op {
+        0 => "add",
+        1 => "subtract",
+        _ => "multiply",
+    };
+    eprintln!("panic: attempt to {} with overflow", verb);
+    std::process::abort();
+}
+
+/// Panic on an uncaught failed integer division
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_panic_arithmetic(kind: i64) {
+    eprintln!("panic: {}", naml_std_core::arithmetic_error_message(kind));
+    std::process::abort();
+}
@@ -0,0 +1,186 @@
+///
+/// naml-std-markdown - Markdown
+///
+/// Renders CommonMark to HTML and exposes the parsed document as a flat
+/// list of nodes, accessed as `std::text::markdown`.
+///
+/// ## Options
+///
+/// Every function takes an options string: a comma-separated list of
+/// `tables`, `footnotes`, `strikethrough`, `tasklists`, `smart_punctuation`,
+/// `heading_ids` and `escape_html`. An empty string is plain CommonMark.
+///
+/// ## Rendering
+///
+/// - `to_html(md: string, options: string) -> string throws MarkdownError`
+///
+/// ## Walking
+///
+/// - `parse(md: string, options: string) -> int throws MarkdownError` - Parse into a document
+/// - `node_count(doc: int) -> int throws MarkdownError`
+/// - `node_kind / node_text / node_url / node_lang(doc, index) -> string throws MarkdownError`
+/// - `node_depth / node_level(doc, index) -> int throws MarkdownError`
+/// - `close(doc: int)` - Release a document
+///
+/// ## Handles
+///
+/// Documents live in a process-wide registry (the same scheme as pdf
+/// documents) and stay allocated until `close`.
+///
+
+mod render;
+mod tree;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use naml_std_core::{naml_exception_set_typed, naml_stack_capture, naml_string_new, NamlString, EXCEPTION_TYPE_MARKDOWN_ERROR};
+
+use render::{MarkdownOptions, MarkdownResult};
+use tree::Node;
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static DOCUMENTS: LazyLock<Mutex<HashMap<i64, Vec<Node>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+/// Throw MarkdownError with layout: message@0, stack@8
+fn throw_markdown_error(message: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(16, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate MarkdownError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_string_new(message.as_ptr(), message.len());
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_MARKDOWN_ERROR);
+    }
+}
+
+/// Run `f` on one node of a parsed document
+fn with_node<T>(handle: i64, index: i64, f: impl FnOnce(&Node) -> T) -> MarkdownResult<T> {
+    let documents = DOCUMENTS.lock().unwrap();
+    let nodes = documents
+        .get(&handle)
+        .ok_or_else(|| format!("unknown markdown document handle {}", handle))?;
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| nodes.get(index))
+        .map(f)
+        .ok_or_else(|| format!("node index {} out of range for {} nodes", index, nodes.len()))
+}
+
+fn node_string(handle: i64, index: i64, field: impl FnOnce(&Node) -> &str) -> *mut NamlString {
+    match with_node(handle, index, |node| {
+        let value = field(node);
+        unsafe { naml_string_new(value.as_ptr(), value.len()) }
+    }) {
+        Ok(value) => value,
+        Err(message) => {
+            throw_markdown_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+fn node_int(handle: i64, index: i64, field: impl FnOnce(&Node) -> i64) -> i64 {
+    with_node(handle, index, field).unwrap_or_else(|message| {
+        throw_markdown_error(&message);
+        -1
+    })
+}
+
+/// # Safety
+/// The caller must ensure `markdown` is a valid pointer to a NamlString and
+/// `options` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_markdown_to_html(markdown: *const NamlString, options: *const NamlString) -> *mut NamlString {
+    let markdown = unsafe { string_from_naml(markdown) };
+    let options = unsafe { string_from_naml(options) };
+    match MarkdownOptions::parse(&options) {
+        Ok(options) => {
+            let html = render::to_html(&markdown, &options);
+            unsafe { naml_string_new(html.as_ptr(), html.len()) }
+        }
+        Err(message) => {
+            throw_markdown_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `markdown` is a valid pointer to a NamlString and
+/// `options` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_markdown_parse(markdown: *const NamlString, options: *const NamlString) -> i64 {
+    let markdown = unsafe { string_from_naml(markdown) };
+    let options = unsafe { string_from_naml(options) };
+    match MarkdownOptions::parse(&options) {
+        Ok(options) => {
+            let nodes = tree::parse_nodes(&markdown, &options);
+            let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+            DOCUMENTS.lock().unwrap().insert(handle, nodes);
+            handle
+        }
+        Err(message) => {
+            throw_markdown_error(&message);
+            -1
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_count(handle: i64) -> i64 {
+    match DOCUMENTS.lock().unwrap().get(&handle) {
+        Some(nodes) => nodes.len() as i64,
+        None => {
+            throw_markdown_error(&format!("unknown markdown document handle {}", handle));
+            -1
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_kind(handle: i64, index: i64) -> *mut NamlString {
+    node_string(handle, index, |node| node.kind)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_text(handle: i64, index: i64) -> *mut NamlString {
+    node_string(handle, index, |node| &node.text)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_url(handle: i64, index: i64) -> *mut NamlString {
+    node_string(handle, index, |node| &node.url)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_lang(handle: i64, index: i64) -> *mut NamlString {
+    node_string(handle, index, |node| &node.lang)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_depth(handle: i64, index: i64) -> i64 {
+    node_int(handle, index, |node| node.depth)
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_node_level(handle: i64, index: i64) -> i64 {
+    node_int(handle, index, |node| node.level)
+}
+
+/// Release a document; unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_markdown_close(handle: i64) {
+    DOCUMENTS.lock().unwrap().remove(&handle);
+}
@@ -0,0 +1,106 @@
+///
+/// Markdown Options and HTML Rendering
+///
+/// Options are a comma-separated list of extension names so they can be
+/// passed from naml as one string; an empty string is plain CommonMark.
+/// `escape_html` is not a parser extension: it turns raw HTML in the
+/// source into text before rendering.
+///
+
+use pulldown_cmark::{html, Event, Options, Parser};
+
+pub type MarkdownResult<T> = Result<T, String>;
+
+const EXTENSIONS: &[(&str, Options)] = &[
+    ("tables", Options::ENABLE_TABLES),
+    ("footnotes", Options::ENABLE_FOOTNOTES),
+    ("strikethrough", Options::ENABLE_STRIKETHROUGH),
+    ("tasklists", Options::ENABLE_TASKLISTS),
+    ("smart_punctuation", Options::ENABLE_SMART_PUNCTUATION),
+    ("heading_ids", Options::ENABLE_HEADING_ATTRIBUTES),
+];
+
+pub struct MarkdownOptions {
+    pub parser: Options,
+    pub escape_html: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions { parser: Options::empty(), escape_html: false }
+    }
+}
+
+impl MarkdownOptions {
+    pub fn parse(spec: &str) -> MarkdownResult<Self> {
+        let mut options = MarkdownOptions::default();
+        for name in spec.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            if name == "escape_html" {
+                options.escape_html = true;
+                continue;
+            }
+            match EXTENSIONS.iter().find(|(known, _)| *known == name) {
+                Some((_, flag)) => options.parser |= *flag,
+                None => {
+                    let known: Vec<&str> = EXTENSIONS.iter().map(|(known, _)| *known).collect();
+                    return Err(format!(
+                        "unknown option '{}', expected any of: {}, escape_html",
+                        name,
+                        known.join(", ")
+                    ));
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
+pub fn to_html(markdown: &str, options: &MarkdownOptions) -> String {
+    let escape_html = options.escape_html;
+    let events = Parser::new_ext(markdown, options.parser).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) if escape_html => Event::Text(raw),
+        other => other,
+    });
+    let mut out = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(markdown: &str, spec: &str) -> String {
+        to_html(markdown, &MarkdownOptions::parse(spec).unwrap())
+    }
+
+    #[test]
+    fn test_commonmark() {
+        assert_eq!(render("# Title\n\nSome *text*.", ""), "<h1>Title</h1>\n<p>Some <em>text</em>.</p>\n");
+        assert_eq!(render("| a |\n|---|\n| 1 |", ""), "<p>| a |\n|---|\n| 1 |</p>\n");
+    }
+
+    #[test]
+    fn test_extensions() {
+        let table = render("| a |\n|---|\n| 1 |", "tables");
+        assert!(table.starts_with("<table>"), "{}", table);
+        assert_eq!(render("~~gone~~", " strikethrough "), "<p><del>gone</del></p>\n");
+        let note = render("Hi[^1]\n\n[^1]: Note", "footnotes");
+        assert!(note.contains("footnote-definition"), "{}", note);
+        assert!(render("- [x] done", "tasklists").contains("checked=\"\""));
+        assert_eq!(render("# Intro {#start}", "heading_ids"), "<h1 id=\"start\">Intro</h1>\n");
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(render("<b>hi</b>", ""), "<p><b>hi</b></p>\n");
+        assert_eq!(render("<b>hi</b>", "escape_html"), "<p>&lt;b&gt;hi&lt;/b&gt;</p>\n");
+        assert!(!render("<script>x()</script>\n", "escape_html").contains("<script>"));
+    }
+
+    #[test]
+    fn test_unknown_option() {
+        let err = MarkdownOptions::parse("tables,emoji").err().unwrap();
+        assert!(err.contains("'emoji'") && err.contains("footnotes"), "{}", err);
+    }
+}
@@ -0,0 +1,168 @@
+///
+/// Markdown Document Nodes
+///
+/// A parsed document is flattened into its nodes in document order
+/// (pre-order), each with its nesting depth, so naml code can walk it with
+/// an index instead of recursing. A node's text is the plain text of
+/// everything inside it, which is what a table of contents or link
+/// checker wants.
+///
+
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag};
+
+use crate::render::MarkdownOptions;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Node {
+    pub kind: &'static str,
+    pub depth: i64,
+    /// Heading level, first number of an ordered list, or 1 for a checked task
+    pub level: i64,
+    pub text: String,
+    /// Destination of a link or image, or the label of a footnote
+    pub url: String,
+    /// Language of a fenced code block
+    pub lang: String,
+}
+
+fn start_node(tag: Tag<'_>) -> Node {
+    let mut node = Node::default();
+    node.kind = match tag {
+        Tag::Paragraph => "paragraph",
+        Tag::Heading { level, .. } => {
+            node.level = level as i64;
+            "heading"
+        }
+        Tag::BlockQuote(_) => "blockquote",
+        Tag::CodeBlock(kind) => {
+            if let CodeBlockKind::Fenced(info) = kind {
+                node.lang = info.split_whitespace().next().unwrap_or("").to_string();
+            }
+            "code_block"
+        }
+        Tag::HtmlBlock => "html_block",
+        Tag::List(Some(start)) => {
+            node.level = start as i64;
+            "ordered_list"
+        }
+        Tag::List(None) => "list",
+        Tag::Item => "item",
+        Tag::FootnoteDefinition(label) => {
+            node.url = label.to_string();
+            "footnote"
+        }
+        Tag::Table(_) => "table",
+        Tag::TableHead => "table_head",
+        Tag::TableRow => "table_row",
+        Tag::TableCell => "table_cell",
+        Tag::Emphasis => "emphasis",
+        Tag::Strong => "strong",
+        Tag::Strikethrough => "strikethrough",
+        Tag::Link { dest_url, .. } => {
+            node.url = dest_url.to_string();
+            "link"
+        }
+        Tag::Image { dest_url, .. } => {
+            node.url = dest_url.to_string();
+            "image"
+        }
+        _ => "other",
+    };
+    node
+}
+
+pub fn parse_nodes(markdown: &str, options: &MarkdownOptions) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    for event in Parser::new_ext(markdown, options.parser) {
+        let (leaf, text) = match event {
+            Event::Start(tag) => {
+                let mut node = start_node(tag);
+                node.depth = open.len() as i64;
+                open.push(nodes.len());
+                nodes.push(node);
+                continue;
+            }
+            Event::End(_) => {
+                open.pop();
+                continue;
+            }
+            Event::Text(text) => (Some(Node { kind: "text", ..Node::default() }), text.to_string()),
+            Event::Code(code) => (Some(Node { kind: "code", ..Node::default() }), code.to_string()),
+            Event::InlineHtml(html) => (Some(Node { kind: "inline_html", ..Node::default() }), html.to_string()),
+            Event::Html(html) => (None, html.to_string()),
+            Event::SoftBreak => (None, " ".to_string()),
+            Event::HardBreak => (None, "\n".to_string()),
+            Event::Rule => (Some(Node { kind: "rule", ..Node::default() }), String::new()),
+            Event::FootnoteReference(label) => {
+                (Some(Node { kind: "footnote_ref", url: label.to_string(), ..Node::default() }), String::new())
+            }
+            Event::TaskListMarker(checked) => {
+                (Some(Node { kind: "task", level: checked as i64, ..Node::default() }), String::new())
+            }
+            _ => continue,
+        };
+        for &index in &open {
+            nodes[index].text.push_str(&text);
+        }
+        if let Some(mut node) = leaf {
+            node.depth = open.len() as i64;
+            node.text = text;
+            nodes.push(node);
+        }
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(nodes: &[Node]) -> Vec<(&str, i64)> {
+        nodes.iter().map(|node| (node.kind, node.depth)).collect()
+    }
+
+    #[test]
+    fn test_headings_and_links() {
+        let nodes = parse_nodes(
+            "# Getting *Started*\n\nSee [the guide](https://example.com/guide) and ![logo](logo.png).\n\n## Next\n",
+            &MarkdownOptions::default(),
+        );
+        assert_eq!(kinds(&nodes), vec![
+            ("heading", 0),
+            ("text", 1),
+            ("emphasis", 1),
+            ("text", 2),
+            ("paragraph", 0),
+            ("text", 1),
+            ("link", 1),
+            ("text", 2),
+            ("text", 1),
+            ("image", 1),
+            ("text", 2),
+            ("text", 1),
+            ("heading", 0),
+            ("text", 1),
+        ]);
+        assert_eq!((nodes[0].level, nodes[0].text.as_str()), (1, "Getting Started"));
+        assert_eq!((nodes[6].url.as_str(), nodes[6].text.as_str()), ("https://example.com/guide", "the guide"));
+        assert_eq!((nodes[9].url.as_str(), nodes[9].text.as_str()), ("logo.png", "logo"));
+        assert_eq!(nodes[4].text, "See the guide and logo.");
+        assert_eq!(nodes[12].level, 2);
+    }
+
+    #[test]
+    fn test_blocks() {
+        let options = MarkdownOptions::parse("tasklists,footnotes").unwrap();
+        let nodes = parse_nodes("3. [x] done\n4. [ ] todo[^n]\n\n```rust ignore\nfn main() {}\n```\n\n[^n]: Later\n", &options);
+        let list = &nodes[0];
+        assert_eq!((list.kind, list.level), ("ordered_list", 3));
+        let tasks: Vec<i64> = nodes.iter().filter(|node| node.kind == "task").map(|node| node.level).collect();
+        assert_eq!(tasks, vec![1, 0]);
+        let code = nodes.iter().find(|node| node.kind == "code_block").unwrap();
+        assert_eq!((code.lang.as_str(), code.text.as_str()), ("rust", "fn main() {}\n"));
+        let reference = nodes.iter().find(|node| node.kind == "footnote_ref").unwrap();
+        let footnote = nodes.iter().find(|node| node.kind == "footnote").unwrap();
+        assert_eq!((reference.url.as_str(), footnote.url.as_str(), footnote.text.as_str()), ("n", "n", "Later"));
+    }
+}
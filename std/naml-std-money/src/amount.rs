@@ -0,0 +1,341 @@
+///
+/// Money Amounts
+///
+/// An amount is a whole number of minor units (cents, fils) in one
+/// currency. Arithmetic is exact; only multiplication, allocation and
+/// conversion can produce fractions of a minor unit, and those round half
+/// to even (banker's rounding) so repeated operations do not drift.
+///
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::currency::{self, Currency};
+
+pub type MoneyResult<T> = Result<T, String>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Money {
+    pub minor: i64,
+    pub currency: &'static Currency,
+}
+
+/// An exact decimal number: `mantissa * 10^-scale`
+#[derive(Debug, PartialEq)]
+struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+/// Parse a plain decimal such as `-12.50` (no exponent or grouping)
+fn parse_decimal(s: &str) -> Option<Decimal> {
+    let s = s.trim();
+    let (negative, digits) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    let mut mantissa: i128 = 0;
+    for b in int_part.bytes().chain(frac_part.bytes()) {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+    }
+    Some(Decimal {
+        mantissa: if negative { -mantissa } else { mantissa },
+        scale: frac_part.len() as u32,
+    })
+}
+
+fn pow10(n: u32) -> Option<i128> {
+    10i128.checked_pow(n)
+}
+
+/// `n / d` rounded half to even; `d` must be positive
+fn div_round(n: i128, d: i128) -> i128 {
+    let q = n.div_euclid(d);
+    let r = n.rem_euclid(d);
+    match r.cmp(&(d - r)) {
+        Ordering::Less => q,
+        Ordering::Greater => q + 1,
+        Ordering::Equal => q + (q & 1),
+    }
+}
+
+fn out_of_range() -> String {
+    "amount out of range".to_string()
+}
+
+pub fn currency(code: &str) -> MoneyResult<&'static Currency> {
+    currency::lookup(&code.trim().to_ascii_uppercase())
+        .ok_or_else(|| format!("unknown currency code '{}'", code))
+}
+
+/// Reject operations mixing two currencies
+fn same_currency(a: &Money, b: &Money, verb: &str) -> MoneyResult<()> {
+    if a.currency.code == b.currency.code {
+        Ok(())
+    } else {
+        Err(format!("cannot {} {} and {} amounts", verb, a.currency.code, b.currency.code))
+    }
+}
+
+impl Money {
+    fn new(minor: i128, currency: &'static Currency) -> MoneyResult<Money> {
+        let minor = i64::try_from(minor).map_err(|_| out_of_range())?;
+        Ok(Money { minor, currency })
+    }
+
+    /// Minor units for `value`; rounds when `exact` is false, otherwise
+    /// rejects values finer than the currency's minor unit
+    fn from_decimal(value: Decimal, currency: &'static Currency, exact: bool) -> MoneyResult<Money> {
+        if value.scale <= currency.digits {
+            let factor = pow10(currency.digits - value.scale).ok_or_else(out_of_range)?;
+            let minor = value.mantissa.checked_mul(factor).ok_or_else(out_of_range)?;
+            return Money::new(minor, currency);
+        }
+        if exact {
+            return Err(format!(
+                "{} takes at most {} decimal places",
+                currency.code, currency.digits
+            ));
+        }
+        match pow10(value.scale - currency.digits) {
+            Some(divisor) => Money::new(div_round(value.mantissa, divisor), currency),
+            // Smaller than half a minor unit
+            None => Money::new(0, currency),
+        }
+    }
+
+    /// An exact amount such as `"12.34"` in `code`
+    pub fn of(amount: &str, code: &str) -> MoneyResult<Money> {
+        let currency = currency(code)?;
+        let value = parse_decimal(amount).ok_or_else(|| format!("invalid amount '{}'", amount))?;
+        Money::from_decimal(value, currency, true)
+    }
+
+    pub fn from_minor(minor: i64, code: &str) -> MoneyResult<Money> {
+        Ok(Money { minor, currency: currency(code)? })
+    }
+
+    /// Parse the canonical form produced by `to_string`, e.g. `12.34 USD`
+    pub fn parse(s: &str) -> MoneyResult<Money> {
+        match s.trim().split_once(' ') {
+            Some((amount, code)) => Money::of(amount, code),
+            None => Err(format!("invalid money '{}', expected an amount and a currency code", s)),
+        }
+    }
+
+    /// The nearest amount to `value`, rounding to the minor unit
+    pub fn from_float(value: f64, code: &str) -> MoneyResult<Money> {
+        let currency = currency(code)?;
+        if !value.is_finite() {
+            return Err(format!("invalid amount {}", value));
+        }
+        // Display gives the shortest decimal that reads back as `value`
+        let value = parse_decimal(&value.to_string()).ok_or_else(out_of_range)?;
+        Money::from_decimal(value, currency, false)
+    }
+
+    pub fn to_float(self) -> f64 {
+        self.amount().parse().unwrap_or(0.0)
+    }
+
+    /// The amount with the currency's decimal places, e.g. `-0.50`
+    pub fn amount(&self) -> String {
+        let digits = self.currency.digits as usize;
+        let abs = self.minor.unsigned_abs().to_string();
+        let padded = format!("{:0>width$}", abs, width = digits + 1);
+        let (int_part, frac_part) = padded.split_at(padded.len() - digits);
+        let sign = if self.minor < 0 { "-" } else { "" };
+        if digits == 0 {
+            format!("{}{}", sign, int_part)
+        } else {
+            format!("{}{}.{}", sign, int_part, frac_part)
+        }
+    }
+
+    pub fn add(&self, other: &Money) -> MoneyResult<Money> {
+        same_currency(self, other, "add")?;
+        let minor = self.minor.checked_add(other.minor).ok_or_else(out_of_range)?;
+        Ok(Money { minor, currency: self.currency })
+    }
+
+    pub fn sub(&self, other: &Money) -> MoneyResult<Money> {
+        same_currency(self, other, "subtract")?;
+        let minor = self.minor.checked_sub(other.minor).ok_or_else(out_of_range)?;
+        Ok(Money { minor, currency: self.currency })
+    }
+
+    pub fn negate(&self) -> MoneyResult<Money> {
+        let minor = self.minor.checked_neg().ok_or_else(out_of_range)?;
+        Ok(Money { minor, currency: self.currency })
+    }
+
+    pub fn compare(&self, other: &Money) -> MoneyResult<Ordering> {
+        same_currency(self, other, "compare")?;
+        Ok(self.minor.cmp(&other.minor))
+    }
+
+    /// Multiply by an exact decimal factor such as `"1.075"`
+    pub fn multiply(&self, factor: &str) -> MoneyResult<Money> {
+        let factor = parse_decimal(factor).ok_or_else(|| format!("invalid factor '{}'", factor))?;
+        let product = (self.minor as i128).checked_mul(factor.mantissa).ok_or_else(out_of_range)?;
+        let divisor = pow10(factor.scale).ok_or_else(out_of_range)?;
+        Money::new(div_round(product, divisor), self.currency)
+    }
+
+    /// Split into parts proportional to `ratios` without losing a minor
+    /// unit: leftover units go one each to the first parts with a
+    /// nonzero ratio
+    pub fn allocate(&self, ratios: &[i64]) -> MoneyResult<Vec<Money>> {
+        if ratios.iter().any(|&r| r < 0) {
+            return Err("allocation ratios must not be negative".to_string());
+        }
+        let total: i128 = ratios.iter().map(|&r| r as i128).sum();
+        if total == 0 {
+            return Err("allocation needs at least one positive ratio".to_string());
+        }
+        let amount = self.minor.unsigned_abs() as i128;
+        let mut shares: Vec<i128> = ratios.iter().map(|&r| amount * r as i128 / total).collect();
+        let mut leftover = amount - shares.iter().sum::<i128>();
+        for (share, &ratio) in shares.iter_mut().zip(ratios) {
+            if leftover == 0 {
+                break;
+            }
+            if ratio > 0 {
+                *share += 1;
+                leftover -= 1;
+            }
+        }
+        let sign = if self.minor < 0 { -1 } else { 1 };
+        shares
+            .into_iter()
+            .map(|share| Money::new(sign * share, self.currency))
+            .collect()
+    }
+
+    /// Convert to `code` using `rates`, which maps currency codes to how
+    /// many units of that currency one unit of a common base buys
+    pub fn convert(&self, code: &str, rates: &HashMap<String, String>) -> MoneyResult<Money> {
+        let target = currency(code)?;
+        if target.code == self.currency.code {
+            return Ok(*self);
+        }
+        let rate = |code: &str| -> MoneyResult<Decimal> {
+            let text = rates
+                .iter()
+                .find(|(key, _)| key.trim().eq_ignore_ascii_case(code))
+                .map(|(_, value)| value)
+                .ok_or_else(|| format!("no exchange rate for {}", code))?;
+            match parse_decimal(text) {
+                Some(rate) if rate.mantissa > 0 => Ok(rate),
+                _ => Err(format!("invalid exchange rate for {}: '{}'", code, text)),
+            }
+        };
+        let from = rate(self.currency.code)?;
+        let to = rate(target.code)?;
+        // minor * 10^(to digits) * to / (10^(from digits) * from)
+        let numerator = pow10(target.digits + from.scale)
+            .and_then(|p| (self.minor as i128).checked_mul(p))
+            .and_then(|n| n.checked_mul(to.mantissa))
+            .ok_or_else(out_of_range)?;
+        let denominator = pow10(self.currency.digits + to.scale)
+            .and_then(|p| p.checked_mul(from.mantissa))
+            .ok_or_else(out_of_range)?;
+        Money::new(div_round(numerator, denominator), target)
+    }
+}
+
+impl std::fmt::Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.amount(), self.currency.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn m(s: &str) -> Money {
+        Money::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(m("12.3 USD").to_string(), "12.30 USD");
+        assert_eq!(m("-0.05 usd").to_string(), "-0.05 USD");
+        assert_eq!(m("1500 JPY").to_string(), "1500 JPY");
+        assert_eq!(m(".5 KWD").to_string(), "0.500 KWD");
+        assert_eq!(Money::from_minor(-7, "EUR").unwrap().to_string(), "-0.07 EUR");
+        assert!(Money::of("1.005", "USD").unwrap_err().contains("at most 2 decimal places"));
+        assert!(Money::of("1.5", "JPY").is_err());
+        assert!(Money::of("1,000", "USD").is_err());
+        assert!(Money::of("1e3", "USD").is_err());
+        assert!(Money::of("1", "XYZ").unwrap_err().contains("unknown currency"));
+        assert!(Money::of("99999999999999999999", "USD").is_err());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let total = m("0.10 USD").add(&m("0.20 USD")).unwrap();
+        assert_eq!(total.to_string(), "0.30 USD");
+        assert_eq!(total.sub(&m("1 USD")).unwrap().to_string(), "-0.70 USD");
+        assert_eq!(m("1 USD").compare(&m("0.99 USD")), Ok(Ordering::Greater));
+        assert_eq!(m("1 USD").add(&m("1 EUR")).unwrap_err(), "cannot add USD and EUR amounts");
+        assert!(m("1 USD").compare(&m("1 EUR")).is_err());
+        assert!(Money::from_minor(i64::MAX, "USD").unwrap().add(&m("0.01 USD")).is_err());
+        assert!(Money::from_minor(i64::MIN, "USD").unwrap().negate().is_err());
+    }
+
+    #[test]
+    fn test_multiply_rounds_half_even() {
+        assert_eq!(m("19.99 USD").multiply("1.075").unwrap().to_string(), "21.49 USD");
+        assert_eq!(m("0.05 USD").multiply("0.5").unwrap().to_string(), "0.02 USD");
+        assert_eq!(m("0.15 USD").multiply("0.5").unwrap().to_string(), "0.08 USD");
+        assert_eq!(m("-0.05 USD").multiply("0.5").unwrap().to_string(), "-0.02 USD");
+        assert_eq!(m("10 USD").multiply("-3").unwrap().to_string(), "-30.00 USD");
+        assert!(m("1 USD").multiply("abc").is_err());
+    }
+
+    #[test]
+    fn test_allocate() {
+        let parts: Vec<String> = m("100 USD").allocate(&[1, 1, 1]).unwrap().iter().map(|p| p.to_string()).collect();
+        assert_eq!(parts, ["33.34 USD", "33.33 USD", "33.33 USD"]);
+        let parts: Vec<String> = m("-0.05 EUR").allocate(&[0, 3, 7]).unwrap().iter().map(|p| p.to_string()).collect();
+        assert_eq!(parts, ["0.00 EUR", "-0.02 EUR", "-0.03 EUR"]);
+        assert!(m("1 USD").allocate(&[]).is_err());
+        assert!(m("1 USD").allocate(&[1, -1]).is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        let rates: HashMap<String, String> = [("USD", "1"), ("EUR", "0.92"), ("JPY", "151.37"), ("kwd", "0.3075")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(m("100 USD").convert("EUR", &rates).unwrap().to_string(), "92.00 EUR");
+        assert_eq!(m("100 EUR").convert("JPY", &rates).unwrap().to_string(), "16453 JPY");
+        assert_eq!(m("1000 JPY").convert("USD", &rates).unwrap().to_string(), "6.61 USD");
+        assert_eq!(m("10 USD").convert("KWD", &rates).unwrap().to_string(), "3.075 KWD");
+        assert_eq!(m("5 GBP").convert("GBP", &rates).unwrap().to_string(), "5.00 GBP");
+        assert_eq!(m("5 GBP").convert("USD", &rates).unwrap_err(), "no exchange rate for GBP");
+        let bad: HashMap<String, String> = [("USD".to_string(), "1".to_string()), ("EUR".to_string(), "0".to_string())].into();
+        assert!(m("1 USD").convert("EUR", &bad).unwrap_err().contains("invalid exchange rate"));
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(Money::from_float(0.1 + 0.2, "USD").unwrap().to_string(), "0.30 USD");
+        assert_eq!(Money::from_float(2.675, "USD").unwrap().to_string(), "2.68 USD");
+        assert_eq!(Money::from_float(1e-300, "USD").unwrap().to_string(), "0.00 USD");
+        assert!(Money::from_float(f64::NAN, "USD").is_err());
+        assert!(Money::from_float(1e30, "USD").is_err());
+        assert_eq!(m("12.34 USD").to_float(), 12.34);
+    }
+}
@@ -0,0 +1,88 @@
+///
+/// ISO 4217 Currencies
+///
+/// Active ISO 4217 currency codes with their number of minor-unit digits
+/// (2 for cents, 0 for yen, 3 for Kuwaiti fils) and the symbol used when
+/// formatting. Codes without a common symbol are formatted by code.
+///
+
+#[derive(Debug)]
+pub struct Currency {
+    pub code: &'static str,
+    /// Digits after the decimal point; one minor unit is 10^-digits
+    pub digits: u32,
+    pub symbol: &'static str,
+}
+
+const fn c(code: &'static str, digits: u32, symbol: &'static str) -> Currency {
+    Currency { code, digits, symbol }
+}
+
+static CURRENCIES: &[Currency] = &[
+    c("AED", 2, "AED"), c("AFN", 2, "AFN"), c("ALL", 2, "ALL"), c("AMD", 2, "AMD"),
+    c("ANG", 2, "ANG"), c("AOA", 2, "AOA"), c("ARS", 2, "ARS"), c("AUD", 2, "A$"),
+    c("AWG", 2, "AWG"), c("AZN", 2, "AZN"), c("BAM", 2, "BAM"), c("BBD", 2, "BBD"),
+    c("BDT", 2, "BDT"), c("BGN", 2, "BGN"), c("BHD", 3, "BHD"), c("BIF", 0, "BIF"),
+    c("BMD", 2, "BMD"), c("BND", 2, "BND"), c("BOB", 2, "BOB"), c("BOV", 2, "BOV"),
+    c("BRL", 2, "R$"), c("BSD", 2, "BSD"), c("BTN", 2, "BTN"), c("BWP", 2, "BWP"),
+    c("BYN", 2, "BYN"), c("BZD", 2, "BZD"), c("CAD", 2, "CA$"), c("CDF", 2, "CDF"),
+    c("CHE", 2, "CHE"), c("CHF", 2, "CHF"), c("CHW", 2, "CHW"), c("CLF", 4, "CLF"),
+    c("CLP", 0, "CLP"), c("CNY", 2, "CN¥"), c("COP", 2, "COP"), c("COU", 2, "COU"),
+    c("CRC", 2, "CRC"), c("CUP", 2, "CUP"), c("CVE", 2, "CVE"), c("CZK", 2, "CZK"),
+    c("DJF", 0, "DJF"), c("DKK", 2, "DKK"), c("DOP", 2, "DOP"), c("DZD", 2, "DZD"),
+    c("EGP", 2, "EGP"), c("ERN", 2, "ERN"), c("ETB", 2, "ETB"), c("EUR", 2, "€"),
+    c("FJD", 2, "FJD"), c("FKP", 2, "FKP"), c("GBP", 2, "£"), c("GEL", 2, "GEL"),
+    c("GHS", 2, "GHS"), c("GIP", 2, "GIP"), c("GMD", 2, "GMD"), c("GNF", 0, "GNF"),
+    c("GTQ", 2, "GTQ"), c("GYD", 2, "GYD"), c("HKD", 2, "HK$"), c("HNL", 2, "HNL"),
+    c("HTG", 2, "HTG"), c("HUF", 2, "HUF"), c("IDR", 2, "IDR"), c("ILS", 2, "₪"),
+    c("INR", 2, "₹"), c("IQD", 3, "IQD"), c("IRR", 2, "IRR"), c("ISK", 0, "ISK"),
+    c("JMD", 2, "JMD"), c("JOD", 3, "JOD"), c("JPY", 0, "¥"), c("KES", 2, "KES"),
+    c("KGS", 2, "KGS"), c("KHR", 2, "KHR"), c("KMF", 0, "KMF"), c("KPW", 2, "KPW"),
+    c("KRW", 0, "₩"), c("KWD", 3, "KWD"), c("KYD", 2, "KYD"), c("KZT", 2, "KZT"),
+    c("LAK", 2, "LAK"), c("LBP", 2, "LBP"), c("LKR", 2, "LKR"), c("LRD", 2, "LRD"),
+    c("LSL", 2, "LSL"), c("LYD", 3, "LYD"), c("MAD", 2, "MAD"), c("MDL", 2, "MDL"),
+    c("MGA", 2, "MGA"), c("MKD", 2, "MKD"), c("MMK", 2, "MMK"), c("MNT", 2, "MNT"),
+    c("MOP", 2, "MOP"), c("MRU", 2, "MRU"), c("MUR", 2, "MUR"), c("MVR", 2, "MVR"),
+    c("MWK", 2, "MWK"), c("MXN", 2, "MX$"), c("MXV", 2, "MXV"), c("MYR", 2, "MYR"),
+    c("MZN", 2, "MZN"), c("NAD", 2, "NAD"), c("NGN", 2, "NGN"), c("NIO", 2, "NIO"),
+    c("NOK", 2, "NOK"), c("NPR", 2, "NPR"), c("NZD", 2, "NZ$"), c("OMR", 3, "OMR"),
+    c("PAB", 2, "PAB"), c("PEN", 2, "PEN"), c("PGK", 2, "PGK"), c("PHP", 2, "₱"),
+    c("PKR", 2, "PKR"), c("PLN", 2, "PLN"), c("PYG", 0, "PYG"), c("QAR", 2, "QAR"),
+    c("RON", 2, "RON"), c("RSD", 2, "RSD"), c("RUB", 2, "RUB"), c("RWF", 0, "RWF"),
+    c("SAR", 2, "SAR"), c("SBD", 2, "SBD"), c("SCR", 2, "SCR"), c("SDG", 2, "SDG"),
+    c("SEK", 2, "SEK"), c("SGD", 2, "SGD"), c("SHP", 2, "SHP"), c("SLE", 2, "SLE"),
+    c("SOS", 2, "SOS"), c("SRD", 2, "SRD"), c("SSP", 2, "SSP"), c("STN", 2, "STN"),
+    c("SVC", 2, "SVC"), c("SYP", 2, "SYP"), c("SZL", 2, "SZL"), c("THB", 2, "฿"),
+    c("TJS", 2, "TJS"), c("TMT", 2, "TMT"), c("TND", 3, "TND"), c("TOP", 2, "TOP"),
+    c("TRY", 2, "TRY"), c("TTD", 2, "TTD"), c("TWD", 2, "NT$"), c("TZS", 2, "TZS"),
+    c("UAH", 2, "UAH"), c("UGX", 0, "UGX"), c("USD", 2, "$"), c("USN", 2, "USN"),
+    c("UYI", 0, "UYI"), c("UYU", 2, "UYU"), c("UYW", 4, "UYW"), c("UZS", 2, "UZS"),
+    c("VED", 2, "VED"), c("VES", 2, "VES"), c("VND", 0, "₫"), c("VUV", 0, "VUV"),
+    c("WST", 2, "WST"), c("XAF", 0, "FCFA"), c("XCD", 2, "EC$"), c("XCG", 2, "XCG"),
+    c("XOF", 0, "F\u{202F}CFA"), c("XPF", 0, "CFPF"), c("YER", 2, "YER"), c("ZAR", 2, "ZAR"),
+    c("ZMW", 2, "ZMW"), c("ZWG", 2, "ZWG"),
+];
+
+/// Look up a currency by its ISO 4217 code, e.g. `USD`
+pub fn lookup(code: &str) -> Option<&'static Currency> {
+    CURRENCIES
+        .binary_search_by(|c| c.code.cmp(code))
+        .ok()
+        .map(|i| &CURRENCIES[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_sorted_and_lookup() {
+        assert!(CURRENCIES.windows(2).all(|w| w[0].code < w[1].code));
+        assert!(CURRENCIES.iter().all(|c| c.code.len() == 3 && c.code.bytes().all(|b| b.is_ascii_uppercase())));
+        assert_eq!(lookup("USD").map(|c| c.digits), Some(2));
+        assert_eq!(lookup("JPY").map(|c| c.digits), Some(0));
+        assert_eq!(lookup("KWD").map(|c| c.digits), Some(3));
+        assert!(lookup("usd").is_none());
+        assert!(lookup("XXX").is_none());
+    }
+}
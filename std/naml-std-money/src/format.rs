@@ -0,0 +1,149 @@
+///
+/// Locale Formatting
+///
+/// Formats amounts with a locale's decimal and grouping separators and
+/// symbol placement, following the CLDR patterns for the supported locales.
+/// A currency's own symbol is used unless the locale has a local one for
+/// it (`$` for USD in en-US, but `CA$` for CAD).
+///
+
+use crate::amount::{Money, MoneyResult};
+
+const NBSP: &str = "\u{A0}";
+const NARROW_NBSP: &str = "\u{202F}";
+
+struct Locale {
+    tag: &'static str,
+    decimal: &'static str,
+    group: &'static str,
+    /// Digits needed before the first group is split off, e.g. es-ES
+    /// writes 1234 but 12.345
+    min_group_digits: usize,
+    /// Indian grouping: thousands, then groups of two (12,34,567)
+    indian: bool,
+    symbol_first: bool,
+    symbol_space: &'static str,
+    /// The minus sign goes between the symbol and the number (nl-NL)
+    minus_after_symbol: bool,
+    /// Local symbols for currencies, overriding the currency's own
+    symbols: &'static [(&'static str, &'static str)],
+}
+
+const fn symbol_first(tag: &'static str, decimal: &'static str, group: &'static str, space: &'static str, symbols: &'static [(&'static str, &'static str)]) -> Locale {
+    Locale { tag, decimal, group, min_group_digits: 4, indian: false, symbol_first: true, symbol_space: space, minus_after_symbol: false, symbols }
+}
+
+const fn symbol_last(tag: &'static str, decimal: &'static str, group: &'static str, symbols: &'static [(&'static str, &'static str)]) -> Locale {
+    Locale { tag, decimal, group, min_group_digits: 4, indian: false, symbol_first: false, symbol_space: NBSP, minus_after_symbol: false, symbols }
+}
+
+static LOCALES: &[Locale] = &[
+    symbol_first("en-US", ".", ",", "", &[]),
+    symbol_first("en-GB", ".", ",", "", &[]),
+    symbol_first("en-CA", ".", ",", "", &[("CAD", "$"), ("USD", "US$")]),
+    symbol_first("en-AU", ".", ",", "", &[("AUD", "$"), ("USD", "US$")]),
+    Locale { indian: true, ..symbol_first("en-IN", ".", ",", "", &[]) },
+    symbol_last("de-DE", ",", ".", &[]),
+    symbol_first("de-CH", ".", "’", NBSP, &[]),
+    symbol_last("fr-FR", ",", NARROW_NBSP, &[("USD", "$US")]),
+    Locale { min_group_digits: 5, ..symbol_last("es-ES", ",", ".", &[("USD", "US$")]) },
+    symbol_last("it-IT", ",", ".", &[("USD", "USD")]),
+    Locale { minus_after_symbol: true, ..symbol_first("nl-NL", ",", ".", NBSP, &[("USD", "US$")]) },
+    symbol_first("pt-BR", ",", ".", NBSP, &[("USD", "US$")]),
+    symbol_last("sv-SE", ",", NBSP, &[("SEK", "kr"), ("USD", "US$")]),
+    symbol_first("ja-JP", ".", ",", "", &[("JPY", "￥"), ("USD", "$")]),
+    symbol_first("zh-CN", ".", ",", "", &[("CNY", "¥"), ("USD", "US$"), ("JPY", "JP¥")]),
+];
+
+fn find_locale(tag: &str) -> Option<&'static Locale> {
+    let tag = tag.trim().replace('_', "-");
+    LOCALES
+        .iter()
+        .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+        .or_else(|| {
+            // A bare language such as "de" uses that language's first locale
+            let lang = tag.split('-').next().unwrap_or("");
+            LOCALES.iter().find(|l| l.tag.split('-').next().is_some_and(|l| l.eq_ignore_ascii_case(lang)))
+        })
+}
+
+fn group_digits(digits: &str, locale: &Locale) -> String {
+    if digits.len() < locale.min_group_digits {
+        return digits.to_string();
+    }
+    let mut groups = Vec::new();
+    let mut end = digits.len();
+    let mut size = 3;
+    while end > size {
+        groups.push(&digits[end - size..end]);
+        end -= size;
+        if locale.indian {
+            size = 2;
+        }
+    }
+    groups.push(&digits[..end]);
+    groups.reverse();
+    groups.join(locale.group)
+}
+
+/// `m` formatted for `locale`, e.g. `$1,234.56` (en-US) or `1.234,56 €` (de-DE)
+pub fn format(m: &Money, locale: &str) -> MoneyResult<String> {
+    let locale = find_locale(locale).ok_or_else(|| format!("unsupported locale '{}'", locale))?;
+    let amount = m.amount();
+    let unsigned = amount.trim_start_matches('-');
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let mut number = group_digits(int_part, locale);
+    if !frac_part.is_empty() {
+        number.push_str(locale.decimal);
+        number.push_str(frac_part);
+    }
+    let symbol = locale
+        .symbols
+        .iter()
+        .find(|(code, _)| *code == m.currency.code)
+        .map_or(m.currency.symbol, |(_, symbol)| *symbol);
+    let minus = if m.minor < 0 { "-" } else { "" };
+    if !locale.symbol_first {
+        return Ok(format!("{}{}{}{}", minus, number, locale.symbol_space, symbol));
+    }
+    // Letter symbols such as "CHF" are kept apart from the digits
+    let space = if locale.symbol_space.is_empty() && symbol.ends_with(|c: char| c.is_ascii_alphabetic()) {
+        NBSP
+    } else {
+        locale.symbol_space
+    };
+    if locale.minus_after_symbol {
+        Ok(format!("{}{}{}{}", symbol, space, minus, number))
+    } else {
+        Ok(format!("{}{}{}{}", minus, symbol, space, number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(amount: &str, code: &str, locale: &str) -> String {
+        format(&Money::of(amount, code).unwrap(), locale).unwrap()
+    }
+
+    #[test]
+    fn test_locales() {
+        assert_eq!(f("1234.5", "USD", "en-US"), "$1,234.50");
+        assert_eq!(f("-1234567.89", "USD", "en_us"), "-$1,234,567.89");
+        assert_eq!(f("12.5", "CAD", "en-US"), "CA$12.50");
+        assert_eq!(f("12.5", "CAD", "en-CA"), "$12.50");
+        assert_eq!(f("1234.5", "CHF", "en-US"), "CHF\u{A0}1,234.50");
+        assert_eq!(f("1234567.89", "INR", "en-IN"), "₹12,34,567.89");
+        assert_eq!(f("1234.56", "EUR", "de-DE"), "1.234,56\u{A0}€");
+        assert_eq!(f("1234.56", "CHF", "de-CH"), "CHF\u{A0}1’234.56");
+        assert_eq!(f("-1234.56", "EUR", "fr-FR"), "-1\u{202F}234,56\u{A0}€");
+        assert_eq!(f("1234.56", "EUR", "es-ES"), "1234,56\u{A0}€");
+        assert_eq!(f("12345.67", "EUR", "es"), "12.345,67\u{A0}€");
+        assert_eq!(f("-1234.56", "EUR", "nl-NL"), "€\u{A0}-1.234,56");
+        assert_eq!(f("1234.56", "BRL", "pt-BR"), "R$\u{A0}1.234,56");
+        assert_eq!(f("1234", "JPY", "ja-JP"), "￥1,234");
+        assert_eq!(f("0.5", "KWD", "en-GB"), "KWD\u{A0}0.500");
+        assert!(format(&Money::of("1", "USD").unwrap(), "xx-YY").is_err());
+    }
+}
@@ -0,0 +1,301 @@
+///
+/// naml-std-money - Currency Amounts
+///
+/// Exact amounts of money in ISO 4217 currencies. A `money` value holds a
+/// whole number of minor units (cents) and its currency, so billing code
+/// never goes through floats. Amounts in different currencies cannot be
+/// combined; convert them first with an explicit rate table.
+///
+/// ## Functions
+///
+/// - `of(amount: string, currency: string) -> money throws MoneyError` - Exact amount, e.g. `of("12.34", "USD")`
+/// - `from_minor(minor: int, currency: string) -> money throws MoneyError` - From minor units
+/// - `from_float(value: float, currency: string) -> money throws MoneyError` - Nearest amount to a float
+/// - `parse(s: string) -> money throws MoneyError` - Read the `"12.34 USD"` form back
+/// - `add(a: money, b: money) -> money throws MoneyError` / `sub(...)`
+/// - `negate(m: money) -> money throws MoneyError`
+/// - `multiply(m: money, factor: string) -> money throws MoneyError` - Exact decimal factor
+/// - `allocate(m: money, ratios: [int]) -> [money] throws MoneyError` - Split without losing cents
+/// - `compare(a: money, b: money) -> int throws MoneyError` - -1, 0 or 1
+/// - `convert(m: money, currency: string, rates: map<string, string>) -> money throws MoneyError`
+/// - `format(m: money, locale: string) -> string throws MoneyError` - e.g. `$1,234.56`
+/// - `amount(m: money) -> string` / `currency(m: money) -> string` / `minor_units(m: money) -> int`
+/// - `to_float(m: money) -> float`
+/// - `currency_digits(currency: string) -> int throws MoneyError` - Minor-unit digits, e.g. 2
+///
+/// ## Representation
+///
+/// Money values are immutable strings in the canonical form `"12.34 USD"`,
+/// which is also how they print. Rounding is half to even.
+///
+
+mod amount;
+mod currency;
+mod format;
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use amount::{Money, MoneyResult};
+
+use naml_std_core::{
+    naml_array_get, naml_array_new, naml_array_push, naml_exception_set_typed, naml_stack_capture,
+    naml_string_new, NamlArray, NamlMap, NamlString, EXCEPTION_TYPE_MONEY_ERROR,
+};
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+/// Throw MoneyError with layout: message@0, stack@8
+fn throw_money_error(message: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(16, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate MoneyError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_from_string(message);
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_MONEY_ERROR);
+    }
+}
+
+unsafe fn money_from_naml(m: *const NamlString) -> MoneyResult<Money> {
+    Money::parse(&unsafe { string_from_naml(m) })
+}
+
+/// Return a money result as a naml value, throwing MoneyError on failure
+unsafe fn money_result(result: MoneyResult<Money>) -> *mut NamlString {
+    match result {
+        Ok(m) => unsafe { naml_from_string(&m.to_string()) },
+        Err(message) => {
+            throw_money_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+unsafe fn string_result(result: MoneyResult<String>) -> *mut NamlString {
+    match result {
+        Ok(s) => unsafe { naml_from_string(&s) },
+        Err(message) => {
+            throw_money_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+fn int_result(result: MoneyResult<i64>) -> i64 {
+    result.unwrap_or_else(|message| {
+        throw_money_error(&message);
+        0
+    })
+}
+
+/// Read a `map<string, string>` into owned strings
+unsafe fn rates_from_map(map: *const NamlMap) -> HashMap<String, String> {
+    let mut rates = HashMap::new();
+    if map.is_null() {
+        return rates;
+    }
+    unsafe {
+        for i in 0..(*map).capacity {
+            let entry = &*(*map).entries.add(i);
+            if entry.occupied {
+                rates.insert(
+                    string_from_naml(entry.key as *const NamlString),
+                    string_from_naml(entry.value as *const NamlString),
+                );
+            }
+        }
+    }
+    rates
+}
+
+/// Exact amount from a decimal string
+///
+/// # Safety
+/// The caller must ensure `amount` is a valid pointer to a NamlString and
+/// `code` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_of(amount: *const NamlString, code: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(Money::of(&string_from_naml(amount), &string_from_naml(code))) }
+}
+
+/// Amount from a count of minor units
+///
+/// # Safety
+/// The caller must ensure `code` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_from_minor(minor: i64, code: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(Money::from_minor(minor, &string_from_naml(code))) }
+}
+
+/// Nearest amount to a float, rounded to the minor unit
+///
+/// # Safety
+/// The caller must ensure `code` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_from_float(value: f64, code: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(Money::from_float(value, &string_from_naml(code))) }
+}
+
+/// Parse the canonical `"12.34 USD"` form
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_parse(s: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(Money::parse(&string_from_naml(s))) }
+}
+
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString and `b` is a
+/// valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_add(a: *const NamlString, b: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(money_from_naml(a).and_then(|a| a.add(&money_from_naml(b)?))) }
+}
+
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString and `b` is a
+/// valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_sub(a: *const NamlString, b: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(money_from_naml(a).and_then(|a| a.sub(&money_from_naml(b)?))) }
+}
+
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_negate(m: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(money_from_naml(m).and_then(|m| m.negate())) }
+}
+
+/// Multiply by an exact decimal factor such as `"1.075"`
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString and `factor`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_multiply(m: *const NamlString, factor: *const NamlString) -> *mut NamlString {
+    unsafe { money_result(money_from_naml(m).and_then(|m| m.multiply(&string_from_naml(factor)))) }
+}
+
+/// Split into parts proportional to `ratios`, as an array of money
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString and `ratios`
+/// is a valid pointer to a NamlArray or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_allocate(m: *const NamlString, ratios: *const NamlArray) -> *mut NamlArray {
+    let ratios: Vec<i64> = if ratios.is_null() {
+        Vec::new()
+    } else {
+        unsafe { (0..(*ratios).len as i64).map(|i| naml_array_get(ratios, i)).collect() }
+    };
+    match unsafe { money_from_naml(m) }.and_then(|m| m.allocate(&ratios)) {
+        Ok(parts) => unsafe {
+            let result = naml_array_new(parts.len());
+            for part in parts {
+                naml_array_push(result, naml_from_string(&part.to_string()) as i64);
+            }
+            result
+        },
+        Err(message) => {
+            throw_money_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// -1, 0 or 1 as `a` is less than, equal to or greater than `b`
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString and `b` is a
+/// valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_compare(a: *const NamlString, b: *const NamlString) -> i64 {
+    let ordering = unsafe { money_from_naml(a).and_then(|a| a.compare(&money_from_naml(b)?)) };
+    int_result(ordering.map(|o| match o {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }))
+}
+
+/// Convert through a rate table of `code -> units per base unit`
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString, `code` is a
+/// valid pointer to a NamlString and `rates` is a valid pointer to a NamlMap.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_convert(
+    m: *const NamlString,
+    code: *const NamlString,
+    rates: *const NamlMap,
+) -> *mut NamlString {
+    unsafe {
+        let rates = rates_from_map(rates);
+        money_result(money_from_naml(m).and_then(|m| m.convert(&string_from_naml(code), &rates)))
+    }
+}
+
+/// Format for a locale such as `en-US` or `de-DE`
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString and `locale`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_format(m: *const NamlString, locale: *const NamlString) -> *mut NamlString {
+    unsafe { string_result(money_from_naml(m).and_then(|m| format::format(&m, &string_from_naml(locale)))) }
+}
+
+/// The amount without its currency, e.g. `"12.34"`
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_amount(m: *const NamlString) -> *mut NamlString {
+    unsafe { string_result(money_from_naml(m).map(|m| m.amount())) }
+}
+
+/// The ISO 4217 currency code, e.g. `"USD"`
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_currency(m: *const NamlString) -> *mut NamlString {
+    unsafe { string_result(money_from_naml(m).map(|m| m.currency.code.to_string())) }
+}
+
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_minor_units(m: *const NamlString) -> i64 {
+    int_result(unsafe { money_from_naml(m) }.map(|m| m.minor))
+}
+
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_to_float(m: *const NamlString) -> f64 {
+    unsafe { money_from_naml(m) }.map_or(0.0, |m| m.to_float())
+}
+
+/// Minor-unit digits of a currency, e.g. 2 for USD and 0 for JPY
+///
+/// # Safety
+/// The caller must ensure `code` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_money_currency_digits(code: *const NamlString) -> i64 {
+    int_result(amount::currency(&unsafe { string_from_naml(code) }).map(|c| c.digits as i64))
+}
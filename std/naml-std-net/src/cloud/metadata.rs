@@ -0,0 +1,645 @@
+//!
+//! Cloud Instance Metadata
+//!
+//! Detects whether the program runs on an AWS EC2, Google Compute Engine or
+//! Azure VM and queries that provider's instance metadata service for the
+//! region, instance id and the credentials of the attached role or managed
+//! identity. Requests go through the HTTP client but never through a proxy,
+//! since the metadata services are only reachable from the instance itself.
+//!
+//! ## Functions
+//!
+//! - `naml_cloud_metadata_provider` - "aws", "gcp", "azure" or "none"
+//! - `naml_cloud_metadata_region` - Region the instance runs in
+//! - `naml_cloud_metadata_instance_id` - Provider-assigned instance id
+//! - `naml_cloud_metadata_credentials` - Role/identity credentials as a map
+//! - `naml_cloud_metadata_get` - Raw metadata value at a provider path
+//! - `naml_cloud_metadata_set_endpoint` - Use a fixed provider and endpoint
+//! - `naml_cloud_metadata_reset` - Forget detection and cached credentials
+//!
+//! ## Detection
+//!
+//! The first call probes the AWS (IMDSv2 token), GCE and Azure endpoints in
+//! turn with a one second timeout and remembers the result for the life of
+//! the process. Setting `AWS_EC2_METADATA_DISABLED=true` skips the AWS probe.
+//!
+//! ## Credentials
+//!
+//! Credentials are cached and fetched again once they are within five
+//! minutes of expiring. The map holds `access_key_id`, `secret_access_key`,
+//! `session_token` and `expires_at` on AWS, and `access_token`, `token_type`
+//! and `expires_at` on GCP and Azure; `expires_at` is in unix seconds.
+//!
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use naml_std_core::map::{naml_map_new, naml_map_set_string, NamlMap};
+use naml_std_core::value::{naml_string_decref, naml_string_new, NamlString};
+use serde_json::Value;
+
+use crate::errors::{string_from_naml, throw_network_error_code};
+use crate::http::client::fetch_direct;
+use crate::http::retry::RETRIES_HEADER;
+
+/// Timeout of each detection probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Timeout of metadata queries once the provider is known
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Credentials closer than this to expiring are fetched again
+const REFRESH_MARGIN_SECS: i64 = 300;
+/// Lifetime requested for IMDSv2 session tokens (the maximum, 6 hours)
+const AWS_TOKEN_TTL_SECS: u64 = 21_600;
+
+const AZURE_INSTANCE_API: &str = "api-version=2021-02-01&format=text";
+const AZURE_TOKEN_PATH: &str =
+    "identity/oauth2/token?api-version=2018-02-01&resource=https%3A%2F%2Fmanagement.azure.com%2F";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Provider {
+    Aws,
+    Gcp,
+    Azure,
+}
+
+impl Provider {
+    fn name(self) -> &'static str {
+        match self {
+            Provider::Aws => "aws",
+            Provider::Gcp => "gcp",
+            Provider::Azure => "azure",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "aws" => Some(Provider::Aws),
+            "gcp" => Some(Provider::Gcp),
+            "azure" => Some(Provider::Azure),
+            _ => None,
+        }
+    }
+
+    fn default_endpoint(self) -> &'static str {
+        match self {
+            Provider::Aws | Provider::Azure => "http://169.254.169.254",
+            Provider::Gcp => "http://metadata.google.internal",
+        }
+    }
+
+    /// Prefix of every metadata path
+    fn root(self) -> &'static str {
+        match self {
+            Provider::Aws => "/latest/",
+            Provider::Gcp => "/computeMetadata/v1/",
+            Provider::Azure => "/metadata/",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Service {
+    provider: Provider,
+    /// Scheme and authority, e.g. `http://169.254.169.254`
+    endpoint: String,
+}
+
+#[derive(Clone, Debug)]
+struct CachedCredentials {
+    fields: Vec<(&'static str, String)>,
+    expires_at: i64,
+}
+
+struct MetadataState {
+    /// `None` until detection ran; `Some(None)` when no service was found
+    service: Option<Option<Service>>,
+    /// IMDSv2 session token and when it was issued
+    aws_token: Option<(String, Instant)>,
+    credentials: Option<CachedCredentials>,
+}
+
+impl MetadataState {
+    const fn new() -> Self {
+        MetadataState { service: None, aws_token: None, credentials: None }
+    }
+}
+
+/// Held across requests so concurrent callers share one detection and
+/// one credentials refresh
+static STATE: Mutex<MetadataState> = Mutex::new(MetadataState::new());
+
+#[derive(Debug)]
+pub(crate) struct MetadataError {
+    message: String,
+    code: i64,
+}
+
+impl MetadataError {
+    fn new(message: impl Into<String>) -> Self {
+        MetadataError { message: message.into(), code: -1 }
+    }
+
+    fn throw(&self) {
+        throw_network_error_code(&self.message, self.code);
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
+/// Unix seconds of an RFC 3339 UTC timestamp such as `2026-10-17T18:30:45Z`
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let num = |range: std::ops::Range<usize>| value.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if value.get(4..5)? != "-" || value.get(7..8)? != "-" || !matches!(value.get(10..11)?, "T" | "t" | " ") {
+        return None;
+    }
+
+    let mut rest = value.get(19..)?;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        rest = fraction.trim_start_matches(|c: char| c.is_ascii_digit());
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.get(0..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let hours: i64 = rest.get(1..3)?.parse().ok()?;
+            let minutes: i64 = rest.get(4..6)?.parse().ok()?;
+            sign * (hours * 3_600 + minutes * 60)
+        }
+    };
+
+    // Days since 1970-01-01 for a civil date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second - offset)
+}
+
+/// Region of a GCE zone such as `projects/123/zones/us-central1-a`
+fn region_from_zone(zone: &str) -> String {
+    let zone = zone.rsplit('/').next().unwrap_or(zone);
+    match zone.rfind('-') {
+        Some(pos) => zone[..pos].to_string(),
+        None => zone.to_string(),
+    }
+}
+
+fn provider_headers(provider: Provider) -> Vec<(String, String)> {
+    match provider {
+        Provider::Aws => Vec::new(),
+        Provider::Gcp => vec![("Metadata-Flavor".to_string(), "Google".to_string())],
+        Provider::Azure => vec![("Metadata".to_string(), "true".to_string())],
+    }
+}
+
+/// Body of a successful request, or the error to throw
+fn request(
+    method: &str,
+    url: &str,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+) -> Result<String, MetadataError> {
+    match fetch_direct(method, url, None, headers, timeout) {
+        Ok((status, body)) if (200..300).contains(&status) => Ok(String::from_utf8_lossy(&body).into_owned()),
+        Ok((status, body)) => {
+            let body = String::from_utf8_lossy(&body);
+            let detail = body.trim();
+            let mut message = format!("metadata request {} {} failed with status {}", method, url, status);
+            if !detail.is_empty() {
+                message.push_str(": ");
+                message.push_str(detail);
+            }
+            Err(MetadataError { message, code: status })
+        }
+        Err(e) => Err(MetadataError {
+            message: format!("metadata request {} {} failed: {}", method, url, e),
+            code: e.raw_os_error().unwrap_or(-1) as i64,
+        }),
+    }
+}
+
+fn aws_token_request(endpoint: &str, timeout: Duration) -> Result<String, MetadataError> {
+    let headers = vec![
+        ("X-aws-ec2-metadata-token-ttl-seconds".to_string(), AWS_TOKEN_TTL_SECS.to_string()),
+        (RETRIES_HEADER.to_string(), "0".to_string()),
+    ];
+    request("PUT", &format!("{}/latest/api/token", endpoint), headers, timeout)
+}
+
+/// Whether `provider`'s metadata service answers at its default endpoint
+fn probe(provider: Provider) -> bool {
+    let endpoint = provider.default_endpoint();
+    let mut headers = provider_headers(provider);
+    headers.push((RETRIES_HEADER.to_string(), "0".to_string()));
+    match provider {
+        Provider::Aws => aws_token_request(endpoint, PROBE_TIMEOUT).is_ok(),
+        Provider::Gcp => request("GET", &format!("{}/computeMetadata/v1/instance/id", endpoint), headers, PROBE_TIMEOUT).is_ok(),
+        Provider::Azure => {
+            let url = format!("{}/metadata/instance/compute/vmId?{}", endpoint, AZURE_INSTANCE_API);
+            request("GET", &url, headers, PROBE_TIMEOUT).is_ok()
+        }
+    }
+}
+
+fn detect() -> Option<Service> {
+    let aws_disabled = std::env::var("AWS_EC2_METADATA_DISABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"));
+    [Provider::Aws, Provider::Gcp, Provider::Azure]
+        .into_iter()
+        .filter(|&provider| !(provider == Provider::Aws && aws_disabled))
+        .find(|&provider| probe(provider))
+        .map(|provider| Service { provider, endpoint: provider.default_endpoint().to_string() })
+}
+
+impl MetadataState {
+    fn service(&mut self) -> Option<Service> {
+        self.service.get_or_insert_with(detect).clone()
+    }
+
+    fn require_service(&mut self) -> Result<Service, MetadataError> {
+        self.service()
+            .ok_or_else(|| MetadataError::new("no cloud instance metadata service found"))
+    }
+
+    fn aws_token(&mut self, endpoint: &str) -> Result<String, MetadataError> {
+        // Renew a minute early so a token never expires mid-request
+        let max_age = Duration::from_secs(AWS_TOKEN_TTL_SECS - 60);
+        if let Some((token, issued)) = &self.aws_token
+            && issued.elapsed() < max_age
+        {
+            return Ok(token.clone());
+        }
+        let token = aws_token_request(endpoint, REQUEST_TIMEOUT)?.trim().to_string();
+        self.aws_token = Some((token.clone(), Instant::now()));
+        Ok(token)
+    }
+
+    /// Metadata value at `path`, relative to the provider's metadata root
+    fn get(&mut self, path: &str) -> Result<String, MetadataError> {
+        let service = self.require_service()?;
+        let mut headers = provider_headers(service.provider);
+        if service.provider == Provider::Aws {
+            headers.push(("X-aws-ec2-metadata-token".to_string(), self.aws_token(&service.endpoint)?));
+        }
+        let url = format!("{}{}{}", service.endpoint, service.provider.root(), path.trim_start_matches('/'));
+        request("GET", &url, headers, REQUEST_TIMEOUT)
+    }
+
+    fn get_json(&mut self, path: &str) -> Result<Value, MetadataError> {
+        let body = self.get(path)?;
+        serde_json::from_str(&body).map_err(|e| MetadataError::new(format!("invalid metadata response for {}: {}", path, e)))
+    }
+
+    fn region(&mut self) -> Result<String, MetadataError> {
+        let service = self.require_service()?;
+        let region = match service.provider {
+            Provider::Aws => self.get("meta-data/placement/region")?,
+            Provider::Gcp => region_from_zone(self.get("instance/zone")?.trim()),
+            Provider::Azure => self.get(&format!("instance/compute/location?{}", AZURE_INSTANCE_API))?,
+        };
+        Ok(region.trim().to_string())
+    }
+
+    fn instance_id(&mut self) -> Result<String, MetadataError> {
+        let service = self.require_service()?;
+        let id = match service.provider {
+            Provider::Aws => self.get("meta-data/instance-id")?,
+            Provider::Gcp => self.get("instance/id")?,
+            Provider::Azure => self.get(&format!("instance/compute/vmId?{}", AZURE_INSTANCE_API))?,
+        };
+        Ok(id.trim().to_string())
+    }
+
+    fn credentials(&mut self) -> Result<CachedCredentials, MetadataError> {
+        if let Some(cached) = &self.credentials
+            && cached.expires_at - now_secs() > REFRESH_MARGIN_SECS
+        {
+            return Ok(cached.clone());
+        }
+        let service = self.require_service()?;
+        let fresh = match service.provider {
+            Provider::Aws => self.fetch_aws_credentials()?,
+            Provider::Gcp => {
+                let doc = self.get_json("instance/service-accounts/default/token")?;
+                let expires_in = doc["expires_in"].as_i64().unwrap_or(0);
+                token_credentials(&doc, now_secs() + expires_in)?
+            }
+            Provider::Azure => {
+                let doc = self.get_json(AZURE_TOKEN_PATH)?;
+                // expires_on is a string of unix seconds
+                let expires_at = match &doc["expires_on"] {
+                    Value::String(s) => s.parse().unwrap_or(0),
+                    other => other.as_i64().unwrap_or(0),
+                };
+                token_credentials(&doc, expires_at)?
+            }
+        };
+        self.credentials = Some(fresh.clone());
+        Ok(fresh)
+    }
+
+    fn fetch_aws_credentials(&mut self) -> Result<CachedCredentials, MetadataError> {
+        let roles = self.get("meta-data/iam/security-credentials/")?;
+        let role = roles
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .ok_or_else(|| MetadataError::new("instance has no IAM role attached"))?
+            .to_string();
+        let doc = self.get_json(&format!("meta-data/iam/security-credentials/{}", role))?;
+        if let Some(code) = doc["Code"].as_str().filter(|code| *code != "Success") {
+            let message = doc["Message"].as_str().unwrap_or_default();
+            return Err(MetadataError::new(format!("IAM credentials for role {} unavailable: {} {}", role, code, message)));
+        }
+
+        let field = |name: &str| {
+            doc[name]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| MetadataError::new(format!("IAM credentials for role {} have no {}", role, name)))
+        };
+        let expiration = field("Expiration")?;
+        let expires_at = parse_timestamp(&expiration)
+            .ok_or_else(|| MetadataError::new(format!("invalid credentials expiration '{}'", expiration)))?;
+        Ok(CachedCredentials {
+            fields: vec![
+                ("access_key_id", field("AccessKeyId")?),
+                ("secret_access_key", field("SecretAccessKey")?),
+                ("session_token", field("Token")?),
+                ("expires_at", expires_at.to_string()),
+            ],
+            expires_at,
+        })
+    }
+}
+
+/// Credentials from an OAuth2 token response
+fn token_credentials(doc: &Value, expires_at: i64) -> Result<CachedCredentials, MetadataError> {
+    let token = doc["access_token"]
+        .as_str()
+        .ok_or_else(|| MetadataError::new("metadata token response has no access_token"))?;
+    Ok(CachedCredentials {
+        fields: vec![
+            ("access_token", token.to_string()),
+            ("token_type", doc["token_type"].as_str().unwrap_or("Bearer").to_string()),
+            ("expires_at", expires_at.to_string()),
+        ],
+        expires_at,
+    })
+}
+
+/// Access key, secret key and session token of the EC2 instance role, when
+/// running on AWS with a role attached
+pub(crate) fn aws_instance_credentials() -> Option<(String, String, String)> {
+    let mut state = STATE.lock().unwrap();
+    if state.service()?.provider != Provider::Aws {
+        return None;
+    }
+    let creds = state.credentials().ok()?;
+    let value = |name: &str| {
+        creds.fields.iter().find(|(key, _)| *key == name).map(|(_, v)| v.clone()).unwrap_or_default()
+    };
+    Some((value("access_key_id"), value("secret_access_key"), value("session_token")))
+}
+
+unsafe fn naml_from_str(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+fn string_result(result: Result<String, MetadataError>) -> *mut NamlString {
+    match result {
+        Ok(value) => unsafe { naml_from_str(&value) },
+        Err(e) => {
+            e.throw();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Detected provider: "aws", "gcp", "azure" or "none"
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_cloud_metadata_provider() -> *mut NamlString {
+    let service = STATE.lock().unwrap().service();
+    let name = service.map_or("none", |s| s.provider.name());
+    unsafe { naml_from_str(name) }
+}
+
+/// Region the instance runs in, e.g. "us-east-1" or "westeurope"
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_cloud_metadata_region() -> *mut NamlString {
+    string_result(STATE.lock().unwrap().region())
+}
+
+/// Provider-assigned id of the instance
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_cloud_metadata_instance_id() -> *mut NamlString {
+    string_result(STATE.lock().unwrap().instance_id())
+}
+
+/// Credentials of the instance role or managed identity
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_cloud_metadata_credentials() -> *mut NamlMap {
+    let creds = match STATE.lock().unwrap().credentials() {
+        Ok(creds) => creds,
+        Err(e) => {
+            e.throw();
+            return std::ptr::null_mut();
+        }
+    };
+    unsafe {
+        let map = naml_map_new(creds.fields.len());
+        for (key, value) in &creds.fields {
+            let key = naml_from_str(key);
+            naml_map_set_string(map, key as i64, naml_from_str(value) as i64);
+            naml_string_decref(key);
+        }
+        map
+    }
+}
+
+/// Raw metadata value at `path`, relative to the provider's metadata root
+/// (`/latest/` on AWS, `/computeMetadata/v1/` on GCP, `/metadata/` on Azure)
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_cloud_metadata_get(path: *const NamlString) -> *mut NamlString {
+    let path = unsafe { string_from_naml(path) };
+    string_result(STATE.lock().unwrap().get(&path))
+}
+
+/// Skip detection and use `provider` at `endpoint` (e.g. an emulator);
+/// an empty endpoint means the provider's default
+///
+/// # Safety
+/// The caller must ensure `provider` is a valid pointer to a NamlString and
+/// `endpoint` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_cloud_metadata_set_endpoint(provider: *const NamlString, endpoint: *const NamlString) {
+    let (name, endpoint) = unsafe { (string_from_naml(provider), string_from_naml(endpoint)) };
+    let Some(provider) = Provider::from_name(&name) else {
+        MetadataError::new(format!("unknown cloud provider '{}': expected aws, gcp or azure", name)).throw();
+        return;
+    };
+    let endpoint = match endpoint.trim_end_matches('/') {
+        "" => provider.default_endpoint().to_string(),
+        endpoint => endpoint.to_string(),
+    };
+    let mut state = STATE.lock().unwrap();
+    *state = MetadataState::new();
+    state.service = Some(Some(Service { provider, endpoint }));
+}
+
+/// Forget the detected provider, endpoint and cached credentials
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_cloud_metadata_reset() {
+    *STATE.lock().unwrap() = MetadataState::new();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes tests that use the shared metadata state
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Serve one (status line, body) response per connection, returning the raw requests
+    fn serve(responses: Vec<(&'static str, &'static str)>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).unwrap();
+                        if n == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(response.as_bytes()).unwrap();
+                    String::from_utf8_lossy(&request).into_owned()
+                })
+                .collect()
+        });
+        (endpoint, server)
+    }
+
+    fn use_service(provider: Provider, endpoint: &str) -> std::sync::MutexGuard<'static, MetadataState> {
+        let mut state = STATE.lock().unwrap();
+        *state = MetadataState::new();
+        state.service = Some(Some(Service { provider, endpoint: endpoint.to_string() }));
+        state
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_timestamp("2000-02-29T12:34:56Z"), Some(951_827_696));
+        assert_eq!(parse_timestamp("2000-02-29T14:34:56.250+02:00"), Some(951_827_696));
+        assert_eq!(parse_timestamp("2000-02-29"), None);
+        assert_eq!(parse_timestamp("2000-02-29T12:34:56"), None);
+    }
+
+    #[test]
+    fn test_region_from_zone() {
+        assert_eq!(region_from_zone("projects/123/zones/us-central1-a"), "us-central1");
+        assert_eq!(region_from_zone("europe-west4-b"), "europe-west4");
+    }
+
+    #[test]
+    fn test_aws_uses_session_token() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        let (endpoint, server) = serve(vec![
+            ("200 OK", "token-1"),
+            ("200 OK", "i-0abc"),
+            ("200 OK", "eu-west-1"),
+        ]);
+        let mut state = use_service(Provider::Aws, &endpoint);
+        let id = state.instance_id().unwrap();
+        let region = state.region().unwrap();
+        *state = MetadataState::new();
+        drop(state);
+
+        let requests = server.join().unwrap();
+        assert_eq!((id.as_str(), region.as_str()), ("i-0abc", "eu-west-1"));
+        assert!(requests[0].starts_with("PUT /latest/api/token HTTP/1.1"), "{}", requests[0]);
+        assert!(requests[0].to_lowercase().contains("x-aws-ec2-metadata-token-ttl-seconds: 21600"), "{}", requests[0]);
+        assert!(requests[1].starts_with("GET /latest/meta-data/instance-id HTTP/1.1"), "{}", requests[1]);
+        assert!(requests[2].to_lowercase().contains("x-aws-ec2-metadata-token: token-1"), "{}", requests[2]);
+    }
+
+    #[test]
+    fn test_aws_credentials_are_cached() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        let doc = r#"{"Code":"Success","AccessKeyId":"ASIA1","SecretAccessKey":"secret","Token":"session","Expiration":"2099-01-01T00:00:00Z"}"#;
+        let (endpoint, server) = serve(vec![("200 OK", "token"), ("200 OK", "app-role\n"), ("200 OK", doc)]);
+        let mut state = use_service(Provider::Aws, &endpoint);
+        let first = state.credentials().unwrap();
+        let second = state.credentials().unwrap();
+        *state = MetadataState::new();
+        drop(state);
+
+        let requests = server.join().unwrap();
+        assert!(requests[2].starts_with("GET /latest/meta-data/iam/security-credentials/app-role HTTP/1.1"));
+        assert_eq!(first.fields[0], ("access_key_id", "ASIA1".to_string()));
+        assert_eq!(first.fields[2], ("session_token", "session".to_string()));
+        assert_eq!(first.expires_at, 4_070_908_800);
+        assert_eq!(second.fields, first.fields);
+    }
+
+    #[test]
+    fn test_gcp_and_azure_tokens() {
+        let _guard = TEST_LOCK.lock().unwrap();
+        let (endpoint, server) = serve(vec![
+            ("200 OK", "projects/42/zones/us-east4-c"),
+            ("200 OK", r#"{"access_token":"ya29","expires_in":3599,"token_type":"Bearer"}"#),
+        ]);
+        let mut state = use_service(Provider::Gcp, &endpoint);
+        let region = state.region().unwrap();
+        let creds = state.credentials().unwrap();
+        drop(state);
+        let requests = server.join().unwrap();
+        assert_eq!(region, "us-east4");
+        assert_eq!(creds.fields[0], ("access_token", "ya29".to_string()));
+        assert!(creds.expires_at > now_secs() + 3_500);
+        assert!(requests[1].starts_with("GET /computeMetadata/v1/instance/service-accounts/default/token HTTP/1.1"));
+        assert!(requests[1].to_lowercase().contains("metadata-flavor: google"), "{}", requests[1]);
+
+        let (endpoint, server) = serve(vec![
+            ("200 OK", r#"{"access_token":"eyJ0","expires_on":"4070908800","token_type":"Bearer"}"#),
+            ("404 Not Found", "not found"),
+        ]);
+        let mut state = use_service(Provider::Azure, &endpoint);
+        let creds = state.credentials().unwrap();
+        let err = state.get("instance/missing").unwrap_err();
+        *state = MetadataState::new();
+        drop(state);
+        let requests = server.join().unwrap();
+        assert_eq!(creds.expires_at, 4_070_908_800);
+        assert!(requests[0].starts_with(&format!("GET /metadata/{} HTTP/1.1", AZURE_TOKEN_PATH)), "{}", requests[0]);
+        assert!(requests[0].to_lowercase().contains("metadata: true"), "{}", requests[0]);
+        assert_eq!(err.code, 404);
+    }
+}
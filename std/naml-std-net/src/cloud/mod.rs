@@ -0,0 +1,9 @@
+//!
+//! Cloud Platform Services
+//!
+//! - `metadata` - Instance metadata for EC2, GCE and Azure VMs
+//!
+
+pub mod metadata;
+
+pub use metadata::*;
@@ -149,7 +149,7 @@ pub(crate) unsafe fn string_from_naml(s: *const NamlString) -> String {
         return String::new();
     }
     unsafe {
-        let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
         String::from_utf8_lossy(slice).into_owned()
     }
 }
@@ -159,8 +159,13 @@ pub(crate) unsafe fn string_from_naml(s: *const NamlString) -> String {
 /// Sets the exception and returns null to indicate an exception was thrown.
 pub(crate) fn throw_network_error(error: std::io::Error) -> *mut u8 {
     let code = error.raw_os_error().unwrap_or(-1) as i64;
-    let message = error.to_string();
+    throw_network_error_code(&error.to_string(), code)
+}
 
+/// Throw a NetworkError with an explicit code (e.g. an HTTP status)
+///
+/// Sets the exception and returns null to indicate an exception was thrown.
+pub(crate) fn throw_network_error_code(message: &str, code: i64) -> *mut u8 {
     unsafe {
         let message_ptr = naml_string_new(message.as_ptr(), message.len());
         let net_error = naml_network_error_new(message_ptr, code);
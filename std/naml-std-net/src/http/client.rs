@@ -12,6 +12,10 @@
 //! - `naml_net_http_client_delete` - HTTP DELETE request with optional headers
 //! - `naml_net_http_client_set_timeout` - Set default timeout
 //!
+//! Requests honor the proxy configuration from the `proxy` module
+//! (environment variables by default) and the retry settings from the
+//! `retry` module.
+//!
 //! All HTTP methods accept an optional headers parameter (`option<map<string, string>>`).
 //! Pass `none` to use default headers, or `some(headers_map)` to set custom headers.
 //!
@@ -25,6 +29,7 @@ use std::time::Duration;
 
 use http_body_util::{BodyExt, Full};
 use hyper::Request;
+use hyper::header::PROXY_AUTHORIZATION;
 use hyper::body::Bytes;
 use hyper_util::client::legacy::Client;
 use hyper_util::rt::TokioExecutor;
@@ -32,6 +37,8 @@ use tokio::runtime::Runtime;
 
 use naml_std_core::{NamlBytes, NamlMap, NamlString, NamlStruct};
 
+use super::proxy;
+use super::retry::{self, RetryPolicy};
 use super::types::{
     naml_net_http_response_new, naml_net_http_response_set_body, naml_net_http_response_set_status,
     vec_to_array,
@@ -44,7 +51,7 @@ unsafe fn bytes_to_vec(bytes: *const NamlBytes) -> Vec<u8> {
     }
     unsafe {
         let len = (*bytes).len;
-        let data = (*bytes).data.as_ptr();
+        let data = (*bytes).as_ptr();
         std::slice::from_raw_parts(data, len).to_vec()
     }
 }
@@ -150,15 +157,71 @@ fn do_request(
     body: Option<Vec<u8>>,
     custom_headers: Vec<(String, String)>,
 ) -> *mut NamlStruct {
+    match fetch(method, url, body, custom_headers) {
+        Ok((status, body_bytes)) => unsafe {
+            let response = naml_net_http_response_new();
+            naml_net_http_response_set_status(response, status);
+            let body_arr = vec_to_array(&body_bytes);
+            naml_net_http_response_set_body(response, body_arr);
+            response
+        },
+        Err(e) => {
+            throw_request_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Throw the exception for a failed `fetch`: TimeoutError when the
+/// client timeout expired, NetworkError otherwise
+pub(crate) fn throw_request_error(e: std::io::Error) {
+    if e.kind() == std::io::ErrorKind::TimedOut {
+        let timeout_ms = DEFAULT_TIMEOUT_MS.load(Ordering::SeqCst);
+        throw_timeout_error(&e.to_string(), timeout_ms as i64);
+    } else {
+        throw_network_error(e);
+    }
+}
+
+/// Perform an HTTP request, blocking until the response body is read.
+/// Uses the client's default timeout, proxy and retry settings.
+/// Returns the status code and body.
+pub fn fetch(
+    method: &str,
+    url: &str,
+    body: Option<Vec<u8>>,
+    custom_headers: Vec<(String, String)>,
+) -> std::io::Result<(i64, Vec<u8>)> {
     let timeout_ms = DEFAULT_TIMEOUT_MS.load(Ordering::SeqCst);
-    let timeout = Duration::from_millis(timeout_ms);
+    fetch_with(method, url, body, custom_headers, Duration::from_millis(timeout_ms), true)
+}
 
+/// Like `fetch`, but with its own timeout and never through a proxy.
+/// Meant for link-local services such as cloud instance metadata.
+pub fn fetch_direct(
+    method: &str,
+    url: &str,
+    body: Option<Vec<u8>>,
+    custom_headers: Vec<(String, String)>,
+    timeout: Duration,
+) -> std::io::Result<(i64, Vec<u8>)> {
+    fetch_with(method, url, body, custom_headers, timeout, false)
+}
+
+fn fetch_with(
+    method: &str,
+    url: &str,
+    body: Option<Vec<u8>>,
+    mut custom_headers: Vec<(String, String)>,
+    timeout: Duration,
+    use_proxy: bool,
+) -> std::io::Result<(i64, Vec<u8>)> {
     let runtime = get_runtime();
 
     let method_clone = method.to_string();
     let url_clone = url.to_string();
 
-    let result: Result<(i64, Vec<u8>), std::io::Error> = runtime.block_on(async move {
+    runtime.block_on(async move {
         // Parse URL
         let uri: hyper::Uri = url_clone
             .parse()
@@ -178,42 +241,35 @@ fn do_request(
             .unwrap()
             .with_root_certificates(root_store)
             .with_no_client_auth();
-        let connector = hyper_rustls::HttpsConnectorBuilder::new()
-            .with_tls_config(tls_config)
-            .https_or_http()
-            .enable_http1()
-            .build();
-        let client = Client::builder(TokioExecutor::new()).build(connector);
-
-        // Build request with default headers
-        let body_bytes = body.unwrap_or_default();
-        let mut req_builder = Request::builder()
-            .method(method_clone.as_str())
-            .uri(uri)
-            .header("User-Agent", "naml-http-client/0.1")
-            .header("Accept", "*/*");
-
-        // Add custom headers (they can override defaults)
-        for (name, value) in custom_headers {
-            req_builder = req_builder.header(name, value);
-        }
+        let route = if use_proxy { proxy::route_for(&uri)? } else { proxy::Route::Direct };
+        let proxy_auth = route.forward_auth();
+        let client = Client::builder(TokioExecutor::new()).build(proxy::connector(tls_config, route));
+        let policy = RetryPolicy::for_request(&method_clone, &mut custom_headers)?;
+
+        // Build request with default headers; rebuilt for every attempt
+        let body_bytes = Bytes::from(body.unwrap_or_default());
+        let build = || {
+            let mut req_builder = Request::builder()
+                .method(method_clone.as_str())
+                .uri(uri.clone())
+                .header("User-Agent", "naml-http-client/0.1")
+                .header("Accept", "*/*");
+            if let Some(auth) = &proxy_auth {
+                req_builder = req_builder.header(PROXY_AUTHORIZATION, auth.clone());
+            }
 
-        let req = req_builder
-            .body(Full::new(Bytes::from(body_bytes)))
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+            // Add custom headers (they can override defaults)
+            for (name, value) in &custom_headers {
+                req_builder = req_builder.header(name, value);
+            }
 
-        // Send request with timeout
-        let response = tokio::time::timeout(timeout, client.request(req))
-            .await
-            .map_err(|_| {
-                std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    format!("Request timed out after {}ms", timeout_ms),
-                )
-            })?
-            .map_err(|e: hyper_util::client::legacy::Error| {
-                std::io::Error::new(std::io::ErrorKind::Other, e.to_string())
-            })?;
+            req_builder
+                .body(Full::new(body_bytes.clone()))
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))
+        };
+
+        // Send request with timeout and retries
+        let response = retry::send(&client, policy, timeout, build).await?;
 
         // Extract status
         let status = response.status().as_u16() as i64;
@@ -230,25 +286,7 @@ fn do_request(
             .to_vec();
 
         Ok((status, body_bytes))
-    });
-
-    match result {
-        Ok((status, body_bytes)) => unsafe {
-            let response = naml_net_http_response_new();
-            naml_net_http_response_set_status(response, status);
-            let body_arr = vec_to_array(&body_bytes);
-            naml_net_http_response_set_body(response, body_arr);
-            response
-        },
-        Err(e) => {
-            if e.kind() == std::io::ErrorKind::TimedOut {
-                throw_timeout_error(&e.to_string(), timeout_ms as i64);
-            } else {
-                throw_network_error(e);
-            }
-            std::ptr::null_mut()
-        }
-    }
+    })
 }
 
 /// HTTP GET request with optional headers
@@ -349,29 +387,32 @@ pub unsafe extern "C" fn naml_net_http_client_get_tls(
             .unwrap()
             .with_root_certificates(root_store)
             .with_no_client_auth();
-        let connector = hyper_rustls::HttpsConnectorBuilder::new()
-            .with_tls_config(tls_config)
-            .https_or_http()
-            .enable_http1()
-            .build();
-        let client: Client<_, Full<Bytes>> =
-            Client::builder(TokioExecutor::new()).build(connector);
-
         let uri: hyper::Uri = url_str
             .parse()
             .map_err(|e: hyper::http::uri::InvalidUri| format!("Invalid URL: {}", e))?;
 
-        let req = Request::builder()
-            .method("GET")
-            .uri(uri)
-            .header("User-Agent", "naml-http-client/0.1")
-            .header("Accept", "*/*")
-            .body(Full::new(Bytes::new()))
-            .map_err(|e| e.to_string())?;
+        let route = proxy::route_for(&uri).map_err(|e| e.to_string())?;
+        let proxy_auth = route.forward_auth();
+        let client: Client<_, Full<Bytes>> =
+            Client::builder(TokioExecutor::new()).build(proxy::connector(tls_config, route));
+
+        let policy = RetryPolicy::for_request("GET", &mut Vec::new()).map_err(|e| e.to_string())?;
+        let build = || {
+            let mut req_builder = Request::builder()
+                .method("GET")
+                .uri(uri.clone())
+                .header("User-Agent", "naml-http-client/0.1")
+                .header("Accept", "*/*");
+            if let Some(auth) = &proxy_auth {
+                req_builder = req_builder.header(PROXY_AUTHORIZATION, auth.clone());
+            }
+            req_builder
+                .body(Full::new(Bytes::new()))
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))
+        };
 
-        let response = tokio::time::timeout(timeout, client.request(req))
+        let response = retry::send(&client, policy, timeout, build)
             .await
-            .map_err(|_| format!("Request timed out after {}ms", timeout_ms))?
             .map_err(|e| e.to_string())?;
 
         let status = response.status().as_u16() as i64;
@@ -404,6 +445,7 @@ pub unsafe extern "C" fn naml_net_http_client_get_tls(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::types::array_to_vec;
     use naml_std_core::naml_string_new;
 
     #[test]
@@ -429,4 +471,171 @@ mod tests {
             assert!(result.is_null(), "Should fail with invalid URL");
         }
     }
+
+    /// Read one HTTP/1.1 request head from a mock proxy connection
+    fn read_head(stream: &mut std::net::TcpStream) -> String {
+        use std::io::Read;
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            if stream.read(&mut byte).unwrap() == 0 {
+                break;
+            }
+            head.push(byte[0]);
+        }
+        String::from_utf8(head).unwrap()
+    }
+
+    /// GET `url` through the configured proxy and return (status, body)
+    fn get_through_proxy(url: &str) -> (i64, String) {
+        unsafe {
+            let url = naml_string_new(url.as_ptr(), url.len());
+            let none_opt = NamlOption { tag: 0, _padding: 0, value: 0 };
+            let response = naml_net_http_client_get(url, &none_opt as *const NamlOption as *const u8);
+            assert!(!response.is_null(), "request through proxy failed");
+            let status = *(*response).fields.as_ptr();
+            let body = *(*response).fields.as_ptr().add(2) as *const naml_std_core::NamlArray;
+            (status, String::from_utf8(array_to_vec(body)).unwrap())
+        }
+    }
+
+    const OK_RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nproxied";
+
+    #[test]
+    fn test_get_through_http_proxy() {
+        use std::io::Write;
+        let _guard = proxy::tests::PROXY_TEST_LOCK.lock().unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let head = read_head(&mut stream);
+            stream.write_all(OK_RESPONSE).unwrap();
+            head
+        });
+
+        assert_eq!(proxy::tests::set_proxy(&format!("http://naml:secret@{}", addr)), 0);
+        let (status, body) = get_through_proxy("http://example.invalid/path");
+        proxy::naml_net_http_client_reset_proxy();
+
+        let head = server.join().unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, "proxied");
+        assert!(head.starts_with("GET http://example.invalid/path HTTP/1.1\r\n"), "{}", head);
+        assert!(head.to_lowercase().contains("proxy-authorization: basic bmftbdpzzwnyzxq="), "{}", head);
+    }
+
+    #[test]
+    fn test_get_through_socks5_proxy() {
+        use std::io::{Read, Write};
+        let _guard = proxy::tests::PROXY_TEST_LOCK.lock().unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Greeting: version, method count, methods; reply "no auth"
+            let mut greeting = [0u8; 2];
+            stream.read_exact(&mut greeting).unwrap();
+            let mut methods = vec![0u8; greeting[1] as usize];
+            stream.read_exact(&mut methods).unwrap();
+            stream.write_all(&[0x05, 0x00]).unwrap();
+
+            // Connect request with a domain name target (socks5h)
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(request[3], 0x03, "expected a domain name address");
+            let mut host = vec![0u8; request[4] as usize];
+            stream.read_exact(&mut host).unwrap();
+            let mut port = [0u8; 2];
+            stream.read_exact(&mut port).unwrap();
+            stream.write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0, 80]).unwrap();
+
+            let head = read_head(&mut stream);
+            stream.write_all(OK_RESPONSE).unwrap();
+            (String::from_utf8(host).unwrap(), u16::from_be_bytes(port), head)
+        });
+
+        assert_eq!(proxy::tests::set_proxy(&format!("socks5h://{}", addr)), 0);
+        let (status, body) = get_through_proxy("http://example.invalid:8080/");
+        proxy::naml_net_http_client_reset_proxy();
+
+        let (host, port, head) = server.join().unwrap();
+        assert_eq!((status, body.as_str()), (200, "proxied"));
+        assert_eq!((host.as_str(), port), ("example.invalid", 8080));
+        assert!(head.starts_with("GET / HTTP/1.1\r\n"), "{}", head);
+    }
+
+    const UNAVAILABLE_RESPONSE: &[u8] =
+        b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    /// Serve one canned response per connection; returns the address and
+    /// the request heads received
+    fn serve_sequence(
+        responses: Vec<&'static [u8]>,
+    ) -> (std::net::SocketAddr, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::Write;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|response| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let head = read_head(&mut stream);
+                    stream.write_all(response).unwrap();
+                    head
+                })
+                .collect()
+        });
+        (addr, server)
+    }
+
+    /// Status of a direct request, or None if it failed
+    fn request_status(method: &str, url: &str, headers: &[(&str, &str)]) -> Option<i64> {
+        let headers = headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let body = if method == "GET" { None } else { Some(b"data".to_vec()) };
+        let response = do_request(method, url, body, headers);
+        (!response.is_null()).then(|| unsafe { *(*response).fields.as_ptr() })
+    }
+
+    #[test]
+    fn test_retries_idempotent_request() {
+        let _guard = proxy::tests::PROXY_TEST_LOCK.lock().unwrap();
+        assert_eq!(proxy::tests::set_proxy(""), 0);
+
+        let (addr, server) = serve_sequence(vec![UNAVAILABLE_RESPONSE, UNAVAILABLE_RESPONSE, OK_RESPONSE]);
+        let status = request_status("GET", &format!("http://{}/", addr), &[(retry::RETRIES_HEADER, "2")]);
+        let heads = server.join().unwrap();
+
+        assert_eq!(status, Some(200));
+        assert_eq!(heads.len(), 3);
+        assert!(!heads[0].to_lowercase().contains("x-naml-retries"), "{}", heads[0]);
+
+        let (addr, server) = serve_sequence(vec![UNAVAILABLE_RESPONSE, OK_RESPONSE]);
+        let status = request_status(
+            "POST",
+            &format!("http://{}/", addr),
+            &[(retry::RETRIES_HEADER, "1"), ("Idempotency-Key", "order-1")],
+        );
+        server.join().unwrap();
+        assert_eq!(status, Some(200));
+
+        proxy::naml_net_http_client_reset_proxy();
+    }
+
+    #[test]
+    fn test_no_retry_for_non_idempotent_request() {
+        let _guard = proxy::tests::PROXY_TEST_LOCK.lock().unwrap();
+        assert_eq!(proxy::tests::set_proxy(""), 0);
+
+        // The server goes away after one response, so a retry would fail
+        let (addr, server) = serve_sequence(vec![UNAVAILABLE_RESPONSE]);
+        let status = request_status("POST", &format!("http://{}/", addr), &[(retry::RETRIES_HEADER, "2")]);
+        server.join().unwrap();
+        assert_eq!(status, Some(503));
+
+        proxy::naml_net_http_client_reset_proxy();
+    }
 }
@@ -0,0 +1,145 @@
+//!
+//! HTTP Request Context
+//!
+//! Request-scoped string values that middleware hand to later middleware
+//! and handlers, such as an authenticated user or a request id. They live
+//! in the request's context map, which is created on the first
+//! `set_value`, and are released when the handler returns.
+//!
+//! The `request_id` middleware stores each request's id here under
+//! "request_id".
+//!
+//! ## Functions
+//!
+//! - `naml_net_http_server_set_value` - Store a request-scoped value
+//! - `naml_net_http_server_get_value` - Read a request-scoped value
+//!
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use naml_std_core::{NamlMap, NamlString, NamlStruct};
+
+use super::types::request_fields::CONTEXT;
+
+/// Store a request-scoped string value under `key`, replacing any previous
+/// value. The request holds its own reference until the handler returns.
+///
+/// # Safety
+/// The caller must ensure `req` is a valid pointer to a NamlStruct or null,
+/// `key` is a valid pointer to a NamlString or null and `value` is a valid
+/// pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_http_server_set_value(
+    req: *mut NamlStruct,
+    key: *const NamlString,
+    value: *const NamlString,
+) {
+    if req.is_null() || key.is_null() {
+        return;
+    }
+    unsafe {
+        let mut context = naml_std_core::naml_struct_get_field(req, CONTEXT) as *mut NamlMap;
+        if context.is_null() {
+            context = naml_std_core::naml_map_new(8);
+            naml_std_core::naml_struct_set_field(req, CONTEXT, context as i64);
+        }
+        naml_std_core::naml_string_incref(value as *mut NamlString);
+        naml_std_core::naml_map_set_string(context, key as i64, value as i64);
+    }
+}
+
+/// Look up a request-scoped value. Returns a new reference, or null when the
+/// key was never set.
+///
+/// # Safety
+/// The caller must ensure `req` is a valid pointer to a NamlStruct or null and
+/// `key` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_http_server_get_value(
+    req: *const NamlStruct,
+    key: *const NamlString,
+) -> *mut NamlString {
+    if req.is_null() || key.is_null() {
+        return std::ptr::null_mut();
+    }
+    unsafe {
+        let context = naml_std_core::naml_struct_get_field(req, CONTEXT) as *const NamlMap;
+        if context.is_null() {
+            return std::ptr::null_mut();
+        }
+        let value = naml_std_core::naml_map_get(context, key as i64) as *mut NamlString;
+        naml_std_core::naml_string_incref(value);
+        value
+    }
+}
+
+/// Drop every request-scoped value once the handler is done with the request
+///
+/// # Safety
+/// The caller must ensure `req` is a valid pointer to a request NamlStruct.
+pub(crate) unsafe fn release_request_context(req: *mut NamlStruct) {
+    unsafe {
+        let context = naml_std_core::naml_struct_get_field(req, CONTEXT) as *mut NamlMap;
+        if !context.is_null() {
+            naml_std_core::naml_map_decref_strings(context);
+            naml_std_core::naml_struct_set_field(req, CONTEXT, 0);
+        }
+    }
+}
+
+/// Generate a request ID: process id plus a per-process sequence number
+pub(crate) fn next_request_id() -> String {
+    static NEXT_REQUEST_SEQ: AtomicU64 = AtomicU64::new(1);
+    let seq = NEXT_REQUEST_SEQ.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:08x}", std::process::id(), seq)
+}
+
+/// Store the request's id as its "request_id" value
+///
+/// # Safety
+/// The caller must ensure `req` is a valid pointer to a request NamlStruct.
+pub(crate) unsafe fn set_request_id(req: *mut NamlStruct, id: &str) {
+    unsafe {
+        let key = naml_std_core::naml_string_new(b"request_id".as_ptr(), 10);
+        let value = naml_std_core::naml_string_new(id.as_ptr(), id.len());
+        naml_net_http_server_set_value(req, key, value);
+        naml_std_core::naml_string_decref(key);
+        naml_std_core::naml_string_decref(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::types::naml_net_http_request_new;
+    use crate::errors::string_from_naml;
+
+    #[test]
+    fn test_request_context_values() {
+        unsafe {
+            let req = naml_net_http_request_new();
+            let key = naml_std_core::naml_string_new(b"user".as_ptr(), 4);
+            assert!(naml_net_http_server_get_value(req, key).is_null());
+
+            let alice = naml_std_core::naml_string_new(b"alice".as_ptr(), 5);
+            let bob = naml_std_core::naml_string_new(b"bob".as_ptr(), 3);
+            naml_net_http_server_set_value(req, key, alice);
+            naml_net_http_server_set_value(req, key, bob);
+            assert_eq!((*alice).header.refcount(), 1);
+            assert_eq!((*bob).header.refcount(), 2);
+
+            let value = naml_net_http_server_get_value(req, key);
+            assert_eq!(string_from_naml(value), "bob");
+            naml_std_core::naml_string_decref(value);
+
+            release_request_context(req);
+            assert_eq!((*bob).header.refcount(), 1);
+            assert!(naml_net_http_server_get_value(req, key).is_null());
+        }
+    }
+
+    #[test]
+    fn test_request_ids_are_unique() {
+        assert_ne!(next_request_id(), next_request_id());
+    }
+}
@@ -0,0 +1,256 @@
+//!
+//! HTTP Health Check Endpoints
+//!
+//! Builds a router that serves Kubernetes-style probe endpoints from a map
+//! of named check functions. Mount it into an application router with
+//! `mount(router, "", health_router(checks))`.
+//!
+//! ## Endpoints
+//!
+//! - `GET /healthz` - Liveness: always 200 while the process can serve
+//!   requests; runs no checks, so a failing dependency does not get the
+//!   process restarted
+//! - `GET /readyz` - Readiness: runs every check, 200 if all pass, 503 otherwise
+//!
+//! ## Response Body
+//!
+//! `/healthz` responds with `{"status":"ok"}`. `/readyz` reports each check:
+//!
+//! ```json
+//! {
+//!   "status": "ok",
+//!   "checks": {
+//!     "db": { "status": "ok", "latency_ms": 0.42 },
+//!     "cache": { "status": "fail", "latency_ms": 12.7 }
+//!   }
+//! }
+//! ```
+//!
+//! ## Functions
+//!
+//! - `naml_net_http_server_health_router` - Create a router with probe routes
+//!
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use naml_std_core::{NamlMap, NamlString};
+
+use super::server::{open_native_router, NativeHandler, NativeResponse};
+use crate::errors::string_from_naml;
+
+/// Compiled check function: fn() -> bool, called with its closure data
+type CheckFn = unsafe extern "C" fn(data_ptr: i64) -> i64;
+
+/// A single named health check captured from a naml closure
+struct HealthCheck {
+    name: String,
+    func: CheckFn,
+    data: i64,
+}
+
+/// Result of running one check
+struct CheckResult {
+    name: String,
+    passed: bool,
+    latency_ms: f64,
+}
+
+/// Run all checks in name order and record pass/fail and latency
+fn run_checks(checks: &[HealthCheck]) -> Vec<CheckResult> {
+    checks
+        .iter()
+        .map(|check| {
+            let start = Instant::now();
+            let passed = unsafe { (check.func)(check.data) } != 0;
+            CheckResult {
+                name: check.name.clone(),
+                passed,
+                latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+            }
+        })
+        .collect()
+}
+
+/// Render check results as a probe response (200 when all pass, 503 otherwise)
+fn render_report(results: &[CheckResult]) -> NativeResponse {
+    let healthy = results.iter().all(|r| r.passed);
+
+    let mut checks = serde_json::Map::new();
+    for result in results {
+        checks.insert(
+            result.name.clone(),
+            serde_json::json!({
+                "status": if result.passed { "ok" } else { "fail" },
+                "latency_ms": result.latency_ms,
+            }),
+        );
+    }
+
+    let body = serde_json::json!({
+        "status": if healthy { "ok" } else { "fail" },
+        "checks": checks,
+    });
+
+    NativeResponse {
+        status: if healthy { 200 } else { 503 },
+        content_type: "application/json",
+        body: body.to_string().into_bytes(),
+    }
+}
+
+/// Liveness response: the process is up, whatever its dependencies say
+fn render_alive() -> NativeResponse {
+    NativeResponse {
+        status: 200,
+        content_type: "application/json",
+        body: br#"{"status":"ok"}"#.to_vec(),
+    }
+}
+
+/// Read `map<string, fn() -> bool>` into check descriptors sorted by name
+unsafe fn checks_from_map(map: *const NamlMap) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+    if map.is_null() {
+        return checks;
+    }
+
+    unsafe {
+        for i in 0..(*map).capacity {
+            let entry = &*(*map).entries.add(i);
+            if !entry.occupied || entry.value == 0 {
+                continue;
+            }
+            let closure = entry.value as *const i64;
+            let func_ptr = *closure;
+            if func_ptr == 0 {
+                continue;
+            }
+            checks.push(HealthCheck {
+                name: string_from_naml(entry.key as *const NamlString),
+                func: std::mem::transmute::<usize, CheckFn>(func_ptr as usize),
+                data: *closure.add(1),
+            });
+        }
+    }
+
+    checks.sort_by(|a, b| a.name.cmp(&b.name));
+    checks
+}
+
+/// Create a router serving `/healthz` (liveness) and `/readyz` (readiness,
+/// backed by the given checks). Closure pointers are copied out of the map
+/// immediately, so the map may be released after this call.
+///
+/// # Safety
+/// The caller must ensure `checks` is a valid pointer to a NamlMap.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_http_server_health_router(checks: *const NamlMap) -> i64 {
+    let checks = Arc::new(unsafe { checks_from_map(checks) });
+
+    let liveness: NativeHandler = Arc::new(render_alive);
+    let readiness: NativeHandler = Arc::new(move || render_report(&run_checks(&checks)));
+
+    open_native_router(vec![
+        ("GET", "/healthz", liveness),
+        ("GET", "/readyz", readiness),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn passing(_data: i64) -> i64 {
+        1
+    }
+
+    extern "C" fn failing(_data: i64) -> i64 {
+        0
+    }
+
+    extern "C" fn data_flag(data: i64) -> i64 {
+        data
+    }
+
+    fn check(name: &str, func: CheckFn, data: i64) -> HealthCheck {
+        HealthCheck {
+            name: name.to_string(),
+            func,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_all_checks_pass() {
+        let checks = vec![check("cache", passing, 0), check("db", passing, 0)];
+        let response = render_report(&run_checks(&checks));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, "application/json");
+
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["checks"]["db"]["status"], "ok");
+        assert!(body["checks"]["cache"]["latency_ms"].is_number());
+    }
+
+    #[test]
+    fn test_failing_check_returns_503() {
+        let checks = vec![check("db", passing, 0), check("queue", failing, 0)];
+        let response = render_report(&run_checks(&checks));
+        assert_eq!(response.status, 503);
+
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["checks"]["db"]["status"], "ok");
+        assert_eq!(body["checks"]["queue"]["status"], "fail");
+    }
+
+    #[test]
+    fn test_closure_data_is_passed() {
+        let results = run_checks(&[check("on", data_flag, 1), check("off", data_flag, 0)]);
+        assert!(results[0].passed);
+        assert!(!results[1].passed);
+    }
+
+    #[test]
+    fn test_liveness_runs_no_checks() {
+        let response = render_alive();
+        assert_eq!(response.status, 200);
+        let body: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(body, serde_json::json!({ "status": "ok" }));
+    }
+
+    #[test]
+    fn test_no_checks_is_healthy() {
+        let response = render_report(&run_checks(&[]));
+        assert_eq!(response.status, 200);
+    }
+
+    #[test]
+    fn test_checks_from_map_sorted() {
+        unsafe {
+            let map = naml_std_core::naml_map_new(4);
+            let closures: Vec<Box<[i64; 3]>> = vec![
+                Box::new([passing as *const () as i64, 0, 0]),
+                Box::new([failing as *const () as i64, 7, 0]),
+            ];
+            let zeta = naml_std_core::naml_string_new(b"zeta".as_ptr(), 4);
+            let alpha = naml_std_core::naml_string_new(b"alpha".as_ptr(), 5);
+            naml_std_core::naml_map_set(map, zeta as i64, closures[0].as_ptr() as i64);
+            naml_std_core::naml_map_set(map, alpha as i64, closures[1].as_ptr() as i64);
+
+            let checks = checks_from_map(map);
+            assert_eq!(checks.len(), 2);
+            assert_eq!(checks[0].name, "alpha");
+            assert_eq!(checks[0].data, 7);
+            assert_eq!(checks[1].name, "zeta");
+        }
+    }
+
+    #[test]
+    fn test_health_router_handle() {
+        let handle = unsafe { naml_net_http_server_health_router(std::ptr::null()) };
+        assert!(handle > 0);
+    }
+}
@@ -6,6 +6,11 @@
 //! ## Submodules
 //!
 //! - `types` - Core HTTP types (request, response)
+//! - `context` - Request-scoped values set by middleware
+//! - `health` - Liveness/readiness probe router
+//! - `pattern` - Route pattern matching with `{name}` params
+//! - `proxy` - HTTP client proxy configuration (HTTP CONNECT and SOCKS5)
+//! - `retry` - HTTP client retries for idempotent requests
 //!
 //! ## Types
 //!
@@ -39,11 +44,20 @@
 //!
 
 pub mod client;
+pub mod context;
+pub mod health;
 pub mod middleware;
+pub mod pattern;
+pub mod proxy;
+pub mod retry;
 pub mod server;
 pub mod types;
 
 pub use client::*;
+pub use context::*;
+pub use health::*;
 pub use middleware::*;
+pub use proxy::*;
+pub use retry::*;
 pub use server::*;
 pub use types::*;
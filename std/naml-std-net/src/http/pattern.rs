@@ -0,0 +1,126 @@
+//!
+//! HTTP Route Patterns
+//!
+//! Route patterns are paths whose segments may be `{name}` parameters,
+//! such as "/users/{id}/posts/{post_id}". A pattern matches a path with the
+//! same number of segments where every literal segment is equal.
+//!
+
+use std::collections::HashMap;
+
+/// Join a mount prefix and a route pattern without doubling or dropping slashes
+pub(crate) fn join_pattern(prefix: &str, pattern: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    if pattern.is_empty() || pattern == "/" {
+        if prefix.is_empty() { "/".to_string() } else { prefix.to_string() }
+    } else if pattern.starts_with('/') {
+        format!("{}{}", prefix, pattern)
+    } else {
+        format!("{}/{}", prefix, pattern)
+    }
+}
+
+/// Extract parameter names from a pattern like "/users/{id}/posts/{post_id}"
+pub(crate) fn extract_param_names(pattern: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut in_param = false;
+    let mut param_name = String::new();
+
+    for c in pattern.chars() {
+        if c == '{' {
+            in_param = true;
+            param_name.clear();
+        } else if c == '}' {
+            if in_param && !param_name.is_empty() {
+                names.push(param_name.clone());
+            }
+            in_param = false;
+        } else if in_param {
+            param_name.push(c);
+        }
+    }
+
+    names
+}
+
+/// Match a path against a pattern and extract the param values
+pub(crate) fn match_route(pattern: &str, path: &str, param_names: &[String]) -> Option<HashMap<String, String>> {
+    let pattern_parts: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path_parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if pattern_parts.len() != path_parts.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+    let mut param_idx = 0;
+
+    for (pattern_part, path_part) in pattern_parts.iter().zip(path_parts.iter()) {
+        if pattern_part.starts_with('{') && pattern_part.ends_with('}') {
+            if param_idx < param_names.len() {
+                params.insert(param_names[param_idx].clone(), path_part.to_string());
+                param_idx += 1;
+            }
+        } else if *pattern_part != *path_part {
+            return None;
+        }
+    }
+
+    Some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_param_names() {
+        let names = extract_param_names("/users/{id}");
+        assert_eq!(names, vec!["id"]);
+
+        let names = extract_param_names("/users/{user_id}/posts/{post_id}");
+        assert_eq!(names, vec!["user_id", "post_id"]);
+
+        let names = extract_param_names("/static/path");
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn test_match_route() {
+        let pattern = "/users/{id}";
+        let param_names = vec!["id".to_string()];
+
+        let result = match_route(pattern, "/users/123", &param_names);
+        assert!(result.is_some());
+        let params = result.unwrap();
+        assert_eq!(params.get("id"), Some(&"123".to_string()));
+
+        let result = match_route(pattern, "/users/123/extra", &param_names);
+        assert!(result.is_none());
+
+        let result = match_route(pattern, "/posts/123", &param_names);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_match_route_multiple_params() {
+        let pattern = "/users/{user_id}/posts/{post_id}";
+        let param_names = vec!["user_id".to_string(), "post_id".to_string()];
+
+        let result = match_route(pattern, "/users/42/posts/99", &param_names);
+        assert!(result.is_some());
+        let params = result.unwrap();
+        assert_eq!(params.get("user_id"), Some(&"42".to_string()));
+        assert_eq!(params.get("post_id"), Some(&"99".to_string()));
+    }
+
+    #[test]
+    fn test_join_pattern() {
+        assert_eq!(join_pattern("/api", "/users"), "/api/users");
+        assert_eq!(join_pattern("/api/", "/users"), "/api/users");
+        assert_eq!(join_pattern("/api", "users"), "/api/users");
+        assert_eq!(join_pattern("/api", "/"), "/api");
+        assert_eq!(join_pattern("", "/healthz"), "/healthz");
+        assert_eq!(join_pattern("", "/"), "/");
+    }
+}
@@ -0,0 +1,187 @@
+//!
+//! HTTP Client Retries
+//!
+//! Retries HTTP client requests that fail at the transport level or come
+//! back with a 5xx status, so naml programs don't need their own retry loops.
+//!
+//! ## Configuration
+//!
+//! - `naml_net_http_client_set_retries` - Set the default retry count and base backoff
+//!
+//! Retries are off by default. A single request can override the retry
+//! count with the `X-Naml-Retries` header; the client removes the header
+//! before sending the request.
+//!
+//! ## What is retried
+//!
+//! Only idempotent requests are retried: GET, HEAD, OPTIONS, TRACE, PUT and
+//! DELETE, plus POST and PATCH requests that carry an `Idempotency-Key` header.
+//! A request is retried when the connection fails or the server answers
+//! with a 5xx status. Timeouts are not retried, since each attempt already
+//! waited for the full timeout.
+//!
+//! ## Backoff
+//!
+//! The wait before retry `n` (starting at 0) is `backoff_ms * 2^n`, capped at
+//! 30 seconds. A `Retry-After` header given in seconds extends the wait.
+//!
+
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::RETRY_AFTER;
+use hyper::{HeaderMap, Request, Response};
+use hyper_util::client::legacy::Client;
+use hyper_util::client::legacy::connect::Connect;
+
+/// Header that overrides the retry count for one request
+pub const RETRIES_HEADER: &str = "X-Naml-Retries";
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Default number of retries after the first attempt
+static DEFAULT_RETRIES: AtomicU64 = AtomicU64::new(0);
+
+/// Default base backoff in milliseconds
+static DEFAULT_BACKOFF_MS: AtomicU64 = AtomicU64::new(100);
+
+/// Retry settings resolved for one request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff_ms: u64,
+}
+
+impl RetryPolicy {
+    /// Resolve the policy for a request, removing the override header from
+    /// `headers`. Requests that are not idempotent get no retries.
+    pub(crate) fn for_request(method: &str, headers: &mut Vec<(String, String)>) -> io::Result<Self> {
+        let mut max_retries = DEFAULT_RETRIES.load(Ordering::SeqCst).min(u32::MAX as u64) as u32;
+        if let Some(pos) = headers.iter().position(|(name, _)| name.eq_ignore_ascii_case(RETRIES_HEADER)) {
+            let (_, value) = headers.remove(pos);
+            max_retries = value.trim().parse().map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Invalid {} header: {}", RETRIES_HEADER, value),
+                )
+            })?;
+        }
+        if !is_idempotent(method, headers) {
+            max_retries = 0;
+        }
+        Ok(Self {
+            max_retries,
+            backoff_ms: DEFAULT_BACKOFF_MS.load(Ordering::SeqCst),
+        })
+    }
+
+    /// Wait before retry `attempt` (0-based)
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+        let backoff = Duration::from_millis(self.backoff_ms.saturating_mul(factor));
+        backoff.max(retry_after.unwrap_or_default()).min(MAX_BACKOFF)
+    }
+}
+
+fn is_idempotent(method: &str, headers: &[(String, String)]) -> bool {
+    match method.to_ascii_uppercase().as_str() {
+        "GET" | "HEAD" | "PUT" | "DELETE" | "OPTIONS" | "TRACE" => true,
+        _ => headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(IDEMPOTENCY_KEY_HEADER)),
+    }
+}
+
+/// `Retry-After` in delay-seconds form; HTTP dates are ignored
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs: u64 = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+/// Send a request built by `build`, retrying according to `policy`.
+/// Each attempt gets the full `timeout`.
+pub(crate) async fn send<C>(
+    client: &Client<C, Full<Bytes>>,
+    policy: RetryPolicy,
+    timeout: Duration,
+    build: impl Fn() -> io::Result<Request<Full<Bytes>>>,
+) -> io::Result<Response<Incoming>>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    let mut attempt = 0;
+    loop {
+        let result = tokio::time::timeout(timeout, client.request(build()?))
+            .await
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("Request timed out after {}ms", timeout.as_millis()),
+                )
+            })?;
+
+        let wait_hint = match result {
+            Ok(response) if attempt < policy.max_retries && response.status().is_server_error() => {
+                retry_after(response.headers())
+            }
+            Ok(response) => return Ok(response),
+            Err(e) if attempt < policy.max_retries && e.is_connect() => None,
+            Err(e) => return Err(io::Error::other(e.to_string())),
+        };
+
+        tokio::time::sleep(policy.delay(attempt, wait_hint)).await;
+        attempt += 1;
+    }
+}
+
+/// Set the default retry count and base backoff for HTTP client requests
+///
+/// # Arguments
+/// * `count` - Retries after the first attempt (0 or less disables retries)
+/// * `backoff_ms` - Wait before the first retry; doubles for each further retry
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_net_http_client_set_retries(count: i64, backoff_ms: i64) {
+    DEFAULT_RETRIES.store(count.max(0) as u64, Ordering::SeqCst);
+    DEFAULT_BACKOFF_MS.store(backoff_ms.max(0) as u64, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_policy_for_request() {
+        let mut plain = headers(&[("x-naml-retries", "3"), ("Accept", "text/plain")]);
+        let policy = RetryPolicy::for_request("GET", &mut plain).unwrap();
+        assert_eq!(policy.max_retries, 3);
+        assert_eq!(plain, headers(&[("Accept", "text/plain")]));
+
+        let mut post = headers(&[(RETRIES_HEADER, "3")]);
+        assert_eq!(RetryPolicy::for_request("POST", &mut post).unwrap().max_retries, 0);
+
+        let mut keyed = headers(&[(RETRIES_HEADER, "3"), (IDEMPOTENCY_KEY_HEADER, "abc")]);
+        assert_eq!(RetryPolicy::for_request("POST", &mut keyed).unwrap().max_retries, 3);
+
+        let mut invalid = headers(&[(RETRIES_HEADER, "many")]);
+        assert!(RetryPolicy::for_request("GET", &mut invalid).is_err());
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let policy = RetryPolicy { max_retries: 5, backoff_ms: 100 };
+        assert_eq!(policy.delay(0, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(800));
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(2))), Duration::from_secs(2));
+        assert_eq!(policy.delay(40, None), MAX_BACKOFF);
+        assert_eq!(policy.delay(0, Some(Duration::from_secs(3600))), MAX_BACKOFF);
+    }
+}
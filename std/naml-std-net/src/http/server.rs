@@ -12,13 +12,16 @@
 //! - `naml_net_http_server_patch` - Register PATCH handler
 //! - `naml_net_http_server_delete` - Register DELETE handler
 //! - `naml_net_http_server_with` - Add middleware to router
-//! - `naml_net_http_server_group` - Create route group
-//! - `naml_net_http_server_mount` - Mount sub-router
+//! - `naml_net_http_server_group` - Build a prefixed route group via a setup callback
+//! - `naml_net_http_server_mount` - Mount sub-router (keeps its middleware scoped)
 //! - `naml_net_http_server_serve` - Start HTTP server
+//! - `naml_net_http_server_health_router` - Probe router (see `health` module)
+//! - `naml_net_http_server_set_value` / `get_value` - Request-scoped values (see `context` module)
 //!
 //! ## Note
 //!
-//! Handlers are naml function pointers: fn(request) -> response
+//! Handlers are naml closures: fn(request) -> response, stored as function
+//! pointer plus closure data
 //! Middleware are naml function pointers: fn(handler) -> handler
 //!
 
@@ -38,29 +41,51 @@ use tokio::runtime::Runtime;
 
 use naml_std_core::{HeapTag, NamlArray, NamlBytes, NamlString, NamlStruct};
 
+use super::context::{next_request_id, release_request_context, set_request_id};
+use super::pattern::{extract_param_names, join_pattern, match_route};
 use super::types::{
     array_to_vec, create_bytes_from, naml_net_http_response_create, naml_net_http_response_get_body,
     naml_net_http_response_get_status, vec_to_array,
 };
 use crate::errors::{string_from_naml, throw_network_error};
 
-/// Handler function type (naml function pointer)
-type HandlerFn = extern "C" fn(*mut NamlStruct) -> *mut NamlStruct;
+/// Handler function type: compiled fn(request) -> response, called with its closure data
+type HandlerFn = unsafe extern "C" fn(data_ptr: i64, req: *mut NamlStruct) -> *mut NamlStruct;
+
+/// Response produced by a runtime-provided (Rust-side) handler
+pub(crate) struct NativeResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+/// Runtime-provided handler, used for built-in endpoints such as health checks
+pub(crate) type NativeHandler = Arc<dyn Fn() -> NativeResponse + Send + Sync>;
+
+/// Route handler: either a naml function pointer or a runtime-provided closure
+#[derive(Clone)]
+enum RouteHandler {
+    Naml { func: HandlerFn, data: i64 },
+    Native(NativeHandler),
+}
 
 /// Route definition
 #[derive(Clone)]
 struct Route {
     pattern: String,
     method: String,
-    handler: HandlerFn,
+    handler: RouteHandler,
     param_names: Vec<String>,
+    /// Middleware scoped to this route (from a group or mounted sub-router),
+    /// applied after the serving router's own middleware
+    middleware_handles: Vec<i64>,
 }
 
 /// Router structure
+#[derive(Clone)]
 struct Router {
     routes: Vec<Route>,
     middleware_handles: Vec<i64>,
-    prefix: String,
 }
 
 impl Router {
@@ -68,144 +93,132 @@ impl Router {
         Self {
             routes: Vec::new(),
             middleware_handles: Vec::new(),
-            prefix: String::new(),
         }
     }
 
-    fn with_prefix(prefix: String) -> Self {
-        Self {
-            routes: Vec::new(),
-            middleware_handles: Vec::new(),
-            prefix,
-        }
-    }
-
-    fn add_route(&mut self, method: &str, pattern: &str, handler: HandlerFn) {
-        let full_pattern = if self.prefix.is_empty() {
-            pattern.to_string()
-        } else {
-            format!("{}{}", self.prefix, pattern)
-        };
-
-        let param_names = extract_param_names(&full_pattern);
-
+    fn add_route(&mut self, method: &str, pattern: &str, handler: RouteHandler) {
         self.routes.push(Route {
-            pattern: full_pattern,
+            pattern: pattern.to_string(),
             method: method.to_string(),
             handler,
-            param_names,
+            param_names: extract_param_names(pattern),
+            middleware_handles: Vec::new(),
         });
     }
 
     fn add_middleware(&mut self, mw_handle: i64) {
         self.middleware_handles.push(mw_handle);
     }
+
+    /// Copy all routes of `sub` under `prefix`. The sub-router's middleware is
+    /// attached to each copied route so it only applies beneath the prefix.
+    fn mount(&mut self, prefix: &str, sub: &Router) {
+        for route in &sub.routes {
+            let pattern = join_pattern(prefix, &route.pattern);
+            let mut middleware_handles = sub.middleware_handles.clone();
+            middleware_handles.extend_from_slice(&route.middleware_handles);
+            self.routes.push(Route {
+                param_names: extract_param_names(&pattern),
+                pattern,
+                method: route.method.clone(),
+                handler: route.handler.clone(),
+                middleware_handles,
+            });
+        }
+    }
+}
+
+/// Effective middleware settings for a route
+#[derive(Clone, Copy, Default)]
+struct MiddlewareFlags {
+    logger: bool,
+    timeout_ms: Option<u64>,
+    recover: bool,
+    compress: bool,
+    request_id: bool,
+}
+
+impl MiddlewareFlags {
+    fn from_handles<'a>(handles: impl IntoIterator<Item = &'a i64>) -> Self {
+        use super::middleware::{get_middleware_config, MiddlewareConfig};
+
+        let mut flags = MiddlewareFlags::default();
+        for handle in handles {
+            if let Some(config) = get_middleware_config(*handle) {
+                match config {
+                    MiddlewareConfig::Logger => flags.logger = true,
+                    MiddlewareConfig::Timeout { ms } => flags.timeout_ms = Some(ms),
+                    MiddlewareConfig::Recover => flags.recover = true,
+                    MiddlewareConfig::Compress => flags.compress = true,
+                    MiddlewareConfig::RequestId => flags.request_id = true,
+                    _ => {}
+                }
+            }
+        }
+        flags
+    }
+}
+
+/// Route with its middleware resolved at serve-time
+struct FrozenRoute {
+    route: Route,
+    flags: MiddlewareFlags,
 }
 
 /// Frozen (immutable) router snapshot for zero-lock request handling.
 /// Created once at serve-time; shared across all worker tasks via Arc.
 struct FrozenRouter {
-    exact_routes: Vec<(String, String, HandlerFn)>,
-    param_routes: Vec<Route>,
-    has_logger: bool,
-    timeout_ms: Option<u64>,
-    has_recover: bool,
-    has_compress: bool,
+    exact_routes: Vec<FrozenRoute>,
+    param_routes: Vec<FrozenRoute>,
+    /// Router-level middleware, used for unmatched requests
+    flags: MiddlewareFlags,
 }
 
 impl FrozenRouter {
     fn from_router(router: &Router) -> Self {
-        use super::middleware::{get_middleware_config, MiddlewareConfig};
-
         let mut exact_routes = Vec::new();
         let mut param_routes = Vec::new();
         for route in &router.routes {
+            let flags = MiddlewareFlags::from_handles(
+                router.middleware_handles.iter().chain(&route.middleware_handles),
+            );
+            let frozen = FrozenRoute {
+                route: route.clone(),
+                flags,
+            };
             if route.param_names.is_empty() {
-                exact_routes.push((
-                    route.method.clone(),
-                    route.pattern.clone(),
-                    route.handler,
-                ));
+                exact_routes.push(frozen);
             } else {
-                param_routes.push(route.clone());
-            }
-        }
-
-        let mut has_logger = false;
-        let mut timeout_ms = None;
-        let mut has_recover = false;
-        let mut has_compress = false;
-
-        for handle in &router.middleware_handles {
-            if let Some(config) = get_middleware_config(*handle) {
-                match config {
-                    MiddlewareConfig::Logger => has_logger = true,
-                    MiddlewareConfig::Timeout { ms } => timeout_ms = Some(ms),
-                    MiddlewareConfig::Recover => has_recover = true,
-                    MiddlewareConfig::Compress => has_compress = true,
-                    _ => {}
-                }
+                param_routes.push(frozen);
             }
         }
 
         FrozenRouter {
             exact_routes,
             param_routes,
-            has_logger,
-            timeout_ms,
-            has_recover,
-            has_compress,
+            flags: MiddlewareFlags::from_handles(&router.middleware_handles),
         }
     }
-}
 
-/// Extract parameter names from a pattern like "/users/{id}/posts/{post_id}"
-fn extract_param_names(pattern: &str) -> Vec<String> {
-    let mut names = Vec::new();
-    let mut in_param = false;
-    let mut param_name = String::new();
-
-    for c in pattern.chars() {
-        if c == '{' {
-            in_param = true;
-            param_name.clear();
-        } else if c == '}' {
-            if in_param && !param_name.is_empty() {
-                names.push(param_name.clone());
+    /// Find the route for a request, returning it with any path parameters
+    fn find(&self, method: &str, path: &str) -> Option<(&FrozenRoute, HashMap<String, String>)> {
+        for frozen in &self.exact_routes {
+            if frozen.route.method == method && frozen.route.pattern == path {
+                return Some((frozen, HashMap::new()));
             }
-            in_param = false;
-        } else if in_param {
-            param_name.push(c);
         }
-    }
-
-    names
-}
-
-/// Convert pattern to regex-like matcher and extract param values
-fn match_route(pattern: &str, path: &str, param_names: &[String]) -> Option<HashMap<String, String>> {
-    let pattern_parts: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
-    let path_parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
-
-    if pattern_parts.len() != path_parts.len() {
-        return None;
-    }
-
-    let mut params = HashMap::new();
-    let mut param_idx = 0;
 
-    for (pattern_part, path_part) in pattern_parts.iter().zip(path_parts.iter()) {
-        if pattern_part.starts_with('{') && pattern_part.ends_with('}') {
-            if param_idx < param_names.len() {
-                params.insert(param_names[param_idx].clone(), path_part.to_string());
-                param_idx += 1;
+        for frozen in &self.param_routes {
+            let route = &frozen.route;
+            if route.method == method {
+                if let Some(params) = match_route(&route.pattern, path, &route.param_names) {
+                    return Some((frozen, params));
+                }
             }
-        } else if *pattern_part != *path_part {
-            return None;
         }
-    }
 
-    Some(params)
+        None
+    }
 }
 
 /// Global router registry
@@ -221,6 +234,19 @@ fn next_router_handle() -> i64 {
     NEXT_ROUTER_HANDLE.fetch_add(1, Ordering::SeqCst)
 }
 
+/// Register a router whose routes are all runtime-provided handlers
+pub(crate) fn open_native_router(routes: Vec<(&str, &str, NativeHandler)>) -> i64 {
+    let mut router = Router::new();
+    for (method, pattern, handler) in routes {
+        router.add_route(method, pattern, RouteHandler::Native(handler));
+    }
+
+    let handle = next_router_handle();
+    let mut routers = get_routers().write().unwrap();
+    routers.insert(handle, Arc::new(Mutex::new(router)));
+    handle
+}
+
 /// Get or create the tokio runtime for HTTP server
 fn get_runtime() -> &'static Runtime {
     use std::sync::OnceLock;
@@ -251,8 +277,10 @@ pub unsafe extern "C" fn naml_net_http_server_get(
     router_handle: i64,
     pattern: *const NamlString,
     handler: HandlerFn,
+    data_ptr: i64,
 ) {
     let pattern_str = unsafe { string_from_naml(pattern) };
+    let handler = RouteHandler::Naml { func: handler, data: data_ptr };
     let routers = get_routers().read().unwrap();
     if let Some(router) = routers.get(&router_handle) {
         router.lock().unwrap().add_route("GET", &pattern_str, handler);
@@ -265,8 +293,10 @@ pub unsafe extern "C" fn naml_net_http_server_post(
     router_handle: i64,
     pattern: *const NamlString,
     handler: HandlerFn,
+    data_ptr: i64,
 ) {
     let pattern_str = unsafe { string_from_naml(pattern) };
+    let handler = RouteHandler::Naml { func: handler, data: data_ptr };
     let routers = get_routers().read().unwrap();
     if let Some(router) = routers.get(&router_handle) {
         router.lock().unwrap().add_route("POST", &pattern_str, handler);
@@ -279,8 +309,10 @@ pub unsafe extern "C" fn naml_net_http_server_put(
     router_handle: i64,
     pattern: *const NamlString,
     handler: HandlerFn,
+    data_ptr: i64,
 ) {
     let pattern_str = unsafe { string_from_naml(pattern) };
+    let handler = RouteHandler::Naml { func: handler, data: data_ptr };
     let routers = get_routers().read().unwrap();
     if let Some(router) = routers.get(&router_handle) {
         router.lock().unwrap().add_route("PUT", &pattern_str, handler);
@@ -293,8 +325,10 @@ pub unsafe extern "C" fn naml_net_http_server_patch(
     router_handle: i64,
     pattern: *const NamlString,
     handler: HandlerFn,
+    data_ptr: i64,
 ) {
     let pattern_str = unsafe { string_from_naml(pattern) };
+    let handler = RouteHandler::Naml { func: handler, data: data_ptr };
     let routers = get_routers().read().unwrap();
     if let Some(router) = routers.get(&router_handle) {
         router.lock().unwrap().add_route("PATCH", &pattern_str, handler);
@@ -307,8 +341,10 @@ pub unsafe extern "C" fn naml_net_http_server_delete(
     router_handle: i64,
     pattern: *const NamlString,
     handler: HandlerFn,
+    data_ptr: i64,
 ) {
     let pattern_str = unsafe { string_from_naml(pattern) };
+    let handler = RouteHandler::Naml { func: handler, data: data_ptr };
     let routers = get_routers().read().unwrap();
     if let Some(router) = routers.get(&router_handle) {
         router.lock().unwrap().add_route("DELETE", &pattern_str, handler);
@@ -324,33 +360,41 @@ pub extern "C" fn naml_net_http_server_with(router_handle: i64, middleware_handl
     }
 }
 
-/// Create a route group with prefix
+/// Group setup callback: fn(group: router), called with its closure data
+type GroupFn = unsafe extern "C" fn(data_ptr: i64, group: i64);
+
+/// Create a route group under a prefix.
+/// The setup callback receives a fresh router handle on which it registers
+/// routes and group-scoped middleware; once it returns, the group is mounted
+/// into the parent at `prefix` and the temporary handle is released.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_net_http_server_group(
     router_handle: i64,
     prefix: *const NamlString,
-) -> i64 {
+    func_ptr: i64,
+    data_ptr: i64,
+) {
+    if func_ptr == 0 {
+        return;
+    }
     let prefix_str = unsafe { string_from_naml(prefix) };
 
-    let routers = get_routers().read().unwrap();
-    let parent_prefix = if let Some(router) = routers.get(&router_handle) {
-        router.lock().unwrap().prefix.clone()
-    } else {
-        String::new()
-    };
-    drop(routers);
-
-    let full_prefix = format!("{}{}", parent_prefix, prefix_str);
     let group_handle = next_router_handle();
-    let group_router = Arc::new(Mutex::new(Router::with_prefix(full_prefix)));
+    get_routers()
+        .write()
+        .unwrap()
+        .insert(group_handle, Arc::new(Mutex::new(Router::new())));
 
-    let mut routers = get_routers().write().unwrap();
-    routers.insert(group_handle, group_router);
+    let setup: GroupFn = unsafe { std::mem::transmute(func_ptr as usize) };
+    unsafe { setup(data_ptr, group_handle) };
 
-    group_handle
+    mount_router(router_handle, &prefix_str, group_handle);
+    get_routers().write().unwrap().remove(&group_handle);
 }
 
-/// Mount a sub-router at a prefix
+/// Mount a sub-router at a prefix.
+/// Routes are copied at mount time together with the sub-router's middleware,
+/// which stays scoped to the mounted routes.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_net_http_server_mount(
     router_handle: i64,
@@ -358,27 +402,22 @@ pub unsafe extern "C" fn naml_net_http_server_mount(
     sub_router_handle: i64,
 ) {
     let prefix_str = unsafe { string_from_naml(prefix) };
+    mount_router(router_handle, &prefix_str, sub_router_handle);
+}
 
-    let routers = get_routers().read().unwrap();
-    let sub_routes = if let Some(sub_router) = routers.get(&sub_router_handle) {
-        let sub = sub_router.lock().unwrap();
-        sub.routes.clone()
-    } else {
+fn mount_router(router_handle: i64, prefix: &str, sub_router_handle: i64) {
+    if router_handle == sub_router_handle {
         return;
+    }
+
+    let routers = get_routers().read().unwrap();
+    let sub = match routers.get(&sub_router_handle) {
+        Some(sub_router) => sub_router.lock().unwrap().clone(),
+        None => return,
     };
 
     if let Some(router) = routers.get(&router_handle) {
-        let mut r = router.lock().unwrap();
-        for route in sub_routes {
-            let new_pattern = format!("{}{}", prefix_str, route.pattern);
-            let param_names = extract_param_names(&new_pattern);
-            r.routes.push(Route {
-                pattern: new_pattern,
-                method: route.method,
-                handler: route.handler,
-                param_names,
-            });
-        }
+        router.lock().unwrap().mount(prefix, &sub);
     }
 }
 
@@ -587,21 +626,41 @@ async fn handle_request(
     req: Request<Incoming>,
     frozen: &FrozenRouter,
 ) -> Result<Response<Full<Bytes>>, std::convert::Infallible> {
-    let start = if frozen.has_logger || frozen.timeout_ms.is_some() {
-        Some(std::time::Instant::now())
-    } else {
-        None
-    };
-
     let (parts, body) = req.into_parts();
     let skip_body = parts.method == Method::GET || parts.method == Method::HEAD;
     let method = parts.method.as_str();
     let path = parts.uri.path();
     let query_string = parts.uri.query().unwrap_or("");
 
-    if let (Some(ms), Some(start)) = (frozen.timeout_ms, &start) {
+    let matched = frozen.find(method, path);
+    let flags = matched.as_ref().map_or(frozen.flags, |(route, _)| route.flags);
+    let (matched_handler, params) = match matched {
+        Some((route, params)) => (Some(&route.route.handler), params),
+        None => (None, HashMap::new()),
+    };
+    let mut content_type: Option<&'static str> = None;
+    let request_id = if flags.request_id {
+        Some(
+            parts
+                .headers
+                .get("x-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+                .unwrap_or_else(next_request_id),
+        )
+    } else {
+        None
+    };
+
+    let start = if flags.logger || flags.timeout_ms.is_some() {
+        Some(std::time::Instant::now())
+    } else {
+        None
+    };
+
+    if let (Some(ms), Some(start)) = (flags.timeout_ms, &start) {
         if start.elapsed().as_millis() > ms as u128 {
-            if frozen.has_logger {
+            if flags.logger {
                 eprintln!("[HTTP] {} {} -> 408 (timeout)", method, path);
             }
             return Ok(Response::builder()
@@ -612,29 +671,12 @@ async fn handle_request(
         }
     }
 
-    let mut matched_handler: Option<HandlerFn> = None;
-    let mut params: HashMap<String, String> = HashMap::new();
-
-    for (route_method, route_path, handler) in &frozen.exact_routes {
-        if route_method == method && route_path == path {
-            matched_handler = Some(*handler);
-            break;
-        }
-    }
-
-    if matched_handler.is_none() {
-        for route in &frozen.param_routes {
-            if route.method == method {
-                if let Some(p) = match_route(&route.pattern, path, &route.param_names) {
-                    matched_handler = Some(route.handler);
-                    params = p;
-                    break;
-                }
-            }
-        }
-    }
-
-    let (status, mut response_body) = if let Some(handler) = matched_handler {
+    let (status, mut response_body) = if let Some(RouteHandler::Native(handler)) = matched_handler {
+        drop(body);
+        let response = handler();
+        content_type = Some(response.content_type);
+        (response.status, response.body)
+    } else if let Some(&RouteHandler::Naml { func, data }) = matched_handler {
         let body_bytes = if skip_body {
             drop(body);
             Vec::new()
@@ -647,12 +689,16 @@ async fn handle_request(
 
         let naml_request =
             unsafe { create_naml_request(method, path, &body_bytes, &params, query_string) };
+        if let Some(id) = &request_id {
+            unsafe { set_request_id(naml_request, id) };
+        }
 
-        let result = if frozen.has_recover {
-            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| handler(naml_request)))
+        let result = if flags.recover {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe { func(data, naml_request) }))
         } else {
-            Ok(handler(naml_request))
+            Ok(unsafe { func(data, naml_request) })
         };
+        unsafe { release_request_context(naml_request) };
 
         match result {
             Ok(naml_response) if !naml_response.is_null() => unsafe {
@@ -662,7 +708,7 @@ async fn handle_request(
                     Vec::new()
                 } else if (*(body_ptr as *const NamlBytes)).header.tag == HeapTag::Bytes {
                     let b = body_ptr as *const NamlBytes;
-                    std::slice::from_raw_parts((*b).data.as_ptr(), (*b).len).to_vec()
+                    std::slice::from_raw_parts((*b).as_ptr(), (*b).len).to_vec()
                 } else {
                     array_to_vec(body_ptr)
                 };
@@ -678,7 +724,7 @@ async fn handle_request(
         (404, b"Not Found".to_vec())
     };
 
-    if frozen.has_compress && response_body.len() >= 1024 {
+    if flags.compress && response_body.len() >= 1024 {
         use flate2::write::GzEncoder;
         use flate2::Compression;
         use std::io::Write;
@@ -693,18 +739,24 @@ async fn handle_request(
         }
     }
 
-    if frozen.has_logger {
+    if flags.logger {
         if let Some(start) = start {
             let elapsed = start.elapsed();
             eprintln!("[HTTP] {} {} -> {} ({:.2?})", method, path, status, elapsed);
         }
     }
 
-    Ok(Response::builder()
+    let mut builder = Response::builder()
         .status(status)
-        .header("content-length", response_body.len())
-        .body(Full::new(Bytes::from(response_body)))
-        .unwrap())
+        .header("content-length", response_body.len());
+    if let Some(content_type) = content_type {
+        builder = builder.header("content-type", content_type);
+    }
+    if let Some(id) = request_id {
+        builder = builder.header("x-request-id", id);
+    }
+
+    Ok(builder.body(Full::new(Bytes::from(response_body))).unwrap())
 }
 
 /// Create a naml request struct from HTTP request data.
@@ -739,6 +791,7 @@ unsafe fn create_naml_request(
         naml_std_core::naml_struct_set_field(request, super::types::request_fields::HEADERS, 0);
         naml_std_core::naml_struct_set_field(request, super::types::request_fields::PARAMS, 0);
         naml_std_core::naml_struct_set_field(request, super::types::request_fields::QUERY, 0);
+        naml_std_core::naml_struct_set_field(request, super::types::request_fields::CONTEXT, 0);
 
         if body.is_empty() {
             naml_std_core::naml_struct_set_field(request, super::types::request_fields::BODY, 0);
@@ -767,7 +820,7 @@ pub unsafe extern "C" fn naml_net_http_server_text_response(
         if body.is_null() {
             return naml_net_http_response_create(status, 0, std::ptr::null_mut());
         }
-        let body_bytes = create_bytes_from((*body).data.as_ptr(), (*body).len);
+        let body_bytes = create_bytes_from((*body).as_ptr(), (*body).len);
         naml_net_http_response_create(status, 0, body_bytes as *mut NamlArray)
     }
 }
@@ -776,45 +829,107 @@ pub unsafe extern "C" fn naml_net_http_server_text_response(
 mod tests {
     use super::*;
 
+    extern "C" fn dummy_handler(_data: i64, req: *mut NamlStruct) -> *mut NamlStruct {
+        req
+    }
+
+    fn router_snapshot(handle: i64) -> Router {
+        get_routers().read().unwrap()[&handle].lock().unwrap().clone()
+    }
+
+    fn add_get(handle: i64, pattern: &str) {
+        unsafe {
+            let pattern = naml_std_core::naml_string_new(pattern.as_ptr(), pattern.len());
+            naml_net_http_server_get(handle, pattern, dummy_handler, 0);
+        }
+    }
+
     #[test]
-    fn test_extract_param_names() {
-        let names = extract_param_names("/users/{id}");
-        assert_eq!(names, vec!["id"]);
+    fn test_mount_scopes_middleware() {
+        let parent = naml_net_http_server_open_router();
+        add_get(parent, "/");
+
+        let admin = naml_net_http_server_open_router();
+        let logger = super::super::middleware::naml_net_http_middleware_logger();
+        naml_net_http_server_with(admin, logger);
+        add_get(admin, "/users/{id}");
 
-        let names = extract_param_names("/users/{user_id}/posts/{post_id}");
-        assert_eq!(names, vec!["user_id", "post_id"]);
+        mount_router(parent, "/admin", admin);
 
-        let names = extract_param_names("/static/path");
-        assert!(names.is_empty());
+        let frozen = FrozenRouter::from_router(&router_snapshot(parent));
+        let (root, _) = frozen.find("GET", "/").unwrap();
+        assert!(!root.flags.logger);
+
+        let (user, params) = frozen.find("GET", "/admin/users/7").unwrap();
+        assert!(user.flags.logger);
+        assert_eq!(params.get("id"), Some(&"7".to_string()));
+        assert!(!frozen.flags.logger);
+    }
+
+    extern "C" fn setup_group(data_ptr: i64, group: i64) {
+        add_get(group, "/stats");
+        naml_net_http_server_with(group, data_ptr);
     }
 
     #[test]
-    fn test_match_route() {
-        let pattern = "/users/{id}";
-        let param_names = vec!["id".to_string()];
+    fn test_group_callback() {
+        let parent = naml_net_http_server_open_router();
+        let recover = super::super::middleware::naml_net_http_middleware_recover();
+        unsafe {
+            let prefix = naml_std_core::naml_string_new(b"/admin".as_ptr(), 6);
+            naml_net_http_server_group(
+                parent,
+                prefix,
+                setup_group as *const () as i64,
+                recover,
+            );
+        }
+
+        let router = router_snapshot(parent);
+        assert_eq!(router.routes.len(), 1);
+        assert_eq!(router.routes[0].pattern, "/admin/stats");
+        assert_eq!(router.routes[0].middleware_handles, vec![recover]);
 
-        let result = match_route(pattern, "/users/123", &param_names);
-        assert!(result.is_some());
-        let params = result.unwrap();
-        assert_eq!(params.get("id"), Some(&"123".to_string()));
+        let frozen = FrozenRouter::from_router(&router);
+        assert!(frozen.find("GET", "/admin/stats").unwrap().0.flags.recover);
+        assert!(frozen.find("GET", "/stats").is_none());
+    }
 
-        let result = match_route(pattern, "/users/123/extra", &param_names);
-        assert!(result.is_none());
+    extern "C" fn failing_check(_data: i64) -> i64 {
+        0
+    }
 
-        let result = match_route(pattern, "/posts/123", &param_names);
-        assert!(result.is_none());
+    fn call_native(frozen: &FrozenRouter, path: &str) -> NativeResponse {
+        match &frozen.find("GET", path).unwrap().0.route.handler {
+            RouteHandler::Native(handler) => handler(),
+            RouteHandler::Naml { .. } => panic!("{} is not a native route", path),
+        }
     }
 
     #[test]
-    fn test_match_route_multiple_params() {
-        let pattern = "/users/{user_id}/posts/{post_id}";
-        let param_names = vec!["user_id".to_string(), "post_id".to_string()];
-
-        let result = match_route(pattern, "/users/42/posts/99", &param_names);
-        assert!(result.is_some());
-        let params = result.unwrap();
-        assert_eq!(params.get("user_id"), Some(&"42".to_string()));
-        assert_eq!(params.get("post_id"), Some(&"99".to_string()));
+    fn test_health_router_endpoints() {
+        let closure = Box::new([failing_check as *const () as i64, 0, 0]);
+        let health = unsafe {
+            let checks = naml_std_core::naml_map_new(4);
+            let name = naml_std_core::naml_string_new(b"db".as_ptr(), 2);
+            naml_std_core::naml_map_set(checks, name as i64, closure.as_ptr() as i64);
+            super::super::health::naml_net_http_server_health_router(checks)
+        };
+        let app = naml_net_http_server_open_router();
+        mount_router(app, "", health);
+        let frozen = FrozenRouter::from_router(&router_snapshot(app));
+
+        let live = call_native(&frozen, "/healthz");
+        assert_eq!(live.status, 200);
+        let body: serde_json::Value = serde_json::from_slice(&live.body).unwrap();
+        assert_eq!(body["status"], "ok");
+        assert!(body.get("checks").is_none());
+
+        let ready = call_native(&frozen, "/readyz");
+        assert_eq!(ready.status, 503);
+        let body: serde_json::Value = serde_json::from_slice(&ready.body).unwrap();
+        assert_eq!(body["status"], "fail");
+        assert_eq!(body["checks"]["db"]["status"], "fail");
     }
 
     #[test]
@@ -13,7 +13,8 @@
 //!     pub headers: map<string, string>,
 //!     pub body: bytes,
 //!     pub params: map<string, string>,  // URL path parameters
-//!     pub query: map<string, string>    // Query string parameters
+//!     pub query: map<string, string>,   // Query string parameters
+//!     context: map<string, string>      // Request-scoped values (set_value/get_value)
 //! }
 //! ```
 //!
@@ -52,7 +53,8 @@ pub mod request_fields {
     pub const BODY: u32 = 3;
     pub const PARAMS: u32 = 4;
     pub const QUERY: u32 = 5;
-    pub const FIELD_COUNT: u32 = 6;
+    pub const CONTEXT: u32 = 6;
+    pub const FIELD_COUNT: u32 = 7;
 }
 
 /// Response field indices
@@ -72,6 +74,7 @@ pub mod response_fields {
 /// - body: empty array
 /// - params: null (caller should set)
 /// - query: null (caller should set)
+/// - context: null (created on first set_value)
 #[unsafe(no_mangle)]
 pub extern "C" fn naml_net_http_request_new() -> *mut NamlStruct {
     unsafe {
@@ -89,6 +92,7 @@ pub extern "C" fn naml_net_http_request_new() -> *mut NamlStruct {
         naml_std_core::naml_struct_set_field(req, request_fields::HEADERS, 0);
         naml_std_core::naml_struct_set_field(req, request_fields::PARAMS, 0);
         naml_std_core::naml_struct_set_field(req, request_fields::QUERY, 0);
+        naml_std_core::naml_struct_set_field(req, request_fields::CONTEXT, 0);
 
         // Initialize body to empty array
         let body = naml_std_core::naml_array_new(0);
@@ -341,13 +345,7 @@ pub(crate) unsafe fn array_to_vec(arr: *const NamlArray) -> Vec<u8> {
 
 /// Helper to create array from bytes
 pub(crate) unsafe fn vec_to_array(bytes: &[u8]) -> *mut NamlArray {
-    unsafe {
-        let arr = naml_std_core::naml_array_new(bytes.len());
-        for &byte in bytes {
-            naml_std_core::naml_array_push(arr, byte as i64);
-        }
-        arr
-    }
+    naml_std_core::naml_array_from_bytes(bytes)
 }
 
 #[cfg(test)]
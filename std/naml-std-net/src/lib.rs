@@ -12,6 +12,8 @@
 //! - `std::net::http::client` - HTTP client (get, post, put, patch, delete)
 //! - `std::net::http::server` - HTTP server with chi-style routing
 //! - `std::net::http::middleware` - Built-in middleware
+//! - `std::net::s3` - S3-compatible object storage client
+//! - `std::cloud::metadata` - EC2/GCE/Azure instance metadata
 //!
 //! ## TCP Server API (std::net::tcp::server)
 //!
@@ -49,7 +51,12 @@
 //! - `get(r: router, pattern: string, h: handler)`
 //! - `post(r: router, pattern: string, h: handler)`
 //! - `with(r: router, mw: middleware)`
+//! - `group(r: router, prefix: string, setup: fn(router))`
+//! - `mount(r: router, prefix: string, sub: router)`
 //! - `serve(address: string, r: router) throws NetworkError`
+//! - `health_router(checks: map<string, fn() -> bool>) -> router`
+//! - `set_value(req: request, key: string, value: string)`
+//! - `get_value(req: request, key: string) -> option<string>`
 //!
 //! ## Middleware API (std::net::http::middleware)
 //!
@@ -61,6 +68,26 @@
 //! - `compress() -> middleware`
 //! - `request_id() -> middleware`
 //!
+//! ## S3 API (std::net::s3)
+//!
+//! - `configure(endpoint: string, region: string, access_key: string, secret_key: string)`
+//! - `reset_config()`
+//! - `put_object(bucket: string, key: string, data: bytes) throws NetworkError, TimeoutError`
+//! - `put_file(bucket: string, key: string, path: string) throws IOError, NetworkError, TimeoutError`
+//! - `get_object(bucket: string, key: string) -> bytes throws NetworkError, TimeoutError`
+//! - `list(bucket: string, prefix: string) -> [string] throws NetworkError, TimeoutError`
+//! - `presign_url(method: string, bucket: string, key: string, expires_secs: int) -> string throws NetworkError`
+//!
+//! ## Cloud Metadata API (std::cloud::metadata)
+//!
+//! - `provider() -> string`
+//! - `region() -> string throws NetworkError`
+//! - `instance_id() -> string throws NetworkError`
+//! - `credentials() -> map<string, string> throws NetworkError`
+//! - `get(path: string) -> string throws NetworkError`
+//! - `set_endpoint(provider: string, endpoint: string) throws NetworkError`
+//! - `reset()`
+//!
 //! ## Exceptions
 //!
 //! - `NetworkError { message: string, code: int }` - General network error
@@ -74,14 +101,18 @@
 //! Native platform first. Server WASM and Browser WASM support planned.
 //!
 
+pub mod cloud;
 mod errors;
 pub mod http;
+pub mod s3;
 pub mod tcp;
 pub mod tls;
 pub mod udp;
 
+pub use cloud::*;
 pub use errors::*;
 pub use http::*;
+pub use s3::*;
 pub use tcp::*;
 pub use tls::*;
 pub use udp::*;
@@ -0,0 +1,553 @@
+//!
+//! S3-Compatible Object Storage Client
+//!
+//! Reads and writes objects on AWS S3 or any S3-compatible service (MinIO,
+//! Cloudflare R2, Ceph, ...). Requests are signed with AWS Signature
+//! Version 4 and sent through the HTTP client, so they share its timeout,
+//! proxy and retry settings. Buckets are addressed path-style
+//! (`{endpoint}/{bucket}/{key}`).
+//!
+//! ## Functions
+//!
+//! - `naml_net_s3_configure` - Set endpoint, region and credentials
+//! - `naml_net_s3_reset_config` - Go back to the environment defaults
+//! - `naml_net_s3_put_object` - Upload bytes as an object
+//! - `naml_net_s3_put_file` - Upload a local file as an object
+//! - `naml_net_s3_get_object` - Download an object
+//! - `naml_net_s3_list` - List object keys under a prefix (ListObjectsV2)
+//! - `naml_net_s3_presign_url` - Presigned URL for a method and key
+//!
+//! ## Configuration
+//!
+//! Until `configure` is called the settings come from the environment on
+//! every request: `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+//! `AWS_SESSION_TOKEN`, `AWS_REGION` (or `AWS_DEFAULT_REGION`, default
+//! `us-east-1`) and `AWS_ENDPOINT_URL_S3` (or `AWS_ENDPOINT_URL`, default
+//! `https://s3.{region}.amazonaws.com`). Without `AWS_ACCESS_KEY_ID`,
+//! the credentials of the EC2 instance role are used when running on AWS
+//! (see `std::cloud::metadata`).
+//!
+//! ## Errors
+//!
+//! A non-2xx response throws NetworkError with the HTTP status as `code`
+//! and the S3 error code and message in `message`.
+//!
+
+pub(crate) mod sigv4;
+
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use naml_std_core::{NamlArray, NamlBytes, NamlString, naml_array_new, naml_array_push, naml_bytes_from, naml_string_new};
+
+use crate::cloud::metadata::aws_instance_credentials;
+use crate::errors::{string_from_naml, throw_network_error_code};
+use crate::http::client::{fetch, throw_request_error};
+use sigv4::{Credentials, RequestParts};
+
+/// Longest lifetime S3 accepts for a presigned URL (7 days)
+const MAX_PRESIGN_SECS: i64 = 604_800;
+
+#[derive(Clone, Debug, PartialEq)]
+struct S3Config {
+    /// Scheme and authority, e.g. `https://s3.us-east-1.amazonaws.com`
+    endpoint: String,
+    region: String,
+    credentials: Credentials,
+}
+
+/// Set by `configure`; `None` means read the environment
+static S3_CONFIG: Mutex<Option<S3Config>> = Mutex::new(None);
+
+fn default_endpoint(region: &str) -> String {
+    format!("https://s3.{}.amazonaws.com", region)
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+}
+
+/// Keys from the environment, else the EC2 instance role's
+fn env_credentials() -> Credentials {
+    if let Some(access_key) = env_var(&["AWS_ACCESS_KEY_ID"]) {
+        return Credentials {
+            access_key,
+            secret_key: env_var(&["AWS_SECRET_ACCESS_KEY"]).unwrap_or_default(),
+            session_token: env_var(&["AWS_SESSION_TOKEN"]),
+        };
+    }
+    match aws_instance_credentials() {
+        Some((access_key, secret_key, token)) => Credentials {
+            access_key,
+            secret_key,
+            session_token: Some(token).filter(|t| !t.is_empty()),
+        },
+        None => Credentials { access_key: String::new(), secret_key: String::new(), session_token: None },
+    }
+}
+
+fn env_config() -> S3Config {
+    let region = env_var(&["AWS_REGION", "AWS_DEFAULT_REGION"]).unwrap_or_else(|| "us-east-1".to_string());
+    S3Config {
+        endpoint: env_var(&["AWS_ENDPOINT_URL_S3", "AWS_ENDPOINT_URL"]).unwrap_or_else(|| default_endpoint(&region)),
+        region,
+        credentials: env_credentials(),
+    }
+}
+
+fn current_config() -> S3Config {
+    S3_CONFIG.lock().unwrap().clone().unwrap_or_else(env_config)
+}
+
+/// Split an endpoint URL into (scheme, authority)
+fn endpoint_parts(endpoint: &str) -> Result<(String, String), String> {
+    let uri: hyper::Uri = endpoint
+        .parse()
+        .map_err(|e| format!("invalid S3 endpoint '{}': {}", endpoint, e))?;
+    match (uri.scheme_str(), uri.authority()) {
+        (Some(scheme @ ("http" | "https")), Some(authority)) => Ok((scheme.to_string(), authority.to_string())),
+        _ => Err(format!("invalid S3 endpoint '{}': expected http:// or https://", endpoint)),
+    }
+}
+
+/// Path-style object path, already URI-encoded
+fn object_path(bucket: &str, key: &str) -> String {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    if key.is_empty() {
+        format!("/{}", sigv4::uri_encode(bucket, false))
+    } else {
+        format!("/{}/{}", sigv4::uri_encode(bucket, false), sigv4::uri_encode(key, true))
+    }
+}
+
+fn request_url(scheme: &str, host: &str, path: &str, query: &[(String, String)]) -> String {
+    let mut url = format!("{}://{}{}", scheme, host, path);
+    for (i, (k, v)) in query.iter().enumerate() {
+        url.push(if i == 0 { '?' } else { '&' });
+        url.push_str(&sigv4::uri_encode(k, false));
+        url.push('=');
+        url.push_str(&sigv4::uri_encode(v, false));
+    }
+    url
+}
+
+/// Why an S3 call failed
+#[derive(Debug)]
+enum S3Error {
+    /// Transport failure or timeout from the HTTP client
+    Io(std::io::Error),
+    /// Non-2xx response, or a request that could not be built
+    Status { message: String, code: i64 },
+}
+
+impl S3Error {
+    fn throw(self) {
+        match self {
+            S3Error::Io(e) => throw_request_error(e),
+            S3Error::Status { message, code } => {
+                throw_network_error_code(&message, code);
+            }
+        }
+    }
+}
+
+/// Send a signed request and return the body of a 2xx response
+fn send(
+    config: &S3Config,
+    method: &str,
+    path: &str,
+    query: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+) -> Result<Vec<u8>, S3Error> {
+    let (scheme, host) =
+        endpoint_parts(&config.endpoint).map_err(|message| S3Error::Status { message, code: -1 })?;
+    let url = request_url(&scheme, &host, path, &query);
+    let payload_hash = sigv4::sha256_hex(body.as_deref().unwrap_or_default());
+    let request = RequestParts { method, host: &host, path, query };
+    let amz_date = sigv4::amz_date(SystemTime::now());
+    let headers = sigv4::sign_headers(&config.credentials, &config.region, &amz_date, &request, &payload_hash);
+
+    let (status, response) = fetch(method, &url, body, headers).map_err(S3Error::Io)?;
+    if (200..300).contains(&status) {
+        return Ok(response);
+    }
+
+    let text = String::from_utf8_lossy(&response);
+    let detail = match (xml_value(&text, "Code"), xml_value(&text, "Message")) {
+        (Some(code), Some(message)) => format!(": {}: {}", code, message),
+        (Some(code), None) => format!(": {}", code),
+        _ => String::new(),
+    };
+    Err(S3Error::Status {
+        message: format!("S3 {} {} failed with status {}{}", method, path, status, detail),
+        code: status,
+    })
+}
+
+fn xml_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else { break };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|n| n.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Text of every `<tag>...</tag>` element, in document order
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else { break };
+        values.push(xml_unescape(&rest[..end]));
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+fn xml_value(xml: &str, tag: &str) -> Option<String> {
+    xml_values(xml, tag).into_iter().next()
+}
+
+/// All keys under `prefix`, following ListObjectsV2 continuation tokens
+fn list_keys(config: &S3Config, bucket: &str, prefix: &str) -> Result<Vec<String>, S3Error> {
+    let path = object_path(bucket, "");
+    let mut keys = Vec::new();
+    let mut token: Option<String> = None;
+    loop {
+        let mut query = vec![
+            ("list-type".to_string(), "2".to_string()),
+            ("prefix".to_string(), prefix.to_string()),
+        ];
+        if let Some(token) = token.take() {
+            query.push(("continuation-token".to_string(), token));
+        }
+        let body = send(config, "GET", &path, query, None)?;
+        let xml = String::from_utf8_lossy(&body);
+        keys.extend(xml_values(&xml, "Key"));
+
+        let truncated = xml_value(&xml, "IsTruncated").is_some_and(|v| v == "true");
+        match xml_value(&xml, "NextContinuationToken") {
+            Some(next) if truncated => token = Some(next),
+            _ => return Ok(keys),
+        }
+    }
+}
+
+/// Presigned URL for `method` on `bucket`/`key`, valid for `expires_secs`
+fn presign(config: &S3Config, method: &str, bucket: &str, key: &str, expires_secs: i64, now: SystemTime) -> Result<String, String> {
+    let (scheme, host) = endpoint_parts(&config.endpoint)?;
+    let path = object_path(bucket, key);
+    let method = method.to_ascii_uppercase();
+    let request = RequestParts { method: &method, host: &host, path: &path, query: Vec::new() };
+    let query = sigv4::presign_query(
+        &config.credentials,
+        &config.region,
+        &sigv4::amz_date(now),
+        request,
+        expires_secs.clamp(1, MAX_PRESIGN_SECS),
+    );
+    Ok(format!("{}://{}{}?{}", scheme, host, path, query))
+}
+
+/// Use the given endpoint, region and credentials instead of the environment.
+/// An empty endpoint selects the AWS endpoint for the region.
+///
+/// # Safety
+/// The caller must ensure `endpoint` is a valid pointer to a NamlString,
+/// `region` is a valid pointer to a NamlString, `access_key` is a valid pointer
+/// to a NamlString and `secret_key` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_s3_configure(
+    endpoint: *const NamlString,
+    region: *const NamlString,
+    access_key: *const NamlString,
+    secret_key: *const NamlString,
+) {
+    let (endpoint, region) = unsafe { (string_from_naml(endpoint), string_from_naml(region)) };
+    let region = if region.is_empty() { "us-east-1".to_string() } else { region };
+    let config = S3Config {
+        endpoint: if endpoint.is_empty() { default_endpoint(&region) } else { endpoint },
+        region,
+        credentials: Credentials {
+            access_key: unsafe { string_from_naml(access_key) },
+            secret_key: unsafe { string_from_naml(secret_key) },
+            session_token: None,
+        },
+    };
+    *S3_CONFIG.lock().unwrap() = Some(config);
+}
+
+/// Drop any configure() settings and read the environment again
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_net_s3_reset_config() {
+    *S3_CONFIG.lock().unwrap() = None;
+}
+
+/// Upload `data` as `bucket`/`key`
+///
+/// # Safety
+/// The caller must ensure `bucket` is a valid pointer to a NamlString, `key` is
+/// a valid pointer to a NamlString and `data` is a valid pointer to a NamlBytes
+/// or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_s3_put_object(
+    bucket: *const NamlString,
+    key: *const NamlString,
+    data: *const NamlBytes,
+) {
+    let (bucket, key) = unsafe { (string_from_naml(bucket), string_from_naml(key)) };
+    let body = if data.is_null() {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts((*data).as_ptr(), (*data).len).to_vec() }
+    };
+    if let Err(e) = send(&current_config(), "PUT", &object_path(&bucket, &key), Vec::new(), Some(body)) {
+        e.throw();
+    }
+}
+
+/// Upload the file at `path` as `bucket`/`key`
+///
+/// # Safety
+/// The caller must ensure `bucket` is a valid pointer to a NamlString, `key` is
+/// a valid pointer to a NamlString and `path` is a valid pointer to a
+/// NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_s3_put_file(
+    bucket: *const NamlString,
+    key: *const NamlString,
+    path: *const NamlString,
+) {
+    let (bucket, key, path) = unsafe { (string_from_naml(bucket), string_from_naml(key), string_from_naml(path)) };
+    let body = match std::fs::read(&path) {
+        Ok(body) => body,
+        Err(e) => {
+            naml_std_fs::throw_io_error(e, &path);
+            return;
+        }
+    };
+    if let Err(e) = send(&current_config(), "PUT", &object_path(&bucket, &key), Vec::new(), Some(body)) {
+        e.throw();
+    }
+}
+
+/// Download `bucket`/`key`; returns null with an exception set on failure
+///
+/// # Safety
+/// The caller must ensure `bucket` is a valid pointer to a NamlString and `key`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_s3_get_object(
+    bucket: *const NamlString,
+    key: *const NamlString,
+) -> *mut NamlBytes {
+    let (bucket, key) = unsafe { (string_from_naml(bucket), string_from_naml(key)) };
+    match send(&current_config(), "GET", &object_path(&bucket, &key), Vec::new(), None) {
+        Ok(body) => unsafe { naml_bytes_from(body.as_ptr(), body.len()) },
+        Err(e) => {
+            e.throw();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Keys of all objects in `bucket` that start with `prefix`
+///
+/// # Safety
+/// The caller must ensure `bucket` is a valid pointer to a NamlString and
+/// `prefix` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_s3_list(
+    bucket: *const NamlString,
+    prefix: *const NamlString,
+) -> *mut NamlArray {
+    let (bucket, prefix) = unsafe { (string_from_naml(bucket), string_from_naml(prefix)) };
+    match list_keys(&current_config(), &bucket, &prefix) {
+        Ok(keys) => unsafe {
+            let arr = naml_array_new(keys.len());
+            for key in keys {
+                naml_array_push(arr, naml_string_new(key.as_ptr(), key.len()) as i64);
+            }
+            arr
+        },
+        Err(e) => {
+            e.throw();
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Presigned URL letting anyone perform `method` on `bucket`/`key` for
+/// `expires_secs` seconds (clamped to 1 second .. 7 days)
+///
+/// # Safety
+/// The caller must ensure `method` is a valid pointer to a NamlString, `bucket`
+/// is a valid pointer to a NamlString and `key` is a valid pointer to a
+/// NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_s3_presign_url(
+    method: *const NamlString,
+    bucket: *const NamlString,
+    key: *const NamlString,
+    expires_secs: i64,
+) -> *mut NamlString {
+    let (method, bucket, key) = unsafe { (string_from_naml(method), string_from_naml(bucket), string_from_naml(key)) };
+    match presign(&current_config(), &method, &bucket, &key, expires_secs, SystemTime::now()) {
+        Ok(url) => unsafe { naml_string_new(url.as_ptr(), url.len()) },
+        Err(message) => {
+            throw_network_error_code(&message, -1);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn test_config(endpoint: &str) -> S3Config {
+        S3Config {
+            endpoint: endpoint.to_string(),
+            region: "us-east-1".to_string(),
+            credentials: Credentials {
+                access_key: "AKID".to_string(),
+                secret_key: "secret".to_string(),
+                session_token: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_object_path_and_url() {
+        assert_eq!(object_path("media", "/a b/c+d.txt"), "/media/a%20b/c%2Bd.txt");
+        assert_eq!(object_path("media", ""), "/media");
+        let query = vec![("prefix".to_string(), "a/b".to_string())];
+        assert_eq!(request_url("http", "localhost:9000", "/media", &query), "http://localhost:9000/media?prefix=a%2Fb");
+        assert!(endpoint_parts("ftp://example.com").is_err());
+        assert_eq!(
+            endpoint_parts("https://s3.eu-west-1.amazonaws.com").unwrap(),
+            ("https".to_string(), "s3.eu-west-1.amazonaws.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_xml_values() {
+        let xml = "<ListBucketResult><IsTruncated>false</IsTruncated>\
+                   <Contents><Key>a&amp;b.txt</Key></Contents>\
+                   <Contents><Key>c&#x2F;d &lt;1&gt;</Key></Contents></ListBucketResult>";
+        assert_eq!(xml_values(xml, "Key"), vec!["a&b.txt", "c/d <1>"]);
+        assert_eq!(xml_value(xml, "IsTruncated").as_deref(), Some("false"));
+        assert_eq!(xml_value(xml, "NextContinuationToken"), None);
+        assert_eq!(xml_unescape("5 &unknown; &#65;"), "5 &unknown; A");
+    }
+
+    #[test]
+    fn test_presign_url() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_369_353_600);
+        let url = presign(&test_config("http://127.0.0.1:9000"), "put", "media", "x y.png", 10_000_000, now).unwrap();
+        assert!(url.starts_with("http://127.0.0.1:9000/media/x%20y.png?X-Amz-Algorithm=AWS4-HMAC-SHA256&"), "{}", url);
+        assert!(url.contains("X-Amz-Credential=AKID%2F20130524%2Fus-east-1%2Fs3%2Faws4_request"), "{}", url);
+        assert!(url.contains("&X-Amz-Expires=604800&"), "{}", url);
+        assert!(url.contains("&X-Amz-Signature="), "{}", url);
+    }
+
+    /// Serve one (status line, body) response per connection, returning the raw requests
+    fn serve(responses: Vec<(&'static str, &'static str)>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).unwrap();
+                        if n == 0 {
+                            break;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    stream.write_all(response.as_bytes()).unwrap();
+                    String::from_utf8_lossy(&request).into_owned()
+                })
+                .collect()
+        });
+        (endpoint, server)
+    }
+
+    #[test]
+    fn test_list_follows_continuation_and_reports_errors() {
+        let _guard = crate::http::proxy::tests::PROXY_TEST_LOCK.lock().unwrap();
+        assert_eq!(crate::http::proxy::tests::set_proxy(""), 0);
+
+        let page1 = "<R><IsTruncated>true</IsTruncated><Key>a</Key><NextContinuationToken>t/1</NextContinuationToken></R>";
+        let page2 = "<R><IsTruncated>false</IsTruncated><Key>b</Key></R>";
+        let missing = "<Error><Code>NoSuchKey</Code><Message>The key does not exist</Message></Error>";
+        let (endpoint, server) = serve(vec![("200 OK", page1), ("200 OK", page2), ("404 Not Found", missing)]);
+        let config = test_config(&endpoint);
+
+        let keys = list_keys(&config, "media", "").unwrap();
+        let err = send(&config, "GET", &object_path("media", "gone"), Vec::new(), None).unwrap_err();
+        crate::http::proxy::naml_net_http_client_reset_proxy();
+
+        let requests = server.join().unwrap();
+        assert_eq!(keys, vec!["a", "b"]);
+        assert!(requests[0].starts_with("GET /media?list-type=2&prefix= HTTP/1.1"), "{}", requests[0]);
+        assert!(requests[1].contains("continuation-token=t%2F1"), "{}", requests[1]);
+        assert!(
+            requests[0].to_lowercase().contains("authorization: aws4-hmac-sha256 credential=akid/"),
+            "{}",
+            requests[0]
+        );
+        match err {
+            S3Error::Status { message, code } => {
+                assert_eq!(code, 404);
+                assert!(message.ends_with("404: NoSuchKey: The key does not exist"), "{}", message);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
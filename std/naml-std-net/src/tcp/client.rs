@@ -188,7 +188,7 @@ pub unsafe extern "C" fn naml_net_tcp_client_write(
     };
 
     let len = unsafe { (*data).len };
-    let bytes = unsafe { std::slice::from_raw_parts((*data).data.as_ptr(), len) };
+    let bytes = unsafe { std::slice::from_raw_parts((*data).as_ptr(), len) };
 
     match stream.write_all(bytes) {
         Ok(()) => {
@@ -250,7 +250,7 @@ mod tests {
             let len = (*bytes_ptr).len;
             let mut result = Vec::with_capacity(len);
             for i in 0..len {
-                result.push(*(*bytes_ptr).data.as_ptr().add(i));
+                result.push(*(*bytes_ptr).as_ptr().add(i));
             }
             result
         }
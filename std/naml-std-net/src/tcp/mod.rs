@@ -6,6 +6,7 @@
 //! ## Server Functions (std::net::tcp::server)
 //!
 //! - `listen(addr: string) -> tcp_listener` - Start a TCP listener
+//! - `listen_backlog(addr: string, backlog: int) -> tcp_listener` - Start a listener with a set backlog
 //! - `accept(listener: tcp_listener) -> tcp_socket` - Accept connection
 //!
 //! ## Client Functions (std::net::tcp::client)
@@ -16,10 +17,16 @@
 //! - `write(socket: tcp_socket, data: bytes)` - Write data to socket
 //! - `close(socket: tcp_socket)` - Close socket
 //! - `set_timeout(socket: tcp_socket, ms: int)` - Set socket timeout
+//! - `set_nodelay(socket: tcp_socket, enabled: bool)` - Disable Nagle's algorithm
+//! - `set_keepalive(socket: tcp_socket, idle_ms: int, interval_ms: int, count: int)` - Configure keepalive
+//! - `set_recv_buffer(socket: tcp_socket, size: int)` - Set the receive buffer size
+//! - `set_linger(socket: tcp_socket, ms: int)` - Set the linger timeout on close
 //!
 
 pub(crate) mod client;
+pub(crate) mod options;
 pub(crate) mod server;
 
 pub use client::*;
+pub use options::*;
 pub use server::*;
@@ -0,0 +1,193 @@
+//!
+//! TCP Socket Options
+//!
+//! Tunes connected TCP sockets for naml programs. Each function looks up the
+//! socket by handle and applies the option through `socket2`, so the same
+//! code covers Unix and Windows.
+//!
+//! ## Functions
+//!
+//! - `naml_net_tcp_client_set_nodelay` - Enable or disable Nagle's algorithm
+//! - `naml_net_tcp_client_set_keepalive` - Configure TCP keepalive probes
+//! - `naml_net_tcp_client_set_recv_buffer` - Set the receive buffer size
+//! - `naml_net_tcp_client_set_linger` - Set the linger timeout used on close
+//!
+//! All functions return 0 on success, or -1 with a NetworkError set.
+//!
+
+use std::io;
+use std::time::Duration;
+
+use socket2::{SockRef, TcpKeepalive};
+
+use crate::errors::throw_network_error;
+
+use super::server::get_sockets;
+
+/// Apply an option to the socket behind `socket_handle`
+fn with_socket<F>(socket_handle: i64, apply: F) -> i64
+where
+    F: FnOnce(SockRef<'_>) -> io::Result<()>,
+{
+    let sockets = get_sockets().lock().unwrap();
+
+    let result = match sockets.get(&socket_handle) {
+        Some(stream) => apply(SockRef::from(stream)),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Invalid socket handle",
+        )),
+    };
+    drop(sockets);
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            throw_network_error(e);
+            -1
+        }
+    }
+}
+
+/// Enable or disable `TCP_NODELAY`
+///
+/// With nodelay enabled, small writes are sent immediately instead of being
+/// coalesced by Nagle's algorithm.
+///
+/// # Arguments
+/// * `socket_handle` - Handle to the TCP socket
+/// * `enabled` - Non-zero to disable Nagle's algorithm
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_net_tcp_client_set_nodelay(socket_handle: i64, enabled: i64) -> i64 {
+    with_socket(socket_handle, |sock| sock.set_tcp_nodelay(enabled != 0))
+}
+
+/// Configure TCP keepalive
+///
+/// # Arguments
+/// * `socket_handle` - Handle to the TCP socket
+/// * `idle_ms` - Idle time before the first probe (0 or less disables keepalive)
+/// * `interval_ms` - Time between probes (0 or less keeps the OS default)
+/// * `count` - Unanswered probes before the connection drops (0 or less keeps the OS default)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_net_tcp_client_set_keepalive(
+    socket_handle: i64,
+    idle_ms: i64,
+    interval_ms: i64,
+    count: i64,
+) -> i64 {
+    with_socket(socket_handle, |sock| {
+        if idle_ms <= 0 {
+            return sock.set_keepalive(false);
+        }
+
+        let mut params = TcpKeepalive::new().with_time(Duration::from_millis(idle_ms as u64));
+        #[cfg(not(any(target_os = "openbsd", target_os = "haiku", target_os = "solaris")))]
+        {
+            if interval_ms > 0 {
+                params = params.with_interval(Duration::from_millis(interval_ms as u64));
+            }
+            if count > 0 {
+                params = params.with_retries(count.min(u32::MAX as i64) as u32);
+            }
+        }
+        #[cfg(any(target_os = "openbsd", target_os = "haiku", target_os = "solaris"))]
+        let _ = (interval_ms, count);
+
+        sock.set_tcp_keepalive(&params)
+    })
+}
+
+/// Set the size of the socket's receive buffer (`SO_RCVBUF`)
+///
+/// The OS may round or clamp the requested size.
+///
+/// # Arguments
+/// * `socket_handle` - Handle to the TCP socket
+/// * `size` - Buffer size in bytes (must be positive)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_net_tcp_client_set_recv_buffer(socket_handle: i64, size: i64) -> i64 {
+    with_socket(socket_handle, |sock| {
+        if size <= 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Receive buffer size must be positive",
+            ));
+        }
+        sock.set_recv_buffer_size(size as usize)
+    })
+}
+
+/// Set the linger timeout (`SO_LINGER`) applied when the socket closes
+///
+/// A linger of 0 resets the connection on close instead of performing the
+/// normal shutdown handshake.
+///
+/// # Arguments
+/// * `socket_handle` - Handle to the TCP socket
+/// * `ms` - Linger timeout in milliseconds (negative disables linger)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_net_tcp_client_set_linger(socket_handle: i64, ms: i64) -> i64 {
+    let linger = if ms < 0 {
+        None
+    } else {
+        Some(Duration::from_millis(ms as u64))
+    };
+    with_socket(socket_handle, |sock| sock.set_linger(linger))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+
+    use crate::tcp::server::next_handle;
+
+    /// Register a connected socket and return its handle with the peer stream
+    fn connected_handle() -> (i64, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        let handle = next_handle();
+        get_sockets().lock().unwrap().insert(handle, client);
+        (handle, server)
+    }
+
+    #[test]
+    fn test_set_options() {
+        let (handle, _peer) = connected_handle();
+
+        assert_eq!(naml_net_tcp_client_set_nodelay(handle, 1), 0);
+        assert_eq!(naml_net_tcp_client_set_keepalive(handle, 30_000, 5_000, 3), 0);
+        assert_eq!(naml_net_tcp_client_set_recv_buffer(handle, 64 * 1024), 0);
+        assert_eq!(naml_net_tcp_client_set_linger(handle, 1_000), 0);
+
+        {
+            let sockets = get_sockets().lock().unwrap();
+            let sock = SockRef::from(sockets.get(&handle).unwrap());
+            assert!(sock.tcp_nodelay().unwrap());
+            assert!(sock.keepalive().unwrap());
+            assert_eq!(sock.linger().unwrap(), Some(Duration::from_secs(1)));
+        }
+
+        assert_eq!(naml_net_tcp_client_set_keepalive(handle, 0, 0, 0), 0);
+        assert_eq!(naml_net_tcp_client_set_linger(handle, -1), 0);
+        {
+            let sockets = get_sockets().lock().unwrap();
+            let sock = SockRef::from(sockets.get(&handle).unwrap());
+            assert!(!sock.keepalive().unwrap());
+            assert_eq!(sock.linger().unwrap(), None);
+        }
+
+        get_sockets().lock().unwrap().remove(&handle);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let (handle, _peer) = connected_handle();
+        assert_eq!(naml_net_tcp_client_set_recv_buffer(handle, 0), -1);
+        get_sockets().lock().unwrap().remove(&handle);
+
+        assert_eq!(naml_net_tcp_client_set_nodelay(99999, 1), -1);
+    }
+}
@@ -6,6 +6,7 @@
 //! ## Functions
 //!
 //! - `naml_net_tcp_server_listen` - Bind and listen on an address
+//! - `naml_net_tcp_server_listen_backlog` - Bind and listen with an explicit backlog
 //! - `naml_net_tcp_server_accept` - Accept an incoming connection
 //!
 //! ## Handle Management
@@ -16,9 +17,11 @@
 //!
 
 use std::collections::HashMap;
-use std::net::{TcpListener, TcpStream};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
 use std::sync::{Mutex, OnceLock};
 
+use socket2::{Domain, Protocol, Socket, Type};
+
 use naml_std_core::{naml_string_new, NamlString};
 
 use crate::errors::{string_from_naml, throw_network_error};
@@ -56,15 +59,61 @@ pub(crate) fn next_handle() -> i64 {
 /// * `address` - The address to bind to (e.g., "127.0.0.1:8080" or ":8080")
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_net_tcp_server_listen(address: *const NamlString) -> i64 {
-    let addr_str = unsafe { string_from_naml(address) };
+    let bind_addr = bind_address(unsafe { string_from_naml(address) });
+    register_listener(TcpListener::bind(&bind_addr))
+}
+
+/// Bind and listen on the given address with an explicit accept backlog
+///
+/// Returns a handle to the TCP listener, or -1 if an error occurred.
+/// On error, a NetworkError exception is set.
+///
+/// # Arguments
+/// * `address` - The address to bind to (e.g., "127.0.0.1:8080" or ":8080")
+/// * `backlog` - Maximum number of pending connections (must be positive)
+///
+/// # Safety
+/// The caller must ensure `address` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_net_tcp_server_listen_backlog(
+    address: *const NamlString,
+    backlog: i64,
+) -> i64 {
+    let bind_addr = bind_address(unsafe { string_from_naml(address) });
+    register_listener(listen_with_backlog(&bind_addr, backlog))
+}
 
-    let bind_addr = if addr_str.starts_with(':') {
+/// Expand a bare ":port" address to listen on all interfaces
+fn bind_address(addr_str: String) -> String {
+    if addr_str.starts_with(':') {
         format!("0.0.0.0{}", addr_str)
     } else {
         addr_str
-    };
+    }
+}
+
+fn listen_with_backlog(bind_addr: &str, backlog: i64) -> std::io::Result<TcpListener> {
+    if backlog <= 0 || backlog > i32::MAX as i64 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Listen backlog must be a positive int32",
+        ));
+    }
+    let addr = bind_addr.to_socket_addrs()?.next().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "Address resolved to nothing")
+    })?;
+
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    // Match TcpListener::bind, which sets SO_REUSEADDR on Unix
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(backlog as i32)?;
+    Ok(socket.into())
+}
 
-    match TcpListener::bind(&bind_addr) {
+fn register_listener(result: std::io::Result<TcpListener>) -> i64 {
+    match result {
         Ok(listener) => {
             let handle = next_handle();
             get_listeners().lock().unwrap().insert(handle, listener);
@@ -228,6 +277,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_listen_backlog() {
+        unsafe {
+            let addr = naml_string_new(b"127.0.0.1:0".as_ptr(), 11);
+            let handle = naml_net_tcp_server_listen_backlog(addr, 16);
+            assert!(handle > 0, "Failed to create listener");
+
+            let local_addr = string_from_naml(naml_net_tcp_server_local_addr(handle));
+            let client = TcpStream::connect(&local_addr);
+            assert!(client.is_ok());
+
+            let socket_handle = naml_net_tcp_server_accept(handle);
+            assert!(socket_handle > 0);
+            naml_net_tcp_server_close(handle);
+
+            assert_eq!(naml_net_tcp_server_listen_backlog(addr, 0), -1);
+        }
+    }
+
     #[test]
     fn test_accept_invalid_handle() {
         let handle = naml_net_tcp_server_accept(99999);
@@ -168,7 +168,7 @@ pub unsafe extern "C" fn naml_net_tls_client_write(
     };
 
     let len = unsafe { (*data).len };
-    let bytes = unsafe { std::slice::from_raw_parts((*data).data.as_ptr(), len) };
+    let bytes = unsafe { std::slice::from_raw_parts((*data).as_ptr(), len) };
 
     match stream.write_all(bytes) {
         Ok(()) => {
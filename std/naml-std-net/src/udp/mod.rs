@@ -133,7 +133,7 @@ pub unsafe extern "C" fn naml_net_udp_send(
     };
 
     let len = unsafe { (*data).len };
-    let bytes = unsafe { std::slice::from_raw_parts((*data).data.as_ptr(), len) };
+    let bytes = unsafe { std::slice::from_raw_parts((*data).as_ptr(), len) };
 
     match socket.send_to(bytes, &addr_str) {
         Ok(n) => n as i64,
@@ -236,10 +236,7 @@ pub extern "C" fn naml_net_udp_receive_from(socket_handle: i64, size: i64) -> *m
         Ok((n, addr)) => {
             unsafe {
                 // Create data array
-                let data_arr = naml_std_core::naml_array_new(n);
-                for &byte in buffer[..n].iter() {
-                    naml_std_core::naml_array_push(data_arr, byte as i64);
-                }
+                let data_arr = naml_std_core::naml_array_from_bytes(&buffer[..n]);
 
                 // Create address string
                 let addr_str = addr.to_string();
@@ -329,7 +326,7 @@ mod tests {
             let len = (*bytes_ptr).len;
             let mut result = Vec::with_capacity(len);
             for i in 0..len {
-                result.push(*(*bytes_ptr).data.as_ptr().add(i));
+                result.push(*(*bytes_ptr).as_ptr().add(i));
             }
             result
         }
@@ -0,0 +1,176 @@
+///
+/// std::os::keyring - Platform Credential Store
+///
+/// Stores passwords and tokens in the operating system's credential store
+/// instead of plaintext dotfiles:
+///   - macOS: Keychain
+///   - Windows: Credential Manager
+///   - Linux/BSD: Secret Service (GNOME Keyring, KWallet) over D-Bus
+///
+/// Credentials are addressed by a service name and a user name. Passwords
+/// are passed in and handed back as `secret` values, so they are redacted
+/// when printed and zeroized when released.
+///
+/// Failures throw OSError. A missing credential uses code ENOENT; every
+/// other failure (locked store, no Secret Service running, access denied)
+/// uses -1 and carries the platform's message.
+///
+/// Functions:
+/// - `naml_os_keyring_set_password(service, user, password)` — Store or replace a credential
+/// - `naml_os_keyring_get_password(service, user) -> secret` — Read a credential
+/// - `naml_os_keyring_delete_password(service, user)` — Remove a credential
+///
+
+use naml_std_core::bytes::NamlBytes;
+use naml_std_core::NamlString;
+use naml_std_crypto::create_secret_from;
+
+use super::throw_os_error;
+
+fn string_as_str(s: *const NamlString) -> &'static str {
+    unsafe {
+        if s.is_null() {
+            return "";
+        }
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+        std::str::from_utf8_unchecked(slice)
+    }
+}
+
+fn bytes_as_slice(b: *const NamlBytes) -> &'static [u8] {
+    unsafe {
+        if b.is_null() {
+            return &[];
+        }
+        std::slice::from_raw_parts((*b).as_ptr(), (*b).len)
+    }
+}
+
+fn throw_keyring_error(action: &str, service: &str, user: &str, err: ::keyring::Error) {
+    let code = match err {
+        ::keyring::Error::NoEntry => libc::ENOENT,
+        _ => -1,
+    };
+    let message = format!("{} for service '{}' user '{}': {}", action, service, user, err);
+    throw_os_error(&message, code);
+}
+
+fn open_entry(action: &str, service: &str, user: &str) -> Option<::keyring::Entry> {
+    match ::keyring::Entry::new(service, user) {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            throw_keyring_error(action, service, user, e);
+            None
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `service` is a valid pointer to a NamlString, `user`
+/// is a valid pointer to a NamlString and `password` is a valid pointer to a
+/// NamlBytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_os_keyring_set_password(
+    service: *const NamlString,
+    user: *const NamlString,
+    password: *const NamlBytes,
+) {
+    let service = string_as_str(service);
+    let user = string_as_str(user);
+    let action = "failed to store password";
+
+    let Ok(password) = std::str::from_utf8(bytes_as_slice(password)) else {
+        throw_os_error(&format!("{}: password is not valid UTF-8", action), -1);
+        return;
+    };
+    if let Some(entry) = open_entry(action, service, user)
+        && let Err(e) = entry.set_password(password)
+    {
+        throw_keyring_error(action, service, user, e);
+    }
+}
+
+/// # Safety
+/// The caller must ensure `service` is a valid pointer to a NamlString and
+/// `user` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_os_keyring_get_password(
+    service: *const NamlString,
+    user: *const NamlString,
+) -> *mut NamlBytes {
+    let service = string_as_str(service);
+    let user = string_as_str(user);
+    let action = "failed to read password";
+
+    let Some(entry) = open_entry(action, service, user) else {
+        return create_secret_from(&[]);
+    };
+    match entry.get_password() {
+        Ok(password) => {
+            // Wipe the intermediate copy once it has been moved into the secret
+            let password = zeroize::Zeroizing::new(password);
+            create_secret_from(password.as_bytes())
+        }
+        Err(e) => {
+            throw_keyring_error(action, service, user, e);
+            create_secret_from(&[])
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `service` is a valid pointer to a NamlString and
+/// `user` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_os_keyring_delete_password(
+    service: *const NamlString,
+    user: *const NamlString,
+) {
+    let service = string_as_str(service);
+    let user = string_as_str(user);
+    let action = "failed to delete password";
+
+    if let Some(entry) = open_entry(action, service, user)
+        && let Err(e) = entry.delete_credential()
+    {
+        throw_keyring_error(action, service, user, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use naml_std_core::{naml_exception_clear, naml_exception_get_type_id, EXCEPTION_TYPE_OS_ERROR};
+
+    fn make_string(s: &str) -> *mut NamlString {
+        unsafe { naml_std_core::naml_string_new(s.as_ptr(), s.len()) }
+    }
+
+    #[test]
+    fn test_missing_credential_throws_enoent() {
+        // The mock store keeps nothing between entries, so every lookup misses
+        ::keyring::set_default_credential_builder(::keyring::mock::default_credential_builder());
+        unsafe {
+            naml_exception_clear();
+            let secret = naml_os_keyring_get_password(make_string("naml-test"), make_string("nobody"));
+            assert!(!secret.is_null());
+            assert_eq!(naml_exception_get_type_id(), EXCEPTION_TYPE_OS_ERROR);
+
+            let exc = naml_std_core::naml_exception_get();
+            let code = *(exc.add(16) as *const i64);
+            assert_eq!(code, libc::ENOENT as i64);
+            naml_exception_clear();
+        }
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_utf8() {
+        unsafe {
+            naml_exception_clear();
+            let password = create_secret_from(&[0xff, 0xfe]);
+            naml_os_keyring_set_password(make_string("naml-test"), make_string("user"), password);
+            assert_eq!(naml_exception_get_type_id(), EXCEPTION_TYPE_OS_ERROR);
+            naml_exception_clear();
+        }
+    }
+}
@@ -21,6 +21,16 @@
 /// - `getegid() -> int` - Get effective group ID
 /// - `getgroups() -> [int] throws OSError` - Get supplementary group list
 ///
+/// ## Credential Store
+///
+/// - `keyring::set_password(service, user, password: secret) throws OSError`
+/// - `keyring::get_password(service, user) -> secret throws OSError`
+/// - `keyring::delete_password(service, user) throws OSError`
+///
+/// ## Network Interfaces
+///
+/// - `net::interfaces() -> [iface_info] throws OSError`
+///
 /// ## Platform Notes
 ///
 /// System information functions work cross-platform via Rust's std library.
@@ -31,40 +41,49 @@
 ///   - Windows: %LOCALAPPDATA%, %APPDATA%
 ///
 
+pub mod keyring;
+pub mod net;
+pub use self::keyring::*;
+pub use self::net::*;
+
 use naml_std_core::{
     naml_array_new, naml_array_push, naml_exception_set_typed, naml_stack_capture,
-    naml_string_new, naml_struct_new, naml_struct_set_field, NamlArray, NamlString, NamlStruct,
-    EXCEPTION_TYPE_OS_ERROR,
+    naml_string_new, NamlArray, NamlString, EXCEPTION_TYPE_OS_ERROR,
 };
 
-const OS_ERROR_STRUCT_TYPE_ID: u32 = 0xFFFF_0008;
-
 unsafe fn naml_from_string(s: &str) -> *mut NamlString {
     unsafe { naml_string_new(s.as_ptr(), s.len()) }
 }
 
+/// Allocate an OSError using the exception layout: message at offset 0,
+/// stack at offset 8, code at offset 16
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_os_error_new(
     message: *const NamlString,
     code: i64,
-) -> *mut NamlStruct {
+) -> *mut u8 {
     unsafe {
-        let exc = naml_struct_new(OS_ERROR_STRUCT_TYPE_ID, 2);
-        naml_struct_set_field(exc, 0, message as i64);
-        naml_struct_set_field(exc, 1, code);
-        exc
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate OSError");
+        }
+        *(ptr as *mut i64) = message as i64;
+        *(ptr.add(8) as *mut i64) = 0;
+        *(ptr.add(16) as *mut i64) = code;
+        ptr
     }
 }
 
-fn throw_os_error(message: &str, code: i32) {
+pub(crate) fn throw_os_error(message: &str, code: i32) {
     unsafe {
         let message_ptr = naml_string_new(message.as_ptr(), message.len());
         let exc = naml_os_error_new(message_ptr, code as i64);
 
         let stack = naml_stack_capture();
-        *(exc as *mut u8).add(8).cast::<*mut u8>() = stack;
+        *(exc.add(8) as *mut *mut u8) = stack;
 
-        naml_exception_set_typed(exc as *mut u8, EXCEPTION_TYPE_OS_ERROR);
+        naml_exception_set_typed(exc, EXCEPTION_TYPE_OS_ERROR);
     }
 }
 
@@ -335,7 +354,7 @@ mod tests {
         let result = naml_os_hostname();
         assert!(!result.is_null());
         let name = unsafe {
-            let slice = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let slice = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             String::from_utf8_lossy(slice).into_owned()
         };
         assert!(!name.is_empty());
@@ -346,7 +365,7 @@ mod tests {
         let result = naml_os_temp_dir();
         assert!(!result.is_null());
         let path = unsafe {
-            let slice = std::slice::from_raw_parts((*result).data.as_ptr(), (*result).len);
+            let slice = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
             String::from_utf8_lossy(slice).into_owned()
         };
         assert!(!path.is_empty());
@@ -0,0 +1,340 @@
+///
+/// std::os::net - Network Interface Enumeration
+///
+/// Lists the host's network interfaces with their addresses, hardware
+/// address, state flags and MTU, so programs can report host addressing
+/// without parsing `ip addr` or `ifconfig` output.
+///
+/// Each interface is an `iface_info` record:
+///   - `name: string` - interface name (`eth0`, `en0`, `Ethernet`)
+///   - `ips: [string]` - IPv4 and IPv6 addresses, without prefix length
+///   - `mac: string` - hardware address as `aa:bb:cc:dd:ee:ff`, empty if none
+///   - `flags: [string]` - any of `up`, `running`, `loopback`, `broadcast`,
+///     `point_to_point`, `multicast`
+///   - `mtu: int` - maximum transmission unit, 0 if unknown
+///
+/// Backed by getifaddrs on Unix and GetAdaptersAddresses on Windows.
+/// Failures throw OSError carrying the platform error code.
+///
+/// Functions:
+/// - `naml_os_net_interfaces() -> [iface_info]` — Enumerate network interfaces
+///
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use naml_std_core::{
+    naml_array_new, naml_array_push, naml_struct_new, naml_struct_set_field, NamlArray,
+    NamlStruct,
+};
+
+use super::{naml_from_string, throw_os_error};
+
+/// Type id reserved for the built-in iface_info record
+const IFACE_INFO_STRUCT_TYPE_ID: u32 = 0xFFFF_0012;
+
+#[derive(Debug, Default)]
+struct Interface {
+    name: String,
+    ips: Vec<String>,
+    mac: String,
+    flags: Vec<&'static str>,
+    mtu: i64,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_os_net_interfaces() -> *mut NamlArray {
+    match list_interfaces() {
+        Ok(interfaces) => unsafe {
+            let arr = naml_array_new(interfaces.len());
+            for iface in &interfaces {
+                naml_array_push(arr, iface_to_naml(iface) as i64);
+            }
+            arr
+        },
+        Err((message, code)) => {
+            throw_os_error(&message, code);
+            unsafe { naml_array_new(0) }
+        }
+    }
+}
+
+unsafe fn iface_to_naml(iface: &Interface) -> *mut NamlStruct {
+    unsafe {
+        let ips = naml_array_new(iface.ips.len());
+        for ip in &iface.ips {
+            naml_array_push(ips, naml_from_string(ip) as i64);
+        }
+        let flags = naml_array_new(iface.flags.len());
+        for flag in &iface.flags {
+            naml_array_push(flags, naml_from_string(flag) as i64);
+        }
+
+        let info = naml_struct_new(IFACE_INFO_STRUCT_TYPE_ID, 5);
+        naml_struct_set_field(info, 0, naml_from_string(&iface.name) as i64);
+        naml_struct_set_field(info, 1, ips as i64);
+        naml_struct_set_field(info, 2, naml_from_string(&iface.mac) as i64);
+        naml_struct_set_field(info, 3, flags as i64);
+        naml_struct_set_field(info, 4, iface.mtu);
+        info
+    }
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// getifaddrs reports one entry per address, so interfaces are merged by
+/// name in the order they first appear
+#[cfg(unix)]
+fn entry<'a>(interfaces: &'a mut Vec<Interface>, name: &str) -> &'a mut Interface {
+    let idx = match interfaces.iter().position(|i| i.name == name) {
+        Some(idx) => idx,
+        None => {
+            interfaces.push(Interface {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            interfaces.len() - 1
+        }
+    };
+    &mut interfaces[idx]
+}
+
+#[cfg(unix)]
+fn flag_names(flags: u32) -> Vec<&'static str> {
+    let known = [
+        (libc::IFF_UP, "up"),
+        (libc::IFF_RUNNING, "running"),
+        (libc::IFF_LOOPBACK, "loopback"),
+        (libc::IFF_BROADCAST, "broadcast"),
+        (libc::IFF_POINTOPOINT, "point_to_point"),
+        (libc::IFF_MULTICAST, "multicast"),
+    ];
+    known
+        .iter()
+        .filter(|(bit, _)| flags & (*bit as u32) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+#[cfg(unix)]
+fn list_interfaces() -> Result<Vec<Interface>, (String, i32)> {
+    let mut head: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut head) } != 0 {
+        let errno = std::io::Error::last_os_error().raw_os_error().unwrap_or(-1);
+        return Err(("failed to enumerate network interfaces".to_string(), errno));
+    }
+
+    let mut interfaces = Vec::new();
+    let mut cursor = head;
+    while !cursor.is_null() {
+        let ifa = unsafe { &*cursor };
+        cursor = ifa.ifa_next;
+        if ifa.ifa_name.is_null() {
+            continue;
+        }
+        let name = unsafe { std::ffi::CStr::from_ptr(ifa.ifa_name) }.to_string_lossy();
+        let iface = entry(&mut interfaces, &name);
+        iface.flags = flag_names(ifa.ifa_flags as u32);
+        if !ifa.ifa_addr.is_null() {
+            unsafe { read_address(iface, ifa) };
+        }
+    }
+    unsafe { libc::freeifaddrs(head) };
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    for iface in &mut interfaces {
+        iface.mtu = interface_mtu(&iface.name);
+    }
+
+    Ok(interfaces)
+}
+
+#[cfg(unix)]
+unsafe fn read_address(iface: &mut Interface, ifa: &libc::ifaddrs) {
+    unsafe {
+        match (*ifa.ifa_addr).sa_family as libc::c_int {
+            libc::AF_INET => {
+                let addr = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+                let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+                iface.ips.push(ip.to_string());
+            }
+            libc::AF_INET6 => {
+                let addr = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+                iface.ips.push(Ipv6Addr::from(addr.sin6_addr.s6_addr).to_string());
+            }
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            libc::AF_PACKET => {
+                let addr = &*(ifa.ifa_addr as *const libc::sockaddr_ll);
+                let len = (addr.sll_halen as usize).min(addr.sll_addr.len());
+                iface.mac = format_mac(&addr.sll_addr[..len]);
+            }
+            #[cfg(target_vendor = "apple")]
+            libc::AF_LINK => {
+                // LLADDR: the hardware address follows the name in sdl_data
+                let addr = &*(ifa.ifa_addr as *const libc::sockaddr_dl);
+                let data = (addr.sdl_data.as_ptr() as *const u8).add(addr.sdl_nlen as usize);
+                iface.mac = format_mac(std::slice::from_raw_parts(data, addr.sdl_alen as usize));
+                if !ifa.ifa_data.is_null() {
+                    iface.mtu = (*(ifa.ifa_data as *const libc::if_data)).ifi_mtu as i64;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn interface_mtu(name: &str) -> i64 {
+    unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0);
+        if fd < 0 {
+            return 0;
+        }
+        let mut req: libc::ifreq = std::mem::zeroed();
+        for (dst, src) in req.ifr_name.iter_mut().zip(name.bytes().take(libc::IFNAMSIZ - 1)) {
+            *dst = src as libc::c_char;
+        }
+        let rc = libc::ioctl(fd, libc::SIOCGIFMTU as _, &mut req);
+        libc::close(fd);
+        if rc < 0 { 0 } else { req.ifr_ifru.ifru_mtu as i64 }
+    }
+}
+
+#[cfg(windows)]
+fn list_interfaces() -> Result<Vec<Interface>, (String, i32)> {
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER, GAA_FLAG_SKIP_MULTICAST,
+        GetAdaptersAddresses, IP_ADAPTER_ADDRESSES_LH,
+    };
+    use windows_sys::Win32::Networking::WinSock::{
+        AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6,
+    };
+
+    const ERROR_BUFFER_OVERFLOW: u32 = 111;
+    const IF_TYPE_SOFTWARE_LOOPBACK: u32 = 24;
+    const IF_OPER_STATUS_UP: i32 = 1;
+    const IP_ADAPTER_NO_MULTICAST: u32 = 0x10;
+
+    let query = GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER;
+    // Start at the 15 KB Microsoft recommends; retry if adapters were added
+    // between the size query and the call
+    let mut size: u32 = 15 * 1024;
+    let mut buffer: Vec<u64> = Vec::new();
+    let mut rc = ERROR_BUFFER_OVERFLOW;
+    for _ in 0..3 {
+        buffer = vec![0u64; (size as usize).div_ceil(8)];
+        rc = unsafe {
+            GetAdaptersAddresses(
+                AF_UNSPEC as u32,
+                query,
+                std::ptr::null(),
+                buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH,
+                &mut size,
+            )
+        };
+        if rc != ERROR_BUFFER_OVERFLOW {
+            break;
+        }
+    }
+    if rc != 0 {
+        return Err(("failed to enumerate network interfaces".to_string(), rc as i32));
+    }
+
+    let mut interfaces = Vec::new();
+    let mut adapter = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+    while !adapter.is_null() {
+        let a = unsafe { &*adapter };
+        adapter = a.Next;
+
+        let mut iface = Interface {
+            name: unsafe { wide_to_string(a.FriendlyName) },
+            ..Default::default()
+        };
+        let mac_len = (a.PhysicalAddressLength as usize).min(a.PhysicalAddress.len());
+        iface.mac = format_mac(&a.PhysicalAddress[..mac_len]);
+        iface.mtu = a.Mtu as i64;
+        if a.OperStatus == IF_OPER_STATUS_UP {
+            iface.flags.push("up");
+            iface.flags.push("running");
+        }
+        if a.IfType == IF_TYPE_SOFTWARE_LOOPBACK {
+            iface.flags.push("loopback");
+        }
+        if unsafe { a.Anonymous2.Flags } & IP_ADAPTER_NO_MULTICAST == 0 {
+            iface.flags.push("multicast");
+        }
+
+        let mut unicast = a.FirstUnicastAddress;
+        while !unicast.is_null() {
+            let u = unsafe { &*unicast };
+            unicast = u.Next;
+            let sockaddr = u.Address.lpSockaddr;
+            if sockaddr.is_null() {
+                continue;
+            }
+            match unsafe { (*sockaddr).sa_family } {
+                AF_INET => {
+                    let sin = unsafe { &*(sockaddr as *const SOCKADDR_IN) };
+                    let raw = unsafe { sin.sin_addr.S_un.S_addr };
+                    iface.ips.push(Ipv4Addr::from(u32::from_be(raw)).to_string());
+                }
+                AF_INET6 => {
+                    let sin6 = unsafe { &*(sockaddr as *const SOCKADDR_IN6) };
+                    let bytes = unsafe { sin6.sin6_addr.u.Byte };
+                    iface.ips.push(Ipv6Addr::from(bytes).to_string());
+                }
+                _ => {}
+            }
+        }
+        interfaces.push(iface);
+    }
+    Ok(interfaces)
+}
+
+#[cfg(windows)]
+unsafe fn wide_to_string(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    unsafe {
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn list_interfaces() -> Result<Vec<Interface>, (String, i32)> {
+    Err(("interfaces not supported on this platform".to_string(), -1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_mac() {
+        assert_eq!(format_mac(&[0x02, 0xfc, 0, 0, 0, 0x01]), "02:fc:00:00:00:01");
+        assert_eq!(format_mac(&[]), "");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_list_interfaces_has_loopback() {
+        let interfaces = list_interfaces().unwrap();
+        let lo = interfaces
+            .iter()
+            .find(|i| i.flags.contains(&"loopback"))
+            .expect("no loopback interface");
+        assert!(lo.ips.iter().any(|ip| ip == "127.0.0.1" || ip == "::1"));
+        let names: std::collections::HashSet<_> = interfaces.iter().map(|i| &i.name).collect();
+        assert_eq!(names.len(), interfaces.len());
+    }
+}
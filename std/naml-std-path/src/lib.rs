@@ -19,11 +19,19 @@
 /// - `separator() -> string` - Get platform path separator
 /// - `to_slash(path: string) -> string` - Convert to forward slashes
 /// - `from_slash(path: string) -> string` - Convert from forward slashes
+/// - `absolute_lexical(path: string) -> string` - Join cwd and normalize without touching the filesystem
+/// - `relative_to(path: string, base: string) -> string` - Relative path from base to path
+/// - `safe_join(base: string, untrusted: string) -> string throws SecurityError` - Join
+///   untrusted input under base, rejecting `..` escapes and absolute/drive/UNC overrides
+/// - `is_within(base: string, path: string) -> bool` - Check that path stays inside base
 ///
 
 use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR};
 
-use naml_std_core::{naml_array_new, naml_array_push, naml_string_new, NamlArray, NamlString};
+use naml_std_core::{
+    naml_array_new, naml_array_push, naml_exception_set_typed, naml_stack_capture,
+    naml_string_new, NamlArray, NamlString, EXCEPTION_TYPE_SECURITY_ERROR,
+};
 
 /// Helper to extract string from NamlString pointer
 unsafe fn string_from_naml(s: *const NamlString) -> String {
@@ -31,7 +39,7 @@ unsafe fn string_from_naml(s: *const NamlString) -> String {
         return String::new();
     }
     unsafe {
-        let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
         String::from_utf8_lossy(slice).into_owned()
     }
 }
@@ -303,6 +311,195 @@ pub unsafe extern "C" fn naml_path_strip_prefix(
     unsafe { naml_from_string(&result) }
 }
 
+/// Resolve `.` and `..` purely by inspecting components.
+/// `..` never climbs above a root, and leading `..` on relative paths are kept.
+fn normalize_lexical(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => normalized.push(component.as_os_str()),
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::Prefix(_)) | Some(Component::RootDir) => {}
+                _ => normalized.push(".."),
+            },
+            Component::Normal(c) => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Make a path absolute against the current directory, then normalize it.
+/// Falls back to the normalized input if the current directory is unavailable.
+fn absolute_lexical(path: &Path) -> PathBuf {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) => cwd.join(path),
+            Err(_) => path.to_path_buf(),
+        }
+    };
+    normalize_lexical(&joined)
+}
+
+/// Compute the path that leads from `base` to `path`.
+/// Mixed absolute/relative inputs, or a base that climbs out with `..`,
+/// are resolved against the current directory first. Returns `path`
+/// unchanged when the two share no root (e.g. different Windows drives).
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let mut path = normalize_lexical(path);
+    let mut base = normalize_lexical(base);
+    if path.is_absolute() != base.is_absolute() || base.starts_with("..") {
+        path = absolute_lexical(&path);
+        base = absolute_lexical(&base);
+    }
+
+    let path_parts: Vec<Component> = path.components().collect();
+    let base_parts: Vec<Component> = base.components().collect();
+    if path.has_root() && path_parts.first() != base_parts.first() {
+        return path;
+    }
+
+    let common = path_parts
+        .iter()
+        .zip(base_parts.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut result = PathBuf::new();
+    for _ in &base_parts[common..] {
+        result.push("..");
+    }
+    for part in &path_parts[common..] {
+        result.push(part.as_os_str());
+    }
+    if result.as_os_str().is_empty() {
+        result.push(".");
+    }
+    result
+}
+
+/// Convert to an absolute, normalized path without resolving symlinks
+/// or requiring the path to exist
+/// absolute_lexical("out/../build/app") -> "/home/user/project/build/app"
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_path_absolute_lexical(path: *const NamlString) -> *mut NamlString {
+    let path_str = unsafe { string_from_naml(path) };
+    let result = absolute_lexical(Path::new(&path_str));
+    unsafe { naml_from_string(&result.to_string_lossy()) }
+}
+
+/// Express path relative to base
+/// relative_to("/a/b/c/d", "/a/b/x") -> "../c/d"
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString and `base`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_path_relative_to(
+    path: *const NamlString,
+    base: *const NamlString,
+) -> *mut NamlString {
+    let path_str = unsafe { string_from_naml(path) };
+    let base_str = unsafe { string_from_naml(base) };
+    let result = relative_path(Path::new(&path_str), Path::new(&base_str));
+    unsafe { naml_from_string(&result.to_string_lossy()) }
+}
+
+/// Join `untrusted` under `base`, refusing anything that could land outside it.
+/// Absolute paths, drive or UNC prefixes (Windows), NUL bytes, and `..`
+/// sequences that climb above `base` are all rejected.
+fn join_untrusted(base: &Path, untrusted: &str) -> Result<PathBuf, &'static str> {
+    if untrusted.contains('\0') {
+        return Err("path contains a NUL byte");
+    }
+
+    let mut depth: usize = 0;
+    for component in Path::new(untrusted).components() {
+        match component {
+            Component::Prefix(_) => return Err("drive or UNC prefix not allowed"),
+            Component::RootDir => return Err("absolute path not allowed"),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if depth == 0 {
+                    return Err("path escapes base directory");
+                }
+                depth -= 1;
+            }
+            Component::Normal(_) => depth += 1,
+        }
+    }
+    Ok(normalize_lexical(&base.join(untrusted)))
+}
+
+/// Check that `path` lies inside `base` once both are made absolute and normalized.
+/// Comparison is per component, so "/srv/app2" is not within "/srv/app".
+fn path_is_within(base: &Path, path: &Path) -> bool {
+    absolute_lexical(path).starts_with(absolute_lexical(base))
+}
+
+/// Throw SecurityError with layout: message@0, stack@8, path@16
+fn throw_security_error(message: &str, path: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate SecurityError");
+        }
+        let message = format!("{}: '{}'", message, path);
+        *(ptr as *mut *mut NamlString) = naml_from_string(&message);
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut *mut NamlString) = naml_from_string(path);
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_SECURITY_ERROR);
+    }
+}
+
+/// Join an untrusted relative path (e.g. from a URL or archive entry) under base
+/// safe_join("/srv/www", "css/site.css") -> "/srv/www/css/site.css"
+/// safe_join("/srv/www", "../etc/passwd") -> throws SecurityError
+///
+/// # Safety
+/// The caller must ensure `base` is a valid pointer to a NamlString and
+/// `untrusted` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_path_safe_join(
+    base: *const NamlString,
+    untrusted: *const NamlString,
+) -> *mut NamlString {
+    let base_str = unsafe { string_from_naml(base) };
+    let untrusted_str = unsafe { string_from_naml(untrusted) };
+    match join_untrusted(Path::new(&base_str), &untrusted_str) {
+        Ok(joined) => unsafe { naml_from_string(&joined.to_string_lossy()) },
+        Err(message) => {
+            throw_security_error(message, &untrusted_str);
+            unsafe { naml_from_string("") }
+        }
+    }
+}
+
+/// Check if path stays inside base, without touching the filesystem
+/// is_within("/srv/www", "/srv/www/a/../b") -> true
+///
+/// # Safety
+/// The caller must ensure `base` is a valid pointer to a NamlString and `path`
+/// is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_path_is_within(
+    base: *const NamlString,
+    path: *const NamlString,
+) -> i64 {
+    let base_str = unsafe { string_from_naml(base) };
+    let path_str = unsafe { string_from_naml(path) };
+    if path_is_within(Path::new(&base_str), Path::new(&path_str)) { 1 } else { 0 }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,4 +544,92 @@ mod tests {
             assert_eq!(result_str, "a/b/c");
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_absolute_lexical() {
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(absolute_lexical(Path::new("/a/b/../c/./d")), PathBuf::from("/a/c/d"));
+        assert_eq!(absolute_lexical(Path::new("/../x")), PathBuf::from("/x"));
+        assert_eq!(
+            absolute_lexical(Path::new("does-not-exist/../out/file.txt")),
+            cwd.join("out/file.txt")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_relative_to() {
+        let cases = [
+            ("/a/b/c/d", "/a/b/x", "../c/d"),
+            ("/a/b", "/a/b", "."),
+            ("/a/b/c", "/a", "b/c"),
+            ("/a", "/a/b/c", "../.."),
+            ("src/main.nm", "src", "main.nm"),
+            ("docs", "src/lib", "../../docs"),
+            ("../x", "y", "../../x"),
+        ];
+        for (path, base, expected) in cases {
+            assert_eq!(
+                relative_path(Path::new(path), Path::new(base)),
+                PathBuf::from(expected),
+                "{} from {}",
+                path,
+                base
+            );
+        }
+
+        // A base that climbs out of the cwd is resolved against it first
+        let cwd_name = std::env::current_dir().unwrap().file_name().unwrap().to_owned();
+        assert_eq!(
+            relative_path(Path::new("x"), Path::new("../y")),
+            Path::new("..").join(cwd_name).join("x")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_safe_join() {
+        let base = Path::new("/srv/www");
+        assert_eq!(join_untrusted(base, "css/site.css"), Ok(PathBuf::from("/srv/www/css/site.css")));
+        assert_eq!(join_untrusted(base, "a/../b/./c"), Ok(PathBuf::from("/srv/www/b/c")));
+        assert_eq!(join_untrusted(base, ""), Ok(PathBuf::from("/srv/www")));
+        assert_eq!(join_untrusted(base, "../etc/passwd"), Err("path escapes base directory"));
+        assert_eq!(join_untrusted(base, "a/../../etc"), Err("path escapes base directory"));
+        assert_eq!(join_untrusted(base, "/etc/passwd"), Err("absolute path not allowed"));
+        assert_eq!(join_untrusted(base, "a\0b"), Err("path contains a NUL byte"));
+        assert_eq!(join_untrusted(Path::new(""), "../x"), Err("path escapes base directory"));
+
+        unsafe {
+            let base = naml_from_string("/srv/www");
+            let bad = naml_from_string("../secret");
+            naml_std_core::naml_exception_clear();
+            naml_path_safe_join(base, bad);
+            assert_eq!(naml_std_core::naml_exception_get_type_id(), EXCEPTION_TYPE_SECURITY_ERROR);
+            let exc = naml_std_core::naml_exception_get();
+            assert_eq!(string_from_naml(*(exc.add(16) as *const *const NamlString)), "../secret");
+            naml_std_core::naml_exception_clear();
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_safe_join_windows_prefixes() {
+        let base = Path::new("C:\\srv\\www");
+        assert_eq!(join_untrusted(base, "D:evil"), Err("drive or UNC prefix not allowed"));
+        assert_eq!(join_untrusted(base, "\\\\server\\share\\x"), Err("drive or UNC prefix not allowed"));
+        assert_eq!(join_untrusted(base, "\\windows"), Err("absolute path not allowed"));
+        assert_eq!(join_untrusted(base, "..\\x"), Err("path escapes base directory"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_within() {
+        let base = Path::new("/srv/app");
+        assert!(path_is_within(base, Path::new("/srv/app")));
+        assert!(path_is_within(base, Path::new("/srv/app/data/../logs")));
+        assert!(!path_is_within(base, Path::new("/srv/app2")));
+        assert!(!path_is_within(base, Path::new("/srv/app/../other")));
+        assert!(path_is_within(Path::new("."), Path::new("src/main.nm")));
+    }
 }
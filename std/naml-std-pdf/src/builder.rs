@@ -0,0 +1,179 @@
+///
+/// PDF Builder
+///
+/// A document is a list of pages, each a list of text runs. Nothing is
+/// laid out until `to_bytes`, which writes every page with the standard
+/// Helvetica font, so generated files need no embedded fonts.
+///
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+
+pub type PdfResult<T> = Result<T, String>;
+
+/// Largest page width or height in points allowed by the PDF format
+pub const MAX_PAGE_SIZE: f64 = 14_400.0;
+
+/// Line spacing for multi-line text, as a multiple of the font size
+const LINE_HEIGHT: f64 = 1.2;
+
+struct TextRun {
+    x: f64,
+    y: f64,
+    size: f64,
+    text: String,
+}
+
+struct Page {
+    width: f64,
+    height: f64,
+    runs: Vec<TextRun>,
+}
+
+#[derive(Default)]
+pub struct PdfBuilder {
+    pages: Vec<Page>,
+}
+
+impl PdfBuilder {
+    /// Append a `width` x `height` page (in points) and return its index
+    pub fn add_page(&mut self, width: f64, height: f64) -> PdfResult<i64> {
+        for (name, value) in [("width", width), ("height", height)] {
+            if !(value > 0.0 && value <= MAX_PAGE_SIZE) {
+                return Err(format!("page {} must be between 0 and {} points, got {}", name, MAX_PAGE_SIZE, value));
+            }
+        }
+        self.pages.push(Page { width, height, runs: Vec::new() });
+        Ok(self.pages.len() as i64 - 1)
+    }
+
+    /// Draw `text` with its first baseline at (x, y), measured in points
+    /// from the bottom-left corner of the page. Each `\n` starts a new line.
+    pub fn draw_text(&mut self, page: i64, x: f64, y: f64, size: f64, text: &str) -> PdfResult<()> {
+        let count = self.pages.len();
+        let page = usize::try_from(page)
+            .ok()
+            .and_then(|i| self.pages.get_mut(i))
+            .ok_or_else(|| format!("page {} does not exist, the document has {} pages", page, count))?;
+        if !(size > 0.0 && size.is_finite()) {
+            return Err(format!("font size must be positive, got {}", size));
+        }
+        page.runs.push(TextRun { x, y, size, text: text.to_string() });
+        Ok(())
+    }
+
+    /// Render the document as PDF bytes
+    pub fn to_bytes(&self) -> PdfResult<Vec<u8>> {
+        if self.pages.is_empty() {
+            return Err("a PDF needs at least one page".to_string());
+        }
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut kids = Vec::with_capacity(self.pages.len());
+        for page in &self.pages {
+            let content = Content { operations: page_operations(page) };
+            let data = content.encode().map_err(|e| e.to_string())?;
+            let content_id = doc.add_object(Stream::new(dictionary! {}, data));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "MediaBox" => vec![0.into(), 0.into(), Object::Real(page.width as f32), Object::Real(page.height as f32)],
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Count" => self.pages.len() as i64,
+                "Kids" => kids,
+                "Resources" => resources_id,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.compress();
+
+        let mut out = Vec::new();
+        doc.save_to(&mut out).map_err(|e| e.to_string())?;
+        Ok(out)
+    }
+}
+
+fn page_operations(page: &Page) -> Vec<Operation> {
+    let mut ops = Vec::new();
+    for run in &page.runs {
+        ops.push(Operation::new("BT", vec![]));
+        ops.push(Operation::new("Tf", vec!["F1".into(), Object::Real(run.size as f32)]));
+        ops.push(Operation::new("TL", vec![Object::Real((run.size * LINE_HEIGHT) as f32)]));
+        ops.push(Operation::new("Td", vec![Object::Real(run.x as f32), Object::Real(run.y as f32)]));
+        for (i, line) in run.text.split('\n').enumerate() {
+            if i > 0 {
+                ops.push(Operation::new("T*", vec![]));
+            }
+            ops.push(Operation::new("Tj", vec![Object::string_literal(win_ansi(line))]));
+        }
+        ops.push(Operation::new("ET", vec![]));
+    }
+    ops
+}
+
+/// Encode text for the standard fonts. Latin-1 characters map to their
+/// own byte; anything the font cannot show becomes `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x20..=0x7E | 0xA0..=0xFF => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_and_validation() {
+        let mut pdf = PdfBuilder::default();
+        assert!(pdf.to_bytes().is_err());
+        assert_eq!(pdf.add_page(595.0, 842.0), Ok(0));
+        assert_eq!(pdf.add_page(612.0, 792.0), Ok(1));
+        assert!(pdf.add_page(0.0, 100.0).is_err());
+        assert!(pdf.add_page(100.0, 20_000.0).is_err());
+        assert!(pdf.draw_text(2, 10.0, 10.0, 12.0, "x").is_err());
+        assert!(pdf.draw_text(-1, 10.0, 10.0, 12.0, "x").is_err());
+        assert!(pdf.draw_text(0, 10.0, 10.0, 0.0, "x").is_err());
+        assert!(pdf.draw_text(0, 10.0, 10.0, 12.0, "x").is_ok());
+    }
+
+    #[test]
+    fn test_win_ansi() {
+        assert_eq!(win_ansi("Caf\u{e9} \u{20ac}5"), b"Caf\xe9 ?5");
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let mut pdf = PdfBuilder::default();
+        pdf.add_page(595.0, 842.0).unwrap();
+        pdf.draw_text(0, 72.0, 770.0, 18.0, "Quarterly report").unwrap();
+        let bytes = pdf.to_bytes().unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.5"));
+        let doc = Document::load_mem(&bytes).unwrap();
+        assert_eq!(doc.get_pages().len(), 1);
+    }
+}
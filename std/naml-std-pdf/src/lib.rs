@@ -0,0 +1,168 @@
+///
+/// naml-std-pdf - PDF Documents
+///
+/// Pulls text out of existing PDFs and builds simple text-only PDFs for
+/// reports, accessed as `std::doc::pdf`.
+///
+/// ## Functions
+///
+/// - `extract_text(path: string) -> string throws PdfError` - Text of every page
+/// - `page_count(path: string) -> int throws PdfError`
+/// - `create() -> int` - Start an empty document
+/// - `add_page(doc: int, width: float, height: float) -> int throws PdfError` - Returns the page index
+/// - `draw_text(doc: int, page: int, x: float, y: float, size: float, text: string) throws PdfError`
+/// - `to_bytes(doc: int) -> bytes throws PdfError` - Render the document
+/// - `save(doc: int, path: string) throws PdfError` - Render and write to a file
+/// - `close(doc: int)` - Release a document
+///
+/// ## Coordinates
+///
+/// Sizes and positions are in points (1/72 inch); A4 is 595 x 842 and US
+/// Letter is 612 x 792. `(x, y)` is the first baseline of the text,
+/// measured from the bottom-left corner of the page. Text uses the
+/// built-in Helvetica font, which covers Latin-1; other characters are
+/// drawn as `?`.
+///
+/// ## Handles
+///
+/// Documents being built live in a process-wide registry (the same scheme
+/// as sqlite connections) and stay allocated until `close`, including
+/// after `save`.
+///
+
+mod builder;
+mod read;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use naml_std_core::bytes::{naml_bytes_from, NamlBytes};
+use naml_std_core::{naml_exception_set_typed, naml_stack_capture, naml_string_new, NamlString, EXCEPTION_TYPE_PDF_ERROR};
+
+use builder::{PdfBuilder, PdfResult};
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static DOCUMENTS: LazyLock<Mutex<HashMap<i64, PdfBuilder>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+/// Throw PdfError with layout: message@0, stack@8
+fn throw_pdf_error(message: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(16, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate PdfError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_string_new(message.as_ptr(), message.len());
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_PDF_ERROR);
+    }
+}
+
+fn read_file(path: &str) -> PdfResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Run `f` on a document being built
+fn with_document<T>(handle: i64, f: impl FnOnce(&mut PdfBuilder) -> PdfResult<T>) -> PdfResult<T> {
+    match DOCUMENTS.lock().unwrap().get_mut(&handle) {
+        Some(doc) => f(doc),
+        None => Err(format!("unknown document handle {}", handle)),
+    }
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_pdf_extract_text(path: *const NamlString) -> *mut NamlString {
+    let path = unsafe { string_from_naml(path) };
+    match read_file(&path).and_then(|data| read::extract_text(&data)) {
+        Ok(text) => unsafe { naml_string_new(text.as_ptr(), text.len()) },
+        Err(message) => {
+            throw_pdf_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_pdf_page_count(path: *const NamlString) -> i64 {
+    let path = unsafe { string_from_naml(path) };
+    read_file(&path).and_then(|data| read::page_count(&data)).unwrap_or_else(|message| {
+        throw_pdf_error(&message);
+        0
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_pdf_create() -> i64 {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    DOCUMENTS.lock().unwrap().insert(handle, PdfBuilder::default());
+    handle
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_pdf_add_page(handle: i64, width: f64, height: f64) -> i64 {
+    with_document(handle, |doc| doc.add_page(width, height)).unwrap_or_else(|message| {
+        throw_pdf_error(&message);
+        -1
+    })
+}
+
+/// # Safety
+/// The caller must ensure `text` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_pdf_draw_text(
+    handle: i64,
+    page: i64,
+    x: f64,
+    y: f64,
+    size: f64,
+    text: *const NamlString,
+) {
+    let text = unsafe { string_from_naml(text) };
+    if let Err(message) = with_document(handle, |doc| doc.draw_text(page, x, y, size, &text)) {
+        throw_pdf_error(&message);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_pdf_to_bytes(handle: i64) -> *mut NamlBytes {
+    match with_document(handle, |doc| doc.to_bytes()) {
+        Ok(data) => unsafe { naml_bytes_from(data.as_ptr(), data.len()) },
+        Err(message) => {
+            throw_pdf_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Render and write; the registry lock is released before the write
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_pdf_save(handle: i64, path: *const NamlString) {
+    let path = unsafe { string_from_naml(path) };
+    let result = with_document(handle, |doc| doc.to_bytes())
+        .and_then(|data| std::fs::write(&path, data).map_err(|e| format!("{}: {}", path, e)));
+    if let Err(message) = result {
+        throw_pdf_error(&message);
+    }
+}
+
+/// Release a document; unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_pdf_close(handle: i64) {
+    DOCUMENTS.lock().unwrap().remove(&handle);
+}
@@ -0,0 +1,59 @@
+///
+/// PDF Reading
+///
+/// Text extraction follows each page's content stream in drawing order,
+/// so the result is only as readable as the producer's layout: columns
+/// and tables come out in the order they were painted. Scanned pages
+/// without a text layer produce no text.
+///
+
+use lopdf::Document;
+
+use crate::builder::PdfResult;
+
+fn load(data: &[u8]) -> PdfResult<Document> {
+    let doc = Document::load_mem(data).map_err(|e| format!("invalid PDF: {}", e))?;
+    if doc.is_encrypted() {
+        return Err("encrypted PDFs are not supported".to_string());
+    }
+    Ok(doc)
+}
+
+/// The text of every page, in page order
+pub fn extract_text(data: &[u8]) -> PdfResult<String> {
+    let doc = load(data)?;
+    let pages: Vec<u32> = doc.get_pages().keys().copied().collect();
+    doc.extract_text(&pages).map_err(|e| e.to_string())
+}
+
+pub fn page_count(data: &[u8]) -> PdfResult<i64> {
+    Ok(load(data)?.get_pages().len() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::PdfBuilder;
+
+    #[test]
+    fn test_round_trip() {
+        let mut pdf = PdfBuilder::default();
+        pdf.add_page(595.0, 842.0).unwrap();
+        pdf.add_page(595.0, 842.0).unwrap();
+        pdf.draw_text(0, 72.0, 770.0, 18.0, "Invoice 42").unwrap();
+        pdf.draw_text(1, 72.0, 770.0, 12.0, "Total due\nCaf\u{e9}").unwrap();
+        let bytes = pdf.to_bytes().unwrap();
+
+        assert_eq!(page_count(&bytes), Ok(2));
+        let text = extract_text(&bytes).unwrap();
+        let first = text.find("Invoice 42").unwrap();
+        assert!(text[first..].contains("Total due"), "{:?}", text);
+        assert!(text.contains("Caf\u{e9}"), "{:?}", text);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(extract_text(b"not a pdf").is_err());
+        assert!(page_count(b"").is_err());
+    }
+}
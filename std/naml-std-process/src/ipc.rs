@@ -0,0 +1,660 @@
+///
+/// std::ipc - Shared-Memory IPC Channels
+///
+/// Point-to-point, bidirectional byte channels between two naml processes on
+/// the same machine. One process creates the channel by name, the other
+/// connects to it; each side sends on one ring and receives on the other.
+///
+/// - ipc_create(name: string, size: int) -> int throws ProcessError: Create a channel
+/// - ipc_connect(name: string) -> int throws ProcessError: Connect to an existing channel
+/// - send(channel: int, data: bytes) throws ProcessError: Send one message (blocks while full)
+/// - receive(channel: int) -> bytes throws ProcessError: Receive one message (blocks while empty)
+/// - ipc_close(channel: int): Close the channel (the creator also removes its name)
+///
+/// Messages are opaque bytes; encode structured values with std::encoding
+/// (json, binary) before sending.
+///
+/// ## Layout
+///
+/// The shared segment holds a header followed by two ring buffers of `size`
+/// bytes each (ring 0: creator -> connector, ring 1: connector -> creator).
+/// Each message is a u32 little-endian length followed by the payload.
+/// Readers and writers are woken through named semaphores, one counting
+/// queued messages and one signalled whenever space is freed.
+///
+/// ## Platform Notes
+///
+/// - Unix only (POSIX shm_open + sem_open); other platforms throw ProcessError
+/// - Creating a channel replaces any stale segment left behind under the same name
+///
+
+use naml_std_core::bytes::NamlBytes;
+use naml_std_core::value::NamlString;
+
+#[cfg(unix)]
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+#[cfg(unix)]
+use std::sync::{Arc, LazyLock, Mutex};
+
+use super::throw_process_error;
+
+#[cfg(unix)]
+const SEGMENT_MAGIC: u32 = 0x4e4d_4c49;
+
+/// Smallest ring capacity accepted by ipc_create
+#[cfg(unix)]
+const MIN_RING_SIZE: u64 = 64;
+
+/// Length prefix written before every message
+#[cfg(unix)]
+const FRAME_HEADER: u64 = 4;
+
+/// Read/write cursors of one ring; both only ever increase
+#[cfg(unix)]
+#[repr(C)]
+struct RingCursors {
+    head: AtomicU64,
+    tail: AtomicU64,
+}
+
+/// Header at the start of the shared segment
+#[cfg(unix)]
+#[repr(C)]
+struct SegmentHeader {
+    magic: AtomicU32,
+    _reserved: u32,
+    ring_size: u64,
+    rings: [RingCursors; 2],
+}
+
+/// One direction of a channel as seen from this process
+#[cfg(unix)]
+struct Ring {
+    cursors: *const RingCursors,
+    data: *mut u8,
+    size: u64,
+    items: *mut libc::sem_t,
+    space: *mut libc::sem_t,
+}
+
+#[cfg(unix)]
+struct Channel {
+    base: *mut libc::c_void,
+    map_len: usize,
+    name: String,
+    owner: bool,
+    tx: Ring,
+    rx: Ring,
+    send_lock: Mutex<()>,
+    recv_lock: Mutex<()>,
+}
+
+// SAFETY: the mapping and semaphores are process-wide resources; concurrent
+// use from several threads is serialized by send_lock / recv_lock.
+#[cfg(unix)]
+unsafe impl Send for Channel {}
+#[cfg(unix)]
+unsafe impl Sync for Channel {}
+
+#[cfg(unix)]
+struct ChannelTable {
+    entries: HashMap<i64, Arc<Channel>>,
+    next_id: i64,
+}
+
+#[cfg(unix)]
+static CHANNEL_TABLE: LazyLock<Mutex<ChannelTable>> = LazyLock::new(|| {
+    Mutex::new(ChannelTable {
+        entries: HashMap::new(),
+        next_id: 1,
+    })
+});
+
+#[cfg(unix)]
+fn last_errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(-1)
+}
+
+#[cfg(unix)]
+fn shm_name(name: &str) -> std::ffi::CString {
+    std::ffi::CString::new(format!("/naml-ipc-{}", name)).unwrap()
+}
+
+/// Semaphore name for `ring` (0 or 1); `kind` is 'i' (items) or 's' (space)
+#[cfg(unix)]
+fn sem_name(name: &str, ring: usize, kind: char) -> std::ffi::CString {
+    std::ffi::CString::new(format!("/naml-ipc-{}.{}{}", name, ring, kind)).unwrap()
+}
+
+#[cfg(unix)]
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains('/') || name.contains('\0') {
+        return Err(format!("invalid ipc channel name '{}'", name));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn segment_len(ring_size: u64) -> usize {
+    std::mem::size_of::<SegmentHeader>() + 2 * ring_size as usize
+}
+
+#[cfg(unix)]
+fn sem_wait_retry(sem: *mut libc::sem_t) -> Result<(), i32> {
+    loop {
+        if unsafe { libc::sem_wait(sem) } == 0 {
+            return Ok(());
+        }
+        let errno = last_errno();
+        if errno != libc::EINTR {
+            return Err(errno);
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Ring {
+    /// Build the ring view for `index` inside a mapped segment
+    unsafe fn new(
+        base: *mut libc::c_void,
+        index: usize,
+        items: *mut libc::sem_t,
+        space: *mut libc::sem_t,
+    ) -> Self {
+        unsafe {
+            let header = base as *const SegmentHeader;
+            let size = (*header).ring_size;
+            let data = (base as *mut u8)
+                .add(std::mem::size_of::<SegmentHeader>())
+                .add(index * size as usize);
+            Ring {
+                cursors: &(*header).rings[index],
+                data,
+                size,
+                items,
+                space,
+            }
+        }
+    }
+
+    unsafe fn write_at(&self, pos: u64, src: &[u8]) {
+        let start = (pos % self.size) as usize;
+        let first = src.len().min(self.size as usize - start);
+        unsafe {
+            std::ptr::copy_nonoverlapping(src.as_ptr(), self.data.add(start), first);
+            std::ptr::copy_nonoverlapping(src.as_ptr().add(first), self.data, src.len() - first);
+        }
+    }
+
+    unsafe fn read_at(&self, pos: u64, dst: &mut [u8]) {
+        let start = (pos % self.size) as usize;
+        let first = dst.len().min(self.size as usize - start);
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.data.add(start), dst.as_mut_ptr(), first);
+            std::ptr::copy_nonoverlapping(self.data, dst.as_mut_ptr().add(first), dst.len() - first);
+        }
+    }
+
+    /// Append one framed message, waiting for the reader to free space
+    fn send(&self, payload: &[u8]) -> Result<(), String> {
+        let needed = FRAME_HEADER + payload.len() as u64;
+        if needed > self.size {
+            return Err(format!(
+                "message of {} bytes exceeds ipc channel capacity of {} bytes",
+                payload.len(),
+                self.size - FRAME_HEADER
+            ));
+        }
+
+        let cursors = unsafe { &*self.cursors };
+        let tail = cursors.tail.load(Ordering::Relaxed);
+        while self.size - (tail - cursors.head.load(Ordering::Acquire)) < needed {
+            sem_wait_retry(self.space).map_err(|e| format!("ipc send wait failed (errno {})", e))?;
+        }
+
+        unsafe {
+            self.write_at(tail, &(payload.len() as u32).to_le_bytes());
+            self.write_at(tail + FRAME_HEADER, payload);
+        }
+        cursors.tail.store(tail + needed, Ordering::Release);
+        unsafe { libc::sem_post(self.items) };
+        Ok(())
+    }
+
+    /// Remove one framed message, waiting until one is queued
+    fn receive(&self) -> Result<Vec<u8>, String> {
+        sem_wait_retry(self.items).map_err(|e| format!("ipc receive wait failed (errno {})", e))?;
+
+        let cursors = unsafe { &*self.cursors };
+        let head = cursors.head.load(Ordering::Relaxed);
+        // Pairs with the writer's Release store so the payload is visible
+        let _ = cursors.tail.load(Ordering::Acquire);
+
+        let mut len_bytes = [0u8; FRAME_HEADER as usize];
+        unsafe { self.read_at(head, &mut len_bytes) };
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        let mut payload = vec![0u8; len];
+        unsafe { self.read_at(head + FRAME_HEADER, &mut payload) };
+
+        cursors.head.store(head + FRAME_HEADER + len as u64, Ordering::Release);
+        unsafe { libc::sem_post(self.space) };
+        Ok(payload)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Channel {
+    fn drop(&mut self) {
+        unsafe {
+            for sem in [self.tx.items, self.tx.space, self.rx.items, self.rx.space] {
+                libc::sem_close(sem);
+            }
+            libc::munmap(self.base, self.map_len);
+            if self.owner {
+                unlink_names(&self.name);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+unsafe fn unlink_names(name: &str) {
+    unsafe {
+        libc::shm_unlink(shm_name(name).as_ptr());
+        for ring in 0..2 {
+            libc::sem_unlink(sem_name(name, ring, 'i').as_ptr());
+            libc::sem_unlink(sem_name(name, ring, 's').as_ptr());
+        }
+    }
+}
+
+/// Open the four semaphores of a channel, creating them when `create` is set
+#[cfg(unix)]
+fn open_semaphores(name: &str, create: bool) -> Result<[*mut libc::sem_t; 4], String> {
+    let mut sems = [std::ptr::null_mut(); 4];
+    for (i, (ring, kind)) in [(0, 'i'), (0, 's'), (1, 'i'), (1, 's')].into_iter().enumerate() {
+        let sem_path = sem_name(name, ring, kind);
+        let sem = unsafe {
+            if create {
+                libc::sem_open(
+                    sem_path.as_ptr(),
+                    libc::O_CREAT | libc::O_EXCL,
+                    0o600 as libc::c_uint,
+                    0 as libc::c_uint,
+                )
+            } else {
+                libc::sem_open(sem_path.as_ptr(), 0)
+            }
+        };
+        if sem == libc::SEM_FAILED {
+            let errno = last_errno();
+            for opened in &sems[..i] {
+                unsafe { libc::sem_close(*opened) };
+            }
+            return Err(format!("sem_open failed for ipc channel '{}' (errno {})", name, errno));
+        }
+        sems[i] = sem;
+    }
+    Ok(sems)
+}
+
+#[cfg(unix)]
+fn create_channel(name: &str, ring_size: u64) -> Result<Channel, String> {
+    validate_name(name)?;
+    if ring_size < MIN_RING_SIZE {
+        return Err(format!("ipc channel size must be at least {} bytes", MIN_RING_SIZE));
+    }
+
+    unsafe {
+        unlink_names(name);
+
+        let path = shm_name(name);
+        let fd = libc::shm_open(path.as_ptr(), libc::O_CREAT | libc::O_EXCL | libc::O_RDWR, 0o600);
+        if fd < 0 {
+            return Err(format!("shm_open failed for ipc channel '{}' (errno {})", name, last_errno()));
+        }
+
+        let map_len = segment_len(ring_size);
+        if libc::ftruncate(fd, map_len as libc::off_t) != 0 {
+            let errno = last_errno();
+            libc::close(fd);
+            libc::shm_unlink(path.as_ptr());
+            return Err(format!("failed to size ipc channel '{}' (errno {})", name, errno));
+        }
+
+        let base = libc::mmap(
+            std::ptr::null_mut(),
+            map_len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        );
+        libc::close(fd);
+        if base == libc::MAP_FAILED {
+            let errno = last_errno();
+            libc::shm_unlink(path.as_ptr());
+            return Err(format!("failed to map ipc channel '{}' (errno {})", name, errno));
+        }
+
+        let header = base as *mut SegmentHeader;
+        (*header).ring_size = ring_size;
+
+        let sems = match open_semaphores(name, true) {
+            Ok(sems) => sems,
+            Err(e) => {
+                libc::munmap(base, map_len);
+                unlink_names(name);
+                return Err(e);
+            }
+        };
+
+        // Publish the header last so connectors never see a half-built segment
+        (*header).magic.store(SEGMENT_MAGIC, Ordering::Release);
+
+        Ok(Channel {
+            base,
+            map_len,
+            name: name.to_string(),
+            owner: true,
+            tx: Ring::new(base, 0, sems[0], sems[1]),
+            rx: Ring::new(base, 1, sems[2], sems[3]),
+            send_lock: Mutex::new(()),
+            recv_lock: Mutex::new(()),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn connect_channel(name: &str) -> Result<Channel, String> {
+    validate_name(name)?;
+
+    unsafe {
+        let path = shm_name(name);
+        let fd = libc::shm_open(path.as_ptr(), libc::O_RDWR, 0);
+        if fd < 0 {
+            return Err(format!("no ipc channel named '{}' (errno {})", name, last_errno()));
+        }
+
+        let mut stat: libc::stat = std::mem::zeroed();
+        if libc::fstat(fd, &mut stat) != 0 {
+            let errno = last_errno();
+            libc::close(fd);
+            return Err(format!("failed to stat ipc channel '{}' (errno {})", name, errno));
+        }
+        let map_len = stat.st_size as usize;
+        if map_len < std::mem::size_of::<SegmentHeader>() {
+            libc::close(fd);
+            return Err(format!("ipc channel '{}' is not ready", name));
+        }
+
+        let base = libc::mmap(
+            std::ptr::null_mut(),
+            map_len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            fd,
+            0,
+        );
+        libc::close(fd);
+        if base == libc::MAP_FAILED {
+            return Err(format!("failed to map ipc channel '{}' (errno {})", name, last_errno()));
+        }
+
+        let header = base as *const SegmentHeader;
+        if (*header).magic.load(Ordering::Acquire) != SEGMENT_MAGIC
+            || segment_len((*header).ring_size) != map_len
+        {
+            libc::munmap(base, map_len);
+            return Err(format!("ipc channel '{}' is not ready", name));
+        }
+
+        let sems = match open_semaphores(name, false) {
+            Ok(sems) => sems,
+            Err(e) => {
+                libc::munmap(base, map_len);
+                return Err(e);
+            }
+        };
+
+        Ok(Channel {
+            base,
+            map_len,
+            name: name.to_string(),
+            owner: false,
+            tx: Ring::new(base, 1, sems[2], sems[3]),
+            rx: Ring::new(base, 0, sems[0], sems[1]),
+            send_lock: Mutex::new(()),
+            recv_lock: Mutex::new(()),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn register_channel(channel: Channel) -> i64 {
+    let mut table = CHANNEL_TABLE.lock().unwrap();
+    let id = table.next_id;
+    table.next_id += 1;
+    table.entries.insert(id, Arc::new(channel));
+    id
+}
+
+#[cfg(unix)]
+fn lookup_channel(handle: i64) -> Option<Arc<Channel>> {
+    CHANNEL_TABLE.lock().unwrap().entries.get(&handle).cloned()
+}
+
+unsafe fn string_arg(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe {
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+        String::from_utf8_lossy(slice).into_owned()
+    }
+}
+
+/// Create a named channel with `size` bytes of buffer per direction
+///
+/// # Safety
+/// The caller must ensure `name` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_ipc_create(name: *const NamlString, size: i64) -> i64 {
+    let name_str = unsafe { string_arg(name) };
+
+    #[cfg(unix)]
+    {
+        match create_channel(&name_str, size.max(0) as u64) {
+            Ok(channel) => register_channel(channel),
+            Err(msg) => {
+                throw_process_error(&msg, -1);
+                -1
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (name_str, size);
+        throw_process_error("ipc channels are not supported on this platform", -1);
+        -1
+    }
+}
+
+/// Connect to a channel created by another process
+///
+/// # Safety
+/// The caller must ensure `name` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_ipc_connect(name: *const NamlString) -> i64 {
+    let name_str = unsafe { string_arg(name) };
+
+    #[cfg(unix)]
+    {
+        match connect_channel(&name_str) {
+            Ok(channel) => register_channel(channel),
+            Err(msg) => {
+                throw_process_error(&msg, -1);
+                -1
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = name_str;
+        throw_process_error("ipc channels are not supported on this platform", -1);
+        -1
+    }
+}
+
+/// Send one message; blocks while the peer's ring is full
+///
+/// # Safety
+/// The caller must ensure `data` is a valid pointer to a NamlBytes or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_ipc_send(handle: i64, data: *const NamlBytes) {
+    let payload: &[u8] = if data.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts((*data).as_ptr(), (*data).len) }
+    };
+
+    #[cfg(unix)]
+    {
+        let Some(channel) = lookup_channel(handle) else {
+            throw_process_error(&format!("invalid ipc channel handle {}", handle), -1);
+            return;
+        };
+        let _guard = channel.send_lock.lock().unwrap();
+        if let Err(msg) = channel.tx.send(payload) {
+            throw_process_error(&msg, -1);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = payload;
+        throw_process_error("ipc channels are not supported on this platform", -1);
+    }
+}
+
+/// Receive one message; blocks until the peer sends
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_ipc_receive(handle: i64) -> *mut NamlBytes {
+    #[cfg(unix)]
+    {
+        let Some(channel) = lookup_channel(handle) else {
+            throw_process_error(&format!("invalid ipc channel handle {}", handle), -1);
+            return std::ptr::null_mut();
+        };
+        let _guard = channel.recv_lock.lock().unwrap();
+        match channel.rx.receive() {
+            Ok(payload) => unsafe {
+                naml_std_core::bytes::naml_bytes_from(payload.as_ptr(), payload.len())
+            },
+            Err(msg) => {
+                throw_process_error(&msg, -1);
+                std::ptr::null_mut()
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = handle;
+        throw_process_error("ipc channels are not supported on this platform", -1);
+        std::ptr::null_mut()
+    }
+}
+
+/// Close a channel handle; the creating side also removes the channel name
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_ipc_close(handle: i64) {
+    #[cfg(unix)]
+    {
+        CHANNEL_TABLE.lock().unwrap().entries.remove(&handle);
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = handle;
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn unique_name(tag: &str) -> String {
+        format!("t{}-{}", std::process::id(), tag)
+    }
+
+    #[test]
+    fn test_roundtrip_both_directions() {
+        let name = unique_name("rt");
+        let server = create_channel(&name, 256).unwrap();
+        let client = connect_channel(&name).unwrap();
+
+        client.tx.send(b"ping").unwrap();
+        assert_eq!(server.rx.receive().unwrap(), b"ping");
+
+        server.tx.send(b"pong").unwrap();
+        server.tx.send(b"").unwrap();
+        assert_eq!(client.rx.receive().unwrap(), b"pong");
+        assert_eq!(client.rx.receive().unwrap(), b"");
+    }
+
+    #[test]
+    fn test_messages_wrap_around_ring() {
+        let name = unique_name("wrap");
+        let server = create_channel(&name, 64).unwrap();
+        let client = connect_channel(&name).unwrap();
+
+        for i in 0..50u8 {
+            let msg = vec![i; 23];
+            server.tx.send(&msg).unwrap();
+            assert_eq!(client.rx.receive().unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn test_sender_blocks_until_space() {
+        let name = unique_name("block");
+        let server = Arc::new(create_channel(&name, 64).unwrap());
+        let client = connect_channel(&name).unwrap();
+
+        let sender = {
+            let server = Arc::clone(&server);
+            std::thread::spawn(move || {
+                for i in 0..10u8 {
+                    server.tx.send(&[i; 30]).unwrap();
+                }
+            })
+        };
+        for i in 0..10u8 {
+            assert_eq!(client.rx.receive().unwrap(), vec![i; 30]);
+        }
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn test_oversized_message_rejected() {
+        let name = unique_name("big");
+        let server = create_channel(&name, 64).unwrap();
+        assert!(server.tx.send(&[0u8; 61]).is_err());
+        assert!(server.tx.send(&[0u8; 60]).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_names_and_sizes() {
+        assert!(create_channel("", 128).is_err());
+        assert!(create_channel("a/b", 128).is_err());
+        assert!(create_channel(&unique_name("small"), 8).is_err());
+        assert!(connect_channel(&unique_name("missing")).is_err());
+    }
+
+    #[test]
+    fn test_creator_close_removes_name() {
+        let name = unique_name("close");
+        drop(create_channel(&name, 128).unwrap());
+        assert!(connect_channel(&name).is_err());
+    }
+}
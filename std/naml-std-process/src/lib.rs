@@ -34,11 +34,25 @@
 /// - `signal` uses libc::kill on Unix
 /// - Process handles are integer indices into a global process table
 ///
+/// ## IPC Channels
+///
+/// Shared-memory channels between processes (`std::ipc`) live in the `ipc` module.
+///
+/// ## Worker Pools
+///
+/// Forked worker processes for isolated jobs (`std::process::pool`) live in the `pool` module.
+///
+
+mod ipc;
+mod pool;
+
+pub use ipc::*;
+pub use pool::*;
 
 use naml_std_core::{
     naml_array_len, naml_array_get, naml_array_new, naml_array_push,
     naml_exception_set_typed, naml_stack_capture,
-    naml_string_new, naml_struct_new, naml_struct_set_field, NamlArray, NamlString, NamlStruct,
+    naml_string_new, NamlArray, NamlString,
 };
 use std::collections::HashMap;
 use std::process::{Child, Command};
@@ -46,7 +60,6 @@ use std::sync::Mutex;
 use std::sync::LazyLock;
 
 const EXCEPTION_TYPE_PROCESS_ERROR: i64 = 9;
-const PROCESS_ERROR_STRUCT_TYPE_ID: u32 = 0xFFFF_0009;
 
 struct ProcessTable {
     entries: HashMap<i64, ProcessEntry>,
@@ -65,20 +78,23 @@ static PROCESS_TABLE: LazyLock<Mutex<ProcessTable>> = LazyLock::new(|| {
     })
 });
 
-unsafe fn naml_from_string(s: &str) -> *mut NamlString {
-    unsafe { naml_string_new(s.as_ptr(), s.len()) }
-}
-
+/// Allocate a ProcessError exception.
+/// Layout: message@0, stack@8, code@16 (matches other exception types)
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_process_error_new(
     message: *const NamlString,
     code: i64,
-) -> *mut NamlStruct {
+) -> *mut u8 {
     unsafe {
-        let exc = naml_struct_new(PROCESS_ERROR_STRUCT_TYPE_ID, 2);
-        naml_struct_set_field(exc, 0, message as i64);
-        naml_struct_set_field(exc, 1, code);
-        exc
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate ProcessError");
+        }
+        *(ptr as *mut i64) = message as i64;
+        *(ptr.add(8) as *mut i64) = 0;
+        *(ptr.add(16) as *mut i64) = code;
+        ptr
     }
 }
 
@@ -88,9 +104,9 @@ fn throw_process_error(message: &str, code: i32) {
         let exc = naml_process_error_new(message_ptr, code as i64);
 
         let stack = naml_stack_capture();
-        *(exc as *mut u8).add(8).cast::<*mut u8>() = stack;
+        *(exc.add(8) as *mut *mut u8) = stack;
 
-        naml_exception_set_typed(exc as *mut u8, EXCEPTION_TYPE_PROCESS_ERROR);
+        naml_exception_set_typed(exc, EXCEPTION_TYPE_PROCESS_ERROR);
     }
 }
 
@@ -164,7 +180,7 @@ pub unsafe extern "C" fn naml_process_start(
     args: *mut NamlArray,
 ) -> i64 {
     let name_str = unsafe {
-        let slice = std::slice::from_raw_parts((*name).data.as_ptr(), (*name).len);
+        let slice = std::slice::from_raw_parts((*name).as_ptr(), (*name).len);
         String::from_utf8_lossy(slice).into_owned()
     };
 
@@ -174,7 +190,7 @@ pub unsafe extern "C" fn naml_process_start(
         let s_ptr = unsafe { naml_array_get(args, i as i64) } as *const NamlString;
         if !s_ptr.is_null() {
             let s = unsafe {
-                let slice = std::slice::from_raw_parts((*s_ptr).data.as_ptr(), (*s_ptr).len);
+                let slice = std::slice::from_raw_parts((*s_ptr).as_ptr(), (*s_ptr).len);
                 String::from_utf8_lossy(slice).into_owned()
             };
             arg_vec.push(s);
@@ -383,6 +399,17 @@ pub extern "C" fn naml_process_sigcont() -> i64 { 19 }
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_process_error_layout() {
+        unsafe {
+            let message = naml_string_new(b"spawn failed".as_ptr(), 12);
+            let exc = naml_process_error_new(message, 127);
+            assert_eq!(*(exc as *const *const NamlString), message as *const NamlString);
+            assert_eq!(*(exc.add(8) as *const i64), 0);
+            assert_eq!(*(exc.add(16) as *const i64), 127);
+        }
+    }
+
     #[test]
     fn test_getpid() {
         let pid = naml_process_getpid();
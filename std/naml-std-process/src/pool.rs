@@ -0,0 +1,679 @@
+///
+/// std::process::pool - Worker-Process Pool
+///
+/// Runs CPU-heavy or crash-prone jobs in forked worker processes so a crash
+/// (segfault, abort, exit) in a job cannot take down the main program.
+///
+/// - open_worker_pool(workers: int, entry_fn_name: string) -> int throws ProcessError
+/// - submit(pool: int, payload: bytes) -> channel<bytes> throws ProcessError: Queue a job;
+///   the channel is its future and receives the result bytes
+/// - await_result(job: channel<bytes>) -> bytes throws ProcessError: Block until the job finishes
+/// - close_pool(pool: int): Stop the workers once queued jobs are done
+///
+/// `entry_fn_name` names a top-level `fn(bytes) -> bytes`; the type checker
+/// resolves it at compile time and codegen passes its address here.
+///
+/// ## Design
+///
+/// open_worker_pool first forks a zygote: a single-threaded copy of the
+/// process taken before the pool starts any thread of its own. The zygote
+/// forks every worker, the first ones and every replacement, so nothing is
+/// ever forked from a dispatcher thread. For each worker it creates a
+/// socketpair and passes the parent's end back over its control socket with
+/// SCM_RIGHTS. Workers are children of the zygote, which ignores SIGCHLD so
+/// they are reaped as soon as they exit.
+///
+/// A worker loops reading framed payloads from its socket, calls the entry
+/// function, and writes the framed result back. One dispatcher thread per
+/// worker feeds it jobs from a shared queue. When a worker dies mid-job,
+/// that job fails with ProcessError and the dispatcher asks the zygote for a
+/// replacement before taking the next job.
+///
+/// Frames are a u32 little-endian length followed by the payload; responses
+/// carry a leading status byte (0 = ok, 1 = entry returned no result).
+///
+/// Each job's channel is completed exactly once, by its dispatcher, and the
+/// pool keeps no other record of it: a job nobody awaits is freed with its
+/// channel.
+///
+/// ## Platform Notes
+///
+/// - Unix only (fork + socketpair); other platforms throw ProcessError
+/// - The zygote closes every inherited descriptor except stdio and its
+///   control socket, so workers start with only their own socket
+/// - Open pools early: the zygote is forked from the calling thread, which is
+///   safe for the runtime allocator but not for locks held by arbitrary user
+///   threads at that moment
+///
+
+use naml_std_core::bytes::NamlBytes;
+use naml_std_threads::{naml_channel_receive, take_channel_failure, NamlChannel};
+
+#[cfg(unix)]
+use std::collections::{HashMap, VecDeque};
+#[cfg(unix)]
+use std::io::{Read, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, FromRawFd};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+
+#[cfg(unix)]
+use naml_std_threads::{complete, completion_channel, Failure};
+
+use super::throw_process_error;
+
+/// Compiled entry function: fn(bytes) -> bytes. Like every naml function it
+/// takes a leading closure data word, which is null for a top-level fn.
+type EntryFn = unsafe extern "C" fn(data_ptr: i64, payload: *mut NamlBytes) -> *mut NamlBytes;
+
+#[cfg(unix)]
+const STATUS_OK: u8 = 0;
+#[cfg(unix)]
+const STATUS_NO_RESULT: u8 = 1;
+
+/// Control request asking the zygote for one more worker
+#[cfg(unix)]
+const SPAWN_WORKER: u8 = 1;
+
+/// Result channel of one submitted job
+#[cfg(unix)]
+struct Job(*mut NamlChannel);
+
+// The channel is reference counted and internally locked; the job holds
+// the reference that `complete` releases.
+#[cfg(unix)]
+unsafe impl Send for Job {}
+
+#[cfg(unix)]
+impl Job {
+    fn finish(self, result: Result<Vec<u8>, String>) {
+        let result = result
+            .map(|bytes| unsafe { naml_std_core::bytes::naml_bytes_from(bytes.as_ptr(), bytes.len()) as i64 })
+            .map_err(|msg| Box::new(msg) as Failure);
+        unsafe { complete(self.0, result) };
+    }
+}
+
+#[cfg(unix)]
+struct JobQueue {
+    jobs: VecDeque<(Vec<u8>, Job)>,
+    closed: bool,
+}
+
+#[cfg(unix)]
+struct Pool {
+    queue: Mutex<JobQueue>,
+    available: Condvar,
+    zygote: Mutex<Option<Zygote>>,
+    dispatchers: Mutex<Vec<std::thread::JoinHandle<()>>>,
+}
+
+#[cfg(unix)]
+impl Pool {
+    /// Next queued job, or None once the pool is closed and drained
+    fn next_job(&self) -> Option<(Vec<u8>, Job)> {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            if let Some(job) = queue.jobs.pop_front() {
+                return Some(job);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self.available.wait(queue).unwrap();
+        }
+    }
+
+    fn spawn_worker(&self) -> Result<Worker, String> {
+        match self.zygote.lock().unwrap().as_mut() {
+            Some(zygote) => zygote.spawn_worker(),
+            None => Err("worker pool is closed".to_string()),
+        }
+    }
+}
+
+/// Parent-side view of the process that forks the workers
+#[cfg(unix)]
+struct Zygote {
+    pid: libc::pid_t,
+    control: UnixStream,
+}
+
+#[cfg(unix)]
+impl Zygote {
+    fn start(entry: EntryFn) -> Result<Zygote, String> {
+        let (control, child) =
+            UnixStream::pair().map_err(|e| format!("socketpair() failed: {}", e))?;
+        let pid = unsafe { libc::fork() };
+        if pid < 0 {
+            return Err(format!("fork() failed: {}", std::io::Error::last_os_error()));
+        }
+        if pid == 0 {
+            let fd = child.as_raw_fd();
+            close_inherited_fds(fd);
+            zygote_main(entry, fd);
+        }
+        drop(child);
+        Ok(Zygote { pid, control })
+    }
+
+    fn spawn_worker(&mut self) -> Result<Worker, String> {
+        let lost = |e: std::io::Error| format!("worker zygote is gone: {}", e);
+        self.control.write_all(&[SPAWN_WORKER]).map_err(lost)?;
+        let (pid, fd) = receive_worker(self.control.as_raw_fd()).map_err(lost)?;
+        if pid < 0 {
+            return Err(format!("fork() failed: {}", std::io::Error::from_raw_os_error(-pid)));
+        }
+        Ok(Worker {
+            pid,
+            stream: unsafe { UnixStream::from_raw_fd(fd) },
+        })
+    }
+
+    /// Close the control socket, which makes the zygote exit, and reap it
+    fn shutdown(self) {
+        let pid = self.pid;
+        drop(self.control);
+        let mut status = 0;
+        unsafe { libc::waitpid(pid, &mut status, 0) };
+    }
+}
+
+/// Parent-side view of one worker
+#[cfg(unix)]
+struct Worker {
+    pid: libc::pid_t,
+    stream: UnixStream,
+}
+
+#[cfg(unix)]
+impl Worker {
+    /// Send one payload and read the response; Err means the worker died
+    fn run(&mut self, payload: &[u8]) -> std::io::Result<Result<Vec<u8>, String>> {
+        write_frame(&mut self.stream, payload)?;
+        let mut status = [0u8; 1];
+        self.stream.read_exact(&mut status)?;
+        let body = read_frame(&mut self.stream)?;
+        Ok(match status[0] {
+            STATUS_OK => Ok(body),
+            _ => Err("worker entry function returned no result".to_string()),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn write_frame(out: &mut impl Write, payload: &[u8]) -> std::io::Result<()> {
+    out.write_all(&(payload.len() as u32).to_le_bytes())?;
+    out.write_all(payload)?;
+    out.flush()
+}
+
+#[cfg(unix)]
+fn read_frame(input: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    input.read_exact(&mut len)?;
+    let mut payload = vec![0u8; u32::from_le_bytes(len) as usize];
+    input.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+/// Zygote main loop: fork a worker for every request until the control
+/// socket closes. Only raw syscalls run here; never returns.
+#[cfg(unix)]
+fn zygote_main(entry: EntryFn, control: libc::c_int) -> ! {
+    unsafe {
+        libc::signal(libc::SIGCHLD, libc::SIG_IGN);
+        let mut request = [0u8; 1];
+        loop {
+            match libc::read(control, request.as_mut_ptr().cast(), 1) {
+                1 => {}
+                n if n < 0 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted => {
+                    continue;
+                }
+                _ => libc::_exit(0),
+            }
+
+            let mut pair = [0 as libc::c_int; 2];
+            if libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, pair.as_mut_ptr()) != 0 {
+                send_worker(control, -last_errno(), None);
+                continue;
+            }
+            let pid = libc::fork();
+            if pid == 0 {
+                libc::close(control);
+                libc::close(pair[0]);
+                worker_main(entry, UnixStream::from_raw_fd(pair[1]));
+            }
+            libc::close(pair[1]);
+            if pid < 0 {
+                send_worker(control, -last_errno(), None);
+            } else {
+                send_worker(control, pid, Some(pair[0]));
+            }
+            libc::close(pair[0]);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn last_errno() -> libc::c_int {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(libc::EIO)
+}
+
+/// Control buffer with room for one descriptor, aligned for cmsghdr
+#[cfg(unix)]
+type FdSpace = [u64; 4];
+
+/// Reply to a spawn request: the worker pid (or a negated errno) plus the
+/// parent's end of the worker socket
+#[cfg(unix)]
+fn send_worker(control: libc::c_int, pid: libc::pid_t, fd: Option<libc::c_int>) {
+    let reply = pid.to_le_bytes();
+    let mut iov = libc::iovec {
+        iov_base: reply.as_ptr() as *mut libc::c_void,
+        iov_len: reply.len(),
+    };
+    let mut space: FdSpace = [0; 4];
+    unsafe {
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        if let Some(fd) = fd {
+            msg.msg_control = space.as_mut_ptr().cast();
+            msg.msg_controllen = libc::CMSG_SPACE(size_of::<libc::c_int>() as u32) as _;
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<libc::c_int>() as u32) as _;
+            std::ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut libc::c_int, fd);
+        }
+        libc::sendmsg(control, &msg, 0);
+    }
+}
+
+/// Read a reply written by `send_worker`
+#[cfg(unix)]
+fn receive_worker(control: libc::c_int) -> std::io::Result<(libc::pid_t, libc::c_int)> {
+    let mut reply = [0u8; 4];
+    let mut iov = libc::iovec {
+        iov_base: reply.as_mut_ptr().cast(),
+        iov_len: reply.len(),
+    };
+    let mut space: FdSpace = [0; 4];
+    unsafe {
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = space.as_mut_ptr().cast();
+        msg.msg_controllen = size_of::<FdSpace>() as _;
+        let n = loop {
+            let n = libc::recvmsg(control, &mut msg, 0);
+            if n >= 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                break n;
+            }
+        };
+        if n < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        if n as usize != reply.len() {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let pid = libc::pid_t::from_le_bytes(reply);
+        if pid < 0 {
+            return Ok((pid, -1));
+        }
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        if cmsg.is_null() || (*cmsg).cmsg_type != libc::SCM_RIGHTS {
+            return Err(std::io::Error::other("no worker socket in reply"));
+        }
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::c_int);
+        libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        Ok((pid, fd))
+    }
+}
+
+/// Worker process main loop; never returns
+#[cfg(unix)]
+fn worker_main(entry: EntryFn, mut stream: UnixStream) -> ! {
+    while let Ok(payload) = read_frame(&mut stream) {
+        let response = unsafe {
+            let arg = naml_std_core::bytes::naml_bytes_from(payload.as_ptr(), payload.len());
+            let result = entry(0, arg);
+            let response = if result.is_null() {
+                (STATUS_NO_RESULT, Vec::new())
+            } else {
+                let bytes = std::slice::from_raw_parts((*result).as_ptr(), (*result).len);
+                (STATUS_OK, bytes.to_vec())
+            };
+            if result != arg {
+                naml_std_core::bytes::naml_bytes_decref(result);
+            }
+            naml_std_core::bytes::naml_bytes_decref(arg);
+            response
+        };
+        if stream.write_all(&[response.0]).is_err() || write_frame(&mut stream, &response.1).is_err() {
+            break;
+        }
+    }
+    unsafe { libc::_exit(0) }
+}
+
+/// Close every inherited descriptor except stdio and `keep`
+#[cfg(unix)]
+fn close_inherited_fds(keep: libc::c_int) {
+    let max_fd = match unsafe { libc::sysconf(libc::_SC_OPEN_MAX) } {
+        n if n > 0 => n.min(65536) as libc::c_int,
+        _ => 1024,
+    };
+    for fd in 3..max_fd {
+        if fd != keep {
+            unsafe { libc::close(fd) };
+        }
+    }
+}
+
+/// Dispatcher thread: feeds one worker, replacing it whenever it dies
+#[cfg(unix)]
+fn dispatch(pool: Arc<Pool>, first: Worker) {
+    let mut worker = Some(first);
+    while let Some((payload, job)) = pool.next_job() {
+        if worker.is_none() {
+            match pool.spawn_worker() {
+                Ok(w) => worker = Some(w),
+                Err(e) => {
+                    job.finish(Err(format!("failed to restart worker: {}", e)));
+                    continue;
+                }
+            }
+        }
+
+        let current = worker.as_mut().unwrap();
+        match current.run(&payload) {
+            Ok(result) => job.finish(result),
+            Err(_) => {
+                let pid = current.pid;
+                worker = None;
+                job.finish(Err(format!("worker process {} crashed while running job", pid)));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+struct PoolTables {
+    pools: HashMap<i64, Arc<Pool>>,
+    next_id: i64,
+}
+
+#[cfg(unix)]
+static POOL_TABLES: LazyLock<Mutex<PoolTables>> = LazyLock::new(|| {
+    Mutex::new(PoolTables {
+        pools: HashMap::new(),
+        next_id: 1,
+    })
+});
+
+#[cfg(unix)]
+fn open_pool(workers: usize, entry: EntryFn) -> Result<i64, String> {
+    if workers == 0 {
+        return Err("worker pool needs at least one worker".to_string());
+    }
+
+    let mut zygote = Zygote::start(entry)?;
+    let mut spawned = Vec::with_capacity(workers);
+    for _ in 0..workers {
+        match zygote.spawn_worker() {
+            Ok(w) => spawned.push(w),
+            Err(e) => {
+                drop(spawned);
+                zygote.shutdown();
+                return Err(e);
+            }
+        }
+    }
+
+    let pool = Arc::new(Pool {
+        queue: Mutex::new(JobQueue {
+            jobs: VecDeque::new(),
+            closed: false,
+        }),
+        available: Condvar::new(),
+        zygote: Mutex::new(Some(zygote)),
+        dispatchers: Mutex::new(Vec::new()),
+    });
+    {
+        let mut dispatchers = pool.dispatchers.lock().unwrap();
+        for worker in spawned {
+            let pool = Arc::clone(&pool);
+            dispatchers.push(std::thread::spawn(move || dispatch(pool, worker)));
+        }
+    }
+
+    let mut tables = POOL_TABLES.lock().unwrap();
+    let id = tables.next_id;
+    tables.next_id += 1;
+    tables.pools.insert(id, pool);
+    Ok(id)
+}
+
+#[cfg(unix)]
+fn submit_job(pool_handle: i64, payload: Vec<u8>) -> Result<*mut NamlChannel, String> {
+    let pool = POOL_TABLES
+        .lock()
+        .unwrap()
+        .pools
+        .get(&pool_handle)
+        .cloned()
+        .ok_or_else(|| format!("invalid worker pool handle {}", pool_handle))?;
+
+    let mut queue = pool.queue.lock().unwrap();
+    if queue.closed {
+        return Err("worker pool is closed".to_string());
+    }
+    let channel = completion_channel();
+    queue.jobs.push_back((payload, Job(channel)));
+    drop(queue);
+    pool.available.notify_one();
+    Ok(channel)
+}
+
+#[cfg(unix)]
+fn close(pool_handle: i64) {
+    let Some(pool) = POOL_TABLES.lock().unwrap().pools.remove(&pool_handle) else {
+        return;
+    };
+    pool.queue.lock().unwrap().closed = true;
+    pool.available.notify_all();
+    let dispatchers = std::mem::take(&mut *pool.dispatchers.lock().unwrap());
+    for handle in dispatchers {
+        let _ = handle.join();
+    }
+    if let Some(zygote) = pool.zygote.lock().unwrap().take() {
+        zygote.shutdown();
+    }
+}
+
+/// Take the result of a job, or why it has none
+fn await_job(job: *mut NamlChannel) -> Result<*mut NamlBytes, String> {
+    let mut value = 0;
+    if unsafe { naml_channel_receive(job, &mut value) } == 1 {
+        return Ok(value as *mut NamlBytes);
+    }
+    let failure = unsafe { take_channel_failure(job) }.and_then(|failure| failure.downcast::<String>().ok());
+    Err(match failure {
+        Some(msg) => *msg,
+        None => "job result was already received".to_string(),
+    })
+}
+
+/// Fork a zygote and `workers` processes that each call the entry function
+/// at `func_ptr` for every job
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_process_pool_open(workers: i64, func_ptr: i64) -> i64 {
+    #[cfg(unix)]
+    {
+        let entry = unsafe { std::mem::transmute::<usize, EntryFn>(func_ptr as usize) };
+        match open_pool(workers.max(0) as usize, entry) {
+            Ok(id) => id,
+            Err(msg) => {
+                throw_process_error(&msg, -1);
+                -1
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (workers, func_ptr);
+        throw_process_error("worker pools are not supported on this platform", -1);
+        -1
+    }
+}
+
+/// Queue a payload for the next free worker; returns the job's result channel
+///
+/// # Safety
+/// The caller must ensure `payload` is a valid pointer to a NamlBytes or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_process_pool_submit(pool: i64, payload: *const NamlBytes) -> *mut NamlChannel {
+    let data = if payload.is_null() {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts((*payload).as_ptr(), (*payload).len).to_vec() }
+    };
+
+    #[cfg(unix)]
+    {
+        match submit_job(pool, data) {
+            Ok(channel) => channel,
+            Err(msg) => {
+                throw_process_error(&msg, -1);
+                std::ptr::null_mut()
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (pool, data);
+        throw_process_error("worker pools are not supported on this platform", -1);
+        std::ptr::null_mut()
+    }
+}
+
+/// Block until a job finishes and return its result bytes
+///
+/// # Safety
+/// The caller must ensure `job` is a valid pointer to a NamlChannel or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_process_pool_await(job: *mut NamlChannel) -> *mut NamlBytes {
+    match await_job(job) {
+        Ok(result) => result,
+        Err(msg) => {
+            throw_process_error(&msg, -1);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Finish queued jobs, then stop every worker and the zygote
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_process_pool_close(pool: i64) {
+    #[cfg(unix)]
+    close(pool);
+    #[cfg(not(unix))]
+    let _ = pool;
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use naml_std_threads::naml_channel_decref;
+
+    unsafe extern "C" fn reverse(_data: i64, payload: *mut NamlBytes) -> *mut NamlBytes {
+        unsafe {
+            let mut bytes =
+                std::slice::from_raw_parts((*payload).as_ptr(), (*payload).len).to_vec();
+            if bytes == b"crash" {
+                libc::abort();
+            }
+            bytes.reverse();
+            naml_std_core::bytes::naml_bytes_from(bytes.as_ptr(), bytes.len())
+        }
+    }
+
+    unsafe extern "C" fn no_result(_data: i64, _payload: *mut NamlBytes) -> *mut NamlBytes {
+        std::ptr::null_mut()
+    }
+
+    /// Await a job, release its channel, and copy out the result
+    fn result(job: *mut NamlChannel) -> Result<Vec<u8>, String> {
+        let result = await_job(job).map(|bytes| unsafe {
+            let copy = std::slice::from_raw_parts((*bytes).as_ptr(), (*bytes).len).to_vec();
+            naml_std_core::bytes::naml_bytes_decref(bytes);
+            copy
+        });
+        unsafe { naml_channel_decref(job) };
+        result
+    }
+
+    #[test]
+    fn test_jobs_run_in_workers() {
+        let pool = open_pool(2, reverse).unwrap();
+        let jobs: Vec<_> = (0..8)
+            .map(|i| submit_job(pool, format!("job{}", i).into_bytes()).unwrap())
+            .collect();
+        for (i, job) in jobs.into_iter().enumerate() {
+            let expected: Vec<u8> = format!("job{}", i).bytes().rev().collect();
+            assert_eq!(result(job).unwrap(), expected);
+        }
+        close(pool);
+    }
+
+    #[test]
+    fn test_crashed_worker_is_replaced() {
+        let pool = open_pool(1, reverse).unwrap();
+        let crash = submit_job(pool, b"crash".to_vec()).unwrap();
+        let after = submit_job(pool, b"abc".to_vec()).unwrap();
+
+        let err = result(crash).unwrap_err();
+        assert!(err.contains("crashed"), "{}", err);
+        assert_eq!(result(after).unwrap(), b"cba");
+        close(pool);
+    }
+
+    #[test]
+    fn test_missing_result_is_an_error() {
+        let pool = open_pool(1, no_result).unwrap();
+        let job = submit_job(pool, b"x".to_vec()).unwrap();
+        assert!(result(job).is_err());
+        close(pool);
+    }
+
+    #[test]
+    fn test_queued_jobs_finish_on_close() {
+        let pool = open_pool(1, reverse).unwrap();
+        let job = submit_job(pool, b"later".to_vec()).unwrap();
+        close(pool);
+        unsafe {
+            let bytes = await_job(job).unwrap();
+            assert_eq!(std::slice::from_raw_parts((*bytes).as_ptr(), (*bytes).len), b"retal");
+            naml_std_core::bytes::naml_bytes_decref(bytes);
+        }
+        assert_eq!(result(job).unwrap_err(), "job result was already received");
+    }
+
+    #[test]
+    fn test_invalid_handles() {
+        assert!(open_pool(0, reverse).is_err());
+        assert!(submit_job(-1, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_submit_after_close_fails() {
+        let pool = open_pool(1, reverse).unwrap();
+        close(pool);
+        assert!(submit_job(pool, b"late".to_vec()).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+///
+/// naml-std-queue - Persistent Job Queues
+///
+/// Background jobs stored in SQLite, so they survive restarts, with
+/// delayed jobs, retries and a capacity limit that pushes back on producers.
+///
+/// ## Functions
+///
+/// - `open_queue(path: string) -> int throws DBError` - Open or create a queue database
+/// - `close_queue(queue: int)` - Close a queue
+/// - `set_capacity(queue: int, max_jobs: int)` - Limit unfinished jobs (0 = unlimited)
+/// - `enqueue(queue: int, payload: string, run_at: int, max_retries: int) -> int throws DBError`
+///   - Add a job, waiting while the queue is full
+/// - `try_enqueue(queue: int, payload: string, run_at: int, max_retries: int) -> int throws DBError`
+///   - Add a job, or return -1 if the queue is full
+/// - `dequeue(queue: int, worker_id: string) -> int throws DBError` - Lease a due job, or -1
+/// - `dequeue_wait(queue: int, worker_id: string, timeout_ms: int) -> int throws DBError`
+///   - Lease a job, waiting up to `timeout_ms` for one to become due
+/// - `job_payload(queue: int, job: int) -> string`
+/// - `job_attempts(queue: int, job: int) -> int` - Times the job has been leased
+/// - `ack(queue: int, job: int) throws DBError` - Finish a job
+/// - `nack(queue: int, job: int, retry_delay_ms: int) throws DBError` - Retry later, or mark dead
+/// - `queue_size(queue: int) -> int` - Pending and running jobs
+/// - `dead_count(queue: int) -> int` - Jobs that ran out of retries
+///
+/// ## Concurrency
+///
+/// A queue handle may be shared by any number of threads. Producers blocked
+/// in `enqueue` and workers blocked in `dequeue_wait` are woken whenever a
+/// job is added, finished or re-queued. `run_at` is in milliseconds since
+/// the Unix epoch (`std::datetime::now_ms`); 0 means now.
+///
+
+mod store;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use naml_std_core::{naml_string_new, NamlString};
+use naml_std_sqlite3::{sqlite_error_code, throw_db_error};
+use rusqlite::Connection;
+
+use store::Nack;
+
+struct Queue {
+    conn: Mutex<Connection>,
+    /// Most unfinished jobs allowed, 0 for no limit
+    capacity: AtomicI64,
+    /// Signalled whenever a job is added, finished or re-queued
+    changed: Condvar,
+}
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static QUEUES: LazyLock<Mutex<HashMap<i64, Arc<Queue>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn get(handle: i64) -> Option<Arc<Queue>> {
+    QUEUES.lock().unwrap().get(&handle).cloned()
+}
+
+/// Like `get`, but throws DBError for an unknown handle
+fn lookup(handle: i64) -> Option<Arc<Queue>> {
+    let queue = get(handle);
+    if queue.is_none() {
+        throw_db_error("Invalid queue handle", -1);
+    }
+    queue
+}
+
+/// Unwrap a storage result, throwing DBError on failure
+fn check<T>(result: rusqlite::Result<T>) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            throw_db_error(&e.to_string(), sqlite_error_code(&e));
+            None
+        }
+    }
+}
+
+/// Open (creating if needed) the queue database at `path`; ":memory:" gives
+/// a queue that lives only as long as the handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_open(path: *const NamlString) -> i64 {
+    let path = string_from_naml(path);
+    let Some(conn) = check(store::open(&path)) else {
+        return 0;
+    };
+    let queue = Arc::new(Queue {
+        conn: Mutex::new(conn),
+        capacity: AtomicI64::new(0),
+        changed: Condvar::new(),
+    });
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
+    QUEUES.lock().unwrap().insert(handle, queue);
+    handle
+}
+
+/// Close a queue; jobs stay in the database
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_close(handle: i64) {
+    if let Some(queue) = QUEUES.lock().unwrap().remove(&handle) {
+        queue.changed.notify_all();
+    }
+}
+
+/// Limit the number of unfinished jobs; 0 removes the limit
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_set_capacity(handle: i64, max_jobs: i64) {
+    if let Some(queue) = QUEUES.lock().unwrap().get(&handle) {
+        queue.capacity.store(max_jobs.max(0), Ordering::SeqCst);
+        queue.changed.notify_all();
+    }
+}
+
+/// Add a job. With `wait` the caller blocks while the queue is full;
+/// otherwise -1 is returned.
+fn enqueue(handle: i64, payload: *const NamlString, run_at: i64, max_retries: i64, wait: bool) -> i64 {
+    let Some(queue) = lookup(handle) else {
+        return -1;
+    };
+    let payload = string_from_naml(payload);
+    let run_at = if run_at <= 0 { now_ms() } else { run_at };
+
+    let mut conn = queue.conn.lock().unwrap();
+    loop {
+        let capacity = queue.capacity.load(Ordering::SeqCst);
+        if capacity == 0 {
+            break;
+        }
+        let Some(unfinished) = check(store::unfinished(&conn)) else {
+            return -1;
+        };
+        if unfinished < capacity {
+            break;
+        }
+        if !wait {
+            return -1;
+        }
+        conn = queue.changed.wait(conn).unwrap();
+    }
+    let Some(id) = check(store::insert(&conn, &payload, run_at, max_retries)) else {
+        return -1;
+    };
+    drop(conn);
+    queue.changed.notify_all();
+    id
+}
+
+/// Add a job, blocking while the queue is at capacity. Returns the job id.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_enqueue(
+    handle: i64,
+    payload: *const NamlString,
+    run_at: i64,
+    max_retries: i64,
+) -> i64 {
+    enqueue(handle, payload, run_at, max_retries, true)
+}
+
+/// Add a job unless the queue is at capacity. Returns the job id or -1.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_try_enqueue(
+    handle: i64,
+    payload: *const NamlString,
+    run_at: i64,
+    max_retries: i64,
+) -> i64 {
+    enqueue(handle, payload, run_at, max_retries, false)
+}
+
+/// Lease the earliest due job to `worker_id`. Returns the job id, or -1 if
+/// no job is due.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_dequeue(handle: i64, worker_id: *const NamlString) -> i64 {
+    let Some(queue) = lookup(handle) else {
+        return -1;
+    };
+    let worker = string_from_naml(worker_id);
+    let conn = queue.conn.lock().unwrap();
+    check(store::lease(&conn, &worker, now_ms())).flatten().unwrap_or(-1)
+}
+
+/// Lease a job, waiting up to `timeout_ms` for one to be added or become
+/// due. Returns the job id, or -1 on timeout.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_dequeue_wait(
+    handle: i64,
+    worker_id: *const NamlString,
+    timeout_ms: i64,
+) -> i64 {
+    let Some(queue) = lookup(handle) else {
+        return -1;
+    };
+    let worker = string_from_naml(worker_id);
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms.max(0) as u64);
+
+    let mut conn = queue.conn.lock().unwrap();
+    loop {
+        let now = now_ms();
+        let Some(leased) = check(store::lease(&conn, &worker, now)) else {
+            return -1;
+        };
+        if let Some(id) = leased {
+            return id;
+        }
+        let Some(next_due) = check(store::next_due(&conn)) else {
+            return -1;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return -1;
+        }
+        let until_due = next_due.map(|at| Duration::from_millis((at - now).max(1) as u64));
+        let wait = until_due.map_or(remaining, |due| due.min(remaining));
+        conn = queue.changed.wait_timeout(conn, wait).unwrap().0;
+    }
+}
+
+/// The job's payload, or an empty string for an unknown job
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_job_payload(handle: i64, job: i64) -> *mut NamlString {
+    let payload = get(handle)
+        .and_then(|queue| store::payload(&queue.conn.lock().unwrap(), job).ok())
+        .flatten()
+        .unwrap_or_default();
+    unsafe { naml_string_new(payload.as_ptr(), payload.len()) }
+}
+
+/// How many times the job has been leased, including the current lease
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_job_attempts(handle: i64, job: i64) -> i64 {
+    get(handle)
+        .and_then(|queue| store::attempts(&queue.conn.lock().unwrap(), job).ok())
+        .flatten()
+        .unwrap_or(0)
+}
+
+/// Mark a leased job as done and remove it
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_ack(handle: i64, job: i64) {
+    let Some(queue) = lookup(handle) else {
+        return;
+    };
+    let removed = check(store::ack(&queue.conn.lock().unwrap(), job));
+    match removed {
+        Some(true) => queue.changed.notify_all(),
+        Some(false) => throw_db_error(&format!("job {} is not running", job), -1),
+        None => {}
+    }
+}
+
+/// Return a leased job for another attempt after `retry_delay_ms`, or mark
+/// it dead if it has used up its retries
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_nack(handle: i64, job: i64, retry_delay_ms: i64) {
+    let Some(queue) = lookup(handle) else {
+        return;
+    };
+    let outcome = check(store::nack(&queue.conn.lock().unwrap(), job, retry_delay_ms, now_ms()));
+    match outcome {
+        Some(Nack::Retry(_)) | Some(Nack::Dead) => queue.changed.notify_all(),
+        Some(Nack::NotRunning) => throw_db_error(&format!("job {} is not running", job), -1),
+        None => {}
+    }
+}
+
+/// Number of pending and running jobs
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_size(handle: i64) -> i64 {
+    let Some(queue) = get(handle) else {
+        return 0;
+    };
+    let conn = queue.conn.lock().unwrap();
+    store::unfinished(&conn).unwrap_or(0)
+}
+
+/// Number of jobs that ran out of retries
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_queue_dead_count(handle: i64) -> i64 {
+    let Some(queue) = get(handle) else {
+        return 0;
+    };
+    let conn = queue.conn.lock().unwrap();
+    store::dead(&conn).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naml(s: &str) -> *mut NamlString {
+        unsafe { naml_string_new(s.as_ptr(), s.len()) }
+    }
+
+    #[test]
+    fn test_enqueue_waits_for_capacity() {
+        let q = naml_queue_open(naml(":memory:"));
+        naml_queue_set_capacity(q, 1);
+        let first = naml_queue_enqueue(q, naml("first"), 0, 0);
+        assert_eq!(naml_queue_try_enqueue(q, naml("extra"), 0, 0), -1);
+
+        let producer = std::thread::spawn(move || {
+            naml_queue_enqueue(q, naml("second") as *const NamlString, 0, 0)
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!producer.is_finished());
+        assert_eq!(naml_queue_size(q), 1);
+
+        assert_eq!(naml_queue_dequeue(q, naml("w")), first);
+        naml_queue_ack(q, first);
+        let second = producer.join().unwrap();
+        assert_eq!(naml_queue_dequeue_wait(q, naml("w"), 1000), second);
+        naml_queue_close(q);
+    }
+}
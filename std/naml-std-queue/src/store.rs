@@ -0,0 +1,198 @@
+///
+/// Job Storage
+///
+/// Jobs live in a single `jobs` table. A job is `pending` until a worker
+/// leases it, `running` while leased, and either deleted by `ack` or put
+/// back to `pending` with a later `run_at` by `nack`. Once a job has been
+/// attempted `max_retries + 1` times a further `nack` marks it `dead`; dead
+/// jobs stay in the table for inspection but no longer count towards the
+/// queue's capacity.
+///
+/// Times are milliseconds since the Unix epoch, the same unit as
+/// `std::datetime::now_ms`.
+///
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS jobs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        payload TEXT NOT NULL,
+        run_at INTEGER NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        max_retries INTEGER NOT NULL,
+        status TEXT NOT NULL DEFAULT 'pending',
+        worker TEXT
+    );
+    CREATE INDEX IF NOT EXISTS jobs_due ON jobs (status, run_at, id);
+";
+
+/// Open the job table, creating it if needed. Jobs a previous process left
+/// `running` are returned to `pending`, since their worker is gone.
+pub(crate) fn open(path: &str) -> rusqlite::Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+    conn.execute(
+        "UPDATE jobs SET status = 'pending', worker = NULL WHERE status = 'running'",
+        [],
+    )?;
+    Ok(conn)
+}
+
+pub(crate) fn insert(
+    conn: &Connection,
+    payload: &str,
+    run_at: i64,
+    max_retries: i64,
+) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO jobs (payload, run_at, max_retries) VALUES (?1, ?2, ?3)",
+        params![payload, run_at, max_retries.max(0)],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Jobs that are pending or running, which is what the capacity limits
+pub(crate) fn unfinished(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("SELECT COUNT(*) FROM jobs WHERE status != 'dead'", [], |r| r.get(0))
+}
+
+pub(crate) fn dead(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("SELECT COUNT(*) FROM jobs WHERE status = 'dead'", [], |r| r.get(0))
+}
+
+/// Lease the earliest due job to `worker`, or None if nothing is due at `now`
+pub(crate) fn lease(conn: &Connection, worker: &str, now: i64) -> rusqlite::Result<Option<i64>> {
+    let id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM jobs WHERE status = 'pending' AND run_at <= ?1
+             ORDER BY run_at, id LIMIT 1",
+            params![now],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if let Some(id) = id {
+        conn.execute(
+            "UPDATE jobs SET status = 'running', worker = ?1, attempts = attempts + 1
+             WHERE id = ?2",
+            params![worker, id],
+        )?;
+    }
+    Ok(id)
+}
+
+/// When the next pending job becomes due, if there is one
+pub(crate) fn next_due(conn: &Connection) -> rusqlite::Result<Option<i64>> {
+    conn.query_row("SELECT MIN(run_at) FROM jobs WHERE status = 'pending'", [], |r| r.get(0))
+}
+
+pub(crate) fn payload(conn: &Connection, job: i64) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT payload FROM jobs WHERE id = ?1", params![job], |r| r.get(0))
+        .optional()
+}
+
+pub(crate) fn attempts(conn: &Connection, job: i64) -> rusqlite::Result<Option<i64>> {
+    conn.query_row("SELECT attempts FROM jobs WHERE id = ?1", params![job], |r| r.get(0))
+        .optional()
+}
+
+/// Remove a finished job. Returns false if the job is not running.
+pub(crate) fn ack(conn: &Connection, job: i64) -> rusqlite::Result<bool> {
+    let removed = conn.execute(
+        "DELETE FROM jobs WHERE id = ?1 AND status = 'running'",
+        params![job],
+    )?;
+    Ok(removed == 1)
+}
+
+/// Outcome of a failed attempt
+#[derive(Debug, PartialEq)]
+pub(crate) enum Nack {
+    /// Pending again, due at the given time
+    Retry(i64),
+    /// Out of retries
+    Dead,
+    /// The job is not running
+    NotRunning,
+}
+
+/// Return a failed job to the queue, due `retry_delay_ms` after `now`, or
+/// mark it dead once its retries are used up
+pub(crate) fn nack(conn: &Connection, job: i64, retry_delay_ms: i64, now: i64) -> rusqlite::Result<Nack> {
+    let row: Option<(i64, i64)> = conn
+        .query_row(
+            "SELECT attempts, max_retries FROM jobs WHERE id = ?1 AND status = 'running'",
+            params![job],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+    let Some((attempts, max_retries)) = row else {
+        return Ok(Nack::NotRunning);
+    };
+    if attempts > max_retries {
+        conn.execute(
+            "UPDATE jobs SET status = 'dead', worker = NULL WHERE id = ?1",
+            params![job],
+        )?;
+        return Ok(Nack::Dead);
+    }
+    let run_at = now + retry_delay_ms.max(0);
+    conn.execute(
+        "UPDATE jobs SET status = 'pending', worker = NULL, run_at = ?1 WHERE id = ?2",
+        params![run_at, job],
+    )?;
+    Ok(Nack::Retry(run_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lease_respects_run_at_and_order() {
+        let conn = open(":memory:").unwrap();
+        let later = insert(&conn, "later", 500, 0).unwrap();
+        let first = insert(&conn, "first", 100, 0).unwrap();
+        let second = insert(&conn, "second", 100, 0).unwrap();
+
+        assert_eq!(lease(&conn, "w", 50).unwrap(), None);
+        assert_eq!(next_due(&conn).unwrap(), Some(100));
+        assert_eq!(lease(&conn, "w", 200).unwrap(), Some(first));
+        assert_eq!(lease(&conn, "w", 200).unwrap(), Some(second));
+        assert_eq!(lease(&conn, "w", 200).unwrap(), None);
+        assert_eq!(lease(&conn, "w", 500).unwrap(), Some(later));
+        assert_eq!(unfinished(&conn).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_nack_retries_then_dead() {
+        let conn = open(":memory:").unwrap();
+        let job = insert(&conn, "flaky", 0, 1).unwrap();
+
+        assert_eq!(lease(&conn, "w", 0).unwrap(), Some(job));
+        assert_eq!(nack(&conn, job, 1000, 10).unwrap(), Nack::Retry(1010));
+        assert_eq!(lease(&conn, "w", 500).unwrap(), None);
+        assert_eq!(lease(&conn, "w", 1010).unwrap(), Some(job));
+        assert_eq!(attempts(&conn, job).unwrap(), Some(2));
+        assert_eq!(nack(&conn, job, 1000, 1020).unwrap(), Nack::Dead);
+        assert_eq!(nack(&conn, job, 1000, 1020).unwrap(), Nack::NotRunning);
+        assert_eq!((unfinished(&conn).unwrap(), dead(&conn).unwrap()), (0, 1));
+        assert!(!ack(&conn, job).unwrap());
+    }
+
+    #[test]
+    fn test_reopen_recovers_running_jobs() {
+        let path = std::env::temp_dir().join(format!("naml_queue_{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        {
+            let conn = open(path).unwrap();
+            let job = insert(&conn, "crash", 0, 3).unwrap();
+            assert_eq!(lease(&conn, "w", 0).unwrap(), Some(job));
+        }
+        let conn = open(path).unwrap();
+        assert!(lease(&conn, "w2", 0).unwrap().is_some());
+        drop(conn);
+        let _ = std::fs::remove_file(path);
+    }
+}
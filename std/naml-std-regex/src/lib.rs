@@ -0,0 +1,162 @@
+///
+/// naml-std-regex - Regular Expressions
+///
+/// Patterns are compiled once into an integer handle and reused, so hot
+/// loops do not pay for recompilation. Matching runs in linear time (no
+/// backtracking), which makes it safe for untrusted input; the price is
+/// that look-around and backreferences are not supported.
+///
+/// ## Functions
+///
+/// - `regex_compile(pattern: string) -> int throws RegexError` - Compile a pattern
+/// - `regex_match(handle: int, s: string) -> bool` - Match anywhere in `s`
+/// - `regex_find_all(handle: int, s: string) -> [string]` - Non-overlapping matches
+/// - `regex_captures(handle: int, s: string) -> [string]` - Groups of the first match
+/// - `regex_replace_all(handle: int, s: string, replacement: string) -> string`
+///   - `$1`, `${name}` and `$$` in the replacement expand to groups and `$`
+/// - `regex_close(handle: int)` - Release a compiled pattern
+///
+/// ## Handles
+///
+/// Compiled patterns live in a process-wide registry (the same scheme as
+/// sqlite connections) and can be shared between threads. Unknown or
+/// closed handles never match: `regex_match` returns false, the array
+/// functions return `[]` and `regex_replace_all` returns `s` unchanged.
+///
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use naml_std_core::{
+    naml_array_new, naml_array_push, naml_exception_set_typed, naml_stack_capture, naml_string_new,
+    NamlArray, NamlString, EXCEPTION_TYPE_REGEX_ERROR,
+};
+use regex::Regex;
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static PATTERNS: LazyLock<Mutex<HashMap<i64, Arc<Regex>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+unsafe fn strings_result<'a>(strings: impl Iterator<Item = &'a str>) -> *mut NamlArray {
+    unsafe {
+        let arr = naml_array_new(0);
+        for s in strings {
+            naml_array_push(arr, naml_from_string(s) as i64);
+        }
+        arr
+    }
+}
+
+/// Throw RegexError with layout: message@0, stack@8, pattern@16
+fn throw_regex_error(message: &str, pattern: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate RegexError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_from_string(message);
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut *mut NamlString) = naml_from_string(pattern);
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_REGEX_ERROR);
+    }
+}
+
+/// The compiled pattern for a handle; cloned out so matching runs without
+/// holding the registry lock
+fn pattern(handle: i64) -> Option<Arc<Regex>> {
+    PATTERNS.lock().unwrap().get(&handle).cloned()
+}
+
+/// Compile a pattern into a handle
+///
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_regex_compile(pattern: *const NamlString) -> i64 {
+    let pattern = unsafe { string_from_naml(pattern) };
+    match Regex::new(&pattern) {
+        Ok(re) => {
+            let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+            PATTERNS.lock().unwrap().insert(handle, Arc::new(re));
+            handle
+        }
+        Err(e) => {
+            throw_regex_error(&e.to_string(), &pattern);
+            -1
+        }
+    }
+}
+
+/// Whether the pattern matches anywhere in `s`
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_regex_match(handle: i64, s: *const NamlString) -> i64 {
+    let s = unsafe { string_from_naml(s) };
+    pattern(handle).is_some_and(|re| re.is_match(&s)) as i64
+}
+
+/// Every non-overlapping match, left to right
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_regex_find_all(handle: i64, s: *const NamlString) -> *mut NamlArray {
+    let s = unsafe { string_from_naml(s) };
+    match pattern(handle) {
+        Some(re) => unsafe { strings_result(re.find_iter(&s).map(|m| m.as_str())) },
+        None => unsafe { naml_array_new(0) },
+    }
+}
+
+/// The first match followed by each capture group; groups that did not
+/// take part in the match are empty strings. Empty when nothing matches.
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_regex_captures(handle: i64, s: *const NamlString) -> *mut NamlArray {
+    let s = unsafe { string_from_naml(s) };
+    match pattern(handle).as_deref().and_then(|re| re.captures(&s)) {
+        Some(caps) => unsafe { strings_result(caps.iter().map(|g| g.map_or("", |m| m.as_str()))) },
+        None => unsafe { naml_array_new(0) },
+    }
+}
+
+/// Replace every match, expanding `$1`, `${name}` and `$$`
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString and
+/// `replacement` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_regex_replace_all(
+    handle: i64,
+    s: *const NamlString,
+    replacement: *const NamlString,
+) -> *mut NamlString {
+    let (s, replacement) = unsafe { (string_from_naml(s), string_from_naml(replacement)) };
+    match pattern(handle) {
+        Some(re) => unsafe { naml_from_string(&re.replace_all(&s, replacement.as_str())) },
+        None => unsafe { naml_from_string(&s) },
+    }
+}
+
+/// Release a compiled pattern; unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_regex_close(handle: i64) {
+    PATTERNS.lock().unwrap().remove(&handle);
+}
@@ -22,14 +22,16 @@ use naml_std_core::{
 };
 use rusqlite::{params_from_iter, Connection, Statement, types::Value as SqlValue};
 
-fn sqlite_error_code(e: &rusqlite::Error) -> i64 {
+/// Extended SQLite error code for a rusqlite error, or -1
+pub fn sqlite_error_code(e: &rusqlite::Error) -> i64 {
     match e {
         rusqlite::Error::SqliteFailure(err, _) => err.extended_code as i64,
         _ => -1,
     }
 }
 
-fn throw_db_error(message: &str, code: i64) {
+/// Throw DBError with layout: message@0, stack@8, code@16
+pub fn throw_db_error(message: &str, code: i64) {
     unsafe {
         let message_ptr = naml_string_new(message.as_ptr(), message.len());
         let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
@@ -51,7 +53,7 @@ fn string_from_naml(s: *const NamlString) -> String {
         return String::new();
     }
     unsafe {
-        let slice = std::slice::from_raw_parts((*s).data.as_ptr(), (*s).len);
+        let slice = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
         String::from_utf8_lossy(slice).into_owned()
     }
 }
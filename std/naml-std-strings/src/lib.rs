@@ -24,6 +24,9 @@
 //! ## Extraction
 //! - `substr(s: string, start: int, end: int) -> string` - Get substring
 //!
+//! Trimming and `substr` return views that share the input's bytes when the
+//! result is too long to store inline.
+//!
 //! ## Padding
 //! - `lpad(s: string, len: int, char: string) -> string` - Pad from start
 //! - `rpad(s: string, len: int, char: string) -> string` - Pad from end
@@ -31,6 +34,18 @@
 //! ## Other
 //! - `repeat(s: string, n: int) -> string` - Repeat n times
 //!
+//! ## Case Styles
+//! - `capitalize(s: string) -> string` - Uppercase the first character
+//! - `to_title(s: string) -> string` - "hello_world" -> "Hello World"
+//! - `to_snake(s: string) -> string` - "helloWorld" -> "hello_world"
+//! - `to_camel(s: string) -> string` - "hello world" -> "helloWorld"
+//! - `to_kebab(s: string) -> string` - "HelloWorld" -> "hello-world"
+//!
+//! The case style functions split words at anything that is not a letter or
+//! digit, at lower-to-upper transitions ("fooBar"), and before the last capital
+//! of an acronym ("HTTPServer" -> "HTTP", "Server"). Digits stay with the word
+//! they follow. Case changes use Unicode case mapping.
+//!
 //! ## Splitting (returns arrays)
 //! - `split(s: string, delim: string) -> [string]` - Split by delimiter
 //! - `lines(s: string) -> [string]` - Split by newlines
@@ -39,8 +54,32 @@
 //! ## Joining
 //! - `concat(arr: [string], delim: string) -> string` - Join array with delimiter
 //!
+//! ## Numbers
+//! - `parse_int_radix`, `to_radix`, `to_hex`, `to_octal`, `to_binary`, `group_digits` (see `radix`)
+//!
+//! ## Unicode
+//! - `normalize`, `casefold`, `graphemes`, `width` (see `unicode`)
+//!
+//! ## Comparison
+//! - `compare(a: string, b: string) -> int` - Code point order (-1, 0, 1)
+//! - `equals_ignore_case(a: string, b: string) -> bool` - Unicode case-insensitive equality
+//! - `natural_compare(a: string, b: string) -> int` - Digit runs compare by value ("file2" < "file10")
+//! - `collate(a: string, b: string, locale: string) -> int` - Dictionary order for a locale
+//!
+//! Collation folds Latin accents and case at the first level, so "apple", "Äpfel" and
+//! "Zebra" sort alphabetically instead of by code point. Ties are broken by accent, then
+//! case, then code point, so the order is total. The locale selects tailorings: `sv`/`fi`
+//! sort å, ä, ö after z, `da`/`nb`/`nn`/`no` sort æ, ø, å after z, and `C`/`POSIX` (or
+//! an empty string) fall back to `compare`. Other locales use the root order.
+//!
+
+pub mod radix;
+pub mod unicode;
 
-use naml_std_core::{NamlString, NamlArray, naml_string_new, naml_string_incref, naml_array_new, naml_array_push};
+pub use radix::*;
+pub use unicode::*;
+
+use naml_std_core::{NamlString, NamlArray, naml_string_new, naml_string_incref, naml_string_view, naml_array_new, naml_array_push, simd};
 
 /// Convert string to uppercase
 #[unsafe(no_mangle)]
@@ -75,9 +114,9 @@ pub unsafe extern "C" fn naml_string_contains(s: *const NamlString, substr: *con
         if s.is_null() || substr.is_null() {
             return 0;
         }
-        let str_val = (*s).as_str();
-        let sub_val = (*substr).as_str();
-        if str_val.contains(sub_val) { 1 } else { 0 }
+        let haystack = std::slice::from_raw_parts((*s).as_ptr(), (*s).len);
+        let needle = std::slice::from_raw_parts((*substr).as_ptr(), (*substr).len);
+        if simd::find_bytes(haystack, needle).is_some() { 1 } else { 0 }
     }
 }
 
@@ -154,7 +193,8 @@ pub unsafe extern "C" fn naml_string_ltrim(s: *const NamlString) -> *mut NamlStr
         }
         let str_val = (*s).as_str();
         let trimmed = str_val.trim_start();
-        naml_string_new(trimmed.as_ptr(), trimmed.len())
+        let start = trimmed.as_ptr().offset_from(str_val.as_ptr()) as usize;
+        naml_string_view(s, start, start + trimmed.len())
     }
 }
 
@@ -167,7 +207,8 @@ pub unsafe extern "C" fn naml_string_rtrim(s: *const NamlString) -> *mut NamlStr
         }
         let str_val = (*s).as_str();
         let trimmed = str_val.trim_end();
-        naml_string_new(trimmed.as_ptr(), trimmed.len())
+        let start = trimmed.as_ptr().offset_from(str_val.as_ptr()) as usize;
+        naml_string_view(s, start, start + trimmed.len())
     }
 }
 
@@ -196,8 +237,7 @@ pub unsafe extern "C" fn naml_string_substr(s: *const NamlString, start: i64, en
             char_indices[end_idx].0
         };
 
-        let substr = &str_val[byte_start..byte_end];
-        naml_string_new(substr.as_ptr(), substr.len())
+        naml_string_view(s, byte_start, byte_end)
     }
 }
 
@@ -373,6 +413,384 @@ pub unsafe extern "C" fn naml_string_chars(s: *const NamlString) -> *mut NamlArr
     }
 }
 
+/// Uppercase the first character of a string, leaving the rest unchanged
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_capitalize(s: *const NamlString) -> *mut NamlString {
+    unsafe {
+        let str_val = string_str(s);
+        let mut chars = str_val.chars();
+        let result: String = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        };
+        naml_string_new(result.as_ptr(), result.len())
+    }
+}
+
+/// Convert a string to Title Case words separated by spaces
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_to_title(s: *const NamlString) -> *mut NamlString {
+    unsafe { join_words(string_str(s), " ", WordCase::Capitalized, WordCase::Capitalized) }
+}
+
+/// Convert a string to snake_case
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_to_snake(s: *const NamlString) -> *mut NamlString {
+    unsafe { join_words(string_str(s), "_", WordCase::Lower, WordCase::Lower) }
+}
+
+/// Convert a string to camelCase
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_to_camel(s: *const NamlString) -> *mut NamlString {
+    unsafe { join_words(string_str(s), "", WordCase::Lower, WordCase::Capitalized) }
+}
+
+/// Convert a string to kebab-case
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_to_kebab(s: *const NamlString) -> *mut NamlString {
+    unsafe { join_words(string_str(s), "-", WordCase::Lower, WordCase::Lower) }
+}
+
+#[derive(Clone, Copy)]
+enum WordCase {
+    Lower,
+    Capitalized,
+}
+
+unsafe fn join_words(s: &str, separator: &str, first: WordCase, rest: WordCase) -> *mut NamlString {
+    let mut result = String::with_capacity(s.len() + separator.len() * 4);
+    for (i, word) in split_words(s).into_iter().enumerate() {
+        if i > 0 {
+            result.push_str(separator);
+        }
+        let case = if i == 0 { first } else { rest };
+        match case {
+            WordCase::Lower => result.extend(word.chars().flat_map(char::to_lowercase)),
+            WordCase::Capitalized => {
+                let mut chars = word.chars();
+                if let Some(c) = chars.next() {
+                    result.extend(c.to_uppercase());
+                    result.extend(chars.flat_map(char::to_lowercase));
+                }
+            }
+        }
+    }
+    naml_string_new(result.as_ptr(), result.len())
+}
+
+fn split_words(s: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let mut start: Option<usize> = None;
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(begin) = start.take() {
+                words.push(&s[begin..offset]);
+            }
+            continue;
+        }
+        let Some(begin) = start else {
+            start = Some(offset);
+            continue;
+        };
+        let prev = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).is_some_and(|&(_, n)| n.is_lowercase());
+        let boundary = c.is_uppercase()
+            && ((prev.is_lowercase() || prev.is_numeric())
+                || (prev.is_uppercase() && next_is_lower));
+        if boundary {
+            words.push(&s[begin..offset]);
+            start = Some(offset);
+        }
+    }
+    if let Some(begin) = start {
+        words.push(&s[begin..]);
+    }
+    words
+}
+
+/// Compare two strings by code point, returning -1, 0 or 1
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString and `b` is a
+/// valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_compare(a: *const NamlString, b: *const NamlString) -> i64 {
+    unsafe { ordering_to_int(string_bytes(a).cmp(string_bytes(b))) }
+}
+
+/// Check whether two strings are equal ignoring case
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString and `b` is a
+/// valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_equals_ignore_case(a: *const NamlString, b: *const NamlString) -> i64 {
+    unsafe {
+        let a = string_str(a);
+        let b = string_str(b);
+        let equal = if a.is_ascii() && b.is_ascii() {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a.chars()
+                .flat_map(char::to_lowercase)
+                .eq(b.chars().flat_map(char::to_lowercase))
+        };
+        if equal { 1 } else { 0 }
+    }
+}
+
+/// Compare two strings treating runs of ASCII digits as numbers
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString and `b` is a
+/// valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_natural_compare(a: *const NamlString, b: *const NamlString) -> i64 {
+    unsafe { ordering_to_int(natural_cmp(string_str(a), string_str(b))) }
+}
+
+/// Compare two strings in dictionary order for a locale
+///
+/// # Safety
+/// The caller must ensure `a` is a valid pointer to a NamlString, `b` is a
+/// valid pointer to a NamlString and `locale` is a valid pointer to a
+/// NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_collate(
+    a: *const NamlString,
+    b: *const NamlString,
+    locale: *const NamlString,
+) -> i64 {
+    unsafe {
+        let a = string_str(a);
+        let b = string_str(b);
+        let ordering = match Tailoring::for_locale(string_str(locale)) {
+            None => a.cmp(b),
+            Some(tailoring) => collate_cmp(a, b, tailoring),
+        };
+        ordering_to_int(ordering)
+    }
+}
+
+unsafe fn string_bytes<'a>(s: *const NamlString) -> &'a [u8] {
+    if s.is_null() {
+        return &[];
+    }
+    std::slice::from_raw_parts((*s).as_ptr(), (*s).len)
+}
+
+unsafe fn string_str<'a>(s: *const NamlString) -> &'a str {
+    if s.is_null() {
+        return "";
+    }
+    (*s).as_str()
+}
+
+fn ordering_to_int(ordering: std::cmp::Ordering) -> i64 {
+    ordering as i64
+}
+
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    let (mut i, mut j) = (0, 0);
+    // Equal numbers with different zero padding ("007" vs "7") are only
+    // ordered when nothing else differs, so "a07b" and "a7c" compare on b/c.
+    let mut padding = Ordering::Equal;
+
+    while i < a.len() && j < b.len() {
+        if a[i].is_ascii_digit() && b[j].is_ascii_digit() {
+            let a_start = i;
+            let b_start = j;
+            while i < a.len() && a[i].is_ascii_digit() {
+                i += 1;
+            }
+            while j < b.len() && b[j].is_ascii_digit() {
+                j += 1;
+            }
+            let a_run = &a[a_start..i];
+            let b_run = &b[b_start..j];
+            let a_digits = trim_leading_zeros(a_run);
+            let b_digits = trim_leading_zeros(b_run);
+            let ordering = a_digits
+                .len()
+                .cmp(&b_digits.len())
+                .then_with(|| a_digits.cmp(b_digits));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+            if padding == Ordering::Equal {
+                padding = b_run.len().cmp(&a_run.len());
+            }
+            continue;
+        }
+        // Compare whole UTF-8 sequences so multi-byte characters keep code point order.
+        let a_len = utf8_len(a[i]);
+        let b_len = utf8_len(b[j]);
+        let ordering = a[i..(i + a_len).min(a.len())].cmp(&b[j..(j + b_len).min(b.len())]);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        i += a_len;
+        j += b_len;
+    }
+
+    (a.len() - i.min(a.len()))
+        .cmp(&(b.len() - j.min(b.len())))
+        .then(padding)
+}
+
+fn trim_leading_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&d| d == b'0').count();
+    &digits[zeros..]
+}
+
+fn utf8_len(first: u8) -> usize {
+    match first {
+        0xF0..=0xFF => 4,
+        0xE0..=0xEF => 3,
+        0xC0..=0xDF => 2,
+        _ => 1,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tailoring {
+    Root,
+    /// Swedish and Finnish: å, ä, ö follow z (æ and ø sort with ä and ö)
+    Swedish,
+    /// Danish and Norwegian: æ, ø, å follow z (ä and ö sort with æ and ø)
+    Danish,
+}
+
+impl Tailoring {
+    /// `None` means plain code point order.
+    fn for_locale(locale: &str) -> Option<Tailoring> {
+        let language = locale
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "" | "c" | "posix" => None,
+            "sv" | "fi" => Some(Tailoring::Swedish),
+            "da" | "nb" | "nn" | "no" => Some(Tailoring::Danish),
+            _ => Some(Tailoring::Root),
+        }
+    }
+
+    /// Letters this locale sorts after z, as an offset past z.
+    fn after_z(self, c: char) -> Option<u32> {
+        match (self, c) {
+            (Tailoring::Swedish, 'å') => Some(1),
+            (Tailoring::Swedish, 'ä' | 'æ') => Some(2),
+            (Tailoring::Swedish, 'ö' | 'ø') => Some(3),
+            (Tailoring::Danish, 'æ' | 'ä') => Some(1),
+            (Tailoring::Danish, 'ø' | 'ö') => Some(2),
+            (Tailoring::Danish, 'å') => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// Base letters for U+00C0..=U+00FF; `*` marks characters handled separately.
+const LATIN1_BASE: &[u8; 64] = b"AAAAAA*CEEEEIIIIDNOOOOO*OUUUUY**aaaaaa*ceeeeiiiidnooooo*ouuuuy*y";
+/// Base letters for U+0100..=U+017F (Latin Extended-A).
+const LATIN_EXT_A_BASE: &[u8; 128] = b"AaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIiIiJjKkkLlLlLlLlLlNnNnNnnNnOoOoOoOoRrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZzs";
+
+/// One level-by-level collation element: letter, accent, case.
+#[derive(Clone, Copy)]
+struct CollationElement {
+    primary: u32,
+    secondary: u32,
+    tertiary: u8,
+}
+
+fn collation_elements(s: &str, tailoring: Tailoring) -> Vec<CollationElement> {
+    let mut elements = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let tertiary = u8::from(lower != c);
+        if let Some(offset) = tailoring.after_z(lower) {
+            elements.push(CollationElement {
+                primary: (('z' as u32) << 8) + offset,
+                secondary: 0,
+                tertiary,
+            });
+            continue;
+        }
+        let expansion: &[char] = match lower {
+            'æ' => &['a', 'e'],
+            'œ' => &['o', 'e'],
+            'ß' => &['s', 's'],
+            'þ' => &['t', 'h'],
+            'ĳ' => &['i', 'j'],
+            _ => &[],
+        };
+        if !expansion.is_empty() {
+            for &base in expansion {
+                elements.push(CollationElement {
+                    primary: (base as u32) << 8,
+                    secondary: lower as u32,
+                    tertiary,
+                });
+            }
+            continue;
+        }
+        let code = lower as u32;
+        let base = match code {
+            0xC0..=0xFF => LATIN1_BASE[(code - 0xC0) as usize],
+            0x100..=0x17F => LATIN_EXT_A_BASE[(code - 0x100) as usize],
+            _ => b'*',
+        };
+        let (primary, secondary) = if base == b'*' {
+            (code << 8, 0)
+        } else {
+            ((base.to_ascii_lowercase() as u32) << 8, code)
+        };
+        elements.push(CollationElement {
+            primary,
+            secondary,
+            tertiary,
+        });
+    }
+    elements
+}
+
+fn collate_cmp(a: &str, b: &str, tailoring: Tailoring) -> std::cmp::Ordering {
+    let a_elements = collation_elements(a, tailoring);
+    let b_elements = collation_elements(b, tailoring);
+    let level = |key: fn(&CollationElement) -> u32| {
+        a_elements
+            .iter()
+            .map(key)
+            .cmp(b_elements.iter().map(key))
+    };
+    level(|e| e.primary)
+        .then_with(|| level(|e| e.secondary))
+        .then_with(|| level(|e| e.tertiary as u32))
+        .then_with(|| a.cmp(b))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -404,6 +822,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_substr_of_long_string_is_view() {
+        unsafe {
+            let text = "héllo, a string longer than the inline capacity";
+            let s = naml_string_new(text.as_ptr(), text.len());
+            let result = naml_string_substr(s, 1, 40);
+            assert!((*result).header.is_view());
+            assert_eq!((*result).as_str(), text.chars().skip(1).take(39).collect::<String>());
+
+            let trimmed = naml_string_ltrim(naml_string_substr(result, 7, 39));
+            assert_eq!((*trimmed).as_str(), "string longer than the inline c");
+            assert_eq!(naml_string_contains(trimmed, naml_string_new("inline".as_ptr(), 6)), 1);
+            assert_eq!((*s).header.refcount(), 4);
+        }
+    }
+
     #[test]
     fn test_repeat() {
         unsafe {
@@ -412,4 +846,52 @@ mod tests {
             assert_eq!((*result).as_str(), "ababab");
         }
     }
+    #[test]
+    fn test_natural_compare() {
+        use std::cmp::Ordering::*;
+        assert_eq!(natural_cmp("file2", "file10"), Less);
+        assert_eq!(natural_cmp("file10", "file2"), Greater);
+        assert_eq!(natural_cmp("file10", "file10"), Equal);
+        assert_eq!(natural_cmp("file", "file1"), Less);
+        assert_eq!(natural_cmp("a07b", "a7c"), Less);
+        assert_eq!(natural_cmp("a007", "a7"), Less);
+        assert_eq!(natural_cmp("v1.10.0", "v1.9.3"), Greater);
+    }
+
+    #[test]
+    fn test_collate() {
+        use std::cmp::Ordering::*;
+        assert_eq!(collate_cmp("Äpfel", "Zebra", Tailoring::Root), Less);
+        assert_eq!(collate_cmp("apple", "Apple", Tailoring::Root), Less);
+        assert_eq!(collate_cmp("resume", "résumé", Tailoring::Root), Less);
+        assert_eq!(collate_cmp("résumé", "resumes", Tailoring::Root), Less);
+        assert_eq!(collate_cmp("Straße", "Strasse", Tailoring::Root), Greater);
+        assert_eq!(collate_cmp("öl", "zon", Tailoring::Swedish), Greater);
+        assert_eq!(collate_cmp("åsa", "ära", Tailoring::Swedish), Less);
+        assert_eq!(collate_cmp("åsa", "øre", Tailoring::Danish), Greater);
+        assert!(Tailoring::for_locale("C").is_none());
+        assert!(Tailoring::for_locale("sv_SE.UTF-8") == Some(Tailoring::Swedish));
+    }
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("helloWorld"), ["hello", "World"]);
+        assert_eq!(split_words("HTTPServer error"), ["HTTP", "Server", "error"]);
+        assert_eq!(split_words("  snake_case--and-kebab "), ["snake", "case", "and", "kebab"]);
+        assert_eq!(split_words("version2Beta"), ["version2", "Beta"]);
+        assert_eq!(split_words("ÉtéChaud"), ["Été", "Chaud"]);
+        assert!(split_words("__").is_empty());
+    }
+
+    #[test]
+    fn test_case_styles() {
+        unsafe {
+            let s = naml_string_new("parseHTTPResponse v2".as_ptr(), 20);
+            assert_eq!((*naml_string_to_snake(s)).as_str(), "parse_http_response_v2");
+            assert_eq!((*naml_string_to_kebab(s)).as_str(), "parse-http-response-v2");
+            assert_eq!((*naml_string_to_camel(s)).as_str(), "parseHttpResponseV2");
+            assert_eq!((*naml_string_to_title(s)).as_str(), "Parse Http Response V2");
+            let s = naml_string_new("élan vital".as_ptr(), "élan vital".len());
+            assert_eq!((*naml_string_capitalize(s)).as_str(), "Élan vital");
+        }
+    }
 }
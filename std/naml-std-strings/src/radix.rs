@@ -0,0 +1,268 @@
+//!
+//! Radix conversion and digit grouping
+//!
+//! - `parse_int_radix(s: string, base: int) -> int throws DecodeError` - Parse in base 2-36
+//! - `to_radix(n: int, base: int) -> string` - Format in base 2-36 (lowercase digits)
+//! - `to_hex(n: int) -> string`, `to_octal(n: int) -> string`, `to_binary(n: int) -> string`
+//! - `group_digits(s: string, size: int, sep: string) -> string` - "1234567" -> "1,234,567"
+//!
+//! Negative numbers are formatted with a leading `-` rather than as two's
+//! complement, so `parse_int_radix(to_radix(n, b), b) == n` for every `n`.
+//! Parsing accepts surrounding whitespace, a sign, a `0x`/`0o`/`0b` prefix
+//! matching the base, and `_` between digits.
+//!
+
+use naml_std_core::{
+    EXCEPTION_TYPE_DECODE_ERROR, NamlString, naml_exception_set_typed, naml_stack_capture,
+    naml_string_new,
+};
+
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Parse an integer in the given base, throwing DecodeError on invalid input
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_parse_int_radix(s: *const NamlString, base: i64) -> i64 {
+    unsafe {
+        let text = if s.is_null() { "" } else { (*s).as_str() };
+        match parse_int_radix(text, base) {
+            Ok(value) => value,
+            Err((message, position)) => {
+                throw_decode_error(&message, position as i64);
+                0
+            }
+        }
+    }
+}
+
+/// Format an integer in the given base
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_string_to_radix(n: i64, base: i64) -> *mut NamlString {
+    if !(2..=36).contains(&base) {
+        eprintln!("panic: radix must be between 2 and 36, got {}", base);
+        std::process::abort();
+    }
+    let text = to_radix(n, base as u32);
+    unsafe { naml_string_new(text.as_ptr(), text.len()) }
+}
+
+/// Format an integer in base 16
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_string_to_hex(n: i64) -> *mut NamlString {
+    naml_string_to_radix(n, 16)
+}
+
+/// Format an integer in base 8
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_string_to_octal(n: i64) -> *mut NamlString {
+    naml_string_to_radix(n, 8)
+}
+
+/// Format an integer in base 2
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_string_to_binary(n: i64) -> *mut NamlString {
+    naml_string_to_radix(n, 2)
+}
+
+/// Insert a separator every `size` digits, counting from the right of the
+/// integer part. A leading sign and anything from the first `.` on are kept as is.
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString or null and
+/// `sep` is a valid pointer to a NamlString or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_group_digits(
+    s: *const NamlString,
+    size: i64,
+    sep: *const NamlString,
+) -> *mut NamlString {
+    unsafe {
+        let text = if s.is_null() { "" } else { (*s).as_str() };
+        let sep = if sep.is_null() { "" } else { (*sep).as_str() };
+        let result = group_digits(text, size, sep);
+        naml_string_new(result.as_ptr(), result.len())
+    }
+}
+
+fn parse_int_radix(text: &str, base: i64) -> Result<i64, (String, usize)> {
+    if !(2..=36).contains(&base) {
+        return Err((format!("base must be between 2 and 36, got {}", base), 0));
+    }
+    let base = base as u32;
+    let leading = text.len() - text.trim_start().len();
+    let trimmed = text.trim();
+    let bytes = trimmed.as_bytes();
+
+    let mut pos = 0;
+    let negative = match bytes.first() {
+        Some(b'-') => {
+            pos = 1;
+            true
+        }
+        Some(b'+') => {
+            pos = 1;
+            false
+        }
+        _ => false,
+    };
+    let prefix = match base {
+        16 => Some(b'x'),
+        8 => Some(b'o'),
+        2 => Some(b'b'),
+        _ => None,
+    };
+    if let Some(p) = prefix
+        && bytes.len() > pos + 1
+        && bytes[pos] == b'0'
+        && bytes[pos + 1].to_ascii_lowercase() == p
+    {
+        pos += 2;
+    }
+
+    // Accumulate as a negative number so i64::MIN parses without overflow.
+    let mut value: i64 = 0;
+    let mut digits = 0;
+    let mut prev_underscore = false;
+    for (i, &b) in bytes.iter().enumerate().skip(pos) {
+        let position = leading + i;
+        if b == b'_' {
+            if digits == 0 || prev_underscore {
+                return Err(("misplaced '_' in number".to_string(), position));
+            }
+            prev_underscore = true;
+            continue;
+        }
+        let digit = match (b as char).to_digit(base) {
+            Some(d) => d as i64,
+            None => {
+                let ch = trimmed[i..].chars().next().unwrap_or('?');
+                return Err((
+                    format!("invalid digit '{}' for base {}", ch, base),
+                    position,
+                ));
+            }
+        };
+        value = match value
+            .checked_mul(base as i64)
+            .and_then(|v| v.checked_sub(digit))
+        {
+            Some(v) => v,
+            None => return Err(("number too large for int".to_string(), leading)),
+        };
+        digits += 1;
+        prev_underscore = false;
+    }
+
+    if digits == 0 {
+        return Err(("no digits to parse".to_string(), leading + pos));
+    }
+    if prev_underscore {
+        return Err(("misplaced '_' in number".to_string(), leading + bytes.len() - 1));
+    }
+    if negative {
+        Ok(value)
+    } else {
+        value
+            .checked_neg()
+            .ok_or_else(|| ("number too large for int".to_string(), leading))
+    }
+}
+
+fn to_radix(n: i64, base: u32) -> String {
+    let mut magnitude = n.unsigned_abs();
+    let mut buf = Vec::with_capacity(65);
+    loop {
+        buf.push(DIGITS[(magnitude % base as u64) as usize]);
+        magnitude /= base as u64;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        buf.push(b'-');
+    }
+    buf.reverse();
+    String::from_utf8(buf).unwrap_or_default()
+}
+
+fn group_digits(text: &str, size: i64, sep: &str) -> String {
+    if size <= 0 {
+        return text.to_string();
+    }
+    let size = size as usize;
+    let sign_len = if text.starts_with(['-', '+']) { 1 } else { 0 };
+    let int_end = text.find('.').unwrap_or(text.len());
+    let (sign, rest) = text[..int_end].split_at(sign_len);
+    let digit_count = rest.chars().count();
+
+    let mut result = String::with_capacity(text.len() + digit_count / size * sep.len());
+    result.push_str(sign);
+    for (i, c) in rest.chars().enumerate() {
+        if i > 0 && (digit_count - i) % size == 0 {
+            result.push_str(sep);
+        }
+        result.push(c);
+    }
+    result.push_str(&text[int_end..]);
+    result
+}
+
+fn throw_decode_error(message: &str, position: i64) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate DecodeError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_string_new(message.as_ptr(), message.len());
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut i64) = position;
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_DECODE_ERROR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_int_radix() {
+        assert_eq!(parse_int_radix("ff", 16), Ok(255));
+        assert_eq!(parse_int_radix(" 0xFF ", 16), Ok(255));
+        assert_eq!(parse_int_radix("-0b1010", 2), Ok(-10));
+        assert_eq!(parse_int_radix("1111_0000", 2), Ok(240));
+        assert_eq!(parse_int_radix("zz", 36), Ok(1295));
+        assert_eq!(parse_int_radix("-8000000000000000", 16), Ok(i64::MIN));
+        assert_eq!(parse_int_radix("8000000000000000", 16).unwrap_err().1, 0);
+        assert_eq!(parse_int_radix("12g4", 16).unwrap_err().1, 2);
+        assert_eq!(parse_int_radix("1__0", 2).unwrap_err().1, 2);
+        assert!(parse_int_radix("", 10).is_err());
+        assert!(parse_int_radix("0x", 16).is_err());
+        assert!(parse_int_radix("10", 1).is_err());
+    }
+
+    #[test]
+    fn test_to_radix() {
+        assert_eq!(to_radix(255, 16), "ff");
+        assert_eq!(to_radix(0, 2), "0");
+        assert_eq!(to_radix(-10, 2), "-1010");
+        assert_eq!(to_radix(i64::MIN, 16), "-8000000000000000");
+        assert_eq!(to_radix(1295, 36), "zz");
+        for n in [i64::MIN, -1, 0, 1, 42, i64::MAX] {
+            for base in [2, 8, 10, 16, 36] {
+                assert_eq!(parse_int_radix(&to_radix(n, base), base as i64), Ok(n));
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits("1234567", 3, ","), "1,234,567");
+        assert_eq!(group_digits("-1234567.891", 3, ","), "-1,234,567.891");
+        assert_eq!(group_digits("123", 3, ","), "123");
+        assert_eq!(group_digits("11110000", 4, "_"), "1111_0000");
+        assert_eq!(group_digits("1234", 0, ","), "1234");
+    }
+}
@@ -0,0 +1,130 @@
+//!
+//! Unicode Text Functions
+//!
+//! - `normalize(s: string, form: string) -> string` - NFC, NFD, NFKC or NFKD
+//! - `casefold(s: string) -> string` - Full Unicode case folding for caseless matching
+//! - `graphemes(s: string) -> [string]` - Split into user-perceived characters
+//! - `width(s: string) -> int` - Terminal display width in columns
+//!
+//! `chars` splits a string into code points, which breaks up emoji sequences
+//! and decomposed accents; `graphemes` keeps them whole using the extended
+//! grapheme cluster rules of UAX #29. `width` counts East Asian wide and
+//! fullwidth characters as two columns and combining marks and zero-width
+//! characters as none, so `lpad`-style alignment can be computed for
+//! terminals.
+//!
+
+use icu_casemap::CaseMapper;
+use naml_std_core::{naml_array_new, naml_array_push, naml_string_new, NamlArray, NamlString};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+unsafe fn string_str<'a>(s: *const NamlString) -> &'a str {
+    if s.is_null() { "" } else { unsafe { (*s).as_str() } }
+}
+
+fn normalize(s: &str, form: &str) -> Option<String> {
+    match form.to_ascii_uppercase().as_str() {
+        "NFC" => Some(s.nfc().collect()),
+        "NFD" => Some(s.nfd().collect()),
+        "NFKC" => Some(s.nfkc().collect()),
+        "NFKD" => Some(s.nfkd().collect()),
+        _ => None,
+    }
+}
+
+fn casefold(s: &str) -> String {
+    if s.is_ascii() {
+        return s.to_ascii_lowercase();
+    }
+    CaseMapper::new().fold_string(s)
+}
+
+/// Convert to a Unicode normalization form (case-insensitive name)
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString and `form` is
+/// a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_normalize(s: *const NamlString, form: *const NamlString) -> *mut NamlString {
+    let form = unsafe { string_str(form) };
+    match normalize(unsafe { string_str(s) }, form) {
+        Some(result) => unsafe { naml_string_new(result.as_ptr(), result.len()) },
+        None => {
+            eprintln!("panic: normalization form must be NFC, NFD, NFKC or NFKD, got {:?}", form);
+            std::process::abort();
+        }
+    }
+}
+
+/// Fold case for caseless comparison ("Straße" and "STRASSE" both fold to "strasse")
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_casefold(s: *const NamlString) -> *mut NamlString {
+    let folded = casefold(unsafe { string_str(s) });
+    unsafe { naml_string_new(folded.as_ptr(), folded.len()) }
+}
+
+/// Split into extended grapheme clusters
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_graphemes(s: *const NamlString) -> *mut NamlArray {
+    unsafe {
+        let arr = naml_array_new(0);
+        for g in string_str(s).graphemes(true) {
+            naml_array_push(arr, naml_string_new(g.as_ptr(), g.len()) as i64);
+        }
+        arr
+    }
+}
+
+/// Display width in terminal columns
+///
+/// # Safety
+/// The caller must ensure `s` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_string_width(s: *const NamlString) -> i64 {
+    unsafe { string_str(s) }.width() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_eq!(normalize(decomposed, "NFC").as_deref(), Some(composed));
+        assert_eq!(normalize(composed, "nfd").as_deref(), Some(decomposed));
+        // Compatibility forms replace ligatures and superscripts
+        assert_eq!(normalize("\u{fb01}x\u{b2}", "NFKC").as_deref(), Some("fix2"));
+        assert_eq!(normalize("\u{fb01}", "NFC").as_deref(), Some("\u{fb01}"));
+        assert_eq!(normalize("x", "NFX"), None);
+    }
+
+    #[test]
+    fn test_casefold() {
+        assert_eq!(casefold("Hello"), "hello");
+        assert_eq!(casefold("Stra\u{df}e"), casefold("STRASSE"));
+        assert_eq!(casefold("\u{3a3}\u{3b1}\u{3c2}"), "\u{3c3}\u{3b1}\u{3c3}");
+        assert_eq!(casefold("\u{fb03}"), "ffi");
+    }
+
+    #[test]
+    fn test_graphemes_and_width() {
+        let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+        let text = format!("e\u{301}{}!", family);
+        let clusters: Vec<&str> = text.graphemes(true).collect();
+        assert_eq!(clusters, ["e\u{301}", family, "!"]);
+        assert_eq!("abc".width(), 3);
+        assert_eq!("\u{65e5}\u{672c}".width(), 4);
+        assert_eq!("e\u{301}".width(), 1);
+        assert_eq!("".width(), 0);
+    }
+}
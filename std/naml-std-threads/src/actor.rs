@@ -0,0 +1,274 @@
+//!
+//! Actors for naml
+//!
+//! An actor owns a behavior function and a mailbox. Messages sent to the
+//! actor are queued in its mailbox and handed to the behavior one at a time,
+//! in the order they arrived, so state the behavior captures is never
+//! touched by two messages at once and needs no mutex.
+//!
+//! Actors do not own a thread. When a message arrives at an idle actor a
+//! drain task is spawned on the M:N scheduler; it processes up to
+//! `BATCH_SIZE` messages and then re-queues itself if more are waiting, so a
+//! busy actor cannot starve other tasks. At most one drain task per actor
+//! exists at any time.
+//!
+//! Functions:
+//! - `spawn_actor(behavior)` - Create an actor running `fn(string) -> string`
+//! - `send(actor, message)` - Queue a message, discarding the reply
+//! - `ask(actor, message, timeout_ms)` - Queue a message and wait for the reply
+//! - `stop_actor(actor)` - Refuse new messages; queued ones still run
+//!
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use naml_std_core::{
+    naml_exception_set_typed, naml_stack_capture, naml_string_decref, naml_string_new,
+    NamlString, EXCEPTION_TYPE_TIMEOUT_ERROR,
+};
+
+use crate::scheduler::{naml_alloc_closure_data, naml_spawn_closure};
+
+/// Behavior signature: closure data, then the message; returns the reply
+type BehaviorFn =
+    unsafe extern "C" fn(data_ptr: i64, message: *mut NamlString) -> *mut NamlString;
+
+/// Most messages a drain task handles before yielding its worker
+const BATCH_SIZE: usize = 64;
+
+struct Envelope {
+    message: String,
+    reply: Option<Sender<String>>,
+}
+
+struct Mailbox {
+    queue: VecDeque<Envelope>,
+    /// A drain task is queued or running
+    scheduled: bool,
+}
+
+struct Actor {
+    func: BehaviorFn,
+    data: i64,
+    mailbox: Mutex<Mailbox>,
+}
+
+// The behavior's closure data is only ever used by one drain task at a time
+unsafe impl Send for Actor {}
+unsafe impl Sync for Actor {}
+
+static NEXT_ACTOR_ID: AtomicI64 = AtomicI64::new(1);
+
+static ACTORS: LazyLock<Mutex<HashMap<i64, Arc<Actor>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+impl Actor {
+    /// Queue a message, spawning a drain task if the actor is idle
+    fn deliver(self: &Arc<Self>, envelope: Envelope) {
+        let mut mailbox = self.mailbox.lock().unwrap();
+        mailbox.queue.push_back(envelope);
+        if !mailbox.scheduled {
+            mailbox.scheduled = true;
+            drop(mailbox);
+            schedule_drain(Arc::clone(self));
+        }
+    }
+
+    /// Run the behavior on one message and return its reply
+    fn handle(&self, message: &str) -> String {
+        unsafe {
+            let arg = naml_string_new(message.as_ptr(), message.len());
+            let result = (self.func)(self.data, arg);
+            let reply = string_from_naml(result);
+            if !result.is_null() && result != arg {
+                naml_string_decref(result);
+            }
+            naml_string_decref(arg);
+            reply
+        }
+    }
+}
+
+fn schedule_drain(actor: Arc<Actor>) {
+    let size = std::mem::size_of::<*const Actor>();
+    let data = naml_alloc_closure_data(size);
+    unsafe {
+        *(data as *mut *const Actor) = Arc::into_raw(actor);
+    }
+    naml_spawn_closure(drain, data, size);
+}
+
+/// Scheduler entry point: process a batch of messages, then either go idle
+/// or re-queue behind other tasks if the mailbox is still non-empty
+extern "C" fn drain(data: *mut u8) {
+    let actor = unsafe { Arc::from_raw(*(data as *mut *const Actor)) };
+    for _ in 0..BATCH_SIZE {
+        let next = actor.mailbox.lock().unwrap().queue.pop_front();
+        let Some(envelope) = next else { break };
+        let reply = actor.handle(&envelope.message);
+        if let Some(sender) = envelope.reply {
+            // The asker may have timed out and dropped its receiver
+            let _ = sender.send(reply);
+        }
+    }
+
+    let mut mailbox = actor.mailbox.lock().unwrap();
+    if mailbox.queue.is_empty() {
+        mailbox.scheduled = false;
+    } else {
+        drop(mailbox);
+        schedule_drain(actor);
+    }
+}
+
+fn lookup(id: i64) -> Option<Arc<Actor>> {
+    ACTORS.lock().unwrap().get(&id).cloned()
+}
+
+fn throw_timeout(message: &str, timeout_ms: i64) {
+    unsafe {
+        let message_ptr = naml_string_new(message.as_ptr(), message.len());
+        let layout = std::alloc::Layout::from_size_align(24, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate TimeoutError");
+        }
+        *(ptr as *mut *mut NamlString) = message_ptr;
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut i64) = timeout_ms;
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_TIMEOUT_ERROR);
+    }
+}
+
+/// Create an actor whose messages are handled by the closure (func_ptr, data_ptr)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_actor_spawn(func_ptr: i64, data_ptr: i64) -> i64 {
+    let actor = Arc::new(Actor {
+        func: unsafe { std::mem::transmute::<usize, BehaviorFn>(func_ptr as usize) },
+        data: data_ptr,
+        mailbox: Mutex::new(Mailbox {
+            queue: VecDeque::new(),
+            scheduled: false,
+        }),
+    });
+    let id = NEXT_ACTOR_ID.fetch_add(1, Ordering::SeqCst);
+    ACTORS.lock().unwrap().insert(id, actor);
+    id
+}
+
+/// Queue a message without waiting; messages to stopped actors are dropped
+///
+/// # Safety
+/// The caller must ensure `message` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_actor_send(id: i64, message: *const NamlString) {
+    if let Some(actor) = lookup(id) {
+        let message = unsafe { string_from_naml(message) };
+        actor.deliver(Envelope { message, reply: None });
+    }
+}
+
+/// Queue a message and wait up to `timeout_ms` for the reply.
+/// Throws TimeoutError on timeout or if the actor is stopped.
+///
+/// # Safety
+/// The caller must ensure `message` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_actor_ask(
+    id: i64,
+    message: *const NamlString,
+    timeout_ms: i64,
+) -> *mut NamlString {
+    let Some(actor) = lookup(id) else {
+        throw_timeout(&format!("actor {} is stopped", id), timeout_ms);
+        return std::ptr::null_mut();
+    };
+
+    let message = unsafe { string_from_naml(message) };
+    let (sender, receiver) = mpsc::channel();
+    actor.deliver(Envelope { message, reply: Some(sender) });
+    drop(actor);
+
+    match receiver.recv_timeout(Duration::from_millis(timeout_ms.max(0) as u64)) {
+        Ok(reply) => unsafe { naml_string_new(reply.as_ptr(), reply.len()) },
+        Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {
+            throw_timeout(
+                &format!("actor {} did not reply within {}ms", id, timeout_ms),
+                timeout_ms,
+            );
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Stop accepting messages; messages already queued are still processed
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_actor_stop(id: i64) {
+    ACTORS.lock().unwrap().remove(&id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+    static OVERLAPPED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Appends each message to a log kept in the closure data
+    unsafe extern "C" fn record(data: i64, message: *mut NamlString) -> *mut NamlString {
+        if IN_FLIGHT.fetch_add(1, Ordering::SeqCst) != 0 {
+            OVERLAPPED.fetch_add(1, Ordering::SeqCst);
+        }
+        let log = unsafe { &mut *(data as *mut Vec<String>) };
+        log.push(unsafe { string_from_naml(message) });
+        let reply = log.len().to_string();
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+        unsafe { naml_string_new(reply.as_ptr(), reply.len()) }
+    }
+
+    fn naml(s: &str) -> *mut NamlString {
+        unsafe { naml_string_new(s.as_ptr(), s.len()) }
+    }
+
+    #[test]
+    fn test_messages_processed_in_order_one_at_a_time() {
+        let log: &'static mut Vec<String> = Box::leak(Box::default());
+        let log_ptr = log as *mut Vec<String> as i64;
+        let actor = naml_actor_spawn(record as *const () as usize as i64, log_ptr);
+
+        for i in 0..500 {
+            unsafe { naml_actor_send(actor, naml(&i.to_string())) };
+        }
+        let reply = unsafe { naml_actor_ask(actor, naml("last"), 5000) };
+        assert_eq!(unsafe { (*reply).as_str() }, "501");
+
+        let log = unsafe { &*(log_ptr as *const Vec<String>) };
+        let expected: Vec<String> = (0..500).map(|i| i.to_string()).collect();
+        assert_eq!(&log[..500], &expected[..]);
+        assert_eq!(OVERLAPPED.load(Ordering::SeqCst), 0);
+        naml_actor_stop(actor);
+    }
+
+    #[test]
+    fn test_ask_stopped_actor_throws() {
+        let log: &'static mut Vec<String> = Box::leak(Box::default());
+        let actor = naml_actor_spawn(record as *const () as usize as i64, log as *mut Vec<String> as i64);
+        naml_actor_stop(actor);
+
+        let reply = unsafe { naml_actor_ask(actor, naml("hello"), 100) };
+        assert!(reply.is_null());
+        assert_eq!(naml_std_core::naml_exception_get_type_id(), EXCEPTION_TYPE_TIMEOUT_ERROR);
+        naml_std_core::naml_exception_clear();
+    }
+}
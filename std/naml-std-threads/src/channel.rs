@@ -5,12 +5,26 @@
 //! Channels are typed at the naml level but at runtime store i64 values
 //! (like all naml values).
 //!
+//! `select` waits on several channels at once. A selecting task adds a
+//! `SelectWaiter` to the wait list of every channel it watches; `send` and
+//! `close` wake all waiters on that channel, and the woken task scans the
+//! channels again. Waiters are added under the channel lock after seeing it
+//! empty, so a send can never slip in between the check and the wait.
+//!
+//! `try_send` and `try_receive` never block, and `receive_timeout` gives up
+//! after a number of milliseconds, so a task can poll a channel without
+//! tying up its worker thread indefinitely.
+//!
 
 use std::alloc::{alloc, dealloc, Layout};
 use std::collections::VecDeque;
-use std::sync::{Mutex, Condvar};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use naml_std_core::{HeapHeader, HeapTag, NamlArray};
 
-use naml_std_core::{HeapHeader, HeapTag};
+use crate::completion::Failure;
 
 /// A bounded channel for inter-task communication
 #[repr(C)]
@@ -25,6 +39,39 @@ pub struct NamlChannel {
 struct ChannelInner {
     buffer: VecDeque<i64>,
     closed: bool,
+    /// Why the channel was closed without a value, for completion channels
+    failure: Option<Failure>,
+    /// Tasks blocked in `select` on this channel
+    selectors: Vec<Arc<SelectWaiter>>,
+}
+
+impl ChannelInner {
+    fn wake_selectors(&mut self) {
+        for waiter in self.selectors.drain(..) {
+            waiter.wake();
+        }
+    }
+}
+
+#[derive(Default)]
+struct SelectWaiter {
+    woken: Mutex<bool>,
+    cond: Condvar,
+}
+
+impl SelectWaiter {
+    fn wake(&self) {
+        *self.woken.lock().unwrap() = true;
+        self.cond.notify_one();
+    }
+
+    fn wait(&self) {
+        let mut woken = self.woken.lock().unwrap();
+        while !*woken {
+            woken = self.cond.wait(woken).unwrap();
+        }
+        *woken = false;
+    }
 }
 
 /// Create a new channel with the given capacity
@@ -45,6 +92,8 @@ pub unsafe extern "C" fn naml_channel_new(capacity: usize) -> *mut NamlChannel {
             inner: Mutex::new(ChannelInner {
                 buffer: VecDeque::with_capacity(cap),
                 closed: false,
+                failure: None,
+                selectors: Vec::new(),
             }),
             not_empty: Condvar::new(),
             not_full: Condvar::new(),
@@ -97,6 +146,7 @@ pub unsafe extern "C" fn naml_channel_send(ch: *mut NamlChannel, value: i64) ->
         }
 
         inner.buffer.push_back(value);
+        inner.wake_selectors();
         channel.not_empty.notify_one();
         1
     }
@@ -148,25 +198,56 @@ pub unsafe extern "C" fn naml_channel_try_send(ch: *mut NamlChannel, value: i64)
         }
 
         inner.buffer.push_back(value);
+        inner.wake_selectors();
         channel.not_empty.notify_one();
         1
     }
 }
 
 /// Try to receive without blocking
+/// Returns 1 and writes value to out_value if a value was waiting, 0 if the
+/// channel is empty or closed (option<T>, like receive)
+///
+/// # Safety
+/// The caller must ensure `ch` is a valid pointer to a NamlChannel and
+/// `out_value` is a valid pointer to an i64.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_channel_try_receive(ch: *mut NamlChannel, out_value: *mut i64) -> i64 {
+    unsafe { naml_channel_receive_timeout(ch, 0, out_value) }
+}
+
+/// Receive a value, waiting at most timeout_ms milliseconds
+/// Returns 1 and writes value to out_value if successful, 0 if the time ran
+/// out or the channel is closed (option<T>, like receive)
+///
+/// # Safety
+/// The caller must ensure `ch` is a valid pointer to a NamlChannel or null and
+/// `out_value` is a valid pointer to an i64 or null.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn naml_channel_try_receive(ch: *mut NamlChannel) -> i64 {
+pub unsafe extern "C" fn naml_channel_receive_timeout(ch: *mut NamlChannel, timeout_ms: i64, out_value: *mut i64) -> i64 {
     if ch.is_null() {
         return 0;
     }
 
     unsafe {
         let channel = &*ch;
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms.max(0) as u64);
         let mut inner = channel.inner.lock().unwrap();
 
+        while inner.buffer.is_empty() && !inner.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                return 0;
+            }
+            inner = channel.not_empty.wait_timeout(inner, deadline - now).unwrap().0;
+        }
+
         if let Some(value) = inner.buffer.pop_front() {
             channel.not_full.notify_one();
-            value
+            if !out_value.is_null() {
+                *out_value = value;
+            }
+            1
         } else {
             0
         }
@@ -184,11 +265,33 @@ pub unsafe extern "C" fn naml_channel_close(ch: *mut NamlChannel) {
         let channel = &*ch;
         let mut inner = channel.inner.lock().unwrap();
         inner.closed = true;
+        inner.wake_selectors();
         channel.not_empty.notify_all();
         channel.not_full.notify_all();
     }
 }
 
+/// Record why the channel produced no value and close it. The failure is
+/// stored before any receiver wakes, so one that sees the channel closed
+/// empty can always take it.
+pub(crate) unsafe fn close_with_failure(ch: *mut NamlChannel, failure: Failure) {
+    unsafe {
+        (*ch).inner.lock().unwrap().failure = Some(failure);
+        naml_channel_close(ch);
+    }
+}
+
+/// Take the failure recorded when the channel was closed, if any
+///
+/// # Safety
+/// The caller must ensure `ch` is a valid pointer to a NamlChannel or null.
+pub unsafe fn take_channel_failure(ch: *mut NamlChannel) -> Option<Failure> {
+    if ch.is_null() {
+        return None;
+    }
+    unsafe { (*ch).inner.lock().unwrap().failure.take() }
+}
+
 /// Check if channel is closed
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn naml_channel_is_closed(ch: *mut NamlChannel) -> i64 {
@@ -217,6 +320,111 @@ pub unsafe extern "C" fn naml_channel_len(ch: *mut NamlChannel) -> i64 {
     }
 }
 
+/// Rotates the channel scanned first, so one busy channel cannot starve
+/// the others in a `select` loop
+static SELECT_START: AtomicUsize = AtomicUsize::new(0);
+
+/// Take a value from the first ready channel, scanning from `start`. Unless
+/// `waiter` is `None`, it is added to every open channel found empty.
+/// Returns the received (index, value), or the number of open channels.
+unsafe fn select_scan(
+    channels: &[*mut NamlChannel],
+    start: usize,
+    waiter: Option<&Arc<SelectWaiter>>,
+) -> Result<(usize, i64), usize> {
+    let mut open = 0;
+    for k in 0..channels.len() {
+        let index = (start + k) % channels.len();
+        let Some(channel) = (unsafe { channels[index].as_ref() }) else {
+            continue;
+        };
+        let mut inner = channel.inner.lock().unwrap();
+        if let Some(value) = inner.buffer.pop_front() {
+            channel.not_full.notify_one();
+            return Ok((index, value));
+        }
+        if !inner.closed {
+            open += 1;
+            if let Some(waiter) = waiter {
+                inner.selectors.push(Arc::clone(waiter));
+            }
+        }
+    }
+    Err(open)
+}
+
+fn select_unregister(channels: &[*mut NamlChannel], waiter: &Arc<SelectWaiter>) {
+    for &ch in channels {
+        if let Some(channel) = unsafe { ch.as_ref() } {
+            let mut inner = channel.inner.lock().unwrap();
+            inner.selectors.retain(|w| !Arc::ptr_eq(w, waiter));
+        }
+    }
+}
+
+/// Block until any of `channels` has a value and receive it. Returns the
+/// channel's index and the value, or `None` once every channel is closed
+/// and drained.
+///
+/// # Safety
+/// The caller must ensure every pointer in `channels` is a valid NamlChannel.
+pub unsafe fn channel_select(channels: &[*mut NamlChannel]) -> Option<(usize, i64)> {
+    if channels.is_empty() {
+        return None;
+    }
+    let start = SELECT_START.fetch_add(1, Ordering::Relaxed) % channels.len();
+    match unsafe { select_scan(channels, start, None) } {
+        Ok(ready) => return Some(ready),
+        Err(0) => return None,
+        Err(_) => {}
+    }
+
+    let waiter = Arc::new(SelectWaiter::default());
+    loop {
+        let result = unsafe { select_scan(channels, start, Some(&waiter)) };
+        if !matches!(result, Err(open) if open > 0) {
+            select_unregister(channels, &waiter);
+            return result.ok();
+        }
+        waiter.wait();
+        select_unregister(channels, &waiter);
+    }
+}
+
+/// Select over an array of channels (select)
+///
+/// Waits for a value on any channel, then calls the handler with the
+/// channel's index and the value and writes its result to `out_value`.
+/// Returns 1 (some) after a call, 0 (none) once every channel is closed
+/// and empty.
+///
+/// # Safety
+/// The caller must ensure `channels` is a valid pointer to a NamlArray or null
+/// and `out_value` is a valid pointer to an i64 or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_channel_select(
+    channels: *const NamlArray,
+    func_ptr: i64,
+    data_ptr: i64,
+    out_value: *mut i64,
+) -> i64 {
+    if channels.is_null() || func_ptr == 0 {
+        return 0;
+    }
+    let channels = unsafe {
+        std::slice::from_raw_parts((*channels).data as *const *mut NamlChannel, (*channels).len)
+    };
+    let Some((index, value)) = (unsafe { channel_select(channels) }) else {
+        return 0;
+    };
+    let handler: unsafe extern "C" fn(i64, i64, i64) -> i64 = unsafe { std::mem::transmute(func_ptr as usize) };
+    let result = unsafe { handler(data_ptr, index as i64, value) };
+    if !out_value.is_null() {
+        unsafe { *out_value = result };
+    }
+    1
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,6 +449,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_channel_try_and_timeout() {
+        unsafe {
+            let ch = naml_channel_new(1);
+            let mut value: i64 = 0;
+            assert_eq!(naml_channel_try_receive(ch, &mut value), 0);
+            assert_eq!(naml_channel_try_send(ch, 7), 1);
+            assert_eq!(naml_channel_try_send(ch, 8), 0);
+            assert_eq!(naml_channel_try_receive(ch, &mut value), 1);
+            assert_eq!(value, 7);
+
+            let started = Instant::now();
+            assert_eq!(naml_channel_receive_timeout(ch, 20, &mut value), 0);
+            assert!(started.elapsed() >= Duration::from_millis(20));
+
+            let ch_send = ch as usize;
+            let sender = thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                naml_channel_send(ch_send as *mut NamlChannel, 9);
+            });
+            assert_eq!(naml_channel_receive_timeout(ch, 5_000, &mut value), 1);
+            assert_eq!(value, 9);
+            sender.join().unwrap();
+
+            naml_channel_close(ch);
+            assert_eq!(naml_channel_try_send(ch, 10), 0);
+            assert_eq!(naml_channel_receive_timeout(ch, 5_000, &mut value), 0);
+            naml_channel_decref(ch);
+        }
+    }
+
     #[test]
     fn test_channel_concurrent() {
         let ch = unsafe { naml_channel_new(10) };
@@ -272,4 +511,38 @@ mod tests {
 
         unsafe { naml_channel_decref(ch); }
     }
+
+    #[test]
+    fn test_channel_select() {
+        unsafe {
+            let work = naml_channel_new(4);
+            let shutdown = naml_channel_new(1);
+            let channels = [work, shutdown];
+
+            naml_channel_send(shutdown, 7);
+            assert_eq!(channel_select(&channels), Some((1, 7)));
+
+            // Blocks until another thread sends
+            let ch = work as usize;
+            let sender = thread::spawn(move || {
+                thread::sleep(std::time::Duration::from_millis(20));
+                naml_channel_send(ch as *mut NamlChannel, 42);
+            });
+            assert_eq!(channel_select(&channels), Some((0, 42)));
+            sender.join().unwrap();
+            assert!((*work).inner.lock().unwrap().selectors.is_empty());
+
+            naml_channel_close(shutdown);
+            let ch = work as usize;
+            let closer = thread::spawn(move || {
+                thread::sleep(std::time::Duration::from_millis(20));
+                naml_channel_close(ch as *mut NamlChannel);
+            });
+            assert_eq!(channel_select(&channels), None);
+            closer.join().unwrap();
+
+            naml_channel_decref(work);
+            naml_channel_decref(shutdown);
+        }
+    }
 }
@@ -0,0 +1,187 @@
+//!
+//! Completion Bridge for naml
+//!
+//! Lets other std crates run blocking work off the M:N scheduler and hand
+//! the result back through a channel. The channel acts as the future: the
+//! caller receives from it and gets the value once the work finishes. If
+//! the work fails, the channel is closed empty with the failure recorded on
+//! it, and the caller can take it with `take_channel_failure` to report why.
+//!
+//! The work runs on a separate pool of blocking threads, so a slow file
+//! read never occupies a scheduler worker. The pool starts threads on
+//! demand, up to `MAX_BLOCKING_THREADS`, and lets idle ones exit.
+//!
+//! The returned channel has capacity 1 and is owned by the caller. The
+//! job holds a second reference until it has delivered its result, so
+//! dropping the channel early never frees it under running work.
+//!
+//! Crates that run the work on threads of their own create the channel
+//! with `completion_channel` and deliver the result with `complete`.
+//!
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::{Condvar, LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::channel::{
+    close_with_failure, naml_channel_close, naml_channel_decref, naml_channel_incref,
+    naml_channel_new, naml_channel_send, NamlChannel,
+};
+
+/// Upper bound on threads running blocking work at once
+const MAX_BLOCKING_THREADS: usize = 64;
+
+/// How long an idle blocking thread waits for work before exiting
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a completion produced no value. The crate that spawned the work
+/// decides what it holds and downcasts it when taking it back.
+pub type Failure = Box<dyn Any + Send>;
+
+type Work = Box<dyn FnOnce() -> Result<i64, Failure> + Send>;
+
+/// Work and its result channel
+struct Pending {
+    work: Work,
+    channel: *mut NamlChannel,
+}
+
+// The channel is reference counted and internally locked; the job holds
+// its own reference until it has delivered the result.
+unsafe impl Send for Pending {}
+
+#[derive(Default)]
+struct PoolState {
+    jobs: VecDeque<Pending>,
+    threads: usize,
+    idle: usize,
+}
+
+#[derive(Default)]
+struct BlockingPool {
+    state: Mutex<PoolState>,
+    ready: Condvar,
+}
+
+static BLOCKING_POOL: LazyLock<BlockingPool> = LazyLock::new(BlockingPool::default);
+
+impl BlockingPool {
+    fn submit(&'static self, pending: Pending) {
+        let mut state = self.state.lock().unwrap();
+        state.jobs.push_back(pending);
+        if state.idle == 0 && state.threads < MAX_BLOCKING_THREADS {
+            state.threads += 1;
+            std::thread::Builder::new()
+                .name("naml-blocking".to_string())
+                .spawn(move || self.run_worker())
+                .expect("Failed to spawn blocking thread");
+        } else {
+            self.ready.notify_one();
+        }
+    }
+
+    fn run_worker(&self) {
+        loop {
+            let mut state = self.state.lock().unwrap();
+            let pending = loop {
+                if let Some(pending) = state.jobs.pop_front() {
+                    break pending;
+                }
+                state.idle += 1;
+                let (next, timeout) = self.ready.wait_timeout(state, IDLE_TIMEOUT).unwrap();
+                state = next;
+                state.idle -= 1;
+                if timeout.timed_out() && state.jobs.is_empty() {
+                    state.threads -= 1;
+                    return;
+                }
+            };
+            drop(state);
+            run_completion(pending);
+        }
+    }
+}
+
+/// Run `work` on a blocking thread and return a channel that receives its
+/// result. An `Err` from `work` closes the channel empty and records the
+/// failure on it.
+pub fn spawn_completion<F>(work: F) -> *mut NamlChannel
+where
+    F: FnOnce() -> Result<i64, Failure> + Send + 'static,
+{
+    let channel = completion_channel();
+    BLOCKING_POOL.submit(Pending {
+        work: Box::new(work),
+        channel,
+    });
+    channel
+}
+
+/// A result channel for work that runs elsewhere. It carries two
+/// references: the caller's, and one that `complete` releases.
+pub fn completion_channel() -> *mut NamlChannel {
+    unsafe {
+        let channel = naml_channel_new(1);
+        naml_channel_incref(channel);
+        channel
+    }
+}
+
+/// Deliver the result of work to a channel from `completion_channel` and
+/// release the work's reference. An `Err` closes the channel empty and
+/// records the failure on it.
+///
+/// # Safety
+/// `channel` must come from `completion_channel` and must not have been
+/// completed before.
+pub unsafe fn complete(channel: *mut NamlChannel, result: Result<i64, Failure>) {
+    unsafe {
+        match result {
+            Ok(value) => {
+                naml_channel_send(channel, value);
+                naml_channel_close(channel);
+            }
+            Err(failure) => close_with_failure(channel, failure),
+        }
+        naml_channel_decref(channel);
+    }
+}
+
+/// Run the work and deliver its result
+fn run_completion(pending: Pending) {
+    let Pending { work, channel } = pending;
+    unsafe { complete(channel, work()) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::{naml_channel_receive, take_channel_failure};
+
+    #[test]
+    fn test_completion_delivers_value() {
+        let ch = spawn_completion(|| Ok(42));
+        let mut value = 0;
+        unsafe {
+            assert_eq!(naml_channel_receive(ch, &mut value), 1);
+            assert_eq!(value, 42);
+            assert_eq!(naml_channel_receive(ch, &mut value), 0);
+            assert!(take_channel_failure(ch).is_none());
+            naml_channel_decref(ch);
+        }
+    }
+
+    #[test]
+    fn test_completion_failure_closes_empty() {
+        let ch = spawn_completion(|| Err(Box::new("disk on fire")));
+        let mut value = 0;
+        unsafe {
+            assert_eq!(naml_channel_receive(ch, &mut value), 0);
+            let failure = take_channel_failure(ch).unwrap();
+            assert_eq!(*failure.downcast::<&str>().unwrap(), "disk on fire");
+            assert!(take_channel_failure(ch).is_none());
+            naml_channel_decref(ch);
+        }
+    }
+}
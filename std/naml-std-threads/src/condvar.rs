@@ -0,0 +1,120 @@
+//!
+//! Condition Variables for naml
+//!
+//! A condition variable lets a task holding a mutex sleep until another
+//! task changes the protected value and notifies it. Waiting releases the
+//! mutex, so the notifier can take it; the waiter holds it again when the
+//! wait returns. Wakeups can be spurious, so waiters re-check their
+//! condition in a loop.
+//!
+//! Condition variables are int handles into a registry, like actors. Use
+//! each one with a single mutex.
+//!
+//! Usage in naml:
+//! ```naml
+//! var cv: int = open_condvar();
+//! locked (count: int in m) {
+//!     while (count == 0) {
+//!         count = condvar_wait(cv, m, count);
+//!     }
+//!     count = count - 1;
+//! }
+//! ```
+//!
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+
+use crate::mutex::{wait_while_locked, NamlMutex};
+
+static NEXT_CONDVAR_ID: AtomicI64 = AtomicI64::new(1);
+
+static CONDVARS: LazyLock<Mutex<HashMap<i64, Arc<Condvar>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn lookup(id: i64) -> Option<Arc<Condvar>> {
+    CONDVARS.lock().unwrap().get(&id).cloned()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_condvar_new() -> i64 {
+    let id = NEXT_CONDVAR_ID.fetch_add(1, Ordering::SeqCst);
+    CONDVARS.lock().unwrap().insert(id, Arc::new(Condvar::new()));
+    id
+}
+
+/// Wait inside `locked (value in m)`: store `value` in `m`, release it until
+/// notified, and return the value `m` holds once relocked. Unknown handles
+/// return `value` without waiting.
+///
+/// # Safety
+/// The caller must ensure `m` is a valid pointer to a NamlMutex or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_condvar_wait(id: i64, m: *mut NamlMutex, value: i64) -> i64 {
+    if m.is_null() {
+        return value;
+    }
+    match lookup(id) {
+        Some(condvar) => unsafe { wait_while_locked(m, &condvar, value) },
+        None => value,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_condvar_notify_one(id: i64) {
+    if let Some(condvar) = lookup(id) {
+        condvar.notify_one();
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_condvar_notify_all(id: i64) {
+    if let Some(condvar) = lookup(id) {
+        condvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutex::{naml_mutex_get, naml_mutex_lock, naml_mutex_new, naml_mutex_unlock};
+    use std::thread;
+
+    #[test]
+    fn test_condvar_hands_over_value() {
+        let cv = naml_condvar_new();
+        let m = naml_mutex_new(0);
+        let m_addr = m as usize;
+
+        let producer = thread::spawn(move || unsafe {
+            let m = m_addr as *mut NamlMutex;
+            thread::sleep(std::time::Duration::from_millis(10));
+            naml_mutex_lock(m);
+            naml_mutex_unlock(m, 5);
+            naml_condvar_notify_all(cv);
+        });
+
+        unsafe {
+            let mut value = naml_mutex_lock(m);
+            while value == 0 {
+                value = naml_condvar_wait(cv, m, value);
+            }
+            assert_eq!(value, 5);
+            naml_mutex_unlock(m, value + 1);
+            producer.join().unwrap();
+            assert_eq!(naml_mutex_get(m), 6);
+        }
+    }
+
+    #[test]
+    fn test_condvar_unknown_handle() {
+        let m = naml_mutex_new(3);
+        unsafe {
+            let value = naml_mutex_lock(m);
+            assert_eq!(naml_condvar_wait(-1, m, value), 3);
+            naml_mutex_unlock(m, value);
+        }
+        naml_condvar_notify_one(-1);
+    }
+}
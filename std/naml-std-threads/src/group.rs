@@ -0,0 +1,227 @@
+//!
+//! Task Groups for naml
+//!
+//! A task group owns the tasks spawned into it, so a request handler can
+//! start child work, wait for all of it, or tear it down when the request is
+//! abandoned. Cancellation is cooperative: `group_cancel` sets a flag that
+//! running tasks poll with `is_cancelled()`, and tasks that have not started
+//! yet are skipped. `group_wait` returns once every spawned task has either
+//! finished or been skipped, running queued tasks itself in the meantime so
+//! nested groups cannot deadlock the worker pool.
+//!
+//! A group created inside a task of another group is its child; cancelling
+//! the parent cancels it too.
+//!
+//! Groups are int handles into a registry, like semaphores.
+//!
+//! Usage in naml:
+//! ```naml
+//! var group: int = group_new();
+//! group_spawn(group, fn() {
+//!     while (!is_cancelled()) {
+//!         poll_upstream();
+//!     }
+//! });
+//! group_cancel(group);
+//! group_wait(group);
+//! ```
+//!
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::scheduler::{naml_alloc_closure_data, naml_spawn_closure, run_queued_task};
+
+/// How long `group_wait` sleeps before looking for queued tasks to run again
+const HELP_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Task signature: closure data only
+type GroupFn = unsafe extern "C" fn(data_ptr: i64);
+
+struct Group {
+    cancelled: AtomicBool,
+    parent: Option<Arc<Group>>,
+    /// Spawned tasks that have not finished or been skipped
+    pending: Mutex<usize>,
+    idle: Condvar,
+}
+
+impl Group {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+            || self.parent.as_ref().is_some_and(|parent| parent.is_cancelled())
+    }
+
+    fn finish_task(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        *pending -= 1;
+        if *pending == 0 {
+            self.idle.notify_all();
+        }
+    }
+}
+
+struct GroupTask {
+    group: Arc<Group>,
+    func: GroupFn,
+    data: i64,
+}
+
+static NEXT_GROUP_ID: AtomicI64 = AtomicI64::new(1);
+
+static GROUPS: LazyLock<Mutex<HashMap<i64, Arc<Group>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+thread_local! {
+    /// The group of the task running on this thread, if any
+    static CURRENT_GROUP: RefCell<Option<Arc<Group>>> = const { RefCell::new(None) };
+}
+
+fn lookup(id: i64) -> Option<Arc<Group>> {
+    GROUPS.lock().unwrap().get(&id).cloned()
+}
+
+/// Scheduler entry point: run the task unless its group was cancelled
+/// before it started
+extern "C" fn run_group_task(data: *mut u8) {
+    let task = unsafe { Box::from_raw(*(data as *mut *mut GroupTask)) };
+    if !task.group.is_cancelled() {
+        let outer = CURRENT_GROUP.with(|current| current.replace(Some(Arc::clone(&task.group))));
+        unsafe { (task.func)(task.data) };
+        CURRENT_GROUP.with(|current| *current.borrow_mut() = outer);
+    }
+    task.group.finish_task();
+}
+
+/// Create a task group, a child of the current task's group if it has one
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_group_new() -> i64 {
+    let id = NEXT_GROUP_ID.fetch_add(1, Ordering::SeqCst);
+    let group = Group {
+        cancelled: AtomicBool::new(false),
+        parent: CURRENT_GROUP.with(|current| current.borrow().clone()),
+        pending: Mutex::new(0),
+        idle: Condvar::new(),
+    };
+    GROUPS.lock().unwrap().insert(id, Arc::new(group));
+    id
+}
+
+/// Spawn a closure into the group. Unknown handles spawn nothing.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_group_spawn(id: i64, func_ptr: i64, data_ptr: i64) {
+    let Some(group) = lookup(id) else {
+        return;
+    };
+    *group.pending.lock().unwrap() += 1;
+    let task = Box::new(GroupTask {
+        group,
+        func: unsafe { std::mem::transmute::<i64, GroupFn>(func_ptr) },
+        data: data_ptr,
+    });
+
+    let size = std::mem::size_of::<*mut GroupTask>();
+    let data = naml_alloc_closure_data(size);
+    unsafe {
+        *(data as *mut *mut GroupTask) = Box::into_raw(task);
+    }
+    naml_spawn_closure(run_group_task, data, size);
+}
+
+/// Ask the group's tasks, and those of its child groups, to stop
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_group_cancel(id: i64) {
+    if let Some(group) = lookup(id) {
+        group.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Block until every task spawned into the group has finished or been
+/// skipped. Unknown handles return at once.
+///
+/// While waiting, the caller runs queued tasks itself, so a group task
+/// waiting on a child group cannot starve the children of workers.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_group_wait(id: i64) {
+    let Some(group) = lookup(id) else {
+        return;
+    };
+    loop {
+        let pending = group.pending.lock().unwrap();
+        if *pending == 0 {
+            return;
+        }
+        drop(pending);
+        if run_queued_task() {
+            continue;
+        }
+        let pending = group.pending.lock().unwrap();
+        if *pending > 0 {
+            let _ = group.idle.wait_timeout(pending, HELP_INTERVAL).unwrap();
+        }
+    }
+}
+
+/// Whether the current task's group has been cancelled: 1 if so, 0
+/// otherwise or outside a group task
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_is_cancelled() -> i64 {
+    CURRENT_GROUP.with(|current| current.borrow().as_ref().is_some_and(|group| group.is_cancelled())) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static RAN: AtomicI64 = AtomicI64::new(0);
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    static SAW_CANCEL: AtomicI64 = AtomicI64::new(0);
+    static RAN_AFTER_CANCEL: AtomicI64 = AtomicI64::new(0);
+
+    unsafe extern "C" fn count_run(_data: i64) {
+        RAN.fetch_add(1, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn poll_until_cancelled(_data: i64) {
+        STARTED.store(true, Ordering::SeqCst);
+        while naml_is_cancelled() == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        SAW_CANCEL.fetch_add(1, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn run_after_cancel(_data: i64) {
+        RAN_AFTER_CANCEL.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_group_wait_runs_every_task() {
+        let group = naml_group_new();
+        for _ in 0..8 {
+            naml_group_spawn(group, count_run as *const () as i64, 0);
+        }
+        naml_group_wait(group);
+        assert_eq!(RAN.load(Ordering::SeqCst), 8);
+        assert_eq!(naml_is_cancelled(), 0);
+        naml_group_wait(-1);
+    }
+
+    #[test]
+    fn test_group_cancel_reaches_running_tasks() {
+        let group = naml_group_new();
+        naml_group_spawn(group, poll_until_cancelled as *const () as i64, 0);
+        while !STARTED.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        naml_group_cancel(group);
+        naml_group_wait(group);
+        assert_eq!(SAW_CANCEL.load(Ordering::SeqCst), 1);
+
+        naml_group_spawn(group, run_after_cancel as *const () as i64, 0);
+        naml_group_wait(group);
+        assert_eq!(RAN_AFTER_CANCEL.load(Ordering::SeqCst), 0);
+    }
+}
@@ -10,6 +10,37 @@
 //! - Thread pool with configurable worker count (defaults to CPU cores)
 //! - Work-stealing queue for load balancing
 //! - Closure support for captured variables
+//! - Task handles: `spawn` returns an id for `task_join(id)`,
+//!   `task_is_done(id) -> bool` and `task_result(id) -> T`
+//!
+//! ## Wait Groups
+//!
+//! Wait for a chosen set of tasks rather than all of them:
+//! - `open_waitgroup() -> waitgroup` - Create a wait group with count zero
+//! - `waitgroup_add(wg, n)` / `waitgroup_done(wg)` - Raise or lower the count
+//! - `waitgroup_wait(wg)` - Block until the count is zero
+//!
+//! ## Condition Variables and Semaphores
+//!
+//! Int handles for coordinating tasks beyond plain mutual exclusion:
+//! - `open_condvar() -> int` - Create a condition variable
+//! - `condvar_wait(cv, m, value) -> T` - Inside `locked (value in m)`, wait
+//!   for a notification and return the mutex's current value
+//! - `condvar_notify_one(cv)` / `condvar_notify_all(cv)` - Wake waiters
+//! - `open_semaphore(permits) -> int` - Create a counting semaphore
+//! - `semaphore_acquire(s)` / `semaphore_try_acquire(s) -> bool` /
+//!   `semaphore_release(s)` - Take or return a permit
+//!
+//! ## Task Groups
+//!
+//! Structured concurrency for work that must be torn down together:
+//! - `group_new() -> int` - Create a group, a child of the current task's
+//!   group if it has one
+//! - `group_spawn(group, task)` - Run `fn()` as a task of the group
+//! - `group_cancel(group)` - Cancel the group and its child groups
+//! - `group_wait(group)` - Block until every task of the group is done
+//! - `is_cancelled() -> bool` - Poll from a task whether its group was
+//!   cancelled
 //!
 //! ## Channels
 //!
@@ -18,6 +49,37 @@
 //! - `channel.send(value)` - Send value (blocks if full)
 //! - `channel.receive() -> T` - Receive value (blocks if empty)
 //! - `channel.close()` - Close the channel
+//! - `try_send(ch, value) -> bool` / `try_receive(ch) -> option<T>` - Never
+//!   block; fail when the channel is full, empty or closed
+//! - `receive_timeout(ch, ms) -> option<T>` - Receive, giving up after `ms`
+//! - `select(channels, handler) -> option<R>` - Receive from whichever
+//!   channel is ready first and hand its index and value to `handler`
+//!
+//! ## Completions
+//!
+//! Bridge for std crates that offload blocking work (such as async file I/O)
+//! onto a pool of blocking threads, off the scheduler:
+//! - `spawn_completion(work) -> channel` - Run work on a blocking thread,
+//!   deliver its result through a channel, or close the channel empty and
+//!   record the failure on it
+//! - `completion_channel()` / `complete(channel, result)` - The same for
+//!   work that runs on the caller's own threads
+//! - `take_channel_failure(channel)` - Take the recorded failure
+//!
+//! ## Parallel Chunks
+//!
+//! Fork-join helper for std crates with data-parallel work (such as
+//! `par_apply` in naml-std-collections):
+//! - `run_chunks(n, work)` - Run `work(0..n)` on the workers and the calling
+//!   thread, returning when every chunk is done
+//!
+//! ## Actors
+//!
+//! Message-passing alternative to shared state (`std::threads::actors`):
+//! - `spawn_actor(behavior: fn(string) -> string) -> int` - Create an actor
+//!   whose messages are handled one at a time, in arrival order
+//! - `send(actor, message)` / `ask(actor, message, timeout_ms) -> string`
+//! - `stop_actor(actor)` - Refuse new messages
 //!
 //! ## Mutex and RwLock
 //!
@@ -38,9 +100,23 @@ pub mod channel;
 pub mod mutex;
 pub mod rwlock;
 pub mod atomic;
+pub mod completion;
+pub mod parallel;
+pub mod actor;
+pub mod waitgroup;
+pub mod condvar;
+pub mod semaphore;
+pub mod group;
 
 pub use scheduler::*;
 pub use channel::*;
 pub use mutex::*;
 pub use rwlock::*;
 pub use atomic::*;
+pub use completion::*;
+pub use parallel::*;
+pub use actor::*;
+pub use waitgroup::*;
+pub use condvar::*;
+pub use semaphore::*;
+pub use group::*;
@@ -16,7 +16,7 @@
 use std::alloc::{alloc, dealloc, Layout};
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::sync::{Mutex, MutexGuard};
+use std::sync::{Condvar, Mutex, MutexGuard};
 
 use naml_std_core::{HeapHeader, HeapTag};
 
@@ -157,6 +157,24 @@ pub unsafe extern "C" fn naml_mutex_try_lock(m: *mut NamlMutex, out_value: *mut
     }
 }
 
+/// Store `value` in `m`, release the calling thread's lock on it while
+/// waiting on `condvar`, and return the mutex's value once relocked. Returns
+/// `value` unchanged if the thread does not hold the lock.
+pub(crate) unsafe fn wait_while_locked(m: *mut NamlMutex, condvar: &Condvar, value: i64) -> i64 {
+    let guard = ACTIVE_GUARDS.with(|guards| guards.borrow_mut().remove(&(m as usize)));
+    let Some(mut guard) = guard else {
+        return value;
+    };
+
+    *guard = value;
+    let guard = condvar.wait(guard).unwrap();
+    let current = *guard;
+    ACTIVE_GUARDS.with(|guards| {
+        guards.borrow_mut().insert(m as usize, guard);
+    });
+    current
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
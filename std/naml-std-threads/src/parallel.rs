@@ -0,0 +1,115 @@
+//!
+//! Parallel Bridge for naml
+//!
+//! Lets other std crates split data-parallel work into chunks and run them
+//! on the M:N scheduler's workers, returning once every chunk is done.
+//!
+//! Workers and the calling thread claim chunks from a shared counter. The
+//! caller always takes part, so a call made from inside a task finishes even
+//! when every worker is busy: it then simply runs all the chunks itself.
+//! Helper tasks that start after the last chunk was claimed return at once.
+//!
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::scheduler::{naml_alloc_closure_data, naml_spawn_closure};
+
+/// Number of scheduler workers, which is also the number of cores used
+pub fn worker_count() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+struct Job {
+    /// Only called for claimed chunks, which `run_chunks` waits for, so the
+    /// borrow it was made from outlives every call
+    work: *const (dyn Fn(usize) + Sync),
+    chunks: usize,
+    next: AtomicUsize,
+    done: Mutex<usize>,
+    finished: Condvar,
+}
+
+unsafe impl Send for Job {}
+unsafe impl Sync for Job {}
+
+impl Job {
+    fn help(&self) {
+        loop {
+            let chunk = self.next.fetch_add(1, Ordering::SeqCst);
+            if chunk >= self.chunks {
+                return;
+            }
+            unsafe { (*self.work)(chunk) };
+            let mut done = self.done.lock().unwrap();
+            *done += 1;
+            if *done == self.chunks {
+                self.finished.notify_all();
+            }
+        }
+    }
+}
+
+/// Call `work(i)` for every `i` in `0..chunks`, spread over the workers and
+/// the calling thread, and return when all calls have finished
+pub fn run_chunks(chunks: usize, work: &(dyn Fn(usize) + Sync)) {
+    if chunks == 0 {
+        return;
+    }
+    // Erase the borrow's lifetime; see `Job::work`
+    let work: *const (dyn Fn(usize) + Sync + '_) = work;
+    let job = Arc::new(Job {
+        work: unsafe { std::mem::transmute::<*const (dyn Fn(usize) + Sync + '_), *const (dyn Fn(usize) + Sync)>(work) },
+        chunks,
+        next: AtomicUsize::new(0),
+        done: Mutex::new(0),
+        finished: Condvar::new(),
+    });
+
+    let helpers = worker_count().min(chunks) - 1;
+    for _ in 0..helpers {
+        let size = std::mem::size_of::<*const Job>();
+        let data = naml_alloc_closure_data(size);
+        unsafe { *(data as *mut *const Job) = Arc::into_raw(Arc::clone(&job)) };
+        naml_spawn_closure(run_helper, data, size);
+    }
+
+    job.help();
+    let mut done = job.done.lock().unwrap();
+    while *done < chunks {
+        done = job.finished.wait(done).unwrap();
+    }
+}
+
+/// Worker entry point: claim chunks until none are left, then release the
+/// job. The scheduler frees the closure data afterwards.
+extern "C" fn run_helper(data: *mut u8) {
+    let job = unsafe { Arc::from_raw(*(data as *mut *const Job)) };
+    job.help();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_chunks_covers_every_chunk() {
+        let hits: Vec<AtomicUsize> = (0..100).map(|_| AtomicUsize::new(0)).collect();
+        run_chunks(hits.len(), &|i| {
+            hits[i].fetch_add(1, Ordering::SeqCst);
+        });
+        assert!(hits.iter().all(|h| h.load(Ordering::SeqCst) == 1));
+        run_chunks(0, &|_| panic!("no chunks"));
+    }
+
+    #[test]
+    fn test_nested_run_chunks() {
+        let total = AtomicUsize::new(0);
+        run_chunks(worker_count() * 2, &|_| {
+            run_chunks(8, &|j| {
+                total.fetch_add(j, Ordering::SeqCst);
+            });
+        });
+        assert_eq!(total.load(Ordering::SeqCst), worker_count() * 2 * 28);
+    }
+}
@@ -8,24 +8,86 @@
 //! - Work-stealing queue for load balancing
 //! - Closure support for captured variables
 //! - Efficient task scheduling
+//! - Task handles: a tracked `spawn` gets an id whose completion state and
+//!   result can be waited on with `task_join` and read with `task_result`
+//!
+//! Completion states stay registered for the rest of the program, so a
+//! handle can be joined any number of times and from any task. Spawns whose
+//! value is discarded run detached and never register one.
 //!
 
 use std::alloc::{alloc, dealloc, Layout};
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex, OnceLock};
 use std::thread::{self, JoinHandle};
 
 /// Task function signature: takes a pointer to captured data
 type TaskFn = extern "C" fn(*mut u8);
 
+/// Spawn block signature: takes a pointer to captured data and returns the
+/// value of its `return` statement, or 0
+type SpawnFn = extern "C" fn(*mut u8) -> i64;
+
+enum TaskFunc {
+    /// Runtime work such as actor drains and completions
+    Plain(TaskFn),
+    /// A `spawn` block, with the completion state of its handle if tracked
+    Spawned(SpawnFn, Option<Arc<TaskState>>),
+}
+
 /// A task consists of a function pointer and captured data
 struct Task {
-    func: TaskFn,
+    func: TaskFunc,
     data: *mut u8,
     data_size: usize,
 }
 
+/// Completion state of a tracked task, shared by the worker running it and
+/// every task waiting on its handle
+struct TaskState {
+    result: Mutex<Option<i64>>,
+    done: Condvar,
+}
+
+impl TaskState {
+    fn new() -> Self {
+        Self {
+            result: Mutex::new(None),
+            done: Condvar::new(),
+        }
+    }
+
+    fn complete(&self, value: i64) {
+        *self.result.lock().unwrap() = Some(value);
+        self.done.notify_all();
+    }
+
+    fn is_done(&self) -> bool {
+        self.result.lock().unwrap().is_some()
+    }
+
+    /// Block until the task has finished and return its result
+    fn wait(&self) -> i64 {
+        let mut result = self.result.lock().unwrap();
+        loop {
+            if let Some(value) = *result {
+                return value;
+            }
+            result = self.done.wait(result).unwrap();
+        }
+    }
+}
+
+static NEXT_TASK_ID: AtomicI64 = AtomicI64::new(1);
+
+static TASKS: LazyLock<Mutex<HashMap<i64, Arc<TaskState>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn lookup_task(id: i64) -> Option<Arc<TaskState>> {
+    TASKS.lock().unwrap().get(&id).cloned()
+}
+
 unsafe impl Send for Task {}
 
 /// The global task queue
@@ -58,6 +120,10 @@ impl TaskQueue {
         tasks.pop_front()
     }
 
+    fn try_pop(&self) -> Option<Task> {
+        self.tasks.lock().unwrap().pop_front()
+    }
+
     fn shutdown(&self) {
         self.shutdown.store(true, Ordering::SeqCst);
         self.condvar.notify_all();
@@ -98,7 +164,7 @@ impl Scheduler {
         }
     }
 
-    fn spawn(&self, func: TaskFn, data: *mut u8, data_size: usize) {
+    fn spawn(&self, func: TaskFunc, data: *mut u8, data_size: usize) {
         self.active_tasks.fetch_add(1, Ordering::SeqCst);
         self.queue.push(Task { func, data, data_size });
     }
@@ -125,16 +191,42 @@ impl Drop for Scheduler {
 
 fn worker_loop(queue: Arc<TaskQueue>, active_tasks: Arc<AtomicUsize>) {
     while let Some(task) = queue.pop() {
-        (task.func)(task.data);
+        run_task(task, &active_tasks);
+    }
+}
 
-        if !task.data.is_null() && task.data_size > 0 {
-            unsafe {
-                let layout = Layout::from_size_align_unchecked(task.data_size, 8);
-                dealloc(task.data, layout);
+fn run_task(task: Task, active_tasks: &AtomicUsize) {
+    match task.func {
+        TaskFunc::Plain(func) => func(task.data),
+        TaskFunc::Spawned(func, state) => {
+            let value = func(task.data);
+            if let Some(state) = state {
+                state.complete(value);
             }
         }
+    }
+
+    if !task.data.is_null() && task.data_size > 0 {
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(task.data_size, 8);
+            dealloc(task.data, layout);
+        }
+    }
+
+    active_tasks.fetch_sub(1, Ordering::SeqCst);
+}
 
-        active_tasks.fetch_sub(1, Ordering::SeqCst);
+/// Run one queued task on the calling thread, if any is waiting. Lets a
+/// task that blocks on other tasks make progress when every worker is busy.
+pub(crate) fn run_queued_task() -> bool {
+    let scheduler = get_scheduler();
+    let task = scheduler.queue.try_pop();
+    match task {
+        Some(task) => {
+            run_task(task, &scheduler.active_tasks);
+            true
+        }
+        None => false,
     }
 }
 
@@ -156,7 +248,53 @@ pub extern "C" fn naml_spawn_closure(
     data: *mut u8,
     data_size: usize,
 ) {
-    get_scheduler().spawn(func, data, data_size);
+    get_scheduler().spawn(TaskFunc::Plain(func), data, data_size);
+}
+
+/// Spawn a `spawn` block and return the id of its handle
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_spawn_task(
+    func: extern "C" fn(*mut u8) -> i64,
+    data: *mut u8,
+    data_size: usize,
+) -> i64 {
+    let id = NEXT_TASK_ID.fetch_add(1, Ordering::SeqCst);
+    let state = Arc::new(TaskState::new());
+    TASKS.lock().unwrap().insert(id, Arc::clone(&state));
+    get_scheduler().spawn(TaskFunc::Spawned(func, Some(state)), data, data_size);
+    id
+}
+
+/// Spawn a `spawn` block whose handle is never used
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_spawn_detached(
+    func: extern "C" fn(*mut u8) -> i64,
+    data: *mut u8,
+    data_size: usize,
+) {
+    get_scheduler().spawn(TaskFunc::Spawned(func, None), data, data_size);
+}
+
+/// Wait for the task with handle `id` to finish. Unknown ids return at once.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_task_join(id: i64) {
+    if let Some(state) = lookup_task(id) {
+        state.wait();
+    }
+}
+
+/// Whether the task with handle `id` has finished (1) or not (0)
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_task_is_done(id: i64) -> i64 {
+    lookup_task(id).is_none_or(|state| state.is_done()) as i64
+}
+
+/// Wait for the task with handle `id` and return the value of its `return`
+/// statement, or 0 if it returned nothing or the id is unknown. The task's
+/// state keeps its reference to a heap result; callers take a new one.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_task_result(id: i64) -> i64 {
+    lookup_task(id).map_or(0, |state| state.wait())
 }
 
 /// Spawn a task without captured data (legacy interface)
@@ -166,7 +304,7 @@ pub extern "C" fn naml_spawn(func: extern "C" fn()) {
         let func: extern "C" fn() = unsafe { std::mem::transmute(data) };
         func();
     }
-    get_scheduler().spawn(wrapper, func as *mut u8, 0);
+    get_scheduler().spawn(TaskFunc::Plain(wrapper), func as *mut u8, 0);
 }
 
 /// Wait for all spawned tasks to complete
@@ -253,4 +391,38 @@ mod tests {
 
         assert_eq!(CLOSURE_COUNTER.load(Ordering::SeqCst), 15);
     }
+
+    static TASK_GATE: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn square_when_released(data: *mut u8) -> i64 {
+        let value = unsafe { *(data as *const i64) };
+        while !TASK_GATE.load(Ordering::SeqCst) {
+            thread::yield_now();
+        }
+        value * value
+    }
+
+    #[test]
+    fn test_task_handles() {
+        let data = naml_alloc_closure_data(8);
+        unsafe {
+            *(data as *mut i64) = 7;
+        }
+        let id = naml_spawn_task(square_when_released, data, 8);
+        assert_eq!(naml_task_is_done(id), 0);
+
+        TASK_GATE.store(true, Ordering::SeqCst);
+        naml_task_join(id);
+        assert_eq!(naml_task_is_done(id), 1);
+        assert_eq!(naml_task_result(id), 49);
+        // Results can be read again after the first join
+        assert_eq!(naml_task_result(id), 49);
+    }
+
+    #[test]
+    fn test_unknown_task_handle() {
+        naml_task_join(-1);
+        assert_eq!(naml_task_is_done(-1), 1);
+        assert_eq!(naml_task_result(-1), 0);
+    }
 }
@@ -0,0 +1,127 @@
+//!
+//! Counting Semaphores for naml
+//!
+//! A semaphore hands out a fixed number of permits. `acquire` takes one,
+//! blocking while none are left, and `release` returns one. Bounding how
+//! many tasks hold a permit at once gives producer/consumer back-pressure
+//! and caps concurrent use of a resource such as connections.
+//!
+//! Semaphores are int handles into a registry, like actors.
+//!
+//! Usage in naml:
+//! ```naml
+//! var slots: int = open_semaphore(4);
+//! semaphore_acquire(slots);
+//! download(url);
+//! semaphore_release(slots);
+//! ```
+//!
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+
+struct Semaphore {
+    permits: Mutex<i64>,
+    available: Condvar,
+}
+
+static NEXT_SEMAPHORE_ID: AtomicI64 = AtomicI64::new(1);
+
+static SEMAPHORES: LazyLock<Mutex<HashMap<i64, Arc<Semaphore>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn lookup(id: i64) -> Option<Arc<Semaphore>> {
+    SEMAPHORES.lock().unwrap().get(&id).cloned()
+}
+
+/// Create a semaphore with `permits` permits; negative counts start at zero
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_semaphore_new(permits: i64) -> i64 {
+    let id = NEXT_SEMAPHORE_ID.fetch_add(1, Ordering::SeqCst);
+    let semaphore = Semaphore {
+        permits: Mutex::new(permits.max(0)),
+        available: Condvar::new(),
+    };
+    SEMAPHORES.lock().unwrap().insert(id, Arc::new(semaphore));
+    id
+}
+
+/// Take a permit, blocking until one is free. Unknown handles return at once.
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_semaphore_acquire(id: i64) {
+    let Some(semaphore) = lookup(id) else {
+        return;
+    };
+    let mut permits = semaphore.permits.lock().unwrap();
+    while *permits == 0 {
+        permits = semaphore.available.wait(permits).unwrap();
+    }
+    *permits -= 1;
+}
+
+/// Take a permit if one is free: 1 on success, 0 otherwise
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_semaphore_try_acquire(id: i64) -> i64 {
+    let Some(semaphore) = lookup(id) else {
+        return 0;
+    };
+    let mut permits = semaphore.permits.lock().unwrap();
+    if *permits == 0 {
+        return 0;
+    }
+    *permits -= 1;
+    1
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_semaphore_release(id: i64) {
+    if let Some(semaphore) = lookup(id) {
+        *semaphore.permits.lock().unwrap() += 1;
+        semaphore.available.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicI64;
+    use std::thread;
+
+    #[test]
+    fn test_semaphore_try_acquire() {
+        let sem = naml_semaphore_new(2);
+        assert_eq!(naml_semaphore_try_acquire(sem), 1);
+        assert_eq!(naml_semaphore_try_acquire(sem), 1);
+        assert_eq!(naml_semaphore_try_acquire(sem), 0);
+        naml_semaphore_release(sem);
+        assert_eq!(naml_semaphore_try_acquire(sem), 1);
+        assert_eq!(naml_semaphore_try_acquire(-1), 0);
+    }
+
+    #[test]
+    fn test_semaphore_bounds_concurrency() {
+        static HOLDING: AtomicI64 = AtomicI64::new(0);
+        static MOST_HELD: AtomicI64 = AtomicI64::new(0);
+        let sem = naml_semaphore_new(2);
+
+        let workers: Vec<_> = (0..6)
+            .map(|_| {
+                thread::spawn(move || {
+                    naml_semaphore_acquire(sem);
+                    let held = HOLDING.fetch_add(1, Ordering::SeqCst) + 1;
+                    MOST_HELD.fetch_max(held, Ordering::SeqCst);
+                    thread::sleep(std::time::Duration::from_millis(5));
+                    HOLDING.fetch_sub(1, Ordering::SeqCst);
+                    naml_semaphore_release(sem);
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert!(MOST_HELD.load(Ordering::SeqCst) <= 2);
+        assert_eq!(naml_semaphore_try_acquire(sem), 1);
+    }
+}
@@ -0,0 +1,176 @@
+//!
+//! Wait Groups for naml
+//!
+//! A wait group counts outstanding pieces of work. Tasks raise the count
+//! with `add` before starting work and lower it with `done` when they
+//! finish; `wait` blocks until the count is back at zero. Unlike
+//! `join()`, which waits for every task in the program, a wait group only
+//! waits for the work registered with it.
+//!
+//! Usage in naml:
+//! ```naml
+//! var wg: waitgroup = open_waitgroup();
+//! waitgroup_add(wg, 2);
+//! spawn { work(); waitgroup_done(wg); };
+//! spawn { work(); waitgroup_done(wg); };
+//! waitgroup_wait(wg);
+//! ```
+//!
+
+use std::alloc::{alloc, dealloc, Layout};
+use std::sync::{Condvar, Mutex};
+
+use naml_std_core::{HeapHeader, HeapTag};
+
+#[repr(C)]
+pub struct NamlWaitgroup {
+    pub header: HeapHeader,
+    count: Mutex<i64>,
+    zero: Condvar,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_waitgroup_new() -> *mut NamlWaitgroup {
+    unsafe {
+        let layout = Layout::new::<NamlWaitgroup>();
+        let ptr = alloc(layout) as *mut NamlWaitgroup;
+        if ptr.is_null() {
+            panic!("Failed to allocate waitgroup");
+        }
+
+        std::ptr::write(ptr, NamlWaitgroup {
+            header: HeapHeader::new(HeapTag::Waitgroup),
+            count: Mutex::new(0),
+            zero: Condvar::new(),
+        });
+
+        ptr
+    }
+}
+
+/// # Safety
+/// The caller must ensure `wg` is a valid pointer to a NamlWaitgroup or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_waitgroup_incref(wg: *mut NamlWaitgroup) {
+    if !wg.is_null() {
+        unsafe { (*wg).header.incref(); }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `wg` is a valid pointer to a NamlWaitgroup or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_waitgroup_decref(wg: *mut NamlWaitgroup) {
+    if !wg.is_null() {
+        unsafe {
+            if (*wg).header.decref() {
+                std::ptr::drop_in_place(wg);
+                let layout = Layout::new::<NamlWaitgroup>();
+                dealloc(wg as *mut u8, layout);
+            }
+        }
+    }
+}
+
+/// Add `delta` to the count. The count never drops below zero, so extra
+/// `done` calls have no effect.
+///
+/// # Safety
+/// The caller must ensure `wg` is a valid pointer to a NamlWaitgroup or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_waitgroup_add(wg: *mut NamlWaitgroup, delta: i64) {
+    if wg.is_null() {
+        return;
+    }
+
+    unsafe {
+        let wg = &*wg;
+        let mut count = wg.count.lock().unwrap();
+        *count = (*count + delta).max(0);
+        if *count == 0 {
+            wg.zero.notify_all();
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `wg` is a valid pointer to a NamlWaitgroup.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_waitgroup_done(wg: *mut NamlWaitgroup) {
+    unsafe { naml_waitgroup_add(wg, -1) }
+}
+
+/// Block until the count is zero
+///
+/// # Safety
+/// The caller must ensure `wg` is a valid pointer to a NamlWaitgroup or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_waitgroup_wait(wg: *mut NamlWaitgroup) {
+    if wg.is_null() {
+        return;
+    }
+
+    unsafe {
+        let wg = &*wg;
+        let mut count = wg.count.lock().unwrap();
+        while *count > 0 {
+            count = wg.zero.wait(count).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_waitgroup_waits_for_done() {
+        let wg = naml_waitgroup_new();
+        let finished = Arc::new(AtomicI64::new(0));
+        unsafe { naml_waitgroup_add(wg, 4) };
+
+        for _ in 0..4 {
+            let wg_ptr = wg as usize;
+            let finished = Arc::clone(&finished);
+            thread::spawn(move || {
+                thread::sleep(std::time::Duration::from_millis(10));
+                finished.fetch_add(1, Ordering::SeqCst);
+                unsafe { naml_waitgroup_done(wg_ptr as *mut NamlWaitgroup) };
+            });
+        }
+
+        unsafe {
+            naml_waitgroup_wait(wg);
+            assert_eq!(finished.load(Ordering::SeqCst), 4);
+            naml_waitgroup_decref(wg);
+        }
+    }
+
+    #[test]
+    fn test_waitgroup_count_floor() {
+        let wg = naml_waitgroup_new();
+        let finished = Arc::new(AtomicI64::new(0));
+        unsafe {
+            // The extra done must not cancel out the add that follows
+            naml_waitgroup_done(wg);
+            naml_waitgroup_add(wg, 1);
+        }
+
+        let wg_ptr = wg as usize;
+        let finished_clone = Arc::clone(&finished);
+        thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(10));
+            finished_clone.store(1, Ordering::SeqCst);
+            unsafe { naml_waitgroup_done(wg_ptr as *mut NamlWaitgroup) };
+        });
+
+        unsafe {
+            naml_waitgroup_wait(wg);
+            assert_eq!(finished.load(Ordering::SeqCst), 1);
+            naml_waitgroup_decref(wg);
+        }
+    }
+}
@@ -211,7 +211,7 @@ pub extern "C" fn naml_timers_schedule(
     }
 
     let expr_str = unsafe {
-        let slice = std::slice::from_raw_parts((*expr_ptr).data.as_ptr(), (*expr_ptr).len);
+        let slice = std::slice::from_raw_parts((*expr_ptr).as_ptr(), (*expr_ptr).len);
         match std::str::from_utf8(slice) {
             Ok(s) => s.to_owned(),
             Err(_) => {
@@ -0,0 +1,208 @@
+///
+/// Format Predicates
+///
+/// Syntax checks for common input formats. They look at the text only and
+/// never resolve names or contact servers.
+///
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Longest local part (RFC 5321 section 4.5.3.1.1)
+const MAX_LOCAL_PART: usize = 64;
+/// Longest address accepted in a forward-path (RFC 5321 section 4.5.3.1.3)
+const MAX_EMAIL: usize = 254;
+const MAX_HOSTNAME: usize = 253;
+const MAX_LABEL: usize = 63;
+
+pub fn is_ipv4(s: &str) -> bool {
+    s.parse::<Ipv4Addr>().is_ok()
+}
+
+pub fn is_ipv6(s: &str) -> bool {
+    s.parse::<Ipv6Addr>().is_ok()
+}
+
+/// RFC 1123 host name: dot-separated labels of letters, digits and inner
+/// hyphens, with a trailing dot allowed. The last label may not be all
+/// digits, so IPv4 addresses are not host names.
+pub fn is_hostname(s: &str) -> bool {
+    let name = s.strip_suffix('.').unwrap_or(s);
+    if name.is_empty() || name.len() > MAX_HOSTNAME {
+        return false;
+    }
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= MAX_LABEL
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    };
+    let numeric_tld = name.rsplit('.').next().is_some_and(|tld| tld.bytes().all(|b| b.is_ascii_digit()));
+    name.split('.').all(valid_label) && !numeric_tld
+}
+
+/// `local@domain` where the local part is a dot-atom (RFC 5322) and the
+/// domain is a host name; quoted local parts and IP literals are rejected
+pub fn is_email(s: &str) -> bool {
+    let Some((local, domain)) = s.rsplit_once('@') else {
+        return false;
+    };
+    let atext = |b: u8| b.is_ascii_alphanumeric() || b"!#$%&'*+/=?^_`{|}~-".contains(&b);
+    s.len() <= MAX_EMAIL
+        && !local.is_empty()
+        && local.len() <= MAX_LOCAL_PART
+        && local.split('.').all(|atom| !atom.is_empty() && atom.bytes().all(atext))
+        && !domain.ends_with('.')
+        && is_hostname(domain)
+}
+
+fn is_port(port: &str) -> bool {
+    !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) && port.parse::<u16>().is_ok_and(|p| p > 0)
+}
+
+/// Host part of a URL authority: a host name, IPv4 address or bracketed
+/// IPv6 address, followed by an optional port
+fn is_authority_host(authority: &str) -> bool {
+    if let Some(rest) = authority.strip_prefix('[') {
+        let Some((ip, after)) = rest.split_once(']') else {
+            return false;
+        };
+        return is_ipv6(ip) && (after.is_empty() || after.strip_prefix(':').is_some_and(is_port));
+    }
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    port.is_none_or(is_port) && (is_ipv4(host) || is_hostname(host))
+}
+
+/// Absolute URL with an authority, such as `https://example.com:8080/a?b#c`:
+/// a scheme, `://`, optional `user@`, a host and optional port, then a path,
+/// query and fragment without whitespace or control characters
+pub fn is_url(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once("://") else {
+        return false;
+    };
+    let scheme_ok = scheme.bytes().next().is_some_and(|b| b.is_ascii_alphabetic())
+        && scheme.bytes().all(|b| b.is_ascii_alphanumeric() || b"+-.".contains(&b));
+    if !scheme_ok || rest.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    is_authority_host(host)
+}
+
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` in hex digits of either case
+pub fn is_uuid(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    bytes.len() == 36
+        && bytes.iter().enumerate().all(|(i, &b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// Luhn (mod 10) checksum over the digits, e.g. of a card number; spaces
+/// and hyphens between digits are ignored
+pub fn matches_luhn(s: &str) -> bool {
+    let mut sum = 0;
+    let mut count = 0;
+    for b in s.bytes().rev() {
+        match b {
+            b'0'..=b'9' => {
+                let mut digit = (b - b'0') as u32;
+                if count % 2 == 1 {
+                    digit *= 2;
+                    if digit > 9 {
+                        digit -= 9;
+                    }
+                }
+                sum += digit;
+                count += 1;
+            }
+            b' ' | b'-' => {}
+            _ => return false,
+        }
+    }
+    count >= 2 && sum % 10 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email() {
+        for ok in ["a@example.com", "first.last+tag@mail.example.co.uk", "x_y!#$%@localhost", "o'neil@xn--bcher-kva.de"] {
+            assert!(is_email(ok), "{}", ok);
+        }
+        for bad in ["", "plain", "@example.com", "a@", "a..b@example.com", ".a@example.com", "a@-example.com", "a b@example.com", "a@example.com.", "a@1.2.3.4"] {
+            assert!(!is_email(bad), "{}", bad);
+        }
+        assert!(!is_email(&format!("{}@example.com", "a".repeat(65))));
+    }
+
+    #[test]
+    fn test_hostname() {
+        assert!(is_hostname("example.com"));
+        assert!(is_hostname("localhost"));
+        assert!(is_hostname("a-1.example.com."));
+        assert!(is_hostname("3com.net"));
+        assert!(!is_hostname("-a.com"));
+        assert!(!is_hostname("a..com"));
+        assert!(!is_hostname("under_score.com"));
+        assert!(!is_hostname("10.0.0.1"));
+        assert!(!is_hostname(&"a".repeat(64)));
+    }
+
+    #[test]
+    fn test_url() {
+        for ok in [
+            "https://example.com",
+            "http://user:pw@example.com:8080/a/b?q=1#top",
+            "http://127.0.0.1/",
+            "http://[::1]:3000/health",
+            "postgres://db.internal/app",
+        ] {
+            assert!(is_url(ok), "{}", ok);
+        }
+        for bad in [
+            "example.com",
+            "https://",
+            "https://exa mple.com",
+            "http://example.com:0/",
+            "http://example.com:99999",
+            "http://example.com:/",
+            "http://[::1/",
+            "1http://example.com",
+            "http://under_score.com",
+        ] {
+            assert!(!is_url(bad), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_ip_and_uuid() {
+        assert!(is_ipv4("192.168.0.1"));
+        assert!(!is_ipv4("256.1.1.1"));
+        assert!(!is_ipv4("1.2.3"));
+        assert!(is_ipv6("2001:db8::1"));
+        assert!(is_ipv6("::ffff:10.0.0.1"));
+        assert!(!is_ipv6("2001:db8:::1"));
+        assert!(is_uuid("123e4567-E89B-12d3-a456-426614174000"));
+        assert!(!is_uuid("123e4567e89b12d3a456426614174000"));
+        assert!(!is_uuid("123e4567-e89b-12d3-a456-42661417400g"));
+    }
+
+    #[test]
+    fn test_luhn() {
+        assert!(matches_luhn("4111 1111 1111 1111"));
+        assert!(matches_luhn("79927398713"));
+        assert!(matches_luhn("5500-0000-0000-0004"));
+        assert!(!matches_luhn("4111 1111 1111 1112"));
+        assert!(!matches_luhn("0"));
+        assert!(!matches_luhn("4111x1111"));
+    }
+}
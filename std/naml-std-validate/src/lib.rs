@@ -0,0 +1,203 @@
+///
+/// naml-std-validate - Input Validation
+///
+/// Format checks for common input, plus validators that check a set of
+/// named string values (form fields, query parameters, decoded JSON
+/// fields) against per-field rules and report failures as data.
+///
+/// ## Functions
+///
+/// - `is_email(s: string) -> bool` - `local@domain` address
+/// - `is_url(s: string) -> bool` - Absolute URL with a host
+/// - `is_uuid(s: string) -> bool` - Hyphenated UUID
+/// - `is_ipv4(s: string) -> bool` / `is_ipv6(s: string) -> bool` - IP addresses
+/// - `is_hostname(s: string) -> bool` - RFC 1123 host name
+/// - `matches_luhn(card: string) -> bool` - Luhn checksum, e.g. card numbers
+/// - `open_validator() -> int` - Create a validator
+/// - `add_rule(validator: int, field: string, rules: string) throws ValidationError`
+///   - Add `|`-separated rules such as `"required|email|max_len:254"`
+/// - `validate(validator: int, values: map<string, string>) -> [map<string, string>]`
+///   - Every failed rule as `{"field", "rule", "message"}`; empty when valid
+/// - `check(validator: int, values: map<string, string>) throws ValidationError`
+///   - Throw for the first failed rule
+/// - `close_validator(validator: int)` - Release a validator
+///
+/// ## Rules
+///
+/// `required`, `email`, `url`, `uuid`, `ipv4`, `ipv6`, `ip`, `hostname`,
+/// `luhn`, `int`, `number`, `min_len:N`, `max_len:N`, `min:N`, `max:N`,
+/// `one_of:a,b,c` and `same:other_field`. A missing or blank value only
+/// fails `required`, so fields without it are optional.
+///
+
+mod formats;
+mod rules;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+pub use formats::{is_email, is_hostname, is_ipv4, is_ipv6, is_url, is_uuid, matches_luhn};
+
+use naml_std_core::{
+    naml_array_new, naml_array_push, naml_exception_set_typed, naml_map_new, naml_map_set_string,
+    naml_stack_capture, naml_string_decref, naml_string_new, NamlArray, NamlMap, NamlString,
+    EXCEPTION_TYPE_VALIDATION_ERROR,
+};
+
+use rules::{Failure, Validator};
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static VALIDATORS: LazyLock<Mutex<HashMap<i64, Validator>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn naml_from_string(s: &str) -> *mut NamlString {
+    unsafe { naml_string_new(s.as_ptr(), s.len()) }
+}
+
+/// Read a `map<string, string>` into owned strings
+unsafe fn values_from_map(map: *const NamlMap) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    if map.is_null() {
+        return values;
+    }
+    unsafe {
+        for i in 0..(*map).capacity {
+            let entry = &*(*map).entries.add(i);
+            if entry.occupied {
+                values.insert(
+                    string_from_naml(entry.key as *const NamlString),
+                    string_from_naml(entry.value as *const NamlString),
+                );
+            }
+        }
+    }
+    values
+}
+
+/// Throw ValidationError with layout: message@0, stack@8, field@16, rule@24
+fn throw_validation_error(message: &str, field: &str, rule: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(32, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate ValidationError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_from_string(message);
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        *(ptr.add(16) as *mut *mut NamlString) = naml_from_string(field);
+        *(ptr.add(24) as *mut *mut NamlString) = naml_from_string(rule);
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_VALIDATION_ERROR);
+    }
+}
+
+fn unknown_validator(handle: i64) -> Failure {
+    Failure {
+        field: String::new(),
+        rule: String::new(),
+        message: format!("unknown validator handle {}", handle),
+    }
+}
+
+fn run_validator(handle: i64, values: *const NamlMap) -> Vec<Failure> {
+    let values = unsafe { values_from_map(values) };
+    match VALIDATORS.lock().unwrap().get(&handle) {
+        Some(validator) => validator.validate(&values),
+        None => vec![unknown_validator(handle)],
+    }
+}
+
+macro_rules! format_predicate {
+    ($name:ident, $check:path) => {
+        /// # Safety
+        /// The caller must ensure `s` is a valid pointer to a NamlString or null.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn $name(s: *const NamlString) -> i64 {
+            let s = unsafe { string_from_naml(s) };
+            $check(&s) as i64
+        }
+    };
+}
+
+format_predicate!(naml_validate_is_email, is_email);
+format_predicate!(naml_validate_is_url, is_url);
+format_predicate!(naml_validate_is_uuid, is_uuid);
+format_predicate!(naml_validate_is_ipv4, is_ipv4);
+format_predicate!(naml_validate_is_ipv6, is_ipv6);
+format_predicate!(naml_validate_is_hostname, is_hostname);
+format_predicate!(naml_validate_matches_luhn, matches_luhn);
+
+/// Create an empty validator
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_validate_open() -> i64 {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::SeqCst);
+    VALIDATORS.lock().unwrap().insert(handle, Validator::default());
+    handle
+}
+
+/// Add `|`-separated rules to a field; throws if a rule is invalid
+///
+/// # Safety
+/// The caller must ensure `field` is a valid pointer to a NamlString and
+/// `rules` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_validate_add_rule(handle: i64, field: *const NamlString, rules: *const NamlString) {
+    let (field, spec) = unsafe { (string_from_naml(field), string_from_naml(rules)) };
+    let mut validators = VALIDATORS.lock().unwrap();
+    let Some(validator) = validators.get_mut(&handle) else {
+        drop(validators);
+        throw_validation_error(&unknown_validator(handle).message, &field, "");
+        return;
+    };
+    if let Err((rule, message)) = validator.add_rules(&field, &spec) {
+        drop(validators);
+        throw_validation_error(&format!("{}: {}", field, message), &field, &rule);
+    }
+}
+
+/// Every failed rule as an array of `{"field", "rule", "message"}` maps
+///
+/// # Safety
+/// The caller must ensure `values` is a valid pointer to a NamlMap.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_validate_validate(handle: i64, values: *const NamlMap) -> *mut NamlArray {
+    let failures = run_validator(handle, values);
+    unsafe {
+        let result = naml_array_new(failures.len());
+        for failure in failures {
+            let map = naml_map_new(4);
+            for (key, value) in [("field", &failure.field), ("rule", &failure.rule), ("message", &failure.message)] {
+                let key = naml_from_string(key);
+                naml_map_set_string(map, key as i64, naml_from_string(value) as i64);
+                naml_string_decref(key);
+            }
+            naml_array_push(result, map as i64);
+        }
+        result
+    }
+}
+
+/// Throw ValidationError for the first failed rule, if any
+///
+/// # Safety
+/// The caller must ensure `values` is a valid pointer to a NamlMap.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_validate_check(handle: i64, values: *const NamlMap) {
+    if let Some(failure) = run_validator(handle, values).into_iter().next() {
+        throw_validation_error(&failure.message, &failure.field, &failure.rule);
+    }
+}
+
+/// Release a validator
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_validate_close(handle: i64) {
+    VALIDATORS.lock().unwrap().remove(&handle);
+}
@@ -0,0 +1,245 @@
+///
+/// Validator Rules
+///
+/// A validator holds, per field, a list of rules parsed from specs such as
+/// `"required|email|max_len:254"`. Validating a set of values reports every
+/// rule that fails, in the order fields and rules were added.
+///
+
+use std::collections::HashMap;
+
+use crate::formats;
+
+enum Check {
+    Required,
+    Email,
+    Url,
+    Uuid,
+    Ipv4,
+    Ipv6,
+    Ip,
+    Hostname,
+    Luhn,
+    Int,
+    Number,
+    MinLen(usize),
+    MaxLen(usize),
+    Min(f64),
+    Max(f64),
+    OneOf(Vec<String>),
+    Same(String),
+}
+
+pub(crate) struct Rule {
+    /// The rule as written, e.g. `min_len:8`
+    text: String,
+    check: Check,
+}
+
+/// A rule that failed for a field
+#[derive(Debug, PartialEq)]
+pub(crate) struct Failure {
+    pub field: String,
+    pub rule: String,
+    pub message: String,
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, arg: Option<&str>) -> Result<T, String> {
+    arg.and_then(|a| a.trim().parse().ok())
+        .ok_or_else(|| format!("rule '{}' needs a number, e.g. '{}:8'", name, name))
+}
+
+fn parse_arg<'a>(name: &str, arg: Option<&'a str>) -> Result<&'a str, String> {
+    arg.filter(|a| !a.is_empty())
+        .ok_or_else(|| format!("rule '{}' needs an argument, e.g. '{}:value'", name, name))
+}
+
+impl Rule {
+    fn parse(text: &str) -> Result<Rule, String> {
+        let (name, arg) = match text.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (text, None),
+        };
+        let check = match name {
+            "required" => Check::Required,
+            "email" => Check::Email,
+            "url" => Check::Url,
+            "uuid" => Check::Uuid,
+            "ipv4" => Check::Ipv4,
+            "ipv6" => Check::Ipv6,
+            "ip" => Check::Ip,
+            "hostname" => Check::Hostname,
+            "luhn" => Check::Luhn,
+            "int" => Check::Int,
+            "number" => Check::Number,
+            "min_len" => Check::MinLen(parse_number(name, arg)?),
+            "max_len" => Check::MaxLen(parse_number(name, arg)?),
+            "min" => Check::Min(parse_number(name, arg)?),
+            "max" => Check::Max(parse_number(name, arg)?),
+            "one_of" => Check::OneOf(parse_arg(name, arg)?.split(',').map(|s| s.trim().to_string()).collect()),
+            "same" => Check::Same(parse_arg(name, arg)?.trim().to_string()),
+            _ => return Err(format!("unknown rule '{}'", name)),
+        };
+        if arg.is_some() && !matches!(name, "min_len" | "max_len" | "min" | "max" | "one_of" | "same") {
+            return Err(format!("rule '{}' takes no argument", name));
+        }
+        Ok(Rule { text: text.to_string(), check })
+    }
+
+    /// Whether a present, non-empty `value` passes
+    fn passes(&self, value: &str, values: &HashMap<String, String>) -> bool {
+        let number = || value.trim().parse::<f64>().ok().filter(|n| n.is_finite());
+        match &self.check {
+            Check::Required => true,
+            Check::Email => formats::is_email(value),
+            Check::Url => formats::is_url(value),
+            Check::Uuid => formats::is_uuid(value),
+            Check::Ipv4 => formats::is_ipv4(value),
+            Check::Ipv6 => formats::is_ipv6(value),
+            Check::Ip => formats::is_ipv4(value) || formats::is_ipv6(value),
+            Check::Hostname => formats::is_hostname(value),
+            Check::Luhn => formats::matches_luhn(value),
+            Check::Int => value.trim().parse::<i64>().is_ok(),
+            Check::Number => number().is_some(),
+            Check::MinLen(n) => value.chars().count() >= *n,
+            Check::MaxLen(n) => value.chars().count() <= *n,
+            Check::Min(min) => number().is_some_and(|v| v >= *min),
+            Check::Max(max) => number().is_some_and(|v| v <= *max),
+            Check::OneOf(options) => options.iter().any(|o| o == value),
+            Check::Same(other) => values.get(other).is_some_and(|v| v == value),
+        }
+    }
+
+    fn message(&self, field: &str) -> String {
+        let expected = match &self.check {
+            Check::Required => return format!("{} is required", field),
+            Check::Email => "be a valid email address".to_string(),
+            Check::Url => "be a valid URL".to_string(),
+            Check::Uuid => "be a valid UUID".to_string(),
+            Check::Ipv4 => "be a valid IPv4 address".to_string(),
+            Check::Ipv6 => "be a valid IPv6 address".to_string(),
+            Check::Ip => "be a valid IP address".to_string(),
+            Check::Hostname => "be a valid host name".to_string(),
+            Check::Luhn => "have a valid check digit".to_string(),
+            Check::Int => "be an integer".to_string(),
+            Check::Number => "be a number".to_string(),
+            Check::MinLen(n) => format!("be at least {} characters", n),
+            Check::MaxLen(n) => format!("be at most {} characters", n),
+            Check::Min(n) => format!("be a number of at least {}", n),
+            Check::Max(n) => format!("be a number of at most {}", n),
+            Check::OneOf(options) => format!("be one of {}", options.join(", ")),
+            Check::Same(other) => format!("match {}", other),
+        };
+        format!("{} must {}", field, expected)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Validator {
+    fields: Vec<(String, Vec<Rule>)>,
+}
+
+impl Validator {
+    /// Add the `|`-separated rules in `spec` to `field`. Nothing is added
+    /// if any rule is invalid; the error names the rule and the problem.
+    pub fn add_rules(&mut self, field: &str, spec: &str) -> Result<(), (String, String)> {
+        let rules = spec
+            .split('|')
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(|text| Rule::parse(text).map_err(|message| (text.to_string(), message)))
+            .collect::<Result<Vec<_>, _>>()?;
+        match self.fields.iter_mut().find(|(name, _)| name == field) {
+            Some((_, existing)) => existing.extend(rules),
+            None => self.fields.push((field.to_string(), rules)),
+        }
+        Ok(())
+    }
+
+    /// Every failed rule. A missing or blank value only fails `required`;
+    /// the field's other rules are skipped, so fields without `required`
+    /// are optional.
+    pub fn validate(&self, values: &HashMap<String, String>) -> Vec<Failure> {
+        let mut failures = Vec::new();
+        for (field, rules) in &self.fields {
+            let value = values.get(field).map(String::as_str).unwrap_or("");
+            let failed: Vec<&Rule> = if value.trim().is_empty() {
+                rules.iter().filter(|r| matches!(r.check, Check::Required)).take(1).collect()
+            } else {
+                rules.iter().filter(|r| !r.passes(value, values)).collect()
+            };
+            failures.extend(failed.into_iter().map(|rule| Failure {
+                field: field.clone(),
+                rule: rule.text.clone(),
+                message: rule.message(field),
+            }));
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn signup() -> Validator {
+        let mut v = Validator::default();
+        v.add_rules("email", "required|email|max_len:254").unwrap();
+        v.add_rules("password", "required|min_len:8").unwrap();
+        v.add_rules("confirm", "same:password").unwrap();
+        v.add_rules("age", "int|min:18|max:130").unwrap();
+        v.add_rules("plan", "one_of:free, pro").unwrap();
+        v
+    }
+
+    #[test]
+    fn test_valid_values() {
+        let ok = values(&[
+            ("email", "ada@example.com"),
+            ("password", "correct horse"),
+            ("confirm", "correct horse"),
+            ("age", "36"),
+            ("plan", "pro"),
+        ]);
+        assert_eq!(signup().validate(&ok), vec![]);
+        // Optional fields may be left out
+        assert_eq!(signup().validate(&values(&[("email", "a@b.io"), ("password", "12345678")])), vec![]);
+    }
+
+    #[test]
+    fn test_failures_in_order() {
+        let bad = values(&[("password", "short"), ("confirm", "other"), ("age", "12.5"), ("plan", "gold")]);
+        let failures = signup().validate(&bad);
+        let rules: Vec<(&str, &str)> = failures.iter().map(|f| (f.field.as_str(), f.rule.as_str())).collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("email", "required"),
+                ("password", "min_len:8"),
+                ("confirm", "same:password"),
+                ("age", "int"),
+                ("age", "min:18"),
+                ("plan", "one_of:free, pro"),
+            ]
+        );
+        assert_eq!(failures[0].message, "email is required");
+        assert_eq!(failures[1].message, "password must be at least 8 characters");
+        assert_eq!(failures[4].message, "age must be a number of at least 18");
+        assert_eq!(failures[5].message, "plan must be one of free, pro");
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let mut v = Validator::default();
+        assert_eq!(v.add_rules("a", "required|emial"), Err(("emial".to_string(), "unknown rule 'emial'".to_string())));
+        assert!(v.add_rules("a", "min_len:x").unwrap_err().1.contains("needs a number"));
+        assert!(v.add_rules("a", "same").unwrap_err().1.contains("needs an argument"));
+        assert!(v.add_rules("a", "email:yes").unwrap_err().1.contains("takes no argument"));
+        // Nothing from a rejected spec was kept
+        assert_eq!(v.validate(&values(&[])), vec![]);
+    }
+}
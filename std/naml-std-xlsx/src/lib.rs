@@ -0,0 +1,332 @@
+///
+/// naml-std-xlsx - Spreadsheets
+///
+/// Reads xlsx, xlsm, xls and ods workbooks and writes xlsx workbooks with
+/// typed, formatted cells, accessed as `std::doc::xlsx`.
+///
+/// ## Reading
+///
+/// - `open(path: string) -> int throws XlsxError` - Load a workbook
+/// - `sheet_names(book: int) -> [string] throws XlsxError`
+/// - `read_rows(book: int, sheet: string) -> [[string]] throws XlsxError` - Cells as text from A1
+///
+/// ## Writing
+///
+/// - `create() -> int` - Start an empty workbook
+/// - `add_sheet(book: int, name: string) -> int throws XlsxError` - Returns the sheet index
+/// - `write_string / write_number / write_bool / write_formula / write_date(book, sheet, row, col, value) throws XlsxError`
+/// - `add_format(book: int) -> int throws XlsxError` - Returns the format index
+/// - `set_bold / set_italic(book, format)`, `set_num_format / set_font_color / set_bg_color / set_align(book, format, value)`
+/// - `format_cell(book, sheet, row, col, format) throws XlsxError`
+/// - `set_column_width(book, sheet, col, width) throws XlsxError`
+/// - `to_bytes(book: int) -> bytes throws XlsxError` / `save(book: int, path: string) throws XlsxError`
+///
+/// `close(book: int)` releases either kind of workbook.
+///
+/// ## Handles
+///
+/// Workbooks live in a process-wide registry (the same scheme as sqlite
+/// connections) and stay allocated until `close`. A handle from `open` only
+/// reads and a handle from `create` only writes.
+///
+
+mod read;
+mod write;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use naml_std_core::array::{naml_array_new, naml_array_push, NamlArray};
+use naml_std_core::bytes::{naml_bytes_from, NamlBytes};
+use naml_std_core::{naml_exception_set_typed, naml_stack_capture, naml_string_new, NamlString, EXCEPTION_TYPE_XLSX_ERROR};
+
+use read::XlsxReader;
+use write::{CellValue, FormatSpec, XlsxBuilder, XlsxResult};
+
+enum Workbook {
+    Reader(Box<XlsxReader>),
+    Writer(XlsxBuilder),
+}
+
+static NEXT_HANDLE: AtomicI64 = AtomicI64::new(1);
+
+static WORKBOOKS: LazyLock<Mutex<HashMap<i64, Workbook>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+unsafe fn string_from_naml(s: *const NamlString) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    unsafe { (*s).as_str().to_string() }
+}
+
+unsafe fn string_array(items: &[String]) -> *mut NamlArray {
+    unsafe {
+        let arr = naml_array_new(items.len());
+        for item in items {
+            naml_array_push(arr, naml_string_new(item.as_ptr(), item.len()) as i64);
+        }
+        arr
+    }
+}
+
+/// Throw XlsxError with layout: message@0, stack@8
+fn throw_xlsx_error(message: &str) {
+    unsafe {
+        let layout = std::alloc::Layout::from_size_align(16, 8).unwrap();
+        let ptr = std::alloc::alloc(layout);
+        if ptr.is_null() {
+            panic!("Failed to allocate XlsxError");
+        }
+        *(ptr as *mut *mut NamlString) = naml_string_new(message.as_ptr(), message.len());
+        *(ptr.add(8) as *mut *mut u8) = naml_stack_capture();
+        naml_exception_set_typed(ptr, EXCEPTION_TYPE_XLSX_ERROR);
+    }
+}
+
+fn register(workbook: Workbook) -> i64 {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    WORKBOOKS.lock().unwrap().insert(handle, workbook);
+    handle
+}
+
+/// Run `f` on a workbook opened for reading
+fn with_reader<T>(handle: i64, f: impl FnOnce(&mut XlsxReader) -> XlsxResult<T>) -> XlsxResult<T> {
+    match WORKBOOKS.lock().unwrap().get_mut(&handle) {
+        Some(Workbook::Reader(reader)) => f(reader),
+        Some(Workbook::Writer(_)) => Err(format!("workbook {} was created for writing and cannot be read", handle)),
+        None => Err(format!("unknown workbook handle {}", handle)),
+    }
+}
+
+/// Run `f` on a workbook being built
+fn with_writer<T>(handle: i64, f: impl FnOnce(&mut XlsxBuilder) -> XlsxResult<T>) -> XlsxResult<T> {
+    match WORKBOOKS.lock().unwrap().get_mut(&handle) {
+        Some(Workbook::Writer(builder)) => f(builder),
+        Some(Workbook::Reader(_)) => Err(format!("workbook {} was opened for reading and cannot be changed", handle)),
+        None => Err(format!("unknown workbook handle {}", handle)),
+    }
+}
+
+fn with_format(handle: i64, format: i64, f: impl FnOnce(&mut FormatSpec) -> XlsxResult<()>) {
+    if let Err(message) = with_writer(handle, |book| f(book.format_mut(format)?)) {
+        throw_xlsx_error(&message);
+    }
+}
+
+fn write_cell(handle: i64, sheet: i64, row: i64, col: i64, value: XlsxResult<CellValue>) {
+    if let Err(message) = value.and_then(|value| with_writer(handle, |book| book.write(sheet, row, col, value))) {
+        throw_xlsx_error(&message);
+    }
+}
+
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_open(path: *const NamlString) -> i64 {
+    let path = unsafe { string_from_naml(path) };
+    let reader = std::fs::read(&path)
+        .map_err(|e| format!("{}: {}", path, e))
+        .and_then(XlsxReader::from_bytes);
+    match reader {
+        Ok(reader) => register(Workbook::Reader(Box::new(reader))),
+        Err(message) => {
+            throw_xlsx_error(&message);
+            -1
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_sheet_names(handle: i64) -> *mut NamlArray {
+    match with_reader(handle, |reader| Ok(reader.sheet_names())) {
+        Ok(names) => unsafe { string_array(&names) },
+        Err(message) => {
+            throw_xlsx_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// The caller must ensure `sheet` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_read_rows(handle: i64, sheet: *const NamlString) -> *mut NamlArray {
+    let sheet = unsafe { string_from_naml(sheet) };
+    match with_reader(handle, |reader| reader.read_rows(&sheet)) {
+        Ok(rows) => unsafe {
+            let result = naml_array_new(rows.len());
+            for row in &rows {
+                naml_array_push(result, string_array(row) as i64);
+            }
+            result
+        },
+        Err(message) => {
+            throw_xlsx_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_create() -> i64 {
+    register(Workbook::Writer(XlsxBuilder::default()))
+}
+
+/// # Safety
+/// The caller must ensure `name` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_add_sheet(handle: i64, name: *const NamlString) -> i64 {
+    let name = unsafe { string_from_naml(name) };
+    with_writer(handle, |book| book.add_sheet(&name)).unwrap_or_else(|message| {
+        throw_xlsx_error(&message);
+        -1
+    })
+}
+
+/// # Safety
+/// The caller must ensure `value` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_write_string(handle: i64, sheet: i64, row: i64, col: i64, value: *const NamlString) {
+    let value = unsafe { string_from_naml(value) };
+    write_cell(handle, sheet, row, col, Ok(CellValue::Text(value)));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_write_number(handle: i64, sheet: i64, row: i64, col: i64, value: f64) {
+    write_cell(handle, sheet, row, col, Ok(CellValue::Number(value)));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_write_bool(handle: i64, sheet: i64, row: i64, col: i64, value: i64) {
+    write_cell(handle, sheet, row, col, Ok(CellValue::Bool(value != 0)));
+}
+
+/// # Safety
+/// The caller must ensure `formula` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_write_formula(handle: i64, sheet: i64, row: i64, col: i64, formula: *const NamlString) {
+    let formula = unsafe { string_from_naml(formula) };
+    write_cell(handle, sheet, row, col, Ok(CellValue::Formula(formula)));
+}
+
+/// # Safety
+/// The caller must ensure `value` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_write_date(handle: i64, sheet: i64, row: i64, col: i64, value: *const NamlString) {
+    let value = unsafe { string_from_naml(value) };
+    write_cell(handle, sheet, row, col, CellValue::date(&value));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_add_format(handle: i64) -> i64 {
+    with_writer(handle, |book| Ok(book.add_format())).unwrap_or_else(|message| {
+        throw_xlsx_error(&message);
+        -1
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_set_bold(handle: i64, format: i64) {
+    with_format(handle, format, |spec| {
+        spec.bold = true;
+        Ok(())
+    });
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_set_italic(handle: i64, format: i64) {
+    with_format(handle, format, |spec| {
+        spec.italic = true;
+        Ok(())
+    });
+}
+
+/// # Safety
+/// The caller must ensure `pattern` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_set_num_format(handle: i64, format: i64, pattern: *const NamlString) {
+    let pattern = unsafe { string_from_naml(pattern) };
+    with_format(handle, format, |spec| {
+        spec.num_format = Some(pattern);
+        Ok(())
+    });
+}
+
+/// # Safety
+/// The caller must ensure `color` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_set_font_color(handle: i64, format: i64, color: *const NamlString) {
+    let color = unsafe { string_from_naml(color) };
+    with_format(handle, format, |spec| {
+        spec.font_color = Some(write::parse_color(&color)?);
+        Ok(())
+    });
+}
+
+/// # Safety
+/// The caller must ensure `color` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_set_bg_color(handle: i64, format: i64, color: *const NamlString) {
+    let color = unsafe { string_from_naml(color) };
+    with_format(handle, format, |spec| {
+        spec.bg_color = Some(write::parse_color(&color)?);
+        Ok(())
+    });
+}
+
+/// # Safety
+/// The caller must ensure `align` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_set_align(handle: i64, format: i64, align: *const NamlString) {
+    let align = unsafe { string_from_naml(align) };
+    with_format(handle, format, |spec| {
+        spec.align = Some(write::parse_align(&align)?);
+        Ok(())
+    });
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_format_cell(handle: i64, sheet: i64, row: i64, col: i64, format: i64) {
+    if let Err(message) = with_writer(handle, |book| book.format_cell(sheet, row, col, format)) {
+        throw_xlsx_error(&message);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_set_column_width(handle: i64, sheet: i64, col: i64, width: f64) {
+    if let Err(message) = with_writer(handle, |book| book.set_column_width(sheet, col, width)) {
+        throw_xlsx_error(&message);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_to_bytes(handle: i64) -> *mut NamlBytes {
+    match with_writer(handle, |book| book.to_bytes()) {
+        Ok(data) => unsafe { naml_bytes_from(data.as_ptr(), data.len()) },
+        Err(message) => {
+            throw_xlsx_error(&message);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Encode and write; the registry lock is released before the write
+///
+/// # Safety
+/// The caller must ensure `path` is a valid pointer to a NamlString.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn naml_xlsx_save(handle: i64, path: *const NamlString) {
+    let path = unsafe { string_from_naml(path) };
+    let result = with_writer(handle, |book| book.to_bytes())
+        .and_then(|data| std::fs::write(&path, data).map_err(|e| format!("{}: {}", path, e)));
+    if let Err(message) = result {
+        throw_xlsx_error(&message);
+    }
+}
+
+/// Release a workbook; unknown handles are ignored
+#[unsafe(no_mangle)]
+pub extern "C" fn naml_xlsx_close(handle: i64) {
+    WORKBOOKS.lock().unwrap().remove(&handle);
+}
@@ -0,0 +1,118 @@
+///
+/// Spreadsheet Reading
+///
+/// Workbooks are loaded fully into memory and parsed with calamine, which
+/// detects xlsx, xlsm, xlsb, xls and ods from the file contents. Every
+/// cell comes back as text: numbers in their shortest form, booleans as
+/// `true`/`false`, dates as ISO 8601 and error cells as their Excel code
+/// such as `#DIV/0!`.
+///
+
+use std::io::Cursor;
+
+use calamine::{open_workbook_auto_from_rs, Data, Reader, Sheets};
+
+use crate::write::XlsxResult;
+
+pub struct XlsxReader {
+    sheets: Sheets<Cursor<Vec<u8>>>,
+}
+
+impl XlsxReader {
+    pub fn from_bytes(data: Vec<u8>) -> XlsxResult<Self> {
+        let sheets = open_workbook_auto_from_rs(Cursor::new(data)).map_err(|e| format!("invalid spreadsheet: {}", e))?;
+        Ok(Self { sheets })
+    }
+
+    pub fn sheet_names(&self) -> Vec<String> {
+        self.sheets.sheet_names()
+    }
+
+    /// The cells of `sheet` as rows of text, starting at cell A1. Every row
+    /// has the same number of cells; blank cells are empty strings.
+    pub fn read_rows(&mut self, sheet: &str) -> XlsxResult<Vec<Vec<String>>> {
+        let names = self.sheet_names();
+        if !names.iter().any(|name| name == sheet) {
+            return Err(format!("no sheet named '{}', the workbook has: {}", sheet, names.join(", ")));
+        }
+        let range = self.sheets.worksheet_range(sheet).map_err(|e| e.to_string())?;
+        let (Some((first_row, first_col)), Some((_, last_col))) = (range.start(), range.end()) else {
+            return Ok(Vec::new());
+        };
+        let width = last_col as usize + 1;
+        let mut rows = vec![vec![String::new(); width]; first_row as usize];
+        for cells in range.rows() {
+            let mut row = vec![String::new(); first_col as usize];
+            row.extend(cells.iter().map(cell_text));
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(value) if value.is_duration() => match value.as_duration() {
+            Some(duration) => {
+                let seconds = duration.num_seconds();
+                format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+            }
+            None => value.to_string(),
+        },
+        Data::DateTime(value) => match value.as_datetime() {
+            Some(datetime) if value.as_f64() < 1.0 => datetime.format("%H:%M:%S").to_string(),
+            Some(datetime) if datetime.time() == chrono::NaiveTime::MIN => datetime.format("%Y-%m-%d").to_string(),
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            None => value.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::write::{CellValue, XlsxBuilder};
+
+    #[test]
+    fn test_round_trip() {
+        let mut book = XlsxBuilder::default();
+        let sales = book.add_sheet("Sales").unwrap();
+        book.add_sheet("Empty").unwrap();
+        book.write(sales, 0, 0, CellValue::Text("Region".to_string())).unwrap();
+        book.write(sales, 0, 1, CellValue::Text("Total".to_string())).unwrap();
+        book.write(sales, 1, 0, CellValue::Text("North".to_string())).unwrap();
+        book.write(sales, 1, 1, CellValue::Number(1250.5)).unwrap();
+        book.write(sales, 2, 1, CellValue::Number(42.0)).unwrap();
+        book.write(sales, 2, 2, CellValue::Bool(true)).unwrap();
+        book.write(sales, 3, 0, CellValue::date("2024-03-09").unwrap()).unwrap();
+        book.write(sales, 3, 1, CellValue::date("2024-03-09T14:30:00").unwrap()).unwrap();
+        let bytes = book.to_bytes().unwrap();
+
+        let mut reader = XlsxReader::from_bytes(bytes).unwrap();
+        assert_eq!(reader.sheet_names(), vec!["Sales", "Empty"]);
+        let rows = reader.read_rows("Sales").unwrap();
+        assert_eq!(rows, vec![
+            vec!["Region", "Total", ""],
+            vec!["North", "1250.5", ""],
+            vec!["", "42", "true"],
+            vec!["2024-03-09", "2024-03-09T14:30:00", ""],
+        ]);
+        assert!(reader.read_rows("Empty").unwrap().is_empty());
+        assert!(reader.read_rows("Missing").unwrap_err().contains("Sales, Empty"));
+    }
+
+    #[test]
+    fn test_rows_start_at_a1() {
+        let mut book = XlsxBuilder::default();
+        let sheet = book.add_sheet("Sheet1").unwrap();
+        book.write(sheet, 1, 2, CellValue::Number(7.0)).unwrap();
+        let mut reader = XlsxReader::from_bytes(book.to_bytes().unwrap()).unwrap();
+        assert_eq!(reader.read_rows("Sheet1").unwrap(), vec![vec!["", "", ""], vec!["", "", "7"]]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(XlsxReader::from_bytes(b"not a spreadsheet".to_vec()).is_err());
+    }
+}
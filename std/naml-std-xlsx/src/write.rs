@@ -0,0 +1,283 @@
+///
+/// Spreadsheet Writing
+///
+/// A workbook being built is a list of sheets holding typed cells, plus a
+/// table of formats that cells refer to by index. Nothing is encoded until
+/// `to_bytes`, which hands the whole model to rust_xlsxwriter, so cells can
+/// be written and formatted in any order.
+///
+
+use std::collections::BTreeMap;
+
+use rust_xlsxwriter::{Color, ExcelDateTime, Format, FormatAlign, Formula, Workbook, Worksheet};
+
+pub type XlsxResult<T> = Result<T, String>;
+
+/// Rows and columns are numbered from 0, so A1 is (0, 0) and the last
+/// cell Excel allows is (1_048_575, 16_383)
+pub const MAX_ROW: i64 = 1_048_575;
+pub const MAX_COL: i64 = 16_383;
+
+pub enum CellValue {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Formula(String),
+    /// A date or time and the number format it is shown with by default
+    Date(ExcelDateTime, &'static str),
+}
+
+impl CellValue {
+    /// Parse `YYYY-MM-DD`, `YYYY-MM-DDTHH:MM:SS` or `HH:MM:SS`
+    pub fn date(value: &str) -> XlsxResult<Self> {
+        let datetime = ExcelDateTime::parse_from_str(value)
+            .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS or HH:MM:SS", value))?;
+        let default_format = match (value.contains('-'), value.contains(':')) {
+            (true, true) => "yyyy-mm-dd hh:mm:ss",
+            (true, false) => "yyyy-mm-dd",
+            _ => "hh:mm:ss",
+        };
+        Ok(CellValue::Date(datetime, default_format))
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct FormatSpec {
+    pub bold: bool,
+    pub italic: bool,
+    pub num_format: Option<String>,
+    pub font_color: Option<u32>,
+    pub bg_color: Option<u32>,
+    pub align: Option<FormatAlign>,
+}
+
+impl FormatSpec {
+    fn to_format(&self, default_num_format: Option<&str>) -> Format {
+        let mut format = Format::new();
+        if self.bold {
+            format = format.set_bold();
+        }
+        if self.italic {
+            format = format.set_italic();
+        }
+        if let Some(num_format) = self.num_format.as_deref().or(default_num_format) {
+            format = format.set_num_format(num_format);
+        }
+        if let Some(color) = self.font_color {
+            format = format.set_font_color(Color::RGB(color));
+        }
+        if let Some(color) = self.bg_color {
+            format = format.set_background_color(Color::RGB(color));
+        }
+        if let Some(align) = self.align {
+            format = format.set_align(align);
+        }
+        format
+    }
+}
+
+/// Parse a `#RRGGBB` color
+pub fn parse_color(color: &str) -> XlsxResult<u32> {
+    color
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6)
+        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| format!("invalid color '{}', expected #RRGGBB", color))
+}
+
+pub fn parse_align(align: &str) -> XlsxResult<FormatAlign> {
+    match align {
+        "left" => Ok(FormatAlign::Left),
+        "center" => Ok(FormatAlign::Center),
+        "right" => Ok(FormatAlign::Right),
+        _ => Err(format!("invalid alignment '{}', expected left, center or right", align)),
+    }
+}
+
+#[derive(Default)]
+struct Cell {
+    value: Option<CellValue>,
+    format: Option<usize>,
+}
+
+struct Sheet {
+    name: String,
+    cells: BTreeMap<(u32, u16), Cell>,
+    column_widths: BTreeMap<u16, f64>,
+}
+
+#[derive(Default)]
+pub struct XlsxBuilder {
+    sheets: Vec<Sheet>,
+    formats: Vec<FormatSpec>,
+}
+
+impl XlsxBuilder {
+    /// Append a sheet and return its index. Names follow Excel's rules:
+    /// at most 31 characters, none of `[]:*?/\`, and unique ignoring case.
+    pub fn add_sheet(&mut self, name: &str) -> XlsxResult<i64> {
+        Worksheet::new().set_name(name).map_err(|e| format!("invalid sheet name '{}': {}", name, e))?;
+        if self.sheets.iter().any(|sheet| sheet.name.to_lowercase() == name.to_lowercase()) {
+            return Err(format!("the workbook already has a sheet named '{}'", name));
+        }
+        self.sheets.push(Sheet { name: name.to_string(), cells: BTreeMap::new(), column_widths: BTreeMap::new() });
+        Ok(self.sheets.len() as i64 - 1)
+    }
+
+    /// Set the value of a cell, keeping any format it already has
+    pub fn write(&mut self, sheet: i64, row: i64, col: i64, value: CellValue) -> XlsxResult<()> {
+        self.cell(sheet, row, col)?.value = Some(value);
+        Ok(())
+    }
+
+    /// Add an empty format and return its index
+    pub fn add_format(&mut self) -> i64 {
+        self.formats.push(FormatSpec::default());
+        self.formats.len() as i64 - 1
+    }
+
+    pub fn format_mut(&mut self, format: i64) -> XlsxResult<&mut FormatSpec> {
+        let count = self.formats.len();
+        usize::try_from(format)
+            .ok()
+            .and_then(|i| self.formats.get_mut(i))
+            .ok_or_else(|| format!("format {} does not exist, the workbook has {} formats", format, count))
+    }
+
+    /// Apply a format to a cell, which may be written before or after
+    pub fn format_cell(&mut self, sheet: i64, row: i64, col: i64, format: i64) -> XlsxResult<()> {
+        self.format_mut(format)?;
+        self.cell(sheet, row, col)?.format = Some(format as usize);
+        Ok(())
+    }
+
+    /// Set a column's width in characters of the default font
+    pub fn set_column_width(&mut self, sheet: i64, col: i64, width: f64) -> XlsxResult<()> {
+        if !(0.0..=255.0).contains(&width) {
+            return Err(format!("column width must be between 0 and 255, got {}", width));
+        }
+        let col = check_position(0, col)?.1;
+        self.sheet_mut(sheet)?.column_widths.insert(col, width);
+        Ok(())
+    }
+
+    /// Encode the workbook as xlsx bytes
+    pub fn to_bytes(&self) -> XlsxResult<Vec<u8>> {
+        if self.sheets.is_empty() {
+            return Err("a workbook needs at least one sheet".to_string());
+        }
+        let formats: Vec<Format> = self.formats.iter().map(|spec| spec.to_format(None)).collect();
+        let mut workbook = Workbook::new();
+        for sheet in &self.sheets {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(&sheet.name).map_err(|e| e.to_string())?;
+            for (&col, &width) in &sheet.column_widths {
+                worksheet.set_column_width(col, width).map_err(|e| e.to_string())?;
+            }
+            for (&(row, col), cell) in &sheet.cells {
+                write_cell(worksheet, row, col, cell, &self.formats, &formats).map_err(|e| e.to_string())?;
+            }
+        }
+        workbook.save_to_buffer().map_err(|e| e.to_string())
+    }
+
+    fn sheet_mut(&mut self, sheet: i64) -> XlsxResult<&mut Sheet> {
+        let count = self.sheets.len();
+        usize::try_from(sheet)
+            .ok()
+            .and_then(|i| self.sheets.get_mut(i))
+            .ok_or_else(|| format!("sheet {} does not exist, the workbook has {} sheets", sheet, count))
+    }
+
+    fn cell(&mut self, sheet: i64, row: i64, col: i64) -> XlsxResult<&mut Cell> {
+        let position = check_position(row, col)?;
+        Ok(self.sheet_mut(sheet)?.cells.entry(position).or_default())
+    }
+}
+
+fn check_position(row: i64, col: i64) -> XlsxResult<(u32, u16)> {
+    if !(0..=MAX_ROW).contains(&row) {
+        return Err(format!("row {} is out of range, rows are numbered 0 to {}", row, MAX_ROW));
+    }
+    if !(0..=MAX_COL).contains(&col) {
+        return Err(format!("column {} is out of range, columns are numbered 0 to {}", col, MAX_COL));
+    }
+    Ok((row as u32, col as u16))
+}
+
+fn write_cell(
+    worksheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    cell: &Cell,
+    specs: &[FormatSpec],
+    formats: &[Format],
+) -> Result<(), rust_xlsxwriter::XlsxError> {
+    let format = cell.format.map(|i| &formats[i]);
+    match (&cell.value, format) {
+        (Some(CellValue::Text(text)), None) => worksheet.write_string(row, col, text)?,
+        (Some(CellValue::Text(text)), Some(format)) => worksheet.write_string_with_format(row, col, text, format)?,
+        (Some(CellValue::Number(number)), None) => worksheet.write_number(row, col, *number)?,
+        (Some(CellValue::Number(number)), Some(format)) => worksheet.write_number_with_format(row, col, *number, format)?,
+        (Some(CellValue::Bool(value)), None) => worksheet.write_boolean(row, col, *value)?,
+        (Some(CellValue::Bool(value)), Some(format)) => worksheet.write_boolean_with_format(row, col, *value, format)?,
+        (Some(CellValue::Formula(formula)), None) => worksheet.write_formula(row, col, Formula::new(formula))?,
+        (Some(CellValue::Formula(formula)), Some(format)) => {
+            worksheet.write_formula_with_format(row, col, Formula::new(formula), format)?
+        }
+        // Dates are plain numbers to Excel, so they always need a number format
+        (Some(CellValue::Date(datetime, default_format)), _) => {
+            let spec = cell.format.map(|i| specs[i].clone()).unwrap_or_default();
+            worksheet.write_datetime_with_format(row, col, datetime, &spec.to_format(Some(default_format)))?
+        }
+        (None, Some(format)) => worksheet.write_blank(row, col, format)?,
+        (None, None) => worksheet,
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation() {
+        let mut book = XlsxBuilder::default();
+        assert!(book.to_bytes().is_err());
+        assert_eq!(book.add_sheet("Data"), Ok(0));
+        assert!(book.add_sheet("data").is_err());
+        assert!(book.add_sheet("a/b").is_err());
+        assert!(book.add_sheet(&"x".repeat(32)).is_err());
+        assert!(book.write(1, 0, 0, CellValue::Number(1.0)).is_err());
+        assert!(book.write(0, -1, 0, CellValue::Number(1.0)).is_err());
+        assert!(book.write(0, 0, MAX_COL + 1, CellValue::Number(1.0)).is_err());
+        assert!(book.write(0, MAX_ROW, MAX_COL, CellValue::Number(1.0)).is_ok());
+        assert!(book.format_cell(0, 0, 0, 0).is_err());
+        assert!(book.set_column_width(0, 0, 300.0).is_err());
+        assert!(CellValue::date("March 9th").is_err());
+    }
+
+    #[test]
+    fn test_parse_format_options() {
+        assert_eq!(parse_color("#1F4E79"), Ok(0x1F4E79));
+        assert!(parse_color("1F4E79").is_err());
+        assert!(parse_color("#12345").is_err());
+        assert!(matches!(parse_align("center"), Ok(FormatAlign::Center)));
+        assert!(parse_align("middle").is_err());
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let mut book = XlsxBuilder::default();
+        let sheet = book.add_sheet("Report").unwrap();
+        let header = book.add_format();
+        book.format_mut(header).unwrap().bold = true;
+        book.format_cell(sheet, 0, 0, header).unwrap();
+        book.write(sheet, 0, 0, CellValue::Text("Total".to_string())).unwrap();
+        book.write(sheet, 1, 0, CellValue::Formula("=SUM(B1:B3)".to_string())).unwrap();
+        book.format_cell(sheet, 2, 0, header).unwrap();
+        book.set_column_width(sheet, 0, 20.0).unwrap();
+        let bytes = book.to_bytes().unwrap();
+        assert!(bytes.starts_with(b"PK"));
+    }
+}
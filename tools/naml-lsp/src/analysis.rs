@@ -16,7 +16,7 @@ use std::sync::Arc;
 use tower_lsp::lsp_types::*;
 
 use namlc::source::Span;
-use namlc::typechecker::TypeError;
+use namlc::typechecker::{TypeError, TypeWarning};
 use namlc::{parse, tokenize, check_with_types, AstArena, ImportedModule};
 
 use crate::lsp_symbols::{LspSymbols, LspModule, snapshot_symbols};
@@ -127,11 +127,16 @@ impl DocumentAnalysis {
 
             for err in &type_result.errors {
                 let range = ctx.span_to_range(err.span());
+                let message = match err.suggestion() {
+                    Some(name) => format!("{}\ndid you mean '{}'?", err, name),
+                    None => err.to_string(),
+                };
                 diagnostics.push(Diagnostic {
                     range,
                     severity: Some(DiagnosticSeverity::ERROR),
+                    code: Some(NumberOrString::String(err.code().to_string())),
                     source: Some("naml".to_string()),
-                    message: err.to_string(),
+                    message,
                     ..Default::default()
                 });
 
@@ -143,6 +148,32 @@ impl DocumentAnalysis {
                 }
             }
 
+            for warning in &type_result.warnings {
+                let (message, tags) = match warning {
+                    TypeWarning::Deprecated { note: Some(note), .. } => {
+                        (format!("{}: {}", warning, note), Some(vec![DiagnosticTag::DEPRECATED]))
+                    }
+                    TypeWarning::Deprecated { note: None, .. } => {
+                        (warning.to_string(), Some(vec![DiagnosticTag::DEPRECATED]))
+                    }
+                    TypeWarning::FloatMapKey { .. } => (
+                        format!("{}: -0.0 and 0.0 are the same key, and NaN keys panic", warning),
+                        None,
+                    ),
+                    TypeWarning::UnusedImport { .. } => {
+                        (warning.to_string(), Some(vec![DiagnosticTag::UNNECESSARY]))
+                    }
+                };
+                diagnostics.push(Diagnostic {
+                    range: ctx.span_to_range(warning.span()),
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    source: Some("naml".to_string()),
+                    message,
+                    tags,
+                    ..Default::default()
+                });
+            }
+
             imported_modules = type_result.imported_modules;
             symbols = Some(snapshot_symbols(&type_result.symbols, &interner));
         } else {
@@ -15,10 +15,10 @@ use crate::lsp_symbols::{LspModule, LspSymbols, LspTypeDef};
 const KEYWORDS: &[&str] = &[
     "fn", "var", "const", "pub", "struct", "enum", "interface", "exception",
     "if", "else", "while", "for", "loop", "break", "continue", "return",
-    "switch", "case", "default", "spawn", "throw", "throws", "try", "catch",
-    "use", "mod", "extern", "true", "false", "some", "none",
+    "switch", "case", "default", "spawn", "throw", "throws", "caused_by", "try", "catch", "finally",
+    "use", "mod", "extern", "except", "true", "false", "some", "none",
     "int", "uint", "float", "bool", "string", "bytes", "option", "map", "channel",
-    "mutex", "rwlock", "atomic", "locked", "rlocked", "wlocked", "implements", "in",
+    "mutex", "rwlock", "atomic", "locked", "rlocked", "wlocked", "implements", "static", "in",
     "and", "or", "not", "as", "is", "self", "super",
 ];
 
@@ -332,12 +332,22 @@ fn format_type(ty: &Type, interner: &Rodeo) -> String {
         Type::Mutex(inner) => format!("mutex<{}>", format_type(inner, interner)),
         Type::Rwlock(inner) => format!("rwlock<{}>", format_type(inner, interner)),
         Type::Atomic(inner) => format!("atomic<{}>", format_type(inner, interner)),
+        Type::Set(inner) => format!("set<{}>", format_type(inner, interner)),
+        Type::PriorityQueue(inner) => format!("priority_queue<{}>", format_type(inner, interner)),
+        Type::Deque(inner) => format!("deque<{}>", format_type(inner, interner)),
+        Type::Iterator(inner) => format!("iterator<{}>", format_type(inner, interner)),
         Type::Struct(s) => interner.resolve(&s.name).to_string(),
         Type::Enum(e) => interner.resolve(&e.name).to_string(),
         Type::Interface(i) => interner.resolve(&i.name).to_string(),
         Type::Exception(name) => interner.resolve(name).to_string(),
         Type::StackFrame => "stack_frame".to_string(),
+        Type::IfaceInfo => "iface_info".to_string(),
+        Type::FsEvent => "fs_event".to_string(),
         Type::Json => "json".to_string(),
+        Type::Secret => "secret".to_string(),
+        Type::Money => "money".to_string(),
+        Type::StringBuilder => "string_builder".to_string(),
+        Type::Waitgroup => "waitgroup".to_string(),
         Type::Function(f) => {
             let mut s = "fn(".to_string();
             for (i, p) in f.params.iter().enumerate() {